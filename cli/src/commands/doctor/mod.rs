@@ -0,0 +1,340 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use node::p2p::webrtc::SignalingMethod;
+use serde::Serialize;
+
+/// Files open at the same time by a node with full set of peers.
+const MIN_OPEN_FILES: u64 = 4096;
+
+/// Maximum allowed difference between local clock and the clock of a
+/// remote server. Slots are 3 minutes long, but block producer needs
+/// to propagate the block early in the slot.
+const MAX_CLOCK_DRIFT: Duration = Duration::from_secs(5);
+
+/// Fallback server to check the clock against, if there are no signaling
+/// servers. Circuit blobs are fetched from it anyway.
+const CLOCK_CHECK_URL: &str = "https://github.com";
+
+/// Validates the environment before starting the node.
+///
+/// Exits with an error if any of the checks failed.
+#[derive(Debug, clap::Args)]
+pub struct Doctor {
+    #[arg(
+        long,
+        short = 'd',
+        default_value = "~/.openmina",
+        env = "OPENMINA_HOME"
+    )]
+    pub work_dir: String,
+
+    /// Peers to check connectivity to. Default peers of the network are
+    /// checked if none are passed.
+    #[arg(long, short = 'P', alias = "peer")]
+    pub peers: Vec<P2pConnectionOutgoingInitOpts>,
+
+    /// File containing initial peers.
+    ///
+    /// Each line should contain peer's multiaddr.
+    #[arg(long, env)]
+    pub peer_list_file: Option<PathBuf>,
+
+    /// Minimum free space in the work dir, in GiB.
+    #[arg(long, default_value_t = 20)]
+    pub min_free_space: u64,
+
+    /// Connection timeout, in seconds.
+    #[arg(long, default_value_t = 5)]
+    pub timeout: u64,
+
+    /// Print the report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Failed,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub details: String,
+    /// How to fix the problem, if check didn't pass.
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn ok(name: impl Into<String>, details: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Ok,
+            details: details.into(),
+            hint: None,
+        }
+    }
+
+    fn warning(name: impl Into<String>, details: impl Into<String>, hint: &str) -> Self {
+        Self {
+            status: CheckStatus::Warning,
+            hint: Some(hint.to_owned()),
+            ..Self::ok(name, details)
+        }
+    }
+
+    fn failed(name: impl Into<String>, details: impl Into<String>, hint: &str) -> Self {
+        Self {
+            status: CheckStatus::Failed,
+            hint: Some(hint.to_owned()),
+            ..Self::ok(name, details)
+        }
+    }
+}
+
+impl Doctor {
+    pub fn run(self) -> anyhow::Result<()> {
+        let work_dir = PathBuf::from(shellexpand::full(&self.work_dir)?.into_owned());
+        let timeout = Duration::from_secs(self.timeout);
+        let peers = self.peers()?;
+
+        let mut checks = vec![check_work_dir(&work_dir)];
+        if checks[0].status == CheckStatus::Ok {
+            checks.push(check_free_space(&work_dir, self.min_free_space));
+        }
+        checks.push(check_open_files_limit());
+        checks.push(check_clock(&peers, timeout));
+        checks.push(check_circuit_blobs());
+        checks.extend(peers.iter().map(|peer| check_peer(peer, timeout)));
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&checks)?);
+        } else {
+            for check in &checks {
+                let status = match check.status {
+                    CheckStatus::Ok => "  OK  ",
+                    CheckStatus::Warning => " WARN ",
+                    CheckStatus::Failed => " FAIL ",
+                };
+                println!("[{status}] {}: {}", check.name, check.details);
+                if let Some(hint) = &check.hint {
+                    println!("         hint: {hint}");
+                }
+            }
+        }
+
+        let failed = checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Failed)
+            .count();
+        if failed > 0 {
+            anyhow::bail!("{failed} check(s) failed");
+        }
+        Ok(())
+    }
+
+    fn peers(&self) -> anyhow::Result<Vec<P2pConnectionOutgoingInitOpts>> {
+        let mut peers = self.peers.clone();
+        if let Some(path) = &self.peer_list_file {
+            for line in BufReader::new(File::open(path)?).lines() {
+                let line = line?;
+                let line = line.trim();
+                if !line.is_empty() {
+                    peers.push(line.parse()?);
+                }
+            }
+        }
+        if peers.is_empty() {
+            peers = openmina_core::NetworkConfig::global()
+                .default_peers
+                .iter()
+                .filter_map(|s| s.parse().ok())
+                .collect();
+        }
+        Ok(peers)
+    }
+}
+
+fn check_work_dir(work_dir: &Path) -> Check {
+    const NAME: &str = "work dir";
+    const HINT: &str = "check the permissions or pass a different `--work-dir`";
+    if let Err(err) = std::fs::create_dir_all(work_dir) {
+        return Check::failed(NAME, format!("{work_dir:?} can't be created: {err}"), HINT);
+    }
+    let probe = work_dir.join(".doctor");
+    match std::fs::write(&probe, b"ok").and_then(|_| std::fs::remove_file(&probe)) {
+        Ok(()) => Check::ok(NAME, format!("{work_dir:?} is writable")),
+        Err(err) => Check::failed(NAME, format!("{work_dir:?} is not writable: {err}"), HINT),
+    }
+}
+
+fn check_free_space(work_dir: &Path, min_free_space_gib: u64) -> Check {
+    const NAME: &str = "free space";
+    let stat = match nix::sys::statvfs::statvfs(work_dir) {
+        Ok(stat) => stat,
+        Err(err) => {
+            return Check::warning(
+                NAME,
+                format!("failed to query: {err}"),
+                "make sure the work dir is on a local filesystem",
+            )
+        }
+    };
+    let free_gib = stat.blocks_available() as u64 * stat.fragment_size() as u64 / (1 << 30);
+    let details = format!("{free_gib} GiB available in the work dir");
+    if free_gib < min_free_space_gib {
+        Check::failed(
+            NAME,
+            details,
+            &format!("free up space, at least {min_free_space_gib} GiB is required"),
+        )
+    } else {
+        Check::ok(NAME, details)
+    }
+}
+
+fn check_open_files_limit() -> Check {
+    use nix::sys::resource::{getrlimit, Resource};
+
+    const NAME: &str = "open files limit";
+    match getrlimit(Resource::RLIMIT_NOFILE) {
+        Ok((soft, _)) if soft < MIN_OPEN_FILES => Check::warning(
+            NAME,
+            format!("soft limit is {soft}"),
+            &format!("raise the limit to at least {MIN_OPEN_FILES} with `ulimit -n`"),
+        ),
+        Ok((soft, _)) => Check::ok(NAME, format!("soft limit is {soft}")),
+        Err(err) => Check::warning(
+            NAME,
+            format!("failed to query: {err}"),
+            "check the limit manually with `ulimit -n`",
+        ),
+    }
+}
+
+/// Compares local clock with the `Date` header of the first signaling
+/// server, or of [`CLOCK_CHECK_URL`].
+fn check_clock(peers: &[P2pConnectionOutgoingInitOpts], timeout: Duration) -> Check {
+    const NAME: &str = "clock";
+    const HINT: &str = "enable time synchronization (e.g. `timedatectl set-ntp true`)";
+    let url = peers
+        .iter()
+        .find_map(|peer| match peer {
+            P2pConnectionOutgoingInitOpts::WebRTC { signaling, .. } => signaling.http_url(),
+            P2pConnectionOutgoingInitOpts::LibP2P(_) => None,
+        })
+        .unwrap_or_else(|| CLOCK_CHECK_URL.to_owned());
+
+    let remote_time = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+        .and_then(|client| client.head(&url).send())
+        .map_err(|err| err.to_string())
+        .and_then(|resp| {
+            let date = resp
+                .headers()
+                .get(reqwest::header::DATE)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| "no `Date` header in response".to_owned())?;
+            parse_http_date(date).ok_or_else(|| format!("invalid `Date` header: {date}"))
+        });
+    let remote_time = match remote_time {
+        Ok(time) => time,
+        Err(err) => {
+            return Check::warning(
+                NAME,
+                format!("failed to get time from {url}: {err}"),
+                "make sure the node has internet access",
+            )
+        }
+    };
+
+    let local_time = SystemTime::now();
+    let drift = local_time
+        .duration_since(remote_time)
+        .or_else(|_| remote_time.duration_since(local_time))
+        .unwrap_or_default();
+    // `Date` header has a precision of one second.
+    let details = format!("differs by {}s from {url}", drift.as_secs());
+    if drift > MAX_CLOCK_DRIFT + Duration::from_secs(1) {
+        Check::failed(NAME, details, HINT)
+    } else {
+        Check::ok(NAME, details)
+    }
+}
+
+fn parse_http_date(date: &str) -> Option<SystemTime> {
+    let format = time::macros::format_description!(
+        "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+    );
+    let time = time::PrimitiveDateTime::parse(date, format)
+        .ok()?
+        .assume_utc();
+    Some(time.into())
+}
+
+/// Snark worker runs inside the node process, so instead of the worker
+/// binary we check that the circuits it needs are available locally.
+fn check_circuit_blobs() -> Check {
+    const NAME: &str = "snark worker circuits";
+    let found = ledger::proofs::circuit_blobs_local_base_dirs()
+        .into_iter()
+        .find(|dir| dir.read_dir().is_ok_and(|mut d| d.next().is_some()));
+    match found {
+        Some(dir) => Check::ok(NAME, format!("circuit-blobs found in {dir:?}")),
+        None => Check::warning(
+            NAME,
+            "circuit-blobs not found locally",
+            "they will be downloaded on startup; set `OPENMINA_CIRCUIT_BLOBS_BASE_DIR` to use a local copy",
+        ),
+    }
+}
+
+fn check_peer(peer: &P2pConnectionOutgoingInitOpts, timeout: Duration) -> Check {
+    const HINT: &str = "check the firewall and that the address is correct";
+    let (name, addr) = match peer {
+        P2pConnectionOutgoingInitOpts::LibP2P(opts) => (
+            format!("peer {}", opts.peer_id),
+            format!("{}:{}", opts.host, opts.port),
+        ),
+        P2pConnectionOutgoingInitOpts::WebRTC { peer_id, signaling } => {
+            let name = format!("signaling server of {peer_id}");
+            match signaling {
+                SignalingMethod::Http(info)
+                | SignalingMethod::Https(info)
+                | SignalingMethod::HttpsProxy(_, info)
+                | SignalingMethod::Wss(info)
+                | SignalingMethod::Ws(info) => (name, format!("{}:{}", info.host, info.port)),
+                SignalingMethod::P2p { .. } => {
+                    return Check::ok(name, "signaling is relayed by a peer, nothing to check")
+                }
+            }
+        }
+    };
+
+    let result = addr
+        .to_socket_addrs()
+        .map_err(|err| format!("failed to resolve {addr}: {err}"))
+        .and_then(|mut addrs| {
+            addrs
+                .next()
+                .ok_or_else(|| format!("failed to resolve {addr}"))
+        })
+        .and_then(|socket_addr| {
+            TcpStream::connect_timeout(&socket_addr, timeout)
+                .map_err(|err| format!("{addr} is unreachable: {err}"))
+        });
+    match result {
+        Ok(_) => Check::ok(name, format!("{addr} is reachable")),
+        Err(err) => Check::warning(name, err, HINT),
+    }
+}
@@ -1,6 +1,15 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+
 use libp2p_identity::PeerId;
+use mina_p2p_messages::binprot::BinProtWrite;
 use node::account::AccountSecretKey;
 use node::p2p::identity::SecretKey;
+use node::rpc::RpcNodeStatusTransitionFrontier;
+use node::transition_frontier::ChainArchiveReader;
+use openmina_core::debug_dump::DumpDecryptor;
 
 #[derive(Debug, clap::Args)]
 pub struct Misc {
@@ -13,6 +22,12 @@ impl Misc {
         match self.command {
             MiscCommand::P2PKeyPair(command) => command.run(),
             MiscCommand::MinaKeyPair(command) => command.run(),
+            MiscCommand::DecryptDump(command) => command.run(),
+            MiscCommand::Openapi(command) => command.run(),
+            MiscCommand::LedgerExport(command) => command.run(),
+            MiscCommand::ChainExport(command) => command.run(),
+            MiscCommand::ChainImport(command) => command.run(),
+            MiscCommand::SlotClaimCoordinator(command) => command.run(),
         }
     }
 }
@@ -21,6 +36,12 @@ impl Misc {
 pub enum MiscCommand {
     P2PKeyPair(P2PKeyPair),
     MinaKeyPair(MinaKeyPair),
+    DecryptDump(DecryptDump),
+    Openapi(Openapi),
+    LedgerExport(LedgerExport),
+    ChainExport(ChainExport),
+    ChainImport(ChainImport),
+    SlotClaimCoordinator(SlotClaimCoordinator),
 }
 
 #[derive(Debug, Clone, clap::Args)]
@@ -60,3 +81,321 @@ impl MinaKeyPair {
         Ok(())
     }
 }
+
+/// Print OpenAPI document of the node's HTTP RPC.
+#[derive(Debug, Clone, clap::Args)]
+pub struct Openapi {
+    /// Write the document to the file instead of stdout.
+    #[arg(long, short)]
+    out: Option<PathBuf>,
+}
+
+impl Openapi {
+    pub fn run(self) -> anyhow::Result<()> {
+        let doc = serde_json::to_string_pretty(&openmina_node_client::openapi())?;
+        match self.out {
+            Some(out) => std::fs::write(out, doc)?,
+            None => println!("{doc}"),
+        }
+        Ok(())
+    }
+}
+
+/// Export the accounts of the ledger from the running node, for audits
+/// and external analytics.
+#[derive(Debug, Clone, clap::Args)]
+pub struct LedgerExport {
+    /// HTTP RPC address of the node.
+    #[arg(long, default_value = "http://127.0.0.1:3000")]
+    node: reqwest::Url,
+    /// Ledger to export: `best-tip`, `staking` or `next`.
+    #[arg(long, default_value = "best-tip")]
+    ledger: String,
+    /// Output format: `csv` or `jsonl`.
+    #[arg(long, default_value = "csv")]
+    format: String,
+    /// Comma separated fields to export, all of them if not set:
+    /// `public_key`, `token_id`, `balance`, `nonce`, `delegate`,
+    /// `zkapp_state_hash`.
+    #[arg(long)]
+    fields: Option<String>,
+    /// Write the export to the file instead of stdout.
+    #[arg(long, short)]
+    out: Option<PathBuf>,
+}
+
+impl LedgerExport {
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut url = self.node.join("ledger/export")?;
+        url.query_pairs_mut()
+            .append_pair("ledger", &self.ledger)
+            .append_pair("format", &self.format);
+        if let Some(fields) = &self.fields {
+            url.query_pairs_mut().append_pair("fields", fields);
+        }
+
+        // Exporting big ledger takes a while.
+        let client = reqwest::blocking::Client::builder().timeout(None).build()?;
+        let mut response = client.get(url).send()?;
+        if !response.status().is_success() {
+            let status = response.status();
+            anyhow::bail!("export failed with {status}: {}", response.text()?);
+        }
+        match self.out {
+            Some(out) => {
+                response.copy_to(&mut std::fs::File::create(out)?)?;
+            }
+            None => {
+                response.copy_to(&mut std::io::stdout().lock())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Export the contiguous range of the best chain blocks from the running
+/// node into the chain archive, for the reproducible interoperability
+/// tests. Only the blocks of the transition frontier are available.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ChainExport {
+    /// HTTP RPC address of the node.
+    #[arg(long, default_value = "http://127.0.0.1:3000")]
+    node: reqwest::Url,
+    /// Height of the first exported block, the root of the frontier if
+    /// not set.
+    #[arg(long)]
+    from: Option<u32>,
+    /// Height of the last exported block, the best tip if not set.
+    #[arg(long)]
+    to: Option<u32>,
+    /// Chain archive file to write.
+    #[arg(long, short)]
+    out: PathBuf,
+}
+
+impl ChainExport {
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut url = self.node.join("best-chain/export")?;
+        if let Some(from) = self.from {
+            url.query_pairs_mut().append_pair("from", &from.to_string());
+        }
+        if let Some(to) = self.to {
+            url.query_pairs_mut().append_pair("to", &to.to_string());
+        }
+
+        let client = reqwest::blocking::Client::builder().timeout(None).build()?;
+        let response = client.get(url).send()?;
+        if !response.status().is_success() {
+            let status = response.status();
+            anyhow::bail!("export failed with {status}: {}", response.text()?);
+        }
+        let archive = response.bytes()?;
+        let reader = ChainArchiveReader::new(archive.as_ref())?;
+        let header = reader.header().clone();
+        std::fs::write(&self.out, &archive)?;
+        println!(
+            "exported blocks {}..={} to {}",
+            header.from_height,
+            header.to_height,
+            self.out.display()
+        );
+        Ok(())
+    }
+}
+
+/// Import the chain archive into the running node. Blocks are sent one
+/// by one, each of them is verified and applied by the node before the
+/// next one is sent, so the first block must extend the node's best tip,
+/// e.g. a fresh node with the archive starting right after the genesis.
+/// The blocks go through the same timing checks as the blocks received
+/// from the peers.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ChainImport {
+    /// Chain archive file, as written by `chain-export`.
+    archive: PathBuf,
+    /// HTTP RPC address of the node.
+    #[arg(long, default_value = "http://127.0.0.1:3000")]
+    node: reqwest::Url,
+    /// How long to wait for each block to be applied, in seconds.
+    #[arg(long, default_value = "300")]
+    timeout: u64,
+    /// Admin token of the node, see `--http-admin-token`. Not needed when
+    /// the node is on the loopback address.
+    #[arg(long, env)]
+    http_admin_token: Option<String>,
+}
+
+impl ChainImport {
+    pub fn run(self) -> anyhow::Result<()> {
+        let file = std::fs::File::open(&self.archive)
+            .with_context(|| format!("opening {}", self.archive.display()))?;
+        let reader = ChainArchiveReader::new(std::io::BufReader::new(file))?;
+        let header = reader.header().clone();
+        println!(
+            "importing blocks {}..={} of the chain {}",
+            header.from_height, header.to_height, header.genesis_state_hash
+        );
+
+        let client = reqwest::blocking::Client::new();
+        let import_url = self.node.join("best-chain/import")?;
+        let status_url = self.node.join("status")?;
+        let timeout = Duration::from_secs(self.timeout);
+
+        for block in reader {
+            let block = block?;
+            let mut body = Vec::new();
+            block.block.binprot_write(&mut body)?;
+            let mut request = client.post(import_url.clone()).body(body);
+            if let Some(token) = &self.http_admin_token {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send()?;
+            if !response.status().is_success() {
+                let status = response.status();
+                anyhow::bail!(
+                    "block {} at height {} rejected with {status}: {}",
+                    block.hash(),
+                    block.height(),
+                    response.text()?
+                );
+            }
+
+            let started = Instant::now();
+            loop {
+                #[derive(serde::Deserialize)]
+                struct Status {
+                    transition_frontier: RpcNodeStatusTransitionFrontier,
+                }
+                let status: Status = client.get(status_url.clone()).send()?.json()?;
+                let best_tip = status.transition_frontier.best_tip;
+                if best_tip.is_some_and(|best_tip| &best_tip.hash == block.hash()) {
+                    break;
+                }
+                if started.elapsed() > timeout {
+                    anyhow::bail!(
+                        "block {} at height {} wasn't applied in time, check the node logs",
+                        block.hash(),
+                        block.height()
+                    );
+                }
+                std::thread::sleep(Duration::from_secs(1));
+            }
+            println!("{} {}: applied", block.height(), block.hash());
+        }
+        Ok(())
+    }
+}
+
+/// Decrypt debug dump, recorder output or event journal encrypted with
+/// `--dump-password`.
+#[derive(Debug, Clone, clap::Args)]
+pub struct DecryptDump {
+    /// Encrypted dump file, recorder or event journal directory.
+    path: PathBuf,
+    /// Where to write decrypted file, or directory.
+    #[arg(long, short)]
+    out: PathBuf,
+    #[arg(long, env = "OPENMINA_DUMP_PASSWORD")]
+    password: String,
+}
+
+impl DecryptDump {
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut decryptor = DumpDecryptor::new(self.password);
+        if !self.path.is_dir() {
+            return decrypt_file(&mut decryptor, &self.path, &self.out);
+        }
+
+        std::fs::create_dir_all(&self.out)?;
+        for entry in std::fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            let out = self.out.join(name);
+            let name = name.to_string_lossy();
+            let is_records = name.starts_with("actions_") || name.starts_with("events_");
+            if is_records && name.ends_with(".postcard") {
+                decrypt_recorded_actions(&mut decryptor, &path, &out)?;
+            } else if path.is_file() {
+                decrypt_file(&mut decryptor, &path, &out)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn decrypt_file(decryptor: &mut DumpDecryptor, path: &Path, out: &Path) -> anyhow::Result<()> {
+    let data = std::fs::read(path)?;
+    let data = if openmina_core::debug_dump::is_dump_sealed(&data) {
+        decryptor
+            .open(&data)
+            .map_err(|err| anyhow::anyhow!("failed to decrypt {}: {err}", path.display()))?
+    } else {
+        data
+    };
+    std::fs::write(out, data)?;
+    println!("decrypted: {}", out.display());
+    Ok(())
+}
+
+/// Recorded actions and journaled events are stored as length prefixed
+/// records, each of them encrypted separately.
+fn decrypt_recorded_actions(
+    decryptor: &mut DumpDecryptor,
+    path: &Path,
+    out: &Path,
+) -> anyhow::Result<()> {
+    let data = std::fs::read(path)?;
+    let mut data = data.as_slice();
+    let mut decrypted = Vec::with_capacity(data.len());
+    while let Some((len, rest)) = data.split_first_chunk::<8>() {
+        let len = u64::from_be_bytes(*len) as usize;
+        if rest.len() < len {
+            // Last record is incomplete.
+            break;
+        }
+        let (record, rest) = rest.split_at(len);
+        let record = if openmina_core::debug_dump::is_dump_sealed(record) {
+            decryptor
+                .open(record)
+                .map_err(|err| anyhow::anyhow!("failed to decrypt {}: {err}", path.display()))?
+        } else {
+            record.to_vec()
+        };
+        decrypted.extend_from_slice(&(record.len() as u64).to_be_bytes());
+        decrypted.extend_from_slice(&record);
+        data = rest;
+    }
+    std::fs::write(out, decrypted)?;
+    println!("decrypted: {}", out.display());
+    Ok(())
+}
+
+/// Run the slot claim coordinator for the redundant producers running with
+/// the same key. Producers pass `http://<host>:<port>/claim` as their
+/// `--slot-claim-url`. Coordinator doesn't authenticate the claims, so it
+/// must only be reachable from the producers.
+#[derive(Debug, Clone, clap::Args)]
+pub struct SlotClaimCoordinator {
+    #[arg(long, short, default_value = "3086")]
+    port: u16,
+    /// File the claims are persisted to, so that the slots claimed before
+    /// the restart aren't granted again. Claims are only kept in memory
+    /// if not set.
+    #[arg(long)]
+    state_file: Option<PathBuf>,
+}
+
+impl SlotClaimCoordinator {
+    pub fn run(self) -> anyhow::Result<()> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        runtime.block_on(openmina_node_native::slot_claim_coordinator::run(
+            self.port,
+            self.state_file,
+        ))?;
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 pub mod build_info;
+pub mod doctor;
 pub mod misc;
 pub mod node;
 pub mod replay;
@@ -37,6 +38,8 @@ pub enum Command {
     Misc(misc::Misc),
     Replay(replay::Replay),
     BuildInfo(build_info::Command),
+    /// Checks that the environment is ready to run the node.
+    Doctor(doctor::Doctor),
 }
 
 impl Command {
@@ -47,6 +50,7 @@ impl Command {
             Self::Misc(v) => v.run(),
             Self::Replay(v) => v.run(),
             Self::BuildInfo(v) => v.run(),
+            Self::Doctor(v) => v.run(),
         }
     }
 }
@@ -1,23 +1,38 @@
-use std::{fs::File, path::PathBuf, sync::Arc};
+use std::{fs::File, path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use ledger::proofs::provers::BlockProver;
+use ledger::scan_state::currency::{Balance, Fee};
+use ledger::transaction_pool::{FeePayerRateLimits, ZkappComplexityLimits};
 use node::{
     account::AccountSecretKey,
+    ledger::LedgerPersistPolicy,
     snark::{BlockVerifier, TransactionVerifier},
-    transition_frontier::genesis::GenesisConfig,
+    transaction_pool::sponsor::FeeSponsorLimits,
+    transition_frontier::{genesis::GenesisConfig, TransitionFrontierCandidatesPruneConfig},
 };
 
 use openmina_node_account::AccountPublicKey;
 use reqwest::Url;
 
 use node::core::log::inner::Level;
+use node::core::proxy::{ProxyBypass, ProxyConfig, ProxyUrl};
 use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use node::p2p::feature_flags::{P2pFeature, P2pFeatureFlags};
 use node::p2p::identity::SecretKey;
+use node::p2p::{P2pGossipTopic, P2pIpFamily, P2pListenFamily};
+use node::rpc::RpcResponseLimits;
 use node::service::Recorder;
-use node::SnarkerStrategy;
+use node::{CheckpointConfig, NodeProfile, SnarkerStrategy, WatchtowerConfig};
 
-use openmina_node_native::{archive::config::ArchiveStorageOptions, tracing, NodeBuilder};
+use openmina_node_native::{
+    archive::{self, config::ArchiveStorageOptions},
+    block_producer::SlotClaimConfig,
+    tracing, DiskMonitorConfig, NodeBuilder,
+};
+
+mod validate;
+pub use validate::{ConfigProblem, ConfigProblems};
 
 /// Openmina node
 #[derive(Debug, clap::Args)]
@@ -34,6 +49,12 @@ pub struct Node {
     #[arg(long, short = 's', env = "OPENMINA_P2P_SEC_KEY")]
     pub p2p_secret_key: Option<SecretKey>,
 
+    /// Secret key of a separate identity presented on the webrtc
+    /// connections, so that the webrtc peer id (e.g. of a public
+    /// signaling entry point) can't be linked to the libp2p one.
+    #[arg(long, env = "OPENMINA_P2P_WEBRTC_SEC_KEY")]
+    pub p2p_webrtc_secret_key: Option<SecretKey>,
+
     // warning, this overrides `OPENMINA_P2P_SEC_KEY`
     /// Compatibility with OCaml Mina node
     #[arg(long)]
@@ -52,10 +73,53 @@ pub struct Node {
     #[arg(long, short, env, default_value = "3000")]
     pub port: u16,
 
+    /// Max size of the lists in rpc responses (accounts, scan state), in
+    /// bytes. Larger responses are truncated and carry a continuation token.
+    #[arg(long, env, default_value_t = RpcResponseLimits::default().max_bytes)]
+    pub rpc_max_response_bytes: usize,
+
+    /// HTTP RPC address of another node of the same operator, compared in
+    /// the block propagation report (`GET /stats/block-propagation/report`).
+    /// Can be repeated.
+    #[arg(long = "sibling-node")]
+    pub sibling_nodes: Vec<String>,
+
     /// LibP2P port to listen on
     #[arg(long, env, default_value = "8302")]
     pub libp2p_port: u16,
 
+    /// Address families to listen on with libp2p (options: ipv4, ipv6,
+    /// dual-stack).
+    #[arg(long, env, default_value = "ipv4")]
+    pub libp2p_listen_family: P2pListenFamily,
+
+    /// Dial the addresses of this family first, if the peer advertises
+    /// both (options: ipv4, ipv6).
+    #[arg(long, env)]
+    pub libp2p_dial_family: Option<P2pIpFamily>,
+
+    /// Gossip message types to subscribe to, comma separated (options:
+    /// blocks, transactions, snarks), `blocks` is required. Webrtc peers
+    /// don't send the messages of the other types. Libp2p peers still send
+    /// them on the shared topic, but they are dropped without being decoded.
+    #[arg(
+        long,
+        env,
+        value_delimiter = ',',
+        default_value = "blocks,transactions,snarks"
+    )]
+    pub gossip_topics: Vec<P2pGossipTopic>,
+
+    /// P2p features to enable, comma separated (options: compact_blocks,
+    /// compression, chunked_rpc). Can be changed at runtime with
+    /// the operator endpoint `POST /feature-flags`, see `--http-admin-token`.
+    #[arg(long, env, value_delimiter = ',')]
+    pub enable_features: Vec<P2pFeature>,
+
+    /// P2p features to disable, comma separated, see `--enable-features`.
+    #[arg(long, env, value_delimiter = ',')]
+    pub disable_features: Vec<P2pFeature>,
+
     /// Verbosity level (options: trace, debug, info, warn, error)
     #[arg(long, short, env, default_value = "info")]
     pub verbosity: Level,
@@ -90,10 +154,48 @@ pub struct Node {
     #[arg(long, default_value = "100")]
     pub max_peers: usize,
 
+    /// Maximum number of rpc requests waiting for the response from a
+    /// webrtc peer, at most 5.
+    #[arg(long, env, default_value = "1")]
+    pub rpc_max_in_flight_per_peer: usize,
+
+    /// Number of times a timed out rpc request is resent to a webrtc peer,
+    /// before the peer is disconnected, at most 4.
+    #[arg(long, env, default_value = "0")]
+    pub rpc_timeout_retries: usize,
+
+    /// Proxy for the outgoing connections of the node, `socks5://host:port`
+    /// or `http://host:port` (HTTP CONNECT), optionally with `user:password@`.
+    ///
+    /// Used by the libp2p dialer, the http signaling and the archiver process
+    /// connection.
+    #[arg(long, env = "OPENMINA_PROXY")]
+    pub proxy: Option<ProxyUrl>,
+
+    /// Destinations which are connected to directly, bypassing the `--proxy`.
+    ///
+    /// Hostnames (with their subdomains), IPs, networks like `10.0.0.0/8`,
+    /// or `*` for all.
+    #[arg(
+        long,
+        env = "OPENMINA_NO_PROXY",
+        value_delimiter = ',',
+        requires = "proxy"
+    )]
+    pub no_proxy: Vec<ProxyBypass>,
+
     /// Run the node in seed mode. No default peers will be added.
     #[arg(long, env)]
     pub seed: bool,
 
+    /// Node profile (options: full, producer, snarker, archive, relay, light-relay,
+    /// watchtower).
+    ///
+    /// Only subsystems needed for the role are set up. Overrides the
+    /// `profile` from the config file.
+    #[arg(long, env = "OPENMINA_PROFILE")]
+    pub profile: Option<NodeProfile>,
+
     /// Run Snark Worker.
     ///
     /// Pass snarker private key as an argument.
@@ -126,13 +228,241 @@ pub struct Node {
     #[arg(long, requires = "producer")]
     pub coinbase_receiver: Option<AccountPublicKey>,
 
+    /// Seconds reserved at the end of the won slot for the block proof
+    /// generation and broadcast. Transactions are left out of the block
+    /// if including them would cut into this margin.
+    #[arg(long, env, default_value_t = 60, requires = "producer")]
+    pub producer_proof_margin_secs: u64,
+
+    /// Url of the slot claim coordinator, shared by the redundant
+    /// producers running with the same key. Won slot is claimed with it
+    /// before producing the block, so that only one of the nodes
+    /// produces for the slot. See `openmina misc slot-claim-coordinator`.
+    #[arg(long, env, requires_all = ["producer", "slot_claim_holder"])]
+    pub slot_claim_url: Option<String>,
+
+    /// Id of this node for the slot claim coordinator. Must be unique
+    /// among the redundant producers and stay the same across restarts.
+    #[arg(long, env, requires = "slot_claim_url")]
+    pub slot_claim_holder: Option<String>,
+
+    /// Enable payout batches (`POST /payouts/batch`), paid from the
+    /// account of this key file.
+    ///
+    /// MINA_PAYOUT_PRIVKEY_PASS must be set to decrypt the keyfile if it is password-protected
+    #[arg(long, env)]
+    pub payout_key: Option<PathBuf>,
+
+    /// Password used to decrypt the payout key file.
+    #[arg(env = "MINA_PAYOUT_PRIVKEY_PASS", default_value = "")]
+    pub payout_key_password: String,
+
+    /// Enable sponsoring the fees of the zkApp commands
+    /// (`POST /zkapp/sponsor`), paid from the account of this key file.
+    ///
+    /// MINA_FEE_SPONSOR_PRIVKEY_PASS must be set to decrypt the keyfile if it is password-protected
+    #[arg(long, env)]
+    pub fee_sponsor_key: Option<PathBuf>,
+
+    /// Password used to decrypt the fee sponsor key file.
+    #[arg(env = "MINA_FEE_SPONSOR_PRIVKEY_PASS", default_value = "")]
+    pub fee_sponsor_key_password: String,
+
+    /// Max fee of a single sponsored command, in nanomina.
+    #[arg(long, env, default_value_t = 100_000_000, requires = "fee_sponsor_key")]
+    pub fee_sponsor_max_fee: u64,
+
+    /// Max number of the commands sponsored per origin per hour.
+    #[arg(long, env, default_value_t = 10, requires = "fee_sponsor_key")]
+    pub fee_sponsor_max_per_hour: u32,
+
+    /// Max sum of the fees sponsored per origin per hour, in nanomina.
+    #[arg(
+        long,
+        env,
+        default_value_t = 1_000_000_000,
+        requires = "fee_sponsor_key"
+    )]
+    pub fee_sponsor_max_fees_per_hour: u64,
+
+    /// Max sum of the fees sponsored for all the dapps together per hour,
+    /// in nanomina.
+    #[arg(
+        long,
+        env,
+        default_value_t = 10_000_000_000,
+        requires = "fee_sponsor_key"
+    )]
+    pub fee_sponsor_max_total_fees_per_hour: u64,
+
+    /// File with the api keys of the dapps allowed to use the fee sponsor,
+    /// a `<dapp> <api key>` pair per line. Dapps pass the key as a bearer
+    /// token in the `Authorization` header.
+    #[arg(long, env, requires = "fee_sponsor_key")]
+    pub fee_sponsor_api_keys: Option<PathBuf>,
+
+    /// Bearer token required by the operator endpoints of the http server,
+    /// like the block template submission. Without it, they are only open
+    /// to the requests from the loopback address.
+    #[arg(long, env)]
+    pub http_admin_token: Option<String>,
+
+    /// Fully verify only this percentage of the snark work received
+    /// from the peers, to reduce the load on low-power devices.
+    ///
+    /// Peers which sent invalid work are always fully verified, and so
+    /// is all the work if the node produces blocks.
+    #[arg(long, env, value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub snark_verify_sample_percent: Option<u8>,
+
+    /// Reject zkApp commands with more account updates from the
+    /// transaction pool. Doesn't affect the validation of blocks.
+    #[arg(long, env)]
+    pub pool_zkapp_max_account_updates: Option<usize>,
+
+    /// Reject zkApp commands with more proof segments from the
+    /// transaction pool. Doesn't affect the validation of blocks.
+    #[arg(long, env)]
+    pub pool_zkapp_max_proof_segments: Option<usize>,
+
+    /// Reject zkApp commands with more event elements from the
+    /// transaction pool. Doesn't affect the validation of blocks.
+    #[arg(long, env)]
+    pub pool_zkapp_max_event_elements: Option<usize>,
+
+    /// Admit at most this many commands per fee payer to the transaction
+    /// pool within `--pool-fee-payer-window-secs`. Commands received via
+    /// RPC aren't limited.
+    #[arg(long, env)]
+    pub pool_fee_payer_max_commands: Option<usize>,
+
+    #[arg(long, env, default_value_t = 60)]
+    pub pool_fee_payer_window_secs: u64,
+
+    /// Candidate blocks for slots more than this many slots before the
+    /// best tip are pruned, checked every `--candidates-prune-interval-secs`.
+    #[arg(long, env, default_value_t = 290)]
+    pub candidates_retention_slots: u32,
+
+    #[arg(long, env, default_value_t = 60)]
+    pub candidates_prune_interval_secs: u64,
+
+    /// Fee payers with at least this balance (in nanomina) aren't limited
+    /// by `--pool-fee-payer-max-commands`.
+    #[arg(long, env)]
+    pub pool_fee_payer_exempt_balance: Option<u64>,
+
+    /// Fee payers which aren't limited by `--pool-fee-payer-max-commands`.
+    #[arg(
+        long = "pool-fee-payer-allow",
+        env = "OPENMINA_POOL_FEE_PAYER_ALLOWLIST",
+        value_delimiter = ','
+    )]
+    pub pool_fee_payer_allowlist: Vec<AccountPublicKey>,
+
+    /// Raise the watchtower alert if the producer doesn't produce a block
+    /// on the best chain for `--watchtower-inactivity-slots`. Enables the
+    /// watchtower alerts with any profile.
+    #[arg(
+        long = "watch-producer",
+        env = "OPENMINA_WATCH_PRODUCERS",
+        value_delimiter = ','
+    )]
+    pub watch_producers: Vec<AccountPublicKey>,
+
+    /// Raise the watchtower alert when the best tip switches to a fork
+    /// abandoning at least this many blocks.
+    #[arg(long, env, default_value_t = WatchtowerConfig::DEFAULT_FORK_DEPTH_ALERT)]
+    pub watchtower_fork_depth: u32,
+
+    /// Slots without a block of the watched producer on the best chain
+    /// after which the alert is raised.
+    #[arg(long, env, default_value_t = WatchtowerConfig::DEFAULT_PRODUCER_INACTIVITY_SLOTS)]
+    pub watchtower_inactivity_slots: u32,
+
+    /// Sign the checkpoints of the transition frontier root with the
+    /// producer key and gossip them to the peers. Requires the block
+    /// producer.
+    #[arg(long, env, requires = "producer_key")]
+    pub sign_checkpoints: bool,
+
+    /// Blocks between the signed checkpoints.
+    #[arg(long, env, default_value_t = CheckpointConfig::DEFAULT_SIGN_INTERVAL)]
+    pub checkpoint_interval: u32,
+
+    /// Only collect and relay the checkpoints of these signers. Checkpoints
+    /// received from the peers are ignored if not set.
+    #[arg(
+        long = "checkpoint-signer",
+        env = "OPENMINA_CHECKPOINT_SIGNERS",
+        value_delimiter = ','
+    )]
+    pub checkpoint_signers: Vec<AccountPublicKey>,
+
+    /// Kind of the proofs produced and accepted: `full`, or `dummy` and
+    /// `constraints-checked` for faster private devnets.
+    ///
+    /// UNSAFE: invalid proofs are accepted unless it's `full`. Refused
+    /// on mainnet.
+    #[cfg(feature = "unsafe-devnet")]
+    #[arg(long, env = "OPENMINA_PROOF_KIND", default_value_t = openmina_node_native::ProofKind::Full)]
+    pub proof_kind: openmina_node_native::ProofKind,
+
     #[arg(long, default_value = "none", env)]
     pub record: String,
 
+    /// Journal all the events received from the services, with their
+    /// timestamps, in the `event_journal` dir of the work dir.
+    #[arg(long, env)]
+    pub event_journal: bool,
+
+    /// Re-inject the events journaled in the dir on startup, to
+    /// reconstruct the state of the node for the post-mortem analysis.
+    ///
+    /// Best used with `--no-peers-discovery` and no initial peers, so
+    /// that the replayed events aren't mixed with the live ones.
+    #[arg(long, env)]
+    pub replay_event_journal: Option<PathBuf>,
+
+    /// Password used to encrypt recorder output, event journal and debug dumps.
+    ///
+    /// Use `openmina misc decrypt-dump` to decrypt them for analysis.
+    #[arg(long, env = "OPENMINA_DUMP_PASSWORD")]
+    pub dump_password: Option<String>,
+
+    /// Remove the oldest debug dumps and locally archived precomputed
+    /// blocks while there is less free space than this on the disk of
+    /// the work dir.
+    #[arg(long, env)]
+    pub disk_prune_below_mb: Option<u64>,
+
+    /// Pause debug dumps, recorder and event journal while there is less
+    /// free space than this on the disk of the work dir. The files are
+    /// pruned first, and `/healthz` fails while the writers are paused.
+    #[arg(long, env)]
+    pub disk_pause_below_mb: Option<u64>,
+
     /// Do not use peers discovery.
     #[arg(long)]
     pub no_peers_discovery: bool,
 
+    /// Do not persist transition frontier root in the work dir, which
+    /// is otherwise used to resume quickly after restart.
+    #[arg(long, env)]
+    pub no_root_snapshot: bool,
+
+    /// Which commits of the transition frontier root are persisted in the
+    /// root snapshot: `block` (every commit), `blocks:<n>` (every n-th
+    /// commit) or `periodic:<seconds>`. Commits in between are batched into
+    /// the next persisted one, and applied again after a crash.
+    #[arg(long, env, default_value = "blocks:10")]
+    pub ledger_persist_policy: LedgerPersistPolicy,
+
+    /// Do not persist the connected peers in the work dir, which are
+    /// otherwise reconnected to first after restart.
+    #[arg(long, env)]
+    pub no_peer_resumption: bool,
+
     /// Config JSON file to load at startup.
     // TODO: make this argument required.
     #[arg(short = 'c', long, env)]
@@ -178,6 +508,8 @@ pub struct Node {
 
 impl Node {
     pub fn run(self) -> anyhow::Result<()> {
+        self.validate()?;
+
         let work_dir = shellexpand::full(&self.work_dir).unwrap().into_owned();
 
         let _guard = if !self.disable_filesystem_logging {
@@ -201,6 +533,12 @@ impl Node {
             .build_global()
             .context("failed to initialize threadpool")?;
 
+        let proxy = self.proxy.map(|url| ProxyConfig {
+            url,
+            bypass: self.no_proxy,
+        });
+        ProxyConfig::init(proxy).map_err(anyhow::Error::msg)?;
+
         let (daemon_conf, genesis_conf) = match self.config {
             Some(config) => {
                 let reader = File::open(config).context("config file {config:?}")?;
@@ -240,6 +578,11 @@ impl Node {
         };
         let mut node_builder: NodeBuilder =
             NodeBuilder::new(custom_rng_seed, daemon_conf, genesis_conf);
+        if let Some(profile) = self.profile {
+            node_builder.profile(profile);
+        }
+        let profile = node_builder.get_profile();
+        node::core::info!(node::core::log::system_time(); summary = "node profile", profile = profile.to_string());
 
         // let genesis_config = match self.config {
         //     Some(config_path) => GenesisConfig::DaemonJsonFile(config_path).into(),
@@ -250,6 +593,9 @@ impl Node {
         if let Some(sec_key) = self.p2p_secret_key {
             node_builder.p2p_sec_key(sec_key);
         }
+        if let Some(sec_key) = self.p2p_webrtc_secret_key {
+            node_builder.p2p_webrtc_sec_key(sec_key);
+        }
 
         // warning, this overrides `OPENMINA_P2P_SEC_KEY`
         if let (Some(key_file), Some(password)) = (&self.libp2p_keypair, &self.libp2p_password) {
@@ -291,6 +637,22 @@ impl Node {
         );
 
         node_builder.p2p_max_peers(self.max_peers);
+        node_builder
+            .p2p_rpc_max_in_flight_per_peer(self.rpc_max_in_flight_per_peer)
+            .p2p_rpc_timeout_retries(self.rpc_timeout_retries);
+        node_builder.p2p_listen_family(self.libp2p_listen_family);
+        if let Some(family) = self.libp2p_dial_family {
+            node_builder.p2p_dial_family(family);
+        }
+        node_builder.p2p_gossip_topics(&self.gossip_topics)?;
+        let mut feature_flags = P2pFeatureFlags::default();
+        for feature in &self.enable_features {
+            feature_flags.set(*feature, true);
+        }
+        for feature in &self.disable_features {
+            feature_flags.set(*feature, false);
+        }
+        node_builder.p2p_feature_flags(feature_flags);
         self.seed.then(|| node_builder.p2p_seed_node());
         self.no_peers_discovery
             .then(|| node_builder.p2p_no_discovery());
@@ -303,6 +665,7 @@ impl Node {
             node_builder.initial_peers_from_url(url)?;
         }
 
+        ledger::proofs::verifiers::set_cache_dir(PathBuf::from(&work_dir).join("verifier-index"));
         let block_verifier_index = BlockVerifier::make();
         let work_verifier_index = TransactionVerifier::make();
         node_builder
@@ -317,14 +680,102 @@ impl Node {
                 node::core::info!(node::core::log::system_time(); summary = "loaded provers index");
             });
             node_builder.block_producer_from_file(producer_key_path, password, None)?;
+            node_builder.block_producer_signing_journal(&work_dir)?;
 
             if let Some(pub_key) = self.coinbase_receiver {
                 node_builder
                     .custom_coinbase_receiver(pub_key.into())
                     .unwrap();
             }
+            node_builder
+                .block_producer_proof_and_broadcast_margin(Duration::from_secs(
+                    self.producer_proof_margin_secs,
+                ))
+                .unwrap();
+
+            if let Some((url, holder)) = self.slot_claim_url.zip(self.slot_claim_holder) {
+                node_builder
+                    .block_producer_slot_claim(SlotClaimConfig::new(url, holder))
+                    .unwrap();
+            }
+        }
+
+        if let Some(payout_key_path) = self.payout_key {
+            node_builder.payout_key_from_file(payout_key_path, &self.payout_key_password)?;
+        }
+
+        if let Some(fee_sponsor_key_path) = self.fee_sponsor_key {
+            let limits = FeeSponsorLimits {
+                max_fee: Fee::from_u64(self.fee_sponsor_max_fee),
+                max_commands: self.fee_sponsor_max_per_hour,
+                max_fees: Fee::from_u64(self.fee_sponsor_max_fees_per_hour),
+                max_total_fees: Fee::from_u64(self.fee_sponsor_max_total_fees_per_hour),
+                window: Duration::from_secs(60 * 60),
+            };
+            node_builder.fee_sponsor_key_from_file(
+                fee_sponsor_key_path,
+                &self.fee_sponsor_key_password,
+                limits,
+            )?;
+            if let Some(path) = self.fee_sponsor_api_keys {
+                node_builder.fee_sponsor_api_keys_from_file(path)?;
+            }
+        }
+
+        if let Some(token) = self.http_admin_token {
+            node_builder.http_admin_token(token);
+        }
+
+        if let Some(percent) = self.snark_verify_sample_percent {
+            node_builder.snark_verify_sampling(percent);
         }
 
+        node_builder.tx_pool_zkapp_limits(ZkappComplexityLimits {
+            max_account_updates: self.pool_zkapp_max_account_updates,
+            max_proof_segments: self.pool_zkapp_max_proof_segments,
+            max_event_elements: self.pool_zkapp_max_event_elements,
+        });
+        node_builder.candidates_prune(TransitionFrontierCandidatesPruneConfig {
+            retention_slots: self.candidates_retention_slots,
+            interval: Duration::from_secs(self.candidates_prune_interval_secs),
+        });
+        node_builder.tx_pool_fee_payer_limits(FeePayerRateLimits {
+            max_commands: self.pool_fee_payer_max_commands,
+            window: Duration::from_secs(self.pool_fee_payer_window_secs),
+            exempt_balance: self.pool_fee_payer_exempt_balance.map(Balance::from_u64),
+            allowlist: self
+                .pool_fee_payer_allowlist
+                .into_iter()
+                .map(|pub_key| {
+                    let display = pub_key.to_string();
+                    pub_key
+                        .try_into()
+                        .map_err(|_| anyhow::anyhow!("invalid public key: {display}"))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        });
+        node_builder.rpc_response_limits(RpcResponseLimits {
+            max_bytes: self.rpc_max_response_bytes,
+        });
+        node_builder.sibling_nodes(self.sibling_nodes);
+
+        if profile == NodeProfile::Watchtower || !self.watch_producers.is_empty() {
+            node_builder.watchtower(WatchtowerConfig {
+                producers: self.watch_producers,
+                fork_depth_alert: self.watchtower_fork_depth,
+                producer_inactivity_slots: self.watchtower_inactivity_slots,
+            });
+        }
+        node_builder.checkpoint(CheckpointConfig {
+            sign_interval: self.sign_checkpoints.then_some(self.checkpoint_interval),
+            signers: self.checkpoint_signers,
+            persisted: Vec::new(),
+        });
+        node_builder.checkpoints_persist(&work_dir)?;
+
+        #[cfg(feature = "unsafe-devnet")]
+        node_builder.proof_kind(self.proof_kind);
+
         let archive_storage_options = ArchiveStorageOptions::from_iter(
             [
                 (
@@ -332,7 +783,8 @@ impl Node {
                     ArchiveStorageOptions::LOCAL_PRECOMPUTED_STORAGE,
                 ),
                 (
-                    self.archive_archiver_process,
+                    // Archive profile always writes to the archive database.
+                    self.archive_archiver_process || profile == NodeProfile::Archive,
                     ArchiveStorageOptions::ARCHIVER_PROCESS,
                 ),
                 (
@@ -365,11 +817,47 @@ impl Node {
             node_builder.archive(archive_storage_options, work_dir.clone());
         }
 
+        if !self.no_root_snapshot {
+            node_builder
+                .ledger_root_snapshot(&work_dir)
+                .ledger_persist_policy(self.ledger_persist_policy);
+        }
+        if !self.no_peer_resumption {
+            node_builder.p2p_preferred_peers(&work_dir)?;
+        }
+
         if let Some(sec_key) = self.run_snarker {
             node_builder.snarker(sec_key, self.snarker_fee, self.snarker_strategy);
         }
 
         openmina_core::set_work_dir(work_dir.clone().into());
+        ledger::verifier::srs::set_cache_dir(PathBuf::from(&work_dir).join("srs"));
+        if let Some(password) = self.dump_password.as_deref() {
+            openmina_core::debug_dump::set_dump_encryption_password(password)
+                .context("failed to set dump encryption password")?;
+        }
+
+        if let Some(dir) = self.replay_event_journal.as_ref() {
+            node_builder.event_journal_replay(dir);
+        }
+        if self.event_journal {
+            node_builder.event_journal(&work_dir);
+        }
+        if self.disk_prune_below_mb.is_some() || self.disk_pause_below_mb.is_some() {
+            let mut prune_dirs = vec![openmina_core::get_debug_dir()];
+            if self.archive_local_storage {
+                prune_dirs.push(archive::local_precomputed_storage_path(&work_dir).into());
+            }
+            let mb = |v: Option<u64>| v.unwrap_or(0).saturating_mul(1024 * 1024);
+            node_builder.disk_monitor(
+                &work_dir,
+                DiskMonitorConfig {
+                    prune_below_bytes: mb(self.disk_prune_below_mb),
+                    pause_below_bytes: mb(self.disk_pause_below_mb),
+                    prune_dirs,
+                },
+            );
+        }
 
         node_builder
             .http_server(self.port)
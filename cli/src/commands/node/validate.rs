@@ -0,0 +1,287 @@
+//! Validation of the node configuration, done before any of the services
+//! is started, so that all the problems are reported at once instead of
+//! failing on the first one.
+
+use std::{fmt, fs::File, net::IpAddr, path::Path};
+
+use ledger::{AccountId, TokenId};
+use node::NodeProfile;
+
+use super::Node;
+
+/// Problem with the node configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// Flag or config file field with the problem, e.g. `--producer-key`.
+    pub field: String,
+    pub problem: String,
+    /// How to fix the problem.
+    pub suggestion: Option<String>,
+}
+
+/// All the problems found in the node configuration.
+#[derive(Debug, Default)]
+pub struct ConfigProblems(pub Vec<ConfigProblem>);
+
+impl ConfigProblems {
+    fn add(&mut self, field: &str, problem: impl Into<String>, suggestion: Option<&str>) {
+        self.0.push(ConfigProblem {
+            field: field.to_owned(),
+            problem: problem.into(),
+            suggestion: suggestion.map(ToOwned::to_owned),
+        });
+    }
+
+    fn check_file(&mut self, field: &str, path: &Path) {
+        match File::open(path) {
+            Ok(file) if file.metadata().is_ok_and(|m| m.is_dir()) => {
+                self.add(field, format!("{path:?} is a directory"), None)
+            }
+            Ok(_) => {}
+            Err(err) => self.add(
+                field,
+                format!("can't open {path:?}: {err}"),
+                Some("check the path and the file permissions"),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ConfigProblems {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "invalid node configuration:")?;
+        for ConfigProblem {
+            field,
+            problem,
+            suggestion,
+        } in &self.0
+        {
+            write!(f, "  {field}: {problem}")?;
+            if let Some(suggestion) = suggestion {
+                write!(f, " (hint: {suggestion})")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigProblems {}
+
+impl Node {
+    /// Parses and cross-checks the configuration, without starting
+    /// anything.
+    pub fn validate(&self) -> Result<(), ConfigProblems> {
+        let mut problems = ConfigProblems::default();
+
+        if let Err(err) = shellexpand::full(&self.work_dir) {
+            problems.add("--work-dir", err.to_string(), None);
+        }
+
+        let mut profile = self.profile;
+        if let Some(path) = &self.config {
+            match File::open(path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| {
+                    serde_json::from_reader::<_, node::daemon_json::DaemonJson>(file)
+                        .map_err(|e| e.to_string())
+                }) {
+                Ok(config) => {
+                    profile = profile.or_else(|| config.daemon.as_ref()?.profile());
+                }
+                Err(err) => problems.add("--config", format!("{path:?}: {err}"), None),
+            }
+        }
+        let profile = profile.unwrap_or_default();
+
+        if let Some(seed) = &self.rng_seed {
+            if !hex::decode(seed).is_ok_and(|bytes| bytes.len() == 32) {
+                problems.add(
+                    "--rng-seed",
+                    "must be 32 bytes encoded as hex",
+                    Some("generate one with `openssl rand -hex 32`"),
+                );
+            }
+        }
+
+        if let Some(path) = &self.libp2p_keypair {
+            problems.check_file("--libp2p-keypair", Path::new(path));
+            if self.libp2p_password.is_none() {
+                problems.add(
+                    "--libp2p-keypair",
+                    "key file is set, but its password isn't",
+                    Some("set `MINA_LIBP2P_PASS`"),
+                );
+            }
+        }
+        for ip in &self.libp2p_external_ip {
+            if ip.parse::<IpAddr>().is_err() {
+                problems.add(
+                    "--libp2p-external-ip",
+                    format!("{ip:?} isn't an ip address"),
+                    None,
+                );
+            }
+        }
+        if self.port == self.libp2p_port {
+            problems.add(
+                "--libp2p-port",
+                format!("same as the http `--port` {}", self.port),
+                None,
+            );
+        }
+        if let Some(path) = &self.peer_list_file {
+            problems.check_file("--peer-list-file", path);
+        }
+
+        if let Some(path) = &self.producer_key {
+            problems.check_file("--producer-key", path);
+            if !profile.allows_block_producer() {
+                problems.add(
+                    "--producer-key",
+                    format!("block producer can't be enabled with `{profile}` profile"),
+                    Some("use `full` or `producer` profile"),
+                );
+            }
+        } else if profile == NodeProfile::Producer {
+            problems.add(
+                "--profile",
+                "`producer` profile requires block producer key",
+                Some("pass the key file with `--producer-key`"),
+            );
+        }
+        if let Some(path) = &self.payout_key {
+            problems.check_file("--payout-key", path);
+        }
+        if let Some(path) = &self.fee_sponsor_key {
+            problems.check_file("--fee-sponsor-key", path);
+        }
+        if self.run_snarker.is_some() && !profile.allows_snarker() {
+            problems.add(
+                "--run-snarker",
+                format!("snarker can't be enabled with `{profile}` profile"),
+                Some("use `full` or `snarker` profile"),
+            );
+        } else if self.run_snarker.is_none() && profile == NodeProfile::Snarker {
+            problems.add(
+                "--profile",
+                "`snarker` profile requires snarker key",
+                Some("pass the key with `--run-snarker`"),
+            );
+        }
+        let archive_enabled = self.archive_local_storage
+            || self.archive_archiver_process
+            || self.archive_gcp_storage
+            || self.archive_aws_storage;
+        if archive_enabled && !profile.allows_archive() {
+            problems.add(
+                "--archive-*",
+                format!("archive can't be enabled with `{profile}` profile"),
+                Some("use `full` or `archive` profile"),
+            );
+        }
+
+        if self.pool_fee_payer_max_commands.is_some() && self.pool_fee_payer_window_secs == 0 {
+            problems.add(
+                "--pool-fee-payer-window-secs",
+                "must not be 0 if `--pool-fee-payer-max-commands` is set",
+                None,
+            );
+        }
+        for pub_key in &self.pool_fee_payer_allowlist {
+            if pub_key
+                .clone()
+                .try_into()
+                .map(|pub_key| AccountId::new(pub_key, TokenId::default()))
+                .is_err()
+            {
+                problems.add(
+                    "--pool-fee-payer-allow",
+                    format!("invalid public key {pub_key}"),
+                    None,
+                );
+            }
+        }
+
+        if let (Some(prune), Some(pause)) = (self.disk_prune_below_mb, self.disk_pause_below_mb) {
+            if prune <= pause {
+                problems.add(
+                    "--disk-prune-below-mb",
+                    format!("must be above `--disk-pause-below-mb` {pause}"),
+                    Some("prune the old dumps before pausing them"),
+                );
+            }
+        }
+        if self.dump_password.as_deref() == Some("") {
+            problems.add("--dump-password", "must not be empty", None);
+        }
+        if !matches!(self.record.trim(), "none" | "state-with-input-actions") {
+            problems.add(
+                "--record",
+                format!("unknown strategy {:?}", self.record),
+                Some("use `none` or `state-with-input-actions`"),
+            );
+        }
+
+        if problems.0.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        node: Node,
+    }
+
+    fn problems(args: &[&str]) -> Vec<String> {
+        let cli =
+            Cli::try_parse_from(std::iter::once("openmina").chain(args.iter().copied())).unwrap();
+        match cli.node.validate() {
+            Ok(()) => vec![],
+            Err(problems) => problems.0.into_iter().map(|p| p.field).collect(),
+        }
+    }
+
+    #[test]
+    fn reports_all_problems() {
+        assert!(problems(&[]).is_empty());
+        assert_eq!(
+            problems(&[
+                "--profile",
+                "producer",
+                "--libp2p-port",
+                "3000",
+                "--libp2p-external-ip",
+                "not-an-ip",
+                "--record",
+                "everything",
+            ]),
+            [
+                "--libp2p-external-ip",
+                "--libp2p-port",
+                "--profile",
+                "--record"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_missing_key_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing-key");
+        assert_eq!(
+            problems(&["--producer-key", path.to_str().unwrap()]),
+            ["--producer-key"]
+        );
+    }
+}
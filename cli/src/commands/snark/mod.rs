@@ -1,6 +1,9 @@
 pub mod precalculate_block_verifier_index_and_srs;
 pub use precalculate_block_verifier_index_and_srs::PrecalculateBlockVerifierIndexAndSrs;
 
+pub mod verify_block;
+pub use verify_block::VerifyBlock;
+
 #[derive(Debug, clap::Args)]
 pub struct Snark {
     #[command(subcommand)]
@@ -10,12 +13,14 @@ pub struct Snark {
 #[derive(Debug, clap::Subcommand)]
 pub enum SnarkCommand {
     PrecalculateBlockVerifierIndexAndSrs(PrecalculateBlockVerifierIndexAndSrs),
+    VerifyBlock(VerifyBlock),
 }
 
 impl Snark {
     pub fn run(self) -> anyhow::Result<()> {
         match self.command {
             SnarkCommand::PrecalculateBlockVerifierIndexAndSrs(v) => v.run(),
+            SnarkCommand::VerifyBlock(v) => v.run(),
         }
     }
 }
@@ -0,0 +1,121 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use mina_p2p_messages::{
+    binprot::BinProtRead,
+    v2::{MinaBlockBlockStableV2, PrecomputedBlock, PrecomputedBlockData, StateHash},
+};
+use node::snark::block_verify::BlockVerifierOffline;
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum BlockFormat {
+    /// Precomputed block, as exported by the archive node.
+    Json,
+    /// Binprot encoded block, as sent over the network.
+    Binprot,
+}
+
+#[derive(Debug, clap::Args)]
+/// Verify the blocks (proof and structure) without running the node.
+///
+/// Hash of the precomputed block is checked against the one in the
+/// file name, if present (`<network>-<height>-<state_hash>.json`).
+pub struct VerifyBlock {
+    /// Block files to verify.
+    #[arg(required = true)]
+    pub blocks: Vec<PathBuf>,
+
+    /// Format of the block files, detected from the extension if not set.
+    #[arg(long, value_enum)]
+    pub format: Option<BlockFormat>,
+
+    /// Expected hash of the block, if a single block is verified.
+    #[arg(long)]
+    pub hash: Option<StateHash>,
+
+    /// Verifier index, as precalculated by
+    /// `openmina snark precalculate-block-verifier-index-and-srs`.
+    /// The one for the selected network is used if not set.
+    #[arg(long, requires = "srs")]
+    pub verifier_index: Option<PathBuf>,
+
+    /// Verifier SRS, as precalculated by
+    /// `openmina snark precalculate-block-verifier-index-and-srs`.
+    #[arg(long, requires = "verifier_index")]
+    pub srs: Option<PathBuf>,
+}
+
+impl VerifyBlock {
+    pub fn run(self) -> anyhow::Result<()> {
+        if self.hash.is_some() && self.blocks.len() > 1 {
+            anyhow::bail!("`--hash` can only be used with a single block");
+        }
+
+        let verifier = match (&self.verifier_index, &self.srs) {
+            (Some(index), Some(srs)) => {
+                let index = fs::read(index).with_context(|| format!("reading {index:?}"))?;
+                let srs = fs::read(srs).with_context(|| format!("reading {srs:?}"))?;
+                BlockVerifierOffline::from_bytes(&index, &srs)?
+            }
+            _ => BlockVerifierOffline::make(),
+        };
+
+        let mut failed = 0usize;
+        for path in &self.blocks {
+            let result = self.read_block(path).and_then(|(block, expected_hash)| {
+                Ok(verifier.verify(&block, self.hash.as_ref().or(expected_hash.as_ref()))?)
+            });
+            match result {
+                Ok(hash) => println!("{}: ok {hash}", path.display()),
+                Err(err) => {
+                    failed = failed.saturating_add(1);
+                    println!("{}: failed: {err:#}", path.display());
+                }
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!(
+                "{failed} of {} blocks failed verification",
+                self.blocks.len()
+            );
+        }
+        Ok(())
+    }
+
+    fn read_block(
+        &self,
+        path: &Path,
+    ) -> anyhow::Result<(MinaBlockBlockStableV2, Option<StateHash>)> {
+        let bytes = fs::read(path).with_context(|| format!("reading {path:?}"))?;
+        let format =
+            self.format
+                .unwrap_or_else(|| match path.extension().and_then(|ext| ext.to_str()) {
+                    Some("json") => BlockFormat::Json,
+                    _ => BlockFormat::Binprot,
+                });
+
+        match format {
+            BlockFormat::Json => {
+                let block = serde_json::from_slice::<PrecomputedBlockData>(&bytes)
+                    .map(|block| block.data)
+                    .or_else(|_| serde_json::from_slice::<PrecomputedBlock>(&bytes))
+                    .context("parsing precomputed block")?;
+                let expected_hash = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.rsplit('-').next())
+                    .and_then(|hash| hash.parse().ok());
+                Ok((block.into(), expected_hash))
+            }
+            BlockFormat::Binprot => {
+                let block = MinaBlockBlockStableV2::binprot_read(&mut bytes.as_slice())
+                    .context("parsing binprot block")?;
+                Ok((block, None))
+            }
+        }
+    }
+}
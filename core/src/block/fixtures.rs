@@ -0,0 +1,47 @@
+//! Blocks for the tests of the crates handling them.
+
+use std::sync::Arc;
+
+use mina_p2p_messages::v2;
+
+use super::{ArcBlockWithHash, Block};
+
+/// Best tip of the recorded `BestTipWithProof` rpc response.
+pub fn best_tip() -> Block {
+    let response: serde_json::Value = serde_json::from_slice(include_bytes!(
+        "../../../p2p/tests/files/rpc/best_tip_with_proof_response.json"
+    ))
+    .expect("valid response");
+    serde_json::from_value(response["BestTipWithProof"]["best_tip"].clone()).expect("valid block")
+}
+
+/// [`best_tip`] with its hash.
+pub fn best_tip_with_hash() -> ArcBlockWithHash {
+    ArcBlockWithHash::try_new(Arc::new(best_tip())).expect("hashable block")
+}
+
+/// Copy of the `base`, `height` blocks and `slots` slots after it, modified
+/// by `f`.
+pub fn block_after(
+    base: &ArcBlockWithHash,
+    height: u32,
+    slots: u32,
+    f: impl FnOnce(&mut Block),
+) -> ArcBlockWithHash {
+    let mut block = (*base.block).clone();
+    let consensus_state = &mut block.header.protocol_state.body.consensus_state;
+    consensus_state.blockchain_length =
+        v2::UnsignedExtendedUInt32StableV1(base.height().saturating_add(height).into());
+    consensus_state.curr_global_slot_since_hard_fork.slot_number =
+        v2::MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork(
+            base.global_slot().saturating_add(slots).into(),
+        );
+    consensus_state.global_slot_since_genesis =
+        v2::MinaNumbersGlobalSlotSinceGenesisMStableV1::SinceGenesis(
+            base.global_slot_since_genesis()
+                .saturating_add(slots)
+                .into(),
+        );
+    f(&mut block);
+    ArcBlockWithHash::try_new(Arc::new(block)).expect("hashable block")
+}
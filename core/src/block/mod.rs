@@ -8,6 +8,9 @@ pub mod prevalidate;
 
 pub mod genesis;
 
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod fixtures;
+
 use std::sync::Arc;
 
 pub use mina_p2p_messages::v2::MinaBlockBlockStableV2 as Block;
@@ -1,7 +1,14 @@
+use mina_p2p_messages::v2::{
+    MinaBaseUserCommandStableV2, StagedLedgerDiffDiffFtStableV1,
+    StagedLedgerDiffDiffPreDiffWithAtMostOneCoinbaseStableV2Coinbase as AtMostOneCoinbase,
+    StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B,
+    StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2Coinbase as AtMostTwoCoinbase,
+    TransactionSnarkWorkTStableV2,
+};
 use serde::{Deserialize, Serialize};
 
 use super::ArcBlockWithHash;
-use crate::constants::PROTOCOL_VERSION;
+use crate::constants::{constraint_constants, PROTOCOL_VERSION};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum BlockPrevalidationError {
@@ -20,6 +27,31 @@ pub enum BlockPrevalidationError {
     MismatchedProtocolVersion,
     ConsantsMismatch,
     InvalidDeltaBlockChainProof,
+    InvalidStagedLedgerDiff(StagedLedgerDiffPrevalidationError),
+}
+
+/// Structural errors in the staged ledger diff, which can be detected
+/// without the ledger.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StagedLedgerDiffPrevalidationError {
+    #[error("too many user commands: {count} > {max}")]
+    TooManyCommands { count: usize, max: usize },
+    #[error("too many completed works: {count} > {max}")]
+    TooManyCompletedWorks { count: usize, max: usize },
+    #[error("sum of the transaction fees overflows")]
+    TransactionFeesOverflow,
+    #[error("sum of the snark work fees overflows")]
+    SnarkFeesOverflow,
+    #[error("coinbase in both pre diffs")]
+    CoinbaseInBothPreDiffs,
+    #[error("coinbase fee transfers {fee} exceed the coinbase amount {coinbase_amount}")]
+    CoinbaseFeeTransferTooLarge { fee: u64, coinbase_amount: u64 },
+    #[error("insufficient fee in pre diff {pre_diff}: budget {budget} < work fee {work_fee}")]
+    InsufficientFee {
+        pre_diff: usize,
+        budget: u64,
+        work_fee: u64,
+    },
 }
 
 impl BlockPrevalidationError {
@@ -101,6 +133,132 @@ pub fn validate_constants(
     Ok(())
 }
 
+/// Cheap structural checks of the staged ledger diff: sizes, fee
+/// arithmetic and coinbase. Passing them doesn't mean that the diff
+/// applies, but failing them means that it never will.
+pub fn validate_staged_ledger_diff(
+    block: &ArcBlockWithHash,
+) -> Result<(), StagedLedgerDiffPrevalidationError> {
+    let constants = constraint_constants();
+    let capacity = 1usize
+        .checked_shl(constants.transaction_capacity_log_2 as u32)
+        .unwrap_or(usize::MAX);
+    let body = block.body();
+    let diff = block.staged_ledger_diff();
+
+    let count = body.commands_iter().count();
+    if count > capacity {
+        return Err(StagedLedgerDiffPrevalidationError::TooManyCommands {
+            count,
+            max: capacity,
+        });
+    }
+    // Each work covers at most two jobs and each transaction adds at
+    // most two jobs to the scan state.
+    let count = body.completed_works_count();
+    let max = capacity.saturating_mul(2);
+    if count > max {
+        return Err(StagedLedgerDiffPrevalidationError::TooManyCompletedWorks { count, max });
+    }
+
+    let first_coinbase: Vec<_> = match &diff.0.coinbase {
+        AtMostTwoCoinbase::Zero => vec![],
+        AtMostTwoCoinbase::One(ft) => ft.iter().collect(),
+        AtMostTwoCoinbase::Two(fts) => fts
+            .iter()
+            .flat_map(|(ft1, ft2)| std::iter::once(ft1).chain(ft2))
+            .collect(),
+    };
+    validate_pre_diff_fees(0, &diff.0.commands, &diff.0.completed_works, first_coinbase)?;
+    if let Some(second) = diff.1.as_ref() {
+        let second_coinbase = match &second.coinbase {
+            AtMostOneCoinbase::Zero => None,
+            AtMostOneCoinbase::One(ft) => ft.as_ref(),
+        };
+        validate_pre_diff_fees(
+            1,
+            &second.commands,
+            &second.completed_works,
+            second_coinbase,
+        )?;
+    }
+
+    let second_coinbase = diff.1.as_ref().map(|d| &d.coinbase);
+    if !matches!(diff.0.coinbase, AtMostTwoCoinbase::Zero)
+        && matches!(second_coinbase, Some(AtMostOneCoinbase::One(_)))
+    {
+        return Err(StagedLedgerDiffPrevalidationError::CoinbaseInBothPreDiffs);
+    }
+
+    // Coinbase may be supercharged, so we can only check against the
+    // upper bound.
+    let coinbase_amount = constants
+        .coinbase_amount
+        .saturating_mul(constants.supercharged_coinbase_factor.max(1));
+    let fee = body
+        .coinbase_fee_transfers_iter()
+        .map(|ft| ft.0.fee.as_u64())
+        .try_fold(0u64, u64::checked_add)
+        .unwrap_or(u64::MAX);
+    if fee > coinbase_amount {
+        return Err(
+            StagedLedgerDiffPrevalidationError::CoinbaseFeeTransferTooLarge {
+                fee,
+                coinbase_amount,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Fee excess of a single pre diff, same as `fee_remainder` in the
+/// staged ledger: transaction fees must pay for the snark work that
+/// isn't already paid from the coinbase.
+fn validate_pre_diff_fees<'a>(
+    pre_diff: usize,
+    commands: &[StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B],
+    completed_works: &[TransactionSnarkWorkTStableV2],
+    coinbase_fee_transfers: impl IntoIterator<Item = &'a StagedLedgerDiffDiffFtStableV1>,
+) -> Result<(), StagedLedgerDiffPrevalidationError> {
+    let budget = commands
+        .iter()
+        .map(|command| match &command.data {
+            MinaBaseUserCommandStableV2::SignedCommand(v) => v.payload.common.fee.as_u64(),
+            MinaBaseUserCommandStableV2::ZkappCommand(v) => v.fee_payer.body.fee.as_u64(),
+        })
+        .try_fold(0u64, u64::checked_add)
+        .ok_or(StagedLedgerDiffPrevalidationError::TransactionFeesOverflow)?;
+    let work_fee = completed_works
+        .iter()
+        .map(|work| work.fee.as_u64())
+        .try_fold(0u64, u64::checked_add)
+        .ok_or(StagedLedgerDiffPrevalidationError::SnarkFeesOverflow)?;
+    let coinbase_fee = coinbase_fee_transfers
+        .into_iter()
+        .map(|ft| ft.0.fee.as_u64())
+        .try_fold(0u64, u64::checked_add)
+        .unwrap_or(u64::MAX);
+    check_fee_excess(pre_diff, budget, work_fee, coinbase_fee)
+}
+
+fn check_fee_excess(
+    pre_diff: usize,
+    budget: u64,
+    work_fee: u64,
+    coinbase_fee: u64,
+) -> Result<(), StagedLedgerDiffPrevalidationError> {
+    let work_fee = work_fee.saturating_sub(coinbase_fee);
+    if budget < work_fee {
+        return Err(StagedLedgerDiffPrevalidationError::InsufficientFee {
+            pre_diff,
+            budget,
+            work_fee,
+        });
+    }
+    Ok(())
+}
+
 pub fn prevalidate_block(
     block: &ArcBlockWithHash,
     genesis: &ArcBlockWithHash,
@@ -111,6 +269,7 @@ pub fn prevalidate_block(
     validate_genesis_state(block, genesis)?;
     validate_protocol_versions(block)?;
     validate_constants(block, genesis)?;
+    validate_staged_ledger_diff(block).map_err(BlockPrevalidationError::InvalidStagedLedgerDiff)?;
 
     // TODO(tizoc): check for InvalidDeltaBlockChainProof
     // https://github.com/MinaProtocol/mina/blob/d800da86a764d8d37ffb8964dd8d54d9f522b358/src/lib/mina_block/validation.ml#L369
@@ -118,3 +277,135 @@ pub fn prevalidate_block(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use binprot::BinProtRead;
+    use mina_p2p_messages::v2::{
+        self, ArchiveTransitionFrontierDiff, MinaBaseCoinbaseFeeTransferStableV1,
+    };
+
+    use super::*;
+    use crate::block::{fixtures::best_tip, Block, BlockWithHash};
+
+    fn command(nonce: u32) -> StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B {
+        let pk = "B62qnEdPB1V5YPEcGaETb19naLJV6sWdveCZEjSLhcVyrPcPWHkGGax";
+        serde_json::from_value(serde_json::json!({
+            "data": ["Signed_command", {
+                "payload": {
+                    "common": {
+                        "fee": "85776125",
+                        "fee_payer_pk": pk,
+                        "nonce": nonce.to_string(),
+                        "valid_until": "4294967295",
+                        "memo": "E4Yd67s51QN9DZVDy8JKPEoNGykMsYQ5KRiKpZHiLZTjA8dB9SnFT",
+                    },
+                    "body": ["Payment", {
+                        "receiver_pk": "B62qmurT6PKCyetUB6aqagxPbWc74uDaddMdDSm9FQd7ELTKn6BRdv4",
+                        "amount": "1500000",
+                    }],
+                },
+                "signer": pk,
+                "signature": "7mX1Jc4Et7GHmRcTLSJU5E1aKUoja4gyusEXe2xFnyGoy6DHHDVWmuLCTveWnTaBcWQi51sLNuYVMsXkSiR4QBnf5Ga4yCR1",
+            }],
+            "status": ["Applied"],
+        }))
+        .unwrap()
+    }
+
+    fn fee_transfer(block: &Block, fee: u64) -> StagedLedgerDiffDiffFtStableV1 {
+        StagedLedgerDiffDiffFtStableV1(MinaBaseCoinbaseFeeTransferStableV1 {
+            receiver_pk: block
+                .header
+                .protocol_state
+                .body
+                .consensus_state
+                .coinbase_receiver
+                .clone(),
+            fee: v2::CurrencyFeeStableV1(fee.into()),
+        })
+    }
+
+    fn validate(block: Block) -> Result<(), StagedLedgerDiffPrevalidationError> {
+        validate_staged_ledger_diff(&BlockWithHash::try_new(Arc::new(block)).unwrap())
+    }
+
+    fn coinbase_upper_bound() -> u64 {
+        let constants = constraint_constants();
+        constants
+            .coinbase_amount
+            .saturating_mul(constants.supercharged_coinbase_factor.max(1))
+    }
+
+    #[test]
+    fn honest_blocks_pass() {
+        assert_eq!(validate(best_tip()), Ok(()));
+
+        let mut block = best_tip();
+        block.body.staged_ledger_diff.diff.0.commands = (1..=3).map(command).collect();
+        assert_eq!(validate(block), Ok(()));
+
+        let breadcrumb = include_bytes!(
+            "../../../tests/files/archive-breadcrumb/3NK56ZbCS31qb8SvCtCCYza4beRDtKgXA2JL6s3evKouG2KkKtiy.bin"
+        );
+        let diff = ArchiveTransitionFrontierDiff::binprot_read(&mut breadcrumb.as_slice()).unwrap();
+        assert_eq!(validate(diff.block().unwrap()), Ok(()));
+    }
+
+    #[test]
+    fn coinbase_fee_transfer_above_coinbase() {
+        let mut block = best_tip();
+        let ft = fee_transfer(&block, coinbase_upper_bound().saturating_add(1));
+        block.body.staged_ledger_diff.diff.0.coinbase = AtMostTwoCoinbase::One(Some(ft));
+        assert!(matches!(
+            validate(block),
+            Err(StagedLedgerDiffPrevalidationError::CoinbaseFeeTransferTooLarge { .. })
+        ));
+
+        // each transfer fits, but not both of them.
+        let mut block = best_tip();
+        let half = coinbase_upper_bound().checked_div(2).unwrap();
+        let ft1 = fee_transfer(&block, half.saturating_add(1));
+        let ft2 = fee_transfer(&block, half.saturating_add(1));
+        block.body.staged_ledger_diff.diff.0.coinbase =
+            AtMostTwoCoinbase::Two(Some((ft1, Some(ft2))));
+        assert!(matches!(
+            validate(block),
+            Err(StagedLedgerDiffPrevalidationError::CoinbaseFeeTransferTooLarge { .. })
+        ));
+
+        let mut block = best_tip();
+        let ft1 = fee_transfer(&block, half);
+        let ft2 = fee_transfer(&block, half);
+        block.body.staged_ledger_diff.diff.0.coinbase =
+            AtMostTwoCoinbase::Two(Some((ft1, Some(ft2))));
+        assert_eq!(validate(block), Ok(()));
+    }
+
+    #[test]
+    fn fee_excess() {
+        assert_eq!(check_fee_excess(0, 0, 0, 0), Ok(()));
+        assert_eq!(check_fee_excess(0, 15, 15, 0), Ok(()));
+        // the coinbase pays for part of the work.
+        assert_eq!(check_fee_excess(0, 10, 15, 5), Ok(()));
+        assert_eq!(check_fee_excess(1, 0, 5, 10), Ok(()));
+        assert_eq!(
+            check_fee_excess(1, 10, 15, 0),
+            Err(StagedLedgerDiffPrevalidationError::InsufficientFee {
+                pre_diff: 1,
+                budget: 10,
+                work_fee: 15,
+            })
+        );
+        assert_eq!(
+            check_fee_excess(0, 10, 16, 5),
+            Err(StagedLedgerDiffPrevalidationError::InsufficientFee {
+                pre_diff: 0,
+                budget: 10,
+                work_fee: 11,
+            })
+        );
+    }
+}
@@ -67,6 +67,18 @@ pub mod mpsc {
         pub fn try_send(&self, message: T) -> Result<(), TrySendError<T>> {
             self.0.try_send(message)
         }
+
+        pub fn len(&self) -> usize {
+            self.0.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+
+        pub fn capacity(&self) -> Option<usize> {
+            self.0.capacity()
+        }
     }
 
     impl<T> Receiver<T> {
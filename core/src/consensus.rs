@@ -1,3 +1,5 @@
+pub mod finality;
+
 use mina_p2p_messages::v2::{
     self, BlockTimeTimeStableV1,
     ConsensusProofOfStakeDataConsensusStateValueStableV2 as MinaConsensusState, StateHash,
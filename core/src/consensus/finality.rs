@@ -0,0 +1,128 @@
+//! Finality estimation for the best chain blocks.
+//!
+//! Block is final once it is `k` blocks deep. Before that, we estimate
+//! the probability of it not being reverted, assuming that fraction `q`
+//! of the stake is adversarial. Probability of an adversary catching up
+//! from `depth` blocks behind is `(q / (1 - q))^depth` (see Bitcoin
+//! whitepaper, section 11).
+//!
+//! `q` is estimated from the chain density in the current window of the
+//! best tip and from the number of competing fork blocks we have observed.
+//! Missing chain density is conservatively treated as withheld stake.
+
+use serde::{Deserialize, Serialize};
+
+/// Active slots coefficient `f` of the Ouroboros Samasika consensus.
+pub const ACTIVE_SLOTS_COEFF: f64 = 0.75;
+
+/// Lower bound for the adversarial stake estimate, so that healthy chain
+/// doesn't make estimate overconfident.
+pub const MIN_ADVERSARIAL_STAKE: f64 = 0.1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FinalityObservations {
+    /// Number of blocks in the current window of the best tip.
+    pub window_density: u32,
+    pub slots_per_window: u32,
+    /// Number of observed blocks on competing forks.
+    pub fork_blocks: u32,
+    /// Number of best chain blocks during which forks were observed.
+    pub chain_length: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FinalityEstimator {
+    k: u32,
+    adversarial_stake: f64,
+}
+
+impl FinalityEstimator {
+    pub fn new(k: u32, observations: &FinalityObservations) -> Self {
+        let expected_density = observations.slots_per_window as f64 * ACTIVE_SLOTS_COEFF;
+        let participation = if expected_density > 0.0 {
+            (observations.window_density as f64 / expected_density).min(1.0)
+        } else {
+            1.0
+        };
+        let fork_rate = if observations.chain_length > 0 {
+            (observations.fork_blocks as f64 / observations.chain_length as f64).min(1.0)
+        } else {
+            0.0
+        };
+        let adversarial_stake = (1.0 - participation)
+            .max(fork_rate)
+            .clamp(MIN_ADVERSARIAL_STAKE, 0.5);
+        Self {
+            k,
+            adversarial_stake,
+        }
+    }
+
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    pub fn adversarial_stake(&self) -> f64 {
+        self.adversarial_stake
+    }
+
+    /// Estimated probability that the block `depth` blocks deep from the
+    /// best tip won't be reverted.
+    pub fn confidence(&self, depth: u32) -> f64 {
+        if depth >= self.k {
+            return 1.0;
+        }
+        let q = self.adversarial_stake;
+        if q >= 0.5 {
+            return 0.0;
+        }
+        1.0 - (q / (1.0 - q)).powi(depth as i32)
+    }
+
+    /// Minimum number of confirmations needed to reach the `confidence`.
+    /// Never more than `k`, after which blocks are final.
+    pub fn confirmations_for(&self, confidence: f64) -> u32 {
+        (0..self.k)
+            .find(|depth| self.confidence(*depth) >= confidence)
+            .unwrap_or(self.k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observations(window_density: u32, fork_blocks: u32) -> FinalityObservations {
+        FinalityObservations {
+            window_density,
+            slots_per_window: 77,
+            fork_blocks,
+            chain_length: 100,
+        }
+    }
+
+    #[test]
+    fn confidence_grows_with_depth() {
+        let estimator = FinalityEstimator::new(290, &observations(58, 0));
+        assert_eq!(estimator.adversarial_stake(), MIN_ADVERSARIAL_STAKE);
+        assert_eq!(estimator.confidence(0), 0.0);
+        assert!(estimator.confidence(1) < estimator.confidence(2));
+        assert_eq!(estimator.confidence(290), 1.0);
+    }
+
+    #[test]
+    fn low_density_needs_more_confirmations() {
+        let healthy = FinalityEstimator::new(290, &observations(58, 0));
+        let sparse = FinalityEstimator::new(290, &observations(30, 0));
+        assert!(sparse.adversarial_stake() > healthy.adversarial_stake());
+        assert!(sparse.confirmations_for(0.999) > healthy.confirmations_for(0.999));
+    }
+
+    #[test]
+    fn forks_cap_confirmations_at_k() {
+        let estimator = FinalityEstimator::new(290, &observations(58, 100));
+        assert_eq!(estimator.adversarial_stake(), 0.5);
+        assert_eq!(estimator.confidence(289), 0.0);
+        assert_eq!(estimator.confirmations_for(0.99), 290);
+    }
+}
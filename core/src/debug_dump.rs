@@ -0,0 +1,208 @@
+//! Optional encryption at rest for debug dumps and recorder output.
+//!
+//! The key is derived from a password with argon2, the same way as for
+//! [`crate::EncryptedSecretKey`]. Salt is generated once per process, so
+//! the key is derived only once, and each encrypted blob carries the
+//! parameters needed to derive it again when decrypting.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use argon2::{password_hash::SaltString, PasswordHasher};
+use base64::Engine;
+use crypto_secretbox::aead::{Aead, OsRng};
+use crypto_secretbox::{AeadCore, KeyInit, XSalsa20Poly1305};
+use once_cell::sync::OnceCell;
+
+use crate::encrypted_key::{setup_argon, EncryptionError};
+
+/// Prefix of the encrypted blob.
+const MAGIC: &[u8; 8] = b"OMDUMPE1";
+const NONCE_LEN: usize = 24;
+/// Argon2i parameters, same as for the encrypted secret keys.
+const PW_DIFF: (u32, u32) = (134217728, 6);
+
+struct DumpKey {
+    pwdiff: (u32, u32),
+    salt: Vec<u8>,
+    key: Vec<u8>,
+}
+
+static DUMP_KEY: OnceCell<DumpKey> = OnceCell::new();
+
+static WRITES_PAUSED: AtomicBool = AtomicBool::new(false);
+
+fn derive_key(password: &str, salt: &[u8], pwdiff: (u32, u32)) -> Result<Vec<u8>, EncryptionError> {
+    // Same as in `EncryptedSecretKey`, `SaltString` can only be built from base64.
+    let salt_encoded = base64::engine::general_purpose::STANDARD_NO_PAD.encode(salt);
+    let salt = SaltString::from_b64(&salt_encoded)?;
+    let hash = setup_argon(pwdiff)?
+        .hash_password(password.as_bytes(), &salt)?
+        .hash
+        .ok_or(EncryptionError::HashMissing)?;
+    Ok(hash.as_bytes().to_vec())
+}
+
+impl DumpKey {
+    fn new(password: &str, pwdiff: (u32, u32)) -> Result<Self, EncryptionError> {
+        let salt = SaltString::generate(&mut OsRng);
+        let mut salt_bytes = [0; 32];
+        let salt = salt.decode_b64(&mut salt_bytes)?.to_vec();
+        let key = derive_key(password, &salt, pwdiff)?;
+        Ok(Self { pwdiff, salt, key })
+    }
+
+    fn seal(&self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let Self { pwdiff, salt, key } = self;
+        let nonce = XSalsa20Poly1305::generate_nonce(&mut OsRng);
+        let cipher = XSalsa20Poly1305::new_from_slice(key)?;
+        let ciphertext = cipher.encrypt(&nonce, data)?;
+
+        let mut sealed =
+            Vec::with_capacity(MAGIC.len() + 9 + salt.len() + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(MAGIC);
+        sealed.extend_from_slice(&pwdiff.0.to_be_bytes());
+        sealed.extend_from_slice(&pwdiff.1.to_be_bytes());
+        sealed.push(salt.len() as u8);
+        sealed.extend_from_slice(salt);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+}
+
+/// Enables encryption of dumps with the key derived from the `password`.
+///
+/// Can only be set once.
+pub fn set_dump_encryption_password(password: &str) -> Result<(), EncryptionError> {
+    DUMP_KEY
+        .set(DumpKey::new(password, PW_DIFF)?)
+        .map_err(|_| EncryptionError::Other("dump encryption password already set".to_owned()))
+}
+
+pub fn is_dump_encryption_enabled() -> bool {
+    DUMP_KEY.get().is_some()
+}
+
+/// Whether the data was encrypted with [`dump_seal`].
+pub fn is_dump_sealed(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encrypts the data if dump encryption is enabled, returns it as is otherwise.
+pub fn dump_seal(data: Vec<u8>) -> Result<Vec<u8>, EncryptionError> {
+    match DUMP_KEY.get() {
+        Some(key) => key.seal(&data),
+        None => Ok(data),
+    }
+}
+
+/// Pauses or resumes the writes of the debug dumps, recorder and event
+/// journal, which aren't needed for the node to function. Used by the
+/// disk monitor when the disk is about to get full.
+pub fn set_writes_paused(paused: bool) {
+    WRITES_PAUSED.store(paused, Ordering::Relaxed);
+}
+
+pub fn are_writes_paused() -> bool {
+    WRITES_PAUSED.load(Ordering::Relaxed)
+}
+
+/// Writes the dump to the file, encrypting it if dump encryption is enabled.
+///
+/// Fails if the writes are paused, see [`set_writes_paused`].
+pub fn dump_write(path: impl AsRef<Path>, data: Vec<u8>) -> std::io::Result<()> {
+    if are_writes_paused() {
+        return Err(std::io::Error::other("debug writes paused, low disk space"));
+    }
+    let data = dump_seal(data).map_err(std::io::Error::other)?;
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(&data)?;
+    file.sync_all()
+}
+
+/// Decrypts dumps encrypted with [`dump_seal`].
+///
+/// Derived keys are cached, since all the dumps written by the same
+/// process are encrypted with the same key.
+pub struct DumpDecryptor {
+    password: String,
+    keys: BTreeMap<(u32, u32, Vec<u8>), Vec<u8>>,
+}
+
+impl DumpDecryptor {
+    pub fn new(password: String) -> Self {
+        Self {
+            password,
+            keys: Default::default(),
+        }
+    }
+
+    pub fn open(&mut self, sealed: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let invalid = || EncryptionError::Other("invalid encrypted dump".to_owned());
+        let data = sealed.strip_prefix(MAGIC).ok_or_else(invalid)?;
+        let u32_at = |i: usize| -> Result<u32, EncryptionError> {
+            let bytes = data.get(i..i + 4).ok_or_else(invalid)?;
+            Ok(u32::from_be_bytes(bytes.try_into().map_err(|_| invalid())?))
+        };
+        let pwdiff = (u32_at(0)?, u32_at(4)?);
+        let salt_len = *data.get(8).ok_or_else(invalid)? as usize;
+        let data = data.get(9..).ok_or_else(invalid)?;
+        if data.len() < salt_len + NONCE_LEN {
+            return Err(invalid());
+        }
+        let (salt, data) = data.split_at(salt_len);
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+
+        let key = match self.keys.get(&(pwdiff.0, pwdiff.1, salt.to_vec())) {
+            Some(key) => key,
+            None => {
+                let key = derive_key(&self.password, salt, pwdiff)?;
+                self.keys
+                    .entry((pwdiff.0, pwdiff.1, salt.to_vec()))
+                    .or_insert(key)
+            }
+        };
+        let cipher = XSalsa20Poly1305::new_from_slice(key)?;
+        Ok(cipher.decrypt(nonce.into(), ciphertext)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheaper than [`PW_DIFF`], the parameters are read from the blob anyway.
+    const TEST_PW_DIFF: (u32, u32) = (64 * 1024, 1);
+
+    #[test]
+    fn test_dump_seal_open() {
+        let data = b"peer ids and transactions".to_vec();
+        let sealed = DumpKey::new("secret", TEST_PW_DIFF)
+            .unwrap()
+            .seal(&data)
+            .unwrap();
+        assert!(is_dump_sealed(&sealed));
+        assert!(!sealed
+            .windows(data.len())
+            .any(|window| window == data.as_slice()));
+
+        let mut decryptor = DumpDecryptor::new("secret".to_owned());
+        assert_eq!(decryptor.open(&sealed).unwrap(), data);
+        // Cached key opens it again.
+        assert_eq!(decryptor.open(&sealed).unwrap(), data);
+
+        let mut tampered = sealed.clone();
+        if let Some(byte) = tampered.last_mut() {
+            *byte ^= 1;
+        }
+        assert!(decryptor.open(&tampered).is_err());
+        assert!(decryptor.open(&sealed[..sealed.len() / 2]).is_err());
+        assert!(decryptor.open(&data).is_err());
+
+        let mut wrong_password = DumpDecryptor::new("other".to_owned());
+        assert!(wrong_password.open(&sealed).is_err());
+    }
+}
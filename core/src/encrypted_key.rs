@@ -60,7 +60,7 @@ impl EncryptedSecretKeyFile {
     }
 }
 
-fn setup_argon(pwdiff: (u32, u32)) -> Result<Argon2<'static>, EncryptionError> {
+pub(crate) fn setup_argon(pwdiff: (u32, u32)) -> Result<Argon2<'static>, EncryptionError> {
     let params = argon2::Params::new(
         pwdiff.0 / 1024,
         pwdiff.1,
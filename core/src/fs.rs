@@ -0,0 +1,68 @@
+//! Filesystem helpers.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Path of a temporary file next to the `path`, unique to this process
+/// and call, so that concurrent writers (threads or nodes sharing the
+/// work dir) don't write into the same temporary file.
+pub fn unique_tmp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{file_name}.{}.{n}.tmp", std::process::id()))
+}
+
+/// Writes the file with `write` to a temporary file first and then renames
+/// it over the `path`, so that the readers never see it partially written
+/// and the previous version survives a crash in the middle.
+pub fn write_atomic(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let tmp_path = unique_tmp_path(path);
+    let result = (|| {
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        write(&mut writer)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        std::fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = std::env::temp_dir().join(format!("openmina-fs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("file.json");
+
+        assert_ne!(unique_tmp_path(&path), unique_tmp_path(&path));
+
+        write_atomic(&path, |w| w.write_all(b"first")).unwrap();
+        write_atomic(&path, |w| w.write_all(b"second")).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+
+        // Failed write keeps the previous content and no temporary files.
+        let err = write_atomic(&path, |_| Err(std::io::Error::other("failed")));
+        assert!(err.is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
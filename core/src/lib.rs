@@ -30,12 +30,21 @@ pub use substate::{Substate, SubstateAccess, SubstateResult};
 pub mod network;
 pub use network::NetworkConfig;
 
+pub mod proxy;
+pub use proxy::ProxyConfig;
+
 mod chain_id;
 pub use chain_id::*;
 
 pub mod encrypted_key;
 pub use encrypted_key::*;
 
+pub mod debug_dump;
+
+pub mod fs;
+
+pub mod profiler;
+
 mod work_dir {
     use once_cell::sync::OnceCell;
     use std::path::PathBuf;
@@ -57,11 +66,8 @@ mod work_dir {
 
 pub use work_dir::{get_debug_dir, get_work_dir, set_work_dir};
 
-use rand::prelude::*;
-#[inline(always)]
-pub fn pseudo_rng(time: redux::Timestamp) -> StdRng {
-    StdRng::seed_from_u64(time.into())
-}
+pub mod rng;
+pub use rng::pseudo_rng;
 
 pub fn preshared_key(chain_id: &ChainId) -> [u8; 32] {
     use multihash::Hasher;
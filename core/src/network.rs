@@ -27,6 +27,7 @@ pub struct NetworkConfig {
     pub default_peers: Vec<&'static str>,
     pub circuits_config: &'static CircuitsConfig,
     pub constraint_constants: &'static ConstraintConstants,
+    pub p2p_msg_size_limits: &'static P2pMsgSizeLimits,
 }
 
 #[derive(Debug)]
@@ -43,6 +44,40 @@ pub struct CircuitsConfig {
     pub step_transaction_proved_gates: &'static str,
 }
 
+/// Maximum sizes in bytes of the messages sent over the p2p channels.
+#[derive(Debug)]
+pub struct P2pMsgSizeLimits {
+    pub signaling_discovery: usize,
+    pub signaling_exchange: usize,
+    pub best_tip_propagation: usize,
+    /// Transaction info or digests.
+    pub transaction_propagation: usize,
+    /// Snark info, or a batch of up to [`crate::snark::SnarkInfoBatch::MAX_LEN`]
+    /// of them.
+    pub snark_propagation: usize,
+    pub snark_job_commitment_propagation: usize,
+    pub rpc: usize,
+    pub streaming_rpc: usize,
+    pub checkpoint_propagation: usize,
+}
+
+impl P2pMsgSizeLimits {
+    pub const DEFAULT: Self = Self {
+        // TODO(binier): measure signaling message sizes
+        signaling_discovery: 16 * 1024, // 16KB
+        signaling_exchange: 16 * 1024,  // 16KB
+        // TODO(binier): reduce this value once we change message for best tip
+        // propagation to just propagating consensus state with block hash.
+        best_tip_propagation: 32 * 1024 * 1024,     // 32MB
+        transaction_propagation: 16 * 1024,         // 16KB
+        snark_propagation: 8 * 1024,                // 8KB
+        snark_job_commitment_propagation: 2 * 1024, // 2KB
+        rpc: 256 * 1024 * 1024,                     // 256MB
+        streaming_rpc: 16 * 1024 * 1024,            // 16MB
+        checkpoint_propagation: 1024,               // 1KB
+    };
+}
+
 static CONFIG: OnceCell<NetworkConfig> = OnceCell::new();
 
 impl NetworkConfig {
@@ -88,6 +123,7 @@ impl NetworkConfig {
             default_peers: mainnet::default_peers(),
             circuits_config: &mainnet::CIRCUITS_CONFIG,
             constraint_constants: &mainnet::CONSTRAINT_CONSTANTS,
+            p2p_msg_size_limits: &mainnet::P2P_MSG_SIZE_LIMITS,
         }
     }
 
@@ -102,6 +138,7 @@ impl NetworkConfig {
             default_peers: devnet::default_peers(),
             circuits_config: &devnet::CIRCUITS_CONFIG,
             constraint_constants: &devnet::CONSTRAINT_CONSTANTS,
+            p2p_msg_size_limits: &devnet::P2P_MSG_SIZE_LIMITS,
         }
     }
 }
@@ -109,7 +146,7 @@ impl NetworkConfig {
 // Network constants
 
 pub mod devnet {
-    use super::{CircuitsConfig, NetworkId};
+    use super::{CircuitsConfig, NetworkId, P2pMsgSizeLimits};
     use crate::constants::{ConstraintConstants, ForkConstants};
     use mina_hasher::Fp;
 
@@ -179,6 +216,14 @@ pub mod devnet {
             "step-step-proving-key-transaction-snark-proved-4-0cafcbc6dffccddbc82f8c2519c16341",
     };
 
+    /// Devnet runs the releases before mainnet, so the fixed size messages
+    /// have a tighter limit there, for a message outgrowing the mainnet
+    /// limit to be dropped on devnet first.
+    pub const P2P_MSG_SIZE_LIMITS: P2pMsgSizeLimits = P2pMsgSizeLimits {
+        snark_job_commitment_propagation: 1024, // 1KB
+        ..P2pMsgSizeLimits::DEFAULT
+    };
+
     pub fn default_peers() -> Vec<&'static str> {
         vec![
             "/dns4/seed-1.devnet.gcp.o1test.net/tcp/10003/p2p/12D3KooWAdgYL6hv18M3iDBdaK1dRygPivSfAfBNDzie6YqydVbs",
@@ -192,7 +237,7 @@ pub mod devnet {
 }
 
 pub mod mainnet {
-    use super::{CircuitsConfig, NetworkId};
+    use super::{CircuitsConfig, NetworkId, P2pMsgSizeLimits};
     use crate::constants::{ConstraintConstants, ForkConstants};
     use mina_hasher::Fp;
 
@@ -262,6 +307,8 @@ pub mod mainnet {
             "step-step-proving-key-transaction-snark-proved-4-7bb3855dfcf14da4b3ffa7091adc0143",
     };
 
+    pub const P2P_MSG_SIZE_LIMITS: P2pMsgSizeLimits = P2pMsgSizeLimits::DEFAULT;
+
     pub fn default_peers() -> Vec<&'static str> {
         vec![
             // /dns4/mina-seed.etonec.com/tcp/8302/p2p/12D3KooWKQ1YVtqZFzxDmSw8RASCPZpDCQBywnFz76RbrvZCXk5T
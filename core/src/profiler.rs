@@ -0,0 +1,143 @@
+//! Markers for the sampling profiler.
+//!
+//! State machine thread marks which action it is currently processing and
+//! whether it is in the reducer or in the effects, and service threads
+//! mark when they are busy. Sampler periodically reads the markers, so
+//! samples can be attributed to the action kind or the service.
+//!
+//! Markers are only updated while the profiler is enabled, so they cost a
+//! single atomic load otherwise.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Phase in the upper 16 bits, action kind in the lower 16 bits.
+static CURRENT: AtomicU32 = AtomicU32::new(0);
+
+static SERVICES_BUSY: [AtomicU32; ProfilerService::ALL.len()] =
+    [const { AtomicU32::new(0) }; ProfilerService::ALL.len()];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfilerPhase {
+    Idle,
+    Reducer,
+    Effects,
+}
+
+/// Services which run on their own threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfilerService {
+    Ledger,
+    SnarkBlockVerify,
+    SnarkWorkVerify,
+    SnarkUserCommandVerify,
+}
+
+impl ProfilerPhase {
+    fn from_u16(v: u16) -> Self {
+        match v {
+            1 => Self::Reducer,
+            2 => Self::Effects,
+            _ => Self::Idle,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Reducer => "reducer",
+            Self::Effects => "effects",
+        }
+    }
+}
+
+impl ProfilerService {
+    pub const ALL: [Self; 4] = [
+        Self::Ledger,
+        Self::SnarkBlockVerify,
+        Self::SnarkWorkVerify,
+        Self::SnarkUserCommandVerify,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ledger => "ledger",
+            Self::SnarkBlockVerify => "snark_block_verify",
+            Self::SnarkWorkVerify => "snark_work_verify",
+            Self::SnarkUserCommandVerify => "snark_user_command_verify",
+        }
+    }
+}
+
+pub fn set_profiler_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_profiler_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Restores the previous marker when dropped, so that nested actions
+/// (dispatched from the effects) are attributed correctly.
+pub struct ProfilerActionScope {
+    prev: u32,
+}
+
+impl Drop for ProfilerActionScope {
+    fn drop(&mut self) {
+        CURRENT.store(self.prev, Ordering::Relaxed);
+    }
+}
+
+/// Marks that the state machine is processing the action with the
+/// given kind. Returns `None` if profiler isn't enabled.
+pub fn profiler_action_scope(
+    phase: ProfilerPhase,
+    action_kind: u16,
+) -> Option<ProfilerActionScope> {
+    if !is_profiler_enabled() {
+        return None;
+    }
+    let current = (phase as u32) << 16 | action_kind as u32;
+    Some(ProfilerActionScope {
+        prev: CURRENT.swap(current, Ordering::Relaxed),
+    })
+}
+
+pub struct ProfilerServiceScope {
+    service: ProfilerService,
+}
+
+impl Drop for ProfilerServiceScope {
+    fn drop(&mut self) {
+        SERVICES_BUSY[self.service as usize].fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Marks that the service is busy. Returns `None` if profiler isn't enabled.
+pub fn profiler_service_scope(service: ProfilerService) -> Option<ProfilerServiceScope> {
+    if !is_profiler_enabled() {
+        return None;
+    }
+    SERVICES_BUSY[service as usize].fetch_add(1, Ordering::Relaxed);
+    Some(ProfilerServiceScope { service })
+}
+
+#[derive(Debug, Clone)]
+pub struct ProfilerSample {
+    pub phase: ProfilerPhase,
+    pub action_kind: u16,
+    /// Number of threads busy with each of [`ProfilerService::ALL`].
+    pub services_busy: [u32; ProfilerService::ALL.len()],
+}
+
+/// Reads the current state of the markers.
+pub fn profiler_sample() -> ProfilerSample {
+    let current = CURRENT.load(Ordering::Relaxed);
+    ProfilerSample {
+        phase: ProfilerPhase::from_u16((current >> 16) as u16),
+        action_kind: current as u16,
+        services_busy: std::array::from_fn(|i| SERVICES_BUSY[i].load(Ordering::Relaxed)),
+    }
+}
@@ -0,0 +1,153 @@
+//! Randomness for the state machine.
+//!
+//! State machine must be deterministic, so the randomness is derived
+//! from the action's time using [`pseudo_rng`]. To make unintended
+//! nondeterminism detectable, every draw goes through [`AuditedRng`],
+//! which, when the audit is enabled, summarizes the values drawn from it
+//! into a [`RngDraw`]. Recorder stores the draws alongside the action
+//! and replayer checks that the same draws are made again.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+static AUDIT_ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static DRAWS: RefCell<Vec<RngDraw>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Values drawn from a single [`AuditedRng`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RngDraw {
+    /// What the randomness was used for.
+    pub purpose: String,
+    /// Number of values drawn.
+    pub count: u32,
+    /// FNV-1a hash of the drawn values.
+    pub hash: u64,
+}
+
+/// Enables recording of [`RngDraw`]s for all the state machines in the
+/// process. Draws are collected per thread and taken with [`audit_take`].
+pub fn audit_enable() {
+    AUDIT_ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_audit_enabled() -> bool {
+    AUDIT_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Takes the draws recorded on the current thread since the last call.
+pub fn audit_take() -> Vec<RngDraw> {
+    DRAWS.with(|draws| std::mem::take(&mut *draws.borrow_mut()))
+}
+
+#[inline(always)]
+pub fn pseudo_rng(time: redux::Timestamp, purpose: &'static str) -> AuditedRng {
+    AuditedRng {
+        rng: StdRng::seed_from_u64(time.into()),
+        purpose,
+        audit: is_audit_enabled().then_some((0, FNV_OFFSET_BASIS)),
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Rng of the state machine, see [module docs](self).
+pub struct AuditedRng {
+    rng: StdRng,
+    purpose: &'static str,
+    /// Count and the hash of the drawn values, if audit is enabled.
+    audit: Option<(u32, u64)>,
+}
+
+impl AuditedRng {
+    fn record(&mut self, bytes: &[u8]) {
+        if let Some((count, hash)) = self.audit.as_mut() {
+            *count = count.saturating_add(1);
+            for byte in bytes {
+                *hash = (*hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME);
+            }
+        }
+    }
+}
+
+impl RngCore for AuditedRng {
+    fn next_u32(&mut self) -> u32 {
+        let v = self.rng.next_u32();
+        self.record(&v.to_le_bytes());
+        v
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let v = self.rng.next_u64();
+        self.record(&v.to_le_bytes());
+        v
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+        self.record(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)?;
+        self.record(dest);
+        Ok(())
+    }
+}
+
+impl Drop for AuditedRng {
+    fn drop(&mut self) {
+        let Some((count, hash)) = self.audit else {
+            return;
+        };
+        if count == 0 {
+            return;
+        }
+        let draw = RngDraw {
+            purpose: self.purpose.to_owned(),
+            count,
+            hash,
+        };
+        DRAWS.with(|draws| draws.borrow_mut().push(draw));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::seq::SliceRandom;
+
+    use super::*;
+
+    #[test]
+    fn same_draws_for_same_time() {
+        audit_enable();
+        let time = redux::Timestamp::new(1_000);
+        let shuffle = || {
+            let mut v = (0..10).collect::<Vec<_>>();
+            v.shuffle(&mut pseudo_rng(time, "test"));
+            v
+        };
+        let _ = audit_take();
+
+        assert_eq!(shuffle(), shuffle());
+        let draws = audit_take();
+        assert_eq!(draws.len(), 2);
+        assert_eq!(draws[0], draws[1]);
+        assert_eq!(draws[0].purpose, "test");
+        assert!(audit_take().is_empty());
+    }
+
+    #[test]
+    fn unused_rng_isnt_recorded() {
+        audit_enable();
+        let _ = audit_take();
+        drop(pseudo_rng(redux::Timestamp::new(1_000), "test"));
+        assert!(audit_take().is_empty());
+    }
+}
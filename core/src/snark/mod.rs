@@ -1,11 +1,11 @@
 mod snark_job_id;
-pub use snark_job_id::SnarkJobId;
+pub use snark_job_id::{LedgerHashTransitionPasses, SnarkJobId};
 
 mod snark_job_commitment;
 pub use snark_job_commitment::SnarkJobCommitment;
 
 mod snark_info;
-pub use snark_info::SnarkInfo;
+pub use snark_info::{SnarkInfo, SnarkInfoBatch};
 
 #[allow(clippy::module_inception)]
 mod snark;
@@ -16,3 +16,148 @@ impl SnarkInfo {
         super::tie_breaker_hash(&self.job_id, &self.prover)
     }
 }
+
+/// Infos of the works from the same prover with the same fee, sent as a
+/// single message, so that the shared metadata is only sent once.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkInfoBatch {
+    pub fee: CurrencyFeeStableV1,
+    pub prover: NonZeroCurvePoint,
+    pub job_ids: Vec<SnarkJobId>,
+}
+
+impl SnarkInfoBatch {
+    /// Max number of works in a single batch.
+    pub const MAX_LEN: usize = 32;
+
+    pub fn len(&self) -> usize {
+        self.job_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.job_ids.is_empty()
+    }
+
+    /// Groups consecutive infos with the same prover and fee into batches
+    /// of up to [`Self::MAX_LEN`] works, preserving the order.
+    pub fn bundle<I>(snarks: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = SnarkInfo>,
+    {
+        let mut batches: Vec<Self> = vec![];
+        for snark in snarks {
+            match batches.last_mut() {
+                Some(batch)
+                    if batch.len() < Self::MAX_LEN
+                        && batch.prover == snark.prover
+                        && batch.fee == snark.fee =>
+                {
+                    batch.job_ids.push(snark.job_id);
+                }
+                _ => batches.push(Self {
+                    fee: snark.fee,
+                    prover: snark.prover,
+                    job_ids: vec![snark.job_id],
+                }),
+            }
+        }
+        batches
+    }
+
+    /// Infos of the works in the batch.
+    pub fn unbundle(self) -> impl Iterator<Item = SnarkInfo> {
+        let Self {
+            fee,
+            prover,
+            job_ids,
+        } = self;
+        job_ids.into_iter().map(move |job_id| SnarkInfo {
+            job_id,
+            fee: fee.clone(),
+            prover: prover.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use mina_p2p_messages::binprot::BinProtWrite;
+
+    use super::*;
+
+    fn info(job_id: &str, prover: &str, fee: u64) -> SnarkInfo {
+        SnarkInfo {
+            job_id: SnarkJobId::from_str(job_id).unwrap(),
+            fee: CurrencyFeeStableV1(fee.into()),
+            prover: prover.parse().unwrap(),
+        }
+    }
+
+    fn encoded_len<T: BinProtWrite>(v: &T) -> usize {
+        let mut buf = vec![];
+        v.binprot_write(&mut buf).unwrap();
+        buf.len()
+    }
+
+    #[test]
+    fn test_snark_info_batch_bundle_unbundle() {
+        let job_id = "jw9nPCs68UNaKaLZwV6QzdswKWomwQxvTgrpmKWmnFJyswnrn4N_jwhHYWzvJG8esmqtYXbUZy3UGbLSjhKvn1FSxBGL1JDFHqbHMJc-jwiLuRrEqNgASgXEqibGs4VqKwSwiuFEtuPD53v8hiTtVuLfmTr_jwhHYWzvJG8esmqtYXbUZy3UGbLSjhKvn1FSxBGL1JDFHqbHMJc";
+        let prover1 = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg";
+        let prover2 = "B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g";
+
+        let snarks = std::iter::repeat_with(|| info(job_id, prover1, 10))
+            .take(SnarkInfoBatch::MAX_LEN + 2)
+            .chain([info(job_id, prover1, 20), info(job_id, prover2, 20)])
+            .collect::<Vec<_>>();
+        let batches = SnarkInfoBatch::bundle(snarks.clone());
+        assert_eq!(
+            batches.iter().map(SnarkInfoBatch::len).collect::<Vec<_>>(),
+            [SnarkInfoBatch::MAX_LEN, 2, 1, 1]
+        );
+
+        let unbundled = batches
+            .iter()
+            .cloned()
+            .flat_map(SnarkInfoBatch::unbundle)
+            .collect::<Vec<_>>();
+        assert_eq!(unbundled.len(), snarks.len());
+        for (a, b) in unbundled.iter().zip(&snarks) {
+            assert_eq!(a.fee, b.fee);
+            assert_eq!(a.prover, b.prover);
+            assert_eq!(a.job_id, b.job_id);
+        }
+
+        let batch_len = encoded_len(&batches[0]);
+        let separate_len = snarks[..SnarkInfoBatch::MAX_LEN]
+            .iter()
+            .map(encoded_len)
+            .sum::<usize>();
+        assert!(batch_len < separate_len);
+    }
+
+    #[test]
+    fn test_snark_info_batch_fits_msg_size_limit() {
+        let job_id = "jw9nPCs68UNaKaLZwV6QzdswKWomwQxvTgrpmKWmnFJyswnrn4N_jwhHYWzvJG8esmqtYXbUZy3UGbLSjhKvn1FSxBGL1JDFHqbHMJc-jwiLuRrEqNgASgXEqibGs4VqKwSwiuFEtuPD53v8hiTtVuLfmTr_jwhHYWzvJG8esmqtYXbUZy3UGbLSjhKvn1FSxBGL1JDFHqbHMJc";
+        let prover = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg";
+        let batches = SnarkInfoBatch::bundle(
+            std::iter::repeat_with(|| info(job_id, prover, u64::MAX)).take(SnarkInfoBatch::MAX_LEN),
+        );
+        assert_eq!(batches.len(), 1);
+
+        // Channel and message tags.
+        let msg_len = encoded_len(&batches[0]) + 2;
+        let limits = [
+            crate::network::P2pMsgSizeLimits::DEFAULT,
+            crate::network::devnet::P2P_MSG_SIZE_LIMITS,
+        ];
+        for limit in limits {
+            assert!(
+                msg_len <= limit.snark_propagation,
+                "full batch of {msg_len} bytes exceeds the limit of {}",
+                limit.snark_propagation
+            );
+        }
+    }
+}
@@ -17,6 +17,10 @@ pub enum TransactionPoolMessageSource {
     Pubsub {
         id: P2pNetworkPubsubMessageCacheId,
     },
+    /// Payment of the payout batch, created and signed by the node.
+    Payout {
+        batch_id: u64,
+    },
     #[default]
     None,
 }
@@ -30,8 +34,12 @@ impl TransactionPoolMessageSource {
         Self::Pubsub { id }
     }
 
+    pub fn payout(batch_id: u64) -> Self {
+        Self::Payout { batch_id }
+    }
+
     pub fn is_sender_local(&self) -> bool {
-        matches!(self, Self::Rpc { .. })
+        matches!(self, Self::Rpc { .. } | Self::Payout { .. })
     }
 
     pub fn is_libp2p(&self) -> bool {
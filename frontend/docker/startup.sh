@@ -74,43 +74,58 @@ download_circuit_files() {
     done
 }
 
-download_wasm_files() {
-    if [ -z "$OPENMINA_WASM_VERSION" ]; then
-        echo "Error: OPENMINA_WASM_VERSION is not set. Exiting."
-        exit 1
-    fi
-
-    WASM_URL="$OPENMINA_BASE_URL/openmina/releases/download/$OPENMINA_WASM_VERSION/openmina-$OPENMINA_WASM_VERSION-webnode-wasm.tar.gz"
-    TARGET_DIR="/usr/local/apache2/htdocs/assets/webnode/pkg"
+# Downloads the web node build `$1` (`pkg` or `pkg-simd`) from the
+# release asset with the suffix `$2`. The SIMD build is optional, the
+# loader in index.html falls back to the baseline one without it.
+download_wasm_package() {
+    local pkg="$1"
+    local suffix="$2"
+    local required="$3"
+
+    local wasm_url="$OPENMINA_BASE_URL/openmina/releases/download/$OPENMINA_WASM_VERSION/openmina-$OPENMINA_WASM_VERSION-webnode-wasm$suffix.tar.gz"
+    local archive="/tmp/openmina-$OPENMINA_WASM_VERSION-webnode-wasm$suffix.tar.gz"
+    TARGET_DIR="/usr/local/apache2/htdocs/assets/webnode/$pkg"
 
     mkdir -p "$TARGET_DIR"
 
-    echo "Downloading WASM files from $WASM_URL..."
-    curl -s -L --retry 3 --retry-delay 5 -o "/tmp/openmina-$OPENMINA_WASM_VERSION-webnode-wasm.tar.gz" "$WASM_URL"
+    echo "Downloading WASM files from $wasm_url..."
+    curl -s -f -L --retry 3 --retry-delay 5 -o "$archive" "$wasm_url"
 
     if [[ $? -ne 0 ]]; then
-        echo "Failed to download the WASM file after 3 attempts, exiting."
+        if [ "$required" = "required" ]; then
+            echo "Failed to download the WASM file after 3 attempts, exiting."
+            exit 1
+        fi
+        echo "$pkg build isn't available, skipping it."
+        rm -f "$archive"
+        return 0
+    fi
+
+    echo "WASM file downloaded successfully. Extracting to $TARGET_DIR..."
+
+    tar -xzf "$archive" -C "$TARGET_DIR"
+
+    # Check if the extraction was successful
+    if [[ $? -ne 0 ]]; then
+        echo "Failed to extract the WASM file, exiting."
         exit 1
-    else
-        echo "WASM file downloaded successfully. Extracting to $TARGET_DIR..."
+    fi
+    echo "WASM files extracted successfully to $TARGET_DIR"
 
-        tar -xzf "/tmp/openmina-$OPENMINA_WASM_VERSION-webnode-wasm.tar.gz" -C "$TARGET_DIR"
+    # Inject caching logic into openmina_node_web.js
+    inject_caching_logic "$pkg" "$TARGET_DIR/openmina_node_web.js" "/usr/local/apache2/htdocs/index.html"
 
-        # Check if the extraction was successful
-        if [[ $? -ne 0 ]]; then
-            echo "Failed to extract the WASM file, exiting."
-            exit 1
-        else
-            echo "WASM files extracted successfully to $TARGET_DIR"
+    rm "$archive"
+}
 
-            # Inject caching logic into openmina_node_web.js
-            OPENMINA_JS="$TARGET_DIR/openmina_node_web.js"
-            INDEX_HTML="/usr/local/apache2/htdocs/index.html"
-            inject_caching_logic "$OPENMINA_JS" "$INDEX_HTML"
-        fi
+download_wasm_files() {
+    if [ -z "$OPENMINA_WASM_VERSION" ]; then
+        echo "Error: OPENMINA_WASM_VERSION is not set. Exiting."
+        exit 1
     fi
 
-    rm "/tmp/openmina-$OPENMINA_WASM_VERSION-webnode-wasm.tar.gz"
+    download_wasm_package "pkg" "" "required"
+    download_wasm_package "pkg-simd" "-simd" "optional"
 }
 
 get_short_sha1() {
@@ -135,8 +150,9 @@ get_short_sha1() {
 }
 
 inject_caching_logic() {
-    local js_file="$1"
-    local index_html="$2"
+    local pkg="$1"
+    local js_file="$2"
+    local index_html="$3"
 
     # Check if JavaScript file exists
     if [ ! -f "$js_file" ]; then
@@ -178,8 +194,8 @@ inject_caching_logic() {
     # Rename JS file with hash
     mv "$js_target_file" "$js_new_file" || { echo "Failed to rename $js_target_file to $js_new_file"; return 1; }
 
-    # Replace JS filename in index.html
-    sed -i "s/openmina_node_web\.js/openmina_node_web.${js_file_hash}.js/g" "$index_html" || { echo "Failed to update JS filename in $index_html"; return 1; }
+    # Replace JS filename of this build in index.html
+    sed -i "s|webnode/${pkg}/openmina_node_web\.js|webnode/${pkg}/openmina_node_web.${js_file_hash}.js|g" "$index_html" || { echo "Failed to update JS filename in $index_html"; return 1; }
 
     echo "Successfully injected caching logic into $js_file"
 }
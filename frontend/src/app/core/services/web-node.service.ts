@@ -79,6 +79,9 @@ export class WebNodeService {
       return merge(
         of(any(window).webnode).pipe(filter(Boolean)),
         fromEvent(window, 'webNodeLoaded'),
+        fromEvent(window, 'webNodeUnsupported').pipe(
+          switchMap((event: Event) => throwError(() => new Error((event as CustomEvent).detail))),
+        ),
       ).pipe(
         switchMap(() => {
           const DEFAULT_NETWORK = 'devnet';
@@ -50,7 +50,9 @@ export interface BlockProductionWonSlotTimes {
 export type BlockProductionWonSlotsDiscardReason =
   'BestTipStakingLedgerDifferent'
   | 'BestTipGlobalSlotHigher'
-  | 'BestTipSuperior';
+  | 'BestTipSuperior'
+  | 'SlotClaimedByOther'
+  | 'SigningRefused';
 
 export enum BlockProductionWonSlotsStatus {
   Scheduled = 'Scheduled',
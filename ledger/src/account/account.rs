@@ -311,6 +311,18 @@ impl Permissions<AuthRequired> {
         }
     }
 
+    /// Permissions set by o1js when deploying a contract, the state and the
+    /// balance can only be changed with a proof.
+    pub fn zkapp_default() -> Self {
+        use AuthRequired::*;
+        Self {
+            edit_state: Proof,
+            send: Proof,
+            edit_action_state: Proof,
+            ..Self::user_default()
+        }
+    }
+
     pub fn empty() -> Self {
         use AuthRequired::*;
         Self {
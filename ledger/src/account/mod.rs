@@ -3,8 +3,10 @@ mod account;
 mod common;
 mod conv;
 mod legacy;
+mod template;
 
 pub use account::*;
 pub use common::*;
 pub use conv::*;
 pub use legacy::*;
+pub use template::*;
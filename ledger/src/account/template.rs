@@ -0,0 +1,130 @@
+use mina_hasher::Fp;
+use mina_signer::CompressedPubKey;
+
+use crate::scan_state::currency::{Balance, Nonce};
+
+use super::{
+    Account, AccountId, AuthRequired, Permissions, Timing, VerificationKey, VerificationKeyWire,
+    ZkAppAccount,
+};
+
+/// Builder of the ledger accounts in a given state, e.g. with a deployed
+/// zkApp, so that the zkApp tests can start from a deployed contract
+/// without applying the deployment transaction first.
+///
+/// ```ignore
+/// let account = AccountTemplate::zkapp(account_id, balance, vk)
+///     .app_state(app_state)
+///     .proved_state(true)
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct AccountTemplate {
+    account: Account,
+}
+
+impl AccountTemplate {
+    /// Same account as created by a transaction, with the default
+    /// permissions and without a zkApp.
+    pub fn new(account_id: AccountId, balance: Balance) -> Self {
+        Self {
+            account: Account::create_with(account_id, balance),
+        }
+    }
+
+    /// Account with the contract deployed, as with o1js, see
+    /// [`Permissions::zkapp_default`].
+    pub fn zkapp(account_id: AccountId, balance: Balance, vk: VerificationKey) -> Self {
+        Self::new(account_id, balance)
+            .verification_key(vk)
+            .permissions(Permissions::zkapp_default())
+    }
+
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.account.nonce = nonce;
+        self
+    }
+
+    pub fn delegate(mut self, delegate: Option<CompressedPubKey>) -> Self {
+        self.account.delegate = delegate;
+        self
+    }
+
+    pub fn permissions(mut self, permissions: Permissions<AuthRequired>) -> Self {
+        self.account.permissions = permissions;
+        self
+    }
+
+    pub fn timing(mut self, timing: Timing) -> Self {
+        self.account.timing = timing;
+        self
+    }
+
+    pub fn verification_key(mut self, vk: VerificationKey) -> Self {
+        self.zkapp_mut().verification_key = Some(VerificationKeyWire::new(vk));
+        self
+    }
+
+    pub fn app_state(mut self, app_state: [Fp; 8]) -> Self {
+        self.zkapp_mut().app_state = app_state;
+        self
+    }
+
+    /// Whether the app state was last set by a proof, set by the `init`
+    /// method of the o1js contracts.
+    pub fn proved_state(mut self, proved_state: bool) -> Self {
+        self.zkapp_mut().proved_state = proved_state;
+        self
+    }
+
+    pub fn build(self) -> Account {
+        self.account
+    }
+
+    fn zkapp_mut(&mut self) -> &mut ZkAppAccount {
+        self.account.zkapp.get_or_insert_with(Default::default)
+    }
+}
+
+impl From<AccountTemplate> for Account {
+    fn from(template: AccountTemplate) -> Self {
+        template.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_family = "wasm")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    use crate::{BaseLedger, Database, Mask};
+
+    use super::*;
+
+    #[test]
+    fn test_zkapp_account_template() {
+        let account_id = AccountId::new(CompressedPubKey::empty(), Default::default());
+        let vk = (*VerificationKey::dummy()).clone();
+        let app_state = std::array::from_fn(|i| Fp::from(i as u64));
+        let account = AccountTemplate::zkapp(account_id.clone(), Balance::from_u64(1000), vk)
+            .app_state(app_state)
+            .proved_state(true)
+            .build();
+
+        let zkapp = account.zkapp.as_ref().unwrap();
+        assert_eq!(
+            zkapp.verification_key.as_ref().map(|vk| vk.hash()),
+            Some(VerificationKeyWire::dummy_hash())
+        );
+        assert_eq!(zkapp.app_state, app_state);
+        assert!(zkapp.proved_state);
+        assert_eq!(account.permissions.edit_state, AuthRequired::Proof);
+        assert_eq!(account.permissions.receive, AuthRequired::None);
+
+        let mut mask = Mask::new_root(Database::create(10));
+        mask.get_or_create_account(account_id.clone(), account.clone())
+            .unwrap();
+        let location = mask.location_of_account(&account_id).unwrap();
+        assert_eq!(mask.get(location).as_deref(), Some(&account));
+    }
+}
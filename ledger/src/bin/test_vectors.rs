@@ -0,0 +1,50 @@
+//! Exports the transaction application test vectors, or checks this crate
+//! against externally provided ones.
+//!
+//! Usage:
+//!     test_vectors generate <path> [seed]
+//!     test_vectors check <path>
+
+use mina_tree::test_vectors::{self, TestVector};
+
+fn usage() -> ! {
+    eprintln!("usage:\n  test_vectors generate <path> [seed]\n  test_vectors check <path>");
+    std::process::exit(2);
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["generate", path, rest @ ..] => {
+            let seed = match rest {
+                [] => 0,
+                [seed] => seed.parse().unwrap_or_else(|_| usage()),
+                _ => usage(),
+            };
+            let vectors = test_vectors::generate::signed_command_vectors(seed);
+            let json = serde_json::to_string_pretty(&vectors).expect("failed to serialize vectors");
+            std::fs::write(path, json).expect("failed to write vectors");
+            println!("exported {} vectors to {path}", vectors.len());
+        }
+        ["check", path] => {
+            let json = std::fs::read_to_string(path).expect("failed to read vectors");
+            let vectors: Vec<TestVector> =
+                serde_json::from_str(&json).expect("failed to parse vectors");
+            let failed = test_vectors::check_all(&vectors);
+            for (name, reason) in &failed {
+                println!("FAIL {name}: {reason}");
+            }
+            println!(
+                "{} passed, {} failed",
+                vectors.len() - failed.len(),
+                failed.len()
+            );
+            if !failed.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        _ => usage(),
+    }
+}
@@ -8,7 +8,7 @@ pub use openmina_core::dummy::*;
 #[cfg(any(test, feature = "fuzzing"))]
 use crate::VerificationKey;
 
-#[cfg(any(test, feature = "fuzzing"))]
+#[cfg(any(test, feature = "fuzzing", feature = "test-vectors"))]
 pub mod for_tests;
 
 /// Value of `vk` when we run `dune runtest src/lib/staged_ledger -f`
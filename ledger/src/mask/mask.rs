@@ -159,6 +159,15 @@ impl Mask {
         }
     }
 
+    /// Copy of the mask sharing no lock with it or its ancestors, so it
+    /// can be read from another thread while the original is in use.
+    pub fn deep_copy(&self) -> Mask {
+        let mask = self.with(|this| this.deep_copy());
+        Self {
+            inner: Arc::new(Mutex::new(mask)),
+        }
+    }
+
     /// Make `mask` a child of `self`
     pub fn register_mask(&self, mask: Mask) -> Mask {
         // elog!("self={:p} mask={:p}", &self.inner, &mask.inner);
@@ -58,10 +58,22 @@ impl Drop for MaskImpl {
 
 impl Clone for MaskImpl {
     fn clone(&self) -> Self {
+        self.copy_impl(false)
+    }
+}
+
+impl MaskImpl {
+    /// With `deep`, the ancestors are copied too and the children are
+    /// left out, so that no lock is shared with the original.
+    fn copy_impl(&self, deep: bool) -> Self {
+        let copy_childs = |childs: &HashMap<Uuid, Mask>| match deep {
+            false => childs.clone(),
+            true => HashMap::new(),
+        };
         let copy = match self {
             Self::Root { database, childs } => Self::Root {
                 database: database.clone_db(database.get_directory().unwrap()),
-                childs: childs.clone(),
+                childs: copy_childs(childs),
             },
             Self::Attached {
                 parent,
@@ -74,13 +86,16 @@ impl Clone for MaskImpl {
                 hashes,
                 uuid: _,
             } => Self::Attached {
-                parent: parent.clone(),
+                parent: match deep {
+                    false => parent.clone(),
+                    true => parent.deep_copy(),
+                },
                 owning_account: owning_account.clone(),
                 token_owners: token_owners.clone(),
                 id_to_addr: id_to_addr.clone(),
                 last_location: last_location.clone(),
                 depth: *depth,
-                childs: childs.clone(),
+                childs: copy_childs(childs),
                 hashes: hashes.clone(),
                 uuid: next_uuid(),
             },
@@ -95,7 +110,7 @@ impl Clone for MaskImpl {
                 uuid: _,
             } => Self::Unattached {
                 depth: *depth,
-                childs: childs.clone(),
+                childs: copy_childs(childs),
                 owning_account: owning_account.clone(),
                 token_owners: token_owners.clone(),
                 id_to_addr: id_to_addr.clone(),
@@ -325,6 +340,11 @@ impl MaskImpl {
         self.remove_parent()
     }
 
+    /// Clones the mask and all its ancestors, without their children.
+    pub fn deep_copy(&self) -> Self {
+        self.copy_impl(true)
+    }
+
     pub fn set_parent(&mut self, parent: Mask, parent_last_filled: Option<Option<Address>>) {
         match self {
             Root { .. } => panic!("set_parent() on a root"),
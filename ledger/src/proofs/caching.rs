@@ -112,6 +112,15 @@ where
     }
 }
 
+impl GroupAffineCached {
+    fn is_valid<F>(&self) -> bool
+    where
+        F: ark_ff::Field + TryFrom<ark_ff::BigInteger256, Error = InvalidBigInt>,
+    {
+        self.x.to_field::<F>().is_ok() && self.y.to_field::<F>().is_ok()
+    }
+}
+
 impl<T> From<&GroupAffineCached> for GroupAffine<T>
 where
     T: ark_ec::SWModelParameters,
@@ -177,6 +186,26 @@ where
     }
 }
 
+impl SRSCached {
+    /// Points are converted without checks, so the srs read from an
+    /// untrusted file must be checked first.
+    fn is_valid<F>(&self) -> bool
+    where
+        F: ark_ff::Field + TryFrom<ark_ff::BigInteger256, Error = InvalidBigInt>,
+    {
+        let lagrange_bases = self
+            .lagrange_bases
+            .values()
+            .flatten()
+            .flat_map(|comm| &comm.elems);
+        self.g
+            .iter()
+            .chain([&self.h])
+            .chain(lagrange_bases)
+            .all(GroupAffineCached::is_valid::<F>)
+    }
+}
+
 impl<'a, G> From<&'a SRSCached> for SRS<G>
 where
     G: CommitmentCurve + From<&'a GroupAffineCached>,
@@ -473,13 +502,25 @@ where
     postcard::to_stdvec(&srs).unwrap()
 }
 
-pub fn srs_from_bytes<G>(bytes: &[u8]) -> SRS<G>
+#[derive(Debug, thiserror::Error)]
+pub enum SrsFromBytesError {
+    #[error("Error reading srs from bytes: {0}")]
+    Decode(#[from] postcard::Error),
+    #[error("Error reading srs from bytes: invalid field element")]
+    InvalidBigInt,
+}
+
+pub fn srs_from_bytes<G>(bytes: &[u8]) -> Result<SRS<G>, SrsFromBytesError>
 where
     G: CommitmentCurve,
     G: for<'a> From<&'a GroupAffineCached>,
+    <G as AffineCurve>::BaseField: TryFrom<ark_ff::BigInteger256, Error = InvalidBigInt>,
 {
-    let srs: SRSCached = postcard::from_bytes(bytes).unwrap();
-    (&srs).into()
+    let srs: SRSCached = postcard::from_bytes(bytes)?;
+    if !srs.is_valid::<<G as AffineCurve>::BaseField>() {
+        return Err(SrsFromBytesError::InvalidBigInt);
+    }
+    Ok((&srs).into())
 }
 
 pub fn openmina_cache_path<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
@@ -500,3 +541,35 @@ pub fn ensure_path_exists<P: AsRef<Path> + Clone>(path: P) -> Result<(), std::io
         Err(e) => Err(e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mina_curves::pasta::Vesta;
+
+    use super::*;
+
+    #[test]
+    fn test_srs_from_bytes() {
+        let srs = SRS::<Vesta>::create(8);
+        let bytes = srs_to_bytes(&srs);
+
+        let decoded = srs_from_bytes::<Vesta>(&bytes).unwrap();
+        assert_eq!(decoded.g, srs.g);
+        assert_eq!(decoded.h, srs.h);
+
+        assert!(matches!(
+            srs_from_bytes::<Vesta>(bytes.get(..16).unwrap()),
+            Err(SrsFromBytesError::Decode(_))
+        ));
+        assert!(srs_from_bytes::<Vesta>(&[]).is_err());
+
+        // Decodes fine, but the point isn't a field element.
+        let mut cached: SRSCached = postcard::from_bytes(&bytes).unwrap();
+        cached.h.x = ark_ff::BigInteger256([u64::MAX; 4]).into();
+        let bytes = postcard::to_stdvec(&cached).unwrap();
+        assert!(matches!(
+            srs_from_bytes::<Vesta>(&bytes),
+            Err(SrsFromBytesError::InvalidBigInt)
+        ));
+    }
+}
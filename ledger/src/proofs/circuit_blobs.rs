@@ -7,6 +7,21 @@ pub fn home_base_dir() -> Option<std::path::PathBuf> {
     Some(path)
 }
 
+/// Local directories where circuit-blobs are looked up, in order.
+#[cfg(not(target_family = "wasm"))]
+pub fn local_base_dirs() -> Vec<std::path::PathBuf> {
+    [
+        std::env::var("OPENMINA_CIRCUIT_BLOBS_BASE_DIR").ok(),
+        Some(env!("CARGO_MANIFEST_DIR").to_string()),
+    ]
+    .into_iter()
+    .flatten()
+    .map(Into::into)
+    .chain(home_base_dir())
+    .chain(Some("/usr/local/lib/openmina/circuit-blobs".into()))
+    .collect()
+}
+
 fn git_release_url(filename: &impl AsRef<Path>) -> String {
     const RELEASES_PATH: &str = "https://github.com/openmina/circuit-blobs/releases/download";
     let filename_str = filename.as_ref().to_str().unwrap();
@@ -16,14 +31,6 @@ fn git_release_url(filename: &impl AsRef<Path>) -> String {
 
 #[cfg(not(target_family = "wasm"))]
 pub fn fetch_blocking(filename: &impl AsRef<Path>) -> std::io::Result<Vec<u8>> {
-    use std::path::PathBuf;
-
-    fn try_base_dir<P: Into<PathBuf>>(base_dir: P, filename: &impl AsRef<Path>) -> Option<PathBuf> {
-        let mut path = base_dir.into();
-        path.push(filename);
-        path.exists().then_some(path)
-    }
-
     fn to_io_err(err: impl std::fmt::Display) -> std::io::Error {
         std::io::Error::new(
             std::io::ErrorKind::Other,
@@ -33,17 +40,10 @@ pub fn fetch_blocking(filename: &impl AsRef<Path>) -> std::io::Result<Vec<u8>> {
         )
     }
 
-    let home_base_dir = home_base_dir();
-    let found = None
-        .or_else(|| {
-            try_base_dir(
-                std::env::var("OPENMINA_CIRCUIT_BLOBS_BASE_DIR").ok()?,
-                filename,
-            )
-        })
-        .or_else(|| try_base_dir(env!("CARGO_MANIFEST_DIR").to_string(), filename))
-        .or_else(|| try_base_dir(home_base_dir.clone()?, filename))
-        .or_else(|| try_base_dir("/usr/local/lib/openmina/circuit-blobs", filename));
+    let found = local_base_dirs()
+        .into_iter()
+        .map(|base_dir| base_dir.join(filename))
+        .find(|path| path.exists());
 
     if let Some(path) = found {
         return std::fs::read(path);
@@ -56,7 +56,7 @@ pub fn fetch_blocking(filename: &impl AsRef<Path>) -> std::io::Result<Vec<u8>> {
         filename = filename.as_ref().to_str().unwrap(),
     );
 
-    let base_dir = home_base_dir.expect("$HOME env not set!");
+    let base_dir = home_base_dir().expect("$HOME env not set!");
 
     let bytes = reqwest::blocking::get(git_release_url(filename))
         .map_err(to_io_err)?
@@ -5,6 +5,8 @@ pub mod accumulator_check;
 pub mod block;
 pub mod caching;
 mod circuit_blobs;
+#[cfg(not(target_family = "wasm"))]
+pub use circuit_blobs::local_base_dirs as circuit_blobs_local_base_dirs;
 pub mod constants;
 mod conv;
 pub mod field;
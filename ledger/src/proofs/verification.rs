@@ -2,7 +2,6 @@ use std::rc::Rc;
 
 use ark_ff::fields::arithmetic::InvalidBigInt;
 use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
-use ark_serialize::Write;
 use itertools::Itertools;
 use poly_commitment::srs::SRS;
 
@@ -1004,9 +1003,7 @@ mod on_fail {
             .to_string();
         std::fs::create_dir_all(&debug_dir)?;
 
-        let mut file = std::fs::File::create(&filename)?;
-        file.write_all(&bin)?;
-        file.sync_all()?;
+        openmina_core::debug_dump::dump_write(&filename, bin)?;
 
         openmina_core::error!(
             message = format!("proof verication failed, dumped data to {:?}", &filename)
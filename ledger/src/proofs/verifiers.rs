@@ -58,6 +58,21 @@ impl std::fmt::Display for Kind {
     }
 }
 
+/// Identifies the format of the verifier index stored in the directory
+/// set with [`set_cache_dir`], bumped when it changes.
+#[cfg(not(target_family = "wasm"))]
+const MAGIC: &[u8; 8] = b"omvidx-1";
+
+static CACHE_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Sets the directory, where the prepared verifier indexes are stored, so
+/// that they are loaded from there on the next startups instead of being
+/// prepared again. Has no effect after the verifier index was made,
+/// returns `false` if the directory was already set.
+pub fn set_cache_dir(dir: PathBuf) -> bool {
+    CACHE_DIR.set(dir).is_ok()
+}
+
 fn cache_filename(kind: Kind) -> PathBuf {
     let circuits_config = openmina_core::NetworkConfig::global().circuits_config;
     Path::new(circuits_config.directory_name).join(kind.filename())
@@ -68,6 +83,43 @@ fn cache_path(kind: Kind) -> Option<PathBuf> {
     super::circuit_blobs::home_base_dir().map(|p| p.join(cache_filename(kind)))
 }
 
+#[cfg(not(target_family = "wasm"))]
+fn cache_dir_path(kind: Kind) -> Option<PathBuf> {
+    CACHE_DIR.get().map(|dir| dir.join(cache_filename(kind)))
+}
+
+/// Decodes the verifier index, checking that it was prepared from the
+/// source index with the `source_digest`.
+fn from_cache_bytes(mut slice: &[u8], source_digest: &[u8]) -> anyhow::Result<VerifierIndex<Fq>> {
+    let mut d = [0; 32];
+    // source digest
+    slice.read_exact(&mut d).context("reading source digest")?;
+    if d != source_digest {
+        anyhow::bail!("source digest verification failed");
+    }
+
+    // index digest
+    slice.read_exact(&mut d).context("reading index digest")?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(slice);
+    let digest = hasher.finalize();
+    if d != digest.as_slice() {
+        anyhow::bail!("verifier index digest verification failed");
+    }
+    Ok(super::caching::verifier_index_from_bytes(slice)?)
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn to_cache_bytes(index: &VerifierIndex<Fq>, source_digest: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let payload = super::caching::verifier_index_to_bytes(index)?;
+    let mut bytes = Vec::with_capacity(payload.len().saturating_add(64));
+    bytes.extend_from_slice(source_digest);
+    bytes.extend_from_slice(&Sha256::digest(&payload));
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
 macro_rules! read_cache {
     ($kind: expr, $digest: expr) => {{
         #[cfg(not(target_family = "wasm"))]
@@ -77,57 +129,67 @@ macro_rules! read_cache {
         let data = super::circuit_blobs::fetch(&cache_filename($kind))
             .await
             .context("fetching verifier index failed")?;
-        let mut slice = data.as_slice();
-        let mut d = [0; 32];
-        // source digest
-        slice.read_exact(&mut d).context("reading source digest")?;
-        if d != $digest {
-            anyhow::bail!("source digest verification failed");
-        }
-
-        // index digest
-        slice.read_exact(&mut d).context("reading index digest")?;
-
-        let mut hasher = Sha256::new();
-        hasher.update(slice);
-        let digest = hasher.finalize();
-        if d != digest.as_slice() {
-            anyhow::bail!("verifier index digest verification failed");
-        }
-        Ok(super::caching::verifier_index_from_bytes(slice)?)
+        from_cache_bytes(&data, $digest)
     }};
 }
 
 #[cfg(not(target_family = "wasm"))]
 fn read_cache(kind: Kind, digest: &[u8]) -> anyhow::Result<VerifierIndex<Fq>> {
+    if let Some(path) = cache_dir_path(kind) {
+        match read_cache_dir(&path, digest) {
+            Ok(index) => return Ok(index),
+            Err(err) => warn!(system_time(); "Cannot load verifier index from {path:?}: {err}"),
+        }
+    }
     read_cache!(kind, digest)
 }
 
+#[cfg(not(target_family = "wasm"))]
+fn read_cache_dir(path: &Path, digest: &[u8]) -> anyhow::Result<VerifierIndex<Fq>> {
+    let bytes = std::fs::read(path)?;
+    match bytes.strip_prefix(MAGIC.as_slice()) {
+        Some(bytes) => from_cache_bytes(bytes, digest),
+        None => anyhow::bail!("unknown file format"),
+    }
+}
+
 #[cfg(target_family = "wasm")]
 async fn read_cache(kind: Kind, digest: &[u8]) -> anyhow::Result<VerifierIndex<Fq>> {
     read_cache!(kind, digest)
 }
 
+/// Stores the verifier index into the directory set with [`set_cache_dir`],
+/// or into the local circuit-blobs directory, if not set.
 #[cfg(not(target_family = "wasm"))]
 fn write_cache(kind: Kind, index: &VerifierIndex<Fq>, digest: &[u8]) -> anyhow::Result<()> {
-    use std::{fs::File, io::Write};
+    let (path, magic) = match cache_dir_path(kind) {
+        Some(path) => (path, MAGIC.as_slice()),
+        None => (
+            cache_path(kind).ok_or_else(|| {
+                anyhow::anyhow!("$HOME env not set, so can't cache verifier index")
+            })?,
+            [].as_slice(),
+        ),
+    };
+    let bytes = to_cache_bytes(index, digest)?;
+    write_cache_file(&path, magic, &bytes)
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write_cache_file(path: &Path, magic: &[u8], bytes: &[u8]) -> anyhow::Result<()> {
+    use std::io::Write;
 
-    let path = cache_path(kind)
-        .ok_or_else(|| anyhow::anyhow!("$HOME env not set, so can't cache verifier index"))?;
-    let bytes = super::caching::verifier_index_to_bytes(index)?;
-    let mut hasher = Sha256::new();
-    hasher.update(&bytes);
     let Some(parent) = path.parent() else {
         anyhow::bail!("cannot get parent for {path:?}");
     };
     std::fs::create_dir_all(parent).context("creating cache file parent directory")?;
-    let mut file = File::create(path).context("creating cache file")?;
-    file.write_all(digest).context("storing source digest")?;
-    file.write_all(&hasher.finalize())
-        .context("storing verifier index digest")?;
-    file.write_all(&bytes)
-        .context("storing verifier index into cache file")?;
-    Ok(())
+    // replaced at once, so that the concurrently starting nodes never read
+    // the partially written file, nor write into the same temporary file.
+    openmina_core::fs::write_atomic(path, |file| {
+        file.write_all(magic)?;
+        file.write_all(bytes)
+    })
+    .context("storing verifier index into cache file")
 }
 
 macro_rules! make_with_ext_cache {
@@ -468,3 +530,50 @@ pub fn make_zkapp_verifier_index(vk: &VerificationKey) -> VerifierIndex<Fq> {
         zk_rows,
     }
 }
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_cache_file_concurrently() {
+        let dir = std::env::temp_dir().join(format!("openmina-verifiers-{}", std::process::id()));
+        let path = dir.join("devnet").join("block_verifier_index.bin");
+
+        let contents = (0..8u8).map(|i| vec![i; 64 * 1024]).collect::<Vec<_>>();
+        std::thread::scope(|s| {
+            for bytes in &contents {
+                let path = &path;
+                s.spawn(move || write_cache_file(path, MAGIC.as_slice(), bytes).unwrap());
+            }
+        });
+
+        // One of the writes wins as a whole, no temporary files are left.
+        let written = std::fs::read(&path).unwrap();
+        let written = written.strip_prefix(MAGIC.as_slice()).unwrap();
+        assert!(contents.iter().any(|bytes| bytes.as_slice() == written));
+        let files = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(files, 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_from_cache_bytes_digest_mismatch() {
+        let payload = [1u8; 16];
+        let source_digest = [2u8; 32];
+        let mut bytes = source_digest.to_vec();
+        bytes.extend_from_slice(&Sha256::digest(payload));
+        bytes.extend_from_slice(&payload);
+
+        let err = from_cache_bytes(&bytes, &[3u8; 32]).err().unwrap();
+        assert!(err.to_string().contains("source digest"));
+
+        let last = bytes.len().saturating_sub(1);
+        if let Some(byte) = bytes.get_mut(last) {
+            *byte ^= 1;
+        }
+        let err = from_cache_bytes(&bytes, &source_digest).err().unwrap();
+        assert!(err.to_string().contains("index digest"));
+    }
+}
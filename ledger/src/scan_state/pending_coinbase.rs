@@ -519,6 +519,16 @@ impl PendingCoinbase {
         self.tree.merkle_root()
     }
 
+    /// Depth of the tree, which holds `2^depth` stacks.
+    pub fn depth(&self) -> usize {
+        self.tree.depth
+    }
+
+    /// Stacks of the coinbases, which aren't yet emitted by the scan state.
+    pub fn stacks_count(&self) -> usize {
+        self.pos_list.len()
+    }
+
     fn get_stack(&self, addr: Address) -> &Stack {
         self.tree.get_exn(addr)
     }
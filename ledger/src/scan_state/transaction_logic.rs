@@ -121,6 +121,7 @@ impl Display for TransactionFailure {
             Self::UpdateNotPermittedBalance => "Update_not_permitted_balance",
             Self::UpdateNotPermittedAccess => "Update_not_permitted_access",
             Self::UpdateNotPermittedTiming => "Update_not_permitted_timing",
+            // Lowercase in the OCaml `to_string` as well.
             Self::UpdateNotPermittedDelegate => "update_not_permitted_delegate",
             Self::UpdateNotPermittedAppState => "Update_not_permitted_app_state",
             Self::UpdateNotPermittedVerificationKey => "Update_not_permitted_verification_key",
@@ -165,6 +166,147 @@ impl Display for TransactionFailure {
     }
 }
 
+impl TransactionFailure {
+    /// All the failures, in the order of the OCaml type. The app state
+    /// precondition failure is listed with the index 0.
+    pub const ALL: [Self; 45] = [
+        Self::Predicate,
+        Self::SourceNotPresent,
+        Self::ReceiverNotPresent,
+        Self::AmountInsufficientToCreateAccount,
+        Self::CannotPayCreationFeeInToken,
+        Self::SourceInsufficientBalance,
+        Self::SourceMinimumBalanceViolation,
+        Self::ReceiverAlreadyExists,
+        Self::TokenOwnerNotCaller,
+        Self::Overflow,
+        Self::GlobalExcessOverflow,
+        Self::LocalExcessOverflow,
+        Self::LocalSupplyIncreaseOverflow,
+        Self::GlobalSupplyIncreaseOverflow,
+        Self::SignedCommandOnZkappAccount,
+        Self::ZkappAccountNotPresent,
+        Self::UpdateNotPermittedBalance,
+        Self::UpdateNotPermittedAccess,
+        Self::UpdateNotPermittedTiming,
+        Self::UpdateNotPermittedDelegate,
+        Self::UpdateNotPermittedAppState,
+        Self::UpdateNotPermittedVerificationKey,
+        Self::UpdateNotPermittedActionState,
+        Self::UpdateNotPermittedZkappUri,
+        Self::UpdateNotPermittedTokenSymbol,
+        Self::UpdateNotPermittedPermissions,
+        Self::UpdateNotPermittedNonce,
+        Self::UpdateNotPermittedVotingFor,
+        Self::ZkappCommandReplayCheckFailed,
+        Self::FeePayerNonceMustIncrease,
+        Self::FeePayerMustBeSigned,
+        Self::AccountBalancePreconditionUnsatisfied,
+        Self::AccountNoncePreconditionUnsatisfied,
+        Self::AccountReceiptChainHashPreconditionUnsatisfied,
+        Self::AccountDelegatePreconditionUnsatisfied,
+        Self::AccountActionStatePreconditionUnsatisfied,
+        Self::AccountAppStatePreconditionUnsatisfied(0),
+        Self::AccountProvedStatePreconditionUnsatisfied,
+        Self::AccountIsNewPreconditionUnsatisfied,
+        Self::ProtocolStatePreconditionUnsatisfied,
+        Self::UnexpectedVerificationKeyHash,
+        Self::ValidWhilePreconditionUnsatisfied,
+        Self::IncorrectNonce,
+        Self::InvalidFeeExcess,
+        Self::Cancelled,
+    ];
+
+    /// Position of the failure in the OCaml type, which is also its
+    /// binprot tag.
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::Predicate => 0,
+            Self::SourceNotPresent => 1,
+            Self::ReceiverNotPresent => 2,
+            Self::AmountInsufficientToCreateAccount => 3,
+            Self::CannotPayCreationFeeInToken => 4,
+            Self::SourceInsufficientBalance => 5,
+            Self::SourceMinimumBalanceViolation => 6,
+            Self::ReceiverAlreadyExists => 7,
+            Self::TokenOwnerNotCaller => 8,
+            Self::Overflow => 9,
+            Self::GlobalExcessOverflow => 10,
+            Self::LocalExcessOverflow => 11,
+            Self::LocalSupplyIncreaseOverflow => 12,
+            Self::GlobalSupplyIncreaseOverflow => 13,
+            Self::SignedCommandOnZkappAccount => 14,
+            Self::ZkappAccountNotPresent => 15,
+            Self::UpdateNotPermittedBalance => 16,
+            Self::UpdateNotPermittedAccess => 17,
+            Self::UpdateNotPermittedTiming => 18,
+            Self::UpdateNotPermittedDelegate => 19,
+            Self::UpdateNotPermittedAppState => 20,
+            Self::UpdateNotPermittedVerificationKey => 21,
+            Self::UpdateNotPermittedActionState => 22,
+            Self::UpdateNotPermittedZkappUri => 23,
+            Self::UpdateNotPermittedTokenSymbol => 24,
+            Self::UpdateNotPermittedPermissions => 25,
+            Self::UpdateNotPermittedNonce => 26,
+            Self::UpdateNotPermittedVotingFor => 27,
+            Self::ZkappCommandReplayCheckFailed => 28,
+            Self::FeePayerNonceMustIncrease => 29,
+            Self::FeePayerMustBeSigned => 30,
+            Self::AccountBalancePreconditionUnsatisfied => 31,
+            Self::AccountNoncePreconditionUnsatisfied => 32,
+            Self::AccountReceiptChainHashPreconditionUnsatisfied => 33,
+            Self::AccountDelegatePreconditionUnsatisfied => 34,
+            Self::AccountActionStatePreconditionUnsatisfied => 35,
+            Self::AccountAppStatePreconditionUnsatisfied(_) => 36,
+            Self::AccountProvedStatePreconditionUnsatisfied => 37,
+            Self::AccountIsNewPreconditionUnsatisfied => 38,
+            Self::ProtocolStatePreconditionUnsatisfied => 39,
+            Self::UnexpectedVerificationKeyHash => 40,
+            Self::ValidWhilePreconditionUnsatisfied => 41,
+            Self::IncorrectNonce => 42,
+            Self::InvalidFeeExcess => 43,
+            Self::Cancelled => 44,
+        }
+    }
+
+    /// Failure with the given [`Self::code`]. `app_state_index` is only
+    /// used for the app state precondition failure.
+    pub fn from_code(code: u8, app_state_index: u64) -> Option<Self> {
+        match Self::ALL.get(usize::from(code))? {
+            Self::AccountAppStatePreconditionUnsatisfied(_) => Some(
+                Self::AccountAppStatePreconditionUnsatisfied(app_state_index),
+            ),
+            failure => Some(failure.clone()),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown transaction failure: `{0}`")]
+pub struct TransactionFailureParseError(pub String);
+
+impl std::str::FromStr for TransactionFailure {
+    type Err = TransactionFailureParseError;
+
+    /// Parses the failure from its OCaml string, the inverse of [`Display`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let app_state_index = s
+            .strip_prefix("Account_app_state_")
+            .and_then(|s| s.strip_suffix("_precondition_unsatisfied"));
+        if let Some(index) = app_state_index {
+            return index
+                .parse()
+                .map(Self::AccountAppStatePreconditionUnsatisfied)
+                .map_err(|_| TransactionFailureParseError(s.to_owned()));
+        }
+        Self::ALL
+            .iter()
+            .find(|f| f.to_string() == s)
+            .cloned()
+            .ok_or_else(|| TransactionFailureParseError(s.to_owned()))
+    }
+}
+
 /// https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/mina_base/transaction_status.ml#L452
 #[derive(SerdeYojsonEnum, Debug, Clone, PartialEq, Eq)]
 pub enum TransactionStatus {
@@ -3578,6 +3720,18 @@ pub mod zkapp_command {
         pub authorization: Signature,
     }
 
+    /// See [`ZkAppCommand::complexity`].
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct ZkappCommandComplexity {
+        /// Fee payer not included.
+        pub account_updates: usize,
+        pub proof_segments: usize,
+        pub signed_single_segments: usize,
+        pub signed_pair_segments: usize,
+        pub event_elements: usize,
+        pub action_elements: usize,
+    }
+
     /// https://github.com/MinaProtocol/mina/blob/2ee6e004ba8c6a0541056076aab22ea162f7eb3a/src/lib/mina_base/zkapp_command.ml#L959
     #[derive(Debug, Clone, PartialEq)]
     pub struct ZkAppCommand {
@@ -3678,10 +3832,8 @@ pub mod zkapp_command {
                 + (signed_single_cost * (signed_single_segments as f64))
         }
 
-        /// Zkapp_command transactions are filtered using this predicate
-        /// - when adding to the transaction pool
-        /// - in incoming blocks
-        pub fn valid_size(&self) -> Result<(), String> {
+        /// Sizes of the command which its processing cost depends on.
+        pub fn complexity(&self) -> ZkappCommandComplexity {
             use crate::proofs::zkapp::group::{SegmentBasic, ZkappCommandIntermediateState};
 
             let Self {
@@ -3693,17 +3845,16 @@ pub mod zkapp_command {
             let events_elements =
                 |events: &[Event]| -> usize { events.iter().map(Event::len).sum() };
 
-            let mut n_account_updates = 0;
-            let (mut num_event_elements, mut num_action_elements) = (0, 0);
+            let mut complexity = ZkappCommandComplexity::default();
 
             account_updates.fold((), |_, account_update| {
-                num_event_elements += events_elements(account_update.body.events.events());
-                num_action_elements += events_elements(account_update.body.actions.events());
-                n_account_updates += 1;
+                complexity.event_elements += events_elements(account_update.body.events.events());
+                complexity.action_elements += events_elements(account_update.body.actions.events());
+                complexity.account_updates += 1;
             });
 
             let group = std::iter::repeat(((), (), ()))
-                .take(n_account_updates + 2) // + 2 to prepend two. See OCaml
+                .take(complexity.account_updates + 2) // + 2 to prepend two. See OCaml
                 .collect::<Vec<_>>();
 
             let groups = crate::proofs::zkapp::group::group_by_zkapp_command_rev::<_, (), (), ()>(
@@ -3711,17 +3862,30 @@ pub mod zkapp_command {
                 vec![vec![((), (), ())], group],
             );
 
-            let (mut proof_segments, mut signed_single_segments, mut signed_pair_segments) =
-                (0, 0, 0);
-
             for ZkappCommandIntermediateState { spec, .. } in &groups {
                 match spec {
-                    SegmentBasic::Proved => proof_segments += 1,
-                    SegmentBasic::OptSigned => signed_single_segments += 1,
-                    SegmentBasic::OptSignedOptSigned => signed_pair_segments += 1,
+                    SegmentBasic::Proved => complexity.proof_segments += 1,
+                    SegmentBasic::OptSigned => complexity.signed_single_segments += 1,
+                    SegmentBasic::OptSignedOptSigned => complexity.signed_pair_segments += 1,
                 }
             }
 
+            complexity
+        }
+
+        /// Zkapp_command transactions are filtered using this predicate
+        /// - when adding to the transaction pool
+        /// - in incoming blocks
+        pub fn valid_size(&self) -> Result<(), String> {
+            let ZkappCommandComplexity {
+                account_updates: _,
+                proof_segments,
+                signed_single_segments,
+                signed_pair_segments,
+                event_elements: num_event_elements,
+                action_elements: num_action_elements,
+            } = self.complexity();
+
             let GenesisConstant {
                 zkapp_transaction_cost_limit: cost_limit,
                 max_event_elements,
@@ -4115,18 +4279,33 @@ pub mod zkapp_command {
 
         pub struct Cache {
             cache: HashMap<AccountId, HashMap<Fp, VerificationKeyWire>>,
+            /// Looked up by the hash only, if not found for the account.
+            fallback: std::sync::Arc<HashMap<Fp, VerificationKeyWire>>,
         }
 
         impl Cache {
             pub fn new(cache: HashMap<AccountId, HashMap<Fp, VerificationKeyWire>>) -> Self {
-                Self { cache }
+                Self {
+                    cache,
+                    fallback: Default::default(),
+                }
+            }
+
+            pub fn with_fallback(
+                mut self,
+                fallback: std::sync::Arc<HashMap<Fp, VerificationKeyWire>>,
+            ) -> Self {
+                self.fallback = fallback;
+                self
             }
         }
 
         impl ToVerifiableCache for Cache {
             fn find(&self, account_id: &AccountId, vk_hash: &Fp) -> Option<&VerificationKeyWire> {
-                let vks = self.cache.get(account_id)?;
-                vks.get(vk_hash)
+                self.cache
+                    .get(account_id)
+                    .and_then(|vks| vks.get(vk_hash))
+                    .or_else(|| self.fallback.get(vk_hash))
             }
             fn add(&mut self, account_id: AccountId, vk: VerificationKeyWire) {
                 let vks = self.cache.entry(account_id).or_default();
@@ -8200,4 +8379,45 @@ mod tests {
         let result = cons_signed_command_payload(&tx, prev_receipt_chain_hash);
         assert_eq!(result, next_receipt_chain_hash);
     }
+
+    #[test]
+    fn test_transaction_failure_ocaml_strings() {
+        use binprot::BinProtWrite;
+        use mina_p2p_messages::v2::MinaBaseTransactionStatusFailureStableV2;
+
+        let lines = include_str!("data/transaction_failures.txt")
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        for line in lines {
+            let (code, message) = line.split_once(' ').unwrap();
+            let code: u8 = code.parse().unwrap();
+
+            let failure = TransactionFailure::from_str(message).unwrap();
+            assert_eq!(failure.to_string(), message);
+            assert_eq!(failure.code(), code, "{message}");
+
+            let mut bytes = Vec::new();
+            MinaBaseTransactionStatusFailureStableV2::from(&failure)
+                .binprot_write(&mut bytes)
+                .unwrap();
+            assert_eq!(bytes[0], code, "{message}");
+        }
+
+        assert!(TransactionFailure::from_str("Update_not_permitted_delegate").is_err());
+        assert!(
+            TransactionFailure::from_str("Account_app_state_x_precondition_unsatisfied").is_err()
+        );
+        assert_eq!(
+            TransactionFailure::from_code(36, 3),
+            Some(TransactionFailure::AccountAppStatePreconditionUnsatisfied(
+                3
+            ))
+        );
+        assert_eq!(TransactionFailure::from_code(45, 0), None);
+
+        for (code, failure) in TransactionFailure::ALL.iter().enumerate() {
+            assert_eq!(usize::from(failure.code()), code, "{failure}");
+        }
+    }
 }
@@ -33,7 +33,11 @@ use crate::{
     },
     sparse_ledger::SparseLedger,
     split_at, split_at_vec,
-    staged_ledger::{pre_diff_info, resources::IncreaseBy, transaction_validator},
+    staged_ledger::{
+        pre_diff_info,
+        resources::IncreaseBy,
+        transaction_validator::{IncrementalApplication, IncrementalApplicationProgress},
+    },
     verifier::{Verifier, VerifierError},
     zkapps::non_snark::LedgerNonSnark,
     AccountId, BaseLedger, Mask, TokenId,
@@ -1802,6 +1806,48 @@ impl StagedLedger {
     >
     where
         F: Fn(&work::Statement) -> Option<work::Checked>,
+    {
+        self.create_diff_with_budget(
+            constraint_constants,
+            global_slot,
+            log_block_creation,
+            coinbase_receiver,
+            logger,
+            current_state_view,
+            transactions_by_fee,
+            get_completed_work,
+            supercharge_coinbase,
+            |_| true,
+        )
+    }
+
+    /// Same as [`Self::create_diff`], but `should_continue` is called with
+    /// the accumulated effects of the commands included so far, before each
+    /// command is applied. Once it returns `false`, no more commands are
+    /// included, so the block producer can build the diff greedily and stop
+    /// when its time budget runs out.
+    pub fn create_diff_with_budget<F, B>(
+        &self,
+        constraint_constants: &ConstraintConstants,
+        global_slot: Slot,
+        log_block_creation: Option<bool>,
+        coinbase_receiver: CompressedPubKey,
+        logger: (),
+        current_state_view: &ProtocolStateView,
+        transactions_by_fee: Vec<valid::UserCommand>,
+        get_completed_work: F,
+        supercharge_coinbase: bool,
+        mut should_continue: B,
+    ) -> Result<
+        (
+            with_valid_signatures_and_proofs::Diff,
+            Vec<(valid::UserCommand, String)>,
+        ),
+        PreDiffError,
+    >
+    where
+        F: Fn(&work::Statement) -> Option<work::Checked>,
+        B: FnMut(&IncrementalApplicationProgress) -> bool,
     {
         let _log_block_creation = log_block_creation.unwrap_or(false);
 
@@ -1864,18 +1910,23 @@ impl StagedLedger {
             let length = transactions_by_fee.len();
             let mut valid_on_this_ledger = Vec::with_capacity(length);
             let mut invalid_on_this_ledger = Vec::with_capacity(length);
-            let mut count = 0;
 
             let _transactions_by_fee_len = transactions_by_fee.len();
 
+            let mut application = IncrementalApplication::new(
+                constraint_constants,
+                global_slot,
+                current_state_view,
+                validating_ledger.clone(),
+                self.scan_state.free_space(),
+            );
+
             for txn in transactions_by_fee {
-                let res = transaction_validator::apply_transaction_first_pass(
-                    constraint_constants,
-                    global_slot,
-                    current_state_view,
-                    validating_ledger,
-                    &Transaction::Command(txn.forget_check()),
-                );
+                if !should_continue(application.progress()) {
+                    break;
+                }
+
+                let res = application.apply(&Transaction::Command(txn.forget_check()));
 
                 match res {
                     Err(e) => {
@@ -1885,10 +1936,10 @@ impl StagedLedger {
                         );
                         invalid_on_this_ledger.push((txn, e));
                     }
-                    Ok(_txn_partially_applied) => {
+                    Ok(progress) => {
+                        let is_full = progress.is_full();
                         valid_on_this_ledger.push(txn);
-                        count += 1;
-                        if count >= self.scan_state.free_space() {
+                        if is_full {
                             break;
                         }
                     }
@@ -1999,6 +2050,36 @@ impl StagedLedger {
             })
             .collect::<Vec<_>>()
     }
+
+    /// Coinbases and fee transfers applied for the latest block, in the
+    /// order they were applied.
+    pub fn latest_block_internal_commands(
+        &self,
+        previous_block_state_hash: Fp,
+    ) -> Vec<scan_state::transaction_logic::transaction_applied::Varying> {
+        use scan_state::transaction_logic::transaction_applied::Varying;
+
+        let f = |t: Arc<TransactionWithWitness>| {
+            let TransactionWithWitness {
+                transaction_with_info,
+                state_hash: (leaf_block_hash, _),
+                ..
+            } = t.as_ref();
+            match &transaction_with_info.varying {
+                varying @ (Varying::FeeTransfer(_) | Varying::Coinbase(_))
+                    if leaf_block_hash == &previous_block_state_hash =>
+                {
+                    Some(varying.clone())
+                }
+                _ => None,
+            }
+        };
+
+        // Transactions of the block fill up the earlier tree first.
+        let earlier = self.scan_state.base_jobs_on_earlier_tree(0).filter_map(f);
+        let latest = self.scan_state.base_jobs_on_latest_tree().filter_map(f);
+        earlier.chain(latest).collect()
+    }
 }
 
 #[cfg(test)]
@@ -5630,6 +5711,54 @@ mod tests_ocaml {
         );
     }
 
+    #[test]
+    fn create_diff_stops_when_budget_runs_out() {
+        const BUDGET: usize = 3;
+
+        let ledger_init_state = gen_initial_ledger_state();
+        let global_slot = Slot::gen_small();
+        let cmds = signed_command_sequence(BUDGET * 2, SignKind::Real, &ledger_init_state);
+
+        async_with_ledgers(
+            &ledger_init_state,
+            cmds.clone(),
+            vec![],
+            |_snarked_ledger, sl, _test_mask| {
+                let current_state_view = dummy_state_view(Some(global_slot));
+                let mut seen = Vec::new();
+
+                let (diff, invalid_txns) = sl
+                    .create_diff_with_budget(
+                        &CONSTRAINT_CONSTANTS,
+                        global_slot,
+                        None,
+                        COINBASE_RECEIVER.clone(),
+                        (),
+                        &current_state_view,
+                        cmds,
+                        stmt_to_work_zero_fee(SELF_PK.clone()),
+                        false,
+                        |progress| {
+                            seen.push(progress.clone());
+                            progress.applied < BUDGET
+                        },
+                    )
+                    .unwrap();
+
+                assert!(invalid_txns.is_empty());
+                assert_eq!(diff.commands().len(), BUDGET);
+                assert_eq!(seen.len(), BUDGET + 1);
+                assert_eq!(seen[0].applied, 0);
+                assert_eq!(seen[BUDGET].applied, BUDGET);
+                assert_eq!(
+                    seen[BUDGET].available_space + BUDGET as u64,
+                    seen[0].available_space
+                );
+                assert!(seen[BUDGET].fee_excess.fee_excess_l.is_non_neg());
+            },
+        );
+    }
+
     /// Blocks having commands with insufficient funds are rejected
     ///
     /// https://github.com/MinaProtocol/mina/blob/3753a8593cc1577bcf4da16620daf9946d88e8e5/src/lib/staged_ledger/staged_ledger.ml#L3665
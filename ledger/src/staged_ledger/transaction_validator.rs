@@ -2,7 +2,8 @@ use openmina_core::constants::ConstraintConstants;
 
 use crate::{
     scan_state::{
-        currency::Slot,
+        currency::{Amount, Signed, Slot},
+        fee_excess::FeeExcess,
         transaction_logic::{
             self,
             protocol_state::ProtocolStateView,
@@ -82,3 +83,105 @@ pub fn apply_user_command(
         )
     })
 }
+
+/// Accumulated effects of the commands applied by [`IncrementalApplication`].
+#[derive(Debug, Clone)]
+pub struct IncrementalApplicationProgress {
+    /// Number of commands applied so far.
+    pub applied: usize,
+    pub fee_excess: FeeExcess,
+    /// For zkApp commands only the fee payer part is included, account
+    /// updates are applied in the second pass.
+    pub supply_increase: Signed<Amount>,
+    /// Number of commands which still fit into the scan state.
+    pub available_space: u64,
+}
+
+impl IncrementalApplicationProgress {
+    pub fn is_full(&self) -> bool {
+        self.available_space == 0
+    }
+}
+
+/// Applies the first pass of the commands one at a time, keeping track of
+/// their accumulated effects, so that the caller can decide after each
+/// command whether to continue.
+///
+/// Successfully applied commands are committed to the ledger, failed ones
+/// leave it untouched.
+pub struct IncrementalApplication<'a> {
+    constraint_constants: &'a ConstraintConstants,
+    global_slot: Slot,
+    txn_state_view: &'a ProtocolStateView,
+    ledger: Mask,
+    progress: IncrementalApplicationProgress,
+}
+
+impl<'a> IncrementalApplication<'a> {
+    pub fn new(
+        constraint_constants: &'a ConstraintConstants,
+        global_slot: Slot,
+        txn_state_view: &'a ProtocolStateView,
+        ledger: Mask,
+        available_space: u64,
+    ) -> Self {
+        Self {
+            constraint_constants,
+            global_slot,
+            txn_state_view,
+            ledger,
+            progress: IncrementalApplicationProgress {
+                applied: 0,
+                fee_excess: FeeExcess::empty(),
+                supply_increase: Signed::<Amount>::zero(),
+                available_space,
+            },
+        }
+    }
+
+    pub fn progress(&self) -> &IncrementalApplicationProgress {
+        &self.progress
+    }
+
+    pub fn apply(
+        &mut self,
+        transaction: &Transaction,
+    ) -> Result<&IncrementalApplicationProgress, String> {
+        let constraint_constants = self.constraint_constants;
+        let fee_excess = transaction
+            .fee_excess()
+            .and_then(|fee_excess| FeeExcess::combine(&self.progress.fee_excess, &fee_excess))?;
+
+        let supply_increase = within_mask(self.ledger.clone(), |ledger| {
+            let partially_applied = transaction_logic::apply_transaction_first_pass(
+                constraint_constants,
+                self.global_slot,
+                self.txn_state_view,
+                ledger,
+                transaction,
+            )?;
+            let supply_increase = match partially_applied {
+                TransactionPartiallyApplied::ZkappCommand(partially_applied) => {
+                    partially_applied.global_state.supply_increase
+                }
+                partially_applied => transaction_logic::apply_transaction_second_pass(
+                    constraint_constants,
+                    ledger,
+                    partially_applied,
+                )?
+                .supply_increase(constraint_constants)?,
+            };
+            self.progress
+                .supply_increase
+                .add(&supply_increase)
+                .ok_or_else(|| "supply increase overflow".to_string())
+        })?;
+
+        let progress = &mut self.progress;
+        progress.applied += 1;
+        progress.fee_excess = fee_excess;
+        progress.supply_increase = supply_increase;
+        progress.available_space = progress.available_space.saturating_sub(1);
+        Ok(progress)
+    }
+}
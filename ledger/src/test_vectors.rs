@@ -0,0 +1,374 @@
+//! Transaction application test vectors, used for conformance testing
+//! against other implementations (e.g. the OCaml node).
+//!
+//! A vector describes a ledger built from `initial_accounts` (inserted in
+//! order into an empty ledger of depth `ledger_depth`), a command applied
+//! to it at `global_slot`, and the expected outcome: either the resulting
+//! status, accounts and ledger hash, or the rejection of the command.
+//!
+//! Types from `mina_p2p_messages` are used, so the JSON matches the
+//! format used by the OCaml node. Signatures and proofs aren't verified
+//! when applying the command.
+
+use mina_p2p_messages::v2;
+use openmina_core::constants::constraint_constants;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    scan_state::{
+        currency::Slot,
+        transaction_logic::{
+            apply_transactions, protocol_state::protocol_state_body_view, Transaction, UserCommand,
+        },
+    },
+    Account, BaseLedger, Mask,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestVector {
+    pub name: String,
+    pub ledger_depth: usize,
+    pub global_slot: u32,
+    pub protocol_state_body: v2::MinaStateProtocolStateBodyValueStableV2,
+    pub initial_accounts: Vec<v2::MinaBaseAccountBinableArgStableV2>,
+    pub initial_ledger_hash: v2::LedgerHash,
+    pub command: v2::MinaBaseUserCommandStableV2,
+    pub expected: TestVectorOutcome,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind")]
+pub enum TestVectorOutcome {
+    /// Command was included, though it might have failed, see `status`.
+    Applied {
+        status: v2::MinaBaseTransactionStatusStableV2,
+        /// All accounts of the resulting ledger, ordered by their index.
+        accounts: Vec<v2::MinaBaseAccountBinableArgStableV2>,
+        ledger_hash: v2::LedgerHash,
+    },
+    /// Command can't be included. The error is informative only, it isn't
+    /// compared, as implementations word their errors differently.
+    Rejected { error: String },
+}
+
+impl TestVectorOutcome {
+    fn matches(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Rejected { .. }, Self::Rejected { .. }) => true,
+            (a, b) => a == b,
+        }
+    }
+}
+
+fn ledger_hash(ledger: &mut Mask) -> v2::LedgerHash {
+    v2::LedgerHash::from_fp(ledger.merkle_root())
+}
+
+fn build_ledger(depth: usize, accounts: &[Account]) -> Result<Mask, String> {
+    let mut ledger = Mask::new_unattached(depth);
+    for account in accounts {
+        ledger
+            .get_or_create_account(account.id(), account.clone())
+            .map_err(|err| format!("failed to add account {:?}: {err:?}", account.id()))?;
+    }
+    Ok(ledger)
+}
+
+fn apply(
+    ledger: &mut Mask,
+    global_slot: u32,
+    protocol_state_body: &v2::MinaStateProtocolStateBodyValueStableV2,
+    command: UserCommand,
+) -> Result<TestVectorOutcome, String> {
+    let state_view = protocol_state_body_view(protocol_state_body)
+        .map_err(|err| format!("invalid protocol state body: {err:?}"))?;
+
+    let applied = apply_transactions(
+        constraint_constants(),
+        Slot::from_u32(global_slot),
+        &state_view,
+        ledger,
+        &[Transaction::Command(command)],
+    );
+
+    Ok(match applied {
+        Err(error) => TestVectorOutcome::Rejected { error },
+        Ok(applied) => {
+            let status = applied
+                .first()
+                .ok_or("no transaction applied")?
+                .transaction_status()
+                .into();
+            TestVectorOutcome::Applied {
+                status,
+                accounts: ledger.to_list().iter().map(Into::into).collect(),
+                ledger_hash: ledger_hash(ledger),
+            }
+        }
+    })
+}
+
+/// Applies the command of the vector and returns the actual outcome.
+pub fn run(vector: &TestVector) -> Result<TestVectorOutcome, String> {
+    let accounts = vector
+        .initial_accounts
+        .iter()
+        .map(Account::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("invalid account: {err:?}"))?;
+    let command = UserCommand::try_from(&vector.command)
+        .map_err(|err| format!("invalid command: {err:?}"))?;
+
+    let mut ledger = build_ledger(vector.ledger_depth, &accounts)?;
+    let initial_ledger_hash = ledger_hash(&mut ledger);
+    if initial_ledger_hash != vector.initial_ledger_hash {
+        return Err(format!(
+            "initial ledger hash mismatch, expected: {}, got: {initial_ledger_hash}",
+            vector.initial_ledger_hash
+        ));
+    }
+
+    apply(
+        &mut ledger,
+        vector.global_slot,
+        &vector.protocol_state_body,
+        command,
+    )
+}
+
+/// Checks that applying the command of the vector leads to the expected
+/// outcome.
+pub fn check(vector: &TestVector) -> Result<(), String> {
+    let actual = run(vector)?;
+    if actual.matches(&vector.expected) {
+        return Ok(());
+    }
+    let to_json = |outcome: &TestVectorOutcome| {
+        serde_json::to_string_pretty(outcome).unwrap_or_else(|err| err.to_string())
+    };
+    Err(format!(
+        "outcome mismatch\nexpected: {}\ngot: {}",
+        to_json(&vector.expected),
+        to_json(&actual)
+    ))
+}
+
+/// Checks all the vectors, returns names of the failed ones along with
+/// the reason.
+pub fn check_all(vectors: &[TestVector]) -> Vec<(String, String)> {
+    vectors
+        .iter()
+        .filter_map(|vector| {
+            check(vector)
+                .err()
+                .map(|reason| (vector.name.clone(), reason))
+        })
+        .collect()
+}
+
+#[cfg(any(test, feature = "test-vectors"))]
+pub mod generate {
+    //! Generates the vectors from the logic of this crate.
+
+    use mina_signer::{CompressedPubKey, Keypair, NetworkId, Signer};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        dummy::for_tests::dummy_protocol_state,
+        scan_state::{
+            currency::{Amount, Balance, Fee, Nonce},
+            transaction_logic::{
+                signed_command::{
+                    Body, PaymentPayload, SignedCommand, SignedCommandPayload,
+                    StakeDelegationPayload,
+                },
+                transaction_union_payload::TransactionUnionPayload,
+                Memo,
+            },
+        },
+        AccountId, TokenId,
+    };
+
+    const LEDGER_DEPTH: usize = 35;
+    const GLOBAL_SLOT: u32 = 100;
+    const MINA: u64 = 1_000_000_000;
+
+    struct Case {
+        name: &'static str,
+        fee: u64,
+        nonce: u32,
+        valid_until: Option<u32>,
+        body: Body,
+    }
+
+    fn sign(kp: &Keypair, case: Case) -> UserCommand {
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(case.fee),
+            kp.public.into_compressed(),
+            Nonce::from_u32(case.nonce),
+            case.valid_until.map(Slot::from_u32),
+            Memo::dummy(),
+            case.body,
+        );
+        let payload_to_sign = TransactionUnionPayload::of_user_command_payload(&payload);
+        let mut signer = mina_signer::create_legacy(NetworkId::TESTNET);
+        let signature = signer.sign(kp, &payload_to_sign);
+
+        UserCommand::SignedCommand(Box::new(SignedCommand {
+            payload,
+            signer: kp.public.into_compressed(),
+            signature,
+        }))
+    }
+
+    fn payment(receiver_pk: &CompressedPubKey, amount: u64) -> Body {
+        Body::Payment(PaymentPayload {
+            receiver_pk: receiver_pk.clone(),
+            amount: Amount::from_u64(amount),
+        })
+    }
+
+    fn delegation(new_delegate: &CompressedPubKey) -> Body {
+        Body::StakeDelegation(StakeDelegationPayload::SetDelegate {
+            new_delegate: new_delegate.clone(),
+        })
+    }
+
+    /// Generates the vectors deterministically from `seed`, with the
+    /// expected outcomes computed by this crate.
+    pub fn signed_command_vectors(seed: u64) -> Vec<TestVector> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut keypair = || Keypair::rand(&mut rng).unwrap();
+        let sender = keypair();
+        let receiver = keypair();
+        let new_account = keypair().public.into_compressed();
+
+        let sender_pk = sender.public.into_compressed();
+        let receiver_pk = receiver.public.into_compressed();
+        let account = |pk: &CompressedPubKey, balance: u64| {
+            Account::create_with(
+                AccountId::new(pk.clone(), TokenId::default()),
+                Balance::from_u64(balance),
+            )
+        };
+        let accounts = vec![
+            account(&sender_pk, 100 * MINA),
+            account(&receiver_pk, 10 * MINA),
+        ];
+
+        let protocol_state_body = dummy_protocol_state().body;
+        let account_creation_fee = constraint_constants().account_creation_fee;
+
+        let case = |name, fee, nonce, body| Case {
+            name,
+            fee,
+            nonce,
+            valid_until: None,
+            body,
+        };
+        let cases = vec![
+            case("payment", MINA / 10, 0, payment(&receiver_pk, 5 * MINA)),
+            case(
+                "payment_creates_account",
+                MINA / 10,
+                0,
+                payment(&new_account, 5 * MINA),
+            ),
+            case(
+                "payment_below_account_creation_fee",
+                MINA / 10,
+                0,
+                payment(&new_account, account_creation_fee / 2),
+            ),
+            case(
+                "payment_insufficient_balance",
+                MINA / 10,
+                0,
+                payment(&receiver_pk, 1_000 * MINA),
+            ),
+            case("delegation", MINA / 10, 0, delegation(&receiver_pk)),
+            case(
+                "delegation_to_unknown_account",
+                MINA / 10,
+                0,
+                delegation(&new_account),
+            ),
+            case("nonce_mismatch", MINA / 10, 1, payment(&receiver_pk, MINA)),
+            case(
+                "fee_exceeds_balance",
+                1_000 * MINA,
+                0,
+                payment(&receiver_pk, MINA),
+            ),
+            Case {
+                valid_until: Some(GLOBAL_SLOT - 1),
+                ..case("expired", MINA / 10, 0, payment(&receiver_pk, MINA))
+            },
+        ];
+
+        cases
+            .into_iter()
+            .map(|case| {
+                let name = case.name.to_owned();
+                let command = sign(&sender, case);
+
+                let mut ledger = build_ledger(LEDGER_DEPTH, &accounts).unwrap();
+                let initial_ledger_hash = ledger_hash(&mut ledger);
+                let expected = apply(
+                    &mut ledger,
+                    GLOBAL_SLOT,
+                    &protocol_state_body,
+                    command.clone(),
+                )
+                .unwrap();
+
+                TestVector {
+                    name,
+                    ledger_depth: LEDGER_DEPTH,
+                    global_slot: GLOBAL_SLOT,
+                    protocol_state_body: protocol_state_body.clone(),
+                    initial_accounts: accounts.iter().map(Into::into).collect(),
+                    initial_ledger_hash,
+                    command: (&command).into(),
+                    expected,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_vectors_roundtrip() {
+        let vectors = generate::signed_command_vectors(0);
+        let json = serde_json::to_string(&vectors).unwrap();
+        let mut vectors: Vec<TestVector> = serde_json::from_str(&json).unwrap();
+        assert_eq!(check_all(&vectors), vec![]);
+
+        let outcome = |name: &str| {
+            vectors
+                .iter()
+                .find(|v| v.name == name)
+                .map(|v| v.expected.clone())
+                .unwrap()
+        };
+        assert!(matches!(
+            outcome("payment"),
+            TestVectorOutcome::Applied { .. }
+        ));
+        assert!(matches!(
+            outcome("nonce_mismatch"),
+            TestVectorOutcome::Rejected { .. }
+        ));
+
+        // tampered vector is reported.
+        let tampered = outcome("payment_creates_account");
+        let vector = vectors.iter_mut().find(|v| v.name == "payment").unwrap();
+        vector.expected = tampered;
+        assert_eq!(check_all(&vectors).len(), 1);
+    }
+}
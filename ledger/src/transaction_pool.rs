@@ -3,11 +3,14 @@ use serde::{Deserialize, Serialize};
 use std::{
     borrow::{Borrow, Cow},
     collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
 };
 
 use itertools::Itertools;
 use mina_hasher::Fp;
 use mina_p2p_messages::{bigint::BigInt, v2};
+use mina_signer::CompressedPubKey;
 use openmina_core::{bug_condition, consensus::ConsensusConstants};
 
 use crate::{
@@ -18,7 +21,7 @@ use crate::{
             valid, verifiable,
             zkapp_command::{
                 from_unapplied_sequence::{self, FromUnappliedSequence},
-                MaybeWithStatus, WithHash,
+                MaybeWithStatus, WithHash, ZkappCommandComplexity,
             },
             TransactionStatus::Applied,
             UserCommand, WellFormednessError, WithStatus,
@@ -46,6 +49,9 @@ pub enum TransactionError {
     Verifier(#[from] VerifierError),
     #[error(transparent)]
     WellFormedness(#[from] WellFormednessError),
+    /// See [`ZkappComplexityLimits`].
+    #[error("zkApp command exceeds the pool limits: {0}")]
+    ZkappLimits(String),
 }
 
 impl From<String> for TransactionPoolErrors {
@@ -282,6 +288,122 @@ pub struct Config {
     pub trust_system: (),
     pub pool_max_size: usize,
     pub slot_tx_end: Option<Slot>,
+    #[serde(default)]
+    pub zkapp_limits: ZkappComplexityLimits,
+    #[serde(default)]
+    pub fee_payer_limits: FeePayerRateLimits,
+}
+
+/// Limits of the zkApp commands admitted to the pool, stricter than the
+/// ones checked by [`ZkAppCommand::valid_size`]. They are local to the
+/// node and only applied at the pool admission, blocks including the
+/// commands exceeding them are still valid.
+///
+/// [`ZkAppCommand::valid_size`]: crate::scan_state::transaction_logic::zkapp_command::ZkAppCommand::valid_size
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZkappComplexityLimits {
+    pub max_account_updates: Option<usize>,
+    pub max_proof_segments: Option<usize>,
+    pub max_event_elements: Option<usize>,
+}
+
+impl ZkappComplexityLimits {
+    pub fn check(&self, complexity: &ZkappCommandComplexity) -> Result<(), String> {
+        let exceeded = [
+            (
+                "account updates",
+                complexity.account_updates,
+                self.max_account_updates,
+            ),
+            (
+                "proof segments",
+                complexity.proof_segments,
+                self.max_proof_segments,
+            ),
+            (
+                "event elements",
+                complexity.event_elements,
+                self.max_event_elements,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, value, max)| {
+            let max = max.filter(|max| value > *max)?;
+            Some(format!("too many {name}: {value} > {max}"))
+        })
+        .join("; ");
+
+        if exceeded.is_empty() {
+            Ok(())
+        } else {
+            Err(exceeded)
+        }
+    }
+}
+
+/// Limits the number of new commands admitted to the pool per fee payer,
+/// so that a single account with many cheap commands can't take up all
+/// of the verification capacity.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeePayerRateLimits {
+    /// Max commands admitted per fee payer within the `window`. Not
+    /// limited if `None`.
+    pub max_commands: Option<usize>,
+    pub window: Duration,
+    /// Fee payers with at least this balance aren't limited.
+    pub exempt_balance: Option<Balance>,
+    /// Fee payers which aren't limited.
+    pub allowlist: BTreeSet<CompressedPubKey>,
+}
+
+impl FeePayerRateLimits {
+    pub fn is_exempt(&self, fee_payer: &AccountId, account: Option<&Account>) -> bool {
+        self.allowlist.contains(&fee_payer.public_key)
+            || self
+                .exempt_balance
+                .zip(account)
+                .is_some_and(|(min, account)| account.balance >= min)
+    }
+}
+
+/// Commands admitted per fee payer within the current window, see
+/// [`FeePayerRateLimits`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct FeePayerAdmissions {
+    windows: BTreeMap<AccountId, (redux::Timestamp, usize)>,
+}
+
+impl FeePayerAdmissions {
+    fn admit(
+        &mut self,
+        limits: &FeePayerRateLimits,
+        diff: &mut diff::Diff,
+        accounts: &BTreeMap<AccountId, Account>,
+        now: redux::Timestamp,
+    ) -> Vec<UserCommand> {
+        let Some(max_commands) = limits.max_commands else {
+            return Vec::new();
+        };
+        self.windows.retain(|_, (start, _)| {
+            now.checked_sub(*start)
+                .is_some_and(|elapsed| elapsed < limits.window)
+        });
+
+        let (admitted, limited) = std::mem::take(&mut diff.list).into_iter().partition(|cmd| {
+            let fee_payer = cmd.fee_payer();
+            if limits.is_exempt(&fee_payer, accounts.get(&fee_payer)) {
+                return true;
+            }
+            let (_, count) = self.windows.entry(fee_payer).or_insert((now, 0));
+            if *count >= max_commands {
+                return false;
+            }
+            *count = count.saturating_add(1);
+            true
+        });
+        diff.list = admitted;
+        limited
+    }
 }
 
 /// Used to be able to de/serialize our `TransactionPool` in the state machine
@@ -520,6 +642,105 @@ impl VkRefcountTable {
     }
 }
 
+/// Used to be able to de/serialize our `VkRegistry` in the state machine
+#[derive(Serialize, Deserialize)]
+struct VkRegistryBigInts {
+    verification_keys: Vec<(WithHash<VerificationKey, BigInt>, redux::Timestamp)>,
+}
+impl From<VkRegistry> for VkRegistryBigInts {
+    fn from(value: VkRegistry) -> Self {
+        Self {
+            verification_keys: value
+                .verification_keys
+                .values()
+                .filter_map(|vk| {
+                    let expires_at = *value.expires_at.get(&vk.hash())?;
+                    let vk = WithHash {
+                        hash: vk.hash().into(),
+                        data: vk.vk().clone(),
+                    };
+                    Some((vk, expires_at))
+                })
+                .collect(),
+        }
+    }
+}
+impl From<VkRegistryBigInts> for VkRegistry {
+    fn from(value: VkRegistryBigInts) -> Self {
+        let mut registry = Self::default();
+        for (vk, expires_at) in value.verification_keys {
+            let hash: Fp = vk.hash.to_field().unwrap(); // We trust our serialized data
+            registry.register(VerificationKeyWire::with_hash(vk.data, hash), expires_at);
+        }
+        registry
+    }
+}
+
+/// Maximum number of the verification keys in the [`VkRegistry`].
+const VK_REGISTRY_MAX_SIZE: usize = 256;
+
+/// Verification keys registered by the user, before they are deployed.
+///
+/// Used as the fallback when the vk of the proved account update isn't in
+/// the ledger nor in the pool, so that the commands using the vk can be
+/// admitted together with the command deploying it. Such commands still
+/// fail when applied, if the account doesn't have the vk by then.
+///
+/// At most [`VK_REGISTRY_MAX_SIZE`] keys are kept, the ones expiring
+/// first are evicted to make room for the new ones.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(into = "VkRegistryBigInts")]
+#[serde(from = "VkRegistryBigInts")]
+struct VkRegistry {
+    /// Shared with the verification cache, so that the keys aren't copied
+    /// for every admitted diff.
+    verification_keys: Arc<HashMap<Fp, VerificationKeyWire>>,
+    expires_at: HashMap<Fp, redux::Timestamp>,
+}
+
+impl VkRegistry {
+    fn register(&mut self, vk: VerificationKeyWire, expires_at: redux::Timestamp) {
+        let hash = vk.hash();
+        let expires_at = match self.expires_at.get(&hash) {
+            Some(prev) => expires_at.max(*prev),
+            None => {
+                if self.expires_at.len() >= VK_REGISTRY_MAX_SIZE {
+                    self.evict_first_expiring();
+                }
+                Arc::make_mut(&mut self.verification_keys).insert(hash, vk);
+                expires_at
+            }
+        };
+        self.expires_at.insert(hash, expires_at);
+    }
+
+    fn evict_first_expiring(&mut self) {
+        let first_expiring = self
+            .expires_at
+            .iter()
+            .min_by_key(|(_, expires_at)| **expires_at)
+            .map(|(hash, _)| *hash);
+        if let Some(hash) = first_expiring {
+            self.expires_at.remove(&hash);
+            Arc::make_mut(&mut self.verification_keys).remove(&hash);
+        }
+    }
+
+    fn prune(&mut self, now: redux::Timestamp) {
+        let len = self.expires_at.len();
+        self.expires_at.retain(|_, expires_at| *expires_at > now);
+        if self.expires_at.len() != len {
+            let expires_at = &self.expires_at;
+            Arc::make_mut(&mut self.verification_keys)
+                .retain(|hash, _| expires_at.contains_key(hash));
+        }
+    }
+
+    fn vks(&self) -> Arc<HashMap<Fp, VerificationKeyWire>> {
+        Arc::clone(&self.verification_keys)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 enum Batch {
     Of(usize),
@@ -676,6 +897,11 @@ impl IndexedPool {
         self.all_by_hash.get(hash)
     }
 
+    /// All commands in the pool, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &ValidCommandWithHash> {
+        self.all_by_hash.values()
+    }
+
     fn check_expiry(
         &self,
         global_slot_since_genesis: Slot,
@@ -1523,6 +1749,15 @@ impl IndexedPool {
             .collect()
     }
 
+    /// Nonce the first command of the sender in the pool is applicable at,
+    /// and the nonce expected after its last command.
+    fn sender_nonce_range(&self, sender: &AccountId) -> Option<(Nonce, Nonce)> {
+        let (cmds, _) = self.all_by_sender.get(sender)?;
+        let first = cmds.front()?.data.forget_check().applicable_at_nonce();
+        let target = cmds.back()?.data.forget_check().expected_target_nonce();
+        Some((first, target))
+    }
+
     fn get_pending_amount_and_nonce(&self) -> HashMap<AccountId, (Option<Nonce>, Amount)> {
         // TODO(adonagy): clone too expensive here?
         self.all_by_sender
@@ -1583,6 +1818,19 @@ pub enum ApplyDecision {
     Reject,
 }
 
+/// Commands removed from the pool by [`TransactionPool::handle_transition_frontier_diff`].
+#[derive(Debug, Default)]
+pub struct BestTipDiffDropped {
+    /// Included in the blocks of the new best chain.
+    pub committed: Vec<v2::TransactionHash>,
+    /// No longer valid against the new best tip ledger.
+    pub invalidated: Vec<v2::TransactionHash>,
+    /// Didn't fit in the pool, after the commands of the abandoned
+    /// blocks were added back.
+    pub evicted: Vec<v2::TransactionHash>,
+    pub expired: Vec<v2::TransactionHash>,
+}
+
 const MAX_PER_15_SECONDS: usize = 10;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1596,6 +1844,10 @@ pub struct TransactionPool {
     batcher: (),
     best_tip_diff_relay: Option<()>,
     verification_key_table: VkRefcountTable,
+    #[serde(default)]
+    registered_vks: VkRegistry,
+    #[serde(default)]
+    fee_payer_admissions: FeePayerAdmissions,
 }
 
 impl TransactionPool {
@@ -1610,9 +1862,34 @@ impl TransactionPool {
             batcher: (),
             best_tip_diff_relay: None,
             verification_key_table: Default::default(),
+            registered_vks: Default::default(),
+            fee_payer_admissions: Default::default(),
         }
     }
 
+    /// Registers the verification key, to be used for the pool admission
+    /// of the commands proved against it, until `expires_at`.
+    pub fn register_vk(&mut self, vk: VerificationKeyWire, expires_at: redux::Timestamp) {
+        self.registered_vks.register(vk, expires_at);
+    }
+
+    pub fn prune_registered_vks(&mut self, now: redux::Timestamp) {
+        self.registered_vks.prune(now);
+    }
+
+    /// Removes the commands of the fee payers, which exceeded the
+    /// [`FeePayerRateLimits`], from the `diff` and counts the remaining
+    /// ones as admitted. Returns the removed commands.
+    pub fn rate_limit_fee_payers(
+        &mut self,
+        diff: &mut diff::Diff,
+        accounts: &BTreeMap<AccountId, Account>,
+        now: redux::Timestamp,
+    ) -> Vec<UserCommand> {
+        self.fee_payer_admissions
+            .admit(&self.config.fee_payer_limits, diff, accounts, now)
+    }
+
     pub fn size(&self) -> usize {
         self.pool.size()
     }
@@ -1621,10 +1898,22 @@ impl TransactionPool {
         self.pool.get_all_transactions()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &ValidCommandWithHash> {
+        self.pool.iter()
+    }
+
+    pub fn get(&self, hash: &v2::TransactionHash) -> Option<&ValidCommandWithHash> {
+        self.pool.get(hash)
+    }
+
     pub fn get_pending_amount_and_nonce(&self) -> HashMap<AccountId, (Option<Nonce>, Amount)> {
         self.pool.get_pending_amount_and_nonce()
     }
 
+    pub fn sender_nonce_range(&self, sender: &AccountId) -> Option<(Nonce, Nonce)> {
+        self.pool.sender_nonce_range(sender)
+    }
+
     pub fn transactions(&mut self, limit: usize) -> Vec<ValidCommandWithHash> {
         self.pool.transactions(limit)
     }
@@ -1637,23 +1926,63 @@ impl TransactionPool {
         self.pool.all_by_sender.keys().cloned().collect()
     }
 
+    /// Senders among the `touched` accounts, whose commands might have
+    /// become invalid.
+    pub fn get_accounts_to_revalidate_touched(
+        &self,
+        touched: &BTreeSet<AccountId>,
+    ) -> BTreeSet<AccountId> {
+        self.pool
+            .all_by_sender
+            .keys()
+            .filter(|sender| touched.contains(sender))
+            .cloned()
+            .collect()
+    }
+
     pub fn on_new_best_tip(
         &mut self,
         global_slot_since_genesis: Slot,
         accounts: &BTreeMap<AccountId, Account>,
     ) -> Result<Vec<ValidCommandWithHash>, CommandError> {
-        let dropped = self.pool.revalidate(
+        self.revalidate(
             global_slot_since_genesis,
             RevalidateKind::EntirePool,
-            |sender_id| {
+            accounts,
+        )
+    }
+
+    /// Revalidates only the commands of the `senders`, whose accounts were
+    /// touched by the blocks applied on top of the previous best tip.
+    pub fn on_accounts_touched(
+        &mut self,
+        global_slot_since_genesis: Slot,
+        senders: &BTreeSet<AccountId>,
+        accounts: &BTreeMap<AccountId, Account>,
+    ) -> Result<Vec<ValidCommandWithHash>, CommandError> {
+        self.revalidate(
+            global_slot_since_genesis,
+            RevalidateKind::Subset(senders),
+            accounts,
+        )
+    }
+
+    fn revalidate(
+        &mut self,
+        global_slot_since_genesis: Slot,
+        kind: RevalidateKind,
+        accounts: &BTreeMap<AccountId, Account>,
+    ) -> Result<Vec<ValidCommandWithHash>, CommandError> {
+        let dropped = self
+            .pool
+            .revalidate(global_slot_since_genesis, kind, |sender_id| {
                 Some(
                     accounts
                         .get(sender_id)
                         .cloned()
                         .unwrap_or_else(Account::empty),
                 )
-            },
-        )?;
+            })?;
 
         let dropped_locally_generated = dropped
             .iter()
@@ -1739,7 +2068,7 @@ impl TransactionPool {
         account_ids: &BTreeSet<AccountId>,
         accounts: &BTreeMap<AccountId, Account>,
         uncommited: &BTreeMap<AccountId, Account>,
-    ) -> Result<(), String> {
+    ) -> Result<BestTipDiffDropped, String> {
         let diff::BestTipDiff {
             new_commands,
             removed_commands,
@@ -1902,7 +2231,23 @@ impl TransactionPool {
             self.locally_generated_uncommitted.remove(cmd);
         }
 
-        Ok(())
+        let hashes = |cmds: &[&ValidCommandWithHash]| {
+            cmds.iter().map(|cmd| cmd.hash.clone()).collect::<Vec<_>>()
+        };
+        Ok(BestTipDiffDropped {
+            committed: hashes(&committed_commands),
+            invalidated: hashes(&dropped_commit_conflicts),
+            // Locally generated ones might have been added back.
+            evicted: dropped_backtrack
+                .iter()
+                .filter(|cmd| self.pool.get(&cmd.hash).is_none())
+                .map(|cmd| cmd.hash.clone())
+                .collect(),
+            expired: expired_commands
+                .iter()
+                .map(|cmd| cmd.hash.clone())
+                .collect(),
+        })
     }
 
     pub fn get_accounts_to_apply_diff(&self, diff: &diff::DiffVerified) -> BTreeSet<AccountId> {
@@ -2132,6 +2477,21 @@ impl TransactionPool {
             ));
         }
 
+        let limits_errors = diff
+            .list
+            .iter()
+            .filter_map(|cmd| match cmd {
+                UserCommand::SignedCommand(_) => None,
+                UserCommand::ZkAppCommand(cmd) => {
+                    self.config.zkapp_limits.check(&cmd.complexity()).err()
+                }
+            })
+            .map(TransactionError::ZkappLimits)
+            .collect_vec();
+        if !limits_errors.is_empty() {
+            return Err(TransactionPoolErrors::BatchedErrors(limits_errors));
+        }
+
         Ok(diff)
     }
 
@@ -2181,7 +2541,7 @@ impl TransactionPool {
                         accum
                     });
 
-            from_unapplied_sequence::Cache::new(merged)
+            from_unapplied_sequence::Cache::new(merged).with_fallback(self.registered_vks.vks())
         })
         .map_err(TransactionPoolErrors::LoadingVK)?;
 
@@ -2327,4 +2687,134 @@ mod tests {
 
         dbg!(merged);
     }
+
+    #[test]
+    fn test_vk_registry_expiry() {
+        let vk = VerificationKeyWire::dummy();
+        let hash = vk.hash();
+        let mut registry = VkRegistry::default();
+        registry.register(vk.clone(), redux::Timestamp::new(100));
+        // re-registering doesn't shorten the ttl.
+        registry.register(vk, redux::Timestamp::new(50));
+
+        registry.prune(redux::Timestamp::new(99));
+        assert!(registry.vks().contains_key(&hash));
+        let vks = registry.vks();
+        registry.prune(redux::Timestamp::new(100));
+        assert!(registry.vks().is_empty());
+        // the keys handed out before aren't affected.
+        assert!(vks.contains_key(&hash));
+    }
+
+    #[test]
+    fn test_vk_registry_eviction() {
+        let vk = |i: u64| {
+            let vk = VerificationKey::dummy();
+            VerificationKeyWire::with_hash((*vk).clone(), Fp::from(i))
+        };
+        let mut registry = VkRegistry::default();
+        let max = VK_REGISTRY_MAX_SIZE as u64;
+        // The key registered first expires last.
+        for i in 0..max {
+            registry.register(vk(i), redux::Timestamp::new(max.saturating_sub(i)));
+        }
+        assert_eq!(registry.vks().len(), VK_REGISTRY_MAX_SIZE);
+
+        // Re-registering a known key evicts nothing.
+        registry.register(vk(0), redux::Timestamp::new(1));
+        assert_eq!(registry.vks().len(), VK_REGISTRY_MAX_SIZE);
+
+        registry.register(vk(max), redux::Timestamp::new(max));
+        let vks = registry.vks();
+        assert_eq!(vks.len(), VK_REGISTRY_MAX_SIZE);
+        assert!(vks.contains_key(&Fp::from(max)));
+        assert!(!vks.contains_key(&Fp::from(max.saturating_sub(1))));
+        assert!(vks.contains_key(&Fp::from(0u64)));
+    }
+
+    #[test]
+    fn test_zkapp_complexity_limits() {
+        let complexity = ZkappCommandComplexity {
+            account_updates: 5,
+            proof_segments: 2,
+            event_elements: 10,
+            ..Default::default()
+        };
+        assert!(ZkappComplexityLimits::default().check(&complexity).is_ok());
+
+        let limits = ZkappComplexityLimits {
+            max_account_updates: Some(5),
+            max_proof_segments: Some(1),
+            max_event_elements: Some(9),
+        };
+        assert_eq!(
+            limits.check(&complexity),
+            Err("too many proof segments: 2 > 1; too many event elements: 10 > 9".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_fee_payer_rate_limits() {
+        use crate::scan_state::transaction_logic::{
+            signed_command::{Body, PaymentPayload, SignedCommand, SignedCommandPayload},
+            Memo,
+        };
+        use mina_signer::{Keypair, Signature};
+
+        let pub_key = || {
+            Keypair::rand(&mut rand::thread_rng())
+                .unwrap()
+                .public
+                .into_compressed()
+        };
+        let (spammer, rich, allowed) = (pub_key(), pub_key(), pub_key());
+        let payment = |fee_payer: &CompressedPubKey| {
+            let payload = SignedCommandPayload::create(
+                Fee::from_u64(1_000_000),
+                fee_payer.clone(),
+                Nonce::from_u32(0),
+                None,
+                Memo::dummy(),
+                Body::Payment(PaymentPayload {
+                    receiver_pk: fee_payer.clone(),
+                    amount: Amount::from_u64(1),
+                }),
+            );
+            UserCommand::SignedCommand(Box::new(SignedCommand {
+                payload,
+                signer: fee_payer.clone(),
+                signature: Signature::dummy(),
+            }))
+        };
+
+        let limits = FeePayerRateLimits {
+            max_commands: Some(2),
+            window: Duration::from_secs(60),
+            exempt_balance: Some(Balance::from_u64(1_000)),
+            allowlist: [allowed.clone()].into(),
+        };
+        let rich_id = AccountId::new(rich.clone(), TokenId::default());
+        let accounts = [(
+            rich_id.clone(),
+            Account::create_with(rich_id, Balance::from_u64(1_000)),
+        )]
+        .into();
+        let mut admissions = FeePayerAdmissions::default();
+        let mut admit = |fee_payers: &[&CompressedPubKey], secs: u64| {
+            let mut diff = diff::Diff {
+                list: fee_payers.iter().map(|pk| payment(pk)).collect(),
+            };
+            let now = redux::Timestamp::new(secs * 1_000_000_000);
+            let limited = admissions.admit(&limits, &mut diff, &accounts, now);
+            (diff.list.len(), limited.len())
+        };
+
+        assert_eq!(
+            admit(&[&spammer, &spammer, &spammer, &rich, &rich, &rich], 0),
+            (5, 1)
+        );
+        assert_eq!(admit(&[&allowed, &allowed, &allowed, &spammer], 59), (3, 1));
+        // new window
+        assert_eq!(admit(&[&spammer, &spammer, &spammer], 60), (2, 1));
+    }
 }
@@ -14,6 +14,8 @@ use crate::{
 
 use self::common::CheckResult;
 
+pub mod srs;
+
 #[derive(Debug, Clone)]
 pub struct Verifier;
 
@@ -34,12 +36,14 @@ pub static VERIFIER_INDEX: Lazy<Arc<VerifierIndex<Fq>>> = Lazy::new(|| {
 });
 
 /// Returns the SRS on the other curve (immutable version for verifiers)
+///
+/// The SRS is created once per process and shared between threads, see
+/// [`srs`] for how the verifier SRS is stored on disk.
 pub fn get_srs<F: FieldWitness>() -> Arc<SRS<F::OtherCurve>> {
     cache! {
         Arc<SRS<F::OtherCurve>>,
         {
-            let srs = SRS::<F::OtherCurve>::create(F::Scalar::SRS_DEPTH);
-            Arc::new(srs)
+            srs::shared_srs::<F>()
         }
     }
 }
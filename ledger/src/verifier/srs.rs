@@ -0,0 +1,285 @@
+//! Storage of the verifier SRS.
+//!
+//! Creating the SRS takes a while, so once created it is stored in the
+//! directory set with [`set_cache_dir`], and on the first use after the
+//! next startups it is decoded directly from the memory-mapped file, without
+//! reading it into a buffer first. The verifiers need the decoded points in
+//! memory, so only the file itself is mapped.
+//!
+//! The stored SRS is only used if its checksum matches, otherwise it is
+//! created again. Hashing the whole file is slow, so it is only done the
+//! first time the file is loaded, after which a stamp with the size and the
+//! modification time of the verified file is stored next to it. The file
+//! is hashed again only if it changes.
+
+use std::{
+    any::{Any, TypeId},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use mina_curves::pasta::Vesta;
+use mina_hasher::Fp;
+use once_cell::sync::OnceCell;
+use poly_commitment::srs::SRS;
+
+use crate::proofs::field::FieldWitness;
+
+/// Identifies the file format, bumped when it changes.
+const MAGIC: &[u8; 8] = b"omsrs-v1";
+const HEADER_LEN: usize = MAGIC.len() + 8 + 32;
+
+static CACHE_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Sets the directory, where the verifier SRS is stored. Has no effect
+/// after the SRS was loaded, returns `false` if the directory was
+/// already set.
+pub fn set_cache_dir(dir: PathBuf) -> bool {
+    CACHE_DIR.set(dir).is_ok()
+}
+
+fn verifier_srs_depth() -> usize {
+    <Fp as FieldWitness>::Scalar::SRS_DEPTH
+}
+
+fn cache_path(depth: usize) -> Option<PathBuf> {
+    CACHE_DIR
+        .get()
+        .map(|dir| dir.join(format!("verifier_srs_{depth}.bin")))
+}
+
+/// Path of the stamp recording that the file at `path` was verified.
+#[cfg(not(target_family = "wasm"))]
+fn stamp_path(path: &Path) -> PathBuf {
+    path.with_extension("verified")
+}
+
+/// Returns the SRS on the other curve, creating it only once per process.
+pub(super) fn shared_srs<F: FieldWitness>() -> Arc<SRS<F::OtherCurve>> {
+    static SHARED: Mutex<Vec<Box<dyn Any + Send + Sync>>> = Mutex::new(Vec::new());
+
+    // Keep the lock while creating, so that other threads wait for it
+    // instead of creating their own.
+    let mut shared = SHARED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(srs) = shared
+        .iter()
+        .find_map(|srs| srs.downcast_ref::<Arc<SRS<F::OtherCurve>>>())
+    {
+        return srs.clone();
+    }
+
+    let srs: Box<dyn Any> = if TypeId::of::<F::OtherCurve>() == TypeId::of::<Vesta>() {
+        Box::new(load_or_create_verifier_srs())
+    } else {
+        Box::new(SRS::<F::OtherCurve>::create(F::Scalar::SRS_DEPTH))
+    };
+    let srs = match srs.downcast::<SRS<F::OtherCurve>>() {
+        Ok(srs) => Arc::new(*srs),
+        Err(_) => unreachable!("curve type is checked above"),
+    };
+    shared.push(Box::new(srs.clone()));
+    srs
+}
+
+/// Loads the verifier SRS from the cache directory, or creates it and
+/// stores it there.
+#[cfg(not(target_family = "wasm"))]
+pub(super) fn load_or_create_verifier_srs() -> SRS<Vesta> {
+    use openmina_core::{info, log::system_time, warn};
+
+    let depth = verifier_srs_depth();
+    let Some(path) = cache_path(depth) else {
+        return SRS::create(depth);
+    };
+    match read(&path, depth) {
+        Ok(srs) => {
+            info!(system_time(); "Verifier SRS is loaded from {path:?}");
+            return srs;
+        }
+        Err(err) => warn!(system_time(); "Cannot load verifier SRS from {path:?}: {err}"),
+    }
+
+    let srs = SRS::create(depth);
+    match write(&path, depth, &srs) {
+        Ok(()) => info!(system_time(); "Stored verifier SRS to {path:?}"),
+        Err(err) => warn!(system_time(); "Cannot store verifier SRS to {path:?}: {err}"),
+    }
+    srs
+}
+
+#[cfg(target_family = "wasm")]
+pub(super) fn load_or_create_verifier_srs() -> SRS<Vesta> {
+    SRS::create(verifier_srs_depth())
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read(path: &Path, depth: usize) -> anyhow::Result<SRS<Vesta>> {
+    let file = std::fs::File::open(path)?;
+    // SAFETY: the file is only replaced by renaming a complete temporary
+    // file over it, never modified in place, so the mapped data doesn't
+    // change while it is being read.
+    let mmap = unsafe { memmap2::Mmap::map(&file)? };
+    #[cfg(unix)]
+    let _ = mmap.advise(memmap2::Advice::Sequential);
+
+    let (digest, payload) = parse_header(&mmap, depth)?;
+    let stamp = file_stamp(&file.metadata()?, digest);
+    let stamp_path = stamp_path(path);
+    if std::fs::read(&stamp_path).ok().as_deref() != Some(stamp.as_slice()) {
+        verify_checksum(digest, payload)?;
+        // If the stamp can't be stored, the file is just verified again.
+        let _ =
+            openmina_core::fs::write_atomic(&stamp_path, |w| std::io::Write::write_all(w, &stamp));
+    }
+    Ok(crate::proofs::caching::srs_from_bytes(payload)?)
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write(path: &Path, depth: usize, srs: &SRS<Vesta>) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let bytes = to_bytes(srs, depth);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // The stamp of the previous file must not match the new one.
+    let stamp_path = stamp_path(path);
+    if let Err(err) = std::fs::remove_file(&stamp_path) {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            return Err(err.into());
+        }
+    }
+    openmina_core::fs::write_atomic(path, |w| w.write_all(&bytes))?;
+
+    // Just written from the created SRS, no need to hash it on the next load.
+    if let Ok((digest, _)) = parse_header(&bytes, depth) {
+        let stamp = file_stamp(&std::fs::metadata(path)?, digest);
+        let _ = openmina_core::fs::write_atomic(&stamp_path, |w| w.write_all(&stamp));
+    }
+    Ok(())
+}
+
+/// Size and modification time of the file, with the checksum from its
+/// header.
+#[cfg(not(target_family = "wasm"))]
+fn file_stamp(metadata: &std::fs::Metadata, digest: &[u8]) -> Vec<u8> {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .unwrap_or_default();
+    let mut stamp = Vec::with_capacity(8 + 8 + 4 + 32);
+    stamp.extend_from_slice(&metadata.len().to_le_bytes());
+    stamp.extend_from_slice(&modified.as_secs().to_le_bytes());
+    stamp.extend_from_slice(&modified.subsec_nanos().to_le_bytes());
+    stamp.extend_from_slice(digest);
+    stamp
+}
+
+fn to_bytes(srs: &SRS<Vesta>, depth: usize) -> Vec<u8> {
+    use sha2::{Digest, Sha256};
+
+    let payload = crate::proofs::caching::srs_to_bytes(srs);
+    let mut bytes = Vec::with_capacity(HEADER_LEN.saturating_add(payload.len()));
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&(depth as u64).to_le_bytes());
+    bytes.extend_from_slice(&Sha256::digest(&payload));
+    bytes.extend_from_slice(&payload);
+    bytes
+}
+
+/// Checks the header and returns the stored checksum and the payload.
+fn parse_header(bytes: &[u8], depth: usize) -> anyhow::Result<(&[u8], &[u8])> {
+    if bytes.len() < HEADER_LEN {
+        anyhow::bail!("file is truncated");
+    }
+    let (magic, rest) = bytes.split_at(MAGIC.len());
+    let (stored_depth, rest) = rest.split_at(8);
+    let (digest, payload) = rest.split_at(32);
+    if magic != MAGIC {
+        anyhow::bail!("unknown file format");
+    }
+    let stored_depth = u64::from_le_bytes(stored_depth.try_into()?);
+    if stored_depth != depth as u64 {
+        anyhow::bail!("depth mismatch, expected: {depth}, found: {stored_depth}");
+    }
+    Ok((digest, payload))
+}
+
+fn verify_checksum(digest: &[u8], payload: &[u8]) -> anyhow::Result<()> {
+    use sha2::{Digest, Sha256};
+
+    if Sha256::digest(payload).as_slice() != digest {
+        anyhow::bail!("checksum mismatch");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+fn from_bytes(bytes: &[u8], depth: usize) -> anyhow::Result<SRS<Vesta>> {
+    let (digest, payload) = parse_header(bytes, depth)?;
+    verify_checksum(digest, payload)?;
+    Ok(crate::proofs::caching::srs_from_bytes(payload)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verifier_srs_roundtrip() {
+        let srs = SRS::<Vesta>::create(8);
+        let mut bytes = to_bytes(&srs, 8);
+
+        let decoded = from_bytes(&bytes, 8).unwrap();
+        assert_eq!(decoded.g, srs.g);
+        assert_eq!(decoded.h, srs.h);
+
+        assert!(from_bytes(&bytes, 16).is_err());
+        assert!(from_bytes(&bytes[..HEADER_LEN - 1], 8).is_err());
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(from_bytes(&bytes, 8).is_err());
+    }
+
+    #[test]
+    fn test_verifier_srs_invalid_payload() {
+        use sha2::{Digest, Sha256};
+
+        // Valid header and checksum, but the payload isn't an SRS.
+        let payload = [0xff; 16];
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&8u64.to_le_bytes());
+        bytes.extend_from_slice(&Sha256::digest(payload));
+        bytes.extend_from_slice(&payload);
+        assert!(from_bytes(&bytes, 8).is_err());
+    }
+
+    #[test]
+    fn test_verifier_srs_file() {
+        let dir = std::env::temp_dir().join(format!("openmina-srs-{}", std::process::id()));
+        let path = dir.join("verifier_srs_8.bin");
+        let srs = SRS::<Vesta>::create(8);
+
+        write(&path, 8, &srs).unwrap();
+        assert!(stamp_path(&path).exists());
+        let loaded = read(&path, 8).unwrap();
+        assert_eq!(loaded.g, srs.g);
+
+        // Without a matching stamp the file is hashed again.
+        std::fs::write(stamp_path(&path), b"stale").unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        openmina_core::fs::write_atomic(&path, |w| std::io::Write::write_all(w, &bytes)).unwrap();
+        assert!(read(&path, 8).is_err());
+
+        // Only the file and its stamp are left, no temporary files.
+        write(&path, 8, &srs).unwrap();
+        assert_eq!(read(&path, 8).unwrap().h, srs.h);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
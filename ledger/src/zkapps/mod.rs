@@ -1,5 +1,6 @@
 pub mod checks;
 pub mod intefaces;
 pub mod non_snark;
+pub mod preconditions_report;
 pub mod snark;
 pub mod zkapp_logic;
@@ -0,0 +1,436 @@
+//! Evaluation of zkapp preconditions outside of the transaction logic,
+//! reporting which of the clauses hold, along with the actual values.
+//!
+//! Useful for wallets, to warn the user before submitting a command that
+//! would fail, e.g. because its `valid_while` window already closed.
+//!
+//! Only clauses that are checked (not `Ignore`) are reported. Account
+//! preconditions are evaluated against the account as it is in the
+//! ledger, changes made by the preceding account updates of the same
+//! command (e.g. nonce increments) aren't taken into account.
+
+use mina_hasher::Fp;
+use mina_p2p_messages::v2;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    scan_state::{
+        currency::{Amount, Balance, Length, Nonce, Slot},
+        transaction_logic::{
+            protocol_state::{self, ProtocolStateView},
+            zkapp_command::{
+                AccountPreconditions, ClosedInterval, EpochData, EpochLedger, Numeric, OrIgnore,
+                Preconditions, ZkAppCommand, ZkAppPreconditions,
+            },
+        },
+    },
+    Account, AccountId, FpExt,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PreconditionClause {
+    /// Path of the clause, e.g. `network.blockchain_length` or `account.nonce`.
+    pub name: String,
+    pub holds: bool,
+    pub expected: String,
+    pub actual: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PreconditionsReport {
+    pub clauses: Vec<PreconditionClause>,
+}
+
+impl PreconditionsReport {
+    pub fn holds(&self) -> bool {
+        self.clauses.iter().all(|clause| clause.holds)
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &PreconditionClause> {
+        self.clauses.iter().filter(|clause| !clause.holds)
+    }
+
+    fn equal<T: PartialEq>(
+        &mut self,
+        name: &str,
+        precondition: &OrIgnore<T>,
+        actual: &T,
+        show: impl Fn(&T) -> String,
+    ) {
+        if let OrIgnore::Check(expected) = precondition {
+            self.clauses.push(PreconditionClause {
+                name: name.to_owned(),
+                holds: expected == actual,
+                expected: show(expected),
+                actual: show(actual),
+            });
+        }
+    }
+
+    fn interval<T: PartialOrd>(
+        &mut self,
+        name: &str,
+        precondition: &Numeric<T>,
+        actual: &T,
+        show: impl Fn(&T) -> String,
+    ) {
+        if let OrIgnore::Check(ClosedInterval { lower, upper }) = precondition {
+            self.clauses.push(PreconditionClause {
+                name: name.to_owned(),
+                holds: lower <= actual && actual <= upper,
+                expected: format!("[{}, {}]", show(lower), show(upper)),
+                actual: show(actual),
+            });
+        }
+    }
+
+    fn epoch_data(
+        &mut self,
+        prefix: &str,
+        precondition: &EpochData,
+        view: &protocol_state::EpochData<Fp>,
+    ) {
+        let EpochData {
+            ledger:
+                EpochLedger {
+                    hash,
+                    total_currency,
+                },
+            // Not checked by the transaction logic either.
+            seed: _,
+            start_checkpoint,
+            lock_checkpoint,
+            epoch_length,
+        } = precondition;
+
+        let name = |field: &str| format!("network.{prefix}.{field}");
+        self.equal(
+            &name("ledger.hash"),
+            hash,
+            &view.ledger.hash,
+            show_ledger_hash,
+        );
+        self.interval(
+            &name("ledger.total_currency"),
+            total_currency,
+            &view.ledger.total_currency,
+            show_amount,
+        );
+        self.equal(
+            &name("start_checkpoint"),
+            start_checkpoint,
+            &view.start_checkpoint,
+            show_state_hash,
+        );
+        self.equal(
+            &name("lock_checkpoint"),
+            lock_checkpoint,
+            &view.lock_checkpoint,
+            show_state_hash,
+        );
+        self.interval(
+            &name("epoch_length"),
+            epoch_length,
+            &view.epoch_length,
+            show_length,
+        );
+    }
+
+    /// Evaluates the network preconditions against the view of the
+    /// protocol state the command would be applied on.
+    pub fn network(&mut self, precondition: &ZkAppPreconditions, view: &ProtocolStateView) {
+        let ZkAppPreconditions {
+            snarked_ledger_hash,
+            blockchain_length,
+            min_window_density,
+            total_currency,
+            global_slot_since_genesis,
+            staking_epoch_data,
+            next_epoch_data,
+        } = precondition;
+
+        self.equal(
+            "network.snarked_ledger_hash",
+            snarked_ledger_hash,
+            &view.snarked_ledger_hash,
+            show_ledger_hash,
+        );
+        self.interval(
+            "network.blockchain_length",
+            blockchain_length,
+            &view.blockchain_length,
+            show_length,
+        );
+        self.interval(
+            "network.min_window_density",
+            min_window_density,
+            &view.min_window_density,
+            show_length,
+        );
+        self.interval(
+            "network.total_currency",
+            total_currency,
+            &view.total_currency,
+            show_amount,
+        );
+        self.interval(
+            "network.global_slot_since_genesis",
+            global_slot_since_genesis,
+            &view.global_slot_since_genesis,
+            show_slot,
+        );
+        self.epoch_data(
+            "staking_epoch_data",
+            staking_epoch_data,
+            &view.staking_epoch_data,
+        );
+        self.epoch_data("next_epoch_data", next_epoch_data, &view.next_epoch_data);
+    }
+
+    /// Evaluates the account preconditions against the `account`, which
+    /// is `is_new` if it doesn't exist in the ledger yet.
+    pub fn account(
+        &mut self,
+        precondition: &AccountPreconditions,
+        account: &Account,
+        is_new: bool,
+    ) {
+        let AccountPreconditions(precondition) = precondition;
+        let zkapp = account.zkapp_or_empty();
+
+        self.interval(
+            "account.balance",
+            &precondition.balance,
+            &account.balance,
+            show_balance,
+        );
+        self.interval(
+            "account.nonce",
+            &precondition.nonce,
+            &account.nonce,
+            show_nonce,
+        );
+        self.equal(
+            "account.receipt_chain_hash",
+            &precondition.receipt_chain_hash,
+            &account.receipt_chain_hash.0,
+            Fp::to_decimal,
+        );
+        self.equal(
+            "account.delegate",
+            &precondition.delegate,
+            &*account.delegate_or_empty(),
+            |pk| pk.into_address(),
+        );
+        for (i, (precondition, actual)) in precondition
+            .state
+            .iter()
+            .zip(zkapp.app_state.iter())
+            .enumerate()
+        {
+            self.equal(
+                &format!("account.state.{i}"),
+                precondition,
+                actual,
+                Fp::to_decimal,
+            );
+        }
+        // Holds if any of the recent action states matches.
+        if let OrIgnore::Check(expected) = &precondition.action_state {
+            self.clauses.push(PreconditionClause {
+                name: "account.action_state".to_owned(),
+                holds: zkapp.action_state.contains(expected),
+                expected: expected.to_decimal(),
+                actual: format!(
+                    "[{}]",
+                    zkapp
+                        .action_state
+                        .iter()
+                        .map(Fp::to_decimal)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            });
+        }
+        self.equal(
+            "account.proved_state",
+            &precondition.proved_state,
+            &zkapp.proved_state,
+            bool::to_string,
+        );
+        self.equal(
+            "account.is_new",
+            &precondition.is_new,
+            &is_new,
+            bool::to_string,
+        );
+    }
+
+    /// Evaluates the `valid_while` precondition against the global slot
+    /// of the block the command would be included in.
+    pub fn valid_while(&mut self, precondition: &Numeric<Slot>, global_slot: Slot) {
+        self.interval("valid_while", precondition, &global_slot, show_slot);
+    }
+
+    /// Evaluates all the preconditions of the account update.
+    pub fn evaluate(
+        preconditions: &Preconditions,
+        view: &ProtocolStateView,
+        global_slot: Slot,
+        account: &Account,
+        is_new: bool,
+    ) -> Self {
+        let Preconditions {
+            network,
+            account: account_precondition,
+            valid_while,
+        } = preconditions;
+
+        let mut report = Self::default();
+        report.network(network, view);
+        report.account(account_precondition, account, is_new);
+        report.valid_while(valid_while, global_slot);
+        report
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AccountUpdatePreconditionsReport {
+    pub account_id: AccountId,
+    #[serde(flatten)]
+    pub report: PreconditionsReport,
+}
+
+/// Evaluates the preconditions of all the account updates of the command,
+/// fee payer first. Accounts missing from `accounts` are considered new.
+pub fn evaluate_zkapp_command(
+    command: &ZkAppCommand,
+    view: &ProtocolStateView,
+    global_slot: Slot,
+    accounts: &[Account],
+) -> Vec<AccountUpdatePreconditionsReport> {
+    command
+        .all_account_updates_list()
+        .iter()
+        .map(|account_update| {
+            let account_id = account_update.account_id();
+            let existing = accounts.iter().find(|account| account.id() == account_id);
+            let report = match existing {
+                Some(account) => PreconditionsReport::evaluate(
+                    &account_update.body.preconditions,
+                    view,
+                    global_slot,
+                    account,
+                    false,
+                ),
+                None => PreconditionsReport::evaluate(
+                    &account_update.body.preconditions,
+                    view,
+                    global_slot,
+                    &Account::initialize(&account_id),
+                    true,
+                ),
+            };
+            AccountUpdatePreconditionsReport { account_id, report }
+        })
+        .collect()
+}
+
+fn show_ledger_hash(hash: &Fp) -> String {
+    v2::LedgerHash::from_fp(*hash).to_string()
+}
+
+fn show_state_hash(hash: &Fp) -> String {
+    v2::StateHash::from_fp(*hash).to_string()
+}
+
+fn show_amount(value: &Amount) -> String {
+    value.as_u64().to_string()
+}
+
+fn show_balance(value: &Balance) -> String {
+    value.as_u64().to_string()
+}
+
+fn show_length(value: &Length) -> String {
+    value.as_u32().to_string()
+}
+
+fn show_nonce(value: &Nonce) -> String {
+    value.as_u32().to_string()
+}
+
+fn show_slot(value: &Slot) -> String {
+    value.as_u32().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_signer::Keypair;
+
+    use super::*;
+    use crate::{
+        dummy::for_tests::dummy_protocol_state,
+        scan_state::transaction_logic::protocol_state::protocol_state_view, TokenId,
+    };
+
+    #[test]
+    fn reports_checked_clauses() {
+        let view = protocol_state_view(&dummy_protocol_state()).unwrap();
+        let pk = Keypair::rand(&mut rand::thread_rng())
+            .unwrap()
+            .public
+            .into_compressed();
+        let mut account = Account::initialize(&AccountId::new(pk, TokenId::default()));
+        account.nonce = Nonce::from_u32(5);
+
+        let mut preconditions = Preconditions {
+            network: ZkAppPreconditions::accept(),
+            account: AccountPreconditions::with_nonce(Nonce::from_u32(5)),
+            valid_while: OrIgnore::Check(ClosedInterval {
+                lower: Slot::from_u32(10),
+                upper: Slot::from_u32(20),
+            }),
+        };
+        preconditions.network.blockchain_length = OrIgnore::Check(ClosedInterval {
+            lower: view.blockchain_length,
+            upper: view.blockchain_length,
+        });
+
+        let report = PreconditionsReport::evaluate(
+            &preconditions,
+            &view,
+            Slot::from_u32(15),
+            &account,
+            false,
+        );
+        let names = report
+            .clauses
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["network.blockchain_length", "account.nonce", "valid_while"]
+        );
+        assert!(report.holds());
+
+        // window closed and nonce already used.
+        account.nonce = Nonce::from_u32(6);
+        let report = PreconditionsReport::evaluate(
+            &preconditions,
+            &view,
+            Slot::from_u32(21),
+            &account,
+            false,
+        );
+        let failed = report.failed().cloned().collect::<Vec<_>>();
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[0].name, "account.nonce");
+        assert_eq!(failed[0].expected, "[5, 5]");
+        assert_eq!(failed[0].actual, "6");
+        assert_eq!(failed[1].name, "valid_while");
+        assert_eq!(failed[1].expected, "[10, 20]");
+        assert_eq!(failed[1].actual, "21");
+    }
+}
@@ -864,6 +864,24 @@ impl PrecomputedBlock {
     }
 }
 
+/// Block as sent over the network, without the archive data.
+impl From<PrecomputedBlock> for MinaBlockBlockStableV2 {
+    fn from(value: PrecomputedBlock) -> Self {
+        Self {
+            header: MinaBlockHeaderStableV2 {
+                protocol_state: value.protocol_state,
+                protocol_state_proof: value.protocol_state_proof.0.into(),
+                delta_block_chain_proof: value.delta_transition_chain_proof,
+                current_protocol_version: value.protocol_version,
+                proposed_protocol_version_opt: value.proposed_protocol_version,
+            },
+            body: StagedLedgerDiffBodyStableV1 {
+                staged_ledger_diff: value.staged_ledger_diff,
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fmt::Debug;
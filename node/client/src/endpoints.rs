@@ -0,0 +1,288 @@
+use mina_p2p_messages::v2::{
+    MinaBaseZkappCommandTStableV1WireStableV1, StateHash, TransactionHash,
+};
+use node::account::AccountPublicKey;
+use node::block_producer::{BlockTemplate, BlockTemplateExternalDiff};
+use node::ledger::read::{LedgerSessionId, LedgerSessionResponse};
+use node::p2p::feature_flags::P2pFeatureFlags;
+use node::rpc::*;
+use node::stats::block_propagation::{BlockPropagationReport, BlockPropagationTimeline};
+use node::stats::profiler::ProfilerStatus;
+use node::transaction_pool::payouts::{PayoutBatch, PayoutBatchId};
+
+use crate::{
+    path_segment, Client, ClientError, HttpMethod, QueryParam, RpcEndpoint, RpcQueryParam,
+    TraceSchema,
+};
+
+macro_rules! opt_type_name {
+    () => {
+        None
+    };
+    ($ty:ty) => {
+        Some(stringify!($ty))
+    };
+}
+
+macro_rules! opt_schema {
+    () => {
+        None
+    };
+    ($ty:ty) => {
+        Some(crate::schema::trace::<$ty> as TraceSchema)
+    };
+}
+
+/// Generates [`ENDPOINTS`] and the [`Client`] methods.
+///
+/// Path parameters are the method arguments, which are percent-encoded
+/// into the path, query parameters are the arguments after them, typed
+/// as `Option` unless the node requires them.
+macro_rules! rpc_endpoints {
+    ($(
+        $(#[doc = $doc:literal])*
+        $method:ident $path:literal => fn $name:ident($($param:ident: $param_ty:ty),*)
+        $(query($($query:ident: $query_ty:ty),*))?
+        $(body($body:ty))?
+        -> $resp:ty;
+    )*) => {
+        /// HTTP endpoints covered by the [`Client`].
+        pub const ENDPOINTS: &[RpcEndpoint] = &[$(
+            RpcEndpoint {
+                name: stringify!($name),
+                doc: concat!($($doc, "\n"),*),
+                method: HttpMethod::$method,
+                path: $path,
+                path_params: &[$(stringify!($param)),*],
+                query_params: &[$($(RpcQueryParam {
+                    name: stringify!($query),
+                    required: <$query_ty as QueryParam>::REQUIRED,
+                }),*)?],
+                body: opt_type_name!($($body)?),
+                body_schema: opt_schema!($($body)?),
+                response: stringify!($resp),
+                response_schema: crate::schema::trace::<$resp>,
+            },
+        )*];
+
+        impl Client {
+            $(
+                $(#[doc = $doc])*
+                pub async fn $name(
+                    &self,
+                    $($param: $param_ty,)*
+                    $($($query: $query_ty,)*)?
+                    $(body: &$body,)?
+                ) -> Result<$resp, ClientError> {
+                    #[allow(unused_mut)]
+                    let mut query: Vec<(&str, String)> = Vec::new();
+                    $($(
+                        if let Some(value) = $query.query_value() {
+                            query.push((stringify!($query), value));
+                        }
+                    )*)?
+                    $(let $param = path_segment($param);)*
+                    let req = self
+                        .request(HttpMethod::$method, &format!($path))
+                        .query(&query);
+                    $(let req = req.json::<$body>(body);)?
+                    self.send(req).await
+                }
+            )*
+        }
+    };
+}
+
+// Endpoints whose response types don't implement `Deserialize` yet
+// (e.g. `/status`, `/snarker/workers`) are not covered.
+rpc_endpoints! {
+    /// Connected peers.
+    Get "/state/peers" => fn peers() -> Option<RpcPeersGetResponse>;
+    /// Digest of the whole node state, for comparing the nodes which
+    /// should have applied the same actions.
+    Get "/state/digest" => fn state_digest() -> RpcStateDigestGetResponse;
+    /// P2p diagnostic bundle for support requests, with the `errors`
+    /// latest connection errors.
+    Get "/p2p/diagnostics" => fn p2p_diagnostics() query(errors: Option<usize>)
+        -> RpcP2pDiagnosticsGetResponse;
+    /// Progress of the messages exchanged with the peers.
+    Get "/state/message-progress" => fn message_progress()
+        -> Option<RpcMessageProgressResponse>;
+    /// Action stats since the start, for the latest block (`id=latest`)
+    /// or for the block with the given id.
+    Get "/stats/actions" => fn action_stats() query(id: Option<String>)
+        -> RpcActionStatsGetResponse;
+    /// Sync stats, limited to the `limit` latest snapshots.
+    Get "/stats/sync" => fn sync_stats() query(limit: Option<usize>) -> RpcSyncStatsGetResponse;
+    /// Peers queried during the sync, ordered by their score, best first.
+    Get "/stats/sync/peers" => fn sync_peer_scores() -> Option<RpcSyncPeerScoresGetResponse>;
+    /// Block producer stats for the current epoch.
+    Get "/stats/block_producer" => fn block_producer_stats()
+        -> RpcBlockProducerStatsGetResponse;
+    /// Won, produced, canonical and missed slots of the block producer
+    /// in the `epoch`, current epoch by default.
+    Get "/stats/block_producer/report" => fn block_producer_epoch_report() query(epoch: Option<u32>)
+        -> RpcBlockProducerEpochReportGetResponse;
+    /// Failure reasons of the zkApp commands in the best chain blocks, per
+    /// epoch, starting from `from_epoch`.
+    Get "/stats/zkapp-failures" => fn zkapp_failure_stats() query(from_epoch: Option<u32>)
+        -> RpcZkappFailureStatsGetResponse;
+    /// Durations of the block and snark work proof verifications, and the
+    /// wasm features the verifier was built with.
+    Get "/stats/snark-verify" => fn snark_verify_stats() -> RpcSnarkVerifyStatsGetResponse;
+    /// Time series of block application times, action latencies and
+    /// peer counts within `from..=to` (unix time in nanoseconds).
+    Get "/stats/history" => fn stats_history() query(from: Option<u64>, to: Option<u64>)
+        -> RpcStatsHistoryGetResponse;
+    /// When the node produced, first saw and adopted as best tip each of
+    /// the latest blocks, starting from `from_height`.
+    Get "/stats/block-propagation" => fn block_propagation() query(from_height: Option<u32>)
+        -> Option<Vec<BlockPropagationTimeline>>;
+    /// Propagation delays of the latest blocks between this node and its
+    /// sibling nodes.
+    Get "/stats/block-propagation/report" => fn block_propagation_report()
+        query(from_height: Option<u32>) -> BlockPropagationReport;
+    /// Starts the sampling profiler.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/stats/profiler/start" => fn profiler_start() query(frequency_hz: Option<u32>)
+        -> ProfilerStatus;
+    /// Stops the sampling profiler.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/stats/profiler/stop" => fn profiler_stop() -> ProfilerStatus;
+    /// Scan state summary for the block with the given height or hash.
+    /// Trees are truncated to the response limits, `continuation` from a
+    /// truncated response requests the rest.
+    Get "/scan-state/summary/{block}" => fn scan_state_summary(block: &str)
+        query(continuation: Option<RpcContinuationToken>) -> RpcScanStateSummary;
+    /// Snarker configuration, if the node runs as a snarker.
+    Get "/snarker/config" => fn snarker_config() -> RpcSnarkerConfigGetResponse;
+    /// Transactions in the transaction pool.
+    Get "/transaction-pool" => fn transaction_pool() -> RpcTransactionPoolResponse;
+    /// Statistics of the zkApp commands in the transaction pool.
+    Get "/transaction-pool/zkapp-stats" => fn transaction_pool_zkapp_stats()
+        -> RpcTransactionPoolZkappStats;
+    /// Injects payments into the transaction pool.
+    Post "/send-payment" => fn send_payment() body(Vec<RpcInjectPayment>)
+        -> RpcTransactionInjectResponse;
+    /// User commands included in the best chain.
+    Get "/best-chain-user-commands" => fn best_chain_user_commands()
+        -> RpcTransitionFrontierUserCommandsResponse;
+    /// Chain id, protocol versions, capabilities and build info of the node.
+    Get "/node-info" => fn node_info() -> RpcNodeInfoGetResponse;
+    /// Codes and messages of the transaction failures.
+    Get "/transaction-failures" => fn transaction_failures()
+        -> RpcTransactionFailureCodesGetResponse;
+    /// Finality estimate for the blocks of the best chain.
+    Get "/best-chain/finality" => fn finality_estimate() -> RpcFinalityEstimateGetResponse;
+    /// Merkle proof of the transaction inclusion in the block.
+    Get "/transaction-inclusion-proof/{block_hash}/{tx_hash}" => fn transaction_inclusion_proof(
+        block_hash: &StateHash,
+        tx_hash: &TransactionHash
+    ) -> RpcTransactionInclusionProof;
+    /// Accounts in the ledger as of the block with the given height or hash.
+    Get "/accounts/at-block/{block}" => fn accounts_at_block(block: &str)
+        query(public_key: Option<AccountPublicKey>) -> RpcLedgerAccountsAtBlock;
+    /// Nonces to use for the new commands of the accounts, taking into
+    /// account their commands in the transaction pool. `public_keys` is
+    /// a comma separated list.
+    Get "/accounts/next-nonce" => fn account_next_nonces()
+        query(public_keys: String) -> RpcAccountNextNonces;
+    /// Coinbases and fee transfers of the block with the given height or
+    /// hash.
+    Get "/internal-commands/block/{block}" => fn block_internal_commands(block: &str)
+        -> Vec<RpcInternalCommand>;
+    /// Coinbases and fee transfers received by the account in the best chain.
+    Get "/internal-commands/account/{public_key}" => fn account_internal_commands(
+        public_key: &AccountPublicKey
+    ) -> Vec<RpcInternalCommand>;
+    /// Accounts count, scan state job counts, pending coinbase stacks and
+    /// total currency of the best tip.
+    Get "/ledger/status" => fn ledger_status() -> RpcLedgerStatusExtended;
+    /// Opens a session pinning the best tip ledger, for consistent queries.
+    Post "/ledger/sessions" => fn ledger_session_open() -> LedgerSessionResponse;
+    /// Accounts in the ledger pinned by the session.
+    Get "/ledger/sessions/{session_id}/accounts" => fn ledger_session_accounts(
+        session_id: &LedgerSessionId
+    ) query(public_key: Option<AccountPublicKey>) -> LedgerSessionResponse;
+    /// Closes the session, unpinning its ledger.
+    Post "/ledger/sessions/{session_id}/close" => fn ledger_session_close(
+        session_id: &LedgerSessionId
+    ) -> LedgerSessionResponse;
+    /// Maintenance mode status.
+    Get "/maintenance" => fn maintenance_status() -> RpcMaintenanceModeResponse;
+    /// Enters the maintenance mode.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/maintenance/enter" => fn maintenance_enter() -> RpcMaintenanceModeResponse;
+    /// Leaves the maintenance mode.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/maintenance/leave" => fn maintenance_leave() -> RpcMaintenanceModeResponse;
+    /// P2p feature flags.
+    Get "/feature-flags" => fn feature_flags_get() -> P2pFeatureFlags;
+    /// Replaces the p2p feature flags, affects the new connections.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/feature-flags" => fn feature_flags_set() body(P2pFeatureFlags) -> P2pFeatureFlags;
+    /// Verified checkpoints of the frontier root.
+    Get "/checkpoints" => fn checkpoints() -> RpcCheckpointsGetResponse;
+    /// Snapshots of the transaction pool captured so far.
+    Get "/transaction-pool/snapshots" => fn transaction_pool_snapshots()
+        -> RpcTransactionPoolSnapshotResult;
+    /// Captures the current contents of the transaction pool.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/transaction-pool/snapshots" => fn transaction_pool_snapshot_capture()
+        -> RpcTransactionPoolSnapshotResult;
+    /// Commands added, removed (with the reason) and reordered between the
+    /// snapshots `from` and `to`, or the current pool if `to` is omitted.
+    Get "/transaction-pool/snapshots/diff" => fn transaction_pool_snapshot_diff()
+        query(from: u64, to: Option<u64>) -> RpcTransactionPoolSnapshotResult;
+    /// Registers the zkApp verification key for the pool admission,
+    /// before it is deployed.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/zkapp/vk/register" => fn zkapp_vk_register() body(RpcZkappVkRegisterRequest)
+        -> RpcZkappVkRegisterResponse;
+    /// Which preconditions of the zkApp command currently hold.
+    Post "/zkapp/preconditions" => fn zkapp_preconditions_evaluate()
+        body(MinaBaseZkappCommandTStableV1WireStableV1) -> RpcZkappPreconditionsEvaluateResponse;
+    /// Submits the payout batch, to be paid from the payout key's account.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/payouts/batch" => fn payouts_batch_submit() body(RpcPayoutsBatchSubmitRequest)
+        -> PayoutBatchId;
+    /// Status of each payment of the payout batch.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Get "/payouts/batch/{batch_id}" => fn payouts_batch_get(batch_id: &PayoutBatchId)
+        -> PayoutBatch;
+    /// Injects the zkApp command with the fee paid by the node's fee
+    /// sponsor account. Requires the dapp's api key as the bearer token.
+    Post "/zkapp/sponsor" => fn zkapp_fee_sponsor()
+        body(MinaBaseZkappCommandTStableV1WireStableV1) -> RpcZkappFeeSponsorResponse;
+    /// Checks the claimed vrf output of the producer against the threshold
+    /// of its delegators' stake in the slot's staking ledger.
+    Post "/vrf/verify" => fn vrf_verify() body(RpcVrfVerifyQuery) -> RpcVrfVerification;
+    /// Running and the last consistency audit of the best tip ledger.
+    Get "/ledger/audit" => fn ledger_audit_status() -> RpcLedgerAuditStatus;
+    /// Starts the consistency audit of the best tip ledger in the background.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/ledger/audit/start" => fn ledger_audit_start() -> RpcLedgerAuditStatus;
+    /// Template of the block for the next won slot.
+    Get "/block-producer/template" => fn block_template() -> BlockTemplate;
+    /// Makes the node use the staged ledger diff for the won slot's block.
+    /// Requires the admin token as the bearer token, unless on loopback.
+    Post "/block-producer/template/submit" => fn block_template_submit()
+        body(RpcBlockTemplateSubmit) -> BlockTemplateExternalDiff;
+    /// Public keys watched for account changes.
+    Get "/subscriptions/accounts" => fn account_subscriptions()
+        -> RpcAccountSubscriptionsResponse;
+    /// Starts watching the account for changes.
+    Post "/subscriptions/accounts/add/{public_key}" => fn account_subscription_add(
+        public_key: &AccountPublicKey
+    ) -> RpcAccountSubscriptionsResponse;
+    /// Stops watching the account for changes.
+    Post "/subscriptions/accounts/remove/{public_key}" => fn account_subscription_remove(
+        public_key: &AccountPublicKey
+    ) -> RpcAccountSubscriptionsResponse;
+    /// Kademlia routing table.
+    Get "/discovery/routing_table" => fn discovery_routing_table()
+        -> RpcDiscoveryRoutingTableResponse;
+    /// Kademlia bootstrap stats.
+    Get "/discovery/bootstrap_stats" => fn discovery_bootstrap_stats()
+        -> RpcDiscoveryBoostrapStatsResponse;
+}
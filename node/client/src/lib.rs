@@ -0,0 +1,183 @@
+//! Typed client for the node's HTTP RPC.
+//!
+//! Endpoints are described once in [`endpoints`], which generates both
+//! the [`Client`] methods and the [`ENDPOINTS`] table, from which the
+//! OpenAPI document is built. Request and response types are the ones
+//! from [`node::rpc`], so the client can't get out of sync with the node.
+//! The node's tests check that every endpoint is routed by its server.
+
+mod endpoints;
+pub use endpoints::ENDPOINTS;
+
+mod openapi;
+pub use openapi::openapi;
+
+mod schema;
+
+use std::fmt::Display;
+
+use reqwest::RequestBuilder;
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+/// Description of a single HTTP endpoint of the node.
+#[derive(Debug, Clone, Copy)]
+pub struct RpcEndpoint {
+    /// Name of the [`Client`] method.
+    pub name: &'static str,
+    pub doc: &'static str,
+    pub method: HttpMethod,
+    /// Path with parameters in braces, e.g. `/scan-state/summary/{block}`.
+    pub path: &'static str,
+    pub path_params: &'static [&'static str],
+    pub query_params: &'static [RpcQueryParam],
+    /// Rust type of the JSON request body, if any.
+    pub body: Option<&'static str>,
+    pub body_schema: Option<TraceSchema>,
+    /// Rust type of the JSON response.
+    pub response: &'static str,
+    pub response_schema: TraceSchema,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RpcQueryParam {
+    pub name: &'static str,
+    pub required: bool,
+}
+
+/// Type of the query parameter argument of the [`Client`] method.
+pub trait QueryParam {
+    /// Whether the node rejects the requests without the parameter.
+    const REQUIRED: bool;
+
+    fn query_value(&self) -> Option<String>;
+}
+
+impl<T: Display> QueryParam for Option<T> {
+    const REQUIRED: bool = false;
+
+    fn query_value(&self) -> Option<String> {
+        self.as_ref().map(T::to_string)
+    }
+}
+
+macro_rules! required_query_param {
+    ($($ty:ty),*) => {$(
+        impl QueryParam for $ty {
+            const REQUIRED: bool = true;
+
+            fn query_value(&self) -> Option<String> {
+                Some(self.to_string())
+            }
+        }
+    )*};
+}
+
+required_query_param!(String, u64);
+
+/// Traces the JSON schema of the type from its `Deserialize`
+/// implementation, `None` if it can't be traced.
+pub type TraceSchema = fn() -> Option<serde_json::Value>;
+
+/// Percent-encodes the path parameter, so that it can't change the path,
+/// e.g. with a `/` or `?` in it.
+fn path_segment(value: impl Display) -> String {
+    let mut segment = String::new();
+    for byte in value.to_string().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                segment.push(char::from(byte))
+            }
+            byte => segment.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    segment
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("node responded with status {status}: {body}")]
+    Status { status: u16, body: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    base_url: String,
+    http: reqwest::Client,
+    bearer_token: Option<String>,
+}
+
+impl Client {
+    /// `base_url` is the address of the node's HTTP server, e.g.
+    /// `http://127.0.0.1:3000`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        let mut base_url = base_url.into();
+        while base_url.ends_with('/') {
+            base_url.pop();
+        }
+        Self {
+            base_url,
+            http,
+            bearer_token: None,
+        }
+    }
+
+    /// Token sent in the `Authorization` header, the api key of the dapp
+    /// for the fee sponsor, or the admin token for the operator endpoints.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn request(&self, method: HttpMethod, path: &str) -> RequestBuilder {
+        let url = format!("{}{path}", self.base_url);
+        let req = match method {
+            HttpMethod::Get => self.http.get(url),
+            HttpMethod::Post => self.http.post(url),
+        };
+        match &self.bearer_token {
+            Some(token) => req.bearer_auth(token),
+            None => req,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, req: RequestBuilder) -> Result<T, ClientError> {
+        let resp = req.send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(ClientError::Status {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        Ok(resp.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_segment_is_encoded() {
+        assert_eq!(path_segment("3NKa-b_c.d~9"), "3NKa-b_c.d~9");
+        assert_eq!(path_segment("../ledger?x=1"), "..%2Fledger%3Fx%3D1");
+        assert_eq!(path_segment("a b/ü"), "a%20b%2F%C3%BC");
+    }
+}
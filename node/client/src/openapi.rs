@@ -0,0 +1,146 @@
+use serde_json::{json, Map, Value};
+
+use crate::{HttpMethod, RpcEndpoint, TraceSchema, ENDPOINTS};
+
+/// OpenAPI 3 document describing [`ENDPOINTS`].
+///
+/// Schemas of the request bodies and responses are traced from the Rust
+/// types, see [`crate::schema`], and name the type with the
+/// `x-rust-type` extension.
+pub fn openapi() -> Value {
+    let mut paths = Map::new();
+    for endpoint in ENDPOINTS {
+        let method = match endpoint.method {
+            HttpMethod::Get => "get",
+            HttpMethod::Post => "post",
+        };
+        let path = paths
+            .entry(endpoint.path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(path) = path {
+            path.insert(method.to_owned(), operation(endpoint));
+        }
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Openmina node RPC",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+    })
+}
+
+fn operation(endpoint: &RpcEndpoint) -> Value {
+    let path_params = endpoint.path_params.iter().map(|name| {
+        json!({
+            "name": name,
+            "in": "path",
+            "required": true,
+            "schema": { "type": "string" },
+        })
+    });
+    let query_params = endpoint.query_params.iter().map(|param| {
+        json!({
+            "name": param.name,
+            "in": "query",
+            "required": param.required,
+            "schema": { "type": "string" },
+        })
+    });
+
+    let mut operation = json!({
+        "operationId": endpoint.name,
+        "summary": endpoint.doc.lines().map(str::trim).collect::<Vec<_>>().join(" "),
+        "parameters": path_params.chain(query_params).collect::<Vec<_>>(),
+        "responses": {
+            "200": {
+                "description": endpoint.response,
+                "content": {
+                    "application/json": {
+                        "schema": schema(endpoint.response, endpoint.response_schema),
+                    },
+                },
+            },
+        },
+    });
+    if let (Some(body), Some(body_schema)) = (endpoint.body, endpoint.body_schema) {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": schema(body, body_schema),
+                },
+            },
+        });
+    }
+    operation
+}
+
+fn schema(rust_type: &str, trace: TraceSchema) -> Value {
+    let mut schema = trace().unwrap_or_else(|| json!({}));
+    if let Value::Object(schema) = &mut schema {
+        schema.insert("x-rust-type".to_owned(), rust_type.into());
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_params_match_path() {
+        for endpoint in ENDPOINTS {
+            let in_path = endpoint
+                .path
+                .split('/')
+                .filter_map(|s| s.strip_prefix('{')?.strip_suffix('}'))
+                .collect::<Vec<_>>();
+            assert_eq!(in_path, endpoint.path_params, "{}", endpoint.path);
+        }
+    }
+
+    #[test]
+    fn responses_have_schemas() {
+        let doc = openapi();
+        let schema = &doc["paths"]["/ledger/audit"]["get"]["responses"]["200"]["content"]
+            ["application/json"]["schema"];
+        assert_eq!(schema["x-rust-type"], "RpcLedgerAuditStatus");
+        assert_eq!(schema["type"], "object");
+        assert!(schema["properties"]
+            .as_object()
+            .is_some_and(|p| !p.is_empty()));
+    }
+
+    #[test]
+    fn query_params_required_as_by_node() {
+        let doc = openapi();
+        let params = doc["paths"]["/transaction-pool/snapshots/diff"]["get"]["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| {
+                (
+                    p["name"].as_str().unwrap(),
+                    p["required"].as_bool().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(params, [("from", true), ("to", false)]);
+    }
+
+    #[test]
+    fn operation_ids_are_unique() {
+        let doc = openapi();
+        let ids = doc["paths"]
+            .as_object()
+            .unwrap()
+            .values()
+            .flat_map(|path| path.as_object().unwrap().values())
+            .map(|op| op["operationId"].as_str().unwrap())
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(ids.len(), ENDPOINTS.len());
+    }
+}
@@ -0,0 +1,719 @@
+//! JSON schemas of the request and response types, traced from their
+//! `Deserialize` implementations.
+//!
+//! The tracer is a deserializer which records what the type asks for and
+//! makes up the values. The type is deserialized repeatedly, once for each
+//! variant of its enums. Strings rejected by the type (hashes, public keys
+//! and other base58 encoded values) are retried with the samples of such
+//! strings.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use mina_p2p_messages::v2::{EpochSeed, LedgerHash, StateHash, TransactionHash};
+use node::p2p::PeerId;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde_json::{json, Map, Value};
+
+/// Nesting depth after which sequences and maps are traced empty and
+/// options as `None`, so that the recursive types terminate.
+const MAX_DEPTH: usize = 24;
+/// Limit of the deserialization runs per type.
+const MAX_RUNS: usize = 1024;
+
+/// Schema of the JSON representation of `T`, `None` if the type couldn't
+/// be deserialized from the made up values.
+pub(crate) fn trace<T: DeserializeOwned>() -> Option<Value> {
+    let mut tracer = Tracer {
+        samples: string_samples(),
+        ..Default::default()
+    };
+    let mut traced = false;
+    for _ in 0..MAX_RUNS {
+        tracer.last_string = None;
+        tracer.last_path = None;
+        let result = T::deserialize(TraceDeserializer {
+            tracer: &mut tracer,
+            path: String::new(),
+            depth: 0,
+        });
+        match result {
+            Ok(_) => traced = true,
+            Err(_) if tracer.next_string_sample() => continue,
+            Err(_) => {
+                if !tracer.skip_failed() && !traced {
+                    return None;
+                }
+            }
+        }
+        if let Some(path) = tracer.exploring.take() {
+            // variant unreachable or failing, don't retry it.
+            tracer.records.entry(path).or_insert(Record::Untraced);
+        }
+        if !tracer.next_variant() {
+            break;
+        }
+    }
+    traced.then(|| tracer.schema(""))
+}
+
+/// Strings accepted by the types which parse them.
+fn string_samples() -> Vec<String> {
+    vec![
+        "0".to_owned(),
+        StateHash::zero().to_string(),
+        LedgerHash::zero().to_string(),
+        EpochSeed::zero().to_string(),
+        TransactionHash::from(&[0; 32]).to_string(),
+        PeerId::from_bytes([0; 32]).to_string(),
+        "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV".to_owned(),
+    ]
+}
+
+/// What the type asked for at the path.
+#[derive(Debug, Clone)]
+enum Record {
+    Leaf(Value),
+    Unit,
+    Option,
+    Newtype,
+    Seq,
+    Tuple(usize),
+    Map,
+    Struct {
+        fields: &'static [&'static str],
+    },
+    Enum {
+        variants: &'static [&'static str],
+    },
+    /// Variant which failed to deserialize.
+    Untraced,
+}
+
+#[derive(Default)]
+struct Tracer {
+    /// Records by the path, which is made of the field names, variant
+    /// names and the placeholders for the elements, e.g. `/peers/[]/id`.
+    records: BTreeMap<String, Record>,
+    /// Index of the variant to pick, by the path of the enum.
+    variants: BTreeMap<String, usize>,
+    /// Path of the variant traced in the current run.
+    exploring: Option<String>,
+    /// Paths of the options which failed to deserialize as `Some`.
+    nones: BTreeSet<String>,
+    samples: Vec<String>,
+    /// Index of the sample, by the path of the string.
+    strings: BTreeMap<String, usize>,
+    /// Path of the latest string handed out, the likely reason of the
+    /// failed run.
+    last_string: Option<String>,
+    /// Path of the latest value, where the failed run stopped.
+    last_path: Option<String>,
+}
+
+impl Tracer {
+    fn string_sample(&mut self, path: &str) -> String {
+        self.last_string = Some(path.to_owned());
+        let i = self.strings.get(path).copied().unwrap_or_default();
+        self.samples.get(i).cloned().unwrap_or_default()
+    }
+
+    fn next_string_sample(&mut self) -> bool {
+        let Some(path) = self.last_string.take() else {
+            return false;
+        };
+        let i = self.strings.entry(path).or_default();
+        if i.saturating_add(1) >= self.samples.len() {
+            return false;
+        }
+        *i = i.saturating_add(1);
+        true
+    }
+
+    /// Marks the innermost variant the failed run stopped in as untraced
+    /// and picks another variant of its enum instead, or of the enclosing
+    /// enum if there is none. Options the run stopped in are traced as
+    /// `None` from then on.
+    fn skip_failed(&mut self) -> bool {
+        let Some(mut path) = self.last_path.take() else {
+            return false;
+        };
+        loop {
+            let Some((parent, segment)) = path.rsplit_once('/') else {
+                return false;
+            };
+            if let Some(Record::Option) = self.records.get(parent) {
+                if self.nones.insert(parent.to_owned()) {
+                    return true;
+                }
+            }
+            if let Some(&Record::Enum { variants }) = self.records.get(parent) {
+                if variants.contains(&segment) {
+                    self.records.insert(path.clone(), Record::Untraced);
+                    let other = variants.iter().position(|name| {
+                        !matches!(
+                            self.records.get(&format!("{parent}/{name}")),
+                            Some(Record::Untraced)
+                        )
+                    });
+                    if let Some(i) = other {
+                        self.variants.insert(parent.to_owned(), i);
+                        return true;
+                    }
+                }
+            }
+            path = parent.to_owned();
+        }
+    }
+
+    /// Picks the first variant which wasn't traced yet, along with the
+    /// variants of the enums it is nested in.
+    fn next_variant(&mut self) -> bool {
+        let next = self.records.iter().find_map(|(path, record)| match record {
+            Record::Enum { variants } => variants
+                .iter()
+                .map(|name| format!("{path}/{name}"))
+                .find(|variant| !self.records.contains_key(variant)),
+            _ => None,
+        });
+        let Some(next) = next else {
+            return false;
+        };
+        let mut prefix = String::new();
+        for segment in next.split('/').skip(1) {
+            if let Some(Record::Enum { variants }) = self.records.get(&prefix) {
+                if let Some(i) = variants.iter().position(|name| *name == segment) {
+                    self.variants.insert(prefix.clone(), i);
+                }
+            }
+            prefix.push('/');
+            prefix.push_str(segment);
+        }
+        self.exploring = Some(next);
+        true
+    }
+
+    fn schema(&self, path: &str) -> Value {
+        let child = |segment: &str| self.schema(&format!("{path}/{segment}"));
+        match self.records.get(path) {
+            None | Some(Record::Untraced) => json!({}),
+            Some(Record::Leaf(schema)) => schema.clone(),
+            Some(Record::Unit) => json!({ "nullable": true }),
+            Some(Record::Option) => {
+                let mut schema = child("?");
+                if let Value::Object(schema) = &mut schema {
+                    schema.insert("nullable".to_owned(), true.into());
+                }
+                schema
+            }
+            Some(Record::Newtype) => child("~"),
+            Some(Record::Seq) => json!({ "type": "array", "items": child("[]") }),
+            Some(Record::Tuple(len)) => {
+                let mut items = Vec::new();
+                for i in 0..*len {
+                    let item = child(&i.to_string());
+                    if !items.contains(&item) {
+                        items.push(item);
+                    }
+                }
+                let items = match items.len() {
+                    1 => items.pop().unwrap_or_default(),
+                    _ => json!({ "anyOf": items }),
+                };
+                json!({ "type": "array", "minItems": len, "maxItems": len, "items": items })
+            }
+            Some(Record::Map) => {
+                json!({ "type": "object", "additionalProperties": child("<value>") })
+            }
+            Some(Record::Struct { fields }) => {
+                let properties = fields
+                    .iter()
+                    .map(|field| (field.to_string(), child(field)))
+                    .collect::<Map<_, _>>();
+                let required = fields
+                    .iter()
+                    .filter(|field| {
+                        !matches!(
+                            self.records.get(&format!("{path}/{field}")),
+                            Some(Record::Option)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                json!({ "type": "object", "properties": properties, "required": required })
+            }
+            Some(Record::Enum { variants }) => {
+                let is_unit = |name: &&str| {
+                    matches!(
+                        self.records.get(&format!("{path}/{name}")),
+                        Some(Record::Unit)
+                    )
+                };
+                if variants.iter().all(is_unit) {
+                    return json!({ "type": "string", "enum": variants });
+                }
+                let variants = variants
+                    .iter()
+                    .map(|name| match is_unit(name) {
+                        true => json!({ "type": "string", "enum": [name] }),
+                        false => json!({
+                            "type": "object",
+                            "properties": { *name: child(name) },
+                            "required": [name],
+                            "additionalProperties": false,
+                        }),
+                    })
+                    .collect::<Vec<_>>();
+                json!({ "oneOf": variants })
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TraceError(String);
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl de::Error for TraceError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+struct TraceDeserializer<'a> {
+    tracer: &'a mut Tracer,
+    path: String,
+    depth: usize,
+}
+
+impl<'a> TraceDeserializer<'a> {
+    fn record(&mut self, record: Record) {
+        self.tracer.last_path = Some(self.path.clone());
+        self.tracer.records.insert(self.path.clone(), record);
+    }
+
+    fn into_child(self, segment: &str) -> Self {
+        Self {
+            path: format!("{}/{segment}", self.path),
+            depth: self.depth.saturating_add(1),
+            tracer: self.tracer,
+        }
+    }
+
+    fn is_too_deep(&self) -> bool {
+        self.depth >= MAX_DEPTH
+    }
+
+    fn enter(&self) -> Result<(), TraceError> {
+        match self.is_too_deep() {
+            true => Err(de::Error::custom("nested too deep")),
+            false => Ok(()),
+        }
+    }
+
+    fn elements(self, segments: Vec<String>) -> Elements<'a> {
+        Elements {
+            segments: segments.into_iter(),
+            depth: self.depth.saturating_add(1),
+            path: self.path,
+            tracer: self.tracer,
+        }
+    }
+}
+
+fn integer(format: &str, unsigned: bool) -> Record {
+    match unsigned {
+        true => Record::Leaf(json!({ "type": "integer", "format": format, "minimum": 0 })),
+        false => Record::Leaf(json!({ "type": "integer", "format": format })),
+    }
+}
+
+macro_rules! deserialize_integer {
+    ($($method:ident $visit:ident $format:literal $unsigned:literal,)*) => {$(
+        fn $method<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+            self.record(integer($format, $unsigned));
+            visitor.$visit(0)
+        }
+    )*};
+}
+
+impl<'de, 'a> de::Deserializer<'de> for TraceDeserializer<'a> {
+    type Error = TraceError;
+
+    fn deserialize_any<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Leaf(json!({})));
+        visitor.visit_unit()
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Leaf(json!({ "type": "boolean" })));
+        visitor.visit_bool(false)
+    }
+
+    deserialize_integer! {
+        deserialize_i8 visit_i8 "int32" false,
+        deserialize_i16 visit_i16 "int32" false,
+        deserialize_i32 visit_i32 "int32" false,
+        deserialize_i64 visit_i64 "int64" false,
+        deserialize_u8 visit_u8 "int32" true,
+        deserialize_u16 visit_u16 "int32" true,
+        deserialize_u32 visit_u32 "int32" true,
+        deserialize_u64 visit_u64 "int64" true,
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Leaf(json!({ "type": "number", "format": "float" })));
+        visitor.visit_f32(0.0)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Leaf(
+            json!({ "type": "number", "format": "double" }),
+        ));
+        visitor.visit_f64(0.0)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Leaf(
+            json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+        ));
+        visitor.visit_char('a')
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Leaf(json!({ "type": "string" })));
+        let sample = self.tracer.string_sample(&self.path);
+        visitor.visit_string(sample)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Leaf(json!({
+            "type": "array",
+            "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+        })));
+        visitor.visit_byte_buf(Vec::new())
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Option);
+        match self.is_too_deep() || self.tracer.nones.contains(&self.path) {
+            true => visitor.visit_none(),
+            false => visitor.visit_some(self.into_child("?")),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Unit);
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        mut self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        self.enter()?;
+        self.record(Record::Newtype);
+        visitor.visit_newtype_struct(self.into_child("~"))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Seq);
+        let segments = match self.is_too_deep() {
+            true => vec![],
+            false => vec!["[]".to_owned()],
+        };
+        visitor.visit_seq(self.elements(segments))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        mut self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        self.enter()?;
+        self.record(Record::Tuple(len));
+        let segments = (0..len).map(|i| i.to_string()).collect();
+        visitor.visit_seq(self.elements(segments))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, TraceError> {
+        self.record(Record::Map);
+        let segments = match self.is_too_deep() {
+            true => vec![],
+            false => vec!["<key>".to_owned(), "<value>".to_owned()],
+        };
+        visitor.visit_map(self.elements(segments))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        mut self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        self.enter()?;
+        self.record(Record::Struct { fields });
+        visitor.visit_map(Fields {
+            fields: fields.iter(),
+            field: None,
+            depth: self.depth.saturating_add(1),
+            path: self.path,
+            tracer: self.tracer,
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        mut self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        self.enter()?;
+        self.record(Record::Enum { variants });
+        let i = self
+            .tracer
+            .variants
+            .get(&self.path)
+            .copied()
+            .unwrap_or_default();
+        let name = *variants
+            .get(i)
+            .ok_or_else(|| TraceError("enum without variants".to_owned()))?;
+        visitor.visit_enum(Variant {
+            name,
+            de: self.into_child(name),
+        })
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        true
+    }
+}
+
+/// Elements of the sequences, tuples and maps, a single one for the
+/// sequences and maps.
+struct Elements<'a> {
+    tracer: &'a mut Tracer,
+    path: String,
+    segments: std::vec::IntoIter<String>,
+    depth: usize,
+}
+
+impl Elements<'_> {
+    fn next<'de, T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, TraceError> {
+        let Some(segment) = self.segments.next() else {
+            return Ok(None);
+        };
+        seed.deserialize(TraceDeserializer {
+            tracer: &mut *self.tracer,
+            path: format!("{}/{segment}", self.path),
+            depth: self.depth,
+        })
+        .map(Some)
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for Elements<'_> {
+    type Error = TraceError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, TraceError> {
+        self.next(seed)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Elements<'_> {
+    type Error = TraceError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, TraceError> {
+        self.next(seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, TraceError> {
+        self.next(seed)?
+            .ok_or_else(|| TraceError("value without a key".to_owned()))
+    }
+}
+
+struct Fields<'a> {
+    tracer: &'a mut Tracer,
+    path: String,
+    fields: std::slice::Iter<'static, &'static str>,
+    field: Option<&'static str>,
+    depth: usize,
+}
+
+impl<'de> de::MapAccess<'de> for Fields<'_> {
+    type Error = TraceError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, TraceError> {
+        let Some(field) = self.fields.next() else {
+            return Ok(None);
+        };
+        self.field = Some(field);
+        let key: de::value::StrDeserializer<'static, TraceError> = field.into_deserializer();
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, TraceError> {
+        let field = self
+            .field
+            .take()
+            .ok_or_else(|| TraceError("value without a key".to_owned()))?;
+        seed.deserialize(TraceDeserializer {
+            tracer: &mut *self.tracer,
+            path: format!("{}/{field}", self.path),
+            depth: self.depth,
+        })
+    }
+}
+
+struct Variant<'a> {
+    name: &'static str,
+    de: TraceDeserializer<'a>,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Variant<'a> {
+    type Error = TraceError;
+    type Variant = TraceDeserializer<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), TraceError> {
+        let key: de::value::StrDeserializer<'static, TraceError> = self.name.into_deserializer();
+        Ok((seed.deserialize(key)?, self.de))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for TraceDeserializer<'_> {
+    type Error = TraceError;
+
+    fn unit_variant(mut self) -> Result<(), TraceError> {
+        self.record(Record::Unit);
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        mut self,
+        seed: T,
+    ) -> Result<T::Value, TraceError> {
+        self.record(Record::Newtype);
+        seed.deserialize(self.into_child("~"))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    enum Kind {
+        Empty,
+        Named { name: String },
+        Hash(StateHash),
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct Item {
+        id: u32,
+        kind: Kind,
+        tags: Vec<String>,
+        parent: Option<Box<Item>>,
+    }
+
+    #[test]
+    fn traces_structs_and_all_variants() {
+        let schema = trace::<Item>().unwrap();
+        let properties = &schema["properties"];
+        assert_eq!(properties["id"]["type"], "integer");
+        assert_eq!(properties["tags"]["items"]["type"], "string");
+        assert_eq!(properties["parent"]["nullable"], true);
+        assert_eq!(properties["parent"]["properties"]["id"]["type"], "integer");
+        assert_eq!(schema["required"], json!(["id", "kind", "tags"]));
+
+        let variants = properties["kind"]["oneOf"].as_array().unwrap();
+        assert_eq!(variants[0], json!({ "type": "string", "enum": ["Empty"] }));
+        let named = &variants[1]["properties"]["Named"];
+        assert_eq!(named["properties"]["name"]["type"], "string");
+        // the state hash is only traced with its sample, not with "0".
+        assert_eq!(variants[2]["properties"]["Hash"]["type"], "string");
+    }
+}
@@ -0,0 +1,249 @@
+//! Accounts as of the blocks older than the transition frontier, read
+//! from the precomputed blocks in the local storage.
+//!
+//! Precomputed blocks only store the accounts accessed by the block, so
+//! the state of an account is the one after the latest block accessing
+//! it, looked up going from the queried block to its ancestors. Accounts
+//! not accessed since the storage was started can't be found.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use ledger::{Account, AccountId};
+use mina_p2p_messages::v2::{PrecomputedBlock, StateHash};
+use node::account::AccountPublicKey;
+use node::rpc::{
+    AccountQuery, GetBlockQuery, RpcLedgerAccountsAtBlock, RpcLedgerAccountsAtBlockQuery,
+    RpcLedgerAccountsAtBlockResponse,
+};
+
+/// Precomputed block in the local storage, named
+/// `{network}-{height}-{state_hash}.json`.
+#[derive(Debug, Clone, PartialEq)]
+struct StoredBlock {
+    height: u32,
+    hash: StateHash,
+    path: PathBuf,
+}
+
+impl StoredBlock {
+    fn parse(path: PathBuf, network: &str) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        let (height, hash) = name
+            .strip_prefix(network)?
+            .strip_prefix('-')?
+            .strip_suffix(".json")?
+            .split_once('-')?;
+        Some(Self {
+            height: height.parse().ok()?,
+            hash: hash.parse().ok()?,
+            path,
+        })
+    }
+
+    fn read(&self) -> Result<PrecomputedBlock, String> {
+        let data = std::fs::read(&self.path)
+            .map_err(|e| format!("failed to read archived block {}: {e}", self.hash))?;
+        serde_json::from_slice(&data)
+            .map_err(|e| format!("failed to decode archived block {}: {e}", self.hash))
+    }
+}
+
+fn stored_blocks(dir: &Path, network: &str) -> Result<BTreeMap<StateHash, StoredBlock>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("failed to read the archive: {e}"))?;
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| StoredBlock::parse(entry.path(), network))
+        .map(|block| (block.hash.clone(), block))
+        .collect())
+}
+
+/// Accounts matching the query, in the order they were found in.
+enum AccountsFound {
+    PublicKey(AccountPublicKey, Vec<Account>),
+    Ids(Vec<(AccountId, Option<Account>)>),
+}
+
+impl AccountsFound {
+    fn new(query: &AccountQuery) -> Result<Self, String> {
+        let ids = match query {
+            AccountQuery::All => {
+                return Err(
+                    "only the accounts accessed by the archived blocks are stored, \
+                    all the accounts can't be queried from the archive"
+                        .to_owned(),
+                )
+            }
+            AccountQuery::SinglePublicKey(public_key) => {
+                return Ok(Self::PublicKey(public_key.clone(), Vec::new()))
+            }
+            AccountQuery::PubKeyWithTokenId(public_key, token_id) => {
+                let public_key = public_key
+                    .clone()
+                    .try_into()
+                    .map_err(|_| format!("invalid public key {public_key}"))?;
+                vec![AccountId::new(public_key, token_id.clone().into())]
+            }
+            AccountQuery::MultipleIds(ids) => ids.clone(),
+        };
+        Ok(Self::Ids(ids.into_iter().map(|id| (id, None)).collect()))
+    }
+
+    /// Adds the account as it was after the block, unless the account was
+    /// found in a newer block already.
+    fn add(&mut self, account: Account) {
+        match self {
+            Self::PublicKey(public_key, found) => {
+                if public_key != &AccountPublicKey::from(account.public_key.clone())
+                    || found.iter().any(|found| found.id() == account.id())
+                {
+                    return;
+                }
+                found.push(account);
+            }
+            Self::Ids(ids) => {
+                let id = account.id();
+                if let Some((_, found @ None)) = ids.iter_mut().find(|(queried, _)| queried == &id)
+                {
+                    *found = Some(account);
+                }
+            }
+        }
+    }
+
+    /// Whether the older blocks don't need to be looked at. Accounts of
+    /// the public key may have been accessed by any block.
+    fn is_complete(&self) -> bool {
+        match self {
+            Self::PublicKey(..) => false,
+            Self::Ids(ids) => ids.iter().all(|(_, found)| found.is_some()),
+        }
+    }
+
+    fn into_accounts(self) -> Vec<Account> {
+        match self {
+            Self::PublicKey(_, found) => found,
+            Self::Ids(ids) => ids.into_iter().filter_map(|(_, found)| found).collect(),
+        }
+    }
+}
+
+/// Looks up the accounts as of the block in the precomputed blocks stored
+/// in the `dir`.
+pub fn accounts_at_block(
+    dir: &Path,
+    network: &str,
+    query: &RpcLedgerAccountsAtBlockQuery,
+) -> RpcLedgerAccountsAtBlockResponse {
+    let mut found = AccountsFound::new(&query.account_query)?;
+    let blocks = stored_blocks(dir, network)?;
+    let target = match &query.block {
+        GetBlockQuery::Hash(hash) => blocks.get(hash),
+        GetBlockQuery::Height(height) => {
+            let mut at_height = blocks.values().filter(|block| block.height == *height);
+            match (at_height.next(), at_height.next()) {
+                (block, None) => block,
+                (Some(_), Some(_)) => {
+                    return Err(format!(
+                        "multiple archived blocks at height {height}, query it by the state hash"
+                    ))
+                }
+                (None, Some(_)) => None,
+            }
+        }
+    };
+    let target = target.ok_or_else(|| "block not found in the archive".to_owned())?;
+
+    let block = target.read()?;
+    let ledger_hash = block
+        .protocol_state
+        .body
+        .blockchain_state
+        .staged_ledger_hash
+        .non_snark
+        .ledger_hash
+        .clone();
+    let mut next = Some(block);
+    let mut height = target.height;
+    while let Some(block) = next.take() {
+        for (_, account) in block.accounts_accessed.iter() {
+            let account = Account::try_from(account)
+                .map_err(|e| format!("invalid account in the archived block: {e:?}"))?;
+            found.add(account);
+        }
+        if found.is_complete() {
+            break;
+        }
+        let Some(parent_height) = height.checked_sub(1) else {
+            break;
+        };
+        let parent = blocks
+            .get(&block.protocol_state.previous_state_hash)
+            .filter(|parent| parent.height == parent_height);
+        if let Some(parent) = parent {
+            next = Some(parent.read()?);
+            height = parent_height;
+        }
+    }
+
+    Ok(RpcLedgerAccountsAtBlock {
+        block_hash: target.hash.clone(),
+        height: target.height,
+        ledger_hash,
+        accounts: found.into_accounts(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "3NKjZ5fjms6BMaH4aq7DopPGyMY7PbG6vhRsX5XnYRxih8i9G7dj";
+
+    fn query(block: GetBlockQuery, account_query: AccountQuery) -> RpcLedgerAccountsAtBlockQuery {
+        RpcLedgerAccountsAtBlockQuery {
+            block,
+            account_query,
+        }
+    }
+
+    #[test]
+    fn stored_block_name() {
+        let path = PathBuf::from(format!("/archive/devnet-12-{HASH}.json"));
+        let block = StoredBlock::parse(path.clone(), "devnet").unwrap();
+        assert_eq!(block.height, 12);
+        assert_eq!(block.hash.to_string(), HASH);
+        assert_eq!(block.path, path);
+
+        for name in [
+            format!("mainnet-12-{HASH}.json"),
+            format!("devnet-12-{HASH}.bin"),
+            format!("devnet-x-{HASH}.json"),
+            "devnet-12-garbage.json".to_owned(),
+        ] {
+            assert_eq!(StoredBlock::parse(PathBuf::from(&name), "devnet"), None);
+        }
+    }
+
+    #[test]
+    fn unanswerable_queries() {
+        let dir = std::env::temp_dir().join(format!("archive-accounts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(format!("devnet-12-{HASH}.json")), b"{}").unwrap();
+
+        let all = query(GetBlockQuery::Height(12), AccountQuery::All);
+        assert!(accounts_at_block(&dir, "devnet", &all)
+            .unwrap_err()
+            .contains("all the accounts"));
+
+        let ids = |block| query(block, AccountQuery::MultipleIds(vec![]));
+        let missing = accounts_at_block(&dir, "devnet", &ids(GetBlockQuery::Height(11)));
+        assert_eq!(missing.unwrap_err(), "block not found in the archive");
+        let other_network = accounts_at_block(&dir, "mainnet", &ids(GetBlockQuery::Height(12)));
+        assert_eq!(other_network.unwrap_err(), "block not found in the archive");
+        let undecodable = accounts_at_block(&dir, "devnet", &ids(GetBlockQuery::Height(12)));
+        assert!(undecodable.unwrap_err().starts_with("failed to decode"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
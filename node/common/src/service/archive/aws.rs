@@ -1,7 +1,19 @@
-use std::env;
+use std::{
+    env,
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use aws_smithy_runtime::client::http::hyper_014::HyperClientBuilder;
+use openmina_core::ProxyConfig;
 
 use super::Error;
 
+const PROXY_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub(crate) struct ArchiveAWSClient {
     client: aws_sdk_s3::Client,
     bucket_name: String,
@@ -10,7 +22,21 @@ pub(crate) struct ArchiveAWSClient {
 
 impl ArchiveAWSClient {
     pub async fn new() -> Result<Self, Error> {
-        let config = aws_config::load_from_env().await;
+        let config = match ProxyConfig::global() {
+            None => aws_config::load_from_env().await,
+            Some(proxy) => {
+                let https = hyper_rustls::HttpsConnectorBuilder::new()
+                    .with_native_roots()
+                    .https_or_http()
+                    .enable_http1()
+                    .enable_http2()
+                    .wrap_connector(ProxyConnector(proxy));
+                aws_config::from_env()
+                    .http_client(HyperClientBuilder::new().build(https))
+                    .load()
+                    .await
+            }
+        };
         let bucket_name = env::var("OPENMINA_AWS_BUCKET_NAME").map_err(|_| {
             Error::EnvironmentVariableNotSet("OPENMINA_AWS_BUCKET_NAME".to_string())
         })?;
@@ -37,3 +63,40 @@ impl ArchiveAWSClient {
         Ok(())
     }
 }
+
+/// Connector of the s3 client, which tunnels the connections through the
+/// proxy of the node. The SDK itself has no proxy support.
+#[derive(Clone)]
+struct ProxyConnector(&'static ProxyConfig);
+
+impl hyper::service::Service<hyper::Uri> for ProxyConnector {
+    type Response = tokio::net::TcpStream;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: hyper::Uri) -> Self::Future {
+        let proxy = self.0;
+        Box::pin(async move {
+            let host = uri
+                .host()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "uri without host"))?
+                .to_owned();
+            let port = uri.port_u16().unwrap_or(match uri.scheme_str() {
+                Some("http") => 80,
+                _ => 443,
+            });
+            let stream = tokio::task::spawn_blocking(move || {
+                let stream = proxy.connect_host(&host, port, PROXY_CONNECT_TIMEOUT)?;
+                stream.set_nonblocking(true)?;
+                Ok::<_, io::Error>(stream)
+            })
+            .await
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))??;
+            tokio::net::TcpStream::from_std(stream)
+        })
+    }
+}
@@ -23,10 +23,17 @@ impl ArchiveGCPClient {
             .await
             .map_err(|e| Error::UploadError(format!("GCP credentials error: {}", e)))?;
 
-        let config = gcs::client::ClientConfig::default()
+        let mut config = gcs::client::ClientConfig::default()
             .with_credentials(credentials)
             .await
             .map_err(|e| Error::UploadError(format!("GCP config error: {}", e)))?;
+        if let Some(proxy) = crate::service::proxy::http_proxy() {
+            let http = reqwest::Client::builder()
+                .proxy(proxy)
+                .build()
+                .map_err(|e| Error::UploadError(format!("GCP http client error: {}", e)))?;
+            config.http = Some(reqwest_middleware::ClientBuilder::new(http).build());
+        }
 
         Ok(ArchiveGCPClient {
             client: gcs::client::Client::new(config),
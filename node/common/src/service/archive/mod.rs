@@ -1,6 +1,10 @@
 use mina_p2p_messages::v2::{self};
 use node::core::{channels::mpsc, thread};
+use node::event_source::Event;
 use node::ledger::write::BlockApplyResult;
+use node::rpc::{RpcId, RpcLedgerAccountsAtBlockQuery};
+use node::stats::zkapp_failures::ZkappFailureStats;
+use node::transition_frontier::archive::ArchiveEvent;
 use std::env;
 use std::io::Write;
 
@@ -8,8 +12,10 @@ use mina_p2p_messages::v2::PrecomputedBlock;
 use openmina_core::NetworkConfig;
 use std::net::SocketAddr;
 
-use super::NodeService;
+use super::{EventSender, NodeService};
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod accounts;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod aws;
 #[cfg(not(target_arch = "wasm32"))]
@@ -24,6 +30,10 @@ use config::ArchiveStorageOptions;
 const ARCHIVE_SEND_RETRIES: u8 = 5;
 const MAX_EVENT_COUNT: u64 = 100;
 const RETRY_INTERVAL_MS: u64 = 1000;
+/// Directory in the local precomputed storage, with the failure reasons of
+/// the zkApp commands of the archived blocks, aggregated in a file per
+/// epoch, so that only the file of the current epoch is rewritten.
+pub const ZKAPP_FAILURES_DIR: &str = "zkapp-failures";
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -33,8 +43,23 @@ pub enum Error {
     UploadError(String),
 }
 
+/// Dir in which the precomputed blocks are stored, if the local
+/// precomputed storage is enabled.
+pub fn local_precomputed_storage_path(work_dir: &str) -> String {
+    let env_path = env::var("OPENMINA_LOCAL_PRECOMPUTED_STORAGE_PATH");
+    env_path.unwrap_or_else(|_| format!("{}/archive-precomputed", work_dir))
+}
+
+pub enum ArchiveRequest {
+    Block(BlockApplyResult),
+    AccountsAtBlock(RpcId, RpcLedgerAccountsAtBlockQuery),
+}
+
 pub struct ArchiveService {
-    archive_sender: mpsc::UnboundedSender<BlockApplyResult>,
+    archive_sender: mpsc::UnboundedSender<ArchiveRequest>,
+    /// Whether the precomputed blocks are stored locally, so the accounts
+    /// of the blocks older than the frontier can be looked up in them.
+    uses_local_precomputed_storage: bool,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -43,6 +68,7 @@ struct ArchiveServiceClients {
     aws_client: Option<aws::ArchiveAWSClient>,
     gcp_client: Option<gcp::ArchiveGCPClient>,
     local_path: Option<String>,
+    zkapp_failures: Option<ZkappFailureStats>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -63,13 +89,13 @@ impl ArchiveServiceClients {
         };
 
         let local_path = if options.uses_local_precomputed_storage() {
-            let env_path = env::var("OPENMINA_LOCAL_PRECOMPUTED_STORAGE_PATH");
-            let default = format!("{}/archive-precomputed", work_dir);
-            Some(env_path.unwrap_or(default))
+            Some(local_precomputed_storage_path(&work_dir))
         } else {
             None
         };
 
+        let zkapp_failures = local_path.as_deref().map(read_zkapp_failures);
+
         let archiver_address = if options.uses_archiver_process() {
             let address = std::env::var("OPENMINA_ARCHIVE_ADDRESS")
                 .expect("OPENMINA_ARCHIVE_ADDRESS is not set");
@@ -90,10 +116,26 @@ impl ArchiveServiceClients {
             aws_client,
             gcp_client,
             local_path,
+            zkapp_failures,
         })
     }
 
-    pub async fn send_block(&self, breadcrumb: BlockApplyResult, options: &ArchiveStorageOptions) {
+    pub async fn send_block(
+        &mut self,
+        breadcrumb: BlockApplyResult,
+        options: &ArchiveStorageOptions,
+    ) {
+        if let (Some(path), Some(stats)) = (&self.local_path, &mut self.zkapp_failures) {
+            if let Some(epoch) = stats.add_block(&breadcrumb.block) {
+                if let Err(e) = write_zkapp_failures(path, &stats.epoch(epoch), epoch) {
+                    node::core::warn!(
+                        summary = "Failed to write zkapp failure stats to local storage",
+                        error = e.to_string()
+                    );
+                }
+            }
+        }
+
         if options.uses_archiver_process() {
             if let Some(socket_addr) = self.archiver_address {
                 Self::handle_archiver_process(&breadcrumb, &socket_addr).await;
@@ -212,20 +254,45 @@ impl ArchiveServiceClients {
             retries -= 1;
         }
     }
+
+    fn accounts_at_block(
+        &self,
+        rpc_id: RpcId,
+        query: RpcLedgerAccountsAtBlockQuery,
+        event_sender: &EventSender,
+    ) {
+        let response = match &self.local_path {
+            Some(path) => accounts::accounts_at_block(
+                std::path::Path::new(path),
+                NetworkConfig::global().name,
+                &query,
+            ),
+            None => Err("local precomputed storage path not set".to_owned()),
+        };
+        let event = ArchiveEvent::AccountsAtBlock(rpc_id, response);
+        let _ = event_sender.send(Event::Archive(event));
+    }
 }
 
 impl ArchiveService {
-    fn new(archive_sender: mpsc::UnboundedSender<BlockApplyResult>) -> Self {
-        Self { archive_sender }
+    fn new(
+        archive_sender: mpsc::UnboundedSender<ArchiveRequest>,
+        uses_local_precomputed_storage: bool,
+    ) -> Self {
+        Self {
+            archive_sender,
+            uses_local_precomputed_storage,
+        }
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     async fn run(
-        mut archive_receiver: mpsc::UnboundedReceiver<BlockApplyResult>,
+        mut archive_receiver: mpsc::UnboundedReceiver<ArchiveRequest>,
         options: ArchiveStorageOptions,
         work_dir: String,
+        event_sender: EventSender,
     ) {
-        let clients = match ArchiveServiceClients::new(&options, work_dir).await {
+        let mut clients = match ArchiveServiceClients::new(&options, work_dir).await {
             Ok(clients) => clients,
             Err(e) => {
                 node::core::error!(
@@ -236,38 +303,52 @@ impl ArchiveService {
             }
         };
 
-        while let Some(breadcrumb) = archive_receiver.recv().await {
-            clients.send_block(breadcrumb, &options).await;
+        while let Some(request) = archive_receiver.recv().await {
+            match request {
+                ArchiveRequest::Block(breadcrumb) => {
+                    clients.send_block(breadcrumb, &options).await;
+                }
+                ArchiveRequest::AccountsAtBlock(rpc_id, query) => {
+                    clients.accounts_at_block(rpc_id, query, &event_sender);
+                }
+            }
         }
     }
 
     // Note: Placeholder for the wasm implementation, if we decide to include an archive mode in the future
     #[cfg(target_arch = "wasm32")]
     fn run(
-        mut archive_receiver: mpsc::UnboundedReceiver<BlockApplyResult>,
+        mut archive_receiver: mpsc::UnboundedReceiver<ArchiveRequest>,
         options: ArchiveStorageOptions,
         work_dir: String,
+        event_sender: EventSender,
     ) {
         unimplemented!()
     }
 
-    pub fn start(options: ArchiveStorageOptions, work_dir: String) -> Self {
-        let (archive_sender, archive_receiver) = mpsc::unbounded_channel::<BlockApplyResult>();
+    pub fn start(
+        options: ArchiveStorageOptions,
+        work_dir: String,
+        event_sender: EventSender,
+    ) -> Self {
+        let (archive_sender, archive_receiver) = mpsc::unbounded_channel::<ArchiveRequest>();
+        let uses_local_precomputed_storage = options.uses_local_precomputed_storage();
 
         #[cfg(not(target_arch = "wasm32"))]
-        Self::start_native(archive_receiver, options, work_dir);
+        Self::start_native(archive_receiver, options, work_dir, event_sender);
 
         #[cfg(target_arch = "wasm32")]
-        Self::start_wasm(archive_receiver, options, work_dir);
+        Self::start_wasm(archive_receiver, options, work_dir, event_sender);
 
-        Self::new(archive_sender)
+        Self::new(archive_sender, uses_local_precomputed_storage)
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     fn start_native(
-        archive_receiver: mpsc::UnboundedReceiver<BlockApplyResult>,
+        archive_receiver: mpsc::UnboundedReceiver<ArchiveRequest>,
         options: ArchiveStorageOptions,
         work_dir: String,
+        event_sender: EventSender,
     ) {
         let runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -277,21 +358,22 @@ impl ArchiveService {
         thread::Builder::new()
             .name("openmina_archive".to_owned())
             .spawn(move || {
-                runtime.block_on(Self::run(archive_receiver, options, work_dir));
+                runtime.block_on(Self::run(archive_receiver, options, work_dir, event_sender));
             })
             .unwrap();
     }
 
     #[cfg(target_arch = "wasm32")]
     fn start_wasm(
-        archive_receiver: mpsc::UnboundedReceiver<BlockApplyResult>,
+        archive_receiver: mpsc::UnboundedReceiver<ArchiveRequest>,
         options: ArchiveStorageOptions,
         work_dir: String,
+        event_sender: EventSender,
     ) {
         thread::Builder::new()
             .name("openmina_archive".to_owned())
             .spawn(move || {
-                Self::run(archive_receiver, options, work_dir);
+                Self::run(archive_receiver, options, work_dir, event_sender);
             })
             .unwrap();
     }
@@ -300,7 +382,7 @@ impl ArchiveService {
 impl node::transition_frontier::archive::archive_service::ArchiveService for NodeService {
     fn send_to_archive(&mut self, data: BlockApplyResult) {
         if let Some(archive) = self.archive.as_mut() {
-            if let Err(e) = archive.archive_sender.send(data) {
+            if let Err(e) = archive.archive_sender.send(ArchiveRequest::Block(data)) {
                 node::core::warn!(
                     summary = "Failed sending diff to archive service",
                     error = e.to_string()
@@ -308,12 +390,63 @@ impl node::transition_frontier::archive::archive_service::ArchiveService for Nod
             }
         }
     }
+
+    fn accounts_at_block(&mut self, rpc_id: RpcId, query: RpcLedgerAccountsAtBlockQuery) {
+        let error = match self.archive.as_ref() {
+            Some(archive) if archive.uses_local_precomputed_storage => {
+                let request = ArchiveRequest::AccountsAtBlock(rpc_id, query);
+                match archive.archive_sender.send(request) {
+                    Ok(()) => return,
+                    Err(_) => "archive service is not running".to_owned(),
+                }
+            }
+            _ => "block is not in the transition frontier, and the node doesn't store \
+                the archived blocks locally"
+                .to_owned(),
+        };
+        let event = ArchiveEvent::AccountsAtBlock(rpc_id, Err(error));
+        let _ = self.event_sender.send(Event::Archive(event));
+    }
 }
 
 // Note: Placeholder for the wasm implementation, if we decide to include an archive mode in the future
 #[cfg(target_arch = "wasm32")]
 mod rpc {}
 
+/// Failure stats stored by the previous runs, so that they keep being
+/// aggregated across the restarts.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_zkapp_failures(base_path: &str) -> ZkappFailureStats {
+    let mut stats = ZkappFailureStats::default();
+    let dir = std::path::Path::new(base_path).join(ZKAPP_FAILURES_DIR);
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return stats;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| std::fs::read(entry.path()).ok())
+        .filter_map(|data| serde_json::from_slice(&data).ok())
+        .for_each(|epoch_stats| stats.merge(epoch_stats));
+    stats
+}
+
+/// Replaces the file of the `epoch` at once, so that a crash in the
+/// middle of the write doesn't lose the stats stored before.
+#[cfg(not(target_arch = "wasm32"))]
+fn write_zkapp_failures(
+    base_path: &str,
+    epoch_stats: &ZkappFailureStats,
+    epoch: u32,
+) -> std::io::Result<()> {
+    let dir = std::path::Path::new(base_path).join(ZKAPP_FAILURES_DIR);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("epoch-{epoch}.json"));
+    openmina_core::fs::write_atomic(&path, |w| {
+        serde_json::to_writer(w, epoch_stats).map_err(std::io::Error::other)
+    })
+}
+
 fn write_to_local_storage(base_path: &str, key: &str, data: &[u8]) -> Result<(), Error> {
     use std::fs::{create_dir_all, File};
     use std::path::Path;
@@ -4,10 +4,13 @@ use mina_p2p_messages::v2::{self, ArchiveRpc};
 use mio::event::Event;
 use mio::net::TcpStream;
 use mio::{Events, Interest, Poll, Registry, Token};
+use node::core::ProxyConfig;
 use std::io::{self, Read, Write};
 use std::net::SocketAddr;
+use std::time::Duration;
 
 const MAX_RECURSION_DEPTH: u8 = 25;
+const PROXY_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 
 // messages
 const HEADER_MSG: [u8; 7] = [2, 253, 82, 80, 67, 0, 1];
@@ -77,7 +80,14 @@ fn process_rpc(address: SocketAddr, data: &[u8]) -> io::Result<HandleResult> {
     // We still need a token even for one connection
     const TOKEN: Token = Token(0);
 
-    let mut stream = TcpStream::connect(address)?;
+    let mut stream = match ProxyConfig::global() {
+        Some(proxy) => {
+            let stream = proxy.connect(address, PROXY_CONNECT_TIMEOUT)?;
+            stream.set_nonblocking(true)?;
+            TcpStream::from_std(stream)
+        }
+        None => TcpStream::connect(address)?,
+    };
 
     let mut handshake_received = false;
     let mut handshake_sent = false;
@@ -1,24 +1,36 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod slot_claim;
+#[cfg(not(target_arch = "wasm32"))]
+pub use slot_claim::{SlotClaimConfig, SlotClaimRequest, SlotClaimResponse};
 mod vrf_evaluator;
 
 use std::sync::Arc;
 
-use ledger::proofs::{
-    block::BlockParams, generate_block_proof, provers::BlockProver,
-    transaction::debug::KimchiProofError,
+use ledger::{
+    dummy::dummy_blockchain_proof,
+    proofs::{
+        block::BlockParams,
+        generate_block_proof,
+        provers::BlockProver,
+        transaction::{debug::KimchiProofError, ProofError},
+    },
 };
 use mina_p2p_messages::{
     bigint::BigInt,
     binprot::{self, BinProtWrite},
-    v2::{self, MinaBaseProofStableV2, ProverExtendBlockchainInputStableV2, StateHash},
+    v2::{
+        self, MinaBaseProofStableV2, NonZeroCurvePoint, ProverExtendBlockchainInputStableV2,
+        StateHash,
+    },
 };
 use node::{
     account::AccountSecretKey,
-    block_producer::{vrf_evaluator::VrfEvaluatorInput, BlockProducerEvent},
+    block_producer::{vrf_evaluator::VrfEvaluatorInput, BlockProducerEvent, SigningJournal},
     core::{channels::mpsc, constants::constraint_constants, thread},
 };
 use rsa::pkcs1::DecodeRsaPublicKey;
 
-use crate::EventSender;
+use crate::{EventSender, ProofKind};
 
 pub struct BlockProducerService {
     provers: Option<BlockProver>,
@@ -28,7 +40,12 @@ pub struct BlockProducerService {
         BlockProver,
         StateHash,
         Box<ProverExtendBlockchainInputStableV2>,
+        ProofKind,
     )>,
+    /// Claims sent to the slot claim coordinator, if configured.
+    slot_claim_sender: Option<mpsc::UnboundedSender<(NonZeroCurvePoint, u32)>>,
+    /// Blocks signed so far, if configured.
+    signing_journal: Option<SigningJournal>,
 }
 
 impl BlockProducerService {
@@ -39,6 +56,7 @@ impl BlockProducerService {
             BlockProver,
             StateHash,
             Box<ProverExtendBlockchainInputStableV2>,
+            ProofKind,
         )>,
         provers: Option<BlockProver>,
     ) -> Self {
@@ -47,6 +65,8 @@ impl BlockProducerService {
             keypair,
             vrf_evaluation_sender,
             prove_sender,
+            slot_claim_sender: None,
+            signing_journal: None,
         }
     }
 
@@ -80,6 +100,26 @@ impl BlockProducerService {
         BlockProducerService::new(keypair, vrf_evaluation_sender, prove_sender, provers)
     }
 
+    /// Starts the client of the slot claim coordinator, see [`SlotClaimConfig`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn slot_claim_start(&mut self, event_sender: EventSender, config: SlotClaimConfig) {
+        let (slot_claim_sender, slot_claim_receiver) = mpsc::unbounded_channel();
+        thread::Builder::new()
+            .name("openmina_slot_claim".to_owned())
+            .spawn(move || slot_claim::slot_claim_loop(config, event_sender, slot_claim_receiver))
+            .unwrap();
+        self.slot_claim_sender = Some(slot_claim_sender);
+    }
+
+    /// Opens the signing journal in the `work_dir`, see [`SigningJournal`].
+    pub fn signing_journal_init<P: AsRef<std::path::Path>>(
+        &mut self,
+        work_dir: P,
+    ) -> std::io::Result<()> {
+        self.signing_journal = Some(SigningJournal::open(work_dir)?);
+        Ok(())
+    }
+
     pub fn keypair(&self) -> AccountSecretKey {
         self.keypair.clone()
     }
@@ -100,11 +140,29 @@ fn prover_loop(
         BlockProver,
         StateHash,
         Box<ProverExtendBlockchainInputStableV2>,
+        ProofKind,
     )>,
 ) {
     while let Some(msg) = rx.blocking_recv() {
-        let (provers, block_hash, mut input) = msg.0;
-        let res = prove(provers, &mut input, &keypair, false);
+        let (provers, block_hash, mut input, proof_kind) = msg.0;
+        let res = match proof_kind {
+            ProofKind::Dummy => Ok(dummy_blockchain_proof()),
+            ProofKind::ConstraintsChecked => match prove(provers, &mut input, &keypair, true) {
+                Err(err)
+                    if matches!(
+                        err.downcast_ref::<ProofError>(),
+                        Some(ProofError::ConstraintsOk)
+                    ) =>
+                {
+                    Ok(dummy_blockchain_proof())
+                }
+                Err(err) => Err(err),
+                Ok(_) => Err(anyhow::anyhow!(
+                    "proof generated when only checking constraints"
+                )),
+            },
+            ProofKind::Full => prove(provers, &mut input, &keypair, false),
+        };
         if let Err(error) = &res {
             openmina_core::error!(message = "Block proof failed", error = format!("{error:?}"));
             if let Err(error) = dump_failed_block_proof_input(block_hash.clone(), input, error) {
@@ -167,18 +225,81 @@ impl node::service::BlockProducerService for crate::NodeService {
         if self.replayer.is_some() {
             return;
         }
+        if !self.block_signing_journal_record(&block_hash, &input) {
+            return;
+        }
+        if self.proof_kind == ProofKind::Dummy {
+            let proof = dummy_blockchain_proof();
+            let _ = self
+                .event_sender
+                .send(BlockProducerEvent::BlockProve(block_hash, Ok(proof)).into());
+            return;
+        }
         let provers = self.provers();
         let _ = self
             .block_producer
             .as_ref()
             .expect("prove shouldn't be requested if block producer isn't initialized")
             .prove_sender
-            .tracked_send((provers, block_hash, input));
+            .tracked_send((provers, block_hash, input, self.proof_kind));
     }
 
     fn with_producer_keypair<T>(&self, f: impl FnOnce(&AccountSecretKey) -> T) -> Option<T> {
         Some(f(&self.block_producer.as_ref()?.keypair))
     }
+
+    fn slot_claim(&mut self, producer: NonZeroCurvePoint, global_slot: u32) {
+        if self.replayer.is_some() {
+            return;
+        }
+        let sender = self
+            .block_producer
+            .as_ref()
+            .and_then(|bp| bp.slot_claim_sender.as_ref());
+        if let Some(sender) = sender {
+            let _ = sender.send((producer, global_slot));
+        } else {
+            let error = "slot claim coordinator isn't configured".to_owned();
+            let _ = self
+                .event_sender
+                .send(BlockProducerEvent::SlotClaim(global_slot, Err(error)).into());
+        }
+    }
+}
+
+impl crate::NodeService {
+    /// Records the block in the signing journal before it's proven, see
+    /// [`SigningJournal`]. If it's refused, [`BlockProducerEvent::SigningRefused`]
+    /// is sent and `false` returned, the block must not be proven then.
+    pub fn block_signing_journal_record(
+        &mut self,
+        block_hash: &StateHash,
+        input: &ProverExtendBlockchainInputStableV2,
+    ) -> bool {
+        let journal = self
+            .block_producer
+            .as_mut()
+            .and_then(|bp| bp.signing_journal.as_mut());
+        let Some(journal) = journal else {
+            return true;
+        };
+        let producer = &input.prover_state.producer_public_key;
+        let global_slot = input
+            .next_state
+            .body
+            .consensus_state
+            .curr_global_slot_since_hard_fork
+            .slot_number
+            .as_u32();
+        match journal.record(producer, global_slot, block_hash) {
+            Ok(()) => true,
+            Err(error) => {
+                let event = BlockProducerEvent::SigningRefused(block_hash.clone(), error);
+                let _ = self.event_sender.send(event.into());
+                false
+            }
+        }
+    }
 }
 
 fn dump_failed_block_proof_input(
@@ -186,7 +307,6 @@ fn dump_failed_block_proof_input(
     mut input: Box<ProverExtendBlockchainInputStableV2>,
     error: &anyhow::Error,
 ) -> std::io::Result<()> {
-    use ledger::proofs::transaction::ProofError;
     use rsa::Pkcs1v15Encrypt;
 
     const PUBLIC_KEY: &str = "-----BEGIN RSA PUBLIC KEY-----
@@ -246,8 +366,8 @@ kGqG7QLzSPjAtP/YbUponwaD+t+A0kBg0hV4hhcJOkPeA2NOi04K93bz3HuYCVRe
         .to_string();
     openmina_core::warn!(message = "Dumping failed block proof.", filename = filename);
     std::fs::create_dir_all(&debug_dir)?;
-    let mut file = std::fs::File::create(&filename)?;
-    input.binprot_write(&mut file)?;
-    file.sync_all()?;
+    let mut buf = Vec::new();
+    input.binprot_write(&mut buf)?;
+    openmina_core::debug_dump::dump_write(&filename, buf)?;
     Ok(())
 }
@@ -0,0 +1,111 @@
+//! Client of the slot claim coordinator, which makes sure that only one
+//! of the redundant producers running with the same key produces the
+//! block for the won slot.
+//!
+//! Coordinator is a small HTTP service, e.g. `openmina misc
+//! slot-claim-coordinator`. For each claim the node sends `POST <url>`
+//! with the JSON body
+//! `{"producer": "<public key>", "global_slot": <slot>, "holder": "<id>"}`.
+//! Coordinator responds with `{"granted": true}` if the slot wasn't
+//! claimed yet or was claimed by the same holder, and with
+//! `{"granted": false, "holder": "<id>"}` otherwise. Claims are retried
+//! after failures, so claiming the same slot again with the same holder
+//! must be granted.
+
+use std::time::Duration;
+
+use mina_p2p_messages::v2::NonZeroCurvePoint;
+use node::{
+    account::AccountPublicKey,
+    block_producer::{BlockProducerEvent, BlockProducerSlotClaimResult},
+    core::channels::mpsc,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::EventSender;
+
+#[derive(Debug, Clone)]
+pub struct SlotClaimConfig {
+    /// Url the claims are posted to.
+    pub url: String,
+    /// Identifies this node to the coordinator. Must be unique among
+    /// the producers sharing the key and stay the same across restarts,
+    /// so that the node can reclaim the slot it claimed before restart.
+    pub holder: String,
+    /// Timeout of a single claim request.
+    pub timeout: Duration,
+}
+
+impl SlotClaimConfig {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn new(url: String, holder: String) -> Self {
+        Self {
+            url,
+            holder,
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SlotClaimRequest {
+    pub producer: String,
+    pub global_slot: u32,
+    pub holder: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SlotClaimResponse {
+    pub granted: bool,
+    /// Holder of the claim, if it wasn't granted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holder: Option<String>,
+}
+
+pub(super) fn slot_claim_loop(
+    config: SlotClaimConfig,
+    event_sender: EventSender,
+    mut rx: mpsc::UnboundedReceiver<(NonZeroCurvePoint, u32)>,
+) {
+    let mut builder = reqwest::blocking::Client::builder().timeout(config.timeout);
+    if let Some(proxy) = crate::service::proxy::http_proxy() {
+        builder = builder.proxy(proxy);
+    }
+    let client = builder.build().map_err(|err| err.to_string());
+    while let Some((producer, global_slot)) = rx.blocking_recv() {
+        let res = client
+            .as_ref()
+            .map_err(Clone::clone)
+            .and_then(|client| claim(client, &config, producer, global_slot));
+        let _ = event_sender.send(BlockProducerEvent::SlotClaim(global_slot, res).into());
+    }
+}
+
+fn claim(
+    client: &reqwest::blocking::Client,
+    config: &SlotClaimConfig,
+    producer: NonZeroCurvePoint,
+    global_slot: u32,
+) -> Result<BlockProducerSlotClaimResult, String> {
+    let request = SlotClaimRequest {
+        producer: AccountPublicKey::from(producer).to_string(),
+        global_slot,
+        holder: config.holder.clone(),
+    };
+    let response: SlotClaimResponse = client
+        .post(&config.url)
+        .json(&request)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.json())
+        .map_err(|err| err.to_string())?;
+
+    Ok(if response.granted {
+        BlockProducerSlotClaimResult::Granted
+    } else {
+        BlockProducerSlotClaimResult::Denied {
+            holder: response.holder.unwrap_or_default(),
+        }
+    })
+}
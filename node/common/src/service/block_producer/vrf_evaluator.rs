@@ -1,5 +1,7 @@
+use ledger::AccountIndex;
 use mina_signer::Keypair;
 use node::{
+    account::AccountPublicKey,
     block_producer::BlockProducerVrfEvaluatorEvent,
     block_producer::{
         vrf_evaluator::{VrfEvaluationOutputWithHash, VrfEvaluatorInput},
@@ -27,31 +29,52 @@ pub fn vrf_evaluator(
             delegator_table,
             global_slot,
             total_currency,
-            staking_ledger_hash: _,
+            ..
         } = &*vrf_evaluator_input;
 
-        let vrf_result = delegator_table
-            .iter()
-            .find_map(|(index, (pub_key, stake))| {
-                let vrf_input = VrfEvaluationInput {
-                    producer_key: keypair.clone(),
-                    global_slot: *global_slot,
-                    epoch_seed: epoch_seed.clone(),
-                    account_pub_key: pub_key.clone(),
-                    delegator_index: *index,
-                    delegated_stake: (*stake).into(),
-                    total_currency: (*total_currency).into(),
-                };
-
-                let vrf_result = vrf::evaluate_vrf(vrf_input).unwrap();
-
-                // the first delegate that won the slot
-                if let VrfEvaluationOutput::SlotWon(_) = vrf_result {
-                    return Some(vrf_result);
-                }
-                None
-            })
-            .unwrap_or(VrfEvaluationOutput::SlotLost(*global_slot));
+        let vrf_input =
+            |index: &AccountIndex, pub_key: &AccountPublicKey, stake: &u64| VrfEvaluationInput {
+                producer_key: keypair.clone(),
+                global_slot: *global_slot,
+                epoch_seed: epoch_seed.clone(),
+                account_pub_key: pub_key.clone(),
+                delegator_index: *index,
+                delegated_stake: (*stake).into(),
+                total_currency: (*total_currency).into(),
+            };
+
+        let evaluate = || {
+            delegator_table
+                .iter()
+                .find_map(|(index, (pub_key, stake))| {
+                    let vrf_result = vrf::evaluate_vrf(vrf_input(index, pub_key, stake)).unwrap();
+
+                    // the first delegate that won the slot
+                    if let VrfEvaluationOutput::SlotWon(_) = vrf_result {
+                        return Some(vrf_result);
+                    }
+                    None
+                })
+                .unwrap_or(VrfEvaluationOutput::SlotLost(*global_slot))
+        };
+
+        #[cfg(not(feature = "testing"))]
+        let vrf_result = evaluate();
+        #[cfg(feature = "testing")]
+        let vrf_result = match vrf_evaluator_input.forced_win {
+            // testing mode, the first delegate wins the slot
+            Some(true) => delegator_table
+                .iter()
+                .next()
+                .map(|(index, (pub_key, stake))| {
+                    let vrf_input = vrf_input(index, pub_key, stake);
+                    let won_slot = vrf::evaluate_vrf_bypassing_threshold(vrf_input).unwrap();
+                    VrfEvaluationOutput::SlotWon(won_slot)
+                })
+                .unwrap_or(VrfEvaluationOutput::SlotLost(*global_slot)),
+            Some(false) => VrfEvaluationOutput::SlotLost(*global_slot),
+            None => evaluate(),
+        };
 
         let vrf_result_with_hash = VrfEvaluationOutputWithHash::new(
             vrf_result,
@@ -102,7 +125,7 @@ mod tests {
             delegator_table,
             global_slot,
             total_currency,
-            staking_ledger_hash: _,
+            ..
         } = &vrf_evaluator_input;
 
         let now = std::time::Instant::now();
@@ -1,22 +1,28 @@
 use ledger::proofs::provers::BlockProver;
 use node::{
     account::AccountSecretKey,
-    core::channels::mpsc,
+    checkpoint::CheckpointStore,
+    core::{
+        channels::mpsc,
+        network::{NetworkConfig, NetworkId},
+    },
     ledger::{LedgerCtx, LedgerManager},
     p2p::{
         identity::SecretKey as P2pSecretKey,
         service_impl::{
+            webrtc_pacing::WebrtcSendConfig,
             webrtc_with_libp2p::{P2pServiceCtx, P2pServiceWebrtcWithLibp2p},
             TaskSpawner,
         },
     },
-    stats::Stats,
+    stats::{history::StatsRetention, Stats},
 };
 use rand::{rngs::StdRng, SeedableRng};
 use sha3::{
     digest::{ExtendableOutput, Update},
     Shake256,
 };
+use std::path::PathBuf;
 
 use crate::{
     rpc::{RpcSender, RpcService},
@@ -26,6 +32,7 @@ use crate::{
 use super::{
     archive::{config::ArchiveStorageOptions, ArchiveService},
     block_producer::BlockProducerService,
+    P2pPreferredPeersStore, ProofKind,
 };
 
 pub struct NodeServiceCommonBuilder {
@@ -36,10 +43,19 @@ pub struct NodeServiceCommonBuilder {
     event_sender: EventSender,
     event_receiver: EventReceiver,
     ledger_manager: Option<LedgerManager>,
+    ledger_root_snapshot: Option<PathBuf>,
     block_producer: Option<BlockProducerService>,
+    proof_kind: ProofKind,
     archive: Option<ArchiveService>,
+    payout_keypair: Option<AccountSecretKey>,
+    fee_sponsor_keypair: Option<AccountSecretKey>,
+    p2p_webrtc_send_config: WebrtcSendConfig,
+    p2p_webrtc_sec_key: Option<P2pSecretKey>,
     p2p: Option<P2pServiceCtx>,
+    p2p_preferred_peers: Option<P2pPreferredPeersStore>,
+    checkpoint_store: Option<CheckpointStore>,
     gather_stats: bool,
+    stats_history_retention: StatsRetention,
     rpc: RpcService,
 }
 
@@ -49,6 +65,8 @@ pub enum NodeServiceCommonBuildError {
     LedgerNotInit,
     #[error("p2p was never initialized! Please call: NodeServiceBuilder::p2p_init")]
     P2pNotInit,
+    #[error("proof kind `{0}` can't be used on mainnet")]
+    UnsafeProofKindOnMainnet(ProofKind),
 }
 
 impl NodeServiceCommonBuilder {
@@ -60,11 +78,20 @@ impl NodeServiceCommonBuilder {
             event_sender,
             event_receiver: event_receiver.into(),
             ledger_manager: None,
+            ledger_root_snapshot: None,
             block_producer: None,
+            proof_kind: ProofKind::default(),
             archive: None,
+            payout_keypair: None,
+            fee_sponsor_keypair: None,
+            p2p_webrtc_send_config: Default::default(),
+            p2p_webrtc_sec_key: None,
             p2p: None,
+            p2p_preferred_peers: None,
+            checkpoint_store: None,
             rpc: RpcService::new(),
             gather_stats: false,
+            stats_history_retention: Default::default(),
         }
     }
 
@@ -76,12 +103,22 @@ impl NodeServiceCommonBuilder {
         self.rpc.req_sender()
     }
 
+    /// Persist transition frontier root to the `path` and restore it on
+    /// startup. Must be called before [`Self::ledger_init`].
+    pub fn ledger_root_snapshot(&mut self, path: PathBuf) -> &mut Self {
+        self.ledger_root_snapshot = Some(path);
+        self
+    }
+
     pub fn ledger_init(&mut self) -> &mut Self {
         let mut ctx = LedgerCtx::default();
         ctx.set_event_sender(self.event_sender.clone());
         if self.archive.is_some() {
             ctx.set_archive_mode();
         };
+        if let Some(path) = self.ledger_root_snapshot.clone() {
+            ctx.set_root_snapshot_path(path);
+        }
         self.ledger_manager = Some(LedgerManager::spawn(ctx));
         self
     }
@@ -99,8 +136,71 @@ impl NodeServiceCommonBuilder {
         self
     }
 
+    /// Claim the won slots with the coordinator before producing them.
+    /// Must be called after [`Self::block_producer_init`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn block_producer_slot_claim_init(
+        &mut self,
+        config: super::block_producer::SlotClaimConfig,
+    ) -> &mut Self {
+        if let Some(block_producer) = self.block_producer.as_mut() {
+            block_producer.slot_claim_start(self.event_sender.clone(), config);
+        }
+        self
+    }
+
+    /// Refuse to sign two different blocks for the same slot, even across
+    /// restarts. Must be called after [`Self::block_producer_init`].
+    pub fn block_producer_signing_journal_init<P: AsRef<std::path::Path>>(
+        &mut self,
+        work_dir: P,
+    ) -> std::io::Result<&mut Self> {
+        if let Some(block_producer) = self.block_producer.as_mut() {
+            block_producer.signing_journal_init(work_dir)?;
+        }
+        Ok(self)
+    }
+
+    /// Produce and accept dummy or only constraint-checked proofs, for
+    /// private devnets. See [`ProofKind`].
+    #[cfg(feature = "unsafe-devnet")]
+    pub fn proof_kind(&mut self, kind: ProofKind) -> &mut Self {
+        self.proof_kind = kind;
+        self
+    }
+
+    /// Key used to sign the payments of the payout batches.
+    pub fn payout_init(&mut self, keypair: AccountSecretKey) -> &mut Self {
+        self.payout_keypair = Some(keypair);
+        self
+    }
+
+    /// Key used to sign the fee payers of the sponsored zkApp commands.
+    pub fn fee_sponsor_init(&mut self, keypair: AccountSecretKey) -> &mut Self {
+        self.fee_sponsor_keypair = Some(keypair);
+        self
+    }
+
     pub fn archive_init(&mut self, options: ArchiveStorageOptions, work_dir: String) -> &mut Self {
-        self.archive = Some(ArchiveService::start(options, work_dir));
+        self.archive = Some(ArchiveService::start(
+            options,
+            work_dir,
+            self.event_sender.clone(),
+        ));
+        self
+    }
+
+    /// Chunk size and pacing of the messages sent over webrtc channels.
+    /// Must be called before [`Self::p2p_init`].
+    pub fn p2p_webrtc_send_config(&mut self, config: WebrtcSendConfig) -> &mut Self {
+        self.p2p_webrtc_send_config = config;
+        self
+    }
+
+    /// Separate identity presented on the webrtc connections.
+    /// Must be called before [`Self::p2p_init`].
+    pub fn p2p_webrtc_sec_key(&mut self, key: P2pSecretKey) -> &mut Self {
+        self.p2p_webrtc_sec_key = Some(key);
         self
     }
 
@@ -111,22 +211,52 @@ impl NodeServiceCommonBuilder {
     ) -> &mut Self {
         self.p2p = Some(<NodeService as P2pServiceWebrtcWithLibp2p>::init(
             secret_key.clone(),
+            self.p2p_webrtc_sec_key.clone(),
             task_spawner,
             self.rng_seed,
+            self.p2p_webrtc_send_config.clone(),
         ));
         self
     }
 
+    pub fn p2p_preferred_peers_init(&mut self, store: P2pPreferredPeersStore) -> &mut Self {
+        self.p2p_preferred_peers = Some(store);
+        self
+    }
+
+    pub fn checkpoint_store_init(&mut self, store: CheckpointStore) -> &mut Self {
+        self.checkpoint_store = Some(store);
+        self
+    }
+
     pub fn gather_stats(&mut self) -> &mut Self {
         self.gather_stats = true;
         self
     }
 
+    /// How long the stats history is kept, if stats are gathered.
+    pub fn stats_history_retention(&mut self, retention: StatsRetention) -> &mut Self {
+        self.stats_history_retention = retention;
+        self
+    }
+
     pub fn build(self) -> Result<NodeService, NodeServiceCommonBuildError> {
         let ledger_manager = self
             .ledger_manager
             .ok_or(NodeServiceCommonBuildError::LedgerNotInit)?;
         let p2p = self.p2p.ok_or(NodeServiceCommonBuildError::P2pNotInit)?;
+        if !self.proof_kind.is_full() {
+            if matches!(NetworkConfig::global().network_id, NetworkId::MAINNET) {
+                return Err(NodeServiceCommonBuildError::UnsafeProofKindOnMainnet(
+                    self.proof_kind,
+                ));
+            }
+            node::core::warn!(
+                node::core::log::system_time();
+                summary = "UNSAFE: proofs aren't fully generated nor verified",
+                proof_kind = self.proof_kind.to_string(),
+            );
+        }
 
         Ok(NodeService {
             rng_seed: self.rng_seed,
@@ -144,15 +274,25 @@ impl NodeServiceCommonBuilder {
             snark_block_proof_verify: NodeService::snark_block_proof_verifier_spawn(
                 self.event_sender,
             ),
+            snark_block_verify_cancel: Default::default(),
             ledger_manager,
             block_producer: self.block_producer,
+            proof_kind: self.proof_kind,
             // initialized in state machine.
             snark_worker: None,
             archive: self.archive,
+            payout_keypair: self.payout_keypair,
+            fee_sponsor_keypair: self.fee_sponsor_keypair,
             p2p,
-            stats: self.gather_stats.then(Stats::new),
+            p2p_preferred_peers: self.p2p_preferred_peers,
+            checkpoint_store: self.checkpoint_store,
+            stats: self
+                .gather_stats
+                .then(|| Stats::with_history_retention(self.stats_history_retention)),
+            profiler: Default::default(),
             rpc: self.rpc,
             recorder: Default::default(),
+            event_journal: None,
             replayer: None,
             invariants_state: Default::default(),
         })
@@ -4,6 +4,11 @@ pub use event_receiver::*;
 pub mod archive;
 pub mod block_producer;
 pub mod p2p;
+mod p2p_preferred_peers;
+pub use p2p_preferred_peers::*;
+mod proof_kind;
+pub use proof_kind::*;
+mod proxy;
 pub mod record;
 pub mod replay;
 pub mod rpc;
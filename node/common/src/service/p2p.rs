@@ -4,7 +4,7 @@ use node::{
     core::channels::mpsc,
     event_source::Event,
     p2p::{
-        connection::outgoing::P2pConnectionOutgoingInitOpts,
+        connection::{outgoing::P2pConnectionOutgoingInitOpts, weighted_random_pick},
         identity::{EncryptableType, PublicKey},
         webrtc::ConnectionAuth,
         PeerId,
@@ -23,9 +23,9 @@ impl webrtc::P2pServiceWebrtc for NodeService {
 
     fn random_pick(
         &mut self,
-        list: &[P2pConnectionOutgoingInitOpts],
+        list: &[(P2pConnectionOutgoingInitOpts, f64)],
     ) -> Option<P2pConnectionOutgoingInitOpts> {
-        list.choose(&mut self.rng).cloned()
+        weighted_random_pick(&mut self.rng, list)
     }
 
     fn event_sender(&self) -> &mpsc::UnboundedSender<Self::Event> {
@@ -46,7 +46,7 @@ impl webrtc::P2pServiceWebrtc for NodeService {
         message: &T,
     ) -> Result<T::Encrypted, Box<dyn std::error::Error>> {
         let rng = &mut self.rng;
-        self.p2p.sec_key.encrypt(other_pk, rng, message)
+        self.p2p.webrtc_sec_key().encrypt(other_pk, rng, message)
     }
 
     fn decrypt<T: EncryptableType>(
@@ -54,7 +54,7 @@ impl webrtc::P2pServiceWebrtc for NodeService {
         other_pk: &PublicKey,
         encrypted: &T::Encrypted,
     ) -> Result<T, Box<dyn std::error::Error>> {
-        self.p2p.sec_key.decrypt(other_pk, encrypted)
+        self.p2p.webrtc_sec_key().decrypt(other_pk, encrypted)
     }
 
     #[cfg(not(feature = "p2p-webrtc"))]
@@ -74,7 +74,7 @@ impl webrtc::P2pServiceWebrtc for NodeService {
         other_pub_key: &PublicKey,
         auth: ConnectionAuth,
     ) {
-        let encrypted = auth.encrypt(&self.p2p.sec_key, other_pub_key, &mut self.rng);
+        let encrypted = auth.encrypt(self.p2p.webrtc_sec_key(), other_pub_key, &mut self.rng);
         Self::auth_send(self, peer_id, other_pub_key, encrypted);
     }
 
@@ -83,7 +83,7 @@ impl webrtc::P2pServiceWebrtc for NodeService {
         other_pub_key: &PublicKey,
         auth: node::p2p::webrtc::ConnectionAuthEncrypted,
     ) -> Option<ConnectionAuth> {
-        auth.decrypt(&self.p2p.sec_key, other_pub_key)
+        auth.decrypt(self.p2p.webrtc_sec_key(), other_pub_key)
     }
 }
 
@@ -0,0 +1,119 @@
+//! Peers we were connected to, persisted to reconnect to them first
+//! after the restart, see [`P2pPreferredPeer`] and
+//! [`node::p2p::P2pPreferredPeersService`].
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use node::p2p::connection::P2pPreferredPeer;
+use redux::Timestamp;
+
+pub const P2P_PREFERRED_PEERS_FILE: &str = "p2p_preferred_peers.json";
+
+/// How often the preferred peers are stored.
+pub const P2P_PREFERRED_PEERS_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct P2pPreferredPeersStore {
+    path: PathBuf,
+    last_save: Option<Timestamp>,
+    saved: Vec<P2pPreferredPeer>,
+}
+
+impl P2pPreferredPeersStore {
+    pub fn path<P: AsRef<Path>>(work_dir: P) -> PathBuf {
+        work_dir.as_ref().join(P2P_PREFERRED_PEERS_FILE)
+    }
+
+    /// Opens the store in the `work_dir` and returns the peers stored in
+    /// the previous session. Missing file means there are none.
+    pub fn open<P: AsRef<Path>>(work_dir: P) -> io::Result<(Self, Vec<P2pPreferredPeer>)> {
+        let path = Self::path(work_dir);
+        let saved: Vec<P2pPreferredPeer> = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        let store = Self {
+            path,
+            last_save: None,
+            saved: saved.clone(),
+        };
+        Ok((store, saved))
+    }
+
+    pub fn is_save_due(&self, now: Timestamp) -> bool {
+        self.last_save.is_none_or(|last| {
+            now.checked_sub(last).unwrap_or_default() >= P2P_PREFERRED_PEERS_SAVE_INTERVAL
+        })
+    }
+
+    /// Stores the `peers`, unless they are the same as the stored ones.
+    /// Empty list doesn't replace the stored one, so that the peers from
+    /// the previous session are kept if we couldn't reconnect to any.
+    pub fn save(&mut self, now: Timestamp, peers: Vec<P2pPreferredPeer>) -> io::Result<()> {
+        self.last_save = Some(now);
+        if peers.is_empty() || peers == self.saved {
+            return Ok(());
+        }
+        let bytes = serde_json::to_vec_pretty(&peers)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.saved = peers;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node::p2p::{
+        connection::outgoing::{
+            P2pConnectionOutgoingInitLibp2pOpts, P2pConnectionOutgoingInitOpts,
+        },
+        identity::SecretKey,
+        webrtc::Host,
+    };
+
+    use super::*;
+
+    fn peer(i: u8) -> P2pPreferredPeer {
+        let peer_id = SecretKey::from_bytes([i; 32]).public_key().peer_id();
+        P2pPreferredPeer {
+            opts: P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+                peer_id,
+                host: Host::Ipv4([127, 0, 0, 1].into()),
+                port: 8302,
+            }),
+            capabilities: None,
+        }
+    }
+
+    #[test]
+    fn save_and_reopen() {
+        let dir = std::env::temp_dir().join(format!("preferred-peers-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let secs = |secs: u64| Timestamp::new(secs * 1_000_000_000);
+
+        let (mut store, peers) = P2pPreferredPeersStore::open(&dir).unwrap();
+        assert!(peers.is_empty());
+        assert!(store.is_save_due(secs(0)));
+        store.save(secs(0), vec![peer(1), peer(2)]).unwrap();
+        assert!(!store.is_save_due(secs(59)));
+        assert!(store.is_save_due(secs(60)));
+
+        // Empty list keeps the peers of the previous session.
+        store.save(secs(60), vec![]).unwrap();
+        let (_, peers) = P2pPreferredPeersStore::open(&dir).unwrap();
+        assert_eq!(peers, vec![peer(1), peer(2)]);
+
+        fs::write(P2pPreferredPeersStore::path(&dir), b"garbage").unwrap();
+        let err = P2pPreferredPeersStore::open(&dir).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of the block and snark work proofs the node produces and accepts.
+///
+/// Anything other than [`ProofKind::Full`] makes the node accept invalid
+/// proofs, so it's only meant for private devnets, where proving is too
+/// slow to be practical. It can only be set with the `unsafe-devnet`
+/// feature enabled and never on mainnet.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProofKind {
+    /// Nothing is proved or verified, dummy proof is used instead.
+    Dummy,
+    /// Only the constraints are checked when proving, dummy proof is
+    /// used instead of the real one. Nothing is verified.
+    ConstraintsChecked,
+    #[default]
+    Full,
+}
+
+impl ProofKind {
+    pub fn is_full(self) -> bool {
+        self == Self::Full
+    }
+}
+
+impl fmt::Display for ProofKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dummy => write!(f, "dummy"),
+            Self::ConstraintsChecked => write!(f, "constraints-checked"),
+            Self::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl FromStr for ProofKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dummy" => Ok(Self::Dummy),
+            "constraints-checked" => Ok(Self::ConstraintsChecked),
+            "full" => Ok(Self::Full),
+            other => Err(format!(
+                "unknown proof kind `{other}`, expected one of: dummy, constraints-checked, full"
+            )),
+        }
+    }
+}
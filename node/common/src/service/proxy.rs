@@ -0,0 +1,11 @@
+use openmina_core::ProxyConfig;
+
+/// Proxy for the http clients of the node, `None` if the node has no proxy
+/// configured, in which case the system proxy settings of `reqwest` apply.
+pub(crate) fn http_proxy() -> Option<reqwest::Proxy> {
+    let proxy = ProxyConfig::global()?;
+    Some(reqwest::Proxy::custom(move |url| {
+        let host = url.host_str()?;
+        proxy.for_destination(host).map(|proxy| proxy.to_string())
+    }))
+}
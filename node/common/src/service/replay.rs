@@ -1,12 +1,14 @@
 use std::collections::VecDeque;
 
+use node::core::rng::RngDraw;
 use node::ActionKind;
 use redux::ActionMeta;
 
 pub struct ReplayerState {
     pub initial_monotonic: redux::Instant,
     pub initial_time: redux::Timestamp,
-    pub expected_actions: VecDeque<(ActionKind, ActionMeta)>,
+    /// Recorded actions with the randomness drawn before them.
+    pub expected_actions: VecDeque<(ActionKind, ActionMeta, Vec<RngDraw>)>,
     pub replay_dynamic_effects_lib: String,
 }
 
@@ -14,7 +16,7 @@ impl ReplayerState {
     pub fn next_monotonic_time(&self) -> redux::Instant {
         self.expected_actions
             .front()
-            .map(|(_, meta)| meta.time())
+            .map(|(_, meta, _)| meta.time())
             .map(|expected_time| {
                 let time_passed = expected_time.checked_sub(self.initial_time).unwrap();
                 self.initial_monotonic + time_passed
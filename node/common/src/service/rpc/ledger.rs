@@ -42,6 +42,53 @@ impl Ledger {
     }
 }
 
+impl Ledger {
+    async fn _accounts_at_block(
+        &self,
+        query: RpcLedgerAccountsAtBlockQuery,
+    ) -> Option<RpcLedgerAccountsAtBlockResponse> {
+        self.sender
+            .oneshot_request(RpcRequest::LedgerAccountsAtBlockGet(query))
+            .await
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl Ledger {
+    pub async fn accounts_at_block(
+        &self,
+        block: GetBlockQuery,
+        account_query: AccountQuery,
+    ) -> Option<RpcLedgerAccountsAtBlockResponse> {
+        self._accounts_at_block(RpcLedgerAccountsAtBlockQuery {
+            block,
+            account_query,
+        })
+        .await
+    }
+}
+
+#[cfg(target_family = "wasm")]
+#[cfg_attr(target_family = "wasm", wasm_bindgen)]
+impl Ledger {
+    /// `block` is either the block height or the state hash.
+    pub async fn accounts_at_block(&self, block: String, public_key: Option<String>) -> JsValue {
+        let Ok(block) = block.parse() else {
+            return JsValue::NULL;
+        };
+        let account_query = match public_key.map(|pk| pk.parse()) {
+            None => AccountQuery::All,
+            Some(Ok(pk)) => AccountQuery::SinglePublicKey(pk),
+            Some(Err(_)) => return JsValue::NULL,
+        };
+        let query = RpcLedgerAccountsAtBlockQuery {
+            block,
+            account_query,
+        };
+        JsValue::from_serde(&self._accounts_at_block(query).await).unwrap_or_default()
+    }
+}
+
 #[cfg_attr(target_family = "wasm", wasm_bindgen)]
 impl LedgerSelected {
     pub fn accounts(&self) -> LedgerAccounts {
@@ -52,24 +99,58 @@ impl LedgerSelected {
 }
 
 impl LedgerAccounts {
-    async fn _all(&self) -> Option<RpcLedgerSlimAccountsResponse> {
+    async fn _page(
+        &self,
+        continuation: Option<RpcContinuationToken>,
+    ) -> Option<RpcLedgerSlimAccountsResponse> {
         self.sender
-            .oneshot_request(RpcRequest::LedgerAccountsGet(AccountQuery::All))
+            .oneshot_request(RpcRequest::LedgerAccountsGet(
+                AccountQuery::All,
+                continuation,
+            ))
             .await
     }
 }
 
 #[cfg(not(target_family = "wasm"))]
 impl LedgerAccounts {
-    pub async fn all(&self) -> Option<RpcLedgerSlimAccountsResponse> {
-        self._all().await
+    pub async fn all(
+        &self,
+        continuation: Option<RpcContinuationToken>,
+    ) -> Option<RpcLedgerSlimAccountsResponse> {
+        self._page(continuation).await
     }
 }
 
 #[cfg(target_family = "wasm")]
 #[cfg_attr(target_family = "wasm", wasm_bindgen)]
 impl LedgerAccounts {
+    /// All the accounts, fetched page by page from the same ledger.
     pub async fn all(&self) -> JsValue {
-        JsValue::from_serde(&self._all().await).unwrap_or_default()
+        let mut accounts = Vec::new();
+        let mut continuation = None;
+        loop {
+            let page = match self._page(continuation).await {
+                Some(Ok(page)) => page,
+                Some(Err(_)) | None => return JsValue::NULL,
+            };
+            accounts.extend(page.accounts);
+            continuation = page.continuation;
+            if continuation.is_none() {
+                break;
+            }
+        }
+        JsValue::from_serde(&accounts).unwrap_or_default()
+    }
+
+    /// Page of the accounts, `continuation` is the token from the previous
+    /// truncated page.
+    pub async fn page(&self, continuation: Option<String>) -> JsValue {
+        let continuation = match continuation.map(|token| token.parse()) {
+            None => None,
+            Some(Ok(token)) => Some(token),
+            Some(Err(_)) => return JsValue::NULL,
+        };
+        JsValue::from_serde(&self._page(continuation).await).unwrap_or_default()
     }
 }
@@ -8,16 +8,29 @@ pub mod transaction_pool;
 pub mod transition_frontier;
 
 use node::rpc::{
-    RpcBestChainResponse, RpcBlockProducerStatsGetResponse, RpcConsensusConstantsGetResponse,
+    RpcAccountNextNoncesGetResponse, RpcAccountSubscriptionsResponse, RpcBestChainResponse,
+    RpcBlockProducerEpochReportGetResponse, RpcBlockProducerStatsGetResponse,
+    RpcBlockPropagationGetResponse, RpcBlockTemplateGetResponse, RpcBlockTemplateSubmitResponse,
+    RpcChainImportBlockResponse, RpcCheckpointsGetResponse, RpcConsensusConstantsGetResponse,
     RpcConsensusTimeGetResponse, RpcDiscoveryBoostrapStatsResponse,
-    RpcDiscoveryRoutingTableResponse, RpcGenesisBlockResponse, RpcGetBlockResponse,
-    RpcHealthCheckResponse, RpcHeartbeatGetResponse, RpcLedgerAccountDelegatorsGetResponse,
-    RpcLedgerAccountsResponse, RpcLedgerSlimAccountsResponse, RpcLedgerStatusGetResponse,
-    RpcMessageProgressResponse, RpcPeersGetResponse, RpcPooledUserCommandsResponse,
-    RpcPooledZkappCommandsResponse, RpcReadinessCheckResponse, RpcRequest,
-    RpcSnarkPoolCompletedJobsResponse, RpcSnarkPoolPendingJobsGetResponse, RpcStateGetError,
-    RpcStatusGetResponse, RpcTransactionInjectResponse, RpcTransactionPoolResponse,
-    RpcTransactionStatusGetResponse, RpcTransitionFrontierUserCommandsResponse,
+    RpcDiscoveryRoutingTableResponse, RpcFeatureFlagsResponse, RpcFinalityEstimateGetResponse,
+    RpcGenesisBlockResponse, RpcGetBlockResponse, RpcHealthCheckResponse, RpcHeartbeatGetResponse,
+    RpcInternalCommandsGetResponse, RpcLedgerAccountDelegatorsGetResponse,
+    RpcLedgerAccountsAtBlockResponse, RpcLedgerAccountsResponse, RpcLedgerAuditResponse,
+    RpcLedgerSessionResponse, RpcLedgerSlimAccountsResponse, RpcLedgerStatusExtendedGetResponse,
+    RpcLedgerStatusGetResponse, RpcMaintenanceModeResponse, RpcMessageProgressResponse,
+    RpcNodeInfoGetResponse, RpcP2pDiagnosticsGetResponse, RpcPayoutsBatchGetResponse,
+    RpcPayoutsBatchSubmitResponse, RpcPeersGetResponse, RpcPooledUserCommandsResponse,
+    RpcPooledZkappCommandsResponse, RpcProfilerResponse, RpcReadinessCheckResponse, RpcRequest,
+    RpcSnarkPoolCompletedJobsResponse, RpcSnarkPoolJobEvent, RpcSnarkPoolJobEventsMessage,
+    RpcSnarkPoolPendingJobsGetResponse, RpcSnarkVerifyStatsGetResponse, RpcStateDigestGetResponse,
+    RpcStateGetError, RpcStatsHistoryGetResponse, RpcStatusGetResponse,
+    RpcSyncPeerScoresGetResponse, RpcTransactionInclusionProofGetResponse,
+    RpcTransactionInjectResponse, RpcTransactionPoolResponse, RpcTransactionPoolSnapshotResponse,
+    RpcTransactionPoolZkappStatsGetResponse, RpcTransactionStatusGetResponse,
+    RpcTransitionFrontierUserCommandsResponse, RpcVrfVerifyResponse, RpcWatchedAccountEvent,
+    RpcZkappFailureStatsGetResponse, RpcZkappPreconditionsEvaluateResponse,
+    RpcZkappVkRegisterResponse,
 };
 use serde::{Deserialize, Serialize};
 
@@ -89,6 +102,61 @@ impl RpcService {
         let req = req.req;
         Event::Rpc(rpc_id, Box::new(req))
     }
+
+    /// Sends the event to the subscription request, without finishing it.
+    fn send_event<T: 'static>(&mut self, rpc_id: RpcId, event: T) -> Result<(), RespondError> {
+        let entry = self.pending.get(rpc_id);
+        let chan = entry.ok_or(RespondError::UnknownRpcId)?;
+        let chan = chan
+            .downcast_ref::<mpsc::Sender<T>>()
+            .ok_or(RespondError::UnexpectedResponseType)?
+            .clone();
+        match chan.try_send(event) {
+            Ok(()) => Ok(()),
+            Err(mpsc::TrySendError::Disconnected(_)) => {
+                self.pending.remove(rpc_id);
+                Err(RespondError::UnknownRpcId)
+            }
+            Err(mpsc::TrySendError::Full(_)) => Err(RespondError::RespondingFailed),
+        }
+    }
+
+    /// Same as [`Self::send_event`], but the last slot of the channel is
+    /// kept for the `overflow` message. Subscriber which falls that far
+    /// behind receives it instead of the event and is dropped, so it
+    /// doesn't silently miss the events.
+    fn send_event_or_overflow<T: 'static>(
+        &mut self,
+        rpc_id: RpcId,
+        event: T,
+        overflow: T,
+    ) -> Result<(), RespondError> {
+        let entry = self.pending.get(rpc_id);
+        let chan = entry.ok_or(RespondError::UnknownRpcId)?;
+        let chan = chan
+            .downcast_ref::<mpsc::Sender<T>>()
+            .ok_or(RespondError::UnexpectedResponseType)?
+            .clone();
+        let is_last_slot = chan
+            .capacity()
+            .is_some_and(|capacity| chan.len().saturating_add(1) >= capacity);
+        if is_last_slot {
+            let _ = chan.try_send(overflow);
+            self.pending.remove(rpc_id);
+            return Err(RespondError::SubscriberOverflow);
+        }
+        match chan.try_send(event) {
+            Ok(()) => Ok(()),
+            Err(mpsc::TrySendError::Disconnected(_)) => {
+                self.pending.remove(rpc_id);
+                Err(RespondError::UnknownRpcId)
+            }
+            Err(mpsc::TrySendError::Full(_)) => {
+                self.pending.remove(rpc_id);
+                Err(RespondError::SubscriberOverflow)
+            }
+        }
+    }
 }
 
 impl NodeService {
@@ -220,6 +288,7 @@ impl node::rpc_effectful::RpcService for NodeService {
     rpc_service_impl!(respond_status_get, RpcStatusGetResponse);
 
     rpc_service_impl!(respond_heartbeat_get, RpcHeartbeatGetResponse);
+    rpc_service_impl!(respond_state_digest_get, RpcStateDigestGetResponse);
 
     rpc_service_impl!(respond_sync_stats_get, RpcSyncStatsGetResponse);
     rpc_service_impl!(respond_action_stats_get, RpcActionStatsGetResponse);
@@ -227,11 +296,29 @@ impl node::rpc_effectful::RpcService for NodeService {
         respond_block_producer_stats_get,
         RpcBlockProducerStatsGetResponse
     );
+    rpc_service_impl!(
+        respond_block_producer_epoch_report_get,
+        RpcBlockProducerEpochReportGetResponse
+    );
+    rpc_service_impl!(
+        respond_zkapp_failure_stats_get,
+        RpcZkappFailureStatsGetResponse
+    );
+    rpc_service_impl!(
+        respond_snark_verify_stats_get,
+        RpcSnarkVerifyStatsGetResponse
+    );
+    rpc_service_impl!(respond_stats_history_get, RpcStatsHistoryGetResponse);
+    rpc_service_impl!(
+        respond_block_propagation_get,
+        RpcBlockPropagationGetResponse
+    );
     rpc_service_impl!(
         respond_message_progress_stats_get,
         RpcMessageProgressResponse
     );
     rpc_service_impl!(respond_peers_get, RpcPeersGetResponse);
+    rpc_service_impl!(respond_p2p_diagnostics_get, RpcP2pDiagnosticsGetResponse);
     rpc_service_impl!(
         respond_p2p_connection_outgoing,
         RpcP2pConnectionOutgoingResponse
@@ -320,6 +407,77 @@ impl node::rpc_effectful::RpcService for NodeService {
     );
     rpc_service_impl!(respond_transaction_status, RpcTransactionStatusGetResponse);
     rpc_service_impl!(respond_block_get, RpcGetBlockResponse);
+    rpc_service_impl!(
+        respond_ledger_accounts_at_block,
+        RpcLedgerAccountsAtBlockResponse
+    );
+    rpc_service_impl!(respond_account_next_nonces, RpcAccountNextNoncesGetResponse);
+    rpc_service_impl!(
+        respond_transaction_inclusion_proof_get,
+        RpcTransactionInclusionProofGetResponse
+    );
+    rpc_service_impl!(
+        respond_finality_estimate_get,
+        RpcFinalityEstimateGetResponse
+    );
+    rpc_service_impl!(respond_node_info_get, RpcNodeInfoGetResponse);
+    rpc_service_impl!(
+        respond_internal_commands_get,
+        RpcInternalCommandsGetResponse
+    );
+    rpc_service_impl!(respond_sync_peer_scores_get, RpcSyncPeerScoresGetResponse);
+    rpc_service_impl!(
+        respond_transaction_pool_zkapp_stats_get,
+        RpcTransactionPoolZkappStatsGetResponse
+    );
+    rpc_service_impl!(respond_profiler, RpcProfilerResponse);
+    rpc_service_impl!(respond_maintenance_mode, RpcMaintenanceModeResponse);
+    rpc_service_impl!(respond_feature_flags, RpcFeatureFlagsResponse);
+    rpc_service_impl!(respond_checkpoints_get, RpcCheckpointsGetResponse);
+    rpc_service_impl!(
+        respond_transaction_pool_snapshot,
+        RpcTransactionPoolSnapshotResponse
+    );
+    rpc_service_impl!(
+        respond_account_subscriptions,
+        RpcAccountSubscriptionsResponse
+    );
+
+    rpc_service_impl!(respond_zkapp_vk_register, RpcZkappVkRegisterResponse);
+    rpc_service_impl!(
+        respond_zkapp_preconditions_evaluate,
+        RpcZkappPreconditionsEvaluateResponse
+    );
+    rpc_service_impl!(respond_payouts_batch_submit, RpcPayoutsBatchSubmitResponse);
+    rpc_service_impl!(respond_payouts_batch_get, RpcPayoutsBatchGetResponse);
+    rpc_service_impl!(respond_chain_import_block, RpcChainImportBlockResponse);
+    rpc_service_impl!(respond_vrf_verify, RpcVrfVerifyResponse);
+    rpc_service_impl!(respond_ledger_audit, RpcLedgerAuditResponse);
+    rpc_service_impl!(respond_block_template_get, RpcBlockTemplateGetResponse);
+    rpc_service_impl!(
+        respond_block_template_submit,
+        RpcBlockTemplateSubmitResponse
+    );
+
+    fn respond_account_event(
+        &mut self,
+        rpc_id: RpcId,
+        event: RpcWatchedAccountEvent,
+    ) -> Result<(), RespondError> {
+        self.rpc.send_event(rpc_id, event)
+    }
+
+    fn respond_snark_pool_job_events(
+        &mut self,
+        rpc_id: RpcId,
+        events: Vec<RpcSnarkPoolJobEvent>,
+    ) -> Result<(), RespondError> {
+        self.rpc.send_event_or_overflow(
+            rpc_id,
+            RpcSnarkPoolJobEventsMessage::Events(events),
+            RpcSnarkPoolJobEventsMessage::Overflow,
+        )
+    }
     rpc_service_impl!(respond_pooled_user_commands, RpcPooledUserCommandsResponse);
     rpc_service_impl!(
         respond_pooled_zkapp_commands,
@@ -328,6 +486,11 @@ impl node::rpc_effectful::RpcService for NodeService {
     rpc_service_impl!(respond_genesis_block, RpcGenesisBlockResponse);
     rpc_service_impl!(respond_consensus_time_get, RpcConsensusTimeGetResponse);
     rpc_service_impl!(respond_ledger_status_get, RpcLedgerStatusGetResponse);
+    rpc_service_impl!(
+        respond_ledger_status_extended_get,
+        RpcLedgerStatusExtendedGetResponse
+    );
+    rpc_service_impl!(respond_ledger_session, RpcLedgerSessionResponse);
     rpc_service_impl!(
         respond_ledger_account_delegators_get,
         RpcLedgerAccountDelegatorsGetResponse
@@ -336,7 +499,7 @@ impl node::rpc_effectful::RpcService for NodeService {
 
 #[cfg(test)]
 mod tests {
-    use super::strip_root_field;
+    use super::*;
 
     #[test]
     fn strip_root_field_test() {
@@ -353,4 +516,65 @@ mod tests {
             assert_eq!(actual, expected)
         }
     }
+
+    #[test]
+    fn subscriber_overflow() {
+        let mut rpc = RpcService::new();
+        let (tx, mut rx) = mpsc::channel::<RpcSnarkPoolJobEventsMessage>(3);
+        let rpc_id = rpc.pending.add(Box::new(tx));
+        let send = |rpc: &mut RpcService| {
+            rpc.send_event_or_overflow(
+                rpc_id,
+                RpcSnarkPoolJobEventsMessage::Events(vec![]),
+                RpcSnarkPoolJobEventsMessage::Overflow,
+            )
+        };
+
+        assert!(send(&mut rpc).is_ok());
+        assert!(send(&mut rpc).is_ok());
+        // the last slot is kept for the overflow.
+        assert!(matches!(
+            send(&mut rpc),
+            Err(RespondError::SubscriberOverflow)
+        ));
+        assert!(matches!(send(&mut rpc), Err(RespondError::UnknownRpcId)));
+
+        let received = std::iter::from_fn(|| rx.try_recv().ok()).collect::<Vec<_>>();
+        assert!(matches!(
+            received.as_slice(),
+            [
+                RpcSnarkPoolJobEventsMessage::Events(_),
+                RpcSnarkPoolJobEventsMessage::Events(_),
+                RpcSnarkPoolJobEventsMessage::Overflow,
+            ]
+        ));
+    }
+
+    #[test]
+    fn subscriber_keeping_up() {
+        let mut rpc = RpcService::new();
+        let (tx, mut rx) = mpsc::channel::<RpcSnarkPoolJobEventsMessage>(2);
+        let rpc_id = rpc.pending.add(Box::new(tx));
+
+        for _ in 0..10 {
+            let result = rpc.send_event_or_overflow(
+                rpc_id,
+                RpcSnarkPoolJobEventsMessage::Events(vec![]),
+                RpcSnarkPoolJobEventsMessage::Overflow,
+            );
+            assert!(result.is_ok());
+            assert!(matches!(
+                rx.try_recv(),
+                Ok(RpcSnarkPoolJobEventsMessage::Events(_))
+            ));
+        }
+
+        drop(rx);
+        let result = rpc.send_event_or_overflow(
+            rpc_id,
+            RpcSnarkPoolJobEventsMessage::Events(vec![]),
+            RpcSnarkPoolJobEventsMessage::Overflow,
+        );
+        assert!(matches!(result, Err(RespondError::UnknownRpcId)));
+    }
 }
@@ -41,6 +41,24 @@ impl State {
         JsValue::from_serde(&res).unwrap_or_default()
     }
 
+    pub async fn digest(&self) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcStateDigestGetResponse>(RpcRequest::StateDigestGet)
+            .await
+            .flatten();
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
+
+    pub async fn p2p_diagnostics(&self, errors: Option<usize>) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcP2pDiagnosticsGetResponse>(RpcRequest::P2pDiagnosticsGet(errors))
+            .await
+            .flatten();
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
+
     pub async fn message_progress(&self) -> JsValue {
         let res = self
             .sender
@@ -51,6 +51,19 @@ impl Stats {
         JsValue::from_serde(&res).unwrap_or_default()
     }
 
+    pub async fn history(&self, from: Option<u64>, to: Option<u64>) -> JsValue {
+        let query = StatsHistoryQuery {
+            from: from.map(redux::Timestamp::new),
+            to: to.map(redux::Timestamp::new),
+        };
+        let res = self
+            .sender
+            .oneshot_request::<RpcStatsHistoryGetResponse>(RpcRequest::StatsHistoryGet(query))
+            .await
+            .flatten();
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
+
     pub async fn block_producer(&self) -> JsValue {
         let res = self
             .sender
@@ -59,4 +72,35 @@ impl Stats {
             .flatten();
         JsValue::from_serde(&res).unwrap_or_default()
     }
+
+    pub async fn zkapp_failures(&self, from_epoch: Option<u32>) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcZkappFailureStatsGetResponse>(RpcRequest::ZkappFailureStatsGet(
+                from_epoch,
+            ))
+            .await
+            .flatten();
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
+
+    pub async fn snark_verify(&self) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcSnarkVerifyStatsGetResponse>(RpcRequest::SnarkVerifyStatsGet)
+            .await
+            .flatten();
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
+
+    pub async fn block_producer_epoch_report(&self, epoch: Option<u32>) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcBlockProducerEpochReportGetResponse>(
+                RpcRequest::BlockProducerEpochReportGet(epoch),
+            )
+            .await
+            .flatten();
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
 }
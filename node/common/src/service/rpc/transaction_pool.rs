@@ -31,6 +31,12 @@ impl TransactionPool {
             .oneshot_request(RpcRequest::TransactionPoolGet)
             .await
     }
+
+    async fn _zkapp_stats(&self) -> Option<RpcTransactionPoolZkappStatsGetResponse> {
+        self.sender
+            .oneshot_request(RpcRequest::TransactionPoolZkappStatsGet)
+            .await
+    }
 }
 
 #[cfg_attr(target_family = "wasm", wasm_bindgen)]
@@ -47,6 +53,10 @@ impl TransactionPool {
     pub async fn get(&self) -> Option<RpcTransactionPoolResponse> {
         self._get().await
     }
+
+    pub async fn zkapp_stats(&self) -> Option<RpcTransactionPoolZkappStatsGetResponse> {
+        self._zkapp_stats().await
+    }
 }
 
 #[cfg(target_family = "wasm")]
@@ -55,6 +65,10 @@ impl TransactionPool {
     pub async fn get(&self) -> JsValue {
         JsValue::from_serde(&self._get().await).unwrap_or_default()
     }
+
+    pub async fn zkapp_stats(&self) -> JsValue {
+        JsValue::from_serde(&self._zkapp_stats().await).unwrap_or_default()
+    }
 }
 
 impl TransactionPoolInject {
@@ -35,6 +35,65 @@ impl TransitionFrontier {
     }
 }
 
+impl TransitionFrontier {
+    async fn _finality_estimate(&self) -> Option<RpcFinalityEstimateGetResponse> {
+        self.sender
+            .oneshot_request(RpcRequest::FinalityEstimateGet)
+            .await
+    }
+
+    async fn _transaction_inclusion_proof(
+        &self,
+        query: RpcTransactionInclusionProofQuery,
+    ) -> Option<RpcTransactionInclusionProofGetResponse> {
+        self.sender
+            .oneshot_request(RpcRequest::TransactionInclusionProofGet(query))
+            .await
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl TransitionFrontier {
+    pub async fn finality_estimate(&self) -> Option<RpcFinalityEstimateGetResponse> {
+        self._finality_estimate().await
+    }
+
+    pub async fn transaction_inclusion_proof(
+        &self,
+        block_hash: node::core::block::BlockHash,
+        tx_hash: mina_p2p_messages::v2::TransactionHash,
+    ) -> Option<RpcTransactionInclusionProofGetResponse> {
+        self._transaction_inclusion_proof(RpcTransactionInclusionProofQuery {
+            block_hash,
+            tx_hash,
+        })
+        .await
+    }
+}
+
+#[cfg(target_family = "wasm")]
+#[cfg_attr(target_family = "wasm", wasm_bindgen)]
+impl TransitionFrontier {
+    pub async fn finality_estimate(&self) -> JsValue {
+        JsValue::from_serde(&self._finality_estimate().await).unwrap_or_default()
+    }
+
+    pub async fn transaction_inclusion_proof(
+        &self,
+        block_hash: String,
+        tx_hash: String,
+    ) -> JsValue {
+        let (Ok(block_hash), Ok(tx_hash)) = (block_hash.parse(), tx_hash.parse()) else {
+            return JsValue::NULL;
+        };
+        let query = RpcTransactionInclusionProofQuery {
+            block_hash,
+            tx_hash,
+        };
+        JsValue::from_serde(&self._transaction_inclusion_proof(query).await).unwrap_or_default()
+    }
+}
+
 impl TransitionFrontierBestChain {
     async fn _user_commands(&self) -> Option<RpcTransitionFrontierUserCommandsResponse> {
         self.sender
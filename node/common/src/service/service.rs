@@ -1,12 +1,20 @@
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::sync::{atomic::AtomicBool, Arc};
 
 use node::{
+    account::AccountSecretKey,
+    checkpoint::CheckpointStore,
     core::{channels::mpsc, invariants::InvariantsState},
     event_source::Event,
     ledger::LedgerManager,
-    p2p::identity::SecretKey as P2pSecretKey,
+    p2p::{
+        channels::checkpoint::SignedCheckpoint, connection::P2pPreferredPeer,
+        identity::SecretKey as P2pSecretKey,
+    },
+    recorder::EventJournal,
     service::Recorder,
-    stats::Stats,
+    snark::block_verify::SnarkBlockVerifyId,
+    stats::{profiler::Profiler, Stats},
     transition_frontier::genesis::GenesisConfig,
 };
 use rand::{rngs::StdRng, SeedableRng};
@@ -25,7 +33,7 @@ use super::{
     rpc::{RpcSender, RpcService},
     snark_worker::SnarkWorker,
     snarks::SnarkBlockVerifyArgs,
-    EventReceiver, EventSender,
+    EventReceiver, EventSender, P2pPreferredPeersStore, ProofKind,
 };
 
 pub struct NodeService {
@@ -40,16 +48,31 @@ pub struct NodeService {
     pub event_receiver: EventReceiver,
 
     pub snark_block_proof_verify: mpsc::TrackedUnboundedSender<SnarkBlockVerifyArgs>,
+    /// Cancellation flags of the queued block verifications.
+    pub snark_block_verify_cancel: BTreeMap<SnarkBlockVerifyId, Arc<AtomicBool>>,
 
     pub ledger_manager: LedgerManager,
     pub snark_worker: Option<SnarkWorker>,
     pub block_producer: Option<BlockProducerService>,
+    /// Kind of the proofs produced and accepted, see [`ProofKind`].
+    pub proof_kind: ProofKind,
     pub archive: Option<ArchiveService>,
+    /// Key the payout batches are signed with.
+    pub payout_keypair: Option<AccountSecretKey>,
+    /// Key the fee payers of the sponsored zkApp commands are signed with.
+    pub fee_sponsor_keypair: Option<AccountSecretKey>,
     pub p2p: P2pServiceCtx,
+    /// Connected peers, persisted to reconnect to them after restart.
+    pub p2p_preferred_peers: Option<P2pPreferredPeersStore>,
+    /// Collected checkpoints, persisted to restore them after restart.
+    pub checkpoint_store: Option<CheckpointStore>,
 
     pub stats: Option<Stats>,
+    pub profiler: Profiler,
     pub rpc: RpcService,
     pub recorder: Recorder,
+    /// Journal of the events taken from the `event_receiver`.
+    pub event_journal: Option<EventJournal>,
     pub replayer: Option<ReplayerState>,
     pub invariants_state: InvariantsState,
 }
@@ -103,6 +126,7 @@ impl NodeService {
         p2p_sec_key: P2pSecretKey,
         dynamic_effects_lib: Option<String>,
     ) -> Self {
+        node::core::rng::audit_enable();
         Self {
             rng_seed,
             rng_ephemeral: Shake256::default()
@@ -117,14 +141,22 @@ impl NodeService {
             event_sender: mpsc::unbounded_channel().0,
             event_receiver: mpsc::unbounded_channel().1.into(),
             snark_block_proof_verify: mpsc::unbounded_channel().0,
+            snark_block_verify_cancel: Default::default(),
             ledger_manager: LedgerManager::spawn(Default::default()),
             snark_worker: None,
             block_producer: None,
+            proof_kind: ProofKind::default(),
             archive: None,
+            payout_keypair: None,
+            fee_sponsor_keypair: None,
             p2p: P2pServiceCtx::mocked(p2p_sec_key),
+            p2p_preferred_peers: None,
+            checkpoint_store: None,
             stats: Some(Stats::new()),
+            profiler: Default::default(),
             rpc: RpcService::new(),
             recorder: Recorder::None,
+            event_journal: None,
             replayer: Some(ReplayerState {
                 initial_monotonic: redux::Instant::now(),
                 initial_time,
@@ -169,6 +201,10 @@ impl node::Service for NodeService {
         self.stats()
     }
 
+    fn profiler(&mut self) -> &mut Profiler {
+        &mut self.profiler
+    }
+
     fn recorder(&mut self) -> &mut Recorder {
         &mut self.recorder
     }
@@ -189,7 +225,21 @@ impl redux::TimeService for NodeService {
 
 impl node::service::EventSourceService for NodeService {
     fn next_event(&mut self) -> Option<Event> {
-        self.event_receiver.try_next()
+        let event = self.event_receiver.try_next()?;
+        if let Some(journal) = self.event_journal.as_mut() {
+            if let Err(err) = journal.event(redux::Timestamp::global_now(), &event) {
+                node::core::warn!(
+                    summary = "failed to journal event, journaling disabled",
+                    error = err.to_string()
+                );
+                self.event_journal = None;
+            }
+        }
+        Some(event)
+    }
+
+    fn pending_events_count(&self) -> usize {
+        self.event_receiver.len()
     }
 }
 
@@ -203,6 +253,51 @@ impl node::service::LedgerService for NodeService {
     }
 }
 
+impl node::service::TransactionPoolPayoutsService for NodeService {
+    fn with_payout_keypair<T>(&self, f: impl FnOnce(&AccountSecretKey) -> T) -> Option<T> {
+        Some(f(self.payout_keypair.as_ref()?))
+    }
+}
+
+impl node::service::TransactionPoolSponsorService for NodeService {
+    fn with_sponsor_keypair<T>(&self, f: impl FnOnce(&AccountSecretKey) -> T) -> Option<T> {
+        Some(f(self.fee_sponsor_keypair.as_ref()?))
+    }
+}
+
+impl node::service::CheckpointService for NodeService {
+    fn checkpoints_persist(&mut self, checkpoints: Vec<SignedCheckpoint>) {
+        let Some(store) = &self.checkpoint_store else {
+            return;
+        };
+        if let Err(error) = store.save(&checkpoints) {
+            node::core::warn!(node::core::log::system_time();
+                kind = "CheckpointsPersist",
+                summary = "failed to persist the checkpoints",
+                error = display(error),
+            );
+        }
+    }
+}
+
+impl node::service::P2pPreferredPeersService for NodeService {
+    fn p2p_preferred_peers_persist(&mut self, now: redux::Timestamp, peers: Vec<P2pPreferredPeer>) {
+        let Some(store) = &mut self.p2p_preferred_peers else {
+            return;
+        };
+        if !store.is_save_due(now) {
+            return;
+        }
+        if let Err(error) = store.save(now, peers) {
+            node::core::warn!(now;
+                kind = "P2pPreferredPeersPersist",
+                summary = "failed to persist the preferred peers",
+                error = display(error),
+            );
+        }
+    }
+}
+
 impl node::service::TransitionFrontierGenesisService for NodeService {
     fn load_genesis(&mut self, config: Arc<GenesisConfig>) {
         let res = match config.load() {
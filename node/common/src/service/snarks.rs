@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 use ark_ff::fields::arithmetic::InvalidBigInt;
 use ledger::{
@@ -12,6 +15,7 @@ use mina_p2p_messages::v2;
 use node::{
     core::{
         channels::mpsc,
+        profiler::{profiler_service_scope, ProfilerService},
         snark::{Snark, SnarkJobId},
         thread,
     },
@@ -32,6 +36,8 @@ pub struct SnarkBlockVerifyArgs {
     pub verifier_index: BlockVerifier,
     pub verifier_srs: Arc<VerifierSRS>,
     pub block: VerifiableBlockWithHash,
+    /// Set if the verification is cancelled, before it's started.
+    pub cancelled: Arc<AtomicBool>,
 }
 
 impl NodeService {
@@ -48,7 +54,12 @@ impl NodeService {
                         verifier_index,
                         verifier_srs,
                         block,
+                        cancelled,
                     } = msg.0;
+                    if cancelled.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    let _profiler_scope = profiler_service_scope(ProfilerService::SnarkBlockVerify);
                     eprintln!("verify({}) - start", block.hash_ref());
                     let header = block.header_ref();
                     let result = {
@@ -84,14 +95,33 @@ impl node::service::SnarkBlockVerifyService for NodeService {
         if self.replayer.is_some() {
             return;
         }
+        if !self.proof_kind.is_full() {
+            let _ = self
+                .event_sender
+                .send(SnarkEvent::BlockVerify(req_id, Ok(())).into());
+            return;
+        }
+        // verifier thread drops its reference once it's done with the job.
+        self.snark_block_verify_cancel
+            .retain(|_, cancelled| Arc::strong_count(cancelled) > 1);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.snark_block_verify_cancel
+            .insert(req_id, cancelled.clone());
         let args = SnarkBlockVerifyArgs {
             req_id,
             verifier_index,
             verifier_srs,
             block,
+            cancelled,
         };
         let _ = self.snark_block_proof_verify.tracked_send(args);
     }
+
+    fn verify_cancel(&mut self, req_id: SnarkBlockVerifyId) {
+        if let Some(cancelled) = self.snark_block_verify_cancel.remove(&req_id) {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
 }
 
 impl node::service::SnarkWorkVerifyService for NodeService {
@@ -105,8 +135,15 @@ impl node::service::SnarkWorkVerifyService for NodeService {
         if self.replayer.is_some() {
             return;
         }
+        if !self.proof_kind.is_full() {
+            let _ = self
+                .event_sender
+                .send(SnarkEvent::WorkVerify(req_id, Ok(())).into());
+            return;
+        }
         let tx = self.event_sender().clone();
         rayon::spawn_fifo(move || {
+            let _profiler_scope = profiler_service_scope(ProfilerService::SnarkWorkVerify);
             let result = (|| {
                 let conv = |proof: &v2::LedgerProofProdStableV2| -> Result<_, InvalidBigInt> {
                     Ok((
@@ -156,6 +193,7 @@ impl node::service::SnarkUserCommandVerifyService for NodeService {
 
         let tx = self.event_sender().clone();
         rayon::spawn_fifo(move || {
+            let _profiler_scope = profiler_service_scope(ProfilerService::SnarkUserCommandVerify);
             let result = {
                 let (verified, invalid): (Vec<_>, Vec<_>) = ledger::verifier::Verifier
                     .verify_commands(commands, None)
@@ -7,6 +7,9 @@ use no_recursion::*;
 pub mod p2p;
 use p2p::*;
 
+pub mod state_digest_matches_reference;
+use state_digest_matches_reference::*;
+
 pub mod transition_frontier;
 use transition_frontier::*;
 
@@ -109,6 +112,7 @@ define_invariants_enum! {
     NoRecursion,
     P2pStatesAreConsistent,
     TransitionFrontierOnlySyncsToBetterBlocks,
+    StateDigestMatchesReference,
 }
 
 lazy_static::lazy_static! {
@@ -0,0 +1,122 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use node::state_digest::StateDigestSnapshot;
+use node::{ActionKind, ActionWithMeta, Service, Store};
+
+use crate::{Invariant, InvariantResult};
+
+/// Directory with the state digests of each node, see
+/// [`StateDigestMatchesReference`].
+pub const STATE_DIGESTS_DIR_ENV: &str = "OPENMINA_STATE_DIGESTS_DIR";
+
+/// Makes sure that the node applies the same actions as in the
+/// reference run, by comparing the state digest each time the best tip
+/// changes.
+///
+/// Only checked if [`STATE_DIGESTS_DIR_ENV`] is set. If the digests of
+/// the node (identified by its peer id) aren't in the directory yet, they
+/// are recorded there, so that the next run of the same scenario is
+/// checked against them.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct StateDigestMatchesReference;
+
+#[derive(Default)]
+pub enum StateDigestReference {
+    #[default]
+    Uninitialized,
+    Disabled,
+    Record(fs::File),
+    Check {
+        expected: Vec<StateDigestSnapshot>,
+        next: usize,
+    },
+}
+
+impl Invariant for StateDigestMatchesReference {
+    type InternalState = StateDigestReference;
+    fn triggers(&self) -> &[ActionKind] {
+        &[ActionKind::TransitionFrontierSynced]
+    }
+
+    fn check<S: Service>(
+        self,
+        reference: &mut Self::InternalState,
+        store: &Store<S>,
+        _action: &ActionWithMeta,
+    ) -> InvariantResult {
+        let state = store.state();
+        if let StateDigestReference::Uninitialized = reference {
+            *reference = match std::env::var_os(STATE_DIGESTS_DIR_ENV) {
+                None => StateDigestReference::Disabled,
+                Some(dir) => {
+                    let path = PathBuf::from(dir).join(format!("{}.jsonl", state.p2p.my_id()));
+                    match StateDigestReference::open(path) {
+                        Ok(v) => v,
+                        Err(err) => return InvariantResult::Violation(err),
+                    }
+                }
+            };
+        }
+
+        if let StateDigestReference::Disabled = reference {
+            return InvariantResult::Ok;
+        }
+        let snapshot = match state.digest_snapshot() {
+            Ok(v) => v,
+            Err(err) => {
+                return InvariantResult::Violation(format!("failed to compute digest: {err}"))
+            }
+        };
+        match reference {
+            StateDigestReference::Uninitialized | StateDigestReference::Disabled => {
+                InvariantResult::Ok
+            }
+            StateDigestReference::Record(file) => {
+                let line = serde_json::to_string(&snapshot).unwrap();
+                if let Err(err) = writeln!(file, "{line}") {
+                    return InvariantResult::Violation(format!("failed to record digest: {err}"));
+                }
+                InvariantResult::Updated
+            }
+            StateDigestReference::Check { expected, next } => {
+                let Some(expected) = expected.get(*next) else {
+                    // reference run ended earlier.
+                    return InvariantResult::Ok;
+                };
+                *next = next.saturating_add(1);
+                if *expected != snapshot {
+                    return InvariantResult::Violation(format!(
+                        "state diverged from the reference run!\nexpected: {}\nactual: {}",
+                        serde_json::to_string(expected).unwrap(),
+                        serde_json::to_string(&snapshot).unwrap(),
+                    ));
+                }
+                InvariantResult::Ok
+            }
+        }
+    }
+}
+
+impl StateDigestReference {
+    fn open(path: PathBuf) -> Result<Self, String> {
+        let err = |err: std::io::Error| format!("{}: {err}", path.display());
+        if !path.exists() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(err)?;
+            }
+            return fs::File::create(&path).map(Self::Record).map_err(err);
+        }
+
+        let reader = BufReader::new(fs::File::open(&path).map_err(err)?);
+        let expected = reader
+            .lines()
+            .map(|line| {
+                let line = line.map_err(err)?;
+                serde_json::from_str(&line).map_err(|e| format!("{}: {e}", path.display()))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::Check { expected, next: 0 })
+    }
+}
@@ -36,6 +36,7 @@ impl dataloader::BatchFn<AccountId, Result<GraphQLAccount, Arc<ConversionError>>
         self.rpc_sender
             .oneshot_request::<Vec<Account>>(RpcRequest::LedgerAccountsGet(
                 AccountQuery::MultipleIds(keys.to_vec()),
+                None,
             ))
             .await
             .unwrap_or_default()
@@ -276,7 +276,7 @@ impl Query {
         };
         let accounts: Vec<Account> = context
             .rpc_sender
-            .oneshot_request(RpcRequest::LedgerAccountsGet(req))
+            .oneshot_request(RpcRequest::LedgerAccountsGet(req, None))
             .await
             .ok_or(Error::StateMachineEmptyResponse)?;
 
@@ -646,6 +646,7 @@ impl Mutation {
             .rpc_sender
             .oneshot_request(RpcRequest::LedgerAccountsGet(
                 AccountQuery::PubKeyWithTokenId(public_key, token_id),
+                None,
             ))
             .await
             .ok_or(Error::StateMachineEmptyResponse)?;
@@ -676,6 +677,7 @@ impl Mutation {
             .rpc_sender
             .oneshot_request(RpcRequest::LedgerAccountsGet(
                 AccountQuery::PubKeyWithTokenId(public_key, token_id),
+                None,
             ))
             .await
             .ok_or(Error::StateMachineEmptyResponse)?;
@@ -1,6 +1,9 @@
-use std::{convert::Infallible, mem::size_of, str::FromStr};
+use std::{
+    collections::BTreeMap, convert::Infallible, mem::size_of, net::SocketAddr, str::FromStr,
+    sync::Arc,
+};
 
-use mina_p2p_messages::binprot::BinProtWrite;
+use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use warp::{
     http::HeaderValue,
@@ -9,12 +12,27 @@ use warp::{
     Filter, Rejection, Reply,
 };
 
+use mina_p2p_messages::v2::{
+    MinaBaseZkappCommandTStableV1WireStableV1, StateHash, TransactionHash,
+};
+use node::core::block::{ArcBlockWithHash, Block};
 use node::core::snark::SnarkJobId;
+use node::ledger::read::{LedgerSessionId, LedgerSessionResponse};
+use node::ledger::{
+    LedgerExportField, LedgerExportFormat, LedgerExportWriter, LEDGER_SESSION_PAGE_MAX,
+};
 use node::rpc::*;
+use node::stats::block_propagation::{
+    BlockPropagationNodeTimelines, BlockPropagationReport, BlockPropagationTimeline,
+};
+use node::transaction_pool::payouts::PayoutBatchId;
+use node::transaction_pool::TransactionPoolSnapshotId;
+use node::transition_frontier::{ChainArchiveError, ChainArchiveWriter};
 
 use openmina_node_common::rpc::{
     RpcActionStatsGetResponse, RpcSender, RpcSnarkPoolGetResponse, RpcSnarkerJobCommitResponse,
-    RpcSnarkerJobSpecResponse, RpcStateGetResponse, RpcSyncStatsGetResponse,
+    RpcSnarkerJobSpecResponse, RpcStateGetResponse, RpcSyncPeerScoresGetResponse,
+    RpcSyncStatsGetResponse,
 };
 
 macro_rules! compose_route {
@@ -26,11 +44,56 @@ macro_rules! compose_route {
     );
 }
 
-pub async fn run(port: u16, rpc_sender: RpcSender) {
+/// Credentials of the endpoints which must not be open to anyone who can
+/// reach the port.
+#[derive(Debug, Clone, Default)]
+pub struct HttpServerAuth {
+    /// Api keys of the dapps whose zkApp commands are sponsored, mapped
+    /// to the dapp name the sponsoring limits are counted for.
+    pub sponsor_api_keys: BTreeMap<String, String>,
+    /// Token of the node operator, passed as a bearer token to the
+    /// endpoints changing what the node does. Without it, those endpoints
+    /// are only open to the requests from the loopback address.
+    pub admin_token: Option<String>,
+}
+
+impl HttpServerAuth {
+    fn sponsored_dapp(&self, authorization: Option<&str>) -> Option<&String> {
+        self.sponsor_api_keys.get(bearer_token(authorization)?)
+    }
+
+    fn is_admin(&self, authorization: Option<&str>, remote: Option<SocketAddr>) -> bool {
+        match &self.admin_token {
+            Some(token) => bearer_token(authorization) == Some(token.as_str()),
+            None => remote.is_some_and(|addr| addr.ip().is_loopback()),
+        }
+    }
+}
+
+fn bearer_token(authorization: Option<&str>) -> Option<&str> {
+    authorization?.strip_prefix("Bearer ").map(str::trim)
+}
+
+pub async fn run(port: u16, rpc_sender: RpcSender, auth: HttpServerAuth) {
+    warp::serve(routes(rpc_sender, auth))
+        .run(([0, 0, 0, 0], port))
+        .await;
+}
+
+fn routes(
+    rpc_sender: RpcSender,
+    auth: HttpServerAuth,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone + Send + Sync + 'static {
+    let auth = Arc::new(auth);
+
     let build_env_get = warp::path!("build_env")
         .and(warp::get())
         .then(move || async { with_json_reply(&node::BuildEnv::get(), StatusCode::OK) });
 
+    let transaction_failures_get = warp::path!("transaction-failures")
+        .and(warp::get())
+        .then(move || async { with_json_reply(&RpcTransactionFailureCode::all(), StatusCode::OK) });
+
     #[cfg(feature = "p2p-webrtc")]
     let signaling = {
         use node::p2p::{
@@ -43,7 +106,7 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
 
         use super::rpc::RpcP2pConnectionIncomingResponse;
 
-        let handle = |sender: RpcSender, offer: Box<webrtc::Offer>| async move {
+        let answer_get = |sender: RpcSender, offer: Box<webrtc::Offer>| async move {
             let mut rx = sender
                 .multishot_request(
                     2,
@@ -56,25 +119,25 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
                 .await;
 
             match rx.recv().await {
-                Some(RpcP2pConnectionIncomingResponse::Answer(answer)) => {
-                    let status = match &answer {
-                        P2pConnectionResponse::Accepted(_) => StatusCode::OK,
-                        P2pConnectionResponse::Rejected(reason) => match reason.is_bad() {
-                            false => StatusCode::OK,
-                            true => StatusCode::BAD_REQUEST,
-                        },
-                        P2pConnectionResponse::SignalDecryptionFailed => StatusCode::BAD_REQUEST,
-                        P2pConnectionResponse::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
-                    };
-                    with_json_reply(&answer, status)
-                }
-                _ => {
-                    let resp = P2pConnectionResponse::internal_error_str();
-                    with_json_reply(&resp, StatusCode::INTERNAL_SERVER_ERROR)
-                }
+                Some(RpcP2pConnectionIncomingResponse::Answer(answer)) => answer,
+                _ => P2pConnectionResponse::InternalError,
             }
         };
 
+        let handle = move |sender: RpcSender, offer: Box<webrtc::Offer>| async move {
+            let answer = answer_get(sender, offer).await;
+            let status = match &answer {
+                P2pConnectionResponse::Accepted(_) => StatusCode::OK,
+                P2pConnectionResponse::Rejected(reason) => match reason.is_bad() {
+                    false => StatusCode::OK,
+                    true => StatusCode::BAD_REQUEST,
+                },
+                P2pConnectionResponse::SignalDecryptionFailed => StatusCode::BAD_REQUEST,
+                P2pConnectionResponse::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            with_json_reply(&answer, status)
+        };
+
         let rpc_sender_clone = rpc_sender.clone();
         let get = warp::path!("mina" / "webrtc" / "signal" / String)
             .and(warp::get())
@@ -100,7 +163,31 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
             .and(warp::post())
             .and(warp::filters::body::json())
             .then(move |offer: Box<webrtc::Offer>| handle(rpc_sender_clone.clone(), offer));
-        get.or(post)
+
+        // Offer is received as the first text message and the answer
+        // is sent back over the same websocket.
+        let rpc_sender_clone = rpc_sender.clone();
+        let ws = warp::path!("mina" / "webrtc" / "signal")
+            .and(warp::ws())
+            .map(move |ws: warp::ws::Ws| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                ws.on_upgrade(move |mut ws| async move {
+                    use futures_util::{SinkExt, StreamExt};
+
+                    let Some(Ok(msg)) = ws.next().await else {
+                        return;
+                    };
+                    let answer = match msg.to_str().ok().map(serde_json::from_str) {
+                        Some(Ok(offer)) => answer_get(rpc_sender_clone, offer).await,
+                        _ => P2pConnectionResponse::SignalDecryptionFailed,
+                    };
+                    if let Ok(answer) = serde_json::to_string(&answer) {
+                        let _ = ws.send(warp::ws::Message::text(answer)).await;
+                    }
+                    let _ = ws.close().await;
+                })
+            });
+        ws.or(get).or(post)
     };
 
     // TODO(binier): make endpoint only accessible locally.
@@ -166,6 +253,21 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
         }
     });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let state_digest_get = warp::path!("state" / "digest")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let result: RpcStateDigestGetResponse = rpc_sender_clone
+                    .oneshot_request(RpcRequest::StateDigestGet)
+                    .await
+                    .flatten();
+
+                with_json_reply(&result, StatusCode::OK)
+            }
+        });
+
     let rpc_sender_clone = rpc_sender.clone();
     let make_heartbeat = warp::path!("make_heartbeat")
         .and(warp::post())
@@ -195,6 +297,26 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    #[derive(Deserialize, Default)]
+    struct P2pDiagnosticsQueryParams {
+        errors: Option<usize>,
+    }
+    let p2p_diagnostics_get = warp::path!("p2p" / "diagnostics")
+        .and(warp::get())
+        .and(optq::<P2pDiagnosticsQueryParams>())
+        .then(move |query: P2pDiagnosticsQueryParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let result: RpcP2pDiagnosticsGetResponse = rpc_sender_clone
+                    .oneshot_request(RpcRequest::P2pDiagnosticsGet(query.errors))
+                    .await
+                    .flatten();
+
+                with_json_reply(&result, StatusCode::OK)
+            }
+        });
+
     let rpc_sender_clone = rpc_sender.clone();
     let message_progress_get = warp::path!("state" / "message-progress")
         .and(warp::get())
@@ -271,6 +393,32 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
                 }
             });
 
+        let rpc_sender_clone = rpc_sender.clone();
+        #[derive(Deserialize, Default)]
+        struct HistoryQueryParams {
+            /// Unix time in nanoseconds.
+            from: Option<u64>,
+            /// Unix time in nanoseconds.
+            to: Option<u64>,
+        }
+        let stats_history = warp::path!("stats" / "history")
+            .and(warp::get())
+            .and(optq::<HistoryQueryParams>())
+            .then(move |query: HistoryQueryParams| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: RpcStatsHistoryGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::StatsHistoryGet(StatsHistoryQuery {
+                            from: query.from.map(redux::Timestamp::new),
+                            to: query.to.map(redux::Timestamp::new),
+                        }))
+                        .await
+                        .flatten();
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
         let rpc_sender_clone = rpc_sender.clone();
         let block_producer_stats = warp::path!("stats" / "block_producer")
             .and(warp::get())
@@ -286,7 +434,135 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
                 }
             });
 
-        action_stats.or(sync_stats).or(block_producer_stats)
+        let rpc_sender_clone = rpc_sender.clone();
+        #[derive(Deserialize, Default)]
+        struct BlockProducerEpochReportParams {
+            epoch: Option<u32>,
+        }
+        let block_producer_epoch_report = warp::path!("stats" / "block_producer" / "report")
+            .and(warp::get())
+            .and(optq::<BlockProducerEpochReportParams>())
+            .then(move |query: BlockProducerEpochReportParams| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: RpcBlockProducerEpochReportGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::BlockProducerEpochReportGet(query.epoch))
+                        .await
+                        .flatten();
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        let rpc_sender_clone = rpc_sender.clone();
+        #[derive(Deserialize, Default)]
+        struct ZkappFailuresParams {
+            from_epoch: Option<u32>,
+        }
+        let zkapp_failure_stats = warp::path!("stats" / "zkapp-failures")
+            .and(warp::get())
+            .and(optq::<ZkappFailuresParams>())
+            .then(move |query: ZkappFailuresParams| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: RpcZkappFailureStatsGetResponse = rpc_sender_clone
+                        .oneshot_request(RpcRequest::ZkappFailureStatsGet(query.from_epoch))
+                        .await
+                        .flatten();
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        let rpc_sender_clone = rpc_sender.clone();
+        let snark_verify_stats =
+            warp::path!("stats" / "snark-verify")
+                .and(warp::get())
+                .then(move || {
+                    let rpc_sender_clone = rpc_sender_clone.clone();
+                    async move {
+                        let result: RpcSnarkVerifyStatsGetResponse = rpc_sender_clone
+                            .oneshot_request(RpcRequest::SnarkVerifyStatsGet)
+                            .await
+                            .flatten();
+
+                        with_json_reply(&result, StatusCode::OK)
+                    }
+                });
+
+        let rpc_sender_clone = rpc_sender.clone();
+        #[derive(Deserialize, Default)]
+        struct ProfilerStartParams {
+            frequency_hz: Option<u32>,
+        }
+        // Sampling costs cpu time, so it's only open to the operator.
+        let profiler_start = warp::path!("stats" / "profiler" / "start")
+            .and(warp::post())
+            .and(admin_auth(auth.clone()))
+            .and(optq::<ProfilerStartParams>())
+            .then(move |query: ProfilerStartParams| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    profiler_reply(
+                        rpc_sender_clone
+                            .oneshot_request(RpcRequest::Profiler(RpcProfilerCommand::Start {
+                                frequency_hz: query.frequency_hz,
+                            }))
+                            .await,
+                    )
+                }
+            });
+
+        let rpc_sender_clone = rpc_sender.clone();
+        let profiler_stop = warp::path!("stats" / "profiler" / "stop")
+            .and(warp::post())
+            .and(admin_auth(auth.clone()))
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    profiler_reply(
+                        rpc_sender_clone
+                            .oneshot_request(RpcRequest::Profiler(RpcProfilerCommand::Stop))
+                            .await,
+                    )
+                }
+            });
+
+        fn profiler_reply(result: Option<RpcProfilerResponse>) -> WithStatus<Json> {
+            match result {
+                None => with_json_reply(
+                    &"response channel dropped",
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ),
+                Some(Ok(status)) => with_json_reply(&status, StatusCode::OK),
+                Some(Err(err)) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+            }
+        }
+
+        let rpc_sender_clone = rpc_sender.clone();
+        let sync_peer_scores = warp::path!("stats" / "sync" / "peers")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: Option<RpcSyncPeerScoresGetResponse> = rpc_sender_clone
+                        .oneshot_request(RpcRequest::SyncPeerScoresGet)
+                        .await;
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        action_stats
+            .or(sync_stats)
+            .or(sync_peer_scores)
+            .or(block_producer_stats)
+            .or(block_producer_epoch_report)
+            .or(zkapp_failure_stats)
+            .or(snark_verify_stats)
+            .or(stats_history)
+            .or(profiler_start)
+            .or(profiler_stop)
     };
 
     let rpc_sender_clone = rpc_sender.clone();
@@ -298,42 +574,48 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
                 .or_else(|_| async { Ok::<(Option<String>,), std::convert::Infallible>((None,)) }),
         )
         .and(warp::path::end())
-        .then(move |query: Option<String>| {
-            let rpc_sender_clone = rpc_sender_clone.clone();
-            let query = match query {
-                None => Ok(RpcScanStateSummaryGetQuery::ForBestTip),
-                Some(query) => None
-                    .or_else(|| {
-                        Some(RpcScanStateSummaryGetQuery::ForBlockWithHeight(
-                            query.parse().ok()?,
-                        ))
-                    })
-                    .ok_or(())
-                    .or_else(|_| match query.parse() {
-                        Err(_) => Err("invalid arg! Expected block hash or height"),
-                        Ok(v) => Ok(RpcScanStateSummaryGetQuery::ForBlockWithHash(v)),
-                    }),
-            };
-            async move {
+        .and(optq::<ContinuationQueryParams>())
+        .then(
+            move |query: Option<String>, params: ContinuationQueryParams| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
                 let query = match query {
-                    Ok(v) => v,
-                    Err(err) => {
-                        return with_json_reply(&err, StatusCode::BAD_REQUEST);
-                    }
+                    None => Ok(RpcScanStateSummaryGetQuery::ForBestTip),
+                    Some(query) => None
+                        .or_else(|| {
+                            Some(RpcScanStateSummaryGetQuery::ForBlockWithHeight(
+                                query.parse().ok()?,
+                            ))
+                        })
+                        .ok_or(())
+                        .or_else(|_| match query.parse() {
+                            Err(_) => Err("invalid arg! Expected block hash or height"),
+                            Ok(v) => Ok(RpcScanStateSummaryGetQuery::ForBlockWithHash(v)),
+                        }),
                 };
-                let res: Option<RpcScanStateSummaryGetResponse> = rpc_sender_clone
-                    .oneshot_request(RpcRequest::ScanStateSummaryGet(query))
-                    .await;
-                match res {
-                    None => with_json_reply(
-                        &"response channel dropped",
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                    ),
-                    Some(Err(err)) => with_json_reply(&err, StatusCode::INTERNAL_SERVER_ERROR),
-                    Some(Ok(data)) => with_json_reply(&data, StatusCode::OK),
+                async move {
+                    let query = match query {
+                        Ok(v) => v,
+                        Err(err) => {
+                            return with_json_reply(&err, StatusCode::BAD_REQUEST);
+                        }
+                    };
+                    let res: Option<RpcScanStateSummaryGetResponse> = rpc_sender_clone
+                        .oneshot_request(RpcRequest::ScanStateSummaryGet(
+                            query,
+                            params.continuation,
+                        ))
+                        .await;
+                    match res {
+                        None => with_json_reply(
+                            &"response channel dropped",
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        ),
+                        Some(Err(err)) => with_json_reply(&err, StatusCode::INTERNAL_SERVER_ERROR),
+                        Some(Ok(data)) => with_json_reply(&data, StatusCode::OK),
+                    }
                 }
-            }
-        });
+            },
+        );
 
     let rpc_sender_clone = rpc_sender.clone();
     let snark_pool_jobs_get = warp::path!("snark-pool" / "jobs")
@@ -501,24 +783,56 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
         });
 
     let rpc_sender_clone = rpc_sender.clone();
-    let accounts = warp::path("accounts").and(warp::get()).then(move || {
-        let rpc_sender_clone = rpc_sender_clone.clone();
-
-        async move {
-            rpc_sender_clone
-                .ledger()
-                .latest()
-                .accounts()
-                .all()
-                .await
-                .map_or_else(
-                    dropped_channel_response,
-                    |reply: node::rpc::RpcLedgerSlimAccountsResponse| {
+    let transaction_pool_zkapp_stats = warp::path!("transaction-pool" / "zkapp-stats")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .transaction_pool()
+                    .zkapp_stats()
+                    .await
+                    .map_or_else(dropped_channel_response, |reply| {
                         with_json_reply(&reply, StatusCode::OK)
-                    },
-                )
-        }
-    });
+                    })
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let accounts = warp::path("accounts")
+        .and(warp::get())
+        .and(optq::<ContinuationQueryParams>())
+        .then(move |params: ContinuationQueryParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+
+            async move {
+                rpc_sender_clone
+                    .ledger()
+                    .latest()
+                    .accounts()
+                    .all(params.continuation)
+                    .await
+                    .map_or_else(
+                        || dropped_channel_response().into_response(),
+                        |reply: node::rpc::RpcLedgerSlimAccountsResponse| match reply {
+                            Err(err) => with_json_reply(&err, StatusCode::GONE).into_response(),
+                            // Response is the list of the accounts, the token
+                            // for the rest of them is in the header.
+                            Ok(page) => match page.continuation {
+                                None => {
+                                    with_json_reply(&page.accounts, StatusCode::OK).into_response()
+                                }
+                                Some(continuation) => warp::reply::with_header(
+                                    with_json_reply(&page.accounts, StatusCode::OK),
+                                    CONTINUATION_HEADER,
+                                    continuation.to_string(),
+                                )
+                                .into_response(),
+                            },
+                        },
+                    )
+            }
+        });
 
     let rpc_sender_clone = rpc_sender.clone();
     let transaction_post = warp::path("send-payment")
@@ -566,6 +880,62 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let finality_estimate = warp::path!("best-chain" / "finality")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+
+            async move {
+                rpc_sender_clone
+                    .transition_frontier()
+                    .finality_estimate()
+                    .await
+                    .map_or_else(dropped_channel_response, |reply| {
+                        with_json_reply(&reply, StatusCode::OK)
+                    })
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let node_info = warp::path!("node-info").and(warp::get()).then(move || {
+        let rpc_sender_clone = rpc_sender_clone.clone();
+
+        async move {
+            rpc_sender_clone
+                .oneshot_request::<RpcNodeInfoGetResponse>(RpcRequest::NodeInfoGet)
+                .await
+                .map_or_else(dropped_channel_response, |reply| {
+                    with_json_reply(&reply, StatusCode::OK)
+                })
+        }
+    });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let transaction_inclusion_proof =
+        warp::path!("transaction-inclusion-proof" / StateHash / TransactionHash)
+            .and(warp::get())
+            .then(move |block_hash, tx_hash| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+
+                async move {
+                    rpc_sender_clone
+                        .transition_frontier()
+                        .transaction_inclusion_proof(block_hash, tx_hash)
+                        .await
+                        .map_or_else(
+                            dropped_channel_response,
+                            |reply: RpcTransactionInclusionProofGetResponse| match reply {
+                                Some(proof) => with_json_reply(&proof, StatusCode::OK),
+                                None => with_json_reply(
+                                    &"transaction not found in the block",
+                                    StatusCode::NOT_FOUND,
+                                ),
+                            },
+                        )
+                }
+            });
+
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(["GET", "POST", "PUT", "DELETE", "OPTIONS"])
@@ -584,10 +954,14 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
     let routes = signaling.or(state_get).or(state_post);
     let routes = compose_route!(
         build_env_get,
+        transaction_failures_get,
+        node_info,
         routes,
         status,
         make_heartbeat,
+        state_digest_get,
         peers_get,
+        p2p_diagnostics_get,
         message_progress_get,
         stats,
         scan_state_summary_get,
@@ -598,36 +972,1167 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
         snarker_job_spec,
         snark_workers,
         transaction_pool,
+        transaction_pool_zkapp_stats,
+        // before `accounts`, which would match the paths too.
+        accounts_at_block(rpc_sender.clone()),
+        account_next_nonces(rpc_sender.clone()),
         accounts,
+        internal_commands(rpc_sender.clone()),
         transaction_post,
         transition_frontier_user_commands,
+        transaction_inclusion_proof,
+        finality_estimate,
         healthcheck(rpc_sender.clone()),
         readiness(rpc_sender.clone()),
+        maintenance(rpc_sender.clone(), auth.clone()),
+        feature_flags(rpc_sender.clone(), auth.clone()),
+        checkpoints(rpc_sender.clone()),
+        transaction_pool_snapshots(rpc_sender.clone(), auth.clone()),
+        ledger_status(rpc_sender.clone()),
+        ledger_session(rpc_sender.clone()),
+        ledger_export(rpc_sender.clone()),
+        account_subscriptions(rpc_sender.clone()),
+        snark_pool_job_events(rpc_sender.clone()),
+        zkapp_vk_register(rpc_sender.clone(), auth.clone()),
+        zkapp_preconditions_evaluate(rpc_sender.clone()),
+        payouts_batch_submit(rpc_sender.clone(), auth.clone()),
+        payouts_batch_get(rpc_sender.clone(), auth.clone()),
+        zkapp_fee_sponsor(rpc_sender.clone(), auth.clone()),
+        chain_export(rpc_sender.clone()),
+        chain_import(rpc_sender.clone(), auth.clone()),
+        vrf_verify(rpc_sender.clone()),
+        ledger_audit(rpc_sender.clone(), auth.clone()),
+        block_propagation(rpc_sender.clone()),
+        block_template(rpc_sender.clone(), auth.clone()),
         discovery::routing_table(rpc_sender.clone()),
         discovery::bootstrap_stats(rpc_sender.clone()),
         super::graphql::routes(rpc_sender),
     );
 
-    let routes = routes.recover(recover).with(cors);
+    routes.recover(recover).with(cors)
+}
+
+/// Header with the continuation token of the truncated `/accounts` response,
+/// which is kept a plain list of the accounts.
+const CONTINUATION_HEADER: &str = "x-continuation";
 
-    warp::serve(routes).run(([0, 0, 0, 0], port)).await;
+/// `?continuation=...` from a response truncated to the rpc response limits.
+#[derive(Deserialize, Default)]
+struct ContinuationQueryParams {
+    continuation: Option<RpcContinuationToken>,
 }
 
-fn healthcheck(
+#[derive(Deserialize, Default)]
+struct AccountsAtBlockQueryParams {
+    public_key: Option<String>,
+}
+
+/// `GET /accounts/at-block/{height or state hash}?public_key=...`
+/// returns accounts in the ledger as of the given block.
+fn accounts_at_block(
     rpc_sender: RpcSender,
 ) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
-    warp::path!("healthz").and(warp::get()).then(move || {
-        let rpc_sender = rpc_sender.clone();
-        async move {
-            rpc_sender
-                .oneshot_request(RpcRequest::HealthCheck)
-                .await
-                .map_or_else(
-                    || {
-                        with_status(
-                            String::from(DROPPED_CHANNEL),
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                        )
+    warp::path!("accounts" / "at-block" / String)
+        .and(warp::get())
+        .and(optq::<AccountsAtBlockQueryParams>())
+        .then(move |block: String, params: AccountsAtBlockQueryParams| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let block = match block.parse::<GetBlockQuery>() {
+                    Ok(block) => block,
+                    Err(err) => return with_json_reply(&err, StatusCode::BAD_REQUEST),
+                };
+                let account_query = match params.public_key.map(|pk| pk.parse()) {
+                    None => AccountQuery::All,
+                    Some(Ok(pk)) => AccountQuery::SinglePublicKey(pk),
+                    Some(Err(_)) => {
+                        return with_json_reply(&"invalid public key", StatusCode::BAD_REQUEST)
+                    }
+                };
+                rpc_sender
+                    .ledger()
+                    .accounts_at_block(block, account_query)
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcLedgerAccountsAtBlockResponse| match reply {
+                            Ok(accounts) => with_json_reply(&accounts, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        })
+}
+
+#[derive(Deserialize)]
+struct AccountNextNoncesQueryParams {
+    /// Comma separated public keys.
+    public_keys: String,
+}
+
+/// `GET /accounts/next-nonce?public_keys=...` returns the nonces to use
+/// for the new commands of the accounts, see [`RpcAccountNextNonces`].
+fn account_next_nonces(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("accounts" / "next-nonce")
+        .and(warp::get())
+        .and(warp::query::<AccountNextNoncesQueryParams>())
+        .then(move |params: AccountNextNoncesQueryParams| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let public_keys = match params
+                    .public_keys
+                    .split(',')
+                    .map(|pk| pk.trim().parse())
+                    .collect::<Result<Vec<_>, _>>()
+                {
+                    Ok(public_keys) => public_keys,
+                    Err(_) => {
+                        return with_json_reply(&"invalid public key", StatusCode::BAD_REQUEST)
+                    }
+                };
+                rpc_sender
+                    .oneshot_request(RpcRequest::AccountNextNoncesGet(public_keys))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcAccountNextNoncesGetResponse| match reply {
+                            Ok(nonces) => with_json_reply(&nonces, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::SERVICE_UNAVAILABLE),
+                        },
+                    )
+            }
+        })
+}
+
+/// `GET /internal-commands/block/{height or state hash}` and
+/// `GET /internal-commands/account/{public key}` return the coinbases and
+/// fee transfers of the block, or the ones received by the account, in
+/// the best chain.
+fn internal_commands(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let block = warp::path!("internal-commands" / "block" / String).map(|block: String| {
+        block
+            .parse::<GetBlockQuery>()
+            .map(RpcInternalCommandsQuery::Block)
+    });
+    let account = warp::path!("internal-commands" / "account" / String).map(|pk: String| {
+        pk.parse::<node::account::AccountPublicKey>()
+            .map(RpcInternalCommandsQuery::Receiver)
+            .map_err(|_| "invalid public key".to_owned())
+    });
+    block.or(account).unify().and(warp::get()).then(
+        move |query: Result<RpcInternalCommandsQuery, String>| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let query = match query {
+                    Ok(query) => query,
+                    Err(err) => return with_json_reply(&err, StatusCode::BAD_REQUEST),
+                };
+                rpc_sender
+                    .oneshot_request(RpcRequest::InternalCommandsGet(query))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcInternalCommandsGetResponse| match reply {
+                            Ok(commands) => with_json_reply(&commands, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        },
+    )
+}
+
+/// `GET /maintenance` reports whether it is safe to stop the node,
+/// `POST /maintenance/enter` and `POST /maintenance/leave` switch the mode.
+/// Switching requires the admin token, see [`HttpServerAuth`].
+fn maintenance(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let status = warp::path!("maintenance")
+        .and(warp::get())
+        .map(|| RpcMaintenanceModeCommand::Status);
+    let enter = warp::path!("maintenance" / "enter")
+        .and(warp::post())
+        .and(admin_auth(auth.clone()))
+        .map(|| RpcMaintenanceModeCommand::Enter);
+    let leave = warp::path!("maintenance" / "leave")
+        .and(warp::post())
+        .and(admin_auth(auth))
+        .map(|| RpcMaintenanceModeCommand::Leave);
+    status
+        .or(enter)
+        .unify()
+        .or(leave)
+        .unify()
+        .then(move |command| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::MaintenanceMode(command))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcMaintenanceModeResponse| with_json_reply(&reply, StatusCode::OK),
+                    )
+            }
+        })
+}
+
+/// `GET /feature-flags` returns the p2p feature flags, `POST /feature-flags`
+/// replaces them with the ones in the body. Changing them requires the
+/// admin token, see [`HttpServerAuth`].
+fn feature_flags(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let get = warp::path!("feature-flags")
+        .and(warp::get())
+        .map(|| RpcFeatureFlagsCommand::Get);
+    let set = warp::path!("feature-flags")
+        .and(warp::post())
+        .and(admin_auth(auth))
+        .and(warp::filters::body::json())
+        .map(RpcFeatureFlagsCommand::Set);
+    get.or(set).unify().then(move |command| {
+        let rpc_sender = rpc_sender.clone();
+        async move {
+            rpc_sender
+                .oneshot_request(RpcRequest::FeatureFlags(command))
+                .await
+                .map_or_else(
+                    || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                    |reply: RpcFeatureFlagsResponse| match reply {
+                        Ok(flags) => with_json_reply(&flags, StatusCode::OK),
+                        Err(err) => with_json_reply(&err, StatusCode::SERVICE_UNAVAILABLE),
+                    },
+                )
+        }
+    })
+}
+
+/// `GET /checkpoints` returns the verified checkpoints of the frontier root,
+/// signed by us or received from the peers.
+fn checkpoints(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("checkpoints").and(warp::get()).then(move || {
+        let rpc_sender = rpc_sender.clone();
+        async move {
+            rpc_sender
+                .oneshot_request(RpcRequest::CheckpointsGet)
+                .await
+                .map_or_else(
+                    || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                    |reply: RpcCheckpointsGetResponse| with_json_reply(&reply, StatusCode::OK),
+                )
+        }
+    })
+}
+
+/// `GET /ledger/status` returns the number of accounts, scan state job
+/// counts, pending coinbase stacks and total currency of the best tip.
+fn ledger_status(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("ledger" / "status")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::LedgerStatusExtendedGet)
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcLedgerStatusExtendedGetResponse| match reply {
+                            Some(status) => with_json_reply(&status, StatusCode::OK),
+                            None => with_json_reply(&"ledger not ready", StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        })
+}
+
+#[derive(Deserialize)]
+struct TransactionPoolSnapshotDiffQueryParams {
+    from: TransactionPoolSnapshotId,
+    to: Option<TransactionPoolSnapshotId>,
+}
+
+/// `POST /transaction-pool/snapshots` captures the contents of the pool,
+/// `GET /transaction-pool/snapshots` lists the captured snapshots and
+/// `GET /transaction-pool/snapshots/diff?from=..&to=..` compares them, or
+/// the snapshot with the current pool if `to` is omitted. Capturing
+/// requires the admin token, see [`HttpServerAuth`].
+fn transaction_pool_snapshots(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let list = warp::path!("transaction-pool" / "snapshots")
+        .and(warp::get())
+        .map(|| RpcTransactionPoolSnapshotCommand::List);
+    let capture = warp::path!("transaction-pool" / "snapshots")
+        .and(warp::post())
+        .and(admin_auth(auth))
+        .map(|| RpcTransactionPoolSnapshotCommand::Capture);
+    let diff = warp::path!("transaction-pool" / "snapshots" / "diff")
+        .and(warp::get())
+        .and(warp::query::<TransactionPoolSnapshotDiffQueryParams>())
+        .map(|TransactionPoolSnapshotDiffQueryParams { from, to }| {
+            RpcTransactionPoolSnapshotCommand::Diff { from, to }
+        });
+    list.or(capture)
+        .unify()
+        .or(diff)
+        .unify()
+        .then(move |command| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::TransactionPoolSnapshot(command))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcTransactionPoolSnapshotResponse| match reply {
+                            Ok(result) => with_json_reply(&result, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        })
+}
+
+/// `POST /ledger/sessions` pins the best tip ledger, which is then queried
+/// with `GET /ledger/sessions/{id}/accounts?public_key=...`, until it is
+/// unpinned with `POST /ledger/sessions/{id}/close` or the session expires.
+fn ledger_session(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let open = warp::path!("ledger" / "sessions")
+        .and(warp::post())
+        .map(|| Ok::<_, &str>(RpcLedgerSessionRequest::Open));
+    let accounts_get = warp::path!("ledger" / "sessions" / LedgerSessionId / "accounts")
+        .and(warp::get())
+        .and(optq::<AccountsAtBlockQueryParams>())
+        .map(|session_id, params: AccountsAtBlockQueryParams| {
+            // All the accounts are read with `GET /ledger/export`.
+            let query = match params.public_key.map(|pk| pk.parse()) {
+                None => return Err("public key is required"),
+                Some(Ok(pk)) => AccountQuery::SinglePublicKey(pk),
+                Some(Err(_)) => return Err("invalid public key"),
+            };
+            Ok(RpcLedgerSessionRequest::AccountsGet { session_id, query })
+        });
+    let close = warp::path!("ledger" / "sessions" / LedgerSessionId / "close")
+        .and(warp::post())
+        .map(|session_id| Ok::<_, &str>(RpcLedgerSessionRequest::Close { session_id }));
+    open.or(accounts_get).unify().or(close).unify().then(
+        move |request: Result<RpcLedgerSessionRequest, &str>| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let request = match request {
+                    Ok(request) => request,
+                    Err(err) => return with_json_reply(&err, StatusCode::BAD_REQUEST),
+                };
+                rpc_sender
+                    .oneshot_request(RpcRequest::LedgerSession(request))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcLedgerSessionResponse| match reply {
+                            Ok(response) => with_json_reply(&response, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        },
+    )
+}
+
+#[derive(Deserialize, Default)]
+struct LedgerExportQueryParams {
+    ledger: Option<String>,
+    format: Option<String>,
+    /// Comma separated names of the exported fields.
+    fields: Option<String>,
+}
+
+impl LedgerExportQueryParams {
+    fn parse(self) -> Result<(RpcLedgerSelector, LedgerExportWriter), String> {
+        let ledger = self.ledger.as_deref().map(str::parse).transpose()?;
+        let format = self
+            .format
+            .as_deref()
+            .map(str::parse::<LedgerExportFormat>)
+            .transpose()?;
+        let fields = self
+            .fields
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|field| !field.is_empty())
+            .map(str::parse::<LedgerExportField>)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((
+            ledger.unwrap_or_default(),
+            LedgerExportWriter::new(format.unwrap_or_default(), fields),
+        ))
+    }
+}
+
+/// `GET /ledger/export?ledger=best-tip|staking|next&format=csv|jsonl&fields=public_key,balance`
+/// streams all the accounts of the ledger, one account per line. The
+/// ledger is pinned with the ledger session for the duration of the
+/// export, so the accounts stay consistent while the best tip changes.
+fn ledger_export(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("ledger" / "export")
+        .and(warp::get())
+        .and(optq::<LedgerExportQueryParams>())
+        .then(move |params: LedgerExportQueryParams| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let (ledger, writer) = match params.parse() {
+                    Ok(v) => v,
+                    Err(err) => {
+                        return with_json_reply(&err, StatusCode::BAD_REQUEST).into_response()
+                    }
+                };
+                let request = RpcLedgerSessionRequest::OpenLedger { ledger };
+                let reply: Option<RpcLedgerSessionResponse> = rpc_sender
+                    .oneshot_request(RpcRequest::LedgerSession(request))
+                    .await;
+                let session_id = match reply {
+                    Some(Ok(LedgerSessionResponse::Opened(info))) => info.id,
+                    Some(Ok(_)) | None => {
+                        return with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR)
+                            .into_response()
+                    }
+                    Some(Err(err)) => {
+                        return with_json_reply(&err, StatusCode::NOT_FOUND).into_response()
+                    }
+                };
+
+                let content_type = writer.format().content_type();
+                let header =
+                    futures_util::stream::iter(writer.header().map(Ok::<_, std::io::Error>));
+                let pages = futures_util::stream::unfold(Some(0), move |offset| {
+                    let rpc_sender = rpc_sender.clone();
+                    let writer = writer.clone();
+                    async move {
+                        let offset = offset?;
+                        let request = RpcLedgerSessionRequest::AccountsPage {
+                            session_id,
+                            offset,
+                            limit: LEDGER_SESSION_PAGE_MAX,
+                        };
+                        let reply: Option<RpcLedgerSessionResponse> = rpc_sender
+                            .oneshot_request(RpcRequest::LedgerSession(request))
+                            .await;
+                        let accounts = match reply {
+                            Some(Ok(LedgerSessionResponse::Accounts(accounts))) => accounts,
+                            // The body is aborted, so that the client sees
+                            // the export is incomplete. The session expires
+                            // on its own.
+                            Some(Err(err)) => return Some((Err(std::io::Error::other(err)), None)),
+                            Some(Ok(_)) | None => {
+                                let err = std::io::Error::other(DROPPED_CHANNEL);
+                                return Some((Err(err), None));
+                            }
+                        };
+                        if accounts.is_empty() {
+                            let request = RpcLedgerSessionRequest::Close { session_id };
+                            let _: Option<RpcLedgerSessionResponse> = rpc_sender
+                                .oneshot_request(RpcRequest::LedgerSession(request))
+                                .await;
+                            return None;
+                        }
+                        let mut chunk = String::new();
+                        for account in &accounts {
+                            writer.write(account, &mut chunk);
+                        }
+                        Some((Ok(chunk), Some(offset + accounts.len() as u64)))
+                    }
+                });
+
+                let body =
+                    warp::hyper::Body::wrap_stream(futures_util::StreamExt::chain(header, pages));
+                let mut response = Response::new(body);
+                response
+                    .headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+                response
+            }
+        })
+}
+
+/// `GET /subscriptions/accounts` lists the watched public keys,
+/// `POST /subscriptions/accounts/add/{public_key}` and
+/// `POST /subscriptions/accounts/remove/{public_key}` change them.
+/// Changes of the watched accounts are streamed over the websocket at
+/// `/subscriptions/accounts/events`.
+fn account_subscriptions(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let list = warp::path!("subscriptions" / "accounts")
+        .and(warp::get())
+        .map(|| Ok::<_, String>(RpcAccountSubscriptionsCommand::List));
+    let add = warp::path!("subscriptions" / "accounts" / "add" / String)
+        .and(warp::post())
+        .map(|public_key: String| {
+            public_key
+                .parse()
+                .map(RpcAccountSubscriptionsCommand::Add)
+                .map_err(|_| public_key)
+        });
+    let remove = warp::path!("subscriptions" / "accounts" / "remove" / String)
+        .and(warp::post())
+        .map(|public_key: String| {
+            public_key
+                .parse()
+                .map(RpcAccountSubscriptionsCommand::Remove)
+                .map_err(|_| public_key)
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let commands = list.or(add).unify().or(remove).unify().then(
+        move |command: Result<RpcAccountSubscriptionsCommand, String>| {
+            let rpc_sender = rpc_sender_clone.clone();
+            async move {
+                let command = match command {
+                    Ok(command) => command,
+                    Err(public_key) => {
+                        let error = format!("invalid public key: {public_key}");
+                        return with_json_reply(&error, StatusCode::BAD_REQUEST);
+                    }
+                };
+                rpc_sender
+                    .oneshot_request(RpcRequest::AccountSubscriptions(command))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcAccountSubscriptionsResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        },
+    );
+
+    let events = warp::path!("subscriptions" / "accounts" / "events")
+        .and(warp::ws())
+        .map(move |ws: warp::ws::Ws| {
+            let rpc_sender = rpc_sender.clone();
+            ws.on_upgrade(move |ws| {
+                stream_events(
+                    ws,
+                    rpc_sender,
+                    RpcRequest::AccountEventsSubscribe,
+                    |event: RpcWatchedAccountEvent| {
+                        serde_json::to_string(&event)
+                            .ok()
+                            .map(warp::ws::Message::text)
+                    },
+                )
+            })
+        });
+
+    events.or(commands)
+}
+
+/// Lifecycle events of the snark pool jobs (see [`RpcSnarkPoolJobEvent`])
+/// are streamed over the websocket at `/snark-pool/jobs/events`, as json
+/// arrays of the events of a single pool update. Subscriber which falls
+/// behind is disconnected with the close code 1013 instead of silently
+/// missing the events.
+fn snark_pool_job_events(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("snark-pool" / "jobs" / "events")
+        .and(warp::ws())
+        .map(move |ws: warp::ws::Ws| {
+            let rpc_sender = rpc_sender.clone();
+            ws.on_upgrade(move |ws| {
+                stream_events(
+                    ws,
+                    rpc_sender,
+                    RpcRequest::SnarkPoolJobEventsSubscribe,
+                    |message| match message {
+                        RpcSnarkPoolJobEventsMessage::Events(events) => {
+                            serde_json::to_string(&events)
+                                .ok()
+                                .map(warp::ws::Message::text)
+                        }
+                        RpcSnarkPoolJobEventsMessage::Overflow => Some(
+                            warp::ws::Message::close_with(1013u16, "subscriber overflow"),
+                        ),
+                    },
+                )
+            })
+        })
+}
+
+/// Sends the events of the subscription `request` as messages made by
+/// `to_message`, until the websocket or the subscription is closed.
+async fn stream_events<T, F>(
+    mut ws: warp::ws::WebSocket,
+    rpc_sender: RpcSender,
+    request: RpcRequest,
+    to_message: F,
+) where
+    T: 'static + Send + Serialize,
+    F: Fn(T) -> Option<warp::ws::Message>,
+{
+    use futures_util::{SinkExt, StreamExt};
+
+    let mut rx = rpc_sender.multishot_request::<T>(64, request).await;
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                let Some(event) = event else { break };
+                let Some(msg) = to_message(event) else { continue };
+                let is_close = msg.is_close();
+                if ws.send(msg).await.is_err() || is_close {
+                    return;
+                }
+            }
+            msg = ws.next() => match msg {
+                Some(Ok(msg)) if !msg.is_close() => {}
+                _ => break,
+            },
+        }
+    }
+    // dropping the receiver unsubscribes on the next event.
+    let _ = ws.close().await;
+}
+
+/// `POST /zkapp/vk/register` registers the verification key for the
+/// pool admission of the zkApp commands proved against it, before the
+/// key is deployed. Requires the admin token, see [`HttpServerAuth`].
+fn zkapp_vk_register(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("zkapp" / "vk" / "register")
+        .and(warp::post())
+        .and(admin_auth(auth))
+        .and(warp::filters::body::json())
+        .then(move |request: RpcZkappVkRegisterRequest| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::ZkappVkRegister(request))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcZkappVkRegisterResponse| match reply {
+                            Ok(registered) => with_json_reply(&registered, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        })
+}
+
+/// `POST /vrf/verify` checks the vrf output of the block with the given
+/// state hash in the best chain against the threshold of the winner's
+/// stake in the block's staking ledger.
+fn vrf_verify(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("vrf" / "verify")
+        .and(warp::post())
+        .and(warp::filters::body::json())
+        .then(move |query: RpcVrfVerifyQuery| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::VrfVerify(query))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcVrfVerifyResponse| match reply {
+                            Ok(verification) => with_json_reply(&verification, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        })
+}
+
+/// `GET /ledger/audit` returns the running and the last ledger audit,
+/// `POST /ledger/audit/start` starts the audit of the best tip ledger.
+/// Starting it requires the admin token, see [`HttpServerAuth`].
+fn ledger_audit(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let status = warp::path!("ledger" / "audit")
+        .and(warp::get())
+        .map(|| RpcLedgerAuditCommand::Status);
+    let start = warp::path!("ledger" / "audit" / "start")
+        .and(warp::post())
+        .and(admin_auth(auth))
+        .map(|| RpcLedgerAuditCommand::Start);
+    status.or(start).unify().then(move |command| {
+        let rpc_sender = rpc_sender.clone();
+        async move {
+            rpc_sender
+                .oneshot_request(RpcRequest::LedgerAudit(command))
+                .await
+                .map_or_else(
+                    || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                    |reply: RpcLedgerAuditResponse| match reply {
+                        Ok(status) => with_json_reply(&status, StatusCode::OK),
+                        Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                    },
+                )
+        }
+    })
+}
+
+/// `GET /block-producer/template` returns what's needed to assemble the
+/// staged ledger diff for the next won slot, `POST
+/// /block-producer/template/submit` makes the node use the assembled diff,
+/// so it's only open to the operator.
+fn block_template(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let rpc_sender_clone = rpc_sender.clone();
+    let get = warp::path!("block-producer" / "template")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender = rpc_sender_clone.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::BlockTemplateGet)
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcBlockTemplateGetResponse| match reply {
+                            Ok(template) => with_json_reply(&template, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        });
+
+    let submit = warp::path!("block-producer" / "template" / "submit")
+        .and(warp::post())
+        .and(admin_auth(auth))
+        .and(warp::filters::body::json())
+        .then(move |submit: RpcBlockTemplateSubmit| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::BlockTemplateSubmit(Box::new(submit)))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcBlockTemplateSubmitResponse| match reply {
+                            Ok(submitted) => with_json_reply(&submitted, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        });
+
+    get.or(submit)
+}
+
+#[derive(Deserialize, Default)]
+struct BlockPropagationQueryParams {
+    from_height: Option<u32>,
+}
+
+/// `GET /stats/block-propagation` returns when the node saw the latest
+/// blocks, `GET /stats/block-propagation/report` compares it with the
+/// timelines of the configured sibling nodes.
+fn block_propagation(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let rpc_sender_clone = rpc_sender.clone();
+    let timelines = warp::path!("stats" / "block-propagation")
+        .and(warp::get())
+        .and(optq::<BlockPropagationQueryParams>())
+        .then(move |query: BlockPropagationQueryParams| {
+            let rpc_sender = rpc_sender_clone.clone();
+            async move {
+                let result: RpcBlockPropagationGetResponse = rpc_sender
+                    .oneshot_request(RpcRequest::BlockPropagationGet(query.from_height))
+                    .await
+                    .flatten();
+                with_json_reply(&result.map(|own| own.blocks), StatusCode::OK)
+            }
+        });
+
+    let report = warp::path!("stats" / "block-propagation" / "report")
+        .and(warp::get())
+        .and(optq::<BlockPropagationQueryParams>())
+        .then(move |query: BlockPropagationQueryParams| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let result: Option<RpcBlockPropagationGetResponse> = rpc_sender
+                    .oneshot_request(RpcRequest::BlockPropagationGet(query.from_height))
+                    .await;
+                let own = match result {
+                    None => {
+                        return with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR)
+                    }
+                    Some(None) => {
+                        return with_json_reply(&"stats are disabled", StatusCode::NOT_FOUND)
+                    }
+                    Some(Some(own)) => own,
+                };
+
+                let client = match sibling_http_client() {
+                    Ok(client) => client,
+                    Err(err) => {
+                        return with_json_reply(&err.to_string(), StatusCode::INTERNAL_SERVER_ERROR)
+                    }
+                };
+                let siblings = own.siblings.into_iter().map(|node| {
+                    let client = client.clone();
+                    async move {
+                        let blocks =
+                            fetch_block_propagation(&client, &node, query.from_height).await;
+                        BlockPropagationNodeTimelines { node, blocks }
+                    }
+                });
+                let mut nodes = vec![BlockPropagationNodeTimelines {
+                    node: String::new(),
+                    blocks: Ok(own.blocks),
+                }];
+                nodes.extend(futures_util::future::join_all(siblings).await);
+
+                with_json_reply(&BlockPropagationReport::new(nodes), StatusCode::OK)
+            }
+        });
+
+    timelines.or(report)
+}
+
+/// Client for the sibling nodes, which goes through the proxy, if the node
+/// has one configured.
+fn sibling_http_client() -> reqwest::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = openmina_core::ProxyConfig::global() {
+        builder = builder.proxy(reqwest::Proxy::custom(move |url| {
+            let host = url.host_str()?;
+            proxy.for_destination(host).map(|proxy| proxy.to_string())
+        }));
+    }
+    builder.build()
+}
+
+async fn fetch_block_propagation(
+    client: &reqwest::Client,
+    node: &str,
+    from_height: Option<u32>,
+) -> Result<Vec<BlockPropagationTimeline>, String> {
+    let url = format!("{}/stats/block-propagation", node.trim_end_matches('/'));
+    let mut request = client.get(url).timeout(std::time::Duration::from_secs(10));
+    if let Some(from_height) = from_height {
+        request = request.query(&[("from_height", from_height)]);
+    }
+    request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| err.to_string())?
+        .json::<Option<Vec<BlockPropagationTimeline>>>()
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "stats are disabled".to_owned())
+}
+
+/// `POST /zkapp/preconditions` reports which preconditions of the zkApp
+/// command currently hold, evaluated against the best tip.
+fn zkapp_preconditions_evaluate(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("zkapp" / "preconditions")
+        .and(warp::post())
+        .and(warp::filters::body::json())
+        .then(move |command: MinaBaseZkappCommandTStableV1WireStableV1| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::ZkappPreconditionsEvaluate(Box::new(command)))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcZkappPreconditionsEvaluateResponse| match reply {
+                            Ok(reports) => with_json_reply(&reports, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        })
+}
+
+/// `POST /payouts/batch` signs the payments of the batch with the payout
+/// key and injects them into the pool, in the background. Requires the
+/// admin token, see [`HttpServerAuth`].
+fn payouts_batch_submit(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("payouts" / "batch")
+        .and(warp::post())
+        .and(admin_auth(auth))
+        .and(warp::filters::body::json())
+        .then(move |request: RpcPayoutsBatchSubmitRequest| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::PayoutsBatchSubmit(request))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcPayoutsBatchSubmitResponse| match reply {
+                            Ok(batch_id) => with_json_reply(&batch_id, StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        })
+}
+
+/// `GET /payouts/batch/{id}` reports the status of each payment of the
+/// batch. Requires the admin token, since it reveals the receivers and
+/// the amounts.
+fn payouts_batch_get(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("payouts" / "batch" / PayoutBatchId)
+        .and(warp::get())
+        .and(admin_auth(auth))
+        .then(move |batch_id| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::PayoutsBatchGet(batch_id))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcPayoutsBatchGetResponse| match reply {
+                            Some(batch) => with_json_reply(&batch, StatusCode::OK),
+                            None => with_json_reply(&"batch not found", StatusCode::NOT_FOUND),
+                        },
+                    )
+            }
+        })
+}
+
+#[derive(Deserialize, Default)]
+struct ChainExportQueryParams {
+    from: Option<u32>,
+    to: Option<u32>,
+}
+
+/// `GET /best-chain/export?from=<height>&to=<height>` exports the blocks
+/// of the best chain in the range (all of them by default) as the chain
+/// archive, see [`node::transition_frontier::ChainArchiveHeader`].
+///
+/// The node keeps only the blocks of the transition frontier, so the
+/// range outside of it is rejected instead of being cut silently.
+fn chain_export(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("best-chain" / "export")
+        .and(warp::get())
+        .and(optq::<ChainExportQueryParams>())
+        .then(move |params: ChainExportQueryParams| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let Some(best_chain): Option<RpcBestChainResponse> = rpc_sender
+                    .oneshot_request(RpcRequest::BestChain(MaxLength::MAX))
+                    .await
+                else {
+                    return with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR)
+                        .into_response();
+                };
+                let (Some(root), Some(best_tip)) = (best_chain.first(), best_chain.last()) else {
+                    return with_json_reply(
+                        &"node doesn't have the best tip yet",
+                        StatusCode::NOT_FOUND,
+                    )
+                    .into_response();
+                };
+                let (root, best_tip) = (root.block.height(), best_tip.block.height());
+                let from = params.from.unwrap_or(root);
+                let to = params.to.unwrap_or(best_tip);
+                if from < root || to > best_tip {
+                    return with_json_reply(
+                        &format!(
+                            "blocks {from}..={to} are outside of the transition frontier \
+                             {root}..={best_tip}, older blocks aren't kept by the node"
+                        ),
+                        StatusCode::NOT_FOUND,
+                    )
+                    .into_response();
+                }
+                let blocks = best_chain
+                    .into_iter()
+                    .map(|b| b.block)
+                    .filter(|b| (from..=to).contains(&b.height()))
+                    .collect::<Vec<_>>();
+
+                let write = || {
+                    let mut writer = ChainArchiveWriter::new(Vec::new(), &blocks)?;
+                    for block in &blocks {
+                        writer.write_block(block)?;
+                    }
+                    writer.finish()
+                };
+                match write() {
+                    Ok(archive) => {
+                        let mut response = Response::new(archive.into());
+                        response.headers_mut().insert(
+                            CONTENT_TYPE,
+                            HeaderValue::from_static("application/octet-stream"),
+                        );
+                        response
+                    }
+                    Err(ChainArchiveError::Empty) => {
+                        with_json_reply(&"no blocks in the range", StatusCode::NOT_FOUND)
+                            .into_response()
+                    }
+                    Err(err) => {
+                        with_json_reply(&err.to_string(), StatusCode::INTERNAL_SERVER_ERROR)
+                            .into_response()
+                    }
+                }
+            }
+        })
+}
+
+/// `POST /best-chain/import` with the binprot encoded block in the body
+/// verifies and applies the block on top of the best tip. The response
+/// is sent once the block is accepted, it is applied asynchronously.
+/// Requires the admin token, see [`HttpServerAuth`].
+fn chain_import(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("best-chain" / "import")
+        .and(warp::post())
+        .and(admin_auth(auth))
+        .and(warp::filters::body::bytes())
+        .then(move |body: bytes::Bytes| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let block = match Block::binprot_read(&mut body.as_ref()) {
+                    Ok(block) => ArcBlockWithHash::try_new(Arc::new(block))
+                        .map_err(|_| "invalid field element".to_owned()),
+                    Err(err) => Err(err.to_string()),
+                };
+                let block = match block {
+                    Ok(block) => block,
+                    Err(err) => return with_json_reply(&err, StatusCode::BAD_REQUEST),
+                };
+                rpc_sender
+                    .oneshot_request(RpcRequest::ChainImportBlock(block))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcChainImportBlockResponse| match reply {
+                            Ok(()) => with_json_reply(&(), StatusCode::OK),
+                            Err(err) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                        },
+                    )
+            }
+        })
+}
+
+/// `POST /zkapp/sponsor` replaces the fee payer of the zkApp command
+/// with the fee sponsor account and injects it into the pool. Requests
+/// must carry the api key of the dapp as a bearer token, limits are
+/// applied per dapp.
+fn zkapp_fee_sponsor(
+    rpc_sender: RpcSender,
+    auth: Arc<HttpServerAuth>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("zkapp" / "sponsor")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("authorization").and_then(
+            move |authorization: Option<String>| {
+                let dapp = auth.sponsored_dapp(authorization.as_deref()).cloned();
+                async move { dapp.ok_or_else(|| warp::reject::custom(Unauthorized)) }
+            },
+        ))
+        .and(warp::filters::body::json())
+        .then(
+            move |origin: String, command: MinaBaseZkappCommandTStableV1WireStableV1| {
+                let rpc_sender = rpc_sender.clone();
+                let request = RpcZkappFeeSponsorRequest {
+                    origin,
+                    command: Box::new(command),
+                };
+                async move {
+                    rpc_sender
+                        .oneshot_request(RpcRequest::ZkappFeeSponsor(request))
+                        .await
+                        .map_or_else(
+                            || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                            |reply: RpcZkappFeeSponsorResponse| match reply {
+                                RpcTransactionInjectResponse::Failure(_) => {
+                                    with_json_reply(&reply, StatusCode::BAD_REQUEST)
+                                }
+                                _ => with_json_reply(&reply, StatusCode::OK),
+                            },
+                        )
+                }
+            },
+        )
+}
+
+/// Passes the requests to the operator endpoints, see [`HttpServerAuth`].
+fn admin_auth(auth: Arc<HttpServerAuth>) -> impl Filter<Error = Rejection, Extract = ()> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and(warp::addr::remote())
+        .and_then(
+            move |authorization: Option<String>, remote: Option<SocketAddr>| {
+                let is_admin = auth.is_admin(authorization.as_deref(), remote);
+                async move {
+                    if is_admin {
+                        Ok(())
+                    } else {
+                        Err(warp::reject::custom(Unauthorized))
+                    }
+                }
+            },
+        )
+        .untuple_one()
+}
+
+fn healthcheck(
+    rpc_sender: RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("healthz").and(warp::get()).then(move || {
+        let rpc_sender = rpc_sender.clone();
+        async move {
+            if let Some(low) = crate::low_disk_space() {
+                return with_status(low.to_string(), StatusCode::SERVICE_UNAVAILABLE);
+            }
+            rpc_sender
+                .oneshot_request(RpcRequest::HealthCheck)
+                .await
+                .map_or_else(
+                    || {
+                        with_status(
+                            String::from(DROPPED_CHANNEL),
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        )
                     },
                     |reply: node::rpc::RpcHealthCheckResponse| match reply {
                         Ok(()) => with_status(String::new(), StatusCode::OK),
@@ -726,12 +2231,22 @@ struct DroppedChannel;
 
 impl warp::reject::Reject for DroppedChannel {}
 
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
 async fn recover(rejection: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
     if let Some(DroppedChannel) = rejection.find() {
         Ok(warp::reply::with_status(
             warp::reply::json(&serde_json::json!({"error": DROPPED_CHANNEL})),
             StatusCode::INTERNAL_SERVER_ERROR,
         ))
+    } else if let Some(Unauthorized) = rejection.find() {
+        Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "unauthorized"})),
+            StatusCode::UNAUTHORIZED,
+        ))
     } else {
         Err(rejection)
     }
@@ -809,3 +2324,54 @@ impl Reply for JsonOrBinary {
 fn with_json_reply<T: Serialize>(reply: &T, status: StatusCode) -> WithStatus<Json> {
     with_status(json(reply), status)
 }
+
+#[cfg(test)]
+mod tests {
+    use node::core::channels::mpsc;
+    use openmina_node_client::{HttpMethod, ENDPOINTS};
+
+    use super::*;
+
+    fn path_param_sample(name: &str) -> String {
+        match name {
+            "block" | "session_id" | "batch_id" => "1".to_owned(),
+            "block_hash" => StateHash::zero().to_string(),
+            "tx_hash" => TransactionHash::from(&[0; 32]).to_string(),
+            "public_key" => "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV".to_owned(),
+            name => panic!("no sample for the path parameter `{name}`"),
+        }
+    }
+
+    /// Every endpoint of the typed client is served by the node.
+    #[tokio::test]
+    async fn client_endpoints_are_routed() {
+        // requests fail right away, as nothing handles them.
+        let (tx, _) = mpsc::channel(1);
+        let routes = routes(RpcSender::new(tx), HttpServerAuth::default());
+        for endpoint in ENDPOINTS {
+            let path = endpoint
+                .path_params
+                .iter()
+                .fold(endpoint.path.to_owned(), |path, name| {
+                    path.replace(&format!("{{{name}}}"), &path_param_sample(name))
+                });
+            let method = match endpoint.method {
+                HttpMethod::Get => "GET",
+                HttpMethod::Post => "POST",
+            };
+            let result = warp::test::request()
+                .method(method)
+                .path(&path)
+                .filter(&routes)
+                .await;
+            if let Err(rejection) = result {
+                assert!(
+                    !rejection.is_not_found()
+                        && rejection.find::<warp::reject::MethodNotAllowed>().is_none(),
+                    "{method} {path} of `{}` isn't routed: {rejection:?}",
+                    endpoint.name
+                );
+            }
+        }
+    }
+}
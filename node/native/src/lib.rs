@@ -2,6 +2,7 @@ pub use openmina_node_common::*;
 
 pub mod graphql;
 pub mod http_server;
+pub mod slot_claim_coordinator;
 
 mod service;
 pub use service::{NodeService, *};
@@ -1,33 +1,47 @@
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::{BufRead, BufReader, Read},
     net::IpAddr,
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
 
 use anyhow::Context;
 use ledger::proofs::provers::BlockProver;
+use ledger::transaction_pool::{FeePayerRateLimits, ZkappComplexityLimits};
 use mina_p2p_messages::v2::{self, NonZeroCurvePoint};
 use node::{
     account::AccountSecretKey,
+    checkpoint::CheckpointStore,
     daemon_json::Daemon,
+    ledger::{LedgerPersistPolicy, LEDGER_ROOT_SNAPSHOT_FILE},
     p2p::{
         channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts,
-        identity::SecretKey as P2pSecretKey, P2pLimits, P2pMeshsubConfig, P2pTimeouts,
+        feature_flags::P2pFeatureFlags, identity::SecretKey as P2pSecretKey, P2pGossipTopic,
+        P2pIpFamily, P2pLimits, P2pListenFamily, P2pMeshsubConfig, P2pTimeouts,
     },
+    rpc::RpcResponseLimits,
     service::Recorder,
     snark::{get_srs, BlockVerifier, TransactionVerifier, VerifierSRS},
-    transition_frontier::{archive::archive_config::ArchiveConfig, genesis::GenesisConfig},
-    BlockProducerConfig, GlobalConfig, LedgerConfig, P2pConfig, SnarkConfig, SnarkerConfig,
-    SnarkerStrategy, TransitionFrontierConfig,
+    transaction_pool::sponsor::{FeeSponsorConfig, FeeSponsorLimits},
+    transition_frontier::{
+        archive::archive_config::ArchiveConfig, genesis::GenesisConfig,
+        TransitionFrontierCandidatesPruneConfig,
+    },
+    BlockProducerConfig, CheckpointConfig, GlobalConfig, LedgerConfig, NodeProfile, P2pConfig,
+    SnarkConfig, SnarkPoolConfig, SnarkerConfig, SnarkerStrategy, TransitionFrontierConfig,
+    WatchtowerConfig,
 };
 use openmina_core::{consensus::ConsensusConstants, constants::constraint_constants};
-use openmina_node_common::{archive::config::ArchiveStorageOptions, p2p::TaskSpawner};
+use openmina_node_common::{
+    archive::config::ArchiveStorageOptions, block_producer::SlotClaimConfig, p2p::TaskSpawner,
+    P2pPreferredPeersStore,
+};
 use rand::Rng;
 
-use crate::NodeServiceBuilder;
+use crate::{http_server::HttpServerAuth, DiskMonitorConfig, NodeServiceBuilder};
 
 use super::Node;
 
@@ -42,12 +56,38 @@ pub struct NodeBuilder {
     block_producer: Option<BlockProducerConfig>,
     archive: Option<ArchiveConfig>,
     snarker: Option<SnarkerConfig>,
+    rpc_response_limits: RpcResponseLimits,
+    sibling_nodes: Vec<String>,
+    ledger_persist_policy: LedgerPersistPolicy,
+    snark_pool: SnarkPoolConfig,
+    tx_pool_zkapp_limits: ZkappComplexityLimits,
+    tx_pool_fee_payer_limits: FeePayerRateLimits,
+    candidates_prune: TransitionFrontierCandidatesPruneConfig,
+    watchtower: Option<WatchtowerConfig>,
+    checkpoint: CheckpointConfig,
+    fee_sponsor: Option<FeeSponsorConfig>,
     service: NodeServiceBuilder,
     verifier_srs: Option<Arc<VerifierSRS>>,
     block_verifier_index: Option<BlockVerifier>,
     work_verifier_index: Option<TransactionVerifier>,
     http_port: Option<u16>,
+    http_auth: HttpServerAuth,
     daemon_conf: Daemon,
+    profile: NodeProfile,
+    profile_services: ProfileServices,
+}
+
+/// Services of the subsystems not needed by every profile.
+/// They are only started in [`NodeBuilder::build`], once it's known that
+/// the profile needs them.
+#[derive(Default)]
+struct ProfileServices {
+    block_producer: Option<(AccountSecretKey, Option<BlockProver>)>,
+    block_producer_slot_claim: Option<SlotClaimConfig>,
+    block_producer_signing_journal: Option<PathBuf>,
+    archive: Option<(ArchiveStorageOptions, String)>,
+    payout_key: Option<AccountSecretKey>,
+    fee_sponsor_key: Option<AccountSecretKey>,
 }
 
 impl NodeBuilder {
@@ -72,8 +112,12 @@ impl NodeBuilder {
                 listen_port: None,
                 // Must be replaced with builder api.
                 identity_pub_key: P2pSecretKey::deterministic(0).public_key(),
+                webrtc_identity_pub_key: None,
                 initial_peers: Vec::new(),
+                preferred_peers: Vec::new(),
                 external_addrs: Vec::new(),
+                listen_family: Default::default(),
+                dial_family: None,
                 enabled_channels: ChannelId::iter_all().collect(),
                 peer_discovery: true,
                 meshsub: P2pMeshsubConfig {
@@ -82,6 +126,7 @@ impl NodeBuilder {
                 },
                 timeouts: P2pTimeouts::default(),
                 limits: P2pLimits::default().with_max_peers(Some(100)),
+                feature_flags: Default::default(),
             },
             p2p_sec_key: None,
             p2p_is_seed: false,
@@ -89,11 +134,24 @@ impl NodeBuilder {
             block_producer: None,
             archive: None,
             snarker: None,
+            rpc_response_limits: RpcResponseLimits::default(),
+            sibling_nodes: Vec::new(),
+            ledger_persist_policy: LedgerPersistPolicy::default(),
+            snark_pool: SnarkPoolConfig::default(),
+            tx_pool_zkapp_limits: ZkappComplexityLimits::default(),
+            tx_pool_fee_payer_limits: FeePayerRateLimits::default(),
+            candidates_prune: TransitionFrontierCandidatesPruneConfig::default(),
+            watchtower: None,
+            checkpoint: CheckpointConfig::default(),
+            fee_sponsor: None,
             service: NodeServiceBuilder::new(rng_seed),
             verifier_srs: None,
             block_verifier_index: None,
             work_verifier_index: None,
             http_port: None,
+            http_auth: Default::default(),
+            profile: daemon_conf.profile().unwrap_or_default(),
+            profile_services: Default::default(),
             daemon_conf,
         }
     }
@@ -104,6 +162,31 @@ impl NodeBuilder {
         self
     }
 
+    /// Set the node profile. Overrides the one from the daemon config.
+    /// Subsystems the profile doesn't need aren't set up, even if
+    /// configured.
+    pub fn profile(&mut self, profile: NodeProfile) -> &mut Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn get_profile(&self) -> NodeProfile {
+        self.profile
+    }
+
+    /// Enable the watchtower alerts. With the `watchtower` profile they
+    /// are enabled with the default config even if not called.
+    pub fn watchtower(&mut self, config: WatchtowerConfig) -> &mut Self {
+        self.watchtower = Some(config);
+        self
+    }
+
+    /// Sign and/or collect the checkpoints of the transition frontier root.
+    pub fn checkpoint(&mut self, config: CheckpointConfig) -> &mut Self {
+        self.checkpoint = config;
+        self
+    }
+
     /// If not called, random one will be generated and used instead.
     pub fn p2p_sec_key(&mut self, key: P2pSecretKey) -> &mut Self {
         self.p2p.identity_pub_key = key.public_key();
@@ -111,6 +194,15 @@ impl NodeBuilder {
         self
     }
 
+    /// Presents a separate identity on the webrtc connections, so that
+    /// the webrtc peer id can't be linked to the libp2p one. Must be
+    /// called before [`Self::p2p_custom_task_spawner`].
+    pub fn p2p_webrtc_sec_key(&mut self, key: P2pSecretKey) -> &mut Self {
+        self.p2p.webrtc_identity_pub_key = Some(key.public_key());
+        self.service.p2p_webrtc_sec_key(key);
+        self
+    }
+
     pub fn p2p_libp2p_port(&mut self, port: u16) -> &mut Self {
         self.p2p.libp2p_port = Some(port);
         self
@@ -141,6 +233,32 @@ impl NodeBuilder {
         self
     }
 
+    /// Address families the libp2p listener is bound to.
+    pub fn p2p_listen_family(&mut self, family: P2pListenFamily) -> &mut Self {
+        self.p2p.listen_family = family;
+        self
+    }
+
+    /// Dial the addresses of the `family` first, if the peer has both.
+    pub fn p2p_dial_family(&mut self, family: P2pIpFamily) -> &mut Self {
+        self.p2p.dial_family = Some(family);
+        self
+    }
+
+    /// Subscribe only to the gossip messages of the `topics`.
+    pub fn p2p_gossip_topics(&mut self, topics: &[P2pGossipTopic]) -> anyhow::Result<&mut Self> {
+        self.p2p
+            .set_gossip_topics(topics)
+            .map_err(anyhow::Error::msg)?;
+        Ok(self)
+    }
+
+    /// Initial p2p feature flags, may be changed at runtime with the rpc.
+    pub fn p2p_feature_flags(&mut self, flags: P2pFeatureFlags) -> &mut Self {
+        self.p2p.feature_flags = flags;
+        self
+    }
+
     /// Extend p2p initial peers from file.
     pub fn initial_peers_from_file(&mut self, path: impl AsRef<Path>) -> anyhow::Result<&mut Self> {
         peers_from_reader(
@@ -178,6 +296,19 @@ impl NodeBuilder {
         self
     }
 
+    /// Maximum number of rpc requests waiting for the response from a
+    /// webrtc peer. Libp2p peers only take one at a time.
+    pub fn p2p_rpc_max_in_flight_per_peer(&mut self, limit: usize) -> &mut Self {
+        self.p2p.limits = self.p2p.limits.with_rpc_max_in_flight_per_peer(Some(limit));
+        self
+    }
+
+    /// Number of times a timed out rpc request is resent to a webrtc peer.
+    pub fn p2p_rpc_timeout_retries(&mut self, retries: usize) -> &mut Self {
+        self.p2p.limits = self.p2p.limits.with_rpc_timeout_retries(Some(retries));
+        self
+    }
+
     /// Override default p2p task spawner.
     pub fn p2p_custom_task_spawner(
         &mut self,
@@ -196,13 +327,9 @@ impl NodeBuilder {
         key: AccountSecretKey,
         provers: Option<BlockProver>,
     ) -> &mut Self {
-        let config = BlockProducerConfig {
-            pub_key: key.public_key().into(),
-            custom_coinbase_receiver: None,
-            proposed_protocol_version: None,
-        };
+        let config = BlockProducerConfig::new(key.public_key().into());
         self.block_producer = Some(config);
-        self.service.block_producer_init(key, provers);
+        self.profile_services.block_producer = Some((key, provers));
         self
     }
 
@@ -218,9 +345,133 @@ impl NodeBuilder {
         Ok(self.block_producer(key, provers))
     }
 
+    /// Produce and accept dummy or only constraint-checked proofs, for
+    /// private devnets. Node fails to build if used on mainnet.
+    #[cfg(feature = "unsafe-devnet")]
+    pub fn proof_kind(&mut self, kind: openmina_node_common::ProofKind) -> &mut Self {
+        self.service.proof_kind(kind);
+        self
+    }
+
+    /// Set up the key the payout batches are signed with.
+    pub fn payout_key(&mut self, key: AccountSecretKey) -> &mut Self {
+        self.profile_services.payout_key = Some(key);
+        self
+    }
+
+    /// Set up the payout key using keys from file.
+    pub fn payout_key_from_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        password: &str,
+    ) -> anyhow::Result<&mut Self> {
+        let key = AccountSecretKey::from_encrypted_file(path, password)
+            .context("Failed to decrypt payout secret key file")?;
+        Ok(self.payout_key(key))
+    }
+
+    /// Set up the key the fees of the sponsored zkApp commands are paid
+    /// with, along with the limits per origin.
+    pub fn fee_sponsor_key(
+        &mut self,
+        key: AccountSecretKey,
+        limits: FeeSponsorLimits,
+    ) -> &mut Self {
+        self.fee_sponsor = Some(FeeSponsorConfig {
+            pub_key: key.public_key(),
+            limits,
+        });
+        self.profile_services.fee_sponsor_key = Some(key);
+        self
+    }
+
+    /// Set up the fee sponsor key using keys from file.
+    pub fn fee_sponsor_key_from_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        password: &str,
+        limits: FeeSponsorLimits,
+    ) -> anyhow::Result<&mut Self> {
+        let key = AccountSecretKey::from_encrypted_file(path, password)
+            .context("Failed to decrypt fee sponsor secret key file")?;
+        Ok(self.fee_sponsor_key(key, limits))
+    }
+
+    /// Api keys of the dapps allowed to use the fee sponsor, mapped to
+    /// the dapp name the limits are counted for.
+    pub fn fee_sponsor_api_keys(&mut self, keys: BTreeMap<String, String>) -> &mut Self {
+        self.http_auth.sponsor_api_keys = keys;
+        self
+    }
+
+    /// Token required by the operator endpoints of the http server, which
+    /// are otherwise only open to the loopback address.
+    pub fn http_admin_token(&mut self, token: String) -> &mut Self {
+        self.http_auth.admin_token = Some(token);
+        self
+    }
+
+    /// Read the fee sponsor api keys from the file, with a `<dapp> <api key>`
+    /// pair per line.
+    pub fn fee_sponsor_api_keys_from_file(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> anyhow::Result<&mut Self> {
+        let file = File::open(path).context("Failed to open fee sponsor api keys file")?;
+        let mut keys = BTreeMap::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.context("Failed to read fee sponsor api keys file")?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (dapp, key) = line
+                .split_once(char::is_whitespace)
+                .context("Expected `<dapp> <api key>` in fee sponsor api keys file")?;
+            keys.insert(key.trim().to_owned(), dapp.to_owned());
+        }
+        Ok(self.fee_sponsor_api_keys(keys))
+    }
+
+    /// Which commits of the root are persisted in the root snapshot.
+    pub fn ledger_persist_policy(&mut self, policy: LedgerPersistPolicy) -> &mut Self {
+        self.ledger_persist_policy = policy;
+        self
+    }
+
+    /// Persist transition frontier root in the `work_dir`, so that the
+    /// node resumes from it after restart.
+    pub fn ledger_root_snapshot(&mut self, work_dir: impl AsRef<Path>) -> &mut Self {
+        let path = work_dir.as_ref().join(LEDGER_ROOT_SNAPSHOT_FILE);
+        self.service.ledger_root_snapshot(path);
+        self
+    }
+
+    /// Persist the collected checkpoints in the `work_dir` and restore the
+    /// ones from the previous session.
+    pub fn checkpoints_persist(&mut self, work_dir: impl AsRef<Path>) -> anyhow::Result<&mut Self> {
+        let path = CheckpointStore::path(&work_dir);
+        let (store, checkpoints) = CheckpointStore::open(&work_dir)
+            .with_context(|| format!("failed to open checkpoints: {}", path.display()))?;
+        self.checkpoint.persisted = checkpoints;
+        self.service.checkpoint_store_init(store);
+        Ok(self)
+    }
+
+    /// Persist the connected peers in the `work_dir` and reconnect to the
+    /// ones from the previous session first.
+    pub fn p2p_preferred_peers(&mut self, work_dir: impl AsRef<Path>) -> anyhow::Result<&mut Self> {
+        let path = P2pPreferredPeersStore::path(&work_dir);
+        let (store, peers) = P2pPreferredPeersStore::open(&work_dir)
+            .with_context(|| format!("failed to open preferred peers: {}", path.display()))?;
+        self.p2p.preferred_peers = peers;
+        self.service.p2p_preferred_peers_init(store);
+        Ok(self)
+    }
+
     pub fn archive(&mut self, options: ArchiveStorageOptions, work_dir: String) -> &mut Self {
         self.archive = Some(ArchiveConfig::new(work_dir.clone()));
-        self.service.archive_init(options, work_dir.clone());
+        self.profile_services.archive = Some((options, work_dir));
         self
     }
 
@@ -238,6 +489,50 @@ impl NodeBuilder {
         Ok(self)
     }
 
+    /// Time reserved at the end of the won slot for the block proof
+    /// generation and broadcast.
+    pub fn block_producer_proof_and_broadcast_margin(
+        &mut self,
+        margin: Duration,
+    ) -> anyhow::Result<&mut Self> {
+        let bp = self.block_producer.as_mut().ok_or_else(|| {
+            anyhow::anyhow!(
+                "can't set proof_and_broadcast_margin when block producer is not initialized."
+            )
+        })?;
+        bp.proof_and_broadcast_margin = margin;
+        Ok(self)
+    }
+
+    /// Claim the won slots with the coordinator before producing them,
+    /// so that redundant producers running with the same key don't
+    /// produce blocks for the same slot.
+    pub fn block_producer_slot_claim(
+        &mut self,
+        config: SlotClaimConfig,
+    ) -> anyhow::Result<&mut Self> {
+        let bp = self.block_producer.as_mut().ok_or_else(|| {
+            anyhow::anyhow!("can't set slot_claim when block producer is not initialized.")
+        })?;
+        bp.slot_claim_required = true;
+        self.profile_services.block_producer_slot_claim = Some(config);
+        Ok(self)
+    }
+
+    /// Persist the signed blocks in the `work_dir` and refuse to sign
+    /// another block for the slot a block was already signed for.
+    pub fn block_producer_signing_journal<P: AsRef<std::path::Path>>(
+        &mut self,
+        work_dir: P,
+    ) -> anyhow::Result<&mut Self> {
+        if self.block_producer.is_none() {
+            anyhow::bail!("can't set signing_journal when block producer is not initialized.");
+        }
+        self.profile_services.block_producer_signing_journal =
+            Some(work_dir.as_ref().to_path_buf());
+        Ok(self)
+    }
+
     pub fn custom_block_producer_config(
         &mut self,
         config: BlockProducerConfig,
@@ -267,6 +562,50 @@ impl NodeBuilder {
     }
 
     /// Set verifier srs. If not set, default will be used.
+    /// Fully verify only `percent` of the snark work received from the
+    /// peers. See [`SnarkPoolConfig::verify_sample_percent`].
+    pub fn snark_verify_sampling(&mut self, percent: u8) -> &mut Self {
+        self.snark_pool.verify_sample_percent = Some(percent.min(100));
+        self
+    }
+
+    /// Stricter limits of the zkApp commands admitted to the transaction
+    /// pool. See [`ZkappComplexityLimits`].
+    pub fn tx_pool_zkapp_limits(&mut self, limits: ZkappComplexityLimits) -> &mut Self {
+        self.tx_pool_zkapp_limits = limits;
+        self
+    }
+
+    /// Limits of the commands admitted to the transaction pool per fee
+    /// payer. See [`FeePayerRateLimits`].
+    pub fn tx_pool_fee_payer_limits(&mut self, limits: FeePayerRateLimits) -> &mut Self {
+        self.tx_pool_fee_payer_limits = limits;
+        self
+    }
+
+    /// Retention of the transition frontier candidate blocks, see
+    /// [`TransitionFrontierCandidatesPruneConfig`].
+    pub fn candidates_prune(
+        &mut self,
+        config: TransitionFrontierCandidatesPruneConfig,
+    ) -> &mut Self {
+        self.candidates_prune = config;
+        self
+    }
+
+    /// Limits of the rpc responses, see [`RpcResponseLimits`].
+    pub fn rpc_response_limits(&mut self, limits: RpcResponseLimits) -> &mut Self {
+        self.rpc_response_limits = limits;
+        self
+    }
+
+    /// HTTP RPC addresses of the sibling nodes compared in the block
+    /// propagation report.
+    pub fn sibling_nodes(&mut self, urls: impl IntoIterator<Item = String>) -> &mut Self {
+        self.sibling_nodes.extend(urls);
+        self
+    }
+
     pub fn verifier_srs(&mut self, srs: Arc<VerifierSRS>) -> &mut Self {
         self.verifier_srs = Some(srs);
         self
@@ -292,24 +631,56 @@ impl NodeBuilder {
         self
     }
 
+    /// Journal the events received from the services in the `work_dir`,
+    /// for the post-mortem analysis.
+    pub fn event_journal(&mut self, work_dir: impl AsRef<Path>) -> &mut Self {
+        self.service.event_journal(work_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Re-inject the events journaled in the `dir` on startup.
+    pub fn event_journal_replay(&mut self, dir: impl AsRef<Path>) -> &mut Self {
+        self.service
+            .event_journal_replay(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Monitor the free space on the disk of the `work_dir`.
+    pub fn disk_monitor(
+        &mut self,
+        work_dir: impl AsRef<Path>,
+        config: DiskMonitorConfig,
+    ) -> &mut Self {
+        self.service
+            .disk_monitor(work_dir.as_ref().to_path_buf(), config);
+        self
+    }
+
     pub fn http_server(&mut self, port: u16) -> &mut Self {
         self.http_port = Some(port);
-        self.service.http_server_init(port);
+        self.service
+            .http_server_init(port, std::mem::take(&mut self.http_auth));
         self
     }
 
     pub fn build(mut self) -> anyhow::Result<Node> {
+        self.check_profile()?;
+        self.reduce_to_profile();
         let p2p_sec_key = self.p2p_sec_key.clone().unwrap_or_else(P2pSecretKey::rand);
         self.p2p_sec_key(p2p_sec_key.clone());
         if self.p2p.initial_peers.is_empty() && !self.p2p_is_seed {
             self.p2p.initial_peers = default_peers();
         }
 
+        let my_ids = [
+            p2p_sec_key.public_key().peer_id(),
+            self.p2p.webrtc_identity_pub_key().peer_id(),
+        ];
         self.p2p.initial_peers = self
             .p2p
             .initial_peers
             .into_iter()
-            .filter(|opts| *opts.peer_id() != p2p_sec_key.public_key().peer_id())
+            .filter(|opts| !my_ids.contains(opts.peer_id()))
             .filter_map(|opts| match opts {
                 P2pConnectionOutgoingInitOpts::LibP2P(mut opts) => {
                     opts.host = opts.host.resolve()?;
@@ -318,6 +689,9 @@ impl NodeBuilder {
                 x => Some(x),
             })
             .collect();
+        self.p2p
+            .preferred_peers
+            .retain(|peer| !my_ids.contains(peer.opts.peer_id()));
 
         let srs = self.verifier_srs.unwrap_or_else(get_srs);
         let block_verifier_index = self
@@ -346,27 +720,74 @@ impl NodeBuilder {
                 consensus_constants: consensus_consts.clone(),
                 testing_run: false,
                 client_port: self.http_port,
+                profile: self.profile,
+                payout_pub_key: self
+                    .profile_services
+                    .payout_key
+                    .as_ref()
+                    .map(AccountSecretKey::public_key),
+                rpc_response_limits: self.rpc_response_limits,
+                sibling_nodes: self.sibling_nodes,
             },
             p2p: self.p2p,
-            ledger: LedgerConfig {},
+            ledger: LedgerConfig {
+                persist_policy: self.ledger_persist_policy,
+            },
             snark: SnarkConfig {
                 block_verifier_index,
                 block_verifier_srs: srs.clone(),
                 work_verifier_index,
                 work_verifier_srs: srs,
+                verify_admission: Default::default(),
+            },
+            snark_pool: self.snark_pool,
+            transition_frontier: TransitionFrontierConfig {
+                candidates_prune: self.candidates_prune,
+                ..TransitionFrontierConfig::new(self.genesis_config)
             },
-            transition_frontier: TransitionFrontierConfig::new(self.genesis_config),
             block_producer: self.block_producer,
             archive: self.archive,
             tx_pool: ledger::transaction_pool::Config {
                 trust_system: (),
                 pool_max_size: self.daemon_conf.tx_pool_max_size(),
                 slot_tx_end: self.daemon_conf.slot_tx_end(),
+                zkapp_limits: self.tx_pool_zkapp_limits,
+                fee_payer_limits: self.tx_pool_fee_payer_limits,
             },
+            watchtower: self.watchtower.or_else(|| {
+                (self.profile == NodeProfile::Watchtower).then(WatchtowerConfig::default)
+            }),
+            checkpoint: self.checkpoint,
+            fee_sponsor: self.fee_sponsor,
         };
 
         // build service
         let mut service = self.service;
+        let services = self.profile_services;
+        if let Some((key, provers)) = services.block_producer {
+            service.block_producer_init(key, provers);
+            if let Some(config) = services.block_producer_slot_claim {
+                service.block_producer_slot_claim_init(config);
+            }
+            if let Some(work_dir) = services.block_producer_signing_journal {
+                let path = node::block_producer::SigningJournal::path(&work_dir);
+                service
+                    .block_producer_signing_journal_init(&work_dir)
+                    .with_context(|| {
+                        format!("failed to open signing journal: {}", path.display())
+                    })?;
+            }
+        }
+        // Must be started before the ledger, which is set up for the archive.
+        if let Some((options, work_dir)) = services.archive {
+            service.archive_init(options, work_dir);
+        }
+        if let Some(key) = services.payout_key {
+            service.payout_init(key);
+        }
+        if let Some(key) = services.fee_sponsor_key {
+            service.fee_sponsor_init(key);
+        }
         service.ledger_init();
 
         if !self.p2p_is_started {
@@ -380,6 +801,63 @@ impl NodeBuilder {
     }
 }
 
+impl NodeBuilder {
+    /// Make sure that the subsystems required by the profile are set up.
+    fn check_profile(&self) -> anyhow::Result<()> {
+        let profile = self.profile;
+        match profile {
+            NodeProfile::Producer if self.block_producer.is_none() => {
+                anyhow::bail!("`{profile}` profile requires block producer key")
+            }
+            NodeProfile::Snarker if self.snarker.is_none() => {
+                anyhow::bail!("`{profile}` profile requires snarker key")
+            }
+            NodeProfile::Archive if self.archive.is_none() => {
+                anyhow::bail!("`{profile}` profile requires archive storage")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl NodeBuilder {
+    /// Drop the subsystems the profile doesn't need, so that neither their
+    /// state nor their services are set up.
+    fn reduce_to_profile(&mut self) {
+        let profile = self.profile;
+        let disabled = |subsystem: &str| {
+            node::core::warn!(
+                node::core::log::system_time();
+                summary = format!("{subsystem} disabled by `{profile}` profile"),
+            );
+        };
+        if !profile.allows_block_producer() && self.block_producer.take().is_some() {
+            self.profile_services.block_producer = None;
+            self.profile_services.block_producer_slot_claim = None;
+            self.profile_services.block_producer_signing_journal = None;
+            disabled("block producer");
+        }
+        if !profile.allows_snarker() && self.snarker.take().is_some() {
+            disabled("snarker");
+        }
+        if !profile.allows_archive() && self.archive.take().is_some() {
+            self.profile_services.archive = None;
+            disabled("archive");
+        }
+        if profile.is_light() {
+            // Transactions can't be checked against the ledgers, which
+            // aren't maintained in light mode.
+            if self.profile_services.payout_key.take().is_some() {
+                disabled("payout");
+            }
+            if self.fee_sponsor.take().is_some() {
+                self.profile_services.fee_sponsor_key = None;
+                disabled("fee sponsor");
+            }
+        }
+    }
+}
+
 fn default_peers() -> Vec<P2pConnectionOutgoingInitOpts> {
     openmina_core::NetworkConfig::global()
         .default_peers
@@ -402,3 +880,65 @@ fn peers_from_reader(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use node::{
+        account::AccountSecretKey, daemon_json::Daemon, NodeProfile, SnarkerStrategy, DEVNET_CONFIG,
+    };
+    use openmina_node_common::archive::config::ArchiveStorageOptions;
+
+    use super::NodeBuilder;
+
+    fn builder_with_all(profile: NodeProfile) -> NodeBuilder {
+        let mut builder = NodeBuilder::new(Some([0; 32]), Daemon::DEFAULT, DEVNET_CONFIG.clone());
+        builder
+            .profile(profile)
+            .block_producer(AccountSecretKey::rand(), None)
+            .snarker(AccountSecretKey::rand(), 0, SnarkerStrategy::Sequential)
+            .payout_key(AccountSecretKey::rand())
+            .archive(
+                ArchiveStorageOptions::LOCAL_PRECOMPUTED_STORAGE,
+                "/tmp/archive".to_owned(),
+            );
+        builder
+            .block_producer_signing_journal("/tmp/signing-journal")
+            .unwrap();
+        builder
+    }
+
+    #[test]
+    fn test_reduce_to_profile() {
+        let mut builder = builder_with_all(NodeProfile::Full);
+        builder.reduce_to_profile();
+        assert!(builder.block_producer.is_some());
+        assert!(builder.profile_services.block_producer.is_some());
+        assert!(builder.snarker.is_some());
+        assert!(builder.archive.is_some());
+        assert!(builder.profile_services.archive.is_some());
+        assert!(builder.profile_services.payout_key.is_some());
+
+        let mut builder = builder_with_all(NodeProfile::Relay);
+        builder.reduce_to_profile();
+        assert!(builder.block_producer.is_none());
+        assert!(builder.profile_services.block_producer.is_none());
+        assert!(builder
+            .profile_services
+            .block_producer_signing_journal
+            .is_none());
+        assert!(builder.snarker.is_none());
+        assert!(builder.archive.is_none());
+        assert!(builder.profile_services.archive.is_none());
+        assert!(builder.profile_services.payout_key.is_some());
+
+        let mut builder = builder_with_all(NodeProfile::Archive);
+        builder.reduce_to_profile();
+        assert!(builder.block_producer.is_none());
+        assert!(builder.snarker.is_none());
+        assert!(builder.profile_services.archive.is_some());
+
+        let mut builder = builder_with_all(NodeProfile::LightRelay);
+        builder.reduce_to_profile();
+        assert!(builder.profile_services.payout_key.is_none());
+    }
+}
@@ -75,15 +75,15 @@ pub fn replay_state_with_input_actions(
                 0,
                 "not all expected effects of the input action were dispatched! Ones left: {expected_actions:?}"
             );
-            let (action, meta) = actions
-                .next()
-                .unwrap()
+            let recorded = actions.next().unwrap();
+            let rng_draws = recorded.rng_draws.clone();
+            let (action, meta) = recorded
                 .as_action_with_meta()
                 .expect("expected input action, got effect action")
                 .split();
             let kind = action.kind();
             let _ = input_action.insert(action);
-            expected_actions.push_back((kind, meta));
+            expected_actions.push_back((kind, meta, rng_draws));
             actions.peek()
         } else {
             Some(action)
@@ -92,7 +92,7 @@ pub fn replay_state_with_input_actions(
         let is_done = if let Some(action) = action {
             if action.action.is_none() {
                 let action = actions.next().unwrap();
-                expected_actions.push_back((action.kind, action.meta));
+                expected_actions.push_back((action.kind, action.meta, action.rng_draws));
                 false
             } else {
                 true
@@ -119,13 +119,18 @@ fn replayer_effects_with_dyn_effects(store: &mut Store<NodeService>, action: Act
 
 fn replayer_effects(store: &mut Store<NodeService>, action: ActionWithMeta) {
     let replayer = store.service.replayer().unwrap();
-    let (kind, meta) = match replayer.expected_actions.pop_front() {
+    let (kind, meta, rng_draws) = match replayer.expected_actions.pop_front() {
         Some(v) => v,
         None => panic!("unexpected action: {:?}", action),
     };
 
     assert_eq!(kind, action.action().kind());
     assert_eq!(meta.time(), action.meta().time());
+    assert_eq!(
+        rng_draws,
+        node::core::rng::audit_take(),
+        "randomness drawn before {kind:?} doesn't match the recorded one"
+    );
 
     node::effects(store, action)
 }
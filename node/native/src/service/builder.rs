@@ -1,19 +1,33 @@
+use std::path::PathBuf;
+
 use ledger::proofs::provers::BlockProver;
 use node::{
-    account::AccountSecretKey, core::thread, p2p::identity::SecretKey as P2pSecretKey,
+    account::AccountSecretKey,
+    core::thread,
+    p2p::{identity::SecretKey as P2pSecretKey, service_impl::webrtc_pacing::WebrtcSendConfig},
+    recorder::{EventJournal, EventJournalReader},
     service::Recorder,
 };
 pub use openmina_node_common::NodeServiceCommonBuildError;
 use openmina_node_common::{
-    archive::config::ArchiveStorageOptions, p2p::TaskSpawner, rpc::RpcSender, EventSender,
-    NodeServiceCommonBuilder,
+    archive::config::ArchiveStorageOptions, block_producer::SlotClaimConfig, p2p::TaskSpawner,
+    rpc::RpcSender, EventSender, NodeServiceCommonBuilder, P2pPreferredPeersStore,
 };
 
-use crate::{http_server, NodeService, P2pTaskSpawner};
+use crate::{
+    http_server::{self, HttpServerAuth},
+    DiskMonitorConfig, NodeService, P2pTaskSpawner,
+};
 
 pub struct NodeServiceBuilder {
     common: NodeServiceCommonBuilder,
     pub(super) recorder: Recorder,
+    /// Work dir in which the events are journaled.
+    event_journal: Option<PathBuf>,
+    /// Journal dir from which the events are re-injected on startup.
+    event_journal_replay: Option<PathBuf>,
+    /// Work dir whose disk is monitored for the free space.
+    disk_monitor: Option<(PathBuf, DiskMonitorConfig)>,
     http_server_port: Option<u16>,
 }
 
@@ -21,6 +35,9 @@ pub struct NodeServiceBuilder {
 pub enum NodeServiceBuildError {
     #[error("error when building common parts of the service: {0}")]
     Common(NodeServiceCommonBuildError),
+    #[error("event journal error: {0}")]
+    #[from(ignore)]
+    EventJournal(String),
 }
 
 impl NodeServiceBuilder {
@@ -28,6 +45,9 @@ impl NodeServiceBuilder {
         Self {
             common: NodeServiceCommonBuilder::new(rng_seed),
             recorder: Default::default(),
+            event_journal: None,
+            event_journal_replay: None,
+            disk_monitor: None,
             http_server_port: None,
         }
     }
@@ -40,6 +60,11 @@ impl NodeServiceBuilder {
         self.common.rpc_sender()
     }
 
+    pub fn ledger_root_snapshot(&mut self, path: PathBuf) -> &mut Self {
+        self.common.ledger_root_snapshot(path);
+        self
+    }
+
     pub fn ledger_init(&mut self) -> &mut Self {
         self.common.ledger_init();
         self
@@ -54,11 +79,45 @@ impl NodeServiceBuilder {
         self
     }
 
+    pub fn block_producer_slot_claim_init(&mut self, config: SlotClaimConfig) -> &mut Self {
+        self.common.block_producer_slot_claim_init(config);
+        self
+    }
+
+    pub fn block_producer_signing_journal_init<P: AsRef<std::path::Path>>(
+        &mut self,
+        work_dir: P,
+    ) -> std::io::Result<&mut Self> {
+        self.common.block_producer_signing_journal_init(work_dir)?;
+        Ok(self)
+    }
+
+    #[cfg(feature = "unsafe-devnet")]
+    pub fn proof_kind(&mut self, kind: openmina_node_common::ProofKind) -> &mut Self {
+        self.common.proof_kind(kind);
+        self
+    }
+
+    pub fn payout_init(&mut self, keypair: AccountSecretKey) -> &mut Self {
+        self.common.payout_init(keypair);
+        self
+    }
+
     pub fn archive_init(&mut self, options: ArchiveStorageOptions, work_dir: String) -> &mut Self {
         self.common.archive_init(options, work_dir);
         self
     }
 
+    pub fn p2p_webrtc_send_config(&mut self, config: WebrtcSendConfig) -> &mut Self {
+        self.common.p2p_webrtc_send_config(config);
+        self
+    }
+
+    pub fn p2p_webrtc_sec_key(&mut self, key: P2pSecretKey) -> &mut Self {
+        self.common.p2p_webrtc_sec_key(key);
+        self
+    }
+
     pub fn p2p_init(&mut self, secret_key: P2pSecretKey) -> &mut Self {
         self.common.p2p_init(secret_key, P2pTaskSpawner {});
         self
@@ -73,6 +132,11 @@ impl NodeServiceBuilder {
         self
     }
 
+    pub fn p2p_preferred_peers_init(&mut self, store: P2pPreferredPeersStore) -> &mut Self {
+        self.common.p2p_preferred_peers_init(store);
+        self
+    }
+
     pub fn gather_stats(&mut self) -> &mut Self {
         self.common.gather_stats();
         self
@@ -83,7 +147,27 @@ impl NodeServiceBuilder {
         self
     }
 
-    pub fn http_server_init(&mut self, port: u16) -> &mut Self {
+    /// Journal the events received from the services in the `work_dir`.
+    pub fn event_journal(&mut self, work_dir: PathBuf) -> &mut Self {
+        self.event_journal = Some(work_dir);
+        self
+    }
+
+    /// Re-inject the events journaled in the `dir` on startup, before
+    /// any other events are processed.
+    pub fn event_journal_replay(&mut self, dir: PathBuf) -> &mut Self {
+        self.event_journal_replay = Some(dir);
+        self
+    }
+
+    /// Monitor the free space on the disk of the `work_dir`, pruning the
+    /// oldest files and pausing the debug writers when it runs low.
+    pub fn disk_monitor(&mut self, work_dir: PathBuf, config: DiskMonitorConfig) -> &mut Self {
+        self.disk_monitor = Some((work_dir, config));
+        self
+    }
+
+    pub fn http_server_init(&mut self, port: u16, auth: HttpServerAuth) -> &mut Self {
         if let Some(cur_port) = self.http_server_port {
             panic!("trying to start http server on port `{port}`, when it's already running on port `{cur_port}`");
         }
@@ -95,7 +179,7 @@ impl NodeServiceBuilder {
             .unwrap();
         thread::Builder::new()
             .name("openmina_http_server".to_owned())
-            .spawn(move || runtime.block_on(http_server::run(port, rpc_sender)))
+            .spawn(move || runtime.block_on(http_server::run(port, rpc_sender, auth)))
             .unwrap();
         self
     }
@@ -103,6 +187,39 @@ impl NodeServiceBuilder {
     pub fn build(self) -> Result<NodeService, NodeServiceBuildError> {
         let mut service = self.common.build()?;
         service.recorder = self.recorder;
+
+        // Replayed before the journal is started, as it might be replayed
+        // from the journal dir, which is cleared when the journal starts.
+        if let Some(dir) = self.event_journal_replay {
+            let mut count = 0_usize;
+            for event in EventJournalReader::new(&dir).read_events() {
+                let event = event.map_err(|err| {
+                    NodeServiceBuildError::EventJournal(format!(
+                        "failed to read journal {}: {err}",
+                        dir.display()
+                    ))
+                })?;
+                let _ = service.event_sender.send(event.event);
+                count = count.saturating_add(1);
+            }
+            node::core::info!(
+                summary = "replaying journaled events",
+                dir = dir.display().to_string(),
+                count = count
+            );
+        }
+        if let Some(work_dir) = self.event_journal {
+            let journal = EventJournal::new(&work_dir).map_err(|err| {
+                NodeServiceBuildError::EventJournal(format!(
+                    "failed to start journal in {}: {err}",
+                    work_dir.display()
+                ))
+            })?;
+            service.event_journal = Some(journal);
+        }
+        if let Some((work_dir, config)) = self.disk_monitor {
+            super::disk_monitor::spawn(work_dir, config);
+        }
         Ok(service)
     }
 }
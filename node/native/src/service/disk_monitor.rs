@@ -0,0 +1,227 @@
+//! Monitors the free space on the disk of the work dir.
+//!
+//! Debug dumps, recorder output, event journal and archived precomputed
+//! blocks can fill the disk, after which the ledger and the block store
+//! fail to persist too. When the free space drops below the prune
+//! threshold, oldest files in the prunable dirs are removed. If that's
+//! not enough and it drops below the pause threshold, the writers which
+//! aren't needed for the node to function are paused until there is
+//! enough space again. Meanwhile `GET /healthz` fails with the low disk
+//! space, see [`low_disk_space`], so that the monitoring alerts on it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use node::core::thread;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+static LOW_DISK_SPACE: Mutex<Option<LowDiskSpace>> = Mutex::new(None);
+
+/// Free space below the pause threshold.
+#[derive(Debug, Clone, Copy)]
+pub struct LowDiskSpace {
+    pub available_bytes: u64,
+    pub threshold_bytes: u64,
+}
+
+impl std::fmt::Display for LowDiskSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "low disk space: {} bytes available, below {} bytes, debug writers paused",
+            self.available_bytes, self.threshold_bytes
+        )
+    }
+}
+
+/// Set while the debug writers are paused because of the low disk space.
+pub fn low_disk_space() -> Option<LowDiskSpace> {
+    *LOW_DISK_SPACE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn set_low_disk_space(low: Option<LowDiskSpace>) {
+    *LOW_DISK_SPACE.lock().unwrap_or_else(|err| err.into_inner()) = low;
+    node::core::debug_dump::set_writes_paused(low.is_some());
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskMonitorConfig {
+    /// Oldest files in `prune_dirs` are removed while there is less
+    /// free space than this, or than `pause_below_bytes` if it's higher,
+    /// so that the files are pruned before the writers get paused.
+    pub prune_below_bytes: u64,
+    /// Debug dumps, recorder and event journal are paused while there
+    /// is less free space than this.
+    pub pause_below_bytes: u64,
+    /// Dirs which are pruned, in this order: debug dumps first, then the
+    /// block bodies of the locally archived precomputed blocks.
+    pub prune_dirs: Vec<PathBuf>,
+}
+
+pub fn spawn(work_dir: PathBuf, config: DiskMonitorConfig) {
+    thread::Builder::new()
+        .name("openmina_disk_monitor".to_owned())
+        .spawn(move || run(work_dir, config))
+        .unwrap();
+}
+
+fn run(work_dir: PathBuf, config: DiskMonitorConfig) {
+    let mut paused = false;
+    loop {
+        match check(&work_dir, &config) {
+            Err(err) => {
+                node::core::warn!(
+                    summary = "failed to check free disk space",
+                    work_dir = work_dir.display().to_string(),
+                    error = err.to_string()
+                );
+            }
+            Ok(available) => {
+                let should_pause = available < config.pause_below_bytes;
+                if should_pause && !paused {
+                    node::core::error!(
+                        summary = "low disk space, debug writers paused",
+                        work_dir = work_dir.display().to_string(),
+                        available_bytes = available,
+                        threshold_bytes = config.pause_below_bytes
+                    );
+                } else if !should_pause && paused {
+                    node::core::info!(
+                        summary = "disk space recovered, debug writers resumed",
+                        available_bytes = available
+                    );
+                }
+                paused = should_pause;
+                set_low_disk_space(paused.then_some(LowDiskSpace {
+                    available_bytes: available,
+                    threshold_bytes: config.pause_below_bytes,
+                }));
+            }
+        }
+        thread::sleep(CHECK_INTERVAL);
+    }
+}
+
+/// Prunes the dirs if needed and returns the free space left.
+fn check(work_dir: &Path, config: &DiskMonitorConfig) -> nix::Result<u64> {
+    let available = available_space(work_dir)?;
+    let prune_below = config.prune_below_bytes.max(config.pause_below_bytes);
+    if available >= prune_below {
+        return Ok(available);
+    }
+
+    let to_free = prune_below.saturating_sub(available);
+    let (files, freed) = prune_oldest(&config.prune_dirs, to_free);
+    if files > 0 {
+        node::core::warn!(
+            summary = "low disk space, oldest files pruned",
+            available_bytes = available,
+            threshold_bytes = prune_below,
+            files = files,
+            freed_bytes = freed
+        );
+    }
+    available_space(work_dir)
+}
+
+fn available_space(path: &Path) -> nix::Result<u64> {
+    let stat = nix::sys::statvfs::statvfs(path)?;
+    // Types differ between the platforms.
+    #[allow(clippy::useless_conversion)]
+    let available =
+        u64::from(stat.blocks_available()).saturating_mul(u64::from(stat.fragment_size()));
+    Ok(available)
+}
+
+/// Removes the oldest files from the `dirs`, one dir after another, until
+/// at least `to_free` bytes are freed. Returns the number of the removed
+/// files and the bytes freed.
+fn prune_oldest(dirs: &[PathBuf], to_free: u64) -> (usize, u64) {
+    let mut files_removed = 0_usize;
+    let mut freed = 0_u64;
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut files = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let metadata = entry.metadata().ok()?;
+                if !metadata.is_file() {
+                    return None;
+                }
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((modified, metadata.len(), entry.path()))
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+
+        for (_, len, path) in files {
+            if freed >= to_free {
+                return (files_removed, freed);
+            }
+            if fs::remove_file(&path).is_ok() {
+                files_removed = files_removed.saturating_add(1);
+                freed = freed.saturating_add(len);
+            }
+        }
+    }
+    (files_removed, freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes the files with the `len` and the modification time in seconds.
+    fn create_files(dir: &Path, files: &[(&str, usize, u64)]) {
+        fs::create_dir_all(dir).unwrap();
+        for (name, len, modified) in files {
+            let file = fs::File::create(dir.join(name)).unwrap();
+            file.set_len(*len as u64).unwrap();
+            let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(*modified);
+            file.set_modified(modified).unwrap();
+        }
+    }
+
+    fn remaining(dir: &Path) -> Vec<String> {
+        let mut names = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn prune_oldest_first() {
+        let base = std::env::temp_dir().join(format!("disk-monitor-{}", std::process::id()));
+        let debug = base.join("debug");
+        let blocks = base.join("blocks");
+        create_files(&debug, &[("b", 100, 20), ("a", 100, 30), ("c", 100, 10)]);
+        create_files(&blocks, &[("1", 1000, 1), ("2", 1000, 2)]);
+        fs::create_dir_all(debug.join("subdir")).unwrap();
+        let dirs = [base.join("missing"), debug.clone(), blocks.clone()];
+
+        // Nothing to free.
+        assert_eq!(prune_oldest(&dirs, 0), (0, 0));
+
+        // Oldest first, only as much as needed.
+        assert_eq!(prune_oldest(&dirs, 150), (2, 200));
+        assert_eq!(remaining(&debug), ["a", "subdir"]);
+
+        // Dirs in order, the block bodies after the debug dumps.
+        assert_eq!(prune_oldest(&dirs, 1050), (2, 1100));
+        assert_eq!(remaining(&debug), ["subdir"]);
+        assert_eq!(remaining(&blocks), ["2"]);
+
+        // Stops when there is nothing more to remove.
+        assert_eq!(prune_oldest(&dirs, u64::MAX), (1, 1000));
+        assert!(remaining(&blocks).is_empty());
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
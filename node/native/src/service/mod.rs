@@ -1,6 +1,9 @@
 mod builder;
 pub use builder::*;
 
+mod disk_monitor;
+pub use disk_monitor::{low_disk_space, DiskMonitorConfig, LowDiskSpace};
+
 use openmina_node_common::p2p::TaskSpawner;
 pub use openmina_node_common::NodeService;
 
@@ -0,0 +1,246 @@
+//! Slot claim coordinator shared by the redundant producers running with
+//! the same key, see [`openmina_node_common::block_producer::SlotClaimConfig`].
+//!
+//! Grants the claim of the slot to the first holder claiming it. Claims
+//! are persisted to the state file before they are granted, so that the
+//! coordinator restart doesn't grant the same slot twice.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use openmina_node_common::block_producer::{SlotClaimRequest, SlotClaimResponse};
+use serde::{Deserialize, Serialize};
+use warp::{http::StatusCode, Filter, Rejection, Reply};
+
+/// Number of the latest slots the claims are kept for, per producer.
+/// Older slots can't be produced anymore, so their claims are denied.
+pub const SLOT_CLAIMS_KEEP_SLOTS: u32 = 7140;
+
+/// Holders of the claimed slots, per producer.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct SlotClaims {
+    claims: BTreeMap<String, BTreeMap<u32, String>>,
+}
+
+impl SlotClaims {
+    /// Returns the response and whether the claims changed.
+    pub fn claim(&mut self, request: SlotClaimRequest) -> (SlotClaimResponse, bool) {
+        let slots = self.claims.entry(request.producer).or_default();
+        let latest = slots.keys().next_back().copied().unwrap_or_default();
+        if request.global_slot < latest.saturating_sub(SLOT_CLAIMS_KEEP_SLOTS) {
+            let response = SlotClaimResponse {
+                granted: false,
+                holder: None,
+            };
+            return (response, false);
+        }
+
+        let mut changed = false;
+        let holder = slots.entry(request.global_slot).or_insert_with(|| {
+            changed = true;
+            request.holder.clone()
+        });
+        let response = if *holder == request.holder {
+            SlotClaimResponse {
+                granted: true,
+                holder: None,
+            }
+        } else {
+            SlotClaimResponse {
+                granted: false,
+                holder: Some(holder.clone()),
+            }
+        };
+
+        let keep_from = request
+            .global_slot
+            .max(latest)
+            .saturating_sub(SLOT_CLAIMS_KEEP_SLOTS);
+        *slots = slots.split_off(&keep_from);
+        (response, changed)
+    }
+
+    /// Loads the claims from the state file. Missing file means there
+    /// are none.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Replaces the state file, so that it's never left half written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(self)?)?;
+        fs::rename(tmp, path)
+    }
+}
+
+struct Coordinator {
+    claims: SlotClaims,
+    state_file: Option<PathBuf>,
+}
+
+impl Coordinator {
+    fn claim(&mut self, request: SlotClaimRequest) -> io::Result<SlotClaimResponse> {
+        let (response, changed) = self.claims.claim(request);
+        if let Some(path) = self.state_file.as_ref().filter(|_| changed) {
+            self.claims.save(path)?;
+        }
+        Ok(response)
+    }
+}
+
+fn routes(
+    coordinator: Coordinator,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone + Send + Sync + 'static {
+    let coordinator = Arc::new(Mutex::new(coordinator));
+    warp::path!("claim")
+        .and(warp::post())
+        .and(warp::filters::body::json())
+        .map(move |request: SlotClaimRequest| {
+            let mut coordinator = coordinator.lock().unwrap_or_else(|err| err.into_inner());
+            match coordinator.claim(request) {
+                Ok(response) => {
+                    warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)
+                }
+                // Not granted unless persisted, producer retries the claim.
+                Err(err) => warp::reply::with_status(
+                    warp::reply::json(&err.to_string()),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ),
+            }
+        })
+}
+
+/// Serves `POST /claim` on the `port`. Without the `state_file` claims
+/// are only kept in memory.
+pub async fn run(port: u16, state_file: Option<PathBuf>) -> io::Result<()> {
+    let claims = match &state_file {
+        Some(path) => SlotClaims::load(path)?,
+        None => SlotClaims::default(),
+    };
+    let coordinator = Coordinator { claims, state_file };
+    warp::serve(routes(coordinator))
+        .run(([0, 0, 0, 0], port))
+        .await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(global_slot: u32, holder: &str) -> SlotClaimRequest {
+        SlotClaimRequest {
+            producer: "B62q".to_owned(),
+            global_slot,
+            holder: holder.to_owned(),
+        }
+    }
+
+    #[test]
+    fn claim_granted_once() {
+        let mut claims = SlotClaims::default();
+        let (response, changed) = claims.claim(request(10, "a"));
+        assert!(response.granted && changed);
+
+        // Retry by the same holder is granted again.
+        let (response, changed) = claims.claim(request(10, "a"));
+        assert!(response.granted && !changed);
+
+        let (response, changed) = claims.claim(request(10, "b"));
+        assert!(!response.granted && !changed);
+        assert_eq!(response.holder.as_deref(), Some("a"));
+
+        let (response, _) = claims.claim(request(11, "b"));
+        assert!(response.granted);
+
+        // Other producer's slots are independent.
+        let other = SlotClaimRequest {
+            producer: "B62r".to_owned(),
+            ..request(10, "b")
+        };
+        assert!(claims.claim(other).0.granted);
+    }
+
+    #[test]
+    fn old_claims_pruned() {
+        let mut claims = SlotClaims::default();
+        claims.claim(request(10, "a"));
+        claims.claim(request(10 + SLOT_CLAIMS_KEEP_SLOTS + 1, "a"));
+        assert_eq!(claims.claims.get("B62q").map(BTreeMap::len), Some(1));
+
+        // Pruned slot isn't granted again.
+        let (response, changed) = claims.claim(request(10, "b"));
+        assert!(!response.granted && !changed);
+        assert_eq!(response.holder, None);
+    }
+
+    #[test]
+    fn claims_persisted() {
+        let dir = std::env::temp_dir().join(format!("slot-claims-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("claims.json");
+        let _ = fs::remove_file(&path);
+
+        let mut coordinator = Coordinator {
+            claims: SlotClaims::load(&path).unwrap(),
+            state_file: Some(path.clone()),
+        };
+        assert!(coordinator.claim(request(10, "a")).unwrap().granted);
+
+        // Restarted coordinator still denies the claimed slot.
+        let mut coordinator = Coordinator {
+            claims: SlotClaims::load(&path).unwrap(),
+            state_file: Some(path),
+        };
+        assert!(!coordinator.claim(request(10, "b")).unwrap().granted);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn claim_over_http() {
+        let coordinator = Coordinator {
+            claims: SlotClaims::default(),
+            state_file: None,
+        };
+        let (addr, server) = warp::serve(routes(coordinator)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{addr}/claim");
+        let mut responses = Vec::new();
+        for holder in ["a", "b"] {
+            let response: SlotClaimResponse = client
+                .post(&url)
+                .json(&request(10, holder))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            responses.push(response);
+        }
+        assert_eq!(
+            responses,
+            [
+                SlotClaimResponse {
+                    granted: true,
+                    holder: None
+                },
+                SlotClaimResponse {
+                    granted: false,
+                    holder: Some("a".to_owned())
+                }
+            ]
+        );
+    }
+}
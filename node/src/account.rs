@@ -1 +1,11 @@
 pub use openmina_node_account::*;
+
+use openmina_core::{network::NetworkId, NetworkConfig};
+
+/// Network id, the signatures of the current network are created for.
+pub fn signer_network_id() -> mina_signer::NetworkId {
+    match NetworkConfig::global().network_id {
+        NetworkId::MAINNET => mina_signer::NetworkId::MAINNET,
+        NetworkId::TESTNET => mina_signer::NetworkId::TESTNET,
+    }
+}
@@ -6,6 +6,8 @@ pub type ActionWithMetaRef<'a> = redux::ActionWithMeta<&'a Action>;
 
 pub use crate::block_producer::BlockProducerAction;
 pub use crate::block_producer_effectful::BlockProducerEffectfulAction;
+pub use crate::checkpoint::CheckpointAction;
+use crate::checkpoint::CheckpointEffectfulAction;
 pub use crate::event_source::EventSourceAction;
 pub use crate::external_snark_worker::ExternalSnarkWorkerAction;
 use crate::external_snark_worker_effectful::ExternalSnarkWorkerEffectfulAction;
@@ -22,6 +24,7 @@ pub use crate::transaction_pool::TransactionPoolAction;
 use crate::transaction_pool::TransactionPoolEffectfulAction;
 pub use crate::transition_frontier::TransitionFrontierAction;
 pub use crate::watched_accounts::WatchedAccountsAction;
+pub use crate::watchtower::WatchtowerAction;
 
 pub trait ActionKindGet {
     fn kind(&self) -> crate::ActionKind;
@@ -56,6 +59,9 @@ pub enum Action {
     RpcEffectful(RpcEffectfulAction),
 
     WatchedAccounts(WatchedAccountsAction),
+    Watchtower(WatchtowerAction),
+    Checkpoint(CheckpointAction),
+    CheckpointEffectful(CheckpointEffectfulAction),
 }
 
 impl Action {
@@ -100,6 +106,9 @@ impl redux::EnablingCondition<crate::State> for Action {
             Action::BlockProducerEffectful(a) => a.is_enabled(state, time),
             Action::Rpc(a) => a.is_enabled(state, time),
             Action::WatchedAccounts(a) => a.is_enabled(state, time),
+            Action::Watchtower(a) => a.is_enabled(state, time),
+            Action::Checkpoint(a) => a.is_enabled(state, time),
+            Action::CheckpointEffectful(a) => a.is_enabled(state, time),
             Action::TransactionPool(a) => a.is_enabled(state, time),
             Action::TransactionPoolEffect(a) => a.is_enabled(state, time),
             Action::P2pCallbacks(a) => a.is_enabled(state, time),
@@ -19,15 +19,18 @@ use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorAction;
 use crate::block_producer::BlockProducerAction;
 use crate::block_producer_effectful::vrf_evaluator_effectful::BlockProducerVrfEvaluatorEffectfulAction;
 use crate::block_producer_effectful::BlockProducerEffectfulAction;
+use crate::checkpoint::{CheckpointAction, CheckpointEffectfulAction};
 use crate::event_source::EventSourceAction;
 use crate::external_snark_worker::ExternalSnarkWorkerAction;
 use crate::external_snark_worker_effectful::ExternalSnarkWorkerEffectfulAction;
+use crate::ledger::audit::LedgerAuditAction;
 use crate::ledger::read::LedgerReadAction;
 use crate::ledger::write::LedgerWriteAction;
 use crate::ledger::LedgerAction;
 use crate::ledger_effectful::LedgerEffectfulAction;
 use crate::p2p::callbacks::P2pCallbacksAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
+use crate::p2p::channels::checkpoint::P2pChannelsCheckpointAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
 use crate::p2p::channels::signaling::discovery::P2pChannelsSignalingDiscoveryAction;
 use crate::p2p::channels::signaling::exchange::P2pChannelsSignalingExchangeAction;
@@ -38,6 +41,8 @@ use crate::p2p::channels::transaction::P2pChannelsTransactionAction;
 use crate::p2p::channels::{
     P2pChannelsAction, P2pChannelsEffectfulAction, P2pChannelsMessageReceivedAction,
 };
+use crate::p2p::connection::ice_restart::P2pConnectionIceRestartAction;
+use crate::p2p::connection::ice_restart_effectful::P2pConnectionIceRestartEffectfulAction;
 use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
 use crate::p2p::connection::incoming_effectful::P2pConnectionIncomingEffectfulAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
@@ -45,6 +50,7 @@ use crate::p2p::connection::outgoing_effectful::P2pConnectionOutgoingEffectfulAc
 use crate::p2p::connection::{P2pConnectionAction, P2pConnectionEffectfulAction};
 use crate::p2p::disconnection::P2pDisconnectionAction;
 use crate::p2p::disconnection_effectful::P2pDisconnectionEffectfulAction;
+use crate::p2p::feature_flags::P2pFeatureFlagsAction;
 use crate::p2p::identify::P2pIdentifyAction;
 use crate::p2p::network::identify::stream::P2pNetworkIdentifyStreamAction;
 use crate::p2p::network::identify::stream_effectful::P2pNetworkIdentifyStreamEffectfulAction;
@@ -73,12 +79,15 @@ use crate::snark::block_verify::SnarkBlockVerifyAction;
 use crate::snark::block_verify_effectful::SnarkBlockVerifyEffectfulAction;
 use crate::snark::user_command_verify::SnarkUserCommandVerifyAction;
 use crate::snark::user_command_verify_effectful::SnarkUserCommandVerifyEffectfulAction;
+use crate::snark::verify_admission::SnarkVerifyAdmissionAction;
 use crate::snark::work_verify::SnarkWorkVerifyAction;
 use crate::snark::work_verify_effectful::SnarkWorkVerifyEffectfulAction;
 use crate::snark::SnarkAction;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::{SnarkPoolAction, SnarkPoolEffectfulAction};
 use crate::transaction_pool::candidate::TransactionPoolCandidateAction;
+use crate::transaction_pool::payouts::TransactionPoolPayoutsAction;
+use crate::transaction_pool::sponsor::TransactionPoolSponsorAction;
 use crate::transaction_pool::{TransactionPoolAction, TransactionPoolEffectfulAction};
 use crate::transition_frontier::candidate::TransitionFrontierCandidateAction;
 use crate::transition_frontier::genesis::TransitionFrontierGenesisAction;
@@ -89,6 +98,7 @@ use crate::transition_frontier::sync::ledger::TransitionFrontierSyncLedgerAction
 use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::transition_frontier::TransitionFrontierAction;
 use crate::watched_accounts::WatchedAccountsAction;
+use crate::watchtower::WatchtowerAction;
 use crate::{Action, ActionKindGet, CheckTimeoutsAction};
 
 /// Unified kind enum for all action types
@@ -115,11 +125,16 @@ pub enum ActionKind {
     BlockProducerBlockProveInit,
     BlockProducerBlockProvePending,
     BlockProducerBlockProveSuccess,
+    BlockProducerBlockSigningRefused,
     BlockProducerBlockUnprovenBuild,
+    BlockProducerExternalDiffSubmit,
     BlockProducerStagedLedgerDiffCreateInit,
     BlockProducerStagedLedgerDiffCreatePending,
     BlockProducerStagedLedgerDiffCreateSuccess,
     BlockProducerWonSlot,
+    BlockProducerWonSlotClaimError,
+    BlockProducerWonSlotClaimInit,
+    BlockProducerWonSlotClaimSuccess,
     BlockProducerWonSlotDiscard,
     BlockProducerWonSlotProduceInit,
     BlockProducerWonSlotSearch,
@@ -133,6 +148,7 @@ pub enum ActionKind {
     BlockProducerEffectfulStagedLedgerDiffCreateInit,
     BlockProducerEffectfulStagedLedgerDiffCreateSuccess,
     BlockProducerEffectfulWonSlot,
+    BlockProducerEffectfulWonSlotClaimInit,
     BlockProducerEffectfulWonSlotDiscard,
     BlockProducerVrfEvaluatorBeginDelegatorTableConstruction,
     BlockProducerVrfEvaluatorBeginEpochEvaluation,
@@ -147,6 +163,8 @@ pub enum ActionKind {
     BlockProducerVrfEvaluatorInitializeEpochEvaluation,
     BlockProducerVrfEvaluatorInitializeEvaluator,
     BlockProducerVrfEvaluatorInterruptEpochEvaluation,
+    BlockProducerVrfEvaluatorPrecomputeDelegatorTable,
+    BlockProducerVrfEvaluatorPrecomputeDelegatorTableSuccess,
     BlockProducerVrfEvaluatorProcessSlotEvaluationSuccess,
     BlockProducerVrfEvaluatorSelectInitialSlot,
     BlockProducerVrfEvaluatorWaitForNextEvaluation,
@@ -154,7 +172,18 @@ pub enum ActionKind {
     BlockProducerVrfEvaluatorEffectfulInitializeStats,
     BlockProducerVrfEvaluatorEffectfulSlotEvaluated,
     CheckTimeouts,
+    CheckpointAdd,
+    CheckpointBestChainUpdate,
+    CheckpointP2pSend,
+    CheckpointP2pSendAll,
+    CheckpointReceived,
+    CheckpointEffectfulPersist,
+    CheckpointEffectfulSign,
+    CheckpointEffectfulVerify,
+    EventSourceEventShed,
     EventSourceNewEvent,
+    EventSourceOverloadEnd,
+    EventSourceOverloadStart,
     EventSourceProcessEvents,
     EventSourceWaitForEvents,
     EventSourceWaitTimeout,
@@ -175,16 +204,24 @@ pub enum ActionKind {
     ExternalSnarkWorkerEffectfulKill,
     ExternalSnarkWorkerEffectfulStart,
     ExternalSnarkWorkerEffectfulSubmitWork,
+    LedgerRootSnapshotWritten,
+    LedgerAuditInit,
+    LedgerAuditPending,
+    LedgerAuditSuccess,
+    LedgerEffectfulAuditInit,
     LedgerEffectfulReadInit,
     LedgerEffectfulWriteInit,
     LedgerReadFindTodos,
     LedgerReadInit,
+    LedgerReadPeerThrottled,
     LedgerReadPending,
     LedgerReadPrune,
     LedgerReadSuccess,
     LedgerWriteInit,
     LedgerWritePending,
     LedgerWriteSuccess,
+    P2pCallbacksP2pChannelsBestTipCompactReceived,
+    P2pCallbacksP2pChannelsBestTipCompactReconstructed,
     P2pCallbacksP2pChannelsRpcReady,
     P2pCallbacksP2pChannelsRpcRequestReceived,
     P2pCallbacksP2pChannelsRpcResponseReceived,
@@ -195,14 +232,32 @@ pub enum ActionKind {
     P2pCallbacksP2pDisconnection,
     P2pCallbacksP2pPubsubValidateMessage,
     P2pCallbacksRpcRespondBestTip,
+    P2pChannelsBestTipCompactReceived,
+    P2pChannelsBestTipCompactResolve,
+    P2pChannelsBestTipCompactVerifyError,
+    P2pChannelsBestTipCompactVerifySuccess,
+    P2pChannelsBestTipFullRequestReceived,
     P2pChannelsBestTipInit,
+    P2pChannelsBestTipMissingReceived,
+    P2pChannelsBestTipMissingRequestReceived,
     P2pChannelsBestTipPending,
     P2pChannelsBestTipReady,
     P2pChannelsBestTipReceived,
     P2pChannelsBestTipRequestReceived,
     P2pChannelsBestTipRequestSend,
+    P2pChannelsBestTipResponseExpired,
     P2pChannelsBestTipResponseSend,
+    P2pChannelsCheckpointInit,
+    P2pChannelsCheckpointPending,
+    P2pChannelsCheckpointPromiseReceived,
+    P2pChannelsCheckpointReady,
+    P2pChannelsCheckpointReceived,
+    P2pChannelsCheckpointRequestReceived,
+    P2pChannelsCheckpointRequestSend,
+    P2pChannelsCheckpointResponseSend,
+    P2pChannelsEffectfulCompressionEnable,
     P2pChannelsEffectfulInitChannel,
+    P2pChannelsEffectfulLossyGossipEnable,
     P2pChannelsEffectfulMessageSend,
     P2pChannelsEffectfulSignalingDiscoveryAnswerDecrypt,
     P2pChannelsEffectfulSignalingDiscoveryOfferEncryptAndSend,
@@ -213,6 +268,7 @@ pub enum ActionKind {
     P2pChannelsRpcPending,
     P2pChannelsRpcReady,
     P2pChannelsRpcRequestReceived,
+    P2pChannelsRpcRequestRetry,
     P2pChannelsRpcRequestSend,
     P2pChannelsRpcResponsePending,
     P2pChannelsRpcResponseReceived,
@@ -245,6 +301,7 @@ pub enum ActionKind {
     P2pChannelsSignalingExchangeReady,
     P2pChannelsSignalingExchangeRequestReceived,
     P2pChannelsSignalingExchangeRequestSend,
+    P2pChannelsSnarkBatchReceived,
     P2pChannelsSnarkInit,
     P2pChannelsSnarkLibp2pBroadcast,
     P2pChannelsSnarkLibp2pReceived,
@@ -277,6 +334,7 @@ pub enum ActionKind {
     P2pChannelsStreamingRpcResponseSendInit,
     P2pChannelsStreamingRpcResponseSent,
     P2pChannelsStreamingRpcTimeout,
+    P2pChannelsTransactionAnnounceReceived,
     P2pChannelsTransactionInit,
     P2pChannelsTransactionLibp2pBroadcast,
     P2pChannelsTransactionLibp2pReceived,
@@ -284,9 +342,21 @@ pub enum ActionKind {
     P2pChannelsTransactionPromiseReceived,
     P2pChannelsTransactionReady,
     P2pChannelsTransactionReceived,
+    P2pChannelsTransactionRequestByHashReceived,
+    P2pChannelsTransactionRequestByHashSend,
     P2pChannelsTransactionRequestReceived,
     P2pChannelsTransactionRequestSend,
     P2pChannelsTransactionResponseSend,
+    P2pConnectionIceRestartAnswerSdpCreateSuccess,
+    P2pConnectionIceRestartDegraded,
+    P2pConnectionIceRestartError,
+    P2pConnectionIceRestartInit,
+    P2pConnectionIceRestartNegotiateSuccess,
+    P2pConnectionIceRestartOfferReceived,
+    P2pConnectionIceRestartSuccess,
+    P2pConnectionIceRestartTimeout,
+    P2pConnectionIceRestartEffectfulInit,
+    P2pConnectionIceRestartEffectfulOfferSet,
     P2pConnectionIncomingAnswerReady,
     P2pConnectionIncomingAnswerSdpCreateError,
     P2pConnectionIncomingAnswerSdpCreatePending,
@@ -299,6 +369,7 @@ pub enum ActionKind {
     P2pConnectionIncomingFinalizeSuccess,
     P2pConnectionIncomingInit,
     P2pConnectionIncomingLibp2pReceived,
+    P2pConnectionIncomingRejected,
     P2pConnectionIncomingSuccess,
     P2pConnectionIncomingTimeout,
     P2pConnectionIncomingEffectfulConnectionAuthorizationDecryptAndCheck,
@@ -334,6 +405,7 @@ pub enum ActionKind {
     P2pDisconnectionRandomTry,
     P2pDisconnectionEffectfulInit,
     P2pEffectfulInitialize,
+    P2pFeatureFlagsUpdate,
     P2pIdentifyNewRequest,
     P2pIdentifyUpdatePeerInformation,
     P2pInitializeInitialize,
@@ -391,6 +463,7 @@ pub enum ActionKind {
     P2pNetworkPnetEffectfulOutgoingData,
     P2pNetworkPnetEffectfulSetupNonce,
     P2pNetworkPubsubBroadcast,
+    P2pNetworkPubsubBroadcastCheckpoint,
     P2pNetworkPubsubBroadcastSigned,
     P2pNetworkPubsubBroadcastValidatedMessage,
     P2pNetworkPubsubGraft,
@@ -468,29 +541,65 @@ pub enum ActionKind {
     P2pPeerDiscovered,
     P2pPeerReady,
     P2pPeerRemove,
+    P2pPeerRpcVersionUnsupported,
+    RpcAccountEventsSubscribe,
+    RpcAccountEventsUnsubscribe,
+    RpcAccountNextNoncesGetInit,
+    RpcAccountNextNoncesGetPending,
+    RpcAccountNextNoncesGetSuccess,
+    RpcAccountSubscriptions,
+    RpcAccountSubscriptionsEvaluate,
+    RpcAccountSubscriptionsUpdate,
     RpcActionStatsGet,
     RpcBestChain,
     RpcBlockGet,
+    RpcBlockProducerEpochReportGet,
     RpcBlockProducerStatsGet,
+    RpcBlockPropagationGet,
+    RpcBlockTemplateGet,
+    RpcBlockTemplateSubmit,
+    RpcChainImportBlock,
+    RpcCheckpointsGet,
     RpcConsensusConstantsGet,
     RpcConsensusTimeGet,
     RpcDiscoveryBoostrapStats,
     RpcDiscoveryRoutingTable,
+    RpcFeatureFlags,
+    RpcFinalityEstimateGet,
     RpcFinish,
     RpcGenesisBlock,
     RpcGlobalStateGet,
     RpcHealthCheck,
     RpcHeartbeatGet,
+    RpcInternalCommandsGet,
     RpcLedgerAccountDelegatorsGetInit,
     RpcLedgerAccountDelegatorsGetPending,
     RpcLedgerAccountDelegatorsGetSuccess,
+    RpcLedgerAccountsAtBlockArchiveGetInit,
+    RpcLedgerAccountsAtBlockArchiveGetSuccess,
+    RpcLedgerAccountsAtBlockGetInit,
+    RpcLedgerAccountsAtBlockGetPending,
+    RpcLedgerAccountsAtBlockGetSuccess,
+    RpcLedgerAccountsAtBlockLedgerGetInit,
     RpcLedgerAccountsGetInit,
     RpcLedgerAccountsGetPending,
     RpcLedgerAccountsGetSuccess,
+    RpcLedgerAudit,
+    RpcLedgerSessionInit,
+    RpcLedgerSessionPending,
+    RpcLedgerSessionSuccess,
+    RpcLedgerStatusExtendedGetInit,
+    RpcLedgerStatusExtendedGetPending,
+    RpcLedgerStatusExtendedGetSuccess,
     RpcLedgerStatusGetInit,
     RpcLedgerStatusGetPending,
     RpcLedgerStatusGetSuccess,
+    RpcLightModeRejected,
+    RpcMaintenanceMode,
+    RpcMaintenanceRejected,
     RpcMessageProgressGet,
+    RpcNodeInfoGet,
+    RpcOverloadShed,
     RpcP2pConnectionIncomingAnswerReady,
     RpcP2pConnectionIncomingError,
     RpcP2pConnectionIncomingInit,
@@ -501,9 +610,13 @@ pub enum ActionKind {
     RpcP2pConnectionOutgoingInit,
     RpcP2pConnectionOutgoingPending,
     RpcP2pConnectionOutgoingSuccess,
+    RpcP2pDiagnosticsGet,
+    RpcPayoutsBatchGet,
+    RpcPayoutsBatchSubmit,
     RpcPeersGet,
     RpcPooledUserCommands,
     RpcPooledZkappCommands,
+    RpcProfiler,
     RpcReadinessCheck,
     RpcScanStateSummaryGetInit,
     RpcScanStateSummaryGetPending,
@@ -511,69 +624,127 @@ pub enum ActionKind {
     RpcScanStateSummaryLedgerGetInit,
     RpcSnarkPoolAvailableJobsGet,
     RpcSnarkPoolCompletedJobsGet,
+    RpcSnarkPoolJobEventsPublish,
+    RpcSnarkPoolJobEventsSubscribe,
+    RpcSnarkPoolJobEventsUnsubscribe,
     RpcSnarkPoolJobGet,
     RpcSnarkPoolPendingJobsGet,
+    RpcSnarkVerifyStatsGet,
     RpcSnarkerConfigGet,
     RpcSnarkerJobCommit,
     RpcSnarkerJobSpec,
     RpcSnarkerWorkersGet,
+    RpcStateDigestGet,
+    RpcStatsHistoryGet,
     RpcStatusGet,
+    RpcSyncPeerScoresGet,
     RpcSyncStatsGet,
+    RpcTransactionInclusionProofGet,
     RpcTransactionInjectFailure,
     RpcTransactionInjectInit,
     RpcTransactionInjectPending,
     RpcTransactionInjectRejected,
     RpcTransactionInjectSuccess,
     RpcTransactionPool,
+    RpcTransactionPoolSnapshot,
+    RpcTransactionPoolZkappStatsGet,
     RpcTransactionStatusGet,
     RpcTransitionFrontierUserCommandsGet,
+    RpcVrfVerifyInit,
+    RpcVrfVerifyPending,
+    RpcVrfVerifySuccess,
+    RpcZkappFailureStatsGet,
+    RpcZkappFeeSponsor,
+    RpcZkappPreconditionsEvaluate,
+    RpcZkappVkRegister,
+    RpcEffectfulAccountEventsSend,
+    RpcEffectfulAccountNextNoncesGetSuccess,
+    RpcEffectfulAccountSubscriptions,
+    RpcEffectfulAccountSubscriptionsFetch,
     RpcEffectfulActionStatsGet,
     RpcEffectfulBestChain,
     RpcEffectfulBlockGet,
+    RpcEffectfulBlockProducerEpochReportGet,
     RpcEffectfulBlockProducerStatsGet,
+    RpcEffectfulBlockPropagationGet,
+    RpcEffectfulBlockTemplateGet,
+    RpcEffectfulBlockTemplateSubmit,
+    RpcEffectfulChainImportBlock,
+    RpcEffectfulCheckpointsGet,
     RpcEffectfulConsensusConstantsGet,
     RpcEffectfulConsensusTimeGet,
     RpcEffectfulDiscoveryBoostrapStats,
     RpcEffectfulDiscoveryRoutingTable,
+    RpcEffectfulFeatureFlags,
+    RpcEffectfulFinalityEstimateGet,
     RpcEffectfulGenesisBlock,
     RpcEffectfulGlobalStateGet,
     RpcEffectfulHealthCheck,
     RpcEffectfulHeartbeatGet,
+    RpcEffectfulInternalCommandsGet,
     RpcEffectfulLedgerAccountDelegatorsGetSuccess,
+    RpcEffectfulLedgerAccountsAtBlockArchiveGet,
+    RpcEffectfulLedgerAccountsAtBlockGetSuccess,
     RpcEffectfulLedgerAccountsGetSuccess,
+    RpcEffectfulLedgerAudit,
+    RpcEffectfulLedgerSessionSuccess,
+    RpcEffectfulLedgerStatusExtendedGetSuccess,
     RpcEffectfulLedgerStatusGetSuccess,
+    RpcEffectfulLightModeRejected,
+    RpcEffectfulMaintenanceMode,
+    RpcEffectfulMaintenanceRejected,
     RpcEffectfulMessageProgressGet,
+    RpcEffectfulNodeInfoGet,
+    RpcEffectfulOverloadShed,
     RpcEffectfulP2pConnectionIncomingError,
     RpcEffectfulP2pConnectionIncomingRespond,
     RpcEffectfulP2pConnectionIncomingSuccess,
     RpcEffectfulP2pConnectionOutgoingError,
     RpcEffectfulP2pConnectionOutgoingSuccess,
+    RpcEffectfulP2pDiagnosticsGet,
+    RpcEffectfulPayoutsBatchGet,
+    RpcEffectfulPayoutsBatchSubmit,
     RpcEffectfulPeersGet,
     RpcEffectfulPooledUserCommands,
     RpcEffectfulPooledZkappCommands,
+    RpcEffectfulProfiler,
     RpcEffectfulReadinessCheck,
     RpcEffectfulScanStateSummaryGetSuccess,
     RpcEffectfulSnarkPoolAvailableJobsGet,
     RpcEffectfulSnarkPoolCompletedJobsGet,
+    RpcEffectfulSnarkPoolJobEventsSend,
     RpcEffectfulSnarkPoolJobGet,
     RpcEffectfulSnarkPoolPendingJobsGet,
+    RpcEffectfulSnarkVerifyStatsGet,
     RpcEffectfulSnarkerConfigGet,
     RpcEffectfulSnarkerJobCommit,
     RpcEffectfulSnarkerJobSpec,
     RpcEffectfulSnarkerWorkersGet,
+    RpcEffectfulStateDigestGet,
+    RpcEffectfulStatsHistoryGet,
     RpcEffectfulStatusGet,
+    RpcEffectfulSyncPeerScoresGet,
     RpcEffectfulSyncStatsGet,
+    RpcEffectfulTransactionInclusionProofGet,
     RpcEffectfulTransactionInjectFailure,
     RpcEffectfulTransactionInjectRejected,
     RpcEffectfulTransactionInjectSuccess,
     RpcEffectfulTransactionPool,
+    RpcEffectfulTransactionPoolSnapshot,
+    RpcEffectfulTransactionPoolZkappStatsGet,
     RpcEffectfulTransactionStatusGet,
     RpcEffectfulTransitionFrontierUserCommandsGet,
+    RpcEffectfulVrfVerifySuccess,
+    RpcEffectfulZkappFailureStatsGet,
+    RpcEffectfulZkappPreconditionsEvaluate,
+    RpcEffectfulZkappVkRegister,
+    SnarkBlockVerifyCancel,
     SnarkBlockVerifyError,
     SnarkBlockVerifyFinish,
     SnarkBlockVerifyInit,
     SnarkBlockVerifyPending,
     SnarkBlockVerifySuccess,
+    SnarkBlockVerifyEffectfulCancel,
     SnarkBlockVerifyEffectfulInit,
     SnarkPoolAutoCreateCommitment,
     SnarkPoolCheckTimeouts,
@@ -595,6 +766,7 @@ pub enum ActionKind {
     SnarkPoolCandidateWorkVerifyError,
     SnarkPoolCandidateWorkVerifyNext,
     SnarkPoolCandidateWorkVerifyPending,
+    SnarkPoolCandidateWorkVerifySkipped,
     SnarkPoolCandidateWorkVerifySuccess,
     SnarkPoolEffectfulSnarkPoolJobsRandomChoose,
     SnarkUserCommandVerifyError,
@@ -603,12 +775,14 @@ pub enum ActionKind {
     SnarkUserCommandVerifyPending,
     SnarkUserCommandVerifySuccess,
     SnarkUserCommandVerifyEffectfulInit,
+    SnarkVerifyAdmissionDenied,
     SnarkWorkVerifyError,
     SnarkWorkVerifyFinish,
     SnarkWorkVerifyInit,
     SnarkWorkVerifyPending,
     SnarkWorkVerifySuccess,
     SnarkWorkVerifyEffectfulInit,
+    TransactionPoolAccountsTouched,
     TransactionPoolApplyTransitionFrontierDiff,
     TransactionPoolApplyTransitionFrontierDiffWithAccounts,
     TransactionPoolApplyVerifiedDiff,
@@ -616,13 +790,20 @@ pub enum ActionKind {
     TransactionPoolBestTipChanged,
     TransactionPoolBestTipChangedWithAccounts,
     TransactionPoolCollectTransactionsByFee,
+    TransactionPoolCommandsDropped,
+    TransactionPoolFeePayersRateLimited,
     TransactionPoolP2pSend,
     TransactionPoolP2pSendAll,
     TransactionPoolRebroadcast,
+    TransactionPoolRevalidateTouched,
+    TransactionPoolRevalidateTouchedWithAccounts,
+    TransactionPoolSnapshotCapture,
     TransactionPoolStartVerify,
     TransactionPoolStartVerifyWithAccounts,
     TransactionPoolVerifyError,
     TransactionPoolVerifySuccess,
+    TransactionPoolVkRegister,
+    TransactionPoolCandidateDigestsReceived,
     TransactionPoolCandidateFetchAll,
     TransactionPoolCandidateFetchError,
     TransactionPoolCandidateFetchInit,
@@ -631,11 +812,30 @@ pub enum ActionKind {
     TransactionPoolCandidateInfoReceived,
     TransactionPoolCandidateLibp2pTransactionsReceived,
     TransactionPoolCandidatePeerPrune,
+    TransactionPoolCandidatePoolSyncError,
+    TransactionPoolCandidatePoolSyncInit,
+    TransactionPoolCandidatePoolSyncPageReceived,
+    TransactionPoolCandidatePoolSyncRequestInit,
+    TransactionPoolCandidatePoolSyncRequestPending,
     TransactionPoolCandidateVerifyError,
     TransactionPoolCandidateVerifyNext,
     TransactionPoolCandidateVerifyPending,
     TransactionPoolCandidateVerifySuccess,
     TransactionPoolEffectfulFetchAccounts,
+    TransactionPoolEffectfulPayoutsSign,
+    TransactionPoolEffectfulSponsorSign,
+    TransactionPoolPayoutsBatchAdd,
+    TransactionPoolPayoutsCheckTimeouts,
+    TransactionPoolPayoutsInjectError,
+    TransactionPoolPayoutsInjectNext,
+    TransactionPoolPayoutsInjectResult,
+    TransactionPoolPayoutsNonceFetchInit,
+    TransactionPoolPayoutsNonceFetchSuccess,
+    TransactionPoolPayoutsSigned,
+    TransactionPoolSponsorError,
+    TransactionPoolSponsorInit,
+    TransactionPoolSponsorNonceFetchSuccess,
+    TransactionPoolSponsorSigned,
     TransitionFrontierGenesisInject,
     TransitionFrontierGenesisProvenInject,
     TransitionFrontierSyncFailed,
@@ -649,11 +849,18 @@ pub enum ActionKind {
     TransitionFrontierCandidateBlockSnarkVerifySuccess,
     TransitionFrontierCandidateP2pBestTipUpdate,
     TransitionFrontierCandidatePrune,
+    TransitionFrontierCandidatePruneStale,
     TransitionFrontierCandidateTransitionFrontierSyncTargetUpdate,
     TransitionFrontierGenesisLedgerLoadInit,
     TransitionFrontierGenesisLedgerLoadPending,
     TransitionFrontierGenesisLedgerLoadSuccess,
     TransitionFrontierGenesisProduce,
+    TransitionFrontierGenesisProofPeerFetchInit,
+    TransitionFrontierGenesisProofPeerFetchPending,
+    TransitionFrontierGenesisProofPeerFetchSuccess,
+    TransitionFrontierGenesisProofPeerVerifyError,
+    TransitionFrontierGenesisProofPeerVerifyPending,
+    TransitionFrontierGenesisProofPeerVerifySuccess,
     TransitionFrontierGenesisProveInit,
     TransitionFrontierGenesisProvePending,
     TransitionFrontierGenesisProveSuccess,
@@ -735,10 +942,11 @@ pub enum ActionKind {
     WatchedAccountsLedgerInitialStateGetRetry,
     WatchedAccountsLedgerInitialStateGetSuccess,
     WatchedAccountsTransactionsIncludedInBlock,
+    WatchtowerBestChainUpdate,
 }
 
 impl ActionKind {
-    pub const COUNT: u16 = 628;
+    pub const COUNT: u16 = 826;
 }
 
 impl std::fmt::Display for ActionKind {
@@ -770,6 +978,9 @@ impl ActionKindGet for Action {
             Self::Rpc(a) => a.kind(),
             Self::RpcEffectful(a) => a.kind(),
             Self::WatchedAccounts(a) => a.kind(),
+            Self::Watchtower(a) => a.kind(),
+            Self::Checkpoint(a) => a.kind(),
+            Self::CheckpointEffectful(a) => a.kind(),
         }
     }
 }
@@ -787,6 +998,9 @@ impl ActionKindGet for EventSourceAction {
             Self::NewEvent { .. } => ActionKind::EventSourceNewEvent,
             Self::WaitForEvents => ActionKind::EventSourceWaitForEvents,
             Self::WaitTimeout => ActionKind::EventSourceWaitTimeout,
+            Self::OverloadStart { .. } => ActionKind::EventSourceOverloadStart,
+            Self::OverloadEnd { .. } => ActionKind::EventSourceOverloadEnd,
+            Self::EventShed => ActionKind::EventSourceEventShed,
         }
     }
 }
@@ -801,6 +1015,7 @@ impl ActionKindGet for P2pAction {
             Self::Channels(a) => a.kind(),
             Self::Peer(a) => a.kind(),
             Self::Network(a) => a.kind(),
+            Self::FeatureFlags(a) => a.kind(),
         }
     }
 }
@@ -839,6 +1054,12 @@ impl ActionKindGet for P2pCallbacksAction {
             }
             Self::P2pDisconnection { .. } => ActionKind::P2pCallbacksP2pDisconnection,
             Self::RpcRespondBestTip { .. } => ActionKind::P2pCallbacksRpcRespondBestTip,
+            Self::P2pChannelsBestTipCompactReceived { .. } => {
+                ActionKind::P2pCallbacksP2pChannelsBestTipCompactReceived
+            }
+            Self::P2pChannelsBestTipCompactReconstructed { .. } => {
+                ActionKind::P2pCallbacksP2pChannelsBestTipCompactReconstructed
+            }
             Self::P2pPubsubValidateMessage { .. } => {
                 ActionKind::P2pCallbacksP2pPubsubValidateMessage
             }
@@ -851,6 +1072,8 @@ impl ActionKindGet for LedgerAction {
         match self {
             Self::Write(a) => a.kind(),
             Self::Read(a) => a.kind(),
+            Self::Audit(a) => a.kind(),
+            Self::RootSnapshotWritten { .. } => ActionKind::LedgerRootSnapshotWritten,
         }
     }
 }
@@ -860,6 +1083,7 @@ impl ActionKindGet for LedgerEffectfulAction {
         match self {
             Self::WriteInit { .. } => ActionKind::LedgerEffectfulWriteInit,
             Self::ReadInit { .. } => ActionKind::LedgerEffectfulReadInit,
+            Self::AuditInit { .. } => ActionKind::LedgerEffectfulAuditInit,
         }
     }
 }
@@ -873,6 +1097,7 @@ impl ActionKindGet for SnarkAction {
             Self::WorkVerifyEffect(a) => a.kind(),
             Self::UserCommandVerify(a) => a.kind(),
             Self::UserCommandVerifyEffect(a) => a.kind(),
+            Self::VerifyAdmission(a) => a.kind(),
         }
     }
 }
@@ -924,16 +1149,25 @@ impl ActionKindGet for TransactionPoolAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::Candidate(a) => a.kind(),
+            Self::Payouts(a) => a.kind(),
+            Self::Sponsor(a) => a.kind(),
             Self::StartVerify { .. } => ActionKind::TransactionPoolStartVerify,
             Self::StartVerifyWithAccounts { .. } => {
                 ActionKind::TransactionPoolStartVerifyWithAccounts
             }
             Self::VerifySuccess { .. } => ActionKind::TransactionPoolVerifySuccess,
             Self::VerifyError { .. } => ActionKind::TransactionPoolVerifyError,
+            Self::FeePayersRateLimited { .. } => ActionKind::TransactionPoolFeePayersRateLimited,
+            Self::AccountsTouched { .. } => ActionKind::TransactionPoolAccountsTouched,
             Self::BestTipChanged { .. } => ActionKind::TransactionPoolBestTipChanged,
             Self::BestTipChangedWithAccounts { .. } => {
                 ActionKind::TransactionPoolBestTipChangedWithAccounts
             }
+            Self::RevalidateTouched { .. } => ActionKind::TransactionPoolRevalidateTouched,
+            Self::RevalidateTouchedWithAccounts { .. } => {
+                ActionKind::TransactionPoolRevalidateTouchedWithAccounts
+            }
+            Self::CommandsDropped { .. } => ActionKind::TransactionPoolCommandsDropped,
             Self::ApplyVerifiedDiff { .. } => ActionKind::TransactionPoolApplyVerifiedDiff,
             Self::ApplyVerifiedDiffWithAccounts { .. } => {
                 ActionKind::TransactionPoolApplyVerifiedDiffWithAccounts
@@ -946,6 +1180,8 @@ impl ActionKindGet for TransactionPoolAction {
             }
             Self::Rebroadcast { .. } => ActionKind::TransactionPoolRebroadcast,
             Self::CollectTransactionsByFee => ActionKind::TransactionPoolCollectTransactionsByFee,
+            Self::SnapshotCapture => ActionKind::TransactionPoolSnapshotCapture,
+            Self::VkRegister { .. } => ActionKind::TransactionPoolVkRegister,
             Self::P2pSendAll => ActionKind::TransactionPoolP2pSendAll,
             Self::P2pSend { .. } => ActionKind::TransactionPoolP2pSend,
         }
@@ -956,6 +1192,8 @@ impl ActionKindGet for TransactionPoolEffectfulAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::FetchAccounts { .. } => ActionKind::TransactionPoolEffectfulFetchAccounts,
+            Self::PayoutsSign { .. } => ActionKind::TransactionPoolEffectfulPayoutsSign,
+            Self::SponsorSign { .. } => ActionKind::TransactionPoolEffectfulSponsorSign,
         }
     }
 }
@@ -1000,6 +1238,9 @@ impl ActionKindGet for BlockProducerAction {
             Self::WonSlot { .. } => ActionKind::BlockProducerWonSlot,
             Self::WonSlotDiscard { .. } => ActionKind::BlockProducerWonSlotDiscard,
             Self::WonSlotWait => ActionKind::BlockProducerWonSlotWait,
+            Self::WonSlotClaimInit => ActionKind::BlockProducerWonSlotClaimInit,
+            Self::WonSlotClaimSuccess { .. } => ActionKind::BlockProducerWonSlotClaimSuccess,
+            Self::WonSlotClaimError { .. } => ActionKind::BlockProducerWonSlotClaimError,
             Self::WonSlotTransactionsGet => ActionKind::BlockProducerWonSlotTransactionsGet,
             Self::WonSlotTransactionsSuccess { .. } => {
                 ActionKind::BlockProducerWonSlotTransactionsSuccess
@@ -1012,9 +1253,11 @@ impl ActionKindGet for BlockProducerAction {
             Self::StagedLedgerDiffCreateSuccess { .. } => {
                 ActionKind::BlockProducerStagedLedgerDiffCreateSuccess
             }
+            Self::ExternalDiffSubmit { .. } => ActionKind::BlockProducerExternalDiffSubmit,
             Self::BlockUnprovenBuild => ActionKind::BlockProducerBlockUnprovenBuild,
             Self::BlockProveInit => ActionKind::BlockProducerBlockProveInit,
             Self::BlockProvePending => ActionKind::BlockProducerBlockProvePending,
+            Self::BlockSigningRefused { .. } => ActionKind::BlockProducerBlockSigningRefused,
             Self::BlockProveSuccess { .. } => ActionKind::BlockProducerBlockProveSuccess,
             Self::BlockProduced => ActionKind::BlockProducerBlockProduced,
             Self::BlockInject => ActionKind::BlockProducerBlockInject,
@@ -1029,10 +1272,11 @@ impl ActionKindGet for BlockProducerEffectfulAction {
             Self::VrfEvaluator(a) => a.kind(),
             Self::WonSlot { .. } => ActionKind::BlockProducerEffectfulWonSlot,
             Self::WonSlotDiscard { .. } => ActionKind::BlockProducerEffectfulWonSlotDiscard,
+            Self::WonSlotClaimInit { .. } => ActionKind::BlockProducerEffectfulWonSlotClaimInit,
             Self::StagedLedgerDiffCreateInit => {
                 ActionKind::BlockProducerEffectfulStagedLedgerDiffCreateInit
             }
-            Self::StagedLedgerDiffCreateSuccess => {
+            Self::StagedLedgerDiffCreateSuccess { .. } => {
                 ActionKind::BlockProducerEffectfulStagedLedgerDiffCreateSuccess
             }
             Self::BlockUnprovenBuild => ActionKind::BlockProducerEffectfulBlockUnprovenBuild,
@@ -1049,11 +1293,18 @@ impl ActionKindGet for RpcAction {
             Self::GlobalStateGet { .. } => ActionKind::RpcGlobalStateGet,
             Self::StatusGet { .. } => ActionKind::RpcStatusGet,
             Self::HeartbeatGet { .. } => ActionKind::RpcHeartbeatGet,
+            Self::StateDigestGet { .. } => ActionKind::RpcStateDigestGet,
             Self::ActionStatsGet { .. } => ActionKind::RpcActionStatsGet,
             Self::SyncStatsGet { .. } => ActionKind::RpcSyncStatsGet,
             Self::BlockProducerStatsGet { .. } => ActionKind::RpcBlockProducerStatsGet,
+            Self::BlockProducerEpochReportGet { .. } => ActionKind::RpcBlockProducerEpochReportGet,
+            Self::ZkappFailureStatsGet { .. } => ActionKind::RpcZkappFailureStatsGet,
+            Self::SnarkVerifyStatsGet { .. } => ActionKind::RpcSnarkVerifyStatsGet,
+            Self::StatsHistoryGet { .. } => ActionKind::RpcStatsHistoryGet,
+            Self::BlockPropagationGet { .. } => ActionKind::RpcBlockPropagationGet,
             Self::MessageProgressGet { .. } => ActionKind::RpcMessageProgressGet,
             Self::PeersGet { .. } => ActionKind::RpcPeersGet,
+            Self::P2pDiagnosticsGet { .. } => ActionKind::RpcP2pDiagnosticsGet,
             Self::P2pConnectionOutgoingInit { .. } => ActionKind::RpcP2pConnectionOutgoingInit,
             Self::P2pConnectionOutgoingPending { .. } => {
                 ActionKind::RpcP2pConnectionOutgoingPending
@@ -1095,9 +1346,33 @@ impl ActionKindGet for RpcAction {
             Self::DiscoveryRoutingTable { .. } => ActionKind::RpcDiscoveryRoutingTable,
             Self::DiscoveryBoostrapStats { .. } => ActionKind::RpcDiscoveryBoostrapStats,
             Self::TransactionPool { .. } => ActionKind::RpcTransactionPool,
+            Self::TransactionPoolZkappStatsGet { .. } => {
+                ActionKind::RpcTransactionPoolZkappStatsGet
+            }
             Self::LedgerAccountsGetInit { .. } => ActionKind::RpcLedgerAccountsGetInit,
             Self::LedgerAccountsGetPending { .. } => ActionKind::RpcLedgerAccountsGetPending,
             Self::LedgerAccountsGetSuccess { .. } => ActionKind::RpcLedgerAccountsGetSuccess,
+            Self::LedgerAccountsAtBlockGetInit { .. } => {
+                ActionKind::RpcLedgerAccountsAtBlockGetInit
+            }
+            Self::LedgerAccountsAtBlockLedgerGetInit { .. } => {
+                ActionKind::RpcLedgerAccountsAtBlockLedgerGetInit
+            }
+            Self::LedgerAccountsAtBlockGetPending { .. } => {
+                ActionKind::RpcLedgerAccountsAtBlockGetPending
+            }
+            Self::LedgerAccountsAtBlockGetSuccess { .. } => {
+                ActionKind::RpcLedgerAccountsAtBlockGetSuccess
+            }
+            Self::LedgerAccountsAtBlockArchiveGetInit { .. } => {
+                ActionKind::RpcLedgerAccountsAtBlockArchiveGetInit
+            }
+            Self::LedgerAccountsAtBlockArchiveGetSuccess { .. } => {
+                ActionKind::RpcLedgerAccountsAtBlockArchiveGetSuccess
+            }
+            Self::AccountNextNoncesGetInit { .. } => ActionKind::RpcAccountNextNoncesGetInit,
+            Self::AccountNextNoncesGetPending { .. } => ActionKind::RpcAccountNextNoncesGetPending,
+            Self::AccountNextNoncesGetSuccess { .. } => ActionKind::RpcAccountNextNoncesGetSuccess,
             Self::TransactionInjectInit { .. } => ActionKind::RpcTransactionInjectInit,
             Self::TransactionInjectPending { .. } => ActionKind::RpcTransactionInjectPending,
             Self::TransactionInjectSuccess { .. } => ActionKind::RpcTransactionInjectSuccess,
@@ -1114,6 +1389,16 @@ impl ActionKindGet for RpcAction {
             Self::LedgerStatusGetInit { .. } => ActionKind::RpcLedgerStatusGetInit,
             Self::LedgerStatusGetPending { .. } => ActionKind::RpcLedgerStatusGetPending,
             Self::LedgerStatusGetSuccess { .. } => ActionKind::RpcLedgerStatusGetSuccess,
+            Self::LedgerStatusExtendedGetInit { .. } => ActionKind::RpcLedgerStatusExtendedGetInit,
+            Self::LedgerStatusExtendedGetPending { .. } => {
+                ActionKind::RpcLedgerStatusExtendedGetPending
+            }
+            Self::LedgerStatusExtendedGetSuccess { .. } => {
+                ActionKind::RpcLedgerStatusExtendedGetSuccess
+            }
+            Self::LedgerSessionInit { .. } => ActionKind::RpcLedgerSessionInit,
+            Self::LedgerSessionPending { .. } => ActionKind::RpcLedgerSessionPending,
+            Self::LedgerSessionSuccess { .. } => ActionKind::RpcLedgerSessionSuccess,
             Self::LedgerAccountDelegatorsGetInit { .. } => {
                 ActionKind::RpcLedgerAccountDelegatorsGetInit
             }
@@ -1123,6 +1408,43 @@ impl ActionKindGet for RpcAction {
             Self::LedgerAccountDelegatorsGetSuccess { .. } => {
                 ActionKind::RpcLedgerAccountDelegatorsGetSuccess
             }
+            Self::TransactionInclusionProofGet { .. } => {
+                ActionKind::RpcTransactionInclusionProofGet
+            }
+            Self::FinalityEstimateGet { .. } => ActionKind::RpcFinalityEstimateGet,
+            Self::NodeInfoGet { .. } => ActionKind::RpcNodeInfoGet,
+            Self::InternalCommandsGet { .. } => ActionKind::RpcInternalCommandsGet,
+            Self::SyncPeerScoresGet { .. } => ActionKind::RpcSyncPeerScoresGet,
+            Self::Profiler { .. } => ActionKind::RpcProfiler,
+            Self::MaintenanceMode { .. } => ActionKind::RpcMaintenanceMode,
+            Self::FeatureFlags { .. } => ActionKind::RpcFeatureFlags,
+            Self::CheckpointsGet { .. } => ActionKind::RpcCheckpointsGet,
+            Self::TransactionPoolSnapshot { .. } => ActionKind::RpcTransactionPoolSnapshot,
+            Self::VrfVerifyInit { .. } => ActionKind::RpcVrfVerifyInit,
+            Self::VrfVerifyPending { .. } => ActionKind::RpcVrfVerifyPending,
+            Self::VrfVerifySuccess { .. } => ActionKind::RpcVrfVerifySuccess,
+            Self::LedgerAudit { .. } => ActionKind::RpcLedgerAudit,
+            Self::BlockTemplateGet { .. } => ActionKind::RpcBlockTemplateGet,
+            Self::BlockTemplateSubmit { .. } => ActionKind::RpcBlockTemplateSubmit,
+            Self::MaintenanceRejected { .. } => ActionKind::RpcMaintenanceRejected,
+            Self::OverloadShed { .. } => ActionKind::RpcOverloadShed,
+            Self::LightModeRejected { .. } => ActionKind::RpcLightModeRejected,
+            Self::AccountSubscriptions { .. } => ActionKind::RpcAccountSubscriptions,
+            Self::AccountEventsSubscribe { .. } => ActionKind::RpcAccountEventsSubscribe,
+            Self::AccountEventsUnsubscribe { .. } => ActionKind::RpcAccountEventsUnsubscribe,
+            Self::AccountSubscriptionsEvaluate => ActionKind::RpcAccountSubscriptionsEvaluate,
+            Self::AccountSubscriptionsUpdate { .. } => ActionKind::RpcAccountSubscriptionsUpdate,
+            Self::SnarkPoolJobEventsSubscribe { .. } => ActionKind::RpcSnarkPoolJobEventsSubscribe,
+            Self::SnarkPoolJobEventsUnsubscribe { .. } => {
+                ActionKind::RpcSnarkPoolJobEventsUnsubscribe
+            }
+            Self::SnarkPoolJobEventsPublish { .. } => ActionKind::RpcSnarkPoolJobEventsPublish,
+            Self::ZkappVkRegister { .. } => ActionKind::RpcZkappVkRegister,
+            Self::ZkappPreconditionsEvaluate { .. } => ActionKind::RpcZkappPreconditionsEvaluate,
+            Self::PayoutsBatchSubmit { .. } => ActionKind::RpcPayoutsBatchSubmit,
+            Self::PayoutsBatchGet { .. } => ActionKind::RpcPayoutsBatchGet,
+            Self::ZkappFeeSponsor { .. } => ActionKind::RpcZkappFeeSponsor,
+            Self::ChainImportBlock { .. } => ActionKind::RpcChainImportBlock,
             Self::PooledUserCommands { .. } => ActionKind::RpcPooledUserCommands,
             Self::PooledZkappCommands { .. } => ActionKind::RpcPooledZkappCommands,
             Self::GenesisBlock { .. } => ActionKind::RpcGenesisBlock,
@@ -1137,11 +1459,20 @@ impl ActionKindGet for RpcEffectfulAction {
             Self::GlobalStateGet { .. } => ActionKind::RpcEffectfulGlobalStateGet,
             Self::StatusGet { .. } => ActionKind::RpcEffectfulStatusGet,
             Self::HeartbeatGet { .. } => ActionKind::RpcEffectfulHeartbeatGet,
+            Self::StateDigestGet { .. } => ActionKind::RpcEffectfulStateDigestGet,
             Self::ActionStatsGet { .. } => ActionKind::RpcEffectfulActionStatsGet,
             Self::SyncStatsGet { .. } => ActionKind::RpcEffectfulSyncStatsGet,
             Self::BlockProducerStatsGet { .. } => ActionKind::RpcEffectfulBlockProducerStatsGet,
+            Self::BlockProducerEpochReportGet { .. } => {
+                ActionKind::RpcEffectfulBlockProducerEpochReportGet
+            }
+            Self::ZkappFailureStatsGet { .. } => ActionKind::RpcEffectfulZkappFailureStatsGet,
+            Self::SnarkVerifyStatsGet { .. } => ActionKind::RpcEffectfulSnarkVerifyStatsGet,
+            Self::StatsHistoryGet { .. } => ActionKind::RpcEffectfulStatsHistoryGet,
+            Self::BlockPropagationGet { .. } => ActionKind::RpcEffectfulBlockPropagationGet,
             Self::MessageProgressGet { .. } => ActionKind::RpcEffectfulMessageProgressGet,
             Self::PeersGet { .. } => ActionKind::RpcEffectfulPeersGet,
+            Self::P2pDiagnosticsGet { .. } => ActionKind::RpcEffectfulP2pDiagnosticsGet,
             Self::P2pConnectionOutgoingError { .. } => {
                 ActionKind::RpcEffectfulP2pConnectionOutgoingError
             }
@@ -1177,9 +1508,21 @@ impl ActionKindGet for RpcEffectfulAction {
             Self::DiscoveryRoutingTable { .. } => ActionKind::RpcEffectfulDiscoveryRoutingTable,
             Self::DiscoveryBoostrapStats { .. } => ActionKind::RpcEffectfulDiscoveryBoostrapStats,
             Self::TransactionPool { .. } => ActionKind::RpcEffectfulTransactionPool,
+            Self::TransactionPoolZkappStatsGet { .. } => {
+                ActionKind::RpcEffectfulTransactionPoolZkappStatsGet
+            }
             Self::LedgerAccountsGetSuccess { .. } => {
                 ActionKind::RpcEffectfulLedgerAccountsGetSuccess
             }
+            Self::LedgerAccountsAtBlockArchiveGet { .. } => {
+                ActionKind::RpcEffectfulLedgerAccountsAtBlockArchiveGet
+            }
+            Self::LedgerAccountsAtBlockGetSuccess { .. } => {
+                ActionKind::RpcEffectfulLedgerAccountsAtBlockGetSuccess
+            }
+            Self::AccountNextNoncesGetSuccess { .. } => {
+                ActionKind::RpcEffectfulAccountNextNoncesGetSuccess
+            }
             Self::TransactionInjectSuccess { .. } => {
                 ActionKind::RpcEffectfulTransactionInjectSuccess
             }
@@ -1196,11 +1539,47 @@ impl ActionKindGet for RpcEffectfulAction {
             Self::ConsensusConstantsGet { .. } => ActionKind::RpcEffectfulConsensusConstantsGet,
             Self::TransactionStatusGet { .. } => ActionKind::RpcEffectfulTransactionStatusGet,
             Self::BlockGet { .. } => ActionKind::RpcEffectfulBlockGet,
+            Self::TransactionInclusionProofGet { .. } => {
+                ActionKind::RpcEffectfulTransactionInclusionProofGet
+            }
+            Self::FinalityEstimateGet { .. } => ActionKind::RpcEffectfulFinalityEstimateGet,
+            Self::NodeInfoGet { .. } => ActionKind::RpcEffectfulNodeInfoGet,
+            Self::InternalCommandsGet { .. } => ActionKind::RpcEffectfulInternalCommandsGet,
+            Self::SyncPeerScoresGet { .. } => ActionKind::RpcEffectfulSyncPeerScoresGet,
+            Self::Profiler { .. } => ActionKind::RpcEffectfulProfiler,
+            Self::MaintenanceMode { .. } => ActionKind::RpcEffectfulMaintenanceMode,
+            Self::FeatureFlags { .. } => ActionKind::RpcEffectfulFeatureFlags,
+            Self::CheckpointsGet { .. } => ActionKind::RpcEffectfulCheckpointsGet,
+            Self::TransactionPoolSnapshot { .. } => ActionKind::RpcEffectfulTransactionPoolSnapshot,
+            Self::VrfVerifySuccess { .. } => ActionKind::RpcEffectfulVrfVerifySuccess,
+            Self::LedgerAudit { .. } => ActionKind::RpcEffectfulLedgerAudit,
+            Self::BlockTemplateGet { .. } => ActionKind::RpcEffectfulBlockTemplateGet,
+            Self::BlockTemplateSubmit { .. } => ActionKind::RpcEffectfulBlockTemplateSubmit,
+            Self::MaintenanceRejected { .. } => ActionKind::RpcEffectfulMaintenanceRejected,
+            Self::OverloadShed { .. } => ActionKind::RpcEffectfulOverloadShed,
+            Self::LightModeRejected { .. } => ActionKind::RpcEffectfulLightModeRejected,
+            Self::AccountSubscriptions { .. } => ActionKind::RpcEffectfulAccountSubscriptions,
+            Self::AccountSubscriptionsFetch { .. } => {
+                ActionKind::RpcEffectfulAccountSubscriptionsFetch
+            }
+            Self::AccountEventsSend { .. } => ActionKind::RpcEffectfulAccountEventsSend,
+            Self::SnarkPoolJobEventsSend { .. } => ActionKind::RpcEffectfulSnarkPoolJobEventsSend,
+            Self::ZkappVkRegister { .. } => ActionKind::RpcEffectfulZkappVkRegister,
+            Self::ZkappPreconditionsEvaluate { .. } => {
+                ActionKind::RpcEffectfulZkappPreconditionsEvaluate
+            }
+            Self::PayoutsBatchSubmit { .. } => ActionKind::RpcEffectfulPayoutsBatchSubmit,
+            Self::PayoutsBatchGet { .. } => ActionKind::RpcEffectfulPayoutsBatchGet,
+            Self::ChainImportBlock { .. } => ActionKind::RpcEffectfulChainImportBlock,
             Self::PooledUserCommands { .. } => ActionKind::RpcEffectfulPooledUserCommands,
             Self::PooledZkappCommands { .. } => ActionKind::RpcEffectfulPooledZkappCommands,
             Self::GenesisBlock { .. } => ActionKind::RpcEffectfulGenesisBlock,
             Self::ConsensusTimeGet { .. } => ActionKind::RpcEffectfulConsensusTimeGet,
             Self::LedgerStatusGetSuccess { .. } => ActionKind::RpcEffectfulLedgerStatusGetSuccess,
+            Self::LedgerStatusExtendedGetSuccess { .. } => {
+                ActionKind::RpcEffectfulLedgerStatusExtendedGetSuccess
+            }
+            Self::LedgerSessionSuccess { .. } => ActionKind::RpcEffectfulLedgerSessionSuccess,
             Self::LedgerAccountDelegatorsGetSuccess { .. } => {
                 ActionKind::RpcEffectfulLedgerAccountDelegatorsGetSuccess
             }
@@ -1241,6 +1620,36 @@ impl ActionKindGet for WatchedAccountsAction {
     }
 }
 
+impl ActionKindGet for WatchtowerAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::BestChainUpdate => ActionKind::WatchtowerBestChainUpdate,
+        }
+    }
+}
+
+impl ActionKindGet for CheckpointAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::BestChainUpdate => ActionKind::CheckpointBestChainUpdate,
+            Self::Received { .. } => ActionKind::CheckpointReceived,
+            Self::Add { .. } => ActionKind::CheckpointAdd,
+            Self::P2pSendAll => ActionKind::CheckpointP2pSendAll,
+            Self::P2pSend { .. } => ActionKind::CheckpointP2pSend,
+        }
+    }
+}
+
+impl ActionKindGet for CheckpointEffectfulAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Sign { .. } => ActionKind::CheckpointEffectfulSign,
+            Self::Verify { .. } => ActionKind::CheckpointEffectfulVerify,
+            Self::Persist { .. } => ActionKind::CheckpointEffectfulPersist,
+        }
+    }
+}
+
 impl ActionKindGet for P2pInitializeAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1254,6 +1663,7 @@ impl ActionKindGet for P2pConnectionAction {
         match self {
             Self::Outgoing(a) => a.kind(),
             Self::Incoming(a) => a.kind(),
+            Self::IceRestart(a) => a.kind(),
         }
     }
 }
@@ -1291,6 +1701,7 @@ impl ActionKindGet for P2pChannelsAction {
             Self::SnarkJobCommitment(a) => a.kind(),
             Self::Rpc(a) => a.kind(),
             Self::StreamingRpc(a) => a.kind(),
+            Self::Checkpoint(a) => a.kind(),
         }
     }
 }
@@ -1302,6 +1713,7 @@ impl ActionKindGet for P2pPeerAction {
             Self::Ready { .. } => ActionKind::P2pPeerReady,
             Self::BestTipUpdate { .. } => ActionKind::P2pPeerBestTipUpdate,
             Self::Remove { .. } => ActionKind::P2pPeerRemove,
+            Self::RpcVersionUnsupported { .. } => ActionKind::P2pPeerRpcVersionUnsupported,
         }
     }
 }
@@ -1322,11 +1734,21 @@ impl ActionKindGet for P2pNetworkAction {
     }
 }
 
+impl ActionKindGet for P2pFeatureFlagsAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Update { .. } => ActionKind::P2pFeatureFlagsUpdate,
+        }
+    }
+}
+
 impl ActionKindGet for P2pChannelsEffectfulAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::InitChannel { .. } => ActionKind::P2pChannelsEffectfulInitChannel,
             Self::MessageSend { .. } => ActionKind::P2pChannelsEffectfulMessageSend,
+            Self::CompressionEnable { .. } => ActionKind::P2pChannelsEffectfulCompressionEnable,
+            Self::LossyGossipEnable { .. } => ActionKind::P2pChannelsEffectfulLossyGossipEnable,
             Self::SignalingDiscoveryAnswerDecrypt { .. } => {
                 ActionKind::P2pChannelsEffectfulSignalingDiscoveryAnswerDecrypt
             }
@@ -1348,6 +1770,7 @@ impl ActionKindGet for P2pConnectionEffectfulAction {
         match self {
             Self::Outgoing(a) => a.kind(),
             Self::Incoming(a) => a.kind(),
+            Self::IceRestart(a) => a.kind(),
         }
     }
 }
@@ -1390,6 +1813,17 @@ impl ActionKindGet for LedgerReadAction {
             Self::Pending { .. } => ActionKind::LedgerReadPending,
             Self::Success { .. } => ActionKind::LedgerReadSuccess,
             Self::Prune { .. } => ActionKind::LedgerReadPrune,
+            Self::PeerThrottled { .. } => ActionKind::LedgerReadPeerThrottled,
+        }
+    }
+}
+
+impl ActionKindGet for LedgerAuditAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init => ActionKind::LedgerAuditInit,
+            Self::Pending { .. } => ActionKind::LedgerAuditPending,
+            Self::Success { .. } => ActionKind::LedgerAuditSuccess,
         }
     }
 }
@@ -1402,6 +1836,7 @@ impl ActionKindGet for SnarkBlockVerifyAction {
             Self::Error { .. } => ActionKind::SnarkBlockVerifyError,
             Self::Success { .. } => ActionKind::SnarkBlockVerifySuccess,
             Self::Finish { .. } => ActionKind::SnarkBlockVerifyFinish,
+            Self::Cancel { .. } => ActionKind::SnarkBlockVerifyCancel,
         }
     }
 }
@@ -1410,6 +1845,7 @@ impl ActionKindGet for SnarkBlockVerifyEffectfulAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::Init { .. } => ActionKind::SnarkBlockVerifyEffectfulInit,
+            Self::Cancel { .. } => ActionKind::SnarkBlockVerifyEffectfulCancel,
         }
     }
 }
@@ -1454,6 +1890,14 @@ impl ActionKindGet for SnarkUserCommandVerifyEffectfulAction {
     }
 }
 
+impl ActionKindGet for SnarkVerifyAdmissionAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Denied { .. } => ActionKind::SnarkVerifyAdmissionDenied,
+        }
+    }
+}
+
 impl ActionKindGet for TransitionFrontierGenesisAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1466,6 +1910,22 @@ impl ActionKindGet for TransitionFrontierGenesisAction {
             Self::ProveInit => ActionKind::TransitionFrontierGenesisProveInit,
             Self::ProvePending => ActionKind::TransitionFrontierGenesisProvePending,
             Self::ProveSuccess { .. } => ActionKind::TransitionFrontierGenesisProveSuccess,
+            Self::ProofPeerFetchInit => ActionKind::TransitionFrontierGenesisProofPeerFetchInit,
+            Self::ProofPeerFetchPending { .. } => {
+                ActionKind::TransitionFrontierGenesisProofPeerFetchPending
+            }
+            Self::ProofPeerFetchSuccess { .. } => {
+                ActionKind::TransitionFrontierGenesisProofPeerFetchSuccess
+            }
+            Self::ProofPeerVerifyPending { .. } => {
+                ActionKind::TransitionFrontierGenesisProofPeerVerifyPending
+            }
+            Self::ProofPeerVerifyError { .. } => {
+                ActionKind::TransitionFrontierGenesisProofPeerVerifyError
+            }
+            Self::ProofPeerVerifySuccess { .. } => {
+                ActionKind::TransitionFrontierGenesisProofPeerVerifySuccess
+            }
         }
     }
 }
@@ -1510,6 +1970,7 @@ impl ActionKindGet for TransitionFrontierCandidateAction {
                 ActionKind::TransitionFrontierCandidateTransitionFrontierSyncTargetUpdate
             }
             Self::Prune => ActionKind::TransitionFrontierCandidatePrune,
+            Self::PruneStale => ActionKind::TransitionFrontierCandidatePruneStale,
         }
     }
 }
@@ -1580,6 +2041,7 @@ impl ActionKindGet for SnarkPoolCandidateAction {
             Self::WorkFetchSuccess { .. } => ActionKind::SnarkPoolCandidateWorkFetchSuccess,
             Self::WorkVerifyNext => ActionKind::SnarkPoolCandidateWorkVerifyNext,
             Self::WorkVerifyPending { .. } => ActionKind::SnarkPoolCandidateWorkVerifyPending,
+            Self::WorkVerifySkipped { .. } => ActionKind::SnarkPoolCandidateWorkVerifySkipped,
             Self::WorkVerifyError { .. } => ActionKind::SnarkPoolCandidateWorkVerifyError,
             Self::WorkVerifySuccess { .. } => ActionKind::SnarkPoolCandidateWorkVerifySuccess,
             Self::PeerPrune { .. } => ActionKind::SnarkPoolCandidatePeerPrune,
@@ -1591,11 +2053,23 @@ impl ActionKindGet for TransactionPoolCandidateAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::InfoReceived { .. } => ActionKind::TransactionPoolCandidateInfoReceived,
+            Self::DigestsReceived { .. } => ActionKind::TransactionPoolCandidateDigestsReceived,
             Self::FetchAll => ActionKind::TransactionPoolCandidateFetchAll,
             Self::FetchInit { .. } => ActionKind::TransactionPoolCandidateFetchInit,
             Self::FetchPending { .. } => ActionKind::TransactionPoolCandidateFetchPending,
             Self::FetchError { .. } => ActionKind::TransactionPoolCandidateFetchError,
             Self::FetchSuccess { .. } => ActionKind::TransactionPoolCandidateFetchSuccess,
+            Self::PoolSyncInit { .. } => ActionKind::TransactionPoolCandidatePoolSyncInit,
+            Self::PoolSyncRequestInit { .. } => {
+                ActionKind::TransactionPoolCandidatePoolSyncRequestInit
+            }
+            Self::PoolSyncRequestPending { .. } => {
+                ActionKind::TransactionPoolCandidatePoolSyncRequestPending
+            }
+            Self::PoolSyncPageReceived { .. } => {
+                ActionKind::TransactionPoolCandidatePoolSyncPageReceived
+            }
+            Self::PoolSyncError { .. } => ActionKind::TransactionPoolCandidatePoolSyncError,
             Self::Libp2pTransactionsReceived { .. } => {
                 ActionKind::TransactionPoolCandidateLibp2pTransactionsReceived
             }
@@ -1608,6 +2082,32 @@ impl ActionKindGet for TransactionPoolCandidateAction {
     }
 }
 
+impl ActionKindGet for TransactionPoolPayoutsAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::BatchAdd { .. } => ActionKind::TransactionPoolPayoutsBatchAdd,
+            Self::NonceFetchInit => ActionKind::TransactionPoolPayoutsNonceFetchInit,
+            Self::NonceFetchSuccess { .. } => ActionKind::TransactionPoolPayoutsNonceFetchSuccess,
+            Self::InjectNext => ActionKind::TransactionPoolPayoutsInjectNext,
+            Self::Signed { .. } => ActionKind::TransactionPoolPayoutsSigned,
+            Self::InjectResult { .. } => ActionKind::TransactionPoolPayoutsInjectResult,
+            Self::InjectError { .. } => ActionKind::TransactionPoolPayoutsInjectError,
+            Self::CheckTimeouts => ActionKind::TransactionPoolPayoutsCheckTimeouts,
+        }
+    }
+}
+
+impl ActionKindGet for TransactionPoolSponsorAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init { .. } => ActionKind::TransactionPoolSponsorInit,
+            Self::NonceFetchSuccess { .. } => ActionKind::TransactionPoolSponsorNonceFetchSuccess,
+            Self::Signed { .. } => ActionKind::TransactionPoolSponsorSigned,
+            Self::Error { .. } => ActionKind::TransactionPoolSponsorError,
+        }
+    }
+}
+
 impl ActionKindGet for BlockProducerVrfEvaluatorAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1633,6 +2133,12 @@ impl ActionKindGet for BlockProducerVrfEvaluatorAction {
             Self::FinalizeDelegatorTableConstruction { .. } => {
                 ActionKind::BlockProducerVrfEvaluatorFinalizeDelegatorTableConstruction
             }
+            Self::PrecomputeDelegatorTable { .. } => {
+                ActionKind::BlockProducerVrfEvaluatorPrecomputeDelegatorTable
+            }
+            Self::PrecomputeDelegatorTableSuccess { .. } => {
+                ActionKind::BlockProducerVrfEvaluatorPrecomputeDelegatorTableSuccess
+            }
             Self::SelectInitialSlot { .. } => {
                 ActionKind::BlockProducerVrfEvaluatorSelectInitialSlot
             }
@@ -1726,6 +2232,24 @@ impl ActionKindGet for P2pConnectionIncomingAction {
                 ActionKind::P2pConnectionIncomingFinalizePendingLibp2p
             }
             Self::Libp2pReceived { .. } => ActionKind::P2pConnectionIncomingLibp2pReceived,
+            Self::Rejected { .. } => ActionKind::P2pConnectionIncomingRejected,
+        }
+    }
+}
+
+impl ActionKindGet for P2pConnectionIceRestartAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Degraded { .. } => ActionKind::P2pConnectionIceRestartDegraded,
+            Self::Init { .. } => ActionKind::P2pConnectionIceRestartInit,
+            Self::NegotiateSuccess { .. } => ActionKind::P2pConnectionIceRestartNegotiateSuccess,
+            Self::Error { .. } => ActionKind::P2pConnectionIceRestartError,
+            Self::OfferReceived { .. } => ActionKind::P2pConnectionIceRestartOfferReceived,
+            Self::AnswerSdpCreateSuccess { .. } => {
+                ActionKind::P2pConnectionIceRestartAnswerSdpCreateSuccess
+            }
+            Self::Success { .. } => ActionKind::P2pConnectionIceRestartSuccess,
+            Self::Timeout { .. } => ActionKind::P2pConnectionIceRestartTimeout,
         }
     }
 }
@@ -1809,6 +2333,16 @@ impl ActionKindGet for P2pChannelsBestTipAction {
             Self::Received { .. } => ActionKind::P2pChannelsBestTipReceived,
             Self::RequestReceived { .. } => ActionKind::P2pChannelsBestTipRequestReceived,
             Self::ResponseSend { .. } => ActionKind::P2pChannelsBestTipResponseSend,
+            Self::ResponseExpired { .. } => ActionKind::P2pChannelsBestTipResponseExpired,
+            Self::CompactReceived { .. } => ActionKind::P2pChannelsBestTipCompactReceived,
+            Self::CompactResolve { .. } => ActionKind::P2pChannelsBestTipCompactResolve,
+            Self::MissingRequestReceived { .. } => {
+                ActionKind::P2pChannelsBestTipMissingRequestReceived
+            }
+            Self::MissingReceived { .. } => ActionKind::P2pChannelsBestTipMissingReceived,
+            Self::CompactVerifySuccess { .. } => ActionKind::P2pChannelsBestTipCompactVerifySuccess,
+            Self::CompactVerifyError { .. } => ActionKind::P2pChannelsBestTipCompactVerifyError,
+            Self::FullRequestReceived { .. } => ActionKind::P2pChannelsBestTipFullRequestReceived,
         }
     }
 }
@@ -1824,6 +2358,11 @@ impl ActionKindGet for P2pChannelsTransactionAction {
             Self::Received { .. } => ActionKind::P2pChannelsTransactionReceived,
             Self::RequestReceived { .. } => ActionKind::P2pChannelsTransactionRequestReceived,
             Self::ResponseSend { .. } => ActionKind::P2pChannelsTransactionResponseSend,
+            Self::AnnounceReceived { .. } => ActionKind::P2pChannelsTransactionAnnounceReceived,
+            Self::RequestByHashSend { .. } => ActionKind::P2pChannelsTransactionRequestByHashSend,
+            Self::RequestByHashReceived { .. } => {
+                ActionKind::P2pChannelsTransactionRequestByHashReceived
+            }
             Self::Libp2pReceived { .. } => ActionKind::P2pChannelsTransactionLibp2pReceived,
             Self::Libp2pBroadcast { .. } => ActionKind::P2pChannelsTransactionLibp2pBroadcast,
         }
@@ -1839,6 +2378,7 @@ impl ActionKindGet for P2pChannelsSnarkAction {
             Self::RequestSend { .. } => ActionKind::P2pChannelsSnarkRequestSend,
             Self::PromiseReceived { .. } => ActionKind::P2pChannelsSnarkPromiseReceived,
             Self::Received { .. } => ActionKind::P2pChannelsSnarkReceived,
+            Self::BatchReceived { .. } => ActionKind::P2pChannelsSnarkBatchReceived,
             Self::RequestReceived { .. } => ActionKind::P2pChannelsSnarkRequestReceived,
             Self::ResponseSend { .. } => ActionKind::P2pChannelsSnarkResponseSend,
             Self::Libp2pReceived { .. } => ActionKind::P2pChannelsSnarkLibp2pReceived,
@@ -1873,6 +2413,7 @@ impl ActionKindGet for P2pChannelsRpcAction {
             Self::Pending { .. } => ActionKind::P2pChannelsRpcPending,
             Self::Ready { .. } => ActionKind::P2pChannelsRpcReady,
             Self::RequestSend { .. } => ActionKind::P2pChannelsRpcRequestSend,
+            Self::RequestRetry { .. } => ActionKind::P2pChannelsRpcRequestRetry,
             Self::Timeout { .. } => ActionKind::P2pChannelsRpcTimeout,
             Self::ResponseReceived { .. } => ActionKind::P2pChannelsRpcResponseReceived,
             Self::RequestReceived { .. } => ActionKind::P2pChannelsRpcRequestReceived,
@@ -1909,6 +2450,21 @@ impl ActionKindGet for P2pChannelsStreamingRpcAction {
     }
 }
 
+impl ActionKindGet for P2pChannelsCheckpointAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init { .. } => ActionKind::P2pChannelsCheckpointInit,
+            Self::Pending { .. } => ActionKind::P2pChannelsCheckpointPending,
+            Self::Ready { .. } => ActionKind::P2pChannelsCheckpointReady,
+            Self::RequestSend { .. } => ActionKind::P2pChannelsCheckpointRequestSend,
+            Self::PromiseReceived { .. } => ActionKind::P2pChannelsCheckpointPromiseReceived,
+            Self::Received { .. } => ActionKind::P2pChannelsCheckpointReceived,
+            Self::RequestReceived { .. } => ActionKind::P2pChannelsCheckpointRequestReceived,
+            Self::ResponseSend { .. } => ActionKind::P2pChannelsCheckpointResponseSend,
+        }
+    }
+}
+
 impl ActionKindGet for P2pNetworkSchedulerAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -2032,6 +2588,7 @@ impl ActionKindGet for P2pNetworkPubsubAction {
             Self::Prune { .. } => ActionKind::P2pNetworkPubsubPrune,
             Self::WebRtcRebroadcast { .. } => ActionKind::P2pNetworkPubsubWebRtcRebroadcast,
             Self::Broadcast { .. } => ActionKind::P2pNetworkPubsubBroadcast,
+            Self::BroadcastCheckpoint { .. } => ActionKind::P2pNetworkPubsubBroadcastCheckpoint,
             Self::Sign { .. } => ActionKind::P2pNetworkPubsubSign,
             Self::SignError { .. } => ActionKind::P2pNetworkPubsubSignError,
             Self::BroadcastSigned { .. } => ActionKind::P2pNetworkPubsubBroadcastSigned,
@@ -2099,6 +2656,15 @@ impl ActionKindGet for P2pConnectionIncomingEffectfulAction {
     }
 }
 
+impl ActionKindGet for P2pConnectionIceRestartEffectfulAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init { .. } => ActionKind::P2pConnectionIceRestartEffectfulInit,
+            Self::OfferSet { .. } => ActionKind::P2pConnectionIceRestartEffectfulOfferSet,
+        }
+    }
+}
+
 impl ActionKindGet for P2pNetworkSchedulerEffectfulAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use ledger::scan_state::transaction_logic::valid;
-use mina_p2p_messages::v2::MinaBaseProofStableV2;
+use mina_p2p_messages::v2::{MinaBaseProofStableV2, StagedLedgerDiffDiffStableV2, StateHash};
 use openmina_core::block::ArcBlockWithHash;
 use openmina_core::ActionEvent;
 use serde::{Deserialize, Serialize};
@@ -9,7 +9,10 @@ use serde::{Deserialize, Serialize};
 use crate::block_producer_effectful::StagedLedgerDiffCreateOutput;
 
 use super::vrf_evaluator::BlockProducerVrfEvaluatorAction;
-use super::{BlockProducerCurrentState, BlockProducerWonSlot, BlockProducerWonSlotDiscardReason};
+use super::{
+    BlockProducerCurrentState, BlockProducerSigningError, BlockProducerSlotClaimResult,
+    BlockProducerWonSlot, BlockProducerWonSlotDiscardReason,
+};
 
 pub type BlockProducerActionWithMeta = redux::ActionWithMeta<BlockProducerAction>;
 pub type BlockProducerActionWithMetaRef<'a> = redux::ActionWithMeta<&'a BlockProducerAction>;
@@ -45,6 +48,19 @@ pub enum BlockProducerAction {
         reason: BlockProducerWonSlotDiscardReason,
     },
     WonSlotWait,
+    /// Claim the won slot with the coordinator, before producing the
+    /// block for it.
+    WonSlotClaimInit,
+    #[action_event(level = info, fields(global_slot, result = format!("{result:?}")))]
+    WonSlotClaimSuccess {
+        global_slot: u32,
+        result: BlockProducerSlotClaimResult,
+    },
+    #[action_event(level = warn, fields(global_slot, error))]
+    WonSlotClaimError {
+        global_slot: u32,
+        error: String,
+    },
     WonSlotTransactionsGet,
     WonSlotTransactionsSuccess {
         transactions_by_fee: Vec<valid::UserCommand>,
@@ -55,9 +71,22 @@ pub enum BlockProducerAction {
     StagedLedgerDiffCreateSuccess {
         output: Arc<StagedLedgerDiffCreateOutput>,
     },
+    /// Use the diff assembled outside of the node for the won slot,
+    /// see [`super::BlockTemplate`].
+    #[action_event(level = info, fields(global_slot))]
+    ExternalDiffSubmit {
+        global_slot: u32,
+        diff: Arc<StagedLedgerDiffDiffStableV2>,
+    },
     BlockUnprovenBuild,
     BlockProveInit,
     BlockProvePending,
+    /// Block wasn't signed, as the signing journal refused it.
+    #[action_event(level = error, fields(display(block_hash), display(error)))]
+    BlockSigningRefused {
+        block_hash: StateHash,
+        error: BlockProducerSigningError,
+    },
     BlockProveSuccess {
         proof: Arc<MinaBaseProofStableV2>,
     },
@@ -102,6 +131,18 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
             BlockProducerAction::WonSlotWait => state
                 .block_producer
                 .with(false, |this| this.current.won_slot_should_wait(time)),
+            // no claim in maintenance mode, the slot is left to the
+            // other producers.
+            BlockProducerAction::WonSlotClaimInit => {
+                !state.rpc.is_in_maintenance()
+                    && state
+                        .block_producer
+                        .with(false, |this| this.won_slot_should_claim(time))
+            }
+            BlockProducerAction::WonSlotClaimSuccess { global_slot, .. }
+            | BlockProducerAction::WonSlotClaimError { global_slot, .. } => state
+                .block_producer
+                .with(false, |this| this.slot_claim.is_pending(*global_slot)),
             BlockProducerAction::WonSlotProduceInit { .. } => {
                 state.block_producer.with(false, |this| {
                     let has_genesis_proven_if_needed = || {
@@ -112,6 +153,7 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                         })
                     };
                     this.current.won_slot_should_produce(time)
+                        && this.slot_claim_allows_production()
                         && has_genesis_proven_if_needed()
                         // don't start block production (particularly staged ledger diff creation),
                         // if transition frontier sync commit is pending,
@@ -123,6 +165,9 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                         // So we would be trying to build on top of
                         // non-existent staged ledger causing a failure.
                         && !state.transition_frontier.sync.is_commit_pending()
+                        // block production is drained in maintenance mode,
+                        // the one in progress is finished, no new is started.
+                        && !state.rpc.is_in_maintenance()
                 })
             }
             BlockProducerAction::WonSlotTransactionsGet => {
@@ -165,6 +210,11 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                     )
                 })
             }
+            BlockProducerAction::ExternalDiffSubmit { global_slot, .. } => {
+                state.block_producer.with(false, |this| {
+                    this.current.accepts_external_diff(*global_slot)
+                })
+            }
             BlockProducerAction::BlockUnprovenBuild => state.block_producer.with(false, |this| {
                 matches!(
                     this.current,
@@ -177,6 +227,15 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                     BlockProducerCurrentState::BlockUnprovenBuilt { .. }
                 )
             }),
+            BlockProducerAction::BlockSigningRefused { block_hash, .. } => {
+                state.block_producer.with(false, |this| {
+                    matches!(
+                        &this.current,
+                        BlockProducerCurrentState::BlockProvePending { block_hash: hash, .. }
+                            if hash == block_hash
+                    )
+                })
+            }
             BlockProducerAction::BlockProvePending => state.block_producer.with(false, |this| {
                 matches!(
                     this.current,
@@ -218,7 +277,7 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
             BlockProducerAction::WonSlotDiscard { reason } => {
                 let current_reason = state.block_producer.with(None, |bp| {
                     let best_tip = state.transition_frontier.best_tip()?;
-                    bp.current.won_slot_should_discard(best_tip)
+                    bp.won_slot_should_discard(best_tip)
                 });
                 Some(reason) == current_reason.as_ref()
             }
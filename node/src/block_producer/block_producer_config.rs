@@ -1,22 +1,80 @@
+use std::time::Duration;
+
 use mina_p2p_messages::v2::{NonZeroCurvePoint, ProtocolVersionStableV2};
 use serde::{Deserialize, Serialize};
 
+use super::BlockProducerWonSlot;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockProducerConfig {
     pub pub_key: NonZeroCurvePoint,
     pub custom_coinbase_receiver: Option<NonZeroCurvePoint>,
     pub proposed_protocol_version: Option<ProtocolVersionStableV2>,
+    /// Time reserved at the end of the won slot for the block proof
+    /// generation and broadcast. Staged ledger diff is truncated if its
+    /// creation would take longer.
+    #[serde(default = "BlockProducerConfig::default_proof_and_broadcast_margin")]
+    pub proof_and_broadcast_margin: Duration,
+    /// Won slot must be claimed with the slot claim coordinator of the
+    /// service before the block for it is produced. Set when redundant
+    /// producers run with the same key.
+    #[serde(default)]
+    pub slot_claim_required: bool,
+    /// Testing mode, in which the slots are won regardless of the vrf
+    /// threshold and the produced blocks are empty.
+    #[cfg(feature = "testing")]
+    #[serde(default)]
+    pub empty_blocks: Option<BlockProducerEmptyBlocksConfig>,
+}
+
+/// Producer wins the slots at the configured cadence and produces blocks
+/// without transactions, so that the block propagation can be benchmarked
+/// independently of the pool contents.
+///
+/// Blocks produced this way are only valid with the dummy proofs, as the
+/// vrf threshold isn't met.
+#[cfg(feature = "testing")]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerEmptyBlocksConfig {
+    /// Block is produced at the slots divisible by the cadence, other
+    /// slots are lost.
+    pub cadence: u32,
+}
+
+#[cfg(feature = "testing")]
+impl BlockProducerEmptyBlocksConfig {
+    pub fn wins_slot(&self, global_slot: u32) -> bool {
+        global_slot.checked_rem(self.cadence.max(1)) == Some(0)
+    }
 }
 
 impl BlockProducerConfig {
+    pub const DEFAULT_PROOF_AND_BROADCAST_MARGIN: Duration = Duration::from_secs(60);
+
     pub fn new(pub_key: NonZeroCurvePoint) -> Self {
         Self {
             pub_key,
             custom_coinbase_receiver: None,
             proposed_protocol_version: None,
+            proof_and_broadcast_margin: Self::DEFAULT_PROOF_AND_BROADCAST_MARGIN,
+            slot_claim_required: false,
+            #[cfg(feature = "testing")]
+            empty_blocks: None,
         }
     }
 
+    fn default_proof_and_broadcast_margin() -> Duration {
+        Self::DEFAULT_PROOF_AND_BROADCAST_MARGIN
+    }
+
+    /// Time by which the staged ledger diff for the won slot must be
+    /// created.
+    pub fn assembly_deadline(&self, won_slot: &BlockProducerWonSlot) -> redux::Timestamp {
+        let slot_end = u64::from(won_slot.next_slot_time());
+        let margin = self.proof_and_broadcast_margin.as_nanos() as u64;
+        redux::Timestamp::new(slot_end.saturating_sub(margin))
+    }
+
     pub fn coinbase_receiver(&self) -> &NonZeroCurvePoint {
         self.custom_coinbase_receiver
             .as_ref()
@@ -4,11 +4,15 @@ use mina_p2p_messages::v2::{MinaBaseProofStableV2, StateHash};
 use serde::{Deserialize, Serialize};
 
 pub use super::vrf_evaluator::BlockProducerVrfEvaluatorEvent;
+use super::{BlockProducerSigningError, BlockProducerSlotClaimResult};
 
 #[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
 pub enum BlockProducerEvent {
     VrfEvaluator(BlockProducerVrfEvaluatorEvent),
     BlockProve(StateHash, Result<Arc<MinaBaseProofStableV2>, String>),
+    SlotClaim(u32, Result<BlockProducerSlotClaimResult, String>),
+    /// Block wasn't proven, as the signing journal refused to sign it.
+    SigningRefused(StateHash, BlockProducerSigningError),
 }
 
 impl std::fmt::Display for BlockProducerEvent {
@@ -20,6 +24,17 @@ impl std::fmt::Display for BlockProducerEvent {
                 let res = res.as_ref().map_or("Err", |_| "Ok");
                 write!(f, "BlockProveSuccess, {block_hash}, {res}")
             }
+            Self::SlotClaim(global_slot, res) => {
+                let res = match res {
+                    Ok(BlockProducerSlotClaimResult::Granted) => "Granted",
+                    Ok(BlockProducerSlotClaimResult::Denied { .. }) => "Denied",
+                    Err(_) => "Err",
+                };
+                write!(f, "SlotClaim, {global_slot}, {res}")
+            }
+            Self::SigningRefused(block_hash, error) => {
+                write!(f, "SigningRefused, {block_hash}, {error}")
+            }
         }
     }
 }
@@ -23,7 +23,9 @@ use super::{
         BlockProducerVrfEvaluatorAction, BlockProducerVrfEvaluatorState, InterruptReason,
     },
     BlockProducerAction, BlockProducerActionWithMetaRef, BlockProducerCurrentState,
-    BlockProducerEnabled, BlockProducerState, BlockWithoutProof,
+    BlockProducerEnabled, BlockProducerExternalDiff, BlockProducerExternalDiffStatus,
+    BlockProducerSlotClaimResult, BlockProducerSlotClaimState, BlockProducerState,
+    BlockProducerWonSlotDiscardReason, BlockWithoutProof,
 };
 
 impl BlockProducerState {
@@ -105,6 +107,65 @@ impl BlockProducerEnabled {
                     };
                 }
             }
+            BlockProducerAction::WonSlotClaimInit => {
+                let Some(global_slot) = state.current.won_slot().map(|s| s.global_slot()) else {
+                    bug_condition!(
+                        "Invalid state for `BlockProducerAction::WonSlotClaimInit`: no won slot"
+                    );
+                    return;
+                };
+                state.slot_claim = BlockProducerSlotClaimState::Pending {
+                    time: meta.time(),
+                    global_slot,
+                };
+
+                let producer = state.config.pub_key.clone();
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(BlockProducerEffectfulAction::WonSlotClaimInit {
+                    producer,
+                    global_slot,
+                });
+            }
+            BlockProducerAction::WonSlotClaimSuccess {
+                global_slot,
+                result,
+            } => {
+                let global_slot = *global_slot;
+                state.slot_claim = match result {
+                    BlockProducerSlotClaimResult::Granted => BlockProducerSlotClaimState::Granted {
+                        time: meta.time(),
+                        global_slot,
+                    },
+                    BlockProducerSlotClaimResult::Denied { holder } => {
+                        BlockProducerSlotClaimState::Denied {
+                            time: meta.time(),
+                            global_slot,
+                            holder: holder.clone(),
+                        }
+                    }
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                match result {
+                    BlockProducerSlotClaimResult::Granted => {
+                        dispatcher.push(BlockProducerAction::WonSlotProduceInit);
+                    }
+                    BlockProducerSlotClaimResult::Denied { .. } => {
+                        dispatcher.push(BlockProducerAction::WonSlotDiscard {
+                            reason: BlockProducerWonSlotDiscardReason::SlotClaimedByOther,
+                        });
+                    }
+                }
+            }
+            BlockProducerAction::WonSlotClaimError { global_slot, error } => {
+                // Retried while the slot can still be produced, see
+                // `BlockProducerSlotClaimState::should_claim`.
+                state.slot_claim = BlockProducerSlotClaimState::Error {
+                    time: meta.time(),
+                    global_slot: *global_slot,
+                    error: error.clone(),
+                };
+            }
             BlockProducerAction::WonSlotProduceInit => {
                 if let Some(won_slot) = state.current.won_slot() {
                     if let Some(chain) = best_chain.last().map(|best_tip| {
@@ -145,6 +206,15 @@ impl BlockProducerEnabled {
                     chain: chain.clone(),
                 };
 
+                #[cfg(feature = "testing")]
+                if state.config.empty_blocks.is_some() {
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(BlockProducerAction::WonSlotTransactionsSuccess {
+                        transactions_by_fee: Vec::new(),
+                    });
+                    return;
+                }
+
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(TransactionPoolAction::CollectTransactionsByFee);
             }
@@ -213,9 +283,30 @@ impl BlockProducerEnabled {
                     pending_coinbase_witness: output.pending_coinbase_witness.clone(),
                     stake_proof_sparse_ledger: output.stake_proof_sparse_ledger.clone(),
                 };
+                if let (Some(external), Some(result)) =
+                    (state.external_diff.as_mut(), &output.external_diff)
+                {
+                    external.status = match result {
+                        Ok(()) => BlockProducerExternalDiffStatus::Applied,
+                        Err(error) => BlockProducerExternalDiffStatus::Rejected(error.clone()),
+                    };
+                }
 
                 let dispatcher = state_context.into_dispatcher();
-                dispatcher.push(BlockProducerEffectfulAction::StagedLedgerDiffCreateSuccess);
+                dispatcher.push(
+                    BlockProducerEffectfulAction::StagedLedgerDiffCreateSuccess {
+                        truncated_commands: output.truncated_commands,
+                        external_diff: output.external_diff.clone(),
+                    },
+                );
+            }
+            BlockProducerAction::ExternalDiffSubmit { global_slot, diff } => {
+                state.external_diff = Some(BlockProducerExternalDiff {
+                    global_slot: *global_slot,
+                    time: meta.time(),
+                    diff: diff.clone(),
+                    status: BlockProducerExternalDiffStatus::Submitted,
+                });
             }
             BlockProducerAction::BlockUnprovenBuild => {
                 state.reduce_block_unproved_build(consensus_constants, meta.time());
@@ -227,6 +318,19 @@ impl BlockProducerEnabled {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(BlockProducerEffectfulAction::BlockProveInit);
             }
+            BlockProducerAction::BlockSigningRefused { .. } => {
+                let reason = BlockProducerWonSlotDiscardReason::SigningRefused;
+                if let Some(won_slot) = state.current.won_slot() {
+                    state.current = BlockProducerCurrentState::WonSlotDiscarded {
+                        time: meta.time(),
+                        won_slot: won_slot.clone(),
+                        reason,
+                    };
+                }
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(BlockProducerEffectfulAction::WonSlotDiscard { reason });
+            }
             BlockProducerAction::BlockProvePending => {
                 let current_state = std::mem::take(&mut state.current);
 
@@ -755,7 +859,7 @@ impl BlockProducerEnabled {
 
         if let Some(reason) = state
             .block_producer
-            .with(None, |bp| bp.current.won_slot_should_discard(best_tip))
+            .with(None, |bp| bp.won_slot_should_discard(best_tip))
         {
             dispatcher.push(BlockProducerAction::WonSlotDiscard { reason });
         } else {
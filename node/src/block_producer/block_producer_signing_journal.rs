@@ -0,0 +1,192 @@
+//! Local journal of the blocks signed by the block producer.
+//!
+//! Before the block for the slot is proven with the producer key, the
+//! producer, the slot and the block hash are persisted. Signing another
+//! block for the same producer and slot is refused afterwards, even if
+//! the node crashed or was restarted in between. This is the last line
+//! of defence against double-signing, independent of the slot claim
+//! coordination between the redundant producers.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash};
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+
+pub const SIGNING_JOURNAL_FILE: &str = "block_producer_signing_journal";
+
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
+pub enum BlockProducerSigningError {
+    #[error("block {signed} was already signed for the slot {global_slot}")]
+    AlreadySigned { global_slot: u32, signed: StateHash },
+    #[error("failed to persist the signing journal: {0}")]
+    Journal(String),
+}
+
+/// Append-only file with a `<producer> <global slot> <state hash>` line
+/// for each signed block.
+pub struct SigningJournal {
+    path: PathBuf,
+    file: fs::File,
+    signed: BTreeMap<(String, u32), StateHash>,
+}
+
+impl SigningJournal {
+    pub fn path<P: AsRef<Path>>(work_dir: P) -> PathBuf {
+        work_dir.as_ref().join(SIGNING_JOURNAL_FILE)
+    }
+
+    /// Opens the journal in the `work_dir`, creating it if needed. Fails
+    /// if the existing journal can't be read, since signing without it
+    /// isn't safe.
+    ///
+    /// A last line without the trailing newline is left by a crash during
+    /// [`Self::record`], before the block was signed, so it's truncated.
+    pub fn open<P: AsRef<Path>>(work_dir: P) -> io::Result<Self> {
+        let path = Self::path(work_dir);
+        let mut signed = BTreeMap::new();
+        if path.exists() {
+            let content = fs::read(&path)?;
+            let complete_len = content
+                .iter()
+                .rposition(|b| *b == b'\n')
+                .map_or(0, |i| i.saturating_add(1));
+            let (complete, partial) = content.split_at(complete_len);
+            if !partial.is_empty() {
+                let file = fs::OpenOptions::new().write(true).open(&path)?;
+                file.set_len(complete.len() as u64)?;
+                file.sync_data()?;
+            }
+            for (i, line) in complete.lines().enumerate() {
+                let line = line?;
+                if line.is_empty() {
+                    continue;
+                }
+                let invalid = || {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid signing journal line {}: {line}", i + 1),
+                    )
+                };
+                let mut parts = line.split(' ');
+                let (Some(producer), Some(global_slot), Some(hash), None) =
+                    (parts.next(), parts.next(), parts.next(), parts.next())
+                else {
+                    return Err(invalid());
+                };
+                let global_slot = global_slot.parse().map_err(|_| invalid())?;
+                let hash = hash.parse().map_err(|_| invalid())?;
+                signed.insert((producer.to_owned(), global_slot), hash);
+            }
+        }
+
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        Ok(Self { path, file, signed })
+    }
+
+    /// Records that the block `hash` is about to be signed for the
+    /// `global_slot`. Fails if another block was signed for the same
+    /// producer and slot, or if the record couldn't be persisted.
+    pub fn record(
+        &mut self,
+        producer: &NonZeroCurvePoint,
+        global_slot: u32,
+        hash: &StateHash,
+    ) -> Result<(), BlockProducerSigningError> {
+        let producer = AccountPublicKey::from(producer.clone()).to_string();
+        let key = (producer, global_slot);
+        match self.signed.get(&key) {
+            Some(signed) if signed == hash => return Ok(()),
+            Some(signed) => {
+                return Err(BlockProducerSigningError::AlreadySigned {
+                    global_slot,
+                    signed: signed.clone(),
+                })
+            }
+            None => {}
+        }
+
+        let line = format!("{} {global_slot} {hash}\n", key.0);
+        self.file
+            .write_all(line.as_bytes())
+            .and_then(|_| self.file.sync_data())
+            .map_err(|err| {
+                BlockProducerSigningError::Journal(format!("{}: {err}", self.path.display()))
+            })?;
+        self.signed.insert(key, hash.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work_dir(name: &str) -> PathBuf {
+        let work_dir = std::env::temp_dir().join(format!(
+            "openmina-signing-journal-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&work_dir);
+        fs::create_dir_all(&work_dir).unwrap();
+        work_dir
+    }
+
+    #[test]
+    fn refuses_second_block_for_slot_after_reopen() {
+        let work_dir = work_dir("reopen");
+        let producer: NonZeroCurvePoint = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg"
+            .parse::<AccountPublicKey>()
+            .unwrap()
+            .into();
+        let block: StateHash = "3NK2tkzqqK5spR2sZ7tujjqPksL45M3UUrcA4WhCkeiPtnugyE2"
+            .parse()
+            .unwrap();
+        let other: StateHash = "3NLk2wNbKQPHweZCR9pH9YVPmHi2EM5a1TyAjGNErT2RdYSJPbe"
+            .parse()
+            .unwrap();
+
+        {
+            let mut journal = SigningJournal::open(&work_dir).unwrap();
+            journal.record(&producer, 10, &block).unwrap();
+            journal.record(&producer, 10, &block).unwrap();
+        }
+
+        let mut journal = SigningJournal::open(&work_dir).unwrap();
+        assert!(matches!(
+            journal.record(&producer, 10, &other),
+            Err(BlockProducerSigningError::AlreadySigned { global_slot: 10, signed }) if signed == block
+        ));
+        journal.record(&producer, 11, &other).unwrap();
+
+        let _ = fs::remove_dir_all(&work_dir);
+    }
+
+    #[test]
+    fn truncates_partial_last_line() {
+        let work_dir = work_dir("partial");
+        let path = SigningJournal::path(&work_dir);
+        let producer = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg";
+        let complete =
+            format!("{producer} 10 3NK2tkzqqK5spR2sZ7tujjqPksL45M3UUrcA4WhCkeiPtnugyE2\n");
+        fs::write(&path, format!("{complete}{producer} 11 3NLk2wNb")).unwrap();
+
+        let journal = SigningJournal::open(&work_dir).unwrap();
+        assert_eq!(journal.signed.len(), 1);
+        assert!(journal.signed.contains_key(&(producer.to_owned(), 10)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), complete);
+
+        // Corrupt complete line isn't a crash leftover.
+        fs::write(&path, format!("{producer} 11 3NLk2wNb\n{complete}")).unwrap();
+        assert!(SigningJournal::open(&work_dir).is_err());
+
+        let _ = fs::remove_dir_all(&work_dir);
+    }
+}
@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Claim that failed is retried after this long, while the won slot
+/// can still be produced.
+pub const SLOT_CLAIM_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Response of the slot claim coordinator.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum BlockProducerSlotClaimResult {
+    /// We hold the claim, block for the slot may be produced.
+    Granted,
+    /// Another node producing with the same key holds the claim.
+    Denied { holder: String },
+}
+
+/// Claim of the won slot with the coordinator shared by the redundant
+/// producers running with the same key. Only the node holding the claim
+/// produces the block, so that the slot isn't double-signed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub enum BlockProducerSlotClaimState {
+    #[default]
+    Idle,
+    Pending {
+        time: redux::Timestamp,
+        global_slot: u32,
+    },
+    Granted {
+        time: redux::Timestamp,
+        global_slot: u32,
+    },
+    Denied {
+        time: redux::Timestamp,
+        global_slot: u32,
+        holder: String,
+    },
+    Error {
+        time: redux::Timestamp,
+        global_slot: u32,
+        error: String,
+    },
+}
+
+impl BlockProducerSlotClaimState {
+    pub fn global_slot(&self) -> Option<u32> {
+        match self {
+            Self::Idle => None,
+            Self::Pending { global_slot, .. }
+            | Self::Granted { global_slot, .. }
+            | Self::Denied { global_slot, .. }
+            | Self::Error { global_slot, .. } => Some(*global_slot),
+        }
+    }
+
+    pub fn is_pending(&self, slot: u32) -> bool {
+        matches!(self, Self::Pending { global_slot, .. } if *global_slot == slot)
+    }
+
+    pub fn is_granted(&self, slot: u32) -> bool {
+        matches!(self, Self::Granted { global_slot, .. } if *global_slot == slot)
+    }
+
+    pub fn is_denied(&self, slot: u32) -> bool {
+        matches!(self, Self::Denied { global_slot, .. } if *global_slot == slot)
+    }
+
+    /// Whether the claim for the `slot` should be requested, either
+    /// because it wasn't requested yet or the last request failed.
+    pub fn should_claim(&self, slot: u32, now: redux::Timestamp) -> bool {
+        match self {
+            Self::Error {
+                time, global_slot, ..
+            } if *global_slot == slot => now
+                .checked_sub(*time)
+                .is_some_and(|dur| dur >= SLOT_CLAIM_RETRY_INTERVAL),
+            _ => self.global_slot() != Some(slot),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claim_retried_after_error() {
+        let now = redux::Timestamp::new(1_000_000_000);
+        let idle = BlockProducerSlotClaimState::Idle;
+        assert!(idle.should_claim(10, now));
+
+        let granted = BlockProducerSlotClaimState::Granted {
+            time: now,
+            global_slot: 10,
+        };
+        assert!(granted.is_granted(10));
+        assert!(!granted.should_claim(10, now));
+        assert!(granted.should_claim(11, now));
+
+        let error = BlockProducerSlotClaimState::Error {
+            time: now,
+            global_slot: 10,
+            error: "timeout".to_owned(),
+        };
+        assert!(!error.should_claim(10, now));
+        assert!(error.should_claim(10, now + SLOT_CLAIM_RETRY_INTERVAL));
+
+        let denied = BlockProducerSlotClaimState::Denied {
+            time: now,
+            global_slot: 10,
+            holder: "other".to_owned(),
+        };
+        assert!(denied.is_denied(10));
+        assert!(!denied.should_claim(10, now + SLOT_CLAIM_RETRY_INTERVAL));
+    }
+}
@@ -11,8 +11,8 @@ use serde::{Deserialize, Serialize};
 use crate::account::AccountPublicKey;
 
 use super::{
-    vrf_evaluator::BlockProducerVrfEvaluatorState, BlockProducerConfig, BlockProducerWonSlot,
-    BlockWithoutProof,
+    vrf_evaluator::BlockProducerVrfEvaluatorState, BlockProducerConfig, BlockProducerExternalDiff,
+    BlockProducerSlotClaimState, BlockProducerWonSlot, BlockWithoutProof,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -26,6 +26,11 @@ pub struct BlockProducerEnabled {
     /// Blocks that were injected into transition frontier, but hasn't
     /// become our best tip yet.
     pub injected_blocks: BTreeSet<v2::StateHash>,
+    #[serde(default)]
+    pub slot_claim: BlockProducerSlotClaimState,
+    /// Diff submitted with the block template rpc.
+    #[serde(default)]
+    pub external_diff: Option<BlockProducerExternalDiff>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -140,6 +145,11 @@ pub enum BlockProducerWonSlotDiscardReason {
     BestTipStakingLedgerDifferent,
     BestTipGlobalSlotHigher,
     BestTipSuperior,
+    /// Another producer with the same key claimed the slot.
+    SlotClaimedByOther,
+    /// Signing journal shows that another block was already signed for
+    /// the slot, or the journal couldn't be written.
+    SigningRefused,
 }
 
 impl BlockProducerState {
@@ -149,6 +159,8 @@ impl BlockProducerState {
             vrf_evaluator: BlockProducerVrfEvaluatorState::new(now),
             current: BlockProducerCurrentState::Idle { time: now },
             injected_blocks: Default::default(),
+            slot_claim: Default::default(),
+            external_diff: None,
         }))
     }
 
@@ -226,12 +238,76 @@ impl BlockProducerState {
         self.vrf_evaluator()?.vrf_delegator_table_inputs()
     }
 
+    /// If the next epoch delegator table is being precomputed, get it's
+    /// inputs.
+    pub fn vrf_delegator_table_precompute_inputs(
+        &self,
+    ) -> Option<(&v2::LedgerHash, &AccountPublicKey)> {
+        self.vrf_evaluator()?.delegator_table_precompute_inputs()
+    }
+
     pub fn pending_transactions(&self) -> Vec<valid::UserCommand> {
         self.with(Vec::new(), |this| this.current.pending_transactions())
     }
 }
 
+impl BlockProducerEnabled {
+    /// Whether the block for the current won slot may be produced, as
+    /// far as the slot claim is concerned.
+    pub fn slot_claim_allows_production(&self) -> bool {
+        !self.config.slot_claim_required
+            || self
+                .current
+                .won_slot()
+                .is_some_and(|won_slot| self.slot_claim.is_granted(won_slot.global_slot()))
+    }
+
+    /// Whether the current won slot should be claimed now.
+    pub fn won_slot_should_claim(&self, now: redux::Timestamp) -> bool {
+        self.config.slot_claim_required
+            && self.current.won_slot_should_produce(now)
+            && self
+                .current
+                .won_slot()
+                .is_some_and(|won_slot| self.slot_claim.should_claim(won_slot.global_slot(), now))
+    }
+
+    pub fn won_slot_should_discard(
+        &self,
+        best_tip: &ArcBlockWithHash,
+    ) -> Option<BlockProducerWonSlotDiscardReason> {
+        let reason = self.current.won_slot_should_discard(best_tip);
+        if reason.is_some()
+            || matches!(
+                self.current,
+                BlockProducerCurrentState::WonSlotDiscarded { .. }
+            )
+        {
+            return reason;
+        }
+        self.current
+            .won_slot()
+            .filter(|won_slot| self.slot_claim.is_denied(won_slot.global_slot()))
+            .map(|_| BlockProducerWonSlotDiscardReason::SlotClaimedByOther)
+    }
+}
+
 impl BlockProducerCurrentState {
+    /// Whether the diff for the `global_slot` can still be replaced by the
+    /// externally submitted one, it's too late once its creation started.
+    pub fn accepts_external_diff(&self, global_slot: u32) -> bool {
+        match self {
+            Self::StagedLedgerDiffCreatePending { won_slot, .. }
+            | Self::StagedLedgerDiffCreateSuccess { won_slot, .. }
+            | Self::BlockUnprovenBuilt { won_slot, .. }
+            | Self::BlockProvePending { won_slot, .. }
+            | Self::BlockProveSuccess { won_slot, .. }
+            | Self::Produced { won_slot, .. }
+            | Self::Injected { won_slot, .. } => won_slot.global_slot() != global_slot,
+            _ => true,
+        }
+    }
+
     pub fn won_slot_should_search(&self) -> bool {
         match self {
             Self::Idle { .. } | Self::WonSlotDiscarded { .. } | Self::Injected { .. } => true,
@@ -0,0 +1,467 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use ledger::scan_state::transaction_logic::valid;
+use mina_p2p_messages::v2;
+use openmina_core::{
+    block::AppliedBlock,
+    constants::constraint_constants,
+    snark::{Snark, SnarkJobId},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{BlockProducerCurrentState, BlockProducerWonSlot};
+
+/// Coinbase is always supercharged for now.
+// TODO(binier): supercharge only if the winner account is untimed.
+pub const BLOCK_PRODUCER_SUPERCHARGE_COINBASE: bool = true;
+
+/// Everything needed to assemble the staged ledger diff of the block for
+/// the next won slot outside of the node.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockTemplate {
+    pub won_slot: BlockTemplateWonSlot,
+    pub producer: v2::NonZeroCurvePoint,
+    pub pred_block: BlockTemplatePredBlock,
+    /// Maximum number of transactions in the diff.
+    pub transaction_capacity: usize,
+    /// Commands selected from the pool by the node's policy, by fee, as
+    /// many as the bought snark work leaves room for.
+    pub transactions: Vec<v2::MinaBaseUserCommandStableV2>,
+    /// Snark jobs of the predecessor's scan state, the older ones first.
+    pub snark_jobs: Vec<BlockTemplateSnarkJob>,
+    /// Number of the oldest `snark_jobs` the diff buys, see
+    /// [`BlockTemplate::bought_jobs`].
+    pub bought_jobs: usize,
+    pub coinbase: BlockTemplateCoinbase,
+    /// Fee transfers paying for the bought snark work, in the order the
+    /// jobs are bought, merged per prover.
+    pub fee_transfers: Vec<BlockTemplateFeeTransfer>,
+    /// Diff submitted for one of the won slots.
+    pub external_diff: Option<BlockTemplateExternalDiff>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockTemplateWonSlot {
+    pub global_slot: u32,
+    pub global_slot_since_genesis: v2::MinaNumbersGlobalSlotSinceGenesisMStableV1,
+    pub slot_time: redux::Timestamp,
+    pub delegator: v2::NonZeroCurvePoint,
+    pub staking_ledger_hash: v2::LedgerHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockTemplatePredBlock {
+    pub hash: v2::StateHash,
+    pub height: u32,
+    pub staged_ledger_hash: v2::MinaBaseStagedLedgerHashStableV1,
+    pub protocol_state: v2::MinaStateProtocolStateValueStableV2,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockTemplateSnarkJob {
+    pub job_id: SnarkJobId,
+    pub order: usize,
+    /// Best completed snark work for the job.
+    pub snark: Option<BlockTemplateSnark>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockTemplateSnark {
+    pub prover: v2::NonZeroCurvePoint,
+    pub fee: v2::CurrencyFeeStableV1,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockTemplateCoinbase {
+    pub receiver: v2::NonZeroCurvePoint,
+    pub amount: v2::CurrencyAmountStableV1,
+    pub supercharged: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockTemplateFeeTransfer {
+    pub receiver: v2::NonZeroCurvePoint,
+    pub fee: v2::CurrencyFeeStableV1,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockTemplateExternalDiff {
+    pub global_slot: u32,
+    pub submitted_at: redux::Timestamp,
+    pub status: BlockProducerExternalDiffStatus,
+}
+
+/// Staged ledger diff assembled outside of the node, used instead of the
+/// node's own one for the won slot.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerExternalDiff {
+    pub global_slot: u32,
+    pub time: redux::Timestamp,
+    pub diff: Arc<v2::StagedLedgerDiffDiffStableV2>,
+    pub status: BlockProducerExternalDiffStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum BlockProducerExternalDiffStatus {
+    Submitted,
+    Applied,
+    /// Diff couldn't be applied, the node's own diff was used instead.
+    Rejected(String),
+}
+
+impl BlockTemplate {
+    /// Won slot the template is built for: the one being produced, if its
+    /// diff wasn't created yet, or the next one.
+    pub fn won_slot(state: &crate::State) -> Result<BlockProducerWonSlot, String> {
+        let bp = state
+            .block_producer
+            .as_ref()
+            .ok_or("block producer is disabled")?;
+        let best_tip = state
+            .transition_frontier
+            .best_tip()
+            .ok_or("no best tip yet")?;
+
+        match &bp.current {
+            BlockProducerCurrentState::WonSlot { won_slot, .. }
+            | BlockProducerCurrentState::WonSlotWait { won_slot, .. }
+            | BlockProducerCurrentState::WonSlotProduceInit { won_slot, .. }
+            | BlockProducerCurrentState::WonSlotTransactionsGet { won_slot, .. }
+            | BlockProducerCurrentState::WonSlotTransactionsSuccess { won_slot, .. } => {
+                Ok(won_slot.clone())
+            }
+            _ => {
+                let cur_global_slot = state.cur_global_slot().ok_or("no current slot yet")?;
+                bp.vrf_evaluator
+                    .next_won_slot(cur_global_slot, best_tip)
+                    .ok_or_else(|| "no won slot ahead".to_owned())
+            }
+        }
+    }
+
+    pub fn new(state: &crate::State) -> Result<Self, String> {
+        let won_slot = Self::won_slot(state)?;
+        let bp = state
+            .block_producer
+            .as_ref()
+            .ok_or("block producer is disabled")?;
+        let pred_block = Self::pred_block(state, &won_slot).ok_or("no best tip yet")?;
+
+        let transaction_capacity =
+            2_usize.saturating_pow(constraint_constants().transaction_capacity_log_2 as u32);
+        let mut transactions = state
+            .transaction_pool
+            .list_includable_transactions(transaction_capacity)
+            .into_iter()
+            .map(|cmd| cmd.data.into())
+            .collect::<Vec<v2::MinaBaseUserCommandStableV2>>();
+
+        let mut snark_jobs = state
+            .snark_pool
+            .range(..)
+            .map(|(_, job)| BlockTemplateSnarkJob {
+                job_id: job.id.clone(),
+                order: job.order,
+                snark: job
+                    .snark
+                    .as_ref()
+                    .filter(|snark| snark.is_verified)
+                    .map(|snark| BlockTemplateSnark {
+                        prover: snark.work.snarker.clone(),
+                        fee: snark.work.fee.clone(),
+                    }),
+            })
+            .collect::<Vec<_>>();
+        snark_jobs.sort_by_key(|job| job.order);
+
+        // Coinbase takes a slot too.
+        let slots = transactions.len().saturating_add(1);
+        let bought_jobs = Self::bought_jobs(&snark_jobs, slots);
+        if bought_jobs < snark_jobs.len() {
+            transactions.truncate(bought_jobs.saturating_sub(1));
+        }
+        let fee_transfers = Self::fee_transfers(snark_jobs.get(..bought_jobs).unwrap_or_default());
+
+        let constants = constraint_constants();
+        let coinbase_amount = if BLOCK_PRODUCER_SUPERCHARGE_COINBASE {
+            constants
+                .coinbase_amount
+                .saturating_mul(constants.supercharged_coinbase_factor)
+        } else {
+            constants.coinbase_amount
+        };
+
+        Ok(Self {
+            won_slot: BlockTemplateWonSlot {
+                global_slot: won_slot.global_slot(),
+                global_slot_since_genesis: won_slot
+                    .global_slot_since_genesis(pred_block.global_slot_diff()),
+                slot_time: won_slot.slot_time,
+                delegator: won_slot.delegator.0.clone(),
+                staking_ledger_hash: won_slot.staking_ledger_hash.clone(),
+            },
+            producer: bp.config.pub_key.clone(),
+            pred_block: BlockTemplatePredBlock {
+                hash: pred_block.hash().clone(),
+                height: pred_block.height(),
+                staged_ledger_hash: pred_block.staged_ledger_hashes().clone(),
+                protocol_state: pred_block.header().protocol_state.clone(),
+            },
+            transaction_capacity,
+            transactions,
+            snark_jobs,
+            bought_jobs,
+            coinbase: BlockTemplateCoinbase {
+                receiver: bp.config.coinbase_receiver().clone(),
+                amount: v2::CurrencyAmountStableV1(coinbase_amount.into()),
+                supercharged: BLOCK_PRODUCER_SUPERCHARGE_COINBASE,
+            },
+            fee_transfers,
+            external_diff: bp
+                .external_diff
+                .as_ref()
+                .map(|external| BlockTemplateExternalDiff {
+                    global_slot: external.global_slot,
+                    submitted_at: external.time,
+                    status: external.status.clone(),
+                }),
+        })
+    }
+
+    /// Number of the oldest jobs bought by the diff filling `slots`.
+    ///
+    /// Like the node's own diff, the jobs are bought in order, until the
+    /// first one without the snark work. Work is needed for each occupied
+    /// slot, unless all the jobs are bought.
+    pub fn bought_jobs(snark_jobs: &[BlockTemplateSnarkJob], slots: usize) -> usize {
+        snark_jobs
+            .iter()
+            .take_while(|job| job.snark.is_some())
+            .count()
+            .min(slots)
+    }
+
+    /// Fee transfers paying for the `bought` jobs, merged per prover.
+    pub fn fee_transfers(bought: &[BlockTemplateSnarkJob]) -> Vec<BlockTemplateFeeTransfer> {
+        let mut fee_transfers = Vec::<BlockTemplateFeeTransfer>::new();
+        for snark in bought.iter().filter_map(|job| job.snark.as_ref()) {
+            match fee_transfers
+                .iter_mut()
+                .find(|transfer| transfer.receiver == snark.prover)
+            {
+                Some(transfer) => {
+                    let fee = transfer.fee.0.as_u64().saturating_add(snark.fee.0.as_u64());
+                    transfer.fee = v2::CurrencyFeeStableV1(fee.into());
+                }
+                None => fee_transfers.push(BlockTemplateFeeTransfer {
+                    receiver: snark.prover.clone(),
+                    fee: snark.fee.clone(),
+                }),
+            }
+        }
+        fee_transfers
+    }
+
+    /// Block the block for the won slot will extend, see
+    /// [`super::BlockProducerAction::WonSlotProduceInit`].
+    fn pred_block<'a>(
+        state: &'a crate::State,
+        won_slot: &BlockProducerWonSlot,
+    ) -> Option<&'a AppliedBlock> {
+        let best_chain = &state.transition_frontier.best_chain;
+        let best_tip = best_chain.last()?;
+        if best_tip.global_slot() == won_slot.global_slot() {
+            best_chain.iter().nth_back(1)
+        } else {
+            Some(best_tip)
+        }
+    }
+}
+
+/// Checks that the content of the externally assembled diff was already
+/// verified by the node: snark work is in the snark pool and commands are
+/// in the transaction pool. Proofs then don't need to be verified while
+/// the block is being produced, which wouldn't fit the time budget.
+pub fn external_diff_precheck(
+    diff: &v2::StagedLedgerDiffDiffStableV2,
+    completed_snarks: &BTreeMap<SnarkJobId, Snark>,
+    transactions_by_fee: &[valid::UserCommand],
+) -> Result<(), String> {
+    let (two, one) = (&diff.diff.0, diff.diff.1.as_ref());
+    let completed_works = two
+        .completed_works
+        .iter()
+        .chain(one.into_iter().flat_map(|one| one.completed_works.iter()));
+    for work in completed_works {
+        let job_id = SnarkJobId::from(&work.proofs);
+        let is_known = completed_snarks.get(&job_id).is_some_and(|snark| {
+            snark.snarker == work.prover && snark.fee == work.fee && *snark.proofs == work.proofs
+        });
+        if !is_known {
+            return Err(format!("snark work {job_id} isn't in the snark pool"));
+        }
+    }
+
+    let pool = transactions_by_fee
+        .iter()
+        .map(|cmd| v2::MinaBaseUserCommandStableV2::from(cmd.clone()))
+        .collect::<Vec<_>>();
+    let commands = two
+        .commands
+        .iter()
+        .chain(one.into_iter().flat_map(|one| one.commands.iter()));
+    for (i, command) in commands.enumerate() {
+        if !pool.contains(&command.data) {
+            return Err(format!("command {i} isn't in the transaction pool"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ledger::AccountIndex;
+    use mina_p2p_messages::{
+        bigint::BigInt,
+        list::List,
+        v2::{EpochSeed, LedgerHash, MinaBaseEpochSeedStableV1},
+    };
+    use openmina_node_account::AccountSecretKey;
+    use vrf::VrfWonSlot;
+
+    use super::*;
+    use crate::block_producer::{vrf_evaluator::VrfWonSlotWithHash, BlockProducerCurrentState};
+
+    const LEDGER_HASH: &str = "jxTAZfKKDxoX4vtt68pQCWooXoVLjnfBpusaMwewrcZxsL3uWp6";
+
+    fn job(order: usize, snark: Option<(&v2::NonZeroCurvePoint, u64)>) -> BlockTemplateSnarkJob {
+        let passes = format!("{LEDGER_HASH}_{LEDGER_HASH}");
+        BlockTemplateSnarkJob {
+            job_id: format!("{passes}-{passes}").parse().unwrap(),
+            order,
+            snark: snark.map(|(prover, fee)| BlockTemplateSnark {
+                prover: prover.clone(),
+                fee: v2::CurrencyFeeStableV1(fee.into()),
+            }),
+        }
+    }
+
+    #[test]
+    fn bought_jobs_and_fee_transfers() {
+        let prover1 = AccountSecretKey::rand().public_key().into();
+        let prover2 = AccountSecretKey::rand().public_key().into();
+        let jobs = vec![
+            job(0, Some((&prover1, 10))),
+            job(1, Some((&prover2, 20))),
+            job(2, Some((&prover1, 30))),
+            job(3, None),
+            job(4, Some((&prover2, 40))),
+        ];
+
+        // Jobs are bought in order, only as many as there are slots.
+        assert_eq!(BlockTemplate::bought_jobs(&jobs, 2), 2);
+        // Buying stops at the first job without the work.
+        assert_eq!(BlockTemplate::bought_jobs(&jobs, 10), 3);
+        assert_eq!(BlockTemplate::bought_jobs(&[], 10), 0);
+
+        let fee_transfers = BlockTemplate::fee_transfers(jobs.get(..1).unwrap());
+        assert_eq!(fee_transfers.len(), 1);
+        // Fees of the same prover are merged, the unbought jobs not paid.
+        let fee_transfers = BlockTemplate::fee_transfers(jobs.get(..3).unwrap());
+        let [transfer1, transfer2] = fee_transfers.as_slice() else {
+            panic!("expected 2 fee transfers, got: {fee_transfers:?}");
+        };
+        assert_eq!(transfer1.receiver, prover1);
+        assert_eq!(transfer1.fee.0.as_u64(), 40);
+        assert_eq!(transfer2.receiver, prover2);
+        assert_eq!(transfer2.fee.0.as_u64(), 20);
+    }
+
+    #[test]
+    fn external_diff_accepted_until_diff_creation() {
+        let won_slot = VrfWonSlot {
+            producer: AccountSecretKey::genesis_producer().public_key(),
+            winner_account: AccountSecretKey::genesis_producer().public_key(),
+            vrf_output: Box::new(
+                vrf::genesis_vrf(EpochSeed::from(MinaBaseEpochSeedStableV1(BigInt::zero())))
+                    .unwrap(),
+            ),
+            global_slot: 10,
+            account_index: AccountIndex(0),
+            value_with_threshold: None,
+        };
+        let won_slot =
+            VrfWonSlotWithHash::new(won_slot, LedgerHash::from_str(LEDGER_HASH).unwrap());
+        let won_slot = BlockProducerWonSlot::from_vrf_won_slot(&won_slot, redux::Timestamp::ZERO);
+
+        let idle = BlockProducerCurrentState::Idle {
+            time: redux::Timestamp::ZERO,
+        };
+        assert!(idle.accepts_external_diff(10));
+
+        let pending = BlockProducerCurrentState::StagedLedgerDiffCreatePending {
+            time: redux::Timestamp::ZERO,
+            won_slot,
+            chain: vec![],
+            transactions_by_fee: vec![],
+        };
+        assert!(!pending.accepts_external_diff(10));
+        // Diff for the later won slot can still be submitted.
+        assert!(pending.accepts_external_diff(11));
+    }
+
+    fn command() -> v2::StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B {
+        let pk = "B62qnEdPB1V5YPEcGaETb19naLJV6sWdveCZEjSLhcVyrPcPWHkGGax";
+        serde_json::from_value(serde_json::json!({
+            "data": ["Signed_command", {
+                "payload": {
+                    "common": {
+                        "fee": "85776125",
+                        "fee_payer_pk": pk,
+                        "nonce": "1",
+                        "valid_until": "4294967295",
+                        "memo": "E4Yd67s51QN9DZVDy8JKPEoNGykMsYQ5KRiKpZHiLZTjA8dB9SnFT",
+                    },
+                    "body": ["Payment", {
+                        "receiver_pk": "B62qmurT6PKCyetUB6aqagxPbWc74uDaddMdDSm9FQd7ELTKn6BRdv4",
+                        "amount": "1500000",
+                    }],
+                },
+                "signer": pk,
+                "signature": "7mX1Jc4Et7GHmRcTLSJU5E1aKUoja4gyusEXe2xFnyGoy6DHHDVWmuLCTveWnTaBcWQi51sLNuYVMsXkSiR4QBnf5Ga4yCR1",
+            }],
+            "status": ["Failed", [[["Amount_insufficient_to_create_account"]]]],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn external_diff_precheck_falls_back() {
+        let command = command();
+        let diff = v2::StagedLedgerDiffDiffStableV2 {
+            diff: v2::StagedLedgerDiffDiffDiffStableV2(
+                v2::StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2 {
+                    completed_works: List::new(),
+                    commands: std::iter::once(command.clone()).collect(),
+                    coinbase:
+                        v2::StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2Coinbase::Zero,
+                    internal_command_statuses: List::new(),
+                },
+                None,
+            ),
+        };
+        let completed_snarks = BTreeMap::new();
+
+        // Command which isn't in our pool wasn't verified, so the diff is
+        // rejected and our own one is used instead.
+        assert!(external_diff_precheck(&diff, &completed_snarks, &[]).is_err());
+
+        let pool = vec![valid::UserCommand::try_from(&command.data).unwrap()];
+        assert_eq!(
+            external_diff_precheck(&diff, &completed_snarks, &pool),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use ledger::staged_ledger::transaction_validator::IncrementalApplicationProgress;
+
+/// Included commands are applied again when their statuses are computed
+/// and when the diff is applied to the staged ledger, so the time needed
+/// to finish the diff grows with the number of included commands.
+const FINALIZE_COST_FACTOR: u32 = 2;
+
+/// Decides whether there is enough time left to include one more command
+/// into the staged ledger diff, so that the diff creation finishes within
+/// the budget (see [`super::BlockProducerConfig::assembly_deadline`]).
+///
+/// Commands are ordered by fee, so stopping early drops the lowest-fee ones.
+#[derive(Debug, Clone)]
+pub struct BlockAssemblyTimeBudget {
+    budget: Duration,
+    /// Number of commands considered so far, including invalid ones.
+    considered: u32,
+    /// Set once the budget ran out.
+    exhausted: bool,
+}
+
+impl BlockAssemblyTimeBudget {
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            considered: 0,
+            exhausted: false,
+        }
+    }
+
+    /// Called before each command with the time elapsed since the start
+    /// of the diff creation and the effects of the commands included so far.
+    pub fn should_continue(
+        &mut self,
+        elapsed: Duration,
+        progress: &IncrementalApplicationProgress,
+    ) -> bool {
+        if self.exhausted {
+            return false;
+        }
+        let per_command = elapsed.checked_div(self.considered).unwrap_or_default();
+        let applied = u32::try_from(progress.applied).unwrap_or(u32::MAX);
+        let finalize_cost = per_command
+            .saturating_mul(applied.saturating_add(1))
+            .saturating_mul(FINALIZE_COST_FACTOR);
+        let required = per_command.saturating_add(finalize_cost);
+
+        let remaining = self.budget.saturating_sub(elapsed);
+        if remaining <= required {
+            self.exhausted = true;
+            return false;
+        }
+        self.considered = self.considered.saturating_add(1);
+        true
+    }
+
+    /// Number of commands that weren't considered because the budget
+    /// ran out, out of `total`.
+    pub fn dropped_commands(&self, total: usize) -> usize {
+        if !self.exhausted {
+            return 0;
+        }
+        total.saturating_sub(self.considered as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::{
+        currency::{Amount, Signed},
+        fee_excess::FeeExcess,
+    };
+
+    use super::*;
+
+    fn progress(applied: usize) -> IncrementalApplicationProgress {
+        IncrementalApplicationProgress {
+            applied,
+            fee_excess: FeeExcess::empty(),
+            supply_increase: Signed::<Amount>::zero(),
+            available_space: 128,
+        }
+    }
+
+    fn millis(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn stops_before_deadline() {
+        let mut budget = BlockAssemblyTimeBudget::new(millis(1000));
+        assert!(budget.should_continue(millis(0), &progress(0)));
+        // 100ms per command, including one more needs `100 * (1 + 2 * (applied + 1))`.
+        assert!(budget.should_continue(millis(100), &progress(1)));
+        assert!(budget.should_continue(millis(200), &progress(2)));
+        assert!(!budget.should_continue(millis(300), &progress(3)));
+        assert!(!budget.should_continue(millis(300), &progress(3)));
+        assert_eq!(budget.dropped_commands(10), 7);
+
+        let mut budget = BlockAssemblyTimeBudget::new(millis(1000));
+        assert!(budget.should_continue(millis(0), &progress(0)));
+        assert_eq!(budget.dropped_commands(10), 0);
+    }
+}
@@ -16,6 +16,18 @@ pub use block_producer_actions::*;
 
 mod block_producer_reducer;
 
+mod block_producer_time_budget;
+pub use block_producer_time_budget::*;
+
+mod block_producer_slot_claim;
+pub use block_producer_slot_claim::*;
+
+mod block_producer_signing_journal;
+pub use block_producer_signing_journal::*;
+
+mod block_producer_template;
+pub use block_producer_template::*;
+
 use ledger::AccountIndex;
 use mina_p2p_messages::{list::List, v2};
 use openmina_core::{block::ArcBlockWithHash, constants::constraint_constants};
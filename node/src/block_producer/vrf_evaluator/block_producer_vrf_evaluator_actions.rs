@@ -3,6 +3,7 @@ use std::sync::Arc;
 use crate::account::AccountPublicKey;
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorStatus;
 use crate::block_producer::vrf_evaluator::EpochContext;
+use crate::ledger::read::LedgerReadRequest;
 use mina_p2p_messages::v2::{
     ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1,
     ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1, LedgerHash,
@@ -74,6 +75,19 @@ pub enum BlockProducerVrfEvaluatorAction {
     FinalizeDelegatorTableConstruction {
         delegator_table: Arc<DelegatorTable>,
     },
+    /// Constructing next epoch delegator table in advance.
+    #[action_event(level = info, fields(ledger_hash = display(ledger_hash)))]
+    PrecomputeDelegatorTable {
+        ledger_hash: LedgerHash,
+        producer: AccountPublicKey,
+    },
+    /// Next epoch delegator table constructed.
+    #[action_event(level = info, fields(ledger_hash = display(ledger_hash)))]
+    PrecomputeDelegatorTableSuccess {
+        ledger_hash: LedgerHash,
+        producer: AccountPublicKey,
+        delegator_table: Arc<DelegatorTable>,
+    },
     /// Selecting starting slot.
     #[action_event(level = info, fields(current_global_slot, best_tip_height))]
     SelectInitialSlot {
@@ -175,7 +189,8 @@ impl redux::EnablingCondition<crate::State> for BlockProducerVrfEvaluatorAction
             BlockProducerVrfEvaluatorAction::BeginDelegatorTableConstruction => {
                 state.block_producer.with(false, |this| {
                     this.vrf_evaluator.can_construct_delegator_table()
-                        && state.ledger.read.is_total_cost_under_limit()
+                        && (state.ledger.read.is_total_cost_under_limit()
+                            || this.vrf_evaluator.ready_delegator_table().is_some())
                 })
             }
             BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction { .. } => {
@@ -183,6 +198,30 @@ impl redux::EnablingCondition<crate::State> for BlockProducerVrfEvaluatorAction
                     this.vrf_evaluator.is_delegator_table_requested()
                 })
             }
+            BlockProducerVrfEvaluatorAction::PrecomputeDelegatorTable {
+                ledger_hash,
+                producer,
+            } => state.block_producer.with(false, |this| {
+                this.vrf_evaluator
+                    .precomputed_delegator_table(ledger_hash, producer)
+                    .is_none()
+                    && state.ledger.read.is_total_cost_under_limit()
+                    && !state
+                        .ledger
+                        .read
+                        .has_same_request(&LedgerReadRequest::DelegatorTable(
+                            ledger_hash.clone(),
+                            producer.clone(),
+                        ))
+            }),
+            BlockProducerVrfEvaluatorAction::PrecomputeDelegatorTableSuccess {
+                ledger_hash,
+                producer,
+                ..
+            } => state.block_producer.with(false, |this| {
+                this.vrf_evaluator
+                    .is_delegator_table_precomputing(ledger_hash, producer)
+            }),
             BlockProducerVrfEvaluatorAction::BeginEpochEvaluation { .. } => state
                 .block_producer
                 .with(false, |this| this.vrf_evaluator.is_slot_selection()),
@@ -225,6 +225,19 @@ impl BlockProducerVrfEvaluatorState {
 
                     dispatcher
                         .push(BlockProducerVrfEvaluatorAction::CleanupOldSlots { best_tip_epoch });
+
+                    // Next epoch ledger can't change anymore, so the delegator table
+                    // for it can be constructed before the epoch starts.
+                    if *is_next_epoch_seed_finalized
+                        && next_epoch_data.ledger.hash != staking_epoch_data.ledger.hash
+                    {
+                        dispatcher.push(
+                            BlockProducerVrfEvaluatorAction::PrecomputeDelegatorTable {
+                                ledger_hash: next_epoch_data.ledger.hash.clone(),
+                                producer: config.pub_key.clone().into(),
+                            },
+                        );
+                    }
                 }
             }
             BlockProducerVrfEvaluatorAction::InitializeEpochEvaluation {
@@ -252,6 +265,7 @@ impl BlockProducerVrfEvaluatorState {
                 dispatcher.push(BlockProducerVrfEvaluatorAction::BeginDelegatorTableConstruction);
             }
             BlockProducerVrfEvaluatorAction::BeginDelegatorTableConstruction => {
+                let precomputed = state.ready_delegator_table();
                 let BlockProducerVrfEvaluatorStatus::ReadyToEvaluate {
                     best_tip_epoch,
                     best_tip_slot,
@@ -278,6 +292,14 @@ impl BlockProducerVrfEvaluatorState {
                 };
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                if let Some(delegator_table) = precomputed {
+                    dispatcher.push(
+                        BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction {
+                            delegator_table,
+                        },
+                    );
+                    return;
+                }
                 let (staking_ledger_hash, producer) =
                     match state.block_producer.vrf_delegator_table_inputs() {
                         Some((v1, v2)) => (v1.clone(), v2.clone()),
@@ -286,7 +308,7 @@ impl BlockProducerVrfEvaluatorState {
 
                 dispatcher.push(LedgerReadAction::Init {
                     request: LedgerReadRequest::DelegatorTable(staking_ledger_hash, producer),
-                    callback: LedgerReadInitCallback::None,
+                    callback: LedgerReadInitCallback::none(),
                 })
             }
             BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction {
@@ -363,6 +385,27 @@ impl BlockProducerVrfEvaluatorState {
                     staking_epoch_data: staking_epoch_data.clone(),
                 });
             }
+            BlockProducerVrfEvaluatorAction::PrecomputeDelegatorTable {
+                ledger_hash,
+                producer,
+            } => {
+                state.delegator_table_precompute_init(ledger_hash.clone(), producer.clone());
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::DelegatorTable(
+                        ledger_hash.clone(),
+                        producer.clone(),
+                    ),
+                    callback: LedgerReadInitCallback::none(),
+                });
+            }
+            BlockProducerVrfEvaluatorAction::PrecomputeDelegatorTableSuccess {
+                delegator_table,
+                ..
+            } => {
+                state.delegator_table_precompute_success(delegator_table.clone());
+            }
             BlockProducerVrfEvaluatorAction::BeginEpochEvaluation {
                 best_tip_epoch: _,
                 evaluation_epoch,
@@ -408,8 +451,12 @@ impl BlockProducerVrfEvaluatorState {
                 }
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
-                if let Some(vrf_evaluator_state) = state.block_producer.vrf_evaluator() {
+                if let Some((vrf_evaluator_state, _config)) =
+                    state.block_producer.vrf_evaluator_with_config()
+                {
                     if let Some(vrf_input) = vrf_evaluator_state.construct_vrf_input() {
+                        #[cfg(feature = "testing")]
+                        let vrf_input = vrf_input.with_empty_blocks(_config.empty_blocks.as_ref());
                         dispatcher
                             .push(BlockProducerVrfEvaluatorAction::EvaluateSlot { vrf_input });
                     }
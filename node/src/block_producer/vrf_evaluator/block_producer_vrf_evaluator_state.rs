@@ -20,6 +20,10 @@ pub struct BlockProducerVrfEvaluatorState {
     last_evaluated_epoch: Option<u32>,
     pending_evaluation: Option<PendingEvaluation>,
     epoch_context: EpochContext,
+    /// Delegator table for the next epoch, computed in advance so that
+    /// the evaluation of the next epoch doesn't wait for it.
+    #[serde(default)]
+    next_epoch_delegator_table: Option<PrecomputedDelegatorTable>,
 }
 
 impl BlockProducerVrfEvaluatorState {
@@ -32,6 +36,7 @@ impl BlockProducerVrfEvaluatorState {
             last_evaluated_epoch: Default::default(),
             pending_evaluation: Default::default(),
             epoch_context: EpochContext::Waiting,
+            next_epoch_delegator_table: None,
         }
     }
 
@@ -359,6 +364,88 @@ impl BlockProducerVrfEvaluatorState {
             _ => None,
         }
     }
+
+    /// Precomputed delegator table for the epoch which is about to be
+    /// evaluated, if it is available.
+    pub fn ready_delegator_table(&self) -> Option<Arc<DelegatorTable>> {
+        let BlockProducerVrfEvaluatorStatus::ReadyToEvaluate {
+            staking_epoch_data,
+            producer,
+            ..
+        } = &self.status
+        else {
+            return None;
+        };
+        self.precomputed_delegator_table(&staking_epoch_data.ledger, producer)
+    }
+
+    pub fn precomputed_delegator_table(
+        &self,
+        ledger_hash: &v2::LedgerHash,
+        producer: &AccountPublicKey,
+    ) -> Option<Arc<DelegatorTable>> {
+        self.next_epoch_delegator_table
+            .as_ref()
+            .filter(|v| v.is_for(ledger_hash, producer))?
+            .delegator_table
+            .clone()
+    }
+
+    /// Returns `true` if the delegator table for the ledger is being
+    /// precomputed.
+    pub fn is_delegator_table_precomputing(
+        &self,
+        ledger_hash: &v2::LedgerHash,
+        producer: &AccountPublicKey,
+    ) -> bool {
+        self.next_epoch_delegator_table
+            .as_ref()
+            .is_some_and(|v| v.is_for(ledger_hash, producer) && v.delegator_table.is_none())
+    }
+
+    /// Inputs of the delegator table precomputation, which hasn't finished
+    /// yet.
+    pub fn delegator_table_precompute_inputs(
+        &self,
+    ) -> Option<(&v2::LedgerHash, &AccountPublicKey)> {
+        self.next_epoch_delegator_table
+            .as_ref()
+            .filter(|v| v.delegator_table.is_none())
+            .map(|v| (&v.ledger_hash, &v.producer))
+    }
+
+    pub fn delegator_table_precompute_init(
+        &mut self,
+        ledger_hash: v2::LedgerHash,
+        producer: AccountPublicKey,
+    ) {
+        self.next_epoch_delegator_table = Some(PrecomputedDelegatorTable {
+            ledger_hash,
+            producer,
+            delegator_table: None,
+        });
+    }
+
+    pub fn delegator_table_precompute_success(&mut self, delegator_table: Arc<DelegatorTable>) {
+        if let Some(precomputed) = &mut self.next_epoch_delegator_table {
+            precomputed.delegator_table = Some(delegator_table);
+        }
+    }
+}
+
+/// Delegator table computed ahead of the epoch in which it is used.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrecomputedDelegatorTable {
+    pub ledger_hash: v2::LedgerHash,
+    pub producer: AccountPublicKey,
+    /// `None` while the table is being computed.
+    pub delegator_table: Option<Arc<DelegatorTable>>,
+}
+
+impl PrecomputedDelegatorTable {
+    fn is_for(&self, ledger_hash: &v2::LedgerHash, producer: &AccountPublicKey) -> bool {
+        &self.ledger_hash == ledger_hash && &self.producer == producer
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -665,6 +752,7 @@ mod test {
                 last_evaluated_epoch: None,
                 pending_evaluation: None,
                 epoch_context: EpochContext::Current(DUMMY_STAKING_EPOCH_DATA.to_owned().into()),
+                next_epoch_delegator_table: None,
             };
             Mutex::new(state)
         };
@@ -688,6 +776,7 @@ mod test {
                 last_evaluated_epoch: Some(0),
                 pending_evaluation: None,
                 epoch_context: EpochContext::Current(DUMMY_STAKING_EPOCH_DATA.to_owned().into()),
+                next_epoch_delegator_table: None,
             };
             Mutex::new(state)
         };
@@ -711,6 +800,7 @@ mod test {
                 last_evaluated_epoch: Some(1),
                 pending_evaluation: None,
                 epoch_context: EpochContext::Current(DUMMY_STAKING_EPOCH_DATA.to_owned().into()),
+                next_epoch_delegator_table: None,
             };
             Mutex::new(state)
         };
@@ -734,6 +824,7 @@ mod test {
                 last_evaluated_epoch: None,
                 pending_evaluation: None,
                 epoch_context: EpochContext::Current(DUMMY_STAKING_EPOCH_DATA.to_owned().into()),
+                next_epoch_delegator_table: None,
             };
             Mutex::new(state)
         };
@@ -757,6 +848,7 @@ mod test {
                 last_evaluated_epoch: Some(2),
                 pending_evaluation: None,
                 epoch_context: EpochContext::Current(DUMMY_STAKING_EPOCH_DATA.to_owned().into()),
+                next_epoch_delegator_table: None,
             };
             Mutex::new(state)
         };
@@ -968,4 +1060,40 @@ mod test {
             "First retained slot should be the first slot of the epoch, 142180"
         );
     }
+
+    #[test]
+    fn test_precomputed_delegator_table() {
+        let mut vrf_evaluator_state =
+            BlockProducerVrfEvaluatorState::new(redux::Timestamp::global_now());
+        let ledger_hash = DUMMY_NEXT_EPOCH_DATA.ledger.hash.clone();
+        let producer = AccountSecretKey::genesis_producer().public_key();
+        let other_producer = AccountSecretKey::rand().public_key();
+
+        vrf_evaluator_state.delegator_table_precompute_init(ledger_hash.clone(), producer.clone());
+        assert!(vrf_evaluator_state.is_delegator_table_precomputing(&ledger_hash, &producer));
+        assert!(!vrf_evaluator_state.is_delegator_table_precomputing(&ledger_hash, &other_producer));
+        assert_eq!(
+            vrf_evaluator_state.delegator_table_precompute_inputs(),
+            Some((&ledger_hash, &producer))
+        );
+        assert!(vrf_evaluator_state
+            .precomputed_delegator_table(&ledger_hash, &producer)
+            .is_none());
+
+        let delegator_table = BTreeMap::from([(AccountIndex(0), (producer.clone(), 100))]);
+        vrf_evaluator_state.delegator_table_precompute_success(delegator_table.into());
+        assert!(!vrf_evaluator_state.is_delegator_table_precomputing(&ledger_hash, &producer));
+        assert!(vrf_evaluator_state
+            .delegator_table_precompute_inputs()
+            .is_none());
+        assert_eq!(
+            vrf_evaluator_state
+                .precomputed_delegator_table(&ledger_hash, &producer)
+                .map(|table| table.len()),
+            Some(1)
+        );
+        assert!(vrf_evaluator_state
+            .precomputed_delegator_table(&ledger_hash, &other_producer)
+            .is_none());
+    }
 }
@@ -28,6 +28,11 @@ pub struct VrfEvaluatorInput {
     pub global_slot: u32,
     pub total_currency: u64,
     pub staking_ledger_hash: LedgerHash,
+    /// Outcome of the evaluation forced by the empty blocks testing mode,
+    /// in which case the threshold isn't checked.
+    #[cfg(feature = "testing")]
+    #[serde(default)]
+    pub forced_win: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Clone, Serialize)]
@@ -88,6 +93,17 @@ impl VrfEvaluatorInput {
             global_slot,
             total_currency,
             staking_ledger_hash,
+            #[cfg(feature = "testing")]
+            forced_win: None,
         }
     }
+
+    #[cfg(feature = "testing")]
+    pub fn with_empty_blocks(
+        mut self,
+        config: Option<&crate::block_producer::BlockProducerEmptyBlocksConfig>,
+    ) -> Self {
+        self.forced_win = config.map(|config| config.wins_slot(self.global_slot));
+        self
+    }
 }
@@ -1,5 +1,6 @@
 use super::vrf_evaluator_effectful::BlockProducerVrfEvaluatorEffectfulAction;
 use crate::block_producer::{BlockProducerWonSlot, BlockProducerWonSlotDiscardReason};
+use mina_p2p_messages::v2::NonZeroCurvePoint;
 use openmina_core::{block::ArcBlockWithHash, ActionEvent};
 use serde::{Deserialize, Serialize};
 
@@ -12,8 +13,15 @@ pub enum BlockProducerEffectfulAction {
     WonSlotDiscard {
         reason: BlockProducerWonSlotDiscardReason,
     },
+    WonSlotClaimInit {
+        producer: NonZeroCurvePoint,
+        global_slot: u32,
+    },
     StagedLedgerDiffCreateInit,
-    StagedLedgerDiffCreateSuccess,
+    StagedLedgerDiffCreateSuccess {
+        truncated_commands: usize,
+        external_diff: Option<Result<(), String>>,
+    },
     BlockUnprovenBuild,
     BlockProveInit,
     BlockProveSuccess,
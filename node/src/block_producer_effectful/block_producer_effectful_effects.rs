@@ -1,5 +1,8 @@
 use crate::{
-    block_producer::BlockProducerCurrentState,
+    block_producer::{
+        BlockProducerCurrentState, BlockProducerExternalDiffStatus,
+        BLOCK_PRODUCER_SUPERCHARGE_COINBASE,
+    },
     ledger::write::{LedgerWriteAction, LedgerWriteRequest},
     BlockProducerAction, Store,
 };
@@ -27,9 +30,16 @@ pub fn block_producer_effects<S: crate::Service>(
                 stats.block_producer().scheduled(meta.time(), &won_slot);
             }
             if !store.dispatch(BlockProducerAction::WonSlotWait) {
+                store.dispatch(BlockProducerAction::WonSlotClaimInit);
                 store.dispatch(BlockProducerAction::WonSlotProduceInit);
             }
         }
+        BlockProducerEffectfulAction::WonSlotClaimInit {
+            producer,
+            global_slot,
+        } => {
+            store.service.slot_claim(producer, global_slot);
+        }
         BlockProducerEffectfulAction::StagedLedgerDiffCreateInit => {
             if let Some(stats) = store.service.stats() {
                 stats
@@ -37,27 +47,29 @@ pub fn block_producer_effects<S: crate::Service>(
                     .staged_ledger_diff_create_start(meta.time());
             }
             let state = store.state.get();
-            let Some((won_slot, pred_block, producer, coinbase_receiver)) = None.or_else(|| {
-                let pred_block = state.block_producer.current_parent_chain()?.last()?;
-                let won_slot = state.block_producer.current_won_slot()?;
-                let config = state.block_producer.config()?;
-                Some((
-                    won_slot,
-                    pred_block,
-                    &config.pub_key,
-                    config.coinbase_receiver(),
-                ))
-            }) else {
+            let Some((won_slot, pred_block, producer, coinbase_receiver, deadline)) =
+                None.or_else(|| {
+                    let pred_block = state.block_producer.current_parent_chain()?.last()?;
+                    let won_slot = state.block_producer.current_won_slot()?;
+                    let config = state.block_producer.config()?;
+                    Some((
+                        won_slot,
+                        pred_block,
+                        &config.pub_key,
+                        config.coinbase_receiver(),
+                        config.assembly_deadline(won_slot),
+                    ))
+                })
+            else {
                 return;
             };
 
             let completed_snarks = state
                 .snark_pool
-                .completed_snarks_iter()
+                .verified_snarks_iter()
                 .map(|snark| (snark.job_id(), snark.clone()))
                 .collect();
-            // TODO(binier)
-            let supercharge_coinbase = true;
+            let supercharge_coinbase = BLOCK_PRODUCER_SUPERCHARGE_COINBASE;
             // We want to know if this is a new epoch to decide which staking ledger to use
             // (staking epoch ledger or next epoch ledger).
             let is_new_epoch = won_slot.epoch()
@@ -70,6 +82,12 @@ pub fn block_producer_effects<S: crate::Service>(
                     .as_u32();
 
             let transactions_by_fee = state.block_producer.pending_transactions();
+            let external_diff = state.block_producer.with(None, |bp| {
+                let external = bp.external_diff.as_ref()?;
+                (external.global_slot == won_slot.global_slot()
+                    && external.status == BlockProducerExternalDiffStatus::Submitted)
+                    .then(|| external.diff.clone())
+            });
 
             store.dispatch(LedgerWriteAction::Init {
                 request: LedgerWriteRequest::StagedLedgerDiffCreate {
@@ -83,6 +101,8 @@ pub fn block_producer_effects<S: crate::Service>(
                     completed_snarks,
                     supercharge_coinbase,
                     transactions_by_fee,
+                    time_budget: deadline.checked_sub(meta.time()).unwrap_or_default(),
+                    external_diff,
                 },
                 on_init: redux::callback!(
                     on_staged_ledger_diff_create_init(_request: LedgerWriteRequest) -> crate::Action {
@@ -91,11 +111,33 @@ pub fn block_producer_effects<S: crate::Service>(
                 ),
             });
         }
-        BlockProducerEffectfulAction::StagedLedgerDiffCreateSuccess => {
+        BlockProducerEffectfulAction::StagedLedgerDiffCreateSuccess {
+            truncated_commands,
+            external_diff,
+        } => {
+            match external_diff {
+                Some(Ok(())) => openmina_core::info!(
+                    meta.time();
+                    summary = "using externally submitted staged ledger diff",
+                ),
+                Some(Err(error)) => openmina_core::warn!(
+                    meta.time();
+                    summary = "externally submitted staged ledger diff rejected",
+                    error = error,
+                ),
+                None => {}
+            }
+            if truncated_commands > 0 {
+                openmina_core::warn!(
+                    meta.time();
+                    summary = "staged ledger diff truncated to fit the slot",
+                    truncated_commands = truncated_commands,
+                );
+            }
             if let Some(stats) = store.service.stats() {
                 stats
                     .block_producer()
-                    .staged_ledger_diff_create_end(meta.time());
+                    .staged_ledger_diff_create_end(meta.time(), truncated_commands);
             }
             store.dispatch(BlockProducerAction::BlockUnprovenBuild);
         }
@@ -206,6 +248,8 @@ pub fn block_producer_effects<S: crate::Service>(
             }) else {
                 return;
             };
+            // Refused by the signing journal if another block was already
+            // signed for the slot, see `BlockProducerEvent::SigningRefused`.
             service.prove(block_hash, input);
             store.dispatch(BlockProducerAction::BlockProvePending);
         }
@@ -223,6 +267,7 @@ pub fn block_producer_effects<S: crate::Service>(
         }
         BlockProducerEffectfulAction::BlockProduced { block } => {
             if let Some(stats) = store.service.stats() {
+                stats.block_produced(meta.time(), &block);
                 stats.block_producer().last_produced_block = Some(block.clone());
             }
         }
@@ -4,7 +4,7 @@ use ledger::proofs::provers::BlockProver;
 use mina_p2p_messages::v2::{
     ConsensusBodyReferenceStableV1, LedgerProofProdStableV2, MinaBasePendingCoinbaseUpdateStableV1,
     MinaBasePendingCoinbaseWitnessStableV2, MinaBaseSparseLedgerBaseStableV2,
-    MinaBaseStagedLedgerHashStableV1, ProverExtendBlockchainInputStableV2,
+    MinaBaseStagedLedgerHashStableV1, NonZeroCurvePoint, ProverExtendBlockchainInputStableV2,
     StagedLedgerDiffDiffStableV2, StateHash,
 };
 use openmina_node_account::AccountSecretKey;
@@ -20,10 +20,26 @@ pub struct StagedLedgerDiffCreateOutput {
     pub pending_coinbase_update: MinaBasePendingCoinbaseUpdateStableV1,
     pub pending_coinbase_witness: MinaBasePendingCoinbaseWitnessStableV2,
     pub stake_proof_sparse_ledger: MinaBaseSparseLedgerBaseStableV2,
+    /// Number of transactions left out of the diff, so that it's created
+    /// within the time budget.
+    pub truncated_commands: usize,
+    /// Result of applying the diff submitted with the block template rpc,
+    /// if there was one for the slot. Own diff is used if it failed.
+    #[serde(default)]
+    pub external_diff: Option<Result<(), String>>,
 }
 
 pub trait BlockProducerService {
     fn provers(&self) -> BlockProver;
+    /// Proves the block, unless the signing journal refuses it, see
+    /// [`BlockProducerEvent::SigningRefused`].
+    ///
+    /// [`BlockProducerEvent::SigningRefused`]: crate::block_producer::BlockProducerEvent::SigningRefused
     fn prove(&mut self, block_hash: StateHash, input: Box<ProverExtendBlockchainInputStableV2>);
     fn with_producer_keypair<T>(&self, f: impl FnOnce(&AccountSecretKey) -> T) -> Option<T>;
+    /// Claims the `global_slot` for the `producer` with the slot claim
+    /// coordinator. Result is returned as [`BlockProducerEvent::SlotClaim`].
+    ///
+    /// [`BlockProducerEvent::SlotClaim`]: crate::block_producer::BlockProducerEvent::SlotClaim
+    fn slot_claim(&mut self, producer: NonZeroCurvePoint, global_slot: u32);
 }
@@ -0,0 +1,84 @@
+use mina_p2p_messages::v2::StateHash;
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::channels::checkpoint::SignedCheckpoint;
+use crate::p2p::PeerId;
+
+pub type CheckpointActionWithMeta = redux::ActionWithMeta<CheckpointAction>;
+pub type CheckpointActionWithMetaRef<'a> = redux::ActionWithMeta<&'a CheckpointAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+pub enum CheckpointAction {
+    /// Best chain of the transition frontier changed, sign the root if
+    /// it advanced enough since the last checkpoint.
+    BestChainUpdate,
+    /// Checkpoint received from the peer, not verified yet.
+    #[action_event(level = debug, fields(display(sender)))]
+    Received {
+        sender: PeerId,
+        checkpoint: Box<SignedCheckpoint>,
+    },
+    #[action_event(level = info, fields(
+        height = checkpoint.height.as_u32(),
+        state_hash = display(&checkpoint.state_hash),
+        signer = display(&checkpoint.signer),
+    ))]
+    Add {
+        checkpoint: Box<SignedCheckpoint>,
+        sender: Option<PeerId>,
+    },
+    #[action_event(level = trace)]
+    P2pSendAll,
+    #[action_event(level = trace)]
+    P2pSend { peer_id: PeerId },
+}
+
+impl redux::EnablingCondition<crate::State> for CheckpointAction {
+    fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
+        match self {
+            CheckpointAction::BestChainUpdate => {
+                state.block_producer.is_enabled()
+                    && state.transition_frontier.root().is_some_and(|root| {
+                        state.checkpoint.next_sign_height(root.height()).is_some()
+                    })
+            }
+            CheckpointAction::Received { sender, checkpoint } => {
+                state.checkpoint.should_accept(checkpoint, Some(sender))
+            }
+            CheckpointAction::Add { checkpoint, sender } => {
+                state.checkpoint.should_accept(checkpoint, sender.as_ref())
+            }
+            CheckpointAction::P2pSendAll => !state.checkpoint.is_empty(),
+            CheckpointAction::P2pSend { peer_id } => {
+                state.p2p.get_ready_peer(peer_id).is_some_and(|p| {
+                    let (next_index, limit) = p.channels.checkpoint.next_send_index_and_limit();
+                    limit > 0 && next_index <= state.checkpoint.last_index()
+                })
+            }
+        }
+    }
+}
+
+// Effectful actions
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum CheckpointEffectfulAction {
+    /// Sign the checkpoint with the producer key.
+    Sign { state_hash: StateHash, height: u32 },
+    /// Verify the signature of the checkpoint received from the peer.
+    Verify {
+        sender: PeerId,
+        checkpoint: Box<SignedCheckpoint>,
+    },
+    /// Persist the collected checkpoints.
+    Persist { checkpoints: Vec<SignedCheckpoint> },
+}
+
+pub type CheckpointEffectfulActionWithMeta = redux::ActionWithMeta<CheckpointEffectfulAction>;
+
+impl redux::EnablingCondition<crate::State> for CheckpointEffectfulAction {
+    fn is_enabled(&self, _state: &crate::State, _time: redux::Timestamp) -> bool {
+        true
+    }
+}
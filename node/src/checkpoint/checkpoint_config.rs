@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+use crate::p2p::channels::checkpoint::SignedCheckpoint;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CheckpointConfig {
+    /// Sign the root of the transition frontier with the producer key,
+    /// each time its height advances by this many blocks. Checkpoints
+    /// aren't signed if not set.
+    pub sign_interval: Option<u32>,
+    /// Signers, whose checkpoints are collected and relayed. Checkpoints
+    /// received from the peers are ignored if empty, so that anyone can't
+    /// push the honest ones out of the pool with throwaway keys.
+    pub signers: Vec<AccountPublicKey>,
+    /// Checkpoints persisted in the previous session, verified before
+    /// they were persisted.
+    #[serde(default)]
+    pub persisted: Vec<SignedCheckpoint>,
+}
+
+impl CheckpointConfig {
+    /// Roughly an hour worth of the blocks.
+    pub const DEFAULT_SIGN_INTERVAL: u32 = 20;
+}
@@ -0,0 +1,68 @@
+use crate::p2p::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
+use crate::{Service, Store};
+
+use super::{
+    checkpoint_sign, checkpoint_verify, CheckpointAction, CheckpointEffectfulAction,
+    CheckpointEffectfulActionWithMeta, CheckpointService,
+};
+
+pub fn checkpoint_effects<S: Service>(
+    store: &mut Store<S>,
+    action: CheckpointEffectfulActionWithMeta,
+) {
+    let (action, meta) = action.split();
+
+    match action {
+        CheckpointEffectfulAction::Sign { state_hash, height } => {
+            let signed = store
+                .service()
+                .with_producer_keypair(|sk| checkpoint_sign(sk, &state_hash, height));
+            match signed {
+                Some(Some(checkpoint)) => {
+                    store.dispatch(CheckpointAction::Add {
+                        checkpoint: Box::new(checkpoint),
+                        sender: None,
+                    });
+                }
+                Some(None) => {
+                    openmina_core::log::error!(
+                        meta.time();
+                        kind = "CheckpointSign",
+                        summary = format!("invalid state hash {state_hash}"),
+                    );
+                }
+                None => {
+                    openmina_core::log::error!(
+                        meta.time();
+                        kind = "CheckpointSign",
+                        summary = "producer key not set",
+                    );
+                }
+            }
+        }
+        CheckpointEffectfulAction::Verify { sender, checkpoint } => {
+            if checkpoint_verify(&checkpoint) {
+                store.dispatch(CheckpointAction::Add {
+                    checkpoint,
+                    sender: Some(sender),
+                });
+            } else {
+                openmina_core::log::warn!(
+                    meta.time();
+                    kind = "CheckpointVerify",
+                    summary = "invalid checkpoint signature",
+                    peer_id = sender.to_string(),
+                    height = checkpoint.height.as_u32(),
+                    signer = checkpoint.signer.to_string(),
+                );
+                store.dispatch(P2pDisconnectionAction::Init {
+                    peer_id: sender,
+                    reason: P2pDisconnectionReason::CheckpointSignatureInvalid,
+                });
+            }
+        }
+        CheckpointEffectfulAction::Persist { checkpoints } => {
+            store.service.checkpoints_persist(checkpoints);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::p2p::channels::checkpoint::P2pChannelsCheckpointAction;
+
+use super::{CheckpointAction, CheckpointActionWithMetaRef, CheckpointEffectfulAction};
+
+impl super::CheckpointState {
+    pub fn reducer(
+        mut state_context: crate::Substate<crate::State>,
+        action: CheckpointActionWithMetaRef<'_>,
+    ) {
+        let Ok(state) = state_context.get_substate_mut() else {
+            return;
+        };
+        let (action, meta) = action.split();
+
+        match action {
+            CheckpointAction::BestChainUpdate => {
+                let Some(root) = state.transition_frontier.root() else {
+                    return;
+                };
+                let state_hash = root.hash().clone();
+                let Some(height) = state.checkpoint.next_sign_height(root.height()) else {
+                    return;
+                };
+                state.checkpoint.last_signed_height = Some(height);
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(CheckpointEffectfulAction::Sign { state_hash, height });
+            }
+            CheckpointAction::Received { sender, checkpoint } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(CheckpointEffectfulAction::Verify {
+                    sender: *sender,
+                    checkpoint: checkpoint.clone(),
+                });
+            }
+            CheckpointAction::Add { checkpoint, sender } => {
+                if !state.checkpoint.insert(checkpoint, *sender, meta.time()) {
+                    return;
+                }
+                let checkpoints = state
+                    .checkpoint
+                    .checkpoints()
+                    .map(|entry| entry.checkpoint())
+                    .collect();
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(CheckpointAction::P2pSendAll);
+                #[cfg(feature = "p2p-libp2p")]
+                dispatcher.push(crate::p2p::P2pNetworkPubsubAction::BroadcastCheckpoint {
+                    checkpoint: checkpoint.clone(),
+                });
+                dispatcher.push(CheckpointEffectfulAction::Persist { checkpoints });
+            }
+            CheckpointAction::P2pSendAll => {
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                for peer_id in global_state.p2p.ready_peers() {
+                    dispatcher.push(CheckpointAction::P2pSend { peer_id });
+                }
+            }
+            CheckpointAction::P2pSend { peer_id } => {
+                let peer_id = *peer_id;
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let Some(peer) = global_state.p2p.get_ready_peer(&peer_id) else {
+                    return;
+                };
+                let index_and_limit = peer.channels.checkpoint.next_send_index_and_limit();
+                let (checkpoints, first_index, last_index) = global_state
+                    .checkpoint
+                    .next_checkpoints_to_send(index_and_limit);
+                dispatcher.push(P2pChannelsCheckpointAction::ResponseSend {
+                    peer_id,
+                    checkpoints,
+                    first_index,
+                    last_index,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use crate::p2p::channels::checkpoint::SignedCheckpoint;
+
+pub trait CheckpointService: redux::Service {
+    /// Persists the collected checkpoints, so that they are restored after
+    /// the restart. Noop if the persistence isn't enabled.
+    fn checkpoints_persist(&mut self, checkpoints: Vec<SignedCheckpoint>);
+}
@@ -0,0 +1,111 @@
+use mina_hasher::{Fp, ROInput};
+use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash};
+use mina_signer::{CompressedPubKey, Keypair, NetworkId, PubKey, Signature, Signer};
+
+use crate::account::{signer_network_id, AccountPublicKey, AccountSecretKey};
+use crate::p2p::channels::checkpoint::SignedCheckpoint;
+
+/// Message signed by the checkpoint signer.
+#[derive(Debug, Clone)]
+struct CheckpointMessage {
+    state_hash: Fp,
+    height: u32,
+}
+
+impl CheckpointMessage {
+    fn new(state_hash: &StateHash, height: u32) -> Option<Self> {
+        Some(Self {
+            state_hash: state_hash.to_field().ok()?,
+            height,
+        })
+    }
+}
+
+impl mina_hasher::Hashable for CheckpointMessage {
+    type D = NetworkId;
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new()
+            .append_field(self.state_hash)
+            .append_u32(self.height)
+    }
+
+    fn domain_string(network_id: Self::D) -> Option<String> {
+        // Separate from the transaction signatures, so that a checkpoint
+        // can't be replayed as a signed command, or vice versa.
+        match network_id {
+            NetworkId::MAINNET => "MinaCheckpointMain",
+            NetworkId::TESTNET => "MinaCheckpointTest",
+        }
+        .to_string()
+        .into()
+    }
+}
+
+/// Signs the checkpoint of the block `state_hash` at the `height`.
+///
+/// Returns `None` if the state hash isn't a valid field element.
+pub fn checkpoint_sign(
+    secret_key: &AccountSecretKey,
+    state_hash: &StateHash,
+    height: u32,
+) -> Option<SignedCheckpoint> {
+    let msg = CheckpointMessage::new(state_hash, height)?;
+    let mut signer = mina_signer::create_kimchi::<CheckpointMessage>(signer_network_id());
+    let signature = signer.sign(&Keypair::from(secret_key.clone()), &msg);
+
+    Some(SignedCheckpoint {
+        state_hash: state_hash.clone(),
+        height: height.into(),
+        signer: secret_key.public_key().into(),
+        signature: (&signature).into(),
+    })
+}
+
+/// Whether the checkpoint is signed by its `signer` for the current network.
+pub fn checkpoint_verify(checkpoint: &SignedCheckpoint) -> bool {
+    let verify = || {
+        let msg = CheckpointMessage::new(&checkpoint.state_hash, checkpoint.height.as_u32())?;
+        let signature = Signature::try_from(&checkpoint.signature).ok()?;
+        let pk = signer_pub_key(&checkpoint.signer)?;
+        let mut signer = mina_signer::create_kimchi::<CheckpointMessage>(signer_network_id());
+        Some(signer.verify(&signature, &pk, &msg))
+    };
+    verify().unwrap_or(false)
+}
+
+fn signer_pub_key(signer: &NonZeroCurvePoint) -> Option<PubKey> {
+    let pk = CompressedPubKey::try_from(AccountPublicKey::from(signer.clone())).ok()?;
+    PubKey::from_address(&pk.into_address()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_signature() {
+        let secret_key = AccountSecretKey::deterministic(0);
+        let state_hash = StateHash::from_fp(Fp::from(1u64));
+        let checkpoint = checkpoint_sign(&secret_key, &state_hash, 10).unwrap();
+        assert!(checkpoint_verify(&checkpoint));
+
+        let other_height = SignedCheckpoint {
+            height: 11.into(),
+            ..checkpoint.clone()
+        };
+        assert!(!checkpoint_verify(&other_height));
+
+        let other_hash = SignedCheckpoint {
+            state_hash: StateHash::from_fp(Fp::from(2u64)),
+            ..checkpoint.clone()
+        };
+        assert!(!checkpoint_verify(&other_hash));
+
+        let other_signer = SignedCheckpoint {
+            signer: AccountSecretKey::deterministic(1).public_key().into(),
+            ..checkpoint
+        };
+        assert!(!checkpoint_verify(&other_signer));
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::BTreeSet;
+
+use mina_p2p_messages::v2::{MinaBaseSignatureStableV1, NonZeroCurvePoint, StateHash};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::core::distributed_pool::DistributedPool;
+use crate::p2p::channels::checkpoint::SignedCheckpoint;
+use crate::p2p::PeerId;
+
+use super::CheckpointConfig;
+
+/// Oldest checkpoints are dropped after this many.
+pub const CHECKPOINTS_MAX: usize = 1024;
+/// Checkpoints of the signer with the lowest height are dropped after this
+/// many, so that a single signer can't push out the others.
+pub const CHECKPOINTS_PER_SIGNER_MAX: usize = 16;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CheckpointState {
+    pub config: CheckpointConfig,
+    signers: BTreeSet<NonZeroCurvePoint>,
+    /// Height of the latest checkpoint signed by us.
+    pub last_signed_height: Option<u32>,
+    /// Verified checkpoints, in the order they are sent to the peers.
+    pool: DistributedPool<CheckpointEntry, CheckpointId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CheckpointId {
+    pub signer: NonZeroCurvePoint,
+    pub height: u32,
+    pub state_hash: StateHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckpointEntry {
+    pub id: CheckpointId,
+    pub signature: MinaBaseSignatureStableV1,
+    pub received_at: Timestamp,
+    /// Peer we received the checkpoint from, `None` if signed by us.
+    pub sender: Option<PeerId>,
+}
+
+impl CheckpointId {
+    pub fn new(checkpoint: &SignedCheckpoint) -> Self {
+        Self {
+            signer: checkpoint.signer.clone(),
+            height: checkpoint.height.as_u32(),
+            state_hash: checkpoint.state_hash.clone(),
+        }
+    }
+}
+
+impl CheckpointEntry {
+    pub fn checkpoint(&self) -> SignedCheckpoint {
+        SignedCheckpoint {
+            state_hash: self.id.state_hash.clone(),
+            height: self.id.height.into(),
+            signer: self.id.signer.clone(),
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+impl AsRef<CheckpointId> for CheckpointEntry {
+    fn as_ref(&self) -> &CheckpointId {
+        &self.id
+    }
+}
+
+impl CheckpointState {
+    pub fn new(mut config: CheckpointConfig) -> Self {
+        let persisted = std::mem::take(&mut config.persisted);
+        let mut state = Self {
+            signers: config
+                .signers
+                .iter()
+                .map(|signer| signer.clone().into())
+                .collect(),
+            config,
+            last_signed_height: None,
+            pool: Default::default(),
+        };
+        for checkpoint in &persisted {
+            state.insert(checkpoint, None, Timestamp::ZERO);
+        }
+        state
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pool.len()
+    }
+
+    pub fn last_index(&self) -> u64 {
+        self.pool.last_index()
+    }
+
+    /// Whether the checkpoint is new and its signer is accepted. Checkpoints
+    /// received from the peers are only accepted from the configured signers,
+    /// the ones signed by us (`sender` is `None`) always are.
+    pub fn should_accept(&self, checkpoint: &SignedCheckpoint, sender: Option<&PeerId>) -> bool {
+        (sender.is_none() || self.signers.contains(&checkpoint.signer))
+            && !self.pool.contains(&CheckpointId::new(checkpoint))
+    }
+
+    /// Height of the root, which should be signed next, if any.
+    pub fn next_sign_height(&self, root_height: u32) -> Option<u32> {
+        let interval = self.config.sign_interval?.max(1);
+        match self.last_signed_height {
+            Some(last) if root_height < last.saturating_add(interval) => None,
+            _ => Some(root_height),
+        }
+    }
+
+    /// Adds the verified checkpoint, dropping the oldest ones over the
+    /// limits. Returns `false` if the checkpoint itself doesn't fit.
+    pub fn insert(
+        &mut self,
+        checkpoint: &SignedCheckpoint,
+        sender: Option<PeerId>,
+        time: Timestamp,
+    ) -> bool {
+        let id = CheckpointId::new(checkpoint);
+        if self.pool.contains(&id) {
+            return false;
+        }
+
+        let of_signer = self
+            .pool
+            .states()
+            .filter(|entry| entry.id.signer == id.signer)
+            .map(|entry| &entry.id);
+        if of_signer.clone().count() >= CHECKPOINTS_PER_SIGNER_MAX {
+            let Some(lowest) = of_signer.min_by_key(|id| id.height).cloned() else {
+                return false;
+            };
+            if lowest.height >= id.height {
+                return false;
+            }
+            self.pool.remove(&lowest);
+        }
+        if self.pool.len() >= CHECKPOINTS_MAX {
+            let oldest = self
+                .pool
+                .range(..)
+                .next()
+                .map(|(_, entry)| entry.id.clone());
+            if let Some(oldest) = oldest {
+                self.pool.remove(&oldest);
+            }
+        }
+
+        self.pool.insert(CheckpointEntry {
+            id,
+            signature: checkpoint.signature.clone(),
+            received_at: time,
+            sender,
+        });
+        true
+    }
+
+    pub fn checkpoints(&self) -> impl Iterator<Item = &CheckpointEntry> {
+        self.pool.states()
+    }
+
+    pub fn next_checkpoints_to_send(
+        &self,
+        index_and_limit: (u64, u8),
+    ) -> (Vec<SignedCheckpoint>, u64, u64) {
+        self.pool
+            .next_messages_to_send(index_and_limit, |entry| Some(entry.checkpoint()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_hasher::Fp;
+
+    use crate::account::AccountSecretKey;
+
+    use super::*;
+
+    fn checkpoint(signer: u64, height: u32) -> SignedCheckpoint {
+        SignedCheckpoint {
+            state_hash: StateHash::from_fp(Fp::from(height)),
+            height: height.into(),
+            signer: AccountSecretKey::deterministic(signer).public_key().into(),
+            signature: MinaBaseSignatureStableV1(Default::default(), Default::default()),
+        }
+    }
+
+    #[test]
+    fn test_checkpoints_limits() {
+        let mut state = CheckpointState::new(Default::default());
+        let time = Timestamp::ZERO;
+
+        assert!(state.insert(&checkpoint(0, 1), None, time));
+        assert!(!state.should_accept(&checkpoint(0, 1), None));
+        assert!(!state.insert(&checkpoint(0, 1), None, time));
+
+        for height in 2..=CHECKPOINTS_PER_SIGNER_MAX as u32 {
+            assert!(state.insert(&checkpoint(0, height), None, time));
+        }
+        assert_eq!(state.len(), CHECKPOINTS_PER_SIGNER_MAX);
+        // Lower than all the checkpoints of the signer.
+        assert!(!state.insert(&checkpoint(0, 0), None, time));
+        // Replaces the lowest one.
+        assert!(state.insert(&checkpoint(0, 100), None, time));
+        assert_eq!(state.len(), CHECKPOINTS_PER_SIGNER_MAX);
+        assert!(state.should_accept(&checkpoint(0, 1), None));
+
+        let (checkpoints, first, last) = state.next_checkpoints_to_send((0, 4));
+        assert_eq!(checkpoints.len(), 4);
+        assert_eq!(checkpoints[0], checkpoint(0, 2));
+        assert_eq!((first, last), (1, 4));
+    }
+
+    #[test]
+    fn test_checkpoints_signers() {
+        let sender = crate::p2p::identity::SecretKey::deterministic(0)
+            .public_key()
+            .peer_id();
+        let state = CheckpointState::new(Default::default());
+        // No signers configured, only our own checkpoints are accepted.
+        assert!(!state.should_accept(&checkpoint(0, 1), Some(&sender)));
+        assert!(state.should_accept(&checkpoint(0, 1), None));
+
+        let state = CheckpointState::new(CheckpointConfig {
+            signers: vec![AccountSecretKey::deterministic(1).public_key()],
+            ..Default::default()
+        });
+        assert!(!state.should_accept(&checkpoint(0, 1), Some(&sender)));
+        assert!(state.should_accept(&checkpoint(1, 1), Some(&sender)));
+    }
+
+    #[test]
+    fn test_checkpoints_persisted() {
+        let state = CheckpointState::new(CheckpointConfig {
+            persisted: vec![checkpoint(0, 1), checkpoint(1, 2)],
+            ..Default::default()
+        });
+        assert_eq!(state.len(), 2);
+        assert!(!state.should_accept(&checkpoint(1, 2), None));
+        assert_eq!(
+            state
+                .checkpoints()
+                .map(|c| c.checkpoint())
+                .collect::<Vec<_>>(),
+            vec![checkpoint(0, 1), checkpoint(1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_next_sign_height() {
+        let mut state = CheckpointState::new(CheckpointConfig {
+            sign_interval: Some(10),
+            ..Default::default()
+        });
+        assert_eq!(state.next_sign_height(5), Some(5));
+        state.last_signed_height = Some(5);
+        assert_eq!(state.next_sign_height(14), None);
+        assert_eq!(state.next_sign_height(15), Some(15));
+
+        let state = CheckpointState::new(Default::default());
+        assert_eq!(state.next_sign_height(15), None);
+    }
+}
@@ -0,0 +1,38 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::p2p::channels::checkpoint::SignedCheckpoint;
+
+pub const CHECKPOINTS_FILE: &str = "checkpoints.json";
+
+/// Collected checkpoints, persisted in the work dir so that they survive
+/// the restart, see [`super::CheckpointService`].
+pub struct CheckpointStore {
+    path: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn path<P: AsRef<Path>>(work_dir: P) -> PathBuf {
+        work_dir.as_ref().join(CHECKPOINTS_FILE)
+    }
+
+    /// Opens the store in the `work_dir` and returns the checkpoints stored
+    /// in the previous session. Missing file means there are none.
+    pub fn open<P: AsRef<Path>>(work_dir: P) -> io::Result<(Self, Vec<SignedCheckpoint>)> {
+        let path = Self::path(work_dir);
+        let checkpoints = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        Ok((Self { path }, checkpoints))
+    }
+
+    pub fn save(&self, checkpoints: &[SignedCheckpoint]) -> io::Result<()> {
+        openmina_core::fs::write_atomic(&self.path, |writer| {
+            serde_json::to_writer(writer, checkpoints)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        })
+    }
+}
@@ -0,0 +1,33 @@
+//! Checkpoints are statements, signed with the producer key, that the block
+//! (state hash and height) is part of the signer's canonical chain.
+//!
+//! Configured nodes periodically sign the root of their transition frontier
+//! and all nodes collect the verified checkpoints, relay them to the peers
+//! over [`crate::p2p::channels::checkpoint`] and expose them with
+//! `GET /checkpoints`, so that light clients can crosscheck their view of
+//! the chain against the signers they know. Libp2p peers get them on the
+//! openmina checkpoint pubsub topic instead. Collected checkpoints are
+//! persisted in the work dir, see [`CheckpointStore`].
+
+mod checkpoint_config;
+pub use checkpoint_config::*;
+
+mod checkpoint_signature;
+pub use checkpoint_signature::*;
+
+mod checkpoint_state;
+pub use checkpoint_state::*;
+
+mod checkpoint_actions;
+pub use checkpoint_actions::*;
+
+mod checkpoint_reducer;
+
+mod checkpoint_effects;
+pub use checkpoint_effects::*;
+
+mod checkpoint_service;
+pub use checkpoint_service::*;
+
+mod checkpoint_store;
+pub use checkpoint_store::*;
@@ -7,13 +7,17 @@ use serde::{Deserialize, Serialize};
 
 use crate::account::AccountPublicKey;
 pub use crate::block_producer::BlockProducerConfig;
+pub use crate::checkpoint::CheckpointConfig;
 pub use crate::ledger::LedgerConfig;
 pub use crate::p2p::P2pConfig;
+use crate::rpc::RpcResponseLimits;
 pub use crate::snark::SnarkConfig;
 pub use crate::snark_pool::SnarkPoolConfig;
+use crate::transaction_pool::sponsor::FeeSponsorConfig;
 use crate::transition_frontier::archive::archive_config::ArchiveConfig;
 use crate::transition_frontier::genesis::GenesisConfig;
 pub use crate::transition_frontier::TransitionFrontierConfig;
+pub use crate::watchtower::WatchtowerConfig;
 pub use mina_p2p_messages::v2::MinaBaseProtocolConstantsCheckedValueStableV1 as ProtocolConstants;
 
 // TODO(binier): maybe make sure config is immutable.
@@ -22,12 +26,22 @@ pub use mina_p2p_messages::v2::MinaBaseProtocolConstantsCheckedValueStableV1 as
 pub struct Config {
     pub ledger: LedgerConfig,
     pub snark: SnarkConfig,
+    #[serde(default)]
+    pub snark_pool: SnarkPoolConfig,
     pub p2p: P2pConfig,
     pub transition_frontier: TransitionFrontierConfig,
     pub archive: Option<ArchiveConfig>,
     pub block_producer: Option<BlockProducerConfig>,
     pub global: GlobalConfig,
     pub tx_pool: ledger::transaction_pool::Config,
+    #[serde(default)]
+    pub watchtower: Option<WatchtowerConfig>,
+    #[serde(default)]
+    pub checkpoint: CheckpointConfig,
+    /// Account paying the fees of the zkApp commands sponsored by the
+    /// node, if enabled.
+    #[serde(default)]
+    pub fee_sponsor: Option<FeeSponsorConfig>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -37,6 +51,17 @@ pub struct GlobalConfig {
     pub consensus_constants: ConsensusConstants,
     pub client_port: Option<u16>,
     pub testing_run: bool,
+    #[serde(default)]
+    pub profile: NodeProfile,
+    /// Account the payout batches are paid from.
+    #[serde(default)]
+    pub payout_pub_key: Option<AccountPublicKey>,
+    #[serde(default)]
+    pub rpc_response_limits: RpcResponseLimits,
+    /// HTTP RPC addresses of the other nodes of the same operator, compared
+    /// in the block propagation report.
+    #[serde(default)]
+    pub sibling_nodes: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -132,6 +157,97 @@ impl FromStr for SnarkerStrategy {
     }
 }
 
+/// Role of the node, which determines which subsystems get wired up.
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeProfile {
+    /// All subsystems are available, enabled based on the rest of the config.
+    #[default]
+    Full,
+    Producer,
+    Snarker,
+    /// Archive node, writes blocks to the archive storage.
+    Archive,
+    /// Only participates in the p2p network, relaying blocks, transactions
+    /// and snarks.
+    Relay,
+    /// Relay which doesn't maintain ledgers. Only consensus data and
+    /// proofs of the blocks are verified, blocks aren't applied.
+    LightRelay,
+    /// Monitoring node. Verifies the blocks like the light relay and
+    /// raises the watchtower alerts, but doesn't serve p2p rpcs to the
+    /// peers. See [`crate::watchtower`].
+    Watchtower,
+}
+
+impl NodeProfile {
+    pub fn allows_block_producer(self) -> bool {
+        matches!(self, Self::Full | Self::Producer)
+    }
+
+    pub fn allows_snarker(self) -> bool {
+        matches!(self, Self::Full | Self::Snarker)
+    }
+
+    pub fn allows_archive(self) -> bool {
+        matches!(self, Self::Full | Self::Archive)
+    }
+
+    /// Whether blocks are only verified, without applying them.
+    pub fn is_light(self) -> bool {
+        matches!(self, Self::LightRelay | Self::Watchtower)
+    }
+
+    /// Whether the rpc requests from the peers are answered.
+    pub fn serves_p2p_rpcs(self) -> bool {
+        !matches!(self, Self::Watchtower)
+    }
+
+    /// Whether the node picks snark jobs from the snark pool to work on.
+    pub fn allows_snark_work_selection(self) -> bool {
+        self.allows_snarker()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Producer => "producer",
+            Self::Snarker => "snarker",
+            Self::Archive => "archive",
+            Self::Relay => "relay",
+            Self::LightRelay => "light-relay",
+            Self::Watchtower => "watchtower",
+        }
+    }
+}
+
+impl std::fmt::Display for NodeProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid profile: {0}! expected one of: full/producer/snarker/archive/relay/light-relay/watchtower")]
+pub struct NodeProfileParseError(String);
+
+impl FromStr for NodeProfile {
+    type Err = NodeProfileParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "full" => Self::Full,
+            "producer" | "block-producer" => Self::Producer,
+            "snarker" => Self::Snarker,
+            "archive" => Self::Archive,
+            "relay" => Self::Relay,
+            "light-relay" | "light_relay" => Self::LightRelay,
+            "watchtower" => Self::Watchtower,
+            other => return Err(NodeProfileParseError(other.to_owned())),
+        })
+    }
+}
+
 // Load static devnet genesis ledger for testing
 lazy_static::lazy_static! {
     pub static ref DEVNET_CONFIG: Arc<GenesisConfig> = {
@@ -146,7 +262,7 @@ lazy_static::lazy_static! {
 mod tests {
     use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
-    use super::DEVNET_CONFIG;
+    use super::{NodeProfile, DEVNET_CONFIG};
 
     #[test]
     fn devnet_config() {
@@ -165,4 +281,29 @@ mod tests {
                 .into()
         );
     }
+
+    #[test]
+    fn node_profile_from_str() {
+        for profile in [
+            NodeProfile::Full,
+            NodeProfile::Producer,
+            NodeProfile::Snarker,
+            NodeProfile::Archive,
+            NodeProfile::Relay,
+            NodeProfile::LightRelay,
+            NodeProfile::Watchtower,
+        ] {
+            assert_eq!(profile.as_str().parse::<NodeProfile>().unwrap(), profile);
+        }
+        assert!("validator".parse::<NodeProfile>().is_err());
+        assert!(!NodeProfile::Relay.allows_block_producer());
+        assert!(!NodeProfile::Relay.allows_snark_work_selection());
+        assert!(!NodeProfile::Relay.is_light());
+        assert!(NodeProfile::LightRelay.is_light());
+        assert!(!NodeProfile::LightRelay.allows_archive());
+        assert!(NodeProfile::Watchtower.is_light());
+        assert!(!NodeProfile::Watchtower.allows_block_producer());
+        assert!(!NodeProfile::Watchtower.serves_p2p_rpcs());
+        assert!(NodeProfile::LightRelay.serves_p2p_rpcs());
+    }
 }
@@ -1,4 +1,6 @@
 use ledger::scan_state::currency::Slot;
+
+use crate::config::NodeProfile;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +9,9 @@ pub struct Daemon {
     peer_list_url: Option<String>,
     slot_tx_end: Option<u32>,
     slot_chain_end: Option<u32>,
+    /// Openmina specific, not present in the OCaml node config.
+    #[serde(default)]
+    profile: Option<NodeProfile>,
 }
 
 impl Daemon {
@@ -15,6 +20,7 @@ impl Daemon {
         peer_list_url: None,
         slot_tx_end: None,
         slot_chain_end: None,
+        profile: None,
     };
 
     pub fn tx_pool_max_size(&self) -> usize {
@@ -33,4 +39,8 @@ impl Daemon {
     pub fn slot_chain_end(&self) -> Option<Slot> {
         self.slot_chain_end.map(Slot::from_u32)
     }
+
+    pub fn profile(&self) -> Option<NodeProfile> {
+        self.profile
+    }
 }
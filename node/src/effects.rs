@@ -1,8 +1,10 @@
 use openmina_core::log::system_time;
+use openmina_core::profiler::{profiler_action_scope, ProfilerPhase};
 use rand::prelude::*;
 
 use crate::block_producer::BlockProducerAction;
 use crate::block_producer_effectful::block_producer_effects;
+use crate::checkpoint::{checkpoint_effects, CheckpointAction};
 use crate::event_source::event_source_effects;
 use crate::external_snark_worker_effectful::external_snark_worker_effectful_effects;
 use crate::ledger::read::LedgerReadAction;
@@ -13,7 +15,11 @@ use crate::rpc_effectful::rpc_effects;
 use crate::snark::snark_effects;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::{snark_pool_effects, SnarkPoolAction};
+use crate::state_digest::STATE_DIGEST_LOG_INTERVAL;
+use crate::stats::history::PeerCountSample;
 use crate::transaction_pool::candidate::TransactionPoolCandidateAction;
+use crate::transaction_pool::payouts::TransactionPoolPayoutsAction;
+use crate::transition_frontier::candidate::TransitionFrontierCandidateAction;
 use crate::transition_frontier::genesis::TransitionFrontierGenesisAction;
 use crate::transition_frontier::transition_frontier_effects;
 use crate::{
@@ -27,12 +33,16 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
     store.service.recorder().action(&action);
 
     let (action, meta) = action.split();
+    let _profiler_scope = profiler_action_scope(ProfilerPhase::Effects, action.kind() as u16);
 
     if let Some(stats) = store.service.stats() {
         stats.new_action(action.kind(), meta.clone());
     }
 
     logger_effects(store, meta.clone().with_action(&action));
+    if store.state().applied_actions_count() % STATE_DIGEST_LOG_INTERVAL == 0 {
+        state_digest_log(store, meta.time());
+    }
     match action {
         // Following action gets dispatched very often, so ideally this
         // effect execution should be as light as possible.
@@ -43,18 +53,33 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
 
             store.dispatch(TransitionFrontierGenesisAction::ProveInit);
 
+            // While overloaded, fetching and re-broadcasting of pool
+            // items is deferred until the event queue drains.
+            let is_overloaded = store.state().event_source.is_overloaded();
+
             if store.state().p2p.ready().is_some() {
                 p2p_request_best_tip_if_needed(store);
-                p2p_request_transactions_if_needed(store);
-                p2p_request_snarks_if_needed(store);
+                if !is_overloaded {
+                    p2p_request_transactions_if_needed(store);
+                    p2p_request_snarks_if_needed(store);
+                }
             }
 
-            store.dispatch(TransactionPoolAction::P2pSendAll);
+            if !is_overloaded {
+                store.dispatch(TransactionPoolAction::P2pSendAll);
+            }
             store.dispatch(TransactionPoolCandidateAction::FetchAll);
             store.dispatch(TransactionPoolCandidateAction::VerifyNext);
 
+            store.dispatch(TransactionPoolPayoutsAction::CheckTimeouts);
+            store.dispatch(TransactionPoolPayoutsAction::NonceFetchInit);
+            store.dispatch(TransactionPoolPayoutsAction::InjectNext);
+
             store.dispatch(SnarkPoolAction::CheckTimeouts);
-            store.dispatch(SnarkPoolAction::P2pSendAll);
+            if !is_overloaded {
+                store.dispatch(SnarkPoolAction::P2pSendAll);
+                store.dispatch(CheckpointAction::P2pSendAll);
+            }
 
             store.dispatch(SnarkPoolCandidateAction::WorkFetchAll);
             store.dispatch(SnarkPoolCandidateAction::WorkVerifyNext);
@@ -62,9 +87,14 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
             store.dispatch(ExternalSnarkWorkerAction::StartTimeout { now: meta.time() });
             store.dispatch(ExternalSnarkWorkerAction::WorkTimeout { now: meta.time() });
 
+            store.dispatch(BlockProducerAction::WonSlotClaimInit);
             store.dispatch(BlockProducerAction::WonSlotProduceInit);
             store.dispatch(BlockProducerAction::BlockInject);
             store.dispatch(LedgerReadAction::FindTodos);
+            store.dispatch(TransitionFrontierCandidateAction::PruneStale);
+
+            stats_peers_sample(store, meta.time());
+            p2p_preferred_peers_persist(store, meta.time());
         }
         Action::EventSource(action) => {
             event_source_effects(store, meta.with_action(action));
@@ -96,6 +126,9 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
         Action::RpcEffectful(action) => {
             rpc_effects(store, meta.with_action(action));
         }
+        Action::CheckpointEffectful(action) => {
+            checkpoint_effects(store, meta.with_action(action));
+        }
         Action::BlockProducer(_)
         | Action::SnarkPool(_)
         | Action::ExternalSnarkWorker(_)
@@ -103,6 +136,8 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
         | Action::Ledger(_)
         | Action::Rpc(_)
         | Action::WatchedAccounts(_)
+        | Action::Watchtower(_)
+        | Action::Checkpoint(_)
         | Action::P2pCallbacks(_)
         | Action::P2p(_) => {
             // Handled by reducer
@@ -110,6 +145,25 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
     }
 }
 
+/// Logs the digest of the state, so that the logs of the nodes which
+/// should have applied the same actions can be compared.
+fn state_digest_log<S: Service>(store: &Store<S>, time: redux::Timestamp) {
+    let state = store.state();
+    match state.digest() {
+        Ok(digest) => openmina_core::debug!(time;
+            kind = "StateDigest",
+            summary = format!("state digest: {digest}"),
+            applied_actions_count = state.applied_actions_count(),
+            digest = display(digest),
+        ),
+        Err(error) => openmina_core::warn!(time;
+            kind = "StateDigest",
+            summary = "failed to compute the state digest",
+            error = display(error),
+        ),
+    }
+}
+
 fn p2p_request_best_tip_if_needed<S: Service>(store: &mut Store<S>) {
     // TODO(binier): refactor
     let state = store.state();
@@ -150,7 +204,9 @@ fn request_best_tip<S: Service>(store: &mut Store<S>, consensus_best_tip_hash: O
         ready_peers
     };
 
-    if let Some((peer_id, id)) = peers.choose(&mut store.state().pseudo_rng()) {
+    if let Some((peer_id, id)) =
+        peers.choose(&mut store.state().pseudo_rng("best_tip_with_proof_peer"))
+    {
         store.dispatch(P2pChannelsRpcAction::RequestSend {
             peer_id: *peer_id,
             id: *id,
@@ -173,7 +229,11 @@ fn p2p_request_transactions_if_needed<S: Service>(store: &mut Store<S>) {
     );
     let transaction_reqs = p2p
         .ready_peers_iter()
-        .filter(|(_, p)| p.channels.transaction.can_send_request())
+        .filter(|(_, p)| {
+            p.channels
+                .transaction
+                .can_send_request(p.channels.lossy_gossip, state.time())
+        })
         .map(|(peer_id, _)| {
             let pending_txs = state.snark_pool.candidates.peer_work_count(peer_id);
             (peer_id, MAX_PEER_PENDING_TXS.saturating_sub(pending_txs))
@@ -196,7 +256,11 @@ fn p2p_request_snarks_if_needed<S: Service>(store: &mut Store<S>) {
     let p2p = p2p_ready!(state.p2p, "p2p_request_snarks_if_needed", system_time());
     let snark_reqs = p2p
         .ready_peers_iter()
-        .filter(|(_, p)| p.channels.snark.can_send_request())
+        .filter(|(_, p)| {
+            p.channels
+                .snark
+                .can_send_request(p.channels.lossy_gossip, state.time())
+        })
         .map(|(peer_id, _)| {
             let pending_snarks = state.snark_pool.candidates.peer_work_count(peer_id);
             (
@@ -212,3 +276,28 @@ fn p2p_request_snarks_if_needed<S: Service>(store: &mut Store<S>) {
         store.dispatch(P2pChannelsSnarkAction::RequestSend { peer_id, limit });
     }
 }
+
+fn p2p_preferred_peers_persist<S: Service>(store: &mut Store<S>, time: redux::Timestamp) {
+    let Some(p2p) = store.state.get().p2p.ready() else {
+        return;
+    };
+    let peers = p2p.preferred_peers_snapshot(time);
+    store.service.p2p_preferred_peers_persist(time, peers);
+}
+
+fn stats_peers_sample<S: Service>(store: &mut Store<S>, time: redux::Timestamp) {
+    let Some(p2p) = store.state.get().p2p.ready() else {
+        return;
+    };
+    let ready = p2p.ready_peers_iter().count();
+    let sample = PeerCountSample {
+        ready,
+        connecting: p2p
+            .connected_or_connecting_peers_count()
+            .saturating_sub(ready),
+        known: p2p.peers.len(),
+    };
+    if let Some(stats) = store.service.stats() {
+        stats.peers_sample(time, sample);
+    }
+}
@@ -7,6 +7,7 @@ pub use crate::p2p::{P2pConnectionEvent, P2pEvent};
 pub use crate::rpc::{RpcId, RpcRequest};
 pub use crate::snark::SnarkEvent;
 
+use crate::transition_frontier::archive::ArchiveEvent;
 use crate::transition_frontier::genesis::GenesisConfigLoaded;
 
 #[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
@@ -17,6 +18,7 @@ pub enum Event {
     Rpc(RpcId, Box<RpcRequest>),
     ExternalSnarkWorker(ExternalSnarkWorkerEvent),
     BlockProducerEvent(BlockProducerEvent),
+    Archive(ArchiveEvent),
 
     GenesisLoad(Result<GenesisConfigLoaded, String>),
 }
@@ -33,10 +35,23 @@ impl std::fmt::Display for Event {
                     RpcRequest::StateGet(filter) => write!(f, "StateGet, {filter:?}"),
                     RpcRequest::StatusGet => write!(f, "StatusGet"),
                     RpcRequest::HeartbeatGet => write!(f, "HeartbeatGet"),
+                    RpcRequest::StateDigestGet => write!(f, "StateDigestGet"),
                     RpcRequest::ActionStatsGet(query) => write!(f, "ActionStatsGet, {query:?}"),
                     RpcRequest::SyncStatsGet(query) => write!(f, "SyncStatsGet, {query:?}"),
                     RpcRequest::BlockProducerStatsGet => write!(f, "BlockProducerStatsGet"),
+                    RpcRequest::ZkappFailureStatsGet(epoch) => {
+                        write!(f, "ZkappFailureStatsGet, {epoch:?}")
+                    }
+                    RpcRequest::SnarkVerifyStatsGet => write!(f, "SnarkVerifyStatsGet"),
+                    RpcRequest::BlockProducerEpochReportGet(epoch) => {
+                        write!(f, "BlockProducerEpochReportGet, {epoch:?}")
+                    }
+                    RpcRequest::StatsHistoryGet(query) => write!(f, "StatsHistoryGet, {query:?}"),
+                    RpcRequest::BlockPropagationGet(from_height) => {
+                        write!(f, "BlockPropagationGet, {from_height:?}")
+                    }
                     RpcRequest::PeersGet => write!(f, "PeersGet"),
+                    RpcRequest::P2pDiagnosticsGet(..) => write!(f, "P2pDiagnosticsGet"),
                     RpcRequest::MessageProgressGet => write!(f, "MessageProgressGet"),
                     RpcRequest::P2pConnectionOutgoing(opts) => {
                         write!(f, "P2pConnectionOutgoing, {opts}")
@@ -44,7 +59,7 @@ impl std::fmt::Display for Event {
                     RpcRequest::P2pConnectionIncoming(opts) => {
                         write!(f, "P2pConnectionIncoming, {}", opts.peer_id)
                     }
-                    RpcRequest::ScanStateSummaryGet(query) => {
+                    RpcRequest::ScanStateSummaryGet(query, _) => {
                         write!(f, "ScanStateSummaryGet, {query:?}")
                     }
                     RpcRequest::SnarkPoolGet => write!(f, "SnarkPoolGet"),
@@ -64,9 +79,18 @@ impl std::fmt::Display for Event {
                     RpcRequest::DiscoveryRoutingTable => write!(f, "DiscoveryRoutingTable"),
                     RpcRequest::DiscoveryBoostrapStats => write!(f, "DiscoveryBoostrapStats"),
                     RpcRequest::TransactionPoolGet => write!(f, "TransactionPool"),
-                    RpcRequest::LedgerAccountsGet(account_query) => {
+                    RpcRequest::TransactionPoolZkappStatsGet => {
+                        write!(f, "TransactionPoolZkappStatsGet")
+                    }
+                    RpcRequest::LedgerAccountsGet(account_query, _) => {
                         write!(f, "LedgerAccountsGet, {account_query:?}")
                     }
+                    RpcRequest::LedgerAccountsAtBlockGet(query) => {
+                        write!(f, "LedgerAccountsAtBlockGet, {query:?}")
+                    }
+                    RpcRequest::AccountNextNoncesGet(public_keys) => {
+                        write!(f, "AccountNextNoncesGet, {public_keys:?}")
+                    }
                     RpcRequest::TransactionInject(..) => write!(f, "TransactionInject"),
                     RpcRequest::TransitionFrontierUserCommandsGet => {
                         write!(f, "TransitionFrontierUserCommandsGet")
@@ -80,9 +104,46 @@ impl std::fmt::Display for Event {
                     RpcRequest::GenesisBlockGet => write!(f, "GenesisBlock"),
                     RpcRequest::ConsensusTimeGet(..) => write!(f, "ConsensusTimeGet"),
                     RpcRequest::LedgerStatusGet(..) => write!(f, "LedgerStatusGet"),
+                    RpcRequest::LedgerStatusExtendedGet => write!(f, "LedgerStatusExtendedGet"),
+                    RpcRequest::LedgerSession(..) => write!(f, "LedgerSession"),
                     RpcRequest::LedgerAccountDelegatorsGet(..) => {
                         write!(f, "LedgerAccountDelegatorsGet")
                     }
+                    RpcRequest::TransactionInclusionProofGet(..) => {
+                        write!(f, "TransactionInclusionProofGet")
+                    }
+                    RpcRequest::FinalityEstimateGet => write!(f, "FinalityEstimateGet"),
+                    RpcRequest::NodeInfoGet => write!(f, "NodeInfoGet"),
+                    RpcRequest::InternalCommandsGet(query) => {
+                        write!(f, "InternalCommandsGet, {query:?}")
+                    }
+                    RpcRequest::SyncPeerScoresGet => write!(f, "SyncPeerScoresGet"),
+                    RpcRequest::Profiler(..) => write!(f, "Profiler"),
+                    RpcRequest::MaintenanceMode(..) => write!(f, "MaintenanceMode"),
+                    RpcRequest::FeatureFlags(..) => write!(f, "FeatureFlags"),
+                    RpcRequest::CheckpointsGet => write!(f, "CheckpointsGet"),
+                    RpcRequest::TransactionPoolSnapshot(..) => {
+                        write!(f, "TransactionPoolSnapshot")
+                    }
+                    RpcRequest::AccountSubscriptions(..) => write!(f, "AccountSubscriptions"),
+                    RpcRequest::AccountEventsSubscribe => write!(f, "AccountEventsSubscribe"),
+                    RpcRequest::SnarkPoolJobEventsSubscribe => {
+                        write!(f, "SnarkPoolJobEventsSubscribe")
+                    }
+                    RpcRequest::ZkappVkRegister(..) => write!(f, "ZkappVkRegister"),
+                    RpcRequest::ZkappPreconditionsEvaluate(..) => {
+                        write!(f, "ZkappPreconditionsEvaluate")
+                    }
+                    RpcRequest::PayoutsBatchSubmit(..) => write!(f, "PayoutsBatchSubmit"),
+                    RpcRequest::PayoutsBatchGet(..) => write!(f, "PayoutsBatchGet"),
+                    RpcRequest::ZkappFeeSponsor(..) => write!(f, "ZkappFeeSponsor"),
+                    RpcRequest::ChainImportBlock(..) => write!(f, "ChainImportBlock"),
+                    RpcRequest::VrfVerify(..) => write!(f, "VrfVerify"),
+                    RpcRequest::LedgerAudit(..) => write!(f, "LedgerAudit"),
+                    RpcRequest::BlockTemplateGet => write!(f, "BlockTemplateGet"),
+                    RpcRequest::BlockTemplateSubmit(submit) => {
+                        write!(f, "BlockTemplateSubmit, {}", submit.global_slot)
+                    }
                 }
             }
             Self::ExternalSnarkWorker(event) => {
@@ -98,6 +159,7 @@ impl std::fmt::Display for Event {
                 }
             }
             Self::BlockProducerEvent(event) => event.fmt(f),
+            Self::Archive(event) => event.fmt(f),
             Self::GenesisLoad(res) => {
                 write!(f, "GenesisLoad, ")?;
                 match res {
@@ -1,8 +1,11 @@
+use openmina_core::{action_trace, ActionEvent};
 use serde::{Deserialize, Serialize};
 
 pub type EventSourceActionWithMeta = redux::ActionWithMeta<EventSourceAction>;
+pub type EventSourceActionWithMetaRef<'a> = redux::ActionWithMeta<&'a EventSourceAction>;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = trace)]
 pub enum EventSourceAction {
     /// Notify state machine that the new events might be received/available,
     /// so trigger processing of those events.
@@ -13,6 +16,7 @@ pub enum EventSourceAction {
     ProcessEvents,
 
     /// Process newly retrieved event.
+    #[action_event(expr(log_new_event(context, event)))]
     NewEvent { event: super::Event },
 
     /// Next action won't be dispatched, until new events are available or
@@ -21,15 +25,49 @@ pub enum EventSourceAction {
 
     /// Waiting for events has timed out.
     WaitTimeout,
+
+    /// Event queue depth reached the high threshold, low priority work
+    /// is shed or deferred until it drains.
+    #[action_event(level = warn, fields(queue_depth))]
+    OverloadStart { queue_depth: usize },
+    /// Event queue drained to the low threshold.
+    #[action_event(level = info, fields(queue_depth))]
+    OverloadEnd { queue_depth: usize },
+    /// Low priority event was dropped due to the overload.
+    EventShed,
 }
 
 impl redux::EnablingCondition<crate::State> for EventSourceAction {
-    fn is_enabled(&self, _: &crate::State, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
         match self {
             EventSourceAction::ProcessEvents => true,
             EventSourceAction::NewEvent { event: _ } => true,
             EventSourceAction::WaitForEvents => true,
             EventSourceAction::WaitTimeout => true,
+            EventSourceAction::OverloadStart { queue_depth } => {
+                state.event_source.should_overload_start(*queue_depth)
+            }
+            EventSourceAction::OverloadEnd { queue_depth } => {
+                state.event_source.should_overload_end(*queue_depth)
+            }
+            EventSourceAction::EventShed => state.event_source.is_overloaded(),
         }
     }
 }
+
+fn log_new_event<T>(context: &T, event: &super::Event)
+where
+    T: openmina_core::log::EventContext,
+{
+    match event {
+        super::Event::P2p(e) => match e.correlation_id() {
+            Some(correlation_id) => action_trace!(
+                context,
+                event = display(event),
+                correlation_id = display(correlation_id)
+            ),
+            None => action_trace!(context, event = display(event)),
+        },
+        _ => action_trace!(context, event = display(event)),
+    }
+}
@@ -9,23 +9,28 @@ use crate::action::CheckTimeoutsAction;
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorAction;
 use crate::block_producer::{BlockProducerEvent, BlockProducerVrfEvaluatorEvent};
 use crate::external_snark_worker_effectful::ExternalSnarkWorkerEvent;
+use crate::ledger::audit::LedgerAuditAction;
 use crate::ledger::read::LedgerReadAction;
 use crate::ledger::write::LedgerWriteAction;
+use crate::ledger::LedgerAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
+use crate::p2p::channels::checkpoint::P2pChannelsCheckpointAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
 use crate::p2p::channels::{ChannelId, P2pChannelsMessageReceivedAction};
+use crate::p2p::connection::ice_restart::P2pConnectionIceRestartAction;
 use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
 use crate::p2p::connection::{P2pConnectionErrorResponse, P2pConnectionResponse};
 use crate::p2p::disconnection::{P2pDisconnectionAction, P2pDisconnectionReason};
-use crate::p2p::P2pChannelEvent;
 #[cfg(feature = "p2p-libp2p")]
 use crate::p2p::{MioEvent, P2pNetworkSchedulerAction};
+use crate::p2p::{P2pChannelEvent, P2pChannelSendError};
 use crate::rpc::{RpcAction, RpcRequest};
 use crate::snark::block_verify::SnarkBlockVerifyAction;
 use crate::snark::work_verify::SnarkWorkVerifyAction;
 use crate::snark::SnarkEvent;
+use crate::transition_frontier::archive::ArchiveEvent;
 use crate::transition_frontier::genesis::TransitionFrontierGenesisAction;
 use crate::{BlockProducerAction, ExternalSnarkWorkerAction, Service, Store};
 
@@ -44,6 +49,11 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
             // `CheckTimeoutsAction` in between `EventSourceProcessEventsAction`
             // calls so that we make sure, that action gets called even
             // if we are continously flooded with events.
+            let queue_depth = store.service.pending_events_count();
+            if !store.dispatch(EventSourceAction::OverloadStart { queue_depth }) {
+                store.dispatch(EventSourceAction::OverloadEnd { queue_depth });
+            }
+
             for _ in 0..1024 {
                 match store.service.next_event() {
                     Some(event) => {
@@ -119,7 +129,7 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                     }
                 },
                 P2pEvent::Connection(e) => match e {
-                    P2pConnectionEvent::OfferSdpReady(peer_id, res) => match res {
+                    P2pConnectionEvent::OfferSdpReady(peer_id, _, res) => match res {
                         Err(error) => {
                             store.dispatch(P2pConnectionOutgoingAction::OfferSdpCreateError {
                                 peer_id,
@@ -133,7 +143,7 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                             });
                         }
                     },
-                    P2pConnectionEvent::AnswerSdpReady(peer_id, res) => match res {
+                    P2pConnectionEvent::AnswerSdpReady(peer_id, _, res) => match res {
                         Err(error) => {
                             store.dispatch(P2pConnectionIncomingAction::AnswerSdpCreateError {
                                 peer_id,
@@ -147,7 +157,7 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                             });
                         }
                     },
-                    P2pConnectionEvent::AnswerReceived(peer_id, res) => match res {
+                    P2pConnectionEvent::AnswerReceived(peer_id, _, res) => match res {
                         P2pConnectionResponse::Accepted(answer) => {
                             store.dispatch(P2pConnectionOutgoingAction::AnswerRecvSuccess {
                                 peer_id,
@@ -173,7 +183,7 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                             });
                         }
                     },
-                    P2pConnectionEvent::Finalized(peer_id, res) => match res {
+                    P2pConnectionEvent::Finalized(peer_id, _, res) => match res {
                         Err(error) => {
                             store.dispatch(P2pConnectionOutgoingAction::FinalizeError {
                                 peer_id,
@@ -196,15 +206,42 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                             );
                         }
                     },
+                    P2pConnectionEvent::Degraded(peer_id) => {
+                        store.dispatch(P2pConnectionIceRestartAction::Degraded { peer_id });
+                    }
+                    P2pConnectionEvent::Recovered(peer_id) => {
+                        store.dispatch(P2pConnectionIceRestartAction::Success { peer_id });
+                    }
+                    P2pConnectionEvent::IceRestartNegotiated(peer_id, _, res) => match res {
+                        Err(error) => {
+                            store.dispatch(P2pConnectionIceRestartAction::Error { peer_id, error });
+                        }
+                        Ok(()) => {
+                            store.dispatch(P2pConnectionIceRestartAction::NegotiateSuccess {
+                                peer_id,
+                            });
+                        }
+                    },
+                    P2pConnectionEvent::IceRestartAnswerSdpReady(peer_id, _, res) => match res {
+                        Err(error) => {
+                            store.dispatch(P2pConnectionIceRestartAction::Error { peer_id, error });
+                        }
+                        Ok(sdp) => {
+                            store.dispatch(P2pConnectionIceRestartAction::AnswerSdpCreateSuccess {
+                                peer_id,
+                                sdp,
+                            });
+                        }
+                    },
                     P2pConnectionEvent::Closed(peer_id) => {
                         store.dispatch(P2pDisconnectionAction::PeerClosed { peer_id });
                         store.dispatch(P2pDisconnectionAction::Finish { peer_id });
                     }
                 },
                 P2pEvent::Channel(e) => match e {
-                    P2pChannelEvent::Opened(peer_id, chan_id, res) => match res {
+                    P2pChannelEvent::Opened(peer_id, chan_id, cid, res) => match res {
                         Err(err) => {
-                            openmina_core::log::warn!(meta.time(); kind = "P2pChannelEvent::Opened", peer_id = peer_id.to_string(), error = err);
+                            openmina_core::log::warn!(meta.time(); kind = "P2pChannelEvent::Opened", peer_id = peer_id.to_string(), correlation_id = cid.to_string(), error = err);
                             // TODO(binier): dispatch error action.
                         }
                         Ok(_) => match chan_id {
@@ -238,14 +275,30 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                             ChannelId::StreamingRpc => {
                                 store.dispatch(P2pChannelsStreamingRpcAction::Ready { peer_id });
                             }
+                            ChannelId::CheckpointPropagation => {
+                                store.dispatch(P2pChannelsCheckpointAction::Ready { peer_id });
+                            }
                         },
                     },
-                    P2pChannelEvent::Sent(peer_id, _, _, res) => {
-                        if let Err(err) = res {
+                    P2pChannelEvent::Sent(peer_id, chan_id, _, _, res) => match res {
+                        Ok(()) => {}
+                        Err(P2pChannelSendError::Expired) => {
+                            openmina_core::log::debug!(meta.time(); kind = "P2pChannelEvent::Sent", summary = "message expired", peer_id = peer_id.to_string(), chan_id = chan_id.name());
+                            if chan_id == ChannelId::BestTipPropagation {
+                                store.dispatch(P2pChannelsBestTipAction::ResponseExpired {
+                                    peer_id,
+                                });
+                            }
+                        }
+                        // Our own message is too big, the peer did nothing wrong.
+                        Err(P2pChannelSendError::LenOverLimit { len, limit }) => {
+                            openmina_core::log::warn!(meta.time(); kind = "P2pChannelEvent::Sent", summary = "message over the size limit dropped", peer_id = peer_id.to_string(), chan_id = chan_id.name(), len = len, limit = limit);
+                        }
+                        Err(P2pChannelSendError::Failed(err)) => {
                             let reason = P2pDisconnectionReason::P2pChannelSendFailed(err);
                             store.dispatch(P2pDisconnectionAction::Init { peer_id, reason });
                         }
-                    }
+                    },
                     P2pChannelEvent::Received(peer_id, res) => match res {
                         Err(err) => {
                             let reason = P2pDisconnectionReason::P2pChannelReceiveFailed(err);
@@ -271,6 +324,12 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 LedgerEvent::Read(id, response) => {
                     store.dispatch(LedgerReadAction::Success { id, response });
                 }
+                LedgerEvent::Audit(id, result) => {
+                    store.dispatch(LedgerAuditAction::Success { id, result });
+                }
+                LedgerEvent::RootSnapshotWritten { height, result } => {
+                    store.dispatch(LedgerAction::RootSnapshotWritten { height, result });
+                }
             },
             Event::Snark(event) => match event {
                 SnarkEvent::BlockVerify(req_id, result) => match result {
@@ -300,6 +359,29 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                     }
                 }
             },
+            Event::Rpc(rpc_id, e)
+                if e.is_sheddable() && store.state().event_source.is_overloaded() =>
+            {
+                store.dispatch(EventSourceAction::EventShed);
+                store.dispatch(RpcAction::OverloadShed {
+                    rpc_id,
+                    request: *e,
+                });
+            }
+            Event::Rpc(rpc_id, e) if e.is_write() && store.state().rpc.is_in_maintenance() => {
+                store.dispatch(RpcAction::MaintenanceRejected {
+                    rpc_id,
+                    request: *e,
+                });
+            }
+            Event::Rpc(rpc_id, e)
+                if e.needs_ledger() && store.state().transition_frontier.light =>
+            {
+                store.dispatch(RpcAction::LightModeRejected {
+                    rpc_id,
+                    request: *e,
+                });
+            }
             Event::Rpc(rpc_id, e) => match *e {
                 RpcRequest::StateGet(filter) => {
                     store.dispatch(RpcAction::GlobalStateGet { rpc_id, filter });
@@ -310,6 +392,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::HeartbeatGet => {
                     store.dispatch(RpcAction::HeartbeatGet { rpc_id });
                 }
+                RpcRequest::StateDigestGet => {
+                    store.dispatch(RpcAction::StateDigestGet { rpc_id });
+                }
                 RpcRequest::ActionStatsGet(query) => {
                     store.dispatch(RpcAction::ActionStatsGet { rpc_id, query });
                 }
@@ -319,9 +404,30 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::BlockProducerStatsGet => {
                     store.dispatch(RpcAction::BlockProducerStatsGet { rpc_id });
                 }
+                RpcRequest::ZkappFailureStatsGet(from_epoch) => {
+                    store.dispatch(RpcAction::ZkappFailureStatsGet { rpc_id, from_epoch });
+                }
+                RpcRequest::SnarkVerifyStatsGet => {
+                    store.dispatch(RpcAction::SnarkVerifyStatsGet { rpc_id });
+                }
+                RpcRequest::BlockProducerEpochReportGet(epoch) => {
+                    store.dispatch(RpcAction::BlockProducerEpochReportGet { rpc_id, epoch });
+                }
+                RpcRequest::StatsHistoryGet(query) => {
+                    store.dispatch(RpcAction::StatsHistoryGet { rpc_id, query });
+                }
+                RpcRequest::BlockPropagationGet(from_height) => {
+                    store.dispatch(RpcAction::BlockPropagationGet {
+                        rpc_id,
+                        from_height,
+                    });
+                }
                 RpcRequest::PeersGet => {
                     store.dispatch(RpcAction::PeersGet { rpc_id });
                 }
+                RpcRequest::P2pDiagnosticsGet(errors) => {
+                    store.dispatch(RpcAction::P2pDiagnosticsGet { rpc_id, errors });
+                }
                 RpcRequest::MessageProgressGet => {
                     store.dispatch(RpcAction::MessageProgressGet { rpc_id });
                 }
@@ -331,8 +437,12 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::P2pConnectionIncoming(opts) => {
                     store.dispatch(RpcAction::P2pConnectionIncomingInit { rpc_id, opts });
                 }
-                RpcRequest::ScanStateSummaryGet(query) => {
-                    store.dispatch(RpcAction::ScanStateSummaryGetInit { rpc_id, query });
+                RpcRequest::ScanStateSummaryGet(query, continuation) => {
+                    store.dispatch(RpcAction::ScanStateSummaryGetInit {
+                        rpc_id,
+                        query,
+                        continuation,
+                    });
                 }
                 RpcRequest::SnarkPoolGet => {
                     store.dispatch(RpcAction::SnarkPoolAvailableJobsGet { rpc_id });
@@ -373,10 +483,23 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::TransactionPoolGet => {
                     store.dispatch(RpcAction::TransactionPool { rpc_id });
                 }
-                RpcRequest::LedgerAccountsGet(account_query) => {
+                RpcRequest::TransactionPoolZkappStatsGet => {
+                    store.dispatch(RpcAction::TransactionPoolZkappStatsGet { rpc_id });
+                }
+                RpcRequest::LedgerAccountsGet(account_query, continuation) => {
                     store.dispatch(RpcAction::LedgerAccountsGetInit {
                         rpc_id,
                         account_query,
+                        continuation,
+                    });
+                }
+                RpcRequest::LedgerAccountsAtBlockGet(query) => {
+                    store.dispatch(RpcAction::LedgerAccountsAtBlockGetInit { rpc_id, query });
+                }
+                RpcRequest::AccountNextNoncesGet(public_keys) => {
+                    store.dispatch(RpcAction::AccountNextNoncesGetInit {
+                        rpc_id,
+                        public_keys,
                     });
                 }
                 RpcRequest::TransactionInject(commands) => {
@@ -397,6 +520,63 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::GetBlock(query) => {
                     store.dispatch(RpcAction::BlockGet { rpc_id, query });
                 }
+                RpcRequest::TransactionInclusionProofGet(query) => {
+                    store.dispatch(RpcAction::TransactionInclusionProofGet { rpc_id, query });
+                }
+                RpcRequest::FinalityEstimateGet => {
+                    store.dispatch(RpcAction::FinalityEstimateGet { rpc_id });
+                }
+                RpcRequest::NodeInfoGet => {
+                    store.dispatch(RpcAction::NodeInfoGet { rpc_id });
+                }
+                RpcRequest::InternalCommandsGet(query) => {
+                    store.dispatch(RpcAction::InternalCommandsGet { rpc_id, query });
+                }
+                RpcRequest::SyncPeerScoresGet => {
+                    store.dispatch(RpcAction::SyncPeerScoresGet { rpc_id });
+                }
+                RpcRequest::Profiler(command) => {
+                    store.dispatch(RpcAction::Profiler { rpc_id, command });
+                }
+                RpcRequest::MaintenanceMode(command) => {
+                    store.dispatch(RpcAction::MaintenanceMode { rpc_id, command });
+                }
+                RpcRequest::FeatureFlags(command) => {
+                    store.dispatch(RpcAction::FeatureFlags { rpc_id, command });
+                }
+                RpcRequest::CheckpointsGet => {
+                    store.dispatch(RpcAction::CheckpointsGet { rpc_id });
+                }
+                RpcRequest::TransactionPoolSnapshot(command) => {
+                    store.dispatch(RpcAction::TransactionPoolSnapshot { rpc_id, command });
+                }
+                RpcRequest::AccountSubscriptions(command) => {
+                    store.dispatch(RpcAction::AccountSubscriptions { rpc_id, command });
+                }
+                RpcRequest::AccountEventsSubscribe => {
+                    store.dispatch(RpcAction::AccountEventsSubscribe { rpc_id });
+                }
+                RpcRequest::SnarkPoolJobEventsSubscribe => {
+                    store.dispatch(RpcAction::SnarkPoolJobEventsSubscribe { rpc_id });
+                }
+                RpcRequest::ZkappVkRegister(request) => {
+                    store.dispatch(RpcAction::ZkappVkRegister { rpc_id, request });
+                }
+                RpcRequest::ZkappPreconditionsEvaluate(command) => {
+                    store.dispatch(RpcAction::ZkappPreconditionsEvaluate { rpc_id, command });
+                }
+                RpcRequest::PayoutsBatchSubmit(request) => {
+                    store.dispatch(RpcAction::PayoutsBatchSubmit { rpc_id, request });
+                }
+                RpcRequest::PayoutsBatchGet(batch_id) => {
+                    store.dispatch(RpcAction::PayoutsBatchGet { rpc_id, batch_id });
+                }
+                RpcRequest::ZkappFeeSponsor(request) => {
+                    store.dispatch(RpcAction::ZkappFeeSponsor { rpc_id, request });
+                }
+                RpcRequest::ChainImportBlock(block) => {
+                    store.dispatch(RpcAction::ChainImportBlock { rpc_id, block });
+                }
                 RpcRequest::PooledUserCommands(query) => {
                     store.dispatch(RpcAction::PooledUserCommands { rpc_id, query });
                 }
@@ -415,6 +595,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         ledger_hash,
                     });
                 }
+                RpcRequest::LedgerStatusExtendedGet => {
+                    store.dispatch(RpcAction::LedgerStatusExtendedGetInit { rpc_id });
+                }
                 RpcRequest::LedgerAccountDelegatorsGet(ledger_hash, account_id) => {
                     store.dispatch(RpcAction::LedgerAccountDelegatorsGetInit {
                         rpc_id,
@@ -422,6 +605,21 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         account_id,
                     });
                 }
+                RpcRequest::LedgerSession(request) => {
+                    store.dispatch(RpcAction::LedgerSessionInit { rpc_id, request });
+                }
+                RpcRequest::VrfVerify(query) => {
+                    store.dispatch(RpcAction::VrfVerifyInit { rpc_id, query });
+                }
+                RpcRequest::LedgerAudit(command) => {
+                    store.dispatch(RpcAction::LedgerAudit { rpc_id, command });
+                }
+                RpcRequest::BlockTemplateGet => {
+                    store.dispatch(RpcAction::BlockTemplateGet { rpc_id });
+                }
+                RpcRequest::BlockTemplateSubmit(submit) => {
+                    store.dispatch(RpcAction::BlockTemplateSubmit { rpc_id, submit });
+                }
             },
             Event::ExternalSnarkWorker(e) => match e {
                 ExternalSnarkWorkerEvent::Started => {
@@ -462,10 +660,13 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         "error while trying to produce block proof for block {block_hash} - {err}"
                     ),
                     Ok(proof) => {
-                        if store
-                            .state()
-                            .transition_frontier
-                            .genesis
+                        let genesis = &store.state().transition_frontier.genesis;
+                        if genesis
+                            .proven_block()
+                            .is_some_and(|block| block.hash() == &block_hash)
+                        {
+                            // genesis proof was already received from a peer.
+                        } else if genesis
                             .prove_pending_block_hash()
                             .is_some_and(|hash| hash == block_hash)
                         {
@@ -476,6 +677,31 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                         }
                     }
                 },
+                BlockProducerEvent::SlotClaim(global_slot, res) => match res {
+                    Ok(result) => {
+                        store.dispatch(BlockProducerAction::WonSlotClaimSuccess {
+                            global_slot,
+                            result,
+                        });
+                    }
+                    Err(error) => {
+                        store.dispatch(BlockProducerAction::WonSlotClaimError {
+                            global_slot,
+                            error,
+                        });
+                    }
+                },
+                BlockProducerEvent::SigningRefused(block_hash, error) => {
+                    store.dispatch(BlockProducerAction::BlockSigningRefused { block_hash, error });
+                }
+            },
+            Event::Archive(event) => match event {
+                ArchiveEvent::AccountsAtBlock(rpc_id, response) => {
+                    store.dispatch(RpcAction::LedgerAccountsAtBlockArchiveGetSuccess {
+                        rpc_id,
+                        response,
+                    });
+                }
             },
             Event::GenesisLoad(res) => match res {
                 Err(err) => todo!("error while trying to load genesis config/ledger. - {err}"),
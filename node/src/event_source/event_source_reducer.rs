@@ -0,0 +1,38 @@
+use super::{
+    EventSourceAction, EventSourceActionWithMetaRef, EventSourceOverload, EventSourceState,
+};
+
+impl EventSourceState {
+    pub fn reducer(
+        mut state_context: crate::Substate<Self>,
+        action: EventSourceActionWithMetaRef<'_>,
+    ) {
+        let Ok(state) = state_context.get_substate_mut() else {
+            return;
+        };
+        let (action, meta) = action.split();
+
+        match action {
+            EventSourceAction::ProcessEvents
+            | EventSourceAction::NewEvent { .. }
+            | EventSourceAction::WaitForEvents
+            | EventSourceAction::WaitTimeout => {}
+            EventSourceAction::OverloadStart { queue_depth } => {
+                state.overload = Some(EventSourceOverload {
+                    since: meta.time(),
+                    queue_depth: *queue_depth,
+                    shed: 0,
+                });
+            }
+            EventSourceAction::OverloadEnd { .. } => {
+                state.overload = None;
+            }
+            EventSourceAction::EventShed => {
+                if let Some(overload) = state.overload.as_mut() {
+                    overload.shed = overload.shed.saturating_add(1);
+                }
+                state.shed_total = state.shed_total.saturating_add(1);
+            }
+        }
+    }
+}
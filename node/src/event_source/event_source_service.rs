@@ -2,4 +2,7 @@ use super::Event;
 
 pub trait EventSourceService: redux::Service {
     fn next_event(&mut self) -> Option<Event>;
+
+    /// Number of events waiting to be processed.
+    fn pending_events_count(&self) -> usize;
 }
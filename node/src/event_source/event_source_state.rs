@@ -0,0 +1,75 @@
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Thresholds of the event queue depth for the overload protection.
+///
+/// Once the number of pending events reaches `queue_depth_high`, the
+/// node is considered overloaded and low priority work is shed or
+/// deferred, until the queue drains to `queue_depth_low`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct EventSourceOverloadConfig {
+    pub queue_depth_high: usize,
+    pub queue_depth_low: usize,
+}
+
+impl Default for EventSourceOverloadConfig {
+    fn default() -> Self {
+        Self {
+            queue_depth_high: 8192,
+            queue_depth_low: 1024,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventSourceOverload {
+    pub since: Timestamp,
+    /// Queue depth when the overload started.
+    pub queue_depth: usize,
+    /// Number of events shed during the overload.
+    pub shed: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EventSourceState {
+    pub overload_config: EventSourceOverloadConfig,
+    pub overload: Option<EventSourceOverload>,
+    /// Number of events shed since the start of the node.
+    pub shed_total: u64,
+}
+
+impl EventSourceState {
+    pub fn is_overloaded(&self) -> bool {
+        self.overload.is_some()
+    }
+
+    pub fn should_overload_start(&self, queue_depth: usize) -> bool {
+        !self.is_overloaded() && queue_depth >= self.overload_config.queue_depth_high
+    }
+
+    pub fn should_overload_end(&self, queue_depth: usize) -> bool {
+        self.is_overloaded() && queue_depth <= self.overload_config.queue_depth_low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overload_hysteresis() {
+        let mut state = EventSourceState::default();
+        assert!(!state.should_overload_start(8191));
+        assert!(state.should_overload_start(8192));
+        assert!(!state.should_overload_end(0));
+
+        state.overload = Some(EventSourceOverload {
+            since: Timestamp::ZERO,
+            queue_depth: 8192,
+            shed: 0,
+        });
+        assert!(!state.should_overload_start(10_000));
+        assert!(!state.should_overload_end(1025));
+        assert!(state.should_overload_end(1024));
+    }
+}
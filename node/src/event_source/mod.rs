@@ -9,9 +9,14 @@
 mod event;
 pub use event::*;
 
+mod event_source_state;
+pub use event_source_state::*;
+
 mod event_source_actions;
 pub use event_source_actions::*;
 
+mod event_source_reducer;
+
 mod event_source_effects;
 pub use event_source_effects::*;
 
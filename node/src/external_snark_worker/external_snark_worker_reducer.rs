@@ -129,6 +129,7 @@ impl ExternalSnarkWorker {
                     snark,
                     sender,
                     is_sender_local: true,
+                    is_verified: true,
                 });
                 dispatcher.push(ExternalSnarkWorkerAction::PruneWork);
             }
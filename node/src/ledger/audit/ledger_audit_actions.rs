@@ -0,0 +1,42 @@
+use mina_p2p_messages::v2::LedgerHash;
+use serde::{Deserialize, Serialize};
+
+use super::{LedgerAuditId, LedgerAuditReport};
+
+pub type LedgerAuditActionWithMetaRef<'a> = redux::ActionWithMeta<&'a LedgerAuditAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum LedgerAuditAction {
+    /// Audit the staged ledger of the best tip.
+    Init,
+    Pending {
+        id: LedgerAuditId,
+        ledger_hash: LedgerHash,
+    },
+    Success {
+        id: LedgerAuditId,
+        result: Result<LedgerAuditReport, String>,
+    },
+}
+
+impl redux::EnablingCondition<crate::State> for LedgerAuditAction {
+    fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
+        let audit = &state.ledger.audit;
+        match self {
+            LedgerAuditAction::Init => {
+                !audit.is_pending() && state.transition_frontier.best_tip().is_some()
+            }
+            LedgerAuditAction::Pending { id, .. } => !audit.is_pending() && audit.next_id() == *id,
+            LedgerAuditAction::Success { id, .. } => audit
+                .pending
+                .as_ref()
+                .is_some_and(|pending| pending.id == *id),
+        }
+    }
+}
+
+impl From<LedgerAuditAction> for crate::Action {
+    fn from(value: LedgerAuditAction) -> Self {
+        Self::Ledger(value.into())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{ledger_effectful::LedgerEffectfulAction, Substate};
+
+use super::{LedgerAuditAction, LedgerAuditActionWithMetaRef, LedgerAuditState};
+
+impl LedgerAuditState {
+    pub fn reducer(mut state_context: Substate<Self>, action: LedgerAuditActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        let Ok(state) = state_context.get_substate_mut() else {
+            return;
+        };
+
+        match action {
+            LedgerAuditAction::Init => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(best_tip) = state.transition_frontier.best_tip() else {
+                    return;
+                };
+                dispatcher.push(LedgerEffectfulAction::AuditInit {
+                    id: state.ledger.audit.next_id(),
+                    ledger_hash: best_tip.merkle_root_hash().clone(),
+                    staged_ledger_hash: best_tip.staged_ledger_hashes().clone(),
+                });
+            }
+            LedgerAuditAction::Pending { id, ledger_hash } => {
+                state.start(meta.time(), ledger_hash.clone());
+                openmina_core::info!(meta.time();
+                    kind = "LedgerAuditStart",
+                    summary = format!("ledger audit {id} started"),
+                    ledger_hash = ledger_hash.to_string(),
+                );
+            }
+            LedgerAuditAction::Success { id, result } => {
+                let Some(finished) = state.finish(meta.time(), result.clone()) else {
+                    return;
+                };
+                match &finished.result {
+                    Ok(report) if report.is_ok() => {
+                        openmina_core::info!(meta.time();
+                            kind = "LedgerAuditFinish",
+                            summary = format!("ledger audit {id} found no discrepancies"),
+                            ledger_hash = report.ledger_hash.to_string(),
+                            num_accounts = report.num_accounts,
+                        );
+                    }
+                    Ok(report) => {
+                        openmina_core::warn!(meta.time();
+                            kind = "LedgerAuditFinish",
+                            summary = format!(
+                                "ledger audit {id} found {} discrepancies",
+                                report.discrepancies_count
+                            ),
+                            ledger_hash = report.ledger_hash.to_string(),
+                            num_accounts = report.num_accounts,
+                        );
+                    }
+                    Err(error) => {
+                        openmina_core::warn!(meta.time();
+                            kind = "LedgerAuditFinish",
+                            summary = format!("ledger audit {id} failed"),
+                            error = error.clone(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use mina_p2p_messages::v2::LedgerHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::{LedgerAuditId, LedgerAuditReport};
+
+/// Consistency audit of the best tip ledger, started on request.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LedgerAuditState {
+    next_id: LedgerAuditId,
+    pub pending: Option<LedgerAuditPending>,
+    pub last: Option<LedgerAuditFinished>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerAuditPending {
+    pub id: LedgerAuditId,
+    pub time: Timestamp,
+    pub ledger_hash: LedgerHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerAuditFinished {
+    pub id: LedgerAuditId,
+    pub started_at: Timestamp,
+    pub finished_at: Timestamp,
+    pub result: Result<LedgerAuditReport, String>,
+}
+
+impl LedgerAuditState {
+    /// Id of the audit started next.
+    pub fn next_id(&self) -> LedgerAuditId {
+        self.next_id
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub(super) fn start(&mut self, time: Timestamp, ledger_hash: LedgerHash) {
+        self.pending = Some(LedgerAuditPending {
+            id: self.next_id,
+            time,
+            ledger_hash,
+        });
+        self.next_id = self.next_id.wrapping_add(1);
+    }
+
+    pub(super) fn finish(
+        &mut self,
+        time: Timestamp,
+        result: Result<LedgerAuditReport, String>,
+    ) -> Option<&LedgerAuditFinished> {
+        let pending = self.pending.take()?;
+        self.last = Some(LedgerAuditFinished {
+            id: pending.id,
+            started_at: pending.time,
+            finished_at: time,
+            result,
+        });
+        self.last.as_ref()
+    }
+}
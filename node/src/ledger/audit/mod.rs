@@ -0,0 +1,300 @@
+mod ledger_audit_actions;
+pub use ledger_audit_actions::*;
+
+mod ledger_audit_state;
+pub use ledger_audit_state::*;
+
+mod ledger_audit_reducer;
+
+use ledger::{AccountId, AccountIndex, BaseLedger, Mask, TreeVersion};
+use mina_hasher::Fp;
+use mina_p2p_messages::{bigint::BigInt, v2::LedgerHash};
+use serde::{Deserialize, Serialize};
+
+pub type LedgerAuditId = u64;
+
+/// Only this many discrepancies are listed in the report, the rest are
+/// only counted.
+pub const LEDGER_AUDIT_DISCREPANCIES_MAX: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum LedgerAuditDiscrepancy {
+    /// Ledger is stored under a hash different from its merkle root.
+    LedgerHashMismatch {
+        ledger_hash: LedgerHash,
+        stored_root: LedgerHash,
+    },
+    /// Merkle root recomputed from the accounts differs from the stored one.
+    RootHashMismatch {
+        stored: LedgerHash,
+        computed: LedgerHash,
+    },
+    /// There is no account at the index below the number of accounts.
+    AccountMissing { index: u64 },
+    /// Index of the account looked up by its id differs from the index
+    /// it is stored at.
+    AccountIndexMismatch {
+        index: u64,
+        account_id: AccountId,
+        found: Option<u64>,
+    },
+    /// Hash of the account differs from the one stored in the merkle tree.
+    AccountHashMismatch {
+        index: u64,
+        stored: Option<BigInt>,
+        computed: BigInt,
+    },
+    /// Mask is alive, but isn't referenced by the ledger service.
+    OrphanedMask { uuid: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerAuditReport {
+    /// Hash the ledger is stored under in the ledger service.
+    pub ledger_hash: LedgerHash,
+    pub num_accounts: u64,
+    pub alive_masks: usize,
+    /// Number of all the discrepancies found, only the first
+    /// [`LEDGER_AUDIT_DISCREPANCIES_MAX`] are listed.
+    pub discrepancies_count: usize,
+    pub discrepancies: Vec<LedgerAuditDiscrepancy>,
+}
+
+impl LedgerAuditReport {
+    pub fn is_ok(&self) -> bool {
+        self.discrepancies_count == 0
+    }
+
+    fn add(&mut self, discrepancy: LedgerAuditDiscrepancy) {
+        self.discrepancies_count = self.discrepancies_count.saturating_add(1);
+        if self.discrepancies.len() < LEDGER_AUDIT_DISCREPANCIES_MAX {
+            self.discrepancies.push(discrepancy);
+        }
+    }
+}
+
+/// Snapshot of the ledger taken in the ledger service, so that it can be
+/// read and hashed without blocking it.
+pub struct LedgerAuditInput {
+    ledger_hash: LedgerHash,
+    mask: Mask,
+    alive_masks: usize,
+    orphaned_masks: Vec<String>,
+}
+
+impl LedgerAuditInput {
+    /// Copies the `mask` together with its parents, the copy shares no
+    /// lock with them, so the audit can run in another thread.
+    pub fn new(
+        ledger_hash: LedgerHash,
+        mask: &Mask,
+        alive_masks: usize,
+        orphaned_masks: Vec<String>,
+    ) -> Self {
+        Self {
+            ledger_hash,
+            mask: mask.deep_copy(),
+            alive_masks,
+            orphaned_masks,
+        }
+    }
+
+    /// Re-hashes the accounts and the merkle tree from scratch and
+    /// compares the result with the stored hashes.
+    pub fn run(mut self) -> LedgerAuditReport {
+        let num_accounts = self.mask.num_accounts() as u64;
+        let mut report = LedgerAuditReport {
+            ledger_hash: self.ledger_hash.clone(),
+            num_accounts,
+            alive_masks: self.alive_masks,
+            discrepancies_count: 0,
+            discrepancies: Vec::new(),
+        };
+
+        let stored_root = LedgerHash::from_fp(self.mask.merkle_root());
+        if stored_root != self.ledger_hash {
+            report.add(LedgerAuditDiscrepancy::LedgerHashMismatch {
+                ledger_hash: self.ledger_hash,
+                stored_root: stored_root.clone(),
+            });
+        }
+
+        let mut tree = MerkleTreeBuilder::new(super::LEDGER_DEPTH);
+        for index in (0..num_accounts).map(AccountIndex) {
+            let Some(account) = self.mask.get_at_index(index) else {
+                report.add(LedgerAuditDiscrepancy::AccountMissing {
+                    index: index.as_u64(),
+                });
+                tree.push(ledger::V2::empty_hash_at_height(0));
+                continue;
+            };
+            let found_index = self.mask.index_of_account(account.id());
+            if found_index != Some(index) {
+                report.add(LedgerAuditDiscrepancy::AccountIndexMismatch {
+                    index: index.as_u64(),
+                    account_id: account.id(),
+                    found: found_index.map(|found| found.as_u64()),
+                });
+            }
+            let hash = account.hash();
+            let stored_hash = self.mask.get_account_hash(index);
+            if stored_hash != Some(hash) {
+                report.add(LedgerAuditDiscrepancy::AccountHashMismatch {
+                    index: index.as_u64(),
+                    stored: stored_hash.map(Into::into),
+                    computed: hash.into(),
+                });
+            }
+            tree.push(hash);
+        }
+
+        let computed_root = LedgerHash::from_fp(tree.root());
+        if computed_root != stored_root {
+            report.add(LedgerAuditDiscrepancy::RootHashMismatch {
+                stored: stored_root,
+                computed: computed_root,
+            });
+        }
+
+        for uuid in self.orphaned_masks {
+            report.add(LedgerAuditDiscrepancy::OrphanedMask { uuid });
+        }
+
+        report
+    }
+}
+
+/// Computes the merkle root from the leaves pushed from the left, the
+/// empty leaves after them are implied.
+///
+/// Each subtree is hashed as soon as its last leaf is pushed, so only
+/// one pending node per height is kept instead of all the leaves.
+struct MerkleTreeBuilder {
+    depth: usize,
+    /// Root of the complete left subtree waiting for its right sibling,
+    /// indexed by height.
+    pending: Vec<Option<Fp>>,
+}
+
+impl MerkleTreeBuilder {
+    fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            pending: vec![None; depth.saturating_add(1)],
+        }
+    }
+
+    fn push(&mut self, leaf: Fp) {
+        let mut hash = leaf;
+        for (height, pending) in self.pending.iter_mut().enumerate() {
+            match pending.take() {
+                Some(left) if height < self.depth => {
+                    hash = ledger::V2::hash_node(height, left, hash);
+                }
+                _ => {
+                    *pending = Some(hash);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn root(self) -> Fp {
+        let mut right_most = None;
+        for (height, pending) in self.pending.into_iter().enumerate() {
+            if height == self.depth {
+                return right_most
+                    .or(pending)
+                    .unwrap_or_else(|| ledger::V2::empty_hash_at_height(height));
+            }
+            let empty = ledger::V2::empty_hash_at_height(height);
+            right_most = match (pending, right_most) {
+                (None, None) => None,
+                (Some(left), None) => Some(ledger::V2::hash_node(height, left, empty)),
+                (None, Some(left)) => Some(ledger::V2::hash_node(height, left, empty)),
+                (Some(left), Some(right)) => Some(ledger::V2::hash_node(height, left, right)),
+            };
+        }
+        ledger::V2::empty_hash_at_height(self.depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::Account;
+
+    use super::*;
+
+    fn ledger_with_accounts(accounts: &[Account]) -> Mask {
+        let mut mask = Mask::create(super::super::LEDGER_DEPTH);
+        for account in accounts {
+            mask.get_or_create_account(account.id(), account.clone())
+                .unwrap();
+        }
+        mask
+    }
+
+    fn random_accounts(n: usize) -> Vec<Account> {
+        (0..n).map(|_| Account::rand()).collect()
+    }
+
+    #[test]
+    fn audit_consistent_ledger() {
+        for n in [0, 1, 5, 8] {
+            let mut mask = ledger_with_accounts(&random_accounts(n));
+            let ledger_hash = LedgerHash::from_fp(mask.merkle_root());
+            let report = LedgerAuditInput::new(ledger_hash, &mask, 1, vec![]).run();
+            assert_eq!(report.num_accounts, n as u64);
+            assert!(report.is_ok(), "{:?}", report.discrepancies);
+        }
+    }
+
+    #[test]
+    fn audit_child_mask() {
+        let mut mask = ledger_with_accounts(&random_accounts(3)).make_child();
+        for account in random_accounts(2) {
+            mask.get_or_create_account(account.id(), account).unwrap();
+        }
+        let ledger_hash = LedgerHash::from_fp(mask.merkle_root());
+        let report = LedgerAuditInput::new(ledger_hash, &mask, 1, vec![]).run();
+        assert_eq!(report.num_accounts, 5);
+        assert!(report.is_ok(), "{:?}", report.discrepancies);
+    }
+
+    #[test]
+    fn audit_corrupted_account() {
+        let accounts = random_accounts(5);
+        let mut corrupted = accounts.clone();
+        let account = corrupted.get_mut(3).unwrap();
+        account.nonce = account.nonce.incr();
+        let mut corrupted = ledger_with_accounts(&corrupted);
+        corrupted.merkle_root();
+
+        // Hashes of the other ledger are stored for the accounts.
+        let mut mask = ledger_with_accounts(&accounts);
+        mask.set_raw_inner_hashes(corrupted.get_raw_inner_hashes());
+        let ledger_hash = LedgerHash::from_fp(mask.merkle_root());
+
+        let input = LedgerAuditInput::new(ledger_hash, &mask, 2, vec!["orphan".into()]);
+        let report = input.run();
+        assert_eq!(report.discrepancies_count, 3);
+        assert!(matches!(
+            report.discrepancies.as_slice(),
+            [
+                LedgerAuditDiscrepancy::AccountHashMismatch { index: 3, .. },
+                LedgerAuditDiscrepancy::RootHashMismatch { .. },
+                LedgerAuditDiscrepancy::OrphanedMask { uuid },
+            ] if uuid == "orphan"
+        ));
+    }
+
+    #[test]
+    fn merkle_tree_builder_matches_mask() {
+        for n in [0, 1, 2, 3, 7, 16] {
+            let mut mask = ledger_with_accounts(&random_accounts(n));
+            let mut tree = MerkleTreeBuilder::new(super::super::LEDGER_DEPTH);
+            mask.iter(|account| tree.push(account.hash()));
+            assert_eq!(tree.root(), mask.merkle_root(), "{n} accounts");
+        }
+    }
+}
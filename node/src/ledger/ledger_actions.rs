@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{read::LedgerReadAction, write::LedgerWriteAction};
+use super::{audit::LedgerAuditAction, read::LedgerReadAction, write::LedgerWriteAction};
 
 pub type LedgerActionWithMetaRef<'a> = redux::ActionWithMeta<&'a LedgerAction>;
 
@@ -8,6 +8,13 @@ pub type LedgerActionWithMetaRef<'a> = redux::ActionWithMeta<&'a LedgerAction>;
 pub enum LedgerAction {
     Write(LedgerWriteAction),
     Read(LedgerReadAction),
+    Audit(LedgerAuditAction),
+    /// Root snapshot of the commit of the root at `height` was written.
+    #[from(ignore)]
+    RootSnapshotWritten {
+        height: u32,
+        result: Result<(), String>,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for LedgerAction {
@@ -15,6 +22,8 @@ impl redux::EnablingCondition<crate::State> for LedgerAction {
         match self {
             LedgerAction::Write(action) => action.is_enabled(state, time),
             LedgerAction::Read(action) => action.is_enabled(state, time),
+            LedgerAction::Audit(action) => action.is_enabled(state, time),
+            LedgerAction::RootSnapshotWritten { .. } => true,
         }
     }
 }
@@ -1,4 +1,113 @@
+use std::{str::FromStr, time::Duration};
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct LedgerConfig {}
+use super::LEDGER_ROOT_SNAPSHOT_INTERVAL;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LedgerConfig {
+    #[serde(default)]
+    pub persist_policy: LedgerPersistPolicy,
+}
+
+/// Which commits of the transition frontier root are written to the
+/// disk and synced, when the root snapshot is enabled. Commits in between
+/// are kept in memory only and batched into the next persisted one, so the
+/// ones since the last persisted root are lost on crash, and the blocks
+/// need to be applied again.
+///
+/// Persisting on every commit is the most durable, but writing the whole
+/// snarked ledger takes a while, which slows down the block application.
+/// A commit is never persisted while the previous one is still being
+/// written, it is batched into the next one instead.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LedgerPersistPolicy {
+    /// Every commit is persisted.
+    PerBlock,
+    /// Every `blocks`-th commit since the last persisted one.
+    EveryBlocks { blocks: u32 },
+    /// First commit after `interval` since the last persisted one.
+    Periodic { interval: Duration },
+}
+
+impl Default for LedgerPersistPolicy {
+    fn default() -> Self {
+        Self::EveryBlocks {
+            blocks: LEDGER_ROOT_SNAPSHOT_INTERVAL,
+        }
+    }
+}
+
+impl LedgerPersistPolicy {
+    /// Whether a commit should be persisted, given the number of the
+    /// commits since the last persisted one, including this one, and the
+    /// time of the last persisted commit.
+    pub fn is_due(
+        &self,
+        commits: u32,
+        now: redux::Timestamp,
+        last_persisted: Option<redux::Timestamp>,
+    ) -> bool {
+        match self {
+            Self::PerBlock => true,
+            Self::EveryBlocks { blocks } => commits >= *blocks,
+            Self::Periodic { interval } => last_persisted
+                .is_none_or(|last| now.checked_sub(last).unwrap_or_default() >= *interval),
+        }
+    }
+}
+
+impl FromStr for LedgerPersistPolicy {
+    type Err = String;
+
+    /// Parses `block`, `blocks:<n>` or `periodic:<seconds>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid persist policy `{s}`, \
+                 expected `block`, `blocks:<n>` or `periodic:<seconds>`"
+            )
+        };
+        match s.split_once(':') {
+            None if s == "block" => Ok(Self::PerBlock),
+            Some(("blocks", n)) => {
+                let blocks = n.parse().ok().filter(|n| *n > 0).ok_or_else(invalid)?;
+                Ok(Self::EveryBlocks { blocks })
+            }
+            Some(("periodic", secs)) => {
+                let secs = secs.parse().map_err(|_| invalid())?;
+                Ok(Self::Periodic {
+                    interval: Duration::from_secs(secs),
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledger_persist_policy() {
+        let secs = |s: u64| redux::Timestamp::new(s * 1_000_000_000);
+
+        let policy: LedgerPersistPolicy = "block".parse().unwrap();
+        assert!(policy.is_due(7, secs(0), Some(secs(0))));
+
+        let policy: LedgerPersistPolicy = "blocks:5".parse().unwrap();
+        assert!(!policy.is_due(4, secs(0), None));
+        assert!(policy.is_due(5, secs(0), None));
+        assert!(policy.is_due(7, secs(0), None));
+
+        let policy: LedgerPersistPolicy = "periodic:60".parse().unwrap();
+        assert!(policy.is_due(1, secs(10), None));
+        assert!(!policy.is_due(2, secs(69), Some(secs(10))));
+        assert!(policy.is_due(3, secs(70), Some(secs(10))));
+
+        assert!("blocks:0".parse::<LedgerPersistPolicy>().is_err());
+        assert!("sometimes".parse::<LedgerPersistPolicy>().is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::{
+    audit::{LedgerAuditId, LedgerAuditReport},
     read::{LedgerReadId, LedgerReadResponse},
     write::LedgerWriteResponse,
 };
@@ -9,6 +10,11 @@ use super::{
 pub enum LedgerEvent {
     Write(LedgerWriteResponse),
     Read(LedgerReadId, LedgerReadResponse),
+    Audit(LedgerAuditId, Result<LedgerAuditReport, String>),
+    RootSnapshotWritten {
+        height: u32,
+        result: Result<(), String>,
+    },
 }
 
 impl std::fmt::Display for LedgerEvent {
@@ -48,6 +54,12 @@ impl std::fmt::Display for LedgerEvent {
             Self::Read(id, resp) => {
                 write!(f, "Read, {:?}, {id}", resp.kind())
             }
+            Self::Audit(id, result) => {
+                write!(f, "Audit, {id}, {}", res_kind_str(result))
+            }
+            Self::RootSnapshotWritten { height, result } => {
+                write!(f, "RootSnapshotWritten, {height}, {}", res_kind_str(result))
+            }
         }
     }
 }
@@ -0,0 +1,190 @@
+use std::str::FromStr;
+
+use ledger::{Account, FpExt};
+use mina_p2p_messages::v2::TokenIdKeyHash;
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+
+/// Format of the exported ledger accounts, one account per line.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum LedgerExportFormat {
+    /// Comma separated values, with the header line.
+    #[default]
+    Csv,
+    /// JSON object per line.
+    JsonLines,
+}
+
+impl LedgerExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::JsonLines => "application/jsonl",
+        }
+    }
+}
+
+impl FromStr for LedgerExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "jsonl" => Ok(Self::JsonLines),
+            _ => Err(format!("unknown format `{s}`, expected `csv` or `jsonl`")),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerExportField {
+    PublicKey,
+    TokenId,
+    /// In nanomina.
+    Balance,
+    Nonce,
+    Delegate,
+    /// Decimal hash of the zkApp state of the account, empty if the
+    /// account isn't a zkApp.
+    ZkappStateHash,
+}
+
+impl LedgerExportField {
+    pub const ALL: [Self; 6] = [
+        Self::PublicKey,
+        Self::TokenId,
+        Self::Balance,
+        Self::Nonce,
+        Self::Delegate,
+        Self::ZkappStateHash,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PublicKey => "public_key",
+            Self::TokenId => "token_id",
+            Self::Balance => "balance",
+            Self::Nonce => "nonce",
+            Self::Delegate => "delegate",
+            Self::ZkappStateHash => "zkapp_state_hash",
+        }
+    }
+
+    fn value(&self, account: &Account) -> Option<String> {
+        Some(match self {
+            Self::PublicKey => AccountPublicKey::from(account.public_key.clone()).to_string(),
+            Self::TokenId => TokenIdKeyHash::from(account.token_id.clone()).to_string(),
+            Self::Balance => account.balance.as_u64().to_string(),
+            Self::Nonce => account.nonce.as_u32().to_string(),
+            Self::Delegate => AccountPublicKey::from(account.delegate.clone()?).to_string(),
+            Self::ZkappStateHash => account.zkapp.as_ref()?.hash().to_decimal(),
+        })
+    }
+}
+
+impl FromStr for LedgerExportField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.name() == s)
+            .ok_or_else(|| format!("unknown field `{s}`"))
+    }
+}
+
+/// Formats the accounts for the export, see [`LedgerExportFormat`].
+#[derive(Debug, Clone)]
+pub struct LedgerExportWriter {
+    format: LedgerExportFormat,
+    fields: Vec<LedgerExportField>,
+}
+
+impl LedgerExportWriter {
+    /// All the fields are exported if `fields` is empty.
+    pub fn new(format: LedgerExportFormat, mut fields: Vec<LedgerExportField>) -> Self {
+        if fields.is_empty() {
+            fields = LedgerExportField::ALL.to_vec();
+        }
+        Self { format, fields }
+    }
+
+    pub fn format(&self) -> LedgerExportFormat {
+        self.format
+    }
+
+    /// Line written before the accounts, if the format has one.
+    pub fn header(&self) -> Option<String> {
+        match self.format {
+            LedgerExportFormat::Csv => {
+                let names = self.fields.iter().map(LedgerExportField::name);
+                Some(names.collect::<Vec<_>>().join(",") + "\n")
+            }
+            LedgerExportFormat::JsonLines => None,
+        }
+    }
+
+    /// Appends the line for the `account` to the `out`.
+    pub fn write(&self, account: &Account, out: &mut String) {
+        let values = self.fields.iter().map(|field| field.value(account));
+        match self.format {
+            LedgerExportFormat::Csv => {
+                // Values are base58 or decimal, so they don't need escaping.
+                let values = values.map(Option::unwrap_or_default);
+                out.push_str(&values.collect::<Vec<_>>().join(","));
+            }
+            LedgerExportFormat::JsonLines => {
+                let object = self
+                    .fields
+                    .iter()
+                    .zip(values)
+                    .map(|(field, value)| (field.name().to_owned(), value.into()))
+                    .collect::<serde_json::Map<_, _>>();
+                out.push_str(&serde_json::Value::Object(object).to_string());
+            }
+        }
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_csv_and_json_lines() {
+        let public_key: AccountPublicKey =
+            "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg"
+                .parse()
+                .unwrap();
+        let account = Account::create_with(
+            ledger::AccountId::new_with_default_token(public_key.clone().try_into().unwrap()),
+            ledger::scan_state::currency::Balance::from_u64(1_000),
+        );
+        let fields = vec![
+            LedgerExportField::PublicKey,
+            LedgerExportField::Balance,
+            LedgerExportField::ZkappStateHash,
+        ];
+
+        let csv = LedgerExportWriter::new(LedgerExportFormat::Csv, fields.clone());
+        let mut out = csv.header().unwrap();
+        csv.write(&account, &mut out);
+        assert_eq!(
+            out,
+            format!("public_key,balance,zkapp_state_hash\n{public_key},1000,\n")
+        );
+
+        let jsonl = LedgerExportWriter::new(LedgerExportFormat::JsonLines, fields);
+        assert_eq!(jsonl.header(), None);
+        let mut out = String::new();
+        jsonl.write(&account, &mut out);
+        let line: serde_json::Value = serde_json::from_str(out.trim_end()).unwrap();
+        assert_eq!(line["public_key"], public_key.to_string());
+        assert_eq!(line["balance"], "1000");
+        assert!(line["zkapp_state_hash"].is_null());
+    }
+}
@@ -1,4 +1,5 @@
 use super::{
+    audit::LedgerAuditId,
     read::{LedgerReadId, LedgerReadRequest, LedgerReadResponse, LedgerStatus},
     write::{LedgerWriteRequest, LedgerWriteResponse},
     LedgerCtx, LedgerService,
@@ -13,6 +14,7 @@ use ledger::{
 };
 use mina_p2p_messages::v2::{self, LedgerHash, MinaBaseAccountBinableArgStableV2};
 use mina_signer::CompressedPubKey;
+use openmina_core::profiler::{profiler_service_scope, ProfilerService};
 use openmina_core::{channels::mpsc, thread};
 use std::collections::BTreeMap;
 
@@ -61,6 +63,10 @@ pub(super) enum LedgerRequest {
         staged_ledger_hash: LedgerHash,
         result: Result<StagedLedger, String>,
     },
+    Audit {
+        id: LedgerAuditId,
+        staged_ledger_hash: v2::MinaBaseStagedLedgerHashStableV1,
+    }, // expected response: Success
 }
 
 #[derive(Debug)]
@@ -134,6 +140,8 @@ impl LedgerRequest {
                     completed_snarks,
                     supercharge_coinbase,
                     transactions_by_fee,
+                    time_budget,
+                    external_diff,
                 } => {
                     let pred_block_hash = pred_block.hash().clone();
                     let global_slot_since_genesis = global_slot.clone();
@@ -147,6 +155,8 @@ impl LedgerRequest {
                         completed_snarks,
                         supercharge_coinbase,
                         transactions_by_fee,
+                        time_budget,
+                        external_diff,
                     );
                     LedgerWriteResponse::StagedLedgerDiffCreate {
                         pred_block_hash,
@@ -172,6 +182,7 @@ impl LedgerRequest {
                     ledgers_to_keep,
                     root_snarked_ledger_updates,
                     needed_protocol_states,
+                    root_snapshot_protocol_states,
                     new_root,
                     new_best_tip,
                 } => {
@@ -180,6 +191,7 @@ impl LedgerRequest {
                         ledgers_to_keep,
                         root_snarked_ledger_updates,
                         needed_protocol_states,
+                        root_snapshot_protocol_states,
                         &new_root,
                         &new_best_tip,
                     );
@@ -253,6 +265,10 @@ impl LedgerRequest {
 
                         LedgerReadResponse::AccountsForRpc(rpc_id, res, account_query)
                     }
+                    LedgerReadRequest::AccountsPageForRpc(rpc_id, ledger_hash, offset, limits) => {
+                        let res = ledger_ctx.get_accounts_page_for_rpc(ledger_hash, offset, limits);
+                        LedgerReadResponse::AccountsPageForRpc(rpc_id, res)
+                    }
                     LedgerReadRequest::GetLedgerStatus(rpc_id, ledger_hash) => {
                         let res = ledger_ctx.get_num_accounts(ledger_hash).map(
                             |(num_accounts, ledger_hash)| LedgerStatus {
@@ -263,10 +279,27 @@ impl LedgerRequest {
 
                         LedgerReadResponse::GetLedgerStatus(rpc_id, res)
                     }
+                    LedgerReadRequest::GetLedgerStatusExtended(
+                        rpc_id,
+                        ledger_hash,
+                        staged_ledger_hash,
+                    ) => {
+                        let res =
+                            ledger_ctx.get_ledger_status_extended(ledger_hash, &staged_ledger_hash);
+                        LedgerReadResponse::GetLedgerStatusExtended(rpc_id, res)
+                    }
+                    LedgerReadRequest::VrfVerify(rpc_id, claim, epoch_data) => {
+                        let res = ledger_ctx.vrf_verify(claim, epoch_data);
+                        LedgerReadResponse::VrfVerify(rpc_id, res)
+                    }
                     LedgerReadRequest::GetAccountDelegators(rpc_id, ledger_hash, account_id) => {
                         let res = ledger_ctx.get_account_delegators(&ledger_hash, &account_id);
                         LedgerReadResponse::GetAccountDelegators(rpc_id, res)
                     }
+                    LedgerReadRequest::Session(rpc_id, req) => {
+                        let res = ledger_ctx.session_request(req);
+                        LedgerReadResponse::Session(rpc_id, res)
+                    }
                 },
             ),
             LedgerRequest::AccountsSet {
@@ -298,9 +331,16 @@ impl LedgerRequest {
                 target_snarked_ledger_hash,
                 overwrite,
             } => {
+                // Ledger restored from the root snapshot is the best
+                // origin, when there are no ledgers from the previous sync.
                 let origin_snarked_ledger_hash = origin_snarked_ledger_hash
                     .iter()
                     .find(|hash| ledger_ctx.contains_snarked_ledger(hash))
+                    .or_else(|| {
+                        ledger_ctx
+                            .root_restored_snarked_ledger_hash()
+                            .filter(|hash| ledger_ctx.contains_snarked_ledger(hash))
+                    })
                     .unwrap_or_else(|| {
                         origin_snarked_ledger_hash
                             .first()
@@ -352,6 +392,13 @@ impl LedgerRequest {
                 let res = ledger_ctx.get_accounts(ledger_hash, account_ids);
                 LedgerResponse::AccountsGet(Ok(res))
             }
+            LedgerRequest::Audit {
+                id,
+                staged_ledger_hash,
+            } => {
+                ledger_ctx.audit(id, &staged_ledger_hash);
+                LedgerResponse::Success
+            }
         }
     }
 }
@@ -378,6 +425,7 @@ impl LedgerManager {
         let ledger_manager_loop = move || {
             while let Some(msg) = receiver.blocking_recv() {
                 let LedgerRequestWithChan { request, responder } = msg.0;
+                let _profiler_scope = profiler_service_scope(ProfilerService::Ledger);
                 let response = request.handle(&mut ledger_ctx, &ledger_caller, responder.is_some());
                 match (response, responder) {
                     (LedgerResponse::Write(resp), None) => {
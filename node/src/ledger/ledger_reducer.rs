@@ -1,8 +1,9 @@
 use crate::Substate;
 
 use super::{
+    audit::LedgerAuditState,
     read::LedgerReadState,
-    write::{LedgerWriteAction, LedgerWriteResponse, LedgerWriteState},
+    write::{LedgerWriteAction, LedgerWriteRequest, LedgerWriteResponse, LedgerWriteState},
     LedgerAction, LedgerActionWithMetaRef, LedgerState,
 };
 
@@ -12,6 +13,24 @@ impl LedgerState {
 
         match action {
             LedgerAction::Write(action) => {
+                if let LedgerWriteAction::Init {
+                    request:
+                        LedgerWriteRequest::Commit {
+                            root_snapshot_protocol_states,
+                            new_root,
+                            ..
+                        },
+                    ..
+                } = action
+                {
+                    if let Ok(state) = state_context.get_substate_mut() {
+                        state.root_committed(
+                            meta.time(),
+                            new_root.height(),
+                            root_snapshot_protocol_states.is_some(),
+                        );
+                    }
+                }
                 if let LedgerWriteAction::Success {
                     response: LedgerWriteResponse::Commit { result, .. },
                 } = action
@@ -39,6 +58,15 @@ impl LedgerState {
                 Substate::from_compatible_substate(state_context),
                 meta.with_action(action),
             ),
+            LedgerAction::Audit(action) => LedgerAuditState::reducer(
+                Substate::from_compatible_substate(state_context),
+                meta.with_action(action),
+            ),
+            LedgerAction::RootSnapshotWritten { height, result } => {
+                if let Ok(state) = state_context.get_substate_mut() {
+                    state.root_snapshot_written(*height, result);
+                }
+            }
         }
     }
 }
@@ -0,0 +1,190 @@
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+
+use ledger::{staged_ledger::staged_ledger::StagedLedger, Account};
+use mina_p2p_messages::{
+    binprot::{
+        self,
+        macros::{BinProtRead, BinProtWrite},
+        BinProtRead, BinProtWrite,
+    },
+    v2::{LedgerHash, MinaStateProtocolStateValueStableV2},
+};
+
+use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
+
+/// Name of the snapshot file in the work dir.
+pub const LEDGER_ROOT_SNAPSHOT_FILE: &str = "root_snapshot.bin";
+
+/// By default, snapshot is written on every commit of a root whose height
+/// is a multiple of this value, instead of every commit, as writing the
+/// whole snarked ledger takes a while. See [`super::LedgerPersistPolicy`].
+pub const LEDGER_ROOT_SNAPSHOT_INTERVAL: u32 = 10;
+
+/// Bumped on every change of the snapshot format. Snapshots of other
+/// versions are ignored.
+const LEDGER_ROOT_SNAPSHOT_VERSION: u32 = 1;
+
+/// Transition frontier root persisted to the disk, so that after the
+/// restart, node doesn't need to sync the root ledgers from scratch.
+#[derive(BinProtRead, BinProtWrite, Debug)]
+pub struct LedgerRootSnapshot {
+    pub version: u32,
+    pub protocol_state: MinaStateProtocolStateValueStableV2,
+    pub staged_ledger_parts: StagedLedgerAuxAndPendingCoinbases,
+    pub snarked_ledger_accounts: Vec<Account>,
+}
+
+/// Root staged ledger reconstructed from the snapshot, kept until the
+/// transition frontier sync requests reconstruction from the same parts.
+pub(super) struct LedgerRootRestored {
+    pub snarked_ledger_hash: LedgerHash,
+    /// Binprot encoded parts, used to check if the staged ledger
+    /// requested by the sync is the restored one.
+    pub staged_ledger_parts: Vec<u8>,
+    pub staged_ledger: StagedLedger,
+}
+
+impl LedgerRootSnapshot {
+    pub fn new(
+        protocol_state: MinaStateProtocolStateValueStableV2,
+        staged_ledger_parts: StagedLedgerAuxAndPendingCoinbases,
+        snarked_ledger_accounts: Vec<Account>,
+    ) -> Self {
+        Self {
+            version: LEDGER_ROOT_SNAPSHOT_VERSION,
+            protocol_state,
+            staged_ledger_parts,
+            snarked_ledger_accounts,
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let file = fs::File::open(path).map_err(|err| err.to_string())?;
+        let snapshot =
+            Self::binprot_read(&mut BufReader::new(file)).map_err(|err| err.to_string())?;
+        if snapshot.version != LEDGER_ROOT_SNAPSHOT_VERSION {
+            return Err(format!(
+                "unsupported version {}, expected {LEDGER_ROOT_SNAPSHOT_VERSION}",
+                snapshot.version
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot to a temporary file first and then renames it,
+    /// so that the previous snapshot survives a crash in the middle. The
+    /// temporary file is unique, as the snapshots of the consecutive roots
+    /// may be written by the overlapping threads.
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        openmina_core::fs::write_atomic(path, |writer| self.binprot_write(writer))
+    }
+
+    pub fn snarked_ledger_hash(&self) -> &LedgerHash {
+        &self
+            .protocol_state
+            .body
+            .blockchain_state
+            .ledger_proof_statement
+            .target
+            .first_pass_ledger
+    }
+}
+
+impl LedgerRootRestored {
+    pub fn new(
+        snarked_ledger_hash: LedgerHash,
+        staged_ledger_parts: &StagedLedgerAuxAndPendingCoinbases,
+        staged_ledger: StagedLedger,
+    ) -> std::io::Result<Self> {
+        let mut encoded = Vec::new();
+        staged_ledger_parts.binprot_write(&mut encoded)?;
+        Ok(Self {
+            snarked_ledger_hash,
+            staged_ledger_parts: encoded,
+            staged_ledger,
+        })
+    }
+
+    pub fn matches(
+        &self,
+        snarked_ledger_hash: &LedgerHash,
+        parts: Option<&StagedLedgerAuxAndPendingCoinbases>,
+    ) -> bool {
+        let Some(parts) = parts else {
+            return false;
+        };
+        let mut encoded = Vec::with_capacity(self.staged_ledger_parts.len());
+        &self.snarked_ledger_hash == snarked_ledger_hash
+            && parts.binprot_write(&mut encoded).is_ok()
+            && encoded == self.staged_ledger_parts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openmina_core::block::fixtures;
+
+    use super::*;
+
+    fn fixture_snapshot(accounts: usize) -> LedgerRootSnapshot {
+        let block = fixtures::best_tip();
+        let response: serde_json::Value = serde_json::from_slice(include_bytes!(
+            "../../../p2p/tests/files/rpc/staged_ledger_aux_and_pending_coinbases_at_block_response.json"
+        ))
+        .unwrap();
+        let parts =
+            serde_json::from_value(response["StagedLedgerAuxAndPendingCoinbasesAtBlock"].clone())
+                .unwrap();
+        LedgerRootSnapshot::new(
+            block.header.protocol_state,
+            parts,
+            (0..accounts).map(|_| Account::rand()).collect(),
+        )
+    }
+
+    fn encode(snapshot: &LedgerRootSnapshot) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        snapshot.binprot_write(&mut encoded).unwrap();
+        encoded
+    }
+
+    #[test]
+    fn test_ledger_root_snapshot_roundtrip() {
+        let dir =
+            std::env::temp_dir().join(format!("openmina-root-snapshot-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LEDGER_ROOT_SNAPSHOT_FILE);
+
+        let snapshot = fixture_snapshot(3);
+        snapshot.write(&path).unwrap();
+        let read = LedgerRootSnapshot::read(&path).unwrap();
+        assert_eq!(read.snarked_ledger_hash(), snapshot.snarked_ledger_hash());
+        assert_eq!(
+            read.snarked_ledger_accounts,
+            snapshot.snarked_ledger_accounts
+        );
+        assert_eq!(encode(&read), encode(&snapshot));
+
+        // Overlapping writers of the consecutive roots, the last one to
+        // rename wins as a whole and no temporary files are left.
+        let snapshots = (1..=4).map(fixture_snapshot).collect::<Vec<_>>();
+        std::thread::scope(|s| {
+            for snapshot in &snapshots {
+                let path = &path;
+                s.spawn(move || snapshot.write(path).unwrap());
+            }
+        });
+        let read = encode(&LedgerRootSnapshot::read(&path).unwrap());
+        assert!(snapshots.iter().any(|snapshot| encode(snapshot) == read));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let mut other_version = fixture_snapshot(1);
+        other_version.version = LEDGER_ROOT_SNAPSHOT_VERSION.saturating_add(1);
+        other_version.write(&path).unwrap();
+        assert!(LedgerRootSnapshot::read(&path).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,11 +1,19 @@
 use super::{
+    audit::{LedgerAuditId, LedgerAuditInput},
     ledger_empty_hash_at_depth,
-    read::{LedgerReadId, LedgerReadRequest, LedgerReadResponse},
+    ledger_root_snapshot::{LedgerRootRestored, LedgerRootSnapshot},
+    ledger_sessions::LedgerSessions,
+    read::{
+        LedgerPendingCoinbaseSummary, LedgerReadId, LedgerReadRequest, LedgerReadResponse,
+        LedgerScanStateJobCounts, LedgerSessionRequest, LedgerSessionResponse, LedgerStatus,
+        LedgerStatusExtended,
+    },
     write::{CommitResult, LedgerWriteRequest, LedgerWriteResponse, LedgersToKeep},
     LedgerAddress, LedgerEvent, LEDGER_DEPTH,
 };
 use crate::{
     account::AccountPublicKey,
+    block_producer::{external_diff_precheck, BlockAssemblyTimeBudget},
     block_producer_effectful::StagedLedgerDiffCreateOutput,
     ledger::{
         ledger_manager::{LedgerManager, LedgerRequest},
@@ -13,15 +21,21 @@ use crate::{
     },
     p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases,
     rpc::{
-        RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryScanStateJob,
-        RpcScanStateSummaryScanStateJobKind, RpcSnarkPoolJobSnarkWorkDone,
+        AccountSlim, RpcContinuationToken, RpcResponseLimits, RpcScanStateSummaryBlockTransaction,
+        RpcScanStateSummaryScanStateJob, RpcScanStateSummaryScanStateJobKind,
+        RpcSnarkPoolJobSnarkWorkDone, RpcVrfEpochData, RpcVrfVerification, RpcVrfVerifyClaim,
+        RpcVrfVerifyDelegator, RpcVrfVerifyResponse,
     },
     transition_frontier::{
         genesis::empty_pending_coinbase_hash,
         sync::{
-            ledger::staged::StagedLedgerAuxAndPendingCoinbasesValid,
+            ledger::staged::{
+                StagedLedgerAuxAndPendingCoinbasesValid,
+                StagedLedgerAuxAndPendingCoinbasesValidated,
+            },
             TransitionFrontierRootSnarkedLedgerUpdates,
         },
+        InternalCommand,
     },
 };
 use ark_ff::fields::arithmetic::InvalidBigInt;
@@ -70,8 +84,9 @@ use openmina_core::{
 };
 use std::{
     collections::{BTreeMap, BTreeSet},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 fn merkle_root(mask: &mut Mask) -> LedgerHash {
@@ -121,6 +136,13 @@ impl StagedLedgersStorage {
             .map(|staged_ledger| staged_ledger.ledger())
     }
 
+    /// Whether a staged ledger with the `root_hash` is kept by the commit.
+    fn is_kept(&self, root_hash: &LedgerHash, ledgers_to_keep: &LedgersToKeep) -> bool {
+        self.by_merkle_root_hash
+            .get(root_hash)
+            .is_some_and(|hashes| hashes.iter().any(|hash| ledgers_to_keep.contains(&**hash)))
+    }
+
     fn get(&self, staged_ledger_hash: &MinaBaseStagedLedgerHashStableV1) -> Option<&StagedLedger> {
         self.staged_ledgers.get(staged_ledger_hash)
     }
@@ -179,6 +201,12 @@ pub struct LedgerCtx {
     archive_mode: bool,
     event_sender:
         Option<openmina_core::channels::mpsc::UnboundedSender<crate::event_source::Event>>,
+    /// Where the transition frontier root is persisted, if enabled.
+    root_snapshot_path: Option<PathBuf>,
+    /// Root restored from the snapshot at startup, until it is used by
+    /// the transition frontier sync.
+    root_restored: Option<LedgerRootRestored>,
+    sessions: LedgerSessions,
 }
 
 #[derive(Default)]
@@ -235,6 +263,227 @@ impl LedgerCtx {
         self.event_sender = Some(event_sender);
     }
 
+    /// Enables persisting of the transition frontier root to the `path`
+    /// and restores the root from the existing snapshot, so that sync
+    /// doesn't need to fetch the root ledgers from peers after restart.
+    pub fn set_root_snapshot_path(&mut self, path: PathBuf) {
+        if path.exists() {
+            match LedgerRootSnapshot::read(&path).and_then(|s| self.root_snapshot_restore(s)) {
+                Ok(snarked_ledger_hash) => {
+                    openmina_core::info!(openmina_core::log::system_time();
+                        kind = "LedgerService::root_snapshot_restore",
+                        summary = format!("restored root ledgers from {}", path.display()),
+                        snarked_ledger_hash = snarked_ledger_hash.to_string(),
+                    );
+                }
+                Err(err) => {
+                    openmina_core::warn!(openmina_core::log::system_time();
+                        kind = "LedgerService::root_snapshot_restore",
+                        summary = format!("ignoring root snapshot {}", path.display()),
+                        error = err,
+                    );
+                }
+            }
+        }
+        self.root_snapshot_path = Some(path);
+    }
+
+    /// Rebuilds the snarked and staged ledgers of the snapshot, after
+    /// checking them against the hashes in the root's protocol state.
+    fn root_snapshot_restore(
+        &mut self,
+        snapshot: LedgerRootSnapshot,
+    ) -> Result<LedgerHash, String> {
+        let snarked_ledger_hash = snapshot.snarked_ledger_hash().clone();
+        let staged_ledger_hash = &snapshot
+            .protocol_state
+            .body
+            .blockchain_state
+            .staged_ledger_hash;
+
+        let mut mask = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+        for account in snapshot.snarked_ledger_accounts {
+            mask.get_or_create_account(account.id(), account)
+                .map_err(|err| format!("failed to add account: {err:?}"))?;
+        }
+        let calculated = merkle_root(&mut mask);
+        if calculated != snarked_ledger_hash {
+            return Err(format!(
+                "snarked ledger hash mismatch, expected: {snarked_ledger_hash}, calculated: {calculated}"
+            ));
+        }
+
+        let parts = Arc::new(snapshot.staged_ledger_parts);
+        if !StagedLedgerAuxAndPendingCoinbasesValidated::validate(&parts, staged_ledger_hash)
+            .is_valid()
+        {
+            return Err("staged ledger parts don't match the staged ledger hash".to_owned());
+        }
+        let (_, staged_ledger) = staged_ledger_reconstruct(
+            mask.copy(),
+            snarked_ledger_hash.clone(),
+            Some(parts.clone()),
+        )
+        .map_err(error_to_string)?;
+        let staged_ledger = staged_ledger?;
+
+        self.root_restored = Some(
+            LedgerRootRestored::new(snarked_ledger_hash.clone(), &parts, staged_ledger)
+                .map_err(|err| err.to_string())?,
+        );
+        self.snarked_ledgers
+            .insert(snarked_ledger_hash.clone(), mask);
+        Ok(snarked_ledger_hash)
+    }
+
+    /// Hash of the snarked ledger restored from the root snapshot.
+    pub fn root_restored_snarked_ledger_hash(&self) -> Option<&LedgerHash> {
+        self.root_restored
+            .as_ref()
+            .map(|root| &root.snarked_ledger_hash)
+    }
+
+    /// Takes the staged ledger restored from the root snapshot, if the
+    /// reconstruction is requested for the same root.
+    fn root_restored_staged_ledger_take(
+        &mut self,
+        snarked_ledger_hash: &LedgerHash,
+        parts: Option<&StagedLedgerAuxAndPendingCoinbasesValid>,
+    ) -> Option<StagedLedger> {
+        if !self
+            .root_restored
+            .as_ref()?
+            .matches(snarked_ledger_hash, parts)
+        {
+            return None;
+        }
+        self.root_restored.take().map(|root| root.staged_ledger)
+    }
+
+    /// Writes the root snapshot in a separate thread, reporting the result
+    /// with [`LedgerEvent::RootSnapshotWritten`].
+    fn root_snapshot_save(
+        &mut self,
+        new_root: &ArcBlockWithHash,
+        protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) {
+        let height = new_root.height();
+        let written = |result| LedgerEvent::RootSnapshotWritten { height, result };
+        let Some(path) = self.root_snapshot_path.clone() else {
+            self.send_event(written(Err("root snapshot is disabled".to_owned())));
+            return;
+        };
+        let Some(parts) = self.staged_ledger_aux_and_pending_coinbase(
+            new_root.staged_ledger_hashes(),
+            protocol_states,
+        ) else {
+            openmina_core::warn!(openmina_core::log::system_time();
+                kind = "LedgerService::root_snapshot_save",
+                summary = "root staged ledger or its protocol states are missing",
+                root = new_root.hash().to_string(),
+            );
+            self.send_event(written(Err("root staged ledger is missing".to_owned())));
+            return;
+        };
+        let Some((mask, _)) = self.mask(new_root.snarked_ledger_hash()) else {
+            self.send_event(written(Err("root snarked ledger is missing".to_owned())));
+            return;
+        };
+        let mut accounts = Vec::new();
+        mask.iter(|account| accounts.push(account.clone()));
+
+        let snapshot = LedgerRootSnapshot::new(
+            new_root.header().protocol_state.clone(),
+            (*parts).clone(),
+            accounts,
+        );
+        let event_sender = self.event_sender.clone();
+        // Ledger service must not be blocked by the disk.
+        thread::Builder::new()
+            .name("ledger-root-snapshot".into())
+            .spawn(move || {
+                let result = snapshot.write(&path).map_err(|err| err.to_string());
+                if let Err(err) = &result {
+                    openmina_core::warn!(openmina_core::log::system_time();
+                        kind = "LedgerService::root_snapshot_save",
+                        summary = format!("failed to write root snapshot at height {height}"),
+                        error = err,
+                    );
+                }
+                if let Some(tx) = event_sender {
+                    let _ = tx.send(LedgerEvent::RootSnapshotWritten { height, result }.into());
+                }
+            })
+            .expect("Failed: ledger root snapshot thread");
+    }
+
+    /// Copies the staged ledger and collects the masks not referenced by
+    /// the service, then audits them in a separate thread, reporting the
+    /// result with [`LedgerEvent::Audit`].
+    pub fn audit(
+        &mut self,
+        id: LedgerAuditId,
+        staged_ledger_hash: &MinaBaseStagedLedgerHashStableV1,
+    ) {
+        let Some(staged_ledger) = self.staged_ledgers.get(staged_ledger_hash) else {
+            let err = format!(
+                "staged ledger with root {} not found",
+                staged_ledger_hash.non_snark.ledger_hash
+            );
+            self.send_event(LedgerEvent::Audit(id, Err(err)));
+            return;
+        };
+        let mask = staged_ledger.ledger();
+        let ledger_hash = staged_ledger_hash.non_snark.ledger_hash.clone();
+
+        // Collected before the ledger is copied, the copies would be
+        // reported as orphaned.
+        let (alive_masks, orphaned_masks) = self.orphaned_masks();
+        let input = LedgerAuditInput::new(ledger_hash, &mask, alive_masks, orphaned_masks);
+        let event_sender = self.event_sender.clone();
+        // Hashing the whole ledger takes a while, the ledger service
+        // must not be blocked by it.
+        let spawned = thread::Builder::new()
+            .name("ledger-audit".into())
+            .spawn(move || {
+                let report = input.run();
+                if let Some(tx) = event_sender {
+                    let _ = tx.send(LedgerEvent::Audit(id, Ok(report)).into());
+                }
+            });
+        if let Err(err) = spawned {
+            let err = format!("failed to spawn the ledger audit thread: {err}");
+            self.send_event(LedgerEvent::Audit(id, Err(err)));
+        }
+    }
+
+    /// Number of the alive masks and the ones among them not referenced
+    /// by the ledger service.
+    ///
+    /// Masks of the staged ledgers being reconstructed in the background
+    /// and, when multiple nodes run in the same process, masks of the
+    /// other nodes are reported too.
+    fn orphaned_masks(&self) -> (usize, Vec<String>) {
+        let mut alive: BTreeSet<_> = ::ledger::mask::alive_collect();
+        let alive_masks = alive.len();
+        let staged_ledgers = self
+            .staged_ledgers
+            .staged_ledgers
+            .values()
+            .chain(self.sync.staged_ledgers.staged_ledgers.values())
+            .map(|ledger| ledger.ledger_ref());
+        self.snarked_ledgers
+            .values()
+            .chain(self.additional_snarked_ledgers.values())
+            .chain(self.sync.snarked_ledgers.values())
+            .chain(staged_ledgers)
+            .chain(self.sessions.masks())
+            .for_each(|mask| {
+                alive.remove(&mask.get_uuid());
+            });
+        (alive_masks, alive.into_iter().collect())
+    }
+
     pub(super) fn send_event(&self, event: LedgerEvent) {
         if let Some(tx) = self.event_sender.as_ref() {
             let _ = tx.send(event.into());
@@ -294,6 +543,40 @@ impl LedgerCtx {
         }
     }
 
+    /// Accounts in the order of their index in the ledger, starting at the
+    /// `offset`, as many as fit into the `limits`. Only the returned accounts
+    /// are read, not the whole ledger.
+    ///
+    /// Returns `None` if the ledger isn't available (anymore).
+    pub fn get_accounts_page_for_rpc(
+        &self,
+        ledger_hash: LedgerHash,
+        offset: usize,
+        limits: RpcResponseLimits,
+    ) -> Option<(Vec<Account>, Option<RpcContinuationToken>)> {
+        let (mask, _) = self.mask(&ledger_hash)?;
+        let num_accounts = mask.num_accounts();
+        let mut accounts = Vec::new();
+        let mut size = 0;
+        let mut index = offset;
+        while index < num_accounts {
+            let Some(account) = mask.get_at_index(AccountIndex(index as u64)) else {
+                index = index.saturating_add(1);
+                continue;
+            };
+            if !limits.fits(&mut size, &AccountSlim::from((*account).clone())) {
+                break;
+            }
+            accounts.push(*account);
+            index = index.saturating_add(1);
+        }
+        let continuation = (index < num_accounts).then_some(RpcContinuationToken {
+            ledger_hash,
+            offset: index,
+        });
+        Some((accounts, continuation))
+    }
+
     // TODO(tizoc): explain when `is_synced` is `true` and when it is `false`. Also use something else than a boolean.
     /// Returns a tuple of `(mask, is_synced)` for a [Mask] with the specified `hash` if it exists or `None` otherwise.
     pub fn mask(&self, hash: &LedgerHash) -> Option<(Mask, bool)> {
@@ -575,6 +858,54 @@ impl LedgerCtx {
         Some(producers)
     }
 
+    /// Compares the block's vrf output with the threshold of the winner's
+    /// stake delegated to the producer in the staking ledger.
+    pub fn vrf_verify(
+        &self,
+        claim: RpcVrfVerifyClaim,
+        epoch_data: RpcVrfEpochData,
+    ) -> RpcVrfVerifyResponse {
+        let producers = self
+            .producers_with_delegates(&epoch_data.staking_ledger_hash, |pub_key| {
+                AccountPublicKey::from(pub_key.clone()) == claim.producer
+            })
+            .ok_or_else(|| {
+                format!(
+                    "staking ledger {} not found",
+                    epoch_data.staking_ledger_hash
+                )
+            })?;
+
+        let (index, public_key, stake) = producers
+            .into_values()
+            .flatten()
+            .find(|(_, pub_key, _)| pub_key == &claim.winner)
+            .ok_or_else(|| {
+                format!(
+                    "{} isn't delegating to {} in the staking ledger",
+                    claim.winner, claim.producer
+                )
+            })?;
+        let check = vrf::check_vrf_threshold(
+            &claim.vrf_output,
+            stake.into(),
+            epoch_data.total_currency.into(),
+        )
+        .map_err(|err| err.to_string())?;
+
+        Ok(RpcVrfVerification {
+            threshold_met: check.threshold_met,
+            claim,
+            epoch_data,
+            winner: RpcVrfVerifyDelegator {
+                index,
+                public_key,
+                stake,
+                check,
+            },
+        })
+    }
+
     pub fn child_hashes_get(
         &mut self,
         snarked_ledger_hash: LedgerHash,
@@ -617,6 +948,17 @@ impl LedgerCtx {
     where
         F: 'static + FnOnce(v2::LedgerHash, Result<StagedLedger, String>) + Send,
     {
+        if let Some(staged_ledger) =
+            self.root_restored_staged_ledger_take(&snarked_ledger_hash, parts.as_deref())
+        {
+            let staged_ledger_hash = parts.map(|p| p.staged_ledger_hash.clone());
+            callback(
+                staged_ledger_hash.unwrap_or(snarked_ledger_hash),
+                Ok(staged_ledger),
+            );
+            return Ok(());
+        }
+
         let snarked_ledger = self
             .sync
             .snarked_ledger_mut(snarked_ledger_hash.clone())?
@@ -642,12 +984,23 @@ impl LedgerCtx {
         snarked_ledger_hash: LedgerHash,
         parts: Option<Arc<StagedLedgerAuxAndPendingCoinbasesValid>>,
     ) -> Result<(v2::LedgerHash, Result<(), String>), InvalidBigInt> {
-        let snarked_ledger = self
-            .sync
-            .snarked_ledger_mut(snarked_ledger_hash.clone())?
-            .copy();
         let (staged_ledger_hash, result) =
-            staged_ledger_reconstruct(snarked_ledger, snarked_ledger_hash, parts)?;
+            match self.root_restored_staged_ledger_take(&snarked_ledger_hash, parts.as_deref()) {
+                Some(staged_ledger) => {
+                    let staged_ledger_hash = parts.map(|p| p.staged_ledger_hash.clone());
+                    (
+                        staged_ledger_hash.unwrap_or(snarked_ledger_hash),
+                        Ok(staged_ledger),
+                    )
+                }
+                None => {
+                    let snarked_ledger = self
+                        .sync
+                        .snarked_ledger_mut(snarked_ledger_hash.clone())?
+                        .copy();
+                    staged_ledger_reconstruct(snarked_ledger, snarked_ledger_hash, parts)?
+                }
+            };
         let result = match result {
             Err(err) => Err(err),
             Ok(staged_ledger) => {
@@ -738,6 +1091,25 @@ impl LedgerCtx {
             panic!("staged ledger hash mismatch. found: {ledger_hashes:#?}, expected: {expected_ledger_hashes:#?}");
         }
 
+        let accounts_touched = block
+            .body()
+            .transactions()
+            .filter_map(|tx| UserCommand::try_from(tx).ok())
+            .flat_map(|cmd| cmd.accounts_referenced())
+            .chain(std::iter::once(AccountId::new(
+                coinbase_receiver.clone(),
+                TokenId::default(),
+            )))
+            .chain(block.body().coinbase_fee_transfers_iter().filter_map(|cb| {
+                let receiver: CompressedPubKey = cb.receiver_pk.inner().try_into().ok()?;
+                Some(AccountId::new(receiver, TokenId::default()))
+            }))
+            .collect();
+
+        let internal_commands = InternalCommand::from_applied(
+            &staged_ledger.latest_block_internal_commands(pred_block.hash().to_field()?),
+        );
+
         let archive_data = if self.archive_mode {
             let senders = block
                 .body()
@@ -865,6 +1237,8 @@ impl LedgerCtx {
         Ok(BlockApplyResult {
             block,
             just_emitted_a_proof,
+            accounts_touched,
+            internal_commands,
             archive_data,
         })
     }
@@ -874,6 +1248,9 @@ impl LedgerCtx {
         ledgers_to_keep: LedgersToKeep,
         root_snarked_ledger_updates: TransitionFrontierRootSnarkedLedgerUpdates,
         needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+        root_snapshot_protocol_states: Option<
+            BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+        >,
         new_root: &ArcBlockWithHash,
         new_best_tip: &ArcBlockWithHash,
     ) -> CommitResult {
@@ -885,6 +1262,15 @@ impl LedgerCtx {
             new_root_next_epoch_ledger = new_root.next_epoch_ledger_hash().to_string(),
             new_root_snarked_ledger = new_root.snarked_ledger_hash().to_string(),
         );
+        let staged_ledgers = &self.staged_ledgers;
+        let sync_staged_ledgers = &self.sync.staged_ledgers;
+        let additional_snarked_ledgers = &self.additional_snarked_ledgers;
+        self.sessions.detach_all(|ledger_hash| {
+            ledgers_to_keep.contains(ledger_hash)
+                || staged_ledgers.is_kept(ledger_hash, &ledgers_to_keep)
+                || sync_staged_ledgers.is_kept(ledger_hash, &ledgers_to_keep)
+                || additional_snarked_ledgers.contains_key(ledger_hash)
+        });
         self.recreate_snarked_ledger(
             &root_snarked_ledger_updates,
             &needed_protocol_states,
@@ -959,11 +1345,17 @@ impl LedgerCtx {
         // TODO(tizoc): should this fail silently?
         let Some(new_root_ledger) = self.staged_ledgers.get_mut(new_root.staged_ledger_hashes())
         else {
+            self.sessions_attach();
             return Default::default();
         };
 
         // Make staged ledger mask new root.
         new_root_ledger.commit_and_reparent_to_root();
+        self.sessions_attach();
+
+        if let Some(protocol_states) = root_snapshot_protocol_states {
+            self.root_snapshot_save(new_root, protocol_states);
+        }
 
         let needed_protocol_states = self
             .staged_ledger_mut(new_root.staged_ledger_hashes())
@@ -996,6 +1388,50 @@ impl LedgerCtx {
         }
     }
 
+    fn sessions_attach(&mut self) {
+        let mut sessions = std::mem::take(&mut self.sessions);
+        sessions.attach_all(|ledger_hash| {
+            self.mask(ledger_hash)
+                .filter(|(_, is_synced)| *is_synced)
+                .map(|(mask, _)| mask)
+        });
+        self.sessions = sessions;
+    }
+
+    pub fn session_request(
+        &mut self,
+        req: LedgerSessionRequest,
+    ) -> Result<LedgerSessionResponse, String> {
+        let now = redux::Timestamp::global_now();
+        match req {
+            LedgerSessionRequest::Open { ledger_hash } => {
+                let (mask, _) = self
+                    .mask(&ledger_hash)
+                    .filter(|(_, is_synced)| *is_synced)
+                    .ok_or_else(|| format!("ledger {ledger_hash} not found"))?;
+                self.sessions
+                    .open(now, ledger_hash, &mask)
+                    .map(LedgerSessionResponse::Opened)
+            }
+            LedgerSessionRequest::AccountsGet { session_id, query } => self
+                .sessions
+                .accounts(now, session_id, &query)
+                .map(LedgerSessionResponse::Accounts),
+            LedgerSessionRequest::AccountsPage {
+                session_id,
+                offset,
+                limit,
+            } => self
+                .sessions
+                .accounts_page(now, session_id, offset, limit)
+                .map(LedgerSessionResponse::Accounts),
+            LedgerSessionRequest::Close { session_id } => self
+                .sessions
+                .close(session_id)
+                .map(|_| LedgerSessionResponse::Closed),
+        }
+    }
+
     #[allow(dead_code)]
     fn check_alive_masks(&mut self) {
         let mut alive: BTreeSet<_> = ::ledger::mask::alive_collect();
@@ -1043,6 +1479,52 @@ impl LedgerCtx {
         Some((num_accounts, hash))
     }
 
+    pub fn get_ledger_status_extended(
+        &mut self,
+        ledger_hash: v2::LedgerHash,
+        staged_ledger_hash: &MinaBaseStagedLedgerHashStableV1,
+    ) -> Option<LedgerStatusExtended> {
+        use ledger::scan_state::scan_state::JobValue;
+
+        let (num_accounts, best_tip_staged_ledger_hash) = self.get_num_accounts(ledger_hash)?;
+        let staged_ledger = self.staged_ledgers.get(staged_ledger_hash)?;
+
+        let mut scan_state = LedgerScanStateJobCounts::default();
+        for jobs in staged_ledger.scan_state().view() {
+            scan_state.trees = scan_state.trees.saturating_add(1);
+            for job in jobs {
+                let count = match &job.job {
+                    JobValue::Leaf(JobValueBase::Empty) | JobValue::Node(JobValueMerge::Empty) => {
+                        &mut scan_state.empty
+                    }
+                    JobValue::Node(JobValueMerge::Part(_)) => &mut scan_state.partial,
+                    JobValue::Leaf(JobValueBase::Full(job)) if job.state.is_done() => {
+                        &mut scan_state.done
+                    }
+                    JobValue::Node(JobValueMerge::Full(job)) if job.state.is_done() => {
+                        &mut scan_state.done
+                    }
+                    JobValue::Leaf(JobValueBase::Full(_))
+                    | JobValue::Node(JobValueMerge::Full(_)) => &mut scan_state.todo,
+                };
+                *count = count.saturating_add(1);
+            }
+        }
+
+        let pending_coinbase = staged_ledger.pending_coinbase_collection();
+        Some(LedgerStatusExtended {
+            status: LedgerStatus {
+                num_accounts,
+                best_tip_staged_ledger_hash,
+            },
+            scan_state,
+            pending_coinbase: LedgerPendingCoinbaseSummary {
+                stacks: pending_coinbase.stacks_count(),
+                depth: pending_coinbase.depth(),
+            },
+        })
+    }
+
     pub fn get_child_hashes(
         &mut self,
         ledger_hash: v2::LedgerHash,
@@ -1147,7 +1629,14 @@ impl LedgerCtx {
         completed_snarks: BTreeMap<SnarkJobId, Snark>,
         supercharge_coinbase: bool,
         transactions_by_fee: Vec<valid::UserCommand>,
+        time_budget: Duration,
+        external_diff: Option<Arc<v2::StagedLedgerDiffDiffStableV2>>,
     ) -> Result<StagedLedgerDiffCreateOutput, String> {
+        let started = redux::Instant::now();
+        let budget = time_budget;
+        let mut time_budget = BlockAssemblyTimeBudget::new(time_budget);
+        let transactions_count = transactions_by_fee.len();
+
         let mut staged_ledger = self
             .staged_ledger_mut(pred_block.staged_ledger_hashes())
             .ok_or_else(|| {
@@ -1166,53 +1655,96 @@ impl LedgerCtx {
         let protocol_state_view =
             protocol_state_view(&pred_block.header().protocol_state).map_err(error_to_string)?;
 
-        // TODO(binier): include `invalid_txns` in output.
-        let (pre_diff, _invalid_txns) = staged_ledger
-            .create_diff(
-                constraint_constants(),
-                (&global_slot_since_genesis).into(),
-                Some(true),
-                (&coinbase_receiver).try_into().map_err(error_to_string)?,
-                (),
-                &protocol_state_view,
-                transactions_by_fee,
-                |stmt| {
-                    let job_id = SnarkJobId::from(stmt);
-                    match completed_snarks.get(&job_id) {
-                        Some(snark) => snark.try_into().ok(),
-                        None => None,
-                    }
-                },
-                supercharge_coinbase,
-            )
-            .map_err(|err| format!("{err:?}"))?;
-
-        // TODO(binier): maybe here, check if block reward is above threshold.
-        // https://github.com/minaprotocol/mina/blob/b3d418a8c0ae4370738886c2b26f0ec7bdb49303/src/lib/block_producer/block_producer.ml#L222
-
         let pred_body_hash = pred_block
             .header()
             .protocol_state
             .body
             .try_hash()
             .map_err(error_to_string)?;
-        let diff = (&pre_diff).into();
+        let state_and_body_hash = (
+            pred_block.hash().0.to_field().map_err(error_to_string)?,
+            pred_body_hash.0.to_field().map_err(error_to_string)?,
+        );
+        let coinbase_receiver_pk: CompressedPubKey =
+            (&coinbase_receiver).try_into().map_err(error_to_string)?;
+
+        // Diff submitted with the block template rpc may only contain work
+        // and commands we already verified, so that only the cheap checks
+        // are done here. If it's rejected, we fall back to our own.
+        let external_diff = external_diff.map(|diff| -> Result<_, String> {
+            external_diff_precheck(&diff, &completed_snarks, &transactions_by_fee)?;
+            if started.elapsed() >= budget {
+                return Err("no time left to apply the diff".to_owned());
+            }
+            let witness: Diff = (&*diff).try_into().map_err(error_to_string)?;
+            let res = staged_ledger
+                .clone()
+                .apply(
+                    Some(SkipVerification::All),
+                    constraint_constants(),
+                    (&global_slot_since_genesis).into(),
+                    witness,
+                    (),
+                    &Verifier,
+                    &protocol_state_view,
+                    state_and_body_hash,
+                    coinbase_receiver_pk.clone(),
+                    supercharge_coinbase,
+                )
+                .map_err(|err| format!("{err:?}"))?;
+            Ok(((*diff).clone(), res))
+        });
+        let (external_applied, external_diff) = match external_diff {
+            Some(Ok(applied)) => (Some(applied), Some(Ok(()))),
+            Some(Err(err)) => (None, Some(Err(err))),
+            None => (None, None),
+        };
 
-        let res = staged_ledger
-            .apply_diff_unchecked(
-                constraint_constants(),
-                (&global_slot_since_genesis).into(),
-                pre_diff,
-                (),
-                &protocol_state_view,
-                (
-                    pred_block.hash().0.to_field().map_err(error_to_string)?,
-                    pred_body_hash.0.to_field().map_err(error_to_string)?,
-                ),
-                (&coinbase_receiver).try_into().map_err(error_to_string)?,
-                supercharge_coinbase,
-            )
-            .map_err(|err| format!("{err:?}"))?;
+        let (diff, res, truncated_commands) = match external_applied {
+            Some((diff, res)) => (diff, res, 0),
+            None => {
+                // TODO(binier): include `invalid_txns` in output.
+                let (pre_diff, _invalid_txns) = staged_ledger
+                    .create_diff_with_budget(
+                        constraint_constants(),
+                        (&global_slot_since_genesis).into(),
+                        Some(true),
+                        coinbase_receiver_pk.clone(),
+                        (),
+                        &protocol_state_view,
+                        transactions_by_fee,
+                        |stmt| {
+                            let job_id = SnarkJobId::from(stmt);
+                            match completed_snarks.get(&job_id) {
+                                Some(snark) => snark.try_into().ok(),
+                                None => None,
+                            }
+                        },
+                        supercharge_coinbase,
+                        |progress| time_budget.should_continue(started.elapsed(), progress),
+                    )
+                    .map_err(|err| format!("{err:?}"))?;
+                let truncated_commands = time_budget.dropped_commands(transactions_count);
+
+                // TODO(binier): maybe here, check if block reward is above threshold.
+                // https://github.com/minaprotocol/mina/blob/b3d418a8c0ae4370738886c2b26f0ec7bdb49303/src/lib/block_producer/block_producer.ml#L222
+
+                let diff = (&pre_diff).into();
+                let res = staged_ledger
+                    .apply_diff_unchecked(
+                        constraint_constants(),
+                        (&global_slot_since_genesis).into(),
+                        pre_diff,
+                        (),
+                        &protocol_state_view,
+                        state_and_body_hash,
+                        coinbase_receiver_pk,
+                        supercharge_coinbase,
+                    )
+                    .map_err(|err| format!("{err:?}"))?;
+                (diff, res, truncated_commands)
+            }
+        };
 
         let diff_hash = block_body_hash(&diff).map_err(|err| format!("{err:?}"))?;
         let staking_ledger_hash = if is_new_epoch {
@@ -1237,6 +1769,8 @@ impl LedgerCtx {
             stake_proof_sparse_ledger: self
                 .stake_proof_sparse_ledger(staking_ledger_hash, &producer, &delegator)
                 .map_err(error_to_string)?,
+            truncated_commands,
+            external_diff,
         })
     }
 
@@ -1505,6 +2039,17 @@ pub trait LedgerService: redux::Service {
             self.ledger_manager().call(request);
         }
     }
+
+    fn audit_init(
+        &mut self,
+        id: LedgerAuditId,
+        staged_ledger_hash: MinaBaseStagedLedgerHashStableV1,
+    ) {
+        self.ledger_manager().call(LedgerRequest::Audit {
+            id,
+            staged_ledger_hash,
+        });
+    }
 }
 
 /// Save reconstruction to file, when it fails.
@@ -1558,9 +2103,9 @@ fn dump_reconstruct_to_file(
     std::fs::create_dir_all(&debug_dir)?;
 
     use mina_p2p_messages::binprot::BinProtWrite;
-    let mut file = std::fs::File::create(&filename)?;
-    reconstruct_context.binprot_write(&mut file)?;
-    file.sync_all()?;
+    let mut buf = Vec::new();
+    reconstruct_context.binprot_write(&mut buf)?;
+    openmina_core::debug_dump::dump_write(&filename, buf)?;
 
     openmina_core::info!(
         openmina_core::log::system_time();
@@ -1617,11 +2162,10 @@ fn dump_application_to_file(
         .to_string();
     std::fs::create_dir_all(&debug_dir)?;
 
-    let mut file = std::fs::File::create(&filename)?;
-
     use mina_p2p_messages::binprot::BinProtWrite;
-    apply_context.binprot_write(&mut file)?;
-    file.sync_all()?;
+    let mut buf = Vec::new();
+    apply_context.binprot_write(&mut buf)?;
+    openmina_core::debug_dump::dump_write(&filename, buf)?;
 
     Ok(filename)
 }
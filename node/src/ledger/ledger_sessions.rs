@@ -0,0 +1,417 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use ledger::{Account, AccountId, AccountIndex, BaseLedger, Mask, UnregisterBehavior};
+use mina_hasher::Fp;
+use mina_p2p_messages::v2::LedgerHash;
+use mina_signer::CompressedPubKey;
+
+use super::read::{LedgerSessionId, LedgerSessionInfo};
+use crate::rpc::AccountQuery;
+
+/// Session is closed if it isn't used for this long.
+pub const LEDGER_SESSION_TTL: Duration = Duration::from_secs(60);
+/// Max number of the sessions open at the same time.
+pub const LEDGER_SESSIONS_MAX: usize = 64;
+/// Max number of the accounts returned for a single page.
+pub const LEDGER_SESSION_PAGE_MAX: u64 = 1000;
+
+/// Ids of the accounts of each public key in the ledger of a session,
+/// keyed by the `x` and `is_odd` of the compressed public key.
+type PublicKeyIndex = HashMap<(Fp, bool), Vec<AccountId>>;
+
+struct LedgerSession {
+    ledger_hash: LedgerHash,
+    /// Empty child of the ledger mask of the service, or a copy of the
+    /// ledger if the service dropped it. `None` while the masks of the
+    /// ledger service are being committed.
+    mask: Option<Mask>,
+    /// Whether the `mask` is a copy, not attached to the ledger service.
+    is_copy: bool,
+    /// Built on the first query by the public key, shared by the sessions
+    /// of the same ledger.
+    public_key_index: Option<Arc<PublicKeyIndex>>,
+    expires_at: redux::Timestamp,
+}
+
+/// Ledgers pinned by the RPC sessions.
+///
+/// Each session holds an empty child mask of the ledger it was opened
+/// for. Committing a ledger into its parent requires the mask to have no
+/// children, so the session masks are detached before each commit and
+/// attached again after it to the mask with the same hash. As session
+/// masks are never written to, no data is lost by that.
+///
+/// The ledger service drops the ledgers the transition frontier doesn't
+/// need anymore, so before such a commit, the sessions of these ledgers
+/// take a copy of them (one copy for all the sessions of a ledger) and
+/// keep it until they are closed. Sessions whose ledger unexpectedly
+/// isn't there after the commit are closed.
+#[derive(Default)]
+pub struct LedgerSessions {
+    next_id: LedgerSessionId,
+    sessions: BTreeMap<LedgerSessionId, LedgerSession>,
+}
+
+impl LedgerSessions {
+    pub fn open(
+        &mut self,
+        now: redux::Timestamp,
+        ledger_hash: LedgerHash,
+        ledger: &Mask,
+    ) -> Result<LedgerSessionInfo, String> {
+        self.prune_expired(now);
+        if self.sessions.len() >= LEDGER_SESSIONS_MAX {
+            return Err(format!(
+                "too many ledger sessions open (max: {LEDGER_SESSIONS_MAX})"
+            ));
+        }
+
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.sessions.insert(
+            id,
+            LedgerSession {
+                ledger_hash: ledger_hash.clone(),
+                mask: Some(ledger.make_child()),
+                is_copy: false,
+                public_key_index: None,
+                expires_at: now + LEDGER_SESSION_TTL,
+            },
+        );
+        Ok(LedgerSessionInfo {
+            id,
+            ledger_hash,
+            ttl_secs: LEDGER_SESSION_TTL.as_secs(),
+        })
+    }
+
+    /// Returns the mask of the session and extends its lifetime.
+    pub fn get(&mut self, now: redux::Timestamp, id: LedgerSessionId) -> Result<&Mask, String> {
+        self.prune_expired(now);
+        let session = self
+            .sessions
+            .get_mut(&id)
+            .ok_or_else(|| format!("ledger session {id} not found or expired"))?;
+        session.expires_at = now + LEDGER_SESSION_TTL;
+        session
+            .mask
+            .as_ref()
+            .ok_or_else(|| format!("ledger session {id} is not attached"))
+    }
+
+    /// Returns the accounts matching the `query` from the ledger of the
+    /// session.
+    ///
+    /// Accounts of a public key are looked up in an index of the ledger,
+    /// built by the first such query, so that the ledger is scanned only
+    /// once per session. Reading all the accounts at once isn't supported,
+    /// they are read by pages with [`Self::accounts_page`].
+    pub fn accounts(
+        &mut self,
+        now: redux::Timestamp,
+        id: LedgerSessionId,
+        query: &AccountQuery,
+    ) -> Result<Vec<Account>, String> {
+        self.get(now, id)?;
+        let ids = match query {
+            AccountQuery::All => {
+                return Err("all the accounts of a session are read by pages".to_owned())
+            }
+            AccountQuery::SinglePublicKey(public_key) => {
+                let Ok(public_key) = CompressedPubKey::try_from(public_key.clone()) else {
+                    return Ok(Vec::new());
+                };
+                let index = self.public_key_index(id)?;
+                let key = (public_key.x, public_key.is_odd);
+                index.get(&key).cloned().unwrap_or_default()
+            }
+            AccountQuery::PubKeyWithTokenId(public_key, token_id) => {
+                let Ok(public_key) = public_key.clone().try_into() else {
+                    return Ok(Vec::new());
+                };
+                vec![AccountId {
+                    public_key,
+                    token_id: token_id.clone().into(),
+                }]
+            }
+            AccountQuery::MultipleIds(ids) => ids.clone(),
+        };
+        Ok(ledger_get_accounts(self.get(now, id)?, &ids))
+    }
+
+    /// Returns at most `limit` accounts starting at the index `offset`.
+    pub fn accounts_page(
+        &mut self,
+        now: redux::Timestamp,
+        id: LedgerSessionId,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<Account>, String> {
+        let mask = self.get(now, id)?;
+        Ok(ledger_accounts_page(mask, offset, limit))
+    }
+
+    fn public_key_index(&mut self, id: LedgerSessionId) -> Result<Arc<PublicKeyIndex>, String> {
+        let session = self
+            .sessions
+            .get(&id)
+            .ok_or_else(|| format!("ledger session {id} not found or expired"))?;
+        if let Some(index) = &session.public_key_index {
+            return Ok(index.clone());
+        }
+        let shared = self
+            .sessions
+            .values()
+            .filter(|other| other.ledger_hash == session.ledger_hash)
+            .find_map(|other| other.public_key_index.clone());
+        let index = match shared {
+            Some(index) => index,
+            None => {
+                let mask = session
+                    .mask
+                    .as_ref()
+                    .ok_or_else(|| format!("ledger session {id} is not attached"))?;
+                let mut index = PublicKeyIndex::new();
+                mask.iter(|account| {
+                    index
+                        .entry((account.public_key.x, account.public_key.is_odd))
+                        .or_default()
+                        .push(account.id());
+                });
+                Arc::new(index)
+            }
+        };
+        if let Some(session) = self.sessions.get_mut(&id) {
+            session.public_key_index = Some(index.clone());
+        }
+        Ok(index)
+    }
+
+    pub fn close(&mut self, id: LedgerSessionId) -> Result<(), String> {
+        let session = self
+            .sessions
+            .remove(&id)
+            .ok_or_else(|| format!("ledger session {id} not found or expired"))?;
+        session.detach();
+        Ok(())
+    }
+
+    pub fn prune_expired(&mut self, now: redux::Timestamp) {
+        let expired = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.expires_at <= now)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in expired {
+            let _ = self.close(id);
+        }
+    }
+
+    /// Detaches the session masks from the ledgers, must be followed by
+    /// [`Self::attach_all`]. Sessions of the ledgers which won't be kept
+    /// by the commit, as told by `is_kept`, switch to a copy of the ledger.
+    pub fn detach_all<F>(&mut self, mut is_kept: F)
+    where
+        F: FnMut(&LedgerHash) -> bool,
+    {
+        let mut copies = BTreeMap::<LedgerHash, Mask>::new();
+        for session in self.sessions.values_mut() {
+            if session.is_copy {
+                continue;
+            }
+            let Some(mask) = session.mask.take() else {
+                continue;
+            };
+            if !is_kept(&session.ledger_hash) {
+                let copy = copies
+                    .entry(session.ledger_hash.clone())
+                    .or_insert_with(|| mask.deep_copy());
+                session.mask = Some(copy.clone());
+                session.is_copy = true;
+            }
+            mask.unregister_mask(UnregisterBehavior::Check);
+        }
+    }
+
+    /// Attaches the sessions to the ledgers returned by `get_ledger`,
+    /// closing the ones whose ledger isn't found.
+    pub fn attach_all<F>(&mut self, mut get_ledger: F)
+    where
+        F: FnMut(&LedgerHash) -> Option<Mask>,
+    {
+        self.sessions.retain(|_, session| {
+            if session.is_copy {
+                return true;
+            }
+            let Some(ledger) = get_ledger(&session.ledger_hash) else {
+                return false;
+            };
+            session.mask = Some(ledger.make_child());
+            true
+        });
+    }
+
+    pub fn masks(&self) -> impl Iterator<Item = &Mask> {
+        self.sessions
+            .values()
+            .filter_map(|session| session.mask.as_ref())
+    }
+}
+
+impl LedgerSession {
+    fn detach(self) {
+        match self.mask {
+            // The copy isn't registered anywhere, dropping it is enough.
+            Some(mask) if !self.is_copy => {
+                mask.unregister_mask(UnregisterBehavior::Check);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn ledger_get_accounts(mask: &Mask, ids: &[AccountId]) -> Vec<Account> {
+    let addrs = mask
+        .location_of_account_batch(ids)
+        .into_iter()
+        .filter_map(|(_id, addr)| addr)
+        .collect::<Vec<_>>();
+    mask.get_batch(&addrs)
+        .into_iter()
+        .filter_map(|(_, account)| account.map(|account| *account))
+        .collect()
+}
+
+fn ledger_accounts_page(mask: &Mask, offset: u64, limit: u64) -> Vec<Account> {
+    let num_accounts = mask.num_accounts() as u64;
+    let end = offset
+        .saturating_add(limit.min(LEDGER_SESSION_PAGE_MAX))
+        .min(num_accounts);
+    (offset..end)
+        .filter_map(|index| mask.get_at_index(AccountIndex(index)))
+        .map(|account| *account)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::AccountPublicKey;
+
+    fn ledger_hash() -> LedgerHash {
+        "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn sessions_expire_and_reattach() {
+        let ledger = Mask::create(crate::ledger::LEDGER_DEPTH);
+        let mut sessions = LedgerSessions::default();
+        let now = redux::Timestamp::new(1_000_000_000);
+
+        let first = sessions.open(now, ledger_hash(), &ledger).unwrap();
+        let second = sessions.open(now, ledger_hash(), &ledger).unwrap();
+        assert_ne!(first.id, second.id);
+
+        // Using the session extends its lifetime.
+        let later = now + LEDGER_SESSION_TTL / 2;
+        assert!(sessions.get(later, first.id).is_ok());
+        let expired = now + LEDGER_SESSION_TTL;
+        assert!(sessions.get(expired, first.id).is_ok());
+        assert!(sessions.get(expired, second.id).is_err());
+        assert_eq!(sessions.sessions.len(), 1);
+
+        sessions.detach_all(|_| true);
+        assert!(sessions.get(expired, first.id).is_err());
+        sessions.attach_all(|_| Some(ledger.clone()));
+        assert!(sessions.get(expired, first.id).is_ok());
+
+        sessions.close(first.id).unwrap();
+        assert!(sessions.close(first.id).is_err());
+        assert!(sessions.sessions.is_empty());
+    }
+
+    fn ledger_with_accounts(n: u64) -> (Mask, Vec<Account>) {
+        let mut ledger = Mask::create(crate::ledger::LEDGER_DEPTH);
+        let accounts = (0..n).map(|_| Account::rand()).collect::<Vec<_>>();
+        for account in &accounts {
+            ledger
+                .get_or_create_account(account.id(), account.clone())
+                .unwrap();
+        }
+        (ledger, accounts)
+    }
+
+    #[test]
+    fn sessions_keep_dropped_ledger() {
+        let (root, accounts) = ledger_with_accounts(3);
+        let ledger = root.make_child();
+        let mut sessions = LedgerSessions::default();
+        let now = redux::Timestamp::new(1_000_000_000);
+        let first = sessions.open(now, ledger_hash(), &ledger).unwrap();
+        let second = sessions.open(now, ledger_hash(), &ledger).unwrap();
+
+        // The ledger service drops the ledger in the commit.
+        sessions.detach_all(|_| false);
+        ledger.unregister_mask(UnregisterBehavior::Check);
+        sessions.attach_all(|_| None);
+
+        for session in [first.id, second.id] {
+            let query = AccountQuery::MultipleIds(vec![accounts[1].id()]);
+            let found = sessions.accounts(now, session, &query).unwrap();
+            assert_eq!(found, vec![accounts[1].clone()]);
+        }
+        // The sessions of the same ledger share the copy.
+        let [first_mask, second_mask] =
+            [first.id, second.id].map(|id| sessions.get(now, id).unwrap().get_uuid());
+        assert_eq!(first_mask, second_mask);
+
+        // The copy stays through the following commits.
+        sessions.detach_all(|_| false);
+        sessions.attach_all(|_| None);
+        let found = sessions.accounts_page(now, first.id, 0, 10).unwrap();
+        assert_eq!(found, accounts);
+
+        sessions.close(first.id).unwrap();
+        sessions.close(second.id).unwrap();
+    }
+
+    #[test]
+    fn session_accounts_by_public_key() {
+        let (ledger, accounts) = ledger_with_accounts(4);
+        let mut sessions = LedgerSessions::default();
+        let now = redux::Timestamp::new(1_000_000_000);
+        let session = sessions.open(now, ledger_hash(), &ledger).unwrap();
+        let other = sessions.open(now, ledger_hash(), &ledger).unwrap();
+
+        let all = sessions.accounts(now, session.id, &AccountQuery::All);
+        assert!(all.is_err());
+
+        for account in &accounts {
+            let public_key = AccountPublicKey::from(account.public_key.clone());
+            let query = AccountQuery::SinglePublicKey(public_key);
+            let found = sessions.accounts(now, session.id, &query).unwrap();
+            assert_eq!(found, vec![account.clone()]);
+        }
+        let unknown = AccountPublicKey::from(Account::rand().public_key);
+        let query = AccountQuery::SinglePublicKey(unknown);
+        assert!(sessions
+            .accounts(now, session.id, &query)
+            .unwrap()
+            .is_empty());
+
+        // The index is built once for the ledger.
+        let index = sessions.public_key_index(session.id).unwrap();
+        let other_index = sessions.public_key_index(other.id).unwrap();
+        assert!(Arc::ptr_eq(&index, &other_index));
+
+        sessions.close(session.id).unwrap();
+        sessions.close(other.id).unwrap();
+    }
+}
@@ -1,15 +1,118 @@
-use super::{read::LedgerReadState, write::LedgerWriteState, LedgerConfig};
+use super::{
+    audit::LedgerAuditState, read::LedgerReadState, write::LedgerWriteState, LedgerConfig,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct LedgerState {
+    #[serde(default)]
+    pub config: LedgerConfig,
     pub alive_masks: usize,
     pub write: LedgerWriteState,
     pub read: LedgerReadState,
+    /// Last commit, which persisted the root.
+    #[serde(default)]
+    pub root_persisted: Option<LedgerRootPersisted>,
+    /// Commit, whose root snapshot is being written.
+    #[serde(default)]
+    pub root_persist_pending: Option<LedgerRootPersisted>,
+    /// Commits since the last persisted one, kept only in memory.
+    #[serde(default)]
+    pub commits_since_root_persisted: u32,
+    #[serde(default)]
+    pub audit: LedgerAuditState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerRootPersisted {
+    pub time: redux::Timestamp,
+    pub height: u32,
+    /// Commits since the previously persisted one, including this one.
+    #[serde(default)]
+    pub commits: u32,
 }
 
 impl LedgerState {
-    pub fn new(_config: LedgerConfig) -> Self {
-        Self::default()
+    pub fn new(config: LedgerConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the next commit should persist the root, according to the
+    /// [`super::LedgerPersistPolicy`].
+    pub fn is_root_persist_due(&self, now: redux::Timestamp) -> bool {
+        self.root_persist_pending.is_none()
+            && self.config.persist_policy.is_due(
+                self.commits_since_root_persisted.saturating_add(1),
+                now,
+                self.root_persisted.as_ref().map(|persisted| persisted.time),
+            )
+    }
+
+    /// Called on every commit, `persist` is whether it persists the root.
+    pub fn root_committed(&mut self, time: redux::Timestamp, height: u32, persist: bool) {
+        self.commits_since_root_persisted = self.commits_since_root_persisted.saturating_add(1);
+        if persist {
+            self.root_persist_pending = Some(LedgerRootPersisted {
+                time,
+                height,
+                commits: self.commits_since_root_persisted,
+            });
+        }
+    }
+
+    /// Called once the snapshot of the root at `height` is written.
+    /// Commits since a failed one are persisted with the next commit.
+    pub fn root_snapshot_written(&mut self, height: u32, result: &Result<(), String>) {
+        let Some(pending) = self
+            .root_persist_pending
+            .take_if(|pending| pending.height == height)
+        else {
+            return;
+        };
+        if result.is_ok() {
+            self.commits_since_root_persisted = self
+                .commits_since_root_persisted
+                .saturating_sub(pending.commits);
+            self.root_persisted = Some(pending);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::LedgerPersistPolicy;
+
+    #[test]
+    fn test_root_persist_batching() {
+        let mut state = LedgerState::new(LedgerConfig {
+            persist_policy: LedgerPersistPolicy::EveryBlocks { blocks: 2 },
+        });
+        let now = redux::Timestamp::ZERO;
+
+        state.root_committed(now, 1, false);
+        assert!(state.is_root_persist_due(now));
+        state.root_committed(now, 2, true);
+
+        // Commits during the write are batched into the next one.
+        state.root_committed(now, 3, false);
+        state.root_committed(now, 4, false);
+        assert!(!state.is_root_persist_due(now));
+        assert!(state.root_persisted.is_none());
+
+        state.root_snapshot_written(2, &Ok(()));
+        assert_eq!(state.root_persisted.as_ref().map(|p| p.height), Some(2));
+        assert_eq!(state.commits_since_root_persisted, 2);
+        assert!(state.is_root_persist_due(now));
+
+        // Failed write keeps the commits to be persisted with the next one.
+        state.root_committed(now, 5, true);
+        state.root_snapshot_written(5, &Err("disk full".to_owned()));
+        assert_eq!(state.root_persisted.as_ref().map(|p| p.height), Some(2));
+        assert_eq!(state.commits_since_root_persisted, 3);
+        assert!(state.is_root_persist_due(now));
     }
 }
@@ -1,3 +1,4 @@
+pub mod audit;
 pub mod read;
 pub mod write;
 
@@ -19,6 +20,17 @@ mod ledger_reducer;
 mod ledger_service;
 pub use ledger_service::*;
 
+mod ledger_sessions;
+
+mod ledger_export;
+pub use ledger_export::*;
+pub use ledger_sessions::LEDGER_SESSION_PAGE_MAX;
+
+mod ledger_root_snapshot;
+pub use ledger_root_snapshot::{
+    LedgerRootSnapshot, LEDGER_ROOT_SNAPSHOT_FILE, LEDGER_ROOT_SNAPSHOT_INTERVAL,
+};
+
 pub mod ledger_manager;
 
 pub use ledger::AccountIndex as LedgerAccountIndex;
@@ -1,3 +1,4 @@
+use p2p::PeerId;
 use serde::{Deserialize, Serialize};
 
 use super::{
@@ -17,6 +18,7 @@ pub enum LedgerReadAction {
     Pending {
         id: LedgerReadId,
         request: LedgerReadRequest,
+        peer_id: Option<PeerId>,
     },
     Success {
         id: LedgerReadId,
@@ -25,13 +27,23 @@ pub enum LedgerReadAction {
     Prune {
         id: LedgerReadId,
     },
+    /// Peer reached its cost cap, its requests are deferred until its
+    /// pending ones are done.
+    PeerThrottled {
+        peer_id: PeerId,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for LedgerReadAction {
     fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
         match self {
             LedgerReadAction::FindTodos => state.ledger.read.is_total_cost_under_limit(),
-            LedgerReadAction::Init { .. } => state.ledger.read.is_total_cost_under_limit(),
+            LedgerReadAction::Init { callback, .. } => {
+                state.ledger.read.is_total_cost_under_limit()
+                    && callback
+                        .peer_id()
+                        .is_none_or(|peer_id| state.ledger.read.is_peer_cost_under_limit(&peer_id))
+            }
             LedgerReadAction::Pending { id, .. } => {
                 state.ledger.read.is_total_cost_under_limit()
                     && !state.ledger.read.contains(*id)
@@ -47,6 +59,10 @@ impl redux::EnablingCondition<crate::State> for LedgerReadAction {
                 state.ledger.read.get(*id),
                 Some(LedgerReadRequestState::Success { .. })
             ),
+            LedgerReadAction::PeerThrottled { peer_id } => {
+                !state.ledger.read.is_peer_cost_under_limit(peer_id)
+                    && !state.ledger.read.is_peer_throttled(peer_id)
+            }
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use mina_p2p_messages::v2;
 use openmina_core::{bug_condition, requests::RequestId};
 use p2p::{
@@ -10,8 +12,11 @@ use p2p::{
 use redux::Dispatcher;
 
 use crate::{
-    block_producer::vrf_evaluator::BlockProducerVrfEvaluatorAction,
-    ledger_effectful::LedgerEffectfulAction, Action, RpcAction, State, Substate,
+    account::AccountPublicKey,
+    block_producer::vrf_evaluator::{BlockProducerVrfEvaluatorAction, DelegatorTable},
+    ledger_effectful::LedgerEffectfulAction,
+    rpc::{AccountQuery, RpcRequest},
+    Action, RpcAction, State, Substate,
 };
 
 use super::{
@@ -45,8 +50,10 @@ impl LedgerReadState {
                     id,
                 });
             }
-            LedgerReadAction::Pending { request, .. } => {
-                state.add(meta.time(), request.clone());
+            LedgerReadAction::Pending {
+                request, peer_id, ..
+            } => {
+                state.add(meta.time(), request.clone(), *peer_id);
             }
             LedgerReadAction::Success { id, response } => {
                 state.add_response(*id, meta.time(), response.clone());
@@ -58,6 +65,9 @@ impl LedgerReadState {
             LedgerReadAction::Prune { id } => {
                 state.remove(*id);
             }
+            LedgerReadAction::PeerThrottled { peer_id } => {
+                state.peer_throttled(*peer_id);
+            }
         }
     }
 
@@ -77,31 +87,38 @@ impl LedgerReadState {
                 LedgerReadRequest::DelegatorTable(ledger_hash, pub_key),
                 LedgerReadResponse::DelegatorTable(table),
             ) => {
-                let expected = state.block_producer.vrf_delegator_table_inputs();
-                if !expected.is_some_and(|(expected_hash, producer)| {
-                    ledger_hash == expected_hash && pub_key == producer
-                }) {
+                let is_for = |inputs: Option<(&v2::LedgerHash, &AccountPublicKey)>| {
+                    inputs.is_some_and(|(expected_hash, producer)| {
+                        ledger_hash == expected_hash && pub_key == producer
+                    })
+                };
+                let is_expected = is_for(state.block_producer.vrf_delegator_table_inputs());
+                let is_precomputed =
+                    is_for(state.block_producer.vrf_delegator_table_precompute_inputs());
+                if !is_expected && !is_precomputed {
                     bug_condition!("delegator table unexpected");
                     return;
                 }
-                match table {
-                    None => {
-                        // TODO(tizoc): Revise this, may be better to dispatch a different action here
-                        // and avoid running the VRF evaluator altogether when we know that the
-                        // table is empty.
-                        dispatcher.push(
-                            BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction {
-                                delegator_table: Default::default(),
-                            },
-                        );
-                    }
-                    Some(table) => {
-                        dispatcher.push(
-                            BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction {
-                                delegator_table: table.into(),
-                            },
-                        );
-                    }
+                // TODO(tizoc): Revise this, may be better to dispatch a different action here
+                // and avoid running the VRF evaluator altogether when we know that the
+                // table is empty.
+                let delegator_table: Arc<DelegatorTable> =
+                    table.map(Into::into).unwrap_or_default();
+                if is_precomputed {
+                    dispatcher.push(
+                        BlockProducerVrfEvaluatorAction::PrecomputeDelegatorTableSuccess {
+                            ledger_hash: ledger_hash.clone(),
+                            producer: pub_key.clone(),
+                            delegator_table: delegator_table.clone(),
+                        },
+                    );
+                }
+                if is_expected {
+                    dispatcher.push(
+                        BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction {
+                            delegator_table,
+                        },
+                    );
                 }
             }
             (_, LedgerReadResponse::DelegatorTable(..)) => unreachable!(),
@@ -193,10 +210,36 @@ impl LedgerReadState {
             (_, LedgerReadResponse::ScanStateSummary(..)) => unreachable!(),
             (_req, LedgerReadResponse::GetAccounts(..)) => todo!(),
             (_, LedgerReadResponse::AccountsForRpc(rpc_id, accounts, account_query)) => {
+                let req = state.rpc.requests.get(&rpc_id).map(|req| &req.req);
+                if let Some(RpcRequest::LedgerAccountsAtBlockGet(_)) = req {
+                    dispatcher.push(RpcAction::LedgerAccountsAtBlockGetSuccess {
+                        rpc_id,
+                        accounts: Ok(accounts),
+                    });
+                } else if let Some(RpcRequest::AccountNextNoncesGet(_)) = req {
+                    dispatcher.push(RpcAction::AccountNextNoncesGetSuccess { rpc_id, accounts });
+                } else {
+                    dispatcher.push(RpcAction::LedgerAccountsGetSuccess {
+                        rpc_id,
+                        accounts: Ok(accounts),
+                        account_query,
+                        continuation: None,
+                    });
+                }
+            }
+            (_, LedgerReadResponse::AccountsPageForRpc(rpc_id, page)) => {
+                let (accounts, continuation) = match page {
+                    Some((accounts, continuation)) => (Ok(accounts), continuation),
+                    None => (
+                        Err("ledger of the continuation token isn't available anymore".to_owned()),
+                        None,
+                    ),
+                };
                 dispatcher.push(RpcAction::LedgerAccountsGetSuccess {
                     rpc_id,
                     accounts,
-                    account_query,
+                    account_query: AccountQuery::All,
+                    continuation,
                 });
             }
             (_, LedgerReadResponse::GetLedgerStatus(rpc_id, resp)) => {
@@ -205,12 +248,30 @@ impl LedgerReadState {
                     response: resp.clone(),
                 });
             }
+            (_, LedgerReadResponse::GetLedgerStatusExtended(rpc_id, resp)) => {
+                dispatcher.push(RpcAction::LedgerStatusExtendedGetSuccess {
+                    rpc_id,
+                    response: resp.clone(),
+                });
+            }
+            (_, LedgerReadResponse::VrfVerify(rpc_id, resp)) => {
+                dispatcher.push(RpcAction::VrfVerifySuccess {
+                    rpc_id,
+                    response: resp.clone(),
+                });
+            }
             (_, LedgerReadResponse::GetAccountDelegators(rpc_id, resp)) => {
                 dispatcher.push(RpcAction::LedgerAccountDelegatorsGetSuccess {
                     rpc_id,
                     response: resp.clone(),
                 });
             }
+            (_, LedgerReadResponse::Session(rpc_id, resp)) => {
+                dispatcher.push(RpcAction::LedgerSessionSuccess {
+                    rpc_id,
+                    response: resp,
+                });
+            }
         }
     }
 
@@ -218,8 +279,31 @@ impl LedgerReadState {
         // fetching delegator table, this is required because delegator table construction requires reading from ledger.
         // It could be that ledger read quota was reached when vrf tried to initiate that read, so we need to "retry" it if that's the case
         dispatcher.push(BlockProducerVrfEvaluatorAction::BeginDelegatorTableConstruction);
+        if let Some((ledger_hash, producer)) =
+            state.block_producer.vrf_delegator_table_precompute_inputs()
+        {
+            dispatcher.push(BlockProducerVrfEvaluatorAction::PrecomputeDelegatorTable {
+                ledger_hash: ledger_hash.clone(),
+                producer: producer.clone(),
+            });
+        }
+
+        if !state.config.profile.serves_p2p_rpcs() {
+            // Rpc requests are refused when received, streaming ones here.
+            for (peer_id, peer) in state.p2p.ready_peers_iter() {
+                if let Some((id, _)) = peer.channels.streaming_rpc.remote_todo_request() {
+                    dispatcher.push(P2pChannelsStreamingRpcAction::ResponseSendInit {
+                        peer_id: *peer_id,
+                        id,
+                        response: None,
+                    });
+                }
+            }
+            return;
+        }
 
-        // p2p rpcs
+        // p2p rpcs, scheduled round-robin across peers (ordered by peer id),
+        // starting after the peer scheduled last.
         let mut peers = state
             .p2p
             .ready_peers_iter()
@@ -231,10 +315,18 @@ impl LedgerReadState {
                     .is_some()
                     || peer.channels.streaming_rpc.remote_todo_request().is_some()
             })
-            .map(|(peer_id, peer)| (*peer_id, peer.channels.rpc_remote_last_responded()))
+            .map(|(peer_id, _)| *peer_id)
             .collect::<Vec<_>>();
-        peers.sort_by_key(|(_, last_responded)| *last_responded);
-        for (peer_id, _) in peers {
+        peers.sort();
+        if let Some(last_peer_id) = state.ledger.read.last_scheduled_peer() {
+            let start = peers.partition_point(|peer_id| peer_id <= last_peer_id);
+            peers.rotate_left(start);
+        }
+        for peer_id in peers {
+            if !state.ledger.read.is_peer_cost_under_limit(&peer_id) {
+                dispatcher.push(LedgerReadAction::PeerThrottled { peer_id });
+                continue;
+            }
             let Some((id, request, is_streaming)) = None.or_else(|| {
                 let peer = state.p2p.ready()?.get_ready_peer(&peer_id)?;
                 let mut reqs = peer.channels.rpc.remote_todo_requests_iter();
@@ -273,24 +365,14 @@ impl LedgerReadState {
             };
 
             dispatcher.push(LedgerReadAction::Init {
-            request,
-            callback: LedgerReadInitCallback::P2pChannelsResponsePending
-         {      callback: redux::callback!(on_ledger_read_init_p2p_channels_response_pending((is_streaming: bool, id: P2pRpcId, peer_id: PeerId)) -> crate::Action{
-                    if is_streaming {
-                        P2pAction::from(P2pChannelsStreamingRpcAction::ResponsePending {
-                            peer_id,
-                            id,
-                        })
-                    } else {
-                        P2pAction::from(P2pChannelsRpcAction::ResponsePending {
-                            peer_id,
-                            id,
-                        })
-                    }
-                }),
-                args:(is_streaming, id, peer_id)
-        }
-        });
+                request,
+                callback: LedgerReadInitCallback::new(if is_streaming {
+                    P2pAction::from(P2pChannelsStreamingRpcAction::ResponsePending { peer_id, id })
+                } else {
+                    P2pAction::from(P2pChannelsRpcAction::ResponsePending { peer_id, id })
+                })
+                .for_peer(peer_id),
+            });
 
             if !state.ledger.read.is_total_cost_under_limit() {
                 return;
@@ -328,6 +410,20 @@ impl LedgerReadState {
                 return;
             }
         }
+
+        let ledger_accounts_at_block_rpc = state
+            .rpc
+            .accounts_at_block_request_rpc_ids()
+            .filter(|(_, status)| status.is_init())
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+
+        for rpc_id in ledger_accounts_at_block_rpc {
+            dispatcher.push(RpcAction::LedgerAccountsAtBlockLedgerGetInit { rpc_id });
+            if !state.ledger.read.is_total_cost_under_limit() {
+                return;
+            }
+        }
     }
 }
 
@@ -1,9 +1,15 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use openmina_core::requests::{PendingRequests, RequestId, RequestIdType};
+use p2p::PeerId;
 use serde::{Deserialize, Serialize};
 
 use super::{LedgerReadRequest, LedgerReadResponse};
 
 const MAX_TOTAL_COST: usize = 256;
+/// Max total cost of pending requests initiated by a single peer, so that
+/// one peer can't take up the whole budget.
+const MAX_PEER_COST: usize = MAX_TOTAL_COST / 4;
 
 pub struct LedgerReadIdType;
 impl RequestIdType for LedgerReadIdType {
@@ -19,6 +25,17 @@ pub struct LedgerReadState {
     pending: PendingRequests<LedgerReadIdType, LedgerReadRequestState>,
     /// Total cost of currently pending requests.
     total_cost: usize,
+    /// Cost of currently pending requests, per peer that initiated them.
+    peers_cost: BTreeMap<PeerId, usize>,
+    /// Requests of the peers are scheduled round-robin, starting after
+    /// the peer whose request was scheduled last.
+    last_scheduled_peer: Option<PeerId>,
+    /// Number of times a peer reached its cost cap, so that its requests
+    /// were deferred.
+    throttled: u64,
+    /// Peers at their cost cap, until their pending requests are done.
+    #[serde(default)]
+    throttled_peers: BTreeSet<PeerId>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -26,10 +43,12 @@ pub enum LedgerReadRequestState {
     Pending {
         time: redux::Timestamp,
         request: LedgerReadRequest,
+        peer_id: Option<PeerId>,
     },
     Success {
         time: redux::Timestamp,
         request: LedgerReadRequest,
+        peer_id: Option<PeerId>,
         response: LedgerReadResponse,
     },
 }
@@ -51,19 +70,77 @@ impl LedgerReadState {
         self.total_cost < MAX_TOTAL_COST
     }
 
+    pub fn is_peer_cost_under_limit(&self, peer_id: &PeerId) -> bool {
+        self.peer_cost(peer_id) < MAX_PEER_COST
+    }
+
+    /// Total cost of currently pending requests initiated by the peer.
+    pub fn peer_cost(&self, peer_id: &PeerId) -> usize {
+        self.peers_cost.get(peer_id).copied().unwrap_or(0)
+    }
+
+    pub fn last_scheduled_peer(&self) -> Option<&PeerId> {
+        self.last_scheduled_peer.as_ref()
+    }
+
+    pub fn throttled(&self) -> u64 {
+        self.throttled
+    }
+
+    pub fn throttled_peers(&self) -> impl Iterator<Item = &PeerId> {
+        self.throttled_peers.iter()
+    }
+
+    pub fn is_peer_throttled(&self, peer_id: &PeerId) -> bool {
+        self.throttled_peers.contains(peer_id)
+    }
+
+    /// Counted once per reaching the cap, not for every deferral.
+    pub fn peer_throttled(&mut self, peer_id: PeerId) {
+        if self.throttled_peers.insert(peer_id) {
+            self.throttled = self.throttled.saturating_add(1);
+        }
+    }
+
     pub fn next_req_id(&self) -> LedgerReadId {
         self.pending.next_req_id()
     }
 
-    pub fn add(&mut self, time: redux::Timestamp, request: LedgerReadRequest) -> LedgerReadId {
-        self.total_cost = self.total_cost.saturating_add(request.cost());
-        self.pending
-            .add(LedgerReadRequestState::Pending { time, request })
+    pub fn add(
+        &mut self,
+        time: redux::Timestamp,
+        request: LedgerReadRequest,
+        peer_id: Option<PeerId>,
+    ) -> LedgerReadId {
+        let cost = request.cost();
+        self.total_cost = self.total_cost.saturating_add(cost);
+        if let Some(peer_id) = peer_id {
+            let peer_cost = self.peers_cost.entry(peer_id).or_default();
+            *peer_cost = peer_cost.saturating_add(cost);
+            self.last_scheduled_peer = Some(peer_id);
+        }
+        self.pending.add(LedgerReadRequestState::Pending {
+            time,
+            request,
+            peer_id,
+        })
     }
 
     pub fn remove(&mut self, id: LedgerReadId) -> Option<LedgerReadRequestState> {
         let req = self.pending.remove(id)?;
-        self.total_cost = self.total_cost.saturating_sub(req.request().cost());
+        let cost = req.request().cost();
+        self.total_cost = self.total_cost.saturating_sub(cost);
+        if let Some(peer_id) = req.peer_id() {
+            if let Some(peer_cost) = self.peers_cost.get_mut(peer_id) {
+                *peer_cost = peer_cost.saturating_sub(cost);
+                if *peer_cost == 0 {
+                    self.peers_cost.remove(peer_id);
+                }
+            }
+            if self.is_peer_cost_under_limit(peer_id) {
+                self.throttled_peers.remove(peer_id);
+            }
+        }
         Some(req)
     }
 
@@ -74,9 +151,12 @@ impl LedgerReadState {
         response: LedgerReadResponse,
     ) {
         self.pending.update(id, move |req| match req {
-            LedgerReadRequestState::Pending { request, .. } => LedgerReadRequestState::Success {
+            LedgerReadRequestState::Pending {
+                request, peer_id, ..
+            } => LedgerReadRequestState::Success {
                 time,
                 request,
+                peer_id,
                 response,
             },
             LedgerReadRequestState::Success { .. } => {
@@ -95,7 +175,7 @@ impl LedgerReadState {
         &self,
     ) -> impl Iterator<Item = (LedgerReadId, &LedgerReadRequest, redux::Timestamp)> {
         self.pending.iter().filter_map(|(id, s)| match s {
-            LedgerReadRequestState::Pending { time, request } => Some((id, request, *time)),
+            LedgerReadRequestState::Pending { time, request, .. } => Some((id, request, *time)),
             _ => None,
         })
     }
@@ -107,4 +187,68 @@ impl LedgerReadRequestState {
             Self::Pending { request, .. } | Self::Success { request, .. } => request,
         }
     }
+
+    /// Peer that initiated the request, if it was initiated by a peer.
+    pub fn peer_id(&self) -> Option<&PeerId> {
+        match self {
+            Self::Pending { peer_id, .. } | Self::Success { peer_id, .. } => peer_id.as_ref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::v2;
+
+    use super::*;
+
+    #[test]
+    fn peer_cost_accounting() {
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let other_peer_id = PeerId::from_bytes([2; 32]);
+        let request = LedgerReadRequest::GetNumAccounts(v2::LedgerHash::zero());
+        let mut state = LedgerReadState::default();
+
+        let ids = (0..MAX_PEER_COST)
+            .map(|_| state.add(redux::Timestamp::ZERO, request.clone(), Some(peer_id)))
+            .collect::<Vec<_>>();
+        state.add(redux::Timestamp::ZERO, request.clone(), None);
+        assert!(!state.is_peer_cost_under_limit(&peer_id));
+        assert!(state.is_peer_cost_under_limit(&other_peer_id));
+        assert!(state.is_total_cost_under_limit());
+        assert_eq!(state.last_scheduled_peer(), Some(&peer_id));
+
+        state.remove(ids[0]);
+        assert!(state.is_peer_cost_under_limit(&peer_id));
+        for id in &ids[1..] {
+            state.remove(*id);
+        }
+        assert_eq!(state.peer_cost(&peer_id), 0);
+        assert!(state.peers_cost.is_empty());
+        assert_eq!(state.total_cost, 1);
+    }
+
+    #[test]
+    fn peer_throttled_counted_once() {
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let request = LedgerReadRequest::GetNumAccounts(v2::LedgerHash::zero());
+        let mut state = LedgerReadState::default();
+        let ids = (0..MAX_PEER_COST)
+            .map(|_| state.add(redux::Timestamp::ZERO, request.clone(), Some(peer_id)))
+            .collect::<Vec<_>>();
+
+        // deferred on every scheduling pass, counted once.
+        state.peer_throttled(peer_id);
+        state.peer_throttled(peer_id);
+        assert_eq!(state.throttled(), 1);
+        assert!(state.is_peer_throttled(&peer_id));
+
+        for id in ids {
+            state.remove(id);
+        }
+        assert!(!state.is_peer_throttled(&peer_id));
+        assert_eq!(state.throttled_peers().count(), 0);
+        state.peer_throttled(peer_id);
+        assert_eq!(state.throttled(), 2);
+    }
 }
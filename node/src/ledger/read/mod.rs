@@ -4,11 +4,8 @@ pub use ledger_read_actions::*;
 
 mod ledger_read_state;
 pub use ledger_read_state::*;
-use openmina_core::block::AppliedBlock;
-use openmina_core::requests::{RequestId, RpcId, RpcIdType};
-use p2p::channels::rpc::P2pRpcId;
+use openmina_core::requests::RpcId;
 use p2p::PeerId;
-use redux::Callback;
 
 mod ledger_read_reducer;
 
@@ -22,7 +19,10 @@ use crate::account::AccountPublicKey;
 use crate::block_producer::vrf_evaluator::DelegatorTable;
 use crate::ledger::LedgerAddress;
 use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
-use crate::rpc::{AccountQuery, RpcScanStateSummaryScanStateJob};
+use crate::rpc::{
+    AccountQuery, RpcContinuationToken, RpcResponseLimits, RpcScanStateSummaryScanStateJob,
+    RpcVrfEpochData, RpcVrfVerifyClaim, RpcVrfVerifyResponse,
+};
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
 pub enum LedgerReadKind {
@@ -34,8 +34,12 @@ pub enum LedgerReadKind {
     GetStagedLedgerAuxAndPendingCoinbases,
     ScanStateSummary,
     AccountsForRpc,
+    AccountsPageForRpc,
     GetLedgerStatus,
+    GetLedgerStatusExtended,
     GetAccountDelegators,
+    Session,
+    VrfVerify,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -51,8 +55,13 @@ pub enum LedgerReadRequest {
     // rpcs
     ScanStateSummary(v2::MinaBaseStagedLedgerHashStableV1),
     AccountsForRpc(RpcId, v2::LedgerHash, AccountQuery),
+    /// Page of all the accounts, starting at the offset.
+    AccountsPageForRpc(RpcId, v2::LedgerHash, usize, RpcResponseLimits),
     GetLedgerStatus(RpcId, v2::LedgerHash),
+    GetLedgerStatusExtended(RpcId, v2::LedgerHash, v2::MinaBaseStagedLedgerHashStableV1),
     GetAccountDelegators(RpcId, v2::LedgerHash, AccountId),
+    Session(RpcId, LedgerSessionRequest),
+    VrfVerify(RpcId, RpcVrfVerifyClaim, RpcVrfEpochData),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -61,6 +70,33 @@ pub struct LedgerStatus {
     pub best_tip_staged_ledger_hash: v2::LedgerHash,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct LedgerStatusExtended {
+    #[serde(flatten)]
+    pub status: LedgerStatus,
+    pub scan_state: LedgerScanStateJobCounts,
+    pub pending_coinbase: LedgerPendingCoinbaseSummary,
+}
+
+/// Jobs in all trees of the scan state, by their state.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct LedgerScanStateJobCounts {
+    pub trees: usize,
+    pub empty: usize,
+    /// Merge jobs waiting for the second proof.
+    pub partial: usize,
+    /// Jobs waiting for the snark work.
+    pub todo: usize,
+    pub done: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LedgerPendingCoinbaseSummary {
+    /// Stacks in use, at most `2^depth`.
+    pub stacks: usize,
+    pub depth: usize,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum LedgerReadResponse {
     /// Delegator table requested by vrf state machine.
@@ -74,8 +110,54 @@ pub enum LedgerReadResponse {
     // rpcs
     ScanStateSummary(Result<Vec<Vec<RpcScanStateSummaryScanStateJob>>, String>),
     AccountsForRpc(RpcId, Vec<Account>, AccountQuery),
+    AccountsPageForRpc(RpcId, Option<(Vec<Account>, Option<RpcContinuationToken>)>),
     GetLedgerStatus(RpcId, Option<LedgerStatus>),
+    GetLedgerStatusExtended(RpcId, Option<LedgerStatusExtended>),
     GetAccountDelegators(RpcId, Option<Vec<Account>>),
+    Session(RpcId, Result<LedgerSessionResponse, String>),
+    VrfVerify(RpcId, RpcVrfVerifyResponse),
+}
+
+/// Session pins a ledger, so that a sequence of queries is answered
+/// from the same ledger, even as the chain advances.
+pub type LedgerSessionId = u64;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum LedgerSessionRequest {
+    Open {
+        ledger_hash: v2::LedgerHash,
+    },
+    AccountsGet {
+        session_id: LedgerSessionId,
+        query: AccountQuery,
+    },
+    /// Accounts in the order of their index in the ledger, at most
+    /// [`LEDGER_SESSION_PAGE_MAX`] of them.
+    ///
+    /// [`LEDGER_SESSION_PAGE_MAX`]: crate::ledger::LEDGER_SESSION_PAGE_MAX
+    AccountsPage {
+        session_id: LedgerSessionId,
+        offset: u64,
+        limit: u64,
+    },
+    Close {
+        session_id: LedgerSessionId,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum LedgerSessionResponse {
+    Opened(LedgerSessionInfo),
+    Accounts(Vec<Account>),
+    Closed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerSessionInfo {
+    pub id: LedgerSessionId,
+    pub ledger_hash: v2::LedgerHash,
+    /// Session is closed if it isn't used for this long.
+    pub ttl_secs: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -97,8 +179,12 @@ impl LedgerReadRequest {
             }
             Self::ScanStateSummary(..) => LedgerReadKind::ScanStateSummary,
             Self::AccountsForRpc(..) => LedgerReadKind::AccountsForRpc,
+            Self::AccountsPageForRpc(..) => LedgerReadKind::AccountsPageForRpc,
             Self::GetLedgerStatus(..) => LedgerReadKind::GetLedgerStatus,
+            Self::GetLedgerStatusExtended(..) => LedgerReadKind::GetLedgerStatusExtended,
             Self::GetAccountDelegators(..) => LedgerReadKind::GetAccountDelegators,
+            Self::Session(..) => LedgerReadKind::Session,
+            Self::VrfVerify(..) => LedgerReadKind::VrfVerify,
         }
     }
 
@@ -117,8 +203,18 @@ impl LedgerReadRequest {
             Self::ScanStateSummary(..) => 100,
             // TODO(adonagy): not sure
             Self::AccountsForRpc(..) => 10,
+            Self::AccountsPageForRpc(..) => 10,
             Self::GetLedgerStatus(..) => 1,
+            Self::GetLedgerStatusExtended(..) => 10,
             Self::GetAccountDelegators(..) => 10,
+            Self::Session(
+                _,
+                LedgerSessionRequest::AccountsGet { .. }
+                | LedgerSessionRequest::AccountsPage { .. },
+            ) => 10,
+            Self::Session(..) => 1,
+            // Same as the delegator table, plus the threshold of the winner.
+            Self::VrfVerify(..) => 100,
         };
         cost.max(1)
     }
@@ -137,8 +233,12 @@ impl LedgerReadResponse {
             }
             Self::ScanStateSummary(..) => LedgerReadKind::ScanStateSummary,
             Self::AccountsForRpc(..) => LedgerReadKind::AccountsForRpc,
+            Self::AccountsPageForRpc(..) => LedgerReadKind::AccountsPageForRpc,
             Self::GetLedgerStatus(..) => LedgerReadKind::GetLedgerStatus,
+            Self::GetLedgerStatusExtended(..) => LedgerReadKind::GetLedgerStatusExtended,
             Self::GetAccountDelegators(..) => LedgerReadKind::GetAccountDelegators,
+            Self::Session(..) => LedgerReadKind::Session,
+            Self::VrfVerify(..) => LedgerReadKind::VrfVerify,
         }
     }
 }
@@ -149,27 +249,63 @@ impl PartialEq for LedgerReadStagedLedgerAuxAndPendingCoinbases {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum LedgerReadInitCallback {
-    RpcLedgerAccountsGetPending {
-        callback: Callback<RequestId<RpcIdType>>,
-        args: RequestId<RpcIdType>,
-    },
-    RpcScanStateSummaryGetPending {
-        callback: Callback<(RequestId<RpcIdType>, AppliedBlock)>,
-        args: (RequestId<RpcIdType>, AppliedBlock),
-    },
-    P2pChannelsResponsePending {
-        callback: Callback<(bool, P2pRpcId, PeerId)>,
-        args: (bool, P2pRpcId, PeerId),
-    },
-    RpcLedgerStatusGetPending {
-        callback: Callback<RequestId<RpcIdType>>,
-        args: RequestId<RpcIdType>,
-    },
-    RpcLedgerAccountDelegatorsGetPending {
-        callback: Callback<RequestId<RpcIdType>>,
-        args: RequestId<RpcIdType>,
-    },
-    None,
+/// Action dispatched once the ledger read is initialized.
+///
+/// Any subsystem can register its continuation with
+/// [`LedgerReadInitCallback::new`], without the ledger read knowing about
+/// it. The caller builds the action, so its arguments stay typed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LedgerReadInitCallback {
+    on_init: Option<Box<crate::Action>>,
+    peer_id: Option<PeerId>,
+}
+
+impl LedgerReadInitCallback {
+    pub fn new<A: Into<crate::Action>>(on_init: A) -> Self {
+        Self {
+            on_init: Some(Box::new(on_init.into())),
+            peer_id: None,
+        }
+    }
+
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Marks the read as done on behalf of the peer.
+    pub fn for_peer(mut self, peer_id: PeerId) -> Self {
+        self.peer_id = Some(peer_id);
+        self
+    }
+
+    pub fn into_action(self) -> Option<crate::Action> {
+        self.on_init.map(|action| *action)
+    }
+
+    /// Peer on whose behalf the read is done, if any.
+    pub fn peer_id(&self) -> Option<PeerId> {
+        self.peer_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledger_read_init_callback() {
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let callback = LedgerReadInitCallback::new(LedgerReadAction::FindTodos).for_peer(peer_id);
+        assert_eq!(callback.peer_id(), Some(peer_id));
+        assert!(matches!(
+            callback.into_action(),
+            Some(crate::Action::Ledger(crate::ledger::LedgerAction::Read(
+                LedgerReadAction::FindTodos
+            )))
+        ));
+
+        let callback = LedgerReadInitCallback::none().for_peer(peer_id);
+        assert_eq!(callback.peer_id(), Some(peer_id));
+        assert!(callback.into_action().is_none());
+    }
 }
@@ -3,6 +3,7 @@ use redux::Dispatcher;
 
 use crate::{
     ledger_effectful::LedgerEffectfulAction,
+    transaction_pool::TransactionPoolAction,
     transition_frontier::sync::{
         ledger::staged::TransitionFrontierSyncLedgerStagedAction, TransitionFrontierSyncAction,
     },
@@ -144,6 +145,9 @@ impl LedgerWriteState {
                         .push(TransitionFrontierSyncAction::BlocksNextApplyError { hash, error });
                 }
                 Ok(result) => {
+                    dispatcher.push(TransactionPoolAction::AccountsTouched {
+                        accounts: result.accounts_touched.clone(),
+                    });
                     dispatcher.push(TransitionFrontierSyncAction::BlocksSendToArchive {
                         hash: hash.clone(),
                         data: result.clone(),
@@ -151,6 +155,7 @@ impl LedgerWriteState {
                     dispatcher.push(TransitionFrontierSyncAction::BlocksNextApplySuccess {
                         hash,
                         just_emitted_a_proof: result.just_emitted_a_proof,
+                        internal_commands: result.internal_commands.clone(),
                     });
                 }
             },
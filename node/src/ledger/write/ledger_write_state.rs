@@ -43,6 +43,11 @@ impl LedgerWriteState {
     pub fn is_busy(&self) -> bool {
         self.pending_requests().peekable().peek().is_some()
     }
+
+    /// Whether the write was requested, but its result isn't in yet.
+    pub fn is_in_progress(&self) -> bool {
+        matches!(self, Self::Init { .. } | Self::Pending { .. })
+    }
 }
 
 impl Default for LedgerWriteState {
@@ -11,6 +11,7 @@ mod ledger_write_reducer;
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
 use ledger::scan_state::scan_state::AvailableJobMessage;
@@ -22,6 +23,7 @@ use crate::core::block::ArcBlockWithHash;
 use crate::core::snark::{Snark, SnarkJobId};
 use crate::transition_frontier::sync::ledger::staged::StagedLedgerAuxAndPendingCoinbasesValid;
 use crate::transition_frontier::sync::TransitionFrontierRootSnarkedLedgerUpdates;
+use crate::transition_frontier::InternalCommand;
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
 pub enum LedgerWriteKind {
@@ -47,6 +49,12 @@ pub enum LedgerWriteRequest {
         completed_snarks: BTreeMap<SnarkJobId, Snark>,
         supercharge_coinbase: bool,
         transactions_by_fee: Vec<valid::UserCommand>,
+        /// Time left to create the diff, see
+        /// [`crate::block_producer::BlockProducerConfig::assembly_deadline`].
+        time_budget: Duration,
+        /// Diff to use instead of creating one, see
+        /// [`crate::block_producer::BlockProducerExternalDiff`].
+        external_diff: Option<Arc<v2::StagedLedgerDiffDiffStableV2>>,
     },
     BlockApply {
         block: ArcBlockWithHash,
@@ -57,6 +65,10 @@ pub enum LedgerWriteRequest {
         ledgers_to_keep: LedgersToKeep,
         root_snarked_ledger_updates: TransitionFrontierRootSnarkedLedgerUpdates,
         needed_protocol_states: BTreeMap<v2::StateHash, v2::MinaStateProtocolStateValueStableV2>,
+        /// Protocol states needed by the scan state of the new root, set
+        /// when the root needs to be persisted.
+        root_snapshot_protocol_states:
+            Option<BTreeMap<v2::StateHash, v2::MinaStateProtocolStateValueStableV2>>,
         new_root: AppliedBlock,
         new_best_tip: AppliedBlock,
     },
@@ -87,6 +99,14 @@ pub enum LedgerWriteResponse {
 pub struct BlockApplyResult {
     pub block: ArcBlockWithHash,
     pub just_emitted_a_proof: bool,
+    /// Accounts referenced by the commands of the block, along with the
+    /// coinbase and fee transfer receivers, for the transaction pool to
+    /// revalidate only the commands whose senders might have changed.
+    #[serde(default)]
+    pub accounts_touched: BTreeSet<AccountId>,
+    /// Coinbases and fee transfers applied for the block.
+    #[serde(default)]
+    pub internal_commands: Vec<InternalCommand>,
     pub archive_data: Option<BlockApplyResultArchive>,
 }
 
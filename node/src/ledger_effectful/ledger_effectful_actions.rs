@@ -1,7 +1,9 @@
 use crate::ledger::{
+    audit::LedgerAuditId,
     read::{LedgerReadIdType, LedgerReadInitCallback, LedgerReadRequest},
     write::LedgerWriteRequest,
 };
+use mina_p2p_messages::v2::{LedgerHash, MinaBaseStagedLedgerHashStableV1};
 use openmina_core::requests::RequestId;
 use redux::Callback;
 use serde::{Deserialize, Serialize};
@@ -17,6 +19,11 @@ pub enum LedgerEffectfulAction {
         callback: LedgerReadInitCallback,
         id: RequestId<LedgerReadIdType>,
     },
+    AuditInit {
+        id: LedgerAuditId,
+        ledger_hash: LedgerHash,
+        staged_ledger_hash: MinaBaseStagedLedgerHashStableV1,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for LedgerEffectfulAction {
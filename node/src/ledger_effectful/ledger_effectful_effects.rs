@@ -2,9 +2,7 @@ use redux::ActionWithMeta;
 
 use crate::{
     ledger::{
-        read::{LedgerReadAction, LedgerReadInitCallback},
-        write::LedgerWriteAction,
-        LedgerService,
+        audit::LedgerAuditAction, read::LedgerReadAction, write::LedgerWriteAction, LedgerService,
     },
     Store,
 };
@@ -31,26 +29,24 @@ pub fn ledger_effectful_effects<S>(
             id,
         } => {
             store.service.read_init(id, request.clone());
-            store.dispatch(LedgerReadAction::Pending { id, request });
+            let peer_id = callback.peer_id();
+            store.dispatch(LedgerReadAction::Pending {
+                id,
+                request,
+                peer_id,
+            });
 
-            match callback {
-                LedgerReadInitCallback::RpcLedgerAccountsGetPending { callback, args } => {
-                    store.dispatch_callback(callback, args);
-                }
-                LedgerReadInitCallback::RpcScanStateSummaryGetPending { callback, args } => {
-                    store.dispatch_callback(callback, args);
-                }
-                LedgerReadInitCallback::P2pChannelsResponsePending { callback, args } => {
-                    store.dispatch_callback(callback, args);
-                }
-                LedgerReadInitCallback::RpcLedgerStatusGetPending { callback, args } => {
-                    store.dispatch_callback(callback, args);
-                }
-                LedgerReadInitCallback::RpcLedgerAccountDelegatorsGetPending { callback, args } => {
-                    store.dispatch_callback(callback, args);
-                }
-                LedgerReadInitCallback::None => {}
+            if let Some(action) = callback.into_action() {
+                store.dispatch(action);
             }
         }
+        LedgerEffectfulAction::AuditInit {
+            id,
+            ledger_hash,
+            staged_ledger_hash,
+        } => {
+            store.service.audit_init(id, staged_ledger_hash);
+            store.dispatch(LedgerAuditAction::Pending { id, ledger_hash });
+        }
     }
 }
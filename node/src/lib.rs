@@ -18,6 +18,8 @@ pub use config::*;
 mod state;
 pub use state::{P2p, State, Substate};
 
+pub mod state_digest;
+
 mod reducer;
 pub use reducer::reducer;
 
@@ -34,6 +36,7 @@ pub mod stats;
 
 pub mod block_producer;
 pub mod block_producer_effectful;
+pub mod checkpoint;
 pub mod daemon_json;
 pub mod event_source;
 pub mod external_snark_worker;
@@ -49,6 +52,7 @@ pub mod snark_pool;
 pub mod transaction_pool;
 pub mod transition_frontier;
 pub mod watched_accounts;
+pub mod watchtower;
 
 pub type Store<S> = redux::Store<State, S, Action>;
 pub type Effects<S> = redux::Effects<State, S, Action>;
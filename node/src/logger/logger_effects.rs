@@ -64,9 +64,11 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             P2pAction::Connection(action) => match action {
                 P2pConnectionAction::Outgoing(action) => action.action_event(&context),
                 P2pConnectionAction::Incoming(action) => action.action_event(&context),
+                P2pConnectionAction::IceRestart(action) => action.action_event(&context),
             },
             P2pAction::Disconnection(action) => action.action_event(&context),
             P2pAction::Identify(action) => action.action_event(&context),
+            P2pAction::FeatureFlags(action) => action.action_event(&context),
             P2pAction::Channels(action) => match action {
                 P2pChannelsAction::MessageReceived(action) => action.action_event(&context),
                 P2pChannelsAction::SignalingDiscovery(action) => action.action_event(&context),
@@ -77,6 +79,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 P2pChannelsAction::SnarkJobCommitment(action) => action.action_event(&context),
                 P2pChannelsAction::Rpc(action) => action.action_event(&context),
                 P2pChannelsAction::StreamingRpc(action) => action.action_event(&context),
+                P2pChannelsAction::Checkpoint(action) => action.action_event(&context),
             },
             P2pAction::Peer(action) => action.action_event(&context),
             P2pAction::Network(action) => match action {
@@ -110,6 +113,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             p2p::P2pEffectfulAction::Connection(action) => match action {
                 P2pConnectionEffectfulAction::Outgoing(action) => action.action_event(&context),
                 P2pConnectionEffectfulAction::Incoming(action) => action.action_event(&context),
+                P2pConnectionEffectfulAction::IceRestart(action) => action.action_event(&context),
             },
             p2p::P2pEffectfulAction::Disconnection(action) => action.action_event(&context),
             p2p::P2pEffectfulAction::Network(action) => action.action_event(&context),
@@ -117,11 +121,14 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
         },
         Action::ExternalSnarkWorker(action) => action.action_event(&context),
         Action::SnarkPool(action) => action.action_event(&context),
+        Action::Checkpoint(action) => action.action_event(&context),
         Action::Snark(SnarkAction::WorkVerify(a)) => a.action_event(&context),
         Action::Snark(SnarkAction::UserCommandVerify(a)) => a.action_event(&context),
         Action::TransitionFrontier(a) => match a {
             TransitionFrontierAction::Candidate(
-                TransitionFrontierCandidateAction::BlockReceived { block, chain_proof },
+                TransitionFrontierCandidateAction::BlockReceived {
+                    block, chain_proof, ..
+                },
             ) => {
                 openmina_core::action_info!(
                     context,
@@ -189,6 +196,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             a => a.action_event(&context),
         },
         Action::Rpc(a) => a.action_event(&context),
+        Action::EventSource(a) => a.action_event(&context),
         Action::TransactionPool(a) => a.action_event(&context),
         _ => {}
     }
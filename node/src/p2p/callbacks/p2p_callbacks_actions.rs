@@ -17,6 +17,7 @@ pub enum P2pCallbacksAction {
     P2pChannelsRpcTimeout {
         peer_id: PeerId,
         id: P2pRpcId,
+        request: Box<P2pRpcRequest>,
     },
     P2pChannelsRpcResponseReceived {
         peer_id: PeerId,
@@ -46,6 +47,14 @@ pub enum P2pCallbacksAction {
     RpcRespondBestTip {
         peer_id: PeerId,
     },
+    /// Resolve entries of the compact best tip from the pools.
+    P2pChannelsBestTipCompactReceived {
+        peer_id: PeerId,
+    },
+    /// Verify body of the reconstructed compact best tip against its header.
+    P2pChannelsBestTipCompactReconstructed {
+        peer_id: PeerId,
+    },
     P2pPubsubValidateMessage {
         message_id: P2pNetworkPubsubMessageCacheId,
     },
@@ -66,6 +75,14 @@ impl redux::EnablingCondition<crate::State> for P2pCallbacksAction {
             P2pCallbacksAction::RpcRespondBestTip { .. } => {
                 state.transition_frontier.best_tip().is_some()
             }
+            P2pCallbacksAction::P2pChannelsBestTipCompactReceived { peer_id } => state
+                .p2p
+                .get_ready_peer(peer_id)
+                .is_some_and(|p| p.channels.best_tip.compact_received().is_some()),
+            P2pCallbacksAction::P2pChannelsBestTipCompactReconstructed { peer_id } => state
+                .p2p
+                .get_ready_peer(peer_id)
+                .is_some_and(|p| p.channels.best_tip.compact_reconstructed().is_some()),
             P2pCallbacksAction::P2pPubsubValidateMessage { .. } => true,
         }
     }
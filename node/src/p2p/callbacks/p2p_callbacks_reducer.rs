@@ -6,12 +6,15 @@ use mina_p2p_messages::{
 use openmina_core::{
     block::{prevalidate::BlockPrevalidationError, BlockWithHash},
     bug_condition, log,
-    transaction::TransactionWithHash,
+    transaction::{Transaction, TransactionWithHash},
 };
 use p2p::{
     channels::{
-        best_tip::P2pChannelsBestTipAction,
-        rpc::{BestTipWithProof, P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse},
+        best_tip::{CompactBlockEntries, P2pChannelsBestTipAction},
+        rpc::{
+            BestTipWithProof, P2pChannelsRpcAction, P2pRpcId, P2pRpcRequest, P2pRpcResponse,
+            TRANSACTION_POOL_SUMMARY_PAGE_SIZE,
+        },
         streaming_rpc::P2pStreamingRpcResponseFull,
     },
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
@@ -20,10 +23,12 @@ use p2p::{
 use redux::{ActionMeta, ActionWithMeta, Dispatcher};
 
 use crate::{
+    event_source::EventSourceAction,
     p2p_ready,
     snark_pool::candidate::SnarkPoolCandidateAction,
     transaction_pool::candidate::TransactionPoolCandidateAction,
     transition_frontier::candidate::{allow_block_too_late, TransitionFrontierCandidateAction},
+    transition_frontier::genesis::TransitionFrontierGenesisAction,
     transition_frontier::sync::{
         ledger::{
             snarked::{
@@ -66,9 +71,13 @@ impl crate::State {
                 if state.p2p.get_peer(&peer_id).is_some_and(|p| p.is_libp2p) {
                     // for webrtc peers, we don't need to send this rpc, as we
                     // will receive current best tip in best tip channel anyways.
+                    let id = state
+                        .p2p
+                        .get_ready_peer(&peer_id)
+                        .map_or(0, |p| p.channels.next_local_rpc_id());
                     dispatcher.push(P2pChannelsRpcAction::RequestSend {
                         peer_id,
-                        id: 0,
+                        id,
                         request: Box::new(P2pRpcRequest::BestTipWithProof),
                         on_init: None,
                     });
@@ -77,49 +86,18 @@ impl crate::State {
                 dispatcher.push(TransitionFrontierSyncLedgerSnarkedAction::PeersQuery);
                 dispatcher.push(TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchInit);
                 dispatcher.push(TransitionFrontierSyncAction::BlocksPeersQuery);
+                dispatcher.push(TransactionPoolCandidateAction::PoolSyncInit { peer_id });
+                dispatcher.push(TransitionFrontierGenesisAction::ProofPeerFetchInit);
             }
-            P2pCallbacksAction::P2pChannelsRpcTimeout { peer_id, id } => {
-                let peer_id = *peer_id;
-                let rpc_id = *id;
-                let Some(peer) = state.p2p.get_ready_peer(&peer_id) else {
-                    bug_condition!("get_ready_peer({:?}) returned None", peer_id);
-                    return;
-                };
-
-                let Some(rpc_kind) = peer.channels.rpc.pending_local_rpc_kind() else {
-                    bug_condition!("peer: {:?} pending_local_rpc_kind() returned None", peer_id);
-                    return;
-                };
-
-                dispatcher.push(
-                    TransitionFrontierSyncLedgerSnarkedAction::PeerQueryNumAccountsError {
-                        peer_id,
-                        rpc_id,
-                        error: PeerLedgerQueryError::Timeout,
-                    },
-                );
-                dispatcher.push(
-                    TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressError {
-                        peer_id,
-                        rpc_id,
-                        error: PeerLedgerQueryError::Timeout,
-                    },
-                );
-                dispatcher.push(
-                    TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchError {
-                        peer_id,
-                        rpc_id,
-                        error: PeerStagedLedgerPartsFetchError::Timeout,
-                    },
-                );
-                dispatcher.push(TransitionFrontierSyncAction::BlocksPeerQueryError {
-                    peer_id,
-                    rpc_id,
-                    error: PeerBlockFetchError::Timeout,
-                });
+            P2pCallbacksAction::P2pChannelsRpcTimeout {
+                peer_id,
+                id,
+                request,
+            } => {
+                State::handle_rpc_channels_request_error(dispatcher, *id, *peer_id, request, true);
                 dispatcher.push(P2pDisconnectionAction::Init {
-                    peer_id,
-                    reason: P2pDisconnectionReason::TransitionFrontierRpcTimeout(rpc_kind),
+                    peer_id: *peer_id,
+                    reason: P2pDisconnectionReason::TransitionFrontierRpcTimeout(request.kind()),
                 });
             }
             P2pCallbacksAction::P2pChannelsRpcResponseReceived {
@@ -299,6 +277,63 @@ impl crate::State {
                     best_tip: best_tip.clone(),
                 });
             }
+            P2pCallbacksAction::P2pChannelsBestTipCompactReceived { peer_id } => {
+                let Some(block) = state
+                    .p2p
+                    .get_ready_peer(peer_id)
+                    .and_then(|p| p.channels.best_tip.compact_received())
+                else {
+                    bug_condition!("Compact best tip not found");
+                    return;
+                };
+
+                let transaction_pool = &state.transaction_pool;
+                let commands = block
+                    .commands()
+                    .map(|command| {
+                        let tx = transaction_pool.get(&command.hash)?.clone().forget();
+                        Some(Transaction::from(&tx))
+                    })
+                    .collect();
+                let works = block
+                    .works()
+                    .map(|info| {
+                        let snark = &state.snark_pool.get(&info.job_id)?.snark.as_ref()?.work;
+                        (snark.fee == info.fee && snark.snarker == info.prover)
+                            .then(|| snark.clone().into())
+                    })
+                    .collect();
+
+                dispatcher.push(P2pChannelsBestTipAction::CompactResolve {
+                    peer_id: *peer_id,
+                    entries: CompactBlockEntries { commands, works },
+                });
+            }
+            P2pCallbacksAction::P2pChannelsBestTipCompactReconstructed { peer_id } => {
+                let Some(best_tip) = state
+                    .p2p
+                    .get_ready_peer(peer_id)
+                    .and_then(|p| p.channels.best_tip.compact_reconstructed())
+                else {
+                    bug_condition!("Reconstructed compact best tip not found");
+                    return;
+                };
+
+                let peer_id = *peer_id;
+                match ledger::staged_ledger::validate_block::validate_block(&best_tip.block) {
+                    Ok(()) => {
+                        dispatcher.push(P2pChannelsBestTipAction::CompactVerifySuccess { peer_id });
+                    }
+                    Err(error) => {
+                        openmina_core::warn!(meta.time();
+                            kind = "P2pCompactBestTipBodyMismatch",
+                            peer_id = peer_id.to_string(),
+                            block_hash = best_tip.hash.to_string(),
+                            error = format!("{error:?}"));
+                        dispatcher.push(P2pChannelsBestTipAction::CompactVerifyError { peer_id });
+                    }
+                }
+            }
             P2pCallbacksAction::P2pPubsubValidateMessage { message_id } => {
                 let Some(message_content) = state.p2p.ready().and_then(|p2p| {
                     p2p.network
@@ -342,6 +377,18 @@ impl crate::State {
                             }
                         }
                     }
+                    GossipNetMessageV2::TransactionPoolDiff { .. }
+                    | GossipNetMessageV2::SnarkPoolDiff { .. }
+                        if state.event_source.is_overloaded() =>
+                    {
+                        // Pool diffs are dropped (without relaying them or
+                        // penalizing the sender) until the event queue drains,
+                        // blocks are still processed.
+                        dispatcher.push(EventSourceAction::EventShed);
+                        PreValidationResult::Ignore {
+                            reason: "node overloaded".to_owned(),
+                        }
+                    }
                     _ => {
                         // TODO: add pre validation for Snark pool and Transaction pool diffs
                         PreValidationResult::Continue
@@ -384,6 +431,14 @@ impl crate::State {
         peer_id: PeerId,
         id: u64,
     ) {
+        if !state.config.profile.serves_p2p_rpcs() {
+            dispatcher.push(P2pChannelsRpcAction::ResponseSend {
+                peer_id,
+                id,
+                response: None,
+            });
+            return;
+        }
         match request {
             P2pRpcRequest::BestTipWithProof => {
                 let best_chain = &state.transition_frontier.best_chain;
@@ -449,6 +504,18 @@ impl crate::State {
                     response,
                 });
             }
+            P2pRpcRequest::TransactionPoolSummary { offset } => {
+                let summary = state
+                    .transaction_pool
+                    .summary_page(offset, TRANSACTION_POOL_SUMMARY_PAGE_SIZE);
+                let response = Some(Box::new(P2pRpcResponse::TransactionPoolSummary(summary)));
+
+                dispatcher.push(P2pChannelsRpcAction::ResponseSend {
+                    peer_id,
+                    id,
+                    response,
+                });
+            }
             P2pRpcRequest::Snark(job_id) => {
                 let job = state.snark_pool.get(&job_id);
                 let response = job
@@ -472,6 +539,20 @@ impl crate::State {
                     .collect();
                 let response = Some(Box::new(P2pRpcResponse::InitialPeers(peers)));
 
+                dispatcher.push(P2pChannelsRpcAction::ResponseSend {
+                    peer_id,
+                    id,
+                    response,
+                });
+            }
+            P2pRpcRequest::GenesisProof(hash) => {
+                let response = state
+                    .transition_frontier
+                    .genesis
+                    .proof(&hash)
+                    .map(P2pRpcResponse::GenesisProof)
+                    .map(Box::new);
+
                 dispatcher.push(P2pChannelsRpcAction::ResponseSend {
                     peer_id,
                     id,
@@ -481,59 +562,96 @@ impl crate::State {
         }
     }
 
-    fn handle_rpc_channels_response<'a>(
+    /// Reports the request, which timed out or which the peer couldn't
+    /// answer, to the component which sent it.
+    fn handle_rpc_channels_request_error(
         dispatcher: &mut Dispatcher<Action, State>,
-        meta: ActionMeta,
-        id: u64,
+        rpc_id: P2pRpcId,
         peer_id: PeerId,
-        request: impl FnOnce() -> Option<&'a P2pRpcRequest>,
-        response: &Option<Box<P2pRpcResponse>>,
+        request: &P2pRpcRequest,
+        is_timeout: bool,
     ) {
-        match response.as_deref() {
-            None => {
-                match request() {
-                    Some(P2pRpcRequest::Transaction(hash)) => {
-                        let hash = hash.clone();
-                        dispatcher
-                            .push(TransactionPoolCandidateAction::FetchError { peer_id, hash });
-                        return;
-                    }
-                    Some(P2pRpcRequest::Snark(job_id)) => {
-                        let job_id = job_id.clone();
-                        dispatcher
-                            .push(SnarkPoolCandidateAction::WorkFetchError { peer_id, job_id });
-                        return;
-                    }
-                    _ => {}
-                }
-
+        match request {
+            P2pRpcRequest::LedgerQuery(..) => {
+                let error = if is_timeout {
+                    PeerLedgerQueryError::Timeout
+                } else {
+                    PeerLedgerQueryError::DataUnavailable
+                };
                 dispatcher.push(
                     TransitionFrontierSyncLedgerSnarkedAction::PeerQueryNumAccountsError {
                         peer_id,
-                        rpc_id: id,
-                        error: PeerLedgerQueryError::DataUnavailable,
+                        rpc_id,
+                        error: error.clone(),
                     },
                 );
                 dispatcher.push(
                     TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressError {
                         peer_id,
-                        rpc_id: id,
-                        error: PeerLedgerQueryError::DataUnavailable,
+                        rpc_id,
+                        error,
                     },
                 );
+            }
+            P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesAtBlock(_) => {
+                let error = if is_timeout {
+                    PeerStagedLedgerPartsFetchError::Timeout
+                } else {
+                    PeerStagedLedgerPartsFetchError::DataUnavailable
+                };
                 dispatcher.push(
                     TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchError {
                         peer_id,
-                        rpc_id: id,
-                        error: PeerStagedLedgerPartsFetchError::DataUnavailable,
+                        rpc_id,
+                        error,
                     },
                 );
+            }
+            P2pRpcRequest::Block(_) => {
+                let error = if is_timeout {
+                    PeerBlockFetchError::Timeout
+                } else {
+                    PeerBlockFetchError::DataUnavailable
+                };
                 dispatcher.push(TransitionFrontierSyncAction::BlocksPeerQueryError {
                     peer_id,
-                    rpc_id: id,
-                    error: PeerBlockFetchError::DataUnavailable,
+                    rpc_id,
+                    error,
                 });
             }
+            P2pRpcRequest::Transaction(hash) => {
+                let hash = hash.clone();
+                dispatcher.push(TransactionPoolCandidateAction::FetchError { peer_id, hash });
+            }
+            P2pRpcRequest::Snark(job_id) => {
+                let job_id = job_id.clone();
+                dispatcher.push(SnarkPoolCandidateAction::WorkFetchError { peer_id, job_id });
+            }
+            P2pRpcRequest::TransactionPoolSummary { .. } => {
+                dispatcher.push(TransactionPoolCandidateAction::PoolSyncError { peer_id });
+            }
+            P2pRpcRequest::BestTipWithProof
+            | P2pRpcRequest::InitialPeers
+            | P2pRpcRequest::GenesisProof(_) => {}
+        }
+    }
+
+    fn handle_rpc_channels_response<'a>(
+        dispatcher: &mut Dispatcher<Action, State>,
+        meta: ActionMeta,
+        id: u64,
+        peer_id: PeerId,
+        request: impl FnOnce() -> Option<&'a P2pRpcRequest>,
+        response: &Option<Box<P2pRpcResponse>>,
+    ) {
+        match response.as_deref() {
+            None => {
+                let Some(request) = request() else {
+                    bug_condition!("no request for the rpc response: {peer_id} {id}");
+                    return;
+                };
+                Self::handle_rpc_channels_request_error(dispatcher, id, peer_id, request, false);
+            }
             Some(P2pRpcResponse::BestTipWithProof(resp)) => {
                 let (body_hashes, root_block) = &resp.proof;
 
@@ -647,6 +765,12 @@ impl crate::State {
                     }
                 }
             }
+            Some(P2pRpcResponse::TransactionPoolSummary(summary)) => {
+                dispatcher.push(TransactionPoolCandidateAction::PoolSyncPageReceived {
+                    peer_id,
+                    summary: summary.clone(),
+                });
+            }
             Some(P2pRpcResponse::Snark(snark)) => {
                 dispatcher.push(SnarkPoolCandidateAction::WorkFetchSuccess {
                     peer_id,
@@ -654,6 +778,12 @@ impl crate::State {
                 });
             }
             Some(P2pRpcResponse::InitialPeers(_)) => {}
+            Some(P2pRpcResponse::GenesisProof(proof)) => {
+                dispatcher.push(TransitionFrontierGenesisAction::ProofPeerFetchSuccess {
+                    peer_id,
+                    proof: proof.clone(),
+                });
+            }
         }
     }
 }
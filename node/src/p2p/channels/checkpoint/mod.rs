@@ -0,0 +1,3 @@
+pub use ::p2p::channels::checkpoint::*;
+
+mod p2p_channels_checkpoint_actions;
@@ -1,6 +1,7 @@
 pub use ::p2p::channels::*;
 
 pub mod best_tip;
+pub mod checkpoint;
 pub mod rpc;
 pub mod signaling;
 pub mod snark;
@@ -14,6 +14,9 @@ pub mod callbacks;
 
 mod p2p_effects;
 pub use p2p_effects::*;
+
+mod p2p_preferred_peers_service;
+pub use p2p_preferred_peers_service::*;
 use redux::EnablingCondition;
 
 use crate::State;
@@ -92,6 +95,7 @@ impl_into_global_action!(P2pInitializeAction);
 impl_into_global_action!(connection::outgoing::P2pConnectionOutgoingAction);
 
 impl_into_global_action!(connection::incoming::P2pConnectionIncomingAction);
+impl_into_global_action!(connection::ice_restart::P2pConnectionIceRestartAction);
 
 impl_into_global_action!(disconnection::P2pDisconnectionAction);
 
@@ -106,6 +110,7 @@ impl_into_global_action!(channels::best_tip::P2pChannelsBestTipAction);
 impl_into_global_action!(channels::transaction::P2pChannelsTransactionAction);
 impl_into_global_action!(channels::snark::P2pChannelsSnarkAction);
 impl_into_global_action!(channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction);
+impl_into_global_action!(channels::checkpoint::P2pChannelsCheckpointAction);
 impl_into_global_action!(channels::rpc::P2pChannelsRpcAction);
 impl_into_global_action!(channels::streaming_rpc::P2pChannelsStreamingRpcAction);
 
@@ -125,6 +130,7 @@ impl_into_global_action!(effectful network::kad_effectful::P2pNetworkKadEffectfu
 impl_into_global_action!(effectful p2p::P2pNetworkSchedulerEffectfulAction);
 impl_into_global_action!(effectful p2p::P2pNetworkPnetEffectfulAction);
 impl_into_global_action!(effectful connection::incoming_effectful::P2pConnectionIncomingEffectfulAction);
+impl_into_global_action!(effectful connection::ice_restart_effectful::P2pConnectionIceRestartEffectfulAction);
 impl_into_global_action!(effectful connection::outgoing_effectful::P2pConnectionOutgoingEffectfulAction);
 impl_into_global_action!(effectful p2p::disconnection_effectful::P2pDisconnectionEffectfulAction);
 impl_into_global_action!(effectful network::pubsub::P2pNetworkPubsubEffectfulAction);
@@ -0,0 +1,10 @@
+use redux::Timestamp;
+
+use super::connection::P2pPreferredPeer;
+
+pub trait P2pPreferredPeersService: redux::Service {
+    /// Persists the connected peers, so that they are reconnected to first
+    /// after the restart. Noop if the persistence isn't enabled. The
+    /// service decides how often the peers are actually written.
+    fn p2p_preferred_peers_persist(&mut self, now: Timestamp, peers: Vec<P2pPreferredPeer>);
+}
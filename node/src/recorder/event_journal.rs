@@ -0,0 +1,230 @@
+//! Persistent journal of the events received from the services, for the
+//! post-mortem analysis of production incidents.
+//!
+//! Unlike the recorder, the journal doesn't capture the initial state nor
+//! the randomness, so it can't be used for a deterministic replay. Instead
+//! the journaled events can be re-injected on startup, to bring the node
+//! to a state close to the one it was in when the events were journaled.
+
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::event_source::Event;
+
+/// Journal files are rotated once they exceed this size.
+const FILE_SIZE_LIMIT: u64 = 64 * 1024 * 1024;
+
+fn events_path<P: AsRef<Path>>(path: P, file_index: usize) -> PathBuf {
+    path.as_ref()
+        .join(format!("events_{}.postcard", file_index))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JournaledEvent {
+    /// When the event was taken from the queue to be processed.
+    pub time: redux::Timestamp,
+    pub event: Event,
+}
+
+impl JournaledEvent {
+    pub fn encode(&self) -> postcard::Result<Vec<u8>> {
+        postcard::to_stdvec(self)
+    }
+
+    pub fn decode(encoded: &[u8]) -> postcard::Result<Self> {
+        postcard::from_bytes(encoded)
+    }
+}
+
+/// Writes the events into the `event_journal` dir of the work dir, as
+/// length prefixed records, each of them encrypted separately if dump
+/// encryption is enabled.
+pub struct EventJournal {
+    path: PathBuf,
+    file: BufWriter<fs::File>,
+    file_index: usize,
+    file_bytes_written: u64,
+}
+
+impl EventJournal {
+    pub fn dir<P: AsRef<Path>>(work_dir: P) -> PathBuf {
+        work_dir.as_ref().join("event_journal")
+    }
+
+    /// Starts a new journal in the `work_dir`, removing the previous one.
+    pub fn new<P: AsRef<Path>>(work_dir: P) -> io::Result<Self> {
+        let path = Self::dir(work_dir);
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path)?;
+
+        let file_index = 1;
+        let file = fs::File::create(events_path(&path, file_index))?;
+        Ok(Self {
+            path,
+            file: BufWriter::new(file),
+            file_index,
+            file_bytes_written: 0,
+        })
+    }
+
+    /// Fails if the writes are paused because of low disk space, as the
+    /// journal with the gaps in it is of little use.
+    pub fn event(&mut self, time: redux::Timestamp, event: &Event) -> io::Result<()> {
+        if openmina_core::debug_dump::are_writes_paused() {
+            return Err(io::Error::other("debug writes paused, low disk space"));
+        }
+        let data = JournaledEvent {
+            time,
+            event: event.clone(),
+        };
+        let encoded = data.encode().map_err(io::Error::other)?;
+        let encoded = openmina_core::debug_dump::dump_seal(encoded).map_err(io::Error::other)?;
+
+        if self.file_bytes_written > FILE_SIZE_LIMIT {
+            self.rotate()?;
+        }
+        self.file.write_all(&(encoded.len() as u64).to_be_bytes())?;
+        self.file.write_all(&encoded)?;
+        // Flushed after each event, so that the journal is complete up to
+        // the last event even if the node crashes.
+        self.file.flush()?;
+
+        self.file_bytes_written = self
+            .file_bytes_written
+            .saturating_add(8)
+            .saturating_add(encoded.len() as u64);
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_all()?;
+        self.file_index = self
+            .file_index
+            .checked_add(1)
+            .expect("overflow in event journal file_index");
+        let file = fs::File::create(events_path(&self.path, self.file_index))?;
+        self.file = BufWriter::new(file);
+        self.file_bytes_written = 0;
+        Ok(())
+    }
+}
+
+impl Drop for EventJournal {
+    fn drop(&mut self) {
+        let _ = self.file.flush();
+        let _ = self.file.get_ref().sync_all();
+    }
+}
+
+/// Reads the events journaled by [`EventJournal`]. Encrypted journals
+/// must be decrypted first with `openmina misc decrypt-dump`.
+pub struct EventJournalReader {
+    dir: PathBuf,
+}
+
+impl EventJournalReader {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Events in the order they were journaled. Stops at the first
+    /// incomplete record, which is left by a crash while writing it.
+    /// Records longer than the rest of the file are incomplete too, so a
+    /// corrupted length is never allocated.
+    pub fn read_events(&self) -> impl Iterator<Item = io::Result<JournaledEvent>> + '_ {
+        (1..)
+            .map_while(move |file_index| {
+                let path = events_path(&self.dir, file_index);
+                let file = fs::File::open(path).ok()?;
+                let mut remaining = file.metadata().ok()?.len();
+                let mut file = io::BufReader::new(file);
+
+                Some(std::iter::repeat(()).map_while(move |_| {
+                    let mut len_bytes = [0; 8];
+                    file.read_exact(&mut len_bytes).ok()?;
+                    let len = u64::from_be_bytes(len_bytes);
+                    remaining = remaining
+                        .checked_sub(len_bytes.len() as u64)?
+                        .checked_sub(len)?;
+
+                    let mut data = vec![0; usize::try_from(len).ok()?];
+                    file.read_exact(&mut data).ok()?;
+                    if openmina_core::debug_dump::is_dump_sealed(&data) {
+                        return Some(Err(io::Error::other(
+                            "event journal is encrypted, decrypt it first",
+                        )));
+                    }
+                    Some(JournaledEvent::decode(&data).map_err(io::Error::other))
+                }))
+            })
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_roundtrip() {
+        let work_dir = std::env::temp_dir().join(format!(
+            "openmina-event-journal-test-{}",
+            std::process::id()
+        ));
+        let times = [1_000, 2_000, 3_000].map(redux::Timestamp::new);
+        {
+            let mut journal = EventJournal::new(&work_dir).unwrap();
+            for time in times {
+                journal
+                    .event(time, &Event::GenesisLoad(Err("x".to_owned())))
+                    .unwrap();
+            }
+        }
+
+        let reader = EventJournalReader::new(EventJournal::dir(&work_dir));
+        let events = reader
+            .read_events()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(events.iter().map(|e| e.time).collect::<Vec<_>>(), times);
+        assert!(events
+            .iter()
+            .all(|e| matches!(&e.event, Event::GenesisLoad(Err(err)) if err == "x")));
+
+        let _ = fs::remove_dir_all(&work_dir);
+    }
+
+    #[test]
+    fn journal_corrupted_length() {
+        let work_dir = std::env::temp_dir().join(format!(
+            "openmina-event-journal-corrupted-test-{}",
+            std::process::id()
+        ));
+        {
+            let mut journal = EventJournal::new(&work_dir).unwrap();
+            journal
+                .event(
+                    redux::Timestamp::new(1_000),
+                    &Event::GenesisLoad(Err("x".to_owned())),
+                )
+                .unwrap();
+            journal.file.write_all(&u64::MAX.to_be_bytes()).unwrap();
+            journal.file.write_all(&[0; 16]).unwrap();
+        }
+
+        let reader = EventJournalReader::new(EventJournal::dir(&work_dir));
+        let events = reader
+            .read_events()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(events.len(), 1);
+
+        let _ = fs::remove_dir_all(&work_dir);
+    }
+}
@@ -5,6 +5,9 @@ pub use recorder::Recorder;
 mod replayer;
 pub use replayer::StateWithInputActionsReader;
 
+mod event_journal;
+pub use event_journal::{EventJournal, EventJournalReader, JournaledEvent};
+
 use std::{
     borrow::Cow,
     io::Write,
@@ -13,6 +16,7 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::rng::RngDraw;
 use crate::p2p::identity::SecretKey as P2pSecretKey;
 use crate::{Action, ActionKind, ActionWithMeta, State};
 
@@ -47,6 +51,9 @@ pub struct RecordedActionWithMeta<'a> {
     pub kind: ActionKind,
     pub meta: redux::ActionMeta,
     pub action: Option<Cow<'a, Action>>,
+    /// Randomness drawn since the previous action was recorded, i.e.
+    /// in this action's reducer and in the previous action's effects.
+    pub rng_draws: Vec<RngDraw>,
 }
 
 impl RecordedActionWithMeta<'_> {
@@ -74,6 +81,7 @@ impl<'a> From<&'a ActionWithMeta> for RecordedActionWithMeta<'a> {
             kind: value.action().kind(),
             meta: value.meta().clone(),
             action: Some(Cow::Borrowed(value.action())),
+            rng_draws: Vec::new(),
         }
     }
 }
@@ -84,6 +92,7 @@ impl From<(ActionKind, redux::ActionMeta)> for RecordedActionWithMeta<'static> {
             kind,
             meta,
             action: None,
+            rng_draws: Vec::new(),
         }
     }
 }
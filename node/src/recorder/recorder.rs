@@ -34,6 +34,7 @@ impl Recorder {
 
         let file = fs::File::create(actions_path)
             .expect("creating file for openmina recorder initial state failed!");
+        openmina_core::rng::audit_enable();
         let mut actions_files = ACTIONS_F.try_lock().unwrap();
         actions_files.push(Some(file));
 
@@ -55,15 +56,27 @@ impl Recorder {
                     state: Cow::Borrowed(state),
                 };
                 let initial_state_path = super::initial_state_path(recorder_path);
-                let mut initial_state_f = fs::File::create(initial_state_path)
+                let mut encoded = Vec::new();
+                initial_state.write_to(&mut encoded).unwrap();
+                openmina_core::debug_dump::dump_write(initial_state_path, encoded)
                     .expect("creating file for openmina recorder initial state failed!");
-                initial_state.write_to(&mut initial_state_f).unwrap();
-                initial_state_f.sync_all().unwrap();
             }
         }
     }
 
     pub fn action(&mut self, action: &ActionWithMeta) {
+        // Taken even if not recording, so that the draws don't pile up.
+        let rng_draws = openmina_core::rng::audit_take();
+        // Recording with the gaps in it can't be replayed, so it's stopped
+        // instead of being paused.
+        if !matches!(self, Self::None) && openmina_core::debug_dump::are_writes_paused() {
+            openmina_core::warn!(
+                action.meta().time();
+                summary = "recording stopped, debug writes paused because of low disk space"
+            );
+            *self = Self::None;
+            return;
+        }
         match self {
             Self::None => {}
             Self::OnlyInputActions {
@@ -76,18 +89,24 @@ impl Recorder {
                 let is_input = match action.action() {
                     Action::CheckTimeouts(_) => true,
                     Action::EventSource(e) => match e {
-                        EventSourceAction::NewEvent { .. } => true,
+                        // Overload transitions depend on the queue depth,
+                        // which isn't available during the replay.
+                        EventSourceAction::NewEvent { .. }
+                        | EventSourceAction::OverloadStart { .. }
+                        | EventSourceAction::OverloadEnd { .. } => true,
+                        EventSourceAction::EventShed => false,
                         _ => return,
                     },
                     _ => false,
                 };
 
-                let data = if !is_input {
+                let mut data = if !is_input {
                     let kind = action.action().kind();
                     RecordedActionWithMeta::from((kind, action.meta().clone()))
                 } else {
                     RecordedActionWithMeta::from(action)
                 };
+                data.rng_draws = rng_draws;
 
                 let mut files = ACTIONS_F.try_lock().unwrap();
                 let cur_f = files.get_mut(*recorder_i).unwrap(); // TODO: error propagation
@@ -108,7 +127,9 @@ impl Recorder {
 
                 let mut writer = BufWriter::new(file);
 
-                let encoded = data.encode().unwrap();
+                // Each record is encrypted separately, so that the file stays
+                // readable up to the last complete record.
+                let encoded = openmina_core::debug_dump::dump_seal(data.encode().unwrap()).unwrap();
                 // RecordedActionWithMeta::decode(&encoded)
                 //     .expect(&format!("failed to decode encoded message: {:?}", data));
                 writer
@@ -1,10 +1,11 @@
+use openmina_core::profiler::{profiler_action_scope, ProfilerPhase};
 use openmina_core::{bug_condition, error, Substate};
 use p2p::{P2pAction, P2pEffectfulAction, P2pInitializeAction, P2pState};
 
 use crate::{
     external_snark_worker::ExternalSnarkWorkers,
     rpc::RpcState,
-    state::{BlockProducerState, LedgerState},
+    state::{BlockProducerState, EventSourceState, LedgerState},
     transition_frontier::candidate::TransitionFrontierCandidateAction,
     Action, ActionWithMeta, EventSourceAction, P2p, State,
 };
@@ -15,6 +16,8 @@ pub fn reducer(
     dispatcher: &mut redux::Dispatcher<Action, State>,
 ) {
     let meta = action.meta().clone();
+    let _profiler_scope =
+        profiler_action_scope(ProfilerPhase::Reducer, action.action().kind() as u16);
     match action.action() {
         Action::CheckTimeouts(_) => {
             if state.p2p.ready().is_some() {
@@ -27,7 +30,9 @@ pub fn reducer(
             dispatcher.push(TransitionFrontierCandidateAction::TransitionFrontierSyncTargetUpdate);
         }
         Action::EventSource(EventSourceAction::NewEvent { .. }) => {}
-        Action::EventSource(_) => {}
+        Action::EventSource(action) => {
+            EventSourceState::reducer(Substate::new(state, dispatcher), meta.with_action(action));
+        }
         Action::P2p(a) => match a {
             P2pAction::Initialization(P2pInitializeAction::Initialize { chain_id }) => {
                 if let Err(err) = state.p2p.initialize(chain_id) {
@@ -105,6 +110,19 @@ pub fn reducer(
                 meta.with_action(a),
             );
         }
+        Action::Watchtower(action) => {
+            crate::watchtower::WatchtowerState::reducer(
+                Substate::new(state, dispatcher),
+                meta.with_action(action),
+            );
+        }
+        Action::Checkpoint(action) => {
+            crate::checkpoint::CheckpointState::reducer(
+                Substate::new(state, dispatcher),
+                meta.with_action(action),
+            );
+        }
+        Action::CheckpointEffectful(_) => {}
         Action::P2pCallbacks(action) => {
             State::p2p_callback_reducer(Substate::new(state, dispatcher), meta.with_action(action))
         }
@@ -1,25 +1,35 @@
 mod rpc_state;
 use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::time::Duration;
 
 use ark_ff::fields::arithmetic::InvalidBigInt;
 use ledger::scan_state::currency::{Amount, Balance, Fee, Nonce, Slot};
 use ledger::scan_state::transaction_logic::signed_command::SignedCommandPayload;
-use ledger::scan_state::transaction_logic::{signed_command, valid, Memo};
+use ledger::scan_state::transaction_logic::zkapp_command::{AuthorizationKind, Control};
+use ledger::scan_state::transaction_logic::{
+    signed_command, valid, verifiable, Memo, TransactionFailure,
+};
 use ledger::transaction_pool::{diff, ValidCommandWithHash};
-use ledger::{Account, AccountId};
+use ledger::zkapps::preconditions_report::AccountUpdatePreconditionsReport;
+use ledger::{Account, AccountId, AccountIndex, FpExt, VerificationKey};
 use mina_p2p_messages::bigint::BigInt;
 use mina_p2p_messages::v2::{
-    LedgerHash, MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseSignedCommandStableV2,
-    MinaBaseTransactionStatusStableV2, MinaBaseUserCommandStableV2,
-    MinaBaseZkappCommandTStableV1WireStableV1, MinaTransactionTransactionStableV2,
-    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StateHash, TransactionHash,
-    TransactionSnarkWorkTStableV2,
+    ConsensusBodyReferenceStableV1, ConsensusVrfOutputTruncatedStableV1, EpochSeed, LedgerHash,
+    MinaBaseSignatureStableV1, MinaBaseSignedCommandPayloadBodyStableV2,
+    MinaBaseSignedCommandStableV2, MinaBaseTransactionStatusStableV2, MinaBaseUserCommandStableV2,
+    MinaBaseVerificationKeyWireStableV1, MinaBaseZkappCommandTStableV1WireStableV1,
+    MinaBlockHeaderStableV2, MinaTransactionTransactionStableV2,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StagedLedgerDiffDiffStableV2, StateHash,
+    TransactionHash, TransactionSnarkWorkTStableV2,
 };
 use openmina_core::block::{AppliedBlock, ArcBlockWithHash};
+use openmina_core::consensus::finality::{FinalityEstimator, FinalityObservations};
 use openmina_core::consensus::{ConsensusConstants, ConsensusTime};
 use openmina_node_account::AccountPublicKey;
 use p2p::bootstrap::P2pNetworkKadBootstrapStats;
+use p2p::feature_flags::P2pFeatureFlags;
+use p2p::P2pNetworkPubsubDedupStats;
 pub use rpc_state::*;
 
 mod rpc_actions;
@@ -30,6 +40,9 @@ pub use rpc_reducer::collect_rpc_peers_info;
 
 mod rpc_impls;
 
+mod rpc_response_limits;
+pub use rpc_response_limits::*;
+
 mod heartbeat;
 pub use heartbeat::{NodeHeartbeat, ProducedBlockInfo, SignedNodeHeartbeat};
 
@@ -37,40 +50,79 @@ pub use openmina_core::requests::{RpcId, RpcIdType};
 
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
 use ledger::scan_state::scan_state::AvailableJobMessage;
-use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
+use mina_p2p_messages::v2::{CurrencyAmountStableV1, CurrencyFeeStableV1, NonZeroCurvePoint};
 use openmina_core::snark::SnarkJobId;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
+use snark::user_command_verify::{SnarkUserCommandVerifyState, SnarkUserCommandVerifyStatus};
+use snark::verify_admission::{SnarkVerifySource, SnarkVerifySourceStats};
+use snark::{SnarkVerifyTimeStats, SnarkWasmFeatures};
+use vrf::VrfThresholdCheck;
 
+use crate::block_producer::{BlockTemplate, BlockTemplateExternalDiff};
 use crate::external_snark_worker::{
     ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkSpecError,
 };
-use crate::ledger::read::{LedgerReadId, LedgerReadKind, LedgerStatus};
+use crate::ledger::audit::{LedgerAuditFinished, LedgerAuditId, LedgerAuditPending};
+use crate::ledger::read::{
+    LedgerReadId, LedgerReadKind, LedgerSessionId, LedgerSessionResponse, LedgerStatus,
+    LedgerStatusExtended,
+};
 use crate::ledger::write::LedgerWriteKind;
+use crate::p2p::channels::ChannelId;
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use crate::p2p::connection::{
+    P2pConnectionEvent, P2pHandshakeFailureStats, P2pPeerDialStats, P2pSessionResumptionStats,
+};
+use crate::p2p::service_impl::P2pIceServerInfo;
+use crate::p2p::webrtc::{P2pCapabilities, P2P_PROTOCOL_VERSION};
 use crate::p2p::PeerId;
 use crate::service::Queues;
 use crate::snark_pool::{JobCommitment, JobState, JobSummary};
+use crate::state_digest::StateDigestSnapshot;
 use crate::stats::actions::{ActionStatsForBlock, ActionStatsSnapshot};
 use crate::stats::block_producer::{
-    BlockProductionAttempt, BlockProductionAttemptWonSlot, VrfEvaluatorStats,
+    BlockProducerEpochReport, BlockProductionAttempt, BlockProductionAttemptWonSlot,
+    DiffTruncationStats, VrfEvaluatorStats,
 };
+use crate::stats::block_propagation::BlockPropagationTimeline;
+use crate::stats::history::StatsHistorySnapshot;
+use crate::stats::profiler::ProfilerStatus;
 use crate::stats::sync::SyncStatsSnapshot;
+use crate::stats::zkapp_failures::ZkappFailureEpochStats;
+use crate::transaction_pool::payouts::{PayoutBatch, PayoutBatchId, PAYOUTS_DEFAULT_MAX_PER_SEC};
+use crate::transaction_pool::{
+    TransactionPoolSnapshotDiff, TransactionPoolSnapshotId, TransactionPoolSnapshotInfo,
+    TransactionPoolState,
+};
+use crate::transition_frontier::sync::SyncPeerScore;
+use crate::transition_frontier::{InternalCommand, TransactionPosition, TransitionFrontierState};
+use crate::{BuildEnv, NodeProfile};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RpcRequest {
     StateGet(Option<String>),
     StatusGet,
     HeartbeatGet,
+    StateDigestGet,
     ActionStatsGet(ActionStatsQuery),
     SyncStatsGet(SyncStatsQuery),
     BlockProducerStatsGet,
+    BlockProducerEpochReportGet(Option<u32>),
+    // Failure reasons of the zkApp commands, starting from the epoch.
+    ZkappFailureStatsGet(Option<u32>),
+    // Durations of the proof verifications.
+    SnarkVerifyStatsGet,
+    StatsHistoryGet(StatsHistoryQuery),
+    // Block propagation timelines, starting from the height.
+    BlockPropagationGet(Option<u32>),
     MessageProgressGet,
     PeersGet,
+    P2pDiagnosticsGet(Option<usize>),
     P2pConnectionOutgoing(P2pConnectionOutgoingInitOpts),
     P2pConnectionIncoming(P2pConnectionIncomingInitOpts),
-    ScanStateSummaryGet(RpcScanStateSummaryGetQuery),
+    ScanStateSummaryGet(RpcScanStateSummaryGetQuery, Option<RpcContinuationToken>),
     SnarkPoolGet,
     SnarkPoolJobGet { job_id: SnarkJobId },
     SnarkPoolCompletedJobsGet,
@@ -84,7 +136,12 @@ pub enum RpcRequest {
     DiscoveryRoutingTable,
     DiscoveryBoostrapStats,
     TransactionPoolGet,
-    LedgerAccountsGet(AccountQuery),
+    TransactionPoolZkappStatsGet,
+    LedgerAccountsGet(AccountQuery, Option<RpcContinuationToken>),
+    LedgerAccountsAtBlockGet(RpcLedgerAccountsAtBlockQuery),
+    AccountNextNoncesGet(Vec<AccountPublicKey>),
+    InternalCommandsGet(RpcInternalCommandsQuery),
+    SyncPeerScoresGet,
     TransactionInject(Vec<MinaBaseUserCommandStableV2>),
     TransitionFrontierUserCommandsGet,
     BestChain(MaxLength),
@@ -96,7 +153,173 @@ pub enum RpcRequest {
     GenesisBlockGet,
     ConsensusTimeGet(ConsensusTimeQuery),
     LedgerStatusGet(LedgerHash),
+    // Ledger status of the best tip, with the scan state and pending
+    // coinbase summaries.
+    LedgerStatusExtendedGet,
     LedgerAccountDelegatorsGet(LedgerHash, AccountId),
+    LedgerSession(RpcLedgerSessionRequest),
+    TransactionInclusionProofGet(RpcTransactionInclusionProofQuery),
+    FinalityEstimateGet,
+    NodeInfoGet,
+    Profiler(RpcProfilerCommand),
+    MaintenanceMode(RpcMaintenanceModeCommand),
+    FeatureFlags(RpcFeatureFlagsCommand),
+    AccountSubscriptions(RpcAccountSubscriptionsCommand),
+    AccountEventsSubscribe,
+    SnarkPoolJobEventsSubscribe,
+    ZkappVkRegister(RpcZkappVkRegisterRequest),
+    ZkappPreconditionsEvaluate(Box<MinaBaseZkappCommandTStableV1WireStableV1>),
+    PayoutsBatchSubmit(RpcPayoutsBatchSubmitRequest),
+    PayoutsBatchGet(PayoutBatchId),
+    ZkappFeeSponsor(RpcZkappFeeSponsorRequest),
+    ChainImportBlock(ArcBlockWithHash),
+    CheckpointsGet,
+    TransactionPoolSnapshot(RpcTransactionPoolSnapshotCommand),
+    VrfVerify(RpcVrfVerifyQuery),
+    LedgerAudit(RpcLedgerAuditCommand),
+    // Template of the block for the next won slot.
+    BlockTemplateGet,
+    BlockTemplateSubmit(Box<RpcBlockTemplateSubmit>),
+}
+
+impl RpcRequest {
+    /// Whether the request changes the node's state, so it is rejected
+    /// while the node is in maintenance mode.
+    ///
+    /// Exhaustive, so that a new request has to be classified.
+    pub fn is_write(&self) -> bool {
+        match self {
+            Self::P2pConnectionOutgoing(_)
+            | Self::SnarkerJobCommit { .. }
+            | Self::TransactionInject(_)
+            | Self::ZkappVkRegister(_)
+            | Self::PayoutsBatchSubmit(_)
+            | Self::ZkappFeeSponsor(_)
+            | Self::ChainImportBlock(_)
+            | Self::BlockTemplateSubmit(_) => true,
+            Self::FeatureFlags(command) => matches!(command, RpcFeatureFlagsCommand::Set(_)),
+            Self::LedgerAudit(command) => matches!(command, RpcLedgerAuditCommand::Start),
+            Self::TransactionPoolSnapshot(command) => {
+                matches!(command, RpcTransactionPoolSnapshotCommand::Capture)
+            }
+            Self::AccountSubscriptions(command) => matches!(
+                command,
+                RpcAccountSubscriptionsCommand::Add(_) | RpcAccountSubscriptionsCommand::Remove(_)
+            ),
+            Self::LedgerSession(request) => matches!(
+                request,
+                RpcLedgerSessionRequest::Open | RpcLedgerSessionRequest::OpenLedger { .. }
+            ),
+            // Maintenance mode must be possible to leave, incoming
+            // connections are initiated by the peers.
+            Self::MaintenanceMode(_)
+            | Self::P2pConnectionIncoming(_)
+            | Self::Profiler(_)
+            | Self::StateGet(_)
+            | Self::StatusGet
+            | Self::HeartbeatGet
+            | Self::StateDigestGet
+            | Self::ActionStatsGet(_)
+            | Self::SyncStatsGet(_)
+            | Self::BlockProducerStatsGet
+            | Self::BlockProducerEpochReportGet(_)
+            | Self::ZkappFailureStatsGet(_)
+            | Self::SnarkVerifyStatsGet
+            | Self::StatsHistoryGet(_)
+            | Self::BlockPropagationGet(_)
+            | Self::MessageProgressGet
+            | Self::PeersGet
+            | Self::P2pDiagnosticsGet(_)
+            | Self::ScanStateSummaryGet(..)
+            | Self::SnarkPoolGet
+            | Self::SnarkPoolJobGet { .. }
+            | Self::SnarkPoolCompletedJobsGet
+            | Self::SnarkPoolPendingJobsGet
+            | Self::SnarkerConfig
+            | Self::SnarkerJobSpec { .. }
+            | Self::SnarkerWorkers
+            | Self::HealthCheck
+            | Self::ReadinessCheck
+            | Self::DiscoveryRoutingTable
+            | Self::DiscoveryBoostrapStats
+            | Self::TransactionPoolGet
+            | Self::TransactionPoolZkappStatsGet
+            | Self::LedgerAccountsGet(..)
+            | Self::LedgerAccountsAtBlockGet(_)
+            | Self::AccountNextNoncesGet(_)
+            | Self::InternalCommandsGet(_)
+            | Self::SyncPeerScoresGet
+            | Self::TransitionFrontierUserCommandsGet
+            | Self::BestChain(_)
+            | Self::ConsensusConstantsGet
+            | Self::TransactionStatusGet(_)
+            | Self::GetBlock(_)
+            | Self::PooledUserCommands(_)
+            | Self::PooledZkappCommands(_)
+            | Self::GenesisBlockGet
+            | Self::ConsensusTimeGet(_)
+            | Self::LedgerStatusGet(_)
+            | Self::LedgerStatusExtendedGet
+            | Self::LedgerAccountDelegatorsGet(..)
+            | Self::TransactionInclusionProofGet(_)
+            | Self::FinalityEstimateGet
+            | Self::NodeInfoGet
+            | Self::AccountEventsSubscribe
+            | Self::SnarkPoolJobEventsSubscribe
+            | Self::ZkappPreconditionsEvaluate(_)
+            | Self::PayoutsBatchGet(_)
+            | Self::CheckpointsGet
+            | Self::VrfVerify(_)
+            | Self::BlockTemplateGet => false,
+        }
+    }
+
+    /// Whether the request isn't critical for the node's operation, so
+    /// it is shed while the node is overloaded.
+    pub fn is_sheddable(&self) -> bool {
+        matches!(
+            self,
+            Self::ActionStatsGet(_)
+                | Self::SyncStatsGet(_)
+                | Self::BlockProducerStatsGet
+                | Self::BlockProducerEpochReportGet(_)
+                | Self::ZkappFailureStatsGet(_)
+                | Self::SnarkVerifyStatsGet
+                | Self::LedgerStatusExtendedGet
+                | Self::StatsHistoryGet(_)
+                | Self::BlockPropagationGet(_)
+                | Self::TransactionPoolZkappStatsGet
+        )
+    }
+
+    /// Whether the request is answered from the ledgers or the pools,
+    /// which a node in light mode doesn't maintain.
+    pub fn needs_ledger(&self) -> bool {
+        matches!(
+            self,
+            Self::ScanStateSummaryGet(..)
+                | Self::SnarkPoolGet
+                | Self::SnarkPoolJobGet { .. }
+                | Self::SnarkPoolCompletedJobsGet
+                | Self::SnarkPoolPendingJobsGet
+                | Self::TransactionPoolGet
+                | Self::TransactionPoolZkappStatsGet
+                | Self::LedgerAccountsGet(..)
+                | Self::LedgerAccountsAtBlockGet(_)
+                | Self::AccountNextNoncesGet(_)
+                | Self::TransactionInject(_)
+                | Self::TransactionStatusGet(_)
+                | Self::PooledUserCommands(_)
+                | Self::PooledZkappCommands(_)
+                | Self::LedgerStatusGet(_)
+                | Self::LedgerStatusExtendedGet
+                | Self::LedgerAccountDelegatorsGet(..)
+                | Self::LedgerSession(_)
+                | Self::ZkappPreconditionsEvaluate(_)
+                | Self::TransactionPoolSnapshot(_)
+                | Self::LedgerAudit(_)
+        )
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -161,6 +384,13 @@ pub struct SyncStatsQuery {
     pub limit: Option<usize>,
 }
 
+/// Time range of the stats history, both bounds are inclusive.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct StatsHistoryQuery {
+    pub from: Option<redux::Timestamp>,
+    pub to: Option<redux::Timestamp>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RpcScanStateSummaryGetQuery {
     ForBestTip,
@@ -196,12 +426,71 @@ pub struct RpcPeerInfo {
     pub incoming: bool,
     pub is_libp2p: bool,
     pub time: u64,
+    pub handshake_failures: P2pHandshakeFailureStats,
+}
+
+/// Default number of the latest connection errors in the
+/// [`RpcP2pDiagnostics`], if the request doesn't specify it.
+pub const P2P_DIAGNOSTICS_ERRORS_DEFAULT: usize = 20;
+
+/// State of the p2p layer, packaged for support requests.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcP2pDiagnostics {
+    pub peer_id: PeerId,
+    pub chain_id: String,
+    pub peers: Vec<RpcP2pDiagnosticsPeer>,
+    /// Latest connection milestones, oldest first.
+    pub connection_events: Vec<P2pConnectionEvent>,
+    /// Latest connection errors, newest first.
+    pub connection_errors: Vec<P2pConnectionEvent>,
+    pub handshake_failures: P2pHandshakeFailureStats,
+    /// Reconnects to the peers from the previous session.
+    pub session_resumption: P2pSessionResumptionStats,
+    pub ice_servers: Vec<P2pIceServerInfo>,
+    pub gossip_dedup: RpcP2pGossipDedupStats,
+}
+
+/// Gossip messages not sent to the peers, which already had them
+/// according to the per peer bloom filters.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcP2pGossipDedupStats {
+    /// Sends to the mesh peers, including the skipped ones.
+    pub checked: u64,
+    pub skipped: u64,
+    /// `skipped / checked`, the ratio of the saved bandwidth.
+    pub hit_rate: f64,
+}
+
+impl From<&P2pNetworkPubsubDedupStats> for RpcP2pGossipDedupStats {
+    fn from(stats: &P2pNetworkPubsubDedupStats) -> Self {
+        Self {
+            checked: stats.checked,
+            skipped: stats.skipped,
+            hit_rate: stats.hit_rate(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcP2pDiagnosticsPeer {
+    #[serde(flatten)]
+    pub info: RpcPeerInfo,
+    pub capabilities: Option<P2pCapabilities>,
+    pub dial_stats: P2pPeerDialStats,
+    /// Messages received from the peer, per channel.
+    pub channels_received: BTreeMap<ChannelId, u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcScanStateSummary {
     pub block: RpcScanStateSummaryBlock,
     pub scan_state: Vec<Vec<RpcScanStateSummaryScanStateJob>>,
+    /// Whether the scan state trees were truncated to the response limits.
+    #[serde(default)]
+    pub truncated: bool,
+    /// Pass to the next request to get the rest of the trees.
+    #[serde(default)]
+    pub continuation: Option<RpcContinuationToken>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -308,6 +597,7 @@ pub enum RpcSnarkerJobCommitResponse {
     JobNotFound,
     JobTaken,
     SnarkerBusy,
+    NodeInMaintenance,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -367,10 +657,43 @@ pub enum RpcStateGetError {
 pub type RpcStateGetResponse = Result<serde_json::Value, RpcStateGetError>;
 pub type RpcStatusGetResponse = Option<RpcNodeStatus>;
 pub type RpcHeartbeatGetResponse = Option<SignedNodeHeartbeat>;
+pub type RpcStateDigestGetResponse = Option<StateDigestSnapshot>;
 pub type RpcActionStatsGetResponse = Option<ActionStatsResponse>;
 pub type RpcSyncStatsGetResponse = Option<Vec<SyncStatsSnapshot>>;
 pub type RpcBlockProducerStatsGetResponse = Option<RpcBlockProducerStats>;
+pub type RpcBlockProducerEpochReportGetResponse = Option<BlockProducerEpochReport>;
+pub type RpcZkappFailureStatsGetResponse = Option<BTreeMap<u32, ZkappFailureEpochStats>>;
+pub type RpcSnarkVerifyStatsGetResponse = Option<RpcSnarkVerifyStats>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSnarkVerifyStats {
+    /// Wasm features the verifier was built with, `None` if not in wasm.
+    pub wasm_features: Option<SnarkWasmFeatures>,
+    pub block_verify: SnarkVerifyTimeStats,
+    /// Per batch of the proofs.
+    pub work_verify: SnarkVerifyTimeStats,
+}
+
+impl RpcSnarkVerifyStats {
+    pub fn new(state: &crate::State) -> Self {
+        Self {
+            wasm_features: SnarkWasmFeatures::current(),
+            block_verify: state.snark.block_verify.time_stats,
+            work_verify: state.snark.work_verify.time_stats,
+        }
+    }
+}
+pub type RpcStatsHistoryGetResponse = Option<StatsHistorySnapshot>;
+pub type RpcBlockPropagationGetResponse = Option<RpcBlockPropagation>;
 pub type RpcPeersGetResponse = Vec<RpcPeerInfo>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcBlockPropagation {
+    /// Sibling nodes to compare the timelines with.
+    pub siblings: Vec<String>,
+    pub blocks: Vec<BlockPropagationTimeline>,
+}
+pub type RpcP2pDiagnosticsGetResponse = Option<RpcP2pDiagnostics>;
 pub type RpcP2pConnectionOutgoingResponse = Result<(), String>;
 pub type RpcScanStateSummaryGetResponse = Result<RpcScanStateSummary, String>;
 pub type RpcSnarkPoolGetResponse = Vec<RpcSnarkPoolJobSummary>;
@@ -379,10 +702,13 @@ pub type RpcSnarkPoolPendingJobsGetResponse = Vec<JobState>;
 pub type RpcSnarkPoolJobGetResponse = Option<RpcSnarkPoolJobFull>;
 pub type RpcSnarkerConfigGetResponse = Option<RpcSnarkerConfig>;
 pub type RpcTransactionPoolResponse = Vec<ValidCommandWithHash>;
-pub type RpcLedgerSlimAccountsResponse = Vec<AccountSlim>;
+pub type RpcLedgerSlimAccountsResponse = Result<RpcLedgerSlimAccounts, String>;
 pub type RpcLedgerAccountsResponse = Vec<Account>;
 pub type RpcTransitionFrontierUserCommandsResponse = Vec<MinaBaseUserCommandStableV2>;
 pub type RpcBestChainResponse = Vec<AppliedBlock>;
+/// Block is accepted for the verification and application, which
+/// happens asynchronously, or was already applied.
+pub type RpcChainImportBlockResponse = Result<(), String>;
 pub type RpcConsensusConstantsGetResponse = ConsensusConstants;
 pub type RpcTransactionStatusGetResponse = TransactionStatus;
 pub type RpcPooledUserCommandsResponse = Vec<MinaBaseSignedCommandStableV2>;
@@ -390,8 +716,112 @@ pub type RpcPooledZkappCommandsResponse = Vec<MinaBaseZkappCommandTStableV1WireS
 pub type RpcGenesisBlockResponse = Option<ArcBlockWithHash>;
 pub type RpcConsensusTimeGetResponse = Option<ConsensusTime>;
 pub type RpcLedgerStatusGetResponse = Option<LedgerStatus>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcLedgerStatusExtended {
+    #[serde(flatten)]
+    pub ledger: LedgerStatusExtended,
+    /// Total currency after the best tip.
+    pub total_currency: CurrencyAmountStableV1,
+}
+
+pub type RpcLedgerStatusExtendedGetResponse = Option<RpcLedgerStatusExtended>;
 pub type RpcLedgerAccountDelegatorsGetResponse = Option<Vec<Account>>;
 
+/// Queries answered from the ledger pinned by the session, so that they
+/// are consistent with each other even if the best tip changes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcLedgerSessionRequest {
+    /// Opens a session pinning the ledger of the current best tip.
+    Open,
+    /// Opens a session pinning the selected ledger of the current best tip.
+    OpenLedger {
+        ledger: RpcLedgerSelector,
+    },
+    AccountsGet {
+        session_id: LedgerSessionId,
+        query: AccountQuery,
+    },
+    /// Accounts in the order of their index in the ledger.
+    AccountsPage {
+        session_id: LedgerSessionId,
+        offset: u64,
+        limit: u64,
+    },
+    Close {
+        session_id: LedgerSessionId,
+    },
+}
+
+pub type RpcLedgerSessionResponse = Result<LedgerSessionResponse, String>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcLedgerAuditCommand {
+    Status,
+    /// Starts the consistency audit of the best tip staged ledger in
+    /// the background.
+    Start,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcLedgerAuditStatus {
+    /// Id of the audit started by the request.
+    pub started: Option<LedgerAuditId>,
+    pub pending: Option<LedgerAuditPending>,
+    pub last: Option<LedgerAuditFinished>,
+}
+
+pub type RpcLedgerAuditResponse = Result<RpcLedgerAuditStatus, String>;
+
+/// Staged ledger diff assembled from the [`BlockTemplate`], for the node
+/// to use for the block of the won slot, instead of its own one.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcBlockTemplateSubmit {
+    pub global_slot: u32,
+    pub diff: StagedLedgerDiffDiffStableV2,
+}
+
+pub type RpcBlockTemplateGetResponse = Result<BlockTemplate, String>;
+pub type RpcBlockTemplateSubmitResponse = Result<BlockTemplateExternalDiff, String>;
+
+/// Ledger of the best tip.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum RpcLedgerSelector {
+    /// Staged ledger.
+    #[default]
+    BestTip,
+    /// Ledger used for the slot elections in the current epoch.
+    Staking,
+    /// Ledger used for the slot elections in the next epoch.
+    Next,
+}
+
+impl RpcLedgerSelector {
+    pub fn ledger_hash<'a>(&self, best_tip: &'a ArcBlockWithHash) -> &'a LedgerHash {
+        match self {
+            Self::BestTip => best_tip.merkle_root_hash(),
+            Self::Staking => best_tip.staking_epoch_ledger_hash(),
+            Self::Next => best_tip.next_epoch_ledger_hash(),
+        }
+    }
+}
+
+impl FromStr for RpcLedgerSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "best-tip" => Ok(Self::BestTip),
+            "staking" => Ok(Self::Staking),
+            "next" => Ok(Self::Next),
+            _ => Err(format!(
+                "unknown ledger `{s}`, expected `best-tip`, `staking` or `next`"
+            )),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, strum_macros::Display)]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionStatus {
@@ -488,6 +918,15 @@ pub enum RpcTransactionInjectResponse {
 //     }
 // }
 
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcLedgerSlimAccounts {
+    pub accounts: Vec<AccountSlim>,
+    /// Whether the accounts were truncated to the response limits.
+    pub truncated: bool,
+    /// Pass to the next request to get the rest of the accounts.
+    pub continuation: Option<RpcContinuationToken>,
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct AccountSlim {
     pub public_key: AccountPublicKey,
@@ -512,6 +951,8 @@ pub struct RpcNodeStatus {
     pub ledger: RpcNodeStatusLedger,
     pub snark_pool: RpcNodeStatusSnarkPool,
     pub transaction_pool: RpcNodeStatusTransactionPool,
+    /// Verifications queued, admitted and denied, per source.
+    pub snark_verify_admission: BTreeMap<SnarkVerifySource, SnarkVerifySourceStats>,
     pub current_block_production_attempt: Option<BlockProductionAttempt>,
     pub previous_block_production_attempt: Option<BlockProductionAttempt>,
     pub peers: Vec<RpcPeerInfo>,
@@ -528,6 +969,8 @@ pub struct RpcNodeStatusNetworkInfo {
     pub external_ip: Option<String>,
     pub client_port: Option<u16>,
     pub libp2p_port: Option<u16>,
+    /// Handshake failures with all the peers, per reason.
+    pub handshake_failures: P2pHandshakeFailureStats,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -535,6 +978,10 @@ pub struct RpcNodeStatusLedger {
     pub alive_masks_after_last_commit: usize,
     pub pending_writes: Vec<(LedgerWriteKind, redux::Timestamp)>,
     pub pending_reads: Vec<(LedgerReadId, LedgerReadKind, redux::Timestamp)>,
+    /// Number of times a peer reached its cap of the ledger reads.
+    pub reads_throttled: u64,
+    /// Peers whose ledger reads are currently deferred.
+    pub reads_throttled_peers: Vec<PeerId>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -590,6 +1037,7 @@ pub struct RpcBlockProducerStats {
     pub future_won_slots: Vec<BlockProductionAttemptWonSlot>,
     pub current_epoch_vrf_stats: Option<VrfEvaluatorStats>,
     pub vrf_stats: BTreeMap<u32, VrfEvaluatorStats>,
+    pub diff_truncation: DiffTruncationStats,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -673,8 +1121,974 @@ pub enum GetBlockQuery {
     Height(u32),
 }
 
+impl FromStr for GetBlockQuery {
+    type Err = String;
+
+    /// Parses either the block height or the state hash.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(height) = s.parse() {
+            return Ok(Self::Height(height));
+        }
+        s.parse()
+            .map(Self::Hash)
+            .map_err(|_| format!("expected block height or state hash, got `{s}`"))
+    }
+}
+
 pub type RpcGetBlockResponse = Option<AppliedBlock>;
 
+/// Accounts in the ledger as of the given block.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcLedgerAccountsAtBlockQuery {
+    pub block: GetBlockQuery,
+    pub account_query: AccountQuery,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcLedgerAccountsAtBlock {
+    pub block_hash: StateHash,
+    pub height: u32,
+    /// Staged ledger hash of the block, which the accounts were read from.
+    pub ledger_hash: LedgerHash,
+    pub accounts: Vec<Account>,
+}
+
+/// Blocks within the transition frontier are read from their ledgers.
+/// Older ones are looked up in the archive, see
+/// [`crate::transition_frontier::archive::archive_service::ArchiveService::accounts_at_block`].
+pub type RpcLedgerAccountsAtBlockResponse = Result<RpcLedgerAccountsAtBlock, String>;
+
+/// Next nonces of the accounts, to be used for the new commands.
+///
+/// The ledger nonces are read from the best tip ledger, and the commands
+/// in the transaction pool are taken into account once the read is done.
+/// The pool catches up with the new best tip asynchronously, when it is
+/// not at the same best tip yet, `pool_consistent` is false and the
+/// nonces might be off until the pool is updated. Commands still being
+/// verified aren't counted, so the nonce is only valid until another
+/// command of the account is sent.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcAccountNextNonces {
+    pub block_hash: StateHash,
+    pub height: u32,
+    /// Ledger of the block, which the nonces were read from.
+    pub ledger_hash: LedgerHash,
+    /// Whether the transaction pool is at the same best tip.
+    pub pool_consistent: bool,
+    pub accounts: Vec<RpcAccountNextNonce>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RpcAccountNextNonce {
+    pub public_key: AccountPublicKey,
+    /// `None` if the account doesn't exist in the ledger.
+    pub ledger_nonce: Option<Nonce>,
+    /// Commands of the account in the pool, which are applicable one
+    /// after another on top of the ledger nonce.
+    pub pool_commands: u32,
+    pub next_nonce: Nonce,
+}
+
+impl RpcAccountNextNonce {
+    /// `pool_nonce_range` is the nonce of the first command of the account
+    /// in the pool, and the one expected after its last command.
+    pub fn new(
+        public_key: AccountPublicKey,
+        account: Option<&Account>,
+        pool_nonce_range: Option<(Nonce, Nonce)>,
+    ) -> Self {
+        let ledger_nonce = account.map(|account| account.nonce);
+        let nonce = ledger_nonce.unwrap_or(Nonce::from_u32(0));
+        // Commands already applied in the ledger might still be in the
+        // pool, until it is updated to the best tip.
+        let (pool_commands, next_nonce) = match pool_nonce_range {
+            Some((first, target)) if target > nonce => (
+                target.as_u32().saturating_sub(first.max(nonce).as_u32()),
+                target,
+            ),
+            _ => (0, nonce),
+        };
+        Self {
+            public_key,
+            ledger_nonce,
+            pool_commands,
+            next_nonce,
+        }
+    }
+}
+
+pub type RpcAccountNextNoncesGetResponse = Result<RpcAccountNextNonces, String>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcTransactionInclusionProofQuery {
+    pub block_hash: StateHash,
+    pub tx_hash: TransactionHash,
+}
+
+/// Data needed to verify that the transaction was included in the block,
+/// without trusting the node:
+/// 1. `state_hash` can be computed from `header.protocol_state`.
+/// 2. Hash of the `staged_ledger_diff` (see [`ledger::staged_ledger::validate_block::block_body_hash`])
+///    must match the `body_reference` in the header.
+/// 3. Command found at `position` in the `staged_ledger_diff` must hash to `tx_hash`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcTransactionInclusionProof {
+    pub tx_hash: TransactionHash,
+    pub state_hash: StateHash,
+    pub height: u32,
+    pub header: MinaBlockHeaderStableV2,
+    pub body_reference: ConsensusBodyReferenceStableV1,
+    pub staged_ledger_diff: StagedLedgerDiffDiffStableV2,
+    pub position: TransactionPosition,
+    pub status: MinaBaseTransactionStatusStableV2,
+}
+
+impl RpcTransactionInclusionProof {
+    pub fn new(
+        block: &ArcBlockWithHash,
+        tx_hash: TransactionHash,
+        position: TransactionPosition,
+    ) -> Option<Self> {
+        let diff = block.body().diff();
+        let command = match position.diff_index {
+            0 => diff.0.commands.get(position.command_index as usize),
+            1 => diff
+                .1
+                .as_ref()?
+                .commands
+                .get(position.command_index as usize),
+            _ => None,
+        }?;
+        Some(Self {
+            tx_hash,
+            state_hash: block.hash().clone(),
+            height: block.height(),
+            header: block.header().clone(),
+            body_reference: block
+                .header()
+                .protocol_state
+                .body
+                .blockchain_state
+                .body_reference
+                .clone(),
+            staged_ledger_diff: block.body().staged_ledger_diff.clone(),
+            position,
+            status: command.status.clone(),
+        })
+    }
+}
+
+pub type RpcTransactionInclusionProofGetResponse = Option<RpcTransactionInclusionProof>;
+
+/// Confidence levels for which recommended number of confirmations is reported.
+pub const FINALITY_CONFIDENCE_LEVELS: [f64; 4] = [0.99, 0.999, 0.9999, 0.999999];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcFinalityEstimate {
+    pub best_tip: StateHash,
+    pub best_tip_height: u32,
+    pub k: u32,
+    pub observations: FinalityObservations,
+    pub adversarial_stake_estimate: f64,
+    /// Number of confirmations needed to reach the confidence level.
+    pub recommended_confirmations: Vec<RpcFinalityConfirmations>,
+    /// Best chain blocks, from the best tip to the root.
+    pub blocks: Vec<RpcBlockFinality>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcFinalityConfirmations {
+    pub confidence: f64,
+    pub confirmations: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcBlockFinality {
+    pub hash: StateHash,
+    pub height: u32,
+    pub confirmations: u32,
+    pub confidence: f64,
+    pub is_final: bool,
+}
+
+impl RpcFinalityEstimate {
+    pub fn new(transition_frontier: &TransitionFrontierState) -> Option<Self> {
+        let best_tip = transition_frontier.best_tip()?;
+        let observations = transition_frontier.finality_observations()?;
+        let k = best_tip.constants().k.as_u32();
+        let estimator = FinalityEstimator::new(k, &observations);
+
+        let recommended_confirmations = FINALITY_CONFIDENCE_LEVELS
+            .iter()
+            .map(|confidence| RpcFinalityConfirmations {
+                confidence: *confidence,
+                confirmations: estimator.confirmations_for(*confidence),
+            })
+            .collect();
+        let blocks = transition_frontier
+            .best_chain
+            .iter()
+            .rev()
+            .map(|block| {
+                let confirmations = best_tip.height().saturating_sub(block.height());
+                RpcBlockFinality {
+                    hash: block.hash().clone(),
+                    height: block.height(),
+                    confirmations,
+                    confidence: estimator.confidence(confirmations),
+                    is_final: confirmations >= k,
+                }
+            })
+            .collect();
+
+        Some(Self {
+            best_tip: best_tip.hash().clone(),
+            best_tip_height: best_tip.height(),
+            k,
+            adversarial_stake_estimate: estimator.adversarial_stake(),
+            observations,
+            recommended_confirmations,
+            blocks,
+        })
+    }
+}
+
+pub type RpcFinalityEstimateGetResponse = Option<RpcFinalityEstimate>;
+
+/// Identity, build and protocol versions of the node, to check the
+/// compatibility with other nodes and tooling.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeInfo {
+    /// None until p2p is initialized.
+    pub chain_id: Option<String>,
+    pub peer_id: Option<PeerId>,
+    pub network: String,
+    pub profile: NodeProfile,
+    pub consensus_constants: ConsensusConstants,
+    /// Version of the webrtc p2p protocol.
+    pub p2p_protocol_version: u16,
+    pub channels: Vec<RpcNodeInfoChannel>,
+    pub capabilities: P2pCapabilities,
+    /// Libp2p rpc methods and their versions implemented by the node.
+    pub rpc_versions: Vec<RpcNodeInfoRpcVersion>,
+    /// Digests of the transaction, merge and blockchain circuits.
+    pub constraint_system_digests: Vec<String>,
+    pub build: BuildEnv,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeInfoChannel {
+    pub id: ChannelId,
+    pub enabled: bool,
+    pub supported_by_libp2p: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeInfoRpcVersion {
+    pub name: String,
+    pub version: u32,
+}
+
+impl RpcNodeInfo {
+    pub fn new(state: &crate::State) -> Self {
+        let network = openmina_core::NetworkConfig::global();
+        let p2p_config = state.p2p.config();
+        Self {
+            chain_id: state.p2p.ready().map(|p2p| p2p.chain_id.to_hex()),
+            peer_id: state.p2p.ready().map(|p2p| p2p.my_id()),
+            network: network.name.to_owned(),
+            profile: state.config.profile,
+            consensus_constants: state.config.consensus_constants.clone(),
+            p2p_protocol_version: P2P_PROTOCOL_VERSION,
+            channels: ChannelId::iter_all()
+                .map(|id| RpcNodeInfoChannel {
+                    id,
+                    enabled: p2p_config.enabled_channels.contains(&id),
+                    supported_by_libp2p: id.supported_by_libp2p(),
+                })
+                .collect(),
+            capabilities: P2pCapabilities::local(
+                &p2p_config.enabled_channels,
+                state.p2p.feature_flags(),
+            ),
+            rpc_versions: p2p::RPC_IMPLEMENTED_VERSIONS
+                .iter()
+                .map(|(name, version)| RpcNodeInfoRpcVersion {
+                    name: (*name).to_owned(),
+                    version: *version,
+                })
+                .collect(),
+            constraint_system_digests: network
+                .constraint_system_digests
+                .iter()
+                .map(hex::encode)
+                .collect(),
+            build: (*state.config.build).clone(),
+        }
+    }
+}
+
+pub type RpcNodeInfoGetResponse = RpcNodeInfo;
+
+/// Transaction failure code and its message, as found in the failed
+/// transaction statuses. Same as in the OCaml node.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcTransactionFailureCode {
+    pub code: u8,
+    pub message: String,
+}
+
+impl RpcTransactionFailureCode {
+    /// All the failures, the app state precondition failure is listed
+    /// for each app state field.
+    pub fn all() -> Vec<Self> {
+        TransactionFailure::ALL
+            .iter()
+            .flat_map(|failure| match failure {
+                TransactionFailure::AccountAppStatePreconditionUnsatisfied(_) => (0..8u64)
+                    .map(TransactionFailure::AccountAppStatePreconditionUnsatisfied)
+                    .collect(),
+                failure => vec![failure.clone()],
+            })
+            .map(|failure| Self {
+                code: failure.code(),
+                message: failure.to_string(),
+            })
+            .collect()
+    }
+}
+
+pub type RpcTransactionFailureCodesGetResponse = Vec<RpcTransactionFailureCode>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcInternalCommandsQuery {
+    Block(GetBlockQuery),
+    Receiver(AccountPublicKey),
+}
+
+/// Coinbase or fee transfer of the block in the best chain.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcInternalCommand {
+    pub block_hash: StateHash,
+    pub height: u32,
+    #[serde(flatten)]
+    pub command: InternalCommand,
+}
+
+impl RpcInternalCommand {
+    /// Internal commands of the block, or the ones received by the account
+    /// in the blocks of the best chain.
+    pub fn query(
+        transition_frontier: &TransitionFrontierState,
+        query: &RpcInternalCommandsQuery,
+    ) -> RpcInternalCommandsGetResponse {
+        let index = &transition_frontier.internal_command_index;
+        let new = |block: &AppliedBlock, command: &InternalCommand| Self {
+            block_hash: block.hash().clone(),
+            height: block.height(),
+            command: command.clone(),
+        };
+        match query {
+            RpcInternalCommandsQuery::Block(block_query) => {
+                let block = transition_frontier
+                    .best_chain
+                    .iter()
+                    .find(|block| match block_query {
+                        GetBlockQuery::Hash(hash) => block.hash() == hash,
+                        GetBlockQuery::Height(height) => block.height() == *height,
+                    })
+                    .ok_or_else(|| "block is not in the best chain".to_owned())?;
+                let commands = index
+                    .get(block.hash())
+                    .ok_or_else(|| "block was not applied by this node".to_owned())?;
+                Ok(commands.iter().map(|cmd| new(block, cmd)).collect())
+            }
+            RpcInternalCommandsQuery::Receiver(receiver) => Ok(transition_frontier
+                .best_chain
+                .iter()
+                .flat_map(|block| {
+                    index
+                        .get(block.hash())
+                        .unwrap_or_default()
+                        .iter()
+                        .filter(|cmd| &cmd.receiver == receiver)
+                        .map(move |cmd| new(block, cmd))
+                })
+                .collect()),
+        }
+    }
+}
+
+pub type RpcInternalCommandsGetResponse = Result<Vec<RpcInternalCommand>, String>;
+
+/// Score of the peer as a source of the sync rpcs, see
+/// [`crate::transition_frontier::sync::TransitionFrontierSyncPeerScores`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSyncPeerScore {
+    pub peer_id: PeerId,
+    pub score: f64,
+    pub connected: bool,
+    #[serde(flatten)]
+    pub stats: SyncPeerScore,
+}
+
+impl RpcSyncPeerScore {
+    /// Scored peers, best first.
+    pub fn ranking(state: &crate::State) -> Vec<Self> {
+        state
+            .transition_frontier
+            .sync_peer_scores
+            .ranking()
+            .into_iter()
+            .map(|(peer_id, stats)| Self {
+                peer_id,
+                score: stats.score(),
+                connected: state.p2p.get_ready_peer(&peer_id).is_some(),
+                stats: stats.clone(),
+            })
+            .collect()
+    }
+}
+
+pub type RpcSyncPeerScoresGetResponse = Vec<RpcSyncPeerScore>;
+
+/// Statistics of the zkApp commands in the transaction pool.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RpcTransactionPoolZkappStats {
+    /// zkApp commands in the pool.
+    pub commands: usize,
+    /// Account updates of those commands, fee payers not included.
+    pub account_updates: usize,
+    pub account_updates_per_command_avg: f64,
+    pub account_updates_by_authorization: RpcZkappAuthorizationCounts,
+    /// Number of the account updates authorized with the proof, by the
+    /// decimal hash of the verification key.
+    pub account_updates_by_vk_hash: BTreeMap<String, usize>,
+    pub verification_backlog: RpcZkappVerificationBacklog,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RpcZkappAuthorizationCounts {
+    pub proof: usize,
+    pub signature: usize,
+    pub none_given: usize,
+}
+
+/// zkApp commands which aren't in the pool yet, because they are
+/// waiting for their proofs and signatures to be verified.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RpcZkappVerificationBacklog {
+    /// Received from the peers, verification not started yet.
+    pub received: usize,
+    /// Being verified.
+    pub verifying: usize,
+    /// Proofs of the commands being verified.
+    pub verifying_proofs: usize,
+}
+
+impl RpcTransactionPoolZkappStats {
+    pub fn new(
+        transaction_pool: &TransactionPoolState,
+        user_command_verify: &SnarkUserCommandVerifyState,
+    ) -> Self {
+        let mut stats = Self::default();
+        for cmd in transaction_pool.zkapp_commands() {
+            stats.commands = stats.commands.saturating_add(1);
+            stats = cmd.account_updates.fold(stats, |mut stats, update| {
+                stats.account_updates = stats.account_updates.saturating_add(1);
+                stats
+                    .account_updates_by_authorization
+                    .add(&update.authorization);
+                if let AuthorizationKind::Proof(vk_hash) = &update.body.authorization_kind {
+                    let count = stats
+                        .account_updates_by_vk_hash
+                        .entry(vk_hash.to_decimal())
+                        .or_default();
+                    *count = count.saturating_add(1);
+                }
+                stats
+            });
+        }
+        if stats.commands > 0 {
+            stats.account_updates_per_command_avg =
+                stats.account_updates as f64 / stats.commands as f64;
+        }
+
+        let backlog = &mut stats.verification_backlog;
+        backlog.received = transaction_pool.candidates.zkapp_commands_received_count();
+        let verifying = user_command_verify
+            .jobs
+            .iter()
+            .filter_map(|(_, job)| match job {
+                SnarkUserCommandVerifyStatus::Init { commands, .. }
+                | SnarkUserCommandVerifyStatus::Pending { commands, .. } => Some(commands),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|cmd| match &cmd.data {
+                verifiable::UserCommand::SignedCommand(_) => None,
+                verifiable::UserCommand::ZkAppCommand(cmd) => Some(cmd),
+            });
+        for cmd in verifying {
+            backlog.verifying = backlog.verifying.saturating_add(1);
+            let proofs = cmd.account_updates.fold(0_usize, |proofs, (update, _)| {
+                match update.authorization {
+                    Control::Proof(_) => proofs.saturating_add(1),
+                    _ => proofs,
+                }
+            });
+            backlog.verifying_proofs = backlog.verifying_proofs.saturating_add(proofs);
+        }
+        stats
+    }
+}
+
+impl RpcZkappAuthorizationCounts {
+    fn add(&mut self, authorization: &Control) {
+        let count = match authorization {
+            Control::Proof(_) => &mut self.proof,
+            Control::Signature(_) => &mut self.signature,
+            Control::NoneGiven => &mut self.none_given,
+        };
+        *count = count.saturating_add(1);
+    }
+}
+
+pub type RpcTransactionPoolZkappStatsGetResponse = RpcTransactionPoolZkappStats;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcProfilerCommand {
+    Start { frequency_hz: Option<u32> },
+    Stop,
+}
+
+pub type RpcProfilerResponse = Result<ProfilerStatus, String>;
+
+pub const RPC_MAINTENANCE_MODE_ERROR: &str = "node is in maintenance mode";
+
+pub const RPC_LIGHT_MODE_ERROR: &str = "node runs in light mode, without ledgers";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcMaintenanceModeCommand {
+    Status,
+    Enter,
+    Leave,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcMaintenanceStatus {
+    pub enabled: bool,
+    pub since: Option<redux::Timestamp>,
+    /// Block production started and the block isn't injected yet.
+    pub block_production_in_progress: bool,
+    /// Produced block waits for its slot time, after which it is injected
+    /// and broadcast. Node must be kept running until then, as the block
+    /// isn't handed off to another node.
+    #[serde(default)]
+    pub produced_block_broadcast_time: Option<redux::Timestamp>,
+    /// Slot we won next. In maintenance mode the block isn't produced,
+    /// so the node can be stopped before it without an interrupted
+    /// production.
+    pub next_won_slot_time: Option<redux::Timestamp>,
+    /// Transition frontier is applying blocks.
+    pub sync_pending: bool,
+    /// Ledger write is in progress, or the root snapshot is still
+    /// being written.
+    pub ledger_write_pending: bool,
+    /// Root snapshot of a commit made since the maintenance mode was
+    /// entered is written.
+    pub root_snapshot_persisted: bool,
+    /// Node can be stopped without losing the produced block or the
+    /// applied blocks.
+    pub safe_to_stop: bool,
+}
+
+impl RpcMaintenanceStatus {
+    pub fn new(state: &crate::State) -> Self {
+        let since = state.rpc.maintenance.as_ref().map(|m| m.since);
+        let block_production_in_progress = state.block_producer.is_producing();
+        let produced_block_broadcast_time = state
+            .block_producer
+            .with(None, |this| this.current.produced_block())
+            // injected 1s late to account for time drift between nodes,
+            // see `BlockProducerAction::BlockInject`.
+            .and_then(|block| block.timestamp().checked_add(1_000_000_000));
+        let next_won_slot_time = state
+            .block_producer
+            .current_won_slot()
+            .filter(|_| !block_production_in_progress)
+            .map(|won_slot| won_slot.slot_time);
+        let sync_pending = state.transition_frontier.sync.is_pending();
+        let ledger_write_pending =
+            state.ledger.write.is_in_progress() || state.ledger.root_persist_pending.is_some();
+        let root_snapshot_persisted = since.is_some_and(|since| {
+            state
+                .ledger
+                .root_persisted
+                .as_ref()
+                .is_some_and(|persisted| persisted.time >= since)
+        });
+        Self {
+            enabled: since.is_some(),
+            since,
+            block_production_in_progress,
+            produced_block_broadcast_time,
+            next_won_slot_time,
+            sync_pending,
+            ledger_write_pending,
+            root_snapshot_persisted,
+            safe_to_stop: root_snapshot_persisted
+                && !block_production_in_progress
+                && !sync_pending
+                && !ledger_write_pending,
+        }
+    }
+}
+
+pub type RpcMaintenanceModeResponse = RpcMaintenanceStatus;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcFeatureFlagsCommand {
+    Get,
+    /// Replaces the flags, the new connections are established with them.
+    Set(P2pFeatureFlags),
+}
+
+/// Feature flags in effect after the command.
+pub type RpcFeatureFlagsResponse = Result<P2pFeatureFlags, String>;
+
+/// Verified checkpoint, signed by us or received from the peers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcCheckpoint {
+    pub state_hash: StateHash,
+    pub height: u32,
+    pub signer: AccountPublicKey,
+    pub signature: MinaBaseSignatureStableV1,
+    pub received_at: Timestamp,
+    /// Peer we received the checkpoint from, `None` if signed by us.
+    pub sender: Option<PeerId>,
+    /// Whether our best chain has the same block at that height, `None`
+    /// if the height isn't in our best chain.
+    pub matches_best_chain: Option<bool>,
+}
+
+impl RpcCheckpoint {
+    pub fn list(state: &crate::State) -> Vec<Self> {
+        let best_chain = &state.transition_frontier.best_chain;
+        let block_at = |height: u32| {
+            let first = best_chain.first()?.height();
+            best_chain.get(height.checked_sub(first)? as usize)
+        };
+        state
+            .checkpoint
+            .checkpoints()
+            .map(|entry| Self {
+                state_hash: entry.id.state_hash.clone(),
+                height: entry.id.height,
+                signer: entry.id.signer.clone().into(),
+                signature: entry.signature.clone(),
+                received_at: entry.received_at,
+                sender: entry.sender,
+                matches_best_chain: block_at(entry.id.height)
+                    .map(|block| block.hash() == &entry.id.state_hash),
+            })
+            .collect()
+    }
+}
+
+pub type RpcCheckpointsGetResponse = Vec<RpcCheckpoint>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcTransactionPoolSnapshotCommand {
+    List,
+    Capture,
+    /// Compares the snapshot `from` with the snapshot `to`, or with the
+    /// current contents of the pool if `to` is `None`.
+    Diff {
+        from: TransactionPoolSnapshotId,
+        to: Option<TransactionPoolSnapshotId>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcTransactionPoolSnapshotResult {
+    List(Vec<TransactionPoolSnapshotInfo>),
+    Captured { id: TransactionPoolSnapshotId },
+    Diff(TransactionPoolSnapshotDiff),
+}
+
+pub type RpcTransactionPoolSnapshotResponse = Result<RpcTransactionPoolSnapshotResult, String>;
+
+/// Block in the best chain whose slot win is checked against the stake
+/// distribution of its staking epoch.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RpcVrfVerifyQuery {
+    pub state_hash: StateHash,
+}
+
+/// Slot win claimed by the block.
+///
+/// The block's proof covers the evaluation of the vrf with the seed of
+/// its staking epoch, so the output is only compared with the threshold
+/// given by the winner's stake.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RpcVrfVerifyClaim {
+    pub state_hash: StateHash,
+    pub producer: AccountPublicKey,
+    /// Global slot since the hard fork, as in the block's consensus state.
+    pub global_slot: u32,
+    /// `last_vrf_output` of the block.
+    pub vrf_output: ConsensusVrfOutputTruncatedStableV1,
+    /// Account whose stake won the slot, i.e. `block_stake_winner`.
+    pub winner: AccountPublicKey,
+}
+
+impl RpcVrfVerifyClaim {
+    pub fn new(block: &ArcBlockWithHash) -> Self {
+        Self {
+            state_hash: block.hash().clone(),
+            producer: block.producer().clone().into(),
+            global_slot: block.global_slot(),
+            vrf_output: block.consensus_state().last_vrf_output.clone(),
+            winner: block.block_stake_winner().clone().into(),
+        }
+    }
+}
+
+/// Staking epoch data used to evaluate the vrf at the slot.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RpcVrfEpochData {
+    pub epoch: u32,
+    pub staking_ledger_hash: LedgerHash,
+    pub seed: EpochSeed,
+    pub total_currency: u64,
+}
+
+impl RpcVrfEpochData {
+    /// Staking epoch data the block was produced with.
+    pub fn of_block(block: &ArcBlockWithHash) -> Self {
+        let consensus_state = block.consensus_state();
+        let staking = &consensus_state.staking_epoch_data;
+        Self {
+            epoch: consensus_state.epoch_count.as_u32(),
+            staking_ledger_hash: staking.ledger.hash.clone(),
+            seed: staking.seed.clone(),
+            total_currency: staking.ledger.total_currency.as_u64(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcVrfVerifyDelegator {
+    pub index: AccountIndex,
+    pub public_key: AccountPublicKey,
+    pub stake: u64,
+    #[serde(flatten)]
+    pub check: VrfThresholdCheck,
+}
+
+/// See [`RpcVrfVerifyClaim`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcVrfVerification {
+    pub claim: RpcVrfVerifyClaim,
+    pub epoch_data: RpcVrfEpochData,
+    /// Winner's stake delegated to the producer in the staking ledger.
+    pub winner: RpcVrfVerifyDelegator,
+    pub threshold_met: bool,
+}
+
+pub type RpcVrfVerifyResponse = Result<RpcVrfVerification, String>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcAccountSubscriptionsCommand {
+    List,
+    Add(AccountPublicKey),
+    Remove(AccountPublicKey),
+}
+
+/// Watched public keys, after the command was applied.
+pub type RpcAccountSubscriptionsResponse = Vec<AccountPublicKey>;
+
+/// Fields of the watched account, which are reported when changed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RpcWatchedAccount {
+    pub balance: u64,
+    pub nonce: u32,
+    pub delegate: Option<AccountPublicKey>,
+}
+
+impl From<&Account> for RpcWatchedAccount {
+    fn from(account: &Account) -> Self {
+        Self {
+            balance: account.balance.as_u64(),
+            nonce: account.nonce.as_u32(),
+            delegate: account.delegate.clone().map(Into::into),
+        }
+    }
+}
+
+/// Change of the watched account in the new best tip.
+///
+/// Streamed to [`RpcRequest::AccountEventsSubscribe`] until the receiver
+/// is dropped.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcWatchedAccountEvent {
+    pub public_key: AccountPublicKey,
+    pub block_hash: StateHash,
+    pub height: u32,
+    /// `None` if the account was created.
+    pub previous: Option<RpcWatchedAccount>,
+    pub current: RpcWatchedAccount,
+}
+
+/// Lifecycle event of the job in the snark pool.
+///
+/// Streamed to [`RpcRequest::SnarkPoolJobEventsSubscribe`] until the
+/// receiver is dropped.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcSnarkPoolJobEvent {
+    /// Job was added to the pool with the new best tip.
+    Added { job_id: SnarkJobId },
+    /// Snarker committed to produce the work for the job.
+    Committed {
+        job_id: SnarkJobId,
+        snarker: NonZeroCurvePoint,
+        fee: CurrencyFeeStableV1,
+    },
+    /// First work for the job was received.
+    WorkReceived {
+        job_id: SnarkJobId,
+        snarker: NonZeroCurvePoint,
+        fee: CurrencyFeeStableV1,
+    },
+    /// Work with the better price replaced the existing one.
+    PriceUpdated {
+        job_id: SnarkJobId,
+        snarker: NonZeroCurvePoint,
+        fee: CurrencyFeeStableV1,
+        previous_fee: CurrencyFeeStableV1,
+    },
+    /// Job is no longer in the scan state of the new best tip, usually
+    /// because its work was included in the block.
+    IncludedInBlock {
+        job_id: SnarkJobId,
+        block_hash: Option<StateHash>,
+    },
+}
+
+/// Message of the [`RpcRequest::SnarkPoolJobEventsSubscribe`] stream.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcSnarkPoolJobEventsMessage {
+    /// Events of a single pool update, in order.
+    Events(Vec<RpcSnarkPoolJobEvent>),
+    /// Subscriber didn't keep up with the events and was dropped. It is
+    /// the last message of the stream.
+    Overflow,
+}
+
+pub const RPC_ZKAPP_VK_REGISTER_DEFAULT_TTL: Duration = Duration::from_secs(30 * 60);
+pub const RPC_ZKAPP_VK_REGISTER_MAX_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Verification key to be used for the pool admission of the zkApp
+/// commands proved against it, before it's deployed to the ledger.
+///
+/// Encoded the same way as the verification key in the graphql.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcZkappVkRegisterRequest {
+    /// Base64 encoded binprot of the verification key.
+    pub data: String,
+    /// Decimal hash of the verification key, checked against the `data`.
+    pub hash: String,
+    /// Defaults to [`RPC_ZKAPP_VK_REGISTER_DEFAULT_TTL`], capped at
+    /// [`RPC_ZKAPP_VK_REGISTER_MAX_TTL`].
+    pub ttl_secs: Option<u64>,
+}
+
+impl RpcZkappVkRegisterRequest {
+    pub fn ttl(&self) -> Duration {
+        self.ttl_secs
+            .map_or(RPC_ZKAPP_VK_REGISTER_DEFAULT_TTL, Duration::from_secs)
+            .min(RPC_ZKAPP_VK_REGISTER_MAX_TTL)
+    }
+
+    /// Decodes the verification key and checks that its hash matches.
+    pub fn decode(&self) -> Result<(MinaBaseVerificationKeyWireStableV1, BigInt), String> {
+        let vk = MinaBaseVerificationKeyWireStableV1::from_base64(&self.data)
+            .map_err(|err| format!("invalid verification key: {err}"))?;
+        let hash = VerificationKey::try_from(&vk)
+            .map_err(|err| format!("invalid verification key: {err:?}"))?
+            .hash();
+        if hash.to_decimal() != self.hash {
+            return Err(format!(
+                "verification key hash mismatch, expected: {}, got: {}",
+                self.hash,
+                hash.to_decimal()
+            ));
+        }
+        Ok((vk, hash.into()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcZkappVkRegistered {
+    pub hash: String,
+    pub expires_at: Timestamp,
+}
+
+pub type RpcZkappVkRegisterResponse = Result<RpcZkappVkRegistered, String>;
+
+/// Reports of the preconditions of the account updates of the zkApp
+/// command, fee payer first, evaluated against the best tip.
+pub type RpcZkappPreconditionsEvaluateResponse =
+    Result<Vec<AccountUpdatePreconditionsReport>, String>;
+
+/// Batch of payments (e.g. of the delegation rewards) from the account of
+/// the payout key, injected into the pool with sequential nonces.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPayoutsBatchSubmitRequest {
+    /// Csv with `receiver,amount` lines, amounts in nanomina.
+    pub payments: String,
+    /// Fee of each payment, in nanomina.
+    pub fee: u64,
+    pub memo: Option<String>,
+    /// Defaults to [`PAYOUTS_DEFAULT_MAX_PER_SEC`].
+    pub max_per_sec: Option<u32>,
+}
+
+impl RpcPayoutsBatchSubmitRequest {
+    pub fn memo(&self) -> Result<String, String> {
+        let memo = self.memo.clone().unwrap_or_default();
+        Memo::from_str(&memo).map_err(|_| format!("invalid memo: {memo}"))?;
+        Ok(memo)
+    }
+
+    pub fn max_per_sec(&self) -> Result<u32, String> {
+        match self.max_per_sec {
+            Some(0) => Err("max_per_sec must be positive".to_owned()),
+            max_per_sec => Ok(max_per_sec.unwrap_or(PAYOUTS_DEFAULT_MAX_PER_SEC)),
+        }
+    }
+}
+
+pub type RpcPayoutsBatchSubmitResponse = Result<PayoutBatchId, String>;
+pub type RpcPayoutsBatchGetResponse = Option<PayoutBatch>;
+
+/// zkApp command whose fee is paid from the account of the node's fee
+/// sponsor, instead of the user's.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcZkappFeeSponsorRequest {
+    /// Who asked for the sponsoring (e.g. the dapp), limits are applied
+    /// per origin.
+    pub origin: String,
+    /// Command with the fee payer to be replaced, only its fee is kept.
+    pub command: Box<MinaBaseZkappCommandTStableV1WireStableV1>,
+}
+
+/// Same as for the injected commands, the command with the sponsor as
+/// the fee payer is returned once accepted.
+pub type RpcZkappFeeSponsorResponse = RpcTransactionInjectResponse;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PooledCommandsQuery<ID> {
     pub public_key: Option<AccountPublicKey>,
@@ -773,3 +2187,115 @@ pub mod discovery {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_ledger() {
+        for request in [
+            RpcRequest::TransactionPoolGet,
+            RpcRequest::SnarkPoolGet,
+            RpcRequest::LedgerStatusExtendedGet,
+            RpcRequest::AccountNextNoncesGet(Vec::new()),
+            RpcRequest::LedgerAccountsGet(AccountQuery::All, None),
+            RpcRequest::TransactionInject(Vec::new()),
+        ] {
+            assert!(request.needs_ledger(), "{request:?}");
+        }
+        // answered from the blocks and the p2p state, kept in light mode.
+        for request in [
+            RpcRequest::StatusGet,
+            RpcRequest::BestChain(10),
+            RpcRequest::PeersGet,
+            RpcRequest::ConsensusConstantsGet,
+            RpcRequest::GenesisBlockGet,
+        ] {
+            assert!(!request.needs_ledger(), "{request:?}");
+        }
+    }
+
+    #[test]
+    fn test_is_write() {
+        let public_key = AccountPublicKey::from(Account::empty().public_key);
+        for request in [
+            RpcRequest::TransactionInject(Vec::new()),
+            RpcRequest::FeatureFlags(RpcFeatureFlagsCommand::Set(Default::default())),
+            RpcRequest::LedgerAudit(RpcLedgerAuditCommand::Start),
+            RpcRequest::TransactionPoolSnapshot(RpcTransactionPoolSnapshotCommand::Capture),
+            RpcRequest::AccountSubscriptions(RpcAccountSubscriptionsCommand::Add(
+                public_key.clone(),
+            )),
+            RpcRequest::AccountSubscriptions(RpcAccountSubscriptionsCommand::Remove(public_key)),
+            RpcRequest::LedgerSession(RpcLedgerSessionRequest::Open),
+        ] {
+            assert!(request.is_write(), "{request:?}");
+        }
+        // Maintenance mode must stay possible to leave.
+        for request in [
+            RpcRequest::MaintenanceMode(RpcMaintenanceModeCommand::Leave),
+            RpcRequest::FeatureFlags(RpcFeatureFlagsCommand::Get),
+            RpcRequest::LedgerAudit(RpcLedgerAuditCommand::Status),
+            RpcRequest::TransactionPoolSnapshot(RpcTransactionPoolSnapshotCommand::List),
+            RpcRequest::AccountSubscriptions(RpcAccountSubscriptionsCommand::List),
+            RpcRequest::StatusGet,
+        ] {
+            assert!(!request.is_write(), "{request:?}");
+        }
+    }
+
+    #[test]
+    fn test_account_next_nonce() {
+        let nonce = Nonce::from_u32;
+        let mut account = Account::empty();
+        account.nonce = nonce(5);
+        let public_key = AccountPublicKey::from(account.public_key.clone());
+        let next = |account, range| RpcAccountNextNonce::new(public_key.clone(), account, range);
+
+        let res = next(Some(&account), None);
+        assert_eq!((res.pool_commands, res.next_nonce), (0, nonce(5)));
+
+        // Queued commands with nonces 5, 6 and 7.
+        let res = next(Some(&account), Some((nonce(5), nonce(8))));
+        assert_eq!((res.pool_commands, res.next_nonce), (3, nonce(8)));
+
+        // Pool isn't updated yet, the first two are already applied.
+        let res = next(Some(&account), Some((nonce(3), nonce(8))));
+        assert_eq!((res.pool_commands, res.next_nonce), (3, nonce(8)));
+        let res = next(Some(&account), Some((nonce(3), nonce(5))));
+        assert_eq!((res.pool_commands, res.next_nonce), (0, nonce(5)));
+
+        let res = next(None, None);
+        assert_eq!((res.ledger_nonce, res.next_nonce), (None, nonce(0)));
+    }
+
+    #[test]
+    fn test_vrf_verify_claim_of_block() {
+        let block = openmina_core::block::fixtures::best_tip_with_hash();
+        let consensus_state = block.consensus_state();
+
+        let claim = RpcVrfVerifyClaim::new(&block);
+        assert_eq!(&claim.state_hash, block.hash());
+        assert_eq!(claim.global_slot, block.global_slot());
+        assert_eq!(claim.vrf_output, consensus_state.last_vrf_output);
+        assert_eq!(
+            claim.winner,
+            AccountPublicKey::from(consensus_state.block_stake_winner.clone())
+        );
+        assert_eq!(
+            claim.producer,
+            AccountPublicKey::from(consensus_state.block_creator.clone())
+        );
+
+        // the seed is the one the block was produced with, not the one of
+        // some other block of the epoch.
+        let epoch_data = RpcVrfEpochData::of_block(&block);
+        assert_eq!(epoch_data.seed, consensus_state.staking_epoch_data.seed);
+        assert_eq!(
+            epoch_data.staking_ledger_hash,
+            consensus_state.staking_epoch_data.ledger.hash
+        );
+        assert_eq!(epoch_data.epoch, consensus_state.epoch_count.as_u32());
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
+
 use ledger::transaction_pool::{diff, ValidCommandWithHash};
 use ledger::{Account, AccountId};
 use mina_p2p_messages::v2::TokenIdKeyHash;
-use mina_p2p_messages::v2::{LedgerHash, MinaBaseUserCommandStableV2};
-use openmina_core::block::AppliedBlock;
+use mina_p2p_messages::v2::{
+    LedgerHash, MinaBaseUserCommandStableV2, MinaBaseZkappCommandTStableV1WireStableV1, StateHash,
+};
+use openmina_core::block::{AppliedBlock, ArcBlockWithHash};
 use openmina_core::snark::SnarkJobId;
 use openmina_core::ActionEvent;
 use openmina_node_account::AccountPublicKey;
@@ -10,15 +14,22 @@ use p2p::PeerId;
 use serde::{Deserialize, Serialize};
 
 use crate::external_snark_worker::SnarkWorkId;
+use crate::ledger::read::LedgerStatusExtended;
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts};
 use crate::p2p::connection::P2pConnectionResponse;
 
 use super::{
     ActionStatsQuery, ConsensusTimeQuery, GetBlockQuery, PooledUserCommandsQuery,
-    PooledZkappsCommandsQuery, RpcId, RpcLedgerAccountDelegatorsGetResponse,
-    RpcLedgerStatusGetResponse, RpcScanStateSummaryGetQuery, RpcScanStateSummaryScanStateJob,
-    SyncStatsQuery,
+    PooledZkappsCommandsQuery, RpcAccountSubscriptionsCommand, RpcBlockTemplateSubmit,
+    RpcContinuationToken, RpcFeatureFlagsCommand, RpcId, RpcInternalCommandsQuery,
+    RpcLedgerAccountDelegatorsGetResponse, RpcLedgerAccountsAtBlockQuery,
+    RpcLedgerAccountsAtBlockResponse, RpcLedgerAuditCommand, RpcLedgerSessionRequest,
+    RpcLedgerSessionResponse, RpcLedgerStatusGetResponse, RpcMaintenanceModeCommand,
+    RpcPayoutsBatchSubmitRequest, RpcProfilerCommand, RpcRequest, RpcScanStateSummaryGetQuery,
+    RpcScanStateSummaryScanStateJob, RpcSnarkPoolJobEvent, RpcTransactionInclusionProofQuery,
+    RpcTransactionPoolSnapshotCommand, RpcVrfVerifyQuery, RpcVrfVerifyResponse, RpcWatchedAccount,
+    RpcZkappFeeSponsorRequest, RpcZkappVkRegisterRequest, StatsHistoryQuery, SyncStatsQuery,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
@@ -33,6 +44,9 @@ pub enum RpcAction {
     HeartbeatGet {
         rpc_id: RpcId,
     },
+    StateDigestGet {
+        rpc_id: RpcId,
+    },
 
     // Stats
     ActionStatsGet {
@@ -46,6 +60,25 @@ pub enum RpcAction {
     BlockProducerStatsGet {
         rpc_id: RpcId,
     },
+    BlockProducerEpochReportGet {
+        rpc_id: RpcId,
+        epoch: Option<u32>,
+    },
+    ZkappFailureStatsGet {
+        rpc_id: RpcId,
+        from_epoch: Option<u32>,
+    },
+    SnarkVerifyStatsGet {
+        rpc_id: RpcId,
+    },
+    StatsHistoryGet {
+        rpc_id: RpcId,
+        query: StatsHistoryQuery,
+    },
+    BlockPropagationGet {
+        rpc_id: RpcId,
+        from_height: Option<u32>,
+    },
 
     MessageProgressGet {
         rpc_id: RpcId,
@@ -54,6 +87,10 @@ pub enum RpcAction {
     PeersGet {
         rpc_id: RpcId,
     },
+    P2pDiagnosticsGet {
+        rpc_id: RpcId,
+        errors: Option<usize>,
+    },
 
     P2pConnectionOutgoingInit {
         rpc_id: RpcId,
@@ -97,6 +134,7 @@ pub enum RpcAction {
     ScanStateSummaryGetInit {
         rpc_id: RpcId,
         query: RpcScanStateSummaryGetQuery,
+        continuation: Option<RpcContinuationToken>,
     },
     ScanStateSummaryLedgerGetInit {
         rpc_id: RpcId,
@@ -156,10 +194,14 @@ pub enum RpcAction {
     TransactionPool {
         rpc_id: RpcId,
     },
+    TransactionPoolZkappStatsGet {
+        rpc_id: RpcId,
+    },
     #[action_event(level = info)]
     LedgerAccountsGetInit {
         rpc_id: RpcId,
         account_query: AccountQuery,
+        continuation: Option<RpcContinuationToken>,
     },
     #[action_event(level = info)]
     LedgerAccountsGetPending {
@@ -168,8 +210,51 @@ pub enum RpcAction {
     #[action_event(level = info)]
     LedgerAccountsGetSuccess {
         rpc_id: RpcId,
-        accounts: Vec<Account>,
+        accounts: Result<Vec<Account>, String>,
         account_query: AccountQuery,
+        /// Set if the accounts were truncated to the response limits.
+        continuation: Option<RpcContinuationToken>,
+    },
+    #[action_event(level = info)]
+    LedgerAccountsAtBlockGetInit {
+        rpc_id: RpcId,
+        query: RpcLedgerAccountsAtBlockQuery,
+    },
+    LedgerAccountsAtBlockLedgerGetInit {
+        rpc_id: RpcId,
+    },
+    LedgerAccountsAtBlockGetPending {
+        rpc_id: RpcId,
+        block: Option<AppliedBlock>,
+    },
+    #[action_event(level = info)]
+    LedgerAccountsAtBlockGetSuccess {
+        rpc_id: RpcId,
+        accounts: Result<Vec<Account>, String>,
+    },
+    /// Block is older than the transition frontier, the accounts are
+    /// looked up in the archive.
+    LedgerAccountsAtBlockArchiveGetInit {
+        rpc_id: RpcId,
+    },
+    #[action_event(level = info)]
+    LedgerAccountsAtBlockArchiveGetSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsAtBlockResponse,
+    },
+    #[action_event(level = info)]
+    AccountNextNoncesGetInit {
+        rpc_id: RpcId,
+        public_keys: Vec<AccountPublicKey>,
+    },
+    AccountNextNoncesGetPending {
+        rpc_id: RpcId,
+        block: Option<AppliedBlock>,
+    },
+    #[action_event(level = info)]
+    AccountNextNoncesGetSuccess {
+        rpc_id: RpcId,
+        accounts: Vec<Account>,
     },
     #[action_event(level = info)]
     TransactionInjectInit {
@@ -232,6 +317,28 @@ pub enum RpcAction {
         rpc_id: RpcId,
         response: RpcLedgerStatusGetResponse,
     },
+    LedgerStatusExtendedGetInit {
+        rpc_id: RpcId,
+    },
+    LedgerStatusExtendedGetPending {
+        rpc_id: RpcId,
+        block: Option<AppliedBlock>,
+    },
+    LedgerStatusExtendedGetSuccess {
+        rpc_id: RpcId,
+        response: Option<LedgerStatusExtended>,
+    },
+    LedgerSessionInit {
+        rpc_id: RpcId,
+        request: RpcLedgerSessionRequest,
+    },
+    LedgerSessionPending {
+        rpc_id: RpcId,
+    },
+    LedgerSessionSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerSessionResponse,
+    },
     #[action_event(level = info)]
     LedgerAccountDelegatorsGetInit {
         rpc_id: RpcId,
@@ -248,6 +355,141 @@ pub enum RpcAction {
         response: RpcLedgerAccountDelegatorsGetResponse,
     },
 
+    TransactionInclusionProofGet {
+        rpc_id: RpcId,
+        query: RpcTransactionInclusionProofQuery,
+    },
+    FinalityEstimateGet {
+        rpc_id: RpcId,
+    },
+    NodeInfoGet {
+        rpc_id: RpcId,
+    },
+    InternalCommandsGet {
+        rpc_id: RpcId,
+        query: RpcInternalCommandsQuery,
+    },
+    SyncPeerScoresGet {
+        rpc_id: RpcId,
+    },
+    Profiler {
+        rpc_id: RpcId,
+        command: RpcProfilerCommand,
+    },
+    MaintenanceMode {
+        rpc_id: RpcId,
+        command: RpcMaintenanceModeCommand,
+    },
+    FeatureFlags {
+        rpc_id: RpcId,
+        command: RpcFeatureFlagsCommand,
+    },
+    CheckpointsGet {
+        rpc_id: RpcId,
+    },
+    TransactionPoolSnapshot {
+        rpc_id: RpcId,
+        command: RpcTransactionPoolSnapshotCommand,
+    },
+    VrfVerifyInit {
+        rpc_id: RpcId,
+        query: RpcVrfVerifyQuery,
+    },
+    VrfVerifyPending {
+        rpc_id: RpcId,
+    },
+    VrfVerifySuccess {
+        rpc_id: RpcId,
+        response: RpcVrfVerifyResponse,
+    },
+    LedgerAudit {
+        rpc_id: RpcId,
+        command: RpcLedgerAuditCommand,
+    },
+    BlockTemplateGet {
+        rpc_id: RpcId,
+    },
+    BlockTemplateSubmit {
+        rpc_id: RpcId,
+        submit: Box<RpcBlockTemplateSubmit>,
+    },
+    /// Request was rejected, because the node is in maintenance mode.
+    MaintenanceRejected {
+        rpc_id: RpcId,
+        request: RpcRequest,
+    },
+    /// Request was shed, because the node is overloaded.
+    OverloadShed {
+        rpc_id: RpcId,
+        request: RpcRequest,
+    },
+    /// Request was rejected, because it needs the ledgers, which the
+    /// node in light mode doesn't have.
+    LightModeRejected {
+        rpc_id: RpcId,
+        request: RpcRequest,
+    },
+
+    AccountSubscriptions {
+        rpc_id: RpcId,
+        command: RpcAccountSubscriptionsCommand,
+    },
+    AccountEventsSubscribe {
+        rpc_id: RpcId,
+    },
+    /// Subscriber's channel was closed.
+    AccountEventsUnsubscribe {
+        rpc_id: RpcId,
+    },
+    /// Read the watched accounts from the best tip ledger.
+    AccountSubscriptionsEvaluate,
+    /// Watched accounts read from the best tip ledger, `None` if missing.
+    AccountSubscriptionsUpdate {
+        block_hash: StateHash,
+        height: u32,
+        accounts: BTreeMap<AccountPublicKey, Option<RpcWatchedAccount>>,
+    },
+
+    SnarkPoolJobEventsSubscribe {
+        rpc_id: RpcId,
+    },
+    /// Subscriber's channel was closed.
+    SnarkPoolJobEventsUnsubscribe {
+        rpc_id: RpcId,
+    },
+    /// Sends the events to the subscribers.
+    SnarkPoolJobEventsPublish {
+        events: Vec<RpcSnarkPoolJobEvent>,
+    },
+
+    ZkappVkRegister {
+        rpc_id: RpcId,
+        request: RpcZkappVkRegisterRequest,
+    },
+    ZkappPreconditionsEvaluate {
+        rpc_id: RpcId,
+        command: Box<MinaBaseZkappCommandTStableV1WireStableV1>,
+    },
+    PayoutsBatchSubmit {
+        rpc_id: RpcId,
+        request: RpcPayoutsBatchSubmitRequest,
+    },
+    PayoutsBatchGet {
+        rpc_id: RpcId,
+        batch_id: PayoutBatchId,
+    },
+    ZkappFeeSponsor {
+        rpc_id: RpcId,
+        request: RpcZkappFeeSponsorRequest,
+    },
+    /// Block of the chain archive, to be verified and applied on top of
+    /// the best tip.
+    #[action_event(level = info, fields(hash = display(block.hash()), height = block.height()))]
+    ChainImportBlock {
+        rpc_id: RpcId,
+        block: ArcBlockWithHash,
+    },
+
     PooledUserCommands {
         rpc_id: RpcId,
         query: PooledUserCommandsQuery,
@@ -279,11 +521,18 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::GlobalStateGet { .. } => true,
             RpcAction::StatusGet { .. } => true,
             RpcAction::HeartbeatGet { .. } => true,
+            RpcAction::StateDigestGet { .. } => true,
             RpcAction::ActionStatsGet { .. } => true,
             RpcAction::SyncStatsGet { .. } => true,
             RpcAction::BlockProducerStatsGet { .. } => true,
+            RpcAction::BlockProducerEpochReportGet { .. } => true,
+            RpcAction::ZkappFailureStatsGet { .. } => true,
+            RpcAction::SnarkVerifyStatsGet { .. } => true,
+            RpcAction::StatsHistoryGet { .. } => true,
+            RpcAction::BlockPropagationGet { .. } => true,
             RpcAction::MessageProgressGet { .. } => true,
             RpcAction::PeersGet { .. } => true,
+            RpcAction::P2pDiagnosticsGet { .. } => true,
             RpcAction::P2pConnectionOutgoingInit { rpc_id, .. } => {
                 !state.rpc.requests.contains_key(rpc_id)
             }
@@ -355,6 +604,7 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::DiscoveryRoutingTable { .. } => true,
             RpcAction::DiscoveryBoostrapStats { .. } => true,
             RpcAction::TransactionPool { .. } => true,
+            RpcAction::TransactionPoolZkappStatsGet { .. } => true,
             RpcAction::ConsensusConstantsGet { .. } => true,
             RpcAction::BestChain { .. } => state.transition_frontier.best_tip().is_some(),
             RpcAction::TransactionStatusGet { .. } => true,
@@ -374,6 +624,35 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
                 .requests
                 .get(rpc_id)
                 .is_some_and(|v| v.status.is_pending()),
+            RpcAction::LedgerAccountsAtBlockGetInit { .. } => true,
+            RpcAction::LedgerAccountsAtBlockLedgerGetInit { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::LedgerAccountsAtBlockGetPending { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::LedgerAccountsAtBlockGetSuccess { rpc_id, .. }
+            | RpcAction::LedgerAccountsAtBlockArchiveGetInit { rpc_id }
+            | RpcAction::LedgerAccountsAtBlockArchiveGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
+            RpcAction::AccountNextNoncesGetInit { .. } => true,
+            RpcAction::AccountNextNoncesGetPending { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::AccountNextNoncesGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
 
             RpcAction::TransactionInjectInit { .. } => true,
             RpcAction::TransactionInjectPending { rpc_id } => state
@@ -410,6 +689,28 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
                 .requests
                 .get(rpc_id)
                 .is_some_and(|v| v.status.is_pending()),
+            RpcAction::LedgerStatusExtendedGetInit { .. } => true,
+            RpcAction::LedgerStatusExtendedGetPending { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::LedgerStatusExtendedGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
+            RpcAction::LedgerSessionInit { .. } => true,
+            RpcAction::LedgerSessionPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::LedgerSessionSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
             RpcAction::LedgerAccountDelegatorsGetInit { .. } => {
                 state.transition_frontier.best_tip().is_some()
             }
@@ -423,6 +724,62 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
                 .requests
                 .get(rpc_id)
                 .is_some_and(|v| v.status.is_pending()),
+            RpcAction::TransactionInclusionProofGet { .. } => true,
+            RpcAction::FinalityEstimateGet { .. } => true,
+            RpcAction::NodeInfoGet { .. } => true,
+            RpcAction::InternalCommandsGet { .. } => true,
+            RpcAction::SyncPeerScoresGet { .. } => true,
+            RpcAction::Profiler { .. } => true,
+            RpcAction::MaintenanceMode { .. } => true,
+            RpcAction::FeatureFlags { .. } => true,
+            RpcAction::CheckpointsGet { .. } => true,
+            RpcAction::TransactionPoolSnapshot { .. } => true,
+            RpcAction::VrfVerifyInit { .. } => true,
+            RpcAction::VrfVerifyPending { rpc_id } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_init()),
+            RpcAction::VrfVerifySuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .is_some_and(|v| v.status.is_pending()),
+            RpcAction::LedgerAudit { .. } => true,
+            RpcAction::BlockTemplateGet { .. } => true,
+            RpcAction::BlockTemplateSubmit { .. } => true,
+            RpcAction::MaintenanceRejected { .. } => state.rpc.is_in_maintenance(),
+            RpcAction::OverloadShed { request, .. } => {
+                request.is_sheddable() && state.event_source.is_overloaded()
+            }
+            RpcAction::LightModeRejected { request, .. } => {
+                request.needs_ledger() && state.transition_frontier.light
+            }
+            RpcAction::AccountSubscriptions { .. } => true,
+            RpcAction::AccountEventsSubscribe { .. } => true,
+            RpcAction::AccountEventsUnsubscribe { rpc_id } => {
+                state.rpc.account_subscriptions.subscribers.contains(rpc_id)
+            }
+            RpcAction::AccountSubscriptionsEvaluate => {
+                !state.rpc.account_subscriptions.watched.is_empty()
+                    && state.transition_frontier.best_tip().is_some()
+            }
+            RpcAction::AccountSubscriptionsUpdate { .. } => {
+                !state.rpc.account_subscriptions.watched.is_empty()
+            }
+            RpcAction::SnarkPoolJobEventsSubscribe { .. } => true,
+            RpcAction::SnarkPoolJobEventsUnsubscribe { rpc_id } => {
+                state.rpc.snark_pool_job_subscribers.contains(rpc_id)
+            }
+            RpcAction::SnarkPoolJobEventsPublish { events } => {
+                !events.is_empty() && !state.rpc.snark_pool_job_subscribers.is_empty()
+            }
+            RpcAction::ZkappVkRegister { .. } => true,
+            RpcAction::ZkappPreconditionsEvaluate { .. } => true,
+            RpcAction::PayoutsBatchSubmit { .. } => true,
+            RpcAction::PayoutsBatchGet { .. } => true,
+            RpcAction::ZkappFeeSponsor { .. } => true,
+            RpcAction::ChainImportBlock { .. } => true,
             RpcAction::Finish { rpc_id } => state
                 .rpc
                 .requests
@@ -1,33 +1,62 @@
-use ledger::scan_state::transaction_logic::valid;
+use ledger::{
+    scan_state::{currency::Fee, transaction_logic::valid},
+    AccountId, TokenId,
+};
 use mina_p2p_messages::v2::{
     MinaBaseSignedCommandStableV2, MinaBaseZkappCommandTStableV1WireStableV1, NonZeroCurvePoint,
     TransactionSnarkWorkTStableV2,
 };
+use mina_signer::CompressedPubKey;
 use openmina_core::{
-    block::AppliedBlock,
+    block::{AppliedBlock, ArcBlockWithHash},
     bug_condition,
-    requests::{RequestId, RpcId, RpcIdType},
+    requests::RpcId,
     transaction::{TransactionPoolMessageSource, TransactionWithHash},
 };
 use p2p::{
-    connection::{incoming::P2pConnectionIncomingAction, outgoing::P2pConnectionOutgoingAction},
+    connection::{
+        ice_restart::P2pConnectionIceRestartAction, incoming::P2pConnectionIncomingAction,
+        outgoing::P2pConnectionOutgoingAction,
+    },
+    feature_flags::P2pFeatureFlagsAction,
     webrtc::P2pConnectionResponse,
     PeerId,
 };
 use redux::ActionWithMeta;
 
 use crate::{
-    ledger::read::{LedgerReadAction, LedgerReadInitCallback, LedgerReadRequest},
+    block_producer::{
+        BlockProducerAction, BlockProducerExternalDiffStatus, BlockTemplate,
+        BlockTemplateExternalDiff,
+    },
+    ledger::{
+        audit::LedgerAuditAction,
+        read::{LedgerReadAction, LedgerReadInitCallback, LedgerReadRequest, LedgerSessionRequest},
+    },
     p2p_ready,
     rpc::{GetBlockQuery, PooledCommandsQuery},
     rpc_effectful::RpcEffectfulAction,
+    transaction_pool::{
+        payouts::{payouts_parse_csv, TransactionPoolPayoutsAction},
+        sponsor::TransactionPoolSponsorAction,
+    },
+    transition_frontier::candidate::{allow_block_too_late, TransitionFrontierCandidateAction},
     TransactionPoolAction,
 };
 
 use super::{
-    ConsensusTimeQuery, PeerConnectionStatus, RpcAction, RpcPeerInfo, RpcRequest,
+    AccountQuery, ConsensusTimeQuery, PeerConnectionStatus, RpcAccountNextNonce,
+    RpcAccountNextNonces, RpcAccountSubscriptionsCommand, RpcAction, RpcCheckpoint,
+    RpcFeatureFlagsCommand, RpcFinalityEstimate, RpcInternalCommand, RpcLedgerAccountsAtBlock,
+    RpcLedgerAuditCommand, RpcLedgerAuditStatus, RpcLedgerSelector, RpcLedgerSessionRequest,
+    RpcLedgerStatusExtended, RpcMaintenanceModeCommand, RpcMaintenanceState, RpcMaintenanceStatus,
+    RpcNodeInfo, RpcP2pDiagnostics, RpcP2pDiagnosticsPeer, RpcPeerInfo, RpcRequest,
     RpcRequestExtraData, RpcRequestState, RpcRequestStatus, RpcScanStateSummaryGetQuery,
-    RpcSnarkerConfig, RpcState,
+    RpcSnarkVerifyStats, RpcSnarkerConfig, RpcState, RpcSyncPeerScore,
+    RpcTransactionInclusionProof, RpcTransactionPoolSnapshotCommand,
+    RpcTransactionPoolSnapshotResult, RpcTransactionPoolZkappStats, RpcVrfEpochData,
+    RpcVrfVerifyClaim, RpcWatchedAccountState, RpcZkappVkRegistered,
+    P2P_DIAGNOSTICS_ERRORS_DEFAULT,
 };
 
 impl RpcState {
@@ -53,6 +82,10 @@ impl RpcState {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(RpcEffectfulAction::HeartbeatGet { rpc_id: *rpc_id });
             }
+            RpcAction::StateDigestGet { rpc_id } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::StateDigestGet { rpc_id: *rpc_id });
+            }
             RpcAction::ActionStatsGet { rpc_id, query } => {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(RpcEffectfulAction::ActionStatsGet {
@@ -71,6 +104,45 @@ impl RpcState {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(RpcEffectfulAction::BlockProducerStatsGet { rpc_id: *rpc_id });
             }
+            RpcAction::ZkappFailureStatsGet { rpc_id, from_epoch } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::ZkappFailureStatsGet {
+                    rpc_id: *rpc_id,
+                    from_epoch: *from_epoch,
+                });
+            }
+            RpcAction::SnarkVerifyStatsGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = Some(RpcSnarkVerifyStats::new(state));
+                dispatcher.push(RpcEffectfulAction::SnarkVerifyStatsGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::BlockProducerEpochReportGet { rpc_id, epoch } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::BlockProducerEpochReportGet {
+                    rpc_id: *rpc_id,
+                    epoch: *epoch,
+                });
+            }
+            RpcAction::StatsHistoryGet { rpc_id, query } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::StatsHistoryGet {
+                    rpc_id: *rpc_id,
+                    query: *query,
+                });
+            }
+            RpcAction::BlockPropagationGet {
+                rpc_id,
+                from_height,
+            } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::BlockPropagationGet {
+                    rpc_id: *rpc_id,
+                    from_height: *from_height,
+                });
+            }
             RpcAction::MessageProgressGet { rpc_id } => {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(RpcEffectfulAction::MessageProgressGet { rpc_id: *rpc_id });
@@ -83,6 +155,17 @@ impl RpcState {
                     peers,
                 });
             }
+            RpcAction::P2pDiagnosticsGet { rpc_id, errors } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = collect_rpc_p2p_diagnostics(
+                    state,
+                    errors.unwrap_or(P2P_DIAGNOSTICS_ERRORS_DEFAULT),
+                );
+                dispatcher.push(RpcEffectfulAction::P2pDiagnosticsGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
             RpcAction::P2pConnectionOutgoingInit { rpc_id, opts } => {
                 let rpc_state = RpcRequestState {
                     req: RpcRequest::P2pConnectionOutgoing(opts.clone()),
@@ -160,6 +243,16 @@ impl RpcState {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p = p2p_ready!(state.p2p, meta.time());
 
+                if p2p.ice_restart_offer_accept(opts.peer_id, &opts.offer) {
+                    dispatcher.push(P2pConnectionIceRestartAction::OfferReceived {
+                        peer_id: opts.peer_id,
+                        offer: opts.offer.clone(),
+                        rpc_id: Some(*rpc_id),
+                    });
+                    dispatcher.push(RpcAction::P2pConnectionIncomingPending { rpc_id: *rpc_id });
+                    return;
+                }
+
                 match p2p.incoming_accept(opts.peer_id, &opts.offer) {
                     Ok(_) => {
                         dispatcher.push(P2pConnectionIncomingAction::Init {
@@ -170,6 +263,10 @@ impl RpcState {
                             .push(RpcAction::P2pConnectionIncomingPending { rpc_id: *rpc_id });
                     }
                     Err(reason) => {
+                        dispatcher.push(P2pConnectionIncomingAction::Rejected {
+                            peer_id: opts.peer_id,
+                            reason,
+                        });
                         let response = P2pConnectionResponse::Rejected(reason);
                         dispatcher.push(RpcAction::P2pConnectionIncomingRespond {
                             rpc_id: *rpc_id,
@@ -227,9 +324,13 @@ impl RpcState {
                 dispatcher
                     .push(RpcEffectfulAction::P2pConnectionIncomingSuccess { rpc_id: *rpc_id });
             }
-            RpcAction::ScanStateSummaryGetInit { rpc_id, query } => {
+            RpcAction::ScanStateSummaryGetInit {
+                rpc_id,
+                query,
+                continuation,
+            } => {
                 let rpc_state = RpcRequestState {
-                    req: RpcRequest::ScanStateSummaryGet(query.clone()),
+                    req: RpcRequest::ScanStateSummaryGet(query.clone(), continuation.clone()),
                     status: RpcRequestStatus::Init { time: meta.time() },
                     data: Default::default(),
                 };
@@ -245,7 +346,7 @@ impl RpcState {
                 let Some(query) = None.or_else(|| {
                     let req = state.rpc.requests.get(rpc_id)?;
                     match &req.req {
-                        RpcRequest::ScanStateSummaryGet(query) => Some(query),
+                        RpcRequest::ScanStateSummaryGet(query, _) => Some(query),
                         _ => None,
                     }
                 }) else {
@@ -286,14 +387,10 @@ impl RpcState {
                     request: LedgerReadRequest::ScanStateSummary(
                         block.staged_ledger_hashes().clone(),
                     ),
-                    callback: LedgerReadInitCallback::RpcScanStateSummaryGetPending {
-                        callback: redux::callback!(
-                            on_ledger_read_init_rpc_scan_state_summary_get_pending((rpc_id: RequestId<RpcIdType>, block: AppliedBlock)) -> crate::Action{
-                                RpcAction::ScanStateSummaryGetPending { rpc_id, block: Some(block) }
-                            }
-                        ),
-                        args: (*rpc_id, block),
-                    },
+                    callback: LedgerReadInitCallback::new(RpcAction::ScanStateSummaryGetPending {
+                        rpc_id: *rpc_id,
+                        block: Some(block),
+                    }),
                 });
             }
             RpcAction::ScanStateSummaryGetPending { rpc_id, block } => {
@@ -474,12 +571,24 @@ impl RpcState {
                     response,
                 });
             }
+            RpcAction::TransactionPoolZkappStatsGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let stats = RpcTransactionPoolZkappStats::new(
+                    &state.transaction_pool,
+                    &state.snark.user_command_verify,
+                );
+                dispatcher.push(RpcEffectfulAction::TransactionPoolZkappStatsGet {
+                    rpc_id: *rpc_id,
+                    stats,
+                });
+            }
             RpcAction::LedgerAccountsGetInit {
                 rpc_id,
                 account_query,
+                continuation,
             } => {
                 let rpc_state = RpcRequestState {
-                    req: RpcRequest::LedgerAccountsGet(account_query.clone()),
+                    req: RpcRequest::LedgerAccountsGet(account_query.clone(), continuation.clone()),
                     status: RpcRequestStatus::Init { time: meta.time() },
                     data: Default::default(),
                 };
@@ -492,20 +601,33 @@ impl RpcState {
                     return;
                 };
 
-                dispatcher.push(LedgerReadAction::Init {
-                    request: LedgerReadRequest::AccountsForRpc(
+                let request = match (account_query, continuation) {
+                    // Next pages are read from the same ledger as the first one.
+                    (AccountQuery::All, Some(continuation)) => {
+                        LedgerReadRequest::AccountsPageForRpc(
+                            *rpc_id,
+                            continuation.ledger_hash.clone(),
+                            continuation.offset,
+                            state.config.rpc_response_limits,
+                        )
+                    }
+                    (AccountQuery::All, None) => LedgerReadRequest::AccountsPageForRpc(
+                        *rpc_id,
+                        ledger_hash.clone(),
+                        0,
+                        state.config.rpc_response_limits,
+                    ),
+                    _ => LedgerReadRequest::AccountsForRpc(
                         *rpc_id,
                         ledger_hash.clone(),
                         account_query.clone(),
                     ),
-                    callback: LedgerReadInitCallback::RpcLedgerAccountsGetPending {
-                        callback: redux::callback!(
-                            on_ledger_read_init_rpc_actions_get_init(rpc_id: RequestId<RpcIdType>) -> crate::Action{
-                                RpcAction::LedgerAccountsGetPending { rpc_id }
-                            }
-                        ),
-                        args: *rpc_id,
-                    },
+                };
+                dispatcher.push(LedgerReadAction::Init {
+                    request,
+                    callback: LedgerReadInitCallback::new(RpcAction::LedgerAccountsGetPending {
+                        rpc_id: *rpc_id,
+                    }),
                 })
             }
             RpcAction::LedgerAccountsGetPending { rpc_id } => {
@@ -518,6 +640,7 @@ impl RpcState {
                 rpc_id,
                 account_query,
                 accounts,
+                continuation,
             } => {
                 let Some(rpc) = state.requests.get_mut(rpc_id) else {
                     return;
@@ -529,6 +652,240 @@ impl RpcState {
                     rpc_id: *rpc_id,
                     account_query: account_query.clone(),
                     accounts: accounts.clone(),
+                    continuation: continuation.clone(),
+                });
+            }
+            RpcAction::LedgerAccountsAtBlockGetInit { rpc_id, query } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::LedgerAccountsAtBlockGet(query.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcAction::LedgerAccountsAtBlockLedgerGetInit { rpc_id: *rpc_id });
+            }
+            RpcAction::LedgerAccountsAtBlockLedgerGetInit { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+
+                let Some(query) = None.or_else(|| {
+                    let req = state.rpc.requests.get(rpc_id)?;
+                    match &req.req {
+                        RpcRequest::LedgerAccountsAtBlockGet(query) => Some(query),
+                        _ => None,
+                    }
+                }) else {
+                    return;
+                };
+
+                let find_fn = |block: &&AppliedBlock| match &query.block {
+                    GetBlockQuery::Hash(hash) => block.hash() == hash,
+                    GetBlockQuery::Height(height) => block.height() == *height,
+                };
+                let block = state
+                    .transition_frontier
+                    .best_chain
+                    .iter()
+                    .rev()
+                    .find(find_fn);
+                let Some(block) = block.cloned() else {
+                    dispatcher.push(RpcAction::LedgerAccountsAtBlockGetPending {
+                        rpc_id: *rpc_id,
+                        block: None,
+                    });
+                    dispatcher
+                        .push(RpcAction::LedgerAccountsAtBlockArchiveGetInit { rpc_id: *rpc_id });
+                    return;
+                };
+
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::AccountsForRpc(
+                        *rpc_id,
+                        block.merkle_root_hash().clone(),
+                        query.account_query.clone(),
+                    ),
+                    callback: LedgerReadInitCallback::new(
+                        RpcAction::LedgerAccountsAtBlockGetPending {
+                            rpc_id: *rpc_id,
+                            block: Some(block),
+                        },
+                    ),
+                });
+            }
+            RpcAction::LedgerAccountsAtBlockGetPending { rpc_id, block } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::LedgerAccountsAtBlockGetPending({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+                rpc.data = RpcRequestExtraData::FullBlockOpt(block.clone());
+            }
+            RpcAction::LedgerAccountsAtBlockGetSuccess { rpc_id, accounts } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::LedgerAccountsAtBlockGetSuccess({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+                let block = match &rpc.data {
+                    RpcRequestExtraData::FullBlockOpt(block) => block.as_ref(),
+                    _ => None,
+                };
+                let response = accounts.clone().and_then(|accounts| {
+                    let block = block.ok_or_else(|| "target block not found".to_owned())?;
+                    Ok(RpcLedgerAccountsAtBlock {
+                        block_hash: block.hash().clone(),
+                        height: block.height(),
+                        ledger_hash: block.merkle_root_hash().clone(),
+                        accounts,
+                    })
+                });
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::LedgerAccountsAtBlockGetSuccess {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::LedgerAccountsAtBlockArchiveGetInit { rpc_id } => {
+                let Some(RpcRequest::LedgerAccountsAtBlockGet(query)) =
+                    state.requests.get(rpc_id).map(|rpc| &rpc.req)
+                else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::LedgerAccountsAtBlockArchiveGetInit({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                let query = query.clone();
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::LedgerAccountsAtBlockArchiveGet {
+                    rpc_id: *rpc_id,
+                    query,
+                });
+            }
+            RpcAction::LedgerAccountsAtBlockArchiveGetSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::LedgerAccountsAtBlockArchiveGetSuccess({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::LedgerAccountsAtBlockGetSuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
+            RpcAction::AccountNextNoncesGetInit {
+                rpc_id,
+                public_keys,
+            } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::AccountNextNoncesGet(public_keys.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(best_tip) = state.transition_frontier.best_tip_breadcrumb() else {
+                    dispatcher.push(RpcAction::AccountNextNoncesGetPending {
+                        rpc_id: *rpc_id,
+                        block: None,
+                    });
+                    dispatcher.push(RpcAction::AccountNextNoncesGetSuccess {
+                        rpc_id: *rpc_id,
+                        accounts: Vec::new(),
+                    });
+                    return;
+                };
+                let account_ids = public_keys
+                    .iter()
+                    .filter_map(|pk| CompressedPubKey::try_from(pk.clone()).ok())
+                    .map(|pk| AccountId::new(pk, TokenId::default()))
+                    .collect();
+
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::AccountsForRpc(
+                        *rpc_id,
+                        best_tip.merkle_root_hash().clone(),
+                        AccountQuery::MultipleIds(account_ids),
+                    ),
+                    callback: LedgerReadInitCallback::new(RpcAction::AccountNextNoncesGetPending {
+                        rpc_id: *rpc_id,
+                        block: Some(best_tip.clone()),
+                    }),
+                });
+            }
+            RpcAction::AccountNextNoncesGetPending { rpc_id, block } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::AccountNextNoncesGetPending({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+                rpc.data = RpcRequestExtraData::FullBlockOpt(block.clone());
+            }
+            RpcAction::AccountNextNoncesGetSuccess { rpc_id, accounts } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::AccountNextNoncesGetSuccess({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+                let RpcRequest::AccountNextNoncesGet(public_keys) = rpc.req.clone() else {
+                    bug_condition!(
+                        "unexpected rpc request for RpcAction::AccountNextNoncesGetSuccess"
+                    );
+                    return;
+                };
+                let block = match &rpc.data {
+                    RpcRequestExtraData::FullBlockOpt(block) => block.clone(),
+                    _ => None,
+                };
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = block
+                    .ok_or_else(|| "best tip is not known yet".to_owned())
+                    .map(|block| {
+                        let pool = &state.transaction_pool;
+                        let nonces = public_keys
+                            .into_iter()
+                            .map(|public_key| {
+                                let account_id = CompressedPubKey::try_from(public_key.clone())
+                                    .ok()
+                                    .map(|pk| AccountId::new(pk, TokenId::default()));
+                                let account = accounts
+                                    .iter()
+                                    .find(|account| Some(account.id()) == account_id);
+                                let pool_nonce_range = account_id
+                                    .and_then(|account_id| pool.sender_nonce_range(&account_id));
+                                RpcAccountNextNonce::new(public_key, account, pool_nonce_range)
+                            })
+                            .collect();
+                        RpcAccountNextNonces {
+                            block_hash: block.hash().clone(),
+                            height: block.height(),
+                            ledger_hash: block.merkle_root_hash().clone(),
+                            pool_consistent: pool.best_tip_hash() == Some(block.merkle_root_hash()),
+                            accounts: nonces,
+                        }
+                    });
+                dispatcher.push(RpcEffectfulAction::AccountNextNoncesGetSuccess {
+                    rpc_id: *rpc_id,
+                    response,
                 });
             }
             RpcAction::TransactionInjectInit { rpc_id, commands } => {
@@ -806,14 +1163,9 @@ impl RpcState {
 
                 dispatcher.push(LedgerReadAction::Init {
                     request: LedgerReadRequest::GetLedgerStatus(*rpc_id, ledger_hash.clone()),
-                    callback: LedgerReadInitCallback::RpcLedgerStatusGetPending {
-                        callback: redux::callback!(
-                            on_ledger_read_init_rpc_actions_get_init(rpc_id: RequestId<RpcIdType>) -> crate::Action{
-                                RpcAction::LedgerStatusGetPending { rpc_id }
-                            }
-                        ),
-                        args: *rpc_id,
-                    },
+                    callback: LedgerReadInitCallback::new(RpcAction::LedgerStatusGetPending {
+                        rpc_id: *rpc_id,
+                    }),
                 })
             }
             RpcAction::LedgerStatusGetPending { rpc_id } => {
@@ -834,6 +1186,144 @@ impl RpcState {
                     response: response.clone(),
                 });
             }
+            RpcAction::LedgerStatusExtendedGetInit { rpc_id } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::LedgerStatusExtendedGet,
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(best_tip) = state.transition_frontier.best_tip_breadcrumb() else {
+                    dispatcher.push(RpcAction::LedgerStatusExtendedGetPending {
+                        rpc_id: *rpc_id,
+                        block: None,
+                    });
+                    dispatcher.push(RpcAction::LedgerStatusExtendedGetSuccess {
+                        rpc_id: *rpc_id,
+                        response: None,
+                    });
+                    return;
+                };
+
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::GetLedgerStatusExtended(
+                        *rpc_id,
+                        best_tip.merkle_root_hash().clone(),
+                        best_tip.staged_ledger_hashes().clone(),
+                    ),
+                    callback: LedgerReadInitCallback::new(
+                        RpcAction::LedgerStatusExtendedGetPending {
+                            rpc_id: *rpc_id,
+                            block: Some(best_tip.clone()),
+                        },
+                    ),
+                })
+            }
+            RpcAction::LedgerStatusExtendedGetPending { rpc_id, block } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+                // Total currency is taken from the block the status is read for.
+                rpc.data = RpcRequestExtraData::FullBlockOpt(block.clone());
+            }
+            RpcAction::LedgerStatusExtendedGetSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+                let block = match &rpc.data {
+                    RpcRequestExtraData::FullBlockOpt(block) => block.as_ref(),
+                    RpcRequestExtraData::None => None,
+                };
+                let response =
+                    response
+                        .clone()
+                        .zip(block)
+                        .map(|(ledger, block)| RpcLedgerStatusExtended {
+                            ledger,
+                            total_currency: block.consensus_state().total_currency.clone(),
+                        });
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::LedgerStatusExtendedGetSuccess {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::LedgerSessionInit { rpc_id, request } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::LedgerSession(request.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let request = match request {
+                    RpcLedgerSessionRequest::Open | RpcLedgerSessionRequest::OpenLedger { .. } => {
+                        let Some(best_tip) = global_state.transition_frontier.best_tip() else {
+                            dispatcher.push(RpcEffectfulAction::LedgerSessionSuccess {
+                                rpc_id: *rpc_id,
+                                response: Err("best tip not ready".to_owned()),
+                            });
+                            return;
+                        };
+                        let ledger = match request {
+                            RpcLedgerSessionRequest::OpenLedger { ledger } => *ledger,
+                            _ => RpcLedgerSelector::BestTip,
+                        };
+                        LedgerSessionRequest::Open {
+                            ledger_hash: ledger.ledger_hash(best_tip).clone(),
+                        }
+                    }
+                    RpcLedgerSessionRequest::AccountsGet { session_id, query } => {
+                        LedgerSessionRequest::AccountsGet {
+                            session_id: *session_id,
+                            query: query.clone(),
+                        }
+                    }
+                    RpcLedgerSessionRequest::AccountsPage {
+                        session_id,
+                        offset,
+                        limit,
+                    } => LedgerSessionRequest::AccountsPage {
+                        session_id: *session_id,
+                        offset: *offset,
+                        limit: *limit,
+                    },
+                    RpcLedgerSessionRequest::Close { session_id } => LedgerSessionRequest::Close {
+                        session_id: *session_id,
+                    },
+                };
+
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::Session(*rpc_id, request),
+                    callback: LedgerReadInitCallback::new(RpcAction::LedgerSessionPending {
+                        rpc_id: *rpc_id,
+                    }),
+                })
+            }
+            RpcAction::LedgerSessionPending { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::LedgerSessionSuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::LedgerSessionSuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
             RpcAction::LedgerAccountDelegatorsGetInit {
                 rpc_id,
                 ledger_hash,
@@ -852,15 +1342,14 @@ impl RpcState {
                 let dispatcher = state_context.into_dispatcher();
 
                 dispatcher.push(LedgerReadAction::Init {
-                    request: LedgerReadRequest::GetAccountDelegators(*rpc_id, ledger_hash.clone(), account_id.clone()),
-                    callback: LedgerReadInitCallback::RpcLedgerAccountDelegatorsGetPending {
-                        callback: redux::callback!(
-                            on_ledger_read_init_rpc_actions_get_init(rpc_id: RequestId<RpcIdType>) -> crate::Action{
-                                RpcAction::LedgerAccountDelegatorsGetPending { rpc_id }
-                            }
-                        ),
-                        args: *rpc_id,
-                    },
+                    request: LedgerReadRequest::GetAccountDelegators(
+                        *rpc_id,
+                        ledger_hash.clone(),
+                        account_id.clone(),
+                    ),
+                    callback: LedgerReadInitCallback::new(
+                        RpcAction::LedgerAccountDelegatorsGetPending { rpc_id: *rpc_id },
+                    ),
                 })
             }
             RpcAction::LedgerAccountDelegatorsGetPending { rpc_id } => {
@@ -881,6 +1370,461 @@ impl RpcState {
                     response: response.clone(),
                 });
             }
+            RpcAction::TransactionInclusionProofGet { rpc_id, query } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let transition_frontier = &state.transition_frontier;
+
+                let proof = transition_frontier
+                    .transaction_index
+                    .get(&query.tx_hash)
+                    .filter(|(block_hash, _)| *block_hash == &query.block_hash)
+                    .and_then(|(block_hash, position)| {
+                        let block = transition_frontier
+                            .best_chain
+                            .iter()
+                            .find(|b| b.hash() == block_hash)?;
+                        RpcTransactionInclusionProof::new(block, query.tx_hash.clone(), position)
+                    });
+
+                dispatcher.push(RpcEffectfulAction::TransactionInclusionProofGet {
+                    rpc_id: *rpc_id,
+                    proof,
+                });
+            }
+            RpcAction::FinalityEstimateGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let estimate = RpcFinalityEstimate::new(&state.transition_frontier);
+                dispatcher.push(RpcEffectfulAction::FinalityEstimateGet {
+                    rpc_id: *rpc_id,
+                    estimate,
+                });
+            }
+            RpcAction::NodeInfoGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let info = RpcNodeInfo::new(state);
+                dispatcher.push(RpcEffectfulAction::NodeInfoGet {
+                    rpc_id: *rpc_id,
+                    info,
+                });
+            }
+            RpcAction::InternalCommandsGet { rpc_id, query } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = RpcInternalCommand::query(&state.transition_frontier, query);
+                dispatcher.push(RpcEffectfulAction::InternalCommandsGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::SyncPeerScoresGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = RpcSyncPeerScore::ranking(state);
+                dispatcher.push(RpcEffectfulAction::SyncPeerScoresGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::Profiler { rpc_id, command } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::Profiler {
+                    rpc_id: *rpc_id,
+                    command: command.clone(),
+                });
+            }
+            RpcAction::MaintenanceMode { rpc_id, command } => {
+                match command {
+                    RpcMaintenanceModeCommand::Status => {}
+                    RpcMaintenanceModeCommand::Enter => {
+                        if state.maintenance.is_none() {
+                            openmina_core::info!(meta.time(); summary = "entering maintenance mode");
+                            state.maintenance = Some(RpcMaintenanceState { since: meta.time() });
+                        }
+                    }
+                    RpcMaintenanceModeCommand::Leave => {
+                        if state.maintenance.take().is_some() {
+                            openmina_core::info!(meta.time(); summary = "leaving maintenance mode");
+                        }
+                    }
+                }
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcEffectfulAction::MaintenanceMode {
+                    rpc_id: *rpc_id,
+                    status: RpcMaintenanceStatus::new(state),
+                });
+            }
+            RpcAction::FeatureFlags { rpc_id, command } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = match command {
+                    RpcFeatureFlagsCommand::Get => Ok(*state.p2p.feature_flags()),
+                    RpcFeatureFlagsCommand::Set(flags) => match state.p2p.ready() {
+                        Some(_) => {
+                            dispatcher.push(P2pFeatureFlagsAction::Update { flags: *flags });
+                            Ok(*flags)
+                        }
+                        None => Err("p2p is not initialized".to_owned()),
+                    },
+                };
+                dispatcher.push(RpcEffectfulAction::FeatureFlags {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::CheckpointsGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = RpcCheckpoint::list(state);
+                dispatcher.push(RpcEffectfulAction::CheckpointsGet {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::TransactionPoolSnapshot { rpc_id, command } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let pool = &state.transaction_pool;
+                let response = match command {
+                    RpcTransactionPoolSnapshotCommand::List => Ok(
+                        RpcTransactionPoolSnapshotResult::List(pool.snapshots().list().collect()),
+                    ),
+                    RpcTransactionPoolSnapshotCommand::Capture => {
+                        let id = pool.snapshots().next_id();
+                        dispatcher.push(TransactionPoolAction::SnapshotCapture);
+                        Ok(RpcTransactionPoolSnapshotResult::Captured { id })
+                    }
+                    RpcTransactionPoolSnapshotCommand::Diff { from, to } => pool
+                        .snapshot_diff(*from, *to, meta.time())
+                        .map(RpcTransactionPoolSnapshotResult::Diff),
+                };
+                dispatcher.push(RpcEffectfulAction::TransactionPoolSnapshot {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::VrfVerifyInit { rpc_id, query } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::VrfVerify(query.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(block) = state
+                    .transition_frontier
+                    .best_chain
+                    .iter()
+                    .find(|block| block.hash() == &query.state_hash)
+                else {
+                    dispatcher.push(RpcAction::VrfVerifyPending { rpc_id: *rpc_id });
+                    dispatcher.push(RpcAction::VrfVerifySuccess {
+                        rpc_id: *rpc_id,
+                        response: Err(format!(
+                            "block {} isn't in the best chain",
+                            query.state_hash
+                        )),
+                    });
+                    return;
+                };
+                let claim = RpcVrfVerifyClaim::new(block.block_with_hash());
+                let epoch_data = RpcVrfEpochData::of_block(block.block_with_hash());
+
+                dispatcher.push(LedgerReadAction::Init {
+                    request: LedgerReadRequest::VrfVerify(*rpc_id, claim, epoch_data),
+                    callback: LedgerReadInitCallback::new(RpcAction::VrfVerifyPending {
+                        rpc_id: *rpc_id,
+                    }),
+                })
+            }
+            RpcAction::VrfVerifyPending { rpc_id } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Pending { time: meta.time() };
+            }
+            RpcAction::VrfVerifySuccess { rpc_id, response } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::VrfVerifySuccess {
+                    rpc_id: *rpc_id,
+                    response: response.clone(),
+                });
+            }
+            RpcAction::LedgerAudit { rpc_id, command } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let audit = &state.ledger.audit;
+                let started = match command {
+                    RpcLedgerAuditCommand::Status => Ok(None),
+                    RpcLedgerAuditCommand::Start => match &audit.pending {
+                        Some(pending) => Err(format!("ledger audit {} is running", pending.id)),
+                        None if state.transition_frontier.best_tip().is_none() => {
+                            Err("no best tip yet".to_owned())
+                        }
+                        None => {
+                            dispatcher.push(LedgerAuditAction::Init);
+                            Ok(Some(audit.next_id()))
+                        }
+                    },
+                };
+                let response = started.map(|started| RpcLedgerAuditStatus {
+                    started,
+                    pending: audit.pending.clone(),
+                    last: audit.last.clone(),
+                });
+                dispatcher.push(RpcEffectfulAction::LedgerAudit {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::BlockTemplateGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcEffectfulAction::BlockTemplateGet {
+                    rpc_id: *rpc_id,
+                    response: BlockTemplate::new(state),
+                });
+            }
+            RpcAction::BlockTemplateSubmit { rpc_id, submit } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let global_slot = submit.global_slot;
+                let response = BlockTemplate::won_slot(state).and_then(|won_slot| {
+                    if won_slot.global_slot() != global_slot {
+                        return Err(format!(
+                            "slot {global_slot} isn't the next won slot {}",
+                            won_slot.global_slot()
+                        ));
+                    }
+                    dispatcher.push(BlockProducerAction::ExternalDiffSubmit {
+                        global_slot,
+                        diff: submit.diff.clone().into(),
+                    });
+                    Ok(BlockTemplateExternalDiff {
+                        global_slot,
+                        submitted_at: meta.time(),
+                        status: BlockProducerExternalDiffStatus::Submitted,
+                    })
+                });
+                dispatcher.push(RpcEffectfulAction::BlockTemplateSubmit {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::MaintenanceRejected { rpc_id, request } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::MaintenanceRejected {
+                    rpc_id: *rpc_id,
+                    request: request.clone(),
+                });
+            }
+            RpcAction::OverloadShed { rpc_id, request } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::OverloadShed {
+                    rpc_id: *rpc_id,
+                    request: request.clone(),
+                });
+            }
+            RpcAction::LightModeRejected { rpc_id, request } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::LightModeRejected {
+                    rpc_id: *rpc_id,
+                    request: request.clone(),
+                });
+            }
+            RpcAction::AccountSubscriptions { rpc_id, command } => {
+                let watched = &mut state.account_subscriptions.watched;
+                let added = match command {
+                    RpcAccountSubscriptionsCommand::List => false,
+                    RpcAccountSubscriptionsCommand::Add(public_key) => watched
+                        .insert(public_key.clone(), RpcWatchedAccountState::Unknown)
+                        .is_none(),
+                    RpcAccountSubscriptionsCommand::Remove(public_key) => {
+                        watched.remove(public_key);
+                        false
+                    }
+                };
+                let response = watched.keys().cloned().collect();
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::AccountSubscriptions {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+                if added {
+                    // read the current state of the account, so that the
+                    // next change can be reported.
+                    dispatcher.push(RpcAction::AccountSubscriptionsEvaluate);
+                }
+            }
+            RpcAction::AccountEventsSubscribe { rpc_id } => {
+                state.account_subscriptions.subscribers.insert(*rpc_id);
+            }
+            RpcAction::AccountEventsUnsubscribe { rpc_id } => {
+                state.account_subscriptions.subscribers.remove(rpc_id);
+            }
+            RpcAction::AccountSubscriptionsEvaluate => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(best_tip) = state.transition_frontier.best_tip() else {
+                    return;
+                };
+                dispatcher.push(RpcEffectfulAction::AccountSubscriptionsFetch {
+                    ledger_hash: best_tip.merkle_root_hash().clone(),
+                    block_hash: best_tip.hash().clone(),
+                    height: best_tip.height(),
+                    public_keys: state
+                        .rpc
+                        .account_subscriptions
+                        .watched
+                        .keys()
+                        .cloned()
+                        .collect(),
+                });
+            }
+            RpcAction::ZkappVkRegister { rpc_id, request } => {
+                let dispatcher = state_context.into_dispatcher();
+                let response = request.decode().map(|(verification_key, hash)| {
+                    let expires_at = meta.time() + request.ttl();
+                    let registered = RpcZkappVkRegistered {
+                        hash: hash.to_decimal(),
+                        expires_at,
+                    };
+                    dispatcher.push(TransactionPoolAction::VkRegister {
+                        verification_key,
+                        hash,
+                        expires_at,
+                    });
+                    registered
+                });
+                dispatcher.push(RpcEffectfulAction::ZkappVkRegister {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::ZkappPreconditionsEvaluate { rpc_id, command } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let best_tip = state.transition_frontier.best_tip().cloned();
+                // Commands are applied in the next block, at the current slot.
+                let global_slot = state.cur_global_slot_since_genesis().unwrap_or_else(|| {
+                    best_tip
+                        .as_ref()
+                        .map_or(0, |b| b.global_slot_since_genesis() + 1)
+                });
+                dispatcher.push(RpcEffectfulAction::ZkappPreconditionsEvaluate {
+                    rpc_id: *rpc_id,
+                    command: command.clone(),
+                    best_tip,
+                    global_slot,
+                });
+            }
+            RpcAction::PayoutsBatchSubmit { rpc_id, request } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let batch_id = state.transaction_pool.payouts.next_batch_id();
+                let response = if state.config.payout_pub_key.is_none() {
+                    Err("payout key not set, start the node with `--payout-key`".to_owned())
+                } else {
+                    payouts_parse_csv(&request.payments).and_then(|payments| {
+                        dispatcher.push(TransactionPoolPayoutsAction::BatchAdd {
+                            batch_id,
+                            fee: Fee::from_u64(request.fee),
+                            memo: request.memo()?,
+                            max_per_sec: request.max_per_sec()?,
+                            payments,
+                        });
+                        Ok(batch_id)
+                    })
+                };
+                dispatcher.push(RpcEffectfulAction::PayoutsBatchSubmit {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::PayoutsBatchGet { rpc_id, batch_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcEffectfulAction::PayoutsBatchGet {
+                    rpc_id: *rpc_id,
+                    response: state.transaction_pool.payouts.batch(*batch_id).cloned(),
+                });
+            }
+            RpcAction::ZkappFeeSponsor { rpc_id, request } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::ZkappFeeSponsor(request.clone()),
+                    status: RpcRequestStatus::Init { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcAction::TransactionInjectPending { rpc_id: *rpc_id });
+                if state.transaction_pool.sponsor.config().is_none() {
+                    dispatcher.push(RpcAction::TransactionInjectFailure {
+                        rpc_id: *rpc_id,
+                        errors: vec![
+                            "fee sponsor not set, start the node with `--fee-sponsor-key`"
+                                .to_owned(),
+                        ],
+                    });
+                    return;
+                }
+                dispatcher.push(TransactionPoolSponsorAction::Init {
+                    rpc_id: *rpc_id,
+                    origin: request.origin.clone(),
+                    command: request.command.clone(),
+                });
+            }
+            RpcAction::ChainImportBlock { rpc_id, block } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let is_applied = state
+                    .transition_frontier
+                    .best_chain
+                    .iter()
+                    .any(|b| b.hash() == block.hash());
+                let response = if is_applied {
+                    Ok(())
+                } else {
+                    chain_import_block_check(state, block)
+                };
+                if response.is_ok() && !is_applied {
+                    dispatcher.push(TransitionFrontierCandidateAction::BlockReceived {
+                        block: block.clone(),
+                        chain_proof: None,
+                    });
+                }
+                dispatcher.push(RpcEffectfulAction::ChainImportBlock {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::AccountSubscriptionsUpdate {
+                block_hash,
+                height,
+                accounts,
+            } => {
+                let subscriptions = &mut state.account_subscriptions;
+                let events = subscriptions.update(block_hash, *height, accounts);
+                if events.is_empty() || subscriptions.subscribers.is_empty() {
+                    return;
+                }
+                let subscribers = subscriptions.subscribers.iter().copied().collect();
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::AccountEventsSend {
+                    subscribers,
+                    events,
+                });
+            }
+            RpcAction::SnarkPoolJobEventsSubscribe { rpc_id } => {
+                state.snark_pool_job_subscribers.insert(*rpc_id);
+            }
+            RpcAction::SnarkPoolJobEventsUnsubscribe { rpc_id } => {
+                state.snark_pool_job_subscribers.remove(rpc_id);
+            }
+            RpcAction::SnarkPoolJobEventsPublish { events } => {
+                let subscribers = state.snark_pool_job_subscribers.iter().copied().collect();
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::SnarkPoolJobEventsSend {
+                    subscribers,
+                    events: events.clone(),
+                });
+            }
         }
     }
 }
@@ -937,8 +1881,64 @@ pub fn collect_rpc_peers_info(state: &crate::State) -> Vec<RpcPeerInfo> {
                     best_tip_global_slot: best_tip.map(|bt| bt.global_slot_since_genesis()),
                     best_tip_timestamp: best_tip.map(|bt| bt.timestamp().into()),
                     time,
+                    handshake_failures: state.handshake_failures.clone(),
                 }
             })
             .collect()
     })
 }
+
+pub fn collect_rpc_p2p_diagnostics(
+    state: &crate::State,
+    errors: usize,
+) -> Option<RpcP2pDiagnostics> {
+    let p2p = state.p2p.ready()?;
+    let peers = collect_rpc_peers_info(state)
+        .into_iter()
+        .filter_map(|info| {
+            let peer = p2p.peers.get(&info.peer_id)?;
+            let ready = peer.status.as_ready();
+            Some(RpcP2pDiagnosticsPeer {
+                capabilities: ready.and_then(|r| r.capabilities.clone()),
+                dial_stats: peer.dial_stats.clone(),
+                channels_received: ready
+                    .map(|r| r.channels.received.clone())
+                    .unwrap_or_default(),
+                info,
+            })
+        })
+        .collect();
+    Some(RpcP2pDiagnostics {
+        peer_id: p2p.my_id(),
+        chain_id: p2p.chain_id.to_hex(),
+        peers,
+        connection_events: p2p.connection_events.iter().cloned().collect(),
+        connection_errors: p2p.connection_events.errors(errors).cloned().collect(),
+        handshake_failures: p2p.handshake_failures.clone(),
+        session_resumption: p2p.session_resumption.clone(),
+        ice_servers: p2p::service_impl::ice_servers(),
+        gossip_dedup: (&p2p.network.scheduler.broadcast_state.dedup_stats).into(),
+    })
+}
+
+/// Imported blocks must extend the best tip, as the node has no peers to
+/// fetch the missing blocks from while importing. They go through the
+/// same prevalidation as the blocks received from the peers, including
+/// the reception lateness check.
+fn chain_import_block_check(state: &crate::State, block: &ArcBlockWithHash) -> Result<(), String> {
+    let Some(best_tip) = state.transition_frontier.best_tip() else {
+        return Err("node doesn't have the best tip yet".to_owned());
+    };
+    if block.pred_hash() != best_tip.hash() {
+        return Err(format!(
+            "block {} at height {} doesn't extend the best tip {} at height {}",
+            block.hash(),
+            block.height(),
+            best_tip.hash(),
+            best_tip.height()
+        ));
+    }
+    state
+        .prevalidate_block(block, allow_block_too_late(state, block))
+        .map_err(|err| format!("block prevalidation failed: {err:?}"))
+}
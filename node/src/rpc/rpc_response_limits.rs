@@ -0,0 +1,183 @@
+use std::{fmt, str::FromStr};
+
+use mina_p2p_messages::v2::LedgerHash;
+use serde::{Deserialize, Serialize};
+
+/// Limits of the rpc responses, which could otherwise grow to tens of MB
+/// and stall the rpc thread while being built and sent.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcResponseLimits {
+    /// Max size of the list in the response, in bytes of its json
+    /// representation. Larger responses are truncated and carry a
+    /// continuation token to request the rest with.
+    pub max_bytes: usize,
+}
+
+impl Default for RpcResponseLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 4 * 1024 * 1024,
+        }
+    }
+}
+
+/// Position in the list of a truncated response, from which the next
+/// response continues. Used for the scan state summary and for the
+/// [`super::AccountQuery::All`] accounts.
+///
+/// Carries the hash of the ledger the list was built from, so that the next
+/// pages are read from the same ledger, or rejected once it is gone, instead
+/// of silently continuing in a different one. Encoded as `<ledger_hash>:<offset>`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(into = "String", try_from = "String")]
+pub struct RpcContinuationToken {
+    pub ledger_hash: LedgerHash,
+    pub offset: usize,
+}
+
+impl fmt::Display for RpcContinuationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.ledger_hash, self.offset)
+    }
+}
+
+impl FromStr for RpcContinuationToken {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ledger_hash, offset) = s
+            .split_once(':')
+            .ok_or_else(|| format!("invalid continuation token `{s}`"))?;
+        Ok(Self {
+            ledger_hash: ledger_hash
+                .parse()
+                .map_err(|err| format!("invalid continuation token ledger hash: {err}"))?,
+            offset: offset
+                .parse()
+                .map_err(|err| format!("invalid continuation token offset: {err}"))?,
+        })
+    }
+}
+
+impl From<RpcContinuationToken> for String {
+    fn from(token: RpcContinuationToken) -> Self {
+        token.to_string()
+    }
+}
+
+impl TryFrom<String> for RpcContinuationToken {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl RpcResponseLimits {
+    /// Keeps the items starting at `offset`, as long as they fit into the
+    /// limit. At least one item is kept, so that the requester can make
+    /// progress.
+    ///
+    /// Returns the offset for the next response, if the items were truncated.
+    pub fn truncate<T: Serialize>(&self, items: &mut Vec<T>, offset: usize) -> Option<usize> {
+        let start = offset.min(items.len());
+        items.drain(..start);
+
+        let mut size = 0usize;
+        let end = items.iter().position(|item| !self.fits(&mut size, item))?;
+        if end >= items.len() {
+            return None;
+        }
+        items.truncate(end);
+        Some(start.saturating_add(end))
+    }
+
+    /// Adds the size of the `item` to the `size` of the response so far,
+    /// returns `false` if it doesn't fit anymore. The first item always fits.
+    pub fn fits<T: Serialize>(&self, size: &mut usize, item: &T) -> bool {
+        let first = *size == 0;
+        *size = size.saturating_add(json_size(item).max(1));
+        first || *size <= self.max_bytes
+    }
+}
+
+fn json_size<T: Serialize>(item: &T) -> usize {
+    struct Counter(usize);
+
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 = self.0.saturating_add(buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    let _ = serde_json::to_writer(&mut counter, item);
+    counter.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_response_truncate() {
+        // Each item is 7 bytes as json.
+        let items = (0..10).map(|i| format!("item{i}")).collect::<Vec<_>>();
+        let limits = RpcResponseLimits { max_bytes: 21 };
+
+        let mut page = items.clone();
+        let next = limits.truncate(&mut page, 0);
+        assert_eq!(page, items[..3]);
+        assert_eq!(next, Some(3));
+
+        let mut page = items.clone();
+        let next = limits.truncate(&mut page, 3);
+        assert_eq!(page, items[3..6]);
+        assert_eq!(next, Some(6));
+
+        let mut page = items.clone();
+        let next = limits.truncate(&mut page, 8);
+        assert_eq!(page, items[8..]);
+        assert_eq!(next, None);
+
+        // Too large item is still returned.
+        let limits = RpcResponseLimits { max_bytes: 1 };
+        let mut page = items.clone();
+        let next = limits.truncate(&mut page, 0);
+        assert_eq!(page, items[..1]);
+        assert_eq!(next, Some(1));
+    }
+
+    #[test]
+    fn test_rpc_continuation_token_encoding() {
+        let token = RpcContinuationToken {
+            ledger_hash: "jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee"
+                .parse()
+                .unwrap(),
+            offset: 42,
+        };
+        let encoded = token.to_string();
+        assert_eq!(
+            encoded,
+            "jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee:42"
+        );
+        assert_eq!(encoded.parse::<RpcContinuationToken>(), Ok(token.clone()));
+
+        let json = serde_json::to_string(&token).unwrap();
+        assert_eq!(json, format!("\"{encoded}\""));
+        assert_eq!(
+            serde_json::from_str::<RpcContinuationToken>(&json).unwrap(),
+            token
+        );
+
+        assert!("42".parse::<RpcContinuationToken>().is_err());
+        assert!("jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee:x"
+            .parse::<RpcContinuationToken>()
+            .is_err());
+    }
+}
@@ -1,10 +1,11 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use mina_p2p_messages::v2;
 use openmina_core::block::AppliedBlock;
+use openmina_node_account::AccountPublicKey;
 use serde::{Deserialize, Serialize};
 
-use super::{AccountQuery, RpcId, RpcRequest};
+use super::{AccountQuery, RpcId, RpcRequest, RpcWatchedAccount, RpcWatchedAccountEvent};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcRequestState {
@@ -54,6 +55,79 @@ impl RpcRequestStatus {
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct RpcState {
     pub requests: BTreeMap<RpcId, RpcRequestState>,
+    /// Set while the node is in maintenance mode, in which requests
+    /// changing node's state are rejected.
+    #[serde(default)]
+    pub maintenance: Option<RpcMaintenanceState>,
+    #[serde(default)]
+    pub account_subscriptions: RpcAccountSubscriptions,
+    /// Rpc requests which stream the snark pool job lifecycle events.
+    #[serde(default)]
+    pub snark_pool_job_subscribers: BTreeSet<RpcId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcMaintenanceState {
+    pub since: redux::Timestamp,
+}
+
+/// Accounts watched for changes in the best tip, and the rpc requests
+/// which stream the changes.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RpcAccountSubscriptions {
+    pub watched: BTreeMap<AccountPublicKey, RpcWatchedAccountState>,
+    pub subscribers: BTreeSet<RpcId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum RpcWatchedAccountState {
+    /// Account wasn't read from the ledger yet.
+    Unknown,
+    /// Account doesn't exist in the best tip ledger.
+    Missing,
+    Exists(RpcWatchedAccount),
+}
+
+impl RpcAccountSubscriptions {
+    /// Updates the watched accounts with the ones read from the ledger
+    /// of the new best tip, `None` if the account doesn't exist. Returns
+    /// the changes, except for the accounts read for the first time.
+    pub fn update(
+        &mut self,
+        block_hash: &v2::StateHash,
+        height: u32,
+        accounts: &BTreeMap<AccountPublicKey, Option<RpcWatchedAccount>>,
+    ) -> Vec<RpcWatchedAccountEvent> {
+        let mut events = Vec::new();
+        for (public_key, account) in accounts {
+            // might have been removed since the accounts were read.
+            let Some(state) = self.watched.get_mut(public_key) else {
+                continue;
+            };
+            let new_state = match account {
+                Some(account) => RpcWatchedAccountState::Exists(account.clone()),
+                None => RpcWatchedAccountState::Missing,
+            };
+            let previous = match state {
+                RpcWatchedAccountState::Unknown => None,
+                RpcWatchedAccountState::Missing => Some(None),
+                RpcWatchedAccountState::Exists(account) => Some(Some(account.clone())),
+            };
+            if let (Some(previous), Some(current)) = (previous, account) {
+                if previous.as_ref() != Some(current) {
+                    events.push(RpcWatchedAccountEvent {
+                        public_key: public_key.clone(),
+                        block_hash: block_hash.clone(),
+                        height,
+                        previous,
+                        current: current.clone(),
+                    });
+                }
+            }
+            *state = new_state;
+        }
+        events
+    }
 }
 
 impl RpcState {
@@ -61,6 +135,10 @@ impl RpcState {
         Self::default()
     }
 
+    pub fn is_in_maintenance(&self) -> bool {
+        self.maintenance.is_some()
+    }
+
     pub fn scan_state_summary_rpc_ids(
         &self,
     ) -> impl Iterator<
@@ -72,7 +150,7 @@ impl RpcState {
     > {
         self.requests
             .iter()
-            .filter(|(_, req)| matches!(req.req, RpcRequest::ScanStateSummaryGet(_)))
+            .filter(|(_, req)| matches!(req.req, RpcRequest::ScanStateSummaryGet(..)))
             .filter_map(|(id, req)| {
                 let block = match &req.data {
                     RpcRequestExtraData::FullBlockOpt(block) => block.as_ref()?,
@@ -86,13 +164,22 @@ impl RpcState {
         &self,
     ) -> impl Iterator<Item = (RpcId, AccountQuery, &RpcRequestStatus)> + '_ {
         self.requests.iter().filter_map(|(id, req)| {
-            if let RpcRequest::LedgerAccountsGet(account) = &req.req {
+            if let RpcRequest::LedgerAccountsGet(account, _) = &req.req {
                 Some((*id, account.clone(), &req.status))
             } else {
                 None
             }
         })
     }
+
+    pub fn accounts_at_block_request_rpc_ids(
+        &self,
+    ) -> impl Iterator<Item = (RpcId, &RpcRequestStatus)> + '_ {
+        self.requests
+            .iter()
+            .filter(|(_, req)| matches!(req.req, RpcRequest::LedgerAccountsAtBlockGet(_)))
+            .map(|(id, req)| (*id, &req.status))
+    }
 }
 
 impl Default for RpcRequestExtraData {
@@ -100,3 +187,44 @@ impl Default for RpcRequestExtraData {
         Self::None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(balance: u64) -> RpcWatchedAccount {
+        RpcWatchedAccount {
+            balance,
+            nonce: 0,
+            delegate: None,
+        }
+    }
+
+    #[test]
+    fn account_subscriptions_update() {
+        let key: AccountPublicKey = "B62qmnY6m4c6bdgSPnQGZriSaj9vuSjsfh6qkveGTsFX3yGA5ywRaja"
+            .parse()
+            .unwrap();
+        let hash = v2::StateHash::zero();
+        let mut subscriptions = RpcAccountSubscriptions::default();
+        subscriptions
+            .watched
+            .insert(key.clone(), RpcWatchedAccountState::Unknown);
+
+        // first read only initializes the state.
+        let accounts = BTreeMap::from([(key.clone(), None)]);
+        assert!(subscriptions.update(&hash, 1, &accounts).is_empty());
+
+        let accounts = BTreeMap::from([(key.clone(), Some(account(10)))]);
+        let events = subscriptions.update(&hash, 2, &accounts);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].previous, None);
+        assert!(subscriptions.update(&hash, 3, &accounts).is_empty());
+
+        let accounts = BTreeMap::from([(key.clone(), Some(account(20)))]);
+        let events = subscriptions.update(&hash, 4, &accounts);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].previous, Some(account(10)));
+        assert_eq!(events[0].current, account(20));
+    }
+}
@@ -2,13 +2,24 @@ use crate::{
     external_snark_worker::{ExternalSnarkWorker, SnarkWorkId},
     p2p::connection::P2pConnectionResponse,
     rpc::{
-        discovery::RpcDiscoveryRoutingTable, AccountQuery, ActionStatsQuery, RpcBestChainResponse,
-        RpcConsensusTimeGetResponse, RpcGenesisBlockResponse, RpcGetBlockResponse,
-        RpcLedgerAccountDelegatorsGetResponse, RpcLedgerStatusGetResponse, RpcPeerInfo,
-        RpcPooledUserCommandsResponse, RpcPooledZkappCommandsResponse,
-        RpcScanStateSummaryScanStateJob, RpcSnarkPoolCompletedJobsResponse,
-        RpcSnarkPoolPendingJobsGetResponse, RpcSnarkerConfig, RpcTransactionInjectFailure,
-        RpcTransactionInjectRejected, RpcTransactionInjectSuccess, SyncStatsQuery,
+        discovery::RpcDiscoveryRoutingTable, AccountQuery, ActionStatsQuery,
+        RpcAccountNextNoncesGetResponse, RpcAccountSubscriptionsResponse, RpcBestChainResponse,
+        RpcBlockTemplateGetResponse, RpcBlockTemplateSubmitResponse, RpcChainImportBlockResponse,
+        RpcCheckpointsGetResponse, RpcConsensusTimeGetResponse, RpcContinuationToken,
+        RpcFeatureFlagsResponse, RpcFinalityEstimateGetResponse, RpcGenesisBlockResponse,
+        RpcGetBlockResponse, RpcInternalCommandsGetResponse, RpcLedgerAccountDelegatorsGetResponse,
+        RpcLedgerAccountsAtBlockQuery, RpcLedgerAccountsAtBlockResponse, RpcLedgerAuditResponse,
+        RpcLedgerSessionResponse, RpcLedgerStatusExtendedGetResponse, RpcLedgerStatusGetResponse,
+        RpcMaintenanceModeResponse, RpcNodeInfoGetResponse, RpcP2pDiagnosticsGetResponse,
+        RpcPayoutsBatchGetResponse, RpcPayoutsBatchSubmitResponse, RpcPeerInfo,
+        RpcPooledUserCommandsResponse, RpcPooledZkappCommandsResponse, RpcProfilerCommand,
+        RpcRequest, RpcScanStateSummaryScanStateJob, RpcSnarkPoolCompletedJobsResponse,
+        RpcSnarkPoolJobEvent, RpcSnarkPoolPendingJobsGetResponse, RpcSnarkVerifyStatsGetResponse,
+        RpcSnarkerConfig, RpcSyncPeerScoresGetResponse, RpcTransactionInclusionProofGetResponse,
+        RpcTransactionInjectFailure, RpcTransactionInjectRejected, RpcTransactionInjectSuccess,
+        RpcTransactionPoolSnapshotResponse, RpcTransactionPoolZkappStatsGetResponse,
+        RpcVrfVerifyResponse, RpcWatchedAccountEvent, RpcZkappVkRegisterResponse,
+        StatsHistoryQuery, SyncStatsQuery,
     },
 };
 use ledger::{
@@ -17,8 +28,10 @@ use ledger::{
 };
 use mina_p2p_messages::v2::{self, MinaBaseUserCommandStableV2};
 use openmina_core::{
-    consensus::ConsensusConstants, requests::RpcId, snark::SnarkJobId, ActionEvent,
+    block::ArcBlockWithHash, consensus::ConsensusConstants, requests::RpcId, snark::SnarkJobId,
+    ActionEvent,
 };
+use openmina_node_account::AccountPublicKey;
 use p2p::bootstrap::P2pNetworkKadBootstrapStats;
 use serde::{Deserialize, Serialize};
 
@@ -34,6 +47,9 @@ pub enum RpcEffectfulAction {
     HeartbeatGet {
         rpc_id: RpcId,
     },
+    StateDigestGet {
+        rpc_id: RpcId,
+    },
     ActionStatsGet {
         rpc_id: RpcId,
         query: ActionStatsQuery,
@@ -45,6 +61,26 @@ pub enum RpcEffectfulAction {
     BlockProducerStatsGet {
         rpc_id: RpcId,
     },
+    BlockProducerEpochReportGet {
+        rpc_id: RpcId,
+        epoch: Option<u32>,
+    },
+    ZkappFailureStatsGet {
+        rpc_id: RpcId,
+        from_epoch: Option<u32>,
+    },
+    SnarkVerifyStatsGet {
+        rpc_id: RpcId,
+        response: RpcSnarkVerifyStatsGetResponse,
+    },
+    StatsHistoryGet {
+        rpc_id: RpcId,
+        query: StatsHistoryQuery,
+    },
+    BlockPropagationGet {
+        rpc_id: RpcId,
+        from_height: Option<u32>,
+    },
 
     MessageProgressGet {
         rpc_id: RpcId,
@@ -53,6 +89,10 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         peers: Vec<RpcPeerInfo>,
     },
+    P2pDiagnosticsGet {
+        rpc_id: RpcId,
+        response: RpcP2pDiagnosticsGetResponse,
+    },
     P2pConnectionOutgoingError {
         rpc_id: RpcId,
         error: String,
@@ -125,10 +165,27 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         response: Vec<WithHash<UserCommand, v2::TransactionHash>>,
     },
+    TransactionPoolZkappStatsGet {
+        rpc_id: RpcId,
+        stats: RpcTransactionPoolZkappStatsGetResponse,
+    },
     LedgerAccountsGetSuccess {
         rpc_id: RpcId,
-        accounts: Vec<Account>,
+        accounts: Result<Vec<Account>, String>,
         account_query: AccountQuery,
+        continuation: Option<RpcContinuationToken>,
+    },
+    LedgerAccountsAtBlockArchiveGet {
+        rpc_id: RpcId,
+        query: RpcLedgerAccountsAtBlockQuery,
+    },
+    LedgerAccountsAtBlockGetSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsAtBlockResponse,
+    },
+    AccountNextNoncesGetSuccess {
+        rpc_id: RpcId,
+        response: RpcAccountNextNoncesGetResponse,
     },
     TransactionInjectSuccess {
         rpc_id: RpcId,
@@ -162,6 +219,116 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         block: RpcGetBlockResponse,
     },
+    TransactionInclusionProofGet {
+        rpc_id: RpcId,
+        proof: RpcTransactionInclusionProofGetResponse,
+    },
+    FinalityEstimateGet {
+        rpc_id: RpcId,
+        estimate: RpcFinalityEstimateGetResponse,
+    },
+    NodeInfoGet {
+        rpc_id: RpcId,
+        info: RpcNodeInfoGetResponse,
+    },
+    InternalCommandsGet {
+        rpc_id: RpcId,
+        response: RpcInternalCommandsGetResponse,
+    },
+    SyncPeerScoresGet {
+        rpc_id: RpcId,
+        response: RpcSyncPeerScoresGetResponse,
+    },
+    Profiler {
+        rpc_id: RpcId,
+        command: RpcProfilerCommand,
+    },
+    MaintenanceMode {
+        rpc_id: RpcId,
+        status: RpcMaintenanceModeResponse,
+    },
+    FeatureFlags {
+        rpc_id: RpcId,
+        response: RpcFeatureFlagsResponse,
+    },
+    CheckpointsGet {
+        rpc_id: RpcId,
+        response: RpcCheckpointsGetResponse,
+    },
+    TransactionPoolSnapshot {
+        rpc_id: RpcId,
+        response: RpcTransactionPoolSnapshotResponse,
+    },
+    VrfVerifySuccess {
+        rpc_id: RpcId,
+        response: RpcVrfVerifyResponse,
+    },
+    LedgerAudit {
+        rpc_id: RpcId,
+        response: RpcLedgerAuditResponse,
+    },
+    BlockTemplateGet {
+        rpc_id: RpcId,
+        response: RpcBlockTemplateGetResponse,
+    },
+    BlockTemplateSubmit {
+        rpc_id: RpcId,
+        response: RpcBlockTemplateSubmitResponse,
+    },
+    MaintenanceRejected {
+        rpc_id: RpcId,
+        request: RpcRequest,
+    },
+    OverloadShed {
+        rpc_id: RpcId,
+        request: RpcRequest,
+    },
+    LightModeRejected {
+        rpc_id: RpcId,
+        request: RpcRequest,
+    },
+    AccountSubscriptions {
+        rpc_id: RpcId,
+        response: RpcAccountSubscriptionsResponse,
+    },
+    AccountSubscriptionsFetch {
+        ledger_hash: v2::LedgerHash,
+        block_hash: v2::StateHash,
+        height: u32,
+        public_keys: Vec<AccountPublicKey>,
+    },
+    AccountEventsSend {
+        subscribers: Vec<RpcId>,
+        events: Vec<RpcWatchedAccountEvent>,
+    },
+    SnarkPoolJobEventsSend {
+        subscribers: Vec<RpcId>,
+        events: Vec<RpcSnarkPoolJobEvent>,
+    },
+    ZkappVkRegister {
+        rpc_id: RpcId,
+        response: RpcZkappVkRegisterResponse,
+    },
+    /// Evaluates the preconditions against the best tip and its ledger,
+    /// with `valid_while` against `global_slot`.
+    ZkappPreconditionsEvaluate {
+        rpc_id: RpcId,
+        command: Box<v2::MinaBaseZkappCommandTStableV1WireStableV1>,
+        best_tip: Option<ArcBlockWithHash>,
+        global_slot: u32,
+    },
+    PayoutsBatchSubmit {
+        rpc_id: RpcId,
+        response: RpcPayoutsBatchSubmitResponse,
+    },
+    PayoutsBatchGet {
+        rpc_id: RpcId,
+        response: RpcPayoutsBatchGetResponse,
+    },
+    ChainImportBlock {
+        rpc_id: RpcId,
+        response: RpcChainImportBlockResponse,
+    },
     PooledUserCommands {
         rpc_id: RpcId,
         user_commands: RpcPooledUserCommandsResponse,
@@ -182,6 +349,14 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         response: RpcLedgerStatusGetResponse,
     },
+    LedgerStatusExtendedGetSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerStatusExtendedGetResponse,
+    },
+    LedgerSessionSuccess {
+        rpc_id: RpcId,
+        response: RpcLedgerSessionResponse,
+    },
     LedgerAccountDelegatorsGetSuccess {
         rpc_id: RpcId,
         response: RpcLedgerAccountDelegatorsGetResponse,
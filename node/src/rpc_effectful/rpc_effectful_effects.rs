@@ -4,7 +4,7 @@ use std::{
     time::Duration,
 };
 
-use super::{super::rpc, RpcEffectfulAction};
+use super::{super::rpc, RespondError, RpcEffectfulAction};
 use crate::{
     block_producer::BlockProducerWonSlot,
     external_snark_worker::available_job_to_snark_worker_spec,
@@ -13,25 +13,34 @@ use crate::{
     rpc::{
         AccountQuery, AccountSlim, ActionStatsQuery, ActionStatsResponse, CurrentMessageProgress,
         MessagesStats, NodeHeartbeat, ProducedBlockInfo, RootLedgerSyncProgress,
-        RootStagedLedgerSyncProgress, RpcAction, RpcBlockProducerStats, RpcMessageProgressResponse,
-        RpcNodeStatus, RpcNodeStatusLedger, RpcNodeStatusNetworkInfo, RpcNodeStatusResources,
+        RootStagedLedgerSyncProgress, RpcAction, RpcBlockProducerStats, RpcBlockPropagation,
+        RpcContinuationToken, RpcLedgerSlimAccounts, RpcMessageProgressResponse, RpcNodeStatus,
+        RpcNodeStatusLedger, RpcNodeStatusNetworkInfo, RpcNodeStatusResources,
         RpcNodeStatusTransactionPool, RpcNodeStatusTransitionFrontier,
         RpcNodeStatusTransitionFrontierBlockSummary, RpcNodeStatusTransitionFrontierSync,
-        RpcRequestExtraData, RpcScanStateSummary, RpcScanStateSummaryBlock,
-        RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryBlockTransactionKind,
-        RpcScanStateSummaryScanStateJob, RpcSnarkPoolJobFull, RpcSnarkPoolJobSnarkWork,
-        RpcSnarkPoolJobSummary, RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse,
-        RpcTransactionInjectResponse, TransactionStatus,
+        RpcProfilerCommand, RpcRequest, RpcRequestExtraData, RpcScanStateSummary,
+        RpcScanStateSummaryBlock, RpcScanStateSummaryBlockTransaction,
+        RpcScanStateSummaryBlockTransactionKind, RpcScanStateSummaryScanStateJob,
+        RpcSnarkPoolJobFull, RpcSnarkPoolJobSnarkWork, RpcSnarkPoolJobSummary,
+        RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcTransactionInjectResponse,
+        RpcZkappPreconditionsEvaluateResponse, TransactionStatus, RPC_LIGHT_MODE_ERROR,
+        RPC_MAINTENANCE_MODE_ERROR,
     },
+    service::ArchiveService,
     snark_pool::SnarkPoolAction,
+    stats::profiler::PROFILER_DEFAULT_FREQUENCY_HZ,
     transition_frontier::sync::{
         ledger::TransitionFrontierSyncLedgerState, TransitionFrontierSyncState,
     },
     Service, Store,
 };
 use ledger::{
-    scan_state::currency::{Balance, Magnitude},
-    Account,
+    scan_state::{
+        currency::{Balance, Magnitude, Slot},
+        transaction_logic::{protocol_state::protocol_state_view, zkapp_command::ZkAppCommand},
+    },
+    zkapps::preconditions_report,
+    AccountId,
 };
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use mina_p2p_messages::{rpc_kernel::QueryHeader, v2};
@@ -93,6 +102,19 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
 
             let _ = store.service.respond_heartbeat_get(rpc_id, response);
         }
+        RpcEffectfulAction::StateDigestGet { rpc_id } => {
+            let response = store
+                .state()
+                .digest_snapshot()
+                .map_err(|error| {
+                    bug_condition!("StateDigestGet: failed to serialize the state: {error}")
+                })
+                .ok();
+            respond_or_log!(
+                store.service().respond_state_digest_get(rpc_id, response),
+                meta.time()
+            );
+        }
         RpcEffectfulAction::ActionStatsGet { rpc_id, query } => match query {
             ActionStatsQuery::SinceStart => {
                 let resp = store
@@ -126,6 +148,24 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 .map(|s| s.collect_sync_stats(query.limit));
             let _ = store.service.respond_sync_stats_get(rpc_id, resp);
         }
+        RpcEffectfulAction::StatsHistoryGet { rpc_id, query } => {
+            let resp = store
+                .service
+                .stats()
+                .map(|s| s.collect_history(query.from, query.to));
+            let _ = store.service.respond_stats_history_get(rpc_id, resp);
+        }
+        RpcEffectfulAction::BlockPropagationGet {
+            rpc_id,
+            from_height,
+        } => {
+            let siblings = store.state().config.global.sibling_nodes.clone();
+            let resp = store.service.stats().map(|s| RpcBlockPropagation {
+                siblings,
+                blocks: s.collect_block_propagation(from_height),
+            });
+            let _ = store.service.respond_block_propagation_get(rpc_id, resp);
+        }
         RpcEffectfulAction::BlockProducerStatsGet { rpc_id } => {
             let mut create_response = || {
                 let state = store.state.get();
@@ -151,6 +191,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 let current_epoch_vrf_stats = current_epoch
                     .and_then(|epoch| stats.block_producer().vrf_evaluator.get(&epoch).cloned());
                 let vrf_stats = stats.block_producer().vrf_evaluator.clone();
+                let diff_truncation = stats.block_producer().diff_truncation.clone();
 
                 Some(RpcBlockProducerStats {
                     current_time: meta.time(),
@@ -158,6 +199,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                     current_epoch,
                     current_epoch_vrf_stats,
                     vrf_stats,
+                    diff_truncation,
                     epoch_start,
                     epoch_end: epoch_start
                         .map(|slot| slot.checked_add(slots_per_epoch).expect("overflow")),
@@ -180,6 +222,52 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 .service
                 .respond_block_producer_stats_get(rpc_id, response);
         }
+        RpcEffectfulAction::ZkappFailureStatsGet { rpc_id, from_epoch } => {
+            let resp = store
+                .service
+                .stats()
+                .map(|s| s.collect_zkapp_failures(from_epoch));
+            let _ = store.service.respond_zkapp_failure_stats_get(rpc_id, resp);
+        }
+        RpcEffectfulAction::SnarkVerifyStatsGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_snark_verify_stats_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::BlockProducerEpochReportGet { rpc_id, epoch } => {
+            let create_response = || {
+                let state = store.state.get();
+                let won_slots = &state.block_producer.vrf_evaluator()?.won_slots;
+                let cur_global_slot = state.cur_global_slot()?;
+                let epoch = epoch.or_else(|| state.current_epoch())?;
+                let constants = &state.config.consensus_constants;
+                let epoch_start = epoch.checked_mul(constants.slots_per_epoch)?;
+                let epoch_end = epoch_start.saturating_add(constants.slots_per_epoch);
+                let slot_duration = Duration::from_millis(constants.block_window_duration_ms);
+
+                let stats = store.service.stats()?;
+                Some(
+                    stats.block_producer().epoch_report(
+                        epoch,
+                        won_slots
+                            .range(epoch_start..epoch_end)
+                            .map(|(slot, _)| *slot),
+                        cur_global_slot,
+                        slot_duration,
+                    ),
+                )
+            };
+            let response = create_response();
+            respond_or_log!(
+                store
+                    .service
+                    .respond_block_producer_epoch_report_get(rpc_id, response),
+                meta.time()
+            );
+        }
         RpcEffectfulAction::MessageProgressGet { rpc_id } => {
             // TODO: move to stats
             let p2p = p2p_ready!(store.state().p2p, meta.time());
@@ -310,6 +398,14 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 meta.time()
             );
         }
+        RpcEffectfulAction::P2pDiagnosticsGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_p2p_diagnostics_get(rpc_id, response),
+                meta.time()
+            );
+        }
         RpcEffectfulAction::P2pConnectionOutgoingError { rpc_id, error } => {
             let _ = store
                 .service
@@ -356,6 +452,10 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
             mut scan_state,
         } => {
             let req = store.state().rpc.requests.get(&rpc_id);
+            let continuation = req.and_then(|req| match &req.req {
+                RpcRequest::ScanStateSummaryGet(_, continuation) => continuation.clone(),
+                _ => None,
+            });
             let Some(block) = req.and_then(|req| match &req.data {
                 RpcRequestExtraData::FullBlockOpt(opt) => opt.as_ref(),
                 _ => None,
@@ -366,6 +466,17 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 );
                 return;
             };
+            let ledger_hash = block.merkle_root_hash().clone();
+            if continuation
+                .as_ref()
+                .is_some_and(|token| token.ledger_hash != ledger_hash)
+            {
+                let _ = store.service.respond_scan_state_summary_get(
+                    rpc_id,
+                    Err("continuation token is for the scan state of another block".to_owned()),
+                );
+                return;
+            }
             let coinbases =
                 block
                     .coinbase_fee_transfers_iter()
@@ -430,9 +541,22 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                     };
                 }
             });
-            let res = scan_state.map(|scan_state| RpcScanStateSummary {
-                block: block_summary,
-                scan_state,
+            let limits = store.state().config.rpc_response_limits;
+            let res = scan_state.map(|mut scan_state| {
+                let offset = continuation.map_or(0, |token| token.offset);
+                let continuation =
+                    limits
+                        .truncate(&mut scan_state, offset)
+                        .map(|offset| RpcContinuationToken {
+                            ledger_hash,
+                            offset,
+                        });
+                RpcScanStateSummary {
+                    block: block_summary,
+                    scan_state,
+                    truncated: continuation.is_some(),
+                    continuation,
+                }
             });
             let _ = store.service.respond_scan_state_summary_get(rpc_id, res);
         }
@@ -634,64 +758,87 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 meta.time()
             )
         }
+        RpcEffectfulAction::TransactionPoolZkappStatsGet { rpc_id, stats } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_transaction_pool_zkapp_stats_get(rpc_id, stats),
+                meta.time()
+            )
+        }
         RpcEffectfulAction::LedgerAccountsGetSuccess {
             rpc_id,
             accounts,
             account_query,
+            continuation,
         } => {
-            // Is this todo still relevant?
-            // TODO(adonagy): maybe something more effective?
             match account_query {
-                // all the accounts for the FE in Slim form
+                // page of all the accounts for the FE in Slim form
                 AccountQuery::All => {
-                    let mut accounts: BTreeMap<CompressedPubKey, Account> = accounts
-                        .into_iter()
-                        .map(|acc| (acc.public_key.clone(), acc))
-                        .collect();
+                    let mut accounts = match accounts {
+                        Ok(accounts) => accounts,
+                        Err(err) => {
+                            respond_or_log!(
+                                store
+                                    .service()
+                                    .respond_ledger_slim_accounts(rpc_id, Err(err)),
+                                meta.time()
+                            );
+                            return;
+                        }
+                    };
                     let nonces_and_amount = store
                         .state()
                         .transaction_pool
                         .get_pending_amount_and_nonce();
-
-                    nonces_and_amount
+                    let pending = nonces_and_amount
                         .iter()
-                        .for_each(|(account_id, (nonce, amount))| {
-                            if let Some(account) = accounts.get_mut(&account_id.public_key) {
-                                if let Some(nonce) = nonce {
-                                    if nonce >= &account.nonce {
-                                        // increment the last nonce in the pool
-                                        account.nonce = nonce.incr();
-                                    }
-                                }
-                                account.balance = account
-                                    .balance
-                                    .sub_amount(*amount)
-                                    .unwrap_or(Balance::zero());
+                        .map(|(account_id, pending)| (&account_id.public_key, pending))
+                        .collect::<BTreeMap<_, _>>();
+
+                    for account in &mut accounts {
+                        let Some((nonce, amount)) = pending.get(&account.public_key) else {
+                            continue;
+                        };
+                        if let Some(nonce) = nonce {
+                            if nonce >= &account.nonce {
+                                // increment the last nonce in the pool
+                                account.nonce = nonce.incr();
                             }
-                        });
+                        }
+                        account.balance = account
+                            .balance
+                            .sub_amount(*amount)
+                            .unwrap_or(Balance::zero());
+                    }
 
-                    let accounts = accounts
-                        .into_values()
-                        .map(|v| v.into())
-                        .collect::<Vec<AccountSlim>>();
+                    let response = RpcLedgerSlimAccounts {
+                        accounts: accounts.into_iter().map(AccountSlim::from).collect(),
+                        truncated: continuation.is_some(),
+                        continuation,
+                    };
 
                     respond_or_log!(
                         store
                             .service()
-                            .respond_ledger_slim_accounts(rpc_id, accounts),
+                            .respond_ledger_slim_accounts(rpc_id, Ok(response)),
                         meta.time()
                     )
                 }
                 // for the graphql endpoint
                 AccountQuery::SinglePublicKey(..) | AccountQuery::PubKeyWithTokenId(..) => {
                     respond_or_log!(
-                        store.service().respond_ledger_accounts(rpc_id, accounts),
+                        store
+                            .service()
+                            .respond_ledger_accounts(rpc_id, accounts.unwrap_or_default()),
                         meta.time()
                     )
                 }
                 AccountQuery::MultipleIds(..) => {
                     respond_or_log!(
-                        store.service().respond_ledger_accounts(rpc_id, accounts),
+                        store
+                            .service()
+                            .respond_ledger_accounts(rpc_id, accounts.unwrap_or_default()),
                         meta.time()
                     )
                 }
@@ -794,12 +941,403 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 )
             }
         }
+        RpcEffectfulAction::LedgerAccountsAtBlockArchiveGet { rpc_id, query } => {
+            store.service().accounts_at_block(rpc_id, query);
+        }
+        RpcEffectfulAction::LedgerAccountsAtBlockGetSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_ledger_accounts_at_block(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::AccountNextNoncesGetSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_account_next_nonces(rpc_id, response),
+                meta.time()
+            )
+        }
         RpcEffectfulAction::BlockGet { rpc_id, block } => {
             respond_or_log!(
                 store.service().respond_block_get(rpc_id, block),
                 meta.time()
             )
         }
+        RpcEffectfulAction::FinalityEstimateGet { rpc_id, estimate } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_finality_estimate_get(rpc_id, estimate),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::NodeInfoGet { rpc_id, info } => {
+            respond_or_log!(
+                store.service().respond_node_info_get(rpc_id, info),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::InternalCommandsGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_internal_commands_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::SyncPeerScoresGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_sync_peer_scores_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::Profiler { rpc_id, command } => {
+            let profiler = store.service.profiler();
+            let response = match command {
+                RpcProfilerCommand::Start { frequency_hz } => {
+                    profiler.start(frequency_hz.unwrap_or(PROFILER_DEFAULT_FREQUENCY_HZ))
+                }
+                RpcProfilerCommand::Stop => profiler.stop(),
+            };
+            respond_or_log!(
+                store.service().respond_profiler(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::MaintenanceMode { rpc_id, status } => {
+            respond_or_log!(
+                store.service().respond_maintenance_mode(rpc_id, status),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::FeatureFlags { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_feature_flags(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::CheckpointsGet { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_checkpoints_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::TransactionPoolSnapshot { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_transaction_pool_snapshot(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::VrfVerifySuccess { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_vrf_verify(rpc_id, response),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcEffectfulAction::LedgerAudit { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_ledger_audit(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::BlockTemplateGet { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_block_template_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::BlockTemplateSubmit { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_block_template_submit(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::AccountSubscriptions { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_account_subscriptions(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::AccountSubscriptionsFetch {
+            ledger_hash,
+            block_hash,
+            height,
+            public_keys,
+        } => {
+            let account_ids = public_keys
+                .iter()
+                .filter_map(|public_key| CompressedPubKey::try_from(public_key.clone()).ok())
+                .map(AccountId::new_with_default_token)
+                .collect();
+            // TODO: should be asynchronous, like the ledger reads.
+            let accounts = match store
+                .service()
+                .ledger_manager()
+                .get_accounts(&ledger_hash, account_ids)
+            {
+                Ok(accounts) => accounts,
+                Err(error) => {
+                    openmina_core::log::warn!(meta.time();
+                        kind = "RpcAccountSubscriptionsFetch",
+                        summary = "failed to fetch watched accounts",
+                        ledger_hash = ledger_hash.to_string(),
+                        error = error);
+                    return;
+                }
+            };
+            let mut accounts = accounts
+                .iter()
+                .map(|account| (account.public_key.clone().into(), Some(account.into())))
+                .collect::<BTreeMap<_, _>>();
+            for public_key in public_keys {
+                accounts.entry(public_key).or_insert(None);
+            }
+            store.dispatch(RpcAction::AccountSubscriptionsUpdate {
+                block_hash,
+                height,
+                accounts,
+            });
+        }
+        RpcEffectfulAction::AccountEventsSend {
+            subscribers,
+            events,
+        } => {
+            for rpc_id in subscribers {
+                for event in &events {
+                    match store.service().respond_account_event(rpc_id, event.clone()) {
+                        Ok(()) => {}
+                        Err(RespondError::UnknownRpcId) => {
+                            store.dispatch(RpcAction::AccountEventsUnsubscribe { rpc_id });
+                            break;
+                        }
+                        Err(err) => {
+                            openmina_core::log::warn!(meta.time(); "Failed to respond: {err}");
+                        }
+                    }
+                }
+            }
+        }
+        RpcEffectfulAction::SnarkPoolJobEventsSend {
+            subscribers,
+            events,
+        } => {
+            for rpc_id in subscribers {
+                match store
+                    .service()
+                    .respond_snark_pool_job_events(rpc_id, events.clone())
+                {
+                    Ok(()) => {}
+                    Err(RespondError::UnknownRpcId) => {
+                        store.dispatch(RpcAction::SnarkPoolJobEventsUnsubscribe { rpc_id });
+                    }
+                    Err(RespondError::SubscriberOverflow) => {
+                        openmina_core::log::warn!(meta.time(); "Snark pool job events subscriber {rpc_id} overflowed, dropping it");
+                        store.dispatch(RpcAction::SnarkPoolJobEventsUnsubscribe { rpc_id });
+                    }
+                    Err(err) => {
+                        openmina_core::log::warn!(meta.time(); "Failed to respond: {err}");
+                    }
+                }
+            }
+        }
+        RpcEffectfulAction::ZkappVkRegister { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_zkapp_vk_register(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::ZkappPreconditionsEvaluate {
+            rpc_id,
+            command,
+            best_tip,
+            global_slot,
+        } => {
+            let response = evaluate_zkapp_preconditions(store, &command, best_tip, global_slot);
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_zkapp_preconditions_evaluate(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::PayoutsBatchSubmit { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_payouts_batch_submit(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::PayoutsBatchGet { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_payouts_batch_get(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::ChainImportBlock { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_chain_import_block(rpc_id, response),
+                meta.time()
+            )
+        }
+        RpcEffectfulAction::MaintenanceRejected { rpc_id, request } => {
+            let error = RPC_MAINTENANCE_MODE_ERROR.to_owned();
+            let result = match request {
+                RpcRequest::P2pConnectionOutgoing(_) => store
+                    .service()
+                    .respond_p2p_connection_outgoing(rpc_id, Err(error)),
+                RpcRequest::SnarkerJobCommit { .. } => store.service().respond_snarker_job_commit(
+                    rpc_id,
+                    RpcSnarkerJobCommitResponse::NodeInMaintenance,
+                ),
+                RpcRequest::TransactionInject(_) => store.service().respond_transaction_inject(
+                    rpc_id,
+                    RpcTransactionInjectResponse::Failure(vec![error]),
+                ),
+                RpcRequest::PayoutsBatchSubmit(_) => store
+                    .service()
+                    .respond_payouts_batch_submit(rpc_id, Err(error)),
+                RpcRequest::ZkappFeeSponsor(_) => store.service().respond_transaction_inject(
+                    rpc_id,
+                    RpcTransactionInjectResponse::Failure(vec![error]),
+                ),
+                RpcRequest::ChainImportBlock(_) => store
+                    .service()
+                    .respond_chain_import_block(rpc_id, Err(error)),
+                RpcRequest::BlockTemplateSubmit(_) => store
+                    .service()
+                    .respond_block_template_submit(rpc_id, Err(error)),
+                request => {
+                    bug_condition!("unexpected request rejected in maintenance mode: {request:?}");
+                    return;
+                }
+            };
+            respond_or_log!(result, meta.time())
+        }
+        RpcEffectfulAction::OverloadShed { rpc_id, request } => {
+            let service = store.service();
+            let result = match request {
+                RpcRequest::ActionStatsGet(_) => service.respond_action_stats_get(rpc_id, None),
+                RpcRequest::SyncStatsGet(_) => service.respond_sync_stats_get(rpc_id, None),
+                RpcRequest::BlockProducerStatsGet => {
+                    service.respond_block_producer_stats_get(rpc_id, None)
+                }
+                RpcRequest::BlockProducerEpochReportGet(_) => {
+                    service.respond_block_producer_epoch_report_get(rpc_id, None)
+                }
+                RpcRequest::ZkappFailureStatsGet(_) => {
+                    service.respond_zkapp_failure_stats_get(rpc_id, None)
+                }
+                RpcRequest::SnarkVerifyStatsGet => {
+                    service.respond_snark_verify_stats_get(rpc_id, None)
+                }
+                RpcRequest::StatsHistoryGet(_) => service.respond_stats_history_get(rpc_id, None),
+                RpcRequest::BlockPropagationGet(_) => {
+                    service.respond_block_propagation_get(rpc_id, None)
+                }
+                RpcRequest::LedgerStatusExtendedGet => {
+                    service.respond_ledger_status_extended_get(rpc_id, None)
+                }
+                RpcRequest::TransactionPoolZkappStatsGet => {
+                    service.respond_transaction_pool_zkapp_stats_get(rpc_id, Default::default())
+                }
+                request => {
+                    bug_condition!("unexpected request shed during overload: {request:?}");
+                    return;
+                }
+            };
+            respond_or_log!(result, meta.time())
+        }
+        RpcEffectfulAction::LightModeRejected { rpc_id, request } => {
+            let error = RPC_LIGHT_MODE_ERROR.to_owned();
+            let service = store.service();
+            let result = match request {
+                RpcRequest::ScanStateSummaryGet(..) => {
+                    service.respond_scan_state_summary_get(rpc_id, Err(error))
+                }
+                RpcRequest::SnarkPoolGet => service.respond_snark_pool_get(rpc_id, Vec::new()),
+                RpcRequest::SnarkPoolJobGet { .. } => {
+                    service.respond_snark_pool_job_get(rpc_id, None)
+                }
+                RpcRequest::SnarkPoolCompletedJobsGet => {
+                    service.respond_snark_pool_completed_jobs_get(rpc_id, Vec::new())
+                }
+                RpcRequest::SnarkPoolPendingJobsGet => {
+                    service.respond_snark_pool_pending_jobs_get(rpc_id, Vec::new())
+                }
+                RpcRequest::TransactionPoolGet => {
+                    service.respond_transaction_pool(rpc_id, Vec::new())
+                }
+                RpcRequest::TransactionPoolZkappStatsGet => {
+                    service.respond_transaction_pool_zkapp_stats_get(rpc_id, Default::default())
+                }
+                RpcRequest::LedgerAccountsGet(AccountQuery::All, _) => {
+                    service.respond_ledger_slim_accounts(rpc_id, Err(error))
+                }
+                RpcRequest::LedgerAccountsGet(..) => {
+                    service.respond_ledger_accounts(rpc_id, Vec::new())
+                }
+                RpcRequest::LedgerAccountsAtBlockGet(_) => {
+                    service.respond_ledger_accounts_at_block(rpc_id, Err(error))
+                }
+                RpcRequest::AccountNextNoncesGet(_) => {
+                    service.respond_account_next_nonces(rpc_id, Err(error))
+                }
+                RpcRequest::TransactionInject(_) => service.respond_transaction_inject(
+                    rpc_id,
+                    RpcTransactionInjectResponse::Failure(vec![error]),
+                ),
+                RpcRequest::TransactionStatusGet(_) => {
+                    service.respond_transaction_status(rpc_id, TransactionStatus::Unknown)
+                }
+                RpcRequest::PooledUserCommands(_) => {
+                    service.respond_pooled_user_commands(rpc_id, Vec::new())
+                }
+                RpcRequest::PooledZkappCommands(_) => {
+                    service.respond_pooled_zkapp_commands(rpc_id, Vec::new())
+                }
+                RpcRequest::LedgerStatusGet(_) => service.respond_ledger_status_get(rpc_id, None),
+                RpcRequest::LedgerStatusExtendedGet => {
+                    service.respond_ledger_status_extended_get(rpc_id, None)
+                }
+                RpcRequest::LedgerAccountDelegatorsGet(..) => {
+                    service.respond_ledger_account_delegators_get(rpc_id, None)
+                }
+                RpcRequest::LedgerSession(_) => service.respond_ledger_session(rpc_id, Err(error)),
+                RpcRequest::ZkappPreconditionsEvaluate(_) => {
+                    service.respond_zkapp_preconditions_evaluate(rpc_id, Err(error))
+                }
+                RpcRequest::TransactionPoolSnapshot(_) => {
+                    service.respond_transaction_pool_snapshot(rpc_id, Err(error))
+                }
+                RpcRequest::LedgerAudit(_) => service.respond_ledger_audit(rpc_id, Err(error)),
+                request => {
+                    bug_condition!("unexpected request rejected in light mode: {request:?}");
+                    return;
+                }
+            };
+            respond_or_log!(result, meta.time())
+        }
+        RpcEffectfulAction::TransactionInclusionProofGet { rpc_id, proof } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_transaction_inclusion_proof_get(rpc_id, proof),
+                meta.time()
+            )
+        }
 
         RpcEffectfulAction::PooledUserCommands {
             rpc_id,
@@ -851,6 +1389,21 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 meta.time()
             )
         }
+        RpcEffectfulAction::LedgerStatusExtendedGetSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_ledger_status_extended_get(rpc_id, response),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcEffectfulAction::LedgerSessionSuccess { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_ledger_session(rpc_id, response),
+                meta.time()
+            )
+        }
         RpcEffectfulAction::LedgerAccountDelegatorsGetSuccess { rpc_id, response } => {
             respond_or_log!(
                 store
@@ -862,6 +1415,31 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
     }
 }
 
+fn evaluate_zkapp_preconditions<S: Service>(
+    store: &mut Store<S>,
+    command: &v2::MinaBaseZkappCommandTStableV1WireStableV1,
+    best_tip: Option<ArcBlockWithHash>,
+    global_slot: u32,
+) -> RpcZkappPreconditionsEvaluateResponse {
+    let command =
+        ZkAppCommand::try_from(command).map_err(|err| format!("invalid zkapp command: {err:?}"))?;
+    let best_tip = best_tip.ok_or("best tip not available")?;
+    let view = protocol_state_view(&best_tip.header().protocol_state)
+        .map_err(|err| format!("invalid best tip protocol state: {err:?}"))?;
+    // TODO: should be asynchronous, like the ledger reads.
+    let accounts = store
+        .service()
+        .ledger_manager()
+        .get_accounts(best_tip.merkle_root_hash(), command.accounts_referenced())?;
+
+    Ok(preconditions_report::evaluate_zkapp_command(
+        &command,
+        &view,
+        Slot::from_u32(global_slot),
+        &accounts,
+    ))
+}
+
 fn compute_node_status<S: Service>(store: &mut Store<S>) -> RpcNodeStatus {
     let state = store.state.get();
     let chain_id = state.p2p.ready().map(|p2p| p2p.chain_id.to_hex());
@@ -894,6 +1472,11 @@ fn compute_node_status<S: Service>(store: &mut Store<S>) -> RpcNodeStatus {
             .map(|addr| addr.to_string()),
         client_port: state.config.client_port,
         libp2p_port: state.p2p.config().libp2p_port,
+        handshake_failures: state
+            .p2p
+            .ready()
+            .map(|p2p| p2p.handshake_failures.clone())
+            .unwrap_or_default(),
     };
 
     let block_producer = state
@@ -932,6 +1515,8 @@ fn compute_node_status<S: Service>(store: &mut Store<S>) -> RpcNodeStatus {
                 .pending_requests()
                 .map(|(id, req, time)| (id, req.kind(), time))
                 .collect(),
+            reads_throttled: state.ledger.read.throttled(),
+            reads_throttled_peers: state.ledger.read.throttled_peers().copied().collect(),
         },
         peers: rpc::collect_rpc_peers_info(state),
         snark_pool: state
@@ -949,6 +1534,7 @@ fn compute_node_status<S: Service>(store: &mut Store<S>) -> RpcNodeStatus {
             transactions_for_propagation: state.transaction_pool.for_propagation_size(),
             transaction_candidates: state.transaction_pool.candidates.transactions_count(),
         },
+        snark_verify_admission: state.snark.verify_admission_stats(),
         current_block_production_attempt,
         previous_block_production_attempt,
         resources_status: RpcNodeStatusResources {
@@ -1,20 +1,33 @@
 use crate::{
     p2p::connection::P2pConnectionResponse,
     rpc::{
-        RpcActionStatsGetResponse, RpcBestChainResponse, RpcBlockProducerStatsGetResponse,
-        RpcConsensusTimeGetResponse, RpcDiscoveryBoostrapStatsResponse,
-        RpcDiscoveryRoutingTableResponse, RpcGenesisBlockResponse, RpcGetBlockResponse,
-        RpcHealthCheckResponse, RpcHeartbeatGetResponse, RpcId,
-        RpcLedgerAccountDelegatorsGetResponse, RpcLedgerAccountsResponse,
-        RpcLedgerSlimAccountsResponse, RpcLedgerStatusGetResponse, RpcMessageProgressResponse,
-        RpcP2pConnectionOutgoingResponse, RpcPeersGetResponse, RpcPooledUserCommandsResponse,
-        RpcPooledZkappCommandsResponse, RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse,
-        RpcSnarkPoolCompletedJobsResponse, RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse,
-        RpcSnarkPoolPendingJobsGetResponse, RpcSnarkerConfigGetResponse,
-        RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcSnarkerWorkersResponse,
-        RpcStatusGetResponse, RpcSyncStatsGetResponse, RpcTransactionInjectResponse,
-        RpcTransactionPoolResponse, RpcTransactionStatusGetResponse,
-        RpcTransitionFrontierUserCommandsResponse,
+        RpcAccountNextNoncesGetResponse, RpcAccountSubscriptionsResponse,
+        RpcActionStatsGetResponse, RpcBestChainResponse, RpcBlockProducerEpochReportGetResponse,
+        RpcBlockProducerStatsGetResponse, RpcBlockPropagationGetResponse,
+        RpcBlockTemplateGetResponse, RpcBlockTemplateSubmitResponse, RpcChainImportBlockResponse,
+        RpcCheckpointsGetResponse, RpcConsensusTimeGetResponse, RpcDiscoveryBoostrapStatsResponse,
+        RpcDiscoveryRoutingTableResponse, RpcFeatureFlagsResponse, RpcFinalityEstimateGetResponse,
+        RpcGenesisBlockResponse, RpcGetBlockResponse, RpcHealthCheckResponse,
+        RpcHeartbeatGetResponse, RpcId, RpcInternalCommandsGetResponse,
+        RpcLedgerAccountDelegatorsGetResponse, RpcLedgerAccountsAtBlockResponse,
+        RpcLedgerAccountsResponse, RpcLedgerAuditResponse, RpcLedgerSessionResponse,
+        RpcLedgerSlimAccountsResponse, RpcLedgerStatusExtendedGetResponse,
+        RpcLedgerStatusGetResponse, RpcMaintenanceModeResponse, RpcMessageProgressResponse,
+        RpcNodeInfoGetResponse, RpcP2pConnectionOutgoingResponse, RpcP2pDiagnosticsGetResponse,
+        RpcPayoutsBatchGetResponse, RpcPayoutsBatchSubmitResponse, RpcPeersGetResponse,
+        RpcPooledUserCommandsResponse, RpcPooledZkappCommandsResponse, RpcProfilerResponse,
+        RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse,
+        RpcSnarkPoolCompletedJobsResponse, RpcSnarkPoolGetResponse, RpcSnarkPoolJobEvent,
+        RpcSnarkPoolJobGetResponse, RpcSnarkPoolPendingJobsGetResponse,
+        RpcSnarkVerifyStatsGetResponse, RpcSnarkerConfigGetResponse, RpcSnarkerJobCommitResponse,
+        RpcSnarkerJobSpecResponse, RpcSnarkerWorkersResponse, RpcStateDigestGetResponse,
+        RpcStatsHistoryGetResponse, RpcStatusGetResponse, RpcSyncPeerScoresGetResponse,
+        RpcSyncStatsGetResponse, RpcTransactionInclusionProofGetResponse,
+        RpcTransactionInjectResponse, RpcTransactionPoolResponse,
+        RpcTransactionPoolSnapshotResponse, RpcTransactionPoolZkappStatsGetResponse,
+        RpcTransactionStatusGetResponse, RpcTransitionFrontierUserCommandsResponse,
+        RpcVrfVerifyResponse, RpcWatchedAccountEvent, RpcZkappFailureStatsGetResponse,
+        RpcZkappPreconditionsEvaluateResponse, RpcZkappVkRegisterResponse,
     },
     State,
 };
@@ -30,6 +43,8 @@ pub enum RespondError {
     UnexpectedResponseType,
     #[error("responding failed")]
     RespondingFailed,
+    #[error("subscriber fell behind the events")]
+    SubscriberOverflow,
     #[error("{0}")]
     Custom(String),
 }
@@ -62,6 +77,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcHeartbeatGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_state_digest_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcStateDigestGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_action_stats_get(
         &mut self,
         rpc_id: RpcId,
@@ -77,6 +97,31 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcBlockProducerStatsGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_zkapp_failure_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcZkappFailureStatsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snark_verify_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkVerifyStatsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_producer_epoch_report_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockProducerEpochReportGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_stats_history_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcStatsHistoryGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_propagation_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockPropagationGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_message_progress_stats_get(
         &mut self,
         rpc_id: RpcId,
@@ -87,6 +132,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcPeersGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_p2p_diagnostics_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pDiagnosticsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_p2p_connection_outgoing(
         &mut self,
         rpc_id: RpcId,
@@ -212,6 +262,140 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcGetBlockResponse,
     ) -> Result<(), RespondError>;
+    fn respond_ledger_accounts_at_block(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerAccountsAtBlockResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_account_next_nonces(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcAccountNextNoncesGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_transaction_inclusion_proof_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransactionInclusionProofGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_finality_estimate_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcFinalityEstimateGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_node_info_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcNodeInfoGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_internal_commands_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcInternalCommandsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_sync_peer_scores_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSyncPeerScoresGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_transaction_pool_zkapp_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransactionPoolZkappStatsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_profiler(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcProfilerResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_maintenance_mode(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcMaintenanceModeResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_feature_flags(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcFeatureFlagsResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_checkpoints_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcCheckpointsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_transaction_pool_snapshot(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransactionPoolSnapshotResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_vrf_verify(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcVrfVerifyResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_ledger_audit(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerAuditResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_template_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockTemplateGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_template_submit(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockTemplateSubmitResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_account_subscriptions(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcAccountSubscriptionsResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_zkapp_vk_register(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcZkappVkRegisterResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_zkapp_preconditions_evaluate(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcZkappPreconditionsEvaluateResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_payouts_batch_submit(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPayoutsBatchSubmitResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_payouts_batch_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPayoutsBatchGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_chain_import_block(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcChainImportBlockResponse,
+    ) -> Result<(), RespondError>;
+    /// Sends the event to the subscriber, without finishing the request.
+    ///
+    /// Returns [`RespondError::UnknownRpcId`] if the subscriber is gone.
+    fn respond_account_event(
+        &mut self,
+        rpc_id: RpcId,
+        event: RpcWatchedAccountEvent,
+    ) -> Result<(), RespondError>;
+    /// Sends the batch of events to the subscriber, without finishing the
+    /// request.
+    ///
+    /// Returns [`RespondError::UnknownRpcId`] if the subscriber is gone and
+    /// [`RespondError::SubscriberOverflow`] if it was dropped, because it
+    /// didn't keep up with the events.
+    fn respond_snark_pool_job_events(
+        &mut self,
+        rpc_id: RpcId,
+        events: Vec<RpcSnarkPoolJobEvent>,
+    ) -> Result<(), RespondError>;
     fn respond_pooled_user_commands(
         &mut self,
         rpc_id: RpcId,
@@ -237,9 +421,19 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcLedgerStatusGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_ledger_status_extended_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerStatusExtendedGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_ledger_account_delegators_get(
         &mut self,
         rpc_id: RpcId,
         response: RpcLedgerAccountDelegatorsGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_ledger_session(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcLedgerSessionResponse,
+    ) -> Result<(), RespondError>;
 }
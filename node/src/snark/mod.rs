@@ -2,6 +2,7 @@ pub use ::snark::*;
 
 pub mod block_verify;
 pub mod user_command_verify;
+pub mod verify_admission;
 pub mod work_verify;
 
 mod snark_effects;
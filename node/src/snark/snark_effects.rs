@@ -18,5 +18,6 @@ pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMe
         SnarkAction::UserCommandVerifyEffect(a) => {
             a.effects(&meta, store);
         }
+        SnarkAction::VerifyAdmission(_) => {}
     }
 }
@@ -0,0 +1,3 @@
+pub use ::snark::verify_admission::*;
+
+mod snark_verify_admission_actions;
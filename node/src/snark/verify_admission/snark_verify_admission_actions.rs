@@ -0,0 +1,7 @@
+use super::*;
+
+impl From<SnarkVerifyAdmissionAction> for crate::Action {
+    fn from(value: SnarkVerifyAdmissionAction) -> Self {
+        Self::Snark(value.into())
+    }
+}
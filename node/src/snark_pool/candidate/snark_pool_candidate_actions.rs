@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
-use crate::snark::work_verify::SnarkWorkVerifyId;
+use crate::snark::{verify_admission::SnarkVerifySource, work_verify::SnarkWorkVerifyId};
 
 use super::SnarkPoolCandidateState;
 
@@ -46,6 +46,12 @@ pub enum SnarkPoolCandidateAction {
         job_ids: Vec<SnarkJobId>,
         verify_id: SnarkWorkVerifyId,
     },
+    /// Work left out of the verification sample, accepted as is.
+    #[action_event(fields(display(peer_id), batch = batch.len()))]
+    WorkVerifySkipped {
+        peer_id: PeerId,
+        batch: Vec<Snark>,
+    },
     WorkVerifyError {
         peer_id: PeerId,
         verify_id: SnarkWorkVerifyId,
@@ -113,7 +119,11 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolCandidateAction {
                         })
             }
             SnarkPoolCandidateAction::WorkVerifyNext => {
-                state.snark.work_verify.jobs.is_empty()
+                let pending = state.snark.verify_pending(SnarkVerifySource::Work);
+                state
+                    .snark
+                    .verify_admission
+                    .is_within_quota(SnarkVerifySource::Work, pending)
                     && state.transition_frontier.sync.is_synced()
             }
             SnarkPoolCandidateAction::WorkVerifyPending {
@@ -128,6 +138,15 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolCandidateAction {
                             matches!(state, Some(SnarkPoolCandidateState::WorkReceived { .. }))
                         })
             }
+            SnarkPoolCandidateAction::WorkVerifySkipped { peer_id, batch } => {
+                !batch.is_empty()
+                    && batch.iter().all(|work| {
+                        matches!(
+                            state.snark_pool.candidates.get(*peer_id, &work.job_id()),
+                            Some(SnarkPoolCandidateState::WorkReceived { .. })
+                        )
+                    })
+            }
             SnarkPoolCandidateAction::WorkVerifyError { .. } => {
                 // TODO(binier)
                 true
@@ -7,7 +7,12 @@ use p2p::{
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
     BroadcastMessageId, P2pNetworkPubsubAction, PeerId,
 };
-use snark::{work_verify::SnarkWorkVerifyAction, work_verify_effectful::SnarkWorkVerifyId};
+use rand::Rng;
+use snark::{
+    verify_admission::{SnarkVerifyAdmissionAction, SnarkVerifySource},
+    work_verify::SnarkWorkVerifyAction,
+    work_verify_effectful::SnarkWorkVerifyId,
+};
 
 use super::{
     SnarkPoolCandidateAction, SnarkPoolCandidateActionWithMetaRef, SnarkPoolCandidatesState,
@@ -103,10 +108,45 @@ impl SnarkPoolCandidatesState {
                 let batch = global_state
                     .snark_pool
                     .candidates
-                    .get_batch_to_verify(job_ids_ordered_iter);
+                    .get_batch_to_verify(meta.time(), job_ids_ordered_iter);
                 let Some((peer_id, batch)) = batch else {
                     return;
                 };
+                if !global_state
+                    .snark
+                    .is_verify_admitted(SnarkVerifySource::Work)
+                {
+                    // Candidates stay received, they will be verified once
+                    // there is free verification capacity.
+                    dispatcher.push(SnarkVerifyAdmissionAction::Denied {
+                        source: SnarkVerifySource::Work,
+                    });
+                    return;
+                }
+
+                // Work might end up in our blocks, so it's all verified
+                // if we are producing them.
+                let sample_percent = global_state
+                    .snark_pool
+                    .config()
+                    .verify_sample_percent
+                    .filter(|_| !global_state.block_producer.is_enabled());
+                let mut rng = global_state.pseudo_rng("snark_pool_verify_sampling");
+                let (batch, skipped) = global_state.snark_pool.candidates.verify_sampling_split(
+                    &peer_id,
+                    sample_percent,
+                    batch,
+                    |percent| rng.gen_range(0..100) < percent,
+                );
+                if !skipped.is_empty() {
+                    dispatcher.push(SnarkPoolCandidateAction::WorkVerifySkipped {
+                        peer_id,
+                        batch: skipped,
+                    });
+                }
+                if batch.is_empty() {
+                    return;
+                }
 
                 let req_id = global_state.snark.work_verify.next_req_id();
                 let job_ids = batch.iter().map(|v| v.job_id()).collect::<Vec<_>>();
@@ -145,6 +185,20 @@ impl SnarkPoolCandidatesState {
             } => {
                 state.verify_pending(meta.time(), peer_id, *verify_id, job_ids);
             }
+            SnarkPoolCandidateAction::WorkVerifySkipped { peer_id, batch } => {
+                let job_ids = batch.iter().map(|v| v.job_id()).collect::<Vec<_>>();
+                state.verify_skipped(meta.time(), peer_id, &job_ids);
+
+                let dispatcher = state_context.into_dispatcher();
+                for snark in batch {
+                    dispatcher.push(SnarkPoolAction::WorkAdd {
+                        snark: snark.clone(),
+                        sender: *peer_id,
+                        is_sender_local: false,
+                        is_verified: false,
+                    });
+                }
+            }
             SnarkPoolCandidateAction::WorkVerifyError {
                 peer_id,
                 verify_id,
@@ -191,6 +245,7 @@ impl SnarkPoolCandidatesState {
                         snark: snark.clone(),
                         sender: *peer_id,
                         is_sender_local: false,
+                        is_verified: true,
                     });
                 }
             }
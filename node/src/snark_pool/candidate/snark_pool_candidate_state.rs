@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
 
 use openmina_core::snark::{Snark, SnarkInfo, SnarkJobId};
 use redux::Timestamp;
@@ -8,12 +9,47 @@ use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
 use crate::snark::work_verify::SnarkWorkVerifyId;
 
+/// Max number of peers, for which the verify stats are kept.
+const MAX_VERIFY_STATS_PEERS: usize = 1024;
+
+/// How long the work received from the peer waits for the rest of the work
+/// still being fetched from it, e.g. the rest of the snarks received in a
+/// [`openmina_core::snark::SnarkInfoBatch`], so that it's all verified in
+/// a single batch.
+const VERIFY_BATCH_MAX_WAIT: Duration = Duration::from_secs(2);
+
 static EMPTY_PEER_WORK_CANDIDATES: BTreeMap<SnarkJobId, SnarkPoolCandidateState> = BTreeMap::new();
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct SnarkPoolCandidatesState {
     by_peer: BTreeMap<PeerId, BTreeMap<SnarkJobId, SnarkPoolCandidateState>>,
     by_job_id: BTreeMap<SnarkJobId, BTreeSet<PeerId>>,
+    /// Stats of the peers which sent invalid work are kept after the peer
+    /// is removed, so that it stays fully verified after it reconnects.
+    verify_stats: BTreeMap<PeerId, SnarkPoolPeerVerifyStats>,
+}
+
+/// Outcome of the verification of the work received from the peer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct SnarkPoolPeerVerifyStats {
+    /// Number of snarks that were fully verified.
+    pub verified: u64,
+    /// Number of snarks that were accepted without verification.
+    pub skipped: u64,
+    /// Number of snarks in the batches that failed the verification.
+    pub failed: u64,
+}
+
+impl SnarkPoolPeerVerifyStats {
+    /// Share of the verified snarks that were invalid, or rather in the
+    /// invalid batch.
+    pub fn failure_rate(&self) -> f64 {
+        let total = self.verified.saturating_add(self.failed);
+        if total == 0 {
+            return 0.0;
+        }
+        self.failed as f64 / total as f64
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -189,7 +225,14 @@ impl SnarkPoolCandidatesState {
             .insert(job_id, state);
     }
 
-    pub fn get_batch_to_verify<'a, I>(&'a self, job_ids_ordered: I) -> Option<(PeerId, Vec<Snark>)>
+    /// Work received from the peer, to be verified in one batch. The peer
+    /// is skipped while the rest of its work is being fetched, see
+    /// [`VERIFY_BATCH_MAX_WAIT`].
+    pub fn get_batch_to_verify<'a, I>(
+        &'a self,
+        time: Timestamp,
+        job_ids_ordered: I,
+    ) -> Option<(PeerId, Vec<Snark>)>
     where
         I: IntoIterator<Item = &'a SnarkJobId>,
     {
@@ -197,7 +240,9 @@ impl SnarkPoolCandidatesState {
             if let Some(res) = None.or_else(|| {
                 for peer_id in self.by_job_id.get(job_id)? {
                     let peer_jobs = self.by_peer.get(peer_id)?;
-                    if peer_jobs.get(job_id)?.work().is_some() {
+                    if peer_jobs.get(job_id)?.work().is_some()
+                        && is_verify_batch_ready(time, peer_jobs.values())
+                    {
                         let jobs = peer_jobs
                             .iter()
                             .filter_map(|(_, v)| match v {
@@ -241,6 +286,29 @@ impl SnarkPoolCandidatesState {
         }
     }
 
+    /// Marks the work as verified, without actually verifying it.
+    pub fn verify_skipped(&mut self, time: Timestamp, peer_id: &PeerId, job_ids: &[SnarkJobId]) {
+        if !self.by_peer.contains_key(peer_id) {
+            return;
+        }
+        let stats = verify_stats_entry(&mut self.verify_stats, &self.by_peer, peer_id);
+        let Some(peer_jobs) = self.by_peer.get_mut(peer_id) else {
+            return;
+        };
+
+        for job_id in job_ids {
+            if let Some(job_state) = peer_jobs.get_mut(job_id) {
+                if let SnarkPoolCandidateState::WorkReceived { work, .. } = job_state {
+                    *job_state = SnarkPoolCandidateState::WorkVerifySuccess {
+                        time,
+                        work: work.clone(),
+                    };
+                    stats.skipped = stats.skipped.saturating_add(1);
+                }
+            }
+        }
+    }
+
     pub fn verify_result(
         &mut self,
         time: Timestamp,
@@ -248,6 +316,7 @@ impl SnarkPoolCandidatesState {
         verify_id: SnarkWorkVerifyId,
         result: Result<(), ()>,
     ) {
+        let stats = verify_stats_entry(&mut self.verify_stats, &self.by_peer, peer_id);
         if let Some(peer_jobs) = self.by_peer.get_mut(peer_id) {
             for (_, job_state) in peer_jobs
                 .iter_mut()
@@ -258,12 +327,14 @@ impl SnarkPoolCandidatesState {
                 };
                 match result {
                     Ok(_) => {
+                        stats.verified = stats.verified.saturating_add(1);
                         *job_state = SnarkPoolCandidateState::WorkVerifySuccess {
                             time,
                             work: work.clone(),
                         };
                     }
                     Err(_) => {
+                        stats.failed = stats.failed.saturating_add(1);
                         *job_state = SnarkPoolCandidateState::WorkVerifyError {
                             time,
                             work: work.clone(),
@@ -274,7 +345,43 @@ impl SnarkPoolCandidatesState {
         }
     }
 
+    pub fn peer_verify_stats(&self, peer_id: &PeerId) -> SnarkPoolPeerVerifyStats {
+        self.verify_stats.get(peer_id).copied().unwrap_or_default()
+    }
+
+    pub fn peers_verify_stats_iter(
+        &self,
+    ) -> impl Iterator<Item = (&PeerId, &SnarkPoolPeerVerifyStats)> {
+        self.verify_stats.iter()
+    }
+
+    /// Splits the batch into the work that must be verified and the work
+    /// that is accepted without verification, based on the sampling config.
+    ///
+    /// `sample` decides whether the work is a part of the sample.
+    pub fn verify_sampling_split<T, F>(
+        &self,
+        peer_id: &PeerId,
+        sample_percent: Option<u8>,
+        batch: Vec<T>,
+        mut sample: F,
+    ) -> (Vec<T>, Vec<T>)
+    where
+        F: FnMut(u8) -> bool,
+    {
+        let Some(percent) = sample_percent else {
+            return (batch, vec![]);
+        };
+        if self.peer_verify_stats(peer_id).failed > 0 {
+            return (batch, vec![]);
+        }
+        batch.into_iter().partition(|_| sample(percent))
+    }
+
     pub fn peer_remove(&mut self, peer_id: PeerId) {
+        if self.peer_verify_stats(&peer_id).failed == 0 {
+            self.verify_stats.remove(&peer_id);
+        }
         if let Some(works) = self.by_peer.remove(&peer_id) {
             for job_id in works.into_keys() {
                 if let Some(peers) = self.by_job_id.get_mut(&job_id) {
@@ -393,3 +500,178 @@ impl<'a> From<&'a SnarkPoolCandidateState> for openmina_core::snark::SnarkCmp<'a
         }
     }
 }
+
+/// Whether the work received from the peer can be verified now: either
+/// nothing else is being fetched from the peer, or the oldest received work
+/// waited long enough for it.
+fn is_verify_batch_ready<'a, I>(time: Timestamp, peer_jobs: I) -> bool
+where
+    I: IntoIterator<Item = &'a SnarkPoolCandidateState>,
+{
+    let mut fetching = false;
+    let mut oldest_received = None::<Timestamp>;
+    for job in peer_jobs {
+        match job {
+            SnarkPoolCandidateState::InfoReceived { .. }
+            | SnarkPoolCandidateState::WorkFetchPending { .. } => fetching = true,
+            SnarkPoolCandidateState::WorkReceived { time, .. } => {
+                oldest_received = Some(oldest_received.map_or(*time, |t| t.min(*time)));
+            }
+            _ => {}
+        }
+    }
+    !fetching
+        || oldest_received.is_some_and(|received| {
+            time.checked_sub(received)
+                .is_some_and(|waited| waited >= VERIFY_BATCH_MAX_WAIT)
+        })
+}
+
+/// Returns the stats of the peer, evicting stats of the disconnected peer
+/// with the least failures if there are too many of them.
+fn verify_stats_entry<'a, T>(
+    verify_stats: &'a mut BTreeMap<PeerId, SnarkPoolPeerVerifyStats>,
+    by_peer: &BTreeMap<PeerId, T>,
+    peer_id: &PeerId,
+) -> &'a mut SnarkPoolPeerVerifyStats {
+    if !verify_stats.contains_key(peer_id) && verify_stats.len() >= MAX_VERIFY_STATS_PEERS {
+        let evict = verify_stats
+            .iter()
+            .filter(|(id, _)| !by_peer.contains_key(id))
+            .min_by_key(|(_, stats)| stats.failed)
+            .map(|(id, _)| *id);
+        if let Some(id) = evict {
+            verify_stats.remove(&id);
+        }
+    }
+    verify_stats.entry(*peer_id).or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_sampling_split() {
+        let mut state = SnarkPoolCandidatesState::default();
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let batch = (0..10).collect::<Vec<u32>>();
+
+        // Sampling disabled, everything is verified.
+        let (verify, skip) = state.verify_sampling_split(&peer_id, None, batch.clone(), |_| false);
+        assert_eq!((verify.len(), skip.len()), (10, 0));
+
+        // Only sampled work is verified.
+        let mut picks = [false, false, true].into_iter().cycle();
+        let (verify, skip) = state.verify_sampling_split(&peer_id, Some(30), batch.clone(), |p| {
+            assert_eq!(p, 30);
+            picks.next().unwrap()
+        });
+        assert_eq!(verify, vec![2, 5, 8]);
+        assert_eq!(skip.len(), 7);
+
+        // Peer with a failure is always fully verified.
+        state.verify_result(
+            Timestamp::ZERO,
+            &peer_id,
+            SnarkWorkVerifyId::new_unchecked(0, 1),
+            Err(()),
+        );
+        let (verify, skip) = state.verify_sampling_split(&peer_id, Some(0), batch, |_| false);
+        assert_eq!((verify.len(), skip.len()), (10, 0));
+        assert_eq!(
+            state
+                .verify_sampling_split(&PeerId::from_bytes([2; 32]), Some(0), vec![1], |_| false)
+                .1,
+            vec![1]
+        );
+    }
+
+    fn snark() -> Snark {
+        use mina_p2p_messages::{binprot::BinProtRead, v2};
+
+        // Zeroed statement, the proof isn't verified.
+        let statement =
+            v2::MinaStateSnarkedLedgerStateWithSokStableV2::binprot_read(&mut [0; 1024].as_slice())
+                .unwrap();
+        let proof = v2::LedgerProofProdStableV2(v2::TransactionSnarkStableV2 {
+            statement,
+            proof: (*openmina_core::dummy::dummy_transaction_proof()).clone(),
+        });
+        Snark {
+            snarker: "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg"
+                .parse()
+                .unwrap(),
+            fee: v2::CurrencyFeeStableV1(10u64.into()),
+            proofs: v2::TransactionSnarkWorkTStableV2Proofs::One(proof).into(),
+        }
+    }
+
+    #[test]
+    fn verify_batch_waits_for_fetching() {
+        let secs = |secs: u64| Timestamp::new(secs * 1_000_000_000);
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let work = snark();
+        let job_id = work.job_id();
+        let other = SnarkInfo {
+            job_id: "jw9nPCs68UNaKaLZwV6QzdswKWomwQxvTgrpmKWmnFJyswnrn4N_jwhHYWzvJG8esmqtYXbUZy3UGbLSjhKvn1FSxBGL1JDFHqbHMJc-jwiLuRrEqNgASgXEqibGs4VqKwSwiuFEtuPD53v8hiTtVuLfmTr_jwhHYWzvJG8esmqtYXbUZy3UGbLSjhKvn1FSxBGL1JDFHqbHMJc"
+                .parse()
+                .unwrap(),
+            ..work.info()
+        };
+        assert_ne!(other.job_id, job_id);
+
+        // Both infos received in a batch, one work fetched.
+        let mut state = SnarkPoolCandidatesState::default();
+        state.info_received(secs(0), peer_id, work.info());
+        state.info_received(secs(0), peer_id, other.clone());
+        assert!(state.get_batch_to_verify(secs(0), [&job_id]).is_none());
+        state.work_received(secs(1), peer_id, work.clone());
+
+        // Waits for the rest of the batch, but not indefinitely.
+        assert!(state.get_batch_to_verify(secs(2), [&job_id]).is_none());
+        let (peer, batch) = state.get_batch_to_verify(secs(3), [&job_id]).unwrap();
+        assert_eq!((peer, batch.len()), (peer_id, 1));
+
+        // Verified at once when nothing else is being fetched.
+        state.peer_work_remove(peer_id, &other.job_id);
+        let (peer, batch) = state.get_batch_to_verify(secs(1), [&job_id]).unwrap();
+        assert_eq!((peer, batch.len()), (peer_id, 1));
+    }
+
+    #[test]
+    fn verify_stats_bounded() {
+        let mut state = SnarkPoolCandidatesState::default();
+        let peer_id = |i: usize| {
+            let mut bytes = [0; 32];
+            for (b, v) in bytes.iter_mut().zip(i.to_be_bytes()) {
+                *b = v;
+            }
+            PeerId::from_bytes(bytes)
+        };
+        let worst = peer_id(0);
+        state.verify_result(
+            Timestamp::ZERO,
+            &worst,
+            SnarkWorkVerifyId::new_unchecked(0, 1),
+            Err(()),
+        );
+        state.verify_result(
+            Timestamp::ZERO,
+            &worst,
+            SnarkWorkVerifyId::new_unchecked(0, 1),
+            Err(()),
+        );
+        for i in 1..=MAX_VERIFY_STATS_PEERS {
+            state.verify_result(
+                Timestamp::ZERO,
+                &peer_id(i),
+                SnarkWorkVerifyId::new_unchecked(0, 1),
+                Err(()),
+            );
+        }
+        assert_eq!(state.verify_stats.len(), MAX_VERIFY_STATS_PEERS);
+        // Peer with the most failures is kept.
+        assert_eq!(state.peer_verify_stats(&worst).failed, 2);
+    }
+}
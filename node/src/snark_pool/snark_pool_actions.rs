@@ -33,11 +33,13 @@ pub enum SnarkPoolAction {
         commitment: SnarkJobCommitment,
         sender: PeerId,
     },
-    #[action_event(level = info, fields(is_sender_local))]
+    #[action_event(level = info, fields(is_sender_local, is_verified))]
     WorkAdd {
         snark: Snark,
         sender: PeerId,
         is_sender_local: bool,
+        /// Whether the work was verified, or accepted by the sampling.
+        is_verified: bool,
     },
     #[action_event(level = trace)]
     P2pSendAll,
@@ -56,11 +58,16 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
         match self {
             SnarkPoolAction::Candidate(action) => action.is_enabled(state, time),
             SnarkPoolAction::AutoCreateCommitment => {
-                state.config.snarker.as_ref().is_some_and(|v| v.auto_commit)
+                state.config.profile.allows_snark_work_selection()
+                    && state.config.snarker.as_ref().is_some_and(|v| v.auto_commit)
+            }
+            SnarkPoolAction::CommitmentCreateMany { .. } => {
+                state.config.profile.allows_snark_work_selection() && state.config.snarker.is_some()
             }
-            SnarkPoolAction::CommitmentCreateMany { .. } => state.config.snarker.is_some(),
             SnarkPoolAction::CommitmentCreate { job_id } => {
-                state.config.snarker.is_some() && state.snark_pool.should_create_commitment(job_id)
+                state.config.profile.allows_snark_work_selection()
+                    && state.config.snarker.is_some()
+                    && state.snark_pool.should_create_commitment(job_id)
             }
             SnarkPoolAction::CommitmentAdd { commitment, .. } => state
                 .snark_pool
@@ -1,4 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct SnarkPoolConfig {}
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SnarkPoolConfig {
+    /// Percentage of the snark work received from the peers that is
+    /// fully verified, for low-power nodes. The rest is added to the pool
+    /// without verification. `None` means that all the work is verified.
+    ///
+    /// Ignored if the node produces blocks, and for peers which have sent
+    /// invalid work before.
+    #[serde(default)]
+    pub verify_sample_percent: Option<u8>,
+}
@@ -1,9 +1,16 @@
 use std::collections::BTreeMap;
 
-use crate::{snark_pool::JobCommitment, ExternalSnarkWorkerAction, SnarkerStrategy};
+use crate::{
+    rpc::{RpcAction, RpcSnarkPoolJobEvent},
+    snark_pool::JobCommitment,
+    ExternalSnarkWorkerAction, SnarkerStrategy,
+};
 use openmina_core::snark::{SnarkJobCommitment, SnarkJobId};
-use p2p::channels::{
-    snark::P2pChannelsSnarkAction, snark_job_commitment::P2pChannelsSnarkJobCommitmentAction,
+use p2p::{
+    channels::{
+        snark::P2pChannelsSnarkAction, snark_job_commitment::P2pChannelsSnarkJobCommitmentAction,
+    },
+    BroadcastMessageId, P2pNetworkPubsubAction,
 };
 
 use super::{
@@ -39,7 +46,13 @@ impl SnarkPoolState {
                     .map(|(index, job)| (SnarkJobId::from(job), (index, job.clone())))
                     .collect::<BTreeMap<_, _>>();
 
+                let removed_jobs = state
+                    .jobs_iter()
+                    .filter(|job| !jobs_map.contains_key(&job.id))
+                    .map(|job| job.id.clone())
+                    .collect::<Vec<_>>();
                 state.retain(|id| jobs_map.remove(id).map(|(order, _)| order));
+                let added_jobs = jobs_map.keys().cloned().collect::<Vec<_>>();
                 for (id, (order, job)) in jobs_map {
                     state.insert(JobState {
                         time: meta.time(),
@@ -77,6 +90,24 @@ impl SnarkPoolState {
                 } else {
                     dispatcher.push(SnarkPoolAction::AutoCreateCommitment);
                 }
+
+                let block_hash = global_state
+                    .transition_frontier
+                    .best_tip()
+                    .map(|block| block.hash().clone());
+                let events = removed_jobs
+                    .into_iter()
+                    .map(|job_id| RpcSnarkPoolJobEvent::IncludedInBlock {
+                        job_id,
+                        block_hash: block_hash.clone(),
+                    })
+                    .chain(
+                        added_jobs
+                            .into_iter()
+                            .map(|job_id| RpcSnarkPoolJobEvent::Added { job_id }),
+                    )
+                    .collect();
+                dispatcher.push(RpcAction::SnarkPoolJobEventsPublish { events });
             }
             SnarkPoolAction::AutoCreateCommitment => {
                 let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
@@ -159,6 +190,13 @@ impl SnarkPoolState {
                 // Dispatch
                 let commitment = commitment.clone();
                 let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                dispatcher.push(RpcAction::SnarkPoolJobEventsPublish {
+                    events: vec![RpcSnarkPoolJobEvent::Committed {
+                        job_id: commitment.job_id.clone(),
+                        snarker: commitment.snarker.clone(),
+                        fee: commitment.fee.clone(),
+                    }],
+                });
                 if let Some(job_id) = global_state.external_snark_worker.working_job_id() {
                     let Some(config) = global_state.config.snarker.as_ref() else {
                         return;
@@ -174,17 +212,37 @@ impl SnarkPoolState {
                 snark,
                 sender,
                 is_sender_local,
+                is_verified,
             } => {
-                state.set_snark_work(SnarkWork {
+                let previous = state.set_snark_work(SnarkWork {
                     work: snark.clone(),
                     received_t: meta.time(),
                     sender: *sender,
+                    is_verified: *is_verified,
                 });
                 state.candidates.remove_inferior_snarks(snark);
 
                 // Dispatch
                 let snark = snark.clone();
                 let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let job_id = snark.job_id();
+                let (snarker, fee) = (snark.snarker.clone(), snark.fee.clone());
+                let event = match previous {
+                    None => RpcSnarkPoolJobEvent::WorkReceived {
+                        job_id,
+                        snarker,
+                        fee,
+                    },
+                    Some(previous) => RpcSnarkPoolJobEvent::PriceUpdated {
+                        job_id,
+                        snarker,
+                        fee,
+                        previous_fee: previous.work.fee,
+                    },
+                };
+                dispatcher.push(RpcAction::SnarkPoolJobEventsPublish {
+                    events: vec![event],
+                });
                 if let Some(job_id) = global_state
                     .external_snark_worker
                     .working_job_id()
@@ -201,6 +259,16 @@ impl SnarkPoolState {
                     }
                 }
 
+                if !is_verified {
+                    // Don't relay the gossip message of the unverified work.
+                    dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                        message_id: Some(BroadcastMessageId::Snark {
+                            job_id: snark.job_id(),
+                        }),
+                        reason: "Snark work not verified".to_owned(),
+                    });
+                    return;
+                }
                 dispatcher.push(P2pChannelsSnarkAction::Libp2pBroadcast {
                     snark: snark.clone(),
                     nonce: 0,
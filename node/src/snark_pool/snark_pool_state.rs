@@ -43,6 +43,9 @@ pub struct SnarkWork {
     pub work: Snark,
     pub received_t: Timestamp,
     pub sender: PeerId,
+    /// Work accepted without verification by the sampling isn't
+    /// propagated to other peers.
+    pub is_verified: bool,
 }
 
 /// Whether the job is a merge proof job, or a transaction proof job, with particular number of account updates.
@@ -54,20 +57,24 @@ pub enum JobSummary {
 
 impl Default for SnarkPoolState {
     fn default() -> Self {
-        Self::new()
+        Self::new(SnarkPoolConfig::default())
     }
 }
 
 impl SnarkPoolState {
-    pub fn new() -> Self {
+    pub fn new(config: SnarkPoolConfig) -> Self {
         Self {
-            config: SnarkPoolConfig {},
+            config,
             pool: Default::default(),
             candidates: SnarkPoolCandidatesState::new(),
             last_check_timeouts: Timestamp::ZERO,
         }
     }
 
+    pub fn config(&self) -> &SnarkPoolConfig {
+        &self.config
+    }
+
     pub fn is_empty(&self) -> bool {
         self.pool.is_empty()
     }
@@ -174,6 +181,14 @@ impl SnarkPoolState {
             .map(|snark| &snark.work)
     }
 
+    /// Completed snarks, excluding the ones accepted by the verify sampling.
+    pub fn verified_snarks_iter(&self) -> impl '_ + Iterator<Item = &'_ Snark> {
+        self.jobs_iter()
+            .filter_map(|job| job.snark.as_ref())
+            .filter(|snark| snark.is_verified)
+            .map(|snark| &snark.work)
+    }
+
     pub(super) fn job_summary(&self, id: &SnarkJobId) -> Option<JobSummary> {
         self.get(id).map(|job| job.summary())
     }
@@ -249,7 +264,10 @@ impl JobState {
     }
 
     pub fn snark_msg(&self) -> Option<SnarkInfo> {
-        self.snark.as_ref().map(|v| v.work.info())
+        self.snark
+            .as_ref()
+            .filter(|v| v.is_verified)
+            .map(|v| v.work.info())
     }
 
     pub fn summary(&self) -> JobSummary {
@@ -5,9 +5,9 @@ use malloc_size_of_derive::MallocSizeOf;
 use mina_p2p_messages::v2;
 use openmina_core::block::prevalidate::{prevalidate_block, BlockPrevalidationError};
 use openmina_core::consensus::ConsensusTime;
-use openmina_core::transaction::{TransactionInfo, TransactionWithHash};
+use openmina_core::rng::AuditedRng;
+use openmina_core::transaction::{TransactionHash, TransactionInfo, TransactionWithHash};
 use p2p::P2pNetworkPubsubMessageCacheId;
-use rand::prelude::*;
 
 use openmina_core::block::BlockWithHash;
 use openmina_core::requests::RpcId;
@@ -16,23 +16,30 @@ use openmina_core::{
     block::ArcBlockWithHash, consensus::ConsensusConstants, constants::constraint_constants, error,
     snark::SnarkJobCommitment, ChainId,
 };
+use p2p::channels::checkpoint::SignedCheckpoint;
 use p2p::channels::rpc::{P2pRpcId, P2pRpcRequest, P2pRpcResponse};
 use p2p::channels::streaming_rpc::P2pStreamingRpcResponseFull;
 use p2p::connection::outgoing::P2pConnectionOutgoingError;
 use p2p::connection::P2pConnectionResponse;
 use p2p::{
-    bootstrap::P2pNetworkKadBootstrapState, network::identify::P2pNetworkIdentifyState,
-    P2pCallbacks, P2pConfig, P2pNetworkSchedulerState, P2pPeerState, P2pPeerStatusReady, PeerId,
+    bootstrap::P2pNetworkKadBootstrapState, feature_flags::P2pFeatureFlags,
+    network::identify::P2pNetworkIdentifyState, P2pCallbacks, P2pConfig, P2pNetworkSchedulerState,
+    P2pPeerState, P2pPeerStatusReady, PeerId,
 };
 use redux::{ActionMeta, EnablingCondition, Timestamp};
 use serde::{Deserialize, Serialize};
 use snark::block_verify::SnarkBlockVerifyState;
 use snark::user_command_verify::SnarkUserCommandVerifyState;
+use snark::verify_admission::SnarkVerifyAdmissionState;
 use snark::work_verify::SnarkWorkVerifyState;
 
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorState;
 pub use crate::block_producer::BlockProducerState;
+use crate::checkpoint::CheckpointAction;
+pub use crate::checkpoint::CheckpointState;
+pub use crate::event_source::EventSourceState;
 use crate::external_snark_worker::{ExternalSnarkWorker, ExternalSnarkWorkers};
+use crate::ledger::audit::LedgerAuditState;
 use crate::ledger::read::LedgerReadState;
 use crate::ledger::write::LedgerWriteState;
 pub use crate::ledger::LedgerState;
@@ -56,6 +63,7 @@ use crate::transition_frontier::sync::ledger::TransitionFrontierSyncLedgerState;
 use crate::transition_frontier::sync::TransitionFrontierSyncState;
 pub use crate::transition_frontier::TransitionFrontierState;
 pub use crate::watched_accounts::WatchedAccountsState;
+pub use crate::watchtower::WatchtowerState;
 pub use crate::Config;
 use crate::{config::GlobalConfig, SnarkPoolAction};
 use crate::{ActionWithMeta, RpcAction};
@@ -73,8 +81,14 @@ pub struct State {
     pub transaction_pool: TransactionPoolState,
     pub block_producer: BlockProducerState,
     pub rpc: RpcState,
+    #[serde(default)]
+    pub event_source: EventSourceState,
 
     pub watched_accounts: WatchedAccountsState,
+    #[serde(default)]
+    pub watchtower: WatchtowerState,
+    #[serde(default)]
+    pub checkpoint: CheckpointState,
 
     // TODO(binier): include action kind in `last_action`.
     last_action: ActionMeta,
@@ -87,6 +101,7 @@ use openmina_core::{bug_condition, impl_substate_access, SubstateAccess};
 impl_substate_access!(State, SnarkState, snark);
 impl_substate_access!(State, SnarkBlockVerifyState, snark.block_verify);
 impl_substate_access!(State, SnarkWorkVerifyState, snark.work_verify);
+impl_substate_access!(State, SnarkVerifyAdmissionState, snark.verify_admission);
 impl_substate_access!(
     State,
     SnarkUserCommandVerifyState,
@@ -115,10 +130,12 @@ impl_substate_access!(State, SnarkPoolCandidatesState, snark_pool.candidates);
 impl_substate_access!(State, ExternalSnarkWorkers, external_snark_worker);
 impl_substate_access!(State, BlockProducerState, block_producer);
 impl_substate_access!(State, RpcState, rpc);
+impl_substate_access!(State, EventSourceState, event_source);
 impl_substate_access!(State, WatchedAccountsState, watched_accounts);
 impl_substate_access!(State, ExternalSnarkWorker, external_snark_worker.0);
 impl_substate_access!(State, LedgerState, ledger);
 impl_substate_access!(State, LedgerReadState, ledger.read);
+impl_substate_access!(State, LedgerAuditState, ledger.audit);
 impl_substate_access!(State, LedgerWriteState, ledger.write);
 
 impl openmina_core::SubstateAccess<P2pState> for State {
@@ -267,18 +284,26 @@ impl State {
         Self {
             p2p: P2p::Pending(config.p2p),
             ledger: LedgerState::new(config.ledger),
-            snark_pool: SnarkPoolState::new(),
+            snark_pool: SnarkPoolState::new(config.snark_pool),
             snark: SnarkState::new(config.snark),
             transition_frontier: TransitionFrontierState::new(
                 config.transition_frontier,
                 config.archive.is_some(),
+                config.global.profile.is_light(),
             ),
             external_snark_worker: ExternalSnarkWorkers::new(now),
             block_producer: BlockProducerState::new(now, config.block_producer),
             rpc: RpcState::new(),
-            transaction_pool: TransactionPoolState::new(config.tx_pool, constants),
+            event_source: Default::default(),
+            transaction_pool: TransactionPoolState::new(
+                config.tx_pool,
+                config.fee_sponsor,
+                constants,
+            ),
 
             watched_accounts: WatchedAccountsState::new(),
+            watchtower: WatchtowerState::new(config.watchtower),
+            checkpoint: CheckpointState::new(config.checkpoint),
 
             config: config.global,
             last_action: ActionMeta::zero_custom(now),
@@ -290,6 +315,10 @@ impl State {
         &self.last_action
     }
 
+    pub fn applied_actions_count(&self) -> u64 {
+        self.applied_actions_count
+    }
+
     /// Latest time observed by the state machine.
     ///
     /// Only updated when action is dispatched and reducer is executed.
@@ -298,8 +327,9 @@ impl State {
         self.last_action.time()
     }
 
-    pub fn pseudo_rng(&self) -> StdRng {
-        crate::core::pseudo_rng(self.time())
+    /// See [`crate::core::rng`].
+    pub fn pseudo_rng(&self, purpose: &'static str) -> AuditedRng {
+        crate::core::pseudo_rng(self.time(), purpose)
     }
 
     /// Must be called in the global reducer as the last thing only once
@@ -486,6 +516,14 @@ impl P2p {
         }
     }
 
+    /// Current feature flags, the initial ones if p2p isn't initialized yet.
+    pub fn feature_flags(&self) -> &P2pFeatureFlags {
+        match self {
+            P2p::Pending(config) => &config.feature_flags,
+            P2p::Ready(p2p_state) => &p2p_state.feature_flags,
+        }
+    }
+
     // TODO: add chain id
     pub fn initialize(&mut self, chain_id: &ChainId) -> Result<(), P2pInitializationError> {
         let P2p::Pending(config) = self else {
@@ -507,6 +545,11 @@ impl P2p {
                     }
                 }
             )),
+            on_p2p_channels_transaction_announced: Some(redux::callback!(
+                on_p2p_channels_transaction_announced((peer_id: PeerId, hashes: Vec<TransactionHash>)) -> crate::Action {
+                    TransactionPoolCandidateAction::DigestsReceived { peer_id, hashes }
+                }
+            )),
             on_p2p_channels_transactions_libp2p_received: Some(redux::callback!(
                 on_p2p_channels_transactions_libp2p_received((peer_id: PeerId, transactions: Vec<TransactionWithHash>, message_id: P2pNetworkPubsubMessageCacheId)) -> crate::Action {
                     TransactionPoolCandidateAction::Libp2pTransactionsReceived {
@@ -521,6 +564,11 @@ impl P2p {
                     SnarkPoolAction::CommitmentAdd { commitment: *commitment, sender: peer_id }
                 }
             )),
+            on_p2p_channels_checkpoint_received: Some(redux::callback!(
+                on_p2p_channels_checkpoint_received((peer_id: PeerId, checkpoint: Box<SignedCheckpoint>)) -> crate::Action {
+                    CheckpointAction::Received { sender: peer_id, checkpoint }
+                }
+            )),
             on_p2p_channels_snark_received: Some(redux::callback!(
                 on_p2p_channels_snark_received((peer_id: PeerId, snark: Box<SnarkInfo>)) -> crate::Action {
                     SnarkPoolCandidateAction::InfoReceived { peer_id, info: *snark }
@@ -541,6 +589,16 @@ impl P2p {
                     P2pCallbacksAction::RpcRespondBestTip { peer_id }
                 }
             )),
+            on_p2p_channels_best_tip_compact_received: Some(redux::callback!(
+                on_p2p_channels_best_tip_compact_received(peer_id: PeerId) -> crate::Action {
+                    P2pCallbacksAction::P2pChannelsBestTipCompactReceived { peer_id }
+                }
+            )),
+            on_p2p_channels_best_tip_compact_reconstructed: Some(redux::callback!(
+                on_p2p_channels_best_tip_compact_reconstructed(peer_id: PeerId) -> crate::Action {
+                    P2pCallbacksAction::P2pChannelsBestTipCompactReconstructed { peer_id }
+                }
+            )),
             on_p2p_disconnection_finish: Some(redux::callback!(
                 on_p2p_disconnection_finish(peer_id: PeerId) -> crate::Action {
                     P2pCallbacksAction::P2pDisconnection { peer_id }
@@ -582,8 +640,8 @@ impl P2p {
                 }
             )),
             on_p2p_channels_rpc_timeout: Some(redux::callback!(
-                on_p2p_channels_rpc_timeout((peer_id: PeerId, id: P2pRpcId)) -> crate::Action {
-                    P2pCallbacksAction::P2pChannelsRpcTimeout { peer_id, id }
+                on_p2p_channels_rpc_timeout((peer_id: PeerId, id: P2pRpcId, request: Box<P2pRpcRequest>)) -> crate::Action {
+                    P2pCallbacksAction::P2pChannelsRpcTimeout { peer_id, id, request }
                 }
             )),
             on_p2p_channels_rpc_response_received: Some(redux::callback!(
@@ -0,0 +1,296 @@
+//! Deterministic hash of the whole [`State`].
+//!
+//! Nodes which applied the same actions, e.g. replaying the same
+//! recording or running the same testing scenario, must end up with the
+//! same digest, so comparing digests is a cheap way to find where they
+//! diverged.
+//!
+//! Digest is the Blake2b hash of the JSON serialized state, with the
+//! following left out:
+//! - `config.build`, which depends on the binary the node was built as.
+//! - Verifier indices and SRS, which don't serialize deterministically
+//!   and are derived from the constants anyway.
+//! - `transaction_pool.pool`, which is backed by hash maps, so its
+//!   serialization order differs between the processes. Pool contents
+//!   are still covered by the distributed pool next to it.
+
+use std::{fmt, io, str::FromStr};
+
+use blake2::digest::{Update, VariableOutput};
+use redux::{ActionMeta, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block_producer::BlockProducerState, checkpoint::CheckpointState,
+    event_source::EventSourceState, external_snark_worker::ExternalSnarkWorkers,
+    ledger::LedgerState, rpc::RpcState, snark::SnarkState, snark_pool::SnarkPoolState,
+    transition_frontier::TransitionFrontierState, watched_accounts::WatchedAccountsState,
+    watchtower::WatchtowerState, P2p, State,
+};
+
+/// Digest is logged each time this many actions were applied.
+pub const STATE_DIGEST_LOG_INTERVAL: u64 = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StateDigest([u8; 32]);
+
+/// Digest of the state after `applied_actions_count` actions.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StateDigestSnapshot {
+    pub applied_actions_count: u64,
+    pub time: Timestamp,
+    pub digest: StateDigest,
+}
+
+impl State {
+    pub fn digest(&self) -> serde_json::Result<StateDigest> {
+        let mut config = serde_json::to_value(&self.config)?;
+        if let Some(config) = config.as_object_mut() {
+            config.remove("build");
+        }
+        let mut transaction_pool = serde_json::to_value(&self.transaction_pool)?;
+        if let Some(transaction_pool) = transaction_pool.as_object_mut() {
+            transaction_pool.remove("pool");
+        }
+        let state = DigestedState {
+            config,
+            p2p: &self.p2p,
+            ledger: &self.ledger,
+            snark: DigestedSnarkState::new(&self.snark)?,
+            transition_frontier: &self.transition_frontier,
+            snark_pool: &self.snark_pool,
+            external_snark_worker: &self.external_snark_worker,
+            transaction_pool,
+            block_producer: &self.block_producer,
+            rpc: &self.rpc,
+            event_source: &self.event_source,
+            watched_accounts: &self.watched_accounts,
+            watchtower: &self.watchtower,
+            checkpoint: &self.checkpoint,
+            last_action: self.last_action(),
+            applied_actions_count: self.applied_actions_count(),
+        };
+
+        let mut hasher =
+            HashWriter(blake2::Blake2bVar::new(32).expect("Invalid Blake2bVar output size"));
+        serde_json::to_writer(&mut hasher, &state)?;
+        let mut digest = [0; 32];
+        hasher
+            .0
+            .finalize_variable(&mut digest)
+            .expect("Invalid Blake2bVar output size");
+        Ok(StateDigest(digest))
+    }
+
+    pub fn digest_snapshot(&self) -> serde_json::Result<StateDigestSnapshot> {
+        Ok(StateDigestSnapshot {
+            applied_actions_count: self.applied_actions_count(),
+            time: self.time(),
+            digest: self.digest()?,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct DigestedState<'a> {
+    config: serde_json::Value,
+    p2p: &'a P2p,
+    ledger: &'a LedgerState,
+    snark: DigestedSnarkState,
+    transition_frontier: &'a TransitionFrontierState,
+    snark_pool: &'a SnarkPoolState,
+    external_snark_worker: &'a ExternalSnarkWorkers,
+    transaction_pool: serde_json::Value,
+    block_producer: &'a BlockProducerState,
+    rpc: &'a RpcState,
+    event_source: &'a EventSourceState,
+    watched_accounts: &'a WatchedAccountsState,
+    watchtower: &'a WatchtowerState,
+    checkpoint: &'a CheckpointState,
+    last_action: &'a ActionMeta,
+    applied_actions_count: u64,
+}
+
+/// Snark state without the verifier indices and SRS.
+#[derive(Serialize)]
+struct DigestedSnarkState {
+    block_verify_jobs: serde_json::Value,
+    block_verify_cancelled_count: u64,
+    work_verify_jobs: serde_json::Value,
+    user_command_verify_jobs: serde_json::Value,
+}
+
+impl DigestedSnarkState {
+    fn new(snark: &SnarkState) -> serde_json::Result<Self> {
+        Ok(Self {
+            block_verify_jobs: serde_json::to_value(&snark.block_verify.jobs)?,
+            block_verify_cancelled_count: snark.block_verify.cancelled_count,
+            work_verify_jobs: serde_json::to_value(&snark.work_verify.jobs)?,
+            user_command_verify_jobs: serde_json::to_value(&snark.user_command_verify.jobs)?,
+        })
+    }
+}
+
+struct HashWriter(blake2::Blake2bVar);
+
+impl io::Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Display for StateDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl FromStr for StateDigest {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut digest = [0; 32];
+        hex::decode_to_slice(s, &mut digest)?;
+        Ok(Self(digest))
+    }
+}
+
+impl Serialize for StateDigest {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for StateDigest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openmina_core::{consensus::ConsensusConstants, constants::constraint_constants};
+
+    use super::*;
+    use crate::{
+        p2p::{channels::ChannelId, identity::SecretKey as P2pSecretKey},
+        snark::{get_srs, BlockVerifier, TransactionVerifier},
+        BuildEnv, Config, GlobalConfig, LedgerConfig, P2pConfig, SnarkConfig,
+        TransitionFrontierConfig, DEVNET_CONFIG,
+    };
+
+    fn state() -> State {
+        let protocol_constants = DEVNET_CONFIG.protocol_constants().unwrap();
+        let consensus_consts =
+            ConsensusConstants::create(constraint_constants(), &protocol_constants);
+        let srs = get_srs();
+        let config = Config {
+            ledger: LedgerConfig::default(),
+            snark: SnarkConfig {
+                block_verifier_index: BlockVerifier::make(),
+                block_verifier_srs: srs.clone(),
+                work_verifier_index: TransactionVerifier::make(),
+                work_verifier_srs: srs,
+                verify_admission: Default::default(),
+            },
+            snark_pool: Default::default(),
+            global: GlobalConfig {
+                build: BuildEnv::get().into(),
+                snarker: None,
+                consensus_constants: consensus_consts.clone(),
+                client_port: None,
+                testing_run: true,
+                profile: Default::default(),
+                payout_pub_key: None,
+                rpc_response_limits: Default::default(),
+                sibling_nodes: Vec::new(),
+            },
+            p2p: P2pConfig {
+                libp2p_port: None,
+                listen_port: None,
+                identity_pub_key: P2pSecretKey::deterministic(0).public_key(),
+                webrtc_identity_pub_key: None,
+                initial_peers: Vec::new(),
+                preferred_peers: Vec::new(),
+                external_addrs: Vec::new(),
+                listen_family: Default::default(),
+                dial_family: None,
+                enabled_channels: ChannelId::iter_all().collect(),
+                peer_discovery: true,
+                timeouts: Default::default(),
+                limits: Default::default(),
+                meshsub: Default::default(),
+                feature_flags: Default::default(),
+            },
+            transition_frontier: TransitionFrontierConfig::new(DEVNET_CONFIG.clone()),
+            block_producer: None,
+            archive: None,
+            tx_pool: ledger::transaction_pool::Config {
+                trust_system: (),
+                pool_max_size: 3000,
+                slot_tx_end: None,
+                zkapp_limits: Default::default(),
+                fee_payer_limits: Default::default(),
+            },
+            watchtower: None,
+            checkpoint: Default::default(),
+            fee_sponsor: None,
+        };
+        State::new(config, &consensus_consts, Timestamp::ZERO)
+    }
+
+    #[test]
+    fn digest_is_deterministic() {
+        let digest = state().digest().unwrap();
+        assert_eq!(state().digest().unwrap(), digest);
+
+        let snapshot = state().digest_snapshot().unwrap();
+        assert_eq!(snapshot.applied_actions_count, 0);
+        assert_eq!(snapshot.time, Timestamp::ZERO);
+        assert_eq!(snapshot.digest, digest);
+    }
+
+    #[test]
+    fn digest_ignores_build_env() {
+        let mut state = state();
+        let digest = state.digest().unwrap();
+        state.config.build.git.commit_hash = "other".to_owned();
+        state.config.build.version = "other".to_owned();
+        assert_eq!(state.digest().unwrap(), digest);
+    }
+
+    #[test]
+    fn digest_changes_with_state() {
+        let digest = state().digest().unwrap();
+
+        let mut changed = state();
+        changed
+            .config
+            .sibling_nodes
+            .push("http://sibling:3000".to_owned());
+        assert_ne!(changed.digest().unwrap(), digest);
+
+        let mut changed = state();
+        changed.snark.block_verify.cancelled_count = 1;
+        assert_ne!(changed.digest().unwrap(), digest);
+
+        let mut changed = state();
+        changed.watchtower = WatchtowerState::new(Some(Default::default()));
+        assert_ne!(changed.digest().unwrap(), digest);
+    }
+
+    #[test]
+    fn digest_hex_roundtrip() {
+        let digest = StateDigest([0xab; 32]);
+        let json = serde_json::to_string(&digest).unwrap();
+        assert_eq!(json, format!("\"{}\"", "ab".repeat(32)));
+        assert_eq!(serde_json::from_str::<StateDigest>(&json).unwrap(), digest);
+        assert!("abc".parse::<StateDigest>().is_err());
+    }
+}
@@ -16,9 +16,35 @@ pub mod block_producer {
 }
 use block_producer::BlockProducerStats;
 
+mod stats_profiler;
+pub mod profiler {
+    pub use super::stats_profiler::*;
+}
+
+mod stats_history;
+pub mod history {
+    pub use super::stats_history::*;
+}
+use history::{PeerCountSample, StatsHistory, StatsHistorySnapshot, StatsRetention};
+
+mod stats_block_propagation;
+pub mod block_propagation {
+    pub use super::stats_block_propagation::*;
+}
+use block_propagation::{BlockPropagationStats, BlockPropagationTimeline};
+
+mod stats_zkapp_failures;
+pub mod zkapp_failures {
+    pub use super::stats_zkapp_failures::*;
+}
+use zkapp_failures::{ZkappFailureEpochStats, ZkappFailureStats};
+
+use std::collections::BTreeMap;
+
 use openmina_core::block::{AppliedBlock, ArcBlockWithHash};
 use redux::{ActionMeta, ActionWithMeta, Timestamp};
 
+use crate::transition_frontier::candidate::TransitionFrontierCandidatesPruned;
 use crate::transition_frontier::sync::ledger::staged::PeerStagedLedgerPartsFetchError;
 use crate::transition_frontier::sync::ledger::SyncLedgerTargetKind;
 use crate::transition_frontier::sync::TransitionFrontierSyncBlockState;
@@ -31,15 +57,25 @@ pub struct Stats {
     action_stats: ActionStats,
     sync_stats: SyncStats,
     block_producer_stats: BlockProducerStats,
+    history: StatsHistory,
+    zkapp_failures: ZkappFailureStats,
+    block_propagation: BlockPropagationStats,
 }
 
 impl Stats {
     pub fn new() -> Self {
+        Self::with_history_retention(Default::default())
+    }
+
+    pub fn with_history_retention(retention: StatsRetention) -> Self {
         Self {
             last_action: ActionMeta::ZERO.with_action(ActionKind::None),
             action_stats: Default::default(),
             sync_stats: Default::default(),
             block_producer_stats: Default::default(),
+            history: StatsHistory::new(retention),
+            zkapp_failures: Default::default(),
+            block_propagation: Default::default(),
         }
     }
 
@@ -76,6 +112,7 @@ impl Stats {
 
     pub fn syncing_block_update(&mut self, state: &TransitionFrontierSyncBlockState) -> &mut Self {
         self.sync_stats.block_update(state);
+        self.history.block_update(state);
         self
     }
 
@@ -85,16 +122,45 @@ impl Stats {
             .new_best_tip(time, best_tip.height(), best_tip.hash().clone());
         self.sync_stats.synced(time);
         self.block_producer_stats.new_best_chain(time, chain);
+        self.block_propagation.best_tip(time, best_tip);
+        for block in chain {
+            self.zkapp_failures.add_block(block.block_with_hash());
+        }
+        self
+    }
+
+    pub fn block_produced(&mut self, time: Timestamp, block: &ArcBlockWithHash) -> &mut Self {
+        self.block_propagation.produced(time, block);
+        self
+    }
+
+    pub fn block_received(&mut self, time: Timestamp, block: &ArcBlockWithHash) -> &mut Self {
+        self.block_propagation.received(time, block);
         self
     }
 
     pub fn new_action(&mut self, kind: ActionKind, meta: ActionMeta) -> &mut Self {
         let action = meta.with_action(kind);
         self.action_stats.add(&action, &self.last_action);
+        self.history.new_action(&action, &self.last_action);
         self.last_action = action;
         self
     }
 
+    pub fn peers_sample(&mut self, time: Timestamp, sample: PeerCountSample) -> &mut Self {
+        self.history.peers_sample(time, sample);
+        self
+    }
+
+    pub fn candidates_pruned(
+        &mut self,
+        time: Timestamp,
+        pruned: TransitionFrontierCandidatesPruned,
+    ) -> &mut Self {
+        self.history.candidates_pruned(time, pruned);
+        self
+    }
+
     pub fn collect_action_stats_since_start(&self) -> ActionStatsSnapshot {
         self.action_stats.since_start.clone()
     }
@@ -110,6 +176,28 @@ impl Stats {
         self.sync_stats.collect_stats(limit)
     }
 
+    pub fn collect_history(
+        &self,
+        from: Option<Timestamp>,
+        to: Option<Timestamp>,
+    ) -> StatsHistorySnapshot {
+        self.history.collect(from, to)
+    }
+
+    pub fn collect_zkapp_failures(
+        &self,
+        from_epoch: Option<u32>,
+    ) -> BTreeMap<u32, ZkappFailureEpochStats> {
+        self.zkapp_failures.collect(from_epoch)
+    }
+
+    pub fn collect_block_propagation(
+        &self,
+        from_height: Option<u32>,
+    ) -> Vec<BlockPropagationTimeline> {
+        self.block_propagation.collect(from_height)
+    }
+
     pub fn get_sync_time(&self) -> Option<Timestamp> {
         self.sync_stats
             .collect_stats(Some(1))
@@ -1,4 +1,5 @@
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::time::Duration;
 
 use ledger::AccountIndex;
 use mina_p2p_messages::v2;
@@ -17,6 +18,19 @@ pub struct BlockProducerStats {
     pub(super) attempts: VecDeque<BlockProductionAttempt>,
     pub vrf_evaluator: BTreeMap<u32, VrfEvaluatorStats>,
     pub last_produced_block: Option<ArcBlockWithHash>,
+    pub diff_truncation: DiffTruncationStats,
+}
+
+/// How often the staged ledger diff had to be truncated to fit into the
+/// time budget of the slot.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct DiffTruncationStats {
+    /// Number of created diffs.
+    pub created: u64,
+    /// Number of diffs with some transactions left out.
+    pub truncated: u64,
+    /// Total number of transactions left out.
+    pub truncated_commands: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -24,6 +38,9 @@ pub struct BlockProductionAttempt {
     pub won_slot: BlockProductionAttemptWonSlot,
     pub block: Option<ProducedBlock>,
     pub times: BlockProductionTimes,
+    /// Number of transactions left out of the diff to fit the slot.
+    #[serde(default)]
+    pub truncated_commands: usize,
     #[serde(flatten)]
     pub status: BlockProductionStatus,
 }
@@ -107,6 +124,61 @@ impl Default for VrfEvaluatorStats {
     }
 }
 
+/// Performance of the block producer in the epoch, for the won slots
+/// which already passed.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct BlockProducerEpochReport {
+    pub epoch: u32,
+    pub won_slots: u32,
+    pub produced: u32,
+    pub canonical: u32,
+    pub orphaned: u32,
+    /// Won slots for which no block reached the canonical chain.
+    pub missed: Vec<MissedSlot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MissedSlot {
+    pub global_slot: u32,
+    #[serde(flatten)]
+    pub reason: MissedSlotReason,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "reason")]
+pub enum MissedSlotReason {
+    /// Production wasn't attempted, e.g. because the node wasn't running
+    /// or wasn't synced at the time.
+    NotAttempted,
+    /// Production was given up before the block was produced.
+    Discarded {
+        discard_reason: BlockProducerWonSlotDiscardReason,
+    },
+    /// Production didn't finish, see the attempt for details.
+    Unfinished { status: BlockProductionStatus },
+    /// Block was committed after the end of its slot, so it was likely
+    /// ignored by the network. Durations of the production stages tell
+    /// where the time went.
+    ProducedLate {
+        late_by_ms: u64,
+        diff_create_ms: Option<u64>,
+        proof_create_ms: Option<u64>,
+        block_apply_ms: Option<u64>,
+    },
+    /// Block was produced in time, but another block was chosen for its
+    /// height, either by the fork choice or because ours didn't
+    /// propagate in time.
+    Orphaned { orphaned_by: BlockHash },
+}
+
+impl BlockProductionAttempt {
+    /// How long after the end of the slot the block got committed.
+    fn late_by(&self, slot_duration: Duration) -> Option<Duration> {
+        let slot_end = self.won_slot.slot_time + slot_duration;
+        self.times.committed?.checked_sub(slot_end)
+    }
+}
+
 impl BlockProducerStats {
     fn latest_attempt_block_hash_matches(&self, hash: &BlockHash) -> bool {
         self.attempts
@@ -194,6 +266,7 @@ impl BlockProducerStats {
         self.attempts.push_back(BlockProductionAttempt {
             won_slot: won_slot.into(),
             block: None,
+            truncated_commands: 0,
             times: BlockProductionTimes {
                 scheduled: time,
                 staged_ledger_diff_create_start: None,
@@ -224,13 +297,27 @@ impl BlockProducerStats {
         );
     }
 
-    pub fn staged_ledger_diff_create_end(&mut self, time: redux::Timestamp) {
+    pub fn staged_ledger_diff_create_end(
+        &mut self,
+        time: redux::Timestamp,
+        truncated_commands: usize,
+    ) {
+        let truncation = &mut self.diff_truncation;
+        truncation.created = truncation.created.saturating_add(1);
+        if truncated_commands > 0 {
+            truncation.truncated = truncation.truncated.saturating_add(1);
+            truncation.truncated_commands = truncation
+                .truncated_commands
+                .saturating_add(truncated_commands as u64);
+        }
+
         self.update(
             "staged_ledger_diff_create_end",
             move |attempt| match attempt.status {
                 BlockProductionStatus::StagedLedgerDiffCreatePending => {
                     attempt.status = BlockProductionStatus::StagedLedgerDiffCreateSuccess;
                     attempt.times.staged_ledger_diff_create_end = Some(time);
+                    attempt.truncated_commands = truncated_commands;
                     true
                 }
                 _ => false,
@@ -348,6 +435,94 @@ impl BlockProducerStats {
         }
     }
 
+    /// Report for the `epoch`, for the won slots before the
+    /// `cur_global_slot`. `won_slots` are the global slots won in the
+    /// epoch, as far as they are still known. Slots with production
+    /// attempts are included even if missing from them.
+    pub fn epoch_report(
+        &self,
+        epoch: u32,
+        won_slots: impl IntoIterator<Item = u32>,
+        cur_global_slot: u32,
+        slot_duration: Duration,
+    ) -> BlockProducerEpochReport {
+        let attempts = self
+            .attempts
+            .iter()
+            .filter(|attempt| attempt.won_slot.epoch == epoch)
+            .map(|attempt| (attempt.won_slot.global_slot, attempt))
+            .collect::<BTreeMap<_, _>>();
+        let mut slots = won_slots.into_iter().collect::<BTreeSet<_>>();
+        slots.extend(attempts.keys());
+
+        let mut report = BlockProducerEpochReport {
+            epoch,
+            ..Default::default()
+        };
+        for global_slot in slots.into_iter().filter(|slot| *slot < cur_global_slot) {
+            report.won_slots = report.won_slots.saturating_add(1);
+            let Some(attempt) = attempts.get(&global_slot) else {
+                report.missed.push(MissedSlot {
+                    global_slot,
+                    reason: MissedSlotReason::NotAttempted,
+                });
+                continue;
+            };
+            if attempt.block.is_some() {
+                report.produced = report.produced.saturating_add(1);
+            }
+
+            let reason = match &attempt.status {
+                BlockProductionStatus::Canonical { .. } => {
+                    report.canonical = report.canonical.saturating_add(1);
+                    continue;
+                }
+                // Not known yet whether it ends up canonical.
+                BlockProductionStatus::Committed => continue,
+                BlockProductionStatus::Orphaned { orphaned_by } => {
+                    report.orphaned = report.orphaned.saturating_add(1);
+                    match attempt.late_by(slot_duration) {
+                        Some(late_by) if !late_by.is_zero() => {
+                            let times = &attempt.times;
+                            let ms =
+                                |start: Option<redux::Timestamp>, end: Option<redux::Timestamp>| {
+                                    Some(end?.checked_sub(start?)?.as_millis() as u64)
+                                };
+                            MissedSlotReason::ProducedLate {
+                                late_by_ms: late_by.as_millis() as u64,
+                                diff_create_ms: ms(
+                                    times.staged_ledger_diff_create_start,
+                                    times.staged_ledger_diff_create_end,
+                                ),
+                                proof_create_ms: ms(
+                                    times.proof_create_start,
+                                    times.proof_create_end,
+                                ),
+                                block_apply_ms: ms(times.block_apply_start, times.block_apply_end),
+                            }
+                        }
+                        _ => MissedSlotReason::Orphaned {
+                            orphaned_by: orphaned_by.clone(),
+                        },
+                    }
+                }
+                BlockProductionStatus::Discarded { discard_reason } => {
+                    MissedSlotReason::Discarded {
+                        discard_reason: *discard_reason,
+                    }
+                }
+                status => MissedSlotReason::Unfinished {
+                    status: status.clone(),
+                },
+            };
+            report.missed.push(MissedSlot {
+                global_slot,
+                reason,
+            });
+        }
+        report
+    }
+
     /// In case a new run, when the current epoch has less than `slots_per_epoch` slots to evaluate.
     pub fn new_epoch_evaluation(&mut self, epoch: u32, remaining_slots: u32) {
         self.vrf_evaluator.insert(
@@ -428,3 +603,109 @@ impl From<&BlockWithoutProof> for ProducedBlockTransactions {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOT: Duration = Duration::from_secs(180);
+
+    fn attempt(global_slot: u32, status: BlockProductionStatus) -> BlockProductionAttempt {
+        let slot_time = redux::Timestamp::new(u64::from(global_slot) * SLOT.as_nanos() as u64);
+        let producer = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg"
+            .parse::<crate::account::AccountPublicKey>()
+            .unwrap()
+            .into();
+        BlockProductionAttempt {
+            won_slot: BlockProductionAttemptWonSlot {
+                slot_time,
+                global_slot,
+                epoch: 0,
+                delegator: (producer, AccountIndex(0)),
+                value_with_threshold: None,
+            },
+            block: Some(ProducedBlock {
+                hash: v2::StateHash::zero(),
+                height: global_slot,
+                transactions: Default::default(),
+                completed_works_count: 0,
+                coinbase: 0,
+                fees: 0,
+                snark_fees: 0,
+            }),
+            times: BlockProductionTimes {
+                scheduled: slot_time,
+                staged_ledger_diff_create_start: None,
+                staged_ledger_diff_create_end: None,
+                produced: None,
+                proof_create_start: Some(slot_time),
+                proof_create_end: Some(slot_time + SLOT + Duration::from_secs(10)),
+                block_apply_start: None,
+                block_apply_end: None,
+                committed: Some(slot_time + SLOT + Duration::from_secs(20)),
+                discarded: None,
+            },
+            truncated_commands: 0,
+            status,
+        }
+    }
+
+    #[test]
+    fn epoch_report_classifies_missed_slots() {
+        let orphaned = BlockProductionStatus::Orphaned {
+            orphaned_by: v2::StateHash::zero(),
+        };
+        let mut in_time = attempt(3, orphaned.clone());
+        in_time.times.committed = Some(in_time.won_slot.slot_time + Duration::from_secs(30));
+        let mut stats = BlockProducerStats::default();
+        stats.attempts.extend([
+            attempt(
+                1,
+                BlockProductionStatus::Canonical {
+                    last_observed_confirmations: 5,
+                },
+            ),
+            attempt(2, orphaned),
+            in_time,
+            attempt(
+                4,
+                BlockProductionStatus::Discarded {
+                    discard_reason: BlockProducerWonSlotDiscardReason::BestTipSuperior,
+                },
+            ),
+            attempt(9, BlockProductionStatus::Scheduled),
+        ]);
+
+        let report = stats.epoch_report(0, [1, 5, 9], 9, SLOT);
+        assert_eq!(report.won_slots, 5);
+        assert_eq!(report.produced, 4);
+        assert_eq!(report.canonical, 1);
+        assert_eq!(report.orphaned, 2);
+        let missed = report
+            .missed
+            .iter()
+            .map(|missed| (missed.global_slot, &missed.reason))
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            missed[..],
+            [
+                (
+                    2,
+                    MissedSlotReason::ProducedLate {
+                        late_by_ms: 20_000,
+                        proof_create_ms: Some(190_000),
+                        ..
+                    }
+                ),
+                (3, MissedSlotReason::Orphaned { .. }),
+                (
+                    4,
+                    MissedSlotReason::Discarded {
+                        discard_reason: BlockProducerWonSlotDiscardReason::BestTipSuperior
+                    }
+                ),
+                (5, MissedSlotReason::NotAttempted),
+            ]
+        ));
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use mina_p2p_messages::v2::StateHash;
+use openmina_core::block::ArcBlockWithHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Timelines are kept for this many latest blocks.
+pub const BLOCK_PROPAGATION_BLOCKS_MAX: usize = 1024;
+
+/// When the node first saw the block and when it became its best tip.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockPropagationTimeline {
+    pub hash: StateHash,
+    pub height: u32,
+    pub global_slot: u32,
+    /// Set if the block was produced by this node.
+    pub produced: Option<Timestamp>,
+    /// When the block was produced or first received from a peer.
+    pub first_seen: Timestamp,
+    pub best_tip: Option<Timestamp>,
+}
+
+#[derive(Default)]
+pub struct BlockPropagationStats {
+    blocks: BTreeMap<StateHash, BlockPropagationTimeline>,
+    order: VecDeque<StateHash>,
+}
+
+impl BlockPropagationStats {
+    pub fn produced(&mut self, time: Timestamp, block: &ArcBlockWithHash) {
+        self.entry(time, block).produced.get_or_insert(time);
+    }
+
+    pub fn received(&mut self, time: Timestamp, block: &ArcBlockWithHash) {
+        self.entry(time, block);
+    }
+
+    pub fn best_tip(&mut self, time: Timestamp, block: &ArcBlockWithHash) {
+        self.entry(time, block).best_tip.get_or_insert(time);
+    }
+
+    fn entry(
+        &mut self,
+        time: Timestamp,
+        block: &ArcBlockWithHash,
+    ) -> &mut BlockPropagationTimeline {
+        if !self.blocks.contains_key(block.hash()) {
+            while self.order.len() >= BLOCK_PROPAGATION_BLOCKS_MAX {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.blocks.remove(&oldest);
+                }
+            }
+            self.order.push_back(block.hash().clone());
+        }
+        self.blocks
+            .entry(block.hash().clone())
+            .or_insert_with(|| BlockPropagationTimeline {
+                hash: block.hash().clone(),
+                height: block.height(),
+                global_slot: block.global_slot(),
+                produced: None,
+                first_seen: time,
+                best_tip: None,
+            })
+    }
+
+    /// Timelines of the blocks at `from_height` or above, ordered by height.
+    pub fn collect(&self, from_height: Option<u32>) -> Vec<BlockPropagationTimeline> {
+        let mut blocks = self
+            .blocks
+            .values()
+            .filter(|block| from_height.is_none_or(|height| block.height >= height))
+            .cloned()
+            .collect::<Vec<_>>();
+        blocks.sort_by_key(|block| (block.height, block.first_seen));
+        blocks
+    }
+}
+
+/// Timelines reported by one of the compared nodes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockPropagationNodeTimelines {
+    /// Address of the node's HTTP RPC, empty for this node.
+    pub node: String,
+    pub blocks: Result<Vec<BlockPropagationTimeline>, String>,
+}
+
+/// Propagation of the blocks between the compared nodes.
+///
+/// Delays are computed from the timestamps of the different nodes, so
+/// their clocks need to be synchronized.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockPropagationReport {
+    pub nodes: Vec<BlockPropagationNodeSummary>,
+    pub blocks: Vec<BlockPropagationReportBlock>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockPropagationNodeSummary {
+    pub node: String,
+    /// Why the timelines of the node couldn't be fetched.
+    pub error: Option<String>,
+    pub blocks_seen: usize,
+    pub first_seen_delay_mean_ms: Option<i64>,
+    pub first_seen_delay_max_ms: Option<i64>,
+    pub best_tip_delay_mean_ms: Option<i64>,
+    pub best_tip_delay_max_ms: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockPropagationReportBlock {
+    pub hash: StateHash,
+    pub height: u32,
+    pub global_slot: u32,
+    /// Compared node which produced the block.
+    pub producer: Option<String>,
+    /// When the block was produced, or first seen by any of the nodes
+    /// if none of them produced it.
+    pub origin: Timestamp,
+    pub seen: Vec<BlockPropagationSeen>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockPropagationSeen {
+    pub node: String,
+    /// Milliseconds since the origin, negative if the clocks are skewed.
+    pub first_seen_delay_ms: i64,
+    pub best_tip_delay_ms: Option<i64>,
+}
+
+impl BlockPropagationReport {
+    pub fn new(nodes: Vec<BlockPropagationNodeTimelines>) -> Self {
+        let mut blocks = BTreeMap::<StateHash, BlockPropagationReportBlock>::new();
+        for (node, block) in nodes.iter().flat_map(|timelines| {
+            let node_blocks = timelines.blocks.as_deref().unwrap_or_default();
+            node_blocks.iter().map(|block| (&timelines.node, block))
+        }) {
+            let entry =
+                blocks
+                    .entry(block.hash.clone())
+                    .or_insert_with(|| BlockPropagationReportBlock {
+                        hash: block.hash.clone(),
+                        height: block.height,
+                        global_slot: block.global_slot,
+                        producer: None,
+                        origin: block.first_seen,
+                        seen: Vec::new(),
+                    });
+            if let Some(produced) = block.produced {
+                entry.producer = Some(node.clone());
+                entry.origin = produced;
+            } else if entry.producer.is_none() {
+                entry.origin = entry.origin.min(block.first_seen);
+            }
+        }
+
+        let mut blocks = blocks.into_values().collect::<Vec<_>>();
+        blocks.sort_by_key(|block| (block.height, block.origin));
+        for block in &mut blocks {
+            block.seen = nodes
+                .iter()
+                .filter_map(|timelines| {
+                    let node_blocks = timelines.blocks.as_ref().ok()?;
+                    let timeline = node_blocks.iter().find(|b| b.hash == block.hash)?;
+                    Some(BlockPropagationSeen {
+                        node: timelines.node.clone(),
+                        first_seen_delay_ms: delay_ms(block.origin, timeline.first_seen),
+                        best_tip_delay_ms: timeline
+                            .best_tip
+                            .map(|time| delay_ms(block.origin, time)),
+                    })
+                })
+                .collect();
+        }
+
+        let nodes = nodes
+            .iter()
+            .map(|timelines| {
+                let seen = blocks
+                    .iter()
+                    .filter(|block| block.producer.as_ref() != Some(&timelines.node))
+                    .flat_map(|block| &block.seen)
+                    .filter(|seen| seen.node == timelines.node)
+                    .collect::<Vec<_>>();
+                let first_seen = seen.iter().map(|seen| seen.first_seen_delay_ms);
+                let best_tip = seen.iter().filter_map(|seen| seen.best_tip_delay_ms);
+                BlockPropagationNodeSummary {
+                    node: timelines.node.clone(),
+                    error: timelines.blocks.as_ref().err().cloned(),
+                    blocks_seen: timelines.blocks.as_ref().map_or(0, Vec::len),
+                    first_seen_delay_mean_ms: mean(first_seen.clone()),
+                    first_seen_delay_max_ms: first_seen.max(),
+                    best_tip_delay_mean_ms: mean(best_tip.clone()),
+                    best_tip_delay_max_ms: best_tip.max(),
+                }
+            })
+            .collect();
+
+        Self { nodes, blocks }
+    }
+}
+
+fn delay_ms(origin: Timestamp, time: Timestamp) -> i64 {
+    let origin = u64::from(origin) / 1_000_000;
+    let time = u64::from(time) / 1_000_000;
+    if time >= origin {
+        time.saturating_sub(origin) as i64
+    } else {
+        0_i64.saturating_sub(origin.saturating_sub(time) as i64)
+    }
+}
+
+fn mean(values: impl Iterator<Item = i64>) -> Option<i64> {
+    let (sum, count) = values.fold((0_i64, 0_i64), |(sum, count), value| {
+        (sum.saturating_add(value), count.saturating_add(1))
+    });
+    sum.checked_div(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(i: u64) -> StateHash {
+        StateHash::from_fp(mina_hasher::Fp::from(i))
+    }
+
+    fn ms(ms: u64) -> Timestamp {
+        Timestamp::new(ms * 1_000_000)
+    }
+
+    fn timeline(
+        i: u64,
+        produced: bool,
+        first_seen: u64,
+        best_tip: u64,
+    ) -> BlockPropagationTimeline {
+        BlockPropagationTimeline {
+            hash: hash(i),
+            height: i as u32,
+            global_slot: i as u32,
+            produced: produced.then(|| ms(first_seen)),
+            first_seen: ms(first_seen),
+            best_tip: Some(ms(best_tip)),
+        }
+    }
+
+    #[test]
+    fn test_propagation_report() {
+        let report = BlockPropagationReport::new(vec![
+            BlockPropagationNodeTimelines {
+                node: String::new(),
+                blocks: Ok(vec![
+                    timeline(1, true, 1000, 1100),
+                    timeline(2, false, 2300, 2400),
+                ]),
+            },
+            BlockPropagationNodeTimelines {
+                node: "http://b".to_owned(),
+                blocks: Ok(vec![
+                    timeline(1, false, 1200, 1500),
+                    timeline(2, false, 2100, 2200),
+                ]),
+            },
+            BlockPropagationNodeTimelines {
+                node: "http://c".to_owned(),
+                blocks: Err("connection refused".to_owned()),
+            },
+        ]);
+
+        let [block1, block2] = report.blocks.as_slice() else {
+            panic!("unexpected blocks: {:?}", report.blocks);
+        };
+        assert_eq!(block1.producer.as_deref(), Some(""));
+        assert_eq!(block1.origin, ms(1000));
+        assert_eq!(block2.producer, None);
+        // Neither node produced it, so it's relative to the first one to see it.
+        assert_eq!(block2.origin, ms(2100));
+
+        let [own, b, c] = report.nodes.as_slice() else {
+            panic!("unexpected nodes: {:?}", report.nodes);
+        };
+        // Own produced block isn't counted.
+        assert_eq!(own.first_seen_delay_mean_ms, Some(200));
+        assert_eq!(own.best_tip_delay_max_ms, Some(300));
+        assert_eq!(b.first_seen_delay_mean_ms, Some(100));
+        assert_eq!(b.first_seen_delay_max_ms, Some(200));
+        assert_eq!(b.best_tip_delay_mean_ms, Some(300));
+        assert_eq!(c.blocks_seen, 0);
+        assert_eq!(c.error.as_deref(), Some("connection refused"));
+        assert_eq!(c.first_seen_delay_mean_ms, None);
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use mina_p2p_messages::v2::StateHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::transition_frontier::candidate::TransitionFrontierCandidatesPruned;
+use crate::transition_frontier::sync::TransitionFrontierSyncBlockState;
+use crate::ActionKind;
+
+use super::ActionKindWithMeta;
+
+/// Action latencies are aggregated over intervals of this length.
+const ACTION_LATENCY_INTERVAL: Duration = Duration::from_secs(1);
+/// Minimum interval between the peer count samples.
+const PEERS_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long the samples of the time series are kept.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct StatsRetention {
+    /// Max number of samples kept per series.
+    pub max_len: usize,
+    /// Samples older than this are dropped.
+    pub max_age: Duration,
+}
+
+impl Default for StatsRetention {
+    fn default() -> Self {
+        Self {
+            max_len: 3600,
+            max_age: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatsSample<T> {
+    pub time: Timestamp,
+    #[serde(flatten)]
+    pub value: T,
+}
+
+/// Samples ordered by time, oldest are dropped according to
+/// [`StatsRetention`].
+#[derive(Debug, Clone)]
+pub struct StatsRingBuffer<T> {
+    retention: StatsRetention,
+    samples: VecDeque<StatsSample<T>>,
+}
+
+impl<T: Clone> StatsRingBuffer<T> {
+    pub fn new(retention: StatsRetention) -> Self {
+        Self {
+            retention,
+            samples: VecDeque::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn push(&mut self, time: Timestamp, value: T) {
+        self.prune(time);
+        if self.retention.max_len == 0 {
+            return;
+        }
+        while self.samples.len() >= self.retention.max_len {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(StatsSample { time, value });
+    }
+
+    fn prune(&mut self, now: Timestamp) {
+        while let Some(sample) = self.samples.front() {
+            let age = now.checked_sub(sample.time).unwrap_or_default();
+            if age <= self.retention.max_age {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    /// Samples with time within `[from, to]` range, both bounds optional.
+    pub fn range(&self, from: Option<Timestamp>, to: Option<Timestamp>) -> Vec<StatsSample<T>> {
+        self.samples
+            .iter()
+            .skip_while(|s| from.is_some_and(|from| s.time < from))
+            .take_while(|s| to.is_none_or(|to| s.time <= to))
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockApplicationSample {
+    pub height: u32,
+    pub hash: StateHash,
+    /// Time it took to apply the block in nanoseconds.
+    pub duration: u64,
+}
+
+/// Latencies of the actions within [`ACTION_LATENCY_INTERVAL`], starting
+/// at the time of the sample. Time spent waiting for events isn't counted.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ActionLatencySample {
+    pub calls: u64,
+    /// Sum of durations of the actions in nanoseconds.
+    pub total_duration: u64,
+    /// Max duration in nanoseconds.
+    pub max_duration: u64,
+    /// Action with the max duration.
+    pub max_duration_kind: Option<ActionKind>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PeerCountSample {
+    pub ready: usize,
+    pub connecting: usize,
+    pub known: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatsHistorySnapshot {
+    pub block_application: Vec<StatsSample<BlockApplicationSample>>,
+    pub action_latency: Vec<StatsSample<ActionLatencySample>>,
+    pub peers: Vec<StatsSample<PeerCountSample>>,
+    /// Prunings of the stale transition frontier candidates.
+    #[serde(default)]
+    pub candidates_pruned: Vec<StatsSample<TransitionFrontierCandidatesPruned>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatsHistory {
+    block_application: StatsRingBuffer<BlockApplicationSample>,
+    action_latency: StatsRingBuffer<ActionLatencySample>,
+    peers: StatsRingBuffer<PeerCountSample>,
+    candidates_pruned: StatsRingBuffer<TransitionFrontierCandidatesPruned>,
+    block_apply_start: Option<(StateHash, Timestamp)>,
+    action_latency_current: Option<StatsSample<ActionLatencySample>>,
+}
+
+impl StatsHistory {
+    pub fn new(retention: StatsRetention) -> Self {
+        Self {
+            block_application: StatsRingBuffer::new(retention),
+            action_latency: StatsRingBuffer::new(retention),
+            peers: StatsRingBuffer::new(retention),
+            candidates_pruned: StatsRingBuffer::new(retention),
+            block_apply_start: None,
+            action_latency_current: None,
+        }
+    }
+
+    pub fn block_update(&mut self, state: &TransitionFrontierSyncBlockState) {
+        match state {
+            TransitionFrontierSyncBlockState::ApplyPending { time, block } => {
+                self.block_apply_start = Some((block.hash.clone(), *time));
+            }
+            TransitionFrontierSyncBlockState::ApplySuccess { time, block } => {
+                let Some((hash, start)) = self.block_apply_start.take() else {
+                    return;
+                };
+                if &hash != block.hash() {
+                    return;
+                }
+                let duration = time.checked_sub(start).unwrap_or_default();
+                let sample = BlockApplicationSample {
+                    height: block.height(),
+                    hash,
+                    duration: duration.as_nanos() as u64,
+                };
+                self.block_application.push(*time, sample);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn new_action(&mut self, action: &ActionKindWithMeta, prev_action: &ActionKindWithMeta) {
+        let time = action.meta().time();
+        let flush = self.action_latency_current.as_ref().is_some_and(|current| {
+            time.checked_sub(current.time).unwrap_or_default() >= ACTION_LATENCY_INTERVAL
+        });
+        if flush {
+            if let Some(current) = self.action_latency_current.take() {
+                self.action_latency.push(current.time, current.value);
+            }
+        }
+
+        let kind = *prev_action.action();
+        if matches!(
+            kind,
+            ActionKind::None | ActionKind::EventSourceWaitForEvents
+        ) {
+            return;
+        }
+        let duration = action
+            .meta()
+            .time_as_nanos()
+            .saturating_sub(prev_action.meta().time_as_nanos());
+        let current = self
+            .action_latency_current
+            .get_or_insert_with(|| StatsSample {
+                time,
+                value: Default::default(),
+            });
+        let sample = &mut current.value;
+        sample.calls = sample.calls.saturating_add(1);
+        sample.total_duration = sample.total_duration.saturating_add(duration);
+        if duration >= sample.max_duration {
+            sample.max_duration = duration;
+            sample.max_duration_kind = Some(kind);
+        }
+    }
+
+    pub fn peers_sample(&mut self, time: Timestamp, sample: PeerCountSample) {
+        let is_due = self.peers.samples.back().is_none_or(|last| {
+            time.checked_sub(last.time).unwrap_or_default() >= PEERS_SAMPLE_INTERVAL
+        });
+        if is_due {
+            self.peers.push(time, sample);
+        }
+    }
+
+    pub fn candidates_pruned(
+        &mut self,
+        time: Timestamp,
+        pruned: TransitionFrontierCandidatesPruned,
+    ) {
+        self.candidates_pruned.push(time, pruned);
+    }
+
+    pub fn collect(&self, from: Option<Timestamp>, to: Option<Timestamp>) -> StatsHistorySnapshot {
+        StatsHistorySnapshot {
+            block_application: self.block_application.range(from, to),
+            action_latency: self.action_latency.range(from, to),
+            peers: self.peers.range(from, to),
+            candidates_pruned: self.candidates_pruned.range(from, to),
+        }
+    }
+}
+
+impl Default for StatsHistory {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(s: u64) -> Timestamp {
+        Timestamp::new(s * 1_000_000_000)
+    }
+
+    #[test]
+    fn ring_buffer_retention_and_range() {
+        let mut buf = StatsRingBuffer::new(StatsRetention {
+            max_len: 5,
+            max_age: Duration::from_secs(10),
+        });
+        for i in 0..8 {
+            buf.push(secs(i), i);
+        }
+        assert_eq!(buf.len(), 5);
+        let values = |v: Vec<StatsSample<u64>>| v.into_iter().map(|s| s.value).collect::<Vec<_>>();
+        assert_eq!(values(buf.range(None, None)), vec![3, 4, 5, 6, 7]);
+        assert_eq!(
+            values(buf.range(Some(secs(4)), Some(secs(6)))),
+            vec![4, 5, 6]
+        );
+
+        // samples older than `max_age` are dropped.
+        buf.push(secs(16), 16);
+        assert_eq!(values(buf.range(None, None)), vec![6, 7, 16]);
+    }
+}
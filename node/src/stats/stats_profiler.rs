@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use openmina_core::profiler::{
+    profiler_sample, set_profiler_enabled, ProfilerPhase, ProfilerService,
+};
+use openmina_core::thread;
+use serde::{Deserialize, Serialize};
+
+use crate::ActionKind;
+
+pub const PROFILER_DEFAULT_FREQUENCY_HZ: u32 = 99;
+pub const PROFILER_MAX_FREQUENCY_HZ: u32 = 10_000;
+
+/// Sampling profiler, which attributes samples to the action kind that
+/// the state machine is processing and to the busy services.
+///
+/// Output is written to the debug dir in the folded stacks format, which
+/// can be rendered with `flamegraph.pl`, `inferno-flamegraph` or speedscope.
+#[derive(Default)]
+pub struct Profiler {
+    running: Option<ProfilerRun>,
+}
+
+struct ProfilerRun {
+    frequency_hz: u32,
+    stop: Arc<AtomicBool>,
+    handle: thread::JoinHandle<ProfilerSamples>,
+}
+
+#[derive(Default)]
+struct ProfilerSamples {
+    total: u64,
+    stacks: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfilerStatus {
+    pub running: bool,
+    pub frequency_hz: u32,
+    /// Number of samples taken, set once profiler is stopped.
+    pub samples: Option<u64>,
+    /// Path to the written profile, set once profiler is stopped.
+    pub output: Option<String>,
+}
+
+impl Profiler {
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    pub fn start(&mut self, frequency_hz: u32) -> Result<ProfilerStatus, String> {
+        if cfg!(target_family = "wasm") {
+            return Err("profiler is not supported in the browser".to_owned());
+        }
+        if self.running.is_some() {
+            return Err("profiler is already running".to_owned());
+        }
+        if frequency_hz == 0 || frequency_hz > PROFILER_MAX_FREQUENCY_HZ {
+            return Err(format!(
+                "profiler frequency must be in range 1..={PROFILER_MAX_FREQUENCY_HZ}"
+            ));
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let interval = Duration::from_secs(1) / frequency_hz;
+        let handle = {
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name("profiler".to_owned())
+                .spawn(move || {
+                    let mut samples = ProfilerSamples::default();
+                    while !stop.load(Ordering::Relaxed) {
+                        samples.take();
+                        thread::sleep(interval);
+                    }
+                    samples
+                })
+                .map_err(|err| format!("failed to spawn profiler thread: {err}"))?
+        };
+        set_profiler_enabled(true);
+
+        self.running = Some(ProfilerRun {
+            frequency_hz,
+            stop,
+            handle,
+        });
+        Ok(ProfilerStatus {
+            running: true,
+            frequency_hz,
+            samples: None,
+            output: None,
+        })
+    }
+
+    /// Stops the profiler and writes the collected samples to the debug dir.
+    pub fn stop(&mut self) -> Result<ProfilerStatus, String> {
+        let run = self
+            .running
+            .take()
+            .ok_or_else(|| "profiler is not running".to_owned())?;
+        set_profiler_enabled(false);
+        run.stop.store(true, Ordering::Relaxed);
+        let samples = run
+            .handle
+            .join()
+            .map_err(|_| "profiler thread panicked".to_owned())?;
+
+        let debug_dir = openmina_core::get_debug_dir();
+        std::fs::create_dir_all(&debug_dir).map_err(|err| err.to_string())?;
+        let time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = debug_dir.join(format!("profile_{time}.folded"));
+        openmina_core::debug_dump::dump_write(&path, samples.folded().into_bytes())
+            .map_err(|err| err.to_string())?;
+
+        Ok(ProfilerStatus {
+            running: false,
+            frequency_hz: run.frequency_hz,
+            samples: Some(samples.total),
+            output: Some(path.display().to_string()),
+        })
+    }
+}
+
+impl ProfilerSamples {
+    fn take(&mut self) {
+        let sample = profiler_sample();
+        self.total += 1;
+
+        let stack = match sample.phase {
+            ProfilerPhase::Idle => "state_machine;idle".to_owned(),
+            phase => {
+                let kind = ActionKind::try_from(sample.action_kind).unwrap_or(ActionKind::None);
+                format!("state_machine;{};{kind:?}", phase.name())
+            }
+        };
+        *self.stacks.entry(stack).or_default() += 1;
+
+        for (service, busy) in ProfilerService::ALL.iter().zip(sample.services_busy) {
+            if busy > 0 {
+                let stack = format!("services;{}", service.name());
+                *self.stacks.entry(stack).or_default() += busy as u64;
+            }
+        }
+    }
+
+    fn folded(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|(stack, count)| format!("{stack} {count}\n"))
+            .collect()
+    }
+}
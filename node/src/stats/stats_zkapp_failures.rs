@@ -0,0 +1,159 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ledger::scan_state::transaction_logic::TransactionFailure;
+use mina_p2p_messages::v2::{MinaBaseTransactionStatusStableV2, MinaBaseUserCommandStableV2};
+use openmina_core::block::{ArcBlockWithHash, BlockHash};
+use serde::{Deserialize, Serialize};
+
+/// Number of the recently counted blocks, remembered so that the block
+/// isn't counted again when the best chain changes.
+const COUNTED_BLOCKS_MAX: usize = 1024;
+
+/// Failure reasons of the zkApp commands included in the blocks, per
+/// epoch and failure kind, so that it is visible which preconditions fail
+/// the most on the network.
+///
+/// Each failure kind is counted once per command, even if more account
+/// updates of the command failed with it. Blocks are counted once they
+/// get into the best chain, so blocks which were orphaned later stay
+/// counted.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ZkappFailureStats {
+    pub epochs: BTreeMap<u32, ZkappFailureEpochStats>,
+    /// Height, epoch and hash of the counted blocks, the lowest ones are
+    /// forgotten first.
+    #[serde(default)]
+    counted_blocks: BTreeSet<(u32, u32, BlockHash)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ZkappFailureEpochStats {
+    /// Number of the zkApp commands included in the blocks.
+    pub commands: u64,
+    /// Number of the zkApp commands which failed.
+    pub failed: u64,
+    pub failures: BTreeMap<String, u64>,
+}
+
+impl ZkappFailureStats {
+    /// Counts the zkApp commands of the `block`, unless it was already.
+    /// Returns the epoch of the block, if it was counted.
+    pub fn add_block(&mut self, block: &ArcBlockWithHash) -> Option<u32> {
+        let epoch = block.consensus_state().epoch_count.as_u32();
+        let key = (block.height(), epoch, block.hash().clone());
+        if !self.counted_blocks.insert(key) {
+            return None;
+        }
+        if self.counted_blocks.len() > COUNTED_BLOCKS_MAX {
+            self.counted_blocks.pop_first();
+        }
+
+        let stats = self.epochs.entry(epoch).or_default();
+        for command in block.commands_iter() {
+            if !matches!(command.data, MinaBaseUserCommandStableV2::ZkappCommand(_)) {
+                continue;
+            }
+            stats.commands = stats.commands.saturating_add(1);
+            let MinaBaseTransactionStatusStableV2::Failed(failures) = &command.status else {
+                continue;
+            };
+            stats.failed = stats.failed.saturating_add(1);
+            let kinds = failures
+                .0
+                .iter()
+                .flat_map(|failures| failures.iter())
+                .map(|failure| zkapp_failure_kind(&TransactionFailure::from(failure)))
+                .collect::<BTreeSet<_>>();
+            for kind in kinds {
+                let count = stats.failures.entry(kind).or_default();
+                *count = count.saturating_add(1);
+            }
+        }
+        Some(epoch)
+    }
+
+    /// Stats of the single `epoch`, with the blocks counted in it, so that
+    /// they can be stored separately from the other epochs.
+    pub fn epoch(&self, epoch: u32) -> Self {
+        Self {
+            epochs: self
+                .epochs
+                .get(&epoch)
+                .map(|stats| (epoch, stats.clone()))
+                .into_iter()
+                .collect(),
+            counted_blocks: self
+                .counted_blocks
+                .iter()
+                .filter(|(_, block_epoch, _)| *block_epoch == epoch)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Merges the stats stored per epoch with [`Self::epoch`].
+    pub fn merge(&mut self, other: Self) {
+        self.epochs.extend(other.epochs);
+        self.counted_blocks.extend(other.counted_blocks);
+        while self.counted_blocks.len() > COUNTED_BLOCKS_MAX {
+            self.counted_blocks.pop_first();
+        }
+    }
+
+    /// Stats of the epochs, starting from the `from` one.
+    pub fn collect(&self, from: Option<u32>) -> BTreeMap<u32, ZkappFailureEpochStats> {
+        self.epochs
+            .range(from.unwrap_or_default()..)
+            .map(|(epoch, stats)| (*epoch, stats.clone()))
+            .collect()
+    }
+}
+
+/// Same as the OCaml failure name, except that the app state precondition
+/// failures are counted together, regardless of the index of the field.
+pub fn zkapp_failure_kind(failure: &TransactionFailure) -> String {
+    match failure {
+        TransactionFailure::AccountAppStatePreconditionUnsatisfied(_) => {
+            "Account_app_state_precondition_unsatisfied".to_owned()
+        }
+        failure => failure.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openmina_core::block::fixtures;
+
+    use super::*;
+
+    #[test]
+    fn test_zkapp_failure_stats_epochs() {
+        let block = fixtures::best_tip_with_hash();
+        let epoch = block.consensus_state().epoch_count.as_u32();
+
+        let mut stats = ZkappFailureStats::default();
+        assert_eq!(stats.add_block(&block), Some(epoch));
+        assert_eq!(stats.add_block(&block), None);
+        assert!(stats.epoch(epoch.saturating_add(1)).epochs.is_empty());
+
+        // Restored from the per epoch files, the block stays counted.
+        let mut restored = ZkappFailureStats::default();
+        restored.merge(stats.epoch(epoch));
+        assert_eq!(restored.collect(None), stats.collect(None));
+        assert_eq!(restored.add_block(&block), None);
+    }
+
+    #[test]
+    fn test_zkapp_failure_kind() {
+        assert_eq!(
+            zkapp_failure_kind(&TransactionFailure::AccountAppStatePreconditionUnsatisfied(
+                3
+            )),
+            "Account_app_state_precondition_unsatisfied"
+        );
+        assert_eq!(
+            zkapp_failure_kind(&TransactionFailure::AccountNoncePreconditionUnsatisfied),
+            "Account_nonce_precondition_unsatisfied"
+        );
+    }
+}
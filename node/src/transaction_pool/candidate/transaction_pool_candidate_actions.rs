@@ -5,10 +5,10 @@ use openmina_core::ActionEvent;
 use p2p::P2pNetworkPubsubMessageCacheId;
 use serde::{Deserialize, Serialize};
 
-use crate::p2p::channels::rpc::P2pRpcId;
+use crate::p2p::channels::rpc::{P2pRpcId, TransactionPoolSummary};
 use crate::p2p::PeerId;
 
-use super::TransactionPoolCandidateState;
+use super::{TransactionPoolCandidateState, TransactionPoolSyncState};
 
 pub type TransactionPoolCandidateActionWithMeta =
     redux::ActionWithMeta<TransactionPoolCandidateAction>;
@@ -21,6 +21,12 @@ pub enum TransactionPoolCandidateAction {
         peer_id: PeerId,
         info: TransactionInfo,
     },
+    /// Peer announced digests of the transactions, request the ones
+    /// that we don't have yet.
+    DigestsReceived {
+        peer_id: PeerId,
+        hashes: Vec<TransactionHash>,
+    },
     #[action_event(level = trace)]
     FetchAll,
     FetchInit {
@@ -40,6 +46,25 @@ pub enum TransactionPoolCandidateAction {
         peer_id: PeerId,
         transaction: TransactionWithHash,
     },
+    /// Start syncing the pool with the newly connected peer, by requesting
+    /// summaries of its pool page by page.
+    PoolSyncInit {
+        peer_id: PeerId,
+    },
+    PoolSyncRequestInit {
+        peer_id: PeerId,
+    },
+    PoolSyncRequestPending {
+        peer_id: PeerId,
+        rpc_id: P2pRpcId,
+    },
+    PoolSyncPageReceived {
+        peer_id: PeerId,
+        summary: TransactionPoolSummary,
+    },
+    PoolSyncError {
+        peer_id: PeerId,
+    },
     /// Callback for transactions received over pubsub
     Libp2pTransactionsReceived {
         peer_id: PeerId,
@@ -78,6 +103,7 @@ impl redux::EnablingCondition<crate::State> for TransactionPoolCandidateAction {
                         .candidates
                         .peer_contains(*peer_id, &info.hash)
             }
+            TransactionPoolCandidateAction::DigestsReceived { .. } => true,
             TransactionPoolCandidateAction::FetchAll => state.p2p.ready().is_some(),
             TransactionPoolCandidateAction::FetchInit { peer_id, hash } => {
                 let is_peer_available = state
@@ -111,6 +137,39 @@ impl redux::EnablingCondition<crate::State> for TransactionPoolCandidateAction {
                 .candidates
                 .get(*peer_id, transaction.hash())
                 .is_some(),
+            TransactionPoolCandidateAction::PoolSyncInit { peer_id } => {
+                let supports_pool_sync = state.p2p.get_ready_peer(peer_id).is_some_and(|peer| {
+                    peer.capabilities
+                        .as_ref()
+                        .is_some_and(|capabilities| capabilities.pool_sync)
+                });
+                supports_pool_sync
+                    && state
+                        .transaction_pool
+                        .candidates
+                        .pool_sync_state(peer_id)
+                        .is_none()
+            }
+            TransactionPoolCandidateAction::PoolSyncRequestInit { peer_id } => {
+                let is_peer_available = state
+                    .p2p
+                    .get_ready_peer(peer_id)
+                    .is_some_and(|peer| peer.channels.rpc.can_send_request());
+                is_peer_available
+                    && matches!(
+                        state.transaction_pool.candidates.pool_sync_state(peer_id),
+                        Some(TransactionPoolSyncState::Init { .. })
+                    )
+            }
+            TransactionPoolCandidateAction::PoolSyncRequestPending { peer_id, .. } => matches!(
+                state.transaction_pool.candidates.pool_sync_state(peer_id),
+                Some(TransactionPoolSyncState::Init { .. })
+            ),
+            TransactionPoolCandidateAction::PoolSyncPageReceived { peer_id, .. }
+            | TransactionPoolCandidateAction::PoolSyncError { peer_id } => matches!(
+                state.transaction_pool.candidates.pool_sync_state(peer_id),
+                Some(TransactionPoolSyncState::Pending { .. })
+            ),
             TransactionPoolCandidateAction::Libp2pTransactionsReceived { .. } => true,
             TransactionPoolCandidateAction::VerifyNext => {
                 // Don't continue if we are producing a block, or we never synced yet
@@ -145,11 +204,9 @@ impl redux::EnablingCondition<crate::State> for TransactionPoolCandidateAction {
                 true
             }
             TransactionPoolCandidateAction::PeerPrune { peer_id } => {
-                state
-                    .transaction_pool
-                    .candidates
-                    .peer_transaction_count(peer_id)
-                    > 0
+                let candidates = &state.transaction_pool.candidates;
+                candidates.peer_transaction_count(peer_id) > 0
+                    || candidates.pool_sync_state(peer_id).is_some()
             }
         }
     }
@@ -1,14 +1,21 @@
 #![allow(clippy::unit_arg)]
 
-use crate::{p2p_ready, TransactionPoolAction};
+use crate::{
+    p2p_ready,
+    snark::verify_admission::{SnarkVerifyAdmissionAction, SnarkVerifySource},
+    TransactionPoolAction,
+};
 use p2p::{
-    channels::rpc::{P2pChannelsRpcAction, P2pRpcId, P2pRpcRequest},
+    channels::{
+        rpc::{P2pChannelsRpcAction, P2pRpcId, P2pRpcRequest, TRANSACTION_POOL_SUMMARY_PAGE_SIZE},
+        transaction::P2pChannelsTransactionAction,
+    },
     PeerId,
 };
 
 use super::{
     TransactionPoolCandidateAction, TransactionPoolCandidateActionWithMetaRef,
-    TransactionPoolCandidatesState,
+    TransactionPoolCandidatesState, TransactionPoolSyncState,
 };
 
 impl TransactionPoolCandidatesState {
@@ -26,8 +33,29 @@ impl TransactionPoolCandidatesState {
             TransactionPoolCandidateAction::InfoReceived { peer_id, info } => {
                 state.info_received(meta.time(), *peer_id, info.clone());
             }
+            TransactionPoolCandidateAction::DigestsReceived { peer_id, hashes } => {
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let pool = &global_state.transaction_pool;
+                let hashes = hashes
+                    .iter()
+                    .filter(|hash| !pool.contains(hash) && !pool.candidates.contains(hash))
+                    .cloned()
+                    .collect();
+                dispatcher.push(P2pChannelsTransactionAction::RequestByHashSend {
+                    peer_id: *peer_id,
+                    hashes,
+                });
+            }
             TransactionPoolCandidateAction::FetchAll => {
                 let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let candidates = &global_state.transaction_pool.candidates;
+                // request pool summaries first, so that the missing
+                // transactions get fetched sooner.
+                for peer_id in candidates.pool_sync_peers_to_request() {
+                    dispatcher
+                        .push(TransactionPoolCandidateAction::PoolSyncRequestInit { peer_id });
+                }
+
                 let p2p = p2p_ready!(global_state.p2p, meta.time());
                 let peers = p2p.ready_peers_iter().map(|(id, _)| *id);
                 let get_order = |_hash: &_| {
@@ -89,6 +117,77 @@ impl TransactionPoolCandidatesState {
             } => {
                 state.transaction_received(meta.time(), *peer_id, transaction.clone());
             }
+            TransactionPoolCandidateAction::PoolSyncInit { peer_id } => {
+                state.pool_sync_init(meta.time(), *peer_id, 0);
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransactionPoolCandidateAction::PoolSyncRequestInit {
+                    peer_id: *peer_id,
+                });
+            }
+            TransactionPoolCandidateAction::PoolSyncRequestInit { peer_id } => {
+                let Some(TransactionPoolSyncState::Init { offset, .. }) =
+                    state.pool_sync_state(peer_id)
+                else {
+                    return;
+                };
+                let offset = *offset;
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let peer_id = *peer_id;
+                let p2p = p2p_ready!(global_state.p2p, meta.time());
+                let Some(peer) = p2p.get_ready_peer(&peer_id) else {
+                    return;
+                };
+                let rpc_id = peer.channels.next_local_rpc_id();
+
+                dispatcher.push(P2pChannelsRpcAction::RequestSend {
+                    peer_id,
+                    id: rpc_id,
+                    request: Box::new(P2pRpcRequest::TransactionPoolSummary { offset }),
+                    on_init: Some(redux::callback!(
+                        on_send_p2p_pool_summary_rpc_request(
+                            (peer_id: PeerId, rpc_id: P2pRpcId, _request: P2pRpcRequest)
+                        ) -> crate::Action {
+                            TransactionPoolCandidateAction::PoolSyncRequestPending {
+                                peer_id,
+                                rpc_id,
+                            }
+                        }
+                    )),
+                });
+            }
+            TransactionPoolCandidateAction::PoolSyncRequestPending { peer_id, rpc_id } => {
+                state.pool_sync_pending(meta.time(), peer_id, *rpc_id);
+            }
+            TransactionPoolCandidateAction::PoolSyncPageReceived { peer_id, summary } => {
+                let Some(TransactionPoolSyncState::Pending { offset, .. }) =
+                    state.pool_sync_state(peer_id)
+                else {
+                    return;
+                };
+                let (peer_id, offset) = (*peer_id, *offset);
+                // only continue if the peer makes progress, otherwise
+                // a misbehaving peer could keep us requesting forever.
+                match summary.next_offset.filter(|next| *next > offset) {
+                    Some(next_offset) => state.pool_sync_init(meta.time(), peer_id, next_offset),
+                    None => state.pool_sync_done(meta.time(), peer_id),
+                }
+
+                let dispatcher = state_context.into_dispatcher();
+                let transactions = summary
+                    .transactions
+                    .iter()
+                    .take(TRANSACTION_POOL_SUMMARY_PAGE_SIZE as usize);
+                for info in transactions {
+                    dispatcher.push(TransactionPoolCandidateAction::InfoReceived {
+                        peer_id,
+                        info: info.clone(),
+                    });
+                }
+                dispatcher.push(TransactionPoolCandidateAction::PoolSyncRequestInit { peer_id });
+            }
+            TransactionPoolCandidateAction::PoolSyncError { peer_id } => {
+                state.pool_sync_done(meta.time(), *peer_id);
+            }
             TransactionPoolCandidateAction::Libp2pTransactionsReceived {
                 peer_id,
                 transactions,
@@ -111,6 +210,19 @@ impl TransactionPoolCandidatesState {
                 let Some((peer_id, batch, from_source)) = batch else {
                     return;
                 };
+                // Commands from peers are checked here, before their fee
+                // payers are charged for them. Local ones never get here.
+                if !global_state
+                    .snark
+                    .is_verify_admitted(SnarkVerifySource::UserCommand)
+                {
+                    // Candidates stay received, they will be verified once
+                    // there is free verification capacity.
+                    dispatcher.push(SnarkVerifyAdmissionAction::Denied {
+                        source: SnarkVerifySource::UserCommand,
+                    });
+                    return;
+                }
 
                 let transaction_hashes = batch.iter().map(|tx| tx.hash().clone()).collect();
                 dispatcher.push(TransactionPoolAction::StartVerify {
@@ -28,6 +28,22 @@ pub struct TransactionPoolCandidatesState {
     by_peer: BTreeMap<PeerId, BTreeMap<TransactionHash, TransactionPoolCandidateState>>,
     by_hash: BTreeMap<TransactionHash, BTreeSet<PeerId>>,
     by_message_id: BTreeMap<P2pNetworkPubsubMessageCacheId, (PeerId, Vec<TransactionHash>)>,
+    /// Progress of the pool sync with the peers, see
+    /// [`crate::p2p::channels::rpc::TransactionPoolSummary`].
+    pool_sync: BTreeMap<PeerId, TransactionPoolSyncState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TransactionPoolSyncState {
+    /// Page starting at the `offset` needs to be requested.
+    Init { time: Timestamp, offset: u64 },
+    Pending {
+        time: Timestamp,
+        offset: u64,
+        rpc_id: P2pRpcId,
+    },
+    /// Whole pool of the peer was received, or the sync failed.
+    Done { time: Timestamp },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -130,6 +146,58 @@ impl TransactionPoolCandidatesState {
             .map(|hash| (hash, transactions.get(hash)))
     }
 
+    /// Number of the distinct zkApp commands received from the peers,
+    /// whose verification wasn't started yet.
+    pub fn zkapp_commands_received_count(&self) -> usize {
+        self.by_peer
+            .values()
+            .flat_map(|txs| txs.iter())
+            .filter(|(_, state)| match state {
+                TransactionPoolCandidateState::Received { transaction, .. } => matches!(
+                    transaction.body(),
+                    v2::MinaBaseUserCommandStableV2::ZkappCommand(_)
+                ),
+                _ => false,
+            })
+            .map(|(hash, _)| hash)
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+
+    pub fn pool_sync_state(&self, peer_id: &PeerId) -> Option<&TransactionPoolSyncState> {
+        self.pool_sync.get(peer_id)
+    }
+
+    /// Peers for which the next page of their pool needs to be requested.
+    pub fn pool_sync_peers_to_request(&self) -> impl Iterator<Item = PeerId> + '_ {
+        self.pool_sync
+            .iter()
+            .filter(|(_, state)| matches!(state, TransactionPoolSyncState::Init { .. }))
+            .map(|(peer_id, _)| *peer_id)
+    }
+
+    pub fn pool_sync_init(&mut self, time: Timestamp, peer_id: PeerId, offset: u64) {
+        self.pool_sync
+            .insert(peer_id, TransactionPoolSyncState::Init { time, offset });
+    }
+
+    pub fn pool_sync_pending(&mut self, time: Timestamp, peer_id: &PeerId, rpc_id: P2pRpcId) {
+        if let Some(state) = self.pool_sync.get_mut(peer_id) {
+            if let TransactionPoolSyncState::Init { offset, .. } = state {
+                *state = TransactionPoolSyncState::Pending {
+                    time,
+                    offset: *offset,
+                    rpc_id,
+                };
+            }
+        }
+    }
+
+    pub fn pool_sync_done(&mut self, time: Timestamp, peer_id: PeerId) {
+        self.pool_sync
+            .insert(peer_id, TransactionPoolSyncState::Done { time });
+    }
+
     pub fn info_received(&mut self, time: Timestamp, peer_id: PeerId, info: TransactionInfo) {
         self.by_hash
             .entry(info.hash.clone())
@@ -358,6 +426,7 @@ impl TransactionPoolCandidatesState {
     }
 
     pub fn peer_remove(&mut self, peer_id: PeerId) {
+        self.pool_sync.remove(&peer_id);
         if let Some(txs) = self.by_peer.remove(&peer_id) {
             for hash in txs.into_keys() {
                 if let Some(peers) = self.by_hash.get_mut(&hash) {
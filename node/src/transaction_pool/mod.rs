@@ -1,8 +1,13 @@
 pub mod candidate;
+pub mod payouts;
+pub mod sponsor;
 
 mod transaction_pool_state;
 pub use transaction_pool_state::*;
 
+mod transaction_pool_snapshot;
+pub use transaction_pool_snapshot::*;
+
 mod transaction_pool_actions;
 pub use transaction_pool_actions::*;
 
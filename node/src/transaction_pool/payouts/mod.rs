@@ -0,0 +1,7 @@
+mod transaction_pool_payouts_state;
+pub use transaction_pool_payouts_state::*;
+
+mod transaction_pool_payouts_actions;
+pub use transaction_pool_payouts_actions::*;
+
+mod transaction_pool_payouts_reducer;
@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+
+use ledger::{
+    scan_state::currency::{Amount, Fee},
+    Account, AccountId,
+};
+use mina_p2p_messages::v2::{MinaBaseUserCommandStableV2, TransactionHash};
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+
+use super::PayoutBatchId;
+
+pub type TransactionPoolPayoutsActionWithMeta = redux::ActionWithMeta<TransactionPoolPayoutsAction>;
+pub type TransactionPoolPayoutsActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a TransactionPoolPayoutsAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = info)]
+pub enum TransactionPoolPayoutsAction {
+    #[action_event(fields(batch_id, payments = payments.len()))]
+    BatchAdd {
+        batch_id: PayoutBatchId,
+        fee: Fee,
+        memo: String,
+        max_per_sec: u32,
+        payments: Vec<(AccountPublicKey, Amount)>,
+    },
+    #[action_event(level = trace)]
+    NonceFetchInit,
+    NonceFetchSuccess {
+        accounts: BTreeMap<AccountId, Account>,
+    },
+    #[action_event(level = trace)]
+    InjectNext,
+    #[action_event(fields(batch_id, commands = commands.len()))]
+    Signed {
+        batch_id: PayoutBatchId,
+        /// Signed payments along with their index in the batch.
+        commands: Vec<(usize, MinaBaseUserCommandStableV2)>,
+    },
+    #[action_event(fields(batch_id, accepted = accepted.len(), rejected = rejected.len()))]
+    InjectResult {
+        batch_id: PayoutBatchId,
+        accepted: Vec<TransactionHash>,
+        rejected: Vec<(TransactionHash, String)>,
+    },
+    #[action_event(level = warn, fields(batch_id, error))]
+    InjectError {
+        batch_id: PayoutBatchId,
+        error: String,
+    },
+    #[action_event(level = trace)]
+    CheckTimeouts,
+}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolPayoutsAction {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
+        let payouts = &state.transaction_pool.payouts;
+        match self {
+            TransactionPoolPayoutsAction::BatchAdd {
+                batch_id, payments, ..
+            } => {
+                state.config.payout_pub_key.is_some()
+                    && *batch_id == payouts.next_batch_id()
+                    && !payouts.batches().any(|batch| batch.id == *batch_id)
+                    && !payments.is_empty()
+            }
+            TransactionPoolPayoutsAction::NonceFetchInit => {
+                state.config.payout_pub_key.is_some()
+                    && state.transaction_pool.best_tip_hash.is_some()
+                    && payouts.should_fetch_nonce()
+            }
+            TransactionPoolPayoutsAction::NonceFetchSuccess { .. } => {
+                matches!(payouts.nonce(), super::PayoutNonceState::Fetching)
+            }
+            TransactionPoolPayoutsAction::InjectNext => {
+                // Same conditions as for the verification of the candidates.
+                !state.block_producer.is_producing()
+                    && !state.ledger.write.is_busy()
+                    && state.transaction_pool.best_tip_hash.is_some()
+                    && payouts.should_inject_next(time)
+            }
+            TransactionPoolPayoutsAction::Signed { batch_id, .. }
+            | TransactionPoolPayoutsAction::InjectError { batch_id, .. } => payouts
+                .batch(*batch_id)
+                .is_some_and(|batch| batch.is_injecting()),
+            // the pool may respond after the timeout.
+            TransactionPoolPayoutsAction::InjectResult { batch_id, .. } => payouts
+                .batch(*batch_id)
+                .is_some_and(|batch| batch.is_awaiting_result()),
+            TransactionPoolPayoutsAction::CheckTimeouts => payouts.has_injection_timed_out(time),
+        }
+    }
+}
+
+use crate::transaction_pool::TransactionPoolAction;
+
+impl From<TransactionPoolPayoutsAction> for crate::Action {
+    fn from(value: TransactionPoolPayoutsAction) -> Self {
+        Self::TransactionPool(TransactionPoolAction::Payouts(value))
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ledger::{scan_state::currency::Nonce, Account, AccountId, TokenId};
+use mina_signer::CompressedPubKey;
+use openmina_core::{
+    bug_condition,
+    transaction::{TransactionPoolMessageSource, TransactionWithHash},
+};
+use redux::callback;
+
+use crate::transaction_pool::{
+    PendingId, TransactionPoolAction, TransactionPoolEffectfulAction, TransactionPoolState,
+};
+
+use super::{TransactionPoolPayoutsAction, TransactionPoolPayoutsActionWithMetaRef};
+
+impl TransactionPoolState {
+    /// Payouts need the pool too, to find out the nonce of the payout
+    /// account, so they are reduced with the whole pool state.
+    pub(in crate::transaction_pool) fn payouts_reducer(
+        mut state_context: crate::Substate<Self>,
+        action: TransactionPoolPayoutsActionWithMetaRef<'_>,
+    ) {
+        let Ok(state) = state_context.get_substate_mut() else {
+            // TODO: log or propagate
+            return;
+        };
+        let (action, meta) = action.split();
+
+        match action {
+            TransactionPoolPayoutsAction::BatchAdd {
+                batch_id: _,
+                fee,
+                memo,
+                max_per_sec,
+                payments,
+            } => {
+                state.payouts.batch_add(
+                    meta.time(),
+                    *fee,
+                    memo.clone(),
+                    *max_per_sec,
+                    payments.clone(),
+                );
+            }
+            TransactionPoolPayoutsAction::NonceFetchInit => {
+                let Some(ledger_hash) = state.best_tip_hash.clone() else {
+                    return;
+                };
+                state.payouts.nonce_fetch_pending();
+
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let Some(pub_key) = global_state
+                    .config
+                    .payout_pub_key
+                    .clone()
+                    .and_then(|pub_key| CompressedPubKey::try_from(pub_key).ok())
+                else {
+                    bug_condition!("payout key must be set and valid to fetch its nonce");
+                    return;
+                };
+                dispatcher.push(TransactionPoolEffectfulAction::FetchAccounts {
+                    account_ids: BTreeSet::from([AccountId::new(pub_key, TokenId::default())]),
+                    ledger_hash,
+                    on_result: callback!(on_payouts_nonce_fetch((accounts: BTreeMap<AccountId, Account>, id: Option<PendingId>, from_source: TransactionPoolMessageSource)) -> crate::Action {
+                        TransactionPoolPayoutsAction::NonceFetchSuccess { accounts }
+                    }),
+                    pending_id: None,
+                    from_source: TransactionPoolMessageSource::None,
+                });
+            }
+            TransactionPoolPayoutsAction::NonceFetchSuccess { accounts } => {
+                // Commands of the payout account already in the pool (e.g.
+                // sent before the restart) must be skipped over.
+                let pending = state.pool.get_pending_amount_and_nonce();
+                let nonce = accounts
+                    .iter()
+                    .map(|(account_id, account)| {
+                        let after_pending = pending
+                            .get(account_id)
+                            .and_then(|(nonce, _)| *nonce)
+                            .map(|nonce| nonce.succ());
+                        after_pending.map_or(account.nonce, |nonce| nonce.max(account.nonce))
+                    })
+                    .next()
+                    // Account doesn't exist yet, payments will be rejected.
+                    .unwrap_or(Nonce::from_u32(0));
+                state.payouts.nonce_fetched(nonce);
+                let pool = &state.pool;
+                state
+                    .payouts
+                    .resolve_timed_out(|hash| pool.get(hash).is_some());
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransactionPoolPayoutsAction::InjectNext);
+            }
+            TransactionPoolPayoutsAction::InjectNext => {
+                let Some((batch_id, fee, memo, payments)) = state.payouts.inject_next(meta.time())
+                else {
+                    return;
+                };
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransactionPoolEffectfulAction::PayoutsSign {
+                    batch_id,
+                    fee,
+                    memo,
+                    payments,
+                });
+            }
+            TransactionPoolPayoutsAction::Signed { batch_id, commands } => {
+                // Payments which can't be hashed stay unresolved until
+                // they time out.
+                let commands = commands
+                    .iter()
+                    .filter_map(|(index, command)| {
+                        let command = TransactionWithHash::try_new(command.clone()).ok()?;
+                        Some((*index, command))
+                    })
+                    .collect::<Vec<_>>();
+                let hashes = commands
+                    .iter()
+                    .map(|(index, command)| (*index, command.hash().clone()))
+                    .collect::<Vec<_>>();
+                state.payouts.signed(*batch_id, &hashes);
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransactionPoolAction::StartVerify {
+                    commands: commands.into_iter().map(|(_, command)| command).collect(),
+                    from_source: TransactionPoolMessageSource::payout(*batch_id),
+                });
+            }
+            TransactionPoolPayoutsAction::InjectResult {
+                batch_id,
+                accepted,
+                rejected,
+            } => {
+                state.payouts.inject_result(*batch_id, accepted, rejected);
+            }
+            TransactionPoolPayoutsAction::InjectError { batch_id, error } => {
+                state.payouts.inject_failed(*batch_id, error);
+            }
+            TransactionPoolPayoutsAction::CheckTimeouts => {
+                let Some(batch_id) = state.payouts.active_batch().map(|batch| batch.id) else {
+                    return;
+                };
+                state.payouts.inject_timed_out(batch_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,630 @@
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
+
+use ledger::scan_state::{
+    currency::{Amount, Fee, Nonce},
+    transaction_logic::{
+        signed_command::{Body, PaymentPayload, SignedCommand, SignedCommandPayload},
+        transaction_union_payload::TransactionUnionPayload,
+        Memo,
+    },
+};
+use mina_p2p_messages::v2::{MinaBaseUserCommandStableV2, TransactionHash};
+use mina_signer::{Keypair, Signer};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::account::{AccountPublicKey, AccountSecretKey};
+
+pub type PayoutBatchId = u64;
+
+/// Payments injected into the pool per second, if not set in the request.
+pub const PAYOUTS_DEFAULT_MAX_PER_SEC: u32 = 10;
+/// Payments that weren't accepted nor rejected by the pool in this time
+/// are considered failed.
+pub const PAYOUTS_INJECT_TIMEOUT: Duration = Duration::from_secs(60);
+const PAYOUTS_MAX_BATCH_SIZE: usize = 10_000;
+/// Finished batches kept for the status queries, the oldest ones are
+/// pruned when a new batch is added.
+const PAYOUTS_MAX_FINISHED_BATCHES: usize = 32;
+
+/// Batches of payments sent from the payout account, e.g. the rewards of
+/// the delegators.
+///
+/// Payments are signed and injected into the pool by the node, in order,
+/// so the nonces are assigned sequentially and never race. Batches are
+/// processed one after another, in the order they were added.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransactionPoolPayoutsState {
+    batches: BTreeMap<PayoutBatchId, PayoutBatch>,
+    next_batch_id: PayoutBatchId,
+    nonce: PayoutNonceState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub enum PayoutNonceState {
+    /// Nonce must be fetched before injecting next payments, either
+    /// because nothing was injected yet, or because some payment failed.
+    #[default]
+    Unknown,
+    Fetching,
+    Known(Nonce),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PayoutBatch {
+    pub id: PayoutBatchId,
+    pub created_at: Timestamp,
+    pub fee: Fee,
+    pub memo: String,
+    pub max_per_sec: u32,
+    pub last_injected_at: Option<Timestamp>,
+    pub items: Vec<PayoutItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PayoutItem {
+    pub receiver: AccountPublicKey,
+    pub amount: Amount,
+    pub status: PayoutItemStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum PayoutItemStatus {
+    Queued,
+    /// Signed (once `hash` is set) and being injected into the pool.
+    Injecting {
+        time: Timestamp,
+        nonce: Nonce,
+        hash: Option<TransactionHash>,
+    },
+    Accepted {
+        nonce: Nonce,
+        hash: TransactionHash,
+    },
+    Rejected {
+        error: String,
+    },
+    /// Pool didn't respond in time. The payment might still be in the
+    /// pool, so its nonce isn't reused, and the status is updated once
+    /// the payment is found in the pool or the pool responds.
+    TimedOut {
+        nonce: Nonce,
+        hash: Option<TransactionHash>,
+    },
+}
+
+/// Payment to be signed with the payout key.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PayoutPayment {
+    pub index: usize,
+    pub receiver: AccountPublicKey,
+    pub amount: Amount,
+    pub nonce: Nonce,
+}
+
+impl PayoutPayment {
+    pub fn sign(
+        &self,
+        secret_key: &AccountSecretKey,
+        fee: Fee,
+        memo: &str,
+    ) -> Result<MinaBaseUserCommandStableV2, String> {
+        let receiver_pk = self
+            .receiver
+            .clone()
+            .try_into()
+            .map_err(|_| format!("invalid receiver: {}", self.receiver))?;
+        let memo = Memo::from_str(memo).map_err(|_| format!("invalid memo: {memo}"))?;
+        let payload = SignedCommandPayload::create(
+            fee,
+            secret_key.public_key_compressed(),
+            self.nonce,
+            None,
+            memo,
+            Body::Payment(PaymentPayload {
+                receiver_pk,
+                amount: self.amount,
+            }),
+        );
+
+        let payload_to_sign = TransactionUnionPayload::of_user_command_payload(&payload);
+        let mut signer = mina_signer::create_legacy(crate::account::signer_network_id());
+        let signature = signer.sign(&Keypair::from(secret_key.clone()), &payload_to_sign);
+
+        let command = SignedCommand {
+            payload,
+            signer: secret_key.public_key_compressed(),
+            signature,
+        };
+        Ok(MinaBaseUserCommandStableV2::SignedCommand(command.into()))
+    }
+}
+
+impl PayoutBatch {
+    pub fn queued_count(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.status, PayoutItemStatus::Queued))
+            .count()
+    }
+
+    pub fn is_injecting(&self) -> bool {
+        self.items
+            .iter()
+            .any(|item| matches!(item.status, PayoutItemStatus::Injecting { .. }))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.items.iter().all(|item| {
+            matches!(
+                item.status,
+                PayoutItemStatus::Accepted { .. }
+                    | PayoutItemStatus::Rejected { .. }
+                    | PayoutItemStatus::TimedOut { .. }
+            )
+        })
+    }
+
+    /// Whether some payments are waiting for the result from the pool,
+    /// including those which timed out.
+    pub fn is_awaiting_result(&self) -> bool {
+        self.items.iter().any(|item| {
+            matches!(
+                item.status,
+                PayoutItemStatus::Injecting { .. } | PayoutItemStatus::TimedOut { .. }
+            )
+        })
+    }
+
+    fn is_injection_timed_out(&self, now: Timestamp) -> bool {
+        self.items.iter().any(|item| match item.status {
+            PayoutItemStatus::Injecting { time, .. } => {
+                now.checked_sub(time).unwrap_or_default() >= PAYOUTS_INJECT_TIMEOUT
+            }
+            _ => false,
+        })
+    }
+
+    fn can_inject_at(&self, now: Timestamp) -> bool {
+        self.last_injected_at
+            .is_none_or(|last| now.checked_sub(last).unwrap_or_default() >= Duration::from_secs(1))
+    }
+}
+
+impl TransactionPoolPayoutsState {
+    pub fn batch(&self, id: PayoutBatchId) -> Option<&PayoutBatch> {
+        self.batches.get(&id)
+    }
+
+    pub fn batches(&self) -> impl Iterator<Item = &PayoutBatch> {
+        self.batches.values()
+    }
+
+    pub fn next_batch_id(&self) -> PayoutBatchId {
+        self.next_batch_id
+    }
+
+    pub fn nonce(&self) -> PayoutNonceState {
+        self.nonce
+    }
+
+    /// Batch which is being processed, the oldest one with payments left.
+    pub fn active_batch(&self) -> Option<&PayoutBatch> {
+        self.batches.values().find(|batch| !batch.is_finished())
+    }
+
+    pub fn should_fetch_nonce(&self) -> bool {
+        matches!(self.nonce, PayoutNonceState::Unknown)
+            && self
+                .active_batch()
+                .is_some_and(|batch| !batch.is_injecting() && batch.queued_count() > 0)
+    }
+
+    pub fn should_inject_next(&self, now: Timestamp) -> bool {
+        matches!(self.nonce, PayoutNonceState::Known(_))
+            && self.active_batch().is_some_and(|batch| {
+                !batch.is_injecting() && batch.queued_count() > 0 && batch.can_inject_at(now)
+            })
+    }
+
+    pub fn has_injection_timed_out(&self, now: Timestamp) -> bool {
+        self.active_batch()
+            .is_some_and(|batch| batch.is_injection_timed_out(now))
+    }
+
+    pub(super) fn batch_add(
+        &mut self,
+        time: Timestamp,
+        fee: Fee,
+        memo: String,
+        max_per_sec: u32,
+        payments: Vec<(AccountPublicKey, Amount)>,
+    ) -> PayoutBatchId {
+        let id = self.next_batch_id;
+        self.next_batch_id = self.next_batch_id.saturating_add(1);
+        let items = payments
+            .into_iter()
+            .map(|(receiver, amount)| PayoutItem {
+                receiver,
+                amount,
+                status: PayoutItemStatus::Queued,
+            })
+            .collect();
+        self.batches.insert(
+            id,
+            PayoutBatch {
+                id,
+                created_at: time,
+                fee,
+                memo,
+                max_per_sec,
+                last_injected_at: None,
+                items,
+            },
+        );
+        self.prune_finished();
+        id
+    }
+
+    /// Removes the oldest finished batches over the limit. Batches
+    /// waiting for the result of timed out payments are kept.
+    fn prune_finished(&mut self) {
+        let finished = self
+            .batches
+            .values()
+            .filter(|batch| batch.is_finished() && !batch.is_awaiting_result())
+            .map(|batch| batch.id)
+            .collect::<Vec<_>>();
+        let excess = finished.len().saturating_sub(PAYOUTS_MAX_FINISHED_BATCHES);
+        for id in finished.into_iter().take(excess) {
+            self.batches.remove(&id);
+        }
+    }
+
+    pub(super) fn nonce_fetch_pending(&mut self) {
+        self.nonce = PayoutNonceState::Fetching;
+    }
+
+    pub(super) fn nonce_fetched(&mut self, nonce: Nonce) {
+        self.nonce = PayoutNonceState::Known(nonce);
+    }
+
+    /// Assigns sequential nonces to the next queued payments of the active
+    /// batch, returns them along with the fee and memo of the batch.
+    pub(super) fn inject_next(
+        &mut self,
+        time: Timestamp,
+    ) -> Option<(PayoutBatchId, Fee, String, Vec<PayoutPayment>)> {
+        let PayoutNonceState::Known(mut nonce) = self.nonce else {
+            return None;
+        };
+        let batch = self
+            .batches
+            .values_mut()
+            .find(|batch| !batch.is_finished())?;
+        batch.last_injected_at = Some(time);
+
+        let limit = batch.max_per_sec.max(1) as usize;
+        let payments = batch
+            .items
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, item)| matches!(item.status, PayoutItemStatus::Queued))
+            .take(limit)
+            .map(|(index, item)| {
+                item.status = PayoutItemStatus::Injecting {
+                    time,
+                    nonce,
+                    hash: None,
+                };
+                let payment = PayoutPayment {
+                    index,
+                    receiver: item.receiver.clone(),
+                    amount: item.amount,
+                    nonce,
+                };
+                nonce = nonce.succ();
+                payment
+            })
+            .collect();
+        self.nonce = PayoutNonceState::Known(nonce);
+        Some((batch.id, batch.fee, batch.memo.clone(), payments))
+    }
+
+    pub(super) fn signed(&mut self, batch_id: PayoutBatchId, hashes: &[(usize, TransactionHash)]) {
+        let Some(batch) = self.batches.get_mut(&batch_id) else {
+            return;
+        };
+        for (index, new_hash) in hashes {
+            if let Some(PayoutItem {
+                status: PayoutItemStatus::Injecting { hash, .. },
+                ..
+            }) = batch.items.get_mut(*index)
+            {
+                *hash = Some(new_hash.clone());
+            }
+        }
+    }
+
+    /// Updates the statuses of the injected payments with the result from
+    /// the pool.
+    pub(super) fn inject_result(
+        &mut self,
+        batch_id: PayoutBatchId,
+        accepted: &[TransactionHash],
+        rejected: &[(TransactionHash, String)],
+    ) {
+        let Some(batch) = self.batches.get_mut(&batch_id) else {
+            return;
+        };
+        for item in &mut batch.items {
+            let (PayoutItemStatus::Injecting {
+                nonce,
+                hash: Some(hash),
+                ..
+            }
+            | PayoutItemStatus::TimedOut {
+                nonce,
+                hash: Some(hash),
+            }) = &item.status
+            else {
+                continue;
+            };
+            if accepted.contains(hash) {
+                item.status = PayoutItemStatus::Accepted {
+                    nonce: *nonce,
+                    hash: hash.clone(),
+                };
+            } else if let Some((_, error)) = rejected.iter().find(|(h, _)| h == hash) {
+                item.status = PayoutItemStatus::Rejected {
+                    error: error.clone(),
+                };
+            }
+        }
+        if !rejected.is_empty() {
+            // Payments after the rejected one would leave a nonce gap.
+            self.nonce = PayoutNonceState::Unknown;
+        }
+    }
+
+    /// Marks the payments of the batch being injected as timed out. The
+    /// nonce is fetched again, skipping over the ones in the pool.
+    pub(super) fn inject_timed_out(&mut self, batch_id: PayoutBatchId) {
+        if let Some(batch) = self.batches.get_mut(&batch_id) {
+            for item in &mut batch.items {
+                if let PayoutItemStatus::Injecting { nonce, hash, .. } = &item.status {
+                    item.status = PayoutItemStatus::TimedOut {
+                        nonce: *nonce,
+                        hash: hash.clone(),
+                    };
+                }
+            }
+        }
+        self.nonce = PayoutNonceState::Unknown;
+    }
+
+    /// Timed out payments found in the pool are accepted.
+    pub(super) fn resolve_timed_out(&mut self, in_pool: impl Fn(&TransactionHash) -> bool) {
+        let items = self.batches.values_mut().flat_map(|batch| &mut batch.items);
+        for item in items {
+            if let PayoutItemStatus::TimedOut {
+                nonce,
+                hash: Some(hash),
+            } = &item.status
+            {
+                if in_pool(hash) {
+                    item.status = PayoutItemStatus::Accepted {
+                        nonce: *nonce,
+                        hash: hash.clone(),
+                    };
+                }
+            }
+        }
+    }
+
+    /// Marks all the payments of the batch being injected as failed.
+    pub(super) fn inject_failed(&mut self, batch_id: PayoutBatchId, error: &str) {
+        if let Some(batch) = self.batches.get_mut(&batch_id) {
+            for item in &mut batch.items {
+                if matches!(item.status, PayoutItemStatus::Injecting { .. }) {
+                    item.status = PayoutItemStatus::Rejected {
+                        error: error.to_owned(),
+                    };
+                }
+            }
+        }
+        self.nonce = PayoutNonceState::Unknown;
+    }
+}
+
+/// Parses the payments of the batch, one `receiver,amount` pair per line,
+/// with the amount in nanomina. Empty lines, lines starting with `#` and
+/// the `receiver,amount` header are skipped.
+pub fn payouts_parse_csv(csv: &str) -> Result<Vec<(AccountPublicKey, Amount)>, String> {
+    let payments = csv
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .filter(|(_, line)| line.replace(' ', "") != "receiver,amount")
+        .map(|(line_no, line)| {
+            let (receiver, amount) = line
+                .split_once(',')
+                .ok_or_else(|| format!("line {line_no}: expected `receiver,amount`"))?;
+            let receiver = receiver
+                .trim()
+                .parse::<AccountPublicKey>()
+                .map_err(|err| format!("line {line_no}: invalid receiver: {err}"))?;
+            let amount = amount
+                .trim()
+                .parse::<u64>()
+                .map_err(|err| format!("line {line_no}: invalid amount: {err}"))?;
+            Ok((receiver, Amount::from_u64(amount)))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if payments.is_empty() {
+        return Err("no payments in the batch".to_owned());
+    }
+    if payments.len() > PAYOUTS_MAX_BATCH_SIZE {
+        return Err(format!(
+            "too many payments in the batch: {}, max: {PAYOUTS_MAX_BATCH_SIZE}",
+            payments.len()
+        ));
+    }
+    Ok(payments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receiver(i: u64) -> AccountPublicKey {
+        AccountSecretKey::deterministic(i).public_key()
+    }
+
+    fn hash(i: u8) -> TransactionHash {
+        TransactionHash::from(&[i; 32])
+    }
+
+    #[test]
+    fn parse_csv() {
+        let csv = format!(
+            "receiver,amount\n# comment\n\n{},1000\n {} , 2000 \n",
+            receiver(0),
+            receiver(1)
+        );
+        let payments = payouts_parse_csv(&csv).unwrap();
+        assert_eq!(payments.len(), 2);
+        assert_eq!(payments[1].0, receiver(1));
+        assert_eq!(payments[1].1, Amount::from_u64(2000));
+
+        let err = payouts_parse_csv(&format!("{},abc", receiver(0))).unwrap_err();
+        assert!(err.starts_with("line 1: invalid amount"));
+        assert!(payouts_parse_csv("receiver,amount\n").is_err());
+    }
+
+    #[test]
+    fn sequential_nonces() {
+        let mut state = TransactionPoolPayoutsState::default();
+        let payments = (0..5).map(|i| (receiver(i), Amount::from_u64(1))).collect();
+        let id = state.batch_add(
+            Timestamp::ZERO,
+            Fee::from_u64(1),
+            String::new(),
+            2,
+            payments,
+        );
+        assert!(state.should_fetch_nonce());
+        assert!(!state.should_inject_next(Timestamp::ZERO));
+
+        state.nonce_fetched(Nonce::from_u32(7));
+        let (_, _, _, payments) = state.inject_next(Timestamp::ZERO).unwrap();
+        let nonces = payments
+            .iter()
+            .map(|p| p.nonce.as_u32())
+            .collect::<Vec<_>>();
+        assert_eq!(nonces, [7, 8]);
+        // waiting for the result from the pool.
+        assert!(!state.should_inject_next(Timestamp::new(2_000_000_000)));
+
+        state.signed(id, &[(0, hash(0)), (1, hash(1))]);
+        state.inject_result(id, &[hash(0), hash(1)], &[]);
+        // rate limited.
+        assert!(!state.should_inject_next(Timestamp::new(500_000_000)));
+        let now = Timestamp::new(1_000_000_000);
+        let (_, _, _, payments) = state.inject_next(now).unwrap();
+        assert_eq!(payments[0].nonce.as_u32(), 9);
+
+        // rejection resets the nonce.
+        state.signed(id, &[(2, hash(2)), (3, hash(3))]);
+        state.inject_result(id, &[hash(2)], &[(hash(3), "InvalidNonce".to_owned())]);
+        assert!(state.should_fetch_nonce());
+        let batch = state.batch(id).unwrap();
+        assert!(matches!(
+            batch.items[3].status,
+            PayoutItemStatus::Rejected { .. }
+        ));
+        assert_eq!(batch.queued_count(), 1);
+    }
+
+    fn add_batch(state: &mut TransactionPoolPayoutsState, count: u64) -> PayoutBatchId {
+        let payments = (0..count)
+            .map(|i| (receiver(i), Amount::from_u64(1)))
+            .collect();
+        state.batch_add(
+            Timestamp::ZERO,
+            Fee::from_u64(1),
+            String::new(),
+            10,
+            payments,
+        )
+    }
+
+    #[test]
+    fn timed_out_payments_resolved_from_pool() {
+        let mut state = TransactionPoolPayoutsState::default();
+        let id = add_batch(&mut state, 3);
+        state.nonce_fetched(Nonce::from_u32(0));
+        state.inject_next(Timestamp::ZERO).unwrap();
+        state.signed(id, &[(0, hash(0)), (1, hash(1)), (2, hash(2))]);
+
+        let timeout = Timestamp::new(PAYOUTS_INJECT_TIMEOUT.as_nanos() as u64);
+        assert!(state.has_injection_timed_out(timeout));
+        state.inject_timed_out(id);
+        assert!(matches!(state.nonce(), PayoutNonceState::Unknown));
+        let batch = state.batch(id).unwrap();
+        assert!(batch.is_finished() && batch.is_awaiting_result());
+
+        // late response from the pool.
+        state.inject_result(id, &[hash(0)], &[]);
+        // the other one made it into the pool.
+        state.resolve_timed_out(|h| *h == hash(1));
+        let statuses = state
+            .batch(id)
+            .unwrap()
+            .items
+            .iter()
+            .map(|item| match item.status {
+                PayoutItemStatus::Accepted { .. } => "accepted",
+                PayoutItemStatus::TimedOut { .. } => "timed out",
+                _ => "other",
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, ["accepted", "accepted", "timed out"]);
+    }
+
+    #[test]
+    fn finished_batches_pruned() {
+        let mut state = TransactionPoolPayoutsState::default();
+        let finish = |state: &mut TransactionPoolPayoutsState, id| {
+            state.nonce_fetched(Nonce::from_u32(0));
+            state.inject_next(Timestamp::ZERO).unwrap();
+            state.inject_failed(id, "failed");
+        };
+        let first = add_batch(&mut state, 1);
+        finish(&mut state, first);
+        let timed_out = add_batch(&mut state, 1);
+        state.nonce_fetched(Nonce::from_u32(0));
+        state.inject_next(Timestamp::ZERO).unwrap();
+        state.signed(timed_out, &[(0, hash(0))]);
+        state.inject_timed_out(timed_out);
+
+        let total = PAYOUTS_MAX_FINISHED_BATCHES.saturating_add(2);
+        for _ in 0..total {
+            let id = add_batch(&mut state, 1);
+            finish(&mut state, id);
+        }
+        let unfinished = add_batch(&mut state, 1);
+
+        assert!(state.batch(first).is_none());
+        assert!(state.batch(timed_out).is_some());
+        assert!(state.batch(unfinished).is_some());
+        let finished = state
+            .batches()
+            .filter(|batch| batch.is_finished() && !batch.is_awaiting_result())
+            .count();
+        assert_eq!(finished, PAYOUTS_MAX_FINISHED_BATCHES);
+    }
+}
@@ -0,0 +1,7 @@
+mod transaction_pool_sponsor_state;
+pub use transaction_pool_sponsor_state::*;
+
+mod transaction_pool_sponsor_actions;
+pub use transaction_pool_sponsor_actions::*;
+
+mod transaction_pool_sponsor_reducer;
@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use ledger::{Account, AccountId};
+use mina_p2p_messages::v2::{
+    MinaBaseUserCommandStableV2, MinaBaseZkappCommandTStableV1WireStableV1,
+};
+use openmina_core::{requests::RpcId, transaction::TransactionPoolMessageSource, ActionEvent};
+use serde::{Deserialize, Serialize};
+
+pub type TransactionPoolSponsorActionWithMeta = redux::ActionWithMeta<TransactionPoolSponsorAction>;
+pub type TransactionPoolSponsorActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a TransactionPoolSponsorAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = info)]
+pub enum TransactionPoolSponsorAction {
+    /// Pays the fee of the zkApp `command` from the sponsor account, if
+    /// the `origin` is within the limits.
+    #[action_event(fields(display(rpc_id), origin))]
+    Init {
+        rpc_id: RpcId,
+        origin: String,
+        command: Box<MinaBaseZkappCommandTStableV1WireStableV1>,
+    },
+    /// Nonce of the sponsor account is fetched for the rpc request the
+    /// `from_source` points to.
+    #[action_event(level = trace)]
+    NonceFetchSuccess {
+        from_source: TransactionPoolMessageSource,
+        accounts: BTreeMap<AccountId, Account>,
+    },
+    #[action_event(fields(display(rpc_id)))]
+    Signed {
+        rpc_id: RpcId,
+        command: MinaBaseUserCommandStableV2,
+    },
+    #[action_event(level = warn, fields(display(rpc_id), error))]
+    Error { rpc_id: RpcId, error: String },
+}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolSponsorAction {
+    fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
+        let sponsor = &state.transaction_pool.sponsor;
+        match self {
+            TransactionPoolSponsorAction::Init { rpc_id, .. } => {
+                sponsor.config().is_some() && !sponsor.contains(rpc_id)
+            }
+            TransactionPoolSponsorAction::NonceFetchSuccess { from_source, .. } => {
+                matches!(from_source, TransactionPoolMessageSource::Rpc { id } if sponsor.contains(id))
+            }
+            TransactionPoolSponsorAction::Signed { rpc_id, .. }
+            | TransactionPoolSponsorAction::Error { rpc_id, .. } => sponsor.contains(rpc_id),
+        }
+    }
+}
+
+use crate::transaction_pool::TransactionPoolAction;
+
+impl From<TransactionPoolSponsorAction> for crate::Action {
+    fn from(value: TransactionPoolSponsorAction) -> Self {
+        Self::TransactionPool(TransactionPoolAction::Sponsor(value))
+    }
+}
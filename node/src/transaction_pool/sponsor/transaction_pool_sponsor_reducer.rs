@@ -0,0 +1,154 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ledger::{scan_state::currency::Nonce, Account, AccountId, TokenId};
+use mina_signer::CompressedPubKey;
+use openmina_core::{
+    bug_condition,
+    transaction::{TransactionPoolMessageSource, TransactionWithHash},
+};
+use redux::callback;
+
+use crate::{
+    rpc::RpcAction,
+    transaction_pool::{
+        PendingId, TransactionPoolAction, TransactionPoolEffectfulAction, TransactionPoolState,
+    },
+};
+
+use super::{
+    fee_sponsor_validate, FeeSponsorRequest, TransactionPoolSponsorAction,
+    TransactionPoolSponsorActionWithMetaRef,
+};
+
+impl TransactionPoolState {
+    /// Like the payouts, sponsoring needs the pool to find out the nonce
+    /// of the sponsor account.
+    pub(in crate::transaction_pool) fn sponsor_reducer(
+        mut state_context: crate::Substate<Self>,
+        action: TransactionPoolSponsorActionWithMetaRef<'_>,
+    ) {
+        let Ok(state) = state_context.get_substate_mut() else {
+            // TODO: log or propagate
+            return;
+        };
+        let (action, meta) = action.split();
+
+        match action {
+            TransactionPoolSponsorAction::Init {
+                rpc_id,
+                origin,
+                command,
+            } => {
+                state.sponsor.insert(
+                    *rpc_id,
+                    FeeSponsorRequest {
+                        origin: origin.clone(),
+                        command: command.clone(),
+                    },
+                );
+                let Some(config) = state.sponsor.config() else {
+                    bug_condition!("fee sponsor must be configured to sponsor commands");
+                    return;
+                };
+                let Ok(pub_key) = CompressedPubKey::try_from(config.pub_key.clone()) else {
+                    bug_condition!("invalid fee sponsor key {}", config.pub_key);
+                    return;
+                };
+                let limits = config.limits.clone();
+                let ledger_hash = state.best_tip_hash.clone();
+                let reserved = match ledger_hash {
+                    Some(ledger_hash) => fee_sponsor_validate(command)
+                        .and_then(|fee| state.sponsor.reserve(&limits, origin, fee, meta.time()))
+                        .map(|_| ledger_hash),
+                    None => Err("transaction pool isn't ready yet".to_owned()),
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                let ledger_hash = match reserved {
+                    Ok(ledger_hash) => ledger_hash,
+                    Err(error) => {
+                        dispatcher.push(TransactionPoolSponsorAction::Error {
+                            rpc_id: *rpc_id,
+                            error,
+                        });
+                        return;
+                    }
+                };
+                dispatcher.push(TransactionPoolEffectfulAction::FetchAccounts {
+                    account_ids: BTreeSet::from([AccountId::new(pub_key, TokenId::default())]),
+                    ledger_hash,
+                    on_result: callback!(on_sponsor_nonce_fetch((accounts: BTreeMap<AccountId, Account>, id: Option<PendingId>, from_source: TransactionPoolMessageSource)) -> crate::Action {
+                        TransactionPoolSponsorAction::NonceFetchSuccess { from_source, accounts }
+                    }),
+                    pending_id: None,
+                    from_source: TransactionPoolMessageSource::rpc(*rpc_id),
+                });
+            }
+            TransactionPoolSponsorAction::NonceFetchSuccess {
+                from_source,
+                accounts,
+            } => {
+                let TransactionPoolMessageSource::Rpc { id: rpc_id } = from_source else {
+                    bug_condition!("sponsor nonce must be fetched for the rpc request");
+                    return;
+                };
+                // Same as for the payouts, commands of the sponsor already
+                // in the pool must be skipped over.
+                let pending = state.pool.get_pending_amount_and_nonce();
+                let fetched = accounts
+                    .iter()
+                    .map(|(account_id, account)| {
+                        let after_pending = pending
+                            .get(account_id)
+                            .and_then(|(nonce, _)| *nonce)
+                            .map(|nonce| nonce.succ());
+                        after_pending.map_or(account.nonce, |nonce| nonce.max(account.nonce))
+                    })
+                    .next()
+                    // Account doesn't exist, command will be rejected.
+                    .unwrap_or(Nonce::from_u32(0));
+                let nonce = state.sponsor.next_nonce(fetched, meta.time());
+                let Some(request) = state.sponsor.pending(rpc_id) else {
+                    return;
+                };
+                let command = request.command.clone();
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransactionPoolEffectfulAction::SponsorSign {
+                    rpc_id: *rpc_id,
+                    command,
+                    nonce,
+                });
+            }
+            TransactionPoolSponsorAction::Signed { rpc_id, command } => {
+                let Ok(command) = TransactionWithHash::try_new(command.clone()) else {
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(TransactionPoolSponsorAction::Error {
+                        rpc_id: *rpc_id,
+                        error: "sponsored command can't be hashed".to_owned(),
+                    });
+                    return;
+                };
+                state.sponsor.remove(rpc_id);
+
+                // Response is sent once the pool accepts or rejects the
+                // command, same as for the injected ones.
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransactionPoolAction::StartVerify {
+                    commands: vec![command],
+                    from_source: TransactionPoolMessageSource::rpc(*rpc_id),
+                });
+            }
+            TransactionPoolSponsorAction::Error { rpc_id, error } => {
+                state.sponsor.remove(rpc_id);
+                state.sponsor.release_nonce();
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcAction::TransactionInjectFailure {
+                    rpc_id: *rpc_id,
+                    errors: vec![error.clone()],
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,398 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use ledger::{
+    generators::zkapp_command_builder::get_transaction_commitments,
+    scan_state::{
+        currency::{Fee, Magnitude, Nonce},
+        transaction_logic::zkapp_command::{Control, ZkAppCommand},
+    },
+};
+use mina_p2p_messages::v2::{
+    MinaBaseUserCommandStableV2, MinaBaseZkappCommandTStableV1WireStableV1,
+};
+use mina_signer::{Keypair, Signer};
+use openmina_core::requests::RpcId;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::account::{AccountPublicKey, AccountSecretKey};
+
+/// Nonce assigned to a sponsored command is trusted over the one in the
+/// pool for this long, so that the commands sponsored in quick succession,
+/// before the previous one reaches the pool, don't get the same nonce.
+pub const FEE_SPONSOR_NONCE_RESERVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Max number of the origins whose usage is tracked at once. New origins
+/// are refused until the window of some of the tracked ones passes.
+pub const FEE_SPONSOR_ORIGINS_MAX: usize = 1024;
+
+/// Account which pays the fees of the zkApp commands submitted by the
+/// dapps, see [`TransactionPoolSponsorState`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeeSponsorConfig {
+    pub pub_key: AccountPublicKey,
+    #[serde(default)]
+    pub limits: FeeSponsorLimits,
+}
+
+/// Limits of the sponsoring, per origin of the request, and for all the
+/// origins together.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeeSponsorLimits {
+    /// Max fee of a single command.
+    pub max_fee: Fee,
+    /// Max number of the commands sponsored within the `window`.
+    pub max_commands: u32,
+    /// Max sum of the fees sponsored within the `window`.
+    pub max_fees: Fee,
+    /// Max sum of the fees sponsored for all the origins within the `window`.
+    pub max_total_fees: Fee,
+    pub window: Duration,
+}
+
+impl Default for FeeSponsorLimits {
+    fn default() -> Self {
+        Self {
+            max_fee: Fee::from_u64(100_000_000),
+            max_commands: 10,
+            max_fees: Fee::from_u64(1_000_000_000),
+            max_total_fees: Fee::from_u64(10_000_000_000),
+            window: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// Fees paid for the origin in the current window.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeeSponsorUsage {
+    pub window_start: Timestamp,
+    pub commands: u32,
+    pub fees: Fee,
+}
+
+impl FeeSponsorUsage {
+    fn new(now: Timestamp) -> Self {
+        Self {
+            window_start: now,
+            commands: 0,
+            fees: Fee::zero(),
+        }
+    }
+
+    fn is_expired(&self, window: Duration, now: Timestamp) -> bool {
+        now.checked_sub(self.window_start).unwrap_or_default() >= window
+    }
+}
+
+/// zkApp command waiting for the sponsor's nonce and signature.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeeSponsorRequest {
+    /// Name of the dapp, whose api key authenticated the request.
+    pub origin: String,
+    pub command: Box<MinaBaseZkappCommandTStableV1WireStableV1>,
+}
+
+/// zkApp commands, whose fee is paid by the sponsor account of the node,
+/// so that dapps can subsidize the fees of their users.
+///
+/// Fee payer of the command is replaced with the sponsor and signed with
+/// the sponsor key, the account updates are kept as they are. Usage is
+/// counted once the command is accepted for sponsoring, even if the pool
+/// rejects it later. Origins are the dapps authenticated by the http
+/// server, the sum of the fees of all of them is capped as well.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransactionPoolSponsorState {
+    config: Option<FeeSponsorConfig>,
+    origins: BTreeMap<String, FeeSponsorUsage>,
+    total: Option<FeeSponsorUsage>,
+    pending: BTreeMap<RpcId, FeeSponsorRequest>,
+    reserved_nonce: Option<(Nonce, Timestamp)>,
+}
+
+impl TransactionPoolSponsorState {
+    pub fn new(config: Option<FeeSponsorConfig>) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Sponsor config, `None` if sponsoring is disabled.
+    pub fn config(&self) -> Option<&FeeSponsorConfig> {
+        self.config.as_ref()
+    }
+
+    pub fn usage(&self, origin: &str) -> Option<&FeeSponsorUsage> {
+        self.origins.get(origin)
+    }
+
+    /// Usage of all the origins together in the current window.
+    pub fn total_usage(&self) -> Option<&FeeSponsorUsage> {
+        self.total.as_ref()
+    }
+
+    pub fn pending(&self, rpc_id: &RpcId) -> Option<&FeeSponsorRequest> {
+        self.pending.get(rpc_id)
+    }
+
+    pub fn contains(&self, rpc_id: &RpcId) -> bool {
+        self.pending.contains_key(rpc_id)
+    }
+
+    pub fn insert(&mut self, rpc_id: RpcId, request: FeeSponsorRequest) {
+        self.pending.insert(rpc_id, request);
+    }
+
+    pub fn remove(&mut self, rpc_id: &RpcId) -> Option<FeeSponsorRequest> {
+        self.pending.remove(rpc_id)
+    }
+
+    /// Counts the `fee` to the usage of the `origin` and to the total
+    /// usage, if both are within the `limits`. Usage of the origins whose
+    /// window has passed is dropped.
+    pub fn reserve(
+        &mut self,
+        limits: &FeeSponsorLimits,
+        origin: &str,
+        fee: Fee,
+        now: Timestamp,
+    ) -> Result<(), String> {
+        self.origins
+            .retain(|_, usage| !usage.is_expired(limits.window, now));
+        if self
+            .total
+            .as_ref()
+            .is_some_and(|total| total.is_expired(limits.window, now))
+        {
+            self.total = None;
+        }
+
+        if fee > limits.max_fee {
+            return Err(format!(
+                "fee {} is above the sponsored max {}",
+                fee.as_u64(),
+                limits.max_fee.as_u64()
+            ));
+        }
+        if !self.origins.contains_key(origin) && self.origins.len() >= FEE_SPONSOR_ORIGINS_MAX {
+            return Err("too many origins are sponsored at the moment".to_owned());
+        }
+        let total_fees = self
+            .total
+            .as_ref()
+            .map_or(Fee::zero(), |total| total.fees)
+            .checked_add(&fee)
+            .filter(|fees| *fees <= limits.max_total_fees)
+            .ok_or_else(|| "sponsored fees budget is exhausted".to_owned())?;
+        let usage = self
+            .origins
+            .entry(origin.to_owned())
+            .or_insert_with(|| FeeSponsorUsage::new(now));
+        if usage.commands >= limits.max_commands {
+            return Err(format!(
+                "origin `{origin}` reached the sponsored commands limit"
+            ));
+        }
+        let fees = usage
+            .fees
+            .checked_add(&fee)
+            .filter(|fees| *fees <= limits.max_fees)
+            .ok_or_else(|| format!("origin `{origin}` reached the sponsored fees limit"))?;
+        usage.commands = usage.commands.saturating_add(1);
+        usage.fees = fees;
+
+        let total = self.total.get_or_insert_with(|| FeeSponsorUsage::new(now));
+        total.commands = total.commands.saturating_add(1);
+        total.fees = total_fees;
+        Ok(())
+    }
+
+    /// Nonce of the next sponsored command, given the one `fetched` from
+    /// the best tip ledger and the pool.
+    pub fn next_nonce(&mut self, fetched: Nonce, now: Timestamp) -> Nonce {
+        let reserved = self
+            .reserved_nonce
+            .filter(|(_, time)| {
+                now.checked_sub(*time).unwrap_or_default() < FEE_SPONSOR_NONCE_RESERVE_TIMEOUT
+            })
+            .map(|(nonce, _)| nonce);
+        let nonce = reserved.map_or(fetched, |reserved| reserved.max(fetched));
+        self.reserved_nonce = Some((nonce.succ(), now));
+        nonce
+    }
+
+    /// Nonce must be taken from the pool again, as the command with the
+    /// reserved one didn't make it there.
+    pub fn release_nonce(&mut self) {
+        self.reserved_nonce = None;
+    }
+}
+
+/// Fee of the zkApp command, if it can be sponsored.
+///
+/// Account updates authorized by the signature of the full commitment
+/// can't be, as the commitment changes with the fee payer.
+pub fn fee_sponsor_validate(
+    command: &MinaBaseZkappCommandTStableV1WireStableV1,
+) -> Result<Fee, String> {
+    let command =
+        ZkAppCommand::try_from(command).map_err(|err| format!("invalid zkapp command: {err:?}"))?;
+    let signs_full_commitment = command.account_updates.exists(|account_update| {
+        account_update.body.use_full_commitment
+            && matches!(account_update.authorization, Control::Signature(_))
+    });
+    if signs_full_commitment {
+        return Err(
+            "account updates signed with the full commitment can't be sponsored".to_owned(),
+        );
+    }
+    Ok(command.fee_payer.body.fee)
+}
+
+/// Replaces the fee payer of the zkApp `command` with the sponsor account
+/// and signs it with the sponsor key.
+pub fn fee_sponsor_sign(
+    command: &MinaBaseZkappCommandTStableV1WireStableV1,
+    secret_key: &AccountSecretKey,
+    nonce: Nonce,
+) -> Result<MinaBaseUserCommandStableV2, String> {
+    let mut command =
+        ZkAppCommand::try_from(command).map_err(|err| format!("invalid zkapp command: {err:?}"))?;
+    command.fee_payer.body.public_key = secret_key.public_key_compressed();
+    command.fee_payer.body.nonce = nonce;
+
+    let (_, full_commitment) = get_transaction_commitments(&command);
+    let mut signer = mina_signer::create_kimchi(crate::account::signer_network_id());
+    command.fee_payer.authorization =
+        signer.sign(&Keypair::from(secret_key.clone()), &full_commitment);
+
+    Ok(MinaBaseUserCommandStableV2::ZkappCommand((&command).into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_sponsor_limits() {
+        let secs = |s: u64| Timestamp::new(s * 1_000_000_000);
+        let limits = FeeSponsorLimits {
+            max_fee: Fee::from_u64(10),
+            max_commands: 2,
+            max_fees: Fee::from_u64(15),
+            max_total_fees: Fee::from_u64(100),
+            window: Duration::from_secs(60),
+        };
+        let mut state = TransactionPoolSponsorState::default();
+
+        assert!(state
+            .reserve(&limits, "a", Fee::from_u64(11), secs(0))
+            .is_err());
+        assert!(state
+            .reserve(&limits, "a", Fee::from_u64(10), secs(0))
+            .is_ok());
+        // Sum of the fees is over the limit.
+        assert!(state
+            .reserve(&limits, "a", Fee::from_u64(6), secs(1))
+            .is_err());
+        assert!(state
+            .reserve(&limits, "a", Fee::from_u64(5), secs(1))
+            .is_ok());
+        // Number of the commands is over the limit.
+        assert!(state
+            .reserve(&limits, "a", Fee::from_u64(0), secs(2))
+            .is_err());
+        // Other origins have their own limits.
+        assert!(state
+            .reserve(&limits, "b", Fee::from_u64(10), secs(2))
+            .is_ok());
+        // Window has passed.
+        assert!(state
+            .reserve(&limits, "a", Fee::from_u64(10), secs(60))
+            .is_ok());
+        assert_eq!(state.usage("a").map(|usage| usage.commands), Some(1));
+    }
+
+    #[test]
+    fn test_fee_sponsor_total_limits() {
+        let secs = |s: u64| Timestamp::new(s * 1_000_000_000);
+        let limits = FeeSponsorLimits {
+            max_fee: Fee::from_u64(10),
+            max_commands: 10,
+            max_fees: Fee::from_u64(100),
+            max_total_fees: Fee::from_u64(25),
+            window: Duration::from_secs(60),
+        };
+        let mut state = TransactionPoolSponsorState::default();
+
+        assert!(state
+            .reserve(&limits, "a", Fee::from_u64(10), secs(0))
+            .is_ok());
+        assert!(state
+            .reserve(&limits, "b", Fee::from_u64(10), secs(1))
+            .is_ok());
+        // Fresh origin doesn't get a fresh budget.
+        assert!(state
+            .reserve(&limits, "c", Fee::from_u64(10), secs(2))
+            .is_err());
+        assert!(state.usage("c").is_none());
+        assert!(state
+            .reserve(&limits, "c", Fee::from_u64(5), secs(2))
+            .is_ok());
+        assert_eq!(
+            state.total_usage().map(|usage| usage.fees),
+            Some(Fee::from_u64(25))
+        );
+        // Window of the total usage has passed.
+        assert!(state
+            .reserve(&limits, "d", Fee::from_u64(10), secs(60))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_fee_sponsor_origins_max() {
+        let limits = FeeSponsorLimits {
+            max_total_fees: Fee::from_u64(u64::MAX),
+            ..Default::default()
+        };
+        let mut state = TransactionPoolSponsorState::default();
+        for i in 0..FEE_SPONSOR_ORIGINS_MAX {
+            assert!(state
+                .reserve(&limits, &i.to_string(), Fee::zero(), Timestamp::ZERO)
+                .is_ok());
+        }
+        assert!(state
+            .reserve(&limits, "new", Fee::zero(), Timestamp::ZERO)
+            .is_err());
+        // Known origins are still served.
+        assert!(state
+            .reserve(&limits, "0", Fee::zero(), Timestamp::ZERO)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_fee_sponsor_next_nonce() {
+        let secs = |s: u64| Timestamp::new(s * 1_000_000_000);
+        let mut state = TransactionPoolSponsorState::default();
+
+        assert_eq!(
+            state.next_nonce(Nonce::from_u32(3), secs(0)),
+            Nonce::from_u32(3)
+        );
+        // Previous command isn't in the pool yet.
+        assert_eq!(
+            state.next_nonce(Nonce::from_u32(3), secs(1)),
+            Nonce::from_u32(4)
+        );
+        // Reservation has timed out.
+        assert_eq!(
+            state.next_nonce(Nonce::from_u32(3), secs(40)),
+            Nonce::from_u32(3)
+        );
+        state.release_nonce();
+        assert_eq!(
+            state.next_nonce(Nonce::from_u32(2), secs(41)),
+            Nonce::from_u32(2)
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use ledger::{
-    scan_state::transaction_logic::valid,
+    scan_state::{
+        currency::{Fee, Nonce},
+        transaction_logic::valid,
+    },
     transaction_pool::{
         diff::{self, BestTipDiff, DiffVerified},
         ValidCommandWithHash,
@@ -9,17 +12,24 @@ use ledger::{
     Account, AccountId,
 };
 use mina_p2p_messages::{
+    bigint::BigInt,
     list::List,
     v2::{self},
 };
 use openmina_core::{
+    requests::RpcId,
     transaction::{TransactionPoolMessageSource, TransactionWithHash},
     ActionEvent,
 };
 use redux::Callback;
 use serde::{Deserialize, Serialize};
 
-use super::{candidate::TransactionPoolCandidateAction, PendingId};
+use super::{
+    candidate::TransactionPoolCandidateAction,
+    payouts::{PayoutBatchId, PayoutPayment, TransactionPoolPayoutsAction},
+    sponsor::TransactionPoolSponsorAction,
+    PendingId, TransactionPoolRemoveReason,
+};
 
 pub type TransactionPoolActionWithMeta = redux::ActionWithMeta<TransactionPoolAction>;
 pub type TransactionPoolActionWithMetaRef<'a> = redux::ActionWithMeta<&'a TransactionPoolAction>;
@@ -28,6 +38,8 @@ pub type TransactionPoolActionWithMetaRef<'a> = redux::ActionWithMeta<&'a Transa
 #[action_event(level = info)]
 pub enum TransactionPoolAction {
     Candidate(TransactionPoolCandidateAction),
+    Payouts(TransactionPoolPayoutsAction),
+    Sponsor(TransactionPoolSponsorAction),
     StartVerify {
         commands: List<TransactionWithHash>,
         from_source: TransactionPoolMessageSource,
@@ -45,12 +57,43 @@ pub enum TransactionPoolAction {
     VerifyError {
         errors: Vec<String>,
     },
+    /// Commands not admitted to the pool, as their fee payers exceeded
+    /// the [`ledger::transaction_pool::FeePayerRateLimits`].
+    #[action_event(level = info, fields(count, debug(fee_payers)))]
+    FeePayersRateLimited {
+        fee_payers: BTreeSet<AccountId>,
+        count: usize,
+    },
+    /// Accounts touched by the applied block.
+    #[action_event(level = trace)]
+    AccountsTouched {
+        accounts: BTreeSet<AccountId>,
+    },
     BestTipChanged {
         best_tip_hash: v2::LedgerHash,
+        /// Whether the new best tip extends the previous one, in which
+        /// case only the senders touched by the applied blocks are
+        /// revalidated, instead of the entire pool.
+        is_extension: bool,
     },
     BestTipChangedWithAccounts {
         accounts: BTreeMap<AccountId, Account>,
     },
+    #[action_event(fields(senders = senders.len()))]
+    RevalidateTouched {
+        best_tip_hash: v2::LedgerHash,
+        senders: BTreeSet<AccountId>,
+    },
+    RevalidateTouchedWithAccounts {
+        accounts: BTreeMap<AccountId, Account>,
+        pending_id: PendingId,
+    },
+    /// Commands dropped from the pool.
+    #[action_event(level = info, fields(count = hashes.len(), debug(reason)))]
+    CommandsDropped {
+        hashes: Vec<v2::TransactionHash>,
+        reason: TransactionPoolRemoveReason,
+    },
     ApplyVerifiedDiff {
         best_tip_hash: v2::LedgerHash,
         diff: DiffVerified,
@@ -76,6 +119,15 @@ pub enum TransactionPoolAction {
         is_local: bool,
     },
     CollectTransactionsByFee,
+    /// Captures the current contents of the pool, to be compared later.
+    SnapshotCapture,
+    /// Registers the verification key for the pool admission of the
+    /// zkApp commands, which are proved against it before it's deployed.
+    VkRegister {
+        verification_key: v2::MinaBaseVerificationKeyWireStableV1,
+        hash: BigInt,
+        expires_at: redux::Timestamp,
+    },
     #[action_event(level = trace)]
     P2pSendAll,
     #[action_event(level = debug)]
@@ -88,6 +140,8 @@ impl redux::EnablingCondition<crate::State> for TransactionPoolAction {
     fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
         match self {
             TransactionPoolAction::Candidate(a) => a.is_enabled(state, time),
+            TransactionPoolAction::Payouts(a) => a.is_enabled(state, time),
+            TransactionPoolAction::Sponsor(a) => a.is_enabled(state, time),
             TransactionPoolAction::StartVerify { commands, .. } => {
                 !commands.is_empty()
                     && commands
@@ -123,6 +177,11 @@ impl redux::EnablingCondition<crate::State> for TransactionPoolAction {
             TransactionPoolAction::Rebroadcast {
                 accepted, rejected, ..
             } => !(accepted.is_empty() && rejected.is_empty()),
+            TransactionPoolAction::AccountsTouched { accounts } => {
+                !accounts.is_empty() && !state.transition_frontier.light
+            }
+            TransactionPoolAction::RevalidateTouched { senders, .. } => !senders.is_empty(),
+            TransactionPoolAction::CommandsDropped { hashes, .. } => !hashes.is_empty(),
             _ => true,
         }
     }
@@ -143,6 +202,20 @@ pub enum TransactionPoolEffectfulAction {
         pending_id: Option<PendingId>,
         from_source: TransactionPoolMessageSource,
     },
+    /// Signs the payments of the payout batch with the payout key.
+    PayoutsSign {
+        batch_id: PayoutBatchId,
+        fee: Fee,
+        memo: String,
+        payments: Vec<PayoutPayment>,
+    },
+    /// Replaces the fee payer of the zkApp command with the sponsor and
+    /// signs it with the sponsor key.
+    SponsorSign {
+        rpc_id: RpcId,
+        command: Box<v2::MinaBaseZkappCommandTStableV1WireStableV1>,
+        nonce: Nonce,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for TransactionPoolEffectfulAction {}
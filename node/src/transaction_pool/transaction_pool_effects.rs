@@ -3,13 +3,18 @@ use std::collections::BTreeMap;
 use crate::ledger::LedgerService;
 use crate::snark::SnarkStore;
 
-use super::TransactionPoolEffectfulAction;
+use super::payouts::TransactionPoolPayoutsAction;
+use super::sponsor::{fee_sponsor_sign, TransactionPoolSponsorAction};
+use super::{
+    TransactionPoolEffectfulAction, TransactionPoolPayoutsService, TransactionPoolSponsorService,
+};
 
 impl TransactionPoolEffectfulAction {
     pub fn effects<Store, S>(self, store: &mut Store)
     where
         Store: SnarkStore<S>,
-        Store::Service: LedgerService,
+        Store::Service:
+            LedgerService + TransactionPoolPayoutsService + TransactionPoolSponsorService,
     {
         match self {
             TransactionPoolEffectfulAction::FetchAccounts {
@@ -51,6 +56,59 @@ impl TransactionPoolEffectfulAction {
 
                 store.dispatch_callback(on_result, (accounts, pending_id, from_source));
             }
+            TransactionPoolEffectfulAction::PayoutsSign {
+                batch_id,
+                fee,
+                memo,
+                payments,
+            } => {
+                let signed = store.service().with_payout_keypair(|sk| {
+                    payments
+                        .iter()
+                        .map(|payment| Ok((payment.index, payment.sign(sk, fee, &memo)?)))
+                        .collect::<Result<Vec<_>, String>>()
+                });
+                match signed {
+                    Some(Ok(commands)) => {
+                        store.dispatch(TransactionPoolPayoutsAction::Signed { batch_id, commands });
+                    }
+                    Some(Err(error)) => {
+                        store.dispatch(TransactionPoolPayoutsAction::InjectError {
+                            batch_id,
+                            error,
+                        });
+                    }
+                    None => {
+                        store.dispatch(TransactionPoolPayoutsAction::InjectError {
+                            batch_id,
+                            error: "payout key not set".to_owned(),
+                        });
+                    }
+                }
+            }
+            TransactionPoolEffectfulAction::SponsorSign {
+                rpc_id,
+                command,
+                nonce,
+            } => {
+                let signed = store
+                    .service()
+                    .with_sponsor_keypair(|sk| fee_sponsor_sign(&command, sk, nonce));
+                match signed {
+                    Some(Ok(command)) => {
+                        store.dispatch(TransactionPoolSponsorAction::Signed { rpc_id, command });
+                    }
+                    Some(Err(error)) => {
+                        store.dispatch(TransactionPoolSponsorAction::Error { rpc_id, error });
+                    }
+                    None => {
+                        store.dispatch(TransactionPoolSponsorAction::Error {
+                            rpc_id,
+                            error: "fee sponsor key not set".to_owned(),
+                        });
+                    }
+                }
+            }
         }
     }
 }
@@ -2,9 +2,9 @@ use ledger::{
     scan_state::transaction_logic::{valid, GenericCommand, UserCommand},
     transaction_pool::{
         diff::{self, DiffVerified},
-        transaction_hash, ApplyDecision, TransactionPoolErrors,
+        transaction_hash, ApplyDecision, TransactionError, TransactionPoolErrors,
     },
-    Account, AccountId,
+    Account, AccountId, VerificationKey, VerificationKeyWire,
 };
 use openmina_core::{
     bug_condition,
@@ -21,8 +21,9 @@ use std::collections::{BTreeMap, BTreeSet};
 use crate::{BlockProducerAction, RpcAction};
 
 use super::{
-    PendingId, TransactionPoolAction, TransactionPoolActionWithMetaRef,
-    TransactionPoolEffectfulAction, TransactionPoolState, TransactionState,
+    payouts::TransactionPoolPayoutsAction, PendingId, TransactionPoolAction,
+    TransactionPoolActionWithMetaRef, TransactionPoolEffectfulAction, TransactionPoolRemoveReason,
+    TransactionPoolState, TransactionState,
 };
 
 impl TransactionPoolState {
@@ -58,6 +59,12 @@ impl TransactionPoolState {
                     meta.with_action(a),
                 );
             }
+            TransactionPoolAction::Payouts(a) => {
+                Self::payouts_reducer(state, meta.with_action(a));
+            }
+            TransactionPoolAction::Sponsor(a) => {
+                Self::sponsor_reducer(state, meta.with_action(a));
+            }
             TransactionPoolAction::StartVerify {
                 commands,
                 from_source,
@@ -101,6 +108,8 @@ impl TransactionPoolState {
                     panic!()
                 };
 
+                substate.pool.prune_registered_vks(meta.time());
+
                 // TODO: Convert those commands only once
                 let Ok(commands) = commands
                     .iter()
@@ -110,7 +119,33 @@ impl TransactionPoolState {
                 else {
                     return;
                 };
-                let diff = diff::Diff { list: commands };
+                let mut diff = diff::Diff { list: commands };
+                let rate_limited = if from_source.is_sender_local() {
+                    Vec::new()
+                } else {
+                    substate
+                        .pool
+                        .rate_limit_fee_payers(&mut diff, accounts, meta.time())
+                };
+                let rate_limited = (!rate_limited.is_empty()).then(|| {
+                    TransactionPoolAction::FeePayersRateLimited {
+                        fee_payers: rate_limited.iter().map(UserCommand::fee_payer).collect(),
+                        count: rate_limited.len(),
+                    }
+                });
+                if diff.list.is_empty() {
+                    let dispatcher = state.into_dispatcher();
+                    if let Some(action) = rate_limited {
+                        dispatcher.push(action);
+                    }
+                    if let TransactionPoolMessageSource::Pubsub { id } = from_source {
+                        dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                            message_id: Some(BroadcastMessageId::MessageId { message_id: *id }),
+                            reason: "Fee payers rate limited".to_owned(),
+                        });
+                    }
+                    return;
+                }
 
                 match substate
                     .pool
@@ -119,6 +154,9 @@ impl TransactionPoolState {
                 {
                     Ok(verifiable) => {
                         let (dispatcher, global_state) = state.into_dispatcher_and_state();
+                        if let Some(action) = rate_limited {
+                            dispatcher.push(action);
+                        }
                         let req_id = global_state.snark.user_command_verify.next_req_id();
 
                         dispatcher.push(SnarkUserCommandVerifyAction::Init {
@@ -145,8 +183,13 @@ impl TransactionPoolState {
                         });
                     }
                     Err(e) => {
-                        let dispatch_errors = |errors: Vec<String>| {
+                        // `ignore` is set when the diff only breaks the local
+                        // policy, so the sender isn't penalized for it.
+                        let dispatch_errors = |errors: Vec<String>, ignore: bool| {
                             let dispatcher = state.into_dispatcher();
+                            if let Some(action) = rate_limited {
+                                dispatcher.push(action);
+                            }
                             dispatcher.push(TransactionPoolAction::VerifyError {
                                 errors: errors.clone(),
                             });
@@ -158,6 +201,15 @@ impl TransactionPoolState {
                                         errors,
                                     });
                                 }
+                                TransactionPoolMessageSource::Pubsub { id } if ignore => {
+                                    dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                                        message_id: Some(BroadcastMessageId::MessageId {
+                                            message_id: *id,
+                                        }),
+                                        reason: "Transaction diff exceeds the pool limits"
+                                            .to_owned(),
+                                    });
+                                }
                                 TransactionPoolMessageSource::Pubsub { id } => {
                                     dispatcher.push(P2pNetworkPubsubAction::RejectMessage {
                                         message_id: Some(BroadcastMessageId::MessageId {
@@ -167,16 +219,29 @@ impl TransactionPoolState {
                                         reason: "Transaction diff rejected".to_owned(),
                                     });
                                 }
+                                TransactionPoolMessageSource::Payout { batch_id } => {
+                                    dispatcher.push(TransactionPoolPayoutsAction::InjectError {
+                                        batch_id: *batch_id,
+                                        error: errors.join(", "),
+                                    });
+                                }
                                 TransactionPoolMessageSource::None => {}
                             }
                         };
                         match e {
                             TransactionPoolErrors::BatchedErrors(errors) => {
+                                // Limits are node-local and stricter than the
+                                // consensus ones, the diff is still valid.
+                                let ignore = errors
+                                    .iter()
+                                    .all(|e| matches!(e, TransactionError::ZkappLimits(_)));
                                 let errors: Vec<_> =
                                     errors.into_iter().map(|e| e.to_string()).collect();
-                                dispatch_errors(errors);
+                                dispatch_errors(errors, ignore);
+                            }
+                            TransactionPoolErrors::LoadingVK(error) => {
+                                dispatch_errors(vec![error], false)
                             }
-                            TransactionPoolErrors::LoadingVK(error) => dispatch_errors(vec![error]),
                             TransactionPoolErrors::Unexpected(es) => {
                                 panic!("{es}")
                             }
@@ -206,10 +271,30 @@ impl TransactionPoolState {
             TransactionPoolAction::VerifyError { .. } => {
                 // just logging the errors
             }
-            TransactionPoolAction::BestTipChanged { best_tip_hash } => {
-                let account_ids = substate.pool.get_accounts_to_revalidate_on_new_best_tip();
+            TransactionPoolAction::FeePayersRateLimited { .. } => {
+                // just logging
+            }
+            TransactionPoolAction::AccountsTouched { accounts } => {
+                substate.accounts_touched.extend(accounts.iter().cloned());
+            }
+            TransactionPoolAction::BestTipChanged {
+                best_tip_hash,
+                is_extension,
+            } => {
+                let touched = std::mem::take(&mut substate.accounts_touched);
                 substate.best_tip_hash = Some(best_tip_hash.clone());
 
+                if *is_extension {
+                    let senders = substate.pool.get_accounts_to_revalidate_touched(&touched);
+                    let dispatcher = state.into_dispatcher();
+                    dispatcher.push(TransactionPoolAction::RevalidateTouched {
+                        best_tip_hash: best_tip_hash.clone(),
+                        senders,
+                    });
+                    return;
+                }
+
+                let account_ids = substate.pool.get_accounts_to_revalidate_on_new_best_tip();
                 let dispatcher = state.into_dispatcher();
                 dispatcher.push(TransactionPoolEffectfulAction::FetchAccounts {
                     account_ids,
@@ -228,10 +313,66 @@ impl TransactionPoolState {
                 {
                     Err(e) => bug_condition!("transaction pool::on_new_best_tip failed: {:?}", e),
                     Ok(dropped) => {
-                        for tx in dropped {
-                            substate.dpool.remove(&tx.hash);
+                        let dispatcher = state.into_dispatcher();
+                        dispatcher.push(TransactionPoolAction::CommandsDropped {
+                            hashes: dropped.into_iter().map(|tx| tx.hash).collect(),
+                            reason: TransactionPoolRemoveReason::Invalidated,
+                        });
+                    }
+                }
+            }
+            TransactionPoolAction::RevalidateTouched {
+                best_tip_hash,
+                senders,
+            } => {
+                let pending_id = substate.make_action_pending(action);
+
+                let dispatcher = state.into_dispatcher();
+                dispatcher.push(TransactionPoolEffectfulAction::FetchAccounts {
+                    account_ids: senders.clone(),
+                    ledger_hash: best_tip_hash.clone(),
+                    on_result: callback!(fetch_for_revalidate_touched((accounts: BTreeMap<AccountId, Account>, id: Option<PendingId>, from_source: TransactionPoolMessageSource)) -> crate::Action {
+                        TransactionPoolAction::RevalidateTouchedWithAccounts {
+                            accounts,
+                            pending_id: id.unwrap(),
                         }
+                    }),
+                    pending_id: Some(pending_id),
+                    from_source: TransactionPoolMessageSource::None,
+                });
+            }
+            TransactionPoolAction::RevalidateTouchedWithAccounts {
+                accounts,
+                pending_id,
+            } => {
+                let Some(TransactionPoolAction::RevalidateTouched { senders, .. }) =
+                    substate.pending_actions.remove(pending_id)
+                else {
+                    bug_condition!("pending action {pending_id} must be `RevalidateTouched`");
+                    return;
+                };
+
+                match substate.pool.on_accounts_touched(
+                    global_slot_from_genesis,
+                    &senders,
+                    accounts,
+                ) {
+                    Err(e) => {
+                        bug_condition!("transaction pool::on_accounts_touched failed: {:?}", e)
                     }
+                    Ok(dropped) => {
+                        let dispatcher = state.into_dispatcher();
+                        dispatcher.push(TransactionPoolAction::CommandsDropped {
+                            hashes: dropped.into_iter().map(|tx| tx.hash).collect(),
+                            reason: TransactionPoolRemoveReason::Invalidated,
+                        });
+                    }
+                }
+            }
+            TransactionPoolAction::CommandsDropped { hashes, reason } => {
+                for hash in hashes {
+                    substate.removals.record(hash, *reason, meta.time());
+                    substate.dpool.remove(hash);
                 }
             }
             TransactionPoolAction::ApplyVerifiedDiff {
@@ -281,6 +422,11 @@ impl TransactionPoolState {
                 ) {
                     Ok((ApplyDecision::Accept, accepted, rejected, dropped)) => {
                         for hash in dropped {
+                            substate.removals.record(
+                                &hash,
+                                TransactionPoolRemoveReason::Evicted,
+                                meta.time(),
+                            );
                             substate.dpool.remove(&hash);
                         }
                         for tx in &accepted {
@@ -320,6 +466,16 @@ impl TransactionPoolState {
                             });
                         }
                     }
+                    (_, TransactionPoolMessageSource::Payout { batch_id }) => {
+                        dispatcher.push(TransactionPoolPayoutsAction::InjectResult {
+                            batch_id,
+                            accepted: accepted.iter().map(|cmd| cmd.hash.clone()).collect(),
+                            rejected: rejected
+                                .iter()
+                                .map(|(cmd, error)| (cmd.hash.clone(), format!("{error:?}")))
+                                .collect(),
+                        });
+                    }
                     (true, TransactionPoolMessageSource::Pubsub { id }) => {
                         dispatcher.push(P2pNetworkPubsubAction::BroadcastValidatedMessage {
                             message_id: BroadcastMessageId::MessageId { message_id: id },
@@ -400,7 +556,7 @@ impl TransactionPoolState {
                 let in_cmds = collect(&account_ids);
                 let uncommitted = collect(&uncommitted);
 
-                if let Err(e) = substate.pool.handle_transition_frontier_diff(
+                match substate.pool.handle_transition_frontier_diff(
                     global_slot_from_genesis,
                     global_slot,
                     &diff,
@@ -408,10 +564,25 @@ impl TransactionPoolState {
                     &in_cmds,
                     &uncommitted,
                 ) {
-                    bug_condition!(
+                    Err(e) => bug_condition!(
                         "transaction pool::handle_transition_frontier_diff failed: {:?}",
                         e
-                    );
+                    ),
+                    Ok(dropped) => {
+                        let dispatcher = state.into_dispatcher();
+                        for (hashes, reason) in [
+                            (dropped.committed, TransactionPoolRemoveReason::Committed),
+                            (
+                                dropped.invalidated,
+                                TransactionPoolRemoveReason::Invalidated,
+                            ),
+                            (dropped.evicted, TransactionPoolRemoveReason::Evicted),
+                            (dropped.expired, TransactionPoolRemoveReason::Expired),
+                        ] {
+                            dispatcher
+                                .push(TransactionPoolAction::CommandsDropped { hashes, reason });
+                        }
+                    }
                 }
             }
             TransactionPoolAction::Rebroadcast {
@@ -437,6 +608,10 @@ impl TransactionPoolState {
                     });
                 }
             }
+            TransactionPoolAction::SnapshotCapture => {
+                let snapshot = substate.snapshot(meta.time());
+                substate.snapshots.insert(snapshot);
+            }
             TransactionPoolAction::CollectTransactionsByFee => {
                 let transaction_capacity =
                     2u64.pow(constraint_constants().transaction_capacity_log_2 as u32);
@@ -453,6 +628,21 @@ impl TransactionPoolState {
                     transactions_by_fee,
                 });
             }
+            TransactionPoolAction::VkRegister {
+                verification_key,
+                hash,
+                expires_at,
+            } => {
+                let (Ok(vk), Ok(hash)) =
+                    (VerificationKey::try_from(verification_key), hash.to_field())
+                else {
+                    bug_condition!("invalid verification key: {hash:?}");
+                    return;
+                };
+                substate
+                    .pool
+                    .register_vk(VerificationKeyWire::with_hash(vk, hash), *expires_at);
+            }
             TransactionPoolAction::P2pSendAll => {
                 let (dispatcher, global_state) = state.into_dispatcher_and_state();
                 for peer_id in global_state.p2p.ready_peers() {
@@ -4,6 +4,7 @@ use crate::snark::{
     user_command_verify::SnarkUserCommandVerifyId, TransactionVerifier, VerifierSRS,
 };
 use ledger::scan_state::transaction_logic::{verifiable, WithStatus};
+use openmina_node_account::AccountSecretKey;
 
 pub trait VerifyUserCommandsService: redux::Service {
     fn verify_init(
@@ -14,3 +15,14 @@ pub trait VerifyUserCommandsService: redux::Service {
         verifier_srs: Arc<VerifierSRS>,
     );
 }
+
+pub trait TransactionPoolPayoutsService: redux::Service {
+    /// Calls `f` with the key the payout batches are signed with, if set.
+    fn with_payout_keypair<T>(&self, f: impl FnOnce(&AccountSecretKey) -> T) -> Option<T>;
+}
+
+pub trait TransactionPoolSponsorService: redux::Service {
+    /// Calls `f` with the key the fee payers of the sponsored zkApp
+    /// commands are signed with, if set.
+    fn with_sponsor_keypair<T>(&self, f: impl FnOnce(&AccountSecretKey) -> T) -> Option<T>;
+}
@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use mina_p2p_messages::v2::{LedgerHash, TransactionHash};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Removal reasons are kept for this many latest removed commands.
+pub const TRANSACTION_POOL_REMOVALS_MAX: usize = 4096;
+/// Oldest snapshot is dropped once there is this many.
+pub const TRANSACTION_POOL_SNAPSHOTS_MAX: usize = 8;
+
+pub type TransactionPoolSnapshotId = u64;
+
+/// Why the command was removed from the pool.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionPoolRemoveReason {
+    /// Included in the block of the new best chain.
+    Committed,
+    /// No longer valid against the best tip ledger: its nonce was used
+    /// (usually by the same command in a block), the balance is too low
+    /// or the permissions changed.
+    Invalidated,
+    /// Replaced by the command with the same nonce and a higher fee, or
+    /// had the lowest fee when the pool was full.
+    Evicted,
+    /// Its `valid_until` slot has passed.
+    Expired,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionPoolRemoval {
+    pub reason: TransactionPoolRemoveReason,
+    pub time: Timestamp,
+}
+
+/// Reasons of the latest removals from the pool.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransactionPoolRemovals {
+    by_hash: BTreeMap<TransactionHash, TransactionPoolRemoval>,
+    order: VecDeque<TransactionHash>,
+}
+
+impl TransactionPoolRemovals {
+    pub fn record(
+        &mut self,
+        hash: &TransactionHash,
+        reason: TransactionPoolRemoveReason,
+        time: Timestamp,
+    ) {
+        let removal = TransactionPoolRemoval { reason, time };
+        if self.by_hash.insert(hash.clone(), removal).is_none() {
+            self.order.push_back(hash.clone());
+        }
+        while self.order.len() > TRANSACTION_POOL_REMOVALS_MAX {
+            if let Some(oldest) = self.order.pop_front() {
+                self.by_hash.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn get(&self, hash: &TransactionHash) -> Option<&TransactionPoolRemoval> {
+        self.by_hash.get(hash)
+    }
+}
+
+/// Commands in the pool at the time of the snapshot.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolSnapshot {
+    pub time: Timestamp,
+    pub best_tip_hash: Option<LedgerHash>,
+    /// Position of the command in the order the commands are included in
+    /// the produced block, `None` if it can't be included yet (e.g. its
+    /// nonce is ahead of the account's).
+    pub commands: BTreeMap<TransactionHash, Option<u32>>,
+}
+
+impl TransactionPoolSnapshot {
+    pub fn new<'a>(
+        time: Timestamp,
+        best_tip_hash: Option<LedgerHash>,
+        all: impl IntoIterator<Item = &'a TransactionHash>,
+        includable: impl IntoIterator<Item = &'a TransactionHash>,
+    ) -> Self {
+        let mut commands = all
+            .into_iter()
+            .map(|hash| (hash.clone(), None))
+            .collect::<BTreeMap<_, _>>();
+        for (position, hash) in includable.into_iter().enumerate() {
+            commands.insert(hash.clone(), Some(position as u32));
+        }
+        Self {
+            time,
+            best_tip_hash,
+            commands,
+        }
+    }
+
+    pub fn info(&self, id: Option<TransactionPoolSnapshotId>) -> TransactionPoolSnapshotInfo {
+        TransactionPoolSnapshotInfo {
+            id,
+            time: self.time,
+            best_tip_hash: self.best_tip_hash.clone(),
+            size: self.commands.len(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolSnapshotInfo {
+    /// `None` for the current pool contents.
+    pub id: Option<TransactionPoolSnapshotId>,
+    pub time: Timestamp,
+    pub best_tip_hash: Option<LedgerHash>,
+    pub size: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolSnapshotDiff {
+    pub from: TransactionPoolSnapshotInfo,
+    pub to: TransactionPoolSnapshotInfo,
+    pub added: Vec<TransactionHash>,
+    pub removed: Vec<TransactionPoolSnapshotRemoved>,
+    /// Commands whose position in the block inclusion order changed.
+    pub reordered: Vec<TransactionPoolSnapshotReordered>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolSnapshotRemoved {
+    pub hash: TransactionHash,
+    /// `None` if the reason is no longer known.
+    pub removal: Option<TransactionPoolRemoval>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolSnapshotReordered {
+    pub hash: TransactionHash,
+    pub from: Option<u32>,
+    pub to: Option<u32>,
+}
+
+impl TransactionPoolSnapshotDiff {
+    pub fn new(
+        (from_id, from): (Option<TransactionPoolSnapshotId>, &TransactionPoolSnapshot),
+        (to_id, to): (Option<TransactionPoolSnapshotId>, &TransactionPoolSnapshot),
+        removals: &TransactionPoolRemovals,
+    ) -> Self {
+        let added = to
+            .commands
+            .keys()
+            .filter(|hash| !from.commands.contains_key(hash))
+            .cloned()
+            .collect();
+        let removed = from
+            .commands
+            .keys()
+            .filter(|hash| !to.commands.contains_key(hash))
+            .map(|hash| TransactionPoolSnapshotRemoved {
+                hash: hash.clone(),
+                // Reason of a later removal, if the command was added back.
+                removal: removals
+                    .get(hash)
+                    .filter(|removal| removal.time >= from.time)
+                    .copied(),
+            })
+            .collect();
+        let reordered = from
+            .commands
+            .iter()
+            .filter_map(|(hash, from_position)| {
+                let to_position = to.commands.get(hash)?;
+                (from_position != to_position).then(|| TransactionPoolSnapshotReordered {
+                    hash: hash.clone(),
+                    from: *from_position,
+                    to: *to_position,
+                })
+            })
+            .collect();
+
+        Self {
+            from: from.info(from_id),
+            to: to.info(to_id),
+            added,
+            removed,
+            reordered,
+        }
+    }
+}
+
+/// Snapshots captured on request, to be compared later.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransactionPoolSnapshots {
+    next_id: TransactionPoolSnapshotId,
+    snapshots: BTreeMap<TransactionPoolSnapshotId, TransactionPoolSnapshot>,
+}
+
+impl TransactionPoolSnapshots {
+    /// Id of the snapshot captured next.
+    pub fn next_id(&self) -> TransactionPoolSnapshotId {
+        self.next_id
+    }
+
+    pub fn insert(&mut self, snapshot: TransactionPoolSnapshot) -> TransactionPoolSnapshotId {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.snapshots.insert(id, snapshot);
+        while self.snapshots.len() > TRANSACTION_POOL_SNAPSHOTS_MAX {
+            self.snapshots.pop_first();
+        }
+        id
+    }
+
+    pub fn get(&self, id: TransactionPoolSnapshotId) -> Option<&TransactionPoolSnapshot> {
+        self.snapshots.get(&id)
+    }
+
+    pub fn list(&self) -> impl Iterator<Item = TransactionPoolSnapshotInfo> + '_ {
+        self.snapshots
+            .iter()
+            .map(|(id, snapshot)| snapshot.info(Some(*id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(i: u64) -> TransactionHash {
+        let mut bytes = [0; 32];
+        bytes[..8].copy_from_slice(&i.to_le_bytes());
+        TransactionHash::from(&bytes)
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let t = |s: u64| Timestamp::new(s * 1_000_000_000);
+        let hashes = (0..5).map(hash).collect::<Vec<_>>();
+
+        let from = TransactionPoolSnapshot::new(
+            t(1),
+            None,
+            &hashes[..4],
+            [&hashes[0], &hashes[1], &hashes[2]],
+        );
+        let mut removals = TransactionPoolRemovals::default();
+        // Before the snapshot, so it isn't the reason.
+        removals.record(&hashes[3], TransactionPoolRemoveReason::Expired, t(0));
+        removals.record(&hashes[0], TransactionPoolRemoveReason::Committed, t(2));
+        let to = TransactionPoolSnapshot::new(
+            t(3),
+            None,
+            &hashes[1..],
+            [&hashes[2], &hashes[1], &hashes[4]],
+        );
+
+        let diff = TransactionPoolSnapshotDiff::new((Some(0), &from), (None, &to), &removals);
+        assert_eq!(diff.from.size, 4);
+        assert_eq!(diff.to.id, None);
+        assert_eq!(diff.added, vec![hashes[4].clone()]);
+
+        let removed = diff
+            .removed
+            .iter()
+            .map(|removed| (&removed.hash, removed.removal.map(|r| r.reason)))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(
+            removed[&hashes[0]],
+            Some(TransactionPoolRemoveReason::Committed)
+        );
+        assert_eq!(removed[&hashes[3]], None);
+
+        let reordered = diff
+            .reordered
+            .iter()
+            .map(|r| (&r.hash, (r.from, r.to)))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(reordered.len(), 2);
+        assert_eq!(reordered[&hashes[1]], (Some(1), Some(0)));
+        assert_eq!(reordered[&hashes[2]], (Some(2), Some(1)));
+    }
+
+    #[test]
+    fn test_removals_limit() {
+        let mut removals = TransactionPoolRemovals::default();
+        let time = Timestamp::ZERO;
+        for i in 0..=TRANSACTION_POOL_REMOVALS_MAX as u64 {
+            removals.record(&hash(i), TransactionPoolRemoveReason::Evicted, time);
+        }
+        assert!(removals.get(&hash(0)).is_none());
+        assert!(removals
+            .get(&hash(TRANSACTION_POOL_REMOVALS_MAX as u64))
+            .is_some());
+    }
+}
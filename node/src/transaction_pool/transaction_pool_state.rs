@@ -1,29 +1,50 @@
 use ledger::{
     scan_state::{
         currency::{Amount, Nonce, Slot},
-        transaction_logic::valid::UserCommand,
+        transaction_logic::{valid::UserCommand, zkapp_command::ZkAppCommand},
     },
     transaction_pool::{Config, ValidCommandWithHash},
     AccountId,
 };
 use mina_p2p_messages::v2::{self, TransactionHash};
-use openmina_core::{consensus::ConsensusConstants, distributed_pool::DistributedPool};
+use openmina_core::{
+    consensus::ConsensusConstants, distributed_pool::DistributedPool, transaction::Transaction,
+};
+use p2p::channels::rpc::TransactionPoolSummary;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use super::{candidate::TransactionPoolCandidatesState, TransactionPoolAction};
+use super::{
+    candidate::TransactionPoolCandidatesState,
+    payouts::TransactionPoolPayoutsState,
+    sponsor::{FeeSponsorConfig, TransactionPoolSponsorState},
+    TransactionPoolAction, TransactionPoolRemovals, TransactionPoolSnapshot,
+    TransactionPoolSnapshotDiff, TransactionPoolSnapshotId, TransactionPoolSnapshots,
+};
 
 pub(super) type PendingId = u32;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TransactionPoolState {
     pub candidates: TransactionPoolCandidatesState,
+    pub payouts: TransactionPoolPayoutsState,
+    #[serde(default)]
+    pub sponsor: TransactionPoolSponsorState,
     // TODO(binier): ideally this and `.pool` should be merged together.
     pub(super) dpool: DistributedPool<TransactionState, v2::TransactionHash>,
     pub(super) pool: ledger::transaction_pool::TransactionPool,
     pub(super) pending_actions: BTreeMap<PendingId, TransactionPoolAction>,
     pub(super) pending_id: PendingId,
     pub(super) best_tip_hash: Option<v2::LedgerHash>,
+    /// Accounts touched by the blocks applied since the best tip changed
+    /// last time, whose commands are revalidated once it changes again.
+    #[serde(default)]
+    pub(super) accounts_touched: BTreeSet<AccountId>,
+    /// Why the commands were removed, for debugging the stuck commands.
+    #[serde(default)]
+    pub(super) removals: TransactionPoolRemovals,
+    #[serde(default)]
+    pub(super) snapshots: TransactionPoolSnapshots,
     /// For debug only
     #[serde(skip)]
     pub(super) file: Option<std::fs::File>,
@@ -45,25 +66,39 @@ impl Clone for TransactionPoolState {
     fn clone(&self) -> Self {
         Self {
             candidates: self.candidates.clone(),
+            payouts: self.payouts.clone(),
+            sponsor: self.sponsor.clone(),
             dpool: self.dpool.clone(),
             pool: self.pool.clone(),
             pending_actions: self.pending_actions.clone(),
             pending_id: self.pending_id,
             best_tip_hash: self.best_tip_hash.clone(),
+            accounts_touched: self.accounts_touched.clone(),
+            removals: self.removals.clone(),
+            snapshots: self.snapshots.clone(),
             file: None,
         }
     }
 }
 
 impl TransactionPoolState {
-    pub fn new(config: Config, consensus_constants: &ConsensusConstants) -> Self {
+    pub fn new(
+        config: Config,
+        fee_sponsor: Option<FeeSponsorConfig>,
+        consensus_constants: &ConsensusConstants,
+    ) -> Self {
         Self {
             candidates: Default::default(),
+            payouts: Default::default(),
+            sponsor: TransactionPoolSponsorState::new(fee_sponsor),
             dpool: Default::default(),
             pool: ledger::transaction_pool::TransactionPool::new(config, consensus_constants),
             pending_actions: Default::default(),
             pending_id: 0,
             best_tip_hash: None,
+            accounts_touched: Default::default(),
+            removals: Default::default(),
+            snapshots: Default::default(),
             file: None,
         }
     }
@@ -96,10 +131,93 @@ impl TransactionPoolState {
         self.pool.get_all_transactions()
     }
 
+    pub fn zkapp_commands(&self) -> impl Iterator<Item = &ZkAppCommand> {
+        self.pool.iter().filter_map(|cmd| match &cmd.data {
+            UserCommand::SignedCommand(_) => None,
+            UserCommand::ZkAppCommand(cmd) => Some(&cmd.zkapp_command),
+        })
+    }
+
+    /// Page of the pool contents (in the propagation order) starting at
+    /// the `offset`, served to the peers syncing their pool with us.
+    pub fn summary_page(&self, offset: u64, limit: u8) -> TransactionPoolSummary {
+        let (transactions, _, last_index) =
+            self.dpool.next_messages_to_send((offset, limit), |state| {
+                let tx = self.get(&state.hash)?.clone().forget();
+                // TODO(binier): avoid conversion
+                Some((&Transaction::from(&tx)).into())
+            });
+        let next_offset = if transactions.len() < limit as usize {
+            None
+        } else {
+            Some(last_index + 1)
+        };
+        TransactionPoolSummary {
+            transactions: transactions.into_iter().collect(),
+            next_offset,
+        }
+    }
+
     pub fn get_pending_amount_and_nonce(&self) -> HashMap<AccountId, (Option<Nonce>, Amount)> {
         self.pool.get_pending_amount_and_nonce()
     }
 
+    /// Nonce range of the sender's commands in the pool, see
+    /// [`ledger::transaction_pool::TransactionPool::sender_nonce_range`].
+    pub fn sender_nonce_range(&self, sender: &AccountId) -> Option<(Nonce, Nonce)> {
+        self.pool.sender_nonce_range(sender)
+    }
+
+    /// Ledger of the best tip the pool was last updated to.
+    pub fn best_tip_hash(&self) -> Option<&v2::LedgerHash> {
+        self.best_tip_hash.as_ref()
+    }
+
+    /// Current contents of the pool.
+    pub fn snapshot(&self, time: redux::Timestamp) -> TransactionPoolSnapshot {
+        let all = self.get_all_transactions();
+        let includable = self.list_includable_transactions(usize::MAX);
+        TransactionPoolSnapshot::new(
+            time,
+            self.best_tip_hash.clone(),
+            all.iter().map(|cmd| &cmd.hash),
+            includable.iter().map(|cmd| &cmd.hash),
+        )
+    }
+
+    pub fn snapshots(&self) -> &TransactionPoolSnapshots {
+        &self.snapshots
+    }
+
+    /// Compares the captured snapshot `from` with the snapshot `to`, or
+    /// with the current contents of the pool if `to` is `None`.
+    pub fn snapshot_diff(
+        &self,
+        from: TransactionPoolSnapshotId,
+        to: Option<TransactionPoolSnapshotId>,
+        now: redux::Timestamp,
+    ) -> Result<TransactionPoolSnapshotDiff, String> {
+        let get = |id| {
+            self.snapshots
+                .get(id)
+                .ok_or_else(|| format!("snapshot {id} not found"))
+        };
+        let from_snapshot = get(from)?;
+        let current;
+        let to_snapshot = match to {
+            Some(id) => get(id)?,
+            None => {
+                current = self.snapshot(now);
+                &current
+            }
+        };
+        Ok(TransactionPoolSnapshotDiff::new(
+            (Some(from), from_snapshot),
+            (to, to_snapshot),
+            &self.removals,
+        ))
+    }
+
     fn next_pending_id(&mut self) -> PendingId {
         let id = self.pending_id;
         self.pending_id = self.pending_id.wrapping_add(1);
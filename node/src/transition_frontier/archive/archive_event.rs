@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rpc::{RpcId, RpcLedgerAccountsAtBlockResponse};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ArchiveEvent {
+    AccountsAtBlock(RpcId, RpcLedgerAccountsAtBlockResponse),
+}
+
+impl std::fmt::Display for ArchiveEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Archive, ")?;
+
+        match self {
+            Self::AccountsAtBlock(rpc_id, response) => {
+                write!(f, "AccountsAtBlock, {rpc_id}, ")?;
+                match response {
+                    Ok(accounts) => write!(f, "{}, Ok", accounts.block_hash),
+                    Err(_) => write!(f, "Err"),
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,14 @@
-use crate::ledger::write::BlockApplyResult;
+use crate::{
+    ledger::write::BlockApplyResult,
+    rpc::{RpcId, RpcLedgerAccountsAtBlockQuery},
+};
 
 pub trait ArchiveService: redux::Service {
     fn send_to_archive(&mut self, data: BlockApplyResult);
+
+    /// Looks up the accounts as of the block, which is no longer in the
+    /// transition frontier, in the archived blocks. The result is reported
+    /// with [`super::ArchiveEvent::AccountsAtBlock`], also when the node
+    /// doesn't archive the blocks.
+    fn accounts_at_block(&mut self, rpc_id: RpcId, query: RpcLedgerAccountsAtBlockQuery);
 }
@@ -1,2 +1,5 @@
 pub mod archive_config;
 pub mod archive_service;
+
+mod archive_event;
+pub use archive_event::*;
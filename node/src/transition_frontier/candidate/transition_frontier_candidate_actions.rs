@@ -51,10 +51,15 @@ pub enum TransitionFrontierCandidateAction {
     },
     TransitionFrontierSyncTargetUpdate,
     Prune,
+    /// Prunes the candidates too far behind the best tip, according to
+    /// [`crate::transition_frontier::TransitionFrontierCandidatesPruneConfig`].
+    ///
+    /// Dispatched on a timer.
+    PruneStale,
 }
 
 impl redux::EnablingCondition<crate::State> for TransitionFrontierCandidateAction {
-    fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
         match self {
             TransitionFrontierCandidateAction::P2pBestTipUpdate { .. } => true,
             TransitionFrontierCandidateAction::BlockReceived { block, .. } => {
@@ -122,6 +127,14 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierCandidateActio
             TransitionFrontierCandidateAction::Prune => {
                 state.transition_frontier.candidates.best().is_some()
             }
+            TransitionFrontierCandidateAction::PruneStale => {
+                let transition_frontier = &state.transition_frontier;
+                transition_frontier.best_tip().is_some()
+                    && transition_frontier
+                        .candidates
+                        .prune_stats()
+                        .is_due(time, transition_frontier.config.candidates_prune.interval)
+            }
         }
     }
 }
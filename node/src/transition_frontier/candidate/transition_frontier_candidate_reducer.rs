@@ -173,7 +173,17 @@ impl TransitionFrontierCandidatesState {
                 });
             }
             TransitionFrontierCandidateAction::Prune => {
-                state.prune();
+                let pending_verifications = state.prune();
+
+                // Dispatch
+                let dispatcher = state_context.into_dispatcher();
+                for req_id in pending_verifications {
+                    dispatcher.push(SnarkBlockVerifyAction::Cancel { req_id });
+                }
+            }
+            TransitionFrontierCandidateAction::PruneStale => {
+                // Handled by the transition frontier reducer, which has
+                // the best tip and the config.
             }
         }
     }
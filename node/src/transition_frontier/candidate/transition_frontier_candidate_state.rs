@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use mina_p2p_messages::v2::StateHash;
+use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
+use mina_p2p_messages::v2::{MinaBlockBlockStableV2, StateHash};
 use serde::{Deserialize, Serialize};
 
 use openmina_core::block::ArcBlockWithHash;
@@ -116,6 +117,55 @@ impl TransitionFrontierCandidateState {
     pub fn height(&self) -> u32 {
         self.block.height()
     }
+
+    /// Estimated memory used by the block. It's only reclaimed once no
+    /// other state references the block.
+    pub fn estimated_size(&self) -> usize {
+        let mut ops = MallocSizeOfOps::new(None, None);
+        size_of::<MinaBlockBlockStableV2>()
+            .saturating_add(self.block.block.as_ref().size_of(&mut ops))
+    }
+}
+
+/// Result of a single pruning of the stale candidates.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransitionFrontierCandidatesPruned {
+    pub candidates: usize,
+    pub invalid: usize,
+    /// Estimated memory used by the pruned candidate blocks.
+    pub reclaimed_bytes: usize,
+}
+
+/// Counters of the prunings of the stale candidates.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransitionFrontierCandidatesPruneStats {
+    pub last_time: Option<redux::Timestamp>,
+    pub last: TransitionFrontierCandidatesPruned,
+    pub runs: u64,
+    pub total_candidates: u64,
+    pub total_invalid: u64,
+    pub total_reclaimed_bytes: u64,
+}
+
+impl TransitionFrontierCandidatesPruneStats {
+    fn add(&mut self, time: redux::Timestamp, pruned: TransitionFrontierCandidatesPruned) {
+        self.last_time = Some(time);
+        self.last = pruned;
+        self.runs = self.runs.saturating_add(1);
+        self.total_candidates = self
+            .total_candidates
+            .saturating_add(pruned.candidates as u64);
+        self.total_invalid = self.total_invalid.saturating_add(pruned.invalid as u64);
+        self.total_reclaimed_bytes = self
+            .total_reclaimed_bytes
+            .saturating_add(pruned.reclaimed_bytes as u64);
+    }
+
+    /// Whether `interval` has passed since the last pruning.
+    pub fn is_due(&self, now: redux::Timestamp, interval: std::time::Duration) -> bool {
+        self.last_time
+            .is_none_or(|last| now.checked_sub(last).unwrap_or_default() >= interval)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -128,6 +178,8 @@ pub struct TransitionFrontierCandidatesState {
     /// consume less memory while still preventing us from triggering
     /// revalidation for an invalid block if we receive it on p2p again.
     invalid: BTreeMap<StateHash, u32>,
+    #[serde(default)]
+    prune_stats: TransitionFrontierCandidatesPruneStats,
 }
 
 impl TransitionFrontierCandidatesState {
@@ -135,6 +187,10 @@ impl TransitionFrontierCandidatesState {
         Self::default()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &TransitionFrontierCandidateState> {
+        self.ordered.iter()
+    }
+
     pub fn contains(&self, hash: &StateHash) -> bool {
         self.invalid.contains_key(hash) || self.get(hash).is_some()
     }
@@ -204,10 +260,13 @@ impl TransitionFrontierCandidatesState {
         })
     }
 
-    pub(super) fn prune(&mut self) {
+    /// Returns ids of the block verifications of the pruned candidates,
+    /// which are still pending.
+    pub(super) fn prune(&mut self) -> Vec<SnarkBlockVerifyId> {
         let mut has_reached_best_candidate = false;
+        let mut pending_verifications = vec![];
         let Some(best_candidate_hash) = self.best_verified().map(|s| s.block.hash().clone()) else {
-            return;
+            return pending_verifications;
         };
 
         // prune all blocks that are worse(consensus-wise) than the best
@@ -222,8 +281,66 @@ impl TransitionFrontierCandidatesState {
                 has_reached_best_candidate = true;
             }
 
+            if !has_reached_best_candidate {
+                if let TransitionFrontierCandidateStatus::SnarkVerifyPending { req_id, .. } =
+                    &s.status
+                {
+                    pending_verifications.push(*req_id);
+                }
+            }
+
             has_reached_best_candidate
         });
+        pending_verifications
+    }
+
+    /// Prunes candidates and invalid block hashes for slots older than
+    /// `min_slot`, regardless of how they compare to the best candidate.
+    ///
+    /// Returns ids of the block verifications of the pruned candidates,
+    /// which are still pending.
+    pub(in crate::transition_frontier) fn prune_stale(
+        &mut self,
+        time: redux::Timestamp,
+        min_slot: u32,
+    ) -> Vec<SnarkBlockVerifyId> {
+        let mut pruned = TransitionFrontierCandidatesPruned::default();
+        let mut pending_verifications = vec![];
+
+        self.ordered.retain(|s| {
+            if s.block.global_slot() >= min_slot {
+                return true;
+            }
+            if let TransitionFrontierCandidateStatus::SnarkVerifyPending { req_id, .. } = &s.status
+            {
+                pending_verifications.push(*req_id);
+            }
+            pruned.candidates = pruned.candidates.saturating_add(1);
+            pruned.reclaimed_bytes = pruned.reclaimed_bytes.saturating_add(s.estimated_size());
+            false
+        });
+        let invalid_len = self.invalid.len();
+        self.invalid.retain(|_, slot| *slot >= min_slot);
+        pruned.invalid = invalid_len.saturating_sub(self.invalid.len());
+
+        self.prune_stats.add(time, pruned);
+        pending_verifications
+    }
+
+    pub fn prune_stats(&self) -> &TransitionFrontierCandidatesPruneStats {
+        &self.prune_stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.ordered.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ordered.is_empty()
+    }
+
+    pub fn invalid_len(&self) -> usize {
+        self.invalid.len()
     }
 
     pub(super) fn best(&self) -> Option<&TransitionFrontierCandidateState> {
@@ -278,3 +395,77 @@ impl TransitionFrontierCandidatesState {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use openmina_core::block::fixtures;
+
+    use super::*;
+
+    #[test]
+    fn test_prune_stale() {
+        let base = fixtures::best_tip_with_hash();
+        let min_slot = base.global_slot().saturating_add(10);
+        // One block higher for each slot.
+        let [stale, invalid, kept, best] =
+            [0, 5, 10, 20].map(|slots| fixtures::block_after(&base, slots, slots, |_| {}));
+        let time = redux::Timestamp::ZERO;
+        let req_id = SnarkBlockVerifyId::new_unchecked(1, 1);
+
+        let mut candidates = TransitionFrontierCandidatesState::new();
+        for block in [&stale, &invalid, &kept, &best] {
+            candidates.add(time, block.clone(), None);
+        }
+        candidates.update_status(stale.hash(), |_| {
+            TransitionFrontierCandidateStatus::SnarkVerifyPending { time, req_id }
+        });
+        candidates.invalidate(invalid.hash(), true);
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates.invalid_len(), 1);
+        let stale_size = candidates.get(stale.hash()).unwrap().estimated_size();
+
+        assert_eq!(candidates.prune_stale(time, min_slot), vec![req_id]);
+        assert!(!candidates.contains(stale.hash()));
+        assert!(!candidates.contains(invalid.hash()));
+        assert!(candidates.contains(kept.hash()));
+        assert_eq!(candidates.best().unwrap().block.hash(), best.hash());
+        assert_eq!(candidates.invalid_len(), 0);
+
+        let stats = candidates.prune_stats();
+        assert_eq!(stats.last_time, Some(time));
+        assert_eq!(
+            stats.last,
+            TransitionFrontierCandidatesPruned {
+                candidates: 1,
+                invalid: 1,
+                reclaimed_bytes: stale_size,
+            }
+        );
+        assert_eq!(stats.runs, 1);
+
+        // Nothing left to prune, but the run is still counted.
+        assert!(candidates.prune_stale(time, min_slot).is_empty());
+        assert_eq!(candidates.len(), 2);
+        let stats = candidates.prune_stats();
+        assert_eq!(stats.last, TransitionFrontierCandidatesPruned::default());
+        assert_eq!(stats.runs, 2);
+        assert_eq!(stats.total_candidates, 1);
+        assert_eq!(stats.total_invalid, 1);
+        assert_eq!(stats.total_reclaimed_bytes, stale_size as u64);
+    }
+
+    #[test]
+    fn test_prune_stats_is_due() {
+        let interval = std::time::Duration::from_secs(60);
+        let time = redux::Timestamp::ZERO;
+        let mut stats = TransitionFrontierCandidatesPruneStats::default();
+        assert!(stats.is_due(time, interval));
+
+        stats.add(time, Default::default());
+        assert!(!stats.is_due(time, interval));
+        let later = time.checked_add(59_000_000_000).unwrap();
+        assert!(!stats.is_due(later, interval));
+        let later = time.checked_add(60_000_000_000).unwrap();
+        assert!(stats.is_due(later, interval));
+    }
+}
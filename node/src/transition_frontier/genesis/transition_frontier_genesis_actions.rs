@@ -1,8 +1,10 @@
 use std::sync::Arc;
 
 use mina_p2p_messages::v2;
-use openmina_core::ActionEvent;
+use openmina_core::{block::BlockHash, ActionEvent};
+use p2p::PeerId;
 use serde::{Deserialize, Serialize};
+use snark::block_verify::{SnarkBlockVerifyError, SnarkBlockVerifyId};
 
 use super::{GenesisConfigLoaded, TransitionFrontierGenesisState};
 
@@ -30,6 +32,29 @@ pub enum TransitionFrontierGenesisAction {
         proof: Arc<v2::MinaBaseProofStableV2>,
     },
     // TODO(refactor): add prove error
+    /// Request the genesis proof from the peers, which might have it
+    /// already, while it's being proved locally.
+    ProofPeerFetchInit,
+    ProofPeerFetchPending {
+        peer_id: PeerId,
+    },
+    #[action_event(level = info, fields(display(peer_id)))]
+    ProofPeerFetchSuccess {
+        peer_id: PeerId,
+        proof: Arc<v2::MinaBaseProofStableV2>,
+    },
+    ProofPeerVerifyPending {
+        hash: BlockHash,
+        req_id: SnarkBlockVerifyId,
+    },
+    #[action_event(level = warn, fields(display(hash), debug(error)))]
+    ProofPeerVerifyError {
+        hash: BlockHash,
+        error: SnarkBlockVerifyError,
+    },
+    ProofPeerVerifySuccess {
+        hash: BlockHash,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for TransitionFrontierGenesisAction {
@@ -67,6 +92,25 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierGenesisAction
                 genesis_state,
                 TransitionFrontierGenesisState::ProvePending { .. }
             ),
+            TransitionFrontierGenesisAction::ProofPeerFetchInit => {
+                genesis_state.peer_fetch().is_some() && state.p2p.ready().is_some()
+            }
+            TransitionFrontierGenesisAction::ProofPeerFetchPending { peer_id } => genesis_state
+                .peer_fetch()
+                .is_some_and(|fetch| fetch.can_request(peer_id)),
+            TransitionFrontierGenesisAction::ProofPeerFetchSuccess { peer_id, .. } => {
+                genesis_state.peer_fetch().is_some_and(|fetch| {
+                    fetch.requested.contains(peer_id) && fetch.verifying.is_none()
+                })
+            }
+            TransitionFrontierGenesisAction::ProofPeerVerifyPending { hash, .. }
+            | TransitionFrontierGenesisAction::ProofPeerVerifyError { hash, .. }
+            | TransitionFrontierGenesisAction::ProofPeerVerifySuccess { hash } => {
+                genesis_state.prove_pending_block_hash().as_ref() == Some(hash)
+                    && genesis_state
+                        .peer_fetch()
+                        .is_some_and(|fetch| fetch.verifying.is_some())
+            }
         }
     }
 }
@@ -39,10 +39,17 @@ pub enum GenesisConfig {
         fish: usize,
         non_stakers: NonStakers,
         constants: ProtocolConstants,
+        /// Accounts added after the generated ones, e.g. the deployed
+        /// zkApps built with [`ledger::AccountTemplate`].
+        #[serde(default)]
+        accounts: Vec<ledger::Account>,
     },
     BalancesDelegateTable {
         table: Vec<(u64, Vec<u64>)>,
         constants: ProtocolConstants,
+        /// Same as for [`GenesisConfig::Counts`].
+        #[serde(default)]
+        accounts: Vec<ledger::Account>,
     },
     AccountsBinProt {
         bytes: Cow<'static, [u8]>,
@@ -158,6 +165,7 @@ impl GenesisConfig {
                 fish,
                 non_stakers,
                 constants,
+                accounts,
             } => {
                 let (whales, fish) = (*whales, *fish);
                 let delegator_balance =
@@ -174,7 +182,11 @@ impl GenesisConfig {
                 });
                 let delegator_table = whales.chain(fish);
                 let (mut mask, genesis_total_currency) =
-                    Self::build_ledger_from_balances_delegator_table(delegator_table, non_stakers)?;
+                    Self::build_ledger_from_balances_delegator_table(
+                        delegator_table,
+                        non_stakers,
+                        accounts,
+                    )?;
                 let genesis_ledger_hash = ledger_hash(&mut mask);
                 let staking_epoch_total_currency = genesis_total_currency.clone();
                 let next_epoch_total_currency = genesis_total_currency.clone();
@@ -196,13 +208,21 @@ impl GenesisConfig {
                 let masks = vec![mask];
                 (masks, load_result)
             }
-            Self::BalancesDelegateTable { table, constants } => {
+            Self::BalancesDelegateTable {
+                table,
+                constants,
+                accounts,
+            } => {
                 let table = table.iter().map(|(bp_balance, delegators)| {
                     let delegators = delegators.iter().copied();
                     (*bp_balance, delegators)
                 });
                 let (mut mask, genesis_total_currency) =
-                    Self::build_ledger_from_balances_delegator_table(table, &NonStakers::None)?;
+                    Self::build_ledger_from_balances_delegator_table(
+                        table,
+                        &NonStakers::None,
+                        accounts,
+                    )?;
                 let genesis_ledger_hash = ledger_hash(&mut mask);
                 let staking_epoch_total_currency = genesis_total_currency.clone();
                 let next_epoch_total_currency = genesis_total_currency.clone();
@@ -422,6 +442,7 @@ impl GenesisConfig {
     fn build_ledger_from_balances_delegator_table(
         block_producers: impl IntoIterator<Item = (u64, impl IntoIterator<Item = u64>)>,
         non_stakers: &NonStakers,
+        extra_accounts: &[ledger::Account],
     ) -> Result<(ledger::Mask, v2::CurrencyAmountStableV1), InvalidBigInt> {
         let mut counter = 0;
         let mut total_balance = 0;
@@ -489,6 +510,8 @@ impl GenesisConfig {
             }
         }
 
+        accounts.extend(extra_accounts.iter().cloned().map(Ok));
+
         Self::build_ledger_from_accounts(accounts)
     }
 
@@ -1,5 +1,5 @@
 use crate::{
-    account::AccountSecretKey, block_producer::calc_epoch_seed,
+    account::AccountSecretKey, block_producer::calc_epoch_seed, p2p_ready,
     transition_frontier::genesis_effectful::TransitionFrontierGenesisEffectfulAction,
 };
 use ledger::{
@@ -7,16 +7,24 @@ use ledger::{
 };
 use mina_p2p_messages::v2;
 use openmina_core::{
-    block::{genesis::genesis_and_negative_one_protocol_states, BlockWithHash},
-    constants::PROTOCOL_VERSION,
+    block::{
+        genesis::genesis_and_negative_one_protocol_states, ArcBlockWithHash, BlockHash,
+        BlockWithHash,
+    },
     error,
 };
-use p2p::P2pInitializeAction;
+use p2p::{
+    channels::rpc::{P2pChannelsRpcAction, P2pRpcId, P2pRpcRequest},
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+    P2pInitializeAction, PeerId,
+};
+use snark::block_verify::{SnarkBlockVerifyAction, SnarkBlockVerifyError, SnarkBlockVerifyId};
 
 use super::{
-    empty_block_body, empty_block_body_hash, empty_pending_coinbase, empty_pending_coinbase_hash,
-    TransitionFrontierGenesisAction, TransitionFrontierGenesisActionWithMetaRef,
-    TransitionFrontierGenesisState,
+    empty_block_body_hash, empty_pending_coinbase, empty_pending_coinbase_hash, genesis_block,
+    GenesisProofPeerVerifyState, TransitionFrontierGenesisAction,
+    TransitionFrontierGenesisActionWithMetaRef, TransitionFrontierGenesisState,
+    GENESIS_PROOF_PEER_REQUESTS_MAX,
 };
 
 impl TransitionFrontierGenesisState {
@@ -197,34 +205,28 @@ impl TransitionFrontierGenesisState {
                     genesis: genesis.clone(),
                     genesis_hash: genesis_hash.clone(),
                     genesis_producer_stake_proof: genesis_producer_stake_proof.clone(),
+                    peer_fetch: Default::default(),
                 };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransitionFrontierGenesisAction::ProofPeerFetchInit);
             }
             TransitionFrontierGenesisAction::ProveSuccess { proof } => {
                 let Self::ProvePending {
                     genesis,
                     genesis_hash,
+                    peer_fetch,
                     ..
                 } = state
                 else {
                     return;
                 };
+                let verify_req_id = peer_fetch
+                    .verifying
+                    .as_ref()
+                    .and_then(|verifying| verifying.req_id);
 
-                let block = v2::MinaBlockBlockStableV2 {
-                    header: v2::MinaBlockHeaderStableV2 {
-                        protocol_state: genesis.clone(),
-                        protocol_state_proof: proof.clone(),
-                        delta_block_chain_proof: (
-                            genesis_hash.clone(),
-                            std::iter::empty().collect(),
-                        ),
-                        current_protocol_version: PROTOCOL_VERSION.clone(),
-                        proposed_protocol_version_opt: None,
-                    },
-                    body: v2::StagedLedgerDiffBodyStableV1 {
-                        staged_ledger_diff: empty_block_body(),
-                    },
-                };
-
+                let block = genesis_block(genesis, genesis_hash, proof.clone());
                 let Ok(genesis) = BlockWithHash::try_new(block.into()) else {
                     error!(meta.time(); "invalid `genesis` block");
                     return;
@@ -234,6 +236,137 @@ impl TransitionFrontierGenesisState {
                     time: meta.time(),
                     genesis,
                 };
+
+                if let Some(req_id) = verify_req_id {
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(SnarkBlockVerifyAction::Cancel { req_id });
+                }
+            }
+            TransitionFrontierGenesisAction::ProofPeerFetchInit => {
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let Some(peer_fetch) = global_state.transition_frontier.genesis.peer_fetch() else {
+                    return;
+                };
+                let Some(genesis_hash) = global_state
+                    .transition_frontier
+                    .genesis
+                    .prove_pending_block_hash()
+                else {
+                    return;
+                };
+                let p2p = p2p_ready!(global_state.p2p, meta.time());
+
+                let peers = p2p
+                    .ready_rpc_peers_iter()
+                    .filter(|(peer_id, peer)| {
+                        peer.capabilities
+                            .as_ref()
+                            .is_some_and(|capabilities| capabilities.genesis_proof)
+                            && peer_fetch.can_request(peer_id)
+                    })
+                    .map(|(peer_id, peer)| (*peer_id, peer.channels.next_local_rpc_id()))
+                    .take(
+                        GENESIS_PROOF_PEER_REQUESTS_MAX.saturating_sub(peer_fetch.requested.len()),
+                    );
+
+                for (peer_id, rpc_id) in peers {
+                    dispatcher.push(P2pChannelsRpcAction::RequestSend {
+                        peer_id,
+                        id: rpc_id,
+                        request: Box::new(P2pRpcRequest::GenesisProof(genesis_hash.clone())),
+                        on_init: Some(redux::callback!(
+                            on_send_p2p_genesis_proof_rpc_request(
+                                (peer_id: PeerId, _rpc_id: P2pRpcId, _request: P2pRpcRequest)
+                            ) -> crate::Action {
+                                TransitionFrontierGenesisAction::ProofPeerFetchPending { peer_id }
+                            }
+                        )),
+                    });
+                }
+            }
+            TransitionFrontierGenesisAction::ProofPeerFetchPending { peer_id } => {
+                let Self::ProvePending { peer_fetch, .. } = state else {
+                    return;
+                };
+                peer_fetch.requested.insert(*peer_id);
+            }
+            TransitionFrontierGenesisAction::ProofPeerFetchSuccess { peer_id, proof } => {
+                let Self::ProvePending {
+                    genesis,
+                    genesis_hash,
+                    peer_fetch,
+                    ..
+                } = state
+                else {
+                    return;
+                };
+                let block = genesis_block(genesis, genesis_hash, proof.clone());
+                let Ok(block) = ArcBlockWithHash::try_new(block.into()) else {
+                    error!(meta.time(); "invalid `genesis` block");
+                    return;
+                };
+                peer_fetch.verifying = Some(GenesisProofPeerVerifyState {
+                    peer_id: *peer_id,
+                    proof: proof.clone(),
+                    req_id: None,
+                });
+
+                // Dispatch
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(SnarkBlockVerifyAction::Init {
+                    block: block.into(),
+                    on_init: redux::callback!(
+                        on_genesis_peer_proof_verify_init((hash: BlockHash, req_id: SnarkBlockVerifyId)) -> crate::Action {
+                            TransitionFrontierGenesisAction::ProofPeerVerifyPending { hash, req_id }
+                        }),
+                    on_success: redux::callback!(
+                        on_genesis_peer_proof_verify_success(hash: BlockHash) -> crate::Action {
+                            TransitionFrontierGenesisAction::ProofPeerVerifySuccess { hash }
+                        }),
+                    on_error: redux::callback!(
+                        on_genesis_peer_proof_verify_error((hash: BlockHash, error: SnarkBlockVerifyError)) -> crate::Action {
+                            TransitionFrontierGenesisAction::ProofPeerVerifyError { hash, error }
+                        }),
+                });
+            }
+            TransitionFrontierGenesisAction::ProofPeerVerifyPending { req_id, .. } => {
+                let Self::ProvePending { peer_fetch, .. } = state else {
+                    return;
+                };
+                if let Some(verifying) = peer_fetch.verifying.as_mut() {
+                    verifying.req_id = Some(*req_id);
+                }
+            }
+            TransitionFrontierGenesisAction::ProofPeerVerifyError { .. } => {
+                let Self::ProvePending { peer_fetch, .. } = state else {
+                    return;
+                };
+                let Some(verifying) = peer_fetch.verifying.take() else {
+                    return;
+                };
+
+                // Dispatch
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pDisconnectionAction::Init {
+                    peer_id: verifying.peer_id,
+                    reason: P2pDisconnectionReason::GenesisProofInvalid,
+                });
+                // try other peers, local proving continues anyway.
+                dispatcher.push(TransitionFrontierGenesisAction::ProofPeerFetchInit);
+            }
+            TransitionFrontierGenesisAction::ProofPeerVerifySuccess { .. } => {
+                let Self::ProvePending { peer_fetch, .. } = state else {
+                    return;
+                };
+                let Some(verifying) = peer_fetch.verifying.take() else {
+                    return;
+                };
+
+                // Dispatch
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransitionFrontierGenesisAction::ProveSuccess {
+                    proof: verifying.proof,
+                });
             }
         }
     }
@@ -1,7 +1,11 @@
+use std::{collections::BTreeSet, sync::Arc};
+
 use ledger::dummy::dummy_blockchain_proof;
 use mina_p2p_messages::v2;
 use openmina_core::{block::ArcBlockWithHash, constants::PROTOCOL_VERSION};
+use p2p::PeerId;
 use serde::{Deserialize, Serialize};
+use snark::block_verify::SnarkBlockVerifyId;
 
 use super::{empty_block_body, GenesisConfigLoaded};
 
@@ -28,6 +32,9 @@ pub enum TransitionFrontierGenesisState {
         genesis: v2::MinaStateProtocolStateValueStableV2,
         genesis_hash: v2::StateHash,
         genesis_producer_stake_proof: v2::MinaBaseSparseLedgerBaseStableV2,
+        /// Proof requested from the peers while it's being proved locally.
+        #[serde(default)]
+        peer_fetch: GenesisProofPeerFetchState,
     },
     ProveSuccess {
         time: redux::Timestamp,
@@ -35,6 +42,49 @@ pub enum TransitionFrontierGenesisState {
     },
 }
 
+/// Max number of peers the genesis proof is requested from.
+pub const GENESIS_PROOF_PEER_REQUESTS_MAX: usize = 8;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GenesisProofPeerFetchState {
+    /// Peers the proof was requested from.
+    pub requested: BTreeSet<PeerId>,
+    /// Proof received from the peer, which is being verified.
+    pub verifying: Option<GenesisProofPeerVerifyState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GenesisProofPeerVerifyState {
+    pub peer_id: PeerId,
+    pub proof: Arc<v2::MinaBaseProofStableV2>,
+    pub req_id: Option<SnarkBlockVerifyId>,
+}
+
+impl GenesisProofPeerFetchState {
+    pub fn can_request(&self, peer_id: &PeerId) -> bool {
+        self.requested.len() < GENESIS_PROOF_PEER_REQUESTS_MAX && !self.requested.contains(peer_id)
+    }
+}
+
+pub(super) fn genesis_block(
+    genesis: &v2::MinaStateProtocolStateValueStableV2,
+    genesis_hash: &v2::StateHash,
+    proof: Arc<v2::MinaBaseProofStableV2>,
+) -> v2::MinaBlockBlockStableV2 {
+    v2::MinaBlockBlockStableV2 {
+        header: v2::MinaBlockHeaderStableV2 {
+            protocol_state: genesis.clone(),
+            protocol_state_proof: proof,
+            delta_block_chain_proof: (genesis_hash.clone(), std::iter::empty().collect()),
+            current_protocol_version: PROTOCOL_VERSION.clone(),
+            proposed_protocol_version_opt: None,
+        },
+        body: v2::StagedLedgerDiffBodyStableV1 {
+            staged_ledger_diff: empty_block_body(),
+        },
+    }
+}
+
 impl TransitionFrontierGenesisState {
     pub fn block_with_dummy_proof(&self) -> Option<ArcBlockWithHash> {
         let Self::Produced {
@@ -45,22 +95,8 @@ impl TransitionFrontierGenesisState {
         else {
             return None;
         };
-        ArcBlockWithHash::try_new(
-            v2::MinaBlockBlockStableV2 {
-                header: v2::MinaBlockHeaderStableV2 {
-                    protocol_state: genesis.clone(),
-                    protocol_state_proof: dummy_blockchain_proof().clone(),
-                    delta_block_chain_proof: (genesis_hash.clone(), std::iter::empty().collect()),
-                    current_protocol_version: PROTOCOL_VERSION.clone(),
-                    proposed_protocol_version_opt: None,
-                },
-                body: v2::StagedLedgerDiffBodyStableV1 {
-                    staged_ledger_diff: empty_block_body(),
-                },
-            }
-            .into(),
-        )
-        .ok()
+        let proof = dummy_blockchain_proof();
+        ArcBlockWithHash::try_new(genesis_block(genesis, genesis_hash, proof).into()).ok()
     }
 
     pub fn prove_pending_block_hash(&self) -> Option<v2::StateHash> {
@@ -70,6 +106,20 @@ impl TransitionFrontierGenesisState {
         }
     }
 
+    pub fn peer_fetch(&self) -> Option<&GenesisProofPeerFetchState> {
+        match self {
+            Self::ProvePending { peer_fetch, .. } => Some(peer_fetch),
+            _ => None,
+        }
+    }
+
+    /// Proof of the genesis block with the `hash`, if we have proved it.
+    pub fn proof(&self, hash: &v2::StateHash) -> Option<Arc<v2::MinaBaseProofStableV2>> {
+        self.proven_block()
+            .filter(|block| block.hash() == hash)
+            .map(|block| block.header().protocol_state_proof.clone())
+    }
+
     pub fn proven_block(&self) -> Option<&ArcBlockWithHash> {
         match self {
             Self::ProveSuccess { genesis, .. } => Some(genesis),
@@ -10,6 +10,15 @@ pub use transition_frontier_config::*;
 mod transition_frontier_state;
 pub use transition_frontier_state::*;
 
+mod transition_frontier_transaction_index;
+pub use transition_frontier_transaction_index::*;
+
+mod transition_frontier_internal_command_index;
+pub use transition_frontier_internal_command_index::*;
+
+mod transition_frontier_chain_archive;
+pub use transition_frontier_chain_archive::*;
+
 mod transition_frontier_actions;
 pub use transition_frontier_actions::*;
 
@@ -55,7 +55,11 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                     .filter(|(_, p)| p.channels.rpc.can_send_request())
                     .map(|(id, p)| (*id, p.connected_since))
                     .collect::<Vec<_>>();
-                peer_ids.shuffle(&mut global_state.pseudo_rng());
+                peer_ids.shuffle(&mut global_state.pseudo_rng("snarked_ledger_sync_peers"));
+                global_state
+                    .transition_frontier
+                    .sync_peer_scores
+                    .rank(&mut peer_ids, |(peer_id, _)| peer_id);
 
                 if is_num_accounts_pending {
                     for (peer_id, _) in peer_ids {
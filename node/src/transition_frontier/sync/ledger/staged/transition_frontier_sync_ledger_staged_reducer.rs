@@ -99,7 +99,11 @@ impl TransitionFrontierSyncLedgerStagedState {
                         });
 
                 let block_hash = staged_ledger.target().staged.block_hash.clone();
-                peers.shuffle(&mut global_state.pseudo_rng());
+                peers.shuffle(&mut global_state.pseudo_rng("staged_ledger_parts_fetch_peers"));
+                global_state
+                    .transition_frontier
+                    .sync_peer_scores
+                    .rank(&mut peers, |(peer_id, _, _)| *peer_id);
 
                 for (&peer_id, rpc_id, _) in peers {
                     let enqueued = if p2p.is_libp2p_peer(&peer_id) {
@@ -10,6 +10,9 @@ mod transition_frontier_sync_reducer;
 
 mod transition_frontier_sync_effects;
 
+mod transition_frontier_sync_peer_scores;
+pub use transition_frontier_sync_peer_scores::*;
+
 use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
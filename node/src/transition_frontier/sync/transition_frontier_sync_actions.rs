@@ -9,6 +9,7 @@ use crate::ledger::write::{BlockApplyResult, CommitResult};
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
 use crate::transition_frontier::sync::TransitionFrontierSyncLedgerPending;
+use crate::transition_frontier::InternalCommand;
 use crate::TransitionFrontierAction;
 
 use super::ledger::{
@@ -105,6 +106,7 @@ pub enum TransitionFrontierSyncAction {
     BlocksNextApplySuccess {
         hash: StateHash,
         just_emitted_a_proof: bool,
+        internal_commands: Vec<InternalCommand>,
     },
     /// Sending block to archive
     #[action_event(level = info, fields(
@@ -260,10 +262,12 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
                     }
                 )
             ),
-            TransitionFrontierSyncAction::BlocksPending => matches!(
-                state.transition_frontier.sync,
-                TransitionFrontierSyncState::RootLedgerSuccess { .. }
-            ),
+            TransitionFrontierSyncAction::BlocksPending => match state.transition_frontier.sync {
+                TransitionFrontierSyncState::RootLedgerSuccess { .. } => true,
+                // In light mode, ledgers aren't synced.
+                TransitionFrontierSyncState::Init { .. } => state.transition_frontier.light,
+                _ => false,
+            },
             TransitionFrontierSyncAction::BlocksPeersQuery => {
                 let peers_available = state
                     .p2p
@@ -356,6 +360,7 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
             TransitionFrontierSyncAction::BlocksNextApplySuccess {
                 hash,
                 just_emitted_a_proof: _,
+                internal_commands: _,
             } => state
                 .transition_frontier
                 .sync
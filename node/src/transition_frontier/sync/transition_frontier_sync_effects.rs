@@ -1,10 +1,11 @@
 use mina_p2p_messages::v2::LedgerHash;
+use openmina_core::block::prevalidate::{validate_protocol_versions, validate_staged_ledger_diff};
 use openmina_core::block::{AppliedBlock, ArcBlockWithHash};
 use p2p::channels::rpc::{P2pChannelsRpcAction, P2pRpcId};
 use p2p::{P2pNetworkPubsubAction, PeerId};
 use redux::ActionMeta;
 
-use crate::ledger::write::{LedgerWriteAction, LedgerWriteRequest, LedgersToKeep};
+use crate::ledger::write::{CommitResult, LedgerWriteAction, LedgerWriteRequest, LedgersToKeep};
 use crate::p2p::channels::rpc::P2pRpcRequest;
 use crate::service::TransitionFrontierSyncLedgerSnarkedService;
 use crate::{p2p_ready, Service, Store, TransitionFrontierAction};
@@ -21,6 +22,11 @@ impl TransitionFrontierSyncAction {
     {
         match self {
             TransitionFrontierSyncAction::Init { best_tip, .. } => {
+                if store.state().transition_frontier.light {
+                    // no ledgers to sync.
+                    store.dispatch(TransitionFrontierSyncAction::BlocksPending);
+                    return;
+                }
                 let protocol_state_body = &best_tip.block.header.protocol_state.body;
                 let genesis_ledger_hash = &protocol_state_body.blockchain_state.genesis_ledger_hash;
                 let staking_epoch_ledger_hash = &protocol_state_body
@@ -71,6 +77,8 @@ impl TransitionFrontierSyncAction {
                 store.dispatch(TransitionFrontierSyncAction::BlocksPeersQuery);
                 // if we already have a block ready to be applied.
                 store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyInit);
+                // in light mode, if the new root isn't part of the chain.
+                store.dispatch(TransitionFrontierSyncAction::BlocksPending);
 
                 // TODO(binier): cleanup ledgers
                 if let Some(callback) = on_success {
@@ -131,6 +139,11 @@ impl TransitionFrontierSyncAction {
                     .map(|(id, p)| (*id, p.connected_since))
                     .collect::<Vec<_>>();
                 peer_ids.sort_by(|(_, t1), (_, t2)| t2.cmp(t1));
+                store
+                    .state()
+                    .transition_frontier
+                    .sync_peer_scores
+                    .rank(&mut peer_ids, |(peer_id, _)| peer_id);
 
                 let mut retry_hashes = store
                     .state()
@@ -247,6 +260,28 @@ impl TransitionFrontierSyncAction {
                 };
                 let hash = block.hash.clone();
 
+                if store.state().transition_frontier.light {
+                    store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyPending {
+                        hash: hash.clone(),
+                    });
+                    match light_verify_block(&block, &pred_block) {
+                        Ok(()) => {
+                            store.dispatch(TransitionFrontierSyncAction::BlocksNextApplySuccess {
+                                hash,
+                                just_emitted_a_proof: false,
+                                internal_commands: Vec::new(),
+                            })
+                        }
+                        Err(error) => {
+                            store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyError {
+                                hash,
+                                error,
+                            })
+                        }
+                    };
+                    return;
+                }
+
                 let is_our_block;
 
                 if let Some(stats) = store.service.stats() {
@@ -314,6 +349,7 @@ impl TransitionFrontierSyncAction {
             TransitionFrontierSyncAction::BlocksNextApplySuccess {
                 hash,
                 just_emitted_a_proof: _,
+                internal_commands: _,
             } => {
                 if let Some(stats) = store.service.stats() {
                     stats.block_producer().block_apply_end(meta.time(), hash);
@@ -333,6 +369,18 @@ impl TransitionFrontierSyncAction {
             // from previous chain, before it's discarded after dispatching
             // `TransitionFrontierSyncedAction`.
             TransitionFrontierSyncAction::CommitInit => {
+                if store.state().transition_frontier.light {
+                    // nothing to commit, no ledgers are maintained.
+                    store.dispatch(TransitionFrontierSyncAction::CommitPending);
+                    store.dispatch(TransitionFrontierSyncAction::CommitSuccess {
+                        result: CommitResult {
+                            alive_masks: 0,
+                            available_jobs: Default::default(),
+                            needed_protocol_states: Default::default(),
+                        },
+                    });
+                    return;
+                }
                 let transition_frontier = &store.state.get().transition_frontier;
                 let TransitionFrontierSyncState::BlocksSuccess {
                     chain,
@@ -367,6 +415,23 @@ impl TransitionFrontierSyncAction {
                         .extend_with_needed(new_root.block_with_hash(), old_chain);
                 }
 
+                // Root is persisted according to the policy, as the whole
+                // snarked ledger is written. In maintenance mode on every
+                // commit, so that the node can be stopped any time.
+                let persist_root = store.state.get().ledger.is_root_persist_due(meta.time())
+                    || store.state.get().rpc.is_in_maintenance();
+                let root_snapshot_protocol_states = persist_root.then(|| {
+                    let blocks = chain.iter().chain(&transition_frontier.best_chain);
+                    needed_protocol_states
+                        .iter()
+                        .chain(&transition_frontier.needed_protocol_states)
+                        .map(|(k, v)| (k.clone(), v.clone()))
+                        .chain(
+                            blocks.map(|b| (b.hash().clone(), b.header().protocol_state.clone())),
+                        )
+                        .collect()
+                });
+
                 let needed_protocol_states = if root_snarked_ledger_updates.is_empty() {
                     // We don't need protocol states unless we need to
                     // recreate some snarked ledgers during `commit`.
@@ -384,6 +449,7 @@ impl TransitionFrontierSyncAction {
                         ledgers_to_keep,
                         root_snarked_ledger_updates,
                         needed_protocol_states,
+                        root_snapshot_protocol_states,
                         new_root: new_root.clone(),
                         new_best_tip: new_best_tip.clone(),
                     },
@@ -410,6 +476,85 @@ fn sync_best_tip(state: &crate::State) -> ArcBlockWithHash {
     state.transition_frontier.sync.best_tip().unwrap().clone()
 }
 
+/// Checks done in light mode instead of the block application.
+///
+/// Only the best tip's proof is verified, the rest of the chain is
+/// covered by it, as long as the blocks are linked to each other. On
+/// top of the linkage, everything that can be checked without the
+/// ledgers is checked against the predecessor: the consensus state
+/// progression, the constants and the body. Ledger hashes and the
+/// window densities can't be, so a block passing these checks is only
+/// as good as the best tip proof that committed to it.
+fn light_verify_block(block: &ArcBlockWithHash, pred_block: &AppliedBlock) -> Result<(), String> {
+    if block.pred_hash() != pred_block.hash() {
+        return Err(format!(
+            "predecessor hash mismatch, expected: {}, got: {}",
+            pred_block.hash(),
+            block.pred_hash()
+        ));
+    }
+    if block.height() != pred_block.height().saturating_add(1) {
+        return Err(format!(
+            "unexpected height: {}, predecessor height: {}",
+            block.height(),
+            pred_block.height()
+        ));
+    }
+    if block.global_slot() <= pred_block.global_slot() {
+        return Err(format!(
+            "global slot: {} not after predecessor's: {}",
+            block.global_slot(),
+            pred_block.global_slot()
+        ));
+    }
+    let slot_diff = block.global_slot().saturating_sub(pred_block.global_slot());
+    let slot_since_genesis_diff = block
+        .global_slot_since_genesis()
+        .checked_sub(pred_block.global_slot_since_genesis());
+    if slot_since_genesis_diff != Some(slot_diff) {
+        return Err(format!(
+            "global slot since genesis: {} doesn't advance with global slot: {}",
+            block.global_slot_since_genesis(),
+            block.global_slot()
+        ));
+    }
+    let epoch = |block: &ArcBlockWithHash| {
+        let slot = block.curr_global_slot_since_hard_fork();
+        slot.slot_number
+            .as_u32()
+            .checked_div(slot.slots_per_epoch.as_u32())
+    };
+    let expected_epoch_count = match (epoch(block), epoch(pred_block)) {
+        (Some(epoch), Some(pred_epoch)) if epoch >= pred_epoch => pred_block
+            .consensus_state()
+            .epoch_count
+            .as_u32()
+            .checked_add(epoch.saturating_sub(pred_epoch).min(1)),
+        _ => None,
+    };
+    let epoch_count = block.consensus_state().epoch_count.as_u32();
+    if expected_epoch_count != Some(epoch_count) {
+        return Err(format!(
+            "unexpected epoch count: {epoch_count}, expected: {expected_epoch_count:?}"
+        ));
+    }
+    if block.header().genesis_state_hash() != pred_block.header().genesis_state_hash() {
+        return Err(format!(
+            "genesis state hash mismatch, expected: {}, got: {}",
+            pred_block.header().genesis_state_hash(),
+            block.header().genesis_state_hash()
+        ));
+    }
+    if block.constants() != pred_block.constants() {
+        return Err("protocol constants differ from the predecessor's".to_owned());
+    }
+    validate_protocol_versions(block).map_err(|error| format!("{error:?}"))?;
+    validate_staged_ledger_diff(block).map_err(|error| error.to_string())?;
+    ledger::staged_ledger::validate_block::validate_block(&block.block)
+        .map_err(|error| format!("body: {error:?}"))?;
+    Ok(())
+}
+
 /// For snarked ledger sync targets, copy the previous snarked ledger if required
 fn maybe_copy_ledgers_for_sync<S>(
     store: &mut Store<S>,
@@ -512,3 +657,117 @@ where
         .service()
         .copy_snarked_ledger_contents_for_sync(candidate_origins, target, false)
 }
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::v2::{
+        self, StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2Coinbase as Coinbase,
+    };
+    use openmina_core::block::{fixtures, Block};
+
+    use super::*;
+
+    fn fixture_block() -> AppliedBlock {
+        AppliedBlock {
+            block: fixtures::best_tip_with_hash(),
+            just_emitted_a_proof: false,
+        }
+    }
+
+    /// Child of `pred`, `slots` after it, modified by `f`.
+    fn child(pred: &AppliedBlock, slots: u32, f: impl FnOnce(&mut Block)) -> ArcBlockWithHash {
+        fixtures::block_after(&pred.block, 1, slots, |block| {
+            block.header.protocol_state.previous_state_hash = pred.hash().clone();
+            f(block);
+        })
+    }
+
+    fn consensus_state(
+        block: &mut Block,
+    ) -> &mut v2::ConsensusProofOfStakeDataConsensusStateValueStableV2 {
+        &mut block.header.protocol_state.body.consensus_state
+    }
+
+    #[test]
+    fn test_light_verify_block_accepts_child() {
+        let pred = fixture_block();
+        let block = child(&pred, 1, |_| {});
+        assert_eq!(light_verify_block(&block, &pred), Ok(()));
+        let next = AppliedBlock {
+            block: block.clone(),
+            just_emitted_a_proof: false,
+        };
+        assert_eq!(light_verify_block(&child(&next, 3, |_| {}), &next), Ok(()));
+    }
+
+    #[test]
+    fn test_light_verify_block_epoch_change() {
+        let pred = fixture_block();
+        let slots_per_epoch = pred
+            .curr_global_slot_since_hard_fork()
+            .slots_per_epoch
+            .as_u32();
+        let next_epoch = |epoch_count: u32| {
+            child(&pred, slots_per_epoch, |block| {
+                consensus_state(block).epoch_count =
+                    v2::UnsignedExtendedUInt32StableV1(epoch_count.into());
+            })
+        };
+        let epoch_count = pred.consensus_state().epoch_count.as_u32();
+        assert_eq!(
+            light_verify_block(&next_epoch(epoch_count.saturating_add(1)), &pred),
+            Ok(())
+        );
+        assert!(light_verify_block(&next_epoch(epoch_count), &pred).is_err());
+
+        // same epoch, but the count is bumped.
+        let block = child(&pred, 1, |block| {
+            consensus_state(block).epoch_count =
+                v2::UnsignedExtendedUInt32StableV1(epoch_count.saturating_add(1).into());
+        });
+        assert!(light_verify_block(&block, &pred).is_err());
+    }
+
+    #[test]
+    fn test_light_verify_block_rejects_invalid() {
+        let pred = fixture_block();
+        let other = child(&pred, 1, |_| {});
+
+        let unlinked = child(&pred, 1, |block| {
+            block.header.protocol_state.previous_state_hash = other.hash().clone();
+        });
+        let skipped_height = child(&pred, 1, |block| {
+            consensus_state(block).blockchain_length =
+                v2::UnsignedExtendedUInt32StableV1(pred.height().saturating_add(2).into());
+        });
+        let same_slot = child(&pred, 0, |_| {});
+        let slot_since_genesis_skew = child(&pred, 1, |block| {
+            consensus_state(block).global_slot_since_genesis =
+                v2::MinaNumbersGlobalSlotSinceGenesisMStableV1::SinceGenesis(
+                    pred.global_slot_since_genesis().saturating_add(2).into(),
+                );
+        });
+        let other_genesis = child(&pred, 1, |block| {
+            block.header.protocol_state.body.genesis_state_hash = other.hash().clone();
+        });
+        let other_constants = child(&pred, 1, |block| {
+            let k = &mut block.header.protocol_state.body.constants.k;
+            *k = v2::UnsignedExtendedUInt32StableV1(k.as_u32().saturating_add(1).into());
+        });
+        let tampered_body = child(&pred, 1, |block| {
+            block.body.staged_ledger_diff.diff.0.coinbase = Coinbase::Zero;
+        });
+
+        for block in [
+            unlinked,
+            skipped_height,
+            same_slot,
+            slot_since_genesis_skew,
+            other_genesis,
+            other_constants,
+            tampered_body,
+        ] {
+            assert!(light_verify_block(&block, &pred).is_err());
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::channels::rpc::P2pRpcId;
+use crate::p2p::PeerId;
+
+use super::ledger::snarked::{
+    self, PeerLedgerQueryResponse, TransitionFrontierSyncLedgerSnarkedAction,
+};
+use super::ledger::staged::{
+    PeerStagedLedgerPartsFetchState, TransitionFrontierSyncLedgerStagedAction,
+};
+use super::ledger::TransitionFrontierSyncLedgerAction;
+use super::{
+    PeerRpcState, TransitionFrontierSyncAction, TransitionFrontierSyncBlockState,
+    TransitionFrontierSyncState,
+};
+
+/// Max number of the scored peers. Peers which weren't queried for the
+/// longest time are forgotten first.
+pub const SYNC_PEER_SCORES_MAX: usize = 256;
+
+/// Weight of the new sample in the moving averages.
+const SYNC_PEER_SCORE_EWMA_WEIGHT: f64 = 0.25;
+
+/// Penalty for each response with invalid data. It's larger than the
+/// best possible score of the honest peer, so a single invalid response
+/// puts the peer behind all the honest ones.
+const SYNC_PEER_SCORE_INVALID_PENALTY: f64 = 1.0;
+
+/// Performance of the peer as a source of the sync rpcs (blocks, snarked
+/// ledger queries and staged ledger parts).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SyncPeerScore {
+    /// Responses with the data we asked for.
+    pub successes: u32,
+    /// Requests which timed out, or the peer didn't have the data.
+    pub errors: u32,
+    /// Responses whose data didn't match the expected hashes.
+    pub invalid: u32,
+    /// Moving average of the response time.
+    pub latency: Option<Duration>,
+    /// Moving average of the items (blocks, ledger hashes, accounts or
+    /// staged ledger parts) received per second of the response time.
+    pub throughput: Option<f64>,
+    pub last_update: Timestamp,
+}
+
+impl SyncPeerScore {
+    fn new(time: Timestamp) -> Self {
+        Self {
+            successes: 0,
+            errors: 0,
+            invalid: 0,
+            latency: None,
+            throughput: None,
+            last_update: time,
+        }
+    }
+
+    /// Score of the peer, higher is better. Honest peers score between
+    /// 0 and 1, peers without any responses yet score 0.25, so that they
+    /// get a chance next to the known slow ones.
+    pub fn score(&self) -> f64 {
+        let reliability = f64::from(self.successes.saturating_add(1))
+            / f64::from(self.successes.saturating_add(self.errors).saturating_add(2));
+        let speed = self
+            .latency
+            .map_or(0.5, |latency| 1.0 / (1.0 + latency.as_secs_f64()));
+        let throughput = self
+            .throughput
+            .map_or(0.5, |throughput| throughput / (throughput + 1.0));
+        reliability * (speed + throughput) / 2.0
+            - f64::from(self.invalid) * SYNC_PEER_SCORE_INVALID_PENALTY
+    }
+
+    fn success(&mut self, time: Timestamp, latency: Duration, items: usize) {
+        self.successes = self.successes.saturating_add(1);
+        self.latency = Some(match self.latency {
+            Some(prev) => prev
+                .mul_f64(1.0 - SYNC_PEER_SCORE_EWMA_WEIGHT)
+                .saturating_add(latency.mul_f64(SYNC_PEER_SCORE_EWMA_WEIGHT)),
+            None => latency,
+        });
+        // Avoid infinite throughput for the responses within the same tick.
+        let secs = latency.as_secs_f64().max(0.001);
+        let throughput = items as f64 / secs;
+        self.throughput = Some(match self.throughput {
+            Some(prev) => {
+                prev * (1.0 - SYNC_PEER_SCORE_EWMA_WEIGHT)
+                    + throughput * SYNC_PEER_SCORE_EWMA_WEIGHT
+            }
+            None => throughput,
+        });
+        self.last_update = time;
+    }
+
+    fn error(&mut self, time: Timestamp) {
+        self.errors = self.errors.saturating_add(1);
+        self.last_update = time;
+    }
+
+    fn invalid(&mut self, time: Timestamp) {
+        self.invalid = self.invalid.saturating_add(1);
+        self.last_update = time;
+    }
+}
+
+/// Scores of the peers we queried during the transition frontier sync,
+/// used to query the faster and honest peers first.
+///
+/// Scores are kept when the peer disconnects, so that reconnecting
+/// doesn't reset the penalty for the invalid data.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransitionFrontierSyncPeerScores {
+    peers: BTreeMap<PeerId, SyncPeerScore>,
+}
+
+impl TransitionFrontierSyncPeerScores {
+    pub fn get(&self, peer_id: &PeerId) -> Option<&SyncPeerScore> {
+        self.peers.get(peer_id)
+    }
+
+    /// Score of the peer, see [`SyncPeerScore::score`].
+    pub fn score(&self, peer_id: &PeerId) -> f64 {
+        self.peers.get(peer_id).map_or_else(
+            || SyncPeerScore::new(Timestamp::ZERO).score(),
+            |s| s.score(),
+        )
+    }
+
+    /// Orders the `items` by the score of their peer, best first. Sort is
+    /// stable, so the peers with the same score keep their order.
+    pub fn rank<T>(&self, items: &mut [T], peer_id: impl Fn(&T) -> &PeerId) {
+        items.sort_by(|a, b| self.score(peer_id(b)).total_cmp(&self.score(peer_id(a))));
+    }
+
+    /// Scored peers, best first.
+    pub fn ranking(&self) -> Vec<(PeerId, &SyncPeerScore)> {
+        let mut ranking = self
+            .peers
+            .iter()
+            .map(|(peer_id, score)| (*peer_id, score))
+            .collect::<Vec<_>>();
+        self.rank(&mut ranking, |(peer_id, _)| peer_id);
+        ranking
+    }
+
+    fn entry(&mut self, peer_id: PeerId, time: Timestamp) -> &mut SyncPeerScore {
+        if !self.peers.contains_key(&peer_id) && self.peers.len() >= SYNC_PEER_SCORES_MAX {
+            let oldest = self
+                .peers
+                .iter()
+                .min_by_key(|(_, score)| score.last_update)
+                .map(|(peer_id, _)| *peer_id);
+            if let Some(oldest) = oldest {
+                self.peers.remove(&oldest);
+            }
+        }
+        self.peers
+            .entry(peer_id)
+            .or_insert_with(|| SyncPeerScore::new(time))
+    }
+
+    fn success(
+        &mut self,
+        peer_id: PeerId,
+        time: Timestamp,
+        since: Option<Timestamp>,
+        items: usize,
+    ) {
+        let latency = since
+            .and_then(|since| time.checked_sub(since))
+            .unwrap_or_default();
+        self.entry(peer_id, time).success(time, latency, items);
+    }
+
+    fn error(&mut self, peer_id: PeerId, time: Timestamp) {
+        self.entry(peer_id, time).error(time);
+    }
+
+    fn invalid(&mut self, peer_id: PeerId, time: Timestamp) {
+        self.entry(peer_id, time).invalid(time);
+    }
+
+    /// Records the outcome of the sync rpc. Must be called before the
+    /// `action` is applied to the `sync` state, which still holds the
+    /// time the request was sent.
+    pub fn update(
+        &mut self,
+        sync: &TransitionFrontierSyncState,
+        action: &TransitionFrontierSyncAction,
+        time: Timestamp,
+    ) {
+        match action {
+            TransitionFrontierSyncAction::BlocksPeerQuerySuccess {
+                peer_id, rpc_id, ..
+            } => {
+                let since = sync.blocks_iter().find_map(|b| match b {
+                    TransitionFrontierSyncBlockState::FetchPending { attempts, .. } => {
+                        match attempts.get(peer_id)? {
+                            PeerRpcState::Pending { time, rpc_id: id } if id == rpc_id => {
+                                Some(*time)
+                            }
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                });
+                self.success(*peer_id, time, since, 1);
+            }
+            TransitionFrontierSyncAction::BlocksPeerQueryError { peer_id, .. } => {
+                self.error(*peer_id, time);
+            }
+            TransitionFrontierSyncAction::Ledger(TransitionFrontierSyncLedgerAction::Snarked(
+                action,
+            )) => self.update_snarked(sync, action, time),
+            TransitionFrontierSyncAction::Ledger(TransitionFrontierSyncLedgerAction::Staged(
+                action,
+            )) => self.update_staged(sync, action, time),
+            _ => {}
+        }
+    }
+
+    fn update_snarked(
+        &mut self,
+        sync: &TransitionFrontierSyncState,
+        action: &TransitionFrontierSyncLedgerSnarkedAction,
+        time: Timestamp,
+    ) {
+        let snarked = sync.ledger().and_then(|ledger| ledger.snarked());
+        let pending_since = |state: Option<&snarked::PeerRpcState>, rpc_id: P2pRpcId| match state {
+            Some(snarked::PeerRpcState::Pending { time, rpc_id: id }) if *id == rpc_id => {
+                Some(*time)
+            }
+            _ => None,
+        };
+        match action {
+            TransitionFrontierSyncLedgerSnarkedAction::PeerQueryNumAccountsSuccess {
+                peer_id,
+                rpc_id,
+                ..
+            } => {
+                let since = snarked
+                    .and_then(|s| s.peer_num_account_query_get(peer_id, *rpc_id))
+                    .and_then(|pending| pending_since(pending.attempts.get(peer_id), *rpc_id));
+                self.success(*peer_id, time, since, 1);
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressSuccess {
+                peer_id,
+                rpc_id,
+                response,
+            } => {
+                let since = snarked
+                    .and_then(|s| s.peer_address_query_get(peer_id, *rpc_id))
+                    .and_then(|(_, pending)| pending_since(pending.attempts.get(peer_id), *rpc_id));
+                let items = match response {
+                    PeerLedgerQueryResponse::ChildHashes(..) => 2,
+                    PeerLedgerQueryResponse::ChildAccounts(accounts) => accounts.len(),
+                    PeerLedgerQueryResponse::NumAccounts(..) => 1,
+                };
+                self.success(*peer_id, time, since, items);
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::PeerQueryNumAccountsError {
+                peer_id,
+                ..
+            }
+            | TransitionFrontierSyncLedgerSnarkedAction::PeerQueryAddressError {
+                peer_id, ..
+            } => {
+                self.error(*peer_id, time);
+            }
+            TransitionFrontierSyncLedgerSnarkedAction::NumAccountsRejected { sender, .. }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildHashesRejected { sender, .. }
+            | TransitionFrontierSyncLedgerSnarkedAction::ChildAccountsRejected { sender, .. } => {
+                self.invalid(*sender, time);
+            }
+            _ => {}
+        }
+    }
+
+    fn update_staged(
+        &mut self,
+        sync: &TransitionFrontierSyncState,
+        action: &TransitionFrontierSyncLedgerStagedAction,
+        time: Timestamp,
+    ) {
+        match action {
+            TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchSuccess {
+                peer_id,
+                rpc_id,
+                ..
+            } => {
+                let since = sync
+                    .ledger()
+                    .and_then(|ledger| ledger.staged())
+                    .and_then(|staged| staged.fetch_attempts()?.get(peer_id))
+                    .and_then(|state| match state {
+                        PeerStagedLedgerPartsFetchState::Pending { time, rpc_id: id }
+                            if id == rpc_id =>
+                        {
+                            Some(*time)
+                        }
+                        _ => None,
+                    });
+                self.success(*peer_id, time, since, 1);
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsPeerFetchError { peer_id, .. } => {
+                self.error(*peer_id, time);
+            }
+            TransitionFrontierSyncLedgerStagedAction::PartsPeerInvalid { sender, .. } => {
+                self.invalid(*sender, time);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(i: u8) -> PeerId {
+        crate::p2p::identity::SecretKey::from_bytes([i; 32])
+            .public_key()
+            .peer_id()
+    }
+
+    #[test]
+    fn test_sync_peer_scores_rank() {
+        let secs = |s: u64| Timestamp::new(s * 1_000_000_000);
+        let (fast, slow, unknown, bad) = (peer(1), peer(2), peer(3), peer(4));
+        let mut scores = TransitionFrontierSyncPeerScores::default();
+
+        scores.success(fast, secs(11), Some(secs(10)), 1);
+        scores.success(slow, secs(20), Some(secs(10)), 1);
+        scores.error(slow, secs(21));
+        scores.success(bad, secs(11), Some(secs(10)), 100);
+        scores.invalid(bad, secs(11));
+
+        let mut peers = [bad, unknown, slow, fast];
+        scores.rank(&mut peers, |p| p);
+        assert_eq!(peers, [fast, unknown, slow, bad]);
+        assert!(scores.score(&bad) < 0.0);
+
+        let ranking = scores.ranking();
+        let ranked = ranking.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+        assert_eq!(ranked, [fast, slow, bad]);
+    }
+}
@@ -18,6 +18,7 @@ impl TransitionFrontierSyncState {
         mut state_context: crate::Substate<Self>,
         action: TransitionFrontierSyncActionWithMetaRef<'_>,
         best_chain: &[AppliedBlock],
+        light: bool,
     ) {
         let Ok(state) = state_context.get_substate_mut() else {
             // TODO: log or propagate
@@ -199,6 +200,13 @@ impl TransitionFrontierSyncState {
                                 Some((hash, s.take_block()?.block.header.protocol_state.clone()))
                             },
                         ));
+                    } else if light {
+                        *state = Self::Init {
+                            time: meta.time(),
+                            best_tip: new_best_tip.clone(),
+                            root_block: new_root.clone(),
+                            blocks_inbetween: blocks_inbetween.clone(),
+                        };
                     } else {
                         let cur_best_root = best_chain.first();
                         let cur_best_tip = best_chain.last();
@@ -253,6 +261,13 @@ impl TransitionFrontierSyncState {
                             root_snarked_ledger_updates: Default::default(),
                             needed_protocol_states: Default::default(),
                         };
+                    } else if light {
+                        *state = Self::Init {
+                            time: meta.time(),
+                            best_tip: new_best_tip.clone(),
+                            root_block: new_root.clone(),
+                            blocks_inbetween: blocks_inbetween.clone(),
+                        };
                     } else {
                         *state = next_required_ledger_to_sync(
                             meta.time(),
@@ -410,20 +425,42 @@ impl TransitionFrontierSyncState {
                 }
             }
             TransitionFrontierSyncAction::BlocksPending => {
-                let Self::RootLedgerSuccess {
+                let (
                     best_tip,
                     root_block,
                     blocks_inbetween,
                     root_block_updates,
                     needed_protocol_states,
-                    ..
-                } = state
-                else {
-                    return;
+                ) = match state {
+                    Self::RootLedgerSuccess {
+                        best_tip,
+                        root_block,
+                        blocks_inbetween,
+                        root_block_updates,
+                        needed_protocol_states,
+                        ..
+                    } => (
+                        best_tip.clone(),
+                        root_block.clone(),
+                        std::mem::take(blocks_inbetween),
+                        std::mem::take(root_block_updates),
+                        std::mem::take(needed_protocol_states),
+                    ),
+                    // In light mode, ledgers aren't synced.
+                    Self::Init {
+                        best_tip,
+                        root_block,
+                        blocks_inbetween,
+                        ..
+                    } if light => (
+                        best_tip.clone(),
+                        root_block.clone(),
+                        std::mem::take(blocks_inbetween),
+                        Vec::new(),
+                        BTreeMap::new(),
+                    ),
+                    _ => return,
                 };
-                let (best_tip, root_block) = (best_tip.clone(), root_block.clone());
-                let blocks_inbetween = std::mem::take(blocks_inbetween);
-                let root_block_updates = std::mem::take(root_block_updates);
 
                 let mut root_snarked_ledger_updates =
                     TransitionFrontierRootSnarkedLedgerUpdates::default();
@@ -503,7 +540,7 @@ impl TransitionFrontierSyncState {
                     time: meta.time(),
                     chain,
                     root_snarked_ledger_updates,
-                    needed_protocol_states: std::mem::take(needed_protocol_states),
+                    needed_protocol_states,
                 };
             }
             TransitionFrontierSyncAction::BlocksPeersQuery => {}
@@ -620,6 +657,7 @@ impl TransitionFrontierSyncState {
             TransitionFrontierSyncAction::BlocksNextApplySuccess {
                 hash,
                 just_emitted_a_proof,
+                internal_commands: _,
             } => {
                 let Some(block_state) = state.block_state_mut(hash) else {
                     return;
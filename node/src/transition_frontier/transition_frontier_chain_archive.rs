@@ -0,0 +1,291 @@
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::v2::StateHash;
+use openmina_core::block::{ArcBlockWithHash, Block};
+use serde::{Deserialize, Serialize};
+
+/// Marks the chain archive file, the last byte is the format version.
+pub const CHAIN_ARCHIVE_MAGIC: &[u8; 8] = b"omchain1";
+
+/// Blocks are bigger than that only if the file is corrupted.
+const BLOCK_SIZE_MAX: u32 = 256 * 1024 * 1024;
+
+/// Describes the contiguous range of the blocks in the chain archive.
+///
+/// The archive file is:
+/// - [`CHAIN_ARCHIVE_MAGIC`].
+/// - Length (u32, little endian) of the JSON encoded header.
+/// - Blocks in the ascending order of the height, each prefixed with its
+///   length (u32, little endian) and encoded with binprot, the same as
+///   sent over the network, so that the archive may be produced or
+///   consumed by the other implementations.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChainArchiveHeader {
+    /// Genesis of the chain the blocks belong to.
+    pub genesis_state_hash: StateHash,
+    pub from_height: u32,
+    pub to_height: u32,
+}
+
+impl ChainArchiveHeader {
+    pub fn blocks_count(&self) -> u32 {
+        self.to_height
+            .saturating_sub(self.from_height)
+            .saturating_add(1)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ChainArchiveError {
+    #[error("not a chain archive")]
+    InvalidMagic,
+    #[error("invalid archive header: {0}")]
+    InvalidHeader(String),
+    #[error("invalid block at height {height}: {error}")]
+    InvalidBlock { height: u32, error: String },
+    #[error("block at height {height} doesn't continue the chain, expected parent {expected}, found {found}")]
+    Discontinuity {
+        height: u32,
+        expected: StateHash,
+        found: StateHash,
+    },
+    #[error("archive is empty")]
+    Empty,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Writes the blocks of the chain archive, see [`ChainArchiveHeader`].
+pub struct ChainArchiveWriter<W> {
+    inner: W,
+    next_height: u32,
+    to_height: u32,
+    prev_hash: Option<StateHash>,
+}
+
+impl<W: Write> ChainArchiveWriter<W> {
+    /// Writes the header for the `blocks`, which must be contiguous.
+    pub fn new(mut inner: W, blocks: &[ArcBlockWithHash]) -> Result<Self, ChainArchiveError> {
+        let (Some(first), Some(last)) = (blocks.first(), blocks.last()) else {
+            return Err(ChainArchiveError::Empty);
+        };
+        let header = ChainArchiveHeader {
+            genesis_state_hash: genesis_state_hash(first).clone(),
+            from_height: first.height(),
+            to_height: last.height(),
+        };
+        if header.blocks_count() as usize != blocks.len() {
+            return Err(ChainArchiveError::InvalidHeader(format!(
+                "{} blocks for the heights {}..={}",
+                blocks.len(),
+                header.from_height,
+                header.to_height
+            )));
+        }
+        let header_bytes = serde_json::to_vec(&header)
+            .map_err(|err| ChainArchiveError::InvalidHeader(err.to_string()))?;
+
+        inner.write_all(CHAIN_ARCHIVE_MAGIC)?;
+        write_chunk(&mut inner, &header_bytes)?;
+        Ok(Self {
+            inner,
+            next_height: header.from_height,
+            to_height: header.to_height,
+            prev_hash: None,
+        })
+    }
+
+    pub fn write_block(&mut self, block: &ArcBlockWithHash) -> Result<(), ChainArchiveError> {
+        let height = block.height();
+        if height != self.next_height || height > self.to_height {
+            return Err(ChainArchiveError::InvalidBlock {
+                height,
+                error: format!("expected block at height {}", self.next_height),
+            });
+        }
+        check_parent(self.prev_hash.as_ref(), block)?;
+
+        let mut bytes = Vec::new();
+        block.block.binprot_write(&mut bytes)?;
+        write_chunk(&mut self.inner, &bytes)?;
+
+        self.next_height = height.saturating_add(1);
+        self.prev_hash = Some(block.hash().clone());
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W, ChainArchiveError> {
+        if self.next_height <= self.to_height {
+            return Err(ChainArchiveError::InvalidBlock {
+                height: self.next_height,
+                error: "missing block".to_owned(),
+            });
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Reads the blocks of the chain archive, checking that they are
+/// contiguous and linked by their hashes. Proofs aren't verified here,
+/// that is done by the node applying the blocks.
+pub struct ChainArchiveReader<R> {
+    inner: R,
+    header: ChainArchiveHeader,
+    next_height: u32,
+    prev_hash: Option<StateHash>,
+}
+
+impl<R: Read> ChainArchiveReader<R> {
+    pub fn new(mut inner: R) -> Result<Self, ChainArchiveError> {
+        let mut magic = [0; CHAIN_ARCHIVE_MAGIC.len()];
+        inner.read_exact(&mut magic)?;
+        if &magic != CHAIN_ARCHIVE_MAGIC {
+            return Err(ChainArchiveError::InvalidMagic);
+        }
+        let header: ChainArchiveHeader = serde_json::from_slice(&read_chunk(&mut inner)?)
+            .map_err(|err| ChainArchiveError::InvalidHeader(err.to_string()))?;
+        if header.from_height > header.to_height {
+            return Err(ChainArchiveError::InvalidHeader(format!(
+                "invalid heights {}..={}",
+                header.from_height, header.to_height
+            )));
+        }
+        Ok(Self {
+            inner,
+            next_height: header.from_height,
+            header,
+            prev_hash: None,
+        })
+    }
+
+    pub fn header(&self) -> &ChainArchiveHeader {
+        &self.header
+    }
+
+    fn read_block(&mut self) -> Result<ArcBlockWithHash, ChainArchiveError> {
+        let height = self.next_height;
+        let invalid = |error: String| ChainArchiveError::InvalidBlock { height, error };
+
+        let bytes = read_chunk(&mut self.inner)?;
+        let block =
+            Block::binprot_read(&mut bytes.as_slice()).map_err(|err| invalid(err.to_string()))?;
+        let block = ArcBlockWithHash::try_new(Arc::new(block))
+            .map_err(|_| invalid("invalid field element".to_owned()))?;
+        if block.height() != height {
+            return Err(invalid(format!("unexpected height {}", block.height())));
+        }
+        let genesis_state_hash = genesis_state_hash(&block);
+        if genesis_state_hash != &self.header.genesis_state_hash {
+            return Err(invalid(format!(
+                "block of another chain: {genesis_state_hash}"
+            )));
+        }
+        check_parent(self.prev_hash.as_ref(), &block)?;
+
+        self.next_height = height.saturating_add(1);
+        self.prev_hash = Some(block.hash().clone());
+        Ok(block)
+    }
+}
+
+impl<R: Read> Iterator for ChainArchiveReader<R> {
+    type Item = Result<ArcBlockWithHash, ChainArchiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_height > self.header.to_height {
+            return None;
+        }
+        let result = self.read_block();
+        if result.is_err() {
+            // Rest of the archive can't be trusted.
+            self.next_height = self.header.to_height.saturating_add(1);
+        }
+        Some(result)
+    }
+}
+
+/// Genesis block refers to the previous chain instead of itself.
+fn genesis_state_hash(block: &ArcBlockWithHash) -> &StateHash {
+    if block.is_genesis() {
+        block.hash()
+    } else {
+        block.header().genesis_state_hash()
+    }
+}
+
+fn check_parent(
+    prev_hash: Option<&StateHash>,
+    block: &ArcBlockWithHash,
+) -> Result<(), ChainArchiveError> {
+    match prev_hash {
+        Some(expected) if expected != block.pred_hash() => Err(ChainArchiveError::Discontinuity {
+            height: block.height(),
+            expected: expected.clone(),
+            found: block.pred_hash().clone(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn write_chunk(w: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(bytes.len()).map_err(io::Error::other)?;
+    w.write_all(&len.to_le_bytes())?;
+    w.write_all(bytes)
+}
+
+fn read_chunk(r: &mut impl Read) -> Result<Vec<u8>, ChainArchiveError> {
+    let mut len = [0; 4];
+    r.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len);
+    if len > BLOCK_SIZE_MAX {
+        return Err(ChainArchiveError::InvalidHeader(format!(
+            "chunk of {len} bytes"
+        )));
+    }
+    let mut bytes = vec![0; len as usize];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_archive_header() {
+        assert!(matches!(
+            ChainArchiveReader::new(&b"omchain0"[..]),
+            Err(ChainArchiveError::InvalidMagic)
+        ));
+
+        let header = ChainArchiveHeader {
+            genesis_state_hash: "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ"
+                .parse()
+                .unwrap(),
+            from_height: 2,
+            to_height: 1,
+        };
+        let mut bytes = CHAIN_ARCHIVE_MAGIC.to_vec();
+        write_chunk(&mut bytes, &serde_json::to_vec(&header).unwrap()).unwrap();
+        assert!(matches!(
+            ChainArchiveReader::new(bytes.as_slice()),
+            Err(ChainArchiveError::InvalidHeader(_))
+        ));
+
+        let header = ChainArchiveHeader {
+            to_height: 3,
+            ..header
+        };
+        let mut bytes = CHAIN_ARCHIVE_MAGIC.to_vec();
+        write_chunk(&mut bytes, &serde_json::to_vec(&header).unwrap()).unwrap();
+        let mut reader = ChainArchiveReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.header(), &header);
+        assert_eq!(header.blocks_count(), 2);
+        // Truncated archive.
+        assert!(matches!(reader.next(), Some(Err(ChainArchiveError::Io(_)))));
+        assert!(reader.next().is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
@@ -7,10 +8,35 @@ use super::genesis::TransitionFrontierGenesisConfig;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransitionFrontierConfig {
     pub genesis: Arc<TransitionFrontierGenesisConfig>,
+    #[serde(default)]
+    pub candidates_prune: TransitionFrontierCandidatesPruneConfig,
 }
 
 impl TransitionFrontierConfig {
     pub fn new(genesis: Arc<TransitionFrontierGenesisConfig>) -> Self {
-        TransitionFrontierConfig { genesis }
+        TransitionFrontierConfig {
+            genesis,
+            candidates_prune: Default::default(),
+        }
+    }
+}
+
+/// Retention of the candidate blocks, which otherwise accumulate for the
+/// forks that never become better than the best tip.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitionFrontierCandidatesPruneConfig {
+    /// Candidates (and invalid block hashes) for slots more than this
+    /// many slots before the best tip are pruned.
+    pub retention_slots: u32,
+    /// Minimum interval between the prunings.
+    pub interval: Duration,
+}
+
+impl Default for TransitionFrontierCandidatesPruneConfig {
+    fn default() -> Self {
+        Self {
+            retention_slots: 290,
+            interval: Duration::from_secs(60),
+        }
     }
 }
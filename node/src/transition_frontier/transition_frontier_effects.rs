@@ -2,11 +2,14 @@ use mina_p2p_messages::gossip::GossipNetMessageV2;
 use redux::Timestamp;
 
 use crate::block_producer::BlockProducerAction;
+use crate::checkpoint::CheckpointAction;
 use crate::ledger::LEDGER_DEPTH;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
 use crate::p2p::P2pNetworkPubsubAction;
+use crate::rpc::RpcAction;
 use crate::snark_pool::{SnarkPoolAction, SnarkWork};
 use crate::stats::sync::SyncingLedger;
+use crate::watchtower::WatchtowerAction;
 use crate::{Store, TransactionPoolAction};
 
 use super::candidate::TransitionFrontierCandidateAction;
@@ -56,6 +59,25 @@ pub fn transition_frontier_effects<S: crate::Service>(
                 synced_effects(&meta, store);
             }
         }
+        TransitionFrontierAction::Candidate(TransitionFrontierCandidateAction::PruneStale) => {
+            let pruned = store
+                .state()
+                .transition_frontier
+                .candidates
+                .prune_stats()
+                .last;
+            if let Some(stats) = store.service.stats() {
+                stats.candidates_pruned(meta.time(), pruned);
+            }
+        }
+        TransitionFrontierAction::Candidate(TransitionFrontierCandidateAction::BlockReceived {
+            block,
+            ..
+        }) => {
+            if let Some(stats) = store.service.stats() {
+                stats.block_received(meta.time(), &block);
+            }
+        }
         TransitionFrontierAction::Candidate(_) => {}
         TransitionFrontierAction::Sync(a) => {
             match a {
@@ -204,6 +226,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                 TransitionFrontierSyncAction::BlocksNextApplySuccess {
                     ref hash,
                     just_emitted_a_proof: _,
+                    internal_commands: _,
                 } => {
                     if let Some(stats) = store.service.stats() {
                         if let Some(state) =
@@ -255,6 +278,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                             work: v.clone().into(),
                             received_t: meta.time(),
                             sender: own_peer_id,
+                            is_verified: true,
                         })
                         .collect();
 
@@ -324,11 +348,18 @@ fn synced_effects<S: crate::Service>(
 
     let best_tip_hash = best_tip.merkle_root_hash().clone();
     store.dispatch(TransitionFrontierCandidateAction::Prune);
+    store.dispatch(WatchtowerAction::BestChainUpdate);
+    store.dispatch(CheckpointAction::BestChainUpdate);
+    if store.state().transition_frontier.light {
+        // Rest requires ledgers, which aren't maintained in light mode.
+        return;
+    }
     store.dispatch(BlockProducerAction::BestTipUpdate {
         best_tip: best_tip.block.clone(),
     });
     store.dispatch(TransactionPoolAction::BestTipChanged {
         best_tip_hash: best_tip_hash.clone(),
+        is_extension: chain_diff.as_ref().is_some_and(|diff| !diff.reorg_best_tip),
     });
     if let Some(diff) = chain_diff {
         store.dispatch(TransactionPoolAction::ApplyTransitionFrontierDiff {
@@ -336,6 +367,7 @@ fn synced_effects<S: crate::Service>(
             diff,
         });
     }
+    store.dispatch(RpcAction::AccountSubscriptionsEvaluate);
 }
 
 // Handling of the actions related to the synchronization of a target ledger
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use ledger::scan_state::currency::{Amount, Magnitude};
+use ledger::scan_state::transaction_logic::transaction_applied::Varying;
+use ledger::scan_state::transaction_logic::{TransactionFailure, TransactionStatus};
+use mina_p2p_messages::v2::StateHash;
+use openmina_core::block::AppliedBlock;
+use openmina_node_account::AccountPublicKey;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InternalCommandKind {
+    Coinbase,
+    /// Part of the coinbase paid to the snark worker, whose work was
+    /// bought with it.
+    FeeTransferViaCoinbase,
+    FeeTransfer,
+}
+
+/// Coinbase or fee transfer, which is created by the staged ledger when
+/// the block is applied, so it isn't part of the block body.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InternalCommand {
+    pub kind: InternalCommandKind,
+    pub receiver: AccountPublicKey,
+    /// Amount received, for the coinbase it is without the part paid
+    /// with the [`InternalCommandKind::FeeTransferViaCoinbase`].
+    pub amount: Amount,
+    /// Position among the internal commands of the block.
+    pub sequence_no: u32,
+    /// Empty if the command was applied.
+    pub failures: Vec<TransactionFailure>,
+}
+
+impl InternalCommand {
+    /// Internal commands from the coinbases and fee transfers applied
+    /// for the block.
+    pub fn from_applied(applied: &[Varying]) -> Vec<Self> {
+        let failures = |status: &TransactionStatus| match status {
+            TransactionStatus::Applied => Vec::new(),
+            TransactionStatus::Failed(failures) => failures.concat(),
+        };
+        let mut commands = Vec::new();
+        let mut push = |kind, receiver: &mina_signer::CompressedPubKey, amount, failures| {
+            let sequence_no = u32::try_from(commands.len()).unwrap_or(u32::MAX);
+            commands.push(Self {
+                kind,
+                receiver: receiver.clone().into(),
+                amount,
+                sequence_no,
+                failures,
+            });
+        };
+
+        for varying in applied {
+            match varying {
+                Varying::Coinbase(applied) => {
+                    let coinbase = &applied.coinbase.data;
+                    let failures = failures(&applied.coinbase.status);
+                    let fee_transfer = coinbase.fee_transfer.as_ref();
+                    let transferred = fee_transfer
+                        .map(|ft| Amount::of_fee(&ft.fee))
+                        .unwrap_or(Amount::zero());
+                    let amount = coinbase
+                        .amount
+                        .checked_sub(&transferred)
+                        .unwrap_or(Amount::zero());
+                    push(
+                        InternalCommandKind::Coinbase,
+                        &coinbase.receiver,
+                        amount,
+                        failures.clone(),
+                    );
+                    if let Some(ft) = fee_transfer {
+                        push(
+                            InternalCommandKind::FeeTransferViaCoinbase,
+                            &ft.receiver_pk,
+                            transferred,
+                            failures,
+                        );
+                    }
+                }
+                Varying::FeeTransfer(applied) => {
+                    let failures = failures(&applied.fee_transfer.status);
+                    for ft in applied.fee_transfer.data.iter() {
+                        push(
+                            InternalCommandKind::FeeTransfer,
+                            &ft.receiver_pk,
+                            Amount::of_fee(&ft.fee),
+                            failures.clone(),
+                        );
+                    }
+                }
+                Varying::Command(_) => {}
+            }
+        }
+        commands
+    }
+}
+
+/// Index of the internal commands of the applied blocks, kept for the
+/// blocks of the best chain.
+///
+/// Commands are indexed when the block is applied, so the root block
+/// of the transition frontier after the sync doesn't have them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransitionFrontierInternalCommandIndex {
+    blocks: BTreeMap<StateHash, Vec<InternalCommand>>,
+}
+
+impl TransitionFrontierInternalCommandIndex {
+    pub fn insert(&mut self, block_hash: StateHash, commands: Vec<InternalCommand>) {
+        self.blocks.insert(block_hash, commands);
+    }
+
+    /// Removes the blocks which aren't in the new best chain.
+    pub fn update(&mut self, chain: &[AppliedBlock]) {
+        self.blocks
+            .retain(|hash, _| chain.iter().any(|b| b.hash() == hash));
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Internal commands of the block, `None` if it isn't indexed.
+    pub fn get(&self, block_hash: &StateHash) -> Option<&[InternalCommand]> {
+        self.blocks.get(block_hash).map(Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::currency::Fee;
+    use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
+    use ledger::scan_state::transaction_logic::transaction_applied::{
+        CoinbaseApplied, FeeTransferApplied,
+    };
+    use ledger::scan_state::transaction_logic::{
+        Coinbase, CoinbaseFeeTransfer, FeeTransfer, SingleFeeTransfer, WithStatus,
+    };
+    use ledger::TokenId;
+    use mina_signer::CompressedPubKey;
+
+    use super::*;
+
+    #[test]
+    fn test_internal_commands_from_applied() {
+        let producer = CompressedPubKey::from_address(
+            "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV",
+        )
+        .unwrap();
+        let snarker = CompressedPubKey::from_address(
+            "B62qqrHu7qJJrUekPYqNEbsMMzxDebqfApuyT5y6K9xgwm4TUe77kNd",
+        )
+        .unwrap();
+
+        let coinbase_fee_transfer = CoinbaseFeeTransfer::create(snarker.clone(), Fee::from_u64(10));
+        let coinbase = Coinbase::create(
+            Amount::from_u64(720),
+            producer.clone(),
+            Some(coinbase_fee_transfer),
+        )
+        .unwrap();
+        let fee_transfer = FeeTransfer::of_singles(OneOrTwo::One(SingleFeeTransfer::create(
+            producer.clone(),
+            Fee::from_u64(5),
+            TokenId::default(),
+        )))
+        .unwrap();
+        let applied = [
+            Varying::Coinbase(CoinbaseApplied {
+                coinbase: WithStatus::applied(coinbase),
+                new_accounts: Vec::new(),
+                burned_tokens: Amount::zero(),
+            }),
+            Varying::FeeTransfer(FeeTransferApplied {
+                fee_transfer: WithStatus::applied(fee_transfer),
+                new_accounts: Vec::new(),
+                burned_tokens: Amount::zero(),
+            }),
+        ];
+
+        let commands = InternalCommand::from_applied(&applied)
+            .into_iter()
+            .map(|cmd| (cmd.kind, cmd.receiver, cmd.amount.as_u64(), cmd.sequence_no))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            commands,
+            [
+                (
+                    InternalCommandKind::Coinbase,
+                    producer.clone().into(),
+                    710,
+                    0
+                ),
+                (
+                    InternalCommandKind::FeeTransferViaCoinbase,
+                    snarker.into(),
+                    10,
+                    1
+                ),
+                (InternalCommandKind::FeeTransfer, producer.into(), 5, 2),
+            ]
+        );
+    }
+}
@@ -1,8 +1,11 @@
-use super::sync::{SyncError, TransitionFrontierSyncState};
+use super::candidate::TransitionFrontierCandidateAction;
+use super::sync::{SyncError, TransitionFrontierSyncAction, TransitionFrontierSyncState};
 use super::{
     TransitionFrontierAction, TransitionFrontierActionWithMetaRef, TransitionFrontierState,
 };
 use openmina_core::block::AppliedBlock;
+use openmina_core::bug_condition;
+use snark::block_verify::SnarkBlockVerifyAction;
 
 impl TransitionFrontierState {
     pub fn reducer(
@@ -35,6 +38,8 @@ impl TransitionFrontierState {
                     just_emitted_a_proof: true,
                 };
                 state.best_chain = vec![genesis];
+                state.transaction_index.clear();
+                state.internal_command_index.clear();
                 state.sync = TransitionFrontierSyncState::Synced { time: meta.time() };
             }
             TransitionFrontierAction::GenesisProvenInject => {
@@ -49,11 +54,29 @@ impl TransitionFrontierState {
                         just_emitted_a_proof: true,
                     };
                     state.best_chain = vec![genesis];
+                    state.transaction_index.clear();
+                    state.internal_command_index.clear();
                 }
                 if !state.sync.is_pending() {
                     state.sync = TransitionFrontierSyncState::Synced { time: meta.time() };
                 }
             }
+            TransitionFrontierAction::Candidate(TransitionFrontierCandidateAction::PruneStale) => {
+                let Some(best_tip) = state.best_tip() else {
+                    bug_condition!("TransitionFrontierCandidateAction::PruneStale | no best tip");
+                    return;
+                };
+                let min_slot = best_tip
+                    .global_slot()
+                    .saturating_sub(state.config.candidates_prune.retention_slots);
+                let pending_verifications = state.candidates.prune_stale(meta.time(), min_slot);
+
+                // Dispatch
+                let dispatcher = state_context.into_dispatcher();
+                for req_id in pending_verifications {
+                    dispatcher.push(SnarkBlockVerifyAction::Cancel { req_id });
+                }
+            }
             TransitionFrontierAction::Candidate(a) => {
                 super::candidate::TransitionFrontierCandidatesState::reducer(
                     openmina_core::Substate::from_compatible_substate(state_context),
@@ -61,11 +84,24 @@ impl TransitionFrontierState {
                 );
             }
             TransitionFrontierAction::Sync(a) => {
+                if let TransitionFrontierSyncAction::BlocksNextApplySuccess {
+                    hash,
+                    internal_commands,
+                    ..
+                } = a
+                {
+                    state
+                        .internal_command_index
+                        .insert(hash.clone(), internal_commands.clone());
+                }
+                state.sync_peer_scores.update(&state.sync, a, meta.time());
                 let best_chain = state.best_chain.clone();
+                let light = state.light;
                 super::sync::TransitionFrontierSyncState::reducer(
                     openmina_core::Substate::from_compatible_substate(state_context),
                     meta.with_action(a),
                     &best_chain,
+                    light,
                 );
             }
             TransitionFrontierAction::Synced {
@@ -111,6 +147,8 @@ impl TransitionFrontierState {
                         > tip.height()
                 });
                 state.chain_diff = state.maybe_make_chain_diff(&new_chain);
+                state.transaction_index.update(&new_chain);
+                state.internal_command_index.update(&new_chain);
                 state.best_chain = new_chain;
                 state.sync = TransitionFrontierSyncState::Synced { time: meta.time() };
             }
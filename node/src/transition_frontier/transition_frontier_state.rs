@@ -7,12 +7,16 @@ use mina_p2p_messages::v2::{
 };
 use openmina_core::block::{AppliedBlock, ArcBlockWithHash};
 use openmina_core::bug_condition;
+use openmina_core::consensus::{finality::FinalityObservations, slots_per_window};
 use serde::{Deserialize, Serialize};
 
 use super::candidate::TransitionFrontierCandidatesState;
 use super::genesis::TransitionFrontierGenesisState;
-use super::sync::TransitionFrontierSyncState;
-use super::TransitionFrontierConfig;
+use super::sync::{TransitionFrontierSyncPeerScores, TransitionFrontierSyncState};
+use super::{
+    TransitionFrontierConfig, TransitionFrontierInternalCommandIndex,
+    TransitionFrontierTransactionIndex,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransitionFrontierState {
@@ -21,12 +25,20 @@ pub struct TransitionFrontierState {
     pub genesis: TransitionFrontierGenesisState,
     /// Current best known chain, from root of the transition frontier to best tip
     pub best_chain: Vec<AppliedBlock>,
+    /// User commands included in the `best_chain` blocks.
+    pub transaction_index: TransitionFrontierTransactionIndex,
+    /// Coinbases and fee transfers of the `best_chain` blocks.
+    #[serde(default)]
+    pub internal_command_index: TransitionFrontierInternalCommandIndex,
     /// Needed protocol states for applying transactions in the root
     /// scan state that we don't have in the `best_chain` list.
     pub needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
     pub candidates: TransitionFrontierCandidatesState,
     /// Transition frontier synchronization state
     pub sync: TransitionFrontierSyncState,
+    /// Scores of the peers queried during the sync.
+    #[serde(default)]
+    pub sync_peer_scores: TransitionFrontierSyncPeerScores,
 
     /// Blocks which had valid proof but failed block application or
     /// other validations after it reached transition frontier.
@@ -35,20 +47,27 @@ pub struct TransitionFrontierState {
     pub chain_diff: Option<BestTipDiff>,
     /// Archive mode enabled
     pub archive_enabled: bool,
+    /// Light mode enabled. Blocks are verified (consensus data and proof)
+    /// but not applied, so ledgers aren't synced nor maintained.
+    pub light: bool,
 }
 
 impl TransitionFrontierState {
-    pub fn new(config: TransitionFrontierConfig, archive_enabled: bool) -> Self {
+    pub fn new(config: TransitionFrontierConfig, archive_enabled: bool, light: bool) -> Self {
         Self {
             config,
             genesis: TransitionFrontierGenesisState::Idle,
             candidates: TransitionFrontierCandidatesState::new(),
             best_chain: Vec::with_capacity(290),
+            transaction_index: Default::default(),
+            internal_command_index: Default::default(),
             needed_protocol_states: Default::default(),
             sync: TransitionFrontierSyncState::Idle,
+            sync_peer_scores: Default::default(),
             blacklist: Default::default(),
             chain_diff: None,
             archive_enabled,
+            light,
         }
     }
 
@@ -68,17 +87,46 @@ impl TransitionFrontierState {
         self.best_chain.first()
     }
 
-    /// FIXME
-    /// Note(adonagy): This can be expensive, keep a map with all the tx hashis in the best chain
-    pub fn contains_transaction(&self, hash: &TransactionHash) -> bool {
-        self.best_chain.iter().any(|block| {
-            block
-                .body()
-                .transactions()
-                .any(|transaction| transaction.hash().as_ref().ok() == Some(hash))
+    /// Observations of the best chain used for estimating finality.
+    ///
+    /// Fork blocks are candidates and blacklisted blocks which aren't
+    /// higher than the best tip and aren't part of the best chain.
+    pub fn finality_observations(&self) -> Option<FinalityObservations> {
+        let best_tip = self.best_tip()?;
+        let root = self.root()?;
+        let consensus_state = best_tip.consensus_state();
+        let is_fork_block = |hash: &StateHash, height: u32| {
+            height >= root.height()
+                && height <= best_tip.height()
+                && !self.best_chain.iter().any(|b| b.hash() == hash)
+        };
+        let fork_candidates = self
+            .candidates
+            .iter()
+            .filter(|c| is_fork_block(c.block.hash(), c.height()))
+            .count();
+        let fork_blacklisted = self
+            .blacklist
+            .iter()
+            .filter(|(hash, height)| is_fork_block(hash, **height))
+            .count();
+
+        Some(FinalityObservations {
+            window_density: consensus_state
+                .sub_window_densities
+                .iter()
+                .map(|v| v.as_u32())
+                .sum(),
+            slots_per_window: slots_per_window(best_tip.constants()),
+            fork_blocks: (fork_candidates + fork_blacklisted) as u32,
+            chain_length: self.best_chain.len() as u32,
         })
     }
 
+    pub fn contains_transaction(&self, hash: &TransactionHash) -> bool {
+        self.transaction_index.contains(hash)
+    }
+
     /// Looks up state body by state hash.
     pub fn get_state_body(
         &self,
@@ -210,6 +258,9 @@ impl TransitionFrontierState {
                 .needed_protocol_states
                 .len(),
             "blacklist_size": self.blacklist.len(),
+            "candidates_size": self.candidates.len(),
+            "candidates_invalid_size": self.candidates.invalid_len(),
+            "candidates_prune": self.candidates.prune_stats(),
             "diff_tx_size": self
                 .chain_diff
                 .as_ref()
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use mina_p2p_messages::v2::{StagedLedgerDiffBodyStableV1, StateHash, TransactionHash};
+use openmina_core::block::AppliedBlock;
+use serde::{Deserialize, Serialize};
+
+/// Position of the user command in the staged ledger diff of the block.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionPosition {
+    /// Index of the staged ledger diff part (`0` or `1`).
+    pub diff_index: u8,
+    /// Index of the command in the commands of the diff part.
+    pub command_index: u32,
+}
+
+impl TransactionPosition {
+    /// Enumerates hashes of the user commands included in the block body.
+    pub fn enumerate(
+        body: &StagedLedgerDiffBodyStableV1,
+    ) -> impl Iterator<Item = (TransactionHash, TransactionPosition)> + '_ {
+        let diff = body.diff();
+        let first = diff.0.commands.iter().map(|c| (0, &c.data));
+        let second = diff
+            .1
+            .iter()
+            .flat_map(|d| d.commands.iter().map(|c| (1, &c.data)));
+        let mut command_indexes = [0u32; 2];
+        first
+            .chain(second)
+            .filter_map(move |(diff_index, command)| {
+                let command_index = command_indexes[diff_index as usize];
+                command_indexes[diff_index as usize] = command_index.saturating_add(1);
+                let hash = command.hash().ok()?;
+                Some((
+                    hash,
+                    TransactionPosition {
+                        diff_index,
+                        command_index,
+                    },
+                ))
+            })
+    }
+}
+
+/// Index of the user commands included in the blocks of the best chain.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransitionFrontierTransactionIndex {
+    transactions: BTreeMap<TransactionHash, (StateHash, TransactionPosition)>,
+    blocks: BTreeMap<StateHash, Vec<TransactionHash>>,
+}
+
+impl TransitionFrontierTransactionIndex {
+    /// Updates index to match the new best chain, indexing only the
+    /// blocks which weren't indexed before.
+    pub fn update(&mut self, chain: &[AppliedBlock]) {
+        let is_in_chain = |hash: &StateHash| chain.iter().any(|b| b.hash() == hash);
+        let removed = self
+            .blocks
+            .keys()
+            .filter(|hash| !is_in_chain(hash))
+            .cloned()
+            .collect::<Vec<_>>();
+        for block_hash in removed {
+            for tx_hash in self.blocks.remove(&block_hash).unwrap_or_default() {
+                if self
+                    .transactions
+                    .get(&tx_hash)
+                    .is_some_and(|(hash, _)| hash == &block_hash)
+                {
+                    self.transactions.remove(&tx_hash);
+                }
+            }
+        }
+
+        for block in chain {
+            if self.blocks.contains_key(block.hash()) {
+                continue;
+            }
+            let block_hash = block.hash().clone();
+            let hashes = TransactionPosition::enumerate(block.body())
+                .map(|(tx_hash, position)| {
+                    self.transactions
+                        .insert(tx_hash.clone(), (block_hash.clone(), position));
+                    tx_hash
+                })
+                .collect();
+            self.blocks.insert(block_hash, hashes);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.transactions.clear();
+        self.blocks.clear();
+    }
+
+    /// Returns block in which the transaction was included and position
+    /// of the transaction in that block.
+    pub fn get(&self, tx_hash: &TransactionHash) -> Option<(&StateHash, TransactionPosition)> {
+        self.transactions
+            .get(tx_hash)
+            .map(|(block_hash, position)| (block_hash, *position))
+    }
+
+    pub fn contains(&self, tx_hash: &TransactionHash) -> bool {
+        self.transactions.contains_key(tx_hash)
+    }
+}
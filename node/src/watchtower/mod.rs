@@ -0,0 +1,16 @@
+//! Alerts raised by the node monitoring the chain, see
+//! [`crate::config::NodeProfile::Watchtower`].
+//!
+//! Alerts are logged and kept in the state, from where they can be
+//! fetched with `GET /state?filter=$.watchtower.alerts`.
+
+mod watchtower_config;
+pub use watchtower_config::*;
+
+mod watchtower_state;
+pub use watchtower_state::*;
+
+mod watchtower_actions;
+pub use watchtower_actions::*;
+
+mod watchtower_reducer;
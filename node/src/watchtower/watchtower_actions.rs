@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+pub type WatchtowerActionWithMeta = redux::ActionWithMeta<WatchtowerAction>;
+pub type WatchtowerActionWithMetaRef<'a> = redux::ActionWithMeta<&'a WatchtowerAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum WatchtowerAction {
+    /// Best chain of the transition frontier changed.
+    BestChainUpdate,
+}
+
+impl redux::EnablingCondition<crate::State> for WatchtowerAction {
+    fn is_enabled(&self, state: &crate::State, _time: redux::Timestamp) -> bool {
+        match self {
+            WatchtowerAction::BestChainUpdate => {
+                state.watchtower.is_enabled() && state.transition_frontier.best_tip().is_some()
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchtowerConfig {
+    /// Producers expected to keep producing blocks on the best chain.
+    pub producers: Vec<AccountPublicKey>,
+    /// Switching the best tip to a fork at least this many blocks deep
+    /// raises an alert.
+    pub fork_depth_alert: u32,
+    /// Watched producer without a block on the best chain for this many
+    /// slots raises an alert.
+    pub producer_inactivity_slots: u32,
+}
+
+impl WatchtowerConfig {
+    pub const DEFAULT_FORK_DEPTH_ALERT: u32 = 3;
+    /// Day worth of the 3 minute slots.
+    pub const DEFAULT_PRODUCER_INACTIVITY_SLOTS: u32 = 480;
+}
+
+impl Default for WatchtowerConfig {
+    fn default() -> Self {
+        Self {
+            producers: Vec::new(),
+            fork_depth_alert: Self::DEFAULT_FORK_DEPTH_ALERT,
+            producer_inactivity_slots: Self::DEFAULT_PRODUCER_INACTIVITY_SLOTS,
+        }
+    }
+}
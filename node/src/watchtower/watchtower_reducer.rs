@@ -0,0 +1,46 @@
+use super::{WatchtowerAction, WatchtowerActionWithMetaRef, WatchtowerChainBlock, WatchtowerState};
+
+impl WatchtowerState {
+    pub fn reducer(
+        mut state_context: crate::Substate<crate::State>,
+        action: WatchtowerActionWithMetaRef<'_>,
+    ) {
+        let Ok(state) = state_context.get_substate_mut() else {
+            return;
+        };
+        let (action, meta) = action.split();
+
+        match action {
+            WatchtowerAction::BestChainUpdate => {
+                let best_chain = &state.transition_frontier.best_chain;
+                let Some(best_tip) = best_chain.last() else {
+                    return;
+                };
+                let cur_global_slot = state
+                    .cur_global_slot()
+                    .unwrap_or_else(|| best_tip.global_slot());
+                let best_chain = best_chain
+                    .iter()
+                    .map(|block| WatchtowerChainBlock {
+                        hash: block.hash(),
+                        global_slot: block.global_slot(),
+                        producer: block.producer(),
+                    })
+                    .collect::<Vec<_>>();
+                let Some(watchtower) = state.watchtower.get_mut() else {
+                    return;
+                };
+
+                for alert in watchtower.best_chain_update(&best_chain, cur_global_slot) {
+                    openmina_core::log::warn!(
+                        meta.time();
+                        kind = "WatchtowerAlert",
+                        alert = alert.to_string(),
+                        summary = format!("{alert:?}"),
+                    );
+                    watchtower.alert(meta.time(), alert);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash};
+use serde::{Deserialize, Serialize};
+
+use super::WatchtowerConfig;
+
+/// Oldest alerts are dropped after this many.
+pub const WATCHTOWER_ALERTS_MAX: usize = 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WatchtowerState(Option<WatchtowerEnabled>);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchtowerEnabled {
+    pub config: WatchtowerConfig,
+    /// Hashes of the best chain as of the last update, root first.
+    best_chain: Vec<StateHash>,
+    pub producers: BTreeMap<NonZeroCurvePoint, WatchedProducerState>,
+    /// Latest alerts, oldest first.
+    pub alerts: VecDeque<WatchtowerAlert>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WatchedProducerState {
+    /// Global slot of the root when the producer started being watched.
+    pub watched_since_slot: Option<u32>,
+    /// Latest block of the producer seen on the best chain.
+    pub last_block: Option<(u32, StateHash)>,
+    /// Inactivity was alerted, reset once the producer produces again.
+    pub inactive: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchtowerAlert {
+    pub time: redux::Timestamp,
+    pub kind: WatchtowerAlertKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, strum_macros::Display)]
+#[serde(tag = "kind")]
+pub enum WatchtowerAlertKind {
+    /// Best tip switched to the fork, abandoning `depth` blocks of the
+    /// previous best chain. Fork point is unknown if the fork is deeper
+    /// than the whole previous best chain.
+    DeepFork {
+        depth: u32,
+        old_best_tip: StateHash,
+        new_best_tip: StateHash,
+        fork_point: Option<StateHash>,
+    },
+    /// Watched producer has no block on the best chain for a while.
+    ProducerInactive {
+        producer: NonZeroCurvePoint,
+        global_slot: u32,
+        last_block_slot: Option<u32>,
+    },
+}
+
+/// Block of the best chain, as seen by the watchtower.
+pub struct WatchtowerChainBlock<'a> {
+    pub hash: &'a StateHash,
+    pub global_slot: u32,
+    pub producer: &'a NonZeroCurvePoint,
+}
+
+impl WatchtowerState {
+    pub fn new(config: Option<WatchtowerConfig>) -> Self {
+        Self(config.map(|config| {
+            WatchtowerEnabled {
+                producers: config
+                    .producers
+                    .iter()
+                    .map(|producer| (producer.clone().into(), Default::default()))
+                    .collect(),
+                config,
+                best_chain: Vec::new(),
+                alerts: VecDeque::new(),
+            }
+        }))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    pub fn get(&self) -> Option<&WatchtowerEnabled> {
+        self.0.as_ref()
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut WatchtowerEnabled> {
+        self.0.as_mut()
+    }
+}
+
+impl WatchtowerEnabled {
+    /// Checks the new best chain, root first, against the previous one and
+    /// the watched producers. Returns the alerts raised.
+    pub fn best_chain_update(
+        &mut self,
+        best_chain: &[WatchtowerChainBlock<'_>],
+        cur_global_slot: u32,
+    ) -> Vec<WatchtowerAlertKind> {
+        let mut alerts = Vec::new();
+        let (Some(root), Some(best_tip)) = (best_chain.first(), best_chain.last()) else {
+            return alerts;
+        };
+
+        let new_hashes = best_chain
+            .iter()
+            .map(|block| block.hash)
+            .collect::<BTreeSet<_>>();
+        if let Some(old_best_tip) = self.best_chain.last() {
+            if !new_hashes.contains(old_best_tip) {
+                let fork_point = self
+                    .best_chain
+                    .iter()
+                    .rposition(|hash| new_hashes.contains(hash));
+                let abandoned = fork_point.map_or(self.best_chain.len(), |i| {
+                    self.best_chain.len().saturating_sub(i + 1)
+                });
+                let depth = abandoned.try_into().unwrap_or(u32::MAX);
+                if depth >= self.config.fork_depth_alert {
+                    alerts.push(WatchtowerAlertKind::DeepFork {
+                        depth,
+                        old_best_tip: old_best_tip.clone(),
+                        new_best_tip: best_tip.hash.clone(),
+                        fork_point: fork_point.map(|i| self.best_chain[i].clone()),
+                    });
+                }
+            }
+        }
+        self.best_chain = best_chain.iter().map(|b| b.hash.clone()).collect();
+
+        for block in best_chain {
+            let Some(producer) = self.producers.get_mut(block.producer) else {
+                continue;
+            };
+            if producer
+                .last_block
+                .as_ref()
+                .is_none_or(|(slot, _)| *slot < block.global_slot)
+            {
+                producer.last_block = Some((block.global_slot, block.hash.clone()));
+                producer.inactive = false;
+            }
+        }
+        for (key, producer) in &mut self.producers {
+            let watched_since = *producer.watched_since_slot.get_or_insert(root.global_slot);
+            let last_block_slot = producer.last_block.as_ref().map(|(slot, _)| *slot);
+            let inactive_for =
+                cur_global_slot.saturating_sub(last_block_slot.unwrap_or(watched_since));
+            if !producer.inactive && inactive_for >= self.config.producer_inactivity_slots {
+                producer.inactive = true;
+                alerts.push(WatchtowerAlertKind::ProducerInactive {
+                    producer: key.clone(),
+                    global_slot: cur_global_slot,
+                    last_block_slot,
+                });
+            }
+        }
+
+        alerts
+    }
+
+    pub fn alert(&mut self, time: redux::Timestamp, kind: WatchtowerAlertKind) {
+        if self.alerts.len() >= WATCHTOWER_ALERTS_MAX {
+            self.alerts.pop_front();
+        }
+        self.alerts.push_back(WatchtowerAlert { time, kind });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::account::AccountPublicKey;
+
+    use super::*;
+
+    fn chain(blocks: &[(StateHash, NonZeroCurvePoint)]) -> Vec<WatchtowerChainBlock<'_>> {
+        blocks
+            .iter()
+            .enumerate()
+            .map(|(slot, (hash, producer))| WatchtowerChainBlock {
+                hash,
+                global_slot: slot as u32,
+                producer,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn deep_fork_and_inactive_producer_alerted() {
+        let producer: AccountPublicKey = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg"
+            .parse()
+            .unwrap();
+        let mut watchtower = WatchtowerState::new(Some(WatchtowerConfig {
+            producers: vec![producer.clone()],
+            fork_depth_alert: 2,
+            producer_inactivity_slots: 10,
+        }));
+        let watchtower = watchtower.get_mut().unwrap();
+        let producer: NonZeroCurvePoint = producer.into();
+        let other: NonZeroCurvePoint = "B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g"
+            .parse::<AccountPublicKey>()
+            .unwrap()
+            .into();
+        let hash = |i: u64| StateHash::from_fp(mina_hasher::Fp::from(i));
+        let block = |i: u64, producer: &NonZeroCurvePoint| (hash(i), producer.clone());
+
+        let best_chain = [
+            block(0, &other),
+            block(1, &producer),
+            block(2, &other),
+            block(3, &other),
+        ];
+        assert!(watchtower
+            .best_chain_update(&chain(&best_chain), 5)
+            .is_empty());
+
+        // Reorg abandoning a single block isn't alerted.
+        let best_chain = [
+            block(0, &other),
+            block(1, &producer),
+            block(2, &other),
+            block(4, &other),
+        ];
+        assert!(watchtower
+            .best_chain_update(&chain(&best_chain), 6)
+            .is_empty());
+
+        let best_chain = [block(0, &other), block(1, &producer), block(5, &other)];
+        assert_eq!(
+            watchtower.best_chain_update(&chain(&best_chain), 7),
+            vec![WatchtowerAlertKind::DeepFork {
+                depth: 2,
+                old_best_tip: hash(4),
+                new_best_tip: hash(5),
+                fork_point: Some(hash(1)),
+            }]
+        );
+
+        // Latest block of the producer is at the slot 1, inactivity is
+        // alerted only once.
+        assert_eq!(
+            watchtower.best_chain_update(&chain(&best_chain), 11),
+            vec![WatchtowerAlertKind::ProducerInactive {
+                producer: producer.clone(),
+                global_slot: 11,
+                last_block_slot: Some(1),
+            }]
+        );
+        assert!(watchtower
+            .best_chain_update(&chain(&best_chain), 12)
+            .is_empty());
+    }
+}
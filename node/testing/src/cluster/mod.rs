@@ -26,7 +26,9 @@ use node::core::invariants::InvariantsState;
 use node::core::log::system_time;
 use node::core::requests::RpcId;
 use node::core::{thread, warn};
-use node::p2p::{P2pConnectionEvent, P2pEvent, P2pLimits, P2pMeshsubConfig, PeerId};
+use node::p2p::{
+    P2pConnectionEvent, P2pCorrelationId, P2pEvent, P2pLimits, P2pMeshsubConfig, PeerId,
+};
 use node::snark::{BlockVerifier, TransactionVerifier, VerifierSRS};
 use node::{
     event_source::Event,
@@ -259,28 +261,38 @@ impl Cluster {
         let consensus_consts =
             ConsensusConstants::create(constraint_constants(), &protocol_constants);
 
-        let config = Config {
-            ledger: LedgerConfig {},
+        let mut config = Config {
+            ledger: LedgerConfig::default(),
             snark: SnarkConfig {
                 // TODO(binier): use cache
                 block_verifier_index: self.block_verifier_index.clone(),
                 block_verifier_srs: self.verifier_srs.clone(),
                 work_verifier_index: self.work_verifier_index.clone(),
                 work_verifier_srs: self.verifier_srs.clone(),
+                verify_admission: Default::default(),
             },
+            snark_pool: Default::default(),
             global: GlobalConfig {
                 build: BuildEnv::get().into(),
                 snarker: testing_config.snark_worker,
                 consensus_constants: consensus_consts.clone(),
                 client_port: Some(http_port),
                 testing_run: true,
+                profile: Default::default(),
+                payout_pub_key: None,
+                rpc_response_limits: Default::default(),
+                sibling_nodes: Vec::new(),
             },
             p2p: P2pConfig {
                 libp2p_port: Some(libp2p_port),
                 listen_port: Some(http_port),
                 identity_pub_key: p2p_sec_key.public_key(),
+                webrtc_identity_pub_key: None,
                 initial_peers,
+                preferred_peers: Vec::new(),
                 external_addrs: vec![],
+                listen_family: testing_config.listen_family,
+                dial_family: None,
                 enabled_channels: ChannelId::iter_all().collect(),
                 peer_discovery: testing_config.peer_discovery,
                 timeouts: testing_config.timeouts,
@@ -292,6 +304,7 @@ impl Cluster {
                         .unwrap_or_default(),
                     ..Default::default()
                 },
+                feature_flags: Default::default(),
             },
             transition_frontier: TransitionFrontierConfig::new(testing_config.genesis),
             block_producer: block_producer_config,
@@ -300,8 +313,19 @@ impl Cluster {
                 trust_system: (),
                 pool_max_size: 3000,
                 slot_tx_end: None,
+                zkapp_limits: Default::default(),
+                fee_payer_limits: Default::default(),
             },
+            watchtower: None,
+            checkpoint: Default::default(),
+            fee_sponsor: None,
         };
+        if let Some(topics) = &testing_config.gossip_topics {
+            config
+                .p2p
+                .set_gossip_topics(topics)
+                .expect("invalid gossip topics");
+        }
 
         let mut service_builder = NodeServiceBuilder::new(rng_seed);
         service_builder
@@ -719,8 +743,10 @@ impl Cluster {
                             .get(node_id.index())
                             .ok_or_else(|| anyhow::anyhow!("node {node_id:?} not found"))?;
                         let res_is_ok = res.is_ok();
+                        // Correlation id isn't recorded, nor is it a part of
+                        // the event's string representation used for matching.
                         let event = Event::P2p(P2pEvent::Connection(
-                            P2pConnectionEvent::Finalized(peer_id, res),
+                            P2pConnectionEvent::Finalized(peer_id, P2pCorrelationId::next(), res),
                         ));
 
                         if res_is_ok {
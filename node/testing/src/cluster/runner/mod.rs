@@ -1,3 +1,6 @@
+mod reorg;
+pub use reorg::*;
+
 mod run;
 pub use run::*;
 
@@ -5,8 +8,9 @@ use std::{path::PathBuf, time::Duration};
 
 use ledger::BaseLedger;
 use node::account::{AccountPublicKey, AccountSecretKey};
-use node::{event_source::Event, ledger::LedgerService, ActionKind, State};
+use node::{event_source::Event, ledger::LedgerService, Action, ActionKind, State};
 use rand::{rngs::StdRng, SeedableRng};
+use redux::EnablingCondition;
 use time::OffsetDateTime;
 
 use crate::node::OcamlStep;
@@ -49,6 +53,29 @@ impl<'a> ClusterRunner<'a> {
         self.cluster.node_mut(node_id)
     }
 
+    /// Creates a [`FakePeer`](crate::service::FakePeer), which the node
+    /// can connect to.
+    #[cfg(feature = "p2p-webrtc")]
+    pub fn fake_peer_add(
+        &mut self,
+        node_id: ClusterNodeId,
+        sec_key: node::p2p::identity::SecretKey,
+    ) -> Option<crate::service::FakePeer> {
+        Some(self.node_mut(node_id)?.fake_peer_add(sec_key))
+    }
+
+    /// Dispatches the action on the node directly.
+    ///
+    /// It isn't recorded as a scenario step, so runs using it can't be
+    /// replayed.
+    pub fn dispatch_action<T>(&mut self, node_id: ClusterNodeId, action: T) -> bool
+    where
+        T: Into<Action> + EnablingCondition<State>,
+    {
+        self.node_mut(node_id)
+            .is_some_and(|node| node.dispatch(action))
+    }
+
     pub fn ocaml_node(&self, node_id: ClusterOcamlNodeId) -> Option<&OcamlNode> {
         self.cluster.ocaml_node(node_id)
     }
@@ -0,0 +1,203 @@
+//! Controlled chain reorgs, so that the handling of reorgs (by the node,
+//! archive writer, subscriptions, pool revalidation, ...) can be tested
+//! deterministically.
+//!
+//! The alternative chain is built by a fork producer, a block producer
+//! node that isn't connected to the node under test. Once the fork is
+//! heavier than the chain of the node under test, the node is connected
+//! to the fork producer, from which it syncs the fork and switches to it.
+
+use std::{collections::BTreeSet, time::Duration};
+
+use mina_p2p_messages::v2::StateHash;
+use node::{core::consensus::consensus_take, ActionKind, State};
+
+use crate::{
+    cluster::ClusterNodeId,
+    scenario::{ListenerNode, ScenarioStep},
+};
+
+use super::{RunCfg, RunCfgAdvanceTime};
+
+const STEP_DURATION: Duration = Duration::from_secs(5);
+/// Time advanced while the nodes are idle, fixed so that the reorg is
+/// reproducible.
+const TIME_STEP: RunCfgAdvanceTime = RunCfgAdvanceTime::Fixed(100);
+
+#[derive(Debug, Clone)]
+pub struct ReorgOutcome {
+    /// Last block common to the old and the new best chain, `None` if it
+    /// is older than the root of the transition frontier.
+    pub fork_point: Option<StateHash>,
+    /// Blocks of the old best chain after the fork point.
+    pub rolled_back: Vec<StateHash>,
+    /// Blocks of the new best chain after the fork point.
+    pub applied: Vec<StateHash>,
+}
+
+fn best_chain_hashes(state: &State) -> Vec<StateHash> {
+    state
+        .transition_frontier
+        .best_chain
+        .iter()
+        .map(|block| block.hash().clone())
+        .collect()
+}
+
+impl super::ClusterRunner<'_> {
+    fn node_state(&self, node_id: ClusterNodeId) -> anyhow::Result<&State> {
+        self.node(node_id)
+            .map(|node| node.state())
+            .ok_or_else(|| anyhow::anyhow!("node {node_id} not found"))
+    }
+
+    /// Whether the best tip of `node_id` is on the best chain of `other`.
+    fn is_on_chain_of(&self, node_id: ClusterNodeId, other: ClusterNodeId) -> anyhow::Result<bool> {
+        let best_tip = self.node_state(node_id)?.transition_frontier.best_tip();
+        let other_chain = &self.node_state(other)?.transition_frontier.best_chain;
+        Ok(best_tip.is_some_and(|tip| other_chain.iter().any(|block| block.hash() == tip.hash())))
+    }
+
+    /// Whether the best chain of `fork_source` would be taken by `node_id`
+    /// over its own best chain.
+    pub fn is_chain_heavier(&self, node_id: ClusterNodeId, fork_source: ClusterNodeId) -> bool {
+        let best_tip = |node_id| {
+            self.node(node_id)
+                .and_then(|node| node.state().transition_frontier.best_tip())
+        };
+        let (Some(tip), Some(candidate)) = (best_tip(node_id), best_tip(fork_source)) else {
+            return false;
+        };
+        tip.hash() != candidate.hash()
+            && consensus_take(
+                tip.consensus_state(),
+                candidate.consensus_state(),
+                tip.hash(),
+                candidate.hash(),
+            )
+    }
+
+    /// Advances the time to the next slot won by `producer`. Returns
+    /// `false` if it isn't known yet, e.g. the slots aren't evaluated.
+    async fn advance_to_next_won_slot(&mut self, producer: ClusterNodeId) -> anyhow::Result<bool> {
+        let state = self.node_state(producer)?;
+        let next_won_slot = None.or_else(|| {
+            let best_tip = state.transition_frontier.best_tip()?;
+            let cur_global_slot = state.cur_global_slot()?;
+            state
+                .block_producer
+                .vrf_evaluator()?
+                .next_won_slot(cur_global_slot, best_tip)
+        });
+        let Some(won_slot) = next_won_slot else {
+            return Ok(false);
+        };
+        let by_nanos = won_slot
+            .slot_time
+            .checked_sub(state.time())
+            .map_or(0, |dur| dur.as_nanos() as u64);
+        self.exec_step(ScenarioStep::AdvanceTime { by_nanos })
+            .await?;
+        Ok(true)
+    }
+
+    /// Runs the cluster until `fork_producer` builds a chain heavier than
+    /// the one of `node_id`, jumping from one won slot of the fork
+    /// producer to the next, at most `max_slots` times.
+    ///
+    /// The fork producer must not be connected to `node_id`, nor to the
+    /// nodes it syncs from, otherwise both would end up on the same chain.
+    pub async fn build_heavier_fork(
+        &mut self,
+        node_id: ClusterNodeId,
+        fork_producer: ClusterNodeId,
+        max_slots: usize,
+    ) -> anyhow::Result<()> {
+        for _ in 0..max_slots {
+            if self.is_chain_heavier(node_id, fork_producer) {
+                return Ok(());
+            }
+            self.advance_to_next_won_slot(fork_producer).await?;
+            let _ = self
+                .run(
+                    RunCfg::default()
+                        .timeout(STEP_DURATION)
+                        .advance_time(TIME_STEP)
+                        .action_handler(move |id, _, _, action| {
+                            id == fork_producer
+                                && action.action().kind() == ActionKind::TransitionFrontierSynced
+                        }),
+                )
+                .await;
+        }
+        if !self.is_chain_heavier(node_id, fork_producer) {
+            anyhow::bail!(
+                "{fork_producer} didn't build a chain heavier than {node_id} in {max_slots} won slots"
+            );
+        }
+        Ok(())
+    }
+
+    /// Forces `node_id` to reorg to the heavier chain of `fork_source`, by
+    /// connecting it to `fork_source` and waiting until it switches over,
+    /// for at most `max_steps` runs.
+    pub async fn force_reorg(
+        &mut self,
+        node_id: ClusterNodeId,
+        fork_source: ClusterNodeId,
+        max_steps: usize,
+    ) -> anyhow::Result<ReorgOutcome> {
+        if !self.is_chain_heavier(node_id, fork_source) {
+            anyhow::bail!("chain of {fork_source} isn't heavier than the one of {node_id}");
+        }
+        let old_chain = best_chain_hashes(self.node_state(node_id)?);
+
+        self.exec_step(ScenarioStep::ConnectNodes {
+            dialer: node_id,
+            listener: ListenerNode::Rust(fork_source),
+        })
+        .await?;
+
+        let mut steps = 0;
+        while !self.is_on_chain_of(node_id, fork_source)? {
+            if steps >= max_steps {
+                anyhow::bail!(
+                    "node {node_id} didn't reorg to the chain of {fork_source} in {max_steps} steps"
+                );
+            }
+            steps = steps.saturating_add(1);
+            let _ = self
+                .run(
+                    RunCfg::default()
+                        .timeout(STEP_DURATION)
+                        .advance_time(TIME_STEP)
+                        .action_handler(move |id, _, _, action| {
+                            id == node_id
+                                && action.action().kind() == ActionKind::TransitionFrontierSynced
+                        }),
+                )
+                .await;
+        }
+
+        let new_chain = best_chain_hashes(self.node_state(node_id)?);
+        let new_chain_set = new_chain.iter().collect::<BTreeSet<_>>();
+        let fork_point = old_chain
+            .iter()
+            .rev()
+            .find(|hash| new_chain_set.contains(hash))
+            .cloned();
+        let after_fork_point = |chain: &[StateHash]| -> Vec<StateHash> {
+            let start = fork_point
+                .as_ref()
+                .and_then(|fork_point| chain.iter().position(|hash| hash == fork_point))
+                .map_or(0, |i| i + 1);
+            chain[start..].to_vec()
+        };
+
+        Ok(ReorgOutcome {
+            rolled_back: after_fork_point(&old_chain),
+            applied: after_fork_point(&new_chain),
+            fork_point,
+        })
+    }
+}
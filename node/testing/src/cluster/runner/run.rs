@@ -28,6 +28,9 @@ pub enum RunCfgAdvanceTime {
     /// Set the range of time in milliseconds, with which time will be
     /// advanced during `run` function execution.
     Rand(std::ops::RangeInclusive<u64>),
+    /// Advance time by the fixed number of milliseconds, so that the
+    /// run doesn't depend on the random or the real time.
+    Fixed(u64),
     /// Advance time so that node's time matches the real time.
     #[default]
     Real,
@@ -124,6 +127,7 @@ impl super::ClusterRunner<'_> {
                             let (start, end) = (start * 1_000_000, end * 1_000_000);
                             self.rng.gen_range(start..end)
                         }
+                        RunCfgAdvanceTime::Fixed(millis) => millis.saturating_mul(1_000_000),
                         RunCfgAdvanceTime::Real => {
                             let now = redux::Timestamp::global_now();
                             let latest: &mut redux::Timestamp =
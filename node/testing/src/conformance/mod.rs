@@ -0,0 +1,776 @@
+//! Protocol conformance checks against an external peer.
+//!
+//! Launches a Rust node, connects it to the peer (OCaml or Rust) and
+//! checks that every channel gets ready and the messages requested on it
+//! are sent back, and that every p2p rpc is answered correctly, both for
+//! valid requests and for the boundary ones (unknown hashes, out of range
+//! offsets, the oldest block, the root ledger address).
+
+mod report;
+pub use report::*;
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use mina_p2p_messages::v2::{
+    LedgerHash, MinaLedgerSyncLedgerAnswerStableV2, MinaLedgerSyncLedgerQueryStableV1, StateHash,
+    TransactionHash,
+};
+use node::{
+    core::{
+        block::ArcBlockWithHash,
+        snark::{LedgerHashTransitionPasses, SnarkJobId},
+    },
+    ledger::LedgerAddress,
+    p2p::{
+        channels::{
+            best_tip::P2pChannelsBestTipAction,
+            checkpoint::P2pChannelsCheckpointAction,
+            rpc::{P2pChannelsRpcAction, P2pRpcKind, P2pRpcRequest, P2pRpcResponse},
+            snark::P2pChannelsSnarkAction,
+            snark_job_commitment::P2pChannelsSnarkJobCommitmentAction,
+            transaction::P2pChannelsTransactionAction,
+            ChannelId, P2pChannelsAction, P2pChannelsState,
+        },
+        connection::outgoing::P2pConnectionOutgoingInitOpts,
+        disconnection::P2pDisconnectionAction,
+        P2pAction, PeerId,
+    },
+    Action, State,
+};
+
+use crate::{
+    cluster::{
+        runner::{ClusterRunner, DynEffectsData, RunCfg},
+        ClusterNodeId,
+    },
+    node::RustNodeTestingConfig,
+    scenario::{ListenerNode, ScenarioStep},
+};
+
+const PROTOCOL_CONNECTION: &str = "connection";
+
+/// What is the expected answer to the rpc request.
+#[derive(Debug, Clone, Copy)]
+enum RpcExpect {
+    /// Response of the same kind as the request.
+    Response,
+    /// Empty response, since the peer doesn't have what was requested.
+    Empty,
+}
+
+type RpcResult = Result<Option<Box<P2pRpcResponse>>, String>;
+
+/// Messages exchanged with the peer on the propagation channel.
+#[derive(Debug, Default, Clone, Copy)]
+struct ChannelExchange {
+    /// Peer requested the messages from us.
+    requested: bool,
+    /// Number of the messages the peer promised to send.
+    promised: Option<u8>,
+    /// Number of the messages received from the peer.
+    received: usize,
+}
+
+impl ChannelExchange {
+    fn is_delivered(&self) -> bool {
+        self.promised
+            .is_some_and(|promised| self.received >= promised as usize)
+    }
+}
+
+/// Runs conformance checks against the peer listening on `peer`.
+///
+/// Peer must be on the same network as the node launched by the harness
+/// (devnet by default).
+pub struct Conformance {
+    peer: P2pConnectionOutgoingInitOpts,
+    config: RustNodeTestingConfig,
+    timeout: Duration,
+}
+
+impl Conformance {
+    pub fn new(peer: P2pConnectionOutgoingInitOpts) -> Self {
+        Self {
+            peer,
+            config: RustNodeTestingConfig::devnet_default(),
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Config of the node which connects to the peer.
+    pub fn config(mut self, config: RustNodeTestingConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Timeout for each step (connecting, channels getting ready,
+    /// every rpc request).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub async fn run(self, mut runner: ClusterRunner<'_>) -> ConformanceReport {
+        let mut report = ConformanceReport::new(self.peer.to_string());
+        let peer_id = *self.peer.peer_id();
+
+        let node_id = runner.add_rust_node(self.config.clone());
+        eprintln!("launched node({node_id}), connecting to: {}", self.peer);
+        let connected = runner
+            .exec_step(ScenarioStep::ConnectNodes {
+                dialer: node_id,
+                listener: ListenerNode::Custom(self.peer.clone()),
+            })
+            .await;
+        if let Err(err) = connected {
+            report.fail(PROTOCOL_CONNECTION, "dial", err.to_string());
+            return report;
+        }
+        report.pass(PROTOCOL_CONNECTION, "dial");
+
+        let ready = self
+            .wait_for(&mut runner, node_id, move |state| {
+                state.p2p.get_ready_peer(&peer_id).is_some()
+            })
+            .await;
+        if !ready {
+            report.fail(
+                PROTOCOL_CONNECTION,
+                "handshake",
+                "peer isn't ready before the timeout",
+            );
+            return report;
+        }
+        report.pass(PROTOCOL_CONNECTION, "handshake");
+
+        self.check_channels(&mut runner, node_id, peer_id, &mut report)
+            .await;
+        self.check_rpcs(&mut runner, node_id, peer_id, &mut report)
+            .await;
+        report
+    }
+
+    fn is_libp2p(runner: &ClusterRunner<'_>, node_id: ClusterNodeId, peer_id: PeerId) -> bool {
+        runner
+            .node(node_id)
+            .and_then(|node| node.state().p2p.ready())
+            .is_some_and(|p2p| p2p.is_libp2p_peer(&peer_id))
+    }
+
+    fn expected_channels(is_libp2p: bool) -> Vec<ChannelId> {
+        ChannelId::iter_all()
+            .filter(|id| !is_libp2p || id.supported_by_libp2p())
+            .collect()
+    }
+
+    async fn check_channels(
+        &self,
+        runner: &mut ClusterRunner<'_>,
+        node_id: ClusterNodeId,
+        peer_id: PeerId,
+        report: &mut ConformanceReport,
+    ) {
+        let is_libp2p = Self::is_libp2p(runner, node_id, peer_id);
+        let expected = Self::expected_channels(is_libp2p);
+        let expected_clone = expected.clone();
+        self.wait_for(runner, node_id, move |state| {
+            state.p2p.get_ready_peer(&peer_id).is_some_and(|peer| {
+                expected_clone
+                    .iter()
+                    .all(|id| channel_is_ready(&peer.channels, *id))
+            })
+        })
+        .await;
+
+        let state = runner.node(node_id).unwrap().state();
+        let channels = state
+            .p2p
+            .get_ready_peer(&peer_id)
+            .map(|peer| &peer.channels);
+        for id in ChannelId::iter_all() {
+            if !expected.contains(&id) {
+                report.skip(id.name(), "ready", "not supported by libp2p");
+            } else if channels.is_some_and(|channels| channel_is_ready(channels, id)) {
+                report.pass(id.name(), "ready");
+            } else {
+                report.fail(id.name(), "ready", "not ready before the timeout");
+            }
+        }
+
+        if is_libp2p {
+            for id in PROPAGATION_CHANNELS {
+                report.skip(id.name(), "exchange", "propagated over gossip with libp2p");
+            }
+        } else {
+            self.check_channel_messages(runner, node_id, peer_id, report)
+                .await;
+        }
+    }
+
+    /// Requests the best tip and a single message (the smallest limit) on
+    /// every other propagation channel, and checks that the peer sends
+    /// what it promised without getting disconnected for violating the
+    /// protocol. Peer may have nothing to send on the item channels, so a
+    /// promise isn't required there.
+    async fn check_channel_messages(
+        &self,
+        runner: &mut ClusterRunner<'_>,
+        node_id: ClusterNodeId,
+        peer_id: PeerId,
+        report: &mut ConformanceReport,
+    ) {
+        const LIMIT: u8 = 1;
+
+        // Node may have requested them already, then the pending requests
+        // are answered instead.
+        runner.dispatch_action(node_id, P2pChannelsBestTipAction::RequestSend { peer_id });
+        runner.dispatch_action(
+            node_id,
+            P2pChannelsTransactionAction::RequestSend {
+                peer_id,
+                limit: LIMIT,
+            },
+        );
+        runner.dispatch_action(
+            node_id,
+            P2pChannelsSnarkAction::RequestSend {
+                peer_id,
+                limit: LIMIT,
+            },
+        );
+        runner.dispatch_action(
+            node_id,
+            P2pChannelsSnarkJobCommitmentAction::RequestSend {
+                peer_id,
+                limit: LIMIT,
+            },
+        );
+        runner.dispatch_action(
+            node_id,
+            P2pChannelsCheckpointAction::RequestSend {
+                peer_id,
+                limit: LIMIT,
+            },
+        );
+
+        let exchanges = DynEffectsData::new(BTreeMap::<ChannelId, ChannelExchange>::new());
+        let disconnected = DynEffectsData::new(None::<String>);
+        let (exchanges_clone, disconnected_clone) = (exchanges.clone(), disconnected.clone());
+        let _ =
+            runner
+                .run(RunCfg::default().timeout(self.timeout).action_handler(
+                    move |id, _, _, action| {
+                        if id != node_id {
+                            return false;
+                        }
+                        let action = match action.action() {
+                            Action::P2p(P2pAction::Disconnection(
+                                P2pDisconnectionAction::Init { peer_id: p, reason },
+                            )) if *p == peer_id => {
+                                *disconnected_clone.inner() = Some(reason.to_string());
+                                return true;
+                            }
+                            Action::P2p(P2pAction::Channels(action)) => action,
+                            _ => return false,
+                        };
+                        let Some((channel, message)) = channel_message(action, peer_id) else {
+                            return false;
+                        };
+                        let mut exchanges = exchanges_clone.inner();
+                        let exchange = exchanges.entry(channel).or_default();
+                        match message {
+                            ChannelMessage::Request => exchange.requested = true,
+                            ChannelMessage::Promise(count) => exchange.promised = Some(count),
+                            ChannelMessage::Items(count) => {
+                                exchange.received = exchange.received.saturating_add(count)
+                            }
+                            ChannelMessage::BestTip => {
+                                let promised = exchange.promised.unwrap_or(0).saturating_add(1);
+                                exchange.promised = Some(promised);
+                                exchange.received = exchange.received.saturating_add(1);
+                            }
+                        }
+                        PROPAGATION_CHANNELS.iter().all(|id| {
+                            exchanges
+                                .get(id)
+                                .is_some_and(|e| e.requested && e.is_delivered())
+                        })
+                    },
+                ))
+                .await;
+
+        let exchanges = exchanges.inner();
+        let disconnected = disconnected.inner();
+        for id in PROPAGATION_CHANNELS {
+            let exchange = exchanges.get(&id).copied().unwrap_or_default();
+            let result = match (&*disconnected, exchange.promised) {
+                (Some(reason), _) => Err(format!("peer disconnected: {reason}")),
+                (None, Some(promised)) if exchange.received != promised as usize => Err(format!(
+                    "promised {promised} messages, sent {}",
+                    exchange.received
+                )),
+                (None, Some(_)) => Ok(()),
+                (None, None) if id == ChannelId::BestTipPropagation => {
+                    Err("no best tip before the timeout".to_owned())
+                }
+                (None, None) => {
+                    report.skip(id.name(), "exchange", "peer had nothing to send");
+                    continue;
+                }
+            };
+            match result {
+                Ok(()) => report.pass(id.name(), "exchange"),
+                Err(err) => report.fail(id.name(), "exchange", err),
+            }
+            if exchange.requested {
+                report.pass(id.name(), "request");
+            } else {
+                report.skip(id.name(), "request", "peer didn't request anything");
+            }
+        }
+    }
+
+    async fn check_rpcs(
+        &self,
+        runner: &mut ClusterRunner<'_>,
+        node_id: ClusterNodeId,
+        peer_id: PeerId,
+        report: &mut ConformanceReport,
+    ) {
+        let protocol = rpc_protocol(P2pRpcKind::BestTipWithProof);
+        let best_tip = match self
+            .rpc(runner, node_id, peer_id, P2pRpcRequest::BestTipWithProof)
+            .await
+        {
+            Ok(Some(response)) => match *response {
+                P2pRpcResponse::BestTipWithProof(resp) => {
+                    match (
+                        ArcBlockWithHash::try_new(resp.best_tip),
+                        ArcBlockWithHash::try_new(resp.proof.1),
+                    ) {
+                        (Ok(best_tip), Ok(root)) => {
+                            report.pass(&protocol, "valid");
+                            Some((best_tip, root))
+                        }
+                        _ => {
+                            report.fail(&protocol, "valid", "invalid block in the response");
+                            None
+                        }
+                    }
+                }
+                response => {
+                    report.fail(
+                        &protocol,
+                        "valid",
+                        format!("unexpected response: {:?}", response.kind()),
+                    );
+                    None
+                }
+            },
+            Ok(None) => {
+                report.fail(&protocol, "valid", "empty response");
+                None
+            }
+            Err(err) => {
+                report.fail(&protocol, "valid", err);
+                None
+            }
+        };
+
+        let mut cases = vec![
+            (
+                "unknown_hash",
+                P2pRpcRequest::Block(StateHash::zero()),
+                RpcExpect::Empty,
+            ),
+            (
+                "unknown_ledger",
+                P2pRpcRequest::LedgerQuery(
+                    LedgerHash::zero(),
+                    MinaLedgerSyncLedgerQueryStableV1::NumAccounts,
+                ),
+                RpcExpect::Empty,
+            ),
+            (
+                "unknown_hash",
+                P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesAtBlock(StateHash::zero()),
+                RpcExpect::Empty,
+            ),
+            ("valid", P2pRpcRequest::InitialPeers, RpcExpect::Response),
+            (
+                "unknown_job",
+                P2pRpcRequest::Snark(SnarkJobId {
+                    source: LedgerHashTransitionPasses {
+                        first_pass_ledger: LedgerHash::zero(),
+                        second_pass_ledger: LedgerHash::zero(),
+                    },
+                    target: LedgerHashTransitionPasses {
+                        first_pass_ledger: LedgerHash::zero(),
+                        second_pass_ledger: LedgerHash::zero(),
+                    },
+                }),
+                RpcExpect::Empty,
+            ),
+            (
+                "unknown_hash",
+                P2pRpcRequest::Transaction(TransactionHash::from(&[0; 32])),
+                RpcExpect::Empty,
+            ),
+            (
+                "first_page",
+                P2pRpcRequest::TransactionPoolSummary { offset: 0 },
+                RpcExpect::Response,
+            ),
+            (
+                "offset_out_of_range",
+                P2pRpcRequest::TransactionPoolSummary { offset: u64::MAX },
+                RpcExpect::Response,
+            ),
+            (
+                "unknown_hash",
+                P2pRpcRequest::GenesisProof(StateHash::zero()),
+                RpcExpect::Empty,
+            ),
+        ];
+        if let Some((best_tip, root)) = &best_tip {
+            cases.extend([
+                (
+                    "valid",
+                    P2pRpcRequest::Block(best_tip.hash().clone()),
+                    RpcExpect::Response,
+                ),
+                (
+                    "valid",
+                    P2pRpcRequest::LedgerQuery(
+                        root.snarked_ledger_hash().clone(),
+                        MinaLedgerSyncLedgerQueryStableV1::NumAccounts,
+                    ),
+                    RpcExpect::Response,
+                ),
+                (
+                    "oldest_block",
+                    P2pRpcRequest::Block(root.hash().clone()),
+                    RpcExpect::Response,
+                ),
+                (
+                    "root_address",
+                    P2pRpcRequest::LedgerQuery(
+                        root.snarked_ledger_hash().clone(),
+                        MinaLedgerSyncLedgerQueryStableV1::WhatChildHashes(
+                            LedgerAddress::root().into(),
+                        ),
+                    ),
+                    RpcExpect::Response,
+                ),
+                (
+                    "valid",
+                    P2pRpcRequest::StagedLedgerAuxAndPendingCoinbasesAtBlock(root.hash().clone()),
+                    RpcExpect::Response,
+                ),
+                (
+                    "valid",
+                    P2pRpcRequest::GenesisProof(
+                        best_tip
+                            .header()
+                            .protocol_state
+                            .body
+                            .genesis_state_hash
+                            .clone(),
+                    ),
+                    RpcExpect::Response,
+                ),
+            ]);
+        }
+
+        let is_libp2p = Self::is_libp2p(runner, node_id, peer_id);
+        for (check, request, expect) in cases {
+            let kind = request.kind();
+            let protocol = rpc_protocol(kind);
+            if is_libp2p && !kind.supported_by_libp2p() {
+                report.skip(&protocol, check, "not supported by libp2p");
+                continue;
+            }
+            let result = self.rpc(runner, node_id, peer_id, request.clone()).await;
+            match check_rpc_response(&request, expect, result) {
+                Ok(()) => report.pass(&protocol, check),
+                Err(err) => report.fail(&protocol, check, err),
+            }
+        }
+    }
+
+    /// Sends the rpc request to the peer and waits for the response.
+    async fn rpc(
+        &self,
+        runner: &mut ClusterRunner<'_>,
+        node_id: ClusterNodeId,
+        peer_id: PeerId,
+        request: P2pRpcRequest,
+    ) -> RpcResult {
+        // Node may have its own requests in flight, up to the per peer
+        // in-flight cap.
+        let can_send = self
+            .wait_for(runner, node_id, move |state| {
+                state
+                    .p2p
+                    .get_ready_peer(&peer_id)
+                    .is_some_and(|peer| peer.channels.rpc.can_send_request())
+            })
+            .await;
+        if !can_send {
+            return Err("rpc channel isn't available".to_owned());
+        }
+
+        let id = runner
+            .node(node_id)
+            .unwrap()
+            .state()
+            .p2p
+            .get_ready_peer(&peer_id)
+            .map(|peer| peer.channels.next_local_rpc_id())
+            .ok_or_else(|| "peer disconnected".to_owned())?;
+        let result = DynEffectsData::new(None::<RpcResult>);
+        let result_clone = result.clone();
+        let sent = runner.dispatch_action(
+            node_id,
+            P2pChannelsRpcAction::RequestSend {
+                peer_id,
+                id,
+                request: Box::new(request),
+                on_init: None,
+            },
+        );
+        if !sent {
+            return Err("failed to send the request".to_owned());
+        }
+
+        let _ =
+            runner
+                .run(RunCfg::default().timeout(self.timeout).action_handler(
+                    move |_, _, _, action| {
+                        let Action::P2p(P2pAction::Channels(P2pChannelsAction::Rpc(action))) =
+                            action.action()
+                        else {
+                            return false;
+                        };
+                        let res = match action {
+                            P2pChannelsRpcAction::ResponseReceived {
+                                peer_id: p,
+                                id: i,
+                                response,
+                            } if *p == peer_id && *i == id => Ok(response.clone()),
+                            P2pChannelsRpcAction::Timeout { peer_id: p, id: i }
+                                if *p == peer_id && *i == id =>
+                            {
+                                Err("request timed out".to_owned())
+                            }
+                            _ => return false,
+                        };
+                        *result_clone.inner() = Some(res);
+                        true
+                    },
+                ))
+                .await;
+        let result = result.inner().take();
+        result.unwrap_or_else(|| Err("no response before the timeout".to_owned()))
+    }
+
+    /// Runs the cluster until the condition is met or the timeout elapses.
+    async fn wait_for<F>(
+        &self,
+        runner: &mut ClusterRunner<'_>,
+        node_id: ClusterNodeId,
+        condition: F,
+    ) -> bool
+    where
+        F: 'static + Send + Fn(&State) -> bool,
+    {
+        if condition(runner.node(node_id).unwrap().state()) {
+            return true;
+        }
+        runner
+            .run(
+                RunCfg::default()
+                    .timeout(self.timeout)
+                    .action_handler(move |id, state, _, _| id == node_id && condition(state)),
+            )
+            .await
+            .is_ok()
+    }
+}
+
+/// Channels with the messages requested from the peer, see
+/// [`Conformance::check_channel_messages`].
+const PROPAGATION_CHANNELS: [ChannelId; 5] = [
+    ChannelId::BestTipPropagation,
+    ChannelId::TransactionPropagation,
+    ChannelId::SnarkPropagation,
+    ChannelId::SnarkJobCommitmentPropagation,
+    ChannelId::CheckpointPropagation,
+];
+
+enum ChannelMessage {
+    Request,
+    Promise(u8),
+    Items(usize),
+    /// Best tip is sent without a promise.
+    BestTip,
+}
+
+/// Message received from the peer on the propagation channel.
+fn channel_message(
+    action: &P2pChannelsAction,
+    peer_id: PeerId,
+) -> Option<(ChannelId, ChannelMessage)> {
+    use ChannelMessage::*;
+
+    let (channel, p, message) = match action {
+        P2pChannelsAction::BestTip(action) => {
+            let (p, message) = match action {
+                P2pChannelsBestTipAction::Received { peer_id, .. }
+                | P2pChannelsBestTipAction::CompactReceived { peer_id, .. } => (peer_id, BestTip),
+                P2pChannelsBestTipAction::RequestReceived { peer_id } => (peer_id, Request),
+                _ => return None,
+            };
+            (ChannelId::BestTipPropagation, p, message)
+        }
+        P2pChannelsAction::Transaction(action) => {
+            let (p, message) = match action {
+                P2pChannelsTransactionAction::RequestReceived { peer_id, .. } => (peer_id, Request),
+                P2pChannelsTransactionAction::PromiseReceived {
+                    peer_id,
+                    promised_count,
+                } => (peer_id, Promise(*promised_count)),
+                P2pChannelsTransactionAction::Received { peer_id, .. } => (peer_id, Items(1)),
+                _ => return None,
+            };
+            (ChannelId::TransactionPropagation, p, message)
+        }
+        P2pChannelsAction::Snark(action) => {
+            let (p, message) = match action {
+                P2pChannelsSnarkAction::RequestReceived { peer_id, .. } => (peer_id, Request),
+                P2pChannelsSnarkAction::PromiseReceived {
+                    peer_id,
+                    promised_count,
+                } => (peer_id, Promise(*promised_count)),
+                P2pChannelsSnarkAction::Received { peer_id, .. } => (peer_id, Items(1)),
+                P2pChannelsSnarkAction::BatchReceived { peer_id, batch } => {
+                    (peer_id, Items(batch.len()))
+                }
+                _ => return None,
+            };
+            (ChannelId::SnarkPropagation, p, message)
+        }
+        P2pChannelsAction::SnarkJobCommitment(action) => {
+            let (p, message) = match action {
+                P2pChannelsSnarkJobCommitmentAction::RequestReceived { peer_id, .. } => {
+                    (peer_id, Request)
+                }
+                P2pChannelsSnarkJobCommitmentAction::PromiseReceived {
+                    peer_id,
+                    promised_count,
+                } => (peer_id, Promise(*promised_count)),
+                P2pChannelsSnarkJobCommitmentAction::Received { peer_id, .. } => {
+                    (peer_id, Items(1))
+                }
+                _ => return None,
+            };
+            (ChannelId::SnarkJobCommitmentPropagation, p, message)
+        }
+        P2pChannelsAction::Checkpoint(action) => {
+            let (p, message) = match action {
+                P2pChannelsCheckpointAction::RequestReceived { peer_id, .. } => (peer_id, Request),
+                P2pChannelsCheckpointAction::PromiseReceived {
+                    peer_id,
+                    promised_count,
+                } => (peer_id, Promise(*promised_count)),
+                P2pChannelsCheckpointAction::Received { peer_id, .. } => (peer_id, Items(1)),
+                _ => return None,
+            };
+            (ChannelId::CheckpointPropagation, p, message)
+        }
+        _ => return None,
+    };
+    (*p == peer_id).then_some((channel, message))
+}
+
+fn rpc_protocol(kind: P2pRpcKind) -> String {
+    format!("rpc/{kind:?}")
+}
+
+fn channel_is_ready(channels: &P2pChannelsState, id: ChannelId) -> bool {
+    match id {
+        ChannelId::SignalingDiscovery => channels.signaling.discovery.is_ready(),
+        ChannelId::SignalingExchange => channels.signaling.exchange.is_ready(),
+        ChannelId::BestTipPropagation => channels.best_tip.is_ready(),
+        ChannelId::TransactionPropagation => channels.transaction.is_ready(),
+        ChannelId::SnarkPropagation => channels.snark.is_ready(),
+        ChannelId::SnarkJobCommitmentPropagation => channels.snark_job_commitment.is_ready(),
+        ChannelId::Rpc => channels.rpc.is_ready(),
+        ChannelId::StreamingRpc => channels.streaming_rpc.is_ready(),
+        ChannelId::CheckpointPropagation => channels.checkpoint.is_ready(),
+    }
+}
+
+fn check_rpc_response(
+    request: &P2pRpcRequest,
+    expect: RpcExpect,
+    result: RpcResult,
+) -> Result<(), String> {
+    let response = result?;
+    match (expect, response) {
+        (RpcExpect::Empty, None) => Ok(()),
+        (RpcExpect::Empty, Some(response)) => Err(format!(
+            "expected empty response, got: {:?}",
+            response.kind()
+        )),
+        (RpcExpect::Response, None) => Err("empty response".to_owned()),
+        (RpcExpect::Response, Some(response)) => {
+            if response.kind() != request.kind() {
+                return Err(format!("unexpected response: {:?}", response.kind()));
+            }
+            match (request, &*response) {
+                (P2pRpcRequest::Block(hash), P2pRpcResponse::Block(block)) => {
+                    match ArcBlockWithHash::try_new(block.clone()) {
+                        Ok(block) if block.hash() == hash => Ok(()),
+                        Ok(block) => Err(format!("requested block {hash}, got {}", block.hash())),
+                        Err(_) => Err("invalid block".to_owned()),
+                    }
+                }
+                (
+                    P2pRpcRequest::LedgerQuery(hash, _),
+                    P2pRpcResponse::LedgerQuery(MinaLedgerSyncLedgerAnswerStableV2::NumAccounts(
+                        _,
+                        root_hash,
+                    )),
+                ) if hash != root_hash => Err(format!(
+                    "requested ledger {hash}, got the root hash {root_hash}"
+                )),
+                (P2pRpcRequest::LedgerQuery(_, query), P2pRpcResponse::LedgerQuery(answer))
+                    if !matches!(
+                        (query, answer),
+                        (
+                            MinaLedgerSyncLedgerQueryStableV1::WhatChildHashes(_),
+                            MinaLedgerSyncLedgerAnswerStableV2::ChildHashesAre(..)
+                        ) | (
+                            MinaLedgerSyncLedgerQueryStableV1::WhatContents(_),
+                            MinaLedgerSyncLedgerAnswerStableV2::ContentsAre(_)
+                        ) | (
+                            MinaLedgerSyncLedgerQueryStableV1::NumAccounts,
+                            MinaLedgerSyncLedgerAnswerStableV2::NumAccounts(..)
+                        )
+                    ) =>
+                {
+                    Err(format!("answer doesn't match the query {query:?}"))
+                }
+                (
+                    P2pRpcRequest::TransactionPoolSummary { offset: u64::MAX },
+                    P2pRpcResponse::TransactionPoolSummary(summary),
+                ) if !summary.transactions.is_empty() || summary.next_offset.is_some() => {
+                    Err("expected empty last page for the offset out of range".to_owned())
+                }
+                _ => Ok(()),
+            }
+        }
+    }
+}
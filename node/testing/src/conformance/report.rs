@@ -0,0 +1,144 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConformanceStatus {
+    Pass,
+    Fail,
+    /// Check isn't applicable to the peer, e.g. the protocol isn't
+    /// supported over the transport it's connected with.
+    Skip,
+}
+
+impl ConformanceStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Fail => "FAIL",
+            Self::Skip => "SKIP",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConformanceCheck {
+    pub name: String,
+    pub status: ConformanceStatus,
+    /// Reason of the failure or the skip.
+    pub details: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConformanceProtocolReport {
+    pub protocol: String,
+    pub checks: Vec<ConformanceCheck>,
+}
+
+impl ConformanceProtocolReport {
+    /// Fails if any of the checks failed, skipped if all of them were
+    /// skipped.
+    pub fn status(&self) -> ConformanceStatus {
+        if self
+            .checks
+            .iter()
+            .any(|check| check.status == ConformanceStatus::Fail)
+        {
+            ConformanceStatus::Fail
+        } else if self
+            .checks
+            .iter()
+            .all(|check| check.status == ConformanceStatus::Skip)
+        {
+            ConformanceStatus::Skip
+        } else {
+            ConformanceStatus::Pass
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConformanceReport {
+    pub peer: String,
+    pub protocols: Vec<ConformanceProtocolReport>,
+}
+
+impl ConformanceReport {
+    pub fn new(peer: String) -> Self {
+        Self {
+            peer,
+            protocols: Vec::new(),
+        }
+    }
+
+    /// Whether none of the protocols failed.
+    pub fn is_success(&self) -> bool {
+        self.protocols
+            .iter()
+            .all(|protocol| protocol.status() != ConformanceStatus::Fail)
+    }
+
+    pub fn pass(&mut self, protocol: &str, check: &str) {
+        self.add(protocol, check, ConformanceStatus::Pass, None);
+    }
+
+    pub fn fail(&mut self, protocol: &str, check: &str, details: impl Into<String>) {
+        self.add(
+            protocol,
+            check,
+            ConformanceStatus::Fail,
+            Some(details.into()),
+        );
+    }
+
+    pub fn skip(&mut self, protocol: &str, check: &str, details: impl Into<String>) {
+        self.add(
+            protocol,
+            check,
+            ConformanceStatus::Skip,
+            Some(details.into()),
+        );
+    }
+
+    pub fn add(
+        &mut self,
+        protocol: &str,
+        check: &str,
+        status: ConformanceStatus,
+        details: Option<String>,
+    ) {
+        let check = ConformanceCheck {
+            name: check.to_owned(),
+            status,
+            details,
+        };
+        match self.protocols.iter_mut().find(|p| p.protocol == protocol) {
+            Some(report) => report.checks.push(check),
+            None => self.protocols.push(ConformanceProtocolReport {
+                protocol: protocol.to_owned(),
+                checks: vec![check],
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "conformance report for {}", self.peer)?;
+        for protocol in &self.protocols {
+            writeln!(f, "{} {}", protocol.status().as_str(), protocol.protocol)?;
+            for check in &protocol.checks {
+                write!(f, "    {} {}", check.status.as_str(), check.name)?;
+                if let Some(details) = &check.details {
+                    write!(f, ": {details}")?;
+                }
+                writeln!(f)?;
+            }
+        }
+        let result = match self.is_success() {
+            true => ConformanceStatus::Pass,
+            false => ConformanceStatus::Fail,
+        };
+        write!(f, "result: {}", result.as_str())
+    }
+}
@@ -1,7 +1,12 @@
+use std::time::Duration;
+
 use clap::Parser;
 
+use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
 use node::p2p::webrtc::Host;
+use openmina_node_testing::cluster::runner::ClusterRunner;
 use openmina_node_testing::cluster::{Cluster, ClusterConfig};
+use openmina_node_testing::conformance::Conformance;
 use openmina_node_testing::scenario::Scenario;
 use openmina_node_testing::scenarios::Scenarios;
 use openmina_node_testing::{exit_with_error, server, setup};
@@ -21,6 +26,8 @@ pub enum Command {
 
     ScenariosGenerate(CommandScenariosGenerate),
     ScenariosRun(CommandScenariosRun),
+
+    Conformance(CommandConformance),
 }
 
 #[derive(Debug, clap::Args)]
@@ -54,6 +61,20 @@ pub struct CommandScenariosRun {
     pub name: String,
 }
 
+/// Run protocol conformance checks against the external peer.
+#[derive(Debug, clap::Args)]
+pub struct CommandConformance {
+    /// Address of the peer, e.g. `/ip4/127.0.0.1/tcp/8302/p2p/<peer_id>`.
+    #[arg(long, short)]
+    pub peer: P2pConnectionOutgoingInitOpts,
+    /// Timeout in seconds for each step of the checks.
+    #[arg(long, short, default_value = "60")]
+    pub timeout: u64,
+    /// Print the report as json.
+    #[arg(long)]
+    pub json: bool,
+}
+
 impl Command {
     pub fn run(self) -> Result<(), crate::CommandError> {
         let rt = setup();
@@ -152,6 +173,37 @@ impl Command {
                     }
                 })
             }
+            Self::Conformance(cmd) => {
+                let config = ClusterConfig::new(None).map_err(|err| {
+                    anyhow::anyhow!("failed to create cluster configuration: {err}")
+                })?;
+                let conformance =
+                    Conformance::new(cmd.peer).timeout(Duration::from_secs(cmd.timeout));
+
+                let fut = async move {
+                    let mut cluster = Cluster::new(config);
+                    let report = conformance
+                        .run(ClusterRunner::new(&mut cluster, |_| {}))
+                        .await;
+                    if cmd.json {
+                        println!("{}", serde_json::to_string_pretty(&report)?);
+                    } else {
+                        println!("{report}");
+                    }
+                    if !report.is_success() {
+                        anyhow::bail!("peer failed the conformance checks");
+                    }
+                    Ok(())
+                };
+                rt.block_on(async {
+                    tokio::select! {
+                        res = fut => res,
+                        _ = shutdown_rx => {
+                            anyhow::bail!("Received ctrl-c signal! shutting down...");
+                        }
+                    }
+                })
+            }
         }
     }
 }
@@ -5,7 +5,10 @@ use std::sync::Arc;
 use node::account::AccountSecretKey;
 use node::config::DEVNET_CONFIG;
 use node::transition_frontier::genesis::GenesisConfig;
-use node::{p2p::P2pTimeouts, BlockProducerConfig, SnarkerConfig};
+use node::{
+    p2p::{P2pGossipTopic, P2pListenFamily, P2pTimeouts},
+    BlockProducerConfig, SnarkerConfig,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::scenario::ListenerNode;
@@ -41,6 +44,14 @@ pub struct RustNodeTestingConfig {
     #[serde(default)]
     pub recorder: Recorder,
     pub peer_discovery: bool,
+    /// Address families the libp2p listener is bound to. Other nodes of
+    /// the cluster dial the node over IPv6 loopback, unless it listens
+    /// on IPv4.
+    #[serde(default)]
+    pub listen_family: P2pListenFamily,
+    /// Gossip topics the node is subscribed to, all if `None`.
+    #[serde(default)]
+    pub gossip_topics: Option<Vec<P2pGossipTopic>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -70,6 +81,8 @@ impl RustNodeTestingConfig {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            listen_family: Default::default(),
+            gossip_topics: None,
         }
     }
 
@@ -86,6 +99,8 @@ impl RustNodeTestingConfig {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            listen_family: Default::default(),
+            gossip_topics: None,
         }
     }
 
@@ -131,4 +146,14 @@ impl RustNodeTestingConfig {
         self.peer_discovery = false;
         self
     }
+
+    pub fn with_listen_family(mut self, family: P2pListenFamily) -> Self {
+        self.listen_family = family;
+        self
+    }
+
+    pub fn with_gossip_topics(mut self, topics: Vec<P2pGossipTopic>) -> Self {
+        self.gossip_topics = Some(topics);
+        self
+    }
 }
@@ -1,5 +1,8 @@
 use node::{
-    p2p::{webrtc::ConnectionAuthEncrypted, P2pConnectionEvent, P2pEvent, PeerId},
+    p2p::{
+        connection::P2pConnectionFinalizeError, webrtc::ConnectionAuthEncrypted,
+        P2pConnectionEvent, P2pEvent, PeerId,
+    },
     rpc::{RpcId, RpcRequest},
 };
 use serde::{Deserialize, Serialize};
@@ -10,7 +13,10 @@ pub use node::event_source::Event;
 pub enum NonDeterministicEvent {
     /// Non-deterministic because libp2p kademlia initiates connections
     /// without state machine knowing about it.
-    P2pConnectionFinalized(PeerId, Result<ConnectionAuthEncrypted, String>),
+    P2pConnectionFinalized(
+        PeerId,
+        Result<ConnectionAuthEncrypted, P2pConnectionFinalizeError>,
+    ),
     P2pConnectionClosed(PeerId),
 
     RpcReadonly(RpcId, Box<RpcRequest>),
@@ -21,7 +27,7 @@ impl NonDeterministicEvent {
         Some(match event {
             Event::P2p(e) => match e {
                 P2pEvent::Connection(e) => match e {
-                    P2pConnectionEvent::Finalized(id, res) => {
+                    P2pConnectionEvent::Finalized(id, _, res) => {
                         Self::P2pConnectionFinalized(*id, res.clone()).into()
                     }
                     P2pConnectionEvent::Closed(id) => Self::P2pConnectionClosed(*id).into(),
@@ -9,13 +9,15 @@ use node::p2p::connection::outgoing::{
     P2pConnectionOutgoingInitLibp2pOpts, P2pConnectionOutgoingInitOpts,
 };
 use node::p2p::webrtc::SignalingMethod;
-use node::p2p::PeerId;
+use node::p2p::{P2pListenFamily, PeerId};
 use node::service::P2pDisconnectionService;
 use node::{Action, CheckTimeoutsAction, State, Store};
 use redux::EnablingCondition;
 use temp_dir::TempDir;
 
 use crate::cluster::ClusterNodeId;
+#[cfg(feature = "p2p-webrtc")]
+use crate::service::FakePeer;
 use crate::service::{DynEffects, NodeTestingService, PendingEventId};
 
 pub struct Node {
@@ -61,6 +63,11 @@ impl Node {
         self.service_mut().remove_dyn_effects()
     }
 
+    #[cfg(feature = "p2p-webrtc")]
+    pub fn fake_peer_add(&mut self, sec_key: node::p2p::identity::SecretKey) -> FakePeer {
+        self.service_mut().fake_peer_add(sec_key)
+    }
+
     pub fn dial_addr(&self) -> P2pConnectionOutgoingInitOpts {
         let peer_id = self.store.state().p2p.my_id();
         if self.service().rust_to_rust_use_webrtc() {
@@ -68,10 +75,15 @@ impl Node {
             let signaling = SignalingMethod::Http(([127, 0, 0, 1], port).into());
             P2pConnectionOutgoingInitOpts::WebRTC { peer_id, signaling }
         } else {
+            let config = self.store.state().p2p.config();
+            let host = match config.listen_family {
+                P2pListenFamily::Ipv4 => node::p2p::webrtc::Host::Ipv4([127, 0, 0, 1].into()),
+                _ => node::p2p::webrtc::Host::Ipv6(std::net::Ipv6Addr::LOCALHOST),
+            };
             let opts = P2pConnectionOutgoingInitLibp2pOpts {
                 peer_id,
-                host: node::p2p::webrtc::Host::Ipv4([127, 0, 0, 1].into()),
-                port: self.store.state().p2p.config().libp2p_port.unwrap(),
+                host,
+                port: config.libp2p_port.unwrap(),
             };
             P2pConnectionOutgoingInitOpts::LibP2P(opts)
         }
@@ -103,7 +115,7 @@ impl Node {
         )
     }
 
-    fn dispatch<T>(&mut self, action: T) -> bool
+    pub fn dispatch<T>(&mut self, action: T) -> bool
     where
         T: Into<Action> + EnablingCondition<State>,
     {
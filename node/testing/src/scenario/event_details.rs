@@ -14,9 +14,8 @@ pub fn event_details(state: &State, event: &Event) -> Option<String> {
     ))) = event
     {
         let rpc_state = &state.p2p.get_ready_peer(peer_id)?.channels.rpc;
-        if *req_id == rpc_state.pending_local_rpc_id()? {
-            return Some(format!("Request: {}", rpc_state.pending_local_rpc()?));
-        }
+        let request = rpc_state.pending_local_rpc(*req_id)?;
+        return Some(format!("Request: {request}"));
     }
 
     None
@@ -86,7 +86,7 @@ pub fn connection_finalized_event(
     move |node_id, event, _| {
         matches!(
             event,
-            Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(peer, res))) if pred(node_id, peer) && res.is_ok()
+            Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(peer, _, res))) if pred(node_id, peer) && res.is_ok()
         )
     }
 }
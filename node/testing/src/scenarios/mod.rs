@@ -28,11 +28,13 @@ use crate::scenario::{Scenario, ScenarioId, ScenarioStep};
 
 use self::multi_node::basic_connectivity_initial_joining::MultiNodeBasicConnectivityInitialJoining;
 use self::multi_node::basic_connectivity_peer_discovery::MultiNodeBasicConnectivityPeerDiscovery;
+use self::multi_node::chain_import::MultiNodeChainImport;
 use self::multi_node::connection_discovery::RustNodeAsSeed as P2pConnectionDiscoveryRustNodeAsSeed;
 use self::multi_node::connection_discovery::{
     OCamlToRust, OCamlToRustViaSeed, RustToOCaml, RustToOCamlViaSeed,
 };
 use self::multi_node::pubsub_advanced::MultiNodePubsubPropagateBlock;
+use self::multi_node::reorg::MultiNodeReorg;
 use self::multi_node::sync_4_block_producers::MultiNodeSync4BlockProducers;
 use self::multi_node::vrf_correct_ledgers::MultiNodeVrfGetCorrectLedgers;
 use self::multi_node::vrf_correct_slots::MultiNodeVrfGetCorrectSlots;
@@ -50,11 +52,15 @@ use self::p2p::basic_outgoing_connections::{
     DontConnectToInitialPeerWithSameId, DontConnectToNodeWithSameId, DontConnectToSelfInitialPeer,
     MakeMultipleOutgoingConnections, MakeOutgoingConnection,
 };
+use self::p2p::conformance::P2pConformanceRustNode;
+use self::p2p::fake_peer::{P2pFakePeerMsgLenOverLimit, P2pFakePeerSnarkBatch};
+use self::p2p::ipv6::{DualStackAcceptsBothFamilies, Ipv6OnlyCluster};
 use self::p2p::kademlia::KademliaBootstrap;
-use self::p2p::pubsub::P2pReceiveMessage;
+use self::p2p::pubsub::{P2pGossipTopicsSubset, P2pReceiveMessage};
 use self::p2p::signaling::P2pSignaling;
 use self::record_replay::block_production::RecordReplayBlockProduction;
 use self::record_replay::bootstrap::RecordReplayBootstrap;
+use self::simulation::empty_blocks_propagation::SimulationEmptyBlocksPropagation;
 use self::simulation::small::SimulationSmall;
 use self::simulation::small_forever_real_time::SimulationSmallForeverRealTime;
 use self::solo_node::sync_to_genesis::SoloNodeSyncToGenesis;
@@ -83,10 +89,16 @@ pub enum Scenarios {
     MultiNodeBasicConnectivityPeerDiscovery(MultiNodeBasicConnectivityPeerDiscovery),
     SimulationSmall(SimulationSmall),
     SimulationSmallForeverRealTime(SimulationSmallForeverRealTime),
+    SimulationEmptyBlocksPropagation(SimulationEmptyBlocksPropagation),
     P2pReceiveMessage(P2pReceiveMessage),
+    P2pGossipTopicsSubset(P2pGossipTopicsSubset),
     P2pSignaling(P2pSignaling),
+    P2pFakePeerMsgLenOverLimit(P2pFakePeerMsgLenOverLimit),
+    P2pFakePeerSnarkBatch(P2pFakePeerSnarkBatch),
     P2pConnectionDiscoveryRustNodeAsSeed(P2pConnectionDiscoveryRustNodeAsSeed),
     MultiNodePubsubPropagateBlock(MultiNodePubsubPropagateBlock),
+    MultiNodeReorg(MultiNodeReorg),
+    MultiNodeChainImport(MultiNodeChainImport),
     RecordReplayBootstrap(RecordReplayBootstrap),
     RecordReplayBlockProduction(RecordReplayBlockProduction),
 
@@ -110,6 +122,9 @@ pub enum Scenarios {
     SeedConnectionsAreSymmetric(SeedConnectionsAreSymmetric),
     MaxNumberOfPeersIncoming(MaxNumberOfPeersIncoming),
     MaxNumberOfPeersIs1(MaxNumberOfPeersIs1),
+    Ipv6OnlyCluster(Ipv6OnlyCluster),
+    DualStackAcceptsBothFamilies(DualStackAcceptsBothFamilies),
+    P2pConformanceRustNode(P2pConformanceRustNode),
 }
 
 impl Scenarios {
@@ -130,8 +145,14 @@ impl Scenarios {
             Self::MultiNodeBasicConnectivityPeerDiscovery(_) => cfg!(feature = "p2p-webrtc"),
             Self::SimulationSmall(_) => true,
             Self::SimulationSmallForeverRealTime(_) => true,
+            Self::SimulationEmptyBlocksPropagation(_) => true,
             Self::MultiNodePubsubPropagateBlock(_) => true, // in progress
+            Self::MultiNodeReorg(_) => true,
+            Self::MultiNodeChainImport(_) => true,
             Self::P2pSignaling(_) => !cfg!(feature = "p2p-webrtc"),
+            Self::P2pGossipTopicsSubset(_) => cfg!(feature = "p2p-webrtc"),
+            Self::P2pFakePeerMsgLenOverLimit(_) => !cfg!(feature = "p2p-webrtc"),
+            Self::P2pFakePeerSnarkBatch(_) => !cfg!(feature = "p2p-webrtc"),
             _ => false,
         }
     }
@@ -189,12 +210,18 @@ impl Scenarios {
             }
             Self::SimulationSmall(_) => SimulationSmall::DOCS,
             Self::SimulationSmallForeverRealTime(_) => SimulationSmallForeverRealTime::DOCS,
+            Self::SimulationEmptyBlocksPropagation(_) => SimulationEmptyBlocksPropagation::DOCS,
             Self::P2pReceiveMessage(_) => P2pReceiveMessage::DOCS,
+            Self::P2pGossipTopicsSubset(_) => P2pGossipTopicsSubset::DOCS,
             Self::P2pSignaling(_) => P2pSignaling::DOCS,
+            Self::P2pFakePeerMsgLenOverLimit(_) => P2pFakePeerMsgLenOverLimit::DOCS,
+            Self::P2pFakePeerSnarkBatch(_) => P2pFakePeerSnarkBatch::DOCS,
             Self::P2pConnectionDiscoveryRustNodeAsSeed(_) => {
                 P2pConnectionDiscoveryRustNodeAsSeed::DOCS
             }
             Self::MultiNodePubsubPropagateBlock(_) => MultiNodePubsubPropagateBlock::DOCS,
+            Self::MultiNodeReorg(_) => MultiNodeReorg::DOCS,
+            Self::MultiNodeChainImport(_) => MultiNodeChainImport::DOCS,
             Self::RecordReplayBootstrap(_) => RecordReplayBootstrap::DOCS,
             Self::RecordReplayBlockProduction(_) => RecordReplayBlockProduction::DOCS,
 
@@ -218,6 +245,9 @@ impl Scenarios {
             Self::SeedConnectionsAreSymmetric(_) => SeedConnectionsAreSymmetric::DOCS,
             Self::MaxNumberOfPeersIncoming(_) => MaxNumberOfPeersIncoming::DOCS,
             Self::MaxNumberOfPeersIs1(_) => MaxNumberOfPeersIs1::DOCS,
+            Self::Ipv6OnlyCluster(_) => Ipv6OnlyCluster::DOCS,
+            Self::DualStackAcceptsBothFamilies(_) => DualStackAcceptsBothFamilies::DOCS,
+            Self::P2pConformanceRustNode(_) => P2pConformanceRustNode::DOCS,
         }
     }
 
@@ -260,10 +290,16 @@ impl Scenarios {
             Self::MultiNodeBasicConnectivityPeerDiscovery(v) => v.run(runner).await,
             Self::SimulationSmall(v) => v.run(runner).await,
             Self::SimulationSmallForeverRealTime(v) => v.run(runner).await,
+            Self::SimulationEmptyBlocksPropagation(v) => v.run(runner).await,
             Self::P2pReceiveMessage(v) => v.run(runner).await,
+            Self::P2pGossipTopicsSubset(v) => v.run(runner).await,
             Self::P2pSignaling(v) => v.run(runner).await,
+            Self::P2pFakePeerMsgLenOverLimit(v) => v.run(runner).await,
+            Self::P2pFakePeerSnarkBatch(v) => v.run(runner).await,
             Self::P2pConnectionDiscoveryRustNodeAsSeed(v) => v.run(runner).await,
             Self::MultiNodePubsubPropagateBlock(v) => v.run(runner).await,
+            Self::MultiNodeReorg(v) => v.run(runner).await,
+            Self::MultiNodeChainImport(v) => v.run(runner).await,
             Self::RecordReplayBootstrap(v) => v.run(runner).await,
             Self::RecordReplayBlockProduction(v) => v.run(runner).await,
 
@@ -287,6 +323,9 @@ impl Scenarios {
             Self::SeedConnectionsAreSymmetric(v) => v.run(runner).await,
             Self::MaxNumberOfPeersIncoming(v) => v.run(runner).await,
             Self::MaxNumberOfPeersIs1(v) => v.run(runner).await,
+            Self::Ipv6OnlyCluster(v) => v.run(runner).await,
+            Self::DualStackAcceptsBothFamilies(v) => v.run(runner).await,
+            Self::P2pConformanceRustNode(v) => v.run(runner).await,
         }
     }
 
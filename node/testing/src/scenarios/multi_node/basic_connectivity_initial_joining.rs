@@ -58,35 +58,35 @@ impl MultiNodeBasicConnectivityInitialJoining {
                 let node_id = *node_id;
                 let this_id = runner.node(node_id).unwrap().state().p2p.my_id();
 
-                let node_steps = runner
-                    .node_pending_events(node_id, true)
-                    .unwrap()
-                    .1
-                    .map(|(_, event)| {
-                        match event {
-                            Event::P2p(P2pEvent::Connection(P2pConnectionEvent::Finalized(
-                                peer_id,
-                                result,
-                            ))) => connection_events
-                                .entry(this_id)
-                                .or_default()
-                                .entry(*peer_id)
-                                .or_default()
-                                .push(
-                                    result
-                                        .as_ref()
-                                        .err()
-                                        .cloned()
-                                        .unwrap_or_else(|| "ok".to_owned()),
-                                ),
-                            _ => {}
-                        }
-                        ScenarioStep::Event {
-                            node_id,
-                            event: event.to_string(),
-                        }
-                    })
-                    .collect::<Vec<_>>();
+                let node_steps =
+                    runner
+                        .node_pending_events(node_id, true)
+                        .unwrap()
+                        .1
+                        .map(|(_, event)| {
+                            match event {
+                                Event::P2p(P2pEvent::Connection(
+                                    P2pConnectionEvent::Finalized(peer_id, _, result),
+                                )) => connection_events
+                                    .entry(this_id)
+                                    .or_default()
+                                    .entry(*peer_id)
+                                    .or_default()
+                                    .push(
+                                        result
+                                            .as_ref()
+                                            .err()
+                                            .map(|error| error.to_string())
+                                            .unwrap_or_else(|| "ok".to_owned()),
+                                    ),
+                                _ => {}
+                            }
+                            ScenarioStep::Event {
+                                node_id,
+                                event: event.to_string(),
+                            }
+                        })
+                        .collect::<Vec<_>>();
                 steps.extend(node_steps);
             }
 
@@ -0,0 +1,177 @@
+use std::{sync::Arc, time::Duration};
+
+use mina_p2p_messages::v2::{BlockTimeTimeStableV1, PROTOCOL_CONSTANTS};
+use node::{
+    core::{block::ArcBlockWithHash, requests::RpcId},
+    event_source::Event,
+    rpc::RpcRequest,
+    transition_frontier::{
+        genesis::{GenesisConfig, NonStakers},
+        ChainArchiveReader, ChainArchiveWriter,
+    },
+    ActionKind, BlockProducerConfig,
+};
+
+use crate::{
+    cluster::ClusterNodeId,
+    node::{RustNodeBlockProducerTestingConfig, RustNodeTestingConfig},
+    scenario::ScenarioStep,
+    scenarios::{ClusterRunner, RunCfg},
+};
+
+const BLOCKS: u32 = 3;
+
+/// Import the blocks of the chain archive into a node without peers.
+///
+/// 1. Create a block producer and an importer node, which aren't
+///    connected to each other.
+/// 2. Let the producer produce a block, pass it through the chain
+///    archive and import it into the importer with the
+///    `ChainImportBlock` rpc, the same way `chain-import` does.
+/// 3. Check that the importer applied the block before the next one is
+///    produced, so that it goes through the same timing checks as the
+///    blocks received from the peers.
+/// 4. Export the whole imported range and check it matches the best
+///    chain of the producer.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct MultiNodeChainImport;
+
+impl MultiNodeChainImport {
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        let initial_time = redux::Timestamp::global_now();
+        let mut constants = PROTOCOL_CONSTANTS.clone();
+        constants.genesis_state_timestamp =
+            BlockTimeTimeStableV1((u64::from(initial_time) / 1_000_000).into());
+        let genesis = GenesisConfig::Counts {
+            whales: 1,
+            fish: 0,
+            non_stakers: NonStakers::None,
+            constants,
+            accounts: Vec::new(),
+        };
+        let node_config = RustNodeTestingConfig {
+            initial_time,
+            genesis: Arc::new(genesis),
+            ..RustNodeTestingConfig::devnet_default()
+        };
+
+        let importer = runner.add_rust_node(node_config.clone());
+        runner
+            .run_until_nodes_synced(Duration::from_secs(60), &[importer])
+            .await
+            .unwrap();
+
+        let (sec_key, _) = runner
+            .block_producer_sec_keys(importer)
+            .into_iter()
+            .next()
+            .unwrap();
+        let producer = runner.add_rust_node(RustNodeTestingConfig {
+            block_producer: Some(RustNodeBlockProducerTestingConfig {
+                config: BlockProducerConfig::new(sec_key.public_key().into()),
+                sec_key,
+            }),
+            ..node_config
+        });
+
+        let genesis_height = best_tip(&runner, importer).height();
+        for i in 1..=BLOCKS {
+            let height = genesis_height.saturating_add(i);
+            runner
+                .produce_blocks_until(
+                    producer,
+                    "chain-import",
+                    Duration::from_secs(10 * 60),
+                    Duration::from_secs(5),
+                    false,
+                    |state, _, _| {
+                        state
+                            .transition_frontier
+                            .best_tip()
+                            .is_some_and(|tip| tip.height() >= height)
+                    },
+                )
+                .await;
+
+            let block = best_tip(&runner, producer);
+            let archive = export(std::slice::from_ref(&block));
+            for imported in ChainArchiveReader::new(archive.as_slice()).unwrap() {
+                let imported = imported.unwrap();
+                assert_eq!(imported.hash(), block.hash());
+
+                let rpc_id = RpcId::new_unchecked(usize::MAX, i as usize);
+                let request = RpcRequest::ChainImportBlock(imported);
+                runner
+                    .exec_step(ScenarioStep::ManualEvent {
+                        node_id: importer,
+                        event: Box::new(Event::Rpc(rpc_id, Box::new(request))),
+                    })
+                    .await
+                    .unwrap();
+            }
+
+            let now = redux::Instant::now();
+            while best_tip(&runner, importer).hash() != block.hash() {
+                assert!(
+                    now.elapsed() < Duration::from_secs(3 * 60),
+                    "block {} at height {height} wasn't imported",
+                    block.hash()
+                );
+                let _ = runner
+                    .run(
+                        RunCfg::default()
+                            .timeout(Duration::from_secs(5))
+                            .action_handler(move |id, _, _, action| {
+                                id == importer
+                                    && action.action().kind()
+                                        == ActionKind::TransitionFrontierSynced
+                            }),
+                    )
+                    .await;
+            }
+            eprintln!("[chain-import] imported {height} {}", block.hash());
+        }
+
+        let best_chain = |node_id| {
+            runner
+                .node(node_id)
+                .unwrap()
+                .state()
+                .transition_frontier
+                .best_chain
+                .iter()
+                .filter(|block| block.height() > genesis_height)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let imported = best_chain(importer);
+        assert_eq!(imported.len(), BLOCKS as usize);
+        let archive = export(&imported);
+        let reader = ChainArchiveReader::new(archive.as_slice()).unwrap();
+        assert_eq!(
+            reader.header().to_height,
+            genesis_height.saturating_add(BLOCKS)
+        );
+        let exported = reader.map(|block| block.unwrap()).collect::<Vec<_>>();
+        assert_eq!(exported, best_chain(producer));
+    }
+}
+
+fn best_tip(runner: &ClusterRunner<'_>, node_id: ClusterNodeId) -> ArcBlockWithHash {
+    runner
+        .node(node_id)
+        .unwrap()
+        .state()
+        .transition_frontier
+        .best_tip()
+        .unwrap()
+        .clone()
+}
+
+fn export(blocks: &[ArcBlockWithHash]) -> Vec<u8> {
+    let mut writer = ChainArchiveWriter::new(Vec::new(), blocks).unwrap();
+    for block in blocks {
+        writer.write_block(block).unwrap();
+    }
+    writer.finish().unwrap()
+}
@@ -90,6 +90,7 @@ impl MultiNodePubsubPropagateBlock {
             fish: 0,
             non_stakers: NonStakers::None,
             constants,
+            accounts: Vec::new(),
         };
         let config = SimulatorConfig {
             genesis: genesis_cfg.into(),
@@ -101,6 +102,7 @@ impl MultiNodePubsubPropagateBlock {
             run_until: SimulatorRunUntil::BlockchainLength(4),
             run_until_timeout: Duration::from_secs(10 * 60),
             recorder: Recorder::StateWithInputActions,
+            empty_blocks: None,
         };
         let mut simulator = Simulator::new(initial_time, config);
         simulator
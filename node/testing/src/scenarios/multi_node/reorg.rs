@@ -0,0 +1,111 @@
+use std::{sync::Arc, time::Duration};
+
+use mina_p2p_messages::v2::{BlockTimeTimeStableV1, PROTOCOL_CONSTANTS};
+use node::{
+    account::AccountSecretKey,
+    transition_frontier::genesis::{GenesisConfig, NonStakers},
+    BlockProducerConfig,
+};
+
+use crate::{
+    node::{RustNodeBlockProducerTestingConfig, RustNodeTestingConfig},
+    scenario::{ListenerNode, ScenarioStep},
+    scenarios::ClusterRunner,
+};
+
+/// Force a node to reorg to a heavier fork.
+///
+/// 1. Create an observer node and 2 block producer nodes (fish and whale),
+///    which aren't connected to each other.
+/// 2. Connect the observer to the fish and let it produce a few blocks.
+/// 3. Let the whale build a chain heavier than the one of the observer.
+/// 4. Connect the observer to the whale and check that it switched to
+///    the whale's chain, rolling back the fish's blocks.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct MultiNodeReorg;
+
+impl MultiNodeReorg {
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        let initial_time = redux::Timestamp::global_now();
+        let mut constants = PROTOCOL_CONSTANTS.clone();
+        constants.genesis_state_timestamp =
+            BlockTimeTimeStableV1((u64::from(initial_time) / 1_000_000).into());
+        let genesis = GenesisConfig::Counts {
+            whales: 1,
+            fish: 1,
+            non_stakers: NonStakers::None,
+            constants,
+            accounts: Vec::new(),
+        };
+        let node_config = RustNodeTestingConfig {
+            initial_time,
+            genesis: Arc::new(genesis),
+            ..RustNodeTestingConfig::devnet_default()
+        };
+
+        let observer = runner.add_rust_node(node_config.clone());
+        runner
+            .run_until_nodes_synced(Duration::from_secs(60), &[observer])
+            .await
+            .unwrap();
+
+        // ordered by stake, whale first.
+        let mut block_producers = runner.block_producer_sec_keys(observer);
+        let producer_config = |sec_key: AccountSecretKey| RustNodeTestingConfig {
+            block_producer: Some(RustNodeBlockProducerTestingConfig {
+                config: BlockProducerConfig::new(sec_key.public_key().into()),
+                sec_key,
+            }),
+            ..node_config.clone()
+        };
+        let (fish_key, _) = block_producers.pop().unwrap();
+        let (whale_key, _) = block_producers.pop().unwrap();
+        let fish = runner.add_rust_node(producer_config(fish_key));
+        let whale = runner.add_rust_node(producer_config(whale_key));
+
+        // TODO(binier): proper way to wait for all nodes to be ready.
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        runner
+            .exec_step(ScenarioStep::ConnectNodes {
+                dialer: observer,
+                listener: ListenerNode::Rust(fish),
+            })
+            .await
+            .unwrap();
+
+        runner
+            .produce_blocks_until(
+                fish,
+                "reorg",
+                Duration::from_secs(10 * 60),
+                Duration::from_secs(5),
+                true,
+                |_, _, produced_blocks| produced_blocks >= 2,
+            )
+            .await;
+
+        eprintln!("waiting for whale({whale}) to build a heavier fork");
+        runner
+            .build_heavier_fork(observer, whale, 30)
+            .await
+            .unwrap();
+
+        let outcome = runner.force_reorg(observer, whale, 36).await.unwrap();
+        eprintln!("reorg outcome: {outcome:?}");
+
+        assert!(outcome.fork_point.is_some());
+        assert!(!outcome.rolled_back.is_empty());
+        assert!(!outcome.applied.is_empty());
+        let best_tip = |node_id| {
+            runner
+                .node(node_id)
+                .unwrap()
+                .state()
+                .transition_frontier
+                .best_tip()
+                .map(|tip| tip.hash().clone())
+        };
+        assert_eq!(outcome.applied.last().cloned(), best_tip(observer));
+    }
+}
@@ -45,15 +45,13 @@ impl MultiNodeVrfEpochBoundsEvaluation {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            listen_family: Default::default(),
+            gossip_topics: None,
         };
 
         let producer_node = runner.add_rust_node(RustNodeTestingConfig {
             block_producer: Some(RustNodeBlockProducerTestingConfig {
-                config: BlockProducerConfig {
-                    pub_key: sec_key.public_key().into(),
-                    custom_coinbase_receiver: None,
-                    proposed_protocol_version: None,
-                },
+                config: BlockProducerConfig::new(sec_key.public_key().into()),
                 sec_key: sec_key.clone(),
             }),
             ..rust_config.clone()
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use crate::{
+    conformance::Conformance,
+    node::RustNodeTestingConfig,
+    scenarios::{ClusterRunner, RunCfg},
+};
+
+/// Runs the protocol conformance checks against a local Rust node, which
+/// must pass all of them.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct P2pConformanceRustNode;
+
+impl P2pConformanceRustNode {
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        let node_id = runner.add_rust_node(RustNodeTestingConfig::devnet_default());
+        runner
+            .run(
+                RunCfg::default()
+                    .timeout(Duration::from_secs(60))
+                    .action_handler(move |id, state, _, _| {
+                        id == node_id && state.transition_frontier.best_tip().is_some()
+                    }),
+            )
+            .await
+            .expect("node didn't load the genesis block");
+
+        let peer = runner.node(node_id).expect("node must exist").dial_addr();
+        let report = Conformance::new(peer)
+            .timeout(Duration::from_secs(20))
+            .run(runner)
+            .await;
+        eprintln!("{report}");
+        assert!(
+            report.is_success(),
+            "rust node failed the conformance checks"
+        );
+    }
+}
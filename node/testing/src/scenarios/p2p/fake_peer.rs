@@ -0,0 +1,259 @@
+use crate::scenarios::ClusterRunner;
+
+/// Makes sure that the node disconnects the peer, which sends a message
+/// with the length over the channel's limit.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct P2pFakePeerMsgLenOverLimit;
+
+impl P2pFakePeerMsgLenOverLimit {
+    #[cfg(feature = "p2p-webrtc")]
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        use std::time::Duration;
+
+        use node::{
+            p2p::{
+                channels::ChannelId,
+                disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+                identity::SecretKey,
+            },
+            Action, P2pAction,
+        };
+
+        use crate::{
+            node::RustNodeTestingConfig,
+            scenario::{ListenerNode, ScenarioStep},
+            scenarios::{DynEffectsData, RunCfg},
+            service::FakePeerStep,
+        };
+
+        let node_id = runner.add_rust_node(RustNodeTestingConfig::devnet_default());
+        let mut fake_peer = runner
+            .fake_peer_add(node_id, SecretKey::rand())
+            .expect("node must exist");
+        let fake_peer_id = fake_peer.peer_id();
+        fake_peer.script([FakePeerStep::SendRaw {
+            channel: ChannelId::Rpc,
+            data: u32::MAX.to_be_bytes().to_vec(),
+        }]);
+
+        runner
+            .exec_step(ScenarioStep::ConnectNodes {
+                dialer: node_id,
+                listener: ListenerNode::Custom(fake_peer.dial_opts()),
+            })
+            .await
+            .unwrap();
+
+        let fake_peer = DynEffectsData::new(fake_peer);
+        runner
+            .run(
+                RunCfg::default()
+                    .timeout(Duration::from_secs(10))
+                    .advance_time(1..=100)
+                    .action_handler(move |_, _, _, action| {
+                        fake_peer.inner().poll(action.time());
+                        matches!(
+                            action.action(),
+                            Action::P2p(P2pAction::Disconnection(P2pDisconnectionAction::Init {
+                                peer_id,
+                                reason: P2pDisconnectionReason::P2pChannelReceiveFailed(_),
+                            })) if *peer_id == fake_peer_id
+                        )
+                    }),
+            )
+            .await
+            .expect("node didn't disconnect the peer");
+    }
+
+    /// Fake peer is only available with the `p2p-webrtc` feature, the
+    /// scenario is skipped otherwise.
+    #[cfg(not(feature = "p2p-webrtc"))]
+    pub async fn run(self, _runner: ClusterRunner<'_>) {}
+}
+
+/// Compares delivery of snark infos sent one by one with the delivery of
+/// the same infos bundled into a single batch message, by two peers.
+///
+/// Makes sure that the node unbundles the whole batch, and that the batch
+/// takes fewer messages and bytes to deliver. Time advances by a fixed step
+/// per event, so the batch also has to be delivered sooner.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct P2pFakePeerSnarkBatch;
+
+impl P2pFakePeerSnarkBatch {
+    #[cfg(feature = "p2p-webrtc")]
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        use std::{collections::BTreeMap, str::FromStr, time::Duration};
+
+        use node::{
+            core::snark::{SnarkInfo, SnarkInfoBatch, SnarkJobId},
+            p2p::{
+                channels::{
+                    snark::{
+                        P2pChannelsSnarkAction, P2pChannelsSnarkState, SnarkPropagationChannelMsg,
+                        SnarkPropagationState,
+                    },
+                    ChannelMsg,
+                },
+                identity::SecretKey,
+            },
+            Action, P2pAction,
+        };
+        use redux::Timestamp;
+
+        use crate::{
+            node::RustNodeTestingConfig,
+            scenario::{ListenerNode, ScenarioStep},
+            scenarios::{DynEffectsData, RunCfg},
+            service::FakePeerStep,
+        };
+
+        const COUNT: u8 = 16;
+        let job_id = SnarkJobId::from_str("jw9nPCs68UNaKaLZwV6QzdswKWomwQxvTgrpmKWmnFJyswnrn4N_jwhHYWzvJG8esmqtYXbUZy3UGbLSjhKvn1FSxBGL1JDFHqbHMJc-jwiLuRrEqNgASgXEqibGs4VqKwSwiuFEtuPD53v8hiTtVuLfmTr_jwhHYWzvJG8esmqtYXbUZy3UGbLSjhKvn1FSxBGL1JDFHqbHMJc").unwrap();
+        let snarks = (0..COUNT)
+            .map(|_| SnarkInfo {
+                job_id: job_id.clone(),
+                fee: mina_p2p_messages::v2::CurrencyFeeStableV1(10_000_000u64.into()),
+                prover: "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg"
+                    .parse()
+                    .unwrap(),
+            })
+            .collect::<Vec<_>>();
+        let batches = SnarkInfoBatch::bundle(snarks.clone());
+        let encoded_len = |msg: &SnarkPropagationChannelMsg| {
+            let mut buf = vec![];
+            mina_p2p_messages::binprot::BinProtWrite::binprot_write(msg, &mut buf).unwrap();
+            buf.len()
+        };
+        let single_bytes = snarks
+            .iter()
+            .map(|snark| encoded_len(&SnarkPropagationChannelMsg::Snark(snark.clone())))
+            .sum::<usize>();
+        let batch_bytes = batches
+            .iter()
+            .map(|batch| encoded_len(&SnarkPropagationChannelMsg::SnarkBatch(batch.clone())))
+            .sum::<usize>();
+
+        let promise = || {
+            FakePeerStep::Send(ChannelMsg::SnarkPropagation(
+                SnarkPropagationChannelMsg::WillSend { count: COUNT },
+            ))
+        };
+
+        let node_id = runner.add_rust_node(RustNodeTestingConfig::devnet_default());
+
+        let mut single_peer = runner
+            .fake_peer_add(node_id, SecretKey::rand())
+            .expect("node must exist");
+        // Give the node time to request the snarks first.
+        single_peer.script([FakePeerStep::Wait(Duration::from_secs(2)), promise()]);
+        single_peer.script(snarks.iter().cloned().map(|snark| {
+            FakePeerStep::Send(ChannelMsg::SnarkPropagation(
+                SnarkPropagationChannelMsg::Snark(snark),
+            ))
+        }));
+
+        let mut batch_peer = runner
+            .fake_peer_add(node_id, SecretKey::rand())
+            .expect("node must exist");
+        batch_peer.script([FakePeerStep::Wait(Duration::from_secs(2)), promise()]);
+        batch_peer.script(batches.into_iter().map(|batch| {
+            FakePeerStep::Send(ChannelMsg::SnarkPropagation(
+                SnarkPropagationChannelMsg::SnarkBatch(batch),
+            ))
+        }));
+
+        let (single_peer_id, batch_peer_id) = (single_peer.peer_id(), batch_peer.peer_id());
+        for fake_peer in [&single_peer, &batch_peer] {
+            runner
+                .exec_step(ScenarioStep::ConnectNodes {
+                    dialer: node_id,
+                    listener: ListenerNode::Custom(fake_peer.dial_opts()),
+                })
+                .await
+                .unwrap();
+        }
+
+        #[derive(Default, Debug)]
+        struct Delivery {
+            promised_at: Option<Timestamp>,
+            messages: usize,
+            elapsed: Option<Duration>,
+        }
+
+        let fake_peers = DynEffectsData::new([single_peer, batch_peer]);
+        let deliveries = DynEffectsData::new(BTreeMap::<_, Delivery>::new());
+        let deliveries_clone = deliveries.clone();
+        runner
+            .run(
+                RunCfg::default()
+                    .timeout(Duration::from_secs(30))
+                    .advance_time(10u64)
+                    .action_handler(move |_, state, _, action| {
+                        for fake_peer in fake_peers.inner().iter_mut() {
+                            fake_peer.poll(action.time());
+                        }
+                        let Action::P2p(P2pAction::Channels(
+                            node::p2p::channels::P2pChannelsAction::Snark(snark_action),
+                        )) = action.action()
+                        else {
+                            return false;
+                        };
+                        let Some(peer_id) = snark_action.peer_id() else {
+                            return false;
+                        };
+
+                        let mut deliveries = deliveries_clone.inner();
+                        let delivery = deliveries.entry(*peer_id).or_default();
+                        match snark_action {
+                            P2pChannelsSnarkAction::PromiseReceived { .. } => {
+                                delivery.promised_at = Some(action.time());
+                            }
+                            P2pChannelsSnarkAction::Received { .. }
+                            | P2pChannelsSnarkAction::BatchReceived { .. } => {
+                                delivery.messages += 1;
+                            }
+                            _ => return false,
+                        }
+                        let is_responded = state.p2p.get_ready_peer(peer_id).is_some_and(|p| {
+                            matches!(
+                                p.channels.snark,
+                                P2pChannelsSnarkState::Ready {
+                                    local: SnarkPropagationState::Responded { count, .. },
+                                    ..
+                                } if count == COUNT
+                            )
+                        });
+                        if is_responded {
+                            delivery.elapsed = delivery
+                                .promised_at
+                                .and_then(|t| action.time().checked_sub(t));
+                        }
+                        [single_peer_id, batch_peer_id]
+                            .iter()
+                            .all(|id| deliveries.get(id).is_some_and(|d| d.elapsed.is_some()))
+                    }),
+            )
+            .await
+            .expect("node didn't receive all the snarks");
+
+        let deliveries = deliveries.inner();
+        let (single, batch) = (&deliveries[&single_peer_id], &deliveries[&batch_peer_id]);
+        eprintln!("snarks sent one by one: {single:?}");
+        eprintln!("snarks sent as a batch: {batch:?}");
+        eprintln!("bytes one by one: {single_bytes}, as a batch: {batch_bytes}");
+        assert_eq!(single.messages, COUNT as usize);
+        assert_eq!(batch.messages, 1);
+        assert!(batch_bytes < single_bytes);
+        let (single_elapsed, batch_elapsed) = (single.elapsed.unwrap(), batch.elapsed.unwrap());
+        assert!(
+            batch_elapsed < single_elapsed,
+            "batch delivered in {batch_elapsed:?}, one by one in {single_elapsed:?}"
+        );
+    }
+
+    /// Fake peer is only available with the `p2p-webrtc` feature, the
+    /// scenario is skipped otherwise.
+    #[cfg(not(feature = "p2p-webrtc"))]
+    pub async fn run(self, _runner: ClusterRunner<'_>) {}
+}
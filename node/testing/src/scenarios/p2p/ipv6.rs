@@ -0,0 +1,160 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use node::p2p::{
+    connection::outgoing::{P2pConnectionOutgoingInitLibp2pOpts, P2pConnectionOutgoingInitOpts},
+    P2pListenFamily, PeerId,
+};
+
+use crate::{
+    cluster::ClusterNodeId,
+    node::RustNodeTestingConfig,
+    scenario::ListenerNode,
+    scenarios::{
+        add_rust_nodes_with, get_p2p_state, peer_is_ready, wait_for_connection_established,
+        wait_for_nodes_listening_on_localhost, ClusterRunner, Driver,
+    },
+};
+
+/// Nodes listening only on IPv6 should connect to each other over IPv6.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct Ipv6OnlyCluster;
+
+impl Ipv6OnlyCluster {
+    pub async fn run(self, runner: ClusterRunner<'_>) {
+        const MAX: u8 = 4;
+
+        let mut driver = Driver::new(runner);
+        let config =
+            RustNodeTestingConfig::devnet_default().with_listen_family(P2pListenFamily::Ipv6);
+
+        let (seed, _) = driver.add_rust_node(config.clone());
+        let satisfied =
+            wait_for_nodes_listening_on_localhost(&mut driver, Duration::from_secs(30), [seed])
+                .await
+                .unwrap();
+        assert!(satisfied, "the seed should be listening");
+
+        let (nodes, peer_ids): (Vec<ClusterNodeId>, BTreeSet<_>) = add_rust_nodes_with(
+            &mut driver,
+            MAX,
+            config.initial_peers(vec![ListenerNode::Rust(seed)]),
+            |state| state.p2p.my_id(),
+        );
+
+        let mut waiting = peer_ids.clone();
+        let connected = wait_for_connection_established(
+            &mut driver,
+            Duration::from_secs(60),
+            |node_id, peer_id: &PeerId| {
+                node_id == seed && waiting.remove(peer_id) && waiting.is_empty()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(connected, "nodes should connect to the seed");
+
+        for node in nodes.into_iter().chain([seed]) {
+            let p2p = get_p2p_state(driver.inner(), node);
+            assert!(
+                p2p.network
+                    .scheduler
+                    .listeners
+                    .iter()
+                    .all(|addr| addr.is_ipv6()),
+                "node {node:?} should listen only on IPv6"
+            );
+            assert!(
+                p2p.network
+                    .scheduler
+                    .connections
+                    .keys()
+                    .all(|addr| addr.sock_addr.is_ipv6()),
+                "node {node:?} should be connected only over IPv6"
+            );
+        }
+        for peer_id in &peer_ids {
+            assert!(
+                peer_is_ready(driver.inner(), seed, peer_id),
+                "peer {peer_id} should be ready"
+            );
+        }
+    }
+}
+
+/// Dual stack node should accept the connections over both IPv4 and IPv6.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct DualStackAcceptsBothFamilies;
+
+impl DualStackAcceptsBothFamilies {
+    pub async fn run(self, runner: ClusterRunner<'_>) {
+        let mut driver = Driver::new(runner);
+
+        let (node_ut, peer_id_ut) = driver.add_rust_node(
+            RustNodeTestingConfig::devnet_default().with_listen_family(P2pListenFamily::DualStack),
+        );
+        let satisfied =
+            wait_for_nodes_listening_on_localhost(&mut driver, Duration::from_secs(30), [node_ut])
+                .await
+                .unwrap();
+        assert!(satisfied, "the node should be listening");
+
+        let port = driver
+            .inner()
+            .node(node_ut)
+            .unwrap()
+            .state()
+            .p2p
+            .config()
+            .libp2p_port
+            .unwrap();
+        let listener = |host| {
+            ListenerNode::Custom(P2pConnectionOutgoingInitOpts::LibP2P(
+                P2pConnectionOutgoingInitLibp2pOpts {
+                    peer_id: peer_id_ut,
+                    host,
+                    port,
+                },
+            ))
+        };
+        let (node_v4, peer_id_v4) =
+            driver.add_rust_node(RustNodeTestingConfig::devnet_default().initial_peers(vec![
+                listener(node::p2p::webrtc::Host::Ipv4([127, 0, 0, 1].into())),
+            ]));
+        let (node_v6, peer_id_v6) = driver.add_rust_node(
+            RustNodeTestingConfig::devnet_default()
+                .with_listen_family(P2pListenFamily::Ipv6)
+                .initial_peers(vec![listener(node::p2p::webrtc::Host::Ipv6(
+                    std::net::Ipv6Addr::LOCALHOST,
+                ))]),
+        );
+
+        let mut waiting = BTreeSet::from([peer_id_v4, peer_id_v6]);
+        let connected = wait_for_connection_established(
+            &mut driver,
+            Duration::from_secs(60),
+            |node_id, peer_id: &PeerId| {
+                node_id == node_ut && waiting.remove(peer_id) && waiting.is_empty()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(connected, "both nodes should connect");
+
+        let p2p = get_p2p_state(driver.inner(), node_ut);
+        let families = p2p
+            .network
+            .scheduler
+            .connections
+            .keys()
+            .map(|addr| addr.sock_addr.is_ipv6())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            families,
+            BTreeSet::from([false, true]),
+            "connections of both families expected"
+        );
+        for node in [node_v4, node_v6] {
+            assert!(peer_is_ready(driver.inner(), node, &peer_id_ut));
+        }
+    }
+}
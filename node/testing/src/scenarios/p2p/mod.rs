@@ -1,6 +1,9 @@
 pub mod basic_connection_handling;
 pub mod basic_incoming_connections;
 pub mod basic_outgoing_connections;
+pub mod conformance;
+pub mod fake_peer;
+pub mod ipv6;
 pub mod kademlia;
 pub mod pubsub;
 pub mod signaling;
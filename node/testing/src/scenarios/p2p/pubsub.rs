@@ -1,6 +1,18 @@
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
-use node::ActionKind;
+use mina_p2p_messages::{
+    gossip::GossipNetMessageV2, list::List, v2::NetworkPoolTransactionPoolDiffVersionedStableV2,
+};
+use node::{
+    p2p::{P2pGossipTopic, P2pNetworkAction, P2pNetworkPubsubAction},
+    Action, ActionKind, P2pAction,
+};
 
 use crate::{
     hosts,
@@ -57,3 +69,108 @@ impl P2pReceiveMessage {
             .expect("Test failed");
     }
 }
+
+/// Node subscribed only to the blocks ignores the other gossip received
+/// over libp2p, without decoding it.
+/// 1. Create a node and a blocks only node, which connects to the first one
+/// 2. Wait until the first node has a meshsub stream to the second one
+/// 3. Broadcast a transaction pool diff followed by a block from the first node
+/// 4. The blocks only node must ignore the transactions and handle the block
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct P2pGossipTopicsSubset;
+
+impl P2pGossipTopicsSubset {
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        let config = RustNodeTestingConfig::devnet_default().with_no_peer_discovery();
+        let sender = runner.add_rust_node(config.clone());
+        runner
+            .run(
+                RunCfg::default()
+                    .timeout(Duration::from_secs(60))
+                    .action_handler(move |node, state, _, _| {
+                        node == sender && state.transition_frontier.best_tip().is_some()
+                    }),
+            )
+            .await
+            .expect("sender didn't load the genesis block");
+
+        let receiver = runner.add_rust_node(
+            config
+                .initial_peers(vec![sender.into()])
+                .with_gossip_topics(vec![P2pGossipTopic::Blocks]),
+        );
+        let receiver_peer_id = runner.node(receiver).expect("node must exist").peer_id();
+        runner
+            .run(
+                RunCfg::default()
+                    .timeout(Duration::from_secs(60))
+                    .action_handler(move |node, state, _, _| {
+                        node == sender
+                            && state.p2p.ready().is_some_and(|p2p| {
+                                p2p.network
+                                    .scheduler
+                                    .broadcast_state
+                                    .clients
+                                    .get(&receiver_peer_id)
+                                    .is_some_and(|client| client.outgoing_stream_id.is_some())
+                            })
+                    }),
+            )
+            .await
+            .expect("receiver didn't open the meshsub stream");
+
+        let block = runner
+            .node(sender)
+            .and_then(|node| node.state().transition_frontier.best_tip())
+            .expect("sender must have the best tip")
+            .block
+            .clone();
+        let transactions = GossipNetMessageV2::TransactionPoolDiff {
+            message: NetworkPoolTransactionPoolDiffVersionedStableV2(List::new()),
+            nonce: 0u32.into(),
+        };
+        for message in [transactions, GossipNetMessageV2::NewState(block)] {
+            assert!(runner.dispatch_action(sender, P2pNetworkPubsubAction::Broadcast { message }));
+        }
+
+        let ignored = Arc::new(AtomicBool::new(false));
+        let handler_ignored = ignored.clone();
+        runner
+            .run(
+                RunCfg::default()
+                    .timeout(Duration::from_secs(60))
+                    .action_handler(move |node, _, _, action| {
+                        if node != receiver {
+                            return false;
+                        }
+                        match action.action() {
+                            Action::P2p(P2pAction::Network(P2pNetworkAction::Pubsub(
+                                P2pNetworkPubsubAction::IgnoreMessage { reason, .. },
+                            ))) if reason.contains("not subscribed") => {
+                                handler_ignored.store(true, Ordering::Relaxed);
+                                false
+                            }
+                            Action::P2p(P2pAction::Network(P2pNetworkAction::Pubsub(
+                                P2pNetworkPubsubAction::HandleIncomingMessage {
+                                    message_content,
+                                    ..
+                                },
+                            ))) => {
+                                assert!(
+                                    matches!(message_content, GossipNetMessageV2::NewState(_)),
+                                    "receiver must not handle the unsubscribed gossip"
+                                );
+                                true
+                            }
+                            _ => false,
+                        }
+                    }),
+            )
+            .await
+            .expect("receiver didn't handle the block");
+        assert!(
+            ignored.load(Ordering::Relaxed),
+            "receiver must ignore the transactions"
+        );
+    }
+}
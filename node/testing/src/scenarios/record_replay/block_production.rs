@@ -26,6 +26,7 @@ impl RecordReplayBlockProduction {
             fish: 2,
             non_stakers: NonStakers::None,
             constants,
+            accounts: Vec::new(),
         };
         let cfg = SimulatorConfig {
             genesis: genesis_cfg.into(),
@@ -37,6 +38,7 @@ impl RecordReplayBlockProduction {
             run_until: SimulatorRunUntil::BlockchainLength(10),
             run_until_timeout: Duration::from_secs(15 * 60),
             recorder: Recorder::StateWithInputActions,
+            empty_blocks: None,
         };
         let mut simulator = Simulator::new(initial_time, cfg);
         simulator.setup_and_run(&mut runner).await;
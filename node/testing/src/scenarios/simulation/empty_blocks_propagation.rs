@@ -0,0 +1,91 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use mina_p2p_messages::v2::{BlockTimeTimeStableV1, PROTOCOL_CONSTANTS};
+use node::{
+    block_producer::BlockProducerEmptyBlocksConfig,
+    transition_frontier::genesis::{GenesisConfig, NonStakers},
+    ActionWithMeta,
+};
+
+use crate::{
+    scenarios::{ClusterRunner, RunCfgAdvanceTime},
+    service::NodeTestingService,
+    simulator::{BlockPropagationStats, Simulator, SimulatorConfig, SimulatorRunUntil},
+};
+
+/// Propagation of the empty blocks.
+///
+/// Block producer wins every slot regardless of the vrf threshold and
+/// produces blocks without transactions, so that the end-to-end propagation
+/// latency can be measured independently of the pool contents.
+///
+/// Run until `blockchain_length >= 20`.
+///
+/// - **whale** block producers: **1**.
+/// - seed nodes: **2**.
+/// - normal nodes: **4**.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct SimulationEmptyBlocksPropagation;
+
+impl SimulationEmptyBlocksPropagation {
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        let initial_time = redux::Timestamp::global_now();
+        let mut constants = PROTOCOL_CONSTANTS.clone();
+        constants.genesis_state_timestamp =
+            BlockTimeTimeStableV1((u64::from(initial_time) / 1_000_000).into());
+        let genesis_cfg = GenesisConfig::Counts {
+            whales: 1,
+            fish: 0,
+            non_stakers: NonStakers::None,
+            constants,
+            accounts: Vec::new(),
+        };
+        let cfg = SimulatorConfig {
+            genesis: genesis_cfg.into(),
+            seed_nodes: 2,
+            normal_nodes: 4,
+            snark_workers: 0,
+            block_producers: 1,
+            advance_time: RunCfgAdvanceTime::Rand(10..=200),
+            run_until: SimulatorRunUntil::BlockchainLength(20),
+            run_until_timeout: Duration::from_secs(20 * 60),
+            recorder: Default::default(),
+            empty_blocks: Some(BlockProducerEmptyBlocksConfig { cadence: 1 }),
+        };
+
+        let stats = Arc::new(Mutex::new(BlockPropagationStats::new()));
+        let factory = || {
+            let stats = stats.clone();
+            move |_id, state: &node::State, _: &NodeTestingService, action: &ActionWithMeta| {
+                stats.lock().unwrap().on_action(state, action);
+                false
+            }
+        };
+        let mut simulator = Simulator::new(initial_time, cfg);
+        simulator
+            .setup_and_run_with_listener(&mut runner, factory)
+            .await;
+
+        let nodes = runner.nodes_iter().count().saturating_sub(1);
+        let stats = stats.lock().unwrap();
+        for (hash, block) in stats.blocks() {
+            eprintln!(
+                "[propagation] block {} - {hash}: nodes: {}/{nodes}, max: {:?}",
+                block.height,
+                block.latencies.len(),
+                block.latencies.values().max(),
+            );
+        }
+        let summary = stats.summary(nodes);
+        eprintln!("[propagation] {summary}");
+
+        assert!(summary.blocks > 0, "empty blocks should be produced");
+        assert!(
+            summary.fully_propagated > 0,
+            "empty blocks should reach all the nodes"
+        );
+    }
+}
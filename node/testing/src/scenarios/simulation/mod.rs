@@ -1,2 +1,3 @@
+pub mod empty_blocks_propagation;
 pub mod small;
 pub mod small_forever_real_time;
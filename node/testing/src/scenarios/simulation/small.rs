@@ -30,6 +30,7 @@ impl SimulationSmall {
             fish: 4,
             non_stakers: NonStakers::None,
             constants,
+            accounts: Vec::new(),
         };
         let cfg = SimulatorConfig {
             genesis: genesis_cfg.into(),
@@ -41,6 +42,7 @@ impl SimulationSmall {
             run_until: SimulatorRunUntil::Epoch(3),
             run_until_timeout: Duration::from_secs(30 * 60),
             recorder: Default::default(),
+            empty_blocks: None,
         };
         let mut simulator = Simulator::new(initial_time, cfg);
         simulator.setup_and_run(&mut runner).await;
@@ -30,6 +30,7 @@ impl SimulationSmallForeverRealTime {
             fish: 2,
             non_stakers: NonStakers::Count(100),
             constants,
+            accounts: Vec::new(),
         };
         let cfg = SimulatorConfig {
             genesis: genesis_cfg.into(),
@@ -41,6 +42,7 @@ impl SimulationSmallForeverRealTime {
             run_until: SimulatorRunUntil::Forever,
             run_until_timeout: Duration::MAX,
             recorder: Default::default(),
+            empty_blocks: None,
         };
         let mut simulator = Simulator::new(initial_time, cfg);
         simulator.setup_and_run(&mut runner).await;
@@ -223,7 +223,7 @@ impl SoloNodeSyncRootSnarkedLedger {
     fn event_ledger_query_addr(self, state: &State, event: &Event) -> Option<LedgerAddress> {
         let Event::P2p(P2pEvent::Channel(P2pChannelEvent::Received(
             peer_id,
-            Ok(ChannelMsg::Rpc(RpcChannelMsg::Response(_, _))),
+            Ok(ChannelMsg::Rpc(RpcChannelMsg::Response(rpc_id, _))),
         ))) = event
         else {
             return None;
@@ -234,7 +234,7 @@ impl SoloNodeSyncRootSnarkedLedger {
             .unwrap()
             .channels
             .rpc
-            .pending_local_rpc()
+            .pending_local_rpc(*rpc_id)
             .unwrap();
         let P2pRpcRequest::LedgerQuery(_, MinaLedgerSyncLedgerQueryStableV1::WhatChildHashes(addr)) =
             rpc
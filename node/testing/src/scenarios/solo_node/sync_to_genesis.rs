@@ -55,6 +55,8 @@ impl SoloNodeSyncToGenesis {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            listen_family: Default::default(),
+            gossip_topics: None,
         });
 
         runner
@@ -71,6 +71,8 @@ impl SoloNodeSyncToGenesisCustom {
             libp2p_port: None,
             recorder: Default::default(),
             peer_discovery: true,
+            listen_family: Default::default(),
+            gossip_topics: None,
         });
 
         runner
@@ -0,0 +1,351 @@
+//! Scriptable webrtc peer, which plays the role of the remote side of
+//! the connection, so that handling of misbehaving peers can be tested
+//! without running a second node.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+use node::event_source::Event;
+use node::p2p::{
+    channels::{
+        rpc::{P2pRpcRequest, P2pRpcResponse, RpcChannelMsg},
+        ChannelId, ChannelMsg,
+    },
+    connection::{outgoing::P2pConnectionOutgoingInitOpts, P2pConnectionFinalizeError},
+    feature_flags::P2pFeatureFlags,
+    identity::SecretKey,
+    service_impl::webrtc::{ChannelMsgDecoder, MsgBuffer, P2pServiceWebrtc, PeerCmd, PeerState},
+    webrtc::{self, P2pCapabilities, P2pConnectionResponse, SignalingMethod},
+    P2pChannelEvent, P2pConnectionEvent, P2pCorrelationId, P2pEvent, PeerId,
+};
+use openmina_core::channels::{mpsc, Aborter};
+use redux::Timestamp;
+
+use super::NodeTestingService;
+
+/// Sdp of the fake peer. Never parsed by the state machine, only hashed
+/// for the connection auth.
+const FAKE_PEER_SDP: &str = "fake-peer-sdp";
+
+type RpcResponder = Box<dyn FnMut(&P2pRpcRequest) -> Option<P2pRpcResponse> + Send>;
+
+#[derive(Debug, Clone)]
+pub enum FakePeerStep {
+    /// Sends well formed message.
+    Send(ChannelMsg),
+    /// Sends raw bytes on the channel, as if they were received from the
+    /// data channel, e.g. to send a malformed length prefix.
+    SendRaw { channel: ChannelId, data: Vec<u8> },
+    /// Sends only the first `len` bytes of the encoded message, leaving
+    /// the node waiting for the rest of it.
+    SendPartial { msg: ChannelMsg, len: usize },
+    /// Doesn't continue with the next steps until the time passes.
+    Wait(Duration),
+    /// Closes the channel.
+    ChannelClose(ChannelId),
+    /// Closes the connection.
+    Disconnect,
+}
+
+impl FakePeerStep {
+    fn channel_id(&self) -> Option<ChannelId> {
+        match self {
+            Self::Send(msg) | Self::SendPartial { msg, .. } => Some(msg.channel_id()),
+            Self::SendRaw { channel, .. } => Some(*channel),
+            Self::Wait(_) | Self::ChannelClose(_) | Self::Disconnect => None,
+        }
+    }
+}
+
+/// Remote side of the webrtc connection, driven by the test.
+///
+/// Handshake and channel opening requests from the node are answered
+/// automatically. Messages sent by the node are collected and can be
+/// inspected with [`FakePeer::take_received`], while messages from the
+/// peer are produced by the scripted [`FakePeerStep`]s.
+///
+/// Only connections initiated by the node are supported, see
+/// [`FakePeer::dial_opts`].
+pub struct FakePeer {
+    sec_key: SecretKey,
+    event_sender: mpsc::UnboundedSender<Event>,
+    cmd_receiver: mpsc::TrackedUnboundedReceiver<(P2pCorrelationId, PeerCmd)>,
+    offer: Option<webrtc::Offer>,
+    answer: Option<webrtc::Answer>,
+    is_connected: bool,
+    msg_buf: MsgBuffer,
+    decoders: BTreeMap<ChannelId, ChannelMsgDecoder>,
+    received: Vec<ChannelMsg>,
+    script: VecDeque<FakePeerStep>,
+    wait_until: Option<Timestamp>,
+    rpc_responder: Option<RpcResponder>,
+    rpc_delay: Duration,
+    rpc_responses: VecDeque<(Timestamp, ChannelMsg)>,
+}
+
+impl FakePeer {
+    pub fn peer_id(&self) -> PeerId {
+        self.sec_key.public_key().peer_id()
+    }
+
+    /// Options for the node to dial the fake peer. Signaling goes through
+    /// the fake peer instead of http, so the address is never used.
+    pub fn dial_opts(&self) -> P2pConnectionOutgoingInitOpts {
+        P2pConnectionOutgoingInitOpts::WebRTC {
+            peer_id: self.peer_id(),
+            signaling: SignalingMethod::Http(([127, 0, 0, 1], 1).into()),
+        }
+    }
+
+    /// Whether the connection auth was exchanged with the node.
+    pub fn is_connected(&self) -> bool {
+        self.is_connected
+    }
+
+    /// Answers rpc requests from the node with the `responder`, after
+    /// the `delay`. `None` from the responder means that data isn't
+    /// available, same as for the real peer.
+    pub fn set_rpc_responder<F>(&mut self, delay: Duration, responder: F)
+    where
+        F: 'static + Send + FnMut(&P2pRpcRequest) -> Option<P2pRpcResponse>,
+    {
+        self.rpc_delay = delay;
+        self.rpc_responder = Some(Box::new(responder));
+    }
+
+    /// Appends steps to the script, which is executed by [`FakePeer::poll`].
+    pub fn script<I>(&mut self, steps: I)
+    where
+        I: IntoIterator<Item = FakePeerStep>,
+    {
+        self.script.extend(steps);
+    }
+
+    /// Messages sent by the node since the last call.
+    pub fn take_received(&mut self) -> Vec<ChannelMsg> {
+        std::mem::take(&mut self.received)
+    }
+
+    /// Handles commands from the node, sends due rpc responses and
+    /// executes the script. `now` is the node's time.
+    ///
+    /// Steps, which send data on the channel, wait for the node to open
+    /// that channel first.
+    pub fn poll(&mut self, now: Timestamp) {
+        while let Ok(cmd) = self.cmd_receiver.try_recv() {
+            let (cid, cmd) = cmd.0;
+            self.handle_cmd(now, cid, cmd);
+        }
+
+        while self.rpc_responses.front().is_some_and(|(at, _)| *at <= now) {
+            if let Some((_, msg)) = self.rpc_responses.pop_front() {
+                self.send_msg(&msg);
+            }
+        }
+
+        if !self.is_connected() {
+            return;
+        }
+        while let Some(step) = self.script.front() {
+            match step {
+                FakePeerStep::Wait(dur) => {
+                    let until = *self.wait_until.get_or_insert(now + *dur);
+                    if until > now {
+                        return;
+                    }
+                    self.wait_until = None;
+                }
+                step => {
+                    if let Some(id) = step.channel_id() {
+                        if !self.decoders.contains_key(&id) {
+                            return;
+                        }
+                    }
+                }
+            }
+            if let Some(step) = self.script.pop_front() {
+                self.exec(step);
+            }
+        }
+    }
+
+    /// Executes the step right away.
+    pub fn exec(&mut self, step: FakePeerStep) {
+        match step {
+            FakePeerStep::Send(msg) => self.send_msg(&msg),
+            FakePeerStep::SendRaw { channel, data } => self.send_raw(channel, &data),
+            FakePeerStep::SendPartial { msg, len } => {
+                let encoded = self.encode(&msg);
+                let len = len.min(encoded.len());
+                self.send_raw(msg.channel_id(), &encoded[..len]);
+            }
+            FakePeerStep::Wait(_) => {}
+            FakePeerStep::ChannelClose(id) => {
+                self.decoders.remove(&id);
+                self.send_event(P2pChannelEvent::Closed(self.peer_id(), id).into());
+            }
+            FakePeerStep::Disconnect => {
+                self.is_connected = false;
+                self.send_event(P2pConnectionEvent::Closed(self.peer_id()).into());
+            }
+        }
+    }
+
+    fn handle_cmd(&mut self, now: Timestamp, cid: P2pCorrelationId, cmd: PeerCmd) {
+        let peer_id = self.peer_id();
+        match cmd {
+            PeerCmd::OfferSend(_, offer) => {
+                let answer = webrtc::Answer {
+                    sdp: FAKE_PEER_SDP.to_owned(),
+                    identity_pub_key: self.sec_key.public_key(),
+                    target_peer_id: offer.identity_pub_key.peer_id(),
+                    bulk_channels: 0,
+                    tx_digests: true,
+                    compact_blocks: true,
+                    capabilities: Some(P2pCapabilities::local(
+                        &ChannelId::iter_all().collect(),
+                        &P2pFeatureFlags {
+                            chunked_rpc: false,
+                            ..Default::default()
+                        },
+                    )),
+                };
+                self.offer = Some(offer);
+                let response = P2pConnectionResponse::Accepted(Box::new(answer));
+                self.send_event(P2pConnectionEvent::AnswerReceived(peer_id, cid, response).into());
+            }
+            PeerCmd::AnswerSet(answer) => {
+                self.answer = Some(answer);
+            }
+            PeerCmd::ConnectionAuthorizationSend(None) => {}
+            PeerCmd::ConnectionAuthorizationSend(Some(_)) => {
+                let auth =
+                    self.offer
+                        .as_ref()
+                        .zip(self.answer.as_ref())
+                        .and_then(|(offer, answer)| {
+                            offer.conn_auth(answer).encrypt(
+                                &self.sec_key,
+                                &offer.identity_pub_key,
+                                rand::thread_rng(),
+                            )
+                        });
+                self.is_connected = auth.is_some();
+                let res = auth.ok_or_else(|| {
+                    P2pConnectionFinalizeError::ConnectionFailed("fake peer auth failed".to_owned())
+                });
+                self.send_event(P2pConnectionEvent::Finalized(peer_id, cid, res).into());
+            }
+            PeerCmd::ChannelOpen(id) => {
+                self.decoders.insert(id, ChannelMsgDecoder::new(id));
+                self.send_event(P2pChannelEvent::Opened(peer_id, id, cid, Ok(())).into());
+            }
+            PeerCmd::ChannelSend(msg_id, msg) => {
+                let id = msg.channel_id();
+                self.send_event(P2pChannelEvent::Sent(peer_id, id, msg_id, cid, Ok(())).into());
+                if let ChannelMsg::Rpc(RpcChannelMsg::Request(rpc_id, request)) = &msg {
+                    if let Some(responder) = self.rpc_responder.as_mut() {
+                        let response = RpcChannelMsg::Response(*rpc_id, responder(request));
+                        self.rpc_responses
+                            .push_back((now + self.rpc_delay, response.into()));
+                    }
+                }
+                self.received.push(msg);
+            }
+            PeerCmd::IceRestartInit | PeerCmd::IceRestartOfferSet(_) => {
+                let err = "ice restart not supported by the fake peer".to_owned();
+                self.send_event(
+                    P2pConnectionEvent::IceRestartNegotiated(peer_id, cid, Err(err)).into(),
+                );
+            }
+        }
+    }
+
+    fn encode(&mut self, msg: &ChannelMsg) -> Vec<u8> {
+        self.msg_buf
+            .encode(msg)
+            .expect("use `FakePeerStep::SendRaw` to send oversized messages")
+    }
+
+    fn send_msg(&mut self, msg: &ChannelMsg) {
+        let encoded = self.encode(msg);
+        self.send_raw(msg.channel_id(), &encoded);
+    }
+
+    /// Feeds the bytes through the same decoder, which is used for the
+    /// data received from the real data channel.
+    fn send_raw(&mut self, channel: ChannelId, mut data: &[u8]) {
+        let peer_id = self.peer_id();
+        let Some(decoder) = self.decoders.get_mut(&channel) else {
+            panic!("fake peer: channel {channel:?} is not open");
+        };
+        let mut events = vec![];
+        while !data.is_empty() {
+            match decoder.decode_next(&mut data) {
+                Ok(None) => continue,
+                Ok(Some(msg)) => events.push(P2pChannelEvent::Received(peer_id, Ok(msg))),
+                Err(err) => {
+                    // node disconnects the peer on the error, so the
+                    // rest of the data doesn't matter.
+                    events.push(P2pChannelEvent::Received(peer_id, Err(err)));
+                    break;
+                }
+            }
+        }
+        for event in events {
+            self.send_event(event.into());
+        }
+    }
+
+    fn send_event(&self, event: P2pEvent) {
+        let _ = self.event_sender.send(event.into());
+    }
+}
+
+impl NodeTestingService {
+    /// Creates a fake peer, which the node can connect to with
+    /// [`FakePeer::dial_opts`].
+    pub fn fake_peer_add(&mut self, sec_key: SecretKey) -> FakePeer {
+        let (cmd_sender, cmd_receiver) = mpsc::tracked_unbounded_channel();
+        let peer_id = sec_key.public_key().peer_id();
+        self.fake_peers.insert(peer_id, cmd_sender);
+        FakePeer {
+            sec_key,
+            event_sender: P2pServiceWebrtc::event_sender(&self.real).clone(),
+            cmd_receiver,
+            offer: None,
+            answer: None,
+            is_connected: false,
+            msg_buf: MsgBuffer::new(64 * 1024),
+            decoders: Default::default(),
+            received: vec![],
+            script: Default::default(),
+            wait_until: None,
+            rpc_responder: None,
+            rpc_delay: Duration::ZERO,
+            rpc_responses: Default::default(),
+        }
+    }
+
+    /// Instead of creating a real webrtc connection, routes the
+    /// commands for the peer to the fake peer. Returns `None` if the
+    /// peer isn't a fake one.
+    pub(super) fn fake_peer_outgoing_init(&mut self, peer_id: PeerId) -> Option<P2pCorrelationId> {
+        let cmd_sender = self.fake_peers.get(&peer_id).cloned()?;
+        P2pServiceWebrtc::peers(&mut self.real).insert(
+            peer_id,
+            PeerState {
+                cmd_sender,
+                abort: Aborter::default(),
+            },
+        );
+        let correlation_id = P2pCorrelationId::next();
+        let event = P2pConnectionEvent::OfferSdpReady(
+            peer_id,
+            correlation_id,
+            Ok(FAKE_PEER_SDP.to_owned()),
+        );
+        let _ = P2pServiceWebrtc::event_sender(&self.real).send(P2pEvent::from(event).into());
+        Some(correlation_id)
+    }
+}
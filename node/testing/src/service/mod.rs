@@ -1,5 +1,10 @@
 mod rpc_service;
 
+#[cfg(feature = "p2p-webrtc")]
+mod fake_peer;
+#[cfg(feature = "p2p-webrtc")]
+pub use fake_peer::{FakePeer, FakePeerStep};
+
 use std::collections::VecDeque;
 use std::sync::Mutex as StdMutex;
 use std::time::Duration;
@@ -20,14 +25,18 @@ use mina_p2p_messages::v2::{
 use node::account::AccountPublicKey;
 use node::block_producer::vrf_evaluator::VrfEvaluatorInput;
 use node::block_producer::BlockProducerEvent;
+use node::checkpoint::CheckpointService;
 use node::core::channels::mpsc;
 use node::core::invariants::InvariantsState;
 use node::core::snark::{Snark, SnarkJobId};
 use node::external_snark_worker_effectful::ExternalSnarkWorkerEvent;
 use node::ledger::write::BlockApplyResult;
+use node::p2p::channels::checkpoint::SignedCheckpoint;
+use node::p2p::connection::P2pPreferredPeer;
 use node::p2p::service_impl::webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p;
-use node::p2p::P2pCryptoService;
+use node::p2p::{P2pCorrelationId, P2pCryptoService, P2pPreferredPeersService};
 use node::recorder::Recorder;
+use node::rpc::{RpcId, RpcLedgerAccountsAtBlockQuery};
 use node::service::{
     BlockProducerService, BlockProducerVrfEvaluatorService, TransitionFrontierGenesisService,
 };
@@ -39,7 +48,8 @@ use node::snark::user_command_verify_effectful::SnarkUserCommandVerifyService;
 use node::snark::work_verify::{SnarkWorkVerifyId, SnarkWorkVerifyService};
 use node::snark::{BlockVerifier, SnarkEvent, TransactionVerifier, VerifierSRS};
 use node::snark_pool::SnarkPoolService;
-use node::stats::Stats;
+use node::stats::{profiler::Profiler, Stats};
+use node::transaction_pool::{TransactionPoolPayoutsService, TransactionPoolSponsorService};
 use node::transition_frontier::archive::archive_service::ArchiveService;
 use node::transition_frontier::genesis::GenesisConfig;
 use node::{
@@ -119,6 +129,10 @@ impl PendingEvents {
         }
     }
 
+    fn len(&self) -> usize {
+        self.events.len()
+    }
+
     fn iter(&self) -> impl Iterator<Item = (PendingEventId, &Event)> {
         self.events.iter().map(|(id, event)| (*id, event))
     }
@@ -141,6 +155,15 @@ pub struct NodeTestingService {
     snarker_sok_digest: Option<ByteString>,
 
     cluster_invariants_state: Arc<StdMutex<InvariantsState>>,
+    /// Command senders of the [`FakePeer`]s, by their peer id.
+    #[cfg(feature = "p2p-webrtc")]
+    fake_peers: BTreeMap<
+        PeerId,
+        mpsc::TrackedUnboundedSender<(
+            node::p2p::P2pCorrelationId,
+            node::p2p::service_impl::webrtc::PeerCmd,
+        )>,
+    >,
     /// Once dropped, it will cause all threads associated to shutdown.
     _shutdown: Aborter,
 }
@@ -163,6 +186,8 @@ impl NodeTestingService {
             dyn_effects: None,
             snarker_sok_digest: None,
             cluster_invariants_state,
+            #[cfg(feature = "p2p-webrtc")]
+            fake_peers: Default::default(),
             _shutdown,
         }
     }
@@ -288,6 +313,10 @@ impl node::Service for NodeTestingService {
         self.real.stats()
     }
 
+    fn profiler(&mut self) -> &mut Profiler {
+        self.real.profiler()
+    }
+
     fn recorder(&mut self) -> &mut Recorder {
         self.real.recorder()
     }
@@ -344,6 +373,12 @@ impl node::event_source::EventSourceService for NodeTestingService {
     fn next_event(&mut self) -> Option<Event> {
         None
     }
+
+    fn pending_events_count(&self) -> usize {
+        // Events are dispatched by the cluster, but the queue depth still
+        // drives the overload protection.
+        self.pending_events.len()
+    }
 }
 
 impl TransitionFrontierGenesisService for NodeTestingService {
@@ -357,7 +392,7 @@ impl P2pServiceWebrtc for NodeTestingService {
 
     fn random_pick(
         &mut self,
-        list: &[P2pConnectionOutgoingInitOpts],
+        list: &[(P2pConnectionOutgoingInitOpts, f64)],
     ) -> Option<P2pConnectionOutgoingInitOpts> {
         self.real.random_pick(list)
     }
@@ -374,11 +409,15 @@ impl P2pServiceWebrtc for NodeTestingService {
         P2pServiceWebrtc::peers(&mut self.real)
     }
 
-    fn outgoing_init(&mut self, peer_id: PeerId) {
+    fn outgoing_init(&mut self, peer_id: PeerId) -> P2pCorrelationId {
+        #[cfg(feature = "p2p-webrtc")]
+        if let Some(correlation_id) = self.fake_peer_outgoing_init(peer_id) {
+            return correlation_id;
+        }
         P2pServiceWebrtc::outgoing_init(&mut self.real, peer_id)
     }
 
-    fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer) {
+    fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer) -> P2pCorrelationId {
         P2pServiceWebrtc::incoming_init(&mut self.real, peer_id, offer)
     }
 
@@ -452,6 +491,10 @@ impl SnarkBlockVerifyService for NodeTestingService {
             ),
         }
     }
+
+    fn verify_cancel(&mut self, req_id: SnarkBlockVerifyId) {
+        SnarkBlockVerifyService::verify_cancel(&mut self.real, req_id)
+    }
 }
 
 impl SnarkUserCommandVerifyService for NodeTestingService {
@@ -510,6 +553,40 @@ impl ArchiveService for NodeTestingService {
     fn send_to_archive(&mut self, data: BlockApplyResult) {
         self.real.send_to_archive(data);
     }
+
+    fn accounts_at_block(&mut self, rpc_id: RpcId, query: RpcLedgerAccountsAtBlockQuery) {
+        self.real.accounts_at_block(rpc_id, query);
+    }
+}
+
+impl TransactionPoolPayoutsService for NodeTestingService {
+    fn with_payout_keypair<T>(
+        &self,
+        f: impl FnOnce(&node::account::AccountSecretKey) -> T,
+    ) -> Option<T> {
+        self.real.with_payout_keypair(f)
+    }
+}
+
+impl CheckpointService for NodeTestingService {
+    fn checkpoints_persist(&mut self, checkpoints: Vec<SignedCheckpoint>) {
+        self.real.checkpoints_persist(checkpoints);
+    }
+}
+
+impl P2pPreferredPeersService for NodeTestingService {
+    fn p2p_preferred_peers_persist(&mut self, now: redux::Timestamp, peers: Vec<P2pPreferredPeer>) {
+        self.real.p2p_preferred_peers_persist(now, peers);
+    }
+}
+
+impl TransactionPoolSponsorService for NodeTestingService {
+    fn with_sponsor_keypair<T>(
+        &self,
+        f: impl FnOnce(&node::account::AccountSecretKey) -> T,
+    ) -> Option<T> {
+        self.real.with_sponsor_keypair(f)
+    }
 }
 
 use std::cell::RefCell;
@@ -531,6 +608,9 @@ impl BlockProducerService for NodeTestingService {
             let dummy_proof = (*ledger::dummy::dummy_blockchain_proof()).clone();
             BlockProducerEvent::BlockProve(block_hash, Ok(dummy_proof.into())).into()
         }
+        if !self.real.block_signing_journal_record(&block_hash, &input) {
+            return;
+        }
         let keypair = self.real.block_producer().unwrap().keypair();
 
         match self.proof_kind() {
@@ -599,6 +679,10 @@ impl BlockProducerService for NodeTestingService {
     ) -> Option<T> {
         None
     }
+
+    fn slot_claim(&mut self, producer: NonZeroCurvePoint, global_slot: u32) {
+        self.real.slot_claim(producer, global_slot)
+    }
 }
 
 impl ExternalSnarkWorkerService for NodeTestingService {
@@ -16,11 +16,35 @@ impl RpcService for super::NodeTestingService {
     to_real!(respond_state_get, (&State, Option<&str>));
     to_real!(respond_status_get, node::rpc::RpcStatusGetResponse);
     to_real!(respond_heartbeat_get, node::rpc::RpcHeartbeatGetResponse);
+    to_real!(
+        respond_state_digest_get,
+        node::rpc::RpcStateDigestGetResponse
+    );
     to_real!(respond_sync_stats_get, node::rpc::RpcSyncStatsGetResponse);
     to_real!(
         respond_block_producer_stats_get,
         node::rpc::RpcBlockProducerStatsGetResponse
     );
+    to_real!(
+        respond_block_producer_epoch_report_get,
+        node::rpc::RpcBlockProducerEpochReportGetResponse
+    );
+    to_real!(
+        respond_zkapp_failure_stats_get,
+        node::rpc::RpcZkappFailureStatsGetResponse
+    );
+    to_real!(
+        respond_snark_verify_stats_get,
+        node::rpc::RpcSnarkVerifyStatsGetResponse
+    );
+    to_real!(
+        respond_stats_history_get,
+        node::rpc::RpcStatsHistoryGetResponse
+    );
+    to_real!(
+        respond_block_propagation_get,
+        node::rpc::RpcBlockPropagationGetResponse
+    );
 
     to_real!(
         respond_action_stats_get,
@@ -31,6 +55,10 @@ impl RpcService for super::NodeTestingService {
         RpcMessageProgressResponse
     );
     to_real!(respond_peers_get, node::rpc::RpcPeersGetResponse,);
+    to_real!(
+        respond_p2p_diagnostics_get,
+        node::rpc::RpcP2pDiagnosticsGetResponse,
+    );
     to_real!(
         respond_p2p_connection_outgoing,
         node::rpc::RpcP2pConnectionOutgoingResponse,
@@ -117,6 +145,88 @@ impl RpcService for super::NodeTestingService {
         node::rpc::RpcTransactionStatusGetResponse,
     );
     to_real!(respond_block_get, node::rpc::RpcGetBlockResponse,);
+    to_real!(
+        respond_ledger_accounts_at_block,
+        node::rpc::RpcLedgerAccountsAtBlockResponse,
+    );
+    to_real!(
+        respond_account_next_nonces,
+        node::rpc::RpcAccountNextNoncesGetResponse,
+    );
+    to_real!(
+        respond_transaction_inclusion_proof_get,
+        node::rpc::RpcTransactionInclusionProofGetResponse,
+    );
+    to_real!(
+        respond_finality_estimate_get,
+        node::rpc::RpcFinalityEstimateGetResponse,
+    );
+    to_real!(respond_node_info_get, node::rpc::RpcNodeInfoGetResponse);
+    to_real!(
+        respond_internal_commands_get,
+        node::rpc::RpcInternalCommandsGetResponse,
+    );
+    to_real!(
+        respond_sync_peer_scores_get,
+        node::rpc::RpcSyncPeerScoresGetResponse,
+    );
+    to_real!(
+        respond_transaction_pool_zkapp_stats_get,
+        node::rpc::RpcTransactionPoolZkappStatsGetResponse,
+    );
+    to_real!(respond_profiler, node::rpc::RpcProfilerResponse);
+    to_real!(
+        respond_maintenance_mode,
+        node::rpc::RpcMaintenanceModeResponse,
+    );
+    to_real!(respond_feature_flags, node::rpc::RpcFeatureFlagsResponse);
+    to_real!(
+        respond_checkpoints_get,
+        node::rpc::RpcCheckpointsGetResponse
+    );
+    to_real!(
+        respond_transaction_pool_snapshot,
+        node::rpc::RpcTransactionPoolSnapshotResponse
+    );
+    to_real!(
+        respond_account_subscriptions,
+        node::rpc::RpcAccountSubscriptionsResponse,
+    );
+    to_real!(respond_account_event, node::rpc::RpcWatchedAccountEvent,);
+    to_real!(
+        respond_snark_pool_job_events,
+        Vec<node::rpc::RpcSnarkPoolJobEvent>,
+    );
+    to_real!(
+        respond_zkapp_vk_register,
+        node::rpc::RpcZkappVkRegisterResponse,
+    );
+    to_real!(
+        respond_zkapp_preconditions_evaluate,
+        node::rpc::RpcZkappPreconditionsEvaluateResponse,
+    );
+    to_real!(
+        respond_payouts_batch_submit,
+        node::rpc::RpcPayoutsBatchSubmitResponse,
+    );
+    to_real!(
+        respond_payouts_batch_get,
+        node::rpc::RpcPayoutsBatchGetResponse,
+    );
+    to_real!(
+        respond_chain_import_block,
+        node::rpc::RpcChainImportBlockResponse,
+    );
+    to_real!(respond_vrf_verify, node::rpc::RpcVrfVerifyResponse);
+    to_real!(respond_ledger_audit, node::rpc::RpcLedgerAuditResponse);
+    to_real!(
+        respond_block_template_get,
+        node::rpc::RpcBlockTemplateGetResponse,
+    );
+    to_real!(
+        respond_block_template_submit,
+        node::rpc::RpcBlockTemplateSubmitResponse,
+    );
     to_real!(
         respond_pooled_user_commands,
         node::rpc::RpcPooledUserCommandsResponse,
@@ -134,8 +244,13 @@ impl RpcService for super::NodeTestingService {
         respond_ledger_status_get,
         node::rpc::RpcLedgerStatusGetResponse,
     );
+    to_real!(
+        respond_ledger_status_extended_get,
+        node::rpc::RpcLedgerStatusExtendedGetResponse,
+    );
     to_real!(
         respond_ledger_account_delegators_get,
         node::rpc::RpcLedgerAccountDelegatorsGetResponse,
     );
+    to_real!(respond_ledger_session, node::rpc::RpcLedgerSessionResponse,);
 }
@@ -1,6 +1,8 @@
 use std::{sync::Arc, time::Duration};
 
-use node::transition_frontier::genesis::GenesisConfig;
+use node::{
+    block_producer::BlockProducerEmptyBlocksConfig, transition_frontier::genesis::GenesisConfig,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::{node::Recorder, scenarios::RunCfgAdvanceTime};
@@ -20,6 +22,10 @@ pub struct SimulatorConfig {
     pub run_until_timeout: Duration,
     #[serde(default)]
     pub recorder: Recorder,
+    /// Block producers produce empty blocks at every slot of the cadence,
+    /// see [`BlockProducerEmptyBlocksConfig`].
+    #[serde(default)]
+    pub empty_blocks: Option<BlockProducerEmptyBlocksConfig>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -1,6 +1,9 @@
 mod config;
 pub use config::*;
 
+mod propagation;
+pub use propagation::*;
+
 use mina_p2p_messages::v2::{
     CurrencyFeeStableV1, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
 };
@@ -51,6 +54,8 @@ impl Simulator {
             libp2p_port: None,
             recorder: self.config.recorder.clone(),
             peer_discovery: true,
+            listen_family: Default::default(),
+            gossip_topics: None,
         }
     }
 
@@ -204,13 +209,11 @@ impl Simulator {
                 "block producer({}) stake: {stake} mina",
                 sec_key.public_key()
             );
+            let mut bp_config = BlockProducerConfig::new(sec_key.public_key().into());
+            bp_config.empty_blocks = self.config.empty_blocks.clone();
             let config = RustNodeTestingConfig {
                 block_producer: Some(RustNodeBlockProducerTestingConfig {
-                    config: BlockProducerConfig {
-                        pub_key: sec_key.public_key().into(),
-                        custom_coinbase_receiver: None,
-                        proposed_protocol_version: None,
-                    },
+                    config: bp_config,
                     sec_key,
                 }),
                 ..node_config.clone()
@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use mina_p2p_messages::v2::StateHash;
+use node::{p2p::PeerId, ActionKind, ActionWithMeta, State};
+use serde::Serialize;
+
+/// End-to-end propagation latency of the produced blocks, from the time
+/// the block was produced until it became the best tip of the other nodes.
+///
+/// Nodes are identified by their peer id, as the actions of the time
+/// advancement aren't attributed to the nodes in the cluster runner.
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct BlockPropagationStats {
+    blocks: BTreeMap<StateHash, BlockPropagation>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BlockPropagation {
+    pub producer: PeerId,
+    pub height: u32,
+    pub produced_at: redux::Timestamp,
+    /// Time it took for the block to become the best tip of the node.
+    pub latencies: BTreeMap<PeerId, Duration>,
+}
+
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct BlockPropagationSummary {
+    pub blocks: usize,
+    /// Number of the blocks which became the best tip of all the nodes.
+    pub fully_propagated: usize,
+    pub min: Option<Duration>,
+    pub avg: Option<Duration>,
+    pub p90: Option<Duration>,
+    pub max: Option<Duration>,
+}
+
+impl BlockPropagationStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Must be called for every action of every node in the cluster.
+    pub fn on_action(&mut self, state: &State, action: &ActionWithMeta) {
+        if action.action().kind() == ActionKind::BlockProducerBlockProduced {
+            if let Some(block) = state
+                .block_producer
+                .as_ref()
+                .and_then(|bp| bp.current.produced_block())
+            {
+                self.blocks
+                    .entry(block.hash().clone())
+                    .or_insert_with(|| BlockPropagation {
+                        producer: state.p2p.my_id(),
+                        height: block.height(),
+                        produced_at: action.time(),
+                        latencies: Default::default(),
+                    });
+            }
+            return;
+        }
+
+        let Some(best_tip) = state.transition_frontier.best_tip() else {
+            return;
+        };
+        let Some(block) = self.blocks.get_mut(best_tip.hash()) else {
+            return;
+        };
+        let node = state.p2p.my_id();
+        if node != block.producer {
+            let latency = action
+                .time()
+                .checked_sub(block.produced_at)
+                .unwrap_or_default();
+            block.latencies.entry(node).or_insert(latency);
+        }
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = (&StateHash, &BlockPropagation)> {
+        self.blocks.iter()
+    }
+
+    /// Summary of the latencies, given the number of the nodes in the
+    /// cluster, excluding the producer.
+    pub fn summary(&self, nodes: usize) -> BlockPropagationSummary {
+        let mut latencies = self
+            .blocks
+            .values()
+            .flat_map(|block| block.latencies.values().copied())
+            .collect::<Vec<_>>();
+        latencies.sort();
+
+        let avg = u32::try_from(latencies.len())
+            .ok()
+            .and_then(|len| latencies.iter().sum::<Duration>().checked_div(len));
+        let p90 = latencies.get(latencies.len() * 9 / 10);
+
+        BlockPropagationSummary {
+            blocks: self.blocks.len(),
+            fully_propagated: self
+                .blocks
+                .values()
+                .filter(|block| block.latencies.len() >= nodes)
+                .count(),
+            min: latencies.first().copied(),
+            avg,
+            p90: p90.copied(),
+            max: latencies.last().copied(),
+        }
+    }
+}
+
+impl fmt::Display for BlockPropagationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "blocks: {}, fully propagated: {}, min: {:?}, avg: {:?}, p90: {:?}, max: {:?}",
+            self.blocks, self.fully_propagated, self.min, self.avg, self.p90, self.max
+        )
+    }
+}
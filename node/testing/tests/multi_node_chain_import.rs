@@ -0,0 +1,7 @@
+mod common;
+
+scenario_test!(
+    chain_import,
+    openmina_node_testing::scenarios::multi_node::chain_import::MultiNodeChainImport,
+    openmina_node_testing::scenarios::multi_node::chain_import::MultiNodeChainImport
+);
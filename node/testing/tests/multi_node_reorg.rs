@@ -0,0 +1,7 @@
+mod common;
+
+scenario_test!(
+    reorg,
+    openmina_node_testing::scenarios::multi_node::reorg::MultiNodeReorg,
+    openmina_node_testing::scenarios::multi_node::reorg::MultiNodeReorg
+);
@@ -0,0 +1,10 @@
+use openmina_node_testing::scenarios::p2p::conformance::P2pConformanceRustNode;
+
+mod common;
+
+scenario_test!(
+    p2p_conformance_rust_node,
+    P2pConformanceRustNode,
+    P2pConformanceRustNode,
+    true
+);
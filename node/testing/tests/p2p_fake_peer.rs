@@ -0,0 +1,21 @@
+#![cfg(feature = "p2p-webrtc")]
+
+use openmina_node_testing::scenarios::p2p::fake_peer::{
+    P2pFakePeerMsgLenOverLimit, P2pFakePeerSnarkBatch,
+};
+
+mod common;
+
+scenario_test!(
+    p2p_fake_peer_msg_len_over_limit,
+    P2pFakePeerMsgLenOverLimit,
+    P2pFakePeerMsgLenOverLimit,
+    true
+);
+
+scenario_test!(
+    p2p_fake_peer_snark_batch,
+    P2pFakePeerSnarkBatch,
+    P2pFakePeerSnarkBatch,
+    true
+);
@@ -0,0 +1,10 @@
+use openmina_node_testing::scenarios::p2p::ipv6::{DualStackAcceptsBothFamilies, Ipv6OnlyCluster};
+
+mod common;
+
+scenario_test!(ipv6_only_cluster, Ipv6OnlyCluster, Ipv6OnlyCluster);
+scenario_test!(
+    dual_stack_accepts_both_families,
+    DualStackAcceptsBothFamilies,
+    DualStackAcceptsBothFamilies
+);
@@ -1,5 +1,11 @@
-use openmina_node_testing::scenarios::p2p::pubsub::P2pReceiveMessage;
+use openmina_node_testing::scenarios::p2p::pubsub::{P2pGossipTopicsSubset, P2pReceiveMessage};
 
 mod common;
 
 scenario_test!(pubsub_receive_block, P2pReceiveMessage, P2pReceiveMessage);
+
+scenario_test!(
+    pubsub_gossip_topics_subset,
+    P2pGossipTopicsSubset,
+    P2pGossipTopicsSubset
+);
@@ -7,12 +7,14 @@ use node::{
     core::{consensus::ConsensusConstants, constants::constraint_constants},
     p2p::{
         channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts,
-        identity::SecretKey as P2pSecretKey, P2pLimits, P2pMeshsubConfig, P2pTimeouts,
+        feature_flags::P2pFeatureFlags, identity::SecretKey as P2pSecretKey,
+        service_impl::webrtc_pacing::WebrtcSendConfig, P2pGossipTopic, P2pLimits, P2pMeshsubConfig,
+        P2pTimeouts,
     },
     snark::{get_srs, BlockVerifier, TransactionVerifier, VerifierSRS},
     transition_frontier::genesis::GenesisConfig,
-    BlockProducerConfig, GlobalConfig, LedgerConfig, P2pConfig, SnarkConfig, SnarkerConfig,
-    SnarkerStrategy, TransitionFrontierConfig,
+    BlockProducerConfig, GlobalConfig, LedgerConfig, P2pConfig, SnarkConfig, SnarkPoolConfig,
+    SnarkerConfig, SnarkerStrategy, TransitionFrontierConfig,
 };
 use openmina_node_common::{p2p::TaskSpawner, NodeServiceCommonBuilder};
 use rand::Rng;
@@ -26,10 +28,13 @@ pub struct NodeBuilder {
     p2p_sec_key: Option<P2pSecretKey>,
     p2p_is_seed: bool,
     p2p_no_discovery: bool,
+    p2p_gossip_topics: Vec<P2pGossipTopic>,
+    p2p_feature_flags: P2pFeatureFlags,
     p2p_is_started: bool,
     initial_peers: Vec<P2pConnectionOutgoingInitOpts>,
     block_producer: Option<BlockProducerConfig>,
     snarker: Option<SnarkerConfig>,
+    snark_pool: SnarkPoolConfig,
     service: NodeServiceCommonBuilder,
     verifier_srs: Option<Arc<VerifierSRS>>,
     block_verifier_index: Option<BlockVerifier>,
@@ -46,10 +51,13 @@ impl NodeBuilder {
             p2p_sec_key: None,
             p2p_is_seed: false,
             p2p_no_discovery: false,
+            p2p_gossip_topics: P2pGossipTopic::ALL.to_vec(),
+            p2p_feature_flags: P2pFeatureFlags::default(),
             p2p_is_started: false,
             initial_peers: Vec::new(),
             block_producer: None,
             snarker: None,
+            snark_pool: SnarkPoolConfig::default(),
             service: NodeServiceCommonBuilder::new(rng_seed),
             verifier_srs: None,
             block_verifier_index: None,
@@ -81,6 +89,19 @@ impl NodeBuilder {
         self
     }
 
+    /// Subscribe only to the gossip messages of the `topics`, e.g. only
+    /// to the blocks, to save the bandwidth of the browser.
+    pub fn p2p_gossip_topics(&mut self, topics: &[P2pGossipTopic]) -> &mut Self {
+        self.p2p_gossip_topics = topics.to_vec();
+        self
+    }
+
+    /// Initial p2p feature flags, may be changed at runtime with the rpc.
+    pub fn p2p_feature_flags(&mut self, flags: P2pFeatureFlags) -> &mut Self {
+        self.p2p_feature_flags = flags;
+        self
+    }
+
     /// Extend p2p initial peers from an iterable.
     pub fn initial_peers(
         &mut self,
@@ -105,6 +126,13 @@ impl NodeBuilder {
     //     Ok(self)
     // }
 
+    /// Override chunk size and pacing of the messages sent over webrtc
+    /// channels. Must be called before [`Self::p2p_custom_task_spawner`].
+    pub fn p2p_webrtc_send_config(&mut self, config: WebrtcSendConfig) -> &mut Self {
+        self.service.p2p_webrtc_send_config(config);
+        self
+    }
+
     /// Override default p2p task spawner.
     pub fn p2p_custom_task_spawner(
         &mut self,
@@ -122,11 +150,7 @@ impl NodeBuilder {
         key: AccountSecretKey,
         provers: Option<BlockProver>,
     ) -> &mut Self {
-        let config = BlockProducerConfig {
-            pub_key: key.public_key().into(),
-            custom_coinbase_receiver: None,
-            proposed_protocol_version: None,
-        };
+        let config = BlockProducerConfig::new(key.public_key().into());
         self.block_producer = Some(config);
         self.service.block_producer_init(key, provers);
         self
@@ -174,6 +198,13 @@ impl NodeBuilder {
         self
     }
 
+    /// Fully verify only `percent` of the snark work received from the
+    /// peers. See [`SnarkPoolConfig::verify_sample_percent`].
+    pub fn snark_verify_sampling(&mut self, percent: u8) -> &mut Self {
+        self.snark_pool.verify_sample_percent = Some(percent.min(100));
+        self
+    }
+
     /// Set verifier srs. If not set, default will be used.
     pub fn verifier_srs(&mut self, srs: Arc<VerifierSRS>) -> &mut Self {
         self.verifier_srs = Some(srs);
@@ -222,20 +253,28 @@ impl NodeBuilder {
             ConsensusConstants::create(constraint_constants(), &protocol_constants);
 
         // build config
-        let node_config = node::Config {
+        let mut node_config = node::Config {
             global: GlobalConfig {
                 build: node::BuildEnv::get().into(),
                 snarker: self.snarker,
                 consensus_constants: consensus_consts.clone(),
                 testing_run: false,
                 client_port: None,
+                profile: Default::default(),
+                payout_pub_key: None,
+                rpc_response_limits: Default::default(),
+                sibling_nodes: Vec::new(),
             },
             p2p: P2pConfig {
                 libp2p_port: None,
                 listen_port: None,
                 identity_pub_key: p2p_sec_key.public_key(),
+                webrtc_identity_pub_key: None,
                 initial_peers,
+                preferred_peers: Vec::new(),
                 external_addrs: vec![],
+                listen_family: Default::default(),
+                dial_family: None,
                 enabled_channels: ChannelId::iter_all().collect(),
                 peer_discovery: !self.p2p_no_discovery,
                 meshsub: P2pMeshsubConfig {
@@ -244,23 +283,35 @@ impl NodeBuilder {
                 },
                 timeouts: P2pTimeouts::default(),
                 limits: P2pLimits::default().with_max_peers(Some(100)),
+                feature_flags: self.p2p_feature_flags,
             },
-            ledger: LedgerConfig {},
+            ledger: LedgerConfig::default(),
             snark: SnarkConfig {
                 block_verifier_index,
                 block_verifier_srs: srs.clone(),
                 work_verifier_index,
                 work_verifier_srs: srs,
+                verify_admission: Default::default(),
             },
+            snark_pool: self.snark_pool,
             transition_frontier,
             block_producer: self.block_producer,
             tx_pool: ledger::transaction_pool::Config {
                 trust_system: (),
                 pool_max_size: node::daemon_json::Daemon::DEFAULT.tx_pool_max_size(),
                 slot_tx_end: node::daemon_json::Daemon::DEFAULT.slot_tx_end(),
+                zkapp_limits: Default::default(),
+                fee_payer_limits: Default::default(),
             },
             archive: None,
+            watchtower: None,
+            checkpoint: Default::default(),
+            fee_sponsor: None,
         };
+        node_config
+            .p2p
+            .set_gossip_topics(&self.p2p_gossip_topics)
+            .map_err(anyhow::Error::msg)?;
 
         // build service
         let mut service = self.service;
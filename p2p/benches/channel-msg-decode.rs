@@ -0,0 +1,78 @@
+#![allow(unexpected_cfgs)]
+#![cfg(all(benchmarks, feature = "p2p-webrtc"))]
+#![feature(test)]
+
+use std::sync::Arc;
+
+use binprot::BinProtRead;
+use mina_p2p_messages::{
+    rpc::GetStagedLedgerAuxAndPendingCoinbasesAtHashV2,
+    rpc_kernel::{Message, RpcMethod},
+    v2,
+};
+use p2p::{
+    channels::{
+        rpc::{P2pRpcResponse, RpcChannelMsg, StagedLedgerAuxAndPendingCoinbases},
+        ChannelId, ChannelMsg,
+    },
+    service_impl::{
+        webrtc::{ChannelMsgDecoder, MsgBuffer},
+        webrtc_pacing::DEFAULT_CHUNK_SIZE,
+    },
+};
+use test::Bencher;
+
+extern crate test;
+
+/// Staged ledger response, the biggest message received during sync,
+/// encoded as it is sent over the webrtc data channel.
+fn staged_ledger_encoded() -> Vec<u8> {
+    type Response = <GetStagedLedgerAuxAndPendingCoinbasesAtHashV2 as RpcMethod>::Response;
+    let mut p: &[u8] = include_bytes!(
+        "../../mina-p2p-messages/tests/files/v2/rpc/get-staged-ledger-aux/response/00.bin"
+    );
+    let Message::Response(response) = Message::<Response>::binprot_read(&mut p).unwrap() else {
+        panic!("not a response");
+    };
+    let (scan_state, hash, pending_coinbase, needed_blocks) = response.data.0.unwrap().0.unwrap();
+    let data = StagedLedgerAuxAndPendingCoinbases {
+        scan_state,
+        staged_ledger_hash: v2::MinaBaseLedgerHash0StableV1(hash).into(),
+        pending_coinbase,
+        needed_blocks,
+    };
+    let msg = ChannelMsg::Rpc(RpcChannelMsg::Response(
+        response.id,
+        Some(P2pRpcResponse::StagedLedgerAuxAndPendingCoinbasesAtBlock(
+            Arc::new(data),
+        )),
+    ));
+    MsgBuffer::new(0).encode(&msg).unwrap()
+}
+
+fn decode_in_pieces(b: &mut Bencher, piece_size: usize) {
+    let encoded = staged_ledger_encoded();
+    b.bytes = encoded.len() as u64;
+    b.iter(|| {
+        let mut decoder = ChannelMsgDecoder::new(ChannelId::Rpc);
+        let mut decoded = None;
+        for mut piece in encoded.chunks(piece_size) {
+            while !piece.is_empty() {
+                if let Some(msg) = decoder.decode_next(&mut piece).unwrap() {
+                    decoded = Some(msg);
+                }
+            }
+        }
+        decoded.unwrap()
+    })
+}
+
+#[bench]
+fn decode_staged_ledger_whole(b: &mut Bencher) {
+    decode_in_pieces(b, usize::MAX)
+}
+
+#[bench]
+fn decode_staged_ledger_chunked(b: &mut Bencher) {
+    decode_in_pieces(b, DEFAULT_CHUNK_SIZE)
+}
@@ -6,6 +6,9 @@ pub use p2p_channels_best_tip_actions::*;
 
 mod p2p_channels_best_tip_reducer;
 
+mod p2p_channels_best_tip_compact;
+pub use p2p_channels_best_tip_compact::*;
+
 use binprot_derive::{BinProtRead, BinProtWrite};
 use openmina_core::block::ArcBlock;
 use serde::{Deserialize, Serialize};
@@ -15,4 +18,19 @@ pub enum BestTipPropagationChannelMsg {
     /// Request next commitments upto the `limit`.
     GetNext,
     BestTip(ArcBlock),
+    /// Sent instead of `BestTip`, if peer supports compact blocks.
+    CompactBestTip(Box<CompactBlock>),
+    /// Request entries of the last `CompactBestTip`, which are missing
+    /// from our pools.
+    ///
+    /// - Can only be sent, if peer has sent `CompactBestTip` and we
+    ///   haven't reconstructed the block yet.
+    GetMissing(CompactBlockMissing),
+    /// Requested entries of the last `CompactBestTip`.
+    Missing(CompactBlockFill),
+    /// Request the last `CompactBestTip` as the full block, since the
+    /// reconstructed one doesn't match the `body_reference` in its header.
+    ///
+    /// - Peer responds with `BestTip`.
+    GetFull,
 }
@@ -6,7 +6,10 @@ use crate::{
     P2pState, PeerId,
 };
 
-use super::BestTipPropagationState;
+use super::{
+    BestTipPropagationState, CompactBlock, CompactBlockEntries, CompactBlockFill,
+    CompactBlockMissing,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
 #[action_event(fields(display(peer_id), best_tip = display(&best_tip.hash)))]
@@ -40,6 +43,44 @@ pub enum P2pChannelsBestTipAction {
         peer_id: PeerId,
         best_tip: ArcBlockWithHash,
     },
+    /// Best tip response expired in the send queue and wasn't sent,
+    /// so the peer is still waiting for the response.
+    ResponseExpired {
+        peer_id: PeerId,
+    },
+    /// Compact best tip response is received.
+    CompactReceived {
+        peer_id: PeerId,
+        block: Box<CompactBlock>,
+    },
+    /// Entries of the compact best tip are resolved from the pools.
+    CompactResolve {
+        peer_id: PeerId,
+        entries: CompactBlockEntries,
+    },
+    /// Request for the entries of the sent compact best tip is received.
+    MissingRequestReceived {
+        peer_id: PeerId,
+        missing: CompactBlockMissing,
+    },
+    /// Requested entries of the compact best tip are received.
+    MissingReceived {
+        peer_id: PeerId,
+        fill: CompactBlockFill,
+    },
+    /// Body of the reconstructed compact best tip matches its header.
+    CompactVerifySuccess {
+        peer_id: PeerId,
+    },
+    /// Body of the reconstructed compact best tip doesn't match its
+    /// header, request the full block instead.
+    CompactVerifyError {
+        peer_id: PeerId,
+    },
+    /// Request for the sent compact best tip as the full block is received.
+    FullRequestReceived {
+        peer_id: PeerId,
+    },
 }
 
 impl P2pChannelsBestTipAction {
@@ -51,7 +92,15 @@ impl P2pChannelsBestTipAction {
             | Self::RequestSend { peer_id }
             | Self::Received { peer_id, .. }
             | Self::RequestReceived { peer_id }
-            | Self::ResponseSend { peer_id, .. } => peer_id,
+            | Self::ResponseSend { peer_id, .. }
+            | Self::ResponseExpired { peer_id }
+            | Self::CompactReceived { peer_id, .. }
+            | Self::CompactResolve { peer_id, .. }
+            | Self::MissingRequestReceived { peer_id, .. }
+            | Self::MissingReceived { peer_id, .. }
+            | Self::CompactVerifySuccess { peer_id }
+            | Self::CompactVerifyError { peer_id }
+            | Self::FullRequestReceived { peer_id } => peer_id,
         }
     }
 }
@@ -108,20 +157,67 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsBestTipAction {
                     _ => false,
                 }),
             P2pChannelsBestTipAction::ResponseSend { peer_id, best_tip } => state
+                .get_ready_peer(peer_id)
+                .is_some_and(|p| p.channels.best_tip.can_send_response(best_tip)),
+            P2pChannelsBestTipAction::ResponseExpired { peer_id } => state
                 .get_ready_peer(peer_id)
                 .is_some_and(|p| match &p.channels.best_tip {
-                    P2pChannelsBestTipState::Ready {
-                        remote, last_sent, ..
-                    } => {
-                        if !matches!(remote, BestTipPropagationState::Requested { .. }) {
-                            return false;
-                        }
-                        last_sent
-                            .as_ref()
-                            .is_none_or(|sent| sent.hash != best_tip.hash)
+                    P2pChannelsBestTipState::Ready { remote, .. } => {
+                        matches!(remote, BestTipPropagationState::Responded { .. })
                     }
                     _ => false,
                 }),
+            P2pChannelsBestTipAction::CompactReceived { peer_id, block } => state
+                .get_ready_peer(peer_id)
+                .filter(|p| p.channels.compact_blocks)
+                .is_some_and(|p| match &p.channels.best_tip {
+                    P2pChannelsBestTipState::Ready { local, .. } => {
+                        matches!(local, BestTipPropagationState::Requested { .. })
+                            && block.is_valid()
+                    }
+                    _ => false,
+                }),
+            P2pChannelsBestTipAction::CompactResolve { peer_id, entries } => state
+                .get_ready_peer(peer_id)
+                .and_then(|p| p.channels.best_tip.compact_received())
+                .is_some_and(|block| entries.is_valid_for(block)),
+            P2pChannelsBestTipAction::MissingRequestReceived { peer_id, missing } => state
+                .get_ready_peer(peer_id)
+                .filter(|p| p.channels.compact_blocks)
+                .is_some_and(|p| match &p.channels.best_tip {
+                    P2pChannelsBestTipState::Ready {
+                        remote: BestTipPropagationState::Responded { .. },
+                        last_sent: Some(last_sent),
+                        ..
+                    } => !missing.is_empty() && missing.is_valid_for(&last_sent.block),
+                    _ => false,
+                }),
+            P2pChannelsBestTipAction::MissingReceived { peer_id, fill } => state
+                .get_ready_peer(peer_id)
+                .is_some_and(|p| match &p.channels.best_tip {
+                    P2pChannelsBestTipState::Ready {
+                        local: BestTipPropagationState::CompactMissing { entries, .. },
+                        ..
+                    } => entries.can_fill(fill),
+                    _ => false,
+                }),
+            P2pChannelsBestTipAction::CompactVerifySuccess { peer_id }
+            | P2pChannelsBestTipAction::CompactVerifyError { peer_id } => state
+                .get_ready_peer(peer_id)
+                .is_some_and(|p| p.channels.best_tip.compact_reconstructed().is_some()),
+            P2pChannelsBestTipAction::FullRequestReceived { peer_id } => state
+                .get_ready_peer(peer_id)
+                .filter(|p| p.channels.compact_blocks)
+                .is_some_and(|p| {
+                    matches!(
+                        &p.channels.best_tip,
+                        P2pChannelsBestTipState::Ready {
+                            remote: BestTipPropagationState::Responded { .. },
+                            last_sent: Some(_),
+                            ..
+                        }
+                    )
+                }),
         }
     }
 }
@@ -0,0 +1,449 @@
+//! Compact blocks are used to propagate best tip to the webrtc peers,
+//! which most likely already have most of the block's user commands and
+//! snark works in their pools.
+//!
+//! Compact block consists of the block with user commands and completed
+//! works removed from its body, plus short ids for them: transaction hash
+//! for commands and [`SnarkInfo`] for snark works. Receiver fills them in
+//! from its pools and requests only the missing entries.
+//!
+//! Libp2p peers still receive full blocks over pubsub, since the format of
+//! the gossip messages is defined by the Mina protocol.
+
+use std::sync::Arc;
+
+use binprot_derive::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::{
+    list::List,
+    v2::{
+        MinaBaseTransactionStatusStableV2, StagedLedgerDiffBodyStableV1,
+        StagedLedgerDiffDiffDiffStableV2, StagedLedgerDiffDiffPreDiffWithAtMostOneCoinbaseStableV2,
+        StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2,
+        StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B, StagedLedgerDiffDiffStableV2,
+        TransactionHash, TransactionSnarkWorkTStableV2,
+    },
+};
+use openmina_core::{
+    block::{ArcBlock, Block},
+    snark::SnarkInfo,
+    transaction::Transaction,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct CompactBlock {
+    /// Block with user commands and completed works removed from the body.
+    pub block: ArcBlock,
+    /// Short ids of the entries of each part of the staged ledger diff.
+    pub parts: Vec<CompactDiffPart>,
+}
+
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct CompactDiffPart {
+    pub commands: Vec<CompactCommand>,
+    pub works: Vec<SnarkInfo>,
+}
+
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct CompactCommand {
+    pub hash: TransactionHash,
+    pub status: MinaBaseTransactionStatusStableV2,
+}
+
+/// Indexes of the entries which receiver couldn't find in its pools,
+/// among all the commands and works of the compact block.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CompactBlockMissing {
+    pub commands: Vec<u32>,
+    pub works: Vec<u32>,
+}
+
+/// Missing entries, in the same order as they were requested.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct CompactBlockFill {
+    pub commands: Vec<Transaction>,
+    pub works: Vec<TransactionSnarkWorkTStableV2>,
+}
+
+/// Entries of the compact block resolved by the receiver so far.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CompactBlockEntries {
+    pub commands: Vec<Option<Transaction>>,
+    pub works: Vec<Option<TransactionSnarkWorkTStableV2>>,
+}
+
+impl CompactBlock {
+    /// Returns `None` if hash of some command can't be computed, in which
+    /// case full block must be sent instead.
+    pub fn new(block: &Block) -> Option<Self> {
+        let diff = block.body.diff();
+        let mut parts = vec![CompactDiffPart::new(
+            &diff.0.commands,
+            &diff.0.completed_works,
+        )?];
+        if let Some(second) = &diff.1 {
+            parts.push(CompactDiffPart::new(
+                &second.commands,
+                &second.completed_works,
+            )?);
+        }
+
+        // Avoid cloning the proofs of the completed works.
+        let first = StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2 {
+            completed_works: List::new(),
+            commands: List::new(),
+            coinbase: diff.0.coinbase.clone(),
+            internal_command_statuses: diff.0.internal_command_statuses.clone(),
+        };
+        let second = diff.1.as_ref().map(|second| {
+            StagedLedgerDiffDiffPreDiffWithAtMostOneCoinbaseStableV2 {
+                completed_works: List::new(),
+                commands: List::new(),
+                coinbase: second.coinbase.clone(),
+                internal_command_statuses: second.internal_command_statuses.clone(),
+            }
+        });
+        let block = Block {
+            header: block.header.clone(),
+            body: StagedLedgerDiffBodyStableV1 {
+                staged_ledger_diff: StagedLedgerDiffDiffStableV2 {
+                    diff: StagedLedgerDiffDiffDiffStableV2(first, second),
+                },
+            },
+        };
+        Some(Self {
+            block: Arc::new(block),
+            parts,
+        })
+    }
+
+    /// Whether the shape of the compact block matches the block it carries.
+    pub fn is_valid(&self) -> bool {
+        let diff = self.block.body.diff();
+        let expected_parts = if diff.1.is_some() { 2 } else { 1 };
+        self.parts.len() == expected_parts
+            && diff.0.commands.is_empty()
+            && diff.0.completed_works.is_empty()
+            && diff
+                .1
+                .as_ref()
+                .is_none_or(|d| d.commands.is_empty() && d.completed_works.is_empty())
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = &CompactCommand> {
+        self.parts.iter().flat_map(|part| part.commands.iter())
+    }
+
+    pub fn works(&self) -> impl Iterator<Item = &SnarkInfo> {
+        self.parts.iter().flat_map(|part| part.works.iter())
+    }
+
+    /// Rebuilds the full block. Returns `None` if some entry is missing.
+    pub fn reconstruct(&self, entries: CompactBlockEntries) -> Option<ArcBlock> {
+        if !entries.is_complete_for(self) {
+            return None;
+        }
+        let mut commands = entries.commands.into_iter().flatten();
+        let mut works = entries.works.into_iter().flatten();
+        let mut block = (*self.block).clone();
+        let diff = &mut block.body.staged_ledger_diff.diff;
+
+        for (i, part) in self.parts.iter().enumerate() {
+            let part_commands = part
+                .commands
+                .iter()
+                .map(|command| {
+                    Some(StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B {
+                        data: commands.next()?,
+                        status: command.status.clone(),
+                    })
+                })
+                .collect::<Option<List<_>>>()?;
+            let part_works = part
+                .works
+                .iter()
+                .map(|_| works.next())
+                .collect::<Option<List<_>>>()?;
+            match i {
+                0 => {
+                    diff.0.commands = part_commands;
+                    diff.0.completed_works = part_works;
+                }
+                _ => {
+                    let second = diff.1.as_mut()?;
+                    second.commands = part_commands;
+                    second.completed_works = part_works;
+                }
+            }
+        }
+        Some(Arc::new(block))
+    }
+}
+
+impl CompactDiffPart {
+    fn new(
+        commands: &List<StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B>,
+        works: &List<TransactionSnarkWorkTStableV2>,
+    ) -> Option<Self> {
+        let commands = commands
+            .iter()
+            .map(|command| {
+                Some(CompactCommand {
+                    hash: command.data.hash().ok()?,
+                    status: command.status.clone(),
+                })
+            })
+            .collect::<Option<_>>()?;
+        let works = works
+            .iter()
+            .map(|work| SnarkInfo {
+                job_id: (&work.proofs).into(),
+                fee: work.fee.clone(),
+                prover: work.prover.clone(),
+            })
+            .collect();
+        Some(Self { commands, works })
+    }
+}
+
+impl CompactBlockMissing {
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.works.is_empty()
+    }
+
+    /// Whether all the indexes are in range of the block's entries.
+    pub fn is_valid_for(&self, block: &Block) -> bool {
+        let diff = block.body.diff();
+        let commands_len = block.body.commands_iter().count();
+        let works_len =
+            diff.0.completed_works.len() + diff.1.as_ref().map_or(0, |d| d.completed_works.len());
+        self.commands.iter().all(|i| (*i as usize) < commands_len)
+            && self.works.iter().all(|i| (*i as usize) < works_len)
+    }
+}
+
+impl CompactBlockFill {
+    /// Collects the missing entries from the full block.
+    pub fn new(block: &Block, missing: &CompactBlockMissing) -> Option<Self> {
+        let diff = block.body.diff();
+        let commands = block.body.commands_iter().collect::<Vec<_>>();
+        let works = diff
+            .0
+            .completed_works
+            .iter()
+            .chain(diff.1.iter().flat_map(|d| d.completed_works.iter()))
+            .collect::<Vec<_>>();
+        Some(Self {
+            commands: missing
+                .commands
+                .iter()
+                .map(|i| Some(commands.get(*i as usize)?.data.clone()))
+                .collect::<Option<_>>()?,
+            works: missing
+                .works
+                .iter()
+                .map(|i| works.get(*i as usize).map(|w| (*w).clone()))
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+impl CompactBlockEntries {
+    /// Entries of the compact block, none of which are resolved yet.
+    pub fn new(block: &CompactBlock) -> Self {
+        Self {
+            commands: block.commands().map(|_| None).collect(),
+            works: block.works().map(|_| None).collect(),
+        }
+    }
+
+    /// Entries which are still missing.
+    pub fn missing(&self) -> CompactBlockMissing {
+        let missing = |entries: &mut dyn Iterator<Item = bool>| {
+            entries
+                .zip(0..)
+                .filter(|(is_some, _)| !is_some)
+                .map(|(_, i)| i)
+                .collect()
+        };
+        CompactBlockMissing {
+            commands: missing(&mut self.commands.iter().map(Option::is_some)),
+            works: missing(&mut self.works.iter().map(Option::is_some)),
+        }
+    }
+
+    /// Whether entries have the same shape as the compact block.
+    pub fn is_valid_for(&self, block: &CompactBlock) -> bool {
+        self.commands.len() == block.commands().count() && self.works.len() == block.works().count()
+    }
+
+    pub fn is_complete_for(&self, block: &CompactBlock) -> bool {
+        self.is_valid_for(block)
+            && self.commands.iter().all(Option::is_some)
+            && self.works.iter().all(Option::is_some)
+    }
+
+    /// Whether the fill contains exactly the missing entries.
+    pub fn can_fill(&self, fill: &CompactBlockFill) -> bool {
+        let missing = self.missing();
+        missing.commands.len() == fill.commands.len() && missing.works.len() == fill.works.len()
+    }
+
+    /// Fills in the missing entries, in order.
+    pub fn fill(&mut self, fill: CompactBlockFill) {
+        let mut commands = fill.commands.into_iter();
+        for entry in self.commands.iter_mut().filter(|e| e.is_none()) {
+            *entry = commands.next();
+        }
+        let mut works = fill.works.into_iter();
+        for entry in self.works.iter_mut().filter(|e| e.is_none()) {
+            *entry = works.next();
+        }
+    }
+}
+
+/// Stats on how many of the compact blocks' entries we had in our pools.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CompactBlockStats {
+    /// Number of compact blocks received.
+    pub blocks: u64,
+    /// Number of compact blocks reconstructed without requesting
+    /// any entries from the peer.
+    pub blocks_reconstructed: u64,
+    /// Number of compact blocks, which couldn't be reconstructed or which
+    /// body didn't match the header, so the full block was requested.
+    pub blocks_invalid: u64,
+    pub commands: u64,
+    pub commands_missing: u64,
+    pub works: u64,
+    pub works_missing: u64,
+}
+
+impl CompactBlockStats {
+    pub fn record(&mut self, entries: &CompactBlockEntries) {
+        let missing = entries.missing();
+        self.blocks += 1;
+        if missing.is_empty() {
+            self.blocks_reconstructed += 1;
+        }
+        self.commands += entries.commands.len() as u64;
+        self.commands_missing += missing.commands.len() as u64;
+        self.works += entries.works.len() as u64;
+        self.works_missing += missing.works.len() as u64;
+    }
+
+    /// Fraction of the entries which were found in the pools.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.commands + self.works;
+        if total == 0 {
+            return 1.0;
+        }
+        let missing = self.commands_missing + self.works_missing;
+        (total - missing) as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openmina_core::block::fixtures;
+
+    use super::*;
+
+    fn command(nonce: u32) -> StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B {
+        let pk = "B62qnEdPB1V5YPEcGaETb19naLJV6sWdveCZEjSLhcVyrPcPWHkGGax";
+        serde_json::from_value(serde_json::json!({
+            "data": ["Signed_command", {
+                "payload": {
+                    "common": {
+                        "fee": "85776125",
+                        "fee_payer_pk": pk,
+                        "nonce": nonce.to_string(),
+                        "valid_until": "4294967295",
+                        "memo": "E4Yd67s51QN9DZVDy8JKPEoNGykMsYQ5KRiKpZHiLZTjA8dB9SnFT",
+                    },
+                    "body": ["Payment", {
+                        "receiver_pk": "B62qmurT6PKCyetUB6aqagxPbWc74uDaddMdDSm9FQd7ELTKn6BRdv4",
+                        "amount": "1500000",
+                    }],
+                },
+                "signer": pk,
+                "signature": "7mX1Jc4Et7GHmRcTLSJU5E1aKUoja4gyusEXe2xFnyGoy6DHHDVWmuLCTveWnTaBcWQi51sLNuYVMsXkSiR4QBnf5Ga4yCR1",
+            }],
+            "status": ["Failed", [[["Amount_insufficient_to_create_account"]]]],
+        }))
+        .expect("valid command")
+    }
+
+    fn block_with_commands(nonces: &[u32]) -> Block {
+        let mut block = fixtures::best_tip();
+        block.body.staged_ledger_diff.diff.0.commands =
+            nonces.iter().copied().map(command).collect();
+        block
+    }
+
+    #[test]
+    fn test_compact_block_reconstruct() {
+        let block = block_with_commands(&[1, 2, 3]);
+        let compact = CompactBlock::new(&block).expect("commands hashable");
+        assert!(compact.is_valid());
+        assert_eq!(compact.commands().count(), 3);
+
+        // second command is found in the pool, others are requested.
+        let mut entries = CompactBlockEntries::new(&compact);
+        entries.commands[1] = Some(command(2).data);
+        let missing = entries.missing();
+        assert_eq!(missing.commands, vec![0, 2]);
+        assert!(missing.is_valid_for(&block));
+        assert!(compact.reconstruct(entries.clone()).is_none());
+
+        let fill = CompactBlockFill::new(&block, &missing).expect("valid indexes");
+        assert!(entries.can_fill(&fill));
+        entries.fill(fill);
+        assert!(entries.is_complete_for(&compact));
+
+        let reconstructed = compact.reconstruct(entries).expect("complete");
+        assert_eq!(*reconstructed, block);
+    }
+
+    #[test]
+    fn test_compact_block_invalid_fill() {
+        let block = block_with_commands(&[1, 2]);
+        let compact = CompactBlock::new(&block).expect("commands hashable");
+        let entries = CompactBlockEntries::new(&compact);
+
+        let out_of_range = CompactBlockMissing {
+            commands: vec![2],
+            works: vec![],
+        };
+        assert!(!out_of_range.is_valid_for(&block));
+        assert!(CompactBlockFill::new(&block, &out_of_range).is_none());
+
+        let partial = CompactBlockMissing {
+            commands: vec![0],
+            works: vec![],
+        };
+        let fill = CompactBlockFill::new(&block, &partial).expect("valid indexes");
+        assert!(!entries.can_fill(&fill));
+
+        // wrong entry is only caught by verifying the body hash.
+        let mut entries = entries;
+        entries.commands = vec![Some(command(1).data), Some(command(3).data)];
+        let reconstructed = compact.reconstruct(entries).expect("complete");
+        assert_ne!(*reconstructed, block);
+        assert_eq!(reconstructed.header, block.header);
+    }
+
+    #[test]
+    fn test_compact_block_stats() {
+        let block = block_with_commands(&[1, 2, 3, 4]);
+        let compact = CompactBlock::new(&block).expect("commands hashable");
+        let mut entries = CompactBlockEntries::new(&compact);
+        entries.commands[0] = Some(command(1).data);
+
+        let mut stats = CompactBlockStats::default();
+        stats.record(&entries);
+        assert_eq!((stats.blocks, stats.blocks_reconstructed), (1, 0));
+        assert_eq!((stats.commands, stats.commands_missing), (4, 3));
+        assert_eq!(stats.hit_rate(), 0.25);
+    }
+}
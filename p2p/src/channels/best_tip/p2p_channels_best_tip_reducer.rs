@@ -1,5 +1,5 @@
-use openmina_core::{bug_condition, Substate};
-use redux::ActionWithMeta;
+use openmina_core::{block::BlockWithHash, bug_condition, error, Substate};
+use redux::{ActionWithMeta, Dispatcher};
 
 use crate::{
     channels::{ChannelId, ChannelMsg, MsgId, P2pChannelsEffectfulAction},
@@ -7,8 +7,8 @@ use crate::{
 };
 
 use super::{
-    BestTipPropagationChannelMsg, BestTipPropagationState, P2pChannelsBestTipAction,
-    P2pChannelsBestTipState,
+    BestTipPropagationChannelMsg, BestTipPropagationState, CompactBlock, CompactBlockEntries,
+    CompactBlockFill, P2pChannelsBestTipAction, P2pChannelsBestTipState,
 };
 
 impl P2pChannelsBestTipState {
@@ -25,11 +25,12 @@ impl P2pChannelsBestTipState {
         let p2p_state = state_context.get_substate_mut()?;
         let peer_id = *action.peer_id();
         let is_libp2p = p2p_state.is_libp2p_peer(&peer_id);
-        let best_tip_state = &mut p2p_state
+        let channels = &mut p2p_state
             .get_ready_peer_mut(&peer_id)
             .ok_or_else(|| format!("Peer state not found for: {action:?}"))?
-            .channels
-            .best_tip;
+            .channels;
+        let compact_blocks = channels.compact_blocks;
+        let best_tip_state = &mut channels.best_tip;
 
         match action {
             P2pChannelsBestTipAction::Init { .. } => {
@@ -152,18 +153,300 @@ impl P2pChannelsBestTipState {
                 let dispatcher = state_context.into_dispatcher();
 
                 if !is_libp2p {
+                    let compact = compact_blocks
+                        .then(|| CompactBlock::new(&best_tip.block))
+                        .flatten();
+                    let msg = match compact {
+                        Some(block) => {
+                            BestTipPropagationChannelMsg::CompactBestTip(Box::new(block))
+                        }
+                        None => BestTipPropagationChannelMsg::BestTip(best_tip.block),
+                    };
                     dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
                         peer_id,
                         msg_id: MsgId::first(),
-                        msg: ChannelMsg::BestTipPropagation(BestTipPropagationChannelMsg::BestTip(
-                            best_tip.block,
-                        )),
+                        msg: ChannelMsg::BestTipPropagation(msg),
                     });
                     return Ok(());
                 }
 
                 Ok(())
             }
+            P2pChannelsBestTipAction::ResponseExpired { .. } => {
+                if !best_tip_state.response_expired(meta.time()) {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::ResponseExpired`, state: {:?}",
+                        best_tip_state
+                    );
+                }
+                Ok(())
+            }
+            P2pChannelsBestTipAction::CompactReceived { block, .. } => {
+                let Self::Ready { local, .. } = best_tip_state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::CompactReceived`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+                let entries = CompactBlockEntries::new(&block);
+                *local = BestTipPropagationState::CompactReceived {
+                    time: meta.time(),
+                    block,
+                };
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+
+                if let Some(callback) = &p2p_state
+                    .callbacks
+                    .on_p2p_channels_best_tip_compact_received
+                {
+                    dispatcher.push_callback(callback.clone(), peer_id);
+                } else {
+                    // Nobody resolves entries from the pools, so request all.
+                    dispatcher.push(P2pChannelsBestTipAction::CompactResolve { peer_id, entries });
+                }
+                Ok(())
+            }
+            P2pChannelsBestTipAction::CompactResolve { entries, .. } => {
+                let Self::Ready { local, .. } = best_tip_state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::CompactResolve`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+                let BestTipPropagationState::CompactReceived { block, .. } = std::mem::replace(
+                    local,
+                    BestTipPropagationState::Requested { time: meta.time() },
+                ) else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::CompactResolve`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+
+                let missing = entries.missing();
+                let reconstructed = if missing.is_empty() {
+                    Some(block)
+                } else {
+                    *local = BestTipPropagationState::CompactMissing {
+                        time: meta.time(),
+                        block,
+                        entries: entries.clone(),
+                    };
+                    None
+                };
+
+                let p2p_state = state_context.get_substate_mut()?;
+                p2p_state.compact_block_stats.record(&entries);
+
+                if let Some(block) = reconstructed {
+                    return Self::compact_reconstruct(
+                        state_context,
+                        peer_id,
+                        &block,
+                        entries,
+                        meta.time(),
+                    );
+                }
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+                    peer_id,
+                    msg_id: MsgId::first(),
+                    msg: ChannelMsg::BestTipPropagation(BestTipPropagationChannelMsg::GetMissing(
+                        missing,
+                    )),
+                });
+                Ok(())
+            }
+            P2pChannelsBestTipAction::MissingRequestReceived { missing, .. } => {
+                let Self::Ready {
+                    last_sent: Some(last_sent),
+                    ..
+                } = best_tip_state
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::MissingRequestReceived`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+                let Some(fill) = CompactBlockFill::new(&last_sent.block, &missing) else {
+                    bug_condition!("Invalid missing entries request: {:?}", missing);
+                    return Ok(());
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+                    peer_id,
+                    msg_id: MsgId::first(),
+                    msg: ChannelMsg::BestTipPropagation(BestTipPropagationChannelMsg::Missing(
+                        fill,
+                    )),
+                });
+                Ok(())
+            }
+            P2pChannelsBestTipAction::MissingReceived { fill, .. } => {
+                let Self::Ready { local, .. } = best_tip_state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::MissingReceived`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+                let BestTipPropagationState::CompactMissing {
+                    block, mut entries, ..
+                } = std::mem::replace(
+                    local,
+                    BestTipPropagationState::Requested { time: meta.time() },
+                )
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::MissingReceived`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+                entries.fill(fill);
+
+                Self::compact_reconstruct(state_context, peer_id, &block, entries, meta.time())
+            }
+            P2pChannelsBestTipAction::CompactVerifySuccess { .. } => {
+                let Self::Ready { local, .. } = best_tip_state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::CompactVerifySuccess`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+                let BestTipPropagationState::CompactReconstructed { best_tip, .. } =
+                    std::mem::replace(
+                        local,
+                        BestTipPropagationState::Requested { time: meta.time() },
+                    )
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::CompactVerifySuccess`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pChannelsBestTipAction::Received { peer_id, best_tip });
+                Ok(())
+            }
+            P2pChannelsBestTipAction::CompactVerifyError { .. } => {
+                let Self::Ready { local, .. } = best_tip_state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::CompactVerifyError`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+                *local = BestTipPropagationState::Requested { time: meta.time() };
+
+                let p2p_state = state_context.get_substate_mut()?;
+                p2p_state.compact_block_stats.blocks_invalid += 1;
+
+                let dispatcher = state_context.into_dispatcher();
+                Self::full_request_send(dispatcher, peer_id);
+                Ok(())
+            }
+            P2pChannelsBestTipAction::FullRequestReceived { .. } => {
+                let Self::Ready {
+                    last_sent: Some(last_sent),
+                    ..
+                } = best_tip_state
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsBestTipAction::FullRequestReceived`, state: {:?}",
+                        best_tip_state
+                    );
+                    return Ok(());
+                };
+                let block = last_sent.block.clone();
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+                    peer_id,
+                    msg_id: MsgId::first(),
+                    msg: ChannelMsg::BestTipPropagation(BestTipPropagationChannelMsg::BestTip(
+                        block,
+                    )),
+                });
+                Ok(())
+            }
         }
     }
+
+    /// Rebuilds the compact best tip and passes it for verification, since
+    /// entries resolved from the pools or sent by the peer may not be the
+    /// ones the block was built with. Falls back to requesting the full
+    /// block, if the compact one can't be reconstructed.
+    fn compact_reconstruct<Action, State>(
+        mut state_context: Substate<Action, State, P2pState>,
+        peer_id: PeerId,
+        block: &CompactBlock,
+        entries: CompactBlockEntries,
+        time: redux::Timestamp,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let best_tip = match block.reconstruct(entries).map(BlockWithHash::try_new) {
+            Some(Ok(best_tip)) => Some(best_tip),
+            Some(Err(_)) => {
+                error!(time; "CompactBestTip: Invalid bigint in block");
+                None
+            }
+            None => {
+                error!(time; "CompactBestTip: Failed to reconstruct compact block");
+                None
+            }
+        };
+        let p2p_state = state_context.get_substate_mut()?;
+        let Some(Self::Ready { local, .. }) = p2p_state
+            .get_ready_peer_mut(&peer_id)
+            .map(|p| &mut p.channels.best_tip)
+        else {
+            return Ok(());
+        };
+        let Some(best_tip) = best_tip else {
+            *local = BestTipPropagationState::Requested { time };
+            p2p_state.compact_block_stats.blocks_invalid += 1;
+            let dispatcher = state_context.into_dispatcher();
+            Self::full_request_send(dispatcher, peer_id);
+            return Ok(());
+        };
+        *local = BestTipPropagationState::CompactReconstructed { time, best_tip };
+
+        let (dispatcher, state) = state_context.into_dispatcher_and_state();
+        let p2p_state: &P2pState = state.substate()?;
+        match &p2p_state
+            .callbacks
+            .on_p2p_channels_best_tip_compact_reconstructed
+        {
+            Some(callback) => dispatcher.push_callback(callback.clone(), peer_id),
+            // body can't be verified, so don't trust the reconstructed one.
+            None => dispatcher.push(P2pChannelsBestTipAction::CompactVerifyError { peer_id }),
+        }
+        Ok(())
+    }
+
+    fn full_request_send<Action, State>(dispatcher: &mut Dispatcher<Action, State>, peer_id: PeerId)
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+            peer_id,
+            msg_id: MsgId::first(),
+            msg: ChannelMsg::BestTipPropagation(BestTipPropagationChannelMsg::GetFull),
+        });
+    }
 }
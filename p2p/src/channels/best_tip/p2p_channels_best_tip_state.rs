@@ -1,6 +1,8 @@
 use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
+use super::{CompactBlock, CompactBlockEntries};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pChannelsBestTipState {
     Disabled,
@@ -24,13 +26,127 @@ pub enum P2pChannelsBestTipState {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum BestTipPropagationState {
-    WaitingForRequest { time: redux::Timestamp },
-    Requested { time: redux::Timestamp },
-    Responded { time: redux::Timestamp },
+    WaitingForRequest {
+        time: redux::Timestamp,
+    },
+    Requested {
+        time: redux::Timestamp,
+    },
+    Responded {
+        time: redux::Timestamp,
+    },
+    /// Compact best tip received, resolving its entries from the pools.
+    CompactReceived {
+        time: redux::Timestamp,
+        block: Box<CompactBlock>,
+    },
+    /// Requested entries of the compact best tip missing from the pools.
+    CompactMissing {
+        time: redux::Timestamp,
+        block: Box<CompactBlock>,
+        entries: CompactBlockEntries,
+    },
+    /// Compact best tip reconstructed, verifying its body against the
+    /// `body_reference` in the header.
+    CompactReconstructed {
+        time: redux::Timestamp,
+        best_tip: ArcBlockWithHash,
+    },
 }
 
 impl P2pChannelsBestTipState {
     pub fn is_ready(&self) -> bool {
         matches!(self, Self::Ready { .. })
     }
+
+    /// Whether the `best_tip` can be sent as the response to the peer.
+    pub fn can_send_response(&self, best_tip: &ArcBlockWithHash) -> bool {
+        match self {
+            Self::Ready {
+                remote: BestTipPropagationState::Requested { .. },
+                last_sent,
+                ..
+            } => last_sent
+                .as_ref()
+                .is_none_or(|sent| sent.hash != best_tip.hash),
+            _ => false,
+        }
+    }
+
+    /// Response expired in the send queue, so the peer is still waiting
+    /// for it. Returns `false` if there is no response sent.
+    pub fn response_expired(&mut self, time: redux::Timestamp) -> bool {
+        let Self::Ready {
+            remote, last_sent, ..
+        } = self
+        else {
+            return false;
+        };
+        if !matches!(remote, BestTipPropagationState::Responded { .. }) {
+            return false;
+        }
+        // Next best tip is sent as the response, once we have it.
+        *remote = BestTipPropagationState::Requested { time };
+        *last_sent = None;
+        true
+    }
+
+    /// Compact best tip, which entries need to be resolved from the pools.
+    pub fn compact_received(&self) -> Option<&CompactBlock> {
+        match self {
+            Self::Ready {
+                local: BestTipPropagationState::CompactReceived { block, .. },
+                ..
+            } => Some(block),
+            _ => None,
+        }
+    }
+
+    /// Reconstructed compact best tip, which body needs to be verified.
+    pub fn compact_reconstructed(&self) -> Option<&ArcBlockWithHash> {
+        match self {
+            Self::Ready {
+                local: BestTipPropagationState::CompactReconstructed { best_tip, .. },
+                ..
+            } => Some(best_tip),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openmina_core::block::fixtures;
+
+    use super::*;
+
+    #[test]
+    fn test_best_tip_response_expired() {
+        let time = redux::Timestamp::ZERO;
+        let best_tip = fixtures::best_tip_with_hash();
+        let mut state = P2pChannelsBestTipState::Ready {
+            time,
+            local: BestTipPropagationState::WaitingForRequest { time },
+            remote: BestTipPropagationState::Responded { time },
+            last_sent: Some(best_tip.clone()),
+            last_received: None,
+        };
+        assert!(!state.can_send_response(&best_tip));
+
+        // Expired response is sent again, even if the best tip didn't change.
+        assert!(state.response_expired(time));
+        assert!(matches!(
+            state,
+            P2pChannelsBestTipState::Ready {
+                remote: BestTipPropagationState::Requested { .. },
+                last_sent: None,
+                ..
+            }
+        ));
+        assert!(state.can_send_response(&best_tip));
+
+        // Nothing is waiting in the send queue anymore.
+        assert!(!state.response_expired(time));
+        assert!(!P2pChannelsBestTipState::Enabled.response_expired(time));
+    }
 }
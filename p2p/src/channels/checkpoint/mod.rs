@@ -0,0 +1,44 @@
+mod p2p_channels_checkpoint_state;
+pub use p2p_channels_checkpoint_state::*;
+
+mod p2p_channels_checkpoint_actions;
+pub use p2p_channels_checkpoint_actions::*;
+
+mod p2p_channels_checkpoint_reducer;
+
+use binprot_derive::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::{
+    number::UInt32,
+    v2::{MinaBaseSignatureStableV1, NonZeroCurvePoint, StateHash},
+};
+use serde::{Deserialize, Serialize};
+
+/// Statement of the `signer`, that the block `state_hash` at the `height`
+/// is part of its canonical chain.
+///
+/// Signatures are only checked by the node, the channel just carries them.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SignedCheckpoint {
+    pub state_hash: StateHash,
+    pub height: UInt32,
+    pub signer: NonZeroCurvePoint,
+    pub signature: MinaBaseSignatureStableV1,
+}
+
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub enum CheckpointPropagationChannelMsg {
+    /// Request next checkpoints upto the `limit`.
+    ///
+    /// - Must not be sent until peer sends `WillSend` message for the
+    ///   previous request and until peer has fulfilled it.
+    GetNext { limit: u8 },
+    /// Amount of checkpoints which will proceed this message.
+    ///
+    /// - Can only be sent, if peer has sent `GetNext` and we haven't
+    ///   responded with `WillSend` yet.
+    /// - Can't be bigger than limit set by `GetNext`.
+    /// - Amount of promised checkpoints must be delivered.
+    WillSend { count: u8 },
+    /// Signed checkpoint, see [`SignedCheckpoint`].
+    Checkpoint(SignedCheckpoint),
+}
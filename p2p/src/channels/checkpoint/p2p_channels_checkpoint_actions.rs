@@ -0,0 +1,176 @@
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::{P2pState, PeerId};
+
+use super::{CheckpointPropagationState, P2pChannelsCheckpointState, SignedCheckpoint};
+
+pub type P2pChannelsCheckpointActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a P2pChannelsCheckpointAction>;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ActionEvent)]
+#[action_event(fields(display(peer_id)))]
+pub enum P2pChannelsCheckpointAction {
+    Init {
+        peer_id: PeerId,
+    },
+    Pending {
+        peer_id: PeerId,
+    },
+    Ready {
+        peer_id: PeerId,
+    },
+    RequestSend {
+        peer_id: PeerId,
+        limit: u8,
+    },
+    PromiseReceived {
+        peer_id: PeerId,
+        promised_count: u8,
+    },
+    Received {
+        peer_id: PeerId,
+        checkpoint: Box<SignedCheckpoint>,
+    },
+    RequestReceived {
+        peer_id: PeerId,
+        limit: u8,
+    },
+    ResponseSend {
+        peer_id: PeerId,
+        checkpoints: Vec<SignedCheckpoint>,
+        first_index: u64,
+        last_index: u64,
+    },
+}
+
+impl P2pChannelsCheckpointAction {
+    pub fn peer_id(&self) -> &PeerId {
+        match self {
+            Self::Init { peer_id }
+            | Self::Pending { peer_id }
+            | Self::Ready { peer_id }
+            | Self::RequestSend { peer_id, .. }
+            | Self::PromiseReceived { peer_id, .. }
+            | Self::Received { peer_id, .. }
+            | Self::RequestReceived { peer_id, .. }
+            | Self::ResponseSend { peer_id, .. } => peer_id,
+        }
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsCheckpointAction {
+    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+        match self {
+            P2pChannelsCheckpointAction::Init { peer_id } => {
+                state.get_ready_peer(peer_id).is_some_and(|p| {
+                    matches!(&p.channels.checkpoint, P2pChannelsCheckpointState::Enabled)
+                })
+            }
+            P2pChannelsCheckpointAction::Pending { peer_id } => {
+                state.get_ready_peer(peer_id).is_some_and(|p| {
+                    matches!(
+                        &p.channels.checkpoint,
+                        P2pChannelsCheckpointState::Init { .. }
+                    )
+                })
+            }
+            P2pChannelsCheckpointAction::Ready { peer_id } => {
+                state.get_ready_peer(peer_id).is_some_and(|p| {
+                    matches!(
+                        &p.channels.checkpoint,
+                        P2pChannelsCheckpointState::Pending { .. }
+                    )
+                })
+            }
+            P2pChannelsCheckpointAction::RequestSend { peer_id, .. } => {
+                state.get_ready_peer(peer_id).is_some_and(|p| {
+                    matches!(
+                        &p.channels.checkpoint,
+                        P2pChannelsCheckpointState::Ready {
+                            local: CheckpointPropagationState::WaitingForRequest { .. }
+                                | CheckpointPropagationState::Responded { .. },
+                            ..
+                        }
+                    )
+                })
+            }
+            P2pChannelsCheckpointAction::PromiseReceived {
+                peer_id,
+                promised_count,
+            } => state.get_ready_peer(peer_id).is_some_and(|p| {
+                matches!(
+                    &p.channels.checkpoint,
+                    P2pChannelsCheckpointState::Ready {
+                        local: CheckpointPropagationState::Requested {
+                            requested_limit, ..
+                        },
+                        ..
+                    } if *promised_count > 0 && promised_count <= requested_limit
+                )
+            }),
+            P2pChannelsCheckpointAction::Received { peer_id, .. } => {
+                state.get_ready_peer(peer_id).is_some_and(|p| {
+                    matches!(
+                        &p.channels.checkpoint,
+                        P2pChannelsCheckpointState::Ready {
+                            local: CheckpointPropagationState::Responding { .. },
+                            ..
+                        }
+                    )
+                })
+            }
+            P2pChannelsCheckpointAction::RequestReceived { peer_id, limit } => {
+                *limit > 0
+                    && state.get_ready_peer(peer_id).is_some_and(|p| {
+                        matches!(
+                            &p.channels.checkpoint,
+                            P2pChannelsCheckpointState::Ready {
+                                remote: CheckpointPropagationState::WaitingForRequest { .. }
+                                    | CheckpointPropagationState::Responded { .. },
+                                ..
+                            }
+                        )
+                    })
+            }
+            P2pChannelsCheckpointAction::ResponseSend {
+                peer_id,
+                checkpoints,
+                first_index,
+                last_index,
+            } => {
+                !checkpoints.is_empty()
+                    && first_index <= last_index
+                    && state
+                        .get_ready_peer(peer_id)
+                        .is_some_and(|p| match &p.channels.checkpoint {
+                            P2pChannelsCheckpointState::Ready {
+                                remote,
+                                next_send_index,
+                                ..
+                            } => {
+                                if first_index < next_send_index {
+                                    return false;
+                                }
+                                match remote {
+                                    CheckpointPropagationState::Requested {
+                                        requested_limit,
+                                        ..
+                                    } => checkpoints.len() <= *requested_limit as usize,
+                                    _ => false,
+                                }
+                            }
+                            _ => false,
+                        })
+            }
+        }
+    }
+}
+
+use crate::channels::P2pChannelsAction;
+
+impl From<P2pChannelsCheckpointAction> for crate::P2pAction {
+    fn from(action: P2pChannelsCheckpointAction) -> Self {
+        Self::Channels(P2pChannelsAction::Checkpoint(action))
+    }
+}
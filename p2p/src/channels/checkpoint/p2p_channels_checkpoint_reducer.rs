@@ -0,0 +1,224 @@
+use openmina_core::{bug_condition, Substate};
+use redux::ActionWithMeta;
+
+use crate::{
+    channels::{ChannelId, MsgId, P2pChannelsEffectfulAction},
+    P2pState,
+};
+
+use super::{
+    CheckpointPropagationChannelMsg, CheckpointPropagationState, P2pChannelsCheckpointAction,
+    P2pChannelsCheckpointState,
+};
+
+const LIMIT: u8 = 16;
+
+impl P2pChannelsCheckpointState {
+    /// Substate is accessed
+    pub fn reducer<Action, State>(
+        mut state_context: Substate<Action, State, P2pState>,
+        action: ActionWithMeta<P2pChannelsCheckpointAction>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let (action, meta) = action.split();
+        let p2p_state = state_context.get_substate_mut()?;
+        let peer_id = *action.peer_id();
+        let checkpoint_state = &mut p2p_state
+            .get_ready_peer_mut(&peer_id)
+            .ok_or_else(|| format!("Peer state not found for: {action:?}"))?
+            .channels
+            .checkpoint;
+
+        match action {
+            P2pChannelsCheckpointAction::Init { .. } => {
+                *checkpoint_state = Self::Init { time: meta.time() };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pChannelsEffectfulAction::InitChannel {
+                    peer_id,
+                    id: ChannelId::CheckpointPropagation,
+                    on_success: redux::callback!(
+                        on_checkpoint_channel_init(peer_id: crate::PeerId) -> crate::P2pAction {
+                            P2pChannelsCheckpointAction::Pending { peer_id }
+                        }
+                    ),
+                });
+                Ok(())
+            }
+            P2pChannelsCheckpointAction::Pending { .. } => {
+                *checkpoint_state = Self::Pending { time: meta.time() };
+                Ok(())
+            }
+            P2pChannelsCheckpointAction::Ready { .. } => {
+                *checkpoint_state = Self::Ready {
+                    time: meta.time(),
+                    local: CheckpointPropagationState::WaitingForRequest { time: meta.time() },
+                    remote: CheckpointPropagationState::WaitingForRequest { time: meta.time() },
+                    next_send_index: 0,
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pChannelsCheckpointAction::RequestSend {
+                    peer_id,
+                    limit: LIMIT,
+                });
+                Ok(())
+            }
+            P2pChannelsCheckpointAction::RequestSend { limit, .. } => {
+                let Self::Ready { local, .. } = checkpoint_state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsCheckpointAction::RequestSend`, state: {:?}",
+                        checkpoint_state
+                    );
+                    return Ok(());
+                };
+                *local = CheckpointPropagationState::Requested {
+                    time: meta.time(),
+                    requested_limit: limit,
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+                    peer_id,
+                    msg_id: MsgId::first(),
+                    msg: CheckpointPropagationChannelMsg::GetNext { limit }.into(),
+                });
+                Ok(())
+            }
+            P2pChannelsCheckpointAction::PromiseReceived { promised_count, .. } => {
+                let Self::Ready { local, .. } = checkpoint_state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsCheckpointAction::PromiseReceived`, state: {:?}",
+                        checkpoint_state
+                    );
+                    return Ok(());
+                };
+                let CheckpointPropagationState::Requested {
+                    requested_limit, ..
+                } = &local
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsCheckpointAction::PromiseReceived`, state: {:?}",
+                        checkpoint_state
+                    );
+                    return Ok(());
+                };
+
+                *local = CheckpointPropagationState::Responding {
+                    time: meta.time(),
+                    requested_limit: *requested_limit,
+                    promised_count,
+                    current_count: 0,
+                };
+                Ok(())
+            }
+            P2pChannelsCheckpointAction::Received { checkpoint, .. } => {
+                let Self::Ready { local, .. } = checkpoint_state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsCheckpointAction::Received`, state: {:?}",
+                        checkpoint_state
+                    );
+                    return Ok(());
+                };
+                let CheckpointPropagationState::Responding {
+                    promised_count,
+                    current_count,
+                    ..
+                } = local
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsCheckpointAction::Received`, state: {:?}",
+                        checkpoint_state
+                    );
+                    return Ok(());
+                };
+
+                *current_count = current_count.saturating_add(1);
+
+                if current_count >= promised_count {
+                    *local = CheckpointPropagationState::Responded {
+                        time: meta.time(),
+                        count: *current_count,
+                    };
+                }
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                dispatcher.push(P2pChannelsCheckpointAction::RequestSend {
+                    peer_id,
+                    limit: LIMIT,
+                });
+
+                if let Some(callback) = &p2p_state.callbacks.on_p2p_channels_checkpoint_received {
+                    dispatcher.push_callback(callback.clone(), (peer_id, checkpoint));
+                }
+                Ok(())
+            }
+            P2pChannelsCheckpointAction::RequestReceived { limit, .. } => {
+                let Self::Ready { remote, .. } = checkpoint_state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsCheckpointAction::RequestReceived`, state: {:?}",
+                        checkpoint_state
+                    );
+                    return Ok(());
+                };
+                *remote = CheckpointPropagationState::Requested {
+                    time: meta.time(),
+                    requested_limit: limit,
+                };
+                Ok(())
+            }
+            P2pChannelsCheckpointAction::ResponseSend {
+                last_index,
+                checkpoints,
+                ..
+            } => {
+                let Self::Ready {
+                    remote,
+                    next_send_index,
+                    ..
+                } = checkpoint_state
+                else {
+                    bug_condition!(
+                    "Invalid state for `P2pChannelsCheckpointAction::ResponseSend`, state: {:?}",
+                    checkpoint_state
+                );
+                    return Ok(());
+                };
+                *next_send_index = last_index.saturating_add(1);
+
+                let count = checkpoints.len() as u8;
+                if count == 0 {
+                    return Ok(());
+                }
+
+                *remote = CheckpointPropagationState::Responded {
+                    time: meta.time(),
+                    count,
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                let msg = CheckpointPropagationChannelMsg::WillSend { count }.into();
+                dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+                    peer_id,
+                    msg_id: MsgId::first(),
+                    msg,
+                });
+
+                for checkpoint in checkpoints {
+                    let msg = CheckpointPropagationChannelMsg::Checkpoint(checkpoint).into();
+                    dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+                        peer_id,
+                        msg_id: MsgId::first(),
+                        msg,
+                    });
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod best_tip;
+pub mod checkpoint;
 pub mod rpc;
 pub mod signaling;
 pub mod snark;
@@ -17,8 +18,18 @@ mod p2p_channels_reducer;
 mod p2p_channels_service;
 pub use p2p_channels_service::*;
 
+/// Retransmissions of a message on the lossy gossip channels, before it is
+/// given up on.
+pub const GOSSIP_MAX_RETRANSMITS: u16 = 2;
+
+/// Time after which the unfulfilled request on the lossy gossip channel is
+/// considered lost, so that the next one can be sent.
+pub const GOSSIP_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
 mod p2p_channels_effectful_effects;
 
+use std::time::Duration;
+
 use binprot::{BinProtRead, BinProtWrite};
 use binprot_derive::{BinProtRead, BinProtWrite};
 use derive_more::From;
@@ -28,12 +39,17 @@ use signaling::exchange::SignalingExchangeChannelMsg;
 use strum_macros::EnumIter;
 
 use self::best_tip::BestTipPropagationChannelMsg;
+use self::checkpoint::CheckpointPropagationChannelMsg;
 use self::rpc::RpcChannelMsg;
 use self::snark::SnarkPropagationChannelMsg;
 use self::snark_job_commitment::SnarkJobCommitmentPropagationChannelMsg;
 use self::streaming_rpc::StreamingRpcChannelMsg;
 use self::transaction::TransactionPropagationChannelMsg;
 
+/// How long the best tip may wait in the send queue of a slow peer,
+/// before it's considered stale and dropped.
+pub const BEST_TIP_SEND_TTL: Duration = Duration::from_secs(30);
+
 #[derive(Serialize, Deserialize, EnumIter, Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Copy)]
 #[repr(u8)]
 pub enum ChannelId {
@@ -45,6 +61,7 @@ pub enum ChannelId {
     SnarkJobCommitmentPropagation = 6,
     Rpc = 7,
     StreamingRpc = 8,
+    CheckpointPropagation = 9,
 }
 
 impl ChannelId {
@@ -68,38 +85,88 @@ impl ChannelId {
             Self::SnarkJobCommitmentPropagation => "snark_job_commitment/propagation",
             Self::Rpc => "rpc",
             Self::StreamingRpc => "rpc/streaming",
+            Self::CheckpointPropagation => "checkpoint/propagation",
         }
     }
 
     pub fn supported_by_libp2p(self) -> bool {
         match self {
-            Self::SignalingDiscovery => false,
-            Self::SignalingExchange => false,
+            // Relayed as rpc queries, see `signaling::libp2p`.
+            Self::SignalingDiscovery => true,
+            Self::SignalingExchange => true,
             Self::BestTipPropagation => true,
             Self::TransactionPropagation => true,
             Self::SnarkPropagation => true,
             Self::SnarkJobCommitmentPropagation => false,
             Self::Rpc => true,
             Self::StreamingRpc => false,
+            Self::CheckpointPropagation => false,
         }
     }
 
+    /// Limit for the encoded message size, as configured for the network.
     pub fn max_msg_size(self) -> usize {
+        let limits = openmina_core::NetworkConfig::global().p2p_msg_size_limits;
+        match self {
+            Self::SignalingDiscovery => limits.signaling_discovery,
+            Self::SignalingExchange => limits.signaling_exchange,
+            Self::BestTipPropagation => limits.best_tip_propagation,
+            Self::TransactionPropagation => limits.transaction_propagation,
+            Self::SnarkPropagation => limits.snark_propagation,
+            Self::SnarkJobCommitmentPropagation => limits.snark_job_commitment_propagation,
+            Self::Rpc => limits.rpc,
+            Self::StreamingRpc => limits.streaming_rpc,
+            Self::CheckpointPropagation => limits.checkpoint_propagation,
+        }
+    }
+
+    /// Whether messages on the webrtc datachannel are delivered in order.
+    ///
+    /// Datachannels are negotiated out-of-band, so both sides derive the
+    /// delivery mode from the channel id and `lossy_gossip`, which is
+    /// whether both of them announced [`crate::webrtc::P2pCapabilities::lossy_gossip`].
+    pub fn is_ordered(self, lossy_gossip: bool) -> bool {
+        match self {
+            Self::SignalingDiscovery => true,
+            Self::SignalingExchange => true,
+            Self::BestTipPropagation => true,
+            Self::TransactionPropagation => !lossy_gossip,
+            Self::SnarkPropagation => !lossy_gossip,
+            Self::SnarkJobCommitmentPropagation => true,
+            Self::Rpc => true,
+            Self::StreamingRpc => true,
+            Self::CheckpointPropagation => true,
+        }
+    }
+
+    /// Maximum number of retransmissions of a message on the webrtc
+    /// datachannel before it is dropped. `None` means reliable delivery.
+    ///
+    /// Messages of partially reliable channels must fit in a single chunk,
+    /// so that a lost chunk doesn't corrupt the following messages.
+    pub fn max_retransmits(self, lossy_gossip: bool) -> Option<u16> {
         match self {
-            // TODO(binier): measure signaling message sizes
-            Self::SignalingDiscovery => 16 * 1024, // 16KB
-            Self::SignalingExchange => 16 * 1024,  // 16KB
-            // TODO(binier): reduce this value once we change message for best tip
-            // propagation to just propagating consensus state with block hash.
-            Self::BestTipPropagation => 32 * 1024 * 1024, // 32MB
-            Self::TransactionPropagation => 1024,         // 1KB - just transaction info.
-            Self::SnarkPropagation => 1024,               // 1KB - just snark info.
-            Self::SnarkJobCommitmentPropagation => 2 * 1024, // 2KB,
-            Self::Rpc => 256 * 1024 * 1024,               // 256MB,
-            Self::StreamingRpc => 16 * 1024 * 1024,       // 16MB,
+            Self::SignalingDiscovery => None,
+            Self::SignalingExchange => None,
+            Self::BestTipPropagation => None,
+            // Requestor sends a new request, if the previous one wasn't
+            // fulfilled within `GOSSIP_REQUEST_TIMEOUT`.
+            Self::TransactionPropagation | Self::SnarkPropagation => {
+                lossy_gossip.then_some(GOSSIP_MAX_RETRANSMITS)
+            }
+            // Requestor only sends the next request once the previous one
+            // is fulfilled, so a lost message would stall the channel.
+            Self::SnarkJobCommitmentPropagation => None,
+            Self::Rpc => None,
+            Self::StreamingRpc => None,
+            Self::CheckpointPropagation => None,
         }
     }
 
+    pub fn is_reliable(self, lossy_gossip: bool) -> bool {
+        self.is_ordered(lossy_gossip) && self.max_retransmits(lossy_gossip).is_none()
+    }
+
     pub fn iter_all() -> impl Iterator<Item = ChannelId> {
         <Self as strum::IntoEnumIterator>::iter()
     }
@@ -138,6 +205,7 @@ pub enum ChannelMsg {
     SnarkJobCommitmentPropagation(SnarkJobCommitmentPropagationChannelMsg),
     Rpc(RpcChannelMsg),
     StreamingRpc(StreamingRpcChannelMsg),
+    CheckpointPropagation(CheckpointPropagationChannelMsg),
 }
 
 impl ChannelMsg {
@@ -151,6 +219,23 @@ impl ChannelMsg {
             Self::SnarkJobCommitmentPropagation(_) => ChannelId::SnarkJobCommitmentPropagation,
             Self::Rpc(_) => ChannelId::Rpc,
             Self::StreamingRpc(_) => ChannelId::StreamingRpc,
+            Self::CheckpointPropagation(_) => ChannelId::CheckpointPropagation,
+        }
+    }
+
+    /// Max time the message may wait in the send queue of the channel.
+    /// If it expires before it's sent, it's dropped and reported with
+    /// [`crate::P2pChannelSendError::Expired`].
+    ///
+    /// `None` means the message is always sent, which is required for
+    /// messages the peer waits for, unless the expiry is handled.
+    pub fn send_ttl(&self) -> Option<Duration> {
+        match self {
+            Self::BestTipPropagation(
+                BestTipPropagationChannelMsg::BestTip(_)
+                | BestTipPropagationChannelMsg::CompactBestTip(_),
+            ) => Some(BEST_TIP_SEND_TTL),
+            _ => None,
         }
     }
 
@@ -167,6 +252,7 @@ impl ChannelMsg {
             Self::SnarkJobCommitmentPropagation(v) => v.binprot_write(w),
             Self::Rpc(v) => v.binprot_write(w),
             Self::StreamingRpc(v) => v.binprot_write(w),
+            Self::CheckpointPropagation(v) => v.binprot_write(w),
         }
     }
 
@@ -196,6 +282,9 @@ impl ChannelMsg {
             }
             ChannelId::Rpc => RpcChannelMsg::binprot_read(r).map(|v| v.into()),
             ChannelId::StreamingRpc => StreamingRpcChannelMsg::binprot_read(r).map(|v| v.into()),
+            ChannelId::CheckpointPropagation => {
+                CheckpointPropagationChannelMsg::binprot_read(r).map(|v| v.into())
+            }
         }
     }
 }
@@ -210,9 +299,48 @@ impl crate::P2pState {
         State: crate::P2pStateTrait,
         Action: crate::P2pActionTrait<State>,
     {
+        let Some(peer) = self.peers.get(&peer_id) else {
+            return;
+        };
+        let capabilities = peer
+            .status
+            .as_ready()
+            .and_then(|ready| ready.capabilities.as_ref());
+        let is_libp2p = peer.is_libp2p;
+        // Before the channels are opened, so that all messages are compressed.
+        if peer
+            .status
+            .as_ready()
+            .is_some_and(|ready| ready.channels.compression)
+        {
+            dispatcher.push(P2pChannelsEffectfulAction::CompressionEnable { peer_id });
+        }
+        // Before the channels are opened, as it decides their delivery mode.
+        if peer
+            .status
+            .as_ready()
+            .is_some_and(|ready| ready.channels.lossy_gossip)
+        {
+            dispatcher.push(P2pChannelsEffectfulAction::LossyGossipEnable { peer_id });
+        }
+        // Opening a channel, which peer doesn't know about, gets us disconnected.
+        // Webrtc peers which didn't announce the capabilities only know the
+        // channels from before the capability exchange.
+        let supported = |id: &ChannelId| match capabilities {
+            Some(capabilities) => capabilities.supports_channel(*id),
+            None if is_libp2p => id.supported_by_libp2p(),
+            None => crate::webrtc::P2pCapabilities::legacy(0, false, false).supports_channel(*id),
+        };
+
         // Dispatches can be done without a loop, but inside we do
         // exhaustive matching so that we don't miss any channels.
-        for id in self.config.enabled_channels.iter().copied() {
+        for id in self
+            .config
+            .enabled_channels
+            .iter()
+            .copied()
+            .filter(supported)
+        {
             match id {
                 ChannelId::SignalingDiscovery => {
                     dispatcher.push(
@@ -244,6 +372,9 @@ impl crate::P2pState {
                 ChannelId::StreamingRpc => {
                     dispatcher.push(streaming_rpc::P2pChannelsStreamingRpcAction::Init { peer_id });
                 }
+                ChannelId::CheckpointPropagation => {
+                    dispatcher.push(checkpoint::P2pChannelsCheckpointAction::Init { peer_id });
+                }
             }
         }
     }
@@ -10,6 +10,7 @@ use crate::{
 
 use super::{
     best_tip::P2pChannelsBestTipAction,
+    checkpoint::P2pChannelsCheckpointAction,
     rpc::P2pChannelsRpcAction,
     signaling::{
         discovery::P2pChannelsSignalingDiscoveryAction,
@@ -33,6 +34,7 @@ pub enum P2pChannelsAction {
     SnarkJobCommitment(P2pChannelsSnarkJobCommitmentAction),
     Rpc(P2pChannelsRpcAction),
     StreamingRpc(P2pChannelsStreamingRpcAction),
+    Checkpoint(P2pChannelsCheckpointAction),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, openmina_core::ActionEvent)]
@@ -47,6 +49,12 @@ pub enum P2pChannelsEffectfulAction {
         msg_id: MsgId,
         msg: ChannelMsg,
     },
+    CompressionEnable {
+        peer_id: PeerId,
+    },
+    LossyGossipEnable {
+        peer_id: PeerId,
+    },
     SignalingDiscoveryAnswerDecrypt {
         peer_id: PeerId,
         pub_key: PublicKey,
@@ -81,6 +89,7 @@ impl P2pChannelsAction {
             Self::SnarkJobCommitment(v) => Some(v.peer_id()),
             Self::Rpc(v) => Some(v.peer_id()),
             Self::StreamingRpc(v) => Some(v.peer_id()),
+            Self::Checkpoint(v) => Some(v.peer_id()),
         }
     }
 }
@@ -97,6 +106,7 @@ impl redux::EnablingCondition<crate::P2pState> for P2pChannelsAction {
             P2pChannelsAction::SnarkJobCommitment(a) => a.is_enabled(state, time),
             P2pChannelsAction::Rpc(a) => a.is_enabled(state, time),
             P2pChannelsAction::StreamingRpc(a) => a.is_enabled(state, time),
+            P2pChannelsAction::Checkpoint(a) => a.is_enabled(state, time),
         }
     }
 }
@@ -1,8 +1,13 @@
 use openmina_core::bug_condition;
 use redux::ActionMeta;
 
-use crate::webrtc::{Offer, P2pConnectionResponse};
+use crate::{
+    webrtc::{Offer, P2pConnectionResponse},
+    PeerId,
+};
 
+#[cfg(feature = "p2p-libp2p")]
+use super::signaling::libp2p::SignalingRelayV1;
 use super::{
     signaling::{
         discovery::{P2pChannelsSignalingDiscoveryAction, SignalingDiscoveryChannelMsg},
@@ -23,7 +28,9 @@ impl P2pChannelsEffectfulAction {
                 id,
                 on_success,
             } => {
-                store.service().channel_open(peer_id, id);
+                if let Some(correlation_id) = store.service().channel_open(peer_id, id) {
+                    correlation_id.log_issued(meta.time(), peer_id, "P2pChannelsInitChannel");
+                }
                 store.dispatch_callback(on_success, peer_id);
             }
             P2pChannelsEffectfulAction::MessageSend {
@@ -31,7 +38,13 @@ impl P2pChannelsEffectfulAction {
                 msg_id,
                 msg,
             } => {
-                store.service().channel_send(peer_id, msg_id, msg);
+                message_send(store, peer_id, msg_id, msg);
+            }
+            P2pChannelsEffectfulAction::CompressionEnable { peer_id } => {
+                store.service().channels_compression_enable(peer_id);
+            }
+            P2pChannelsEffectfulAction::LossyGossipEnable { peer_id } => {
+                store.service().channels_lossy_gossip_enable(peer_id);
             }
             P2pChannelsEffectfulAction::SignalingDiscoveryAnswerDecrypt {
                 peer_id,
@@ -71,9 +84,7 @@ impl P2pChannelsEffectfulAction {
                 }
                 Ok(offer) => {
                     let message = SignalingDiscoveryChannelMsg::DiscoveredAccept(offer);
-                    store
-                        .service()
-                        .channel_send(peer_id, super::MsgId::first(), message.into());
+                    message_send(store, peer_id, MsgId::first(), message.into());
                 }
             },
             P2pChannelsEffectfulAction::SignalingExchangeOfferDecrypt {
@@ -109,11 +120,7 @@ impl P2pChannelsEffectfulAction {
             } => {
                 let Some(answer) = answer else {
                     let message = SignalingExchangeChannelMsg::Answer(None);
-                    store.service().channel_send(
-                        peer_id,
-                        MsgId::first(),
-                        ChannelMsg::SignalingExchange(message),
-                    );
+                    message_send(store, peer_id, MsgId::first(), message.into());
                     return;
                 };
 
@@ -121,14 +128,30 @@ impl P2pChannelsEffectfulAction {
                     Err(_) => bug_condition!("Failed to encrypt webrtc answer. Shouldn't happen since we managed to decrypt sent offer."),
                     Ok(answer) => {
                         let message = SignalingExchangeChannelMsg::Answer(Some(answer));
-                        store.service().channel_send(
-                            peer_id,
-                            MsgId::first(),
-                            ChannelMsg::SignalingExchange(message),
-                        );
+                        message_send(store, peer_id, MsgId::first(), message.into());
                     }
                 }
             }
         }
     }
 }
+
+/// Sends the message over the webrtc channel. Signaling messages are
+/// also relayed as a libp2p rpc query, which is only enabled if the peer
+/// is connected over libp2p, while the service ignores such peers.
+fn message_send<Store, S>(store: &mut Store, peer_id: PeerId, msg_id: MsgId, msg: ChannelMsg)
+where
+    Store: crate::P2pStore<S>,
+    Store::Service: P2pChannelsService,
+{
+    #[cfg(feature = "p2p-libp2p")]
+    if SignalingRelayV1::is_relayed(&msg) {
+        let (query, data) = SignalingRelayV1::query(&msg);
+        store.dispatch(crate::P2pNetworkRpcAction::OutgoingQuery {
+            peer_id,
+            query,
+            data,
+        });
+    }
+    store.service().channel_send(peer_id, msg_id, msg);
+}
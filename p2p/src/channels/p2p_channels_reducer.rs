@@ -1,5 +1,8 @@
 use super::{
     best_tip::{BestTipPropagationChannelMsg, P2pChannelsBestTipAction, P2pChannelsBestTipState},
+    checkpoint::{
+        CheckpointPropagationChannelMsg, P2pChannelsCheckpointAction, P2pChannelsCheckpointState,
+    },
     rpc::{P2pChannelsRpcAction, P2pChannelsRpcState, RpcChannelMsg},
     signaling::{
         discovery::{
@@ -33,7 +36,7 @@ use redux::{ActionWithMeta, Dispatcher};
 
 impl P2pChannelsState {
     pub fn reducer<Action, State>(
-        state_context: Substate<Action, State, P2pState>,
+        mut state_context: Substate<Action, State, P2pState>,
         action: ActionWithMeta<P2pChannelsAction>,
     ) -> Result<(), String>
     where
@@ -44,6 +47,16 @@ impl P2pChannelsState {
 
         match action {
             P2pChannelsAction::MessageReceived(action) => {
+                let p2p_state = state_context.get_substate_mut()?;
+                if let Some(peer) = p2p_state.get_ready_peer_mut(&action.peer_id) {
+                    let received = peer
+                        .channels
+                        .received
+                        .entry(action.message.channel_id())
+                        .or_default();
+                    *received = received.saturating_add(1);
+                }
+
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 Self::dispatch_message(meta.with_action(action), dispatcher, state)
             }
@@ -71,6 +84,9 @@ impl P2pChannelsState {
             P2pChannelsAction::StreamingRpc(action) => {
                 P2pChannelsStreamingRpcState::reducer(state_context, meta.with_action(action))
             }
+            P2pChannelsAction::Checkpoint(action) => {
+                P2pChannelsCheckpointState::reducer(state_context, meta.with_action(action))
+            }
         }
     }
 
@@ -88,6 +104,10 @@ impl P2pChannelsState {
 
         let peer_id = action.peer_id;
         let chain_id = action.message.channel_id();
+        let p2p_state: &P2pState = state.substate()?;
+        let lossy_gossip = p2p_state
+            .get_ready_peer(&peer_id)
+            .is_some_and(|peer| peer.channels.lossy_gossip);
 
         let mut is_enabled = |action: Action| dispatcher.push_if_enabled(action, state, time);
 
@@ -156,6 +176,18 @@ impl P2pChannelsState {
                         }
                     }
                 }
+                BestTipPropagationChannelMsg::CompactBestTip(block) => {
+                    is_enabled(P2pChannelsBestTipAction::CompactReceived { peer_id, block }.into())
+                }
+                BestTipPropagationChannelMsg::GetMissing(missing) => is_enabled(
+                    P2pChannelsBestTipAction::MissingRequestReceived { peer_id, missing }.into(),
+                ),
+                BestTipPropagationChannelMsg::Missing(fill) => {
+                    is_enabled(P2pChannelsBestTipAction::MissingReceived { peer_id, fill }.into())
+                }
+                BestTipPropagationChannelMsg::GetFull => {
+                    is_enabled(P2pChannelsBestTipAction::FullRequestReceived { peer_id }.into())
+                }
             },
             ChannelMsg::TransactionPropagation(msg) => match msg {
                 TransactionPropagationChannelMsg::GetNext { limit } => is_enabled(
@@ -175,6 +207,12 @@ impl P2pChannelsState {
                     }
                     .into(),
                 ),
+                TransactionPropagationChannelMsg::Announce { hashes } => is_enabled(
+                    P2pChannelsTransactionAction::AnnounceReceived { peer_id, hashes }.into(),
+                ),
+                TransactionPropagationChannelMsg::GetByHash { hashes } => is_enabled(
+                    P2pChannelsTransactionAction::RequestByHashReceived { peer_id, hashes }.into(),
+                ),
             },
             ChannelMsg::SnarkPropagation(msg) => match msg {
                 SnarkPropagationChannelMsg::GetNext { limit } => {
@@ -194,6 +232,13 @@ impl P2pChannelsState {
                     }
                     .into(),
                 ),
+                SnarkPropagationChannelMsg::SnarkBatch(batch) => is_enabled(
+                    P2pChannelsSnarkAction::BatchReceived {
+                        peer_id,
+                        batch: Box::new(batch),
+                    }
+                    .into(),
+                ),
             },
             ChannelMsg::SnarkJobCommitmentPropagation(msg) => match msg {
                 SnarkJobCommitmentPropagationChannelMsg::GetNext { limit } => is_enabled(
@@ -263,9 +308,30 @@ impl P2pChannelsState {
                     ),
                 },
             },
+            ChannelMsg::CheckpointPropagation(msg) => match msg {
+                CheckpointPropagationChannelMsg::GetNext { limit } => is_enabled(
+                    P2pChannelsCheckpointAction::RequestReceived { peer_id, limit }.into(),
+                ),
+                CheckpointPropagationChannelMsg::WillSend { count } => is_enabled(
+                    P2pChannelsCheckpointAction::PromiseReceived {
+                        peer_id,
+                        promised_count: count,
+                    }
+                    .into(),
+                ),
+                CheckpointPropagationChannelMsg::Checkpoint(checkpoint) => is_enabled(
+                    P2pChannelsCheckpointAction::Received {
+                        peer_id,
+                        checkpoint: Box::new(checkpoint),
+                    }
+                    .into(),
+                ),
+            },
         };
 
-        if !was_expected {
+        // Lossy channels may still deliver messages of the exchange which
+        // was given up on after the timeout, these are just dropped.
+        if !was_expected && chain_id.is_reliable(lossy_gossip) {
             // dbg!(&action.message);
             let reason = P2pDisconnectionReason::P2pChannelMsgUnexpected(chain_id);
             dispatcher.push(P2pDisconnectionAction::Init { peer_id, reason });
@@ -1,13 +1,22 @@
 use crate::{
     identity::{EncryptableType, PublicKey},
-    PeerId,
+    P2pCorrelationId, PeerId,
 };
 
 use super::{ChannelId, ChannelMsg, MsgId};
 
 pub trait P2pChannelsService: redux::Service {
-    fn channel_open(&mut self, peer_id: PeerId, id: ChannelId);
+    /// Returns the correlation id of the command sent to the webrtc
+    /// peer, `None` for libp2p peers.
+    fn channel_open(&mut self, peer_id: PeerId, id: ChannelId) -> Option<P2pCorrelationId>;
     fn channel_send(&mut self, peer_id: PeerId, msg_id: MsgId, msg: ChannelMsg);
+    /// Compresses the messages sent to the webrtc peer from now on,
+    /// see [`crate::webrtc::P2pCapabilities::compression`].
+    fn channels_compression_enable(&mut self, peer_id: PeerId);
+    /// Opens the gossip channels of the webrtc peer as unordered and
+    /// partially reliable from now on, see
+    /// [`crate::webrtc::P2pCapabilities::lossy_gossip`].
+    fn channels_lossy_gossip_enable(&mut self, peer_id: PeerId);
     fn encrypt<T: EncryptableType>(
         &mut self,
         other_pk: &PublicKey,
@@ -1,9 +1,10 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 
 use super::{
     best_tip::P2pChannelsBestTipState,
+    checkpoint::P2pChannelsCheckpointState,
     rpc::{P2pChannelsRpcState, P2pRpcId},
     signaling::{
         discovery::P2pChannelsSignalingDiscoveryState, exchange::P2pChannelsSignalingExchangeState,
@@ -25,6 +26,34 @@ pub struct P2pChannelsState {
     pub snark_job_commitment: P2pChannelsSnarkJobCommitmentState,
     pub rpc: P2pChannelsRpcState,
     pub streaming_rpc: P2pChannelsStreamingRpcState,
+    #[serde(default)]
+    pub checkpoint: P2pChannelsCheckpointState,
+    /// Whether peer supports announcing transaction digests on the
+    /// transaction propagation channel. If not, we fall back to sending
+    /// transaction infos directly.
+    #[serde(default)]
+    pub transaction_digests: bool,
+    /// Whether peer supports compact blocks on the best tip propagation
+    /// channel. If not, we fall back to sending full blocks.
+    #[serde(default)]
+    pub compact_blocks: bool,
+    /// Whether peer supports snark batches on the snark propagation
+    /// channel. If not, we send snarks one by one.
+    #[serde(default)]
+    pub snark_batches: bool,
+    /// Whether the messages sent to the peer are compressed. Only if
+    /// both sides enabled the
+    /// [`crate::feature_flags::P2pFeature::Compression`].
+    #[serde(default)]
+    pub compression: bool,
+    /// Whether the transaction and snark propagation channels are
+    /// unordered and lossy. Only if both sides announced the
+    /// [`crate::webrtc::P2pCapabilities::lossy_gossip`].
+    #[serde(default)]
+    pub lossy_gossip: bool,
+    /// Number of the messages received from the peer, per channel.
+    #[serde(default)]
+    pub received: BTreeMap<ChannelId, u64>,
 
     pub(super) next_local_rpc_id: P2pRpcId,
 }
@@ -68,6 +97,16 @@ impl P2pChannelsState {
                 false => P2pChannelsStreamingRpcState::Disabled,
                 true => P2pChannelsStreamingRpcState::Enabled,
             },
+            checkpoint: match enabled_channels.contains(&ChannelId::CheckpointPropagation) {
+                false => P2pChannelsCheckpointState::Disabled,
+                true => P2pChannelsCheckpointState::Enabled,
+            },
+            transaction_digests: false,
+            compact_blocks: false,
+            snark_batches: false,
+            compression: false,
+            lossy_gossip: false,
+            received: Default::default(),
 
             next_local_rpc_id: 0,
         }
@@ -96,6 +135,7 @@ impl P2pChannelsState {
             ChannelId::SnarkJobCommitmentPropagation => self.snark_job_commitment.is_ready(),
             ChannelId::Rpc => self.rpc.is_ready(),
             ChannelId::StreamingRpc => self.rpc.is_ready(),
+            ChannelId::CheckpointPropagation => self.checkpoint.is_ready(),
         }
     }
 }
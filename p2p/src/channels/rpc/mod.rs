@@ -14,7 +14,7 @@ use mina_p2p_messages::{
     rpc_kernel::QueryID,
     v2::{
         LedgerHash, MerkleAddressBinableArgStableV1, MinaBasePendingCoinbaseStableV2,
-        MinaBaseStateBodyHashStableV1, MinaLedgerSyncLedgerAnswerStableV2,
+        MinaBaseProofStableV2, MinaBaseStateBodyHashStableV1, MinaLedgerSyncLedgerAnswerStableV2,
         MinaLedgerSyncLedgerQueryStableV1, MinaStateProtocolStateValueStableV2, StateHash,
         TransactionSnarkScanStateStableV2,
     },
@@ -22,11 +22,11 @@ use mina_p2p_messages::{
 use openmina_core::{
     block::ArcBlock,
     snark::{Snark, SnarkJobId},
-    transaction::{Transaction, TransactionHash},
+    transaction::{Transaction, TransactionHash, TransactionInfo},
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{connection::outgoing::P2pConnectionOutgoingInitOpts, P2pTimeouts};
+use crate::{connection::outgoing::P2pConnectionOutgoingInitOpts, P2pLimits, P2pTimeouts};
 
 pub type P2pRpcId = QueryID;
 
@@ -53,7 +53,9 @@ pub enum P2pRpcKind {
     Block,
     Snark,
     Transaction,
+    TransactionPoolSummary,
     InitialPeers,
+    GenesisProof,
 }
 
 impl P2pRpcKind {
@@ -67,7 +69,24 @@ impl P2pRpcKind {
             Self::Block => config.block,
             Self::Snark => config.snark,
             Self::Transaction => config.transaction,
+            Self::TransactionPoolSummary => config.transaction_pool_summary,
             Self::InitialPeers => config.initial_peers,
+            Self::GenesisProof => config.genesis_proof,
+        }
+    }
+
+    /// Number of times the request is resent after timing out.
+    pub fn max_retries(self, limits: &P2pLimits) -> usize {
+        match self {
+            // other peers can answer these just as well.
+            Self::BestTipWithProof | Self::InitialPeers => 0,
+            Self::LedgerQuery
+            | Self::StagedLedgerAuxAndPendingCoinbasesAtBlock
+            | Self::Block
+            | Self::Snark
+            | Self::Transaction
+            | Self::TransactionPoolSummary
+            | Self::GenesisProof => limits.rpc_timeout_retries(),
         }
     }
 
@@ -79,7 +98,9 @@ impl P2pRpcKind {
             Self::Block => true,
             Self::Snark => false,
             Self::Transaction => false,
+            Self::TransactionPoolSummary => false,
             Self::InitialPeers => true,
+            Self::GenesisProof => false,
         }
     }
 }
@@ -92,7 +113,13 @@ pub enum P2pRpcRequest {
     Block(StateHash),
     Snark(SnarkJobId),
     Transaction(TransactionHash),
+    /// Page of the peer's transaction pool, starting at the `offset`.
+    TransactionPoolSummary {
+        offset: u64,
+    },
     InitialPeers,
+    /// Proof of the genesis block with the given hash.
+    GenesisProof(StateHash),
 }
 
 impl P2pRpcRequest {
@@ -106,7 +133,9 @@ impl P2pRpcRequest {
             Self::Block(_) => P2pRpcKind::Block,
             Self::Snark(_) => P2pRpcKind::Snark,
             Self::Transaction(_) => P2pRpcKind::Transaction,
+            Self::TransactionPoolSummary { .. } => P2pRpcKind::TransactionPoolSummary,
             Self::InitialPeers => P2pRpcKind::InitialPeers,
+            Self::GenesisProof(_) => P2pRpcKind::GenesisProof,
         }
     }
 }
@@ -153,7 +182,8 @@ impl std::fmt::Display for P2pRpcRequest {
                 write!(f, "ledger: {ledger_hash}")
             }
             Self::StagedLedgerAuxAndPendingCoinbasesAtBlock(block_hash)
-            | Self::Block(block_hash) => {
+            | Self::Block(block_hash)
+            | Self::GenesisProof(block_hash) => {
                 write!(f, ", {block_hash}")
             }
             Self::Snark(job_id) => {
@@ -162,6 +192,9 @@ impl std::fmt::Display for P2pRpcRequest {
             Self::Transaction(hash) => {
                 write!(f, ", {hash}")
             }
+            Self::TransactionPoolSummary { offset } => {
+                write!(f, ", offset: {offset}")
+            }
             Self::InitialPeers => Ok(()),
         }
     }
@@ -182,6 +215,19 @@ pub struct StagedLedgerAuxAndPendingCoinbases {
     pub needed_blocks: List<MinaStateProtocolStateValueStableV2>,
 }
 
+/// Max number of transactions in a single [`TransactionPoolSummary`] page.
+pub const TRANSACTION_POOL_SUMMARY_PAGE_SIZE: u8 = 128;
+
+/// Page of the transaction pool contents, exchanged with the peer on
+/// connect so that a freshly (re)started node can fetch the transactions
+/// it's missing, instead of waiting for them to be gossiped again.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolSummary {
+    pub transactions: List<TransactionInfo>,
+    /// Offset of the next page, `None` if this is the last one.
+    pub next_offset: Option<u64>,
+}
+
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
 pub enum P2pRpcResponse {
     BestTipWithProof(BestTipWithProof),
@@ -190,7 +236,9 @@ pub enum P2pRpcResponse {
     Block(ArcBlock),
     Snark(Snark),
     Transaction(Transaction),
+    TransactionPoolSummary(TransactionPoolSummary),
     InitialPeers(List<P2pConnectionOutgoingInitOpts>),
+    GenesisProof(Arc<MinaBaseProofStableV2>),
 }
 
 impl P2pRpcResponse {
@@ -204,7 +252,9 @@ impl P2pRpcResponse {
             Self::Block(_) => P2pRpcKind::Block,
             Self::Snark(_) => P2pRpcKind::Snark,
             Self::Transaction(_) => P2pRpcKind::Transaction,
+            Self::TransactionPoolSummary(_) => P2pRpcKind::TransactionPoolSummary,
             Self::InitialPeers(_) => P2pRpcKind::InitialPeers,
+            Self::GenesisProof(_) => P2pRpcKind::GenesisProof,
         }
     }
 }
@@ -298,6 +348,14 @@ mod libp2p {
                 // should use gossipsub to broadcast
                 None
             }
+            P2pRpcResponse::TransactionPoolSummary(_) => {
+                // libp2p peers sync the pool over gossipsub
+                None
+            }
+            P2pRpcResponse::GenesisProof(_) => {
+                // libp2p peers ship the genesis proof with the node
+                None
+            }
             P2pRpcResponse::InitialPeers(peers) => {
                 type Method = rpc::GetSomeInitialPeersV1ForV2;
                 type Payload = ResponsePayload<<Method as RpcMethod>::Response>;
@@ -399,6 +457,14 @@ mod libp2p {
                 // libp2p cannot fulfill this request
                 None
             }
+            P2pRpcRequest::TransactionPoolSummary { .. } => {
+                // libp2p cannot fulfill this request
+                None
+            }
+            P2pRpcRequest::GenesisProof(_) => {
+                // libp2p cannot fulfill this request
+                None
+            }
             P2pRpcRequest::InitialPeers => {
                 type Method = rpc::GetSomeInitialPeersV1ForV2;
                 type Payload = QueryPayload<<Method as RpcMethod>::Query>;
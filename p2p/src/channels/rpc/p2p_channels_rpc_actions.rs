@@ -4,7 +4,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::{P2pState, PeerId};
 
-use super::{P2pChannelsRpcState, P2pRpcId, P2pRpcLocalState, P2pRpcRequest, P2pRpcResponse};
+use super::{
+    P2pChannelsRpcState, P2pRpcId, P2pRpcLocalRequestState, P2pRpcLocalState, P2pRpcRequest,
+    P2pRpcResponse,
+};
 
 pub type P2pChannelsRpcActionWithMetaRef<'a> = redux::ActionWithMeta<&'a P2pChannelsRpcAction>;
 
@@ -28,10 +31,18 @@ pub enum P2pChannelsRpcAction {
         request: Box<P2pRpcRequest>,
         on_init: Option<redux::Callback<(PeerId, P2pRpcId, P2pRpcRequest)>>,
     },
+    /// Request timed out, but it can still be retried, so it's resent
+    /// under a fresh id. Callers still refer to it by the original id.
+    RequestRetry {
+        peer_id: PeerId,
+        id: P2pRpcId,
+    },
+    /// Request timed out and it won't be retried anymore.
     Timeout {
         peer_id: PeerId,
         id: P2pRpcId,
     },
+    /// Response for the request attempt sent with `id`.
     ResponseReceived {
         peer_id: PeerId,
         id: P2pRpcId,
@@ -63,6 +74,7 @@ impl P2pChannelsRpcAction {
             | Self::Pending { peer_id }
             | Self::Ready { peer_id }
             | Self::RequestSend { peer_id, .. }
+            | Self::RequestRetry { peer_id, .. }
             | Self::Timeout { peer_id, .. }
             | Self::ResponseReceived { peer_id, .. }
             | Self::RequestReceived { peer_id, .. }
@@ -95,42 +107,34 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsRpcAction {
                 .filter(|p| !p.is_libp2p() || request.kind().supported_by_libp2p())
                 .and_then(|p| p.status.as_ready())
                 .is_some_and(|p| {
-                    matches!(
-                        &p.channels.rpc,
-                        P2pChannelsRpcState::Ready {
-                            local: P2pRpcLocalState::WaitingForRequest { .. }
-                                | P2pRpcLocalState::Responded { .. },
-                            ..
-                        } if p.channels.next_local_rpc_id() == *id
-                    )
+                    p.channels.rpc.can_send_request()
+                        && p.channels.next_local_rpc_id() == *id
+                        && p.channels.rpc.local().is_some_and(|local| {
+                            local.remote_in_flight(time) < MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS
+                        })
                 }),
+            P2pChannelsRpcAction::RequestRetry { peer_id, id } => {
+                state.is_peer_rpc_timed_out(peer_id, *id, time)
+                    && local_request(state, peer_id, *id).is_some_and(|(local, req)| {
+                        req.retries < req.max_retries
+                            && local.remote_in_flight(time) < MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS
+                    })
+            }
             P2pChannelsRpcAction::Timeout { peer_id, id } => {
-                state.get_ready_peer(peer_id).is_some_and(|p| {
-                    matches!(
-                        &p.channels.rpc,
-                        P2pChannelsRpcState::Ready {
-                            local: P2pRpcLocalState::Requested { id: rpc_id, .. },
-                            ..
-                        } if rpc_id == id
-                    )
-                }) && state.is_peer_rpc_timed_out(peer_id, *id, time)
+                state.is_peer_rpc_timed_out(peer_id, *id, time)
+                    && local_request(state, peer_id, *id)
+                        .is_some_and(|(_, req)| req.retries >= req.max_retries)
             }
             P2pChannelsRpcAction::ResponseReceived { peer_id, id, .. } => {
                 // TODO(binier): use consensus to enforce that peer doesn't send
                 // us inferior block than it has in the past.
-                state.get_ready_peer(peer_id).is_some_and(|p| {
-                    match &p.channels.rpc {
-                        P2pChannelsRpcState::Ready { local, .. } => {
-                            // TODO(binier): validate that response corresponds to request.
-                            matches!(
-                                local,
-                                P2pRpcLocalState::Requested { id: rpc_id, .. }
-                                    if rpc_id == id
-                            )
-                        }
-                        _ => false,
-                    }
-                })
+                // TODO(binier): validate that response corresponds to request.
+                state
+                    .get_ready_peer(peer_id)
+                    .and_then(|p| p.channels.rpc.local())
+                    .is_some_and(|local| {
+                        local.request_id(*id).is_some() || local.abandoned.contains_key(id)
+                    })
             }
             P2pChannelsRpcAction::RequestReceived { peer_id, id, .. } => state
                 .get_ready_peer(peer_id)
@@ -185,6 +189,15 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsRpcAction {
     }
 }
 
+fn local_request<'a>(
+    state: &'a P2pState,
+    peer_id: &PeerId,
+    id: P2pRpcId,
+) -> Option<(&'a P2pRpcLocalState, &'a P2pRpcLocalRequestState)> {
+    let local = state.get_ready_peer(peer_id)?.channels.rpc.local()?;
+    Some((local, local.requests.get(&id)?))
+}
+
 use crate::channels::P2pChannelsAction;
 
 impl From<P2pChannelsRpcAction> for crate::P2pAction {
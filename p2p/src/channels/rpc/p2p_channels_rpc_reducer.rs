@@ -1,10 +1,12 @@
 use super::{
-    P2pChannelsRpcAction, P2pChannelsRpcState, P2pRpcLocalState, P2pRpcRemotePendingRequestState,
-    P2pRpcRemoteState, P2pRpcResponse, RpcChannelMsg, MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS,
+    P2pChannelsRpcAction, P2pChannelsRpcState, P2pRpcId, P2pRpcLocalRequestState,
+    P2pRpcLocalRespondedState, P2pRpcLocalState, P2pRpcRemotePendingRequestState,
+    P2pRpcRemoteState, P2pRpcRequest, P2pRpcResponse, RpcChannelMsg,
+    MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS,
 };
 use crate::{
     channels::{ChannelId, ChannelMsg, MsgId, P2pChannelsEffectfulAction},
-    P2pNetworkRpcAction, P2pPeerAction, P2pState,
+    P2pNetworkRpcAction, P2pPeerAction, P2pState, PeerId,
 };
 use openmina_core::{block::BlockWithHash, bug_condition, error, Substate};
 use redux::ActionWithMeta;
@@ -24,6 +26,14 @@ impl P2pChannelsRpcState {
         let p2p_state = state_context.get_substate_mut()?;
         let peer_id = *action.peer_id();
         let is_libp2p = p2p_state.is_libp2p_peer(&peer_id);
+        let config = &p2p_state.config;
+        let timeouts = config.timeouts.clone();
+        let limits = config.limits;
+        let max_in_flight = if is_libp2p {
+            1
+        } else {
+            limits.rpc_max_in_flight_per_peer()
+        };
         let peer_state = &mut p2p_state
             .get_ready_peer_mut(&peer_id)
             .ok_or_else(|| format!("Peer state not found for: {action:?}"))?
@@ -56,7 +66,7 @@ impl P2pChannelsRpcState {
             P2pChannelsRpcAction::Ready { .. } => {
                 *rpc_state = Self::Ready {
                     time: meta.time(),
-                    local: P2pRpcLocalState::WaitingForRequest { time: meta.time() },
+                    local: P2pRpcLocalState::new(max_in_flight),
                     remote: P2pRpcRemoteState {
                         pending_requests: VecDeque::with_capacity(
                             MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS,
@@ -86,57 +96,76 @@ impl P2pChannelsRpcState {
                     );
                     return Ok(());
                 };
+                // libp2p peer handles a single query at a time, so we can't
+                // tell whether it's still working on the timed out one.
+                let max_retries = if is_libp2p {
+                    0
+                } else {
+                    request.kind().max_retries(&limits)
+                };
                 *next_local_rpc_id += 1;
-                *local = P2pRpcLocalState::Requested {
-                    time: meta.time(),
+                local.requests.insert(
                     id,
-                    request: request.clone(),
-                };
+                    P2pRpcLocalRequestState::new(
+                        meta.time(),
+                        id,
+                        request.clone(),
+                        max_retries,
+                        &timeouts,
+                    ),
+                );
 
                 let dispatcher = state_context.into_dispatcher();
-
-                #[cfg(feature = "p2p-libp2p")]
-                if is_libp2p {
-                    if let Some((query, data)) =
-                        super::libp2p::internal_request_into_libp2p(*request.clone(), id)
-                    {
-                        dispatcher.push(P2pNetworkRpcAction::OutgoingQuery {
-                            peer_id,
-                            query,
-                            data,
-                        });
-                    }
-                    if let Some(on_init) = on_init {
-                        dispatcher.push_callback(on_init, (peer_id, id, *request));
-                    }
-
-                    return Ok(());
-                }
-
-                dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
-                    peer_id,
-                    msg_id: MsgId::first(),
-                    msg: ChannelMsg::Rpc(RpcChannelMsg::Request(id, *request.clone())),
-                });
+                Self::request_send(dispatcher, is_libp2p, peer_id, id, *request.clone());
 
                 if let Some(callback) = on_init {
                     dispatcher.push_callback(callback, (peer_id, id, *request));
                 }
                 Ok(())
             }
+            P2pChannelsRpcAction::RequestRetry { id, .. } => {
+                let attempt_id = *next_local_rpc_id;
+                let Some(request) = rpc_state
+                    .local_mut()
+                    .and_then(|local| local.retry(id, attempt_id, meta.time(), &timeouts))
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsRpcAction::RequestRetry`, state: {:?}",
+                        rpc_state
+                    );
+                    return Ok(());
+                };
+                let request = request.clone();
+                *next_local_rpc_id += 1;
+
+                let dispatcher = state_context.into_dispatcher();
+                Self::request_send(dispatcher, is_libp2p, peer_id, attempt_id, request);
+                Ok(())
+            }
             P2pChannelsRpcAction::Timeout { id, .. } => {
+                let Some(req) = rpc_state
+                    .local_mut()
+                    .and_then(|local| local.requests.remove(&id))
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsRpcAction::Timeout`, state: {:?}",
+                        rpc_state
+                    );
+                    return Ok(());
+                };
+
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
 
                 if let Some(callback) = &p2p_state.callbacks.on_p2p_channels_rpc_timeout {
-                    dispatcher.push_callback(callback.clone(), (peer_id, id));
+                    dispatcher.push_callback(callback.clone(), (peer_id, id, req.request));
                 }
 
                 Ok(())
             }
             P2pChannelsRpcAction::ResponseReceived {
                 response,
-                id: rpc_id,
+                id: attempt_id,
                 ..
             } => {
                 let Self::Ready { local, .. } = rpc_state else {
@@ -146,18 +175,29 @@ impl P2pChannelsRpcState {
                     );
                     return Ok(());
                 };
-                let P2pRpcLocalState::Requested { id, request, .. } = local else {
+                if local.abandoned.remove(&attempt_id).is_some() {
+                    // late response to the attempt, which was already retried.
+                    return Ok(());
+                }
+                let Some(rpc_id) = local.request_id(attempt_id) else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsRpcAction::ResponseReceived`, state: {:?}",
+                        rpc_state
+                    );
+                    return Ok(());
+                };
+                let Some(req) = local.requests.remove(&rpc_id) else {
                     bug_condition!(
                         "Invalid state for `P2pChannelsRpcAction::ResponseReceived`, state: {:?}",
                         rpc_state
                     );
                     return Ok(());
                 };
-                *local = P2pRpcLocalState::Responded {
+                local.last_responded = Some(P2pRpcLocalRespondedState {
                     time: meta.time(),
-                    id: *id,
-                    request: std::mem::take(request),
-                };
+                    id: rpc_id,
+                    request: req.request,
+                });
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
@@ -256,4 +296,42 @@ impl P2pChannelsRpcState {
             }
         }
     }
+
+    fn local_mut(&mut self) -> Option<&mut P2pRpcLocalState> {
+        match self {
+            Self::Ready { local, .. } => Some(local),
+            _ => None,
+        }
+    }
+
+    fn request_send<Action, State>(
+        dispatcher: &mut redux::Dispatcher<Action, State>,
+        is_libp2p: bool,
+        peer_id: PeerId,
+        id: P2pRpcId,
+        request: P2pRpcRequest,
+    ) where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        #[cfg(feature = "p2p-libp2p")]
+        if is_libp2p {
+            if let Some((query, data)) = super::libp2p::internal_request_into_libp2p(request, id) {
+                dispatcher.push(P2pNetworkRpcAction::OutgoingQuery {
+                    peer_id,
+                    query,
+                    data,
+                });
+            }
+            return;
+        }
+        #[cfg(not(feature = "p2p-libp2p"))]
+        let _ = is_libp2p;
+
+        dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+            peer_id,
+            msg_id: MsgId::first(),
+            msg: ChannelMsg::Rpc(RpcChannelMsg::Request(id, request)),
+        });
+    }
 }
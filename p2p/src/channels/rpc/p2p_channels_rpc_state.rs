@@ -1,4 +1,4 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 
 use serde::{Deserialize, Serialize};
 
@@ -25,21 +25,139 @@ pub enum P2pChannelsRpcState {
     },
 }
 
+/// Requests we sent to the peer, correlated with the responses by id.
+///
+/// Requests are keyed by the id under which they were sent first, which
+/// is the id known to the callers. Every retry is sent under a fresh id
+/// ([`P2pRpcLocalRequestState::attempt_id`]), so that the peer doesn't
+/// see the same id twice.
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum P2pRpcLocalState {
-    WaitingForRequest {
+pub struct P2pRpcLocalState {
+    /// Requests waiting for the response.
+    pub requests: BTreeMap<P2pRpcId, P2pRpcLocalRequestState>,
+    /// Ids of the attempts which were superseded by a retry, mapped to the
+    /// time until which a late response to them is still accepted (and
+    /// ignored). Until then the peer may still be processing them.
+    pub abandoned: BTreeMap<P2pRpcId, redux::Timestamp>,
+    /// Maximum number of `requests`, see
+    /// [`crate::P2pLimits::rpc_max_in_flight_per_peer`].
+    pub max_in_flight: usize,
+    pub last_responded: Option<P2pRpcLocalRespondedState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pRpcLocalRequestState {
+    pub time: redux::Timestamp,
+    /// Id under which the latest attempt was sent.
+    pub attempt_id: P2pRpcId,
+    pub request: Box<P2pRpcRequest>,
+    /// Request times out if it isn't responded by then. Reset when the
+    /// request is retried.
+    pub deadline: Option<redux::Timestamp>,
+    /// Number of times the request was resent after timing out.
+    pub retries: usize,
+    /// Maximum number of `retries`, see [`P2pRpcKind::max_retries`].
+    pub max_retries: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pRpcLocalRespondedState {
+    pub time: redux::Timestamp,
+    pub id: P2pRpcId,
+    pub request: Box<P2pRpcRequest>,
+}
+
+impl P2pRpcLocalState {
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            requests: Default::default(),
+            abandoned: Default::default(),
+            // at least one request must be allowed, or the peer is unusable.
+            max_in_flight: max_in_flight.max(1),
+            last_responded: None,
+        }
+    }
+
+    pub fn can_send_request(&self) -> bool {
+        self.requests.len() < self.max_in_flight
+    }
+
+    /// Requests the peer may still be processing, including the
+    /// abandoned attempts.
+    pub fn remote_in_flight(&self, now: redux::Timestamp) -> usize {
+        let abandoned = self.abandoned.values().filter(|until| now < **until);
+        self.requests.len().saturating_add(abandoned.count())
+    }
+
+    /// Id of the request, whose latest attempt was sent with `attempt_id`.
+    pub fn request_id(&self, attempt_id: P2pRpcId) -> Option<P2pRpcId> {
+        self.requests
+            .iter()
+            .find(|(_, req)| req.attempt_id == attempt_id)
+            .map(|(id, _)| *id)
+    }
+
+    /// Resends the request under `attempt_id`. The previous attempt is
+    /// remembered until the new one times out.
+    pub fn retry(
+        &mut self,
+        rpc_id: P2pRpcId,
+        attempt_id: P2pRpcId,
         time: redux::Timestamp,
-    },
-    Requested {
+        config: &P2pTimeouts,
+    ) -> Option<&P2pRpcRequest> {
+        self.abandoned.retain(|_, until| time < *until);
+        let req = self.requests.get_mut(&rpc_id)?;
+        req.retries = req.retries.saturating_add(1);
+        req.deadline = P2pRpcLocalRequestState::deadline(time, &req.request, config);
+        let abandoned_id = std::mem::replace(&mut req.attempt_id, attempt_id);
+        self.abandoned
+            .insert(abandoned_id, req.deadline.unwrap_or(time));
+        Some(&req.request)
+    }
+
+    pub fn is_timed_out(&self, rpc_id: P2pRpcId, now: redux::Timestamp) -> bool {
+        self.requests
+            .get(&rpc_id)
+            .and_then(|req| req.deadline)
+            .is_some_and(|deadline| now >= deadline)
+    }
+
+    pub fn timed_out_iter(&self, now: redux::Timestamp) -> impl '_ + Iterator<Item = P2pRpcId> {
+        self.requests
+            .keys()
+            .copied()
+            .filter(move |id| self.is_timed_out(*id, now))
+    }
+}
+
+impl P2pRpcLocalRequestState {
+    pub fn new(
         time: redux::Timestamp,
         id: P2pRpcId,
         request: Box<P2pRpcRequest>,
-    },
-    Responded {
+        max_retries: usize,
+        config: &P2pTimeouts,
+    ) -> Self {
+        let deadline = Self::deadline(time, &request, config);
+        Self {
+            time,
+            attempt_id: id,
+            request,
+            deadline,
+            retries: 0,
+            max_retries,
+        }
+    }
+
+    pub fn deadline(
         time: redux::Timestamp,
-        id: P2pRpcId,
-        request: Box<P2pRpcRequest>,
-    },
+        request: &P2pRpcRequest,
+        config: &P2pTimeouts,
+    ) -> Option<redux::Timestamp> {
+        let timeout = request.kind().timeout(config)?;
+        time.checked_add(u64::try_from(timeout.as_nanos()).ok()?)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -66,73 +184,42 @@ impl P2pChannelsRpcState {
         matches!(self, Self::Ready { .. })
     }
 
-    pub fn can_send_request(&self) -> bool {
+    pub fn local(&self) -> Option<&P2pRpcLocalState> {
         match self {
-            Self::Ready { local, .. } => matches!(
-                local,
-                P2pRpcLocalState::WaitingForRequest { .. } | P2pRpcLocalState::Responded { .. }
-            ),
-            _ => false,
+            Self::Ready { local, .. } => Some(local),
+            _ => None,
         }
     }
 
-    pub fn is_timed_out(
-        &self,
-        rpc_id: P2pRpcId,
-        now: redux::Timestamp,
-        config: &P2pTimeouts,
-    ) -> bool {
-        match self {
-            Self::Ready {
-                local: P2pRpcLocalState::Requested { time, id, request },
-                ..
-            } => {
-                rpc_id == *id
-                    && request
-                        .kind()
-                        .timeout(config)
-                        .and_then(|timeout| {
-                            let dur = now.checked_sub(*time)?;
-                            Some(dur >= timeout)
-                        })
-                        .unwrap_or(false)
-            }
-            _ => false,
-        }
+    /// Whether one more request can be sent, without exceeding the
+    /// in-flight cap for the peer.
+    pub fn can_send_request(&self) -> bool {
+        self.local().is_some_and(P2pRpcLocalState::can_send_request)
     }
 
-    pub fn pending_local_rpc_id(&self) -> Option<P2pRpcId> {
-        match self {
-            Self::Ready {
-                local: P2pRpcLocalState::Requested { id, .. },
-                ..
-            } => Some(*id),
-            _ => None,
-        }
+    pub fn is_timed_out(&self, rpc_id: P2pRpcId, now: redux::Timestamp) -> bool {
+        self.local()
+            .is_some_and(|local| local.is_timed_out(rpc_id, now))
     }
 
-    pub fn pending_local_rpc(&self) -> Option<&P2pRpcRequest> {
-        match self {
-            Self::Ready {
-                local: P2pRpcLocalState::Requested { request, .. },
-                ..
-            } => Some(request),
-            _ => None,
-        }
+    pub fn pending_local_rpc_ids(&self) -> impl '_ + Iterator<Item = P2pRpcId> {
+        self.local()
+            .into_iter()
+            .flat_map(|local| local.requests.keys().copied())
     }
 
-    pub fn pending_local_rpc_kind(&self) -> Option<P2pRpcKind> {
-        self.pending_local_rpc().map(|req| req.kind())
+    pub fn pending_local_rpc(&self, rpc_id: P2pRpcId) -> Option<&P2pRpcRequest> {
+        let req = self.local()?.requests.get(&rpc_id)?;
+        Some(&req.request)
+    }
+
+    pub fn pending_local_rpc_kind(&self, rpc_id: P2pRpcId) -> Option<P2pRpcKind> {
+        self.pending_local_rpc(rpc_id).map(|req| req.kind())
     }
 
     pub fn local_responded_request(&self) -> Option<(P2pRpcId, &P2pRpcRequest)> {
-        match self {
-            Self::Ready {
-                local: P2pRpcLocalState::Responded { id, request, .. },
-                ..
-            } => Some((*id, request)),
-            _ => None,
-        }
+        let responded = self.local()?.last_responded.as_ref()?;
+        Some((responded.id, &responded.request))
     }
 
     fn remote_requests(&self) -> impl Iterator<Item = &P2pRpcRemotePendingRequestState> {
@@ -161,3 +248,66 @@ impl P2pChannelsRpcState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_local_requests_correlation() {
+        let config = P2pTimeouts {
+            best_tip_with_proof: Some(Duration::from_secs(10)),
+            initial_peers: None,
+            ..Default::default()
+        };
+        let secs = |s: u64| redux::Timestamp::new(s * 1_000_000_000);
+        let mut local = P2pRpcLocalState::new(2);
+
+        let best_tip = Box::new(P2pRpcRequest::BestTipWithProof);
+        let req = P2pRpcLocalRequestState::new(secs(1), 0, best_tip, 0, &config);
+        assert_eq!(req.deadline, Some(secs(11)));
+        local.requests.insert(0, req);
+        assert!(local.can_send_request());
+
+        let initial_peers = Box::new(P2pRpcRequest::InitialPeers);
+        let req = P2pRpcLocalRequestState::new(secs(2), 1, initial_peers, 0, &config);
+        assert_eq!(req.deadline, None);
+        local.requests.insert(1, req);
+        assert!(!local.can_send_request());
+
+        assert_eq!(local.timed_out_iter(secs(10)).count(), 0);
+        // request without a timeout never times out.
+        assert_eq!(local.timed_out_iter(secs(100)).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_local_request_retry() {
+        let config = P2pTimeouts {
+            best_tip_with_proof: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        let secs = |s: u64| redux::Timestamp::new(s * 1_000_000_000);
+        let mut local = P2pRpcLocalState::new(2);
+
+        let best_tip = Box::new(P2pRpcRequest::BestTipWithProof);
+        let req = P2pRpcLocalRequestState::new(secs(1), 0, best_tip, 1, &config);
+        local.requests.insert(0, req);
+        assert_eq!(local.timed_out_iter(secs(11)).collect::<Vec<_>>(), vec![0]);
+
+        assert!(local.retry(0, 3, secs(11), &config).is_some());
+        let req = &local.requests[&0];
+        assert_eq!((req.attempt_id, req.retries), (3, 1));
+        assert_eq!(req.deadline, Some(secs(21)));
+        // the retry is correlated with the request, the first attempt isn't.
+        assert_eq!(local.request_id(3), Some(0));
+        assert_eq!(local.request_id(0), None);
+        assert_eq!(local.abandoned.get(&0), Some(&secs(21)));
+        assert_eq!(local.timed_out_iter(secs(20)).count(), 0);
+
+        // peer may still be processing the first attempt.
+        assert_eq!(local.remote_in_flight(secs(20)), 2);
+        assert_eq!(local.remote_in_flight(secs(21)), 1);
+    }
+}
@@ -138,6 +138,10 @@ impl P2pChannelsSignalingExchangeState {
                         dispatcher.push(P2pConnectionIncomingAction::Init { opts, rpc_id: None });
                     }
                     Err(reason) => {
+                        dispatcher.push(P2pConnectionIncomingAction::Rejected {
+                            peer_id: opts.peer_id,
+                            reason,
+                        });
                         let answer = P2pConnectionResponse::Rejected(reason);
                         dispatcher.push(P2pChannelsSignalingExchangeAction::AnswerSend {
                             peer_id,
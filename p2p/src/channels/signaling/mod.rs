@@ -14,10 +14,112 @@
 //! 5. [exchange] Relayer relays received webrtc offer to the listener peer.
 //! 6. [exchange] Relayer receives webrtc answer from the listener peer.
 //! 7. [discovery] Relayer relays the answer to the dialer.
+//!
+//! Over libp2p the messages of both channels are relayed as queries of
+//! [`libp2p::SignalingRelayV1`] rpc method, so libp2p peers can act as
+//! relayers too.
 
 pub mod discovery;
 pub mod exchange;
 
+pub mod libp2p {
+    use binprot::BinProtWrite;
+    use mina_p2p_messages::{
+        rpc_kernel::{
+            NeedsLength, QueryHeader, QueryPayload, ResponsePayload, RpcMethod, RpcResult, RpcTag,
+        },
+        versioned::Ver,
+    };
+
+    use crate::{channels::ChannelMsg, Data};
+
+    /// Rpc method, which carries the signaling channel messages over
+    /// libp2p. It's implemented only by the Rust nodes, the OCaml ones
+    /// respond with `Unimplemented_rpc`, after which the messages aren't
+    /// sent to the peer anymore.
+    pub struct SignalingRelayV1;
+
+    impl RpcMethod for SignalingRelayV1 {
+        const NAME: RpcTag = b"__openmina_signaling_relay";
+        const NAME_STR: &'static str = "__openmina_signaling_relay";
+        const VERSION: Ver = 1;
+        type Query = ChannelMsg;
+        type Response = ();
+    }
+
+    impl SignalingRelayV1 {
+        /// Id of the relay queries. Only one query is in flight on the
+        /// outgoing stream, and the rpc channel ids never get that high,
+        /// so the response can't be mistaken for the one of a request.
+        pub const QUERY_ID: u64 = u64::MAX;
+
+        pub fn is_relayed(msg: &ChannelMsg) -> bool {
+            matches!(
+                msg,
+                ChannelMsg::SignalingDiscovery(_) | ChannelMsg::SignalingExchange(_)
+            )
+        }
+
+        pub fn query(msg: &ChannelMsg) -> (QueryHeader, Data) {
+            type Payload = QueryPayload<<SignalingRelayV1 as RpcMethod>::Query>;
+
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&NeedsLength(msg.clone()), &mut v)
+                .unwrap_or_default();
+            let header = QueryHeader {
+                tag: Self::NAME.into(),
+                version: Self::VERSION,
+                id: Self::QUERY_ID,
+            };
+            (header, v.into())
+        }
+
+        /// Response acknowledging the relayed message.
+        pub fn response() -> Data {
+            type Payload = ResponsePayload<<SignalingRelayV1 as RpcMethod>::Response>;
+
+            let mut v = vec![];
+            <Payload as BinProtWrite>::binprot_write(&RpcResult(Ok(NeedsLength(()))), &mut v)
+                .unwrap_or_default();
+            v.into()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use mina_p2p_messages::rpc_kernel::PayloadBinprotReader;
+
+        use super::*;
+        use crate::channels::{
+            rpc::RpcChannelMsg, signaling::exchange::SignalingExchangeChannelMsg,
+        };
+
+        #[test]
+        fn relay_query_roundtrip() {
+            let msg = ChannelMsg::SignalingExchange(SignalingExchangeChannelMsg::GetNext);
+            assert!(SignalingRelayV1::is_relayed(&msg));
+
+            let (header, data) = SignalingRelayV1::query(&msg);
+            assert_eq!(header.tag.as_ref(), SignalingRelayV1::NAME);
+            assert_eq!(header.version, SignalingRelayV1::VERSION);
+            let decoded = SignalingRelayV1::query_payload(&mut &data[..]).unwrap();
+            assert!(matches!(
+                decoded,
+                ChannelMsg::SignalingExchange(SignalingExchangeChannelMsg::GetNext)
+            ));
+
+            let data = SignalingRelayV1::response();
+            SignalingRelayV1::response_payload(&mut &data[..]).unwrap();
+        }
+
+        #[test]
+        fn only_signaling_is_relayed() {
+            let msg = ChannelMsg::Rpc(RpcChannelMsg::Response(0, None));
+            assert!(!SignalingRelayV1::is_relayed(&msg));
+        }
+    }
+}
+
 mod p2p_channels_signaling_state;
 pub use p2p_channels_signaling_state::*;
 
@@ -51,7 +153,7 @@ impl crate::P2pState {
 
         /// random shuffle available peers
         use rand::prelude::*;
-        let mut rng = rand::rngs::StdRng::seed_from_u64(time.into());
+        let mut rng = openmina_core::pseudo_rng(time, "signaling_discovery_peers");
         let mut available_peers_ordered = available_peers.iter().copied().collect::<Vec<_>>();
         available_peers_ordered.shuffle(&mut rng);
 
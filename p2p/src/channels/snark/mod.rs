@@ -7,7 +7,7 @@ pub use p2p_channels_snark_actions::*;
 mod p2p_channels_snark_reducer;
 
 use binprot_derive::{BinProtRead, BinProtWrite};
-use openmina_core::snark::SnarkInfo;
+use openmina_core::snark::{SnarkInfo, SnarkInfoBatch};
 use serde::{Deserialize, Serialize};
 
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
@@ -15,7 +15,9 @@ pub enum SnarkPropagationChannelMsg {
     /// Request next snarks upto the `limit`.
     ///
     /// - Must not be sent until peer sends `WillSend` message for the
-    ///   previous request and until peer has fulfilled it.
+    ///   previous request and until peer has fulfilled it, unless it
+    ///   wasn't fulfilled within [`crate::channels::GOSSIP_REQUEST_TIMEOUT`],
+    ///   as the channel is lossy.
     GetNext { limit: u8 },
     /// Amount of snarks which will proceed this message.
     ///
@@ -26,4 +28,10 @@ pub enum SnarkPropagationChannelMsg {
     WillSend { count: u8 },
     /// Snark.
     Snark(SnarkInfo),
+    /// Multiple snarks from the same prover with the same fee.
+    ///
+    /// - Can only be sent to peers which announced support for it.
+    /// - Each snark in the batch counts towards the amount promised by
+    ///   `WillSend`.
+    SnarkBatch(SnarkInfoBatch),
 }
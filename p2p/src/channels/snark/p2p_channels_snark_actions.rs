@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{channels::P2pChannelsAction, P2pState, PeerId};
 
-use super::{P2pChannelsSnarkState, SnarkInfo, SnarkPropagationState};
+use super::{P2pChannelsSnarkState, SnarkInfo, SnarkInfoBatch, SnarkPropagationState};
 
 pub type P2pChannelsSnarkActionWithMetaRef<'a> = redux::ActionWithMeta<&'a P2pChannelsSnarkAction>;
 
@@ -34,6 +34,11 @@ pub enum P2pChannelsSnarkAction {
         peer_id: PeerId,
         snark: Box<SnarkInfo>,
     },
+    #[action_event(level = debug, fields(display(peer_id), count = batch.len()))]
+    BatchReceived {
+        peer_id: PeerId,
+        batch: Box<SnarkInfoBatch>,
+    },
     #[action_event(level = debug, fields(display(peer_id), limit))]
     RequestReceived {
         peer_id: PeerId,
@@ -70,6 +75,7 @@ impl P2pChannelsSnarkAction {
             | Self::RequestSend { peer_id, .. }
             | Self::PromiseReceived { peer_id, .. }
             | Self::Received { peer_id, .. }
+            | Self::BatchReceived { peer_id, .. }
             | Self::RequestReceived { peer_id, .. }
             | Self::ResponseSend { peer_id, .. } => Some(peer_id),
             Self::Libp2pReceived { peer_id, .. } => Some(peer_id),
@@ -79,7 +85,7 @@ impl P2pChannelsSnarkAction {
 }
 
 impl redux::EnablingCondition<P2pState> for P2pChannelsSnarkAction {
-    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
         match self {
             P2pChannelsSnarkAction::Init { peer_id } => state
                 .get_ready_peer(peer_id)
@@ -94,16 +100,13 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsSnarkAction {
             }
             P2pChannelsSnarkAction::RequestSend { peer_id, .. } => {
                 state.get_ready_peer(peer_id).is_some_and(|p| {
-                    matches!(
-                        &p.channels.snark,
-                        P2pChannelsSnarkState::Ready {
-                            local: SnarkPropagationState::WaitingForRequest { .. }
-                                | SnarkPropagationState::Responded { .. },
-                            ..
-                        }
-                    )
+                    p.channels
+                        .snark
+                        .can_send_request(p.channels.lossy_gossip, time)
                 })
             }
+            // Lossy channel is unordered, so the promise may arrive after
+            // some of the snarks.
             P2pChannelsSnarkAction::PromiseReceived {
                 peer_id,
                 promised_count,
@@ -115,7 +118,15 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsSnarkAction {
                             requested_limit, ..
                         }, ..
                     } if *promised_count > 0 && promised_count <= requested_limit
-                )
+                ) || p.channels.lossy_gossip
+                    && matches!(
+                        &p.channels.snark,
+                        P2pChannelsSnarkState::Ready {
+                            local: SnarkPropagationState::Responding {
+                                requested_limit, current_count, ..
+                            }, ..
+                        } if current_count <= promised_count && promised_count <= requested_limit
+                    )
             }),
             P2pChannelsSnarkAction::Received { peer_id, .. } => {
                 state.get_ready_peer(peer_id).is_some_and(|p| {
@@ -125,12 +136,45 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsSnarkAction {
                             local: SnarkPropagationState::Responding { .. },
                             ..
                         }
-                    )
+                    ) || p.channels.lossy_gossip
+                        && matches!(
+                            &p.channels.snark,
+                            P2pChannelsSnarkState::Ready {
+                                local: SnarkPropagationState::Requested { .. },
+                                ..
+                            }
+                        )
                 })
             }
+            P2pChannelsSnarkAction::BatchReceived { peer_id, batch } => {
+                !batch.is_empty()
+                    && batch.len() <= SnarkInfoBatch::MAX_LEN
+                    && state.get_ready_peer(peer_id).is_some_and(|p| {
+                        let P2pChannelsSnarkState::Ready { local, .. } = &p.channels.snark else {
+                            return false;
+                        };
+                        let remaining = match local {
+                            SnarkPropagationState::Requested {
+                                requested_limit, ..
+                            } if p.channels.lossy_gossip => *requested_limit,
+                            SnarkPropagationState::Responding {
+                                promised_count,
+                                current_count,
+                                ..
+                            } => promised_count.saturating_sub(*current_count),
+                            _ => return false,
+                        };
+                        batch.len() <= remaining as usize
+                    })
+            }
+            // On the lossy channel, the new request replaces the previous
+            // one, which the requestor gave up on.
             P2pChannelsSnarkAction::RequestReceived { peer_id, limit } => {
                 *limit > 0
                     && state.get_ready_peer(peer_id).is_some_and(|p| {
+                        if p.channels.lossy_gossip {
+                            return p.channels.snark.is_ready();
+                        }
                         matches!(
                             &p.channels.snark,
                             P2pChannelsSnarkState::Ready {
@@ -7,7 +7,7 @@ use crate::{
 };
 
 use super::{
-    P2pChannelsSnarkAction, P2pChannelsSnarkState, SnarkPropagationChannelMsg,
+    P2pChannelsSnarkAction, P2pChannelsSnarkState, SnarkInfoBatch, SnarkPropagationChannelMsg,
     SnarkPropagationState,
 };
 use mina_p2p_messages::{gossip::GossipNetMessageV2, v2};
@@ -94,18 +94,32 @@ impl P2pChannelsSnarkState {
                     );
                     return Ok(());
                 };
-                let SnarkPropagationState::Requested {
-                    requested_limit, ..
-                } = &local
-                else {
-                    return Ok(());
-                };
-                *local = SnarkPropagationState::Responding {
-                    time: meta.time(),
-                    requested_limit: *requested_limit,
-                    promised_count,
-                    current_count: 0,
-                };
+                match local {
+                    SnarkPropagationState::Requested {
+                        requested_limit, ..
+                    } => {
+                        *local = SnarkPropagationState::Responding {
+                            time: meta.time(),
+                            requested_limit: *requested_limit,
+                            promised_count,
+                            current_count: 0,
+                        };
+                    }
+                    SnarkPropagationState::Responding {
+                        promised_count: promised,
+                        current_count,
+                        ..
+                    } => {
+                        *promised = promised_count;
+                        if *current_count >= promised_count {
+                            *local = SnarkPropagationState::Responded {
+                                time: meta.time(),
+                                count: *current_count,
+                            };
+                        }
+                    }
+                    _ => {}
+                }
                 Ok(())
             }
             P2pChannelsSnarkAction::Received { peer_id, snark } => {
@@ -117,6 +131,7 @@ impl P2pChannelsSnarkState {
                     );
                     return Ok(());
                 };
+                local.promise_overtaken();
                 let SnarkPropagationState::Responding {
                     promised_count,
                     current_count,
@@ -130,7 +145,7 @@ impl P2pChannelsSnarkState {
                     return Ok(());
                 };
 
-                *current_count += 1;
+                *current_count = current_count.saturating_add(1);
 
                 if current_count >= promised_count {
                     *local = SnarkPropagationState::Responded {
@@ -148,6 +163,49 @@ impl P2pChannelsSnarkState {
 
                 Ok(())
             }
+            P2pChannelsSnarkAction::BatchReceived { peer_id, batch } => {
+                let state = state.inspect_err(|error| bug_condition!("{}", error))?;
+                let Self::Ready { local, .. } = state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsSnarkAction::BatchReceived`, state: {:?}",
+                        state
+                    );
+                    return Ok(());
+                };
+                local.promise_overtaken();
+                let SnarkPropagationState::Responding {
+                    promised_count,
+                    current_count,
+                    ..
+                } = local
+                else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsSnarkAction::BatchReceived`, state: {:?}",
+                        state
+                    );
+                    return Ok(());
+                };
+
+                *current_count = current_count.saturating_add(batch.len() as u8);
+
+                if current_count >= promised_count {
+                    *local = SnarkPropagationState::Responded {
+                        time: meta.time(),
+                        count: *current_count,
+                    };
+                }
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+
+                if let Some(callback) = &p2p_state.callbacks.on_p2p_channels_snark_received {
+                    for snark in batch.unbundle() {
+                        dispatcher.push_callback(callback.clone(), (peer_id, Box::new(snark)));
+                    }
+                }
+
+                Ok(())
+            }
             P2pChannelsSnarkAction::RequestReceived { limit, .. } => {
                 let state = state.inspect_err(|error| bug_condition!("{}", error))?;
                 let Self::Ready { remote, .. } = state else {
@@ -194,18 +252,42 @@ impl P2pChannelsSnarkState {
                     count,
                 };
 
-                let dispatcher = state_context.into_dispatcher();
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                let snark_batches = p2p_state
+                    .get_ready_peer(&peer_id)
+                    .is_some_and(|p| p.channels.snark_batches);
+
                 dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
                     peer_id,
                     msg_id: MsgId::first(),
                     msg: SnarkPropagationChannelMsg::WillSend { count }.into(),
                 });
 
-                for snark in snarks {
+                if !snark_batches {
+                    for snark in snarks {
+                        dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+                            peer_id,
+                            msg_id: MsgId::first(),
+                            msg: SnarkPropagationChannelMsg::Snark(snark).into(),
+                        });
+                    }
+                    return Ok(());
+                }
+
+                for batch in SnarkInfoBatch::bundle(snarks) {
+                    let msg = if batch.len() == 1 {
+                        match batch.unbundle().next() {
+                            Some(snark) => SnarkPropagationChannelMsg::Snark(snark),
+                            None => continue,
+                        }
+                    } else {
+                        SnarkPropagationChannelMsg::SnarkBatch(batch)
+                    };
                     dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
                         peer_id,
                         msg_id: MsgId::first(),
-                        msg: SnarkPropagationChannelMsg::Snark(snark).into(),
+                        msg: msg.into(),
                     });
                 }
                 Ok(())
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::channels::GOSSIP_REQUEST_TIMEOUT;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pChannelsSnarkState {
     Disabled,
@@ -42,20 +44,48 @@ pub enum SnarkPropagationState {
     },
 }
 
+impl SnarkPropagationState {
+    /// Snarks arrived before the promise, which is expected to be up to
+    /// the requested limit until it arrives, as the channel is unordered.
+    pub fn promise_overtaken(&mut self) {
+        if let Self::Requested {
+            time,
+            requested_limit,
+        } = *self
+        {
+            *self = Self::Responding {
+                time,
+                requested_limit,
+                promised_count: requested_limit,
+                current_count: 0,
+            };
+        }
+    }
+}
+
 impl P2pChannelsSnarkState {
     pub fn is_ready(&self) -> bool {
         matches!(self, Self::Ready { .. })
     }
 
-    pub fn can_send_request(&self) -> bool {
-        matches!(
-            self,
-            Self::Ready {
-                local: SnarkPropagationState::WaitingForRequest { .. }
-                    | SnarkPropagationState::Responded { .. },
-                ..
-            }
-        )
+    /// Whether the next request can be sent. On the `lossy_gossip`
+    /// channel, requests which weren't fulfilled within
+    /// [`GOSSIP_REQUEST_TIMEOUT`] are considered lost.
+    pub fn can_send_request(&self, lossy_gossip: bool, now: redux::Timestamp) -> bool {
+        match self {
+            Self::Ready { local, .. } => match local {
+                SnarkPropagationState::WaitingForRequest { .. }
+                | SnarkPropagationState::Responded { .. } => true,
+                SnarkPropagationState::Requested { time, .. }
+                | SnarkPropagationState::Responding { time, .. } => {
+                    lossy_gossip
+                        && now
+                            .checked_sub(*time)
+                            .is_some_and(|elapsed| elapsed >= GOSSIP_REQUEST_TIMEOUT)
+                }
+            },
+            _ => false,
+        }
     }
 
     pub fn next_send_index_and_limit(&self) -> (u64, u8) {
@@ -15,7 +15,9 @@ pub enum TransactionPropagationChannelMsg {
     /// Request next transactions upto the `limit`.
     ///
     /// - Must not be sent until peer sends `WillSend` message for the
-    ///   previous request and until peer has fulfilled it.
+    ///   previous request and until peer has fulfilled it, unless it
+    ///   wasn't fulfilled within [`crate::channels::GOSSIP_REQUEST_TIMEOUT`],
+    ///   as the channel is lossy.
     GetNext { limit: u8 },
     /// Amount of transactions which will proceed this message.
     ///
@@ -26,4 +28,19 @@ pub enum TransactionPropagationChannelMsg {
     WillSend { count: u8 },
     /// Transaction.
     Transaction(TransactionInfo),
+    /// Hashes of the transactions, sent instead of `WillSend` as a
+    /// response to `GetNext`, if peer supports transaction digests.
+    ///
+    /// - Can't contain more hashes than limit set by `GetNext`.
+    /// - Peer must respond with `GetByHash`.
+    Announce { hashes: Vec<TransactionHash> },
+    /// Request transactions from the last `Announce` by their hashes,
+    /// omitting the ones which we already have.
+    ///
+    /// - Can only be sent, if peer has sent `Announce` and we haven't
+    ///   responded with `GetByHash` yet.
+    /// - Can be empty, if we already have all announced transactions.
+    /// - Peer must respond with `WillSend`, followed by the requested
+    ///   transactions, unless the request is empty.
+    GetByHash { hashes: Vec<TransactionHash> },
 }
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::{channels::P2pChannelsAction, P2pState, PeerId};
 
-use super::{P2pChannelsTransactionState, TransactionInfo, TransactionPropagationState};
+use super::{
+    P2pChannelsTransactionState, TransactionHash, TransactionInfo, TransactionPropagationState,
+};
 
 pub type P2pChannelsTransactionActionWithMetaRef<'a> =
     redux::ActionWithMeta<&'a P2pChannelsTransactionAction>;
@@ -44,6 +46,21 @@ pub enum P2pChannelsTransactionAction {
         first_index: u64,
         last_index: u64,
     },
+    /// Peer announced digests of the transactions as a response to
+    /// our request.
+    AnnounceReceived {
+        peer_id: PeerId,
+        hashes: Vec<TransactionHash>,
+    },
+    /// Request announced transactions which we don't have yet.
+    RequestByHashSend {
+        peer_id: PeerId,
+        hashes: Vec<TransactionHash>,
+    },
+    RequestByHashReceived {
+        peer_id: PeerId,
+        hashes: Vec<TransactionHash>,
+    },
     Libp2pReceived {
         peer_id: PeerId,
         transactions: Vec<Transaction>,
@@ -67,7 +84,10 @@ impl P2pChannelsTransactionAction {
             | Self::PromiseReceived { peer_id, .. }
             | Self::Received { peer_id, .. }
             | Self::RequestReceived { peer_id, .. }
-            | Self::ResponseSend { peer_id, .. } => Some(peer_id),
+            | Self::ResponseSend { peer_id, .. }
+            | Self::AnnounceReceived { peer_id, .. }
+            | Self::RequestByHashSend { peer_id, .. }
+            | Self::RequestByHashReceived { peer_id, .. } => Some(peer_id),
             Self::Libp2pReceived { peer_id, .. } => Some(peer_id),
             Self::Libp2pBroadcast { .. } => None,
         }
@@ -75,7 +95,7 @@ impl P2pChannelsTransactionAction {
 }
 
 impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionAction {
-    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
         match self {
             P2pChannelsTransactionAction::Init { peer_id } => {
                 state.get_ready_peer(peer_id).is_some_and(|p| {
@@ -103,16 +123,13 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionAction {
             }
             P2pChannelsTransactionAction::RequestSend { peer_id, .. } => {
                 state.get_ready_peer(peer_id).is_some_and(|p| {
-                    matches!(
-                        &p.channels.transaction,
-                        P2pChannelsTransactionState::Ready {
-                            local: TransactionPropagationState::WaitingForRequest { .. }
-                                | TransactionPropagationState::Responded { .. },
-                            ..
-                        }
-                    )
+                    p.channels
+                        .transaction
+                        .can_send_request(p.channels.lossy_gossip, time)
                 })
             }
+            // Lossy channel is unordered, so the promise may arrive after
+            // some of the transactions.
             P2pChannelsTransactionAction::PromiseReceived {
                 peer_id,
                 promised_count,
@@ -124,7 +141,15 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionAction {
                             requested_limit, ..
                         }, ..
                     } if *promised_count > 0 && promised_count <= requested_limit
-                )
+                ) || p.channels.lossy_gossip
+                    && matches!(
+                        &p.channels.transaction,
+                        P2pChannelsTransactionState::Ready {
+                            local: TransactionPropagationState::Responding {
+                                requested_limit, current_count, ..
+                            }, ..
+                        } if current_count <= promised_count && promised_count <= requested_limit
+                    )
             }),
             P2pChannelsTransactionAction::Received { peer_id, .. } => {
                 state.get_ready_peer(peer_id).is_some_and(|p| {
@@ -134,12 +159,24 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionAction {
                             local: TransactionPropagationState::Responding { .. },
                             ..
                         }
-                    )
+                    ) || p.channels.lossy_gossip
+                        && matches!(
+                            &p.channels.transaction,
+                            P2pChannelsTransactionState::Ready {
+                                local: TransactionPropagationState::Requested { .. },
+                                ..
+                            }
+                        )
                 })
             }
+            // On the lossy channel, the new request replaces the previous
+            // one, which the requestor gave up on.
             P2pChannelsTransactionAction::RequestReceived { peer_id, limit } => {
                 *limit > 0
                     && state.get_ready_peer(peer_id).is_some_and(|p| {
+                        if p.channels.lossy_gossip {
+                            return p.channels.transaction.is_ready();
+                        }
                         matches!(
                             &p.channels.transaction,
                             P2pChannelsTransactionState::Ready {
@@ -180,6 +217,52 @@ impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionAction {
                         }
                     })
             }
+            P2pChannelsTransactionAction::AnnounceReceived { peer_id, hashes } => {
+                !hashes.is_empty()
+                    && state.get_ready_peer(peer_id).is_some_and(|p| {
+                        p.channels.transaction_digests
+                            && matches!(
+                                &p.channels.transaction,
+                                P2pChannelsTransactionState::Ready {
+                                    local: TransactionPropagationState::Requested {
+                                        requested_limit, ..
+                                    },
+                                    ..
+                                } if hashes.len() <= *requested_limit as usize
+                            )
+                    })
+            }
+            P2pChannelsTransactionAction::RequestByHashSend { peer_id, hashes } => {
+                state.get_ready_peer(peer_id).is_some_and(|p| {
+                    matches!(
+                        &p.channels.transaction,
+                        P2pChannelsTransactionState::Ready {
+                            local: TransactionPropagationState::AnnounceReceived {
+                                hashes: announced,
+                                ..
+                            },
+                            ..
+                        } if hashes.iter().all(|hash| announced.contains(hash))
+                    )
+                })
+            }
+            P2pChannelsTransactionAction::RequestByHashReceived { peer_id, hashes } => {
+                state.get_ready_peer(peer_id).is_some_and(|p| {
+                    p.channels.transaction_digests
+                        && matches!(
+                            &p.channels.transaction,
+                            P2pChannelsTransactionState::Ready {
+                                remote: TransactionPropagationState::Announced {
+                                    transactions,
+                                    ..
+                                },
+                                ..
+                            } if hashes.iter().all(|hash| {
+                                transactions.iter().any(|tx| &tx.hash == hash)
+                            })
+                        )
+                })
+            }
             P2pChannelsTransactionAction::Libp2pReceived { peer_id, .. } => {
                 cfg!(feature = "p2p-libp2p")
                     && state
@@ -1,14 +1,14 @@
 use super::{
-    P2pChannelsTransactionAction, P2pChannelsTransactionState, TransactionPropagationChannelMsg,
-    TransactionPropagationState,
+    P2pChannelsTransactionAction, P2pChannelsTransactionState, TransactionInfo,
+    TransactionPropagationChannelMsg, TransactionPropagationState,
 };
 use crate::{
     channels::{ChannelId, MsgId, P2pChannelsEffectfulAction},
-    P2pNetworkPubsubAction, P2pState,
+    P2pNetworkPubsubAction, P2pState, PeerId,
 };
 use mina_p2p_messages::{gossip::GossipNetMessageV2, v2};
 use openmina_core::{bug_condition, transaction::TransactionWithHash, Substate};
-use redux::ActionWithMeta;
+use redux::{ActionWithMeta, Dispatcher};
 
 impl P2pChannelsTransactionState {
     pub fn reducer<Action, State>(
@@ -22,9 +22,13 @@ impl P2pChannelsTransactionState {
         let (action, meta) = action.split();
         let p2p_state = state_context.get_substate_mut()?;
 
-        let transaction_state = action
+        let peer_state = action
             .peer_id()
-            .and_then(|peer_id| p2p_state.get_ready_peer_mut(peer_id))
+            .and_then(|peer_id| p2p_state.get_ready_peer_mut(peer_id));
+        let digests = peer_state
+            .as_ref()
+            .is_some_and(|peer_state| peer_state.channels.transaction_digests);
+        let transaction_state = peer_state
             .map(|peer_state| &mut peer_state.channels.transaction)
             .ok_or_else(|| format!("Invalid state for: {action:?}"));
 
@@ -91,22 +95,37 @@ impl P2pChannelsTransactionState {
                 );
                     return Ok(());
                 };
-                let TransactionPropagationState::Requested {
-                    requested_limit, ..
-                } = &local
-                else {
-                    bug_condition!(
-                    "Invalid state for `P2pChannelsTransactionAction::PromiseReceived `, state: {:?}",
-                    state
-                );
-                    return Ok(());
-                };
-                *local = TransactionPropagationState::Responding {
-                    time: meta.time(),
-                    requested_limit: *requested_limit,
-                    promised_count,
-                    current_count: 0,
-                };
+                match local {
+                    TransactionPropagationState::Requested {
+                        requested_limit, ..
+                    } => {
+                        *local = TransactionPropagationState::Responding {
+                            time: meta.time(),
+                            requested_limit: *requested_limit,
+                            promised_count,
+                            current_count: 0,
+                        };
+                    }
+                    TransactionPropagationState::Responding {
+                        promised_count: promised,
+                        current_count,
+                        ..
+                    } => {
+                        *promised = promised_count;
+                        if *current_count >= promised_count {
+                            *local = TransactionPropagationState::Responded {
+                                time: meta.time(),
+                                count: *current_count,
+                            };
+                        }
+                    }
+                    _ => {
+                        bug_condition!(
+                        "Invalid state for `P2pChannelsTransactionAction::PromiseReceived `, state: {:?}",
+                        state
+                    );
+                    }
+                }
                 Ok(())
             }
             P2pChannelsTransactionAction::Received {
@@ -121,6 +140,7 @@ impl P2pChannelsTransactionState {
                     );
                     return Ok(());
                 };
+                local.promise_overtaken();
                 let TransactionPropagationState::Responding {
                     promised_count,
                     current_count,
@@ -130,7 +150,7 @@ impl P2pChannelsTransactionState {
                     return Ok(());
                 };
 
-                *current_count += 1;
+                *current_count = current_count.saturating_add(1);
 
                 if current_count >= promised_count {
                     *local = TransactionPropagationState::Responded {
@@ -189,27 +209,116 @@ impl P2pChannelsTransactionState {
                     return Ok(());
                 }
 
+                if digests {
+                    let hashes = transactions.iter().map(|tx| tx.hash.clone()).collect();
+                    *remote = TransactionPropagationState::Announced {
+                        time: meta.time(),
+                        transactions,
+                    };
+
+                    let dispatcher = state_context.into_dispatcher();
+                    let msg = TransactionPropagationChannelMsg::Announce { hashes }.into();
+                    dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+                        peer_id,
+                        msg_id: MsgId::first(),
+                        msg,
+                    });
+                    return Ok(());
+                }
+
                 *remote = TransactionPropagationState::Responded {
                     time: meta.time(),
                     count,
                 };
 
                 let dispatcher = state_context.into_dispatcher();
-                let msg = TransactionPropagationChannelMsg::WillSend { count }.into();
+                Self::transactions_send(dispatcher, peer_id, transactions);
+                Ok(())
+            }
+            P2pChannelsTransactionAction::AnnounceReceived { peer_id, hashes } => {
+                let state = transaction_state.inspect_err(|error| bug_condition!("{}", error))?;
+                let Self::Ready { local, .. } = state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsTransactionAction::AnnounceReceived `, state: {:?}",
+                        state
+                    );
+                    return Ok(());
+                };
+                *local = TransactionPropagationState::AnnounceReceived {
+                    time: meta.time(),
+                    hashes: hashes.clone(),
+                };
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+
+                if let Some(callback) = &p2p_state.callbacks.on_p2p_channels_transaction_announced {
+                    dispatcher.push_callback(callback.clone(), (peer_id, hashes));
+                } else {
+                    // Nobody filters known transactions, so request all.
+                    dispatcher
+                        .push(P2pChannelsTransactionAction::RequestByHashSend { peer_id, hashes });
+                }
+                Ok(())
+            }
+            P2pChannelsTransactionAction::RequestByHashSend { peer_id, hashes } => {
+                let state = transaction_state.inspect_err(|error| bug_condition!("{}", error))?;
+                let Self::Ready { local, .. } = state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsTransactionAction::RequestByHashSend `, state: {:?}",
+                        state
+                    );
+                    return Ok(());
+                };
+                *local = match hashes.len() as u8 {
+                    0 => TransactionPropagationState::Responded {
+                        time: meta.time(),
+                        count: 0,
+                    },
+                    requested_limit => TransactionPropagationState::Requested {
+                        time: meta.time(),
+                        requested_limit,
+                    },
+                };
+
+                let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
                     peer_id,
                     msg_id: MsgId::first(),
-                    msg,
+                    msg: TransactionPropagationChannelMsg::GetByHash { hashes }.into(),
                 });
+                Ok(())
+            }
+            P2pChannelsTransactionAction::RequestByHashReceived { peer_id, hashes } => {
+                let state = transaction_state.inspect_err(|error| bug_condition!("{}", error))?;
+                let Self::Ready { remote, .. } = state else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsTransactionAction::RequestByHashReceived `, state: {:?}",
+                        state
+                    );
+                    return Ok(());
+                };
+                let TransactionPropagationState::Announced { transactions, .. } = remote else {
+                    bug_condition!(
+                        "Invalid state for `P2pChannelsTransactionAction::RequestByHashReceived `, state: {:?}",
+                        remote
+                    );
+                    return Ok(());
+                };
+                let transactions = std::mem::take(transactions)
+                    .into_iter()
+                    .filter(|tx| hashes.contains(&tx.hash))
+                    .collect::<Vec<_>>();
+                *remote = TransactionPropagationState::Responded {
+                    time: meta.time(),
+                    count: transactions.len() as u8,
+                };
 
-                for tx in transactions {
-                    let msg = TransactionPropagationChannelMsg::Transaction(tx).into();
-                    dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
-                        peer_id,
-                        msg_id: MsgId::first(),
-                        msg,
-                    });
+                if transactions.is_empty() {
+                    return Ok(());
                 }
+                let dispatcher = state_context.into_dispatcher();
+                Self::transactions_send(dispatcher, peer_id, transactions);
                 Ok(())
             }
             P2pChannelsTransactionAction::Libp2pReceived {
@@ -260,4 +369,30 @@ impl P2pChannelsTransactionState {
             }
         }
     }
+
+    fn transactions_send<Action, State>(
+        dispatcher: &mut Dispatcher<Action, State>,
+        peer_id: PeerId,
+        transactions: Vec<TransactionInfo>,
+    ) where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let count = transactions.len() as u8;
+        let msg = TransactionPropagationChannelMsg::WillSend { count }.into();
+        dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+            peer_id,
+            msg_id: MsgId::first(),
+            msg,
+        });
+
+        for tx in transactions {
+            let msg = TransactionPropagationChannelMsg::Transaction(tx).into();
+            dispatcher.push(P2pChannelsEffectfulAction::MessageSend {
+                peer_id,
+                msg_id: MsgId::first(),
+                msg,
+            });
+        }
+    }
 }
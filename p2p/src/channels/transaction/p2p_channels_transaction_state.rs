@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use super::{TransactionHash, TransactionInfo};
+use crate::channels::GOSSIP_REQUEST_TIMEOUT;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pChannelsTransactionState {
     Disabled,
@@ -40,6 +43,37 @@ pub enum TransactionPropagationState {
         time: redux::Timestamp,
         count: u8,
     },
+    /// Digests of the `transactions` were announced to the requestor,
+    /// waiting for it to request the ones that it doesn't have.
+    Announced {
+        time: redux::Timestamp,
+        transactions: Vec<TransactionInfo>,
+    },
+    /// Responder announced digests, waiting for us to request the
+    /// transactions that we don't have.
+    AnnounceReceived {
+        time: redux::Timestamp,
+        hashes: Vec<TransactionHash>,
+    },
+}
+
+impl TransactionPropagationState {
+    /// Transactions arrived before the promise, which is expected to be up
+    /// to the requested limit until it arrives, as the channel is unordered.
+    pub fn promise_overtaken(&mut self) {
+        if let Self::Requested {
+            time,
+            requested_limit,
+        } = *self
+        {
+            *self = Self::Responding {
+                time,
+                requested_limit,
+                promised_count: requested_limit,
+                current_count: 0,
+            };
+        }
+    }
 }
 
 impl P2pChannelsTransactionState {
@@ -47,15 +81,26 @@ impl P2pChannelsTransactionState {
         matches!(self, Self::Ready { .. })
     }
 
-    pub fn can_send_request(&self) -> bool {
-        matches!(
-            self,
-            Self::Ready {
-                local: TransactionPropagationState::WaitingForRequest { .. }
-                    | TransactionPropagationState::Responded { .. },
-                ..
-            }
-        )
+    /// Whether the next request can be sent. On the `lossy_gossip`
+    /// channel, requests which weren't fulfilled within
+    /// [`GOSSIP_REQUEST_TIMEOUT`] are considered lost.
+    pub fn can_send_request(&self, lossy_gossip: bool, now: redux::Timestamp) -> bool {
+        match self {
+            Self::Ready { local, .. } => match local {
+                TransactionPropagationState::WaitingForRequest { .. }
+                | TransactionPropagationState::Responded { .. } => true,
+                TransactionPropagationState::Requested { time, .. }
+                | TransactionPropagationState::Responding { time, .. }
+                | TransactionPropagationState::AnnounceReceived { time, .. } => {
+                    lossy_gossip
+                        && now
+                            .checked_sub(*time)
+                            .is_some_and(|elapsed| elapsed >= GOSSIP_REQUEST_TIMEOUT)
+                }
+                TransactionPropagationState::Announced { .. } => false,
+            },
+            _ => false,
+        }
     }
 
     pub fn next_send_index_and_limit(&self) -> (u64, u8) {
@@ -74,3 +119,76 @@ impl P2pChannelsTransactionState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lost_request_is_resent() {
+        let secs = |s: u64| redux::Timestamp::new(s * 1_000_000_000);
+        let ready = |local| P2pChannelsTransactionState::Ready {
+            time: secs(0),
+            local,
+            remote: TransactionPropagationState::WaitingForRequest { time: secs(0) },
+            next_send_index: 0,
+        };
+
+        let state = ready(TransactionPropagationState::Requested {
+            time: secs(1),
+            requested_limit: 4,
+        });
+        assert!(!state.can_send_request(true, secs(10)));
+        assert!(state.can_send_request(true, secs(11)));
+
+        // some of the promised transactions got lost.
+        let state = ready(TransactionPropagationState::Responding {
+            time: secs(1),
+            requested_limit: 4,
+            promised_count: 4,
+            current_count: 2,
+        });
+        assert!(!state.can_send_request(true, secs(10)));
+        assert!(state.can_send_request(true, secs(11)));
+        // the reliable channel doesn't lose them.
+        assert!(!state.can_send_request(false, secs(11)));
+    }
+
+    #[test]
+    fn test_promise_overtaken() {
+        let secs = |s: u64| redux::Timestamp::new(s * 1_000_000_000);
+        let mut local = TransactionPropagationState::Requested {
+            time: secs(1),
+            requested_limit: 4,
+        };
+        local.promise_overtaken();
+        assert!(matches!(
+            local,
+            TransactionPropagationState::Responding {
+                requested_limit: 4,
+                promised_count: 4,
+                current_count: 0,
+                ..
+            }
+        ));
+        // keeps the time of the request, so that the timeout still applies.
+        assert_eq!(ready_time(&local), Some(secs(1)));
+
+        let mut responded = TransactionPropagationState::Responded {
+            time: secs(2),
+            count: 1,
+        };
+        responded.promise_overtaken();
+        assert!(matches!(
+            responded,
+            TransactionPropagationState::Responded { count: 1, .. }
+        ));
+    }
+
+    fn ready_time(state: &TransactionPropagationState) -> Option<redux::Timestamp> {
+        match state {
+            TransactionPropagationState::Responding { time, .. } => Some(*time),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+mod p2p_connection_ice_restart_state;
+pub use p2p_connection_ice_restart_state::*;
+
+mod p2p_connection_ice_restart_actions;
+pub use p2p_connection_ice_restart_actions::*;
+
+mod p2p_connection_ice_restart_reducer;
+
+use crate::{webrtc, P2pState, PeerId};
+
+impl P2pState {
+    /// Whether we are the side responsible for restarting ICE with the peer.
+    ///
+    /// Only peers we have dialed directly (over http signaling) qualify,
+    /// since we need a signaling path to send the restart offer over.
+    pub fn is_ice_restart_initiator(&self, peer_id: &PeerId) -> bool {
+        let Some(peer) = self.peers.get(peer_id) else {
+            return false;
+        };
+        let Some(ready) = peer.status.as_ready() else {
+            return false;
+        };
+        !ready.is_incoming
+            && peer
+                .dial_opts
+                .as_ref()
+                .is_some_and(|opts| !opts.is_libp2p() && opts.can_connect_directly())
+    }
+
+    /// Whether the offer received over signaling is an ICE restart offer
+    /// for an already established, but degraded connection.
+    pub fn ice_restart_offer_accept(&self, peer_id: PeerId, offer: &webrtc::Offer) -> bool {
+        let Some(peer) = self.peers.get(&peer_id) else {
+            return false;
+        };
+        let Some(ready) = peer.status.as_ready() else {
+            return false;
+        };
+        let Some(ice_restart) = ready.ice_restart.as_ref() else {
+            return false;
+        };
+        !peer.is_libp2p()
+            && ready.is_incoming
+            && !ice_restart.is_pending()
+            && ice_restart.can_retry(self.config.limits.max_ice_restart_attempts())
+            && self.chain_id == offer.chain_id
+            && peer_id == offer.identity_pub_key.peer_id()
+            && offer.target_peer_id == self.my_webrtc_id()
+    }
+}
@@ -0,0 +1,136 @@
+use openmina_core::{requests::RpcId, ActionEvent};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connection::P2pConnectionAction, webrtc, P2pAction, P2pPeerStatusReady, P2pState, PeerId,
+};
+
+use super::P2pConnectionIceRestartState;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(fields(display(peer_id), display(error)))]
+pub enum P2pConnectionIceRestartAction {
+    /// Connectivity to the peer is lost.
+    #[action_event(level = info)]
+    Degraded {
+        peer_id: PeerId,
+    },
+    /// Initiate ICE restart attempt.
+    #[action_event(level = info)]
+    Init {
+        peer_id: PeerId,
+    },
+    /// ICE restart offer/answer exchange succeeded.
+    NegotiateSuccess {
+        peer_id: PeerId,
+    },
+    /// ICE restart attempt failed.
+    #[action_event(level = warn)]
+    Error {
+        peer_id: PeerId,
+        error: String,
+    },
+    /// Peer sent us an ICE restart offer.
+    OfferReceived {
+        peer_id: PeerId,
+        offer: Box<webrtc::Offer>,
+        rpc_id: Option<RpcId>,
+    },
+    AnswerSdpCreateSuccess {
+        peer_id: PeerId,
+        sdp: String,
+    },
+    /// Connectivity to the peer is restored.
+    #[action_event(level = info)]
+    Success {
+        peer_id: PeerId,
+    },
+    /// ICE restart attempt timed out.
+    #[action_event(level = warn)]
+    Timeout {
+        peer_id: PeerId,
+    },
+}
+
+impl P2pConnectionIceRestartAction {
+    pub fn peer_id(&self) -> &PeerId {
+        match self {
+            Self::Degraded { peer_id }
+            | Self::Init { peer_id }
+            | Self::NegotiateSuccess { peer_id }
+            | Self::Error { peer_id, .. }
+            | Self::OfferReceived { peer_id, .. }
+            | Self::AnswerSdpCreateSuccess { peer_id, .. }
+            | Self::Success { peer_id }
+            | Self::Timeout { peer_id } => peer_id,
+        }
+    }
+}
+
+fn webrtc_ready_peer<'a>(state: &'a P2pState, peer_id: &PeerId) -> Option<&'a P2pPeerStatusReady> {
+    let peer = state.peers.get(peer_id)?;
+    if peer.is_libp2p() {
+        return None;
+    }
+    peer.status.as_ready()
+}
+
+fn ice_restart_state<'a>(
+    state: &'a P2pState,
+    peer_id: &PeerId,
+) -> Option<&'a P2pConnectionIceRestartState> {
+    webrtc_ready_peer(state, peer_id)?.ice_restart.as_ref()
+}
+
+impl redux::EnablingCondition<P2pState> for P2pConnectionIceRestartAction {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
+        let max_attempts = state.config.limits.max_ice_restart_attempts();
+        match self {
+            P2pConnectionIceRestartAction::Degraded { peer_id } => {
+                webrtc_ready_peer(state, peer_id).is_some_and(|p| p.ice_restart.is_none())
+            }
+            P2pConnectionIceRestartAction::Init { peer_id } => {
+                state.is_ice_restart_initiator(peer_id)
+                    && ice_restart_state(state, peer_id)
+                        .is_some_and(|s| !s.is_pending() && s.can_retry(max_attempts))
+            }
+            P2pConnectionIceRestartAction::NegotiateSuccess { peer_id } => {
+                ice_restart_state(state, peer_id).is_some_and(|s| {
+                    matches!(
+                        s.status,
+                        super::P2pConnectionIceRestartStatus::OfferPending { .. }
+                    )
+                })
+            }
+            P2pConnectionIceRestartAction::Error { peer_id, .. } => {
+                ice_restart_state(state, peer_id).is_some_and(|s| s.is_pending())
+            }
+            P2pConnectionIceRestartAction::OfferReceived { peer_id, offer, .. } => {
+                state.ice_restart_offer_accept(*peer_id, offer)
+            }
+            P2pConnectionIceRestartAction::AnswerSdpCreateSuccess { peer_id, .. } => {
+                ice_restart_state(state, peer_id).is_some_and(|s| {
+                    matches!(
+                        s.status,
+                        super::P2pConnectionIceRestartStatus::AnswerPending { .. }
+                    )
+                })
+            }
+            P2pConnectionIceRestartAction::Success { peer_id } => {
+                ice_restart_state(state, peer_id).is_some()
+            }
+            P2pConnectionIceRestartAction::Timeout { peer_id } => {
+                let is_initiator = state.is_ice_restart_initiator(peer_id);
+                ice_restart_state(state, peer_id).is_some_and(|s| {
+                    s.is_timed_out(time, &state.config.timeouts, max_attempts, is_initiator)
+                })
+            }
+        }
+    }
+}
+
+impl From<P2pConnectionIceRestartAction> for P2pAction {
+    fn from(a: P2pConnectionIceRestartAction) -> Self {
+        Self::Connection(P2pConnectionAction::IceRestart(a))
+    }
+}
@@ -0,0 +1,166 @@
+use openmina_core::{bug_condition, Substate};
+use redux::ActionWithMeta;
+
+use crate::{
+    connection::{
+        ice_restart_effectful::P2pConnectionIceRestartEffectfulAction, P2pConnectionResponse,
+    },
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+    webrtc, P2pState,
+};
+
+use super::{
+    P2pConnectionIceRestartAction, P2pConnectionIceRestartState, P2pConnectionIceRestartStatus,
+};
+
+impl P2pConnectionIceRestartState {
+    pub fn reducer<Action, State>(
+        mut state_context: Substate<Action, State, P2pState>,
+        action: ActionWithMeta<P2pConnectionIceRestartAction>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let (action, meta) = action.split();
+        let time = meta.time();
+        let peer_id = *action.peer_id();
+        let p2p_state = state_context.get_substate_mut()?;
+        let is_initiator = p2p_state.is_ice_restart_initiator(&peer_id);
+        let max_attempts = p2p_state.config.limits.max_ice_restart_attempts();
+        let identity_pub_key = p2p_state.config.webrtc_identity_pub_key().clone();
+        let ready = p2p_state
+            .get_ready_peer_mut(&peer_id)
+            .ok_or_else(|| format!("Invalid state for: {:?}", action))?;
+
+        if let P2pConnectionIceRestartAction::Degraded { .. } = action {
+            ready.ice_restart = Some(Self::new(time));
+            if is_initiator {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pConnectionIceRestartAction::Init { peer_id });
+            }
+            return Ok(());
+        }
+
+        let Some(state) = ready.ice_restart.as_mut() else {
+            bug_condition!("Missing ICE restart state for: {:?}", action);
+            return Ok(());
+        };
+
+        match action {
+            // Handled above.
+            P2pConnectionIceRestartAction::Degraded { .. } => Ok(()),
+            P2pConnectionIceRestartAction::Init { .. } => {
+                state.attempts += 1;
+                state.status = P2pConnectionIceRestartStatus::OfferPending { time };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pConnectionIceRestartEffectfulAction::Init { peer_id });
+                Ok(())
+            }
+            P2pConnectionIceRestartAction::NegotiateSuccess { .. } => {
+                state.status = P2pConnectionIceRestartStatus::Negotiated { time, rpc_id: None };
+                Ok(())
+            }
+            P2pConnectionIceRestartAction::Error { error, .. } => {
+                let rpc_id = state.status.rpc_id();
+                state.status = P2pConnectionIceRestartStatus::Error {
+                    time,
+                    error: error.clone(),
+                };
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                if let Some(rpc_id) = rpc_id {
+                    if let Some(callback) = &p2p_state.callbacks.on_p2p_connection_incoming_error {
+                        dispatcher.push_callback(callback.clone(), (rpc_id, error));
+                    }
+                }
+                Ok(())
+            }
+            P2pConnectionIceRestartAction::OfferReceived { offer, rpc_id, .. } => {
+                state.attempts += 1;
+                state.status = P2pConnectionIceRestartStatus::AnswerPending { time, rpc_id };
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher
+                    .push(P2pConnectionIceRestartEffectfulAction::OfferSet { peer_id, offer });
+                Ok(())
+            }
+            P2pConnectionIceRestartAction::AnswerSdpCreateSuccess { sdp, .. } => {
+                let rpc_id = state.status.rpc_id();
+                state.status = P2pConnectionIceRestartStatus::Negotiated { time, rpc_id };
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                if let Some(rpc_id) = rpc_id {
+                    let capabilities = webrtc::P2pCapabilities::local(
+                        &p2p_state.config.enabled_channels,
+                        &p2p_state.feature_flags,
+                    );
+                    let answer = webrtc::Answer {
+                        sdp,
+                        identity_pub_key,
+                        target_peer_id: peer_id,
+                        bulk_channels: capabilities.bulk_channels,
+                        tx_digests: capabilities.tx_digests,
+                        compact_blocks: capabilities.compact_blocks,
+                        capabilities: Some(capabilities),
+                    };
+                    if let Some(callback) =
+                        &p2p_state.callbacks.on_p2p_connection_incoming_answer_ready
+                    {
+                        dispatcher.push_callback(
+                            callback.clone(),
+                            (
+                                rpc_id,
+                                peer_id,
+                                P2pConnectionResponse::Accepted(Box::new(answer)),
+                            ),
+                        );
+                    }
+                }
+                Ok(())
+            }
+            P2pConnectionIceRestartAction::Success { .. } => {
+                let rpc_id = state.status.rpc_id();
+                ready.ice_restart = None;
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                if let Some(rpc_id) = rpc_id {
+                    if let Some(callback) = &p2p_state.callbacks.on_p2p_connection_incoming_success
+                    {
+                        dispatcher.push_callback(callback.clone(), rpc_id);
+                    }
+                }
+                Ok(())
+            }
+            P2pConnectionIceRestartAction::Timeout { .. } => {
+                let rpc_id = state.status.rpc_id();
+                let can_retry = is_initiator && state.can_retry(max_attempts);
+                state.status = P2pConnectionIceRestartStatus::Error {
+                    time,
+                    error: "timeout".to_owned(),
+                };
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                if let Some(rpc_id) = rpc_id {
+                    if let Some(callback) = &p2p_state.callbacks.on_p2p_connection_incoming_error {
+                        dispatcher.push_callback(callback.clone(), (rpc_id, "timeout".to_owned()));
+                    }
+                }
+                if can_retry {
+                    dispatcher.push(P2pConnectionIceRestartAction::Init { peer_id });
+                } else {
+                    dispatcher.push(P2pDisconnectionAction::Init {
+                        peer_id,
+                        reason: P2pDisconnectionReason::IceRestartFailed,
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+}
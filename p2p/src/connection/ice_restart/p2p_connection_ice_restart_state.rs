@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use openmina_core::requests::RpcId;
+
+use crate::{Limit, P2pTimeouts};
+
+/// ICE restart state of the degraded webrtc connection.
+///
+/// Peer that initiated the connection is responsible for restarting ICE,
+/// since it knows the signaling path used to establish the connection.
+/// Other side answers the restart offers and waits for connectivity to
+/// be restored.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pConnectionIceRestartState {
+    /// Time when connectivity to the peer was lost.
+    pub degraded_since: Timestamp,
+    /// Number of ICE restart attempts made (or answered) so far.
+    pub attempts: usize,
+    pub status: P2pConnectionIceRestartStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum P2pConnectionIceRestartStatus {
+    /// Waiting for connectivity to be restored, either by itself or by
+    /// ICE restart initiated by the peer.
+    Idle { time: Timestamp },
+    /// ICE restart offer is being exchanged with the peer.
+    OfferPending { time: Timestamp },
+    /// ICE restart offer/answer exchange is done, waiting for connectivity.
+    Negotiated {
+        time: Timestamp,
+        rpc_id: Option<RpcId>,
+    },
+    /// Creating answer for the ICE restart offer received from the peer.
+    AnswerPending {
+        time: Timestamp,
+        rpc_id: Option<RpcId>,
+    },
+    /// ICE restart attempt failed.
+    Error { time: Timestamp, error: String },
+}
+
+impl P2pConnectionIceRestartState {
+    pub fn new(time: Timestamp) -> Self {
+        Self {
+            degraded_since: time,
+            attempts: 0,
+            status: P2pConnectionIceRestartStatus::Idle { time },
+        }
+    }
+
+    pub fn can_retry(&self, max_attempts: Limit<usize>) -> bool {
+        self.attempts < max_attempts
+    }
+
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self.status,
+            P2pConnectionIceRestartStatus::OfferPending { .. }
+                | P2pConnectionIceRestartStatus::AnswerPending { .. }
+        )
+    }
+
+    /// Initiator of the ICE restart times out on each attempt, while the
+    /// other side gives the initiator time to do all of its attempts.
+    pub fn is_timed_out(
+        &self,
+        now: Timestamp,
+        timeouts: &P2pTimeouts,
+        max_attempts: Limit<usize>,
+        is_initiator: bool,
+    ) -> bool {
+        let Some(timeout) = timeouts.ice_restart else {
+            return false;
+        };
+        if is_initiator {
+            return now
+                .checked_sub(self.status.time())
+                .is_some_and(|dur| dur >= timeout);
+        }
+        let Limit::Some(max_attempts) = max_attempts else {
+            return false;
+        };
+        let attempts = u32::try_from(max_attempts.saturating_add(1)).unwrap_or(u32::MAX);
+        let total = timeout.checked_mul(attempts).unwrap_or(Duration::MAX);
+        now.checked_sub(self.degraded_since)
+            .is_some_and(|dur| dur >= total)
+    }
+}
+
+impl P2pConnectionIceRestartStatus {
+    pub fn time(&self) -> Timestamp {
+        match self {
+            Self::Idle { time }
+            | Self::OfferPending { time }
+            | Self::Negotiated { time, .. }
+            | Self::AnswerPending { time, .. }
+            | Self::Error { time, .. } => *time,
+        }
+    }
+
+    pub fn rpc_id(&self) -> Option<RpcId> {
+        match self {
+            Self::AnswerPending { rpc_id, .. } | Self::Negotiated { rpc_id, .. } => *rpc_id,
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,4 @@
+mod p2p_connection_ice_restart_effectful_actions;
+pub use p2p_connection_ice_restart_effectful_actions::*;
+
+mod p2p_connection_ice_restart_effectful_effects;
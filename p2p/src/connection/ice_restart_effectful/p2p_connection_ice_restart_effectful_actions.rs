@@ -0,0 +1,27 @@
+use crate::{connection::P2pConnectionEffectfulAction, webrtc, P2pState, PeerId};
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(fields(display(peer_id)))]
+pub enum P2pConnectionIceRestartEffectfulAction {
+    /// Create ICE restart offer and send it to the peer.
+    Init { peer_id: PeerId },
+    /// Apply ICE restart offer received from the peer and create an answer.
+    OfferSet {
+        peer_id: PeerId,
+        offer: Box<webrtc::Offer>,
+    },
+}
+
+impl redux::EnablingCondition<P2pState> for P2pConnectionIceRestartEffectfulAction {
+    fn is_enabled(&self, _: &P2pState, _: redux::Timestamp) -> bool {
+        true
+    }
+}
+
+impl From<P2pConnectionIceRestartEffectfulAction> for crate::P2pEffectfulAction {
+    fn from(a: P2pConnectionIceRestartEffectfulAction) -> crate::P2pEffectfulAction {
+        crate::P2pEffectfulAction::Connection(P2pConnectionEffectfulAction::IceRestart(a))
+    }
+}
@@ -0,0 +1,30 @@
+use redux::ActionMeta;
+
+use super::P2pConnectionIceRestartEffectfulAction;
+use crate::connection::P2pConnectionService;
+
+impl P2pConnectionIceRestartEffectfulAction {
+    pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pConnectionService,
+    {
+        match self {
+            P2pConnectionIceRestartEffectfulAction::Init { peer_id } => {
+                if let Some(correlation_id) = store.service().ice_restart_init(peer_id) {
+                    correlation_id.log_issued(meta.time(), peer_id, "P2pConnectionIceRestartInit");
+                }
+            }
+            P2pConnectionIceRestartEffectfulAction::OfferSet { peer_id, offer } => {
+                if let Some(correlation_id) = store.service().ice_restart_offer_set(peer_id, *offer)
+                {
+                    correlation_id.log_issued(
+                        meta.time(),
+                        peer_id,
+                        "P2pConnectionIceRestartOfferSet",
+                    );
+                }
+            }
+        }
+    }
+}
@@ -22,7 +22,7 @@ pub struct P2pConnectionIncomingInitOpts {
 // TODO(binier): maybe move to `crate::webrtc`?
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy, MallocSizeOf)]
 pub enum IncomingSignalingMethod {
-    /// Http rpc is used for sending offer and getting answer as a response.
+    /// Http rpc or websocket is used for sending offer and getting answer as a response.
     Http,
     /// Intermediary/Relay peer is used for exchanging offer and answer messages.
     P2p { relay_peer_id: PeerId },
@@ -42,13 +42,13 @@ impl P2pState {
             return Err(RejectionReason::PeerIdAndPublicKeyMismatch);
         }
 
-        let my_peer_id = self.my_id();
+        let my_peer_id = self.my_webrtc_id();
 
         if offer.target_peer_id != my_peer_id {
             return Err(RejectionReason::TargetPeerIdNotMe);
         }
 
-        if peer_id == my_peer_id {
+        if self.is_my_id(&peer_id) {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
@@ -69,7 +69,7 @@ impl P2pState {
     }
 
     pub fn libp2p_incoming_accept(&self, peer_id: PeerId) -> Result<(), RejectionReason> {
-        if peer_id == self.my_id() {
+        if self.is_my_id(&peer_id) {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
@@ -2,8 +2,9 @@ use super::{
     P2pConnectionIncomingError, P2pConnectionIncomingInitOpts, P2pConnectionIncomingState,
 };
 use crate::{
-    connection::{P2pConnectionAction, P2pConnectionState},
-    webrtc, P2pAction, P2pPeerStatus, P2pState, PeerId,
+    connection::{P2pConnectionAction, P2pConnectionFinalizeError, P2pConnectionState},
+    webrtc::{self, RejectionReason},
+    P2pAction, P2pPeerStatus, P2pState, PeerId,
 };
 use openmina_core::{requests::RpcId, ActionEvent};
 use serde::{Deserialize, Serialize};
@@ -44,7 +45,7 @@ pub enum P2pConnectionIncomingAction {
     /// Error finalizing incoming connection.
     FinalizeError {
         peer_id: PeerId,
-        error: String,
+        error: P2pConnectionFinalizeError,
     },
     /// Incoming connection finalized.
     FinalizeSuccess {
@@ -75,6 +76,12 @@ pub enum P2pConnectionIncomingAction {
     Libp2pReceived {
         peer_id: PeerId,
     },
+    /// Incoming connection offer was rejected.
+    #[action_event(level = debug, fields(display(peer_id), display(reason)))]
+    Rejected {
+        peer_id: PeerId,
+        reason: RejectionReason,
+    },
 }
 
 impl P2pConnectionIncomingAction {
@@ -93,7 +100,8 @@ impl P2pConnectionIncomingAction {
             | Self::Error { peer_id, .. }
             | Self::Success { peer_id }
             | Self::FinalizePendingLibp2p { peer_id, .. }
-            | Self::Libp2pReceived { peer_id } => peer_id,
+            | Self::Libp2pReceived { peer_id }
+            | Self::Rejected { peer_id, .. } => peer_id,
         }
     }
 }
@@ -197,12 +205,12 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionIncomingAction {
                         P2pConnectionIncomingError::SdpCreateError(_) => {
                             matches!(s, P2pConnectionIncomingState::AnswerSdpCreatePending { .. })
                         }
+                        P2pConnectionIncomingError::FinalizeError(error) if error.is_bad_auth() => {
+                            matches!(s, P2pConnectionIncomingState::FinalizeSuccess { .. })
+                        }
                         P2pConnectionIncomingError::FinalizeError(_) => {
                             matches!(s, P2pConnectionIncomingState::FinalizePending { .. })
                         }
-                        P2pConnectionIncomingError::ConnectionAuthError => {
-                            matches!(s, P2pConnectionIncomingState::FinalizeSuccess { .. })
-                        }
                         P2pConnectionIncomingError::Timeout => true,
                     },
                     _ => false,
@@ -231,6 +239,7 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionIncomingAction {
                         )
                     })
             }
+            P2pConnectionIncomingAction::Rejected { .. } => true,
         }
     }
 }
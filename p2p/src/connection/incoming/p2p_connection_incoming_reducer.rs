@@ -10,7 +10,8 @@ use crate::{
         incoming::P2pConnectionIncomingError,
         incoming_effectful::P2pConnectionIncomingEffectfulAction,
         outgoing::{P2pConnectionOutgoingInitLibp2pOpts, P2pConnectionOutgoingInitOpts},
-        P2pConnectionResponse, P2pConnectionState,
+        P2pConnectionEventKind, P2pConnectionResponse, P2pConnectionState,
+        P2pHandshakeFailureReason,
     },
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
     webrtc::{Host, HttpSignalingInfo, SignalingMethod},
@@ -60,6 +61,9 @@ impl P2pConnectionIncomingState {
                         }),
                         status: P2pPeerStatus::Connecting(P2pConnectionState::incoming_init(&opts)),
                         identify: None,
+                        handshake_failures: Default::default(),
+                        dial_stats: Default::default(),
+                        rpc_versions: Default::default(),
                     });
 
                 state.status =
@@ -70,6 +74,14 @@ impl P2pConnectionIncomingState {
                         rpc_id,
                     }));
 
+                // Our answer announces fewer bulk channels if chunked rpcs are
+                // disabled, the service must open the same number as the peer.
+                let mut opts = opts;
+                opts.offer.bulk_channels = opts
+                    .offer
+                    .bulk_channels
+                    .min(p2p_state.feature_flags.bulk_channels());
+
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(P2pConnectionIncomingEffectfulAction::Init { opts });
                 Ok(())
@@ -135,10 +147,18 @@ impl P2pConnectionIncomingState {
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
+                let capabilities = crate::webrtc::P2pCapabilities::local(
+                    &p2p_state.config.enabled_channels,
+                    &p2p_state.feature_flags,
+                );
                 let answer = Box::new(crate::webrtc::Answer {
                     sdp,
-                    identity_pub_key: p2p_state.config.identity_pub_key.clone(),
+                    identity_pub_key: p2p_state.config.webrtc_identity_pub_key().clone(),
                     target_peer_id: peer_id,
+                    bulk_channels: capabilities.bulk_channels,
+                    tx_digests: capabilities.tx_digests,
+                    compact_blocks: capabilities.compact_blocks,
+                    capabilities: Some(capabilities),
                 });
                 dispatcher.push(P2pConnectionIncomingAction::AnswerReady { peer_id, answer });
                 Ok(())
@@ -267,7 +287,7 @@ impl P2pConnectionIncomingState {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(P2pConnectionIncomingAction::Error {
                     peer_id,
-                    error: P2pConnectionIncomingError::FinalizeError(error.to_owned()),
+                    error: P2pConnectionIncomingError::FinalizeError(error),
                 });
                 Ok(())
             }
@@ -349,11 +369,18 @@ impl P2pConnectionIncomingState {
 
                 let rpc_id = state.rpc_id();
                 let str_error = format!("{:?}", error);
+                let event = P2pConnectionEventKind::Error {
+                    incoming: true,
+                    error: error.to_string(),
+                };
                 *state = Self::Error {
                     time: meta.time(),
                     error,
                     rpc_id,
                 };
+                p2p_state
+                    .connection_events
+                    .push(meta.time(), peer_id, event);
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
@@ -428,6 +455,9 @@ impl P2pConnectionIncomingState {
                             )),
                             status: P2pPeerStatus::Disconnected { time: meta.time() },
                             identify: None,
+                            handshake_failures: Default::default(),
+                            dial_stats: Default::default(),
+                            rpc_versions: Default::default(),
                         });
 
                     Self::reduce_finalize_libp2p_pending(state, addr, time, my_id, peer_id);
@@ -466,6 +496,14 @@ impl P2pConnectionIncomingState {
                 }
                 Ok(())
             }
+            P2pConnectionIncomingAction::Rejected { reason, .. } => {
+                p2p_state.handshake_failure_record(
+                    time,
+                    Some(peer_id),
+                    P2pHandshakeFailureReason::from_rejection(&reason),
+                );
+                Ok(())
+            }
         }
     }
 
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use openmina_core::requests::RpcId;
 
-use crate::{webrtc, P2pTimeouts};
+use crate::{connection::P2pConnectionFinalizeError, webrtc, P2pTimeouts};
 
 use super::IncomingSignalingMethod;
 
@@ -140,9 +140,7 @@ pub enum P2pConnectionIncomingError {
     #[error("error creating SDP: {0}")]
     SdpCreateError(String),
     #[error("finalization error: {0}")]
-    FinalizeError(String),
-    #[error("connection authentication failed")]
-    ConnectionAuthError,
+    FinalizeError(P2pConnectionFinalizeError),
     #[error("timeout error")]
     Timeout,
 }
@@ -3,11 +3,11 @@ use redux::ActionMeta;
 use super::P2pConnectionIncomingEffectfulAction;
 use crate::connection::{
     incoming::{P2pConnectionIncomingAction, P2pConnectionIncomingError},
-    P2pConnectionService,
+    P2pConnectionFinalizeError, P2pConnectionService,
 };
 
 impl P2pConnectionIncomingEffectfulAction {
-    pub fn effects<Store, S>(self, _meta: &ActionMeta, store: &mut Store)
+    pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store)
     where
         Store: crate::P2pStore<S>,
         Store::Service: P2pConnectionService,
@@ -15,7 +15,10 @@ impl P2pConnectionIncomingEffectfulAction {
         match self {
             P2pConnectionIncomingEffectfulAction::Init { opts } => {
                 let peer_id = opts.peer_id;
-                store.service().incoming_init(peer_id, *opts.offer);
+                store
+                    .service()
+                    .incoming_init(peer_id, *opts.offer)
+                    .log_issued(meta.time(), peer_id, "P2pConnectionIncomingInit");
                 store.dispatch(P2pConnectionIncomingAction::AnswerSdpCreatePending { peer_id });
             }
             P2pConnectionIncomingEffectfulAction::ConnectionAuthorizationEncryptAndSend {
@@ -33,18 +36,18 @@ impl P2pConnectionIncomingEffectfulAction {
                 expected_auth,
                 auth,
             } => {
-                if store
-                    .service()
-                    .auth_decrypt(&other_pub_key, auth)
-                    .is_some_and(|remote_auth| remote_auth == expected_auth)
-                {
-                    store.dispatch(P2pConnectionIncomingAction::Success { peer_id });
-                } else {
-                    store.dispatch(P2pConnectionIncomingAction::Error {
-                        peer_id,
-                        error: P2pConnectionIncomingError::ConnectionAuthError,
-                    });
-                }
+                let error = match store.service().auth_decrypt(&other_pub_key, auth) {
+                    Some(remote_auth) if remote_auth == expected_auth => {
+                        store.dispatch(P2pConnectionIncomingAction::Success { peer_id });
+                        return;
+                    }
+                    Some(_) => P2pConnectionFinalizeError::AuthIdentityMismatch,
+                    None => P2pConnectionFinalizeError::AuthDecryptFailed,
+                };
+                store.dispatch(P2pConnectionIncomingAction::Error {
+                    peer_id,
+                    error: P2pConnectionIncomingError::FinalizeError(error),
+                });
             }
         }
     }
@@ -1,6 +1,8 @@
+pub mod ice_restart;
 pub mod incoming;
 pub mod outgoing;
 
+pub mod ice_restart_effectful;
 pub mod incoming_effectful;
 pub mod outgoing_effectful;
 
@@ -12,9 +14,22 @@ pub use p2p_connection_actions::*;
 
 mod p2p_connection_reducer;
 
+mod p2p_connection_handshake_failure;
+pub use p2p_connection_handshake_failure::*;
+
+mod p2p_connection_dial_stats;
+pub use p2p_connection_dial_stats::*;
+
+mod p2p_connection_events;
+pub use p2p_connection_events::*;
+
+mod p2p_connection_resumption;
+pub use p2p_connection_resumption::*;
+
 mod p2p_connection_service;
 pub use p2p_connection_service::*;
 
+use malloc_size_of_derive::MallocSizeOf;
 use serde::{Deserialize, Serialize};
 
 pub use crate::webrtc::{Answer, Offer, P2pConnectionResponse, RejectionReason};
@@ -28,3 +43,24 @@ pub enum P2pConnectionErrorResponse {
     #[error("internal error")]
     InternalError,
 }
+
+/// Why the webrtc connection failed to finalize.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, thiserror::Error, MallocSizeOf)]
+pub enum P2pConnectionFinalizeError {
+    #[error("connection failed: {0}")]
+    ConnectionFailed(String),
+    #[error("didn't receive connection authentication message in time")]
+    AuthTimeout,
+    #[error("failed to decrypt connection authentication message")]
+    AuthDecryptFailed,
+    #[error("connection authentication doesn't match peer identity")]
+    AuthIdentityMismatch,
+}
+
+impl P2pConnectionFinalizeError {
+    /// Whether the peer failed to prove its identity, as opposed to the
+    /// connection itself failing.
+    pub fn is_bad_auth(&self) -> bool {
+        matches!(self, Self::AuthDecryptFailed | Self::AuthIdentityMismatch)
+    }
+}
@@ -21,7 +21,7 @@ use mina_p2p_messages::v2;
 
 use crate::{
     webrtc::{self, Host},
-    PeerId,
+    P2pIpFamily, PeerId,
 };
 
 #[cfg(feature = "p2p-libp2p")]
@@ -48,12 +48,22 @@ pub struct P2pConnectionOutgoingInitLibp2pOpts {
 
 impl P2pConnectionOutgoingInitLibp2pOpts {
     /// If the current host is local and there is a better host among the `addrs`,
-    /// replace the current one with the better one.
-    pub fn update_host_if_needed<'a>(&mut self, mut addrs: impl Iterator<Item = &'a Multiaddr>) {
+    /// replace the current one with the better one, of the `preferred` family
+    /// if there is such.
+    pub fn update_host_if_needed<'a>(
+        &mut self,
+        addrs: impl Iterator<Item = &'a Multiaddr>,
+        preferred: Option<P2pIpFamily>,
+    ) {
         fn is_local(ip: impl Into<IpAddr>) -> bool {
             match ip.into() {
-                IpAddr::V4(ip) => ip.is_loopback() || ip.is_private(),
-                IpAddr::V6(ip) => ip.is_loopback(),
+                IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+                // loopback, unique local and link local
+                IpAddr::V6(ip) => {
+                    ip.is_loopback()
+                        || ip.segments()[0] & 0xfe00 == 0xfc00
+                        || ip.segments()[0] & 0xffc0 == 0xfe80
+                }
             }
         }
 
@@ -65,18 +75,26 @@ impl P2pConnectionOutgoingInitLibp2pOpts {
         };
         if update {
             // if new options is better
-            let new = addrs.find_map(|x| {
-                x.iter().find_map(|x| match x {
-                    Protocol::Dns4(hostname) | Protocol::Dns6(hostname) => {
-                        Some(Host::Domain(hostname.into_owned()))
-                    }
-                    Protocol::Ip4(ip) if !is_local(ip) => Some(Host::Ipv4(ip)),
-                    Protocol::Ip6(ip) if !is_local(ip) => Some(Host::Ipv6(ip)),
-                    _ => None,
+            let candidates = addrs
+                .filter_map(|x| {
+                    x.iter().find_map(|x| match x {
+                        Protocol::Dns4(hostname) | Protocol::Dns6(hostname) => {
+                            Some(Host::Domain(hostname.into_owned()))
+                        }
+                        Protocol::Ip4(ip) if !is_local(ip) => Some(Host::Ipv4(ip)),
+                        Protocol::Ip6(ip) if !is_local(ip) => Some(Host::Ipv6(ip)),
+                        _ => None,
+                    })
                 })
-            });
+                .collect::<Vec<_>>();
+            let is_preferred = |host: &&Host| match (host, preferred) {
+                (_, None) | (Host::Domain(_), _) => true,
+                (Host::Ipv4(ip), Some(family)) => family.includes(&(*ip).into()),
+                (Host::Ipv6(ip), Some(family)) => family.includes(&(*ip).into()),
+            };
+            let new = candidates.iter().find(is_preferred).or(candidates.first());
             if let Some(new) = new {
-                self.host = new;
+                self.host = new.clone();
             }
         }
     }
@@ -289,7 +307,9 @@ impl P2pConnectionOutgoingInitOpts {
                         ),
                     })
                 }
-                SignalingMethod::P2p { .. } => None,
+                SignalingMethod::P2p { .. } | SignalingMethod::Wss(_) | SignalingMethod::Ws(_) => {
+                    None
+                }
             },
         }
     }
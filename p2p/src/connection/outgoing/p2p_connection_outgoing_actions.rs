@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use openmina_core::requests::RpcId;
 
-use crate::connection::P2pConnectionErrorResponse;
+use crate::connection::{P2pConnectionErrorResponse, P2pConnectionFinalizeError};
 use crate::{webrtc, P2pState, PeerId};
 
 use super::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts};
@@ -66,7 +66,7 @@ pub enum P2pConnectionOutgoingAction {
     #[action_event(level = debug)]
     FinalizeError {
         peer_id: PeerId,
-        error: String,
+        error: P2pConnectionFinalizeError,
     },
     /// Outgoing connection successfully finalized.
     FinalizeSuccess {
@@ -95,7 +95,7 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
             P2pConnectionOutgoingAction::RandomInit =>  !state.already_has_min_peers() && state.disconnected_peers().next().is_some(),
             P2pConnectionOutgoingAction::Init { opts, .. } => {
                 !state.already_has_min_peers() &&
-                &state.my_id() != opts.peer_id() &&
+                !state.is_my_id(opts.peer_id()) &&
                 state
                     .peers
                     .get(opts.peer_id())
@@ -194,12 +194,12 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingAction {
                         | P2pConnectionOutgoingError::RemoteInternalError => {
                             matches!(s, P2pConnectionOutgoingState::AnswerRecvPending { .. })
                         }
+                        P2pConnectionOutgoingError::FinalizeError(error) if error.is_bad_auth() => {
+                            matches!(s, P2pConnectionOutgoingState::FinalizeSuccess { .. })
+                        }
                         P2pConnectionOutgoingError::FinalizeError(_) => {
                             matches!(s, P2pConnectionOutgoingState::FinalizePending { .. })
                         }
-                        P2pConnectionOutgoingError::ConnectionAuthError => {
-                            matches!(s, P2pConnectionOutgoingState::FinalizeSuccess { .. })
-                        }
                         P2pConnectionOutgoingError::Timeout => true,
                     },
                     _ => false,
@@ -7,7 +7,7 @@ use crate::{
     channels::signaling::discovery::P2pChannelsSignalingDiscoveryAction,
     connection::{
         outgoing_effectful::P2pConnectionOutgoingEffectfulAction, P2pConnectionErrorResponse,
-        P2pConnectionState,
+        P2pConnectionEventKind, P2pConnectionState, P2pHandshakeFailureReason,
     },
     disconnection::P2pDisconnectionAction,
     webrtc::Host,
@@ -39,7 +39,12 @@ impl P2pConnectionOutgoingState {
             P2pConnectionOutgoingAction::RandomInit => {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
-                let peers = p2p_state.disconnected_peers().collect::<Vec<_>>();
+                let peers = p2p_state
+                    .peers
+                    .values()
+                    .filter(|peer| matches!(peer.status, P2pPeerStatus::Disconnected { .. }))
+                    .filter_map(|peer| Some((peer.dial_opts.clone()?, peer.dial_stats.weight())))
+                    .collect::<Vec<_>>();
                 dispatcher.push(P2pConnectionOutgoingEffectfulAction::RandomInit { peers });
                 Ok(())
             }
@@ -59,6 +64,9 @@ impl P2pConnectionOutgoingState {
                                 &opts,
                             )),
                             identify: None,
+                            handshake_failures: Default::default(),
+                            dial_stats: Default::default(),
+                            rpc_versions: Default::default(),
                         });
 
                 peer_state.status =
@@ -68,6 +76,10 @@ impl P2pConnectionOutgoingState {
                         rpc_id,
                         on_success,
                     }));
+                peer_state.dial_stats.dial_start(time);
+                if p2p_state.preferred_peer(opts.peer_id()).is_some() {
+                    p2p_state.session_resumption.attempt(time);
+                }
 
                 let dispatcher = state_context.into_dispatcher();
 
@@ -101,6 +113,7 @@ impl P2pConnectionOutgoingState {
                     .get_mut(opts.peer_id())
                     .ok_or("Missing peer state for: `P2pConnectionOutgoingAction::Reconnect`")?;
 
+                peer_state.dial_stats.dial_start(time);
                 peer_state.status =
                     P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(Self::Init {
                         time,
@@ -192,14 +205,22 @@ impl P2pConnectionOutgoingState {
                     return Ok(());
                 }
 
+                let capabilities = crate::webrtc::P2pCapabilities::local(
+                    &p2p_state.config.enabled_channels,
+                    &p2p_state.feature_flags,
+                );
                 let offer = Box::new(crate::webrtc::Offer {
                     sdp,
                     chain_id,
-                    identity_pub_key: p2p_state.config.identity_pub_key.clone(),
+                    identity_pub_key: p2p_state.config.webrtc_identity_pub_key().clone(),
                     target_peer_id: peer_id,
                     // TODO(vlad9486): put real address
                     host: Host::Ipv4([127, 0, 0, 1].into()),
                     listen_port: p2p_state.config.listen_port,
+                    bulk_channels: capabilities.bulk_channels,
+                    tx_digests: capabilities.tx_digests,
+                    compact_blocks: capabilities.compact_blocks,
+                    capabilities: Some(capabilities),
                 });
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(P2pConnectionOutgoingAction::OfferReady { peer_id, offer });
@@ -332,6 +353,7 @@ impl P2pConnectionOutgoingState {
                 Ok(())
             }
             P2pConnectionOutgoingAction::AnswerRecvSuccess { answer, peer_id } => {
+                let bulk_channels = p2p_state.feature_flags.bulk_channels();
                 let state = p2p_state.outgoing_peer_connection_mut(&peer_id).ok_or(
                     "Missing peer connection for `P2pConnectionOutgoingAction::AnswerRecvSuccess`",
                 )?;
@@ -359,6 +381,10 @@ impl P2pConnectionOutgoingState {
                     );
                 }
 
+                // Peer may answer with more bulk channels than we offered.
+                let mut answer = answer;
+                answer.bulk_channels = answer.bulk_channels.min(bulk_channels);
+
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher
                     .push(P2pConnectionOutgoingEffectfulAction::AnswerSet { peer_id, answer });
@@ -428,7 +454,7 @@ impl P2pConnectionOutgoingState {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(P2pConnectionOutgoingAction::Error {
                     peer_id,
-                    error: P2pConnectionOutgoingError::FinalizeError(error.to_owned()),
+                    error: P2pConnectionOutgoingError::FinalizeError(error),
                 });
                 Ok(())
             }
@@ -511,6 +537,22 @@ impl P2pConnectionOutgoingState {
                     error: error.clone(),
                     rpc_id,
                 };
+                p2p_state.handshake_failure_record(
+                    time,
+                    Some(peer_id),
+                    P2pHandshakeFailureReason::from_outgoing_error(&error),
+                );
+                if let Some(peer_state) = p2p_state.peers.get_mut(&peer_id) {
+                    peer_state.dial_stats.record_failure();
+                }
+                p2p_state.connection_events.push(
+                    time,
+                    peer_id,
+                    P2pConnectionEventKind::Error {
+                        incoming: false,
+                        error: error.to_string(),
+                    },
+                );
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
@@ -568,6 +610,9 @@ impl P2pConnectionOutgoingState {
                     answer: answer.clone(),
                     rpc_id: rpc_id.take(),
                 };
+                if let Some(peer_state) = p2p_state.peers.get_mut(&peer_id) {
+                    peer_state.dial_stats.record_success(time);
+                }
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
@@ -4,7 +4,10 @@ use serde::{Deserialize, Serialize};
 
 use openmina_core::requests::RpcId;
 
-use crate::{connection::RejectionReason, webrtc, P2pTimeouts, PeerId};
+use crate::{
+    connection::{P2pConnectionFinalizeError, RejectionReason},
+    webrtc, P2pTimeouts, PeerId,
+};
 
 use super::P2pConnectionOutgoingInitOpts;
 
@@ -160,9 +163,7 @@ pub enum P2pConnectionOutgoingError {
     #[error("remote internal error")]
     RemoteInternalError,
     #[error("finalization error: {0}")]
-    FinalizeError(String),
-    #[error("connection authorization error")]
-    ConnectionAuthError,
+    FinalizeError(P2pConnectionFinalizeError),
     #[error("timeout error")]
     Timeout,
 }
@@ -13,10 +13,11 @@ use crate::{
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(fields(display(opts), display(peer_id), display(error)))]
 pub enum P2pConnectionOutgoingEffectfulAction {
-    /// Initialize connection to a random peer.
+    /// Initialize connection to a random peer, picked with the
+    /// probability proportional to its weight.
     #[action_event(level = trace)]
     RandomInit {
-        peers: Vec<P2pConnectionOutgoingInitOpts>,
+        peers: Vec<(P2pConnectionOutgoingInitOpts, f64)>,
     },
     /// Initialize connection to a new peer.
     #[action_event(level = info)]
@@ -4,7 +4,7 @@ use redux::ActionMeta;
 use crate::{
     connection::{
         outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingError},
-        P2pConnectionService,
+        P2pConnectionFinalizeError, P2pConnectionService,
     },
     webrtc,
 };
@@ -12,7 +12,7 @@ use crate::{
 use super::P2pConnectionOutgoingEffectfulAction;
 
 impl P2pConnectionOutgoingEffectfulAction {
-    pub fn effects<Store, S>(self, _meta: &ActionMeta, store: &mut Store)
+    pub fn effects<Store, S>(self, meta: &ActionMeta, store: &mut Store)
     where
         Store: crate::P2pStore<S>,
         Store::Service: P2pConnectionService,
@@ -31,7 +31,9 @@ impl P2pConnectionOutgoingEffectfulAction {
             }
             P2pConnectionOutgoingEffectfulAction::Init { opts, .. } => {
                 let peer_id = *opts.peer_id();
-                store.service().outgoing_init(opts);
+                if let Some(correlation_id) = store.service().outgoing_init(opts) {
+                    correlation_id.log_issued(meta.time(), peer_id, "P2pConnectionOutgoingInit");
+                }
                 store.dispatch(P2pConnectionOutgoingAction::OfferSdpCreatePending { peer_id });
             }
             P2pConnectionOutgoingEffectfulAction::OfferSend {
@@ -42,11 +44,18 @@ impl P2pConnectionOutgoingEffectfulAction {
                 match signaling_method {
                     webrtc::SignalingMethod::Http(_)
                     | webrtc::SignalingMethod::Https(_)
-                    | webrtc::SignalingMethod::HttpsProxy(_, _) => {
-                        let Some(url) = signaling_method.http_url() else {
-                            return;
-                        };
-                        store.service().http_signaling_request(url, *offer);
+                    | webrtc::SignalingMethod::HttpsProxy(_, _)
+                    | webrtc::SignalingMethod::Wss(_)
+                    | webrtc::SignalingMethod::Ws(_) => {
+                        if let Some(correlation_id) =
+                            store.service().signaling_request(signaling_method, *offer)
+                        {
+                            correlation_id.log_issued(
+                                meta.time(),
+                                peer_id,
+                                "P2pConnectionOutgoingOfferSend",
+                            );
+                        }
                     }
                     webrtc::SignalingMethod::P2p { .. } => {
                         bug_condition!("`P2pConnectionOutgoingEffectfulAction::OfferSend` shouldn't be called for `webrtc::SignalingMethod::P2p`");
@@ -56,7 +65,13 @@ impl P2pConnectionOutgoingEffectfulAction {
                 store.dispatch(P2pConnectionOutgoingAction::OfferSendSuccess { peer_id });
             }
             P2pConnectionOutgoingEffectfulAction::AnswerSet { peer_id, answer } => {
-                store.service().set_answer(peer_id, *answer);
+                if let Some(correlation_id) = store.service().set_answer(peer_id, *answer) {
+                    correlation_id.log_issued(
+                        meta.time(),
+                        peer_id,
+                        "P2pConnectionOutgoingAnswerSet",
+                    );
+                }
                 store.dispatch(P2pConnectionOutgoingAction::FinalizePending { peer_id });
             }
             P2pConnectionOutgoingEffectfulAction::ConnectionAuthorizationEncryptAndSend {
@@ -74,18 +89,18 @@ impl P2pConnectionOutgoingEffectfulAction {
                 expected_auth,
                 auth,
             } => {
-                if store
-                    .service()
-                    .auth_decrypt(&other_pub_key, auth)
-                    .is_some_and(|remote_auth| remote_auth == expected_auth)
-                {
-                    store.dispatch(P2pConnectionOutgoingAction::Success { peer_id });
-                } else {
-                    store.dispatch(P2pConnectionOutgoingAction::Error {
-                        peer_id,
-                        error: P2pConnectionOutgoingError::ConnectionAuthError,
-                    });
-                }
+                let error = match store.service().auth_decrypt(&other_pub_key, auth) {
+                    Some(remote_auth) if remote_auth == expected_auth => {
+                        store.dispatch(P2pConnectionOutgoingAction::Success { peer_id });
+                        return;
+                    }
+                    Some(_) => P2pConnectionFinalizeError::AuthIdentityMismatch,
+                    None => P2pConnectionFinalizeError::AuthDecryptFailed,
+                };
+                store.dispatch(P2pConnectionOutgoingAction::Error {
+                    peer_id,
+                    error: P2pConnectionOutgoingError::FinalizeError(error),
+                });
             }
         }
     }
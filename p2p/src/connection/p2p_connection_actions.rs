@@ -1,4 +1,6 @@
 use super::{
+    ice_restart::P2pConnectionIceRestartAction,
+    ice_restart_effectful::P2pConnectionIceRestartEffectfulAction,
     incoming::P2pConnectionIncomingAction,
     incoming_effectful::P2pConnectionIncomingEffectfulAction,
     outgoing::P2pConnectionOutgoingAction,
@@ -11,12 +13,14 @@ use serde::{Deserialize, Serialize};
 pub enum P2pConnectionAction {
     Outgoing(P2pConnectionOutgoingAction),
     Incoming(P2pConnectionIncomingAction),
+    IceRestart(P2pConnectionIceRestartAction),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 pub enum P2pConnectionEffectfulAction {
     Outgoing(P2pConnectionOutgoingEffectfulAction),
     Incoming(P2pConnectionIncomingEffectfulAction),
+    IceRestart(P2pConnectionIceRestartEffectfulAction),
 }
 
 impl redux::EnablingCondition<crate::P2pState> for P2pConnectionAction {
@@ -24,6 +28,7 @@ impl redux::EnablingCondition<crate::P2pState> for P2pConnectionAction {
         match self {
             P2pConnectionAction::Outgoing(a) => a.is_enabled(state, time),
             P2pConnectionAction::Incoming(a) => a.is_enabled(state, time),
+            P2pConnectionAction::IceRestart(a) => a.is_enabled(state, time),
         }
     }
 }
@@ -33,6 +38,7 @@ impl redux::EnablingCondition<crate::P2pState> for P2pConnectionEffectfulAction
         match self {
             P2pConnectionEffectfulAction::Outgoing(a) => a.is_enabled(state, time),
             P2pConnectionEffectfulAction::Incoming(a) => a.is_enabled(state, time),
+            P2pConnectionEffectfulAction::IceRestart(a) => a.is_enabled(state, time),
         }
     }
 }
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use malloc_size_of_derive::MallocSizeOf;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::outgoing::P2pConnectionOutgoingInitOpts;
+
+/// Latency assumed for the peers we haven't connected to yet.
+const UNKNOWN_DIAL_LATENCY: Duration = Duration::from_secs(1);
+
+/// Outcomes of the outgoing connections to the peer, used to prefer the
+/// peers which we can connect to quickly and reliably.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, MallocSizeOf)]
+pub struct P2pPeerDialStats {
+    pub successes: u32,
+    pub failures: u32,
+    /// Moving average of the time it took to connect.
+    #[ignore_malloc_size_of = "doesn't allocate"]
+    pub latency: Option<Duration>,
+    /// When the current outgoing connection attempt started.
+    #[ignore_malloc_size_of = "doesn't allocate"]
+    pub dial_started: Option<redux::Timestamp>,
+}
+
+impl P2pPeerDialStats {
+    pub fn dial_start(&mut self, time: redux::Timestamp) {
+        self.dial_started = Some(time);
+    }
+
+    pub fn record_success(&mut self, time: redux::Timestamp) {
+        self.successes = self.successes.saturating_add(1);
+        let Some(latency) = self
+            .dial_started
+            .take()
+            .and_then(|started| time.checked_sub(started))
+        else {
+            return;
+        };
+        self.latency = Some(match self.latency {
+            Some(prev) => (prev * 3 + latency) / 4,
+            None => latency,
+        });
+    }
+
+    pub fn record_failure(&mut self) {
+        self.failures = self.failures.saturating_add(1);
+        self.dial_started = None;
+    }
+
+    /// Relative chance of the peer to be picked for the outgoing
+    /// connection. Success rate is smoothed, so that the peers which we
+    /// haven't tried yet, or which failed before, still get explored.
+    pub fn weight(&self) -> f64 {
+        let attempts = self.successes as f64 + self.failures as f64;
+        let success_rate = (self.successes as f64 + 1.0) / (attempts + 2.0);
+        let latency = self.latency.unwrap_or(UNKNOWN_DIAL_LATENCY);
+        success_rate / (1.0 + latency.as_secs_f64())
+    }
+}
+
+/// Picks one of the peers randomly, with the probability proportional
+/// to its weight. Falls back to the uniform pick if the weights are
+/// invalid.
+pub fn weighted_random_pick<R: Rng + ?Sized>(
+    rng: &mut R,
+    list: &[(P2pConnectionOutgoingInitOpts, f64)],
+) -> Option<P2pConnectionOutgoingInitOpts> {
+    list.choose_weighted(rng, |(_, weight)| *weight)
+        .or_else(|_| list.choose(rng).ok_or(()))
+        .ok()
+        .map(|(opts, _)| opts.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weight_prefers_fast_and_reliable() {
+        let unknown = P2pPeerDialStats::default();
+
+        let mut fast = P2pPeerDialStats::default();
+        for i in 0..5 {
+            fast.dial_start(redux::Timestamp::new(i * 10_000_000_000));
+            fast.record_success(redux::Timestamp::new(i * 10_000_000_000 + 100_000_000));
+        }
+        assert_eq!(fast.latency, Some(Duration::from_millis(100)));
+
+        let mut failing = P2pPeerDialStats::default();
+        for _ in 0..5 {
+            failing.record_failure();
+        }
+
+        assert!(fast.weight() > unknown.weight());
+        assert!(unknown.weight() > failing.weight());
+        assert!(failing.weight() > 0.0);
+    }
+}
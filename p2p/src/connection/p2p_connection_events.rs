@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::PeerId;
+
+/// Number of the latest connection events which are kept.
+pub const P2P_CONNECTION_EVENTS_MAX: usize = 256;
+
+/// Milestone in the lifetime of the connection with the peer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pConnectionEvent {
+    pub time: redux::Timestamp,
+    pub peer_id: PeerId,
+    #[serde(flatten)]
+    pub kind: P2pConnectionEventKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum P2pConnectionEventKind {
+    Connected { incoming: bool },
+    Error { incoming: bool, error: String },
+    Disconnected { reason: String },
+}
+
+/// Latest connection events with all the peers, oldest first.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pConnectionEvents(VecDeque<P2pConnectionEvent>);
+
+impl P2pConnectionEvents {
+    pub fn push(&mut self, time: redux::Timestamp, peer_id: PeerId, kind: P2pConnectionEventKind) {
+        if self.0.len() >= P2P_CONNECTION_EVENTS_MAX {
+            self.0.pop_front();
+        }
+        self.0.push_back(P2pConnectionEvent {
+            time,
+            peer_id,
+            kind,
+        });
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &P2pConnectionEvent> {
+        self.0.iter()
+    }
+
+    /// Latest `limit` connection errors, newest first.
+    pub fn errors(&self, limit: usize) -> impl Iterator<Item = &P2pConnectionEvent> {
+        self.iter()
+            .rev()
+            .filter(|event| matches!(event.kind, P2pConnectionEventKind::Error { .. }))
+            .take(limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_latest_events_and_errors() {
+        let peer_id = PeerId::from_bytes([1u8; 32]);
+        let mut events = P2pConnectionEvents::default();
+        for i in 0..P2P_CONNECTION_EVENTS_MAX as u64 + 10 {
+            let kind = if i % 2 == 0 {
+                P2pConnectionEventKind::Connected { incoming: false }
+            } else {
+                P2pConnectionEventKind::Error {
+                    incoming: false,
+                    error: i.to_string(),
+                }
+            };
+            events.push(redux::Timestamp::new(i), peer_id, kind);
+        }
+
+        assert_eq!(events.iter().count(), P2P_CONNECTION_EVENTS_MAX);
+        assert_eq!(
+            events.iter().next().map(|event| event.time),
+            Some(redux::Timestamp::new(10))
+        );
+        let errors = events
+            .errors(2)
+            .map(|event| match &event.kind {
+                P2pConnectionEventKind::Error { error, .. } => error.as_str(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(errors, ["265", "263"]);
+    }
+}
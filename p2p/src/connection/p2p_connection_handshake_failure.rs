@@ -0,0 +1,267 @@
+use malloc_size_of_derive::MallocSizeOf;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "p2p-libp2p")]
+use crate::{P2pNetworkAction, P2pNetworkSchedulerAction, P2pState, PeerId};
+use crate::{P2pNetworkConnectionError, P2pNetworkConnectionState, SelectKind};
+
+use super::{outgoing::P2pConnectionOutgoingError, RejectionReason};
+
+/// Why the handshake with the peer failed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum P2pHandshakeFailureReason {
+    /// Peer is on a different network. For libp2p, the preshared key is
+    /// derived from the chain id, so mismatch makes the traffic unreadable
+    /// right from the start of the protocol negotiation.
+    ///
+    /// Peers which time out or close the connection before the
+    /// negotiation aren't counted, they might as well be overloaded.
+    #[display(fmt = "chain id mismatch")]
+    ChainIdMismatch,
+    /// Peer doesn't support any of our protocols.
+    #[display(fmt = "incompatible version")]
+    IncompatibleVersion,
+    /// Peer failed to prove its identity.
+    #[display(fmt = "bad auth")]
+    BadAuth,
+}
+
+/// Number of handshake failures per reason.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, MallocSizeOf)]
+pub struct P2pHandshakeFailureStats {
+    pub chain_id_mismatch: u64,
+    pub incompatible_version: u64,
+    pub bad_auth: u64,
+}
+
+impl P2pHandshakeFailureReason {
+    pub fn from_rejection(reason: &RejectionReason) -> Option<Self> {
+        match reason {
+            RejectionReason::ChainIdMismatch => Some(Self::ChainIdMismatch),
+            RejectionReason::PeerIdAndPublicKeyMismatch => Some(Self::BadAuth),
+            _ => None,
+        }
+    }
+
+    pub fn from_outgoing_error(error: &P2pConnectionOutgoingError) -> Option<Self> {
+        match error {
+            P2pConnectionOutgoingError::Rejected(reason) => Self::from_rejection(reason),
+            P2pConnectionOutgoingError::RemoteSignalDecryptionFailed => Some(Self::BadAuth),
+            P2pConnectionOutgoingError::FinalizeError(error) if error.is_bad_auth() => {
+                Some(Self::BadAuth)
+            }
+            _ => None,
+        }
+    }
+
+    /// Classifies protocol negotiation error on the libp2p connection.
+    pub fn from_select_error(kind: &SelectKind, error: &str) -> Option<Self> {
+        match kind {
+            // Timeout doesn't tell anything about the peer's network.
+            _ if error == "timeout" => None,
+            // Wrong preshared key turns the first negotiation into garbage.
+            SelectKind::Authentication if error == "parse_token" => Some(Self::ChainIdMismatch),
+            SelectKind::Authentication
+            | SelectKind::MultiplexingNoPeerId
+            | SelectKind::Multiplexing(_) => Some(Self::IncompatibleVersion),
+            SelectKind::Stream(..) => None,
+        }
+    }
+
+    /// Classifies fatal error on the libp2p connection which hasn't
+    /// finished the handshake yet.
+    pub fn from_connection_error(
+        connection: &P2pNetworkConnectionState,
+        error: &P2pNetworkConnectionError,
+    ) -> Option<Self> {
+        if connection.mux.is_some() {
+            return None;
+        }
+        match error {
+            P2pNetworkConnectionError::Noise(_) => Some(Self::BadAuth),
+            _ => None,
+        }
+    }
+
+    /// Classifies libp2p handshake failure, which is reported by the
+    /// network action. Returns the peer, if it is known at this point.
+    #[cfg(feature = "p2p-libp2p")]
+    pub fn from_network_action(
+        state: &P2pState,
+        action: &P2pNetworkAction,
+    ) -> Option<(Option<PeerId>, Self)> {
+        let P2pNetworkAction::Scheduler(action) = action else {
+            return None;
+        };
+        let addr = match action {
+            P2pNetworkSchedulerAction::SelectError { addr, .. }
+            | P2pNetworkSchedulerAction::Error { addr, .. } => addr,
+            _ => return None,
+        };
+        let connection = state.network.scheduler.connections.get(addr)?;
+        // Only the failure closing the connection is counted, the data
+        // still in flight fails the negotiation again, and the failed
+        // negotiation is reported as the connection error too.
+        if connection.closed.is_some() {
+            return None;
+        }
+        let reason = match action {
+            P2pNetworkSchedulerAction::SelectError { kind, error, .. } => {
+                Self::from_select_error(kind, error)?
+            }
+            P2pNetworkSchedulerAction::Error { error, .. } => {
+                Self::from_connection_error(connection, error)?
+            }
+            _ => return None,
+        };
+        let peer_id = state
+            .peer_with_connection(*addr)
+            .map(|(peer_id, _)| peer_id);
+        Some((peer_id, reason))
+    }
+}
+
+impl P2pHandshakeFailureStats {
+    pub fn record(&mut self, reason: P2pHandshakeFailureReason) {
+        let counter = match reason {
+            P2pHandshakeFailureReason::ChainIdMismatch => &mut self.chain_id_mismatch,
+            P2pHandshakeFailureReason::IncompatibleVersion => &mut self.incompatible_version,
+            P2pHandshakeFailureReason::BadAuth => &mut self.bad_auth,
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    pub fn total(&self) -> u64 {
+        self.chain_id_mismatch
+            .saturating_add(self.incompatible_version)
+            .saturating_add(self.bad_auth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{
+        connection::P2pConnectionFinalizeError, identity::SecretKey, webrtc::RejectionReason,
+        NoiseError, P2pNetworkPnetState, P2pNetworkSelectState,
+    };
+
+    fn connection() -> P2pNetworkConnectionState {
+        P2pNetworkConnectionState {
+            incoming: false,
+            pnet: P2pNetworkPnetState::new([0; 32], redux::Timestamp::ZERO),
+            select_auth: P2pNetworkSelectState::default(),
+            auth: None,
+            select_mux: P2pNetworkSelectState::default(),
+            mux: None,
+            streams: BTreeMap::default(),
+            closed: None,
+            limit: P2pNetworkConnectionState::INITIAL_LIMIT,
+        }
+    }
+
+    #[test]
+    fn select_error_classification() {
+        use P2pHandshakeFailureReason::*;
+
+        let peer_id = SecretKey::rand().public_key().peer_id();
+        let cases = [
+            (
+                SelectKind::Authentication,
+                "parse_token",
+                Some(ChainIdMismatch),
+            ),
+            (SelectKind::Authentication, "timeout", None),
+            (
+                SelectKind::Authentication,
+                "unknown",
+                Some(IncompatibleVersion),
+            ),
+            (SelectKind::MultiplexingNoPeerId, "timeout", None),
+            (
+                SelectKind::Multiplexing(peer_id),
+                "parse_token",
+                Some(IncompatibleVersion),
+            ),
+            (SelectKind::Stream(peer_id, 1), "parse_token", None),
+        ];
+        for (kind, error, expected) in cases {
+            assert_eq!(
+                P2pHandshakeFailureReason::from_select_error(&kind, error),
+                expected,
+                "{kind:?} {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn connection_error_classification() {
+        let connection = connection();
+        let classify =
+            |error| P2pHandshakeFailureReason::from_connection_error(&connection, &error);
+        assert_eq!(
+            classify(P2pNetworkConnectionError::Noise(
+                NoiseError::FirstMacMismatch
+            )),
+            Some(P2pHandshakeFailureReason::BadAuth)
+        );
+        // The peer might be at its connection limit.
+        assert_eq!(classify(P2pNetworkConnectionError::RemoteClosed), None);
+        assert_eq!(classify(P2pNetworkConnectionError::SelectError), None);
+        assert_eq!(
+            classify(P2pNetworkConnectionError::MioError("reset".to_owned())),
+            None
+        );
+    }
+
+    #[test]
+    fn outgoing_error_classification() {
+        use P2pConnectionOutgoingError as Error;
+
+        let classify = |error| P2pHandshakeFailureReason::from_outgoing_error(&error);
+        assert_eq!(
+            classify(Error::Rejected(RejectionReason::ChainIdMismatch)),
+            Some(P2pHandshakeFailureReason::ChainIdMismatch)
+        );
+        assert_eq!(
+            classify(Error::Rejected(RejectionReason::PeerIdAndPublicKeyMismatch)),
+            Some(P2pHandshakeFailureReason::BadAuth)
+        );
+        assert_eq!(
+            classify(Error::Rejected(RejectionReason::PeerCapacityFull)),
+            None
+        );
+        assert_eq!(
+            classify(Error::FinalizeError(
+                P2pConnectionFinalizeError::AuthDecryptFailed
+            )),
+            Some(P2pHandshakeFailureReason::BadAuth)
+        );
+        assert_eq!(
+            classify(Error::FinalizeError(
+                P2pConnectionFinalizeError::AuthTimeout
+            )),
+            None
+        );
+        assert_eq!(
+            classify(Error::FinalizeError(
+                P2pConnectionFinalizeError::ConnectionFailed("closed".to_owned())
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn stats_record() {
+        let mut stats = P2pHandshakeFailureStats::default();
+        stats.record(P2pHandshakeFailureReason::ChainIdMismatch);
+        stats.record(P2pHandshakeFailureReason::ChainIdMismatch);
+        stats.record(P2pHandshakeFailureReason::BadAuth);
+        assert_eq!(stats.chain_id_mismatch, 2);
+        assert_eq!(stats.incompatible_version, 0);
+        assert_eq!(stats.bad_auth, 1);
+        assert_eq!(stats.total(), 3);
+    }
+}
@@ -2,8 +2,8 @@ use openmina_core::Substate;
 use redux::ActionWithMeta;
 
 use super::{
-    incoming::P2pConnectionIncomingState, outgoing::P2pConnectionOutgoingState,
-    P2pConnectionAction, P2pConnectionState,
+    ice_restart::P2pConnectionIceRestartState, incoming::P2pConnectionIncomingState,
+    outgoing::P2pConnectionOutgoingState, P2pConnectionAction, P2pConnectionState,
 };
 use crate::P2pState;
 
@@ -25,6 +25,9 @@ impl P2pConnectionState {
             P2pConnectionAction::Incoming(action) => {
                 P2pConnectionIncomingState::reducer(state_context, meta.with_action(action))
             }
+            P2pConnectionAction::IceRestart(action) => {
+                P2pConnectionIceRestartState::reducer(state_context, meta.with_action(action))
+            }
         }
     }
 }
@@ -0,0 +1,125 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{webrtc::P2pCapabilities, PeerId};
+
+use super::outgoing::P2pConnectionOutgoingInitOpts;
+
+/// Max number of the peers, which are stored to be reconnected to first
+/// after the restart.
+pub const P2P_PREFERRED_PEERS_MAX: usize = 8;
+
+/// Peer we were connected to in the previous session.
+///
+/// Connection auth and the noise keys are bound to the session, so only
+/// the address and the last known capabilities of the peer are kept.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct P2pPreferredPeer {
+    pub opts: P2pConnectionOutgoingInitOpts,
+    /// Capabilities announced by the peer during signaling, `None` for
+    /// libp2p peers.
+    #[serde(default)]
+    pub capabilities: Option<P2pCapabilities>,
+}
+
+/// How quickly the connections to the preferred peers were restored.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pSessionResumptionStats {
+    /// Number of the preferred peers from the previous session.
+    pub preferred: usize,
+    /// Preferred peers we started to reconnect to.
+    pub attempted: usize,
+    /// Preferred peers we reconnected to.
+    pub reconnected: BTreeSet<PeerId>,
+    /// Reconnected peers, which announced different capabilities than
+    /// in the previous session.
+    pub capabilities_changed: usize,
+    /// When the first reconnect attempt was started.
+    pub started: Option<redux::Timestamp>,
+    /// Time it took to reconnect to the first preferred peer.
+    pub first_reconnect: Option<Duration>,
+    /// Time it took to reconnect to all the preferred peers.
+    pub all_reconnected: Option<Duration>,
+}
+
+impl P2pSessionResumptionStats {
+    pub fn new(preferred: usize) -> Self {
+        Self {
+            preferred,
+            ..Default::default()
+        }
+    }
+
+    pub fn attempt(&mut self, time: redux::Timestamp) {
+        self.attempted = self.attempted.saturating_add(1);
+        self.started.get_or_insert(time);
+    }
+
+    /// Records the reconnect to the preferred peer. Only the first
+    /// connection to the peer in this session counts.
+    pub fn reconnect(
+        &mut self,
+        time: redux::Timestamp,
+        peer: &P2pPreferredPeer,
+        capabilities: Option<&P2pCapabilities>,
+    ) {
+        if !self.reconnected.insert(*peer.opts.peer_id()) {
+            return;
+        }
+        if peer.capabilities.is_some() && peer.capabilities.as_ref() != capabilities {
+            self.capabilities_changed = self.capabilities_changed.saturating_add(1);
+        }
+        let elapsed = self
+            .started
+            .and_then(|started| time.checked_sub(started))
+            .unwrap_or_default();
+        self.first_reconnect.get_or_insert(elapsed);
+        if self.reconnected.len() >= self.preferred {
+            self.all_reconnected.get_or_insert(elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        connection::outgoing::P2pConnectionOutgoingInitLibp2pOpts, identity::SecretKey,
+        webrtc::Host,
+    };
+
+    fn preferred_peer(i: u8) -> P2pPreferredPeer {
+        let peer_id = SecretKey::from_bytes([i; 32]).public_key().peer_id();
+        P2pPreferredPeer {
+            opts: P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+                peer_id,
+                host: Host::Ipv4([127, 0, 0, 1].into()),
+                port: 8302,
+            }),
+            capabilities: None,
+        }
+    }
+
+    #[test]
+    fn measures_reconnect_time() {
+        let secs = |s: u64| redux::Timestamp::new(s * 1_000_000_000);
+        let (a, b) = (preferred_peer(1), preferred_peer(2));
+        let mut stats = P2pSessionResumptionStats::new(2);
+
+        stats.attempt(secs(10));
+        stats.attempt(secs(11));
+        stats.reconnect(secs(12), &a, None);
+        assert_eq!(stats.first_reconnect, Some(Duration::from_secs(2)));
+        assert_eq!(stats.all_reconnected, None);
+
+        // Reconnect in the same session doesn't count.
+        stats.reconnect(secs(13), &a, None);
+        assert_eq!(stats.reconnected.len(), 1);
+
+        stats.reconnect(secs(15), &b, None);
+        assert_eq!(stats.attempted, 2);
+        assert_eq!(stats.first_reconnect, Some(Duration::from_secs(2)));
+        assert_eq!(stats.all_reconnected, Some(Duration::from_secs(5)));
+    }
+}
@@ -1,28 +1,52 @@
 use std::collections::BTreeSet;
 
-use crate::{identity::PublicKey, webrtc, PeerId};
+use crate::{identity::PublicKey, webrtc, P2pCorrelationId, PeerId};
 
 use super::outgoing::P2pConnectionOutgoingInitOpts;
 
 pub trait P2pConnectionService: redux::Service {
     fn connections(&self) -> BTreeSet<PeerId>;
 
+    /// Picks one of the peers randomly, with the probability proportional
+    /// to its weight, see [`super::weighted_random_pick`].
     fn random_pick(
         &mut self,
-        list: &[P2pConnectionOutgoingInitOpts],
+        list: &[(P2pConnectionOutgoingInitOpts, f64)],
     ) -> Option<P2pConnectionOutgoingInitOpts>;
 
     /// Initiates an outgoing connection and creates an offer sdp,
     /// which will be received in the state machine as an event.
-    fn outgoing_init(&mut self, opts: P2pConnectionOutgoingInitOpts);
+    ///
+    /// The methods issuing a command to the webrtc peer return the
+    /// correlation id carried by the events emitted in response to it,
+    /// or `None` if no command was issued (libp2p peer, unknown peer).
+    fn outgoing_init(&mut self, opts: P2pConnectionOutgoingInitOpts) -> Option<P2pCorrelationId>;
 
     /// Initiates an incoming connection and creates an answer sdp,
     /// which will be received in the state machine as an event.
-    fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer);
+    fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer) -> P2pCorrelationId;
 
-    fn set_answer(&mut self, peer_id: PeerId, answer: webrtc::Answer);
+    fn set_answer(&mut self, peer_id: PeerId, answer: webrtc::Answer) -> Option<P2pCorrelationId>;
 
-    fn http_signaling_request(&mut self, url: String, offer: webrtc::Offer);
+    /// Sends the offer to the signaling server of the peer, using the
+    /// backend selected by the signaling `method`.
+    fn signaling_request(
+        &mut self,
+        method: webrtc::SignalingMethod,
+        offer: webrtc::Offer,
+    ) -> Option<P2pCorrelationId>;
+
+    /// Restarts ICE for the degraded connection. Result of the
+    /// renegotiation will be received in the state machine as an event.
+    fn ice_restart_init(&mut self, peer_id: PeerId) -> Option<P2pCorrelationId>;
+
+    /// Applies ICE restart offer received from the peer and creates an
+    /// answer sdp, which will be received in the state machine as an event.
+    fn ice_restart_offer_set(
+        &mut self,
+        peer_id: PeerId,
+        offer: webrtc::Offer,
+    ) -> Option<P2pCorrelationId>;
 
     fn auth_encrypt_and_send(
         &mut self,
@@ -4,7 +4,7 @@ use redux::Timestamp;
 use malloc_size_of_derive::MallocSizeOf;
 use serde::{Deserialize, Serialize};
 
-use crate::P2pTimeouts;
+use crate::{webrtc::P2pCapabilities, P2pTimeouts};
 
 use super::incoming::{P2pConnectionIncomingInitOpts, P2pConnectionIncomingState};
 use super::outgoing::{P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingState};
@@ -82,6 +82,21 @@ impl P2pConnectionState {
         }
     }
 
+    /// Capabilities announced by the remote peer during signaling.
+    /// `None` for libp2p connections and unfinished webrtc ones.
+    pub fn remote_capabilities(&self) -> Option<P2pCapabilities> {
+        match self {
+            Self::Outgoing(P2pConnectionOutgoingState::Success {
+                answer: Some(answer),
+                ..
+            }) => Some(answer.remote_capabilities()),
+            Self::Incoming(P2pConnectionIncomingState::Success { offer, .. }) => {
+                Some(offer.remote_capabilities())
+            }
+            _ => None,
+        }
+    }
+
     pub fn time(&self) -> redux::Timestamp {
         match self {
             P2pConnectionState::Outgoing(o) => o.time(),
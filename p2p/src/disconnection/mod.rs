@@ -6,6 +6,7 @@ pub use p2p_disconnection_actions::*;
 
 mod p2p_disconnection_reducer;
 
+use openmina_core::block::prevalidate::StagedLedgerDiffPrevalidationError;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -35,6 +36,8 @@ pub enum P2pDisconnectionReason {
     TransitionFrontierSyncLedgerSnarkedNumAccountsRejected,
     #[error("failed to verify snark pool diff")]
     SnarkPoolVerifyError,
+    #[error("failed to verify genesis proof")]
+    GenesisProofInvalid,
     #[error("duplicate connection")]
     DuplicateConnection,
     #[error("timeout")]
@@ -43,4 +46,10 @@ pub enum P2pDisconnectionReason {
     Unsupported,
     #[error("invalid pubsub message")]
     InvalidMessage,
+    #[error("failed to restore connectivity with ICE restart")]
+    IceRestartFailed,
+    #[error("invalid staged ledger diff: {0}")]
+    InvalidStagedLedgerDiff(StagedLedgerDiffPrevalidationError),
+    #[error("invalid checkpoint signature")]
+    CheckpointSignatureInvalid,
 }
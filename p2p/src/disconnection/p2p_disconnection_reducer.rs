@@ -5,8 +5,8 @@ use rand::prelude::*;
 use redux::ActionWithMeta;
 
 use crate::{
-    disconnection_effectful::P2pDisconnectionEffectfulAction, P2pNetworkSchedulerAction,
-    P2pPeerAction, P2pPeerStatus, P2pState,
+    connection::P2pConnectionEventKind, disconnection_effectful::P2pDisconnectionEffectfulAction,
+    P2pNetworkSchedulerAction, P2pPeerAction, P2pPeerStatus, P2pState,
 };
 
 use super::{P2pDisconnectedState, P2pDisconnectionAction, P2pDisconnectionReason};
@@ -34,7 +34,7 @@ impl P2pDisconnectedState {
                 {
                     return Ok(());
                 }
-                let mut rng = pseudo_rng(meta.time());
+                let mut rng = pseudo_rng(meta.time(), "random_disconnection_peer");
 
                 let peer_id = p2p_state
                     .ready_peers_iter()
@@ -57,6 +57,13 @@ impl P2pDisconnectedState {
                     return Ok(());
                 };
                 peer.status = P2pPeerStatus::Disconnecting { time: meta.time() };
+                p2p_state.connection_events.push(
+                    meta.time(),
+                    peer_id,
+                    P2pConnectionEventKind::Disconnected {
+                        reason: reason.to_string(),
+                    },
+                );
 
                 #[cfg(feature = "p2p-libp2p")]
                 if peer.is_libp2p() {
@@ -0,0 +1,7 @@
+mod p2p_feature_flags_state;
+pub use self::p2p_feature_flags_state::*;
+
+mod p2p_feature_flags_actions;
+pub use self::p2p_feature_flags_actions::*;
+
+mod p2p_feature_flags_reducer;
@@ -0,0 +1,22 @@
+use openmina_macros::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::P2pState;
+
+use super::P2pFeatureFlags;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = info, fields(debug(flags)))]
+pub enum P2pFeatureFlagsAction {
+    /// Replaces the feature flags, affects the connections established
+    /// after the update.
+    Update { flags: P2pFeatureFlags },
+}
+
+impl redux::EnablingCondition<P2pState> for P2pFeatureFlagsAction {
+    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+        match self {
+            Self::Update { flags } => &state.feature_flags != flags,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use openmina_core::Substate;
+use redux::ActionWithMeta;
+
+use crate::P2pState;
+
+use super::P2pFeatureFlagsAction;
+
+impl P2pState {
+    pub fn feature_flags_reducer<Action, State>(
+        mut state_context: Substate<Action, State, P2pState>,
+        action: ActionWithMeta<P2pFeatureFlagsAction>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let (action, _meta) = action.split();
+        let p2p_state = state_context.get_substate_mut()?;
+
+        match action {
+            P2pFeatureFlagsAction::Update { flags } => {
+                p2p_state.feature_flags = flags;
+                Ok(())
+            }
+        }
+    }
+}
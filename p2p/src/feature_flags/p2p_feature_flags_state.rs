@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+use malloc_size_of_derive::MallocSizeOf;
+use serde::{Deserialize, Serialize};
+
+use crate::webrtc::BULK_CHANNELS;
+
+/// Protocol features which may be switched on or off per node, e.g. to
+/// roll them out gradually or to rule them out when debugging.
+///
+/// Flags are part of the state, so that the recorded replays behave the
+/// same as the recorded node, even if they were changed at runtime.
+/// Features are negotiated when the connection is established, so the
+/// changes only affect the new connections.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, MallocSizeOf)]
+#[serde(default)]
+pub struct P2pFeatureFlags {
+    /// Compact blocks on the best tip propagation channel.
+    pub compact_blocks: bool,
+    /// Compression of the bigger channel messages sent to the webrtc
+    /// peers, which enabled it too.
+    pub compression: bool,
+    /// Chunked transfer of the large rpc responses over the bulk channels.
+    pub chunked_rpc: bool,
+}
+
+impl Default for P2pFeatureFlags {
+    fn default() -> Self {
+        Self {
+            compact_blocks: true,
+            compression: false,
+            chunked_rpc: true,
+        }
+    }
+}
+
+impl P2pFeatureFlags {
+    pub fn set(&mut self, feature: P2pFeature, enabled: bool) {
+        match feature {
+            P2pFeature::CompactBlocks => self.compact_blocks = enabled,
+            P2pFeature::Compression => self.compression = enabled,
+            P2pFeature::ChunkedRpc => self.chunked_rpc = enabled,
+        }
+    }
+
+    pub fn is_enabled(&self, feature: P2pFeature) -> bool {
+        match feature {
+            P2pFeature::CompactBlocks => self.compact_blocks,
+            P2pFeature::Compression => self.compression,
+            P2pFeature::ChunkedRpc => self.chunked_rpc,
+        }
+    }
+
+    /// Number of the bulk channels announced to the peers.
+    pub fn bulk_channels(&self) -> u8 {
+        if self.chunked_rpc {
+            BULK_CHANNELS
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum P2pFeature {
+    CompactBlocks,
+    Compression,
+    ChunkedRpc,
+}
+
+impl P2pFeature {
+    pub const ALL: [Self; 3] = [Self::CompactBlocks, Self::Compression, Self::ChunkedRpc];
+}
+
+impl FromStr for P2pFeature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "compact_blocks" | "compact-blocks" => Ok(Self::CompactBlocks),
+            "compression" => Ok(Self::Compression),
+            "chunked_rpc" | "chunked-rpc" => Ok(Self::ChunkedRpc),
+            _ => Err(format!(
+                "invalid feature `{s}`, expected `compact_blocks`, `compression` or `chunked_rpc`"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_flags() {
+        let mut flags = P2pFeatureFlags::default();
+        assert_eq!(flags.bulk_channels(), BULK_CHANNELS);
+
+        flags.set("chunked-rpc".parse().unwrap(), false);
+        assert!(!flags.is_enabled(P2pFeature::ChunkedRpc));
+        assert_eq!(flags.bulk_channels(), 0);
+        assert!("zstd".parse::<P2pFeature>().is_err());
+
+        // Missing flags are the defaults.
+        let flags: P2pFeatureFlags = serde_json::from_str(r#"{"compact_blocks":false}"#).unwrap();
+        assert_eq!(
+            flags,
+            P2pFeatureFlags {
+                compact_blocks: false,
+                ..Default::default()
+            }
+        );
+    }
+}
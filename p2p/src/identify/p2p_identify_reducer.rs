@@ -59,10 +59,11 @@ impl P2pState {
                 addr,
             } => {
                 let info = *info;
+                let dial_family = p2p_state.config.dial_family;
                 if let Some(peer) = p2p_state.peers.get_mut(&peer_id) {
                     peer.identify = Some(info.clone());
                     if let Some(P2pConnectionOutgoingInitOpts::LibP2P(opts)) = &mut peer.dial_opts {
-                        opts.update_host_if_needed(info.listen_addrs.iter());
+                        opts.update_host_if_needed(info.listen_addrs.iter(), dial_family);
                     }
                 } else {
                     bug_condition!(
@@ -7,10 +7,12 @@ pub mod channels;
 pub mod connection;
 pub mod disconnection;
 pub mod disconnection_effectful;
+pub mod feature_flags;
 pub mod identity;
 use bootstrap::P2pNetworkKadBootstrapState;
 use channels::{
     best_tip::P2pChannelsBestTipAction,
+    checkpoint::P2pChannelsCheckpointAction,
     rpc::P2pChannelsRpcAction,
     signaling::{
         discovery::P2pChannelsSignalingDiscoveryAction,
@@ -23,6 +25,8 @@ use channels::{
     P2pChannelsEffectfulAction,
 };
 use connection::{
+    ice_restart::P2pConnectionIceRestartAction,
+    ice_restart_effectful::P2pConnectionIceRestartEffectfulAction,
     incoming::P2pConnectionIncomingAction,
     incoming_effectful::P2pConnectionIncomingEffectfulAction,
     outgoing_effectful::P2pConnectionOutgoingEffectfulAction,
@@ -130,6 +134,7 @@ pub trait P2pActionTrait<State>:
     + From<P2pNetworkPnetEffectfulAction>
     + From<P2pNetworkNoiseAction>
     + From<P2pConnectionIncomingAction>
+    + From<P2pConnectionIceRestartAction>
     + From<P2pNetworkPubsubAction>
     + From<P2pNetworkPubsubEffectfulAction>
     + From<P2pChannelsSignalingExchangeAction>
@@ -143,7 +148,9 @@ pub trait P2pActionTrait<State>:
     + From<P2pChannelsBestTipAction>
     + From<P2pChannelsSnarkJobCommitmentAction>
     + From<P2pChannelsStreamingRpcAction>
+    + From<P2pChannelsCheckpointAction>
     + From<P2pConnectionIncomingEffectfulAction>
+    + From<P2pConnectionIceRestartEffectfulAction>
     + From<P2pConnectionOutgoingEffectfulAction>
     + From<P2pDisconnectionEffectfulAction>
     + From<P2pNetworkKadEffectfulAction>
@@ -354,6 +354,7 @@ impl P2pNetworkIdentifyStreamState {
             stream_id,
             data: Data::empty(),
             flags: YamuxFlags::FIN,
+            expires_at: None,
         });
         dispatcher.push(P2pNetworkIdentifyStreamAction::Prune {
             addr,
@@ -432,6 +433,7 @@ impl P2pNetworkIdentifyStreamState {
             stream_id,
             data,
             flags,
+            expires_at: None,
         });
 
         dispatcher.push(P2pNetworkIdentifyStreamAction::Close {
@@ -1,7 +1,7 @@
 use multiaddr::Multiaddr;
 use openmina_core::{error, log::system_time};
 use redux::ActionMeta;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 
 use super::P2pNetworkIdentifyStreamEffectfulAction;
 use crate::{network::identify::P2pNetworkIdentifyStreamAction, P2pNetworkService};
@@ -20,7 +20,10 @@ where
                 error!(system_time(); "error getting node addresses: {err}");
                 Vec::new()
             }
-            Ok(v) => v.into_iter().filter(|ip| ip.is_ipv6() == is_ipv6).collect(),
+            Ok(v) => v
+                .into_iter()
+                .filter(|ip| ip.is_ipv6() == is_ipv6 && !is_link_local(ip))
+                .collect(),
         }
     } else {
         vec![ip]
@@ -31,6 +34,15 @@ where
         .collect()
 }
 
+/// Link local addresses are useless for the peers, IPv6 ones can't be even
+/// dialed without the interface scope.
+fn is_link_local(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_link_local(),
+        IpAddr::V6(ip) => ip.segments()[0] & 0xffc0 == 0xfe80,
+    }
+}
+
 impl P2pNetworkIdentifyStreamEffectfulAction {
     pub fn effects<Store, S>(self, _meta: &ActionMeta, store: &mut Store)
     where
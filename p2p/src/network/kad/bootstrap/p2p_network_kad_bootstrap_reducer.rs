@@ -29,6 +29,7 @@ impl P2pNetworkKadBootstrapState {
 
         match action {
             P2pNetworkKadBootstrapAction::CreateRequests => {
+                let dial_family = state_context.get_substate()?.config.dial_family;
                 let discovery_state: &P2pNetworkKadState =
                     state_context.get_substate()?.substate()?;
                 let routing_table = &discovery_state.routing_table;
@@ -50,6 +51,7 @@ impl P2pNetworkKadBootstrapState {
                     dispatcher.push(P2pNetworkKadEffectfulAction::MakeRequest {
                         multiaddr: entry.addresses().clone(),
                         filter_local: filter_addrs,
+                        dial_family,
                         peer_id: entry.peer_id,
                     });
                 }
@@ -3,7 +3,7 @@ use openmina_core::ActionEvent;
 use multiaddr::Multiaddr;
 use serde::{Deserialize, Serialize};
 
-use crate::{P2pState, PeerId};
+use crate::{P2pIpFamily, P2pState, PeerId};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 pub enum P2pNetworkKadEffectfulAction {
@@ -15,6 +15,8 @@ pub enum P2pNetworkKadEffectfulAction {
     MakeRequest {
         multiaddr: Vec<Multiaddr>,
         filter_local: bool,
+        /// Addresses of the family are tried first.
+        dial_family: Option<P2pIpFamily>,
         peer_id: PeerId,
     },
 }
@@ -4,7 +4,7 @@ use multiaddr::Multiaddr;
 
 use crate::{
     bootstrap::P2pNetworkKadBoostrapRequestState,
-    connection::outgoing::P2pConnectionOutgoingInitOpts, P2pNetworkKadBootstrapAction,
+    connection::outgoing::P2pConnectionOutgoingInitOpts, P2pIpFamily, P2pNetworkKadBootstrapAction,
     P2pNetworkService, P2pPeerAction, SocketAddrTryFromMultiaddrError,
 };
 
@@ -90,6 +90,7 @@ impl P2pNetworkKadEffectfulAction {
             Self::MakeRequest {
                 multiaddr,
                 filter_local,
+                dial_family,
                 peer_id,
             } => {
                 let addrs = multiaddr.iter().filter_map(|multiaddr| {
@@ -97,7 +98,8 @@ impl P2pNetworkKadEffectfulAction {
                         .ok()
                         .flatten()
                 });
-                let addrs_to_use = addrs.collect::<Vec<_>>();
+                let mut addrs_to_use = addrs.collect::<Vec<_>>();
+                P2pIpFamily::sort_addrs(dial_family, &mut addrs_to_use);
                 let request =
                     addrs_to_use
                         .first()
@@ -212,6 +212,7 @@ impl P2pNetworkKadIncomingStreamState {
                     stream_id,
                     data,
                     flags,
+                    expires_at: None,
                 });
                 dispatcher.push(P2pNetworkKademliaStreamAction::WaitIncoming {
                     addr,
@@ -243,6 +244,7 @@ impl P2pNetworkKadIncomingStreamState {
                     stream_id,
                     data: Data::empty(),
                     flags: YamuxFlags::FIN,
+                    expires_at: None,
                 });
                 dispatcher.push(P2pNetworkKademliaStreamAction::Prune {
                     addr,
@@ -337,6 +339,7 @@ impl P2pNetworkKadOutgoingStreamState {
                     stream_id,
                     data,
                     flags,
+                    expires_at: None,
                 });
                 dispatcher.push(P2pNetworkKademliaStreamAction::WaitIncoming {
                     addr,
@@ -524,6 +527,7 @@ impl P2pNetworkKadOutgoingStreamState {
                     stream_id,
                     data: Data::empty(),
                     flags: YamuxFlags::FIN,
+                    expires_at: None,
                 });
                 dispatcher.push(P2pNetworkKademliaStreamAction::Prune {
                     addr,
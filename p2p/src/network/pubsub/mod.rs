@@ -5,6 +5,11 @@ pub use openmina_core::p2p::P2pNetworkPubsubMessageCacheId;
 mod p2p_network_pubsub_actions;
 pub use self::p2p_network_pubsub_actions::P2pNetworkPubsubAction;
 
+mod p2p_network_pubsub_bloom;
+pub use self::p2p_network_pubsub_bloom::{
+    P2pNetworkPubsubDedupStats, P2pNetworkPubsubMessageDigest, P2pNetworkPubsubPeerBloom,
+};
+
 mod p2p_network_pubsub_state;
 pub use self::p2p_network_pubsub_state::{
     P2pNetworkPubsubClientState, P2pNetworkPubsubClientTopicState, P2pNetworkPubsubState,
@@ -15,6 +20,10 @@ mod p2p_network_pubsub_reducer;
 
 #[cfg(feature = "p2p-libp2p")]
 const TOPIC: &str = "coda/consensus-messages/0.0.1";
+/// Topic of the signed checkpoints, see [`crate::channels::checkpoint`].
+/// Only openmina peers subscribe to it.
+#[cfg(feature = "p2p-libp2p")]
+const CHECKPOINT_TOPIC: &str = "openmina/checkpoints/0.0.1";
 
 pub mod pubsub_effectful;
 use openmina_core::snark::SnarkJobId;
@@ -55,7 +64,7 @@ pub(super) fn webrtc_source_sk_from_bytes(bytes: &[u8]) -> SecretKey {
     SecretKey::from_bytes(hasher.finalize().into())
 }
 
-pub(super) fn encode_message(message: &GossipNetMessageV2) -> std::io::Result<Vec<u8>> {
+pub(super) fn encode_message(message: &impl BinProtWrite) -> std::io::Result<Vec<u8>> {
     let mut buffer = vec![0; 8];
 
     message.binprot_write(&mut buffer)?;
@@ -1,4 +1,5 @@
 use super::{pb, BroadcastMessageId};
+use crate::channels::checkpoint::SignedCheckpoint;
 use crate::{token::BroadcastAlgorithm, ConnectionAddr, Data, P2pState, PeerId, StreamId};
 use mina_p2p_messages::gossip::GossipNetMessageV2;
 use openmina_core::{p2p::P2pNetworkPubsubMessageCacheId, ActionEvent};
@@ -97,6 +98,11 @@ pub enum P2pNetworkPubsubAction {
         message: GossipNetMessageV2,
     },
 
+    /// Broadcast the verified checkpoint on the checkpoint topic.
+    BroadcastCheckpoint {
+        checkpoint: Box<SignedCheckpoint>,
+    },
+
     /// Prepare a message for signing before broadcasting.
     ///
     /// **Fields:**
@@ -150,9 +156,12 @@ pub enum P2pNetworkPubsubAction {
     ///
     /// **Fields:**
     /// - `data`: The encoded data to be sent.
+    /// - `expires_at`: Time after which the data isn't sent anymore, if
+    ///   it's still waiting for the peer's yamux window.
     OutgoingData {
         data: Data,
         peer_id: PeerId,
+        expires_at: Option<redux::Timestamp>,
     },
 
     HandleIncomingMessage {
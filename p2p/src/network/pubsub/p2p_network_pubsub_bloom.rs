@@ -0,0 +1,138 @@
+use malloc_size_of_derive::MallocSizeOf;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Bits in each generation of the filter.
+const BLOOM_BITS: usize = 4096;
+/// Messages inserted into the generation before it is rotated, so that the
+/// false positive rate stays at ~0.15%.
+const BLOOM_GENERATION_CAPACITY: usize = 256;
+const BLOOM_HASHES: u64 = 5;
+
+/// Content digest of the gossip message, the same for the same block,
+/// transaction or snark, regardless of the peer that published it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct P2pNetworkPubsubMessageDigest(u64, u64);
+
+impl P2pNetworkPubsubMessageDigest {
+    pub fn new(data: &[u8]) -> Self {
+        let hash = Sha256::digest(data);
+        let (h1, h2) = hash.split_at(8);
+        Self(
+            u64::from_le_bytes(h1.try_into().expect("8 bytes")),
+            u64::from_le_bytes(h2[..8].try_into().expect("8 bytes")),
+        )
+    }
+
+    fn bits(self) -> impl Iterator<Item = usize> {
+        // Double hashing, see Kirsch and Mitzenmacher.
+        (0..BLOOM_HASHES).map(move |i| {
+            (self.0.wrapping_add(i.wrapping_mul(self.1)) % BLOOM_BITS as u64) as usize
+        })
+    }
+}
+
+/// Gossip messages the peer already has, either because it sent them to
+/// us or because we sent them to it, so that they aren't sent again.
+///
+/// Two generations are kept, once the current one is full the previous
+/// one is dropped, so the peer is "forgotten" gradually and the filter
+/// stays small (1KiB per peer).
+#[derive(Serialize, Deserialize, Debug, Clone, MallocSizeOf)]
+pub struct P2pNetworkPubsubPeerBloom {
+    current: Vec<u64>,
+    previous: Vec<u64>,
+    inserted: usize,
+}
+
+impl Default for P2pNetworkPubsubPeerBloom {
+    fn default() -> Self {
+        Self {
+            current: vec![0; BLOOM_BITS / 64],
+            previous: vec![0; BLOOM_BITS / 64],
+            inserted: 0,
+        }
+    }
+}
+
+impl P2pNetworkPubsubPeerBloom {
+    pub fn contains(&self, digest: P2pNetworkPubsubMessageDigest) -> bool {
+        let contains = |filter: &[u64]| {
+            digest.bits().all(|bit| {
+                filter
+                    .get(bit / 64)
+                    .is_some_and(|w| w & (1 << (bit % 64)) != 0)
+            })
+        };
+        contains(&self.current) || contains(&self.previous)
+    }
+
+    pub fn insert(&mut self, digest: P2pNetworkPubsubMessageDigest) {
+        if self.contains(digest) {
+            return;
+        }
+        if self.inserted >= BLOOM_GENERATION_CAPACITY {
+            self.previous = std::mem::replace(&mut self.current, vec![0; BLOOM_BITS / 64]);
+            self.inserted = 0;
+        }
+        for bit in digest.bits() {
+            if let Some(w) = self.current.get_mut(bit / 64) {
+                *w |= 1 << (bit % 64);
+            }
+        }
+        self.inserted = self.inserted.saturating_add(1);
+    }
+}
+
+/// Messages about to be sent to the peers, and how many of them were
+/// skipped because the peer already had them.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, MallocSizeOf)]
+pub struct P2pNetworkPubsubDedupStats {
+    pub checked: u64,
+    pub skipped: u64,
+}
+
+impl P2pNetworkPubsubDedupStats {
+    pub fn record(&mut self, skipped: bool) {
+        self.checked = self.checked.saturating_add(1);
+        if skipped {
+            self.skipped = self.skipped.saturating_add(1);
+        }
+    }
+
+    /// Ratio of the skipped sends, which is the saved bandwidth.
+    pub fn hit_rate(&self) -> f64 {
+        if self.checked == 0 {
+            return 0.0;
+        }
+        self.skipped as f64 / self.checked as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_bloom() {
+        let digest = |i: u32| P2pNetworkPubsubMessageDigest::new(&i.to_le_bytes());
+        let mut bloom = P2pNetworkPubsubPeerBloom::default();
+        assert!(!bloom.contains(digest(0)));
+
+        for i in 0..BLOOM_GENERATION_CAPACITY as u32 {
+            bloom.insert(digest(i));
+        }
+        assert!((0..BLOOM_GENERATION_CAPACITY as u32).all(|i| bloom.contains(digest(i))));
+
+        let n = BLOOM_GENERATION_CAPACITY as u32;
+        let false_positives = (n..n * 10).filter(|i| bloom.contains(digest(*i))).count();
+        assert!(false_positives < 20, "false positives: {false_positives}");
+
+        // Rotated out after two generations.
+        for i in n..n * 3 {
+            bloom.insert(digest(i));
+        }
+        assert!(bloom.contains(digest(n * 3 - 1)));
+        assert!((0..n).filter(|i| bloom.contains(digest(*i))).count() < 5);
+    }
+}
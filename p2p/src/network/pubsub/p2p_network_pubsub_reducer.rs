@@ -11,10 +11,13 @@ use openmina_core::{
 use redux::{Dispatcher, Timestamp};
 
 use crate::{
-    channels::{snark::P2pChannelsSnarkAction, transaction::P2pChannelsTransactionAction},
+    channels::{
+        checkpoint::SignedCheckpoint, snark::P2pChannelsSnarkAction,
+        transaction::P2pChannelsTransactionAction,
+    },
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
     peer::P2pPeerAction,
-    Data, P2pConfig, P2pNetworkYamuxAction, P2pState, PeerId,
+    Data, P2pConfig, P2pGossipTopic, P2pNetworkYamuxAction, P2pState, PeerId,
 };
 
 use super::{
@@ -24,10 +27,14 @@ use super::{
     },
     pb::{self, Message},
     P2pNetworkPubsubAction, P2pNetworkPubsubClientState, P2pNetworkPubsubEffectfulAction,
-    P2pNetworkPubsubMessageCacheId, P2pNetworkPubsubState, TOPIC,
+    P2pNetworkPubsubMessageCacheId, P2pNetworkPubsubMessageDigest, P2pNetworkPubsubState,
+    CHECKPOINT_TOPIC, TOPIC,
 };
 
 const MAX_MESSAGE_KEEP_DURATION: Duration = Duration::from_secs(300);
+/// Same as [`crate::channels::BEST_TIP_SEND_TTL`], gossip queued longer
+/// than that is outdated.
+const PUBLISH_SEND_TTL: Duration = Duration::from_secs(30);
 
 impl P2pNetworkPubsubState {
     pub fn reducer<Action, State>(
@@ -68,6 +75,7 @@ impl P2pNetworkPubsubState {
                     cache: Default::default(),
                     buffer: vec![],
                     incoming_messages: vec![],
+                    known: Default::default(),
                 });
                 state.protocol = protocol;
                 state.addr = addr;
@@ -100,6 +108,7 @@ impl P2pNetworkPubsubState {
                         cache: Default::default(),
                         buffer: vec![],
                         incoming_messages: vec![],
+                        known: Default::default(),
                     }
                 });
                 state.outgoing_stream_id = Some(stream_id);
@@ -113,10 +122,12 @@ impl P2pNetworkPubsubState {
                     .insert(peer_id, Default::default());
 
                 if let Some(state) = pubsub_state.clients.get_mut(&peer_id) {
-                    state.message.subscriptions.push(pb::rpc::SubOpts {
-                        subscribe: Some(true),
-                        topic_id: Some(TOPIC.to_owned()),
-                    });
+                    for topic in [TOPIC, CHECKPOINT_TOPIC] {
+                        state.message.subscriptions.push(pb::rpc::SubOpts {
+                            subscribe: Some(true),
+                            topic_id: Some(topic.to_owned()),
+                        });
+                    }
                 }
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
@@ -196,6 +207,44 @@ impl P2pNetworkPubsubState {
                     return Ok(());
                 }
 
+                // The peer has the message, even if we have already seen it.
+                if let (Some(data), Some(client)) =
+                    (&message.data, pubsub_state.clients.get_mut(&peer_id))
+                {
+                    client
+                        .known
+                        .insert(P2pNetworkPubsubMessageDigest::new(data));
+                }
+
+                if message.topic == CHECKPOINT_TOPIC {
+                    let checkpoint = pubsub_state.reduce_incoming_checkpoint(&message, seen_limit);
+                    let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                    let p2p_state: &P2pState = global_state.substate()?;
+                    dispatcher.push(P2pNetworkPubsubAction::IncomingMessageCleanup { peer_id });
+                    match checkpoint {
+                        // Relayed by the node once the signature is verified.
+                        Ok(Some(checkpoint)) => {
+                            if let Some(callback) =
+                                &p2p_state.callbacks.on_p2p_channels_checkpoint_received
+                            {
+                                dispatcher.push_callback(
+                                    callback.clone(),
+                                    (peer_id, Box::new(checkpoint)),
+                                );
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(reason) => {
+                            dispatcher.push(P2pNetworkPubsubAction::RejectMessage {
+                                message_id: None,
+                                peer_id: Some(peer_id),
+                                reason,
+                            });
+                        }
+                    }
+                    return Ok(());
+                }
+
                 // Check result later to ensure we always dispatch the cleanup action
                 let reduce_incoming_result =
                     pubsub_state.reduce_incoming_message(&message, seen_limit);
@@ -206,7 +255,7 @@ impl P2pNetworkPubsubState {
 
                 dispatcher.push(P2pNetworkPubsubAction::IncomingMessageCleanup { peer_id });
 
-                let message_content = reduce_incoming_result?;
+                let encoded = reduce_incoming_result?;
 
                 for (topic_id, map) in &state.topics {
                     let mesh_size = map.values().filter(|s| s.on_mesh()).count();
@@ -223,7 +272,19 @@ impl P2pNetworkPubsubState {
                 }
 
                 // This happens if message was already seen
-                if let Some(message_content) = message_content {
+                if let Some(encoded) = encoded {
+                    // Not decoded at all, if it's not subscribed.
+                    if let Some(topic) = P2pGossipTopic::of_encoded(encoded)
+                        .filter(|topic| !p2p_state.config.is_gossip_topic_enabled(*topic))
+                    {
+                        dispatcher.push(P2pNetworkPubsubAction::IgnoreMessage {
+                            message_id: None,
+                            reason: format!("Gossip topic `{topic:?}` is not subscribed"),
+                        });
+                        return Ok(());
+                    }
+                    let message_content = gossip::GossipNetMessageV2::binprot_read(&mut &*encoded)
+                        .map_err(|e| format!("Invalid `GossipNetMessageV2` message, error: {e}"))?;
                     dispatcher.push(P2pNetworkPubsubAction::HandleIncomingMessage {
                         message,
                         message_content,
@@ -350,6 +411,14 @@ impl P2pNetworkPubsubState {
                     );
                     return Ok(());
                 };
+                // Only the gossip expires, subscriptions and control must be delivered.
+                let expires_at = if msg.subscriptions.is_empty() && msg.control.is_none() {
+                    u64::try_from(PUBLISH_SEND_TTL.as_nanos())
+                        .ok()
+                        .and_then(|ttl| time.checked_add(ttl))
+                } else {
+                    None
+                };
 
                 let mut data = vec![];
                 let result = prost::Message::encode_length_delimited(msg, &mut data)
@@ -367,6 +436,7 @@ impl P2pNetworkPubsubState {
                         dispatcher.push(P2pNetworkPubsubAction::OutgoingData {
                             data: Data::from(data),
                             peer_id,
+                            expires_at,
                         });
                     }
                 }
@@ -448,7 +518,18 @@ impl P2pNetworkPubsubState {
                     Ok(data) => data,
                 };
 
-                Self::prepare_to_sign(state_context, data)
+                Self::prepare_to_sign(state_context, data, TOPIC)
+            }
+            P2pNetworkPubsubAction::BroadcastCheckpoint { checkpoint } => {
+                let data = match super::encode_message(checkpoint.as_ref()) {
+                    Err(err) => {
+                        bug_condition!("binprot serialization error: {err}");
+                        return Ok(());
+                    }
+                    Ok(data) => data,
+                };
+
+                Self::prepare_to_sign(state_context, data, CHECKPOINT_TOPIC)
             }
             P2pNetworkPubsubAction::Sign {
                 seqno,
@@ -486,16 +567,39 @@ impl P2pNetworkPubsubState {
             P2pNetworkPubsubAction::BroadcastSigned { signature } => {
                 if let Some(mut message) = pubsub_state.to_sign.pop_front() {
                     message.signature = Some(signature.0.to_vec());
-                    pubsub_state
-                        .clients
-                        .iter_mut()
-                        .for_each(|(_, state)| state.publish(&message));
+                    let digest = message
+                        .data
+                        .as_deref()
+                        .map(P2pNetworkPubsubMessageDigest::new);
+                    // Peers which don't know our own topics aren't subscribed to them.
+                    let subscribers = (message.topic != TOPIC)
+                        .then(|| pubsub_state.topics.get(&message.topic))
+                        .map(|subscribers| subscribers.cloned().unwrap_or_default());
+                    for (peer_id, state) in pubsub_state.clients.iter_mut() {
+                        if subscribers
+                            .as_ref()
+                            .is_some_and(|subscribers| !subscribers.contains_key(peer_id))
+                        {
+                            continue;
+                        }
+                        match digest {
+                            Some(digest) => {
+                                let skipped = state.publish_unless_known(&message, digest);
+                                pubsub_state.dedup_stats.record(skipped);
+                            }
+                            None => state.publish(&message),
+                        }
+                    }
                 }
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 Self::broadcast(dispatcher, state)
             }
-            P2pNetworkPubsubAction::OutgoingData { mut data, peer_id } => {
+            P2pNetworkPubsubAction::OutgoingData {
+                mut data,
+                peer_id,
+                expires_at,
+            } => {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let state: &Self = state.substate()?;
 
@@ -514,6 +618,7 @@ impl P2pNetworkPubsubState {
                         stream_id,
                         data,
                         flags,
+                        expires_at,
                     });
                 }
                 Ok(())
@@ -696,6 +801,7 @@ impl P2pNetworkPubsubState {
     fn prepare_to_sign<Action, State>(
         mut state_context: Substate<Action, State, Self>,
         buffer: Vec<u8>,
+        topic: &str,
     ) -> Result<(), String>
     where
         State: crate::P2pStateTrait,
@@ -712,7 +818,7 @@ impl P2pNetworkPubsubState {
             seqno,
             author: config.identity_pub_key.peer_id(),
             data: buffer.into(),
-            topic: super::TOPIC.to_owned(),
+            topic: topic.to_owned(),
         });
 
         Ok(())
@@ -729,6 +835,11 @@ impl P2pNetworkPubsubState {
         message: &Message,
     ) {
         let topic = self.topics.entry(message.topic.clone()).or_default();
+        let digest = message
+            .data
+            .as_deref()
+            .map(P2pNetworkPubsubMessageDigest::new);
+        let dedup_stats = &mut self.dedup_stats;
 
         self.clients
             .iter_mut()
@@ -741,7 +852,12 @@ impl P2pNetworkPubsubState {
                     return;
                 };
                 if topic_state.on_mesh() {
-                    state.publish(message)
+                    match digest {
+                        Some(digest) => {
+                            dedup_stats.record(state.publish_unless_known(message, digest));
+                        }
+                        None => state.publish(message),
+                    }
                 } else {
                     let ctr = state.message.control.get_or_insert_with(Default::default);
                     ctr.ihave.push(pb::ControlIHave {
@@ -752,11 +868,12 @@ impl P2pNetworkPubsubState {
             });
     }
 
-    /// Processes an incoming message by checking for duplicates and deserializing its contents.
+    /// Processes an incoming message by checking for duplicates and extracting its contents.
     ///
     /// This function performs two main operations:
     /// 1. Deduplication: Tracks recently seen messages using their signatures to avoid processing duplicates
-    /// 2. Deserialization: Converts valid message data into a `GossipNetMessageV2` structure
+    /// 2. Extraction: Returns the binprot encoded `GossipNetMessageV2` of the message, decoded
+    ///    by the caller only if its [`P2pGossipTopic`] is subscribed
     ///
     /// # Arguments
     ///
@@ -765,44 +882,67 @@ impl P2pNetworkPubsubState {
     ///
     /// # Returns
     ///
-    /// * `Ok(Some(GossipNetMessageV2))` - Successfully processed and deserialized message
+    /// * `Ok(Some(encoded))` - Successfully processed message
     /// * `Ok(None)` - Message was a duplicate (already seen)
     /// * `Err(String)` - Error during processing (invalid message format or deserialization failure)
     ///
     #[inline(never)]
-    fn reduce_incoming_message(
+    fn reduce_incoming_message<'a>(
         &mut self,
-        message: &Message,
+        message: &'a Message,
         seen_limit: usize,
-    ) -> Result<Option<GossipNetMessageV2>, String> {
-        let Some(signature) = &message.signature else {
-            bug_condition!("Validation failed: missing signature");
+    ) -> Result<Option<&'a [u8]>, String> {
+        if !self.mark_seen(message, seen_limit) {
             return Ok(None);
-        };
+        }
 
-        // skip recently seen message
-        if !self.seen.contains(signature) {
-            self.seen.push_back(signature.clone());
-            // keep only last `n` to avoid memory leak
-            if self.seen.len() > seen_limit {
-                self.seen.pop_front();
-            }
-        } else {
-            return Ok(None);
+        match &message.data {
+            Some(data) if data.len() > 8 => Ok(Some(&data[8..])),
+            _ => Err("Invalid message".to_owned()),
         }
+    }
 
+    /// Deserializes the checkpoint of the message on the checkpoint topic,
+    /// `Ok(None)` if the message was already seen.
+    fn reduce_incoming_checkpoint(
+        &mut self,
+        message: &Message,
+        seen_limit: usize,
+    ) -> Result<Option<SignedCheckpoint>, String> {
+        if !self.mark_seen(message, seen_limit) {
+            return Ok(None);
+        }
         match &message.data {
             Some(data) if data.len() > 8 => {
                 let mut slice = &data[8..];
-                Ok(Some(
-                    gossip::GossipNetMessageV2::binprot_read(&mut slice)
-                        .map_err(|e| format!("Invalid `GossipNetMessageV2` message, error: {e}"))?,
-                ))
+                SignedCheckpoint::binprot_read(&mut slice)
+                    .map(Some)
+                    .map_err(|e| format!("Invalid `SignedCheckpoint` message, error: {e}"))
             }
             _ => Err("Invalid message".to_owned()),
         }
     }
 
+    /// Remembers the signature of the message, returns `false` if it was
+    /// seen recently.
+    fn mark_seen(&mut self, message: &Message, seen_limit: usize) -> bool {
+        let Some(signature) = &message.signature else {
+            bug_condition!("Validation failed: missing signature");
+            return false;
+        };
+
+        // skip recently seen message
+        if self.seen.contains(signature) {
+            return false;
+        }
+        self.seen.push_back(signature.clone());
+        // keep only last `n` to avoid memory leak
+        if self.seen.len() > seen_limit {
+            self.seen.pop_front();
+        }
+        true
+    }
+
     fn combined_with_pending_buffer<'a>(buffer: &'a mut Vec<u8>, data: &'a [u8]) -> &'a [u8] {
         if buffer.is_empty() {
             // Nothing pending, we can use the data directly
@@ -1,4 +1,7 @@
-use super::{pb, BroadcastMessageId};
+use super::{
+    pb, BroadcastMessageId, P2pNetworkPubsubDedupStats, P2pNetworkPubsubMessageDigest,
+    P2pNetworkPubsubPeerBloom,
+};
 use crate::{token::BroadcastAlgorithm, ConnectionAddr, PeerId, StreamId};
 
 use libp2p_identity::ParseError;
@@ -62,6 +65,10 @@ pub struct P2pNetworkPubsubState {
 
     /// `iwant` requests, tracking the number of times peers have expressed interest in specific messages.
     pub iwant: VecDeque<P2pNetworkPubsubIwantRequestCount>,
+
+    /// Messages not sent to the peers, because they already had them.
+    #[serde(default)]
+    pub dedup_stats: P2pNetworkPubsubDedupStats,
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone, MallocSizeOf)]
@@ -175,6 +182,10 @@ pub struct P2pNetworkPubsubClientState {
     /// Holds fully decoded `pb::Message` instances received from the peer,
     /// ready for further handling such as validation, caching, and broadcasting.
     pub incoming_messages: Vec<pb::Message>,
+
+    /// Messages the peer has, either received from it or sent to it.
+    #[serde(default)]
+    pub known: P2pNetworkPubsubPeerBloom,
 }
 
 impl P2pNetworkPubsubClientState {
@@ -194,6 +205,22 @@ impl P2pNetworkPubsubClientState {
         }
     }
 
+    /// Same as [`Self::publish`], unless the peer already has the message.
+    ///
+    /// Returns whether the message was skipped.
+    pub fn publish_unless_known(
+        &mut self,
+        message: &pb::Message,
+        digest: P2pNetworkPubsubMessageDigest,
+    ) -> bool {
+        if self.known.contains(digest) {
+            return true;
+        }
+        self.known.insert(digest);
+        self.publish(message);
+        false
+    }
+
     pub fn clear_buffer(&mut self) {
         self.buffer.clear();
         self.buffer.shrink_to(0x2000);
@@ -4,5 +4,8 @@ pub use self::p2p_network_rpc_actions::*;
 mod p2p_network_rpc_state;
 pub use self::p2p_network_rpc_state::{P2pNetworkRpcState, RpcMessage};
 
+mod p2p_network_rpc_versions;
+pub use self::p2p_network_rpc_versions::*;
+
 #[cfg(feature = "p2p-libp2p")]
 mod p2p_network_rpc_reducer;
@@ -1,9 +1,9 @@
-use mina_p2p_messages::rpc_kernel::{QueryHeader, QueryID, ResponseHeader};
+use mina_p2p_messages::rpc_kernel::{QueryHeader, QueryID, ResponseHeader, RpcMethod};
 use openmina_core::{action_debug, action_trace, ActionEvent};
 use serde::{Deserialize, Serialize};
 
 use super::{super::*, *};
-use crate::{P2pState, PeerId};
+use crate::{channels::signaling::libp2p::SignalingRelayV1, P2pState, PeerId};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(fields(display(addr), display(peer_id), incoming, stream_id, debug(data), fin))]
@@ -145,7 +145,15 @@ impl redux::EnablingCondition<P2pState> for P2pNetworkRpcAction {
                 peer_id,
                 query,
                 data,
-            } => true,
+            } => {
+                // Relayed signaling messages aren't sent to the peers,
+                // which told us they don't implement the relay.
+                query.tag.as_ref() != SignalingRelayV1::NAME
+                    || state.peers.get(peer_id).is_some_and(|peer| {
+                        peer.rpc_versions
+                            .is_supported_by_peer(SignalingRelayV1::NAME, query.version)
+                    })
+            }
             P2pNetworkRpcAction::OutgoingResponse {
                 peer_id,
                 response,
@@ -4,8 +4,8 @@ use binprot::BinProtRead;
 use mina_p2p_messages::{
     rpc,
     rpc_kernel::{
-        MessageHeader, PayloadBinprotReader as _, QueryHeader, ResponseHeader, RpcMethod,
-        RpcQueryReadError, RpcResponseReadError,
+        Error as RpcError, MessageHeader, PayloadBinprotReader as _, QueryHeader, ResponseHeader,
+        RpcMethod, RpcQueryReadError, RpcResponseReadError,
     },
     v2,
     versioned::Ver,
@@ -14,12 +14,20 @@ use openmina_core::{bug_condition, error, fuzz_maybe, fuzzed_maybe, Substate};
 use redux::Dispatcher;
 
 use crate::{
-    channels::rpc::{
-        BestTipWithProof, P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse,
-        StagedLedgerAuxAndPendingCoinbases,
+    channels::{
+        rpc::{
+            BestTipWithProof, P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse,
+            StagedLedgerAuxAndPendingCoinbases,
+        },
+        signaling::{
+            discovery::P2pChannelsSignalingDiscoveryAction,
+            exchange::P2pChannelsSignalingExchangeAction, libp2p::SignalingRelayV1,
+        },
+        ChannelId, P2pChannelsMessageReceivedAction,
     },
     connection::outgoing::P2pConnectionOutgoingInitOpts,
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+    peer::P2pPeerAction,
     Data, Limit, P2pLimits, P2pNetworkState, P2pNetworkYamuxAction, PeerId,
 };
 
@@ -27,6 +35,10 @@ use self::p2p_network_rpc_state::P2pNetworkRpcError;
 
 use super::*;
 
+/// Max number of the relayed signaling messages waiting for the pending
+/// query on the outgoing stream.
+const MAX_QUEUED_QUERIES: usize = 8;
+
 impl P2pNetworkRpcState {
     /// Substate is accessed
     pub fn reducer<State, Action>(
@@ -174,11 +186,30 @@ impl P2pNetworkRpcState {
                     RpcMessage::Handshake => {
                         if !state.is_incoming {
                             dispatcher.push(P2pChannelsRpcAction::Ready { peer_id });
+                            dispatcher.push(P2pChannelsSignalingDiscoveryAction::Ready { peer_id });
+                            dispatcher.push(P2pChannelsSignalingExchangeAction::Ready { peer_id });
                         }
                     }
                     RpcMessage::Heartbeat => {}
                     RpcMessage::Query { header, bytes } => {
-                        if let Err(e) = dispatch_rpc_query(peer_id, header, bytes, dispatcher) {
+                        // Unknown methods and versions are answered with
+                        // an error, like the OCaml node does, instead of
+                        // failing to decode the query.
+                        if rpc_implemented_version(header.tag.as_ref()) != Some(header.version) {
+                            dispatcher.push(P2pPeerAction::RpcVersionUnsupported {
+                                peer_id,
+                                tag: header.tag.clone(),
+                                version: header.version,
+                                by: P2pRpcUnsupportedBy::Us,
+                            });
+                            dispatcher.push(P2pNetworkRpcAction::OutgoingResponse {
+                                peer_id,
+                                response: ResponseHeader { id: header.id },
+                                data: rpc_unimplemented_response(header),
+                            });
+                        } else if let Err(e) =
+                            dispatch_rpc_query(peer_id, header, bytes, dispatcher)
+                        {
                             dispatcher.push(P2pDisconnectionAction::Init {
                                 peer_id,
                                 reason: P2pDisconnectionReason::P2pChannelReceiveFailed(
@@ -205,15 +236,37 @@ impl P2pNetworkRpcState {
                         // unset pending
                         dispatcher.push(P2pNetworkRpcAction::PrunePending { peer_id, stream_id });
 
-                        if let Err(e) =
-                            dispatch_rpc_response(peer_id, &query_header, bytes, dispatcher)
-                        {
-                            dispatcher.push(P2pDisconnectionAction::Init {
-                                peer_id,
-                                reason: P2pDisconnectionReason::P2pChannelReceiveFailed(
-                                    e.to_string(),
-                                ),
-                            });
+                        match dispatch_rpc_response(peer_id, &query_header, bytes, dispatcher) {
+                            Ok(()) => {}
+                            // Peer doesn't implement the version we queried
+                            // it with, the request fails but the peer is
+                            // kept.
+                            Err(RpcResponseError::Read(RpcResponseReadError::Failure {
+                                error: RpcError::Unimplemented_rpc(tag, version),
+                                ..
+                            })) => {
+                                dispatcher.push(P2pPeerAction::RpcVersionUnsupported {
+                                    peer_id,
+                                    tag,
+                                    version,
+                                    by: P2pRpcUnsupportedBy::Peer,
+                                });
+                                if query_header.tag.as_ref() != SignalingRelayV1::NAME {
+                                    dispatcher.push(P2pChannelsRpcAction::ResponseReceived {
+                                        peer_id,
+                                        id: query_header.id,
+                                        response: None,
+                                    });
+                                }
+                            }
+                            Err(e) => {
+                                dispatcher.push(P2pDisconnectionAction::Init {
+                                    peer_id,
+                                    reason: P2pDisconnectionReason::P2pChannelReceiveFailed(
+                                        e.to_string(),
+                                    ),
+                                });
+                            }
                         }
                     }
                 }
@@ -228,8 +281,17 @@ impl P2pNetworkRpcState {
                 }
                 Ok(())
             }
-            P2pNetworkRpcAction::PrunePending { .. } => {
+            P2pNetworkRpcAction::PrunePending { peer_id, .. } => {
                 rpc_state.pending = None;
+
+                if let Some((query, data)) = rpc_state.queued.pop_front() {
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(P2pNetworkRpcAction::OutgoingQuery {
+                        peer_id,
+                        query,
+                        data,
+                    });
+                }
                 Ok(())
             }
             P2pNetworkRpcAction::HeartbeatSend {
@@ -256,6 +318,15 @@ impl P2pNetworkRpcState {
                 data,
                 peer_id,
             } => {
+                // Relayed signaling messages wait for the pending query,
+                // so that its response isn't dropped as unexpected.
+                if rpc_state.pending.is_some() && query.tag.as_ref() == SignalingRelayV1::NAME {
+                    if rpc_state.queued.len() >= MAX_QUEUED_QUERIES {
+                        rpc_state.queued.pop_front();
+                    }
+                    rpc_state.queued.push_back((query, data));
+                    return Ok(());
+                }
                 rpc_state.last_id = query.id;
                 rpc_state.pending = Some(query.clone());
 
@@ -293,6 +364,7 @@ impl P2pNetworkRpcState {
                     stream_id,
                     data,
                     flags,
+                    expires_at: None,
                 });
 
                 Ok(())
@@ -353,6 +425,7 @@ impl P2pNetworkRpcState {
                     limits.rpc_get_some_initial_peers(),
                     GetSomeInitialPeersV1ForV2::NAME,
                 ),
+                SignalingRelayV1::NAME => (limits.rpc_service_message(), SignalingRelayV1::NAME),
                 _ => (Limit::Some(0), b"<unimplemented>"),
             }
         } else {
@@ -434,6 +507,21 @@ where
                 request: Box::new(P2pRpcRequest::InitialPeers),
             });
         }
+        (SignalingRelayV1::NAME, SignalingRelayV1::VERSION) => {
+            let message = SignalingRelayV1::query_payload(&mut bytes)?;
+            if !SignalingRelayV1::is_relayed(&message) {
+                return Err(RpcQueryError::NotRelayed(message.channel_id()));
+            }
+            dispatcher.push(P2pChannelsMessageReceivedAction {
+                peer_id,
+                message: Box::new(message),
+            });
+            dispatcher.push(P2pNetworkRpcAction::OutgoingResponse {
+                peer_id,
+                response: ResponseHeader { id },
+                data: SignalingRelayV1::response(),
+            });
+        }
         (name, version) => return Err(RpcQueryError::Unimplemented(name, version)),
     }
     Ok(())
@@ -549,6 +637,9 @@ where
                 });
             }
         }
+        (SignalingRelayV1::NAME, SignalingRelayV1::VERSION) => {
+            let () = SignalingRelayV1::response_payload(&mut bytes)?;
+        }
         _ => {}
     }
     Ok(())
@@ -560,6 +651,8 @@ enum RpcQueryError<'a> {
     Read(#[from] RpcQueryReadError),
     #[error("unimplemented rpc {}:{1}", String::from_utf8_lossy(.0))]
     Unimplemented(&'a [u8], Ver),
+    #[error("message of channel {0:?} can't be relayed")]
+    NotRelayed(ChannelId),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -34,6 +34,10 @@ pub struct P2pNetworkRpcState {
     pub is_incoming: bool,
     pub buffer: Vec<u8>,
     pub incoming: VecDeque<RpcMessage>,
+    /// Queries waiting for the response to the pending one.
+    #[serde(default)]
+    #[ignore_malloc_size_of = "rarely populated"]
+    pub queued: VecDeque<(QueryHeader, Data)>,
     pub error: Option<P2pNetworkRpcError>,
 }
 
@@ -49,6 +53,7 @@ impl P2pNetworkRpcState {
             is_incoming: false,
             buffer: vec![],
             incoming: Default::default(),
+            queued: Default::default(),
             error: None,
         }
     }
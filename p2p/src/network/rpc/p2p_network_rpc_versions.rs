@@ -0,0 +1,136 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use binprot::BinProtWrite;
+use malloc_size_of_derive::MallocSizeOf;
+use mina_p2p_messages::{
+    rpc,
+    rpc_kernel::{BinprotTag, Error, QueryHeader, ResponsePayload, RpcMethod, RpcResult},
+    versioned::Ver,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{channels::signaling::libp2p::SignalingRelayV1, Data};
+
+/// RPC methods implemented by us, with their versions.
+pub const RPC_IMPLEMENTED_VERSIONS: &[(&str, Ver)] = &[
+    (rpc::GetBestTipV2::NAME_STR, rpc::GetBestTipV2::VERSION),
+    (
+        rpc::AnswerSyncLedgerQueryV2::NAME_STR,
+        rpc::AnswerSyncLedgerQueryV2::VERSION,
+    ),
+    (
+        rpc::GetStagedLedgerAuxAndPendingCoinbasesAtHashV2::NAME_STR,
+        rpc::GetStagedLedgerAuxAndPendingCoinbasesAtHashV2::VERSION,
+    ),
+    (
+        rpc::GetTransitionChainV2::NAME_STR,
+        rpc::GetTransitionChainV2::VERSION,
+    ),
+    (
+        rpc::GetSomeInitialPeersV1ForV2::NAME_STR,
+        rpc::GetSomeInitialPeersV1ForV2::VERSION,
+    ),
+    (SignalingRelayV1::NAME_STR, SignalingRelayV1::VERSION),
+];
+
+/// Version of the RPC method `tag` implemented by us, if the method is
+/// implemented at all.
+pub fn rpc_implemented_version(tag: &[u8]) -> Option<Ver> {
+    RPC_IMPLEMENTED_VERSIONS
+        .iter()
+        .find(|(name, _)| name.as_bytes() == tag)
+        .map(|(_, version)| *version)
+}
+
+/// Response telling the peer that we don't implement the method or the
+/// version it queried us with.
+pub fn rpc_unimplemented_response(QueryHeader { tag, version, .. }: &QueryHeader) -> Data {
+    let payload: ResponsePayload<()> =
+        RpcResult(Err(Error::Unimplemented_rpc(tag.clone(), *version)));
+    let mut v = vec![];
+    payload.binprot_write(&mut v).unwrap_or_default();
+    v.into()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum P2pRpcUnsupportedBy {
+    /// Peer queried us with the method or version we don't implement.
+    Us,
+    /// Peer responded with `Unimplemented_rpc` to our query.
+    Peer,
+}
+
+/// RPC methods and their versions, which are known to be unsupported by
+/// us or by the peer, so that a version both sides support can be picked
+/// once there is more than one.
+#[serde_with::serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, MallocSizeOf)]
+pub struct P2pRpcPeerVersions {
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[ignore_malloc_size_of = "rarely populated"]
+    pub unsupported_by_us: BTreeMap<BinprotTag, BTreeSet<Ver>>,
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[ignore_malloc_size_of = "rarely populated"]
+    pub unsupported_by_peer: BTreeMap<BinprotTag, BTreeSet<Ver>>,
+}
+
+impl P2pRpcPeerVersions {
+    pub fn record(&mut self, tag: BinprotTag, version: Ver, by: P2pRpcUnsupportedBy) {
+        let versions = match by {
+            P2pRpcUnsupportedBy::Us => &mut self.unsupported_by_us,
+            P2pRpcUnsupportedBy::Peer => &mut self.unsupported_by_peer,
+        };
+        versions.entry(tag).or_default().insert(version);
+    }
+
+    /// Whether the peer may implement the `version` of the method `tag`.
+    pub fn is_supported_by_peer(&self, tag: &[u8], version: Ver) -> bool {
+        !self
+            .unsupported_by_peer
+            .iter()
+            .any(|(t, versions)| t.as_ref() == tag && versions.contains(&version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::rpc_kernel::{PayloadBinprotReader, RpcResponseReadError};
+
+    use super::*;
+
+    #[test]
+    fn unimplemented_response_decodes_as_failure() {
+        let version = rpc::GetBestTipV2::VERSION + 1;
+        let header = QueryHeader {
+            tag: rpc::GetBestTipV2::NAME.into(),
+            version,
+            id: 1,
+        };
+        assert_eq!(
+            rpc_implemented_version(header.tag.as_ref()),
+            Some(version - 1)
+        );
+
+        let data = rpc_unimplemented_response(&header);
+        let res = rpc::GetBestTipV2::response_payload(&mut &data[..]);
+        assert!(matches!(
+            res,
+            Err(RpcResponseReadError::Failure {
+                error: Error::Unimplemented_rpc(tag, v),
+                ..
+            }) if tag == header.tag && v == version
+        ));
+    }
+
+    #[test]
+    fn peer_versions() {
+        let mut versions = P2pRpcPeerVersions::default();
+        let tag = rpc::GetTransitionChainV2::NAME;
+        assert!(versions.is_supported_by_peer(tag, 2));
+        versions.record(tag.into(), 2, P2pRpcUnsupportedBy::Us);
+        assert!(versions.is_supported_by_peer(tag, 2));
+        versions.record(tag.into(), 2, P2pRpcUnsupportedBy::Peer);
+        assert!(!versions.is_supported_by_peer(tag, 2));
+        assert!(versions.is_supported_by_peer(tag, 3));
+    }
+}
@@ -12,7 +12,7 @@ use token::{
 use crate::{
     connection::{
         incoming::P2pConnectionIncomingAction, outgoing::P2pConnectionOutgoingAction,
-        P2pConnectionState,
+        P2pConnectionFinalizeError, P2pConnectionState,
     },
     disconnection::P2pDisconnectionAction,
     identify::P2pIdentifyAction,
@@ -40,7 +40,12 @@ impl P2pNetworkSchedulerState {
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_config: &P2pConfig = state.substate()?;
 
-                if let Some(port) = p2p_config.libp2p_port {
+                // Interfaces of both families are detected, listen only on
+                // the configured ones.
+                if let Some(port) = p2p_config
+                    .libp2p_port
+                    .filter(|_| p2p_config.listen_family.includes(&ip))
+                {
                     dispatcher
                         .push(P2pNetworkSchedulerEffectfulAction::InterfaceDetected { ip, port });
                 }
@@ -161,7 +166,9 @@ impl P2pNetworkSchedulerState {
                         ) {
                             dispatcher.push(P2pConnectionOutgoingAction::FinalizeError {
                                 peer_id,
-                                error: error.to_string(),
+                                error: P2pConnectionFinalizeError::ConnectionFailed(
+                                    error.to_string(),
+                                ),
                             });
                         } else {
                             bug_condition!("Invalid status for `P2pNetworkSchedulerAction::OutgoingDidConnect`: {:?}", peer_state.status);
@@ -227,6 +234,7 @@ impl P2pNetworkSchedulerState {
                             stream_id,
                             data: Data::default(),
                             flags: YamuxFlags::RST,
+                            expires_at: None,
                         });
                         dispatcher
                             .push(P2pNetworkSchedulerAction::PruneStream { peer_id, stream_id });
@@ -359,7 +367,9 @@ impl P2pNetworkSchedulerState {
                             ) => {
                                 dispatcher.push(P2pConnectionIncomingAction::FinalizeError {
                                     peer_id,
-                                    error: reason.to_string(),
+                                    error: P2pConnectionFinalizeError::ConnectionFailed(
+                                        reason.to_string(),
+                                    ),
                                 });
                             }
                             P2pPeerStatus::Connecting(
@@ -367,7 +377,9 @@ impl P2pNetworkSchedulerState {
                             ) => {
                                 dispatcher.push(P2pConnectionOutgoingAction::FinalizeError {
                                     peer_id,
-                                    error: reason.to_string(),
+                                    error: P2pConnectionFinalizeError::ConnectionFailed(
+                                        reason.to_string(),
+                                    ),
                                 });
                             }
                             P2pPeerStatus::Disconnecting { .. } => {}
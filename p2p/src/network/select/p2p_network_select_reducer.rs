@@ -214,6 +214,7 @@ impl P2pNetworkSelectState {
                                 stream_id,
                                 data: na.name().to_vec().into(),
                                 flags: YamuxFlags::FIN,
+                                expires_at: None,
                             });
                         } else {
                             for token in tokens {
@@ -226,6 +227,7 @@ impl P2pNetworkSelectState {
                                     stream_id,
                                     data,
                                     flags: Default::default(),
+                                    expires_at: None,
                                 });
                             }
                         }
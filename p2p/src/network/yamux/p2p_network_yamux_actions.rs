@@ -16,6 +16,8 @@ pub enum P2pNetworkYamuxAction {
         stream_id: StreamId,
         data: Data,
         flags: YamuxFlags,
+        /// Drop the data if it's still queued for the window at this time.
+        expires_at: Option<redux::Timestamp>,
     },
     #[action_event(level = trace)]
     IncomingFrame {
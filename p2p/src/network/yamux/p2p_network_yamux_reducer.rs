@@ -81,6 +81,7 @@ impl P2pNetworkYamuxState {
                 stream_id,
                 data,
                 mut flags,
+                expires_at,
             } => {
                 let stream_state = yamux_state
                     .streams
@@ -99,6 +100,7 @@ impl P2pNetworkYamuxState {
                     flags,
                     stream_id,
                     inner: YamuxFrameInner::Data(data),
+                    expires_at,
                 };
 
                 let dispatcher = state_context.into_dispatcher();
@@ -155,16 +157,7 @@ impl P2pNetworkYamuxState {
                         if *difference > 0 {
                             // have some fresh space in the window
                             // try send as many frames as can
-                            let mut window = stream.window_theirs;
-                            while let Some(frame) = stream.pending.pop_front() {
-                                let len = frame.len_as_u32();
-                                pending_outgoing.push_back(frame);
-                                if let Some(new_window) = window.checked_sub(len) {
-                                    window = new_window;
-                                } else {
-                                    break;
-                                }
-                            }
+                            pending_outgoing = stream.take_sendable(meta.time());
                         }
                     }
                     YamuxFrameInner::Ping { .. } => {}
@@ -217,6 +210,7 @@ impl P2pNetworkYamuxState {
                                     flags: YamuxFlags::FIN,
                                     stream_id: frame.stream_id,
                                     inner: YamuxFrameInner::Data(vec![].into()),
+                                    expires_at: None,
                                 },
                             });
                         }
@@ -242,6 +236,7 @@ impl P2pNetworkYamuxState {
                                     stream_id: frame.stream_id,
                                     flags: YamuxFlags::empty(),
                                     inner: YamuxFrameInner::WindowUpdate { difference },
+                                    expires_at: None,
                                 },
                             });
                         }
@@ -95,6 +95,7 @@ impl P2pNetworkYamuxState {
                         flags,
                         stream_id,
                         inner: YamuxFrameInner::Data(buf[12..(12 + len)].to_vec().into()),
+                        expires_at: None,
                     };
                     self.incoming.push_back(frame);
                     Some(12 + len)
@@ -109,6 +110,7 @@ impl P2pNetworkYamuxState {
                     flags,
                     stream_id,
                     inner: YamuxFrameInner::WindowUpdate { difference },
+                    expires_at: None,
                 };
                 self.incoming.push_back(frame);
                 Some(12)
@@ -120,6 +122,7 @@ impl P2pNetworkYamuxState {
                     flags,
                     stream_id,
                     inner: YamuxFrameInner::Ping { opaque },
+                    expires_at: None,
                 };
                 self.incoming.push_back(frame);
                 Some(12)
@@ -140,6 +143,7 @@ impl P2pNetworkYamuxState {
                     flags,
                     stream_id,
                     inner: YamuxFrameInner::GoAway(result),
+                    expires_at: None,
                 };
                 self.incoming.push_back(frame);
                 Some(12)
@@ -228,6 +232,28 @@ impl YamuxStreamState {
             ..Default::default()
         }
     }
+
+    /// Takes the pending frames which fit in their window, dropping
+    /// the ones expired at `now`.
+    pub fn take_sendable(&mut self, now: redux::Timestamp) -> VecDeque<YamuxFrame> {
+        let mut sendable = VecDeque::default();
+        let mut window = self.window_theirs;
+        while let Some(frame) = self.pending.pop_front() {
+            let expired = frame.expires_at.is_some_and(|expires_at| expires_at <= now)
+                && !frame.flags.intersects(YamuxFlags::SYN | YamuxFlags::FIN);
+            if expired {
+                continue;
+            }
+            let len = frame.len_as_u32();
+            sendable.push_back(frame);
+            if let Some(new_window) = window.checked_sub(len) {
+                window = new_window;
+            } else {
+                break;
+            }
+        }
+        sendable
+    }
 }
 
 bitflags::bitflags! {
@@ -264,6 +290,7 @@ impl YamuxPing {
             },
             stream_id,
             inner: YamuxFrameInner::Ping { opaque },
+            expires_at: None,
         }
     }
 }
@@ -288,6 +315,11 @@ pub struct YamuxFrame {
     pub flags: YamuxFlags,
     pub stream_id: StreamId,
     pub inner: YamuxFrameInner,
+    /// Time after which the frame isn't worth sending anymore, if it's
+    /// still waiting for the window in [`YamuxStreamState::pending`].
+    #[serde(default)]
+    #[ignore_malloc_size_of = "doesn't allocate"]
+    pub expires_at: Option<redux::Timestamp>,
 }
 
 impl YamuxFrame {
@@ -375,6 +407,8 @@ impl YamuxFrame {
                 flags: self.flags.sub(YamuxFlags::SYN | YamuxFlags::ACK) | fin,
                 stream_id: self.stream_id,
                 inner: YamuxFrameInner::Data(rest),
+                // Once a part is sent, the rest must follow.
+                expires_at: self.expires_at.filter(|_| pos == 0),
             })
         } else {
             None
@@ -420,6 +454,43 @@ mod tests {
         assert_eq!(Kademlia.stream_id(false), 5);
         assert_eq!(Kademlia.stream_id(true), 6);
     }
+
+    #[test]
+    fn yamux_pending_expired() {
+        use super::*;
+
+        let expires_at = redux::Timestamp::ZERO.checked_add(1_000);
+        let frame = |len: usize, flags, expires_at| YamuxFrame {
+            flags,
+            stream_id: 1,
+            inner: YamuxFrameInner::Data(vec![0; len].into()),
+            expires_at,
+        };
+
+        // Rest of the partially sent frame doesn't expire.
+        let mut partial = frame(10, YamuxFlags::empty(), expires_at);
+        assert!(partial.split_at(4).unwrap().expires_at.is_none());
+        let mut unsent = frame(10, YamuxFlags::empty(), expires_at);
+        assert!(unsent.split_at(0).unwrap().expires_at.is_some());
+
+        let mut stream = YamuxStreamState {
+            window_theirs: 16,
+            ..Default::default()
+        };
+        stream.pending.extend([
+            frame(8, YamuxFlags::empty(), expires_at),
+            frame(8, YamuxFlags::empty(), None),
+            frame(0, YamuxFlags::FIN, expires_at),
+        ]);
+
+        let now = redux::Timestamp::ZERO.checked_add(2_000).unwrap();
+        let sendable = stream.take_sendable(now);
+        assert_eq!(sendable.len(), 2);
+        assert!(sendable
+            .iter()
+            .all(|frame| frame.expires_at.is_none() || frame.flags.contains(YamuxFlags::FIN)));
+        assert!(stream.pending.is_empty());
+    }
 }
 
 mod measurement {
@@ -10,6 +10,7 @@ use crate::P2pNetworkEffectfulAction;
 use super::channels::P2pChannelsAction;
 use super::connection::P2pConnectionAction;
 use super::disconnection::P2pDisconnectionAction;
+use super::feature_flags::P2pFeatureFlagsAction;
 use super::identify::P2pIdentifyAction;
 use super::network::P2pNetworkAction;
 use super::peer::P2pPeerAction;
@@ -24,6 +25,7 @@ pub enum P2pAction {
     Channels(P2pChannelsAction),
     Peer(P2pPeerAction),
     Network(P2pNetworkAction),
+    FeatureFlags(P2pFeatureFlagsAction),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, derive_more::From, ActionEvent)]
@@ -60,6 +62,7 @@ impl redux::EnablingCondition<crate::P2pState> for P2pAction {
             P2pAction::Peer(a) => a.is_enabled(state, time),
             P2pAction::Identify(a) => a.is_enabled(state, time),
             P2pAction::Network(a) => a.is_enabled(state, time),
+            P2pAction::FeatureFlags(a) => a.is_enabled(state, time),
         }
     }
 }
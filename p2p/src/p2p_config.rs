@@ -1,9 +1,18 @@
-use std::{collections::BTreeSet, net::IpAddr, time::Duration};
+use std::{
+    collections::BTreeSet,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    time::Duration,
+};
 
+use mina_p2p_messages::gossip::GossipNetMessageV2;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts, identity::PublicKey,
+    channels::{rpc::MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS, ChannelId},
+    connection::{outgoing::P2pConnectionOutgoingInitOpts, P2pPreferredPeer},
+    feature_flags::P2pFeatureFlags,
+    identity::PublicKey,
 };
 
 pub const DEVNET_SEEDS: &[&str] = &[
@@ -20,10 +29,26 @@ pub struct P2pConfig {
     pub listen_port: Option<u16>,
     /// The public key used for authentication all p2p communication.
     pub identity_pub_key: PublicKey,
+    /// Public key presented on the WebRTC connections instead of
+    /// `identity_pub_key`, so that the WebRTC peer id (e.g. of a public
+    /// signaling entry point) can't be linked to the libp2p one.
+    #[serde(default)]
+    pub webrtc_identity_pub_key: Option<PublicKey>,
     /// A list addresses of seed nodes.
     pub initial_peers: Vec<P2pConnectionOutgoingInitOpts>,
+    /// Peers from the previous session, which are reconnected to before
+    /// the initial peers.
+    #[serde(default)]
+    pub preferred_peers: Vec<P2pPreferredPeer>,
     /// External addresses
     pub external_addrs: Vec<IpAddr>,
+    /// Address families the libp2p listener is bound to.
+    #[serde(default)]
+    pub listen_family: P2pListenFamily,
+    /// Family of the peer addresses dialed first, when the peer has
+    /// addresses of both.
+    #[serde(default)]
+    pub dial_family: Option<P2pIpFamily>,
 
     pub enabled_channels: BTreeSet<ChannelId>,
 
@@ -35,6 +60,185 @@ pub struct P2pConfig {
     pub peer_discovery: bool,
 
     pub meshsub: P2pMeshsubConfig,
+
+    /// Initial feature flags, see [`P2pFeatureFlags`].
+    #[serde(default)]
+    pub feature_flags: P2pFeatureFlags,
+}
+
+impl P2pConfig {
+    /// Public key presented on the WebRTC connections.
+    pub fn webrtc_identity_pub_key(&self) -> &PublicKey {
+        self.webrtc_identity_pub_key
+            .as_ref()
+            .unwrap_or(&self.identity_pub_key)
+    }
+
+    /// Disables the propagation channels of the gossip topics not in
+    /// `topics`, so that the webrtc peers don't send us those messages.
+    ///
+    /// `topics` must include [`P2pGossipTopic::Blocks`], without the best
+    /// tip propagation the node can't follow the chain.
+    pub fn set_gossip_topics(&mut self, topics: &[P2pGossipTopic]) -> Result<(), String> {
+        if !topics.contains(&P2pGossipTopic::Blocks) {
+            return Err("`blocks` gossip topic is required to follow the chain".to_owned());
+        }
+        P2pGossipTopic::ALL
+            .into_iter()
+            .filter(|topic| !topics.contains(topic))
+            .flat_map(P2pGossipTopic::channels)
+            .for_each(|channel| {
+                self.enabled_channels.remove(channel);
+            });
+        Ok(())
+    }
+
+    /// Whether the node is subscribed to the messages of the `topic`.
+    pub fn is_gossip_topic_enabled(&self, topic: P2pGossipTopic) -> bool {
+        topic
+            .channels()
+            .iter()
+            .any(|channel| self.enabled_channels.contains(channel))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum P2pListenFamily {
+    #[default]
+    Ipv4,
+    /// IPv6 only socket, doesn't accept the IPv4 mapped connections.
+    Ipv6,
+    /// Separate listeners for both of the families.
+    DualStack,
+}
+
+impl P2pListenFamily {
+    pub fn includes(self, ip: &IpAddr) -> bool {
+        match self {
+            Self::Ipv4 => ip.is_ipv4(),
+            Self::Ipv6 => ip.is_ipv6(),
+            Self::DualStack => true,
+        }
+    }
+}
+
+impl FromStr for P2pListenFamily {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ipv4" => Ok(Self::Ipv4),
+            "ipv6" => Ok(Self::Ipv6),
+            "dual-stack" => Ok(Self::DualStack),
+            _ => Err(format!(
+                "invalid listen family `{s}`, expected `ipv4`, `ipv6` or `dual-stack`"
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum P2pIpFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl P2pIpFamily {
+    pub fn includes(self, ip: &IpAddr) -> bool {
+        match self {
+            Self::Ipv4 => ip.is_ipv4(),
+            Self::Ipv6 => ip.is_ipv6(),
+        }
+    }
+
+    /// Moves the addresses of the `preferred` family to the front, keeping
+    /// the order otherwise.
+    pub fn sort_addrs(preferred: Option<Self>, addrs: &mut [SocketAddr]) {
+        if let Some(preferred) = preferred {
+            addrs.sort_by_key(|addr| !preferred.includes(&addr.ip()));
+        }
+    }
+}
+
+impl FromStr for P2pIpFamily {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ipv4" => Ok(Self::Ipv4),
+            "ipv6" => Ok(Self::Ipv6),
+            _ => Err(format!(
+                "invalid address family `{s}`, expected `ipv4` or `ipv6`"
+            )),
+        }
+    }
+}
+
+/// Types of the gossiped messages. Resource-constrained nodes (e.g. the
+/// webnode) may subscribe only to a subset of them.
+///
+/// Over libp2p they all share one meshsub topic, so the peers still send
+/// the unsubscribed messages, which are ignored without being decoded.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum P2pGossipTopic {
+    Blocks,
+    Transactions,
+    /// Snarks and snark job commitments.
+    Snarks,
+}
+
+impl P2pGossipTopic {
+    pub const ALL: [Self; 3] = [Self::Blocks, Self::Transactions, Self::Snarks];
+
+    /// Channels, used to propagate the messages of the topic over webrtc.
+    pub fn channels(self) -> &'static [ChannelId] {
+        match self {
+            Self::Blocks => &[ChannelId::BestTipPropagation],
+            Self::Transactions => &[ChannelId::TransactionPropagation],
+            Self::Snarks => &[
+                ChannelId::SnarkPropagation,
+                ChannelId::SnarkJobCommitmentPropagation,
+            ],
+        }
+    }
+
+    /// Topic of the libp2p gossip message.
+    pub fn of_message(message: &GossipNetMessageV2) -> Self {
+        match message {
+            GossipNetMessageV2::NewState(_) => Self::Blocks,
+            GossipNetMessageV2::TransactionPoolDiff { .. } => Self::Transactions,
+            GossipNetMessageV2::SnarkPoolDiff { .. } => Self::Snarks,
+        }
+    }
+
+    /// Topic of the binprot encoded [`GossipNetMessageV2`], read from its
+    /// variant tag, so that unsubscribed messages aren't decoded at all.
+    pub fn of_encoded(encoded: &[u8]) -> Option<Self> {
+        match encoded.first()? {
+            0 => Some(Self::Blocks),
+            1 => Some(Self::Snarks),
+            2 => Some(Self::Transactions),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for P2pGossipTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blocks" => Ok(Self::Blocks),
+            "transactions" => Ok(Self::Transactions),
+            "snarks" => Ok(Self::Snarks),
+            _ => Err(format!(
+                "invalid gossip topic `{s}`, expected `blocks`, `transactions` or `snarks`"
+            )),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -73,14 +277,19 @@ pub struct P2pTimeouts {
     pub block: Option<Duration>,
     pub snark: Option<Duration>,
     pub transaction: Option<Duration>,
+    pub transaction_pool_summary: Option<Duration>,
     pub initial_peers: Option<Duration>,
+    pub genesis_proof: Option<Duration>,
     pub kademlia_bootstrap: Option<Duration>,
     pub kademlia_initial_bootstrap: Option<Duration>,
     pub select: Option<Duration>,
     pub pnet: Option<Duration>,
+    /// Time given to a single ICE restart attempt of a degraded
+    /// webrtc connection, before retrying or disconnecting.
+    pub ice_restart: Option<Duration>,
 }
 
-fn from_env_or(name: &str, default: Option<Duration>) -> Option<Duration> {
+pub(crate) fn from_env_or(name: &str, default: Option<Duration>) -> Option<Duration> {
     None.or_else(|| {
         let val = std::env::var(name).ok()?.to_ascii_lowercase();
         Some(match val.as_ref() {
@@ -123,7 +332,12 @@ impl Default for P2pTimeouts {
             block: from_env_or("BLOCK_TIMEOUT", Some(Duration::from_secs(8))),
             snark: from_env_or("SNARK_TIMEOUT", Some(Duration::from_secs(8))),
             transaction: from_env_or("TRANSACTION_TIMEOUT", Some(Duration::from_secs(8))),
+            transaction_pool_summary: from_env_or(
+                "TRANSACTION_POOL_SUMMARY_TIMEOUT",
+                Some(Duration::from_secs(10)),
+            ),
             initial_peers: from_env_or("INITIAL_PEERS_TIMEOUT", Some(Duration::from_secs(5))),
+            genesis_proof: from_env_or("GENESIS_PROOF_TIMEOUT", Some(Duration::from_secs(20))),
             kademlia_bootstrap: from_env_or(
                 "KADEMLIA_BOOTSTRAP_TIMEOUT",
                 Some(Duration::from_secs(60)),
@@ -134,6 +348,7 @@ impl Default for P2pTimeouts {
             ),
             select: from_env_or("SELECT_TIMEOUT", Some(Duration::from_secs(5))),
             pnet: from_env_or("PNET_TIMEOUT", Some(Duration::from_secs(2))),
+            ice_restart: from_env_or("ICE_RESTART_TIMEOUT", Some(Duration::from_secs(10))),
         }
     }
 }
@@ -265,6 +480,11 @@ pub struct P2pLimits {
     rpc_get_staged_ledger: Limit<usize>,
     rpc_get_transition_chain: Limit<usize>,
     rpc_get_some_initial_peers: Limit<usize>,
+
+    max_ice_restart_attempts: Limit<usize>,
+
+    rpc_max_in_flight_per_peer: Limit<usize>,
+    rpc_timeout_retries: Limit<usize>,
 }
 
 macro_rules! limit {
@@ -383,6 +603,47 @@ impl P2pLimits {
         #[doc = "RPC some_initial_peers"]
         rpc_get_some_initial_peers
     );
+
+    limit!(
+        /// Maximum number of ICE restart attempts for a degraded webrtc connection.
+        max_ice_restart_attempts,
+        /// Sets the maximum number of ICE restart attempts before the connection is dropped.
+        with_max_ice_restart_attempts
+    );
+
+    /// Maximum number of rpc requests sent to a peer, waiting for the response.
+    ///
+    /// Never more than the peer processes concurrently
+    /// ([`MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS`]), and at least one.
+    pub fn rpc_max_in_flight_per_peer(&self) -> usize {
+        Option::from(self.rpc_max_in_flight_per_peer)
+            .unwrap_or(usize::MAX)
+            .clamp(1, MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS)
+    }
+
+    /// Sets the maximum number of rpc requests waiting for the response from a peer.
+    pub fn with_rpc_max_in_flight_per_peer<T: Into<Limit<usize>>>(mut self, limit: T) -> Self {
+        self.rpc_max_in_flight_per_peer = limit.into();
+        self
+    }
+
+    /// Number of times a timed out rpc request is resent, before the peer is
+    /// considered unresponsive.
+    ///
+    /// The peer may still be processing the timed out attempts, so they
+    /// take up its concurrent requests and there can't be more retries than
+    /// [`MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS`] allows.
+    pub fn rpc_timeout_retries(&self) -> usize {
+        Option::from(self.rpc_timeout_retries)
+            .unwrap_or(usize::MAX)
+            .min(MAX_P2P_RPC_REMOTE_CONCURRENT_REQUESTS.saturating_sub(1))
+    }
+
+    /// Sets the number of times a timed out rpc request is resent.
+    pub fn with_rpc_timeout_retries<T: Into<Limit<usize>>>(mut self, limit: T) -> Self {
+        self.rpc_timeout_retries = limit.into();
+        self
+    }
 }
 
 impl Default for P2pLimits {
@@ -406,6 +667,12 @@ impl Default for P2pLimits {
         let rpc_get_transition_chain = Limit::Some(3_500_000); // 2979112 as observed
         let rpc_get_some_initial_peers = Limit::Some(32_000); // TODO: calculate
 
+        let max_ice_restart_attempts = Limit::Some(3);
+
+        // libp2p rpc streams only support a single pending query anyway.
+        let rpc_max_in_flight_per_peer = Limit::Some(1);
+        let rpc_timeout_retries = Limit::Some(0);
+
         Self {
             max_peers,
             min_peers_in_state,
@@ -425,6 +692,11 @@ impl Default for P2pLimits {
             rpc_get_staged_ledger,
             rpc_get_transition_chain,
             rpc_get_some_initial_peers,
+
+            max_ice_restart_attempts,
+
+            rpc_max_in_flight_per_peer,
+            rpc_timeout_retries,
         }
     }
 }
@@ -432,7 +704,8 @@ impl Default for P2pLimits {
 #[cfg(test)]
 mod tests {
 
-    use super::Limit;
+    use super::{Limit, P2pConfig, P2pGossipTopic, P2pIpFamily, P2pLimits};
+    use crate::{channels::ChannelId, identity::SecretKey, P2pMeshsubConfig};
 
     #[test]
     fn test_limits() {
@@ -445,4 +718,95 @@ mod tests {
         assert!(0 < unlimited);
         assert!(usize::MAX < unlimited);
     }
+
+    #[test]
+    fn test_rpc_limits_clamped() {
+        let limits = P2pLimits::default()
+            .with_rpc_max_in_flight_per_peer(Limit::Unlimited)
+            .with_rpc_timeout_retries(Limit::Unlimited);
+        assert_eq!(limits.rpc_max_in_flight_per_peer(), 5);
+        assert_eq!(limits.rpc_timeout_retries(), 4);
+
+        let limits = limits
+            .with_rpc_max_in_flight_per_peer(Some(0))
+            .with_rpc_timeout_retries(Some(2));
+        assert_eq!(limits.rpc_max_in_flight_per_peer(), 1);
+        assert_eq!(limits.rpc_timeout_retries(), 2);
+    }
+
+    #[test]
+    fn test_sort_addrs() {
+        let v4 = "1.2.3.4:8302".parse().unwrap();
+        let v6 = "[2001:db8::1]:8302".parse().unwrap();
+        let v4_2 = "5.6.7.8:8302".parse().unwrap();
+
+        let mut addrs = [v4, v6, v4_2];
+        P2pIpFamily::sort_addrs(None, &mut addrs);
+        assert_eq!(addrs, [v4, v6, v4_2]);
+        P2pIpFamily::sort_addrs(Some(P2pIpFamily::Ipv6), &mut addrs);
+        assert_eq!(addrs, [v6, v4, v4_2]);
+        P2pIpFamily::sort_addrs(Some(P2pIpFamily::Ipv4), &mut addrs);
+        assert_eq!(addrs, [v4, v4_2, v6]);
+    }
+
+    #[test]
+    fn test_gossip_topics_parse() {
+        let topics = "blocks,snarks"
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<P2pGossipTopic>, _>>()
+            .unwrap();
+        assert_eq!(topics, [P2pGossipTopic::Blocks, P2pGossipTopic::Snarks]);
+        assert!("tx".parse::<P2pGossipTopic>().is_err());
+    }
+
+    #[test]
+    fn test_set_gossip_topics() {
+        let mut config = P2pConfig {
+            libp2p_port: None,
+            listen_port: None,
+            identity_pub_key: SecretKey::deterministic(0).public_key(),
+            webrtc_identity_pub_key: None,
+            initial_peers: Vec::new(),
+            preferred_peers: Vec::new(),
+            external_addrs: Vec::new(),
+            listen_family: Default::default(),
+            dial_family: None,
+            enabled_channels: ChannelId::iter_all().collect(),
+            timeouts: Default::default(),
+            limits: Default::default(),
+            peer_discovery: true,
+            meshsub: P2pMeshsubConfig::default(),
+            feature_flags: Default::default(),
+        };
+
+        assert!(config.set_gossip_topics(&[P2pGossipTopic::Snarks]).is_err());
+        assert!(config.is_gossip_topic_enabled(P2pGossipTopic::Transactions));
+
+        config.set_gossip_topics(&[P2pGossipTopic::Blocks]).unwrap();
+        assert!(config.is_gossip_topic_enabled(P2pGossipTopic::Blocks));
+        assert!(!config.is_gossip_topic_enabled(P2pGossipTopic::Transactions));
+        assert!(!config.is_gossip_topic_enabled(P2pGossipTopic::Snarks));
+    }
+
+    #[test]
+    fn test_gossip_topic_of_encoded() {
+        use binprot::BinProtWrite;
+        use mina_p2p_messages::{
+            gossip::GossipNetMessageV2, list::List,
+            v2::NetworkPoolTransactionPoolDiffVersionedStableV2,
+        };
+
+        let message = GossipNetMessageV2::TransactionPoolDiff {
+            message: NetworkPoolTransactionPoolDiffVersionedStableV2(List::new()),
+            nonce: 0u32.into(),
+        };
+        let mut encoded = vec![];
+        message.binprot_write(&mut encoded).unwrap();
+        assert_eq!(
+            P2pGossipTopic::of_encoded(&encoded),
+            Some(P2pGossipTopic::of_message(&message))
+        );
+        assert_eq!(P2pGossipTopic::of_encoded(&[]), None);
+    }
 }
@@ -13,6 +13,7 @@ impl P2pEffectfulAction {
             P2pEffectfulAction::Connection(action) => match action {
                 P2pConnectionEffectfulAction::Outgoing(action) => action.effects(&meta, store),
                 P2pConnectionEffectfulAction::Incoming(action) => action.effects(&meta, store),
+                P2pConnectionEffectfulAction::IceRestart(action) => action.effects(&meta, store),
             },
             P2pEffectfulAction::Disconnection(action) => action.effects(&meta, store),
             #[cfg(feature = "p2p-libp2p")]
@@ -1,5 +1,6 @@
 use std::fmt;
 use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use derive_more::From;
 use serde::{Deserialize, Serialize};
@@ -11,10 +12,46 @@ use crate::webrtc::ConnectionAuthEncrypted;
 use crate::ConnectionAddr;
 use crate::{
     channels::{transaction::TransactionPropagationChannelMsg, ChannelId, ChannelMsg, MsgId},
-    connection::P2pConnectionResponse,
+    connection::{P2pConnectionFinalizeError, P2pConnectionResponse},
     PeerId,
 };
 
+/// Id of the command sent to the p2p service, carried by the events the
+/// service emits in response to it.
+///
+/// Logged by the service along with the command, so that the traces of
+/// the service threads can be stitched with the events and the actions
+/// which triggered the command.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct P2pCorrelationId(u64);
+
+impl P2pCorrelationId {
+    /// New id, unique within the process.
+    pub fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Logs the id returned by the service along with the action which
+    /// issued the command, so that the action can be matched with the
+    /// events emitted in response to it.
+    pub fn log_issued(self, time: redux::Timestamp, peer_id: PeerId, action: &'static str) {
+        openmina_core::debug!(
+            time;
+            kind = "P2pServiceCmdIssued",
+            peer_id = peer_id.to_string(),
+            correlation_id = self.to_string(),
+            action = action
+        );
+    }
+}
+
+impl fmt::Display for P2pCorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cid:{}", self.0)
+    }
+}
+
 #[derive(Serialize, Deserialize, From, Debug, Clone)]
 pub enum P2pEvent {
     Connection(P2pConnectionEvent),
@@ -58,21 +95,80 @@ pub enum MioEvent {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pConnectionEvent {
-    OfferSdpReady(PeerId, Result<String, String>),
-    AnswerSdpReady(PeerId, Result<String, String>),
-    AnswerReceived(PeerId, P2pConnectionResponse),
-    Finalized(PeerId, Result<ConnectionAuthEncrypted, String>),
+    OfferSdpReady(PeerId, P2pCorrelationId, Result<String, String>),
+    AnswerSdpReady(PeerId, P2pCorrelationId, Result<String, String>),
+    AnswerReceived(PeerId, P2pCorrelationId, P2pConnectionResponse),
+    Finalized(
+        PeerId,
+        P2pCorrelationId,
+        Result<ConnectionAuthEncrypted, P2pConnectionFinalizeError>,
+    ),
+    /// Connectivity to the peer is lost, but the connection is kept
+    /// around so that it can be recovered using an ICE restart.
+    Degraded(PeerId),
+    /// Connectivity to the peer is restored.
+    Recovered(PeerId),
+    /// Result of the ICE restart offer/answer exchange initiated by us.
+    IceRestartNegotiated(PeerId, P2pCorrelationId, Result<(), String>),
+    /// Answer sdp for the ICE restart offer received from the peer.
+    IceRestartAnswerSdpReady(PeerId, P2pCorrelationId, Result<String, String>),
     Closed(PeerId),
 }
 
 #[derive(Serialize, Deserialize, From, Debug, Clone)]
 pub enum P2pChannelEvent {
-    Opened(PeerId, ChannelId, Result<(), String>),
-    Sent(PeerId, ChannelId, MsgId, Result<(), String>),
+    Opened(PeerId, ChannelId, P2pCorrelationId, Result<(), String>),
+    Sent(
+        PeerId,
+        ChannelId,
+        MsgId,
+        P2pCorrelationId,
+        Result<(), P2pChannelSendError>,
+    ),
     Received(PeerId, Result<ChannelMsg, String>),
     Closed(PeerId, ChannelId),
 }
 
+#[derive(Serialize, Deserialize, thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum P2pChannelSendError {
+    /// Message waited in the send queue of the channel for longer than
+    /// its [`ChannelMsg::send_ttl`], so it was dropped without being sent.
+    #[error("expired")]
+    Expired,
+    /// Encoded message is bigger than [`ChannelId::max_msg_size`], so it
+    /// wasn't sent, as the peer would reject it.
+    #[error("message length {len} over the limit {limit}")]
+    LenOverLimit { len: usize, limit: usize },
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl P2pEvent {
+    /// Id of the service command this event is a response to.
+    pub fn correlation_id(&self) -> Option<P2pCorrelationId> {
+        match self {
+            Self::Connection(e) => match e {
+                P2pConnectionEvent::OfferSdpReady(_, cid, _)
+                | P2pConnectionEvent::AnswerSdpReady(_, cid, _)
+                | P2pConnectionEvent::AnswerReceived(_, cid, _)
+                | P2pConnectionEvent::Finalized(_, cid, _)
+                | P2pConnectionEvent::IceRestartNegotiated(_, cid, _)
+                | P2pConnectionEvent::IceRestartAnswerSdpReady(_, cid, _) => Some(*cid),
+                P2pConnectionEvent::Degraded(_)
+                | P2pConnectionEvent::Recovered(_)
+                | P2pConnectionEvent::Closed(_) => None,
+            },
+            Self::Channel(e) => match e {
+                P2pChannelEvent::Opened(_, _, cid, _) | P2pChannelEvent::Sent(_, _, _, cid, _) => {
+                    Some(*cid)
+                }
+                P2pChannelEvent::Received(..) | P2pChannelEvent::Closed(..) => None,
+            },
+            Self::MioEvent(_) => None,
+        }
+    }
+}
+
 fn res_kind<T, E>(res: &Result<T, E>) -> &'static str {
     match res {
         Err(_) => "Err",
@@ -95,13 +191,13 @@ impl fmt::Display for P2pConnectionEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Connection, ")?;
         match self {
-            Self::OfferSdpReady(peer_id, res) => {
+            Self::OfferSdpReady(peer_id, _, res) => {
                 write!(f, "OfferSdpReady, {peer_id}, {}", res_kind(res))
             }
-            Self::AnswerSdpReady(peer_id, res) => {
+            Self::AnswerSdpReady(peer_id, _, res) => {
                 write!(f, "AnswerSdpReady, {peer_id}, {}", res_kind(res))
             }
-            Self::AnswerReceived(peer_id, ans) => match ans {
+            Self::AnswerReceived(peer_id, _, ans) => match ans {
                 P2pConnectionResponse::Accepted(_) => {
                     write!(f, "AnswerReceived, {peer_id}, Accepted")
                 }
@@ -115,7 +211,17 @@ impl fmt::Display for P2pConnectionEvent {
                     write!(f, "AnswerReceived, {peer_id}, InternalError")
                 }
             },
-            Self::Finalized(peer_id, res) => write!(f, "Finalized, {peer_id}, {}", res_kind(res)),
+            Self::Finalized(peer_id, _, res) => {
+                write!(f, "Finalized, {peer_id}, {}", res_kind(res))
+            }
+            Self::Degraded(peer_id) => write!(f, "Degraded, {peer_id}"),
+            Self::Recovered(peer_id) => write!(f, "Recovered, {peer_id}"),
+            Self::IceRestartNegotiated(peer_id, _, res) => {
+                write!(f, "IceRestartNegotiated, {peer_id}, {}", res_kind(res))
+            }
+            Self::IceRestartAnswerSdpReady(peer_id, _, res) => {
+                write!(f, "IceRestartAnswerSdpReady, {peer_id}, {}", res_kind(res))
+            }
             Self::Closed(peer_id) => write!(f, "Closed, {peer_id}"),
         }
     }
@@ -124,19 +230,20 @@ impl fmt::Display for P2pConnectionEvent {
 impl fmt::Display for P2pChannelEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use crate::channels::best_tip::BestTipPropagationChannelMsg;
+        use crate::channels::checkpoint::CheckpointPropagationChannelMsg;
         use crate::channels::rpc::RpcChannelMsg;
         use crate::channels::snark::SnarkPropagationChannelMsg;
         use crate::channels::snark_job_commitment::SnarkJobCommitmentPropagationChannelMsg;
 
         write!(f, "Channel, ")?;
         match self {
-            Self::Opened(peer_id, chan_id, res) => {
+            Self::Opened(peer_id, chan_id, _, res) => {
                 write!(f, "Opened, {peer_id}, {chan_id:?}, {}", res_kind(res))
             }
             Self::Closed(peer_id, chan_id) => {
                 write!(f, "Closed, {peer_id}, {chan_id:?}")
             }
-            Self::Sent(peer_id, chan_id, msg_id, res) => {
+            Self::Sent(peer_id, chan_id, msg_id, _, res) => {
                 write!(
                     f,
                     "Sent, {peer_id}, {chan_id:?}, {msg_id:?}, {}",
@@ -185,6 +292,25 @@ impl fmt::Display for P2pChannelEvent {
                                     Err(_) => write!(f, "[Block_with_invalid_field]"),
                                 }
                             }
+                            BestTipPropagationChannelMsg::CompactBestTip(block) => {
+                                match block.block.try_hash() {
+                                    Ok(block_hash) => write!(f, "Compact, {}", block_hash),
+                                    Err(_) => write!(f, "Compact, [Block_with_invalid_field]"),
+                                }
+                            }
+                            BestTipPropagationChannelMsg::GetMissing(missing) => write!(
+                                f,
+                                "GetMissing, commands: {}, works: {}",
+                                missing.commands.len(),
+                                missing.works.len()
+                            ),
+                            BestTipPropagationChannelMsg::Missing(fill) => write!(
+                                f,
+                                "Missing, commands: {}, works: {}",
+                                fill.commands.len(),
+                                fill.works.len()
+                            ),
+                            BestTipPropagationChannelMsg::GetFull => write!(f, "GetFull"),
                         }
                     }
                     ChannelMsg::TransactionPropagation(v) => match v {
@@ -199,6 +325,12 @@ impl fmt::Display for P2pChannelEvent {
                             "Transaction, fee: {}, fee_payer: {}, hash: {}",
                             tx.fee, tx.fee_payer, tx.hash,
                         ),
+                        TransactionPropagationChannelMsg::Announce { hashes } => {
+                            write!(f, "Announce, count: {}", hashes.len())
+                        }
+                        TransactionPropagationChannelMsg::GetByHash { hashes } => {
+                            write!(f, "GetByHash, count: {}", hashes.len())
+                        }
                     },
                     ChannelMsg::SnarkPropagation(v) => match v {
                         SnarkPropagationChannelMsg::GetNext { limit } => {
@@ -214,6 +346,13 @@ impl fmt::Display for P2pChannelEvent {
                             snark.prover,
                             snark.job_id
                         ),
+                        SnarkPropagationChannelMsg::SnarkBatch(batch) => write!(
+                            f,
+                            "SnarkBatch, count: {}, fee: {}, snarker: {}",
+                            batch.len(),
+                            batch.fee.as_u64(),
+                            batch.prover
+                        ),
                     },
                     ChannelMsg::SnarkJobCommitmentPropagation(v) => match v {
                         SnarkJobCommitmentPropagationChannelMsg::GetNext { limit } => {
@@ -257,6 +396,21 @@ impl fmt::Display for P2pChannelEvent {
                             }
                         }
                     },
+                    ChannelMsg::CheckpointPropagation(v) => match v {
+                        CheckpointPropagationChannelMsg::GetNext { limit } => {
+                            write!(f, "GetNext, limit: {limit}")
+                        }
+                        CheckpointPropagationChannelMsg::WillSend { count } => {
+                            write!(f, "WillSend, count: {count}")
+                        }
+                        CheckpointPropagationChannelMsg::Checkpoint(checkpoint) => write!(
+                            f,
+                            "Checkpoint, height: {}, state_hash: {}, signer: {}",
+                            checkpoint.height.as_u32(),
+                            checkpoint.state_hash,
+                            checkpoint.signer
+                        ),
+                    },
                 }
             }
         }
@@ -4,8 +4,8 @@ use crate::{
         streaming_rpc::P2pChannelsStreamingRpcAction, P2pChannelsState,
     },
     connection::{
-        incoming::P2pConnectionIncomingAction, outgoing::P2pConnectionOutgoingAction,
-        P2pConnectionState,
+        ice_restart::P2pConnectionIceRestartAction, incoming::P2pConnectionIncomingAction,
+        outgoing::P2pConnectionOutgoingAction, P2pConnectionState, P2pHandshakeFailureReason,
     },
     disconnection::{P2pDisconnectedState, P2pDisconnectionAction},
     P2pAction, P2pNetworkKadKey, P2pNetworkKademliaAction, P2pNetworkPnetAction,
@@ -56,6 +56,11 @@ impl P2pState {
             P2pAction::Network(_action) => {
                 #[cfg(feature = "p2p-libp2p")]
                 {
+                    if let Some((peer_id, reason)) =
+                        P2pHandshakeFailureReason::from_network_action(state, &_action)
+                    {
+                        state.handshake_failure_record(meta.time(), peer_id, Some(reason));
+                    }
                     let limits = state.config.limits;
                     P2pNetworkState::reducer(
                         Substate::from_compatible_substate(state_context),
@@ -65,6 +70,9 @@ impl P2pState {
                 }
                 Ok(())
             }
+            P2pAction::FeatureFlags(action) => {
+                Self::feature_flags_reducer(state_context, meta.with_action(action))
+            }
         }
     }
 
@@ -84,6 +92,7 @@ impl P2pState {
         dispatcher.push(P2pConnectionOutgoingAction::RandomInit);
         dispatcher.push(P2pDisconnectionAction::RandomTry);
 
+        state.p2p_connect_preferred_peers(dispatcher);
         state.p2p_connect_initial_peers(dispatcher);
         state.p2p_try_reconnect_disconnected_peers(dispatcher, time)?;
         state.p2p_discovery(dispatcher, time)?;
@@ -124,6 +133,24 @@ impl P2pState {
                 false => dispatcher.push(P2pConnectionIncomingAction::Timeout { peer_id }),
             });
 
+        let max_ice_restart_attempts = self.config.limits.max_ice_restart_attempts();
+        self.peers
+            .iter()
+            .filter_map(|(peer_id, peer)| {
+                let ice_restart = peer.status.as_ready()?.ice_restart.as_ref()?;
+                ice_restart
+                    .is_timed_out(
+                        time,
+                        timeouts,
+                        max_ice_restart_attempts,
+                        self.is_ice_restart_initiator(peer_id),
+                    )
+                    .then_some(*peer_id)
+            })
+            .for_each(|peer_id| {
+                dispatcher.push(P2pConnectionIceRestartAction::Timeout { peer_id })
+            });
+
         Ok(())
     }
 
@@ -156,6 +183,31 @@ impl P2pState {
         Ok(())
     }
 
+    /// Dials the peers from the previous session, once per session. They
+    /// are reconnected to by [`Self::p2p_try_reconnect_disconnected_peers`]
+    /// afterwards, like any other peer.
+    fn p2p_connect_preferred_peers<State, Action>(&self, dispatcher: &mut Dispatcher<Action, State>)
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        if self.already_has_min_peers() {
+            return;
+        }
+
+        self.config
+            .preferred_peers
+            .iter()
+            .filter(|peer| !self.peers.contains_key(peer.opts.peer_id()))
+            .for_each(|peer| {
+                dispatcher.push(P2pConnectionOutgoingAction::Init {
+                    opts: peer.opts.clone(),
+                    rpc_id: None,
+                    on_success: None,
+                });
+            });
+    }
+
     fn p2p_connect_initial_peers<State, Action>(&self, dispatcher: &mut Dispatcher<Action, State>)
     where
         State: crate::P2pStateTrait,
@@ -164,6 +216,11 @@ impl P2pState {
         if self.ready_peers_iter().count() >= self.config.initial_peers.len() {
             return;
         }
+        // Give the preferred peers a chance first, so that we don't
+        // renegotiate with the seeds if they are reachable.
+        if self.is_reconnecting_preferred_peers() {
+            return;
+        }
 
         self.config
             .initial_peers
@@ -194,6 +251,9 @@ impl P2pState {
                 if is_streaming {
                     dispatcher.push(P2pChannelsStreamingRpcAction::Timeout { peer_id, id });
                 } else {
+                    // only one of them is enabled, depending on whether
+                    // the request can still be retried.
+                    dispatcher.push(P2pChannelsRpcAction::RequestRetry { peer_id, id });
                     dispatcher.push(P2pChannelsRpcAction::Timeout { peer_id, id });
                 }
             });
@@ -9,7 +9,7 @@ use openmina_core::{
     impl_substate_access,
     requests::RpcId,
     snark::{Snark, SnarkInfo, SnarkJobCommitment},
-    transaction::{TransactionInfo, TransactionWithHash},
+    transaction::{TransactionHash, TransactionInfo, TransactionWithHash},
     ChainId, SubstateAccess,
 };
 
@@ -20,22 +20,30 @@ use serde::{Deserialize, Serialize};
 use crate::{
     bootstrap::P2pNetworkKadBootstrapState,
     channels::{
+        best_tip::CompactBlockStats,
+        checkpoint::SignedCheckpoint,
         rpc::{P2pRpcId, P2pRpcRequest, P2pRpcResponse},
         streaming_rpc::{P2pStreamingRpcId, P2pStreamingRpcResponseFull},
         ChannelId, P2pChannelsState,
     },
     connection::{
+        ice_restart::P2pConnectionIceRestartState,
         incoming::P2pConnectionIncomingState,
         outgoing::{
             P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingState,
         },
-        P2pConnectionResponse, P2pConnectionState,
+        P2pConnectionEvents, P2pConnectionResponse, P2pConnectionState, P2pHandshakeFailureReason,
+        P2pHandshakeFailureStats, P2pPeerDialStats, P2pPreferredPeer, P2pSessionResumptionStats,
+        P2P_PREFERRED_PEERS_MAX,
     },
+    feature_flags::P2pFeatureFlags,
     is_time_passed,
     network::{
         identify::{P2pNetworkIdentify, P2pNetworkIdentifyState},
+        rpc::P2pRpcPeerVersions,
         P2pNetworkState,
     },
+    webrtc::P2pCapabilities,
     Limit, P2pConfig, P2pLimits, P2pNetworkKadState, P2pNetworkPubsubMessageCacheId,
     P2pNetworkPubsubState, P2pNetworkSchedulerState, P2pTimeouts, PeerId,
 };
@@ -50,6 +58,25 @@ pub struct P2pState {
 
     pub last_random_disconnection_try: redux::Timestamp,
 
+    /// Hit rate of the pools when reconstructing compact best tips.
+    pub compact_block_stats: CompactBlockStats,
+
+    /// Handshake failures with all the peers, per reason.
+    pub handshake_failures: P2pHandshakeFailureStats,
+
+    /// Latest connection milestones and errors, for diagnostics.
+    #[serde(default)]
+    pub connection_events: P2pConnectionEvents,
+
+    /// Reconnects to the peers from the previous session.
+    #[serde(default)]
+    pub session_resumption: P2pSessionResumptionStats,
+
+    /// Initialized from [`P2pConfig::feature_flags`], may be changed at
+    /// runtime.
+    #[serde(default)]
+    pub feature_flags: P2pFeatureFlags,
+
     pub callbacks: P2pCallbacks,
 }
 
@@ -77,6 +104,15 @@ impl P2pState {
                 summary = format!("Current node's id: {peer_id_str}"),
                 peer_id_str = peer_id_str,
             );
+            if let Some(webrtc_pub_key) = &config.webrtc_identity_pub_key {
+                let peer_id_str = webrtc_pub_key.peer_id().to_libp2p_string();
+                openmina_core::log::info!(
+                    openmina_core::log::system_time();
+                    kind = "P2pState new",
+                    summary = format!("Current node's webrtc id: {peer_id_str}"),
+                    peer_id_str = peer_id_str,
+                );
+            }
         }
 
         let known_peers = if cfg!(feature = "p2p-libp2p") {
@@ -95,6 +131,7 @@ impl P2pState {
             Vec::new()
         };
 
+        let session_resumption = P2pSessionResumptionStats::new(config.preferred_peers.len());
         let network = P2pNetworkState::new(
             config.identity_pub_key.clone(),
             addrs,
@@ -102,6 +139,7 @@ impl P2pState {
             chain_id,
             config.peer_discovery,
         );
+        let feature_flags = config.feature_flags;
         Self {
             chain_id: chain_id.clone(),
             config,
@@ -110,6 +148,12 @@ impl P2pState {
 
             last_random_disconnection_try: redux::Timestamp::ZERO,
 
+            compact_block_stats: Default::default(),
+            handshake_failures: Default::default(),
+            connection_events: Default::default(),
+            session_resumption,
+            feature_flags,
+
             callbacks,
         }
     }
@@ -118,6 +162,17 @@ impl P2pState {
         self.config.identity_pub_key.peer_id()
     }
 
+    /// Peer id presented on the WebRTC connections, same as [`Self::my_id`]
+    /// unless a separate WebRTC identity is configured.
+    pub fn my_webrtc_id(&self) -> PeerId {
+        self.config.webrtc_identity_pub_key().peer_id()
+    }
+
+    /// Whether the `peer_id` is one of the node's own identities.
+    pub fn is_my_id(&self, peer_id: &PeerId) -> bool {
+        *peer_id == self.my_id() || *peer_id == self.my_webrtc_id()
+    }
+
     pub fn peer_connection_rpc_id(&self, peer_id: &PeerId) -> Option<RpcId> {
         self.peers.get(peer_id)?.connection_rpc_id()
     }
@@ -206,11 +261,8 @@ impl P2pState {
         rpc_id: P2pRpcId,
         now: redux::Timestamp,
     ) -> bool {
-        self.get_ready_peer(peer_id).is_some_and(|p| {
-            p.channels
-                .rpc
-                .is_timed_out(rpc_id, now, &self.config.timeouts)
-        })
+        self.get_ready_peer(peer_id)
+            .is_some_and(|p| p.channels.rpc.is_timed_out(rpc_id, now))
     }
 
     pub fn is_peer_streaming_rpc_timed_out(
@@ -229,18 +281,20 @@ impl P2pState {
     pub fn peer_rpc_timeouts(&self, now: redux::Timestamp) -> Vec<(PeerId, P2pRpcId, bool)> {
         let config = &self.config.timeouts;
         self.ready_peers_iter()
-            .flat_map(|(peer_id, s)| {
-                let pending_rpc = s.channels.rpc.pending_local_rpc_id();
+            .flat_map(|(&peer_id, s)| {
+                let timed_out_rpcs = s
+                    .channels
+                    .rpc
+                    .local()
+                    .into_iter()
+                    .flat_map(move |local| local.timed_out_iter(now))
+                    .map(move |id| (peer_id, id, false));
                 let pending_streaming_rpc = s.channels.streaming_rpc.pending_local_rpc_id();
-                IntoIterator::into_iter([
-                    pending_rpc
-                        .filter(|id| s.channels.rpc.is_timed_out(*id, now, config))
-                        .map(|id| (*peer_id, id, false)),
+                timed_out_rpcs.chain(
                     pending_streaming_rpc
                         .filter(|id| s.channels.streaming_rpc.is_timed_out(*id, now, config))
-                        .map(|id| (*peer_id, id, true)),
-                ])
-                .flatten()
+                        .map(|id| (peer_id, id, true)),
+                )
             })
             .collect()
     }
@@ -321,6 +375,60 @@ impl P2pState {
             .map(|(peer_id, peer_state)| (*peer_id, peer_state))
     }
 
+    pub fn preferred_peer(&self, peer_id: &PeerId) -> Option<&P2pPreferredPeer> {
+        self.config
+            .preferred_peers
+            .iter()
+            .find(|peer| peer.opts.peer_id() == peer_id)
+    }
+
+    /// Whether we are still reconnecting to the peers from the previous
+    /// session.
+    pub fn is_reconnecting_preferred_peers(&self) -> bool {
+        self.config
+            .preferred_peers
+            .iter()
+            .any(|peer| self.is_peer_connecting(peer.opts.peer_id()))
+    }
+
+    /// Peers to reconnect to first after the restart: the longest
+    /// connected ready peers, which we can dial.
+    pub fn preferred_peers_snapshot(&self, now: Timestamp) -> Vec<P2pPreferredPeer> {
+        let mut peers = self
+            .peers
+            .values()
+            .filter_map(|peer| {
+                let ready = peer.status.as_ready()?;
+                let opts = peer.dial_opts.clone()?;
+                Some((ready.connected_for(now), opts, ready.capabilities.clone()))
+            })
+            .collect::<Vec<_>>();
+        peers.sort_by(|a, b| b.0.cmp(&a.0));
+        peers
+            .into_iter()
+            .take(P2P_PREFERRED_PEERS_MAX)
+            .map(|(_, opts, capabilities)| P2pPreferredPeer { opts, capabilities })
+            .collect()
+    }
+
+    /// Records handshake failure with the peer, if it is one of the
+    /// classified ones.
+    pub fn handshake_failure_record(
+        &mut self,
+        time: Timestamp,
+        peer_id: Option<PeerId>,
+        reason: Option<P2pHandshakeFailureReason>,
+    ) {
+        let Some(reason) = reason else {
+            return;
+        };
+        openmina_core::warn!(time; summary = "handshake failure", reason = display(reason), peer_id = debug(peer_id));
+        self.handshake_failures.record(reason);
+        if let Some(peer) = peer_id.and_then(|peer_id| self.peers.get_mut(&peer_id)) {
+            peer.handshake_failures.record(reason);
+        }
+    }
+
     pub fn incoming_peer_connection_mut(
         &mut self,
         peer_id: &PeerId,
@@ -352,6 +460,12 @@ pub struct P2pPeerState {
     pub dial_opts: Option<P2pConnectionOutgoingInitOpts>,
     pub status: P2pPeerStatus,
     pub identify: Option<P2pNetworkIdentify>,
+    #[serde(default)]
+    pub handshake_failures: P2pHandshakeFailureStats,
+    #[serde(default)]
+    pub dial_stats: P2pPeerDialStats,
+    #[serde(default)]
+    pub rpc_versions: P2pRpcPeerVersions,
 }
 
 impl P2pPeerState {
@@ -480,6 +594,13 @@ pub struct P2pPeerStatusReady {
     pub connected_since: redux::Timestamp,
     pub channels: P2pChannelsState,
     pub best_tip: Option<ArcBlockWithHash>,
+    /// Set while connectivity to the peer is lost and we are trying to
+    /// restore it with ICE restart.
+    pub ice_restart: Option<P2pConnectionIceRestartState>,
+    /// Capabilities announced by the peer during signaling, `None` for
+    /// libp2p peers.
+    #[serde(default)]
+    pub capabilities: Option<P2pCapabilities>,
 }
 
 impl P2pPeerStatusReady {
@@ -493,6 +614,8 @@ impl P2pPeerStatusReady {
             connected_since: time,
             channels: P2pChannelsState::new(enabled_channels),
             best_tip: None,
+            ice_restart: None,
+            capabilities: None,
         }
     }
 
@@ -517,6 +640,8 @@ type OptionalCallback<T> = Option<Callback<T>>;
 pub struct P2pCallbacks {
     /// Callback for [`P2pChannelsTransactionAction::Received`]
     pub on_p2p_channels_transaction_received: OptionalCallback<(PeerId, Box<TransactionInfo>)>,
+    /// Callback for [`P2pChannelsTransactionAction::AnnounceReceived`]
+    pub on_p2p_channels_transaction_announced: OptionalCallback<(PeerId, Vec<TransactionHash>)>,
     /// Callback for [`P2pChannelsTransactionAction::Libp2pReceived`]
     pub on_p2p_channels_transactions_libp2p_received: OptionalCallback<(
         PeerId,
@@ -526,14 +651,23 @@ pub struct P2pCallbacks {
     /// Callback for [`P2pChannelsSnarkJobCommitmentAction::Received`]
     pub on_p2p_channels_snark_job_commitment_received:
         OptionalCallback<(PeerId, Box<SnarkJobCommitment>)>,
+    /// Callback for [`P2pChannelsCheckpointAction::Received`]
+    pub on_p2p_channels_checkpoint_received: OptionalCallback<(PeerId, Box<SignedCheckpoint>)>,
 
-    /// Callback for [`P2pChannelsSnarkAction::Received`]
+    /// Callback for [`P2pChannelsSnarkAction::Received`] and
+    /// [`P2pChannelsSnarkAction::BatchReceived`], called per snark.
     pub on_p2p_channels_snark_received: OptionalCallback<(PeerId, Box<SnarkInfo>)>,
     /// Callback for [`P2pChannelsSnarkAction::Libp2pReceived`]
     pub on_p2p_channels_snark_libp2p_received: OptionalCallback<(PeerId, Box<Snark>)>,
 
     /// Callback for [`P2pChannelsBestTipAction::RequestReceived`]
     pub on_p2p_channels_best_tip_request_received: OptionalCallback<PeerId>,
+    /// Callback for [`P2pChannelsBestTipAction::CompactReceived`]
+    pub on_p2p_channels_best_tip_compact_received: OptionalCallback<PeerId>,
+    /// Callback for the reconstructed compact best tip, which body must be
+    /// verified with [`P2pChannelsBestTipAction::CompactVerifySuccess`] or
+    /// [`P2pChannelsBestTipAction::CompactVerifyError`].
+    pub on_p2p_channels_best_tip_compact_reconstructed: OptionalCallback<PeerId>,
 
     /// Callback for [`P2pDisconnectionAction::Finish`]
     pub on_p2p_disconnection_finish: OptionalCallback<PeerId>,
@@ -560,7 +694,7 @@ pub struct P2pCallbacks {
     /// Callback for [`P2pChannelsRpcAction::Ready`]
     pub on_p2p_channels_rpc_ready: OptionalCallback<PeerId>,
     /// Callback for [`P2pChannelsRpcAction::Timeout`]
-    pub on_p2p_channels_rpc_timeout: OptionalCallback<(PeerId, P2pRpcId)>,
+    pub on_p2p_channels_rpc_timeout: OptionalCallback<(PeerId, P2pRpcId, Box<P2pRpcRequest>)>,
     /// Callback for [`P2pChannelsRpcAction::ResponseReceived`]
     pub on_p2p_channels_rpc_response_received:
         OptionalCallback<(PeerId, P2pRpcId, Option<Box<P2pRpcResponse>>)>,
@@ -654,3 +788,118 @@ mod measurement {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use openmina_core::DEVNET_CHAIN_ID;
+
+    use super::*;
+    use crate::{
+        identity::{PublicKey, SecretKey},
+        webrtc::{self, Host, RejectionReason},
+        P2pMeshsubConfig,
+    };
+
+    fn state(webrtc_identity_pub_key: Option<PublicKey>) -> P2pState {
+        let config = P2pConfig {
+            libp2p_port: None,
+            listen_port: None,
+            identity_pub_key: SecretKey::deterministic(0).public_key(),
+            webrtc_identity_pub_key,
+            initial_peers: Vec::new(),
+            preferred_peers: Vec::new(),
+            external_addrs: Vec::new(),
+            listen_family: Default::default(),
+            dial_family: None,
+            enabled_channels: ChannelId::iter_all().collect(),
+            timeouts: Default::default(),
+            limits: Default::default(),
+            peer_discovery: true,
+            meshsub: P2pMeshsubConfig::default(),
+            feature_flags: Default::default(),
+        };
+        P2pState::new(config, P2pCallbacks::default(), &DEVNET_CHAIN_ID)
+    }
+
+    fn offer(from: &PublicKey, target_peer_id: PeerId) -> webrtc::Offer {
+        webrtc::Offer {
+            sdp: String::new(),
+            chain_id: DEVNET_CHAIN_ID,
+            identity_pub_key: from.clone(),
+            target_peer_id,
+            host: Host::Ipv4([127, 0, 0, 1].into()),
+            listen_port: None,
+            bulk_channels: 0,
+            tx_digests: false,
+            compact_blocks: false,
+            capabilities: None,
+        }
+    }
+
+    #[test]
+    fn test_webrtc_identity_defaults_to_libp2p_one() {
+        let state = state(None);
+        assert_eq!(state.my_webrtc_id(), state.my_id());
+        assert!(state.is_my_id(&state.my_id()));
+        assert!(!state.is_my_id(&SecretKey::deterministic(1).public_key().peer_id()));
+    }
+
+    #[test]
+    fn test_dual_identity_self_dial_rejected() {
+        let webrtc_pub_key = SecretKey::deterministic(1).public_key();
+        let state = state(Some(webrtc_pub_key.clone()));
+        let libp2p_pub_key = state.config.identity_pub_key.clone();
+        let (libp2p_id, webrtc_id) = (state.my_id(), state.my_webrtc_id());
+        assert_ne!(libp2p_id, webrtc_id);
+        assert_eq!(webrtc_id, webrtc_pub_key.peer_id());
+
+        for (pub_key, peer_id) in [(&libp2p_pub_key, libp2p_id), (&webrtc_pub_key, webrtc_id)] {
+            assert!(state.is_my_id(&peer_id));
+            assert_eq!(
+                state.libp2p_incoming_accept(peer_id),
+                Err(RejectionReason::ConnectingToSelf)
+            );
+            assert_eq!(
+                state.incoming_accept(peer_id, &offer(pub_key, webrtc_id)),
+                Err(RejectionReason::ConnectingToSelf)
+            );
+        }
+
+        // Webrtc offers must target the webrtc identity.
+        let other = SecretKey::deterministic(2).public_key();
+        assert_eq!(
+            state.incoming_accept(other.peer_id(), &offer(&other, libp2p_id)),
+            Err(RejectionReason::TargetPeerIdNotMe)
+        );
+        assert_eq!(
+            state.incoming_accept(other.peer_id(), &offer(&other, webrtc_id)),
+            Ok(())
+        );
+        assert_eq!(state.libp2p_incoming_accept(other.peer_id()), Ok(()));
+    }
+
+    #[test]
+    fn test_dual_identity_ice_restart_target() {
+        let mut state = state(Some(SecretKey::deterministic(1).public_key()));
+        let other = SecretKey::deterministic(2).public_key();
+        let mut ready =
+            P2pPeerStatusReady::new(true, Timestamp::ZERO, &state.config.enabled_channels);
+        ready.ice_restart = Some(P2pConnectionIceRestartState::new(Timestamp::ZERO));
+        state.peers.insert(
+            other.peer_id(),
+            P2pPeerState {
+                is_libp2p: false,
+                dial_opts: None,
+                status: P2pPeerStatus::Ready(ready),
+                identify: None,
+                handshake_failures: Default::default(),
+                dial_stats: Default::default(),
+                rpc_versions: Default::default(),
+            },
+        );
+
+        let (libp2p_id, webrtc_id) = (state.my_id(), state.my_webrtc_id());
+        assert!(state.ice_restart_offer_accept(other.peer_id(), &offer(&other, webrtc_id)));
+        assert!(!state.ice_restart_offer_accept(other.peer_id(), &offer(&other, libp2p_id)));
+    }
+}
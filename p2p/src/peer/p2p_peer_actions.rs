@@ -1,7 +1,11 @@
+use mina_p2p_messages::{rpc_kernel::BinprotTag, versioned::Ver};
 use openmina_core::{block::ArcBlockWithHash, ActionEvent};
 use serde::{Deserialize, Serialize};
 
-use crate::{connection::outgoing::P2pConnectionOutgoingInitOpts, P2pState, PeerId};
+use crate::{
+    connection::outgoing::P2pConnectionOutgoingInitOpts, network::rpc::P2pRpcUnsupportedBy,
+    P2pState, PeerId,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(level = debug, fields(display(peer_id), debug(dial_opts), best_tip = display(&best_tip.hash), incoming))]
@@ -21,6 +25,14 @@ pub enum P2pPeerAction {
     },
     /// Remove peer from state
     Remove { peer_id: PeerId },
+    /// RPC method version is not supported by us or by the peer.
+    #[action_event(level = info, fields(display(peer_id), tag = tag.to_string(), version, debug(by)))]
+    RpcVersionUnsupported {
+        peer_id: PeerId,
+        tag: BinprotTag,
+        version: Ver,
+        by: P2pRpcUnsupportedBy,
+    },
 }
 
 impl P2pPeerAction {
@@ -30,6 +42,7 @@ impl P2pPeerAction {
             Self::Ready { peer_id, .. } => peer_id,
             Self::BestTipUpdate { peer_id, .. } => peer_id,
             Self::Remove { peer_id } => peer_id,
+            Self::RpcVersionUnsupported { peer_id, .. } => peer_id,
         }
     }
 }
@@ -38,7 +51,7 @@ impl redux::EnablingCondition<P2pState> for P2pPeerAction {
     fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
         match self {
             P2pPeerAction::Discovered { peer_id, .. } => {
-                peer_id != &state.my_id()
+                !state.is_my_id(peer_id)
                     && state
                         .peers
                         .get(peer_id)
@@ -58,6 +71,9 @@ impl redux::EnablingCondition<P2pState> for P2pPeerAction {
                 state.peers.len() > state.config.limits.min_peers_in_state()
                     && state.peers.contains_key(peer_id)
             }
+            P2pPeerAction::RpcVersionUnsupported { peer_id, .. } => {
+                state.peers.contains_key(peer_id)
+            }
         }
     }
 }
@@ -1,7 +1,10 @@
-use openmina_core::{bug_condition, Substate};
+use openmina_core::{block::prevalidate::validate_staged_ledger_diff, bug_condition, Substate};
 use redux::{ActionWithMeta, Timestamp};
 
-use crate::{P2pPeerState, P2pPeerStatus, P2pPeerStatusReady, P2pState};
+use crate::{
+    connection::P2pConnectionEventKind, disconnection::P2pDisconnectionAction,
+    P2pDisconnectionReason, P2pPeerState, P2pPeerStatus, P2pPeerStatusReady, P2pState,
+};
 
 use super::P2pPeerAction;
 
@@ -28,6 +31,9 @@ impl P2pPeerState {
                         is_libp2p: true,
                         dial_opts: None,
                         identify: None,
+                        handshake_failures: Default::default(),
+                        dial_stats: Default::default(),
+                        rpc_versions: Default::default(),
                         status: P2pPeerStatus::Disconnected {
                             time: Timestamp::ZERO,
                         },
@@ -39,14 +45,47 @@ impl P2pPeerState {
                 Ok(())
             }
             P2pPeerAction::Ready { peer_id, incoming } => {
+                let compact_blocks_enabled = p2p_state.feature_flags.compact_blocks;
+                let compression_enabled = p2p_state.feature_flags.compression;
                 let Some(peer) = p2p_state.peers.get_mut(&peer_id) else {
                     return Ok(());
                 };
-                peer.status = P2pPeerStatus::Ready(P2pPeerStatusReady::new(
+                let capabilities = match &peer.status {
+                    P2pPeerStatus::Connecting(connection) => connection.remote_capabilities(),
+                    _ => None,
+                };
+                let mut ready = P2pPeerStatusReady::new(
                     incoming,
                     meta.time(),
                     &p2p_state.config.enabled_channels,
-                ));
+                );
+                if let Some(capabilities) = &capabilities {
+                    ready.channels.transaction_digests = capabilities.tx_digests;
+                    ready.channels.compact_blocks =
+                        compact_blocks_enabled && capabilities.compact_blocks;
+                    ready.channels.snark_batches = capabilities.snark_batches;
+                    ready.channels.compression = compression_enabled && capabilities.compression;
+                    ready.channels.lossy_gossip = capabilities.lossy_gossip;
+                }
+                if let Some(preferred) = p2p_state
+                    .config
+                    .preferred_peers
+                    .iter()
+                    .find(|preferred| preferred.opts.peer_id() == &peer_id)
+                {
+                    p2p_state.session_resumption.reconnect(
+                        meta.time(),
+                        preferred,
+                        capabilities.as_ref(),
+                    );
+                }
+                ready.capabilities = capabilities;
+                peer.status = P2pPeerStatus::Ready(ready);
+                p2p_state.connection_events.push(
+                    meta.time(),
+                    peer_id,
+                    P2pConnectionEventKind::Connected { incoming },
+                );
 
                 if !peer.is_libp2p {
                     let (dispatcher, state) = state_context.into_dispatcher_and_state();
@@ -57,6 +96,15 @@ impl P2pPeerState {
                 Ok(())
             }
             P2pPeerAction::BestTipUpdate { peer_id, best_tip } => {
+                if let Err(error) = validate_staged_ledger_diff(&best_tip) {
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(P2pDisconnectionAction::Init {
+                        peer_id,
+                        reason: P2pDisconnectionReason::InvalidStagedLedgerDiff(error),
+                    });
+                    return Ok(());
+                }
+
                 let Some(peer) = p2p_state.get_ready_peer_mut(&peer_id) else {
                     bug_condition!("Peer state not found for `P2pPeerAction::BestTipUpdate`");
                     return Ok(());
@@ -80,6 +128,21 @@ impl P2pPeerState {
 
                 Ok(())
             }
+            P2pPeerAction::RpcVersionUnsupported {
+                peer_id,
+                tag,
+                version,
+                by,
+            } => {
+                let Some(peer) = p2p_state.peers.get_mut(&peer_id) else {
+                    bug_condition!(
+                        "Peer state not found for `P2pPeerAction::RpcVersionUnsupported`"
+                    );
+                    return Ok(());
+                };
+                peer.rpc_versions.record(tag, version, by);
+                Ok(())
+            }
         }
     }
 }
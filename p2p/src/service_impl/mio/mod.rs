@@ -1,17 +1,20 @@
+mod proxy_dialer;
 mod token;
+use self::proxy_dialer::ProxyDialer;
 use self::token::{Token, TokenRegistry};
 
 use std::{
     collections::{BTreeMap, VecDeque},
     io::{self, Read, Write},
-    net::{IpAddr, Ipv4Addr, Shutdown, SocketAddr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr},
     process,
+    sync::Arc,
 };
 
 use libp2p_identity::Keypair;
 use mio::net::{TcpListener, TcpStream};
 
-use openmina_core::{bug_condition, channels::mpsc};
+use openmina_core::{bug_condition, channels::mpsc, ProxyConfig};
 use thiserror::Error;
 
 use crate::{ConnectionAddr, MioCmd, MioEvent};
@@ -54,7 +57,7 @@ pub enum MioService {
 pub struct MioRunningService {
     keypair: Keypair,
     cmd_sender: mpsc::UnboundedSender<MioCmd>,
-    waker: Option<mio::Waker>,
+    waker: Option<Arc<mio::Waker>>,
 }
 
 impl redux::TimeService for MioService {}
@@ -134,7 +137,7 @@ impl MioRunningService {
 
         let mut tokens = TokenRegistry::default();
         let waker = match mio::Waker::new(poll.registry(), tokens.register(Token::Waker)) {
-            Ok(v) => v,
+            Ok(v) => Arc::new(v),
             Err(err) => {
                 MioError::Waker(err).report();
                 process::exit(1);
@@ -147,6 +150,8 @@ impl MioRunningService {
             poll,
             event_sender,
             cmd_receiver: rx,
+            waker: waker.clone(),
+            proxy_dialer: None,
             tokens,
             listeners: BTreeMap::default(),
             connections: BTreeMap::default(),
@@ -160,6 +165,9 @@ impl MioRunningService {
                 inner.send(MioEvent::InterfaceDetected(IpAddr::V4(
                     Ipv4Addr::UNSPECIFIED,
                 )));
+                inner.send(MioEvent::InterfaceDetected(IpAddr::V6(
+                    Ipv6Addr::UNSPECIFIED,
+                )));
 
                 let mut events = mio::Events::with_capacity(1024);
 
@@ -181,6 +189,9 @@ struct MioServiceInner<F> {
     poll: mio::Poll,
     event_sender: F,
     cmd_receiver: mpsc::UnboundedReceiver<MioCmd>,
+    waker: Arc<mio::Waker>,
+    /// Created on the first outgoing connection through the proxy.
+    proxy_dialer: Option<ProxyDialer>,
     tokens: TokenRegistry,
     listeners: BTreeMap<SocketAddr, Listener>,
     connections: BTreeMap<ConnectionAddr, Connection>,
@@ -200,6 +211,26 @@ struct Connection {
     incoming_ready: bool,
 }
 
+/// IPv6 listener is bound with `IPV6_V6ONLY`, so that the IPv4 one can be
+/// bound to the same port for the dual stack.
+fn bind_listener(addr: SocketAddr) -> io::Result<TcpListener> {
+    if addr.is_ipv4() {
+        return TcpListener::bind(addr);
+    }
+    let socket = socket2::Socket::new(
+        socket2::Domain::IPV6,
+        socket2::Type::STREAM,
+        Some(socket2::Protocol::TCP),
+    )?;
+    socket.set_only_v6(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    Ok(TcpListener::from_std(socket.into()))
+}
+
 impl<F> MioServiceInner<F>
 where
     F: 'static + Send + Sync + Fn(MioEvent),
@@ -216,6 +247,14 @@ where
                     while let Ok(cmd) = self.cmd_receiver.try_recv() {
                         self.handle(cmd);
                     }
+                    let finished = self
+                        .proxy_dialer
+                        .as_mut()
+                        .map(ProxyDialer::finished)
+                        .unwrap_or_default();
+                    for (addr, result) in finished {
+                        self.proxied_connected(addr, result);
+                    }
                 }
                 Some(Token::Listener(addr)) => {
                     let Some(mut listener) = self.listeners.remove(&addr) else {
@@ -343,7 +382,7 @@ where
         use self::MioCmd::*;
 
         match cmd {
-            ListenOn(addr) => match TcpListener::bind(addr) {
+            ListenOn(addr) => match bind_listener(addr) {
                 Ok(mut listener) => {
                     if let Err(err) = self.poll.registry().register(
                         &mut listener,
@@ -446,6 +485,10 @@ where
                 }
             }
             Connect(addr) => {
+                if let Some(proxy) = ProxyConfig::global().filter(|p| p.for_addr(&addr).is_some()) {
+                    self.proxied_connect(proxy, addr);
+                    return;
+                }
                 match TcpStream::connect(addr) {
                     Ok(mut stream) => {
                         let addr = ConnectionAddr {
@@ -589,12 +632,81 @@ where
                         .registry()
                         .deregister(&mut cn.stream)
                         .unwrap_or_default();
+                } else if !addr.incoming {
+                    // the dial through the proxy might still be pending
+                    if let Some(dialer) = &self.proxy_dialer {
+                        dialer.cancel(addr.sock_addr);
+                    }
                 }
                 self.send(MioEvent::ConnectionDidCloseOnDemand(addr));
             }
         }
     }
 
+    /// Handshake with the proxy is blocking, so it is done by the pool of the
+    /// proxy dialer, which passes the connected stream back to the poll loop.
+    fn proxied_connect(&mut self, proxy: &'static ProxyConfig, addr: SocketAddr) {
+        let dialer = match self.proxy_dialer.take() {
+            Some(dialer) => Ok(dialer),
+            None => ProxyDialer::new(proxy, self.waker.clone()).map_err(|err| err.to_string()),
+        };
+        let dialed = dialer.and_then(|dialer| {
+            let dialed = dialer.dial(addr);
+            self.proxy_dialer = Some(dialer);
+            dialed
+        });
+        if let Err(err) = dialed {
+            self.send(MioEvent::OutgoingConnectionDidConnect(
+                ConnectionAddr {
+                    sock_addr: addr,
+                    incoming: false,
+                },
+                Err(err),
+            ));
+        }
+    }
+
+    fn proxied_connected(&mut self, addr: SocketAddr, result: io::Result<std::net::TcpStream>) {
+        let addr = ConnectionAddr {
+            sock_addr: addr,
+            incoming: false,
+        };
+        let registered = result
+            .and_then(|stream| {
+                stream.set_nonblocking(true)?;
+                Ok(TcpStream::from_std(stream))
+            })
+            .and_then(|mut stream| {
+                self.poll.registry().register(
+                    &mut stream,
+                    self.tokens.register(Token::Connection(addr)),
+                    mio::Interest::READABLE,
+                )?;
+                Ok(stream)
+            });
+        match registered {
+            Ok(stream) => {
+                // Peer address of the stream is the one of the proxy, so the
+                // connection is reported as connected right away.
+                self.connections.insert(
+                    addr,
+                    Connection {
+                        stream,
+                        transmits: VecDeque::default(),
+                        queued_bytes: 0,
+                        connected: true,
+                        incoming_ready: false,
+                    },
+                );
+                self.send(MioEvent::OutgoingConnectionDidConnect(addr, Ok(())));
+            }
+            Err(err) => self.send(MioEvent::OutgoingConnectionDidConnect(
+                addr,
+                Err(err.to_string()),
+            )),
+        }
+    }
+
     pub fn send(&self, event: MioEvent) {
         (self.event_sender)(event);
     }
@@ -0,0 +1,124 @@
+use std::{
+    collections::BTreeSet,
+    io,
+    net::{SocketAddr, TcpStream},
+    sync::{
+        mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use openmina_core::{channels::mpsc, ProxyConfig};
+
+const PROXY_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of threads doing the (blocking) handshakes with the proxy.
+const PROXY_DIAL_THREADS: usize = 4;
+
+/// Maximal number of dials waiting for a free thread.
+const PROXY_DIAL_QUEUE: usize = 64;
+
+type DialResult = (SocketAddr, io::Result<TcpStream>);
+
+/// Dials outgoing connections through the proxy on a fixed pool of threads.
+pub(super) struct ProxyDialer {
+    queue: SyncSender<SocketAddr>,
+    /// Dials which weren't cancelled yet. Queued dials missing here are
+    /// skipped by the workers, and results of those are dropped.
+    pending: Arc<Mutex<BTreeSet<SocketAddr>>>,
+    results: mpsc::UnboundedReceiver<DialResult>,
+}
+
+impl ProxyDialer {
+    pub fn new(proxy: &'static ProxyConfig, waker: Arc<mio::Waker>) -> io::Result<Self> {
+        let (queue, queue_rx) = sync_channel(PROXY_DIAL_QUEUE);
+        let queue_rx = Arc::new(Mutex::new(queue_rx));
+        let pending = Arc::new(Mutex::new(BTreeSet::new()));
+        let (results_tx, results) = mpsc::unbounded_channel();
+
+        for i in 0..PROXY_DIAL_THREADS {
+            let queue_rx = queue_rx.clone();
+            let pending = pending.clone();
+            let results_tx = results_tx.clone();
+            let waker = waker.clone();
+            std::thread::Builder::new()
+                .name(format!("mio-proxy-dial-{i}"))
+                .spawn(move || Self::worker(proxy, &queue_rx, &pending, &results_tx, &waker))?;
+        }
+
+        Ok(Self {
+            queue,
+            pending,
+            results,
+        })
+    }
+
+    fn worker(
+        proxy: &ProxyConfig,
+        queue: &Mutex<Receiver<SocketAddr>>,
+        pending: &Mutex<BTreeSet<SocketAddr>>,
+        results: &mpsc::UnboundedSender<DialResult>,
+        waker: &mio::Waker,
+    ) {
+        loop {
+            let Ok(addr) = queue
+                .lock()
+                .map_err(drop)
+                .and_then(|rx| rx.recv().map_err(drop))
+            else {
+                return;
+            };
+            let cancelled = pending.lock().map_or(true, |p| !p.contains(&addr));
+            if cancelled {
+                continue;
+            }
+            let result = proxy.connect(addr, PROXY_CONNECT_TIMEOUT);
+            if results.send((addr, result)).is_err() {
+                return;
+            }
+            waker.wake().unwrap_or_default();
+        }
+    }
+
+    /// Queues the dial, fails if too many dials are already waiting.
+    pub fn dial(&self, addr: SocketAddr) -> Result<(), String> {
+        match self.pending.lock() {
+            Ok(mut pending) => {
+                if !pending.insert(addr) {
+                    return Err("already dialing through the proxy".to_owned());
+                }
+            }
+            Err(_) => return Err("proxy dialer is poisoned".to_owned()),
+        }
+        match self.queue.try_send(addr) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                self.cancel(addr);
+                Err(match err {
+                    TrySendError::Full(_) => "too many pending dials through the proxy",
+                    TrySendError::Disconnected(_) => "proxy dialer is stopped",
+                }
+                .to_owned())
+            }
+        }
+    }
+
+    /// Cancels the dial, returns `false` if there was no such pending dial.
+    pub fn cancel(&self, addr: SocketAddr) -> bool {
+        self.pending
+            .lock()
+            .map_or(false, |mut pending| pending.remove(&addr))
+    }
+
+    /// Finished dials, which weren't cancelled meanwhile.
+    pub fn finished(&mut self) -> Vec<DialResult> {
+        let mut finished = vec![];
+        while let Ok((addr, result)) = self.results.try_recv() {
+            if self.cancel(addr) {
+                finished.push((addr, result));
+            }
+        }
+        finished
+    }
+}
@@ -2,16 +2,47 @@
 pub mod mio;
 #[cfg(feature = "p2p-webrtc")]
 pub mod webrtc;
+pub mod webrtc_pacing;
 pub mod webrtc_with_libp2p;
 
 use std::future::Future;
 
+use serde::{Deserialize, Serialize};
+
 pub trait TaskSpawner: Send + Clone {
     fn spawn_main<F>(&self, name: &str, fut: F)
     where
         F: 'static + Send + Future<Output = ()>;
 }
 
+/// ICE server used for the webrtc connections. Credential is left out.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pIceServerInfo {
+    pub urls: Vec<String>,
+    pub username: Option<String>,
+    pub has_credential: bool,
+}
+
+/// ICE servers the webrtc connections are created with, empty if webrtc
+/// is disabled.
+pub fn ice_servers() -> Vec<P2pIceServerInfo> {
+    #[cfg(feature = "p2p-webrtc")]
+    {
+        webrtc::RTCConfigIceServers::default()
+            .iter()
+            .map(|server| P2pIceServerInfo {
+                urls: server.urls.clone(),
+                username: server.username.clone(),
+                has_credential: server.credential.is_some(),
+            })
+            .collect()
+    }
+    #[cfg(not(feature = "p2p-webrtc"))]
+    {
+        Vec::new()
+    }
+}
+
 #[cfg(not(feature = "p2p-webrtc"))]
 pub mod webrtc {
     use std::collections::BTreeMap;
@@ -22,9 +53,10 @@ pub mod webrtc {
         channels::{ChannelId, ChannelMsg, MsgId},
         connection::outgoing::P2pConnectionOutgoingInitOpts,
         identity::{EncryptableType, PublicKey, SecretKey},
-        webrtc, P2pEvent, PeerId,
+        webrtc, P2pCorrelationId, P2pEvent, PeerId,
     };
 
+    use super::webrtc_pacing::WebrtcSendConfig;
     use super::TaskSpawner;
 
     pub struct P2pServiceCtx {
@@ -42,7 +74,7 @@ pub mod webrtc {
 
         fn random_pick(
             &mut self,
-            list: &[P2pConnectionOutgoingInitOpts],
+            list: &[(P2pConnectionOutgoingInitOpts, f64)],
         ) -> Option<P2pConnectionOutgoingInitOpts>;
 
         fn event_sender(&self) -> &mpsc::UnboundedSender<Self::Event>;
@@ -55,6 +87,7 @@ pub mod webrtc {
             _secret_key: SecretKey,
             _spawner: S,
             _rng_seed: [u8; 32],
+            _send_config: WebrtcSendConfig,
         ) -> P2pServiceCtx {
             let (cmd_sender, _) = mpsc::unbounded_channel();
             P2pServiceCtx {
@@ -63,22 +96,56 @@ pub mod webrtc {
             }
         }
 
-        fn outgoing_init(&mut self, peer_id: PeerId) {}
+        fn outgoing_init(&mut self, peer_id: PeerId) -> P2pCorrelationId {
+            P2pCorrelationId::next()
+        }
 
-        fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer) {}
+        fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer) -> P2pCorrelationId {
+            P2pCorrelationId::next()
+        }
 
-        fn set_answer(&mut self, peer_id: PeerId, answer: webrtc::Answer) {}
+        fn set_answer(
+            &mut self,
+            peer_id: PeerId,
+            answer: webrtc::Answer,
+        ) -> Option<P2pCorrelationId> {
+            None
+        }
 
-        fn http_signaling_request(&mut self, url: String, offer: webrtc::Offer) {}
+        fn signaling_request(
+            &mut self,
+            method: webrtc::SignalingMethod,
+            offer: webrtc::Offer,
+        ) -> Option<P2pCorrelationId> {
+            None
+        }
+
+        fn ice_restart_init(&mut self, peer_id: PeerId) -> Option<P2pCorrelationId> {
+            None
+        }
+
+        fn ice_restart_offer_set(
+            &mut self,
+            peer_id: PeerId,
+            offer: webrtc::Offer,
+        ) -> Option<P2pCorrelationId> {
+            None
+        }
 
         fn disconnect(&mut self, peer_id: PeerId) -> bool {
             false
         }
 
-        fn channel_open(&mut self, peer_id: PeerId, id: ChannelId) {}
+        fn channel_open(&mut self, peer_id: PeerId, id: ChannelId) -> Option<P2pCorrelationId> {
+            None
+        }
 
         fn channel_send(&mut self, peer_id: PeerId, msg_id: MsgId, msg: ChannelMsg) {}
 
+        fn compression_enable(&mut self, peer_id: PeerId) {}
+
+        fn lossy_gossip_enable(&mut self, peer_id: PeerId) {}
+
         fn encrypt<T: EncryptableType>(
             &mut self,
             other_pk: &PublicKey,
@@ -0,0 +1,215 @@
+//! Bulk transfers split large channel messages into chunks, which are
+//! sent in parallel over multiple datachannels and reassembled on the
+//! receiving side, keyed by transfer id.
+//!
+//! Each chunk is sent as a separate datachannel message, prefixed with
+//! [`BulkChunkHeader`].
+
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+
+use crate::channels::{ChannelId, ChannelMsg};
+
+use super::{framing::ChunkedBuf, CHUNK_SIZE};
+
+/// First negotiated datachannel id used for bulk channels. Lower ids are
+/// reserved for [`ChannelId`].
+pub(super) const BULK_CHANNEL_ID_START: u16 = 64;
+
+/// Messages larger than this are sent as bulk transfers.
+pub(super) const BULK_TRANSFER_MIN_SIZE: usize = 4 * CHUNK_SIZE;
+
+/// Maximum number of bulk transfers which can be received concurrently.
+const BULK_TRANSFERS_RECV_MAX: usize = 8;
+
+const BULK_CHUNK_HEADER_LEN: usize = 13;
+
+const BULK_CHUNK_PAYLOAD_SIZE: usize = CHUNK_SIZE - BULK_CHUNK_HEADER_LEN;
+
+/// Whether messages of the channel may be sent as bulk transfers.
+pub(super) fn is_bulk_transfer_supported(chan_id: ChannelId) -> bool {
+    matches!(chan_id, ChannelId::Rpc | ChannelId::StreamingRpc)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BulkChunkHeader {
+    chan_id: u8,
+    transfer_id: u32,
+    index: u32,
+    count: u32,
+}
+
+impl BulkChunkHeader {
+    fn encode(&self) -> [u8; BULK_CHUNK_HEADER_LEN] {
+        let mut buf = [0; BULK_CHUNK_HEADER_LEN];
+        buf[0] = self.chan_id;
+        buf[1..5].copy_from_slice(&self.transfer_id.to_be_bytes());
+        buf[5..9].copy_from_slice(&self.index.to_be_bytes());
+        buf[9..13].copy_from_slice(&self.count.to_be_bytes());
+        buf
+    }
+
+    fn decode(data: &[u8]) -> Option<(Self, &[u8])> {
+        if data.len() < BULK_CHUNK_HEADER_LEN {
+            return None;
+        }
+        let u32_at = |i: usize| u32::from_be_bytes(data[i..i + 4].try_into().unwrap_or_default());
+        let header = Self {
+            chan_id: data[0],
+            transfer_id: u32_at(1),
+            index: u32_at(5),
+            count: u32_at(9),
+        };
+        Some((header, &data[BULK_CHUNK_HEADER_LEN..]))
+    }
+}
+
+/// Splits encoded message into chunks, each prefixed with the header.
+pub(super) fn bulk_chunks(
+    chan_id: ChannelId,
+    transfer_id: u32,
+    encoded: &[u8],
+) -> impl Iterator<Item = bytes::Bytes> + '_ {
+    let count = encoded.len().div_ceil(BULK_CHUNK_PAYLOAD_SIZE) as u32;
+    encoded
+        .chunks(BULK_CHUNK_PAYLOAD_SIZE)
+        .zip(0..)
+        .map(move |(payload, index)| {
+            let header = BulkChunkHeader {
+                chan_id: chan_id.to_u8(),
+                transfer_id,
+                index,
+                count,
+            };
+            let mut chunk = Vec::with_capacity(BULK_CHUNK_HEADER_LEN + payload.len());
+            chunk.extend_from_slice(&header.encode());
+            chunk.extend_from_slice(payload);
+            chunk.into()
+        })
+}
+
+struct BulkTransferRecv {
+    chunks: Vec<Option<Bytes>>,
+    received: usize,
+}
+
+/// Reassembles bulk transfers received over all bulk channels of the peer.
+#[derive(Default)]
+pub(super) struct BulkTransfersRecv {
+    transfers: BTreeMap<(u8, u32), BulkTransferRecv>,
+}
+
+impl BulkTransfersRecv {
+    /// Handles received chunk. Returns decoded message once all chunks
+    /// of the transfer are received.
+    pub(super) fn chunk_received(&mut self, data: &[u8]) -> Result<Option<ChannelMsg>, String> {
+        let (header, payload) =
+            BulkChunkHeader::decode(data).ok_or_else(|| "BulkChunkTooSmall".to_owned())?;
+        let chan_id = ChannelId::iter_all()
+            .find(|id| id.to_u8() == header.chan_id)
+            .filter(|id| is_bulk_transfer_supported(*id))
+            .ok_or_else(|| format!("BulkChunkInvalidChannel; id: {}", header.chan_id))?;
+        let count = header.count as usize;
+        if count == 0 || header.index >= header.count {
+            return Err(format!(
+                "BulkChunkInvalidIndex; index: {}, count: {}",
+                header.index, header.count
+            ));
+        }
+        if count.saturating_mul(BULK_CHUNK_PAYLOAD_SIZE) > chan_id.max_msg_size() + CHUNK_SIZE {
+            return Err(format!(
+                "BulkTransferLenOverLimit; chunks: {}, limit: {}",
+                count,
+                chan_id.max_msg_size()
+            ));
+        }
+
+        let key = (header.chan_id, header.transfer_id);
+        if !self.transfers.contains_key(&key) && self.transfers.len() >= BULK_TRANSFERS_RECV_MAX {
+            return Err("BulkTransfersRecvOverLimit".to_owned());
+        }
+        let transfer = self
+            .transfers
+            .entry(key)
+            .or_insert_with(|| BulkTransferRecv {
+                chunks: vec![None; count],
+                received: 0,
+            });
+        if transfer.chunks.len() != count {
+            self.transfers.remove(&key);
+            return Err("BulkChunkCountMismatch".to_owned());
+        }
+        let slot = &mut transfer.chunks[header.index as usize];
+        if slot.is_some() {
+            self.transfers.remove(&key);
+            return Err("BulkChunkDuplicate".to_owned());
+        }
+        *slot = Some(Bytes::copy_from_slice(payload));
+        transfer.received += 1;
+        if transfer.received < count {
+            return Ok(None);
+        }
+
+        let transfer = self.transfers.remove(&key).expect("checked above");
+        transfer
+            .chunks
+            .into_iter()
+            .flatten()
+            .collect::<ChunkedBuf>()
+            .decode(chan_id)
+            .map(Some)
+            .map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::rpc::{P2pRpcId, P2pRpcRequest, RpcChannelMsg};
+
+    fn encoded(msg: &ChannelMsg) -> Vec<u8> {
+        let mut buf = Vec::new();
+        msg.encode(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn bulk_transfer_reassembles_out_of_order_chunks() {
+        let msg = ChannelMsg::Rpc(RpcChannelMsg::Request(
+            P2pRpcId::default(),
+            P2pRpcRequest::InitialPeers,
+        ));
+        let mut encoded = encoded(&msg);
+        // pad so that message gets split into several chunks.
+        encoded.resize(3 * BULK_CHUNK_PAYLOAD_SIZE + 1, 0);
+        let chunks = bulk_chunks(ChannelId::Rpc, 7, &encoded).collect::<Vec<_>>();
+        assert_eq!(chunks.len(), 4);
+
+        let mut recv = BulkTransfersRecv::default();
+        for chunk in chunks.iter().rev().skip(1) {
+            assert!(recv.chunk_received(chunk).unwrap().is_none());
+        }
+        let received = recv.chunk_received(&chunks[0]).unwrap().unwrap();
+        assert_eq!(received.channel_id(), ChannelId::Rpc);
+        assert!(recv.transfers.is_empty());
+    }
+
+    #[test]
+    fn bulk_transfer_rejects_duplicate_chunk() {
+        let encoded = vec![0; 2 * BULK_CHUNK_PAYLOAD_SIZE];
+        let chunks = bulk_chunks(ChannelId::StreamingRpc, 1, &encoded).collect::<Vec<_>>();
+
+        let mut recv = BulkTransfersRecv::default();
+        assert!(recv.chunk_received(&chunks[0]).unwrap().is_none());
+        assert!(recv.chunk_received(&chunks[0]).is_err());
+    }
+
+    #[test]
+    fn bulk_transfer_rejects_unsupported_channel() {
+        let chunks = bulk_chunks(ChannelId::BestTipPropagation, 1, &[0; 16]).collect::<Vec<_>>();
+        assert!(BulkTransfersRecv::default()
+            .chunk_received(&chunks[0])
+            .is_err());
+    }
+}
@@ -0,0 +1,430 @@
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+use bytes::{Buf, Bytes};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+
+use crate::channels::{ChannelId, ChannelMsg};
+use crate::P2pChannelSendError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ChannelMsgEncodeError {
+    #[error("ChannelMsgLenOverLimit; channel: {chan_id:?}, len: {len}, limit: {limit}")]
+    LenOverLimit {
+        chan_id: ChannelId,
+        len: usize,
+        limit: usize,
+    },
+    #[error("ChannelMsgEncodeFailed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<ChannelMsgEncodeError> for P2pChannelSendError {
+    fn from(err: ChannelMsgEncodeError) -> Self {
+        match err {
+            ChannelMsgEncodeError::LenOverLimit { len, limit, .. } => {
+                Self::LenOverLimit { len, limit }
+            }
+            ChannelMsgEncodeError::Io(err) => Self::Failed(err.to_string()),
+        }
+    }
+}
+
+/// Set in the length prefix of the compressed messages. Lengths are
+/// limited by [`ChannelId::max_msg_size`], so the bit is never used
+/// by the length itself.
+const COMPRESSED_FLAG: u32 = 1 << 31;
+
+/// Smaller messages are sent uncompressed, they would barely shrink.
+pub const COMPRESSION_MIN_SIZE: usize = 512;
+
+/// Encodes [`ChannelMsg`]s with the length prefix, as they are sent
+/// over the webrtc data channel.
+pub struct MsgBuffer {
+    buf: Vec<u8>,
+}
+
+impl MsgBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buf: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Fails if the message is bigger than [`ChannelId::max_msg_size`],
+    /// since the peer would reject it anyway.
+    pub fn encode(&mut self, msg: &ChannelMsg) -> Result<Vec<u8>, ChannelMsgEncodeError> {
+        self.encode_with_limit(msg, msg.channel_id().max_msg_size())
+    }
+
+    fn encode_with_limit(
+        &mut self,
+        msg: &ChannelMsg,
+        limit: usize,
+    ) -> Result<Vec<u8>, ChannelMsgEncodeError> {
+        let res = msg.encode(&mut self.buf);
+        let len = self.buf.len();
+        if let Err(err) = res {
+            self.buf.clear();
+            return Err(err.into());
+        }
+        if len > limit {
+            self.buf.clear();
+            return Err(ChannelMsgEncodeError::LenOverLimit {
+                chan_id: msg.channel_id(),
+                len,
+                limit,
+            });
+        }
+        let len_encoded = (len as u32).to_be_bytes();
+        let encoded = len_encoded
+            .into_iter()
+            .chain(self.buf.iter().cloned())
+            .collect();
+        self.buf.clear();
+        Ok(encoded)
+    }
+
+    /// Compresses the message encoded by [`Self::encode`], if it's big
+    /// enough and gets smaller. Must only be used if the peer announced
+    /// support for it, see [`crate::webrtc::P2pCapabilities::compression`].
+    pub fn compress(encoded: Vec<u8>) -> Vec<u8> {
+        let Some(data) = encoded.get(4..).filter(|d| d.len() >= COMPRESSION_MIN_SIZE) else {
+            return encoded;
+        };
+        let mut encoder = DeflateEncoder::new(vec![0; 4], Compression::fast());
+        let Ok(mut compressed) = encoder.write_all(data).and_then(|_| encoder.finish()) else {
+            return encoded;
+        };
+        if compressed.len() >= encoded.len() {
+            return encoded;
+        }
+        let len = compressed.len().saturating_sub(4) as u32 | COMPRESSED_FLAG;
+        if let Some(prefix) = compressed.get_mut(..4) {
+            prefix.copy_from_slice(&len.to_be_bytes());
+        }
+        compressed
+    }
+}
+
+/// Decompresses the message, failing if it's bigger than the `limit`,
+/// so that the peer can't make us allocate more than it could send
+/// uncompressed.
+fn decompress(data: impl Read, limit: usize) -> Result<Vec<u8>, String> {
+    let mut decompressed = Vec::new();
+    DeflateDecoder::new(data)
+        .take((limit as u64).saturating_add(1))
+        .read_to_end(&mut decompressed)
+        .map_err(|err| format!("ChannelMsgDecompressFailed: {err}"))?;
+    if decompressed.len() > limit {
+        return Err(format!(
+            "ChannelMsgLenOverLimit; decompressed len over limit: {limit}"
+        ));
+    }
+    Ok(decompressed)
+}
+
+/// Received data kept in the pieces it was received in, which are read
+/// as a single buffer, so that the message split across them can be
+/// decoded without joining them first.
+#[derive(Default)]
+pub(super) struct ChunkedBuf {
+    chunks: VecDeque<Bytes>,
+    len: usize,
+}
+
+impl ChunkedBuf {
+    pub(super) fn push(&mut self, chunk: Bytes) {
+        // `Buf::chunk` must not be empty while there are bytes remaining.
+        if chunk.is_empty() {
+            return;
+        }
+        self.len = self.len.saturating_add(chunk.len());
+        self.chunks.push_back(chunk);
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+
+    /// Decodes the message from the pieces, consuming them.
+    pub(super) fn decode(&mut self, chan_id: ChannelId) -> Result<ChannelMsg, binprot::Error> {
+        let res = ChannelMsg::decode(&mut (&mut *self).reader(), chan_id);
+        self.clear();
+        res
+    }
+
+    /// Decompresses the pieces, consuming them.
+    fn decompress(&mut self, limit: usize) -> Result<Vec<u8>, String> {
+        let res = decompress((&mut *self).reader(), limit);
+        self.clear();
+        res
+    }
+}
+
+impl FromIterator<Bytes> for ChunkedBuf {
+    fn from_iter<T: IntoIterator<Item = Bytes>>(iter: T) -> Self {
+        let mut buf = Self::default();
+        iter.into_iter().for_each(|chunk| buf.push(chunk));
+        buf
+    }
+}
+
+impl Buf for ChunkedBuf {
+    fn remaining(&self) -> usize {
+        self.len
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.chunks.front().map_or(&[], |chunk| chunk.as_ref())
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        while cnt > 0 {
+            let Some(chunk) = self.chunks.front_mut() else {
+                break;
+            };
+            let n = cnt.min(chunk.len());
+            chunk.advance(n);
+            if chunk.is_empty() {
+                self.chunks.pop_front();
+            }
+            cnt -= n;
+            self.len -= n;
+        }
+    }
+}
+
+/// Reassembles length prefixed [`ChannelMsg`]s from the data received
+/// on the webrtc data channel, which may split or join the messages.
+///
+/// Messages received whole are decoded directly from the received data.
+/// Parts of the split ones are copied once into [`ChunkedBuf`] and the
+/// message is decoded from it, without joining them into one buffer.
+/// Compressed messages are decompressed first, see [`MsgBuffer::compress`].
+pub struct ChannelMsgDecoder {
+    chan_id: ChannelId,
+    limit: usize,
+    buf: ChunkedBuf,
+    /// Length of the message being received, if its length prefix was
+    /// already read.
+    len: Option<usize>,
+    /// Whether the message being received is compressed.
+    compressed: bool,
+}
+
+impl ChannelMsgDecoder {
+    pub fn new(chan_id: ChannelId) -> Self {
+        Self::with_limit(chan_id, chan_id.max_msg_size())
+    }
+
+    fn with_limit(chan_id: ChannelId, limit: usize) -> Self {
+        Self {
+            chan_id,
+            limit,
+            buf: ChunkedBuf::default(),
+            len: None,
+            compressed: false,
+        }
+    }
+
+    /// Consumes bytes from the `msg` until the next message is decoded.
+    /// Returns `None` if all bytes were consumed without completing one.
+    pub fn decode_next(&mut self, msg: &mut &[u8]) -> Result<Option<ChannelMsg>, String> {
+        let len = match self.len {
+            Some(len) => len,
+            None if msg.len() < 4 => return Err("WebRTCMessageTooSmall".to_owned()),
+            None => {
+                let len = u32::from_be_bytes(msg[..4].try_into().expect("Size checked above"));
+                *msg = &msg[4..];
+                self.compressed = len & COMPRESSED_FLAG != 0;
+                let len = (len & !COMPRESSED_FLAG) as usize;
+                if len > self.limit {
+                    return Err(format!(
+                        "ChannelMsgLenOverLimit; len: {}, limit: {}",
+                        len, self.limit
+                    ));
+                }
+                self.len = Some(len);
+                len
+            }
+        };
+        let bytes_left = len - self.buf.remaining();
+
+        if bytes_left > msg.len() {
+            self.buf.push(Bytes::copy_from_slice(msg));
+            *msg = &[];
+            return Ok(None);
+        }
+
+        let (data, rest) = msg.split_at(bytes_left);
+        *msg = rest;
+        self.len = None;
+        let chan_id = self.chan_id;
+        let decode = |data: &[u8]| ChannelMsg::decode(&mut &data[..], chan_id);
+        let res = match (
+            self.buf.remaining() == 0,
+            std::mem::take(&mut self.compressed),
+        ) {
+            (true, false) => decode(data).map_err(|err| err.to_string()),
+            (true, true) => {
+                decompress(data, self.limit).and_then(|d| decode(&d).map_err(|e| e.to_string()))
+            }
+            (false, compressed) => {
+                self.buf.push(Bytes::copy_from_slice(data));
+                if compressed {
+                    self.buf
+                        .decompress(self.limit)
+                        .and_then(|d| decode(&d).map_err(|e| e.to_string()))
+                } else {
+                    self.buf.decode(chan_id).map_err(|err| err.to_string())
+                }
+            }
+        };
+        res.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::rpc::{P2pRpcId, P2pRpcRequest, P2pRpcResponse, RpcChannelMsg};
+    use crate::connection::outgoing::P2pConnectionOutgoingInitOpts;
+    use crate::webrtc::SignalingMethod;
+    use crate::PeerId;
+
+    fn msg() -> ChannelMsg {
+        ChannelMsg::Rpc(RpcChannelMsg::Request(
+            P2pRpcId::default(),
+            P2pRpcRequest::InitialPeers,
+        ))
+    }
+
+    fn big_msg() -> ChannelMsg {
+        let relay_peer_id = PeerId::from_bytes([0; 32]);
+        let peers = (0..64u8)
+            .map(|i| P2pConnectionOutgoingInitOpts::WebRTC {
+                peer_id: PeerId::from_bytes([i; 32]),
+                signaling: SignalingMethod::P2p { relay_peer_id },
+            })
+            .collect();
+        ChannelMsg::Rpc(RpcChannelMsg::Response(
+            P2pRpcId::default(),
+            Some(P2pRpcResponse::InitialPeers(peers)),
+        ))
+    }
+
+    fn encoded_len(msg: &ChannelMsg) -> usize {
+        let mut buf = Vec::new();
+        msg.encode(&mut buf).unwrap();
+        buf.len()
+    }
+
+    #[test]
+    fn encode_at_limit() {
+        let msg = msg();
+        let len = encoded_len(&msg);
+        let encoded = MsgBuffer::new(0).encode_with_limit(&msg, len).unwrap();
+        assert_eq!(encoded.len(), len + 4);
+
+        let mut data = &encoded[..];
+        let mut decoder = ChannelMsgDecoder::with_limit(ChannelId::Rpc, len);
+        assert!(decoder.decode_next(&mut data).unwrap().is_some());
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn encode_over_limit() {
+        let msg = msg();
+        let len = encoded_len(&msg);
+        let mut buf = MsgBuffer::new(0);
+        let err = buf.encode_with_limit(&msg, len - 1).unwrap_err();
+        assert!(matches!(
+            err,
+            ChannelMsgEncodeError::LenOverLimit { len: l, limit, .. } if l == len && limit == len - 1
+        ));
+        // surfaced typed in `P2pChannelEvent::Sent`.
+        assert_eq!(
+            P2pChannelSendError::from(err),
+            P2pChannelSendError::LenOverLimit {
+                len,
+                limit: len - 1
+            }
+        );
+        // buffer is reusable after the error.
+        assert_eq!(buf.encode_with_limit(&msg, len).unwrap().len(), len + 4);
+    }
+
+    #[test]
+    fn decode_split_and_joined() {
+        let msg = msg();
+        let encoded = MsgBuffer::new(0).encode(&msg).unwrap();
+        let data = [encoded.clone(), encoded].concat();
+
+        // Pieces end right after the length prefix and mid-message.
+        let mut decoder = ChannelMsgDecoder::new(ChannelId::Rpc);
+        let mut decoded = 0;
+        for mut piece in [&data[..4], &data[4..6], &data[6..]] {
+            while !piece.is_empty() {
+                if decoder.decode_next(&mut piece).unwrap().is_some() {
+                    decoded += 1;
+                }
+            }
+        }
+        assert_eq!(decoded, 2);
+        assert_eq!(decoder.buf.remaining(), 0);
+    }
+
+    #[test]
+    fn compress_roundtrip() {
+        let msg = big_msg();
+        let encoded = MsgBuffer::new(0).encode(&msg).unwrap();
+        let compressed = MsgBuffer::compress(encoded.clone());
+        assert!(compressed.len() < encoded.len());
+
+        // Split mid-message, decoded the same as the uncompressed one.
+        let mut decoder = ChannelMsgDecoder::new(ChannelId::Rpc);
+        let (first, second) = compressed.split_at(compressed.len() / 2);
+        assert!(decoder.decode_next(&mut &first[..]).unwrap().is_none());
+        let decoded = decoder.decode_next(&mut &second[..]).unwrap().unwrap();
+        let mut decoded_encoded = Vec::new();
+        decoded.encode(&mut decoded_encoded).unwrap();
+        assert_eq!(decoded_encoded, encoded[4..]);
+
+        // Whole.
+        let mut data = &compressed[..];
+        assert!(decoder.decode_next(&mut data).unwrap().is_some());
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn compress_skips_small() {
+        let encoded = MsgBuffer::new(0).encode(&msg()).unwrap();
+        assert!(encoded.len() < COMPRESSION_MIN_SIZE);
+        assert_eq!(MsgBuffer::compress(encoded.clone()), encoded);
+    }
+
+    #[test]
+    fn decompress_over_limit() {
+        let msg = big_msg();
+        let len = encoded_len(&msg);
+        let compressed = MsgBuffer::compress(MsgBuffer::new(0).encode(&msg).unwrap());
+
+        // Compressed data fits the limit, decompressed doesn't.
+        let mut data = &compressed[..];
+        let mut decoder = ChannelMsgDecoder::with_limit(ChannelId::Rpc, len - 1);
+        assert!(decoder.decode_next(&mut data).is_err());
+    }
+
+    #[test]
+    fn decode_over_limit() {
+        let msg = msg();
+        let len = encoded_len(&msg);
+        let encoded = MsgBuffer::new(0).encode_with_limit(&msg, len).unwrap();
+
+        let mut data = &encoded[..];
+        let mut decoder = ChannelMsgDecoder::with_limit(ChannelId::Rpc, len - 1);
+        assert!(decoder.decode_next(&mut data).is_err());
+    }
+}
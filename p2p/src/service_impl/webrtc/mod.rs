@@ -5,9 +5,19 @@ mod webrtc_cpp;
 #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-webrtc-rs"))]
 mod webrtc_rs;
 
+mod bulk_transfer;
+mod framing;
+use bulk_transfer::{
+    bulk_chunks, is_bulk_transfer_supported, BulkTransfersRecv, BULK_CHANNEL_ID_START,
+    BULK_TRANSFER_MIN_SIZE,
+};
+pub use framing::{ChannelMsgDecoder, ChannelMsgEncodeError, MsgBuffer};
+mod signaling;
+pub use signaling::{HttpSignaling, Signaling, SignalingBackend, WsSignaling};
+
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::{collections::BTreeMap, time::Duration};
 
 use openmina_core::bug_condition;
@@ -25,40 +35,135 @@ use crate::identity::{EncryptableType, PublicKey};
 use crate::webrtc::{ConnectionAuth, ConnectionAuthEncrypted};
 use crate::{
     channels::{ChannelId, ChannelMsg, MsgId},
-    connection::outgoing::P2pConnectionOutgoingInitOpts,
+    connection::{outgoing::P2pConnectionOutgoingInitOpts, P2pConnectionFinalizeError},
     identity::SecretKey,
-    webrtc, P2pChannelEvent, P2pConnectionEvent, P2pEvent, PeerId,
+    webrtc::{self, P2pConnectionResponse},
+    P2pChannelEvent, P2pChannelSendError, P2pConnectionEvent, P2pCorrelationId, P2pEvent, PeerId,
 };
 
 #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-webrtc-rs"))]
 mod imports {
     pub use super::webrtc_rs::{
-        build_api, certificate_from_pem_key, webrtc_signal_send, Api, RTCCertificate, RTCChannel,
-        RTCConnection, RTCConnectionState, RTCSignalingError,
+        build_api, certificate_from_pem_key, webrtc_signal_send, webrtc_signal_send_ws, Api,
+        RTCCertificate, RTCChannel, RTCConnection, RTCConnectionState, RTCSignalingError,
     };
 }
 #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-webrtc-cpp"))]
 mod imports {
     pub use super::webrtc_cpp::{
-        build_api, certificate_from_pem_key, webrtc_signal_send, Api, RTCCertificate, RTCChannel,
-        RTCConnection, RTCConnectionState, RTCSignalingError,
+        build_api, certificate_from_pem_key, webrtc_signal_send, webrtc_signal_send_ws, Api,
+        RTCCertificate, RTCChannel, RTCConnection, RTCConnectionState, RTCSignalingError,
     };
 }
 #[cfg(target_arch = "wasm32")]
 mod imports {
     pub use super::web::{
-        build_api, certificate_from_pem_key, webrtc_signal_send, Api, RTCCertificate, RTCChannel,
-        RTCConnection, RTCConnectionState, RTCSignalingError,
+        build_api, certificate_from_pem_key, webrtc_signal_send, webrtc_signal_send_ws, Api,
+        RTCCertificate, RTCChannel, RTCConnection, RTCConnectionState, RTCSignalingError,
     };
 }
 
 use imports::*;
-pub use imports::{webrtc_signal_send, RTCSignalingError};
+pub use imports::{webrtc_signal_send, webrtc_signal_send_ws, RTCSignalingError};
 
+use super::webrtc_pacing::{ChunkPacer, WebrtcSendConfig, DEFAULT_CHUNK_SIZE};
 use super::TaskSpawner;
 
-/// 16KB.
-const CHUNK_SIZE: usize = 16 * 1024;
+/// Client for the http signaling, which goes through the proxy, if the
+/// node has one configured.
+#[cfg(not(target_arch = "wasm32"))]
+fn signaling_http_client() -> reqwest::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = openmina_core::ProxyConfig::global() {
+        builder = builder.proxy(reqwest::Proxy::custom(move |url| {
+            let host = url.host_str()?;
+            proxy.for_destination(host).map(|proxy| proxy.to_string())
+        }));
+    }
+    builder.build()
+}
+
+/// Opens the websocket for the signaling, through the proxy, if the node
+/// has one configured.
+#[cfg(not(target_arch = "wasm32"))]
+async fn signaling_ws_connect(
+    url: &str,
+) -> Result<
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+    tokio_tungstenite::tungstenite::Error,
+> {
+    use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest};
+
+    let request = url.into_client_request()?;
+    let host = request.uri().host().map(ToOwned::to_owned);
+    let proxy = openmina_core::ProxyConfig::global()
+        .zip(host)
+        .filter(|(proxy, host)| proxy.for_destination(host).is_some());
+    let Some((proxy, host)) = proxy else {
+        return Ok(tokio_tungstenite::connect_async(request).await?.0);
+    };
+    let port = request
+        .uri()
+        .port_u16()
+        .unwrap_or_else(|| match request.uri().scheme_str() {
+            Some("wss") => 443,
+            _ => 80,
+        });
+    let stream = tokio::task::spawn_blocking(move || {
+        let stream = proxy.connect_host(&host, port, SIGNALING_PROXY_CONNECT_TIMEOUT)?;
+        stream.set_nonblocking(true)?;
+        Ok::<_, std::io::Error>(stream)
+    })
+    .await
+    .map_err(|err| tungstenite::Error::Io(std::io::Error::new(std::io::ErrorKind::Other, err)))??;
+    let stream = tokio::net::TcpStream::from_std(stream)?;
+    Ok(tokio_tungstenite::client_async_tls(request, stream)
+        .await?
+        .0)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const SIGNALING_PROXY_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Size of the bulk transfer chunks. Unlike for other channels, it isn't
+/// configurable, since the receiver validates the number of chunks
+/// against it.
+const CHUNK_SIZE: usize = DEFAULT_CHUNK_SIZE;
+
+/// Exchange of the connection authentication messages, done right after
+/// the webrtc connection is established.
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionAuthConfig {
+    /// Time to wait for the authentication message of the peer. If
+    /// `None`, it is waited for until the connection times out.
+    pub timeout: Option<Duration>,
+    /// Number of times our message is sent again within the `timeout`,
+    /// in case it was lost.
+    pub resends: u8,
+}
+
+impl Default for ConnectionAuthConfig {
+    fn default() -> Self {
+        Self {
+            timeout: crate::p2p_config::from_env_or(
+                "CONNECTION_AUTH_TIMEOUT",
+                Some(Duration::from_secs(10)),
+            ),
+            resends: std::env::var("CONNECTION_AUTH_RESENDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(2),
+        }
+    }
+}
+
+impl ConnectionAuthConfig {
+    /// Time to wait for the peer's message after each send of ours.
+    fn resend_interval(&self) -> Option<Duration> {
+        self.timeout
+            .map(|timeout| timeout / (u32::from(self.resends) + 1))
+    }
+}
 
 pub enum Cmd {
     PeerAdd { args: PeerAddArgs, aborted: Aborted },
@@ -66,20 +171,49 @@ pub enum Cmd {
 
 #[derive(Debug)]
 pub enum PeerCmd {
-    PeerHttpOfferSend(String, webrtc::Offer),
+    OfferSend(Signaling, webrtc::Offer),
     AnswerSet(webrtc::Answer),
     ConnectionAuthorizationSend(Option<ConnectionAuthEncrypted>),
     ChannelOpen(ChannelId),
     ChannelSend(MsgId, ChannelMsg),
+    /// Compress the messages sent to the peer from now on.
+    CompressionEnable,
+    /// Open the gossip channels as unordered and partially reliable
+    /// from now on.
+    LossyGossipEnable,
+    /// Restart ICE by sending a new offer over the signaling path
+    /// which was used to establish the connection.
+    IceRestartInit,
+    /// Apply ICE restart offer received from the peer.
+    IceRestartOfferSet(webrtc::Offer),
+}
+
+impl PeerCmd {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::OfferSend(..) => "OfferSend",
+            Self::AnswerSet(_) => "AnswerSet",
+            Self::ConnectionAuthorizationSend(_) => "ConnectionAuthorizationSend",
+            Self::ChannelOpen(_) => "ChannelOpen",
+            Self::ChannelSend(..) => "ChannelSend",
+            Self::CompressionEnable => "CompressionEnable",
+            Self::LossyGossipEnable => "LossyGossipEnable",
+            Self::IceRestartInit => "IceRestartInit",
+            Self::IceRestartOfferSet(_) => "IceRestartOfferSet",
+        }
+    }
 }
 
 enum PeerCmdInternal {
-    ChannelOpened(ChannelId, Result<RTCChannel, Error>),
+    ChannelOpened(ChannelId, P2pCorrelationId, Result<RTCChannel, Error>),
     ChannelClosed(ChannelId),
+    BulkChannelOpened(u8, Result<RTCChannel, Error>),
+    BulkChannelClosed(u8),
+    ConnectionStateChanged(RTCConnectionState),
 }
 
 enum PeerCmdAll {
-    External(PeerCmd),
+    External(P2pCorrelationId, PeerCmd),
     Internal(PeerCmdInternal),
 }
 
@@ -91,8 +225,10 @@ pub struct P2pServiceCtx {
 pub struct PeerAddArgs {
     peer_id: PeerId,
     kind: PeerConnectionKind,
+    /// Carried by the events emitted while the connection is initialized.
+    correlation_id: P2pCorrelationId,
     event_sender: Arc<dyn Fn(P2pEvent) -> Option<()> + Send + Sync + 'static>,
-    cmd_receiver: mpsc::TrackedUnboundedReceiver<PeerCmd>,
+    cmd_receiver: mpsc::TrackedUnboundedReceiver<(P2pCorrelationId, PeerCmd)>,
 }
 
 pub enum PeerConnectionKind {
@@ -101,10 +237,31 @@ pub enum PeerConnectionKind {
 }
 
 pub struct PeerState {
-    pub cmd_sender: mpsc::TrackedUnboundedSender<PeerCmd>,
+    pub cmd_sender: mpsc::TrackedUnboundedSender<(P2pCorrelationId, PeerCmd)>,
     pub abort: Aborter,
 }
 
+impl PeerState {
+    /// Sends the command to the peer's task, tagged with a new correlation
+    /// id, which is carried by the events emitted in response to it.
+    fn cmd_send(&self, peer_id: PeerId, cmd: PeerCmd) -> P2pCorrelationId {
+        let correlation_id = P2pCorrelationId::next();
+        cmd_log(peer_id, correlation_id, cmd.kind());
+        let _ = self.cmd_sender.tracked_send((correlation_id, cmd));
+        correlation_id
+    }
+}
+
+fn cmd_log(peer_id: PeerId, correlation_id: P2pCorrelationId, cmd: &'static str) {
+    openmina_core::debug!(
+        openmina_core::log::system_time();
+        kind = "P2pWebrtcPeerCmd",
+        peer_id = peer_id.to_string(),
+        correlation_id = correlation_id.to_string(),
+        cmd = cmd
+    );
+}
+
 #[derive(thiserror::Error, derive_more::From, Debug)]
 pub(super) enum Error {
     #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-webrtc-rs"))]
@@ -123,6 +280,12 @@ pub(super) enum Error {
     #[from(ignore)]
     #[error("channel closed")]
     ChannelClosed,
+    #[from(ignore)]
+    #[error("no signaling path available for ice restart")]
+    IceRestartNoSignaling,
+    #[from(ignore)]
+    #[error("ice restart rejected: {0}")]
+    IceRestartRejected(String),
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -157,6 +320,31 @@ pub struct RTCConfigIceServer {
 pub struct RTCChannelConfig {
     pub label: &'static str,
     pub negotiated: Option<u16>,
+    pub ordered: bool,
+    /// Partially reliable delivery, if set. Reliable otherwise.
+    pub max_retransmits: Option<u16>,
+}
+
+impl RTCChannelConfig {
+    /// Ordered and reliable channel config.
+    fn reliable(label: &'static str, negotiated: u16) -> Self {
+        Self {
+            label,
+            negotiated: Some(negotiated),
+            ordered: true,
+            max_retransmits: None,
+        }
+    }
+
+    /// Config of the channel, see [`ChannelId::is_ordered`].
+    fn channel(id: ChannelId, lossy_gossip: bool) -> Self {
+        Self {
+            label: id.name(),
+            negotiated: Some(id.to_u16()),
+            ordered: id.is_ordered(lossy_gossip),
+            max_retransmits: id.max_retransmits(lossy_gossip),
+        }
+    }
 }
 
 impl Default for RTCConfigIceServers {
@@ -215,6 +403,37 @@ async fn sleep(dur: Duration) {
     fut.await
 }
 
+/// Sends the chunk once the send buffer of the channel has room for it,
+/// waiting for it to drain if needed. See [`ChunkPacer`].
+async fn chunk_send_paced(
+    chan: &mut RTCChannel,
+    pacer: &mut ChunkPacer,
+    chunk: &bytes::Bytes,
+) -> Result<(), String> {
+    loop {
+        let buffered = chan.buffered_amount().await;
+        let Some(wait) = pacer.drain_wait(buffered, chunk.len()) else {
+            break;
+        };
+        sleep(wait).await;
+        let drained = buffered.saturating_sub(chan.buffered_amount().await);
+        pacer.drain_observed(drained, wait);
+    }
+
+    chan.send(chunk)
+        .await
+        .map_err(|e| format!("{e:?}"))
+        .and_then(|n| match n == chunk.len() {
+            false => Err("NotAllBytesWritten".to_owned()),
+            true => Ok(()),
+        })?;
+
+    if let Some(delay) = pacer.rate_delay(chunk.len()) {
+        sleep(delay).await;
+    }
+    Ok(())
+}
+
 async fn wait_for_ice_gathering_complete(pc: &mut RTCConnection) {
     let timeout = sleep(Duration::from_secs(3));
 
@@ -224,6 +443,7 @@ async fn wait_for_ice_gathering_complete(pc: &mut RTCConnection) {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn peer_start(
     api: Api,
     args: PeerAddArgs,
@@ -231,14 +451,25 @@ async fn peer_start(
     closed: mpsc::Sender<()>,
     certificate: RTCCertificate,
     rng_seed: [u8; 32],
+    send_config: Arc<WebrtcSendConfig>,
+    auth_config: ConnectionAuthConfig,
 ) {
     let PeerAddArgs {
         peer_id,
         kind,
+        correlation_id,
         event_sender,
         mut cmd_receiver,
     } = args;
+    // Id of the last command which advanced the connection initialization.
+    let mut cid = correlation_id;
     let is_outgoing = matches!(kind, PeerConnectionKind::Outgoing);
+    // Number of bulk channels supported by the remote peer. For outgoing
+    // connections it's known once we receive an answer.
+    let mut bulk_channels = match &kind {
+        PeerConnectionKind::Incoming(offer) => offer.bulk_channels,
+        PeerConnectionKind::Outgoing => 0,
+    };
 
     let config = RTCConfig {
         ice_servers: Default::default(),
@@ -247,12 +478,7 @@ async fn peer_start(
     };
     let fut = async {
         let mut pc = RTCConnection::create(&api, config).await?;
-        let main_channel = pc
-            .channel_create(RTCChannelConfig {
-                label: "",
-                negotiated: Some(0),
-            })
-            .await?;
+        let main_channel = pc.channel_create(RTCChannelConfig::reliable("", 0)).await?;
 
         let offer = match kind {
             PeerConnectionKind::Incoming(offer) => (*offer).try_into()?,
@@ -273,7 +499,9 @@ async fn peer_start(
     let (mut pc, mut main_channel) = match fut.await {
         Ok(v) => v,
         Err(err) => {
-            event_sender(P2pConnectionEvent::OfferSdpReady(peer_id, Err(err.to_string())).into());
+            event_sender(
+                P2pConnectionEvent::OfferSdpReady(peer_id, cid, Err(err.to_string())).into(),
+            );
             return;
         }
     };
@@ -287,31 +515,42 @@ async fn peer_start(
         std::future::ready(())
     });
 
+    // Signaling backend and the offer, which was used to establish the
+    // connection. Reused for ICE restarts.
+    let mut ice_restart_signaling = None;
     let answer = if is_outgoing {
         let answer_fut = async {
             let sdp = pc.local_sdp().await.unwrap();
-            event_sender(P2pConnectionEvent::OfferSdpReady(peer_id, Ok(sdp)).into())
+            event_sender(P2pConnectionEvent::OfferSdpReady(peer_id, cid, Ok(sdp)).into())
                 .ok_or(Error::ChannelClosed)?;
-            match cmd_receiver.recv().await.ok_or(Error::ChannelClosed)?.0 {
-                PeerCmd::PeerHttpOfferSend(url, offer) => {
-                    let answer = webrtc_signal_send(&url, offer).await?;
-                    event_sender(P2pConnectionEvent::AnswerReceived(peer_id, answer).into())
-                        .ok_or(Error::ChannelClosed)?;
-
-                    if let PeerCmd::AnswerSet(v) =
+            let (offer_cid, cmd) = cmd_receiver.recv().await.ok_or(Error::ChannelClosed)?.0;
+            match cmd {
+                PeerCmd::OfferSend(signaling, offer) => {
+                    ice_restart_signaling = Some((signaling.clone(), offer.clone()));
+                    let answer = signaling.offer_send(offer).await?;
+                    event_sender(
+                        P2pConnectionEvent::AnswerReceived(peer_id, offer_cid, answer).into(),
+                    )
+                    .ok_or(Error::ChannelClosed)?;
+
+                    if let (answer_cid, PeerCmd::AnswerSet(v)) =
                         cmd_receiver.recv().await.ok_or(Error::ChannelClosed)?.0
                     {
-                        return Ok(v);
+                        return Ok((answer_cid, v));
                     }
                 }
-                PeerCmd::AnswerSet(v) => return Ok(v),
+                PeerCmd::AnswerSet(v) => return Ok((offer_cid, v)),
                 _cmd => {
                     return Err(Error::UnexpectedCmd);
                 }
             }
             Err(Error::ChannelClosed)
         };
-        answer_fut.await.and_then(|v| Ok(v.try_into()?))
+        answer_fut.await.and_then(|(answer_cid, v)| {
+            cid = answer_cid;
+            bulk_channels = v.bulk_channels;
+            Ok(v.try_into()?)
+        })
     } else {
         pc.answer_create().await.map_err(Error::from)
     };
@@ -322,7 +561,8 @@ async fn peer_start(
     if is_outgoing {
         if let Err(err) = pc.remote_desc_set(answer).await {
             let err = Error::from(err).to_string();
-            let _ = event_sender(P2pConnectionEvent::Finalized(peer_id, Err(err)).into());
+            let err = P2pConnectionFinalizeError::ConnectionFailed(err);
+            let _ = event_sender(P2pConnectionEvent::Finalized(peer_id, cid, Err(err)).into());
         }
     } else {
         let fut = async {
@@ -333,36 +573,50 @@ async fn peer_start(
         let res = fut.await.map_err(|err: Error| err.to_string());
         let is_err = res.is_err();
         let is_err = is_err
-            || event_sender(P2pConnectionEvent::AnswerSdpReady(peer_id, res).into()).is_none();
+            || event_sender(P2pConnectionEvent::AnswerSdpReady(peer_id, cid, res).into()).is_none();
         if is_err {
             return;
         }
     }
 
     let (connected_tx, connected) = oneshot::channel();
+    // Connection state changes after the connection was established.
+    // `Disconnected` and `Failed` don't close the connection, instead state
+    // machine gets a chance to recover it with an ICE restart.
+    let (conn_state_tx, conn_state_rx) = mpsc::unbounded_channel();
+    let mut connected_tx = Some(connected_tx);
     if matches!(pc.connection_state(), RTCConnectionState::Connected) {
-        connected_tx.send(Ok(())).unwrap();
-    } else {
-        let mut connected_tx = Some(connected_tx);
-        pc.on_connection_state_change(Box::new(move |state| {
-            match state {
-                RTCConnectionState::Connected => {
-                    if let Some(connected_tx) = connected_tx.take() {
-                        let _ = connected_tx.send(Ok(()));
-                    }
+        if let Some(connected_tx) = connected_tx.take() {
+            let _ = connected_tx.send(Ok(()));
+        }
+    }
+    pc.on_connection_state_change(Box::new(move |state| {
+        match state {
+            RTCConnectionState::Connected => {
+                if let Some(connected_tx) = connected_tx.take() {
+                    let _ = connected_tx.send(Ok(()));
+                } else {
+                    let _ = conn_state_tx.send(state);
                 }
-                RTCConnectionState::Disconnected | RTCConnectionState::Closed => {
-                    if let Some(connected_tx) = connected_tx.take() {
-                        let _ = connected_tx.send(Err("disconnected"));
-                    } else {
-                        let _ = closed.try_send(());
-                    }
+            }
+            RTCConnectionState::Disconnected | RTCConnectionState::Failed => {
+                if let Some(connected_tx) = connected_tx.take() {
+                    let _ = connected_tx.send(Err("disconnected"));
+                } else {
+                    let _ = conn_state_tx.send(state);
                 }
-                _ => {}
             }
-            Box::pin(std::future::ready(()))
-        }));
-    }
+            RTCConnectionState::Closed => {
+                if let Some(connected_tx) = connected_tx.take() {
+                    let _ = connected_tx.send(Err("disconnected"));
+                } else {
+                    let _ = closed.try_send(());
+                }
+            }
+            _ => {}
+        }
+        Box::pin(std::future::ready(()))
+    }));
     match connected
         .await
         .map_err(|_| Error::ChannelClosed.to_string())
@@ -370,14 +624,15 @@ async fn peer_start(
     {
         Ok(_) => {}
         Err(err) => {
-            let _ = event_sender(P2pConnectionEvent::Finalized(peer_id, Err(err)).into());
+            let err = P2pConnectionFinalizeError::ConnectionFailed(err);
+            let _ = event_sender(P2pConnectionEvent::Finalized(peer_id, cid, Err(err)).into());
             return;
         }
     }
 
     // Exchange encrypted connection authorization messsages. Makes sure
     // there is a link between peer identity and connection.
-    let (remote_auth_tx, remote_auth_rx) = oneshot::channel::<ConnectionAuthEncrypted>();
+    let (remote_auth_tx, mut remote_auth_rx) = oneshot::channel::<ConnectionAuthEncrypted>();
     let mut remote_auth_tx = Some(remote_auth_tx);
     main_channel.on_message(move |data| {
         if let Some(tx) = remote_auth_tx.take() {
@@ -392,7 +647,8 @@ async fn peer_start(
         None => return,
         Some(msg) => msg,
     };
-    match msg.0 {
+    let (auth_cid, cmd) = msg.0;
+    match cmd {
         PeerCmd::ConnectionAuthorizationSend(None) => {
             // eprintln!("PeerCmd::ConnectionAuthorizationSend(None)");
             return;
@@ -404,16 +660,27 @@ async fn peer_start(
             // was opened. Some initial messages get lost otherwise.
             // TODO(binier): find deeper cause and fix it.
             sleep(Duration::from_secs(1)).await;
-            let _ = main_channel
-                .send(&bytes::Bytes::copy_from_slice(auth.as_ref()))
-                .await;
-
-            let res = match remote_auth_rx.await {
-                Err(_) => Err("didn't receive connection authentication message".to_owned()),
-                Ok(remote_auth) => Ok(remote_auth),
+            let auth = bytes::Bytes::copy_from_slice(auth.as_ref());
+
+            let closed_err =
+                |_| P2pConnectionFinalizeError::ConnectionFailed(Error::ChannelClosed.to_string());
+            let mut resends_left = auth_config.resends;
+            let res = loop {
+                let _ = main_channel.send(&auth).await;
+                let Some(interval) = auth_config.resend_interval() else {
+                    break remote_auth_rx.await.map_err(closed_err);
+                };
+                tokio::select! {
+                    res = &mut remote_auth_rx => break res.map_err(closed_err),
+                    _ = sleep(interval) => {}
+                }
+                let Some(left) = resends_left.checked_sub(1) else {
+                    break Err(P2pConnectionFinalizeError::AuthTimeout);
+                };
+                resends_left = left;
             };
             let is_err = res.is_err();
-            let _ = event_sender(P2pConnectionEvent::Finalized(peer_id, res).into());
+            let _ = event_sender(P2pConnectionEvent::Finalized(peer_id, auth_cid, res).into());
             if is_err {
                 return;
             }
@@ -426,39 +693,81 @@ async fn peer_start(
 
     let _ = main_channel.close().await;
 
-    peer_loop(peer_id, event_sender, cmd_receiver, pc, abort).await
+    peer_loop(
+        peer_id,
+        event_sender,
+        cmd_receiver,
+        conn_state_rx,
+        pc,
+        ice_restart_signaling,
+        bulk_channels.min(webrtc::BULK_CHANNELS),
+        send_config,
+        abort,
+    )
+    .await
 }
 
-struct Channel {
-    id: ChannelId,
-    msg_sender: ChannelMsgSender,
+/// Creates an ICE restart offer and exchanges it with the peer over
+/// the same signaling server, which was used to establish the connection.
+async fn ice_restart_offer_send(
+    pc: &mut RTCConnection,
+    signaling: Option<&(Signaling, webrtc::Offer)>,
+) -> Result<(), Error> {
+    let Some((signaling, offer)) = signaling else {
+        return Err(Error::IceRestartNoSignaling);
+    };
+    let desc = pc.offer_create_ice_restart().await?;
+    pc.local_desc_set(desc).await?;
+    wait_for_ice_gathering_complete(pc).await;
+    let sdp = pc.local_sdp().await.ok_or(Error::ChannelClosed)?;
+    let offer = webrtc::Offer {
+        sdp,
+        ..offer.clone()
+    };
+    match signaling.offer_send(offer).await? {
+        P2pConnectionResponse::Accepted(answer) => {
+            pc.remote_desc_set((*answer).try_into()?).await?;
+            Ok(())
+        }
+        P2pConnectionResponse::Rejected(reason) => {
+            Err(Error::IceRestartRejected(reason.to_string()))
+        }
+        P2pConnectionResponse::SignalDecryptionFailed => Err(Error::IceRestartRejected(
+            "signal decryption failed".to_owned(),
+        )),
+        P2pConnectionResponse::InternalError => {
+            Err(Error::IceRestartRejected("internal error".to_owned()))
+        }
+    }
 }
 
-type ChannelMsgSender = mpsc::UnboundedSender<(MsgId, Vec<u8>, Option<mpsc::Tracker>)>;
-
-struct MsgBuffer {
-    buf: Vec<u8>,
+/// Applies ICE restart offer received from the peer and creates an answer.
+async fn ice_restart_answer_create(
+    pc: &mut RTCConnection,
+    offer: webrtc::Offer,
+) -> Result<String, Error> {
+    pc.remote_desc_set(offer.try_into()?).await?;
+    let answer = pc.answer_create().await?;
+    pc.local_desc_set(answer).await?;
+    wait_for_ice_gathering_complete(pc).await;
+    pc.local_sdp().await.ok_or(Error::ChannelClosed)
 }
 
-impl MsgBuffer {
-    fn new(capacity: usize) -> Self {
-        Self {
-            buf: Vec::with_capacity(capacity),
-        }
-    }
-
-    fn encode(&mut self, msg: &ChannelMsg) -> Result<Vec<u8>, std::io::Error> {
-        msg.encode(&mut self.buf)?;
-        let len_encoded = (self.buf.len() as u32).to_be_bytes();
-        let encoded = len_encoded
-            .into_iter()
-            .chain(self.buf.iter().cloned())
-            .collect();
-        self.buf.clear();
-        Ok(encoded)
-    }
+struct Channel {
+    id: ChannelId,
+    msg_sender: ChannelMsgSender,
 }
 
+/// Encoded message queued for sending, with the time after which it's
+/// dropped instead, see [`ChannelMsg::send_ttl`].
+type ChannelMsgSender = mpsc::UnboundedSender<(
+    MsgId,
+    P2pCorrelationId,
+    Vec<u8>,
+    Option<redux::Timestamp>,
+    Option<mpsc::Tracker>,
+)>;
+
 struct Channels {
     list: Vec<Channel>,
 }
@@ -489,116 +798,307 @@ impl Channels {
     }
 }
 
+type BulkChunkSender =
+    mpsc::UnboundedSender<(bytes::Bytes, mpsc::UnboundedSender<Result<(), String>>)>;
+
+/// Channels used for bulk transfers of large messages.
+struct BulkChannels {
+    list: Vec<(u8, BulkChunkSender)>,
+    next_index: usize,
+    next_transfer_id: u32,
+    recv: Arc<Mutex<BulkTransfersRecv>>,
+}
+
+impl BulkChannels {
+    fn new() -> Self {
+        Self {
+            list: Vec::new(),
+            next_index: 0,
+            next_transfer_id: 0,
+            recv: Default::default(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    fn add(&mut self, index: u8, chunk_sender: BulkChunkSender) {
+        self.list.push((index, chunk_sender));
+    }
+
+    fn remove(&mut self, index: u8) {
+        self.list.retain(|(i, _)| *i != index);
+    }
+
+    /// Splits message into chunks and distributes them across bulk
+    /// channels. Returns the number of chunks and a receiver for their
+    /// send results.
+    #[allow(clippy::type_complexity)]
+    fn send(
+        &mut self,
+        chan_id: ChannelId,
+        encoded: &[u8],
+    ) -> Result<(usize, mpsc::UnboundedReceiver<Result<(), String>>), String> {
+        let transfer_id = self.next_transfer_id;
+        self.next_transfer_id = self.next_transfer_id.wrapping_add(1);
+        let (result_tx, result_rx) = mpsc::unbounded_channel();
+        let mut count = 0;
+        for chunk in bulk_chunks(chan_id, transfer_id, encoded) {
+            let (_, chunk_sender) = &self.list[self.next_index % self.list.len()];
+            self.next_index = self.next_index.wrapping_add(1);
+            chunk_sender
+                .send((chunk, result_tx.clone()))
+                .map_err(|_| "BulkChunkMpscSendFailed".to_owned())?;
+            count += 1;
+        }
+        Ok((count, result_rx))
+    }
+}
+
+/// Waits for the created channel to open and sends the result to the
+/// peer loop. After the channel is opened, `closed` command is sent once
+/// it gets closed.
+fn channel_open_wait(
+    chan: Result<RTCChannel, Error>,
+    internal_cmd_sender: &mpsc::UnboundedSender<PeerCmdInternal>,
+    aborted: &Aborted,
+    opened: impl FnOnce(Result<RTCChannel, Error>) -> PeerCmdInternal + 'static,
+    closed: impl Fn() -> PeerCmdInternal + Clone + Send + Sync + 'static,
+) {
+    let internal_cmd_sender = internal_cmd_sender.clone();
+    let fut = async move {
+        let internal_cmd_sender_clone = internal_cmd_sender.clone();
+        let result = async move {
+            let chan = chan?;
+
+            let (done_tx, mut done_rx) = mpsc::channel::<Result<(), Error>>(1);
+
+            let done_tx_clone = done_tx.clone();
+            chan.on_open(move || {
+                let _ = done_tx_clone.try_send(Ok(()));
+                std::future::ready(())
+            });
+
+            let done_tx_clone = done_tx.clone();
+            let internal_cmd_sender = internal_cmd_sender_clone.clone();
+            let closed_clone = closed.clone();
+            chan.on_error(move |err| {
+                if done_tx_clone.try_send(Err(err.into())).is_err() {
+                    let _ = internal_cmd_sender.send(closed_clone());
+                }
+                std::future::ready(())
+            });
+
+            let done_tx_clone = done_tx.clone();
+            let internal_cmd_sender = internal_cmd_sender_clone.clone();
+            chan.on_close(move || {
+                if done_tx_clone.try_send(Err(Error::ChannelClosed)).is_err() {
+                    let _ = internal_cmd_sender.send(closed());
+                }
+                std::future::ready(())
+            });
+
+            done_rx.recv().await.ok_or(Error::ChannelClosed)??;
+
+            Ok(chan)
+        };
+
+        let _ = internal_cmd_sender.send(opened(result.await));
+    };
+    let mut aborted = aborted.clone();
+    spawn_local(async move {
+        tokio::select! {
+            _ = aborted.wait() => {}
+            _ = fut => {}
+        }
+    });
+}
+
 // TODO(binier): remove unwraps
 #[allow(unused_mut)]
 async fn peer_loop(
     peer_id: PeerId,
     event_sender: Arc<dyn Fn(P2pEvent) -> Option<()> + Send + Sync + 'static>,
-    mut cmd_receiver: mpsc::TrackedUnboundedReceiver<PeerCmd>,
+    mut cmd_receiver: mpsc::TrackedUnboundedReceiver<(P2pCorrelationId, PeerCmd)>,
+    mut conn_state_rx: mpsc::UnboundedReceiver<RTCConnectionState>,
     mut pc: RTCConnection,
+    ice_restart_signaling: Option<(Signaling, webrtc::Offer)>,
+    bulk_channels: u8,
+    send_config: Arc<WebrtcSendConfig>,
     aborted: Aborted,
 ) {
     // TODO(binier): maybe use small_vec (stack allocated) or something like that.
     let mut channels = Channels::new();
     let mut msg_buf = MsgBuffer::new(64 * 1024);
+    // Whether both sides announced the support for compression.
+    let mut compression = false;
+    // Whether both sides announced the support for lossy gossip channels.
+    let mut lossy_gossip = false;
 
     let (internal_cmd_sender, mut internal_cmd_receiver) =
         mpsc::unbounded_channel::<PeerCmdInternal>();
 
-    while matches!(pc.connection_state(), RTCConnectionState::Connected) {
+    let mut bulk = BulkChannels::new();
+    for index in 0..bulk_channels {
+        let chan = pc
+            .channel_create(RTCChannelConfig::reliable(
+                "bulk",
+                BULK_CHANNEL_ID_START + index as u16,
+            ))
+            .await;
+        channel_open_wait(
+            chan,
+            &internal_cmd_sender,
+            &aborted,
+            move |res| PeerCmdInternal::BulkChannelOpened(index, res),
+            move || PeerCmdInternal::BulkChannelClosed(index),
+        );
+    }
+
+    while !matches!(pc.connection_state(), RTCConnectionState::Closed) {
         let (cmd, _tracker) = tokio::select! {
             cmd = cmd_receiver.recv() => match cmd {
                 None => return,
-                Some(cmd) => (PeerCmdAll::External(cmd.0), Some(cmd.1)),
+                Some(mpsc::Tracked((cid, cmd), tracker)) => {
+                    (PeerCmdAll::External(cid, cmd), Some(tracker))
+                }
             },
             cmd = internal_cmd_receiver.recv() => match cmd {
                 None => return,
                 Some(cmd) => (PeerCmdAll::Internal(cmd), None),
             },
+            Some(state) = conn_state_rx.recv() => {
+                (PeerCmdAll::Internal(PeerCmdInternal::ConnectionStateChanged(state)), None)
+            }
         };
         match cmd {
             PeerCmdAll::External(
-                PeerCmd::PeerHttpOfferSend(..)
+                _,
+                PeerCmd::OfferSend(..)
                 | PeerCmd::AnswerSet(_)
                 | PeerCmd::ConnectionAuthorizationSend(_),
             ) => {
                 bug_condition!("unexpected peer cmd");
             }
-            PeerCmdAll::External(PeerCmd::ChannelOpen(id)) => {
+            PeerCmdAll::External(cid, PeerCmd::IceRestartInit) => {
+                let res = ice_restart_offer_send(&mut pc, ice_restart_signaling.as_ref())
+                    .await
+                    .map_err(|err| err.to_string());
+                let _ = event_sender(
+                    P2pConnectionEvent::IceRestartNegotiated(peer_id, cid, res).into(),
+                );
+            }
+            PeerCmdAll::External(cid, PeerCmd::IceRestartOfferSet(offer)) => {
+                let res = ice_restart_answer_create(&mut pc, offer)
+                    .await
+                    .map_err(|err| err.to_string());
+                let _ = event_sender(
+                    P2pConnectionEvent::IceRestartAnswerSdpReady(peer_id, cid, res).into(),
+                );
+            }
+            PeerCmdAll::External(cid, PeerCmd::ChannelOpen(id)) => {
                 let chan = pc
-                    .channel_create(RTCChannelConfig {
-                        label: id.name(),
-                        negotiated: Some(id.to_u16()),
-                    })
+                    .channel_create(RTCChannelConfig::channel(id, lossy_gossip))
                     .await;
-                let internal_cmd_sender = internal_cmd_sender.clone();
-                let fut = async move {
-                    let internal_cmd_sender_clone = internal_cmd_sender.clone();
-                    let result = async move {
-                        let chan = chan?;
-
-                        let (done_tx, mut done_rx) = mpsc::channel::<Result<(), Error>>(1);
-
-                        let done_tx_clone = done_tx.clone();
-                        chan.on_open(move || {
-                            let _ = done_tx_clone.try_send(Ok(()));
-                            std::future::ready(())
-                        });
-
-                        let done_tx_clone = done_tx.clone();
-                        let internal_cmd_sender = internal_cmd_sender_clone.clone();
-                        chan.on_error(move |err| {
-                            if done_tx_clone.try_send(Err(err.into())).is_err() {
-                                let _ =
-                                    internal_cmd_sender.send(PeerCmdInternal::ChannelClosed(id));
-                            }
-                            std::future::ready(())
-                        });
-
-                        let done_tx_clone = done_tx.clone();
-                        let internal_cmd_sender = internal_cmd_sender_clone.clone();
-                        chan.on_close(move || {
-                            if done_tx_clone.try_send(Err(Error::ChannelClosed)).is_err() {
-                                let _ =
-                                    internal_cmd_sender.send(PeerCmdInternal::ChannelClosed(id));
-                            }
-                            std::future::ready(())
-                        });
-
-                        done_rx.recv().await.ok_or(Error::ChannelClosed)??;
-
-                        Ok(chan)
-                    };
-
-                    let _ =
-                        internal_cmd_sender.send(PeerCmdInternal::ChannelOpened(id, result.await));
-                };
-                let mut aborted = aborted.clone();
-                spawn_local(async move {
-                    tokio::select! {
-                        _ = aborted.wait() => {}
-                        _ = fut => {}
-                    }
-                });
+                channel_open_wait(
+                    chan,
+                    &internal_cmd_sender,
+                    &aborted,
+                    move |res| PeerCmdInternal::ChannelOpened(id, cid, res),
+                    move || PeerCmdInternal::ChannelClosed(id),
+                );
             }
-            PeerCmdAll::External(PeerCmd::ChannelSend(msg_id, msg)) => {
+            PeerCmdAll::External(_, PeerCmd::CompressionEnable) => {
+                compression = true;
+            }
+            PeerCmdAll::External(_, PeerCmd::LossyGossipEnable) => {
+                lossy_gossip = true;
+            }
+            PeerCmdAll::External(cid, PeerCmd::ChannelSend(msg_id, msg)) => {
                 let id = msg.channel_id();
+                let expires_at = msg
+                    .send_ttl()
+                    .map(|ttl| redux::Timestamp::global_now() + ttl);
                 let err = match channels.get_msg_sender(id) {
                     Some(msg_sender) => match msg_buf.encode(&msg) {
-                        Ok(encoded) => match msg_sender.send((msg_id, encoded, _tracker)) {
-                            Ok(_) => None,
-                            Err(_) => Some("ChannelMsgMpscSendFailed".to_owned()),
-                        },
-                        Err(err) => Some(err.to_string()),
+                        Ok(encoded)
+                            if encoded.len() > BULK_TRANSFER_MIN_SIZE
+                                && is_bulk_transfer_supported(id)
+                                && !bulk.is_empty() =>
+                        {
+                            // Skip the length prefix, chunks carry their own header.
+                            match bulk.send(id, &encoded[4..]) {
+                                Ok((count, mut result_rx)) => {
+                                    let event_sender = event_sender.clone();
+                                    let fut = async move {
+                                        let _tracker = _tracker;
+                                        let mut result = Ok(());
+                                        for _ in 0..count {
+                                            match result_rx.recv().await {
+                                                Some(Ok(())) => {}
+                                                Some(Err(err)) => {
+                                                    result = Err(P2pChannelSendError::Failed(err));
+                                                    break;
+                                                }
+                                                None => {
+                                                    result = Err(P2pChannelSendError::Failed(
+                                                        "BulkChannelClosed".to_owned(),
+                                                    ));
+                                                    break;
+                                                }
+                                            }
+                                        }
+                                        let _ = event_sender(
+                                            P2pChannelEvent::Sent(peer_id, id, msg_id, cid, result)
+                                                .into(),
+                                        );
+                                    };
+                                    let mut aborted = aborted.clone();
+                                    spawn_local(async move {
+                                        tokio::select! {
+                                            _ = aborted.wait() => {}
+                                            _ = fut => {}
+                                        }
+                                    });
+                                    None
+                                }
+                                Err(err) => Some(P2pChannelSendError::Failed(err)),
+                            }
+                        }
+                        Ok(encoded) => {
+                            let encoded = match compression {
+                                true => MsgBuffer::compress(encoded),
+                                false => encoded,
+                            };
+                            if !id.is_reliable(lossy_gossip)
+                                && encoded.len() > send_config.get(id).chunk_size
+                            {
+                                Some(P2pChannelSendError::Failed(format!(
+                                    "ChannelMsgTooBigForUnreliableChannel; len: {}",
+                                    encoded.len()
+                                )))
+                            } else {
+                                match msg_sender.send((msg_id, cid, encoded, expires_at, _tracker))
+                                {
+                                    Ok(_) => None,
+                                    Err(_) => Some(P2pChannelSendError::Failed(
+                                        "ChannelMsgMpscSendFailed".to_owned(),
+                                    )),
+                                }
+                            }
+                        }
+                        Err(err) => Some(err.into()),
                     },
-                    None => Some("ChannelNotOpen".to_owned()),
+                    None => Some(P2pChannelSendError::Failed("ChannelNotOpen".to_owned())),
                 };
                 if let Some(err) = err {
-                    let _ =
-                        event_sender(P2pChannelEvent::Sent(peer_id, id, msg_id, Err(err)).into());
+                    let _ = event_sender(
+                        P2pChannelEvent::Sent(peer_id, id, msg_id, cid, Err(err)).into(),
+                    );
                 }
             }
-            PeerCmdAll::Internal(PeerCmdInternal::ChannelOpened(chan_id, result)) => {
+            PeerCmdAll::Internal(PeerCmdInternal::ChannelOpened(chan_id, open_cid, result)) => {
                 let (sender_tx, mut sender_rx) = mpsc::unbounded_channel();
                 let (chan, res) = match result {
                     Ok(chan) => {
@@ -610,56 +1110,12 @@ async fn peer_loop(
 
                 #[allow(unused_mut)]
                 if let Some(mut chan) = chan {
-                    fn process_msg(
-                        chan_id: ChannelId,
-                        buf: &mut Vec<u8>,
-                        len: &mut u32,
-                        msg: &mut &[u8],
-                    ) -> Result<Option<ChannelMsg>, String> {
-                        let len = if buf.is_empty() {
-                            if msg.len() < 4 {
-                                return Err("WebRTCMessageTooSmall".to_owned());
-                            } else {
-                                *len = u32::from_be_bytes(
-                                    msg[..4].try_into().expect("Size checked above"),
-                                );
-                                *msg = &msg[4..];
-                                let len = *len as usize;
-                                if len > chan_id.max_msg_size() {
-                                    return Err(format!(
-                                        "ChannelMsgLenOverLimit; len: {}, limit: {}",
-                                        len,
-                                        chan_id.max_msg_size()
-                                    ));
-                                }
-                                len
-                            }
-                        } else {
-                            *len as usize
-                        };
-                        let bytes_left = len - buf.len();
-
-                        if bytes_left > msg.len() {
-                            buf.extend_from_slice(msg);
-                            *msg = &[];
-                            return Ok(None);
-                        }
-
-                        buf.extend_from_slice(&msg[..bytes_left]);
-                        *msg = &msg[bytes_left..];
-                        let msg = ChannelMsg::decode(&mut &buf[..], chan_id)
-                            .map_err(|err| err.to_string())?;
-                        buf.clear();
-                        Ok(Some(msg))
-                    }
-
-                    let mut len = 0;
-                    let mut buf = Vec::new();
+                    let mut decoder = ChannelMsgDecoder::new(chan_id);
                     let event_sender_clone = event_sender.clone();
 
                     chan.on_message(move |mut data| {
                         while !data.is_empty() {
-                            let res = match process_msg(chan_id, &mut buf, &mut len, &mut data) {
+                            let res = match decoder.decode_next(&mut data) {
                                 Ok(None) => continue,
                                 Ok(Some(msg)) => Ok(msg),
                                 Err(err) => Err(err),
@@ -672,35 +1128,43 @@ async fn peer_loop(
                     });
 
                     let event_sender = event_sender.clone();
+                    let mut pacer = ChunkPacer::new(send_config.get(chan_id));
                     let fut = async move {
                         // Add a delay for sending messages after channel
                         // was opened. Some initial messages get lost otherwise.
                         // TODO(binier): find deeper cause and fix it.
                         sleep(Duration::from_secs(3)).await;
 
-                        while let Some((msg_id, encoded, _tracker)) = sender_rx.recv().await {
+                        while let Some((msg_id, cid, encoded, expires_at, _tracker)) =
+                            sender_rx.recv().await
+                        {
+                            // Message got stale while it was waiting behind the
+                            // previous ones, don't waste the bandwidth on it.
+                            if expires_at.is_some_and(|t| redux::Timestamp::global_now() >= t) {
+                                let result = Err(P2pChannelSendError::Expired);
+                                let _ = event_sender(
+                                    P2pChannelEvent::Sent(peer_id, chan_id, msg_id, cid, result)
+                                        .into(),
+                                );
+                                continue;
+                            }
                             let encoded = bytes::Bytes::from(encoded);
+                            let chunk_size = pacer.config().chunk_size;
                             let mut chunks =
-                                encoded.chunks(CHUNK_SIZE).map(|b| encoded.slice_ref(b));
+                                encoded.chunks(chunk_size).map(|b| encoded.slice_ref(b));
                             let result = loop {
                                 let Some(chunk) = chunks.next() else {
                                     break Ok(());
                                 };
-                                if let Err(err) = chan
-                                    .send(&chunk)
-                                    .await
-                                    .map_err(|e| format!("{e:?}"))
-                                    .and_then(|n| match n == chunk.len() {
-                                        false => Err("NotAllBytesWritten".to_owned()),
-                                        true => Ok(()),
-                                    })
+                                if let Err(err) =
+                                    chunk_send_paced(&mut chan, &mut pacer, &chunk).await
                                 {
-                                    break Err(err);
+                                    break Err(P2pChannelSendError::Failed(err));
                                 }
                             };
 
                             let _ = event_sender(
-                                P2pChannelEvent::Sent(peer_id, chan_id, msg_id, result).into(),
+                                P2pChannelEvent::Sent(peer_id, chan_id, msg_id, cid, result).into(),
                             );
                         }
                     };
@@ -714,12 +1178,70 @@ async fn peer_loop(
                     });
                 }
 
-                let _ = event_sender(P2pChannelEvent::Opened(peer_id, chan_id, res).into());
+                let _ =
+                    event_sender(P2pChannelEvent::Opened(peer_id, chan_id, open_cid, res).into());
             }
             PeerCmdAll::Internal(PeerCmdInternal::ChannelClosed(id)) => {
                 channels.remove(id);
                 let _ = event_sender(P2pChannelEvent::Closed(peer_id, id).into());
             }
+            PeerCmdAll::Internal(PeerCmdInternal::BulkChannelOpened(index, result)) => {
+                // Bulk channels are optional, if one fails to open,
+                // bulk transfers use the rest of them.
+                #[allow(unused_mut)]
+                let mut chan = match result {
+                    Ok(chan) => chan,
+                    Err(_) => continue,
+                };
+
+                let bulk_recv = bulk.recv.clone();
+                let event_sender_clone = event_sender.clone();
+                chan.on_message(move |data| {
+                    let res = match bulk_recv.lock() {
+                        Ok(mut recv) => recv.chunk_received(data).transpose(),
+                        Err(_) => Some(Err("BulkTransfersRecvPoisoned".to_owned())),
+                    };
+                    if let Some(res) = res {
+                        let _ = event_sender_clone(P2pChannelEvent::Received(peer_id, res).into());
+                    }
+                    #[cfg(not(all(not(target_arch = "wasm32"), feature = "p2p-webrtc-cpp")))]
+                    std::future::ready(())
+                });
+
+                let (chunk_tx, mut chunk_rx) = mpsc::unbounded_channel();
+                bulk.add(index, chunk_tx);
+                // Bulk transfers carry messages of these channels, so they
+                // are paced the same way, except for the chunk size.
+                let mut pacer = ChunkPacer::new(send_config.get(ChannelId::StreamingRpc));
+                let fut = async move {
+                    // Same as for other channels, initial messages might get lost.
+                    sleep(Duration::from_secs(3)).await;
+
+                    while let Some((chunk, result_tx)) = chunk_rx.recv().await {
+                        let result = chunk_send_paced(&mut chan, &mut pacer, &chunk).await;
+                        let _ = result_tx.send(result);
+                    }
+                };
+                let mut aborted = aborted.clone();
+                spawn_local(async move {
+                    tokio::select! {
+                        _ = aborted.wait() => {}
+                        _ = fut => {}
+                    }
+                });
+            }
+            PeerCmdAll::Internal(PeerCmdInternal::BulkChannelClosed(index)) => {
+                bulk.remove(index);
+            }
+            PeerCmdAll::Internal(PeerCmdInternal::ConnectionStateChanged(state)) => match state {
+                RTCConnectionState::Disconnected | RTCConnectionState::Failed => {
+                    let _ = event_sender(P2pConnectionEvent::Degraded(peer_id).into());
+                }
+                RTCConnectionState::Connected => {
+                    let _ = event_sender(P2pConnectionEvent::Recovered(peer_id).into());
+                }
+                _ => {}
+            },
         }
     }
 }
@@ -729,7 +1251,7 @@ pub trait P2pServiceWebrtc: redux::Service {
 
     fn random_pick(
         &mut self,
-        list: &[P2pConnectionOutgoingInitOpts],
+        list: &[(P2pConnectionOutgoingInitOpts, f64)],
     ) -> Option<P2pConnectionOutgoingInitOpts>;
 
     fn event_sender(&self) -> &mpsc::UnboundedSender<Self::Event>;
@@ -742,8 +1264,11 @@ pub trait P2pServiceWebrtc: redux::Service {
         secret_key: SecretKey,
         spawner: S,
         rng_seed: [u8; 32],
+        send_config: WebrtcSendConfig,
     ) -> P2pServiceCtx {
         const MAX_PEERS: usize = 500;
+        let send_config = Arc::new(send_config);
+        let auth_config = ConnectionAuthConfig::default();
         let (cmd_sender, mut cmd_receiver) = mpsc::tracked_unbounded_channel();
 
         let certificate = certificate_from_pem_key(secret_key.to_pem().as_str());
@@ -761,6 +1286,7 @@ pub trait P2pServiceWebrtc: redux::Service {
                         let peer_id = args.peer_id;
                         let event_sender = args.event_sender.clone();
                         let certificate = certificate.clone();
+                        let send_config = send_config.clone();
                         spawn_local(async move {
                             let Ok(_permit) = conn_permits.try_acquire() else {
                                 // state machine shouldn't allow this to happen.
@@ -775,7 +1301,7 @@ pub trait P2pServiceWebrtc: redux::Service {
                                 event_sender_clone(P2pConnectionEvent::Closed(peer_id).into());
                             });
                             tokio::select! {
-                                _ = peer_start(api, args, aborted.clone(), closed_tx.clone(), certificate, rng_seed) => {}
+                                _ = peer_start(api, args, aborted.clone(), closed_tx.clone(), certificate, rng_seed, send_config, auth_config) => {}
                                 _ = aborted.wait() => {
                                 }
                             }
@@ -795,7 +1321,7 @@ pub trait P2pServiceWebrtc: redux::Service {
         }
     }
 
-    fn outgoing_init(&mut self, peer_id: PeerId) {
+    fn outgoing_init(&mut self, peer_id: PeerId) -> P2pCorrelationId {
         let (peer_cmd_sender, peer_cmd_receiver) = mpsc::tracked_unbounded_channel();
         let aborter = Aborter::default();
         let aborted = aborter.aborted();
@@ -810,18 +1336,22 @@ pub trait P2pServiceWebrtc: redux::Service {
         let event_sender = self.event_sender().clone();
         let event_sender =
             Arc::new(move |p2p_event: P2pEvent| event_sender.send(p2p_event.into()).ok());
+        let correlation_id = P2pCorrelationId::next();
+        cmd_log(peer_id, correlation_id, "PeerAdd");
         let _ = self.cmd_sender().tracked_send(Cmd::PeerAdd {
             args: PeerAddArgs {
                 peer_id,
                 kind: PeerConnectionKind::Outgoing,
+                correlation_id,
                 event_sender,
                 cmd_receiver: peer_cmd_receiver,
             },
             aborted,
         });
+        correlation_id
     }
 
-    fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer) {
+    fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer) -> P2pCorrelationId {
         let (peer_cmd_sender, peer_cmd_receiver) = mpsc::tracked_unbounded_channel();
         let aborter = Aborter::default();
         let aborted = aborter.aborted();
@@ -836,29 +1366,49 @@ pub trait P2pServiceWebrtc: redux::Service {
         let event_sender = self.event_sender().clone();
         let event_sender =
             Arc::new(move |p2p_event: P2pEvent| event_sender.send(p2p_event.into()).ok());
+        let correlation_id = P2pCorrelationId::next();
+        cmd_log(peer_id, correlation_id, "PeerAdd");
         let _ = self.cmd_sender().tracked_send(Cmd::PeerAdd {
             args: PeerAddArgs {
                 peer_id,
                 kind: PeerConnectionKind::Incoming(Box::new(offer)),
+                correlation_id,
                 event_sender,
                 cmd_receiver: peer_cmd_receiver,
             },
             aborted,
         });
+        correlation_id
     }
 
-    fn set_answer(&mut self, peer_id: PeerId, answer: webrtc::Answer) {
-        if let Some(peer) = self.peers().get(&peer_id) {
-            let _ = peer.cmd_sender.tracked_send(PeerCmd::AnswerSet(answer));
-        }
+    fn set_answer(&mut self, peer_id: PeerId, answer: webrtc::Answer) -> Option<P2pCorrelationId> {
+        let peer = self.peers().get(&peer_id)?;
+        Some(peer.cmd_send(peer_id, PeerCmd::AnswerSet(answer)))
     }
 
-    fn http_signaling_request(&mut self, url: String, offer: webrtc::Offer) {
-        if let Some(peer) = self.peers().get(&offer.target_peer_id) {
-            let _ = peer
-                .cmd_sender
-                .tracked_send(PeerCmd::PeerHttpOfferSend(url, offer));
-        }
+    fn signaling_request(
+        &mut self,
+        method: webrtc::SignalingMethod,
+        offer: webrtc::Offer,
+    ) -> Option<P2pCorrelationId> {
+        let signaling = Signaling::from_method(&method)?;
+        let peer_id = offer.target_peer_id;
+        let peer = self.peers().get(&peer_id)?;
+        Some(peer.cmd_send(peer_id, PeerCmd::OfferSend(signaling, offer)))
+    }
+
+    fn ice_restart_init(&mut self, peer_id: PeerId) -> Option<P2pCorrelationId> {
+        let peer = self.peers().get(&peer_id)?;
+        Some(peer.cmd_send(peer_id, PeerCmd::IceRestartInit))
+    }
+
+    fn ice_restart_offer_set(
+        &mut self,
+        peer_id: PeerId,
+        offer: webrtc::Offer,
+    ) -> Option<P2pCorrelationId> {
+        let peer = self.peers().get(&peer_id)?;
+        Some(peer.cmd_send(peer_id, PeerCmd::IceRestartOfferSet(offer)))
     }
 
     fn disconnect(&mut self, peer_id: PeerId) -> bool {
@@ -876,17 +1426,26 @@ pub trait P2pServiceWebrtc: redux::Service {
         true
     }
 
-    fn channel_open(&mut self, peer_id: PeerId, id: ChannelId) {
+    fn channel_open(&mut self, peer_id: PeerId, id: ChannelId) -> Option<P2pCorrelationId> {
+        let peer = self.peers().get(&peer_id)?;
+        Some(peer.cmd_send(peer_id, PeerCmd::ChannelOpen(id)))
+    }
+
+    fn channel_send(&mut self, peer_id: PeerId, msg_id: MsgId, msg: ChannelMsg) {
         if let Some(peer) = self.peers().get(&peer_id) {
-            let _ = peer.cmd_sender.tracked_send(PeerCmd::ChannelOpen(id));
+            peer.cmd_send(peer_id, PeerCmd::ChannelSend(msg_id, msg));
         }
     }
 
-    fn channel_send(&mut self, peer_id: PeerId, msg_id: MsgId, msg: ChannelMsg) {
+    fn compression_enable(&mut self, peer_id: PeerId) {
+        if let Some(peer) = self.peers().get(&peer_id) {
+            peer.cmd_send(peer_id, PeerCmd::CompressionEnable);
+        }
+    }
+
+    fn lossy_gossip_enable(&mut self, peer_id: PeerId) {
         if let Some(peer) = self.peers().get(&peer_id) {
-            let _ = peer
-                .cmd_sender
-                .tracked_send(PeerCmd::ChannelSend(msg_id, msg));
+            peer.cmd_send(peer_id, PeerCmd::LossyGossipEnable);
         }
     }
 
@@ -909,9 +1468,7 @@ pub trait P2pServiceWebrtc: redux::Service {
         auth: Option<ConnectionAuthEncrypted>,
     ) {
         if let Some(peer) = self.peers().get(&peer_id) {
-            let _ = peer
-                .cmd_sender
-                .tracked_send(PeerCmd::ConnectionAuthorizationSend(auth));
+            peer.cmd_send(peer_id, PeerCmd::ConnectionAuthorizationSend(auth));
         }
     }
 
@@ -0,0 +1,74 @@
+use std::future::Future;
+
+use crate::webrtc::{Offer, P2pConnectionResponse, SignalingMethod};
+
+use super::{webrtc_signal_send, webrtc_signal_send_ws, RTCSignalingError};
+
+/// Exchanges the offer for the answer with the signaling server of the
+/// peer, which we are connecting to.
+pub trait SignalingBackend {
+    fn offer_send(
+        &self,
+        offer: Offer,
+    ) -> impl Future<Output = Result<P2pConnectionResponse, RTCSignalingError>>;
+}
+
+/// Offer is sent with the http request and the answer is received in
+/// the response.
+#[derive(Debug, Clone)]
+pub struct HttpSignaling {
+    pub url: String,
+}
+
+/// Offer and the answer are exchanged over the websocket, for peers
+/// which aren't allowed to make http requests to the signaling server.
+#[derive(Debug, Clone)]
+pub struct WsSignaling {
+    pub url: String,
+}
+
+/// Signaling backend selected by the [`SignalingMethod`].
+#[derive(Debug, Clone)]
+pub enum Signaling {
+    Http(HttpSignaling),
+    Ws(WsSignaling),
+}
+
+impl Signaling {
+    /// Returns `None` if the offer isn't sent by the service, but
+    /// relayed by the state machine, like for [`SignalingMethod::P2p`].
+    pub fn from_method(method: &SignalingMethod) -> Option<Self> {
+        match method {
+            SignalingMethod::Http(_)
+            | SignalingMethod::Https(_)
+            | SignalingMethod::HttpsProxy(..) => method
+                .http_url()
+                .map(|url| Self::Http(HttpSignaling { url })),
+            SignalingMethod::Wss(_) | SignalingMethod::Ws(_) => {
+                method.ws_url().map(|url| Self::Ws(WsSignaling { url }))
+            }
+            SignalingMethod::P2p { .. } => None,
+        }
+    }
+}
+
+impl SignalingBackend for HttpSignaling {
+    async fn offer_send(&self, offer: Offer) -> Result<P2pConnectionResponse, RTCSignalingError> {
+        webrtc_signal_send(&self.url, offer).await
+    }
+}
+
+impl SignalingBackend for WsSignaling {
+    async fn offer_send(&self, offer: Offer) -> Result<P2pConnectionResponse, RTCSignalingError> {
+        webrtc_signal_send_ws(&self.url, offer).await
+    }
+}
+
+impl SignalingBackend for Signaling {
+    async fn offer_send(&self, offer: Offer) -> Result<P2pConnectionResponse, RTCSignalingError> {
+        match self {
+            Self::Http(backend) => backend.offer_send(offer).await,
+            Self::Ws(backend) => backend.offer_send(offer).await,
+        }
+    }
+}
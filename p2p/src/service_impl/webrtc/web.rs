@@ -7,7 +7,7 @@ use wasm_bindgen::{convert::FromWasmAbi, prelude::*};
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::{
     MessageEvent, RtcConfiguration, RtcDataChannel, RtcDataChannelInit, RtcIceGatheringState,
-    RtcIceTransportPolicy, RtcPeerConnection, RtcPeerConnectionState, RtcSdpType,
+    RtcIceTransportPolicy, RtcOfferOptions, RtcPeerConnection, RtcPeerConnectionState, RtcSdpType,
     RtcSessionDescriptionInit,
 };
 
@@ -51,6 +51,9 @@ pub enum RTCSignalingError {
     SerializeError(serde_json::Error),
     #[error("http request failed: {0}")]
     HttpError(String),
+    #[from(ignore)]
+    #[error("websocket failed: {0}")]
+    WebSocketError(String),
 }
 
 impl From<JsValue> for RTCSignalingError {
@@ -87,6 +90,14 @@ impl RTCConnection {
         Ok(JsFuture::from(self.0.create_offer()).await?.into())
     }
 
+    /// Creates an offer which restarts ICE on the existing connection.
+    pub async fn offer_create_ice_restart(&self) -> Result<RtcSessionDescriptionInit> {
+        let mut options = RtcOfferOptions::new();
+        options.ice_restart(true);
+        let offer = JsFuture::from(self.0.create_offer_with_rtc_offer_options(&options)).await?;
+        Ok(offer.into())
+    }
+
     pub async fn answer_create(&self) -> Result<RtcSessionDescriptionInit> {
         Ok(JsFuture::from(self.0.create_answer()).await?.into())
     }
@@ -232,6 +243,10 @@ impl RTCChannel {
         self.0.send_with_array_buffer(&array.buffer()).map(|_| len)
     }
 
+    pub async fn buffered_amount(&self) -> usize {
+        self.0.buffered_amount() as usize
+    }
+
     pub async fn close(&self) {
         let _ = self.0.close();
     }
@@ -258,6 +273,66 @@ pub async fn webrtc_signal_send(
     Ok(json.into_serde()?)
 }
 
+pub async fn webrtc_signal_send_ws(
+    url: &str,
+    offer: Offer,
+) -> std::result::Result<P2pConnectionResponse, RTCSignalingError> {
+    use std::cell::RefCell;
+    use web_sys::WebSocket;
+
+    type Tx = Rc<RefCell<Option<oneshot::Sender<std::result::Result<String, String>>>>>;
+    fn respond(tx: &Tx, res: std::result::Result<String, String>) {
+        if let Some(tx) = tx.borrow_mut().take() {
+            let _ = tx.send(res);
+        }
+    }
+
+    let offer = serde_json::to_string(&offer)?;
+    let ws =
+        WebSocket::new(url).map_err(|err| RTCSignalingError::WebSocketError(format!("{err:?}")))?;
+    let (tx, rx) = oneshot::channel();
+    let tx: Tx = Rc::new(RefCell::new(Some(tx)));
+
+    let onopen = {
+        let (ws, tx) = (ws.clone(), tx.clone());
+        Closure::<dyn FnMut()>::new(move || {
+            if let Err(err) = ws.send_with_str(&offer) {
+                respond(&tx, Err(format!("{err:?}")));
+            }
+        })
+    };
+    let onmessage = {
+        let tx = tx.clone();
+        Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            let res = event
+                .data()
+                .as_string()
+                .ok_or_else(|| "unexpected binary message".to_owned());
+            respond(&tx, res);
+        })
+    };
+    let onclose = Closure::<dyn FnMut()>::new(move || {
+        respond(&tx, Err("closed before the answer was received".to_owned()));
+    });
+    ws.set_onopen(Some(onopen.as_ref().unchecked_ref()));
+    ws.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    ws.set_onerror(Some(onclose.as_ref().unchecked_ref()));
+    ws.set_onclose(Some(onclose.as_ref().unchecked_ref()));
+
+    let res = rx.await;
+    // handlers are dropped when we return, so they must be detached.
+    ws.set_onopen(None);
+    ws.set_onmessage(None);
+    ws.set_onerror(None);
+    ws.set_onclose(None);
+    let _ = ws.close();
+
+    let answer = res
+        .unwrap_or_else(|_| Err("answer channel closed".to_owned()))
+        .map_err(RTCSignalingError::WebSocketError)?;
+    Ok(serde_json::from_str(&answer)?)
+}
+
 impl Clone for RTCConnection {
     fn clone(&self) -> Self {
         Self(self.0.clone(), false)
@@ -277,10 +352,14 @@ impl From<RTCConfig> for RtcConfiguration {
 impl From<&RTCChannelConfig> for RtcDataChannelInit {
     fn from(value: &RTCChannelConfig) -> Self {
         let mut config = Self::new();
+        config.ordered(value.ordered);
 
         if let Some(negotiated) = value.negotiated {
             config.negotiated(true).id(negotiated);
         }
+        if let Some(max_retransmits) = value.max_retransmits {
+            config.max_retransmits(max_retransmits);
+        }
 
         config
     }
@@ -63,6 +63,8 @@ pub enum RTCSignalingError {
     Serialize(serde_json::Error),
     #[error("http request failed: {0}")]
     Http(reqwest::Error),
+    #[error("websocket failed: {0}")]
+    WebSocket(tokio_tungstenite::tungstenite::Error),
 }
 
 impl RTCConnection {
@@ -111,6 +113,11 @@ impl RTCConnection {
         self.conn.local_description().ok_or(Error::NotAvailable)
     }
 
+    /// libdatachannel doesn't support ICE restarts.
+    pub async fn offer_create_ice_restart(&mut self) -> Result<SessionDescription> {
+        Err(Error::NotAvailable)
+    }
+
     pub async fn answer_create(&mut self) -> Result<SessionDescription> {
         self.conn.local_description().ok_or(Error::NotAvailable)
     }
@@ -232,6 +239,10 @@ impl RTCChannel {
         self.chan.send(data.as_ref()).map(|_| data.len())
     }
 
+    pub async fn buffered_amount(&self) -> usize {
+        self.chan.buffered_amount()
+    }
+
     pub async fn close(&self) {}
 }
 
@@ -264,7 +275,7 @@ pub async fn webrtc_signal_send(
     url: &str,
     offer: Offer,
 ) -> std::result::Result<P2pConnectionResponse, RTCSignalingError> {
-    let client = reqwest::Client::new();
+    let client = super::signaling_http_client()?;
     let res = client
         .post(url)
         .body(serde_json::to_string(&offer)?)
@@ -275,6 +286,31 @@ pub async fn webrtc_signal_send(
     Ok(res)
 }
 
+pub async fn webrtc_signal_send_ws(
+    url: &str,
+    offer: Offer,
+) -> std::result::Result<P2pConnectionResponse, RTCSignalingError> {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let mut ws = super::signaling_ws_connect(url).await?;
+    ws.send(Message::Text(serde_json::to_string(&offer)?))
+        .await?;
+    while let Some(msg) = ws.next().await {
+        match msg? {
+            Message::Text(answer) => {
+                let _ = ws.close(None).await;
+                return Ok(serde_json::from_str(&answer)?);
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    Err(RTCSignalingError::WebSocket(
+        tokio_tungstenite::tungstenite::Error::ConnectionClosed,
+    ))
+}
+
 impl From<RTCConfig> for RtcConfig {
     fn from(value: RTCConfig) -> Self {
         let ice_servers = value
@@ -295,12 +331,18 @@ impl From<RTCConfig> for RtcConfig {
 
 impl From<RTCChannelConfig> for DataChannelInit {
     fn from(value: RTCChannelConfig) -> Self {
-        let reliability = Reliability::default();
+        let mut reliability = Reliability::default();
         assert!(!reliability.unordered);
         assert!(!reliability.unreliable);
         assert_eq!(reliability.max_packet_life_time, 0);
         assert_eq!(reliability.max_retransmits, 0);
 
+        reliability.unordered = !value.ordered;
+        if let Some(max_retransmits) = value.max_retransmits {
+            reliability.unreliable = true;
+            reliability.max_retransmits = max_retransmits.into();
+        }
+
         let config = DataChannelInit::default().reliability(reliability);
         if let Some(stream_id) = value.negotiated {
             config.negotiated().manual_stream().stream(stream_id)
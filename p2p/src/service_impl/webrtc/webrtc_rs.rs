@@ -9,7 +9,8 @@ use webrtc::{
         ice_gathering_state::RTCIceGatheringState, ice_server::RTCIceServer,
     },
     peer_connection::{
-        configuration::RTCConfiguration, peer_connection_state::RTCPeerConnectionState,
+        configuration::RTCConfiguration, offer_answer_options::RTCOfferOptions,
+        peer_connection_state::RTCPeerConnectionState,
         policy::ice_transport_policy::RTCIceTransportPolicy,
         sdp::session_description::RTCSessionDescription, RTCPeerConnection,
     },
@@ -50,6 +51,8 @@ pub enum RTCSignalingError {
     Serialize(serde_json::Error),
     #[error("http request failed: {0}")]
     Http(reqwest::Error),
+    #[error("websocket failed: {0}")]
+    WebSocket(tokio_tungstenite::tungstenite::Error),
 }
 
 impl RTCConnection {
@@ -71,9 +74,9 @@ impl RTCConnection {
             .create_data_channel(
                 config.label,
                 Some(RTCDataChannelInit {
-                    ordered: Some(true),
+                    ordered: Some(config.ordered),
                     max_packet_life_time: None,
-                    max_retransmits: None,
+                    max_retransmits: config.max_retransmits,
                     negotiated: config.negotiated,
                     ..Default::default()
                 }),
@@ -86,6 +89,16 @@ impl RTCConnection {
         self.0.create_offer(None).await
     }
 
+    /// Creates an offer which restarts ICE on the existing connection.
+    pub async fn offer_create_ice_restart(&self) -> Result<RTCSessionDescription> {
+        self.0
+            .create_offer(Some(RTCOfferOptions {
+                ice_restart: true,
+                ..Default::default()
+            }))
+            .await
+    }
+
     pub async fn answer_create(&self) -> Result<RTCSessionDescription> {
         self.0.create_answer(None).await
     }
@@ -171,6 +184,10 @@ impl RTCChannel {
         self.0.send(data).await
     }
 
+    pub async fn buffered_amount(&self) -> usize {
+        self.0.buffered_amount().await
+    }
+
     pub async fn close(&self) {
         let _ = self.0.close().await;
     }
@@ -180,7 +197,7 @@ pub async fn webrtc_signal_send(
     url: &str,
     offer: Offer,
 ) -> std::result::Result<P2pConnectionResponse, RTCSignalingError> {
-    let client = reqwest::Client::new();
+    let client = super::signaling_http_client()?;
     let res = client
         .post(url)
         .body(serde_json::to_string(&offer)?)
@@ -191,6 +208,31 @@ pub async fn webrtc_signal_send(
     Ok(res)
 }
 
+pub async fn webrtc_signal_send_ws(
+    url: &str,
+    offer: Offer,
+) -> std::result::Result<P2pConnectionResponse, RTCSignalingError> {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let mut ws = super::signaling_ws_connect(url).await?;
+    ws.send(Message::Text(serde_json::to_string(&offer)?))
+        .await?;
+    while let Some(msg) = ws.next().await {
+        match msg? {
+            Message::Text(answer) => {
+                let _ = ws.close(None).await;
+                return Ok(serde_json::from_str(&answer)?);
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    Err(RTCSignalingError::WebSocket(
+        tokio_tungstenite::tungstenite::Error::ConnectionClosed,
+    ))
+}
+
 impl Clone for RTCConnection {
     fn clone(&self) -> Self {
         Self(self.0.clone(), false)
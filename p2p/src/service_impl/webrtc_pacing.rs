@@ -0,0 +1,181 @@
+//! Chunking and pacing of the messages sent over webrtc datachannels.
+//!
+//! Messages are split into chunks of [`ChannelSendConfig::chunk_size`].
+//! Before each chunk is sent, [`ChunkPacer`] makes sure that the
+//! `bufferedAmount` of the datachannel stays below
+//! [`ChannelSendConfig::buffered_amount_high`], waiting for the time
+//! estimated from the observed drain rate of the buffer. This matters
+//! for browsers with small SCTP buffers, which otherwise fail or stall
+//! when flooded with chunks.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use crate::channels::ChannelId;
+
+/// Default size of the chunk, 16KB.
+pub const DEFAULT_CHUNK_SIZE: usize = 16 * 1024;
+pub const MIN_CHUNK_SIZE: usize = 1024;
+/// Most browsers can't receive larger datachannel messages.
+pub const MAX_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Bounds of a single wait for the buffer to drain.
+const DRAIN_WAIT_MIN: Duration = Duration::from_millis(5);
+const DRAIN_WAIT_MAX: Duration = Duration::from_millis(500);
+
+/// Send settings of a single channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelSendConfig {
+    /// Size of a single datachannel message.
+    pub chunk_size: usize,
+    /// Max send rate in bytes per second. If `None`, send rate is only
+    /// limited by how fast the buffer drains.
+    pub max_rate: Option<u64>,
+    /// Chunk isn't sent while it would make the `bufferedAmount` of
+    /// the datachannel exceed this.
+    pub buffered_amount_high: usize,
+}
+
+impl Default for ChannelSendConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_rate: None,
+            buffered_amount_high: 16 * DEFAULT_CHUNK_SIZE,
+        }
+    }
+}
+
+impl ChannelSendConfig {
+    /// Clamps the values, so that chunks are accepted by the peer and
+    /// at least one chunk fits into the buffer.
+    pub fn normalized(mut self) -> Self {
+        self.chunk_size = self.chunk_size.clamp(MIN_CHUNK_SIZE, MAX_CHUNK_SIZE);
+        self.buffered_amount_high = self.buffered_amount_high.max(self.chunk_size);
+        self.max_rate = self.max_rate.filter(|rate| *rate > 0);
+        self
+    }
+}
+
+/// Send settings of the webrtc datachannels, with per channel overrides.
+#[derive(Debug, Clone, Default)]
+pub struct WebrtcSendConfig {
+    pub default: ChannelSendConfig,
+    pub channels: BTreeMap<ChannelId, ChannelSendConfig>,
+}
+
+impl WebrtcSendConfig {
+    pub fn with_channel(mut self, chan_id: ChannelId, config: ChannelSendConfig) -> Self {
+        self.channels.insert(chan_id, config);
+        self
+    }
+
+    pub fn get(&self, chan_id: ChannelId) -> ChannelSendConfig {
+        self.channels
+            .get(&chan_id)
+            .copied()
+            .unwrap_or(self.default)
+            .normalized()
+    }
+}
+
+/// Decides how long to wait before sending the next chunk, see
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct ChunkPacer {
+    config: ChannelSendConfig,
+    /// Moving average of the observed drain rate, in bytes per second.
+    drain_rate: Option<f64>,
+}
+
+impl ChunkPacer {
+    pub fn new(config: ChannelSendConfig) -> Self {
+        Self {
+            config: config.normalized(),
+            drain_rate: None,
+        }
+    }
+
+    pub fn config(&self) -> &ChannelSendConfig {
+        &self.config
+    }
+
+    /// How long to wait for the buffer to drain before sending the chunk
+    /// of size `len`, given the current `bufferedAmount`. `None` if the
+    /// chunk can be sent right away.
+    pub fn drain_wait(&self, buffered: usize, len: usize) -> Option<Duration> {
+        let excess = (buffered + len).checked_sub(self.config.buffered_amount_high)?;
+        if excess == 0 || buffered == 0 {
+            return None;
+        }
+        let wait = match self.drain_rate {
+            Some(rate) if rate > 0.0 => Duration::from_secs_f64(excess as f64 / rate),
+            _ => DRAIN_WAIT_MIN,
+        };
+        Some(wait.clamp(DRAIN_WAIT_MIN, DRAIN_WAIT_MAX))
+    }
+
+    /// Records that `drained` bytes left the buffer during `elapsed`.
+    pub fn drain_observed(&mut self, drained: usize, elapsed: Duration) {
+        let elapsed = elapsed.as_secs_f64();
+        if elapsed <= 0.0 {
+            return;
+        }
+        let rate = drained as f64 / elapsed;
+        self.drain_rate = Some(match self.drain_rate {
+            Some(prev) => (prev * 3.0 + rate) / 4.0,
+            None => rate,
+        });
+    }
+
+    /// Delay after sending the chunk of size `len`, to keep the send rate
+    /// under [`ChannelSendConfig::max_rate`].
+    pub fn rate_delay(&self, len: usize) -> Option<Duration> {
+        let rate = self.config.max_rate?;
+        Some(Duration::from_secs_f64(len as f64 / rate as f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drain_wait_adapts_to_drain_rate() {
+        let mut pacer = ChunkPacer::new(ChannelSendConfig {
+            chunk_size: 16 * 1024,
+            max_rate: None,
+            buffered_amount_high: 64 * 1024,
+        });
+        assert_eq!(pacer.drain_wait(0, 16 * 1024), None);
+        assert_eq!(pacer.drain_wait(48 * 1024, 16 * 1024), None);
+        assert_eq!(pacer.drain_wait(64 * 1024, 16 * 1024), Some(DRAIN_WAIT_MIN));
+
+        // 160KB/s, excess of 16KB drains in 100ms.
+        pacer.drain_observed(16 * 1024, Duration::from_millis(100));
+        let wait = pacer.drain_wait(64 * 1024, 16 * 1024).unwrap();
+        assert_eq!(wait.as_millis(), 100);
+
+        // stalled buffer doesn't make us wait forever.
+        for _ in 0..20 {
+            pacer.drain_observed(0, Duration::from_millis(100));
+        }
+        assert_eq!(pacer.drain_wait(64 * 1024, 16 * 1024), Some(DRAIN_WAIT_MAX));
+    }
+
+    #[test]
+    fn config_normalized() {
+        let config = WebrtcSendConfig::default().with_channel(
+            ChannelId::Rpc,
+            ChannelSendConfig {
+                chunk_size: 1024 * 1024,
+                max_rate: Some(0),
+                buffered_amount_high: 0,
+            },
+        );
+        let rpc = config.get(ChannelId::Rpc);
+        assert_eq!(rpc.chunk_size, MAX_CHUNK_SIZE);
+        assert_eq!(rpc.buffered_amount_high, MAX_CHUNK_SIZE);
+        assert_eq!(rpc.max_rate, None);
+        assert_eq!(config.get(ChannelId::SnarkPropagation), config.default);
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
     disconnection_effectful::P2pDisconnectionService,
     identity::{PublicKey, SecretKey},
     webrtc::{ConnectionAuth, ConnectionAuthEncrypted},
-    P2pChannelEvent, P2pEvent, PeerId,
+    P2pChannelEvent, P2pCorrelationId, P2pEvent, PeerId,
 };
 
 #[cfg(feature = "p2p-libp2p")]
@@ -14,10 +14,13 @@ use super::mio::MioService;
 #[cfg(feature = "p2p-libp2p")]
 use crate::{P2pMioService, P2pNetworkService, P2pNetworkServiceError};
 
-use super::{webrtc::P2pServiceWebrtc, TaskSpawner};
+use super::{webrtc::P2pServiceWebrtc, webrtc_pacing::WebrtcSendConfig, TaskSpawner};
 
 pub struct P2pServiceCtx {
     pub sec_key: SecretKey,
+    /// Key of the identity presented on the WebRTC connections, if it
+    /// differs from `sec_key`.
+    pub webrtc_sec_key: Option<SecretKey>,
     pub webrtc: super::webrtc::P2pServiceCtx,
     #[cfg(feature = "p2p-libp2p")]
     pub mio: MioService,
@@ -29,12 +32,24 @@ pub trait P2pServiceWebrtcWithLibp2p: P2pServiceWebrtc {
 
     fn connections(&self) -> BTreeSet<PeerId>;
 
-    fn init<S: TaskSpawner>(sec_key: SecretKey, spawner: S, rng_seed: [u8; 32]) -> P2pServiceCtx {
+    fn init<S: TaskSpawner>(
+        sec_key: SecretKey,
+        webrtc_sec_key: Option<SecretKey>,
+        spawner: S,
+        rng_seed: [u8; 32],
+        send_config: WebrtcSendConfig,
+    ) -> P2pServiceCtx {
         P2pServiceCtx {
             sec_key: sec_key.clone(),
+            webrtc_sec_key: webrtc_sec_key.clone(),
             #[cfg(feature = "p2p-libp2p")]
             mio: MioService::pending(sec_key.clone().try_into().expect("valid keypair")),
-            webrtc: <Self as P2pServiceWebrtc>::init(sec_key, spawner, rng_seed),
+            webrtc: <Self as P2pServiceWebrtc>::init(
+                webrtc_sec_key.unwrap_or(sec_key),
+                spawner,
+                rng_seed,
+                send_config,
+            ),
         }
     }
 
@@ -80,18 +95,18 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pConnectionService for T {
 
     fn random_pick(
         &mut self,
-        list: &[P2pConnectionOutgoingInitOpts],
+        list: &[(P2pConnectionOutgoingInitOpts, f64)],
     ) -> Option<P2pConnectionOutgoingInitOpts> {
         P2pServiceWebrtc::random_pick(self, list)
     }
 
-    fn outgoing_init(&mut self, opts: P2pConnectionOutgoingInitOpts) {
+    fn outgoing_init(&mut self, opts: P2pConnectionOutgoingInitOpts) -> Option<P2pCorrelationId> {
         match opts {
             P2pConnectionOutgoingInitOpts::WebRTC { peer_id, .. } => {
-                P2pServiceWebrtc::outgoing_init(self, peer_id);
+                Some(P2pServiceWebrtc::outgoing_init(self, peer_id))
             }
             #[cfg(not(feature = "p2p-libp2p"))]
-            P2pConnectionOutgoingInitOpts::LibP2P(_) => {}
+            P2pConnectionOutgoingInitOpts::LibP2P(_) => None,
             #[cfg(feature = "p2p-libp2p")]
             P2pConnectionOutgoingInitOpts::LibP2P(opts) => {
                 use crate::webrtc::Host;
@@ -100,27 +115,48 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pConnectionService for T {
                     Host::Ipv6(ip6) => ip6.into(),
                     host => {
                         openmina_core::error!(openmina_core::log::system_time(); "unsupported host for internal libp2p: {host}");
-                        return;
+                        return None;
                     }
                 };
                 self.mio()
                     .send_cmd(crate::MioCmd::Connect(std::net::SocketAddr::new(
                         addr, opts.port,
                     )));
+                None
             }
         }
     }
 
-    fn incoming_init(&mut self, peer_id: PeerId, offer: crate::webrtc::Offer) {
+    fn incoming_init(&mut self, peer_id: PeerId, offer: crate::webrtc::Offer) -> P2pCorrelationId {
         P2pServiceWebrtc::incoming_init(self, peer_id, offer)
     }
 
-    fn set_answer(&mut self, peer_id: PeerId, answer: crate::webrtc::Answer) {
+    fn set_answer(
+        &mut self,
+        peer_id: PeerId,
+        answer: crate::webrtc::Answer,
+    ) -> Option<P2pCorrelationId> {
         P2pServiceWebrtc::set_answer(self, peer_id, answer)
     }
 
-    fn http_signaling_request(&mut self, url: String, offer: crate::webrtc::Offer) {
-        P2pServiceWebrtc::http_signaling_request(self, url, offer)
+    fn signaling_request(
+        &mut self,
+        method: crate::webrtc::SignalingMethod,
+        offer: crate::webrtc::Offer,
+    ) -> Option<P2pCorrelationId> {
+        P2pServiceWebrtc::signaling_request(self, method, offer)
+    }
+
+    fn ice_restart_init(&mut self, peer_id: PeerId) -> Option<P2pCorrelationId> {
+        P2pServiceWebrtc::ice_restart_init(self, peer_id)
+    }
+
+    fn ice_restart_offer_set(
+        &mut self,
+        peer_id: PeerId,
+        offer: crate::webrtc::Offer,
+    ) -> Option<P2pCorrelationId> {
+        P2pServiceWebrtc::ice_restart_offer_set(self, peer_id, offer)
     }
 
     fn auth_encrypt_and_send(
@@ -148,22 +184,29 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pDisconnectionService for T {
 }
 
 impl<T: P2pServiceWebrtcWithLibp2p> P2pChannelsService for T {
-    fn channel_open(&mut self, peer_id: PeerId, id: ChannelId) {
+    fn channel_open(&mut self, peer_id: PeerId, id: ChannelId) -> Option<P2pCorrelationId> {
         if self.peers().contains_key(&peer_id) {
-            P2pServiceWebrtc::channel_open(self, peer_id, id)
-        } else if !matches!(id, ChannelId::Rpc) {
+            return P2pServiceWebrtc::channel_open(self, peer_id, id);
+        }
+        if !matches!(
+            id,
+            ChannelId::Rpc | ChannelId::SignalingDiscovery | ChannelId::SignalingExchange
+        ) {
             // skip sending event for rpc libp2p channel as the ready
             // action is dispatched in the `network` module after the
-            // relevant handshake is done.
+            // relevant handshake is done. Same for the signaling
+            // channels, which are relayed over the rpc stream.
             // TODO: do the same for other channels/streams also.
             let result = match id.supported_by_libp2p() {
                 false => Err("channel not supported".to_owned()),
                 true => Ok(()),
             };
+            let cid = P2pCorrelationId::next();
             self.event_sender()
-                .send(P2pEvent::Channel(P2pChannelEvent::Opened(peer_id, id, result)).into())
+                .send(P2pEvent::Channel(P2pChannelEvent::Opened(peer_id, id, cid, result)).into())
                 .unwrap_or_default();
         }
+        None
     }
 
     fn channel_send(&mut self, peer_id: PeerId, msg_id: MsgId, msg: ChannelMsg) {
@@ -172,6 +215,14 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pChannelsService for T {
         }
     }
 
+    fn channels_compression_enable(&mut self, peer_id: PeerId) {
+        P2pServiceWebrtc::compression_enable(self, peer_id)
+    }
+
+    fn channels_lossy_gossip_enable(&mut self, peer_id: PeerId) {
+        P2pServiceWebrtc::lossy_gossip_enable(self, peer_id)
+    }
+
     fn encrypt<M: crate::identity::EncryptableType>(
         &mut self,
         other_pk: &crate::identity::PublicKey,
@@ -211,10 +262,16 @@ where
 }
 
 impl P2pServiceCtx {
+    /// Key of the identity presented on the WebRTC connections.
+    pub fn webrtc_sec_key(&self) -> &SecretKey {
+        self.webrtc_sec_key.as_ref().unwrap_or(&self.sec_key)
+    }
+
     pub fn mocked(sec_key: SecretKey) -> Self {
         use openmina_core::channels::mpsc;
         Self {
             sec_key: sec_key.clone(),
+            webrtc_sec_key: None,
             #[cfg(feature = "p2p-libp2p")]
             mio: super::mio::MioService::mocked(sec_key.try_into().expect("valid keypair")),
             webrtc: super::webrtc::P2pServiceCtx {
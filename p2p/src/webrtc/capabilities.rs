@@ -0,0 +1,161 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{channels::ChannelId, feature_flags::P2pFeatureFlags};
+
+/// Version of the webrtc protocol between openmina peers.
+///
+/// New features must be announced as capabilities instead, so that they
+/// can be rolled out without breaking older peers. Version only needs to
+/// be bumped on changes, which can't be negotiated.
+pub const P2P_PROTOCOL_VERSION: u16 = 1;
+
+/// Features supported by the peer, announced in the signaling offer and
+/// answer.
+///
+/// Connection auth only covers the sdp of the offer and answer, not the
+/// capabilities. Whoever relays the signaling could alter them, so they
+/// must only ever enable or disable optional features, never be trusted
+/// for anything security relevant.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct P2pCapabilities {
+    /// Protocol version, `0` for peers which predate the capability exchange.
+    pub version: u16,
+    /// Channels the peer is willing to open. Channels unknown to us are
+    /// skipped, so that peers can add new ones.
+    #[serde(deserialize_with = "deserialize_known_channels")]
+    pub channels: BTreeSet<ChannelId>,
+    /// Number of parallel channels the peer supports for chunked transfer
+    /// of large messages (e.g. rpc responses). `0` if not supported.
+    pub bulk_channels: u8,
+    /// Whether transaction digests are supported on the transaction
+    /// propagation channel.
+    pub tx_digests: bool,
+    /// Whether compact blocks are supported on the best tip propagation
+    /// channel.
+    pub compact_blocks: bool,
+    /// Whether the bigger channel messages may be sent compressed.
+    #[serde(default)]
+    pub compression: bool,
+    /// Whether the peer serves [`crate::channels::rpc::P2pRpcRequest::TransactionPoolSummary`].
+    #[serde(default)]
+    pub pool_sync: bool,
+    /// Whether the peer serves [`crate::channels::rpc::P2pRpcRequest::GenesisProof`].
+    #[serde(default)]
+    pub genesis_proof: bool,
+    /// Whether snark batches are supported on the snark propagation
+    /// channel.
+    #[serde(default)]
+    pub snark_batches: bool,
+    /// Whether the transaction and snark propagation channels may be
+    /// unordered and partially reliable, see [`ChannelId::is_ordered`].
+    #[serde(default)]
+    pub lossy_gossip: bool,
+}
+
+impl P2pCapabilities {
+    /// Capabilities of this node.
+    pub fn local(enabled_channels: &BTreeSet<ChannelId>, features: &P2pFeatureFlags) -> Self {
+        Self {
+            version: P2P_PROTOCOL_VERSION,
+            channels: enabled_channels.clone(),
+            bulk_channels: features.bulk_channels(),
+            tx_digests: true,
+            compact_blocks: features.compact_blocks,
+            compression: features.compression,
+            pool_sync: true,
+            genesis_proof: true,
+            snark_batches: true,
+            lossy_gossip: true,
+        }
+    }
+
+    /// Capabilities of the peer which doesn't support the capability
+    /// exchange, derived from the separate flags of the offer or answer.
+    pub fn legacy(bulk_channels: u8, tx_digests: bool, compact_blocks: bool) -> Self {
+        Self {
+            version: 0,
+            // Channels added after the capability exchange are unknown
+            // to such peers.
+            channels: ChannelId::iter_all()
+                .filter(|id| *id != ChannelId::CheckpointPropagation)
+                .collect(),
+            bulk_channels,
+            tx_digests,
+            compact_blocks,
+            compression: false,
+            pool_sync: false,
+            genesis_proof: false,
+            snark_batches: false,
+            lossy_gossip: false,
+        }
+    }
+
+    pub fn supports_channel(&self, id: ChannelId) -> bool {
+        self.channels.contains(&id)
+    }
+}
+
+/// Deserializes the channel ids, skipping the unknown ones. Only the human
+/// readable formats (json of the signaling) can contain unknown ids, the
+/// binary ones are only produced by the same build.
+fn deserialize_known_channels<'de, D>(deserializer: D) -> Result<BTreeSet<ChannelId>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return BTreeSet::deserialize(deserializer);
+    }
+    let ids = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(ids
+        .into_iter()
+        .filter_map(|id| serde_json::from_value(id).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_unknown_channels() {
+        let json = serde_json::json!({
+            "version": 2,
+            "channels": ["Rpc", "FutureChannel", 42, "BestTipPropagation"],
+            "bulk_channels": 2,
+            "tx_digests": true,
+            "compact_blocks": false,
+            "some_future_flag": true,
+        });
+        let capabilities: P2pCapabilities = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            capabilities.channels,
+            [ChannelId::BestTipPropagation, ChannelId::Rpc].into()
+        );
+        assert!(capabilities.supports_channel(ChannelId::Rpc));
+        assert!(!capabilities.supports_channel(ChannelId::StreamingRpc));
+        // Flags added later default to disabled.
+        assert!(!capabilities.pool_sync);
+        assert!(!capabilities.lossy_gossip);
+    }
+
+    #[test]
+    fn test_capabilities_roundtrip() {
+        let local = P2pCapabilities::local(&ChannelId::iter_all().collect(), &Default::default());
+        let json = serde_json::to_vec(&local).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<P2pCapabilities>(&json).unwrap(),
+            local
+        );
+    }
+
+    #[test]
+    fn test_capabilities_legacy() {
+        let legacy = P2pCapabilities::legacy(0, true, false);
+        assert_eq!(legacy.version, 0);
+        assert!(legacy.tx_digests);
+        assert!(legacy.supports_channel(ChannelId::Rpc));
+        assert!(!legacy.supports_channel(ChannelId::CheckpointPropagation));
+    }
+}
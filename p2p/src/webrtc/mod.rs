@@ -1,9 +1,13 @@
 mod host;
 pub use host::Host;
 
+mod capabilities;
+pub use capabilities::{P2pCapabilities, P2P_PROTOCOL_VERSION};
+
 mod signal;
 pub use signal::{
     Answer, EncryptedAnswer, EncryptedOffer, Offer, P2pConnectionResponse, RejectionReason, Signal,
+    BULK_CHANNELS,
 };
 
 mod signaling_method;
@@ -6,7 +6,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::identity::{EncryptableType, PeerId, PublicKey};
 
-use super::{ConnectionAuth, Host};
+use super::{ConnectionAuth, Host, P2pCapabilities};
+
+/// Number of parallel channels this node opens for bulk transfers
+/// (large messages split across several channels).
+pub const BULK_CHANNELS: u8 = 4;
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, MallocSizeOf)]
 pub struct Offer {
@@ -24,6 +28,22 @@ pub struct Offer {
     pub host: Host,
     /// Port of the signaling server of the offerer.
     pub listen_port: Option<u16>,
+    /// Number of parallel bulk transfer channels supported by the offerer.
+    #[serde(default)]
+    pub bulk_channels: u8,
+    /// Whether the offerer supports transaction digests on the transaction
+    /// propagation channel.
+    #[serde(default)]
+    pub tx_digests: bool,
+    /// Whether the offerer supports compact blocks on the best tip
+    /// propagation channel.
+    #[serde(default)]
+    pub compact_blocks: bool,
+    /// Features supported by the offerer. Flags above are still sent for
+    /// the peers which predate the capability exchange.
+    #[serde(default)]
+    #[ignore_malloc_size_of = "neglectible"]
+    pub capabilities: Option<P2pCapabilities>,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, MallocSizeOf)]
@@ -34,6 +54,22 @@ pub struct Answer {
     pub identity_pub_key: PublicKey,
     /// Peer id that the offerer wants to connect to.
     pub target_peer_id: PeerId,
+    /// Number of parallel bulk transfer channels supported by the answerer.
+    #[serde(default)]
+    pub bulk_channels: u8,
+    /// Whether the answerer supports transaction digests on the transaction
+    /// propagation channel.
+    #[serde(default)]
+    pub tx_digests: bool,
+    /// Whether the answerer supports compact blocks on the best tip
+    /// propagation channel.
+    #[serde(default)]
+    pub compact_blocks: bool,
+    /// Features supported by the answerer. Flags above are still sent for
+    /// the peers which predate the capability exchange.
+    #[serde(default)]
+    #[ignore_malloc_size_of = "neglectible"]
+    pub capabilities: Option<P2pCapabilities>,
 }
 
 #[derive(Serialize, Deserialize, From, Eq, PartialEq, Debug, Clone)]
@@ -83,12 +119,24 @@ impl Offer {
     pub fn conn_auth(&self, answer: &Answer) -> ConnectionAuth {
         ConnectionAuth::new(self, answer)
     }
+
+    pub fn remote_capabilities(&self) -> P2pCapabilities {
+        self.capabilities.clone().unwrap_or_else(|| {
+            P2pCapabilities::legacy(self.bulk_channels, self.tx_digests, self.compact_blocks)
+        })
+    }
 }
 
 impl Answer {
     pub fn sdp_hash(&self) -> [u8; 32] {
         sdp_hash(&self.sdp)
     }
+
+    pub fn remote_capabilities(&self) -> P2pCapabilities {
+        self.capabilities.clone().unwrap_or_else(|| {
+            P2pCapabilities::legacy(self.bulk_channels, self.tx_digests, self.compact_blocks)
+        })
+    }
 }
 
 impl RejectionReason {
@@ -18,12 +18,22 @@ pub enum SignalingMethod {
     P2p {
         relay_peer_id: PeerId,
     },
+    /// Offer is sent over the websocket, for peers which can't make
+    /// plain http requests to the signaling server.
+    Wss(HttpSignalingInfo),
+    /// Same as [`SignalingMethod::Wss`], but without tls, for the
+    /// signaling servers in the local network.
+    Ws(HttpSignalingInfo),
 }
 
 impl SignalingMethod {
     pub fn can_connect_directly(&self) -> bool {
         match self {
-            Self::Http(_) | Self::Https(_) | Self::HttpsProxy(_, _) => true,
+            Self::Http(_)
+            | Self::Https(_)
+            | Self::HttpsProxy(_, _)
+            | Self::Wss(_)
+            | Self::Ws(_) => true,
             Self::P2p { .. } => false,
         }
     }
@@ -48,6 +58,20 @@ impl SignalingMethod {
         ))
     }
 
+    /// If method is ws or wss, it will return url of the websocket over
+    /// which an offer can be sent.
+    pub fn ws_url(&self) -> Option<String> {
+        let (ws, info) = match self {
+            Self::Ws(info) => ("ws", info),
+            Self::Wss(info) => ("wss", info),
+            _ => return None,
+        };
+        Some(format!(
+            "{ws}://{}:{}/mina/webrtc/signal",
+            info.host, info.port
+        ))
+    }
+
     pub fn p2p_relay_peer_id(&self) -> Option<PeerId> {
         match self {
             Self::P2p { relay_peer_id } => Some(*relay_peer_id),
@@ -74,6 +98,14 @@ impl fmt::Display for SignalingMethod {
             Self::P2p { relay_peer_id } => {
                 write!(f, "/p2p/{relay_peer_id}")
             }
+            Self::Wss(signaling) => {
+                write!(f, "/wss")?;
+                signaling.fmt(f)
+            }
+            Self::Ws(signaling) => {
+                write!(f, "/ws")?;
+                signaling.fmt(f)
+            }
         }
     }
 }
@@ -110,6 +142,8 @@ impl FromStr for SignalingMethod {
         match &s[1..method_end_index] {
             "http" => Ok(Self::Http(rest.parse()?)),
             "https" => Ok(Self::Https(rest.parse()?)),
+            "wss" => Ok(Self::Wss(rest.parse()?)),
+            "ws" => Ok(Self::Ws(rest.parse()?)),
             "https_proxy" => {
                 let mut iter = rest.splitn(3, '/').filter(|v| !v.trim().is_empty());
                 let (cluster_id, rest) = (
@@ -148,3 +182,35 @@ impl<'de> serde::Deserialize<'de> for SignalingMethod {
         s.parse().map_err(serde::de::Error::custom)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wss_roundtrip() {
+        let s = "/wss/signal.example.com/443";
+        let method: SignalingMethod = s.parse().unwrap();
+        assert!(matches!(method, SignalingMethod::Wss(_)));
+        assert_eq!(method.to_string(), s);
+        assert_eq!(
+            method.ws_url().as_deref(),
+            Some("wss://signal.example.com:443/mina/webrtc/signal")
+        );
+        assert_eq!(method.http_url(), None);
+    }
+
+    #[test]
+    fn ws_roundtrip() {
+        let s = "/ws/127.0.0.1/3000";
+        let method: SignalingMethod = s.parse().unwrap();
+        assert!(matches!(method, SignalingMethod::Ws(_)));
+        assert!(method.can_connect_directly());
+        assert_eq!(method.to_string(), s);
+        assert_eq!(
+            method.ws_url().as_deref(),
+            Some("ws://127.0.0.1:3000/mina/webrtc/signal")
+        );
+        assert_eq!(method.http_url(), None);
+    }
+}
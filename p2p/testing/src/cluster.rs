@@ -353,13 +353,18 @@ impl Cluster {
             libp2p_port: Some(libp2p_port),
             listen_port: Some(listen_port),
             identity_pub_key: secret_key.public_key(),
+            webrtc_identity_pub_key: None,
             initial_peers,
+            preferred_peers: Vec::new(),
             external_addrs: vec![],
+            listen_family: Default::default(),
+            dial_family: None,
             enabled_channels: p2p::channels::ChannelId::for_libp2p().collect(),
             peer_discovery: config.discovery,
             timeouts: config.timeouts,
             limits: config.limits,
             meshsub: P2pMeshsubConfig::default(),
+            feature_flags: Default::default(),
         };
 
         Ok((config, secret_key))
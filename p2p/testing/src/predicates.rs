@@ -153,8 +153,8 @@ pub fn default_errors(event: &ClusterEvent) -> bool {
                 p2p::P2pEvent::Connection(_) => false,
                 p2p::P2pEvent::Channel(e) => matches!(
                     e,
-                    p2p::P2pChannelEvent::Opened(_, _, Err(_))
-                        | p2p::P2pChannelEvent::Sent(_, _, _, Err(_))
+                    p2p::P2pChannelEvent::Opened(_, _, _, Err(_))
+                        | p2p::P2pChannelEvent::Sent(_, _, _, _, Err(_))
                         | p2p::P2pChannelEvent::Received(_, Err(_))
                 ),
                 p2p::P2pEvent::MioEvent(e) => matches!(
@@ -13,6 +13,7 @@ use p2p::{
     bootstrap::P2pNetworkKadBootstrapState,
     channels::{
         best_tip::P2pChannelsBestTipAction,
+        checkpoint::P2pChannelsCheckpointAction,
         rpc::P2pChannelsRpcAction,
         signaling::{
             discovery::P2pChannelsSignalingDiscoveryAction,
@@ -25,6 +26,7 @@ use p2p::{
         P2pChannelsEffectfulAction,
     },
     connection::{
+        ice_restart_effectful::P2pConnectionIceRestartEffectfulAction,
         incoming_effectful::P2pConnectionIncomingEffectfulAction,
         outgoing::P2pConnectionOutgoingAction,
         outgoing_effectful::P2pConnectionOutgoingEffectfulAction,
@@ -290,6 +292,7 @@ impl_from_p2p!(p2p::P2pNetworkSelectAction);
 impl_from_p2p!(p2p::P2pNetworkPnetAction);
 impl_from_p2p!(p2p::P2pNetworkNoiseAction);
 impl_from_p2p!(p2p::connection::incoming::P2pConnectionIncomingAction);
+impl_from_p2p!(p2p::connection::ice_restart::P2pConnectionIceRestartAction);
 impl_from_p2p!(p2p::P2pNetworkPubsubAction);
 impl_from_p2p!(P2pChannelsSignalingDiscoveryAction);
 impl_from_p2p!(P2pChannelsSignalingExchangeAction);
@@ -300,10 +303,12 @@ impl_from_p2p!(P2pChannelsRpcAction);
 impl_from_p2p!(P2pDisconnectionAction);
 impl_from_p2p!(P2pChannelsBestTipAction);
 impl_from_p2p!(P2pChannelsSnarkJobCommitmentAction);
+impl_from_p2p!(P2pChannelsCheckpointAction);
 impl_from_p2p!(P2pChannelsStreamingRpcAction);
 
 impl_from_p2p!(effectful P2pNetworkKadEffectfulAction);
 impl_from_p2p!(effectful P2pConnectionIncomingEffectfulAction);
+impl_from_p2p!(effectful P2pConnectionIceRestartEffectfulAction);
 impl_from_p2p!(effectful p2p::P2pNetworkSchedulerEffectfulAction);
 impl_from_p2p!(effectful p2p::P2pNetworkPnetEffectfulAction);
 impl_from_p2p!(effectful p2p::P2pNetworkPubsubEffectfulAction);
@@ -2,13 +2,14 @@ use std::{collections::VecDeque, time::Instant};
 
 use openmina_core::channels::mpsc;
 use p2p::{
+    connection::{outgoing::P2pConnectionOutgoingInitOpts, weighted_random_pick},
     identity::SecretKey,
     service_impl::{
         mio::MioService, webrtc::P2pServiceWebrtc, webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p,
     },
     P2pCryptoService, P2pEvent,
 };
-use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use redux::{Service, TimeService};
 
 use crate::event::{RustNodeEvent, RustNodeEventStore};
@@ -85,9 +86,9 @@ impl P2pServiceWebrtc for ClusterService {
 
     fn random_pick(
         &mut self,
-        list: &[p2p::connection::outgoing::P2pConnectionOutgoingInitOpts],
-    ) -> Option<p2p::connection::outgoing::P2pConnectionOutgoingInitOpts> {
-        list.choose(&mut self.rng).cloned()
+        list: &[(P2pConnectionOutgoingInitOpts, f64)],
+    ) -> Option<P2pConnectionOutgoingInitOpts> {
+        weighted_random_pick(&mut self.rng, list)
     }
 
     fn event_sender(&self) -> &mpsc::UnboundedSender<Self::Event> {
@@ -6,21 +6,22 @@ use std::{
 
 use p2p::{
     channels::rpc::{
-        P2pChannelsRpcAction, P2pChannelsRpcState, P2pRpcId, P2pRpcLocalState, P2pRpcRemoteState,
-        P2pRpcRequest, P2pRpcResponse,
+        P2pChannelsRpcAction, P2pChannelsRpcState, P2pRpcId, P2pRpcRemoteState, P2pRpcRequest,
+        P2pRpcResponse,
     },
-    PeerId,
+    P2pLimits, P2pTimeouts, PeerId,
 };
 use p2p_testing::{
     cluster::{Cluster, ClusterBuilder, ClusterEvent},
     event::RustNodeEvent,
     futures::TryStreamExt,
-    rust_node::RustNodeId,
+    rust_node::{RustNodeConfig, RustNodeId},
     stream::ClusterStreamExt,
     test_node::TestNode,
     utils::{
-        peer_ids, rust_nodes_from_default_config, try_wait_for_all_nodes_with_value,
-        try_wait_for_nodes_to_connect, try_wait_for_nodes_to_listen,
+        peer_ids, run_cluster, rust_nodes_from_config, rust_nodes_from_default_config,
+        try_wait_for_all_nodes_with_value, try_wait_for_nodes_to_connect,
+        try_wait_for_nodes_to_listen,
     },
 };
 
@@ -200,6 +201,78 @@ async fn rust_to_rust_rpc(
     receive_response(&mut cluster, node1, node2, request_id).await;
 }
 
+#[tokio::test]
+async fn rust_to_rust_timeout() {
+    let mut cluster = ClusterBuilder::new()
+        .ports(11550..11560)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await
+        .expect("should build cluster");
+
+    let timeouts = P2pTimeouts {
+        initial_peers: Some(Duration::from_millis(500)),
+        ..Default::default()
+    };
+    let limits = P2pLimits::default()
+        .with_rpc_max_in_flight_per_peer(Some(3))
+        .with_rpc_timeout_retries(Some(2));
+    let config = RustNodeConfig::default()
+        .with_timeouts(timeouts)
+        .with_limits(limits);
+    let [node1, node2] = rust_nodes_from_config(&mut cluster, config).expect("no error");
+    let [peer_id1, peer_id2] = peer_ids(&cluster, [node1, node2]);
+
+    let listener_is_ready =
+        try_wait_for_nodes_to_listen(&mut cluster, [node1], Duration::from_secs(2))
+            .await
+            .expect("no error");
+    assert!(listener_is_ready, "node1 should be ready");
+
+    cluster.connect(node2, node1).expect("no error");
+    let peers_are_connected = try_wait_for_nodes_to_connect(
+        &mut cluster,
+        [(node2, peer_id1), (node1, peer_id2)],
+        Duration::from_secs(2),
+    )
+    .await
+    .expect("no error");
+    assert!(peers_are_connected, "nodes should be connected");
+
+    assert!(
+        rpc_ready(&mut cluster, [(node2, node1)], Duration::from_secs(2))
+            .await
+            .expect("no errors"),
+        "rpc should be ready"
+    );
+
+    let (query, _response) = rpc_from_json!("initial_peers");
+    let request_id = send_request(&mut cluster, node2, node1, query);
+    receive_request(&mut cluster, node2, node1, request_id).await;
+
+    let rpc = |cluster: &Cluster| {
+        cluster
+            .rust_node(node2)
+            .state()
+            .get_ready_peer(&peer_id1)
+            .expect("peer should be ready")
+            .channels
+            .clone()
+    };
+    // libp2p peer takes a single query at a time, whatever the limit.
+    let channels = rpc(&cluster);
+    assert!(!channels.rpc.can_send_request());
+    assert!(channels.rpc.pending_local_rpc(request_id).is_some());
+
+    // node1 never responds, the request times out without being retried,
+    // as we can't tell if libp2p peer is still working on it.
+    run_cluster(&mut cluster, Duration::from_secs(2)).await;
+    let channels = rpc(&cluster);
+    assert!(channels.rpc.pending_local_rpc(request_id).is_none());
+    assert_eq!(channels.next_local_rpc_id(), request_id + 1);
+    assert!(channels.rpc.can_send_request());
+}
+
 #[tokio::test]
 async fn rust_to_many_rust_query() {
     const PEERS: usize = 20;
@@ -384,14 +457,7 @@ fn send_request(
         .expect("peer should be ready")
         .channels;
     assert!(
-        matches!(
-            sender_receiver_channels.rpc,
-            P2pChannelsRpcState::Ready {
-                local: P2pRpcLocalState::WaitingForRequest { .. }
-                    | P2pRpcLocalState::Responded { .. },
-                ..
-            }
-        ),
+        sender_receiver_channels.rpc.can_send_request(),
         "{sender:?}'s peer {receiver:?} not ready: {:#?}",
         sender_receiver_channels.rpc
     );
@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+
+use p2p_testing::{
+    cluster::ClusterBuilder,
+    futures::TryStreamExt,
+    predicates::{listener_is_ready, peer_is_connected},
+    rust_node::RustNodeConfig,
+    stream::ClusterStreamExt,
+    utils::{peer_ids, run_cluster},
+};
+
+/// Signaling channels of the libp2p peers are relayed as rpc queries.
+/// Once the exchange channel is ready, each node requests the next offer
+/// from the other one, so the peer looking for incoming connections
+/// means the request went over libp2p.
+#[tokio::test]
+async fn signaling_relayed_over_libp2p() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::new()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node1 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let node2 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let [peer_id1, peer_id2] = peer_ids(&cluster, [node1, node2]);
+
+    let listener_is_ready = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(2))
+        .try_any(listener_is_ready(node1))
+        .await?;
+    assert!(listener_is_ready, "node1 should be ready");
+
+    cluster.connect(node2, node1)?;
+    let connected = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(2))
+        .try_any(peer_is_connected(node2, peer_id1))
+        .await?;
+    assert!(connected, "node2 should connect to node1");
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let relayed = [(node1, peer_id2), (node2, peer_id1)]
+            .into_iter()
+            .all(|(node, peer_id)| {
+                cluster
+                    .rust_node(node)
+                    .state()
+                    .get_ready_peer(&peer_id)
+                    .is_some_and(|peer| peer.channels.signaling.is_looking_for_incoming_peer())
+            });
+        if relayed {
+            break;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "signaling request should be relayed\nnode1 peer state: {:#?}",
+            cluster.rust_node(node1).state().get_ready_peer(&peer_id2)
+        );
+        run_cluster(&mut cluster, Duration::from_millis(100)).await;
+    }
+
+    Ok(())
+}
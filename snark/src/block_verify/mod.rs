@@ -7,6 +7,9 @@ pub use snark_block_verify_actions::*;
 mod snark_block_verify_reducer;
 pub use snark_block_verify_reducer::reducer;
 
+mod offline;
+pub use offline::*;
+
 pub use crate::block_verify_effectful::{
     SnarkBlockVerifyError, SnarkBlockVerifyId, SnarkBlockVerifyIdType,
 };
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use ledger::staged_ledger::validate_block::{validate_block, BlockBodyValidationError};
+use openmina_core::block::{Block, BlockHash};
+
+use crate::{srs_from_bytes, verifier_index_from_bytes, VerifierIndex, VerifierSRS};
+
+#[derive(thiserror::Error, Debug)]
+pub enum BlockVerifyOfflineError {
+    #[error("block contains invalid field element")]
+    InvalidBigInt,
+    #[error("block hash mismatch, expected: {expected}, calculated: {calculated}")]
+    HashMismatch {
+        expected: BlockHash,
+        calculated: BlockHash,
+    },
+    #[error("block body doesn't match the header: {0:?}")]
+    InvalidBody(BlockBodyValidationError),
+    #[error("block proof verification failed")]
+    VerificationFailed,
+    #[error(transparent)]
+    InvalidVerifierIndex(#[from] ledger::proofs::caching::VerifierIndexFromBytesError),
+    #[error(transparent)]
+    InvalidVerifierSrs(#[from] ledger::proofs::caching::SrsFromBytesError),
+}
+
+/// Verifies the blocks without running a node, the same way the block is
+/// verified by the node when received (see [`super::SnarkBlockVerifyAction`]),
+/// plus the structural checks normally done before the verification.
+#[derive(Clone)]
+pub struct BlockVerifierOffline {
+    verifier_index: Arc<VerifierIndex>,
+    verifier_srs: Arc<VerifierSRS>,
+}
+
+impl BlockVerifierOffline {
+    pub fn new(verifier_index: Arc<VerifierIndex>, verifier_srs: Arc<VerifierSRS>) -> Self {
+        Self {
+            verifier_index,
+            verifier_srs,
+        }
+    }
+
+    /// Verifier index and srs, as produced by [`crate::verifier_index_to_bytes`]
+    /// and [`crate::srs_to_bytes`].
+    pub fn from_bytes(
+        verifier_index: &[u8],
+        verifier_srs: &[u8],
+    ) -> Result<Self, BlockVerifyOfflineError> {
+        Ok(Self::new(
+            Arc::new(verifier_index_from_bytes(verifier_index)?),
+            Arc::new(srs_from_bytes(verifier_srs)?),
+        ))
+    }
+
+    /// Verifier index and srs used by the node for the current network.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn make() -> Self {
+        Self::new(crate::BlockVerifier::make().into(), crate::get_srs())
+    }
+
+    pub fn verifier_index(&self) -> &VerifierIndex {
+        &self.verifier_index
+    }
+
+    pub fn verifier_srs(&self) -> &VerifierSRS {
+        &self.verifier_srs
+    }
+
+    /// Checks that:
+    /// 1. block hash matches the `expected_hash`, if provided.
+    /// 2. hash of the block body matches the `body_reference` in the header.
+    /// 3. block proof is valid.
+    ///
+    /// Returns the hash of the verified block.
+    pub fn verify(
+        &self,
+        block: &Block,
+        expected_hash: Option<&BlockHash>,
+    ) -> Result<BlockHash, BlockVerifyOfflineError> {
+        let hash = block
+            .try_hash()
+            .map_err(|_| BlockVerifyOfflineError::InvalidBigInt)?;
+        if let Some(expected) = expected_hash.filter(|expected| *expected != &hash) {
+            return Err(BlockVerifyOfflineError::HashMismatch {
+                expected: expected.clone(),
+                calculated: hash,
+            });
+        }
+
+        validate_block(block).map_err(BlockVerifyOfflineError::InvalidBody)?;
+
+        if !ledger::proofs::verification::verify_block(
+            &block.header,
+            &self.verifier_index,
+            &self.verifier_srs,
+        ) {
+            return Err(BlockVerifyOfflineError::VerificationFailed);
+        }
+
+        Ok(hash)
+    }
+}
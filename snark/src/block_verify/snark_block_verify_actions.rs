@@ -28,6 +28,11 @@ pub enum SnarkBlockVerifyAction {
     Finish {
         req_id: SnarkBlockVerifyId,
     },
+    /// Block is no longer needed, e.g. it was pruned from the candidates.
+    /// Callbacks aren't called for the cancelled verification.
+    Cancel {
+        req_id: SnarkBlockVerifyId,
+    },
 }
 
 impl redux::EnablingCondition<crate::SnarkState> for SnarkBlockVerifyAction {
@@ -54,6 +59,11 @@ impl redux::EnablingCondition<crate::SnarkState> for SnarkBlockVerifyAction {
                 .jobs
                 .get(*req_id)
                 .is_some_and(|v| v.is_finished()),
+            SnarkBlockVerifyAction::Cancel { req_id } => state
+                .block_verify
+                .jobs
+                .get(*req_id)
+                .is_some_and(|v| v.is_init() || v.is_pending()),
         }
     }
 }
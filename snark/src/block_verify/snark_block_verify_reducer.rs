@@ -99,11 +99,16 @@ pub fn reducer<State, Action>(
             dispatcher.push(SnarkBlockVerifyAction::Finish { req_id: *req_id });
         }
         SnarkBlockVerifyAction::Success { req_id, .. } => {
+            let time_stats = &mut state.time_stats;
             let callback_and_arg = state.jobs.get_mut(*req_id).and_then(|req| {
                 if let SnarkBlockVerifyStatus::Pending {
-                    block, on_success, ..
+                    time,
+                    block,
+                    on_success,
+                    ..
                 } = req
                 {
+                    time_stats.record(*time, meta.time());
                     let callback = on_success.clone();
                     let block_hash = block.hash_ref().clone();
                     *req = SnarkBlockVerifyStatus::Success {
@@ -128,5 +133,13 @@ pub fn reducer<State, Action>(
         SnarkBlockVerifyAction::Finish { req_id, .. } => {
             state.jobs.remove(*req_id);
         }
+        SnarkBlockVerifyAction::Cancel { req_id } => {
+            state.jobs.remove(*req_id);
+            state.cancelled_count = state.cancelled_count.saturating_add(1);
+
+            // Dispatch
+            let dispatcher = state_context.into_dispatcher();
+            dispatcher.push(SnarkBlockVerifyEffectfulAction::Cancel { req_id: *req_id });
+        }
     }
 }
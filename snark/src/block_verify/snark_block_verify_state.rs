@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use openmina_core::{block::BlockHash, requests::PendingRequests};
 
-use crate::{BlockVerifier, VerifierSRS};
+use crate::{BlockVerifier, SnarkVerifyTimeStats, VerifierSRS};
 
 use super::{
     SnarkBlockVerifyError, SnarkBlockVerifyId, SnarkBlockVerifyIdType, VerifiableBlockWithHash,
@@ -15,6 +15,9 @@ pub struct SnarkBlockVerifyState {
     pub verifier_index: BlockVerifier,
     pub verifier_srs: Arc<VerifierSRS>,
     pub jobs: PendingRequests<SnarkBlockVerifyIdType, SnarkBlockVerifyStatus>,
+    /// Number of verifications cancelled before they finished.
+    pub cancelled_count: u64,
+    pub time_stats: SnarkVerifyTimeStats,
 }
 
 impl SnarkBlockVerifyState {
@@ -23,6 +26,8 @@ impl SnarkBlockVerifyState {
             verifier_index,
             verifier_srs,
             jobs: Default::default(),
+            cancelled_count: 0,
+            time_stats: Default::default(),
         }
     }
 
@@ -38,6 +43,8 @@ impl std::fmt::Debug for SnarkBlockVerifyState {
             .field("verifier_index", &"<content too big>")
             .field("verifier_srs", &"<content too big>")
             .field("jobs", &self.jobs)
+            .field("cancelled_count", &self.cancelled_count)
+            .field("time_stats", &self.time_stats)
             .finish()
     }
 }
@@ -14,6 +14,9 @@ pub enum SnarkBlockVerifyEffectfulAction {
         verifier_index: BlockVerifier,
         verifier_srs: Arc<VerifierSRS>,
     },
+    Cancel {
+        req_id: SnarkBlockVerifyId,
+    },
 }
 
 impl redux::EnablingCondition<crate::SnarkState> for SnarkBlockVerifyEffectfulAction {
@@ -19,6 +19,9 @@ impl SnarkBlockVerifyEffectfulAction {
                     .service()
                     .verify_init(req_id, verifier_index, verifier_srs, block);
             }
+            Self::Cancel { req_id } => {
+                store.service().verify_cancel(req_id);
+            }
         }
     }
 }
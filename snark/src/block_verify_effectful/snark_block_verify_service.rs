@@ -12,4 +12,9 @@ pub trait SnarkBlockVerifyService: redux::Service {
         verifier_srs: Arc<VerifierSRS>,
         block: VerifiableBlockWithHash,
     );
+
+    /// Verification result is no longer needed. Service may skip the
+    /// verification, if it hasn't started yet, or still send the result,
+    /// which will be ignored.
+    fn verify_cancel(&mut self, req_id: SnarkBlockVerifyId);
 }
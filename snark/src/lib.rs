@@ -13,6 +13,7 @@ pub mod block_verify;
 pub mod block_verify_effectful;
 pub mod user_command_verify;
 pub mod user_command_verify_effectful;
+pub mod verify_admission;
 pub mod work_verify;
 pub mod work_verify_effectful;
 
@@ -28,6 +29,9 @@ pub use snark_config::*;
 mod snark_state;
 pub use snark_state::*;
 
+mod snark_verify_stats;
+pub use snark_verify_stats::*;
+
 mod snark_reducer;
 
 pub type VerifierIndex = ledger::proofs::VerifierIndex<mina_curves::pasta::Fq>;
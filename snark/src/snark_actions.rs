@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::block_verify_effectful::SnarkBlockVerifyEffectfulAction;
 use crate::user_command_verify::SnarkUserCommandVerifyAction;
 use crate::user_command_verify_effectful::SnarkUserCommandVerifyEffectfulAction;
+use crate::verify_admission::{SnarkVerifyAdmissionAction, SnarkVerifySource};
 use crate::work_verify_effectful::SnarkWorkVerifyEffectfulAction;
 
 use super::block_verify::SnarkBlockVerifyAction;
@@ -19,6 +20,25 @@ pub enum SnarkAction {
     WorkVerifyEffect(SnarkWorkVerifyEffectfulAction),
     UserCommandVerify(SnarkUserCommandVerifyAction),
     UserCommandVerifyEffect(SnarkUserCommandVerifyEffectfulAction),
+    VerifyAdmission(SnarkVerifyAdmissionAction),
+}
+
+impl SnarkAction {
+    /// Source of the work, if the action passes new work to a verify service.
+    pub fn verify_init_source(&self) -> Option<SnarkVerifySource> {
+        match self {
+            SnarkAction::BlockVerify(SnarkBlockVerifyAction::Init { .. }) => {
+                Some(SnarkVerifySource::Block)
+            }
+            SnarkAction::WorkVerify(SnarkWorkVerifyAction::Init { .. }) => {
+                Some(SnarkVerifySource::Work)
+            }
+            SnarkAction::UserCommandVerify(SnarkUserCommandVerifyAction::Init { .. }) => {
+                Some(SnarkVerifySource::UserCommand)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl redux::EnablingCondition<crate::SnarkState> for SnarkAction {
@@ -30,6 +50,7 @@ impl redux::EnablingCondition<crate::SnarkState> for SnarkAction {
             SnarkAction::WorkVerifyEffect(a) => a.is_enabled(state, time),
             SnarkAction::UserCommandVerify(a) => a.is_enabled(state, time),
             SnarkAction::UserCommandVerifyEffect(a) => a.is_enabled(state, time),
+            SnarkAction::VerifyAdmission(a) => a.is_enabled(state, time),
         }
     }
 }
@@ -2,12 +2,16 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::verify_admission::SnarkVerifyAdmissionConfig;
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SnarkConfig {
     pub block_verifier_index: crate::BlockVerifier,
     pub block_verifier_srs: Arc<crate::VerifierSRS>,
     pub work_verifier_index: crate::TransactionVerifier,
     pub work_verifier_srs: Arc<crate::VerifierSRS>,
+    #[serde(default)]
+    pub verify_admission: SnarkVerifyAdmissionConfig,
 }
 
 impl std::fmt::Debug for SnarkConfig {
@@ -17,6 +21,7 @@ impl std::fmt::Debug for SnarkConfig {
             .field("block_verifier_srs", &"<content too big>")
             .field("work_verifier_index", &"<content too big>")
             .field("work_verifier_srs", &"<content too big>")
+            .field("verify_admission", &self.verify_admission)
             .finish()
     }
 }
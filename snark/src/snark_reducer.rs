@@ -1,4 +1,4 @@
-use openmina_core::{Substate, SubstateAccess};
+use openmina_core::{bug_condition, Substate, SubstateAccess};
 use redux::EnablingCondition;
 
 use crate::{
@@ -6,6 +6,7 @@ use crate::{
     block_verify_effectful::SnarkBlockVerifyEffectfulAction,
     user_command_verify::{SnarkUserCommandVerifyAction, SnarkUserCommandVerifyState},
     user_command_verify_effectful::SnarkUserCommandVerifyEffectfulAction,
+    verify_admission::SnarkVerifyAdmissionState,
     work_verify::{SnarkWorkVerifyAction, SnarkWorkVerifyState},
     work_verify_effectful::SnarkWorkVerifyEffectfulAction,
 };
@@ -14,13 +15,14 @@ use super::{SnarkAction, SnarkActionWithMetaRef, SnarkState};
 
 impl SnarkState {
     pub fn reducer<State, Action>(
-        state: Substate<Action, State, Self>,
+        mut state: Substate<Action, State, Self>,
         action: SnarkActionWithMetaRef<'_>,
     ) where
         State: SubstateAccess<Self>
             + SubstateAccess<SnarkWorkVerifyState>
             + SubstateAccess<SnarkUserCommandVerifyState>
-            + SubstateAccess<SnarkBlockVerifyState>,
+            + SubstateAccess<SnarkBlockVerifyState>
+            + SubstateAccess<SnarkVerifyAdmissionState>,
         Action: From<SnarkBlockVerifyAction>
             + From<SnarkBlockVerifyEffectfulAction>
             + From<SnarkWorkVerifyAction>
@@ -30,6 +32,14 @@ impl SnarkState {
             + From<redux::AnyAction>
             + EnablingCondition<State>,
     {
+        if let Some(source) = action.action().verify_init_source() {
+            let Ok(snark_state) = state.get_substate_mut() else {
+                bug_condition!("SnarkState not available for verify admission");
+                return;
+            };
+            snark_state.verify_admission.admitted(source);
+        }
+
         let (action, meta) = action.split();
         match action {
             SnarkAction::BlockVerify(a) => crate::block_verify::reducer(
@@ -47,6 +57,10 @@ impl SnarkState {
                 meta.with_action(a),
             ),
             SnarkAction::UserCommandVerifyEffect(_) => {}
+            SnarkAction::VerifyAdmission(a) => crate::verify_admission::reducer(
+                Substate::from_compatible_substate(state),
+                meta.with_action(a),
+            ),
         }
     }
 }
@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::user_command_verify::SnarkUserCommandVerifyState;
+use crate::verify_admission::{
+    SnarkVerifyAdmissionState, SnarkVerifySource, SnarkVerifySourceStats,
+};
 use crate::SnarkConfig;
 
 use super::block_verify::SnarkBlockVerifyState;
@@ -11,6 +16,8 @@ pub struct SnarkState {
     pub block_verify: SnarkBlockVerifyState,
     pub work_verify: SnarkWorkVerifyState,
     pub user_command_verify: SnarkUserCommandVerifyState,
+    #[serde(default)]
+    pub verify_admission: SnarkVerifyAdmissionState,
 }
 
 impl SnarkState {
@@ -28,6 +35,27 @@ impl SnarkState {
                 config.work_verifier_index,
                 config.work_verifier_srs,
             ),
+            verify_admission: SnarkVerifyAdmissionState::new(config.verify_admission),
+        }
+    }
+
+    /// Number of verifications in the verify service for the source.
+    pub fn verify_pending(&self, source: SnarkVerifySource) -> usize {
+        match source {
+            SnarkVerifySource::Block => self.block_verify.jobs.len(),
+            SnarkVerifySource::Work => self.work_verify.jobs.len(),
+            SnarkVerifySource::UserCommand => self.user_command_verify.jobs.len(),
         }
     }
+
+    /// Whether new work from the source can be passed to its verify service.
+    pub fn is_verify_admitted(&self, source: SnarkVerifySource) -> bool {
+        self.verify_admission
+            .is_admitted(source, |source| self.verify_pending(source))
+    }
+
+    pub fn verify_admission_stats(&self) -> BTreeMap<SnarkVerifySource, SnarkVerifySourceStats> {
+        self.verify_admission
+            .stats(|source| self.verify_pending(source))
+    }
 }
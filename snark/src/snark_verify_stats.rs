@@ -0,0 +1,72 @@
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Durations of the successful verifications, from the job being passed
+/// to the verifier service until its result is received.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct SnarkVerifyTimeStats {
+    pub count: u64,
+    pub total_ms: u64,
+    pub min_ms: Option<u64>,
+    pub max_ms: u64,
+    pub last_ms: Option<u64>,
+}
+
+impl SnarkVerifyTimeStats {
+    pub fn record(&mut self, started: Timestamp, finished: Timestamp) {
+        let ms = finished
+            .checked_sub(started)
+            .map_or(0, |d| d.as_millis() as u64);
+        self.count = self.count.saturating_add(1);
+        self.total_ms = self.total_ms.saturating_add(ms);
+        self.min_ms = Some(self.min_ms.map_or(ms, |min| min.min(ms)));
+        self.max_ms = self.max_ms.max(ms);
+        self.last_ms = Some(ms);
+    }
+
+    pub fn avg_ms(&self) -> Option<u64> {
+        self.total_ms.checked_div(self.count)
+    }
+}
+
+/// Wasm features the verifier was built with. The web node is built
+/// with and without SIMD and the loader selects the build supported by
+/// the browser, so this is what is used at runtime.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnarkWasmFeatures {
+    pub simd: bool,
+    pub threads: bool,
+}
+
+impl SnarkWasmFeatures {
+    /// `None` if not running in wasm.
+    pub fn current() -> Option<Self> {
+        cfg!(target_family = "wasm").then_some(Self {
+            simd: cfg!(target_feature = "simd128"),
+            threads: cfg!(target_feature = "atomics"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_time_stats() {
+        let mut stats = SnarkVerifyTimeStats::default();
+        assert_eq!(stats.avg_ms(), None);
+
+        let t = |ms: u64| Timestamp::new(ms * 1_000_000);
+        stats.record(t(100), t(400));
+        stats.record(t(1000), t(1100));
+        // Out of order times count as zero.
+        stats.record(t(2000), t(1000));
+
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.min_ms, Some(0));
+        assert_eq!(stats.max_ms, 300);
+        assert_eq!(stats.last_ms, Some(0));
+        assert_eq!(stats.avg_ms(), Some(133));
+    }
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use openmina_core::{transaction::TransactionPoolMessageSource, ActionEvent};
 
+use crate::verify_admission::SnarkVerifySource;
+
 use super::{SnarkUserCommandVerifyError, SnarkUserCommandVerifyId};
 
 pub type SnarkUserCommandVerifyActionWithMeta = redux::ActionWithMeta<SnarkUserCommandVerifyAction>;
@@ -50,7 +52,11 @@ impl redux::EnablingCondition<crate::SnarkState> for SnarkUserCommandVerifyActio
         match self {
             SnarkUserCommandVerifyAction::Init {
                 req_id, commands, ..
-            } => !commands.is_empty() && state.user_command_verify.jobs.next_req_id() == *req_id,
+            } => {
+                !commands.is_empty()
+                    && state.user_command_verify.jobs.next_req_id() == *req_id
+                    && state.is_verify_admitted(SnarkVerifySource::UserCommand)
+            }
             SnarkUserCommandVerifyAction::Pending { req_id } => state
                 .user_command_verify
                 .jobs
@@ -0,0 +1,8 @@
+mod snark_verify_admission_state;
+pub use snark_verify_admission_state::*;
+
+mod snark_verify_admission_actions;
+pub use snark_verify_admission_actions::*;
+
+mod snark_verify_admission_reducer;
+pub use snark_verify_admission_reducer::reducer;
@@ -0,0 +1,38 @@
+use openmina_core::{ActionEvent, SubstateAccess};
+use serde::{Deserialize, Serialize};
+
+use super::SnarkVerifySource;
+
+pub type SnarkVerifyAdmissionActionWithMeta = redux::ActionWithMeta<SnarkVerifyAdmissionAction>;
+pub type SnarkVerifyAdmissionActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a SnarkVerifyAdmissionAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = debug, fields(display(source)))]
+pub enum SnarkVerifyAdmissionAction {
+    /// Verification of work from the `source` was denied, because the
+    /// verification capacity for it is exhausted. Only dispatched once
+    /// until the work from the `source` is admitted again.
+    Denied { source: SnarkVerifySource },
+}
+
+impl redux::EnablingCondition<crate::SnarkState> for SnarkVerifyAdmissionAction {
+    fn is_enabled(&self, state: &crate::SnarkState, _time: redux::Timestamp) -> bool {
+        match self {
+            SnarkVerifyAdmissionAction::Denied { source } => {
+                !state.is_verify_admitted(*source) && !state.verify_admission.is_waiting(*source)
+            }
+        }
+    }
+}
+
+impl<T> redux::EnablingCondition<T> for SnarkVerifyAdmissionAction
+where
+    T: SubstateAccess<crate::SnarkState>,
+{
+    fn is_enabled(&self, state: &T, time: redux::Timestamp) -> bool {
+        state
+            .substate()
+            .is_ok_and(|state| self.is_enabled(state, time))
+    }
+}
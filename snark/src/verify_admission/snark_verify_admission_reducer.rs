@@ -0,0 +1,24 @@
+use openmina_core::{Substate, SubstateAccess};
+
+use super::{
+    SnarkVerifyAdmissionAction, SnarkVerifyAdmissionActionWithMetaRef, SnarkVerifyAdmissionState,
+};
+
+pub fn reducer<State, Action>(
+    mut state_context: Substate<Action, State, SnarkVerifyAdmissionState>,
+    action: SnarkVerifyAdmissionActionWithMetaRef<'_>,
+) where
+    State: SubstateAccess<SnarkVerifyAdmissionState>,
+{
+    let Ok(state) = state_context.get_substate_mut() else {
+        // TODO: log or propagate
+        return;
+    };
+    let (action, _meta) = action.split();
+
+    match action {
+        SnarkVerifyAdmissionAction::Denied { source } => {
+            state.denied(*source);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Source of the work submitted to the verify services.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SnarkVerifySource {
+    Block,
+    Work,
+    UserCommand,
+}
+
+impl SnarkVerifySource {
+    pub const ALL: [Self; 3] = [Self::Block, Self::Work, Self::UserCommand];
+
+    /// Work from a source competes for the verification capacity only
+    /// with sources of the same or higher priority.
+    pub fn priority(self) -> u8 {
+        match self {
+            Self::Block => 2,
+            Self::Work => 1,
+            Self::UserCommand => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for SnarkVerifySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Block => write!(f, "block"),
+            Self::Work => write!(f, "work"),
+            Self::UserCommand => write!(f, "user_command"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkVerifyAdmissionConfig {
+    /// Max number of verifications pending across all the verify services.
+    pub max_pending: usize,
+    /// Max number of pending snark work verifications.
+    pub work_quota: usize,
+    /// Max number of pending user command verifications.
+    pub user_command_quota: usize,
+}
+
+impl SnarkVerifyAdmissionConfig {
+    /// Blocks have no quota of their own, they are always admitted.
+    pub fn quota(&self, source: SnarkVerifySource) -> Option<usize> {
+        match source {
+            SnarkVerifySource::Block => None,
+            SnarkVerifySource::Work => Some(self.work_quota),
+            SnarkVerifySource::UserCommand => Some(self.user_command_quota),
+        }
+    }
+}
+
+impl Default for SnarkVerifyAdmissionConfig {
+    fn default() -> Self {
+        Self {
+            max_pending: 8,
+            work_quota: 1,
+            user_command_quota: 4,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct SnarkVerifySourceStats {
+    /// Verifications currently in the verify service.
+    pub queued: usize,
+    pub admitted: u64,
+    /// Times work from the source had to wait for verification capacity.
+    pub denied: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SnarkVerifyAdmissionState {
+    pub config: SnarkVerifyAdmissionConfig,
+    stats: BTreeMap<SnarkVerifySource, SnarkVerifySourceStats>,
+    /// Sources with work waiting for verification capacity since they were
+    /// last denied, so that the retries aren't counted as new denials.
+    #[serde(default)]
+    waiting: BTreeSet<SnarkVerifySource>,
+}
+
+impl SnarkVerifyAdmissionState {
+    pub fn new(config: SnarkVerifyAdmissionConfig) -> Self {
+        Self {
+            config,
+            stats: Default::default(),
+            waiting: Default::default(),
+        }
+    }
+
+    /// Whether the source is within its own quota, given the number of its
+    /// pending verifications.
+    pub fn is_within_quota(&self, source: SnarkVerifySource, pending: usize) -> bool {
+        self.config
+            .quota(source)
+            .is_none_or(|quota| pending < quota)
+    }
+
+    /// Whether new work from `source` can be verified, given the number of
+    /// pending verifications for each source.
+    pub fn is_admitted(
+        &self,
+        source: SnarkVerifySource,
+        pending: impl Fn(SnarkVerifySource) -> usize,
+    ) -> bool {
+        if source == SnarkVerifySource::Block {
+            return true;
+        }
+        let competing = SnarkVerifySource::ALL
+            .into_iter()
+            .filter(|other| other.priority() >= source.priority())
+            .map(&pending)
+            .fold(0usize, usize::saturating_add);
+        self.is_within_quota(source, pending(source)) && competing < self.config.max_pending
+    }
+
+    /// Whether the work from `source` was denied and is still waiting.
+    pub fn is_waiting(&self, source: SnarkVerifySource) -> bool {
+        self.waiting.contains(&source)
+    }
+
+    pub fn admitted(&mut self, source: SnarkVerifySource) {
+        self.waiting.remove(&source);
+        let stats = self.stats.entry(source).or_default();
+        stats.admitted = stats.admitted.saturating_add(1);
+    }
+
+    pub fn denied(&mut self, source: SnarkVerifySource) {
+        if !self.waiting.insert(source) {
+            return;
+        }
+        let stats = self.stats.entry(source).or_default();
+        stats.denied = stats.denied.saturating_add(1);
+    }
+
+    pub fn stats(
+        &self,
+        pending: impl Fn(SnarkVerifySource) -> usize,
+    ) -> BTreeMap<SnarkVerifySource, SnarkVerifySourceStats> {
+        SnarkVerifySource::ALL
+            .into_iter()
+            .map(|source| {
+                let stats = self.stats.get(&source).copied().unwrap_or_default();
+                let queued = pending(source);
+                (source, SnarkVerifySourceStats { queued, ..stats })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_admission_priorities() {
+        let state = SnarkVerifyAdmissionState::new(SnarkVerifyAdmissionConfig {
+            max_pending: 4,
+            work_quota: 2,
+            user_command_quota: 2,
+        });
+        let pending = |block, work, user_command| {
+            move |source| match source {
+                SnarkVerifySource::Block => block,
+                SnarkVerifySource::Work => work,
+                SnarkVerifySource::UserCommand => user_command,
+            }
+        };
+
+        assert!(state.is_admitted(SnarkVerifySource::Block, pending(10, 2, 2)));
+
+        assert!(state.is_admitted(SnarkVerifySource::Work, pending(0, 1, 2)));
+        assert!(!state.is_admitted(SnarkVerifySource::Work, pending(0, 2, 0)));
+        assert!(!state.is_admitted(SnarkVerifySource::Work, pending(3, 1, 0)));
+
+        assert!(state.is_admitted(SnarkVerifySource::UserCommand, pending(1, 1, 1)));
+        assert!(!state.is_admitted(SnarkVerifySource::UserCommand, pending(2, 1, 1)));
+        assert!(!state.is_admitted(SnarkVerifySource::UserCommand, pending(0, 0, 2)));
+    }
+
+    #[test]
+    fn test_verify_admission_denied_once() {
+        let mut state = SnarkVerifyAdmissionState::default();
+        let denied = |state: &SnarkVerifyAdmissionState| {
+            state
+                .stats(|_| 0)
+                .get(&SnarkVerifySource::Work)
+                .map_or(0, |stats| stats.denied)
+        };
+
+        // Retries of the waiting work aren't new denials.
+        state.denied(SnarkVerifySource::Work);
+        state.denied(SnarkVerifySource::Work);
+        assert!(state.is_waiting(SnarkVerifySource::Work));
+        assert!(!state.is_waiting(SnarkVerifySource::UserCommand));
+        assert_eq!(denied(&state), 1);
+
+        state.admitted(SnarkVerifySource::Work);
+        assert!(!state.is_waiting(SnarkVerifySource::Work));
+        state.denied(SnarkVerifySource::Work);
+        assert_eq!(denied(&state), 2);
+    }
+}
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 
 use openmina_core::{snark::Snark, ActionEvent};
 
+use crate::verify_admission::SnarkVerifySource;
+
 use super::{SnarkWorkVerifyError, SnarkWorkVerifyId};
 
 pub type SnarkWorkVerifyActionWithMeta = redux::ActionWithMeta<SnarkWorkVerifyAction>;
@@ -39,7 +41,9 @@ impl redux::EnablingCondition<crate::SnarkState> for SnarkWorkVerifyAction {
     fn is_enabled(&self, state: &crate::SnarkState, _time: redux::Timestamp) -> bool {
         match self {
             SnarkWorkVerifyAction::Init { req_id, batch, .. } => {
-                !batch.is_empty() && state.work_verify.jobs.next_req_id() == *req_id
+                !batch.is_empty()
+                    && state.work_verify.jobs.next_req_id() == *req_id
+                    && state.is_verify_admitted(SnarkVerifySource::Work)
             }
             SnarkWorkVerifyAction::Pending { req_id } => state
                 .work_verify
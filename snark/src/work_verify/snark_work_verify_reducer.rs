@@ -116,6 +116,7 @@ pub fn reducer<State, Action>(
                 return;
             };
             let SnarkWorkVerifyStatus::Pending {
+                time,
                 batch,
                 sender,
                 on_success,
@@ -128,6 +129,7 @@ pub fn reducer<State, Action>(
                 return;
             };
 
+            state.time_stats.record(*time, meta.time());
             let callback = on_success.clone();
             let sender = std::mem::take(sender);
             let batch = std::mem::take(batch);
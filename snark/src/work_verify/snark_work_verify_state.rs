@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use openmina_core::snark::Snark;
 use openmina_core::{requests::PendingRequests, snark::SnarkJobId};
 
-use crate::{TransactionVerifier, VerifierSRS};
+use crate::{SnarkVerifyTimeStats, TransactionVerifier, VerifierSRS};
 
 use super::{SnarkWorkVerifyError, SnarkWorkVerifyId, SnarkWorkVerifyIdType};
 
@@ -14,6 +14,8 @@ pub struct SnarkWorkVerifyState {
     pub verifier_index: TransactionVerifier,
     pub verifier_srs: Arc<VerifierSRS>,
     pub jobs: PendingRequests<SnarkWorkVerifyIdType, SnarkWorkVerifyStatus>,
+    /// Per batch of the proofs.
+    pub time_stats: SnarkVerifyTimeStats,
 }
 
 impl SnarkWorkVerifyState {
@@ -22,6 +24,7 @@ impl SnarkWorkVerifyState {
             verifier_index,
             verifier_srs,
             jobs: Default::default(),
+            time_stats: Default::default(),
         }
     }
 
@@ -37,6 +40,7 @@ impl std::fmt::Debug for SnarkWorkVerifyState {
             .field("verifier_index", &"<content too big>")
             .field("verifier_srs", &"<content too big>")
             .field("jobs", &self.jobs)
+            .field("time_stats", &self.time_stats)
             .finish()
     }
 }
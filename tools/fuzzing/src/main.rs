@@ -8,6 +8,7 @@ pub mod transaction_fuzzer {
     pub mod generator;
     pub mod invariants;
     pub mod mutator;
+    pub mod zkapp;
     use binprot::{
         macros::{BinProtRead, BinProtWrite},
         BinProtRead, BinProtSize, BinProtWrite, SmallString1k,
@@ -452,8 +453,27 @@ fn main() {
                     .default_value("true")
                     .value_parser(clap::value_parser!(bool)),
             )
+            .arg(
+                clap::Arg::new("zkapp-fuzzing")
+                    .long("zkapp-fuzzing")
+                    .help("Fuzz the zkApp command application without the OCaml process")
+                    .default_value("false")
+                    .value_parser(clap::value_parser!(bool)),
+            )
             .get_matches();
 
+        if *matches.get_one::<bool>("zkapp-fuzzing").unwrap() {
+            if let Some(fuzzcase) = matches.get_one::<String>("fuzzcase") {
+                println!("Reproducing zkApp fuzzcase from file: {}", fuzzcase);
+                transaction_fuzzer::zkapp::reproduce(fuzzcase);
+            } else {
+                let seed = *matches.get_one::<u64>("seed").unwrap();
+                println!("Fuzzing zkApp commands [seed: {seed}]...");
+                transaction_fuzzer::zkapp::fuzz(true, seed, 1000);
+            }
+            return;
+        }
+
         let mut child = Command::new(
             std::env::var("OCAML_TRANSACTION_FUZZER_PATH").unwrap_or_else(
                 #[coverage(off)]
@@ -9,7 +9,7 @@ use ledger::scan_state::transaction_logic::transaction_applied::{
     signed_command_applied, CommandApplied, TransactionApplied, Varying,
 };
 use ledger::scan_state::transaction_logic::{
-    apply_transactions, Transaction, TransactionStatus, UserCommand,
+    apply_transactions, zkapp_command::ZkAppCommand, Transaction, TransactionStatus, UserCommand,
 };
 use ledger::sparse_ledger::LedgerIntf;
 use ledger::staged_ledger::staged_ledger::StagedLedger;
@@ -430,6 +430,23 @@ impl FuzzerCtx {
         self.gen()
     }
 
+    /// Like [`Self::random_user_command`], but only generates zkApp commands.
+    #[coverage(off)]
+    pub fn random_zkapp_command(&mut self) -> ZkAppCommand {
+        if self.gen.rng.gen_bool(0.9) && !self.state.cache_apply.is_empty() {
+            let index = self.gen.rng.gen_range(0..self.state.cache_apply.len());
+
+            if let Some(UserCommand::ZkAppCommand(mut command)) =
+                self.state.cache_apply.get_relative(index).cloned()
+            {
+                self.mutate(command.as_mut());
+                return *command;
+            }
+        }
+
+        self.gen()
+    }
+
     #[coverage(off)]
     pub fn random_tx_proof(
         &mut self,
@@ -661,54 +678,7 @@ impl FuzzerCtx {
                 //println!("RUST: {:?}", applied);
                 //println!("OCAML: {:?}", expected_apply_result);
 
-                // Save applied transactions in the cache for later use (mutation)
-                if *applied.transaction_status() == TransactionStatus::Applied {
-                    if let UserCommand::ZkAppCommand(command) = user_command {
-                        if !command.account_updates.is_empty() {
-                            //println!("Storing in apply cache {:?}", tx);
-                            self.state.cache_apply.push_back(user_command.clone());
-                        }
-                    }
-                } else {
-                    //println!("{:?}", applied.transaction_status());
-                }
-
-                // Add new accounts created by the transaction to the potential senders list
-                let new_accounts = match &applied.varying {
-                    Varying::Command(command) => match command {
-                        CommandApplied::SignedCommand(cmd) => match &cmd.body {
-                            signed_command_applied::Body::Payments { new_accounts } => {
-                                Some(new_accounts)
-                            }
-                            _ => None,
-                        },
-                        CommandApplied::ZkappCommand(cmd) => Some(&cmd.new_accounts),
-                    },
-                    _ => unimplemented!(),
-                };
-
-                if let Some(new_accounts) = new_accounts {
-                    let new_accounts = self.state.potential_new_accounts.iter().filter(
-                        #[coverage(off)]
-                        |(kp, _)| {
-                            new_accounts.iter().any(
-                                #[coverage(off)]
-                                |acc| acc.public_key == kp.public.into_compressed(),
-                            )
-                        },
-                    );
-
-                    for acc in new_accounts {
-                        if !self.state.potential_senders.iter().any(
-                            #[coverage(off)]
-                            |(kp, _)| kp.public == acc.0.public,
-                        ) {
-                            self.state.potential_senders.push(acc.clone())
-                        }
-                    }
-
-                    self.state.potential_new_accounts.clear();
-                }
+                self.record_applied(user_command, applied);
             }
             Err(error_string) => {
                 // Currently disabled until invariants are fixed
@@ -741,6 +711,58 @@ impl FuzzerCtx {
         }
     }
 
+    /// Saves the applied command for later mutation and adds the accounts
+    /// it created to the potential senders.
+    #[coverage(off)]
+    pub fn record_applied(&mut self, user_command: &UserCommand, applied: &TransactionApplied) {
+        // Save applied transactions in the cache for later use (mutation)
+        if *applied.transaction_status() == TransactionStatus::Applied {
+            if let UserCommand::ZkAppCommand(command) = user_command {
+                if !command.account_updates.is_empty() {
+                    //println!("Storing in apply cache {:?}", tx);
+                    self.state.cache_apply.push_back(user_command.clone());
+                }
+            }
+        } else {
+            //println!("{:?}", applied.transaction_status());
+        }
+
+        // Add new accounts created by the transaction to the potential senders list
+        let new_accounts = match &applied.varying {
+            Varying::Command(command) => match command {
+                CommandApplied::SignedCommand(cmd) => match &cmd.body {
+                    signed_command_applied::Body::Payments { new_accounts } => Some(new_accounts),
+                    _ => None,
+                },
+                CommandApplied::ZkappCommand(cmd) => Some(&cmd.new_accounts),
+            },
+            _ => unimplemented!(),
+        };
+
+        if let Some(new_accounts) = new_accounts {
+            let new_accounts = self.state.potential_new_accounts.iter().filter(
+                #[coverage(off)]
+                |(kp, _)| {
+                    new_accounts.iter().any(
+                        #[coverage(off)]
+                        |acc| acc.public_key == kp.public.into_compressed(),
+                    )
+                },
+            );
+
+            for acc in new_accounts {
+                if !self.state.potential_senders.iter().any(
+                    #[coverage(off)]
+                    |(kp, _)| kp.public == acc.0.public,
+                ) {
+                    self.state.potential_senders.push(acc.clone())
+                }
+            }
+
+            self.state.potential_new_accounts.clear();
+        }
+    }
+
     #[coverage(off)]
     pub fn get_ledger_root(&mut self) -> Fp {
         LedgerIntf::merkle_root(self.get_ledger_inner_mut())
@@ -885,6 +907,8 @@ impl FuzzerCtxBuilder {
                 trust_system: (),
                 pool_max_size: 3000,
                 slot_tx_end: None,
+                zkapp_limits: Default::default(),
+                fee_payer_limits: Default::default(),
             },
             &ConsensusConstants::create(&constraint_constants, &protocol_constants),
         );
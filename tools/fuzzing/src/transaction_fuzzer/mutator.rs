@@ -677,9 +677,75 @@ impl Mutator<AccountUpdate> for FuzzerCtx {
     }
 }
 
+/// Upper bound of account updates in the command grown by the structural
+/// mutations.
+const MAX_ACCOUNT_UPDATES: usize = 32;
+
+#[coverage(off)]
+pub fn count_account_updates(forest: &zkapp_command::CallForest<AccountUpdate>) -> usize {
+    forest
+        .0
+        .iter()
+        .map(
+            #[coverage(off)]
+            |tree| 1 + count_account_updates(&tree.elt.calls),
+        )
+        .sum()
+}
+
+/// Changes the shape of the forest, keeping the account updates themselves
+/// intact: drops, duplicates, swaps, nests and flattens the trees.
+#[coverage(off)]
+fn mutate_call_forest_structure(
+    ctx: &mut FuzzerCtx,
+    forest: &mut zkapp_command::CallForest<AccountUpdate>,
+) {
+    let len = forest.0.len();
+    if len == 0 {
+        return;
+    }
+    let i = ctx.gen.rng.gen_range(0..len);
+    let j = ctx.gen.rng.gen_range(0..len);
+
+    match ctx.gen.rng.gen_range(0..5) {
+        0 => {
+            forest.0.remove(i);
+        }
+        1 => {
+            if count_account_updates(forest) < MAX_ACCOUNT_UPDATES {
+                let tree = forest.0[i].clone();
+                forest.0.insert(j, tree);
+            }
+        }
+        2 => forest.0.swap(i, j),
+        3 => {
+            // Make the tree a child of its sibling.
+            if i != j {
+                let tree = forest.0.remove(i);
+                let parent = if j > i { j - 1 } else { j };
+                let calls = &mut forest.0[parent].elt.calls.0;
+                let position = ctx.gen.rng.gen_range(0..=calls.len());
+                calls.insert(position, tree);
+            }
+        }
+        4 => {
+            // Replace the tree with its children.
+            let tree = forest.0.remove(i);
+            for (offset, child) in tree.elt.calls.0.into_iter().enumerate() {
+                forest.0.insert(i + offset, child);
+            }
+        }
+        _ => unimplemented!(),
+    }
+}
+
 impl Mutator<zkapp_command::CallForest<AccountUpdate>> for FuzzerCtx {
     #[coverage(off)]
     fn mutate(&mut self, t: &mut zkapp_command::CallForest<AccountUpdate>) {
+        if self.gen.rng.gen_bool(0.2) {
+            mutate_call_forest_structure(self, t);
+        }
+
         for i in rand_elements(self, t.0.len()) {
             self.mutate(&mut t.0[i].elt.account_update);
             self.mutate(&mut t.0[i].elt.calls);
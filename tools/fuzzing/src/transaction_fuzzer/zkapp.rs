@@ -0,0 +1,465 @@
+//! Fuzzing of the zkApp command application on the Rust side only, without
+//! the OCaml process. Instead of comparing the results, it checks that the
+//! application doesn't panic and preserves the ledger invariants, and
+//! minimizes the failing commands before saving them.
+
+use super::{
+    context::{FuzzerCtx, FuzzerCtxBuilder},
+    generator::sign_account_updates,
+    invariants,
+    mutator::count_account_updates,
+    CoverageStats,
+};
+use ledger::{
+    generators::zkapp_command_builder::get_transaction_commitments,
+    scan_state::{
+        currency::{Magnitude, Sgn},
+        transaction_logic::{
+            apply_transactions,
+            transaction_applied::{CommandApplied, TransactionApplied, Varying},
+            zkapp_command::{
+                self, AccountPreconditions, AccountUpdate, AuthorizationKind, CallForest, Numeric,
+                Preconditions, ZkAppCommand, ZkAppPreconditions,
+            },
+            Memo, Transaction, TransactionStatus, UserCommand,
+        },
+    },
+    sparse_ledger::LedgerIntf,
+    Account, AccountId, BaseLedger, Mask, MutableFp, TokenId,
+};
+use mina_signer::{NetworkId, Signer};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::{env, fmt, panic};
+
+#[derive(Debug, Clone)]
+pub enum ZkappFuzzFailure {
+    Panic(String),
+    Invariant(String),
+}
+
+impl ZkappFuzzFailure {
+    /// Whether the failures are likely caused by the same bug, used to
+    /// keep the minimized command failing the same way.
+    #[coverage(off)]
+    fn same_kind(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Panic(_), Self::Panic(_)) => true,
+            (Self::Invariant(a), Self::Invariant(b)) => invariant_name(a) == invariant_name(b),
+            _ => false,
+        }
+    }
+}
+
+#[coverage(off)]
+fn invariant_name(error: &str) -> &str {
+    error.lines().next().unwrap_or_default()
+}
+
+impl fmt::Display for ZkappFuzzFailure {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Panic(message) => write!(f, "Panic: {message}"),
+            Self::Invariant(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+#[coverage(off)]
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(
+            #[coverage(off)]
+            || payload.downcast_ref::<String>().cloned(),
+        )
+        .unwrap_or_else(
+            #[coverage(off)]
+            || "unknown panic payload".to_string(),
+        )
+}
+
+#[coverage(off)]
+fn default_token_supply(accounts: &[Account]) -> u128 {
+    accounts
+        .iter()
+        .filter(
+            #[coverage(off)]
+            |account| account.token_id == TokenId::default(),
+        )
+        .map(
+            #[coverage(off)]
+            |account| account.balance.as_u64() as u128,
+        )
+        .sum()
+}
+
+/// Applies the command to the `ledger` and checks the invariants.
+///
+/// Returns `Ok(None)` if the command was rejected before being applied,
+/// e.g. the fee payer can't pay the fee.
+#[coverage(off)]
+pub fn check_zkapp_command(
+    ctx: &FuzzerCtx,
+    ledger: &mut Mask,
+    command: &ZkAppCommand,
+) -> Result<Option<TransactionApplied>, ZkappFuzzFailure> {
+    let accounts_before = ledger.to_list();
+    let tx = Transaction::Command(UserCommand::ZkAppCommand(Box::new(command.clone())));
+
+    let applied = panic::catch_unwind(panic::AssertUnwindSafe(
+        #[coverage(off)]
+        || {
+            apply_transactions(
+                &ctx.constraint_constants,
+                ctx.txn_state_view.global_slot_since_genesis,
+                &ctx.txn_state_view,
+                ledger,
+                &[tx],
+            )
+        },
+    ))
+    .map_err(
+        #[coverage(off)]
+        |payload| ZkappFuzzFailure::Panic(panic_message(payload)),
+    )?;
+
+    let applied = match applied {
+        Ok(mut applied) if applied.len() == 1 => applied.remove(0),
+        Ok(applied) => {
+            return Err(ZkappFuzzFailure::Invariant(format!(
+                "Invariant violation: applied {} transactions instead of one",
+                applied.len()
+            )))
+        }
+        Err(error) if error.starts_with("Invariant violation") => {
+            return Err(ZkappFuzzFailure::Invariant(error))
+        }
+        Err(_) => return Ok(None),
+    };
+
+    // The fee and the burned amount come from the application result, which
+    // must agree with the command and with the change of the ledger.
+    let fee_excess = applied
+        .transaction()
+        .data
+        .fee_excess()
+        .map_err(ZkappFuzzFailure::Invariant)?;
+    if fee_excess.fee_token_l != TokenId::default()
+        || fee_excess.fee_excess_l.sgn != Sgn::Pos
+        || fee_excess.fee_excess_l.magnitude != command.fee()
+        || !fee_excess.fee_excess_r.magnitude.is_zero()
+    {
+        return Err(ZkappFuzzFailure::Invariant(format!(
+            "Invariant violation: fee excess\n{fee_excess:?} doesn't match the fee {:?}",
+            command.fee()
+        )));
+    }
+
+    let supply_increase = applied
+        .supply_increase(&ctx.constraint_constants)
+        .map_err(ZkappFuzzFailure::Invariant)?;
+    if supply_increase.sgn == Sgn::Pos && !supply_increase.magnitude.is_zero() {
+        return Err(ZkappFuzzFailure::Invariant(format!(
+            "Invariant violation: supply increase
+{supply_increase:?} from a zkApp command"
+        )));
+    }
+
+    let new_accounts = match &applied.varying {
+        Varying::Command(CommandApplied::ZkappCommand(applied)) => applied.new_accounts.len(),
+        varying => {
+            return Err(ZkappFuzzFailure::Invariant(format!(
+                "Invariant violation: unexpected application result\n{varying:?}"
+            )))
+        }
+    };
+
+    // The fee goes to the fee excess, the account creation fees are burned.
+    let accounts_after = ledger.to_list();
+    let supply_before = default_token_supply(&accounts_before);
+    let supply_after = default_token_supply(&accounts_after);
+    let burned = fee_excess.fee_excess_l.magnitude.as_u64() as u128
+        + supply_increase.magnitude.as_u64() as u128;
+    if supply_before != supply_after + burned {
+        return Err(ZkappFuzzFailure::Invariant(format!(
+            "Invariant violation: total supply\nbefore: {supply_before}, after: {supply_after}, \
+             fee excess and account creation fees: {burned}"
+        )));
+    }
+
+    // Only the fee payer is charged for the failed command.
+    if let TransactionStatus::Failed(failures) = applied.transaction_status() {
+        let fee_payer = command.fee_payer();
+        let before = accounts_before
+            .into_iter()
+            .map(
+                #[coverage(off)]
+                |account| (account.id(), account),
+            )
+            .collect::<BTreeMap<AccountId, Account>>();
+        let changed = accounts_after.iter().find(
+            #[coverage(off)]
+            |account| account.id() != fee_payer && before.get(&account.id()) != Some(account),
+        );
+        if new_accounts != 0 || changed.is_some() {
+            return Err(ZkappFuzzFailure::Invariant(format!(
+                "Invariant violation: failed command changed the ledger\n\
+                 failures: {failures:?}\nnew accounts: {new_accounts}\nchanged: {changed:?}"
+            )));
+        }
+        let charged = before
+            .get(&fee_payer)
+            .zip(accounts_after.iter().find(
+                #[coverage(off)]
+                |account| account.id() == fee_payer,
+            ))
+            .map(
+                #[coverage(off)]
+                |(before, after)| before.balance.as_u64() as i128 - after.balance.as_u64() as i128,
+            );
+        if charged != Some(command.fee().as_u64() as i128) {
+            return Err(ZkappFuzzFailure::Invariant(format!(
+                "Invariant violation: failed command fee\n\
+                 fee payer charged {charged:?}, the fee is {:?}",
+                command.fee()
+            )));
+        }
+    }
+
+    Ok(Some(applied))
+}
+
+/// Drops the cached hashes of the account updates. The candidates share
+/// them with the command they were cloned from, so they would be signed
+/// with its commitments otherwise.
+#[coverage(off)]
+fn reset_hashes(forest: &mut CallForest<AccountUpdate>) {
+    for tree in forest.0.iter_mut() {
+        tree.stack_hash = MutableFp::empty();
+        tree.elt.account_update_digest = MutableFp::empty();
+        reset_hashes(&mut tree.elt.calls);
+    }
+}
+
+#[coverage(off)]
+fn sign_zkapp_command(ctx: &mut FuzzerCtx, command: &mut ZkAppCommand) {
+    reset_hashes(&mut command.account_updates);
+    let (txn_commitment, full_txn_commitment) = get_transaction_commitments(command);
+    let mut signer = mina_signer::create_kimchi(NetworkId::TESTNET);
+
+    if let Some(keypair) = ctx.find_keypair(&command.fee_payer.body.public_key) {
+        command.fee_payer.authorization = signer.sign(keypair, &full_txn_commitment);
+    }
+
+    sign_account_updates(
+        ctx,
+        &mut signer,
+        &txn_commitment,
+        &full_txn_commitment,
+        &mut command.account_updates,
+    );
+}
+
+/// Account update at `index` in the depth-first order.
+#[coverage(off)]
+fn account_update_mut<'a>(
+    forest: &'a mut CallForest<AccountUpdate>,
+    index: &mut usize,
+) -> Option<&'a mut zkapp_command::Tree<AccountUpdate>> {
+    for tree in forest.0.iter_mut() {
+        if *index == 0 {
+            return Some(&mut tree.elt);
+        }
+        *index -= 1;
+        if let Some(found) = account_update_mut(&mut tree.elt.calls, index) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+#[coverage(off)]
+fn remove_account_update(forest: &mut CallForest<AccountUpdate>, index: &mut usize) -> bool {
+    for i in 0..forest.0.len() {
+        if *index == 0 {
+            forest.0.remove(i);
+            return true;
+        }
+        *index -= 1;
+        if remove_account_update(&mut forest.0[i].elt.calls, index) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Smaller variants of the command, each differing in one step: a removed
+/// subtree, dropped children, ignored preconditions, no authorization or an
+/// empty memo.
+#[coverage(off)]
+fn shrink_candidates(command: &ZkAppCommand) -> Vec<ZkAppCommand> {
+    let mut candidates = Vec::new();
+    let count = count_account_updates(&command.account_updates);
+
+    for index in 0..count {
+        let mut candidate = command.clone();
+        if remove_account_update(&mut candidate.account_updates, &mut { index }) {
+            candidates.push(candidate);
+        }
+    }
+
+    let accept = Preconditions::new(
+        ZkAppPreconditions::accept(),
+        AccountPreconditions(zkapp_command::Account::accept()),
+        Numeric::Ignore,
+    );
+    for index in 0..count {
+        let mut candidate = command.clone();
+        let Some(tree) = account_update_mut(&mut candidate.account_updates, &mut { index }) else {
+            continue;
+        };
+        if !tree.calls.0.is_empty() {
+            let mut candidate = command.clone();
+            if let Some(tree) = account_update_mut(&mut candidate.account_updates, &mut { index }) {
+                tree.calls = CallForest::new();
+                candidates.push(candidate);
+            }
+        }
+
+        let body = &mut tree.account_update.body;
+        let mut changed = false;
+        if body.preconditions != accept {
+            body.preconditions = accept.clone();
+            changed = true;
+        }
+        if body.authorization_kind != AuthorizationKind::NoneGiven {
+            body.authorization_kind = AuthorizationKind::NoneGiven;
+            tree.account_update.authorization = zkapp_command::Control::NoneGiven;
+            changed = true;
+        }
+        if changed {
+            candidates.push(candidate);
+        }
+    }
+
+    if command.memo != Memo::empty() {
+        let mut candidate = command.clone();
+        candidate.memo = Memo::empty();
+        candidates.push(candidate);
+    }
+
+    candidates
+}
+
+/// Greedily shrinks the command while it keeps failing the same way.
+#[coverage(off)]
+pub fn minimize(
+    ctx: &mut FuzzerCtx,
+    mut command: ZkAppCommand,
+    failure: &ZkappFuzzFailure,
+) -> ZkAppCommand {
+    // Don't spam the output with the panics of the candidates.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(
+        #[coverage(off)]
+        |_| {},
+    ));
+
+    'shrink: loop {
+        for mut candidate in shrink_candidates(&command) {
+            sign_zkapp_command(ctx, &mut candidate);
+            let mut ledger = ctx.get_ledger_inner().make_child();
+            if let Err(candidate_failure) = check_zkapp_command(ctx, &mut ledger, &candidate) {
+                if candidate_failure.same_kind(failure) {
+                    command = candidate;
+                    continue 'shrink;
+                }
+            }
+        }
+        break;
+    }
+
+    panic::set_hook(hook);
+    command
+}
+
+#[coverage(off)]
+pub fn fuzz(break_on_invariant: bool, seed: u64, minimum_fee: u64) {
+    *invariants::BREAK.write().unwrap() = break_on_invariant;
+    let mut cov_stats = CoverageStats::new();
+    let mut ctx = FuzzerCtxBuilder::new()
+        .seed(seed)
+        .minimum_fee(minimum_fee)
+        .initial_accounts(1000)
+        .fuzzcases_path(env::var("FUZZCASES_PATH").unwrap_or("/tmp/".to_string()))
+        .build();
+
+    let mut fuzzer_made_progress = false;
+
+    for iteration in 0.. {
+        print!("Iteration {}\r", iteration);
+        std::io::stdout().flush().unwrap();
+
+        if (iteration % 10000) == 0 {
+            if fuzzer_made_progress {
+                fuzzer_made_progress = false;
+                ctx.take_snapshot();
+            } else {
+                ctx.restore_snapshot();
+            }
+        }
+
+        // Update coverage statistics every 1000 iterations
+        if (iteration % 1000) == 0 && cov_stats.update_rust() {
+            fuzzer_made_progress = true;
+            cov_stats.print();
+        }
+
+        let command = ctx.random_zkapp_command();
+        let mut ledger = ctx.get_ledger_inner().make_child();
+
+        match check_zkapp_command(&ctx, &mut ledger, &command) {
+            Ok(Some(applied)) => {
+                ledger.commit();
+                ctx.record_applied(&UserCommand::ZkAppCommand(Box::new(command)), &applied);
+            }
+            Ok(None) => {}
+            Err(failure) => {
+                println!("!!! {failure}");
+
+                let original_updates = count_account_updates(&command.account_updates);
+                let command = minimize(&mut ctx, command, &failure);
+                println!(
+                    "Minimized from {original_updates} to {} account updates",
+                    count_account_updates(&command.account_updates)
+                );
+
+                let mut ledger = ctx.get_ledger_inner().make_child();
+                let bigint: num_bigint::BigUint = LedgerIntf::merkle_root(&mut ledger).into();
+                ctx.save_fuzzcase(
+                    &UserCommand::ZkAppCommand(Box::new(command)),
+                    &bigint.to_string(),
+                );
+
+                std::process::exit(0);
+            }
+        }
+    }
+}
+
+#[coverage(off)]
+pub fn reproduce(fuzzcase: &String) {
+    let mut ctx = FuzzerCtxBuilder::new().build();
+    let UserCommand::ZkAppCommand(command) = ctx.load_fuzzcase(fuzzcase) else {
+        panic!("fuzzcase doesn't contain a zkApp command");
+    };
+
+    let mut ledger = ctx.get_ledger_inner().make_child();
+    match check_zkapp_command(&ctx, &mut ledger, &command) {
+        Ok(applied) => println!("check_zkapp_command: Ok({:?})", applied),
+        Err(failure) => println!("check_zkapp_command: {failure}"),
+    }
+}
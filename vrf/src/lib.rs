@@ -2,8 +2,9 @@ use ark_ec::AffineCurve;
 use ark_ff::PrimeField;
 use ledger::AccountIndex;
 use message::VrfMessage;
-use mina_p2p_messages::v2::EpochSeed;
+use mina_p2p_messages::v2::{ConsensusVrfOutputTruncatedStableV1, EpochSeed};
 use num::{rational::Ratio, BigInt, ToPrimitive};
+use o1_utils::FieldHelpers;
 use openmina_node_account::AccountPublicKey;
 use output::VrfOutput;
 use serde::{Deserialize, Serialize};
@@ -50,6 +51,9 @@ pub enum VrfError {
 
     #[error("The witness is invalid")]
     IvalidWitness,
+
+    #[error("Truncated vrf output must be 32 bytes, got: {0}")]
+    InvalidTruncatedOutputLength(usize),
 }
 
 /// 256 bits
@@ -88,6 +92,16 @@ impl VrfEvaluationOutput {
     }
 }
 
+/// Truncated vrf output compared against the threshold of the stake.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VrfThresholdCheck {
+    /// Vrf output as a fraction in `[0, 1)`.
+    pub value: f64,
+    /// Probability of winning a slot with the stake.
+    pub threshold: f64,
+    pub threshold_met: bool,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct VrfEvaluationInput {
     pub producer_key: Keypair,
@@ -126,6 +140,25 @@ fn calculate_vrf(
 
 /// Evaluate vrf with a specific input. Used by the block producer
 pub fn evaluate_vrf(vrf_input: VrfEvaluationInput) -> VrfResult<VrfEvaluationOutput> {
+    let global_slot = vrf_input.global_slot;
+    let (won_slot, threshold_met) = evaluate_vrf_and_threshold(vrf_input)?;
+
+    if threshold_met {
+        Ok(VrfEvaluationOutput::SlotWon(won_slot))
+    } else {
+        Ok(VrfEvaluationOutput::SlotLost(global_slot))
+    }
+}
+
+/// Same as [`evaluate_vrf`], but the slot is won even if the threshold isn't
+/// met. Used by the block producer testing mode, which produces a block at
+/// every slot.
+#[cfg(any(test, feature = "testing"))]
+pub fn evaluate_vrf_bypassing_threshold(vrf_input: VrfEvaluationInput) -> VrfResult<VrfWonSlot> {
+    evaluate_vrf_and_threshold(vrf_input).map(|(won_slot, _)| won_slot)
+}
+
+fn evaluate_vrf_and_threshold(vrf_input: VrfEvaluationInput) -> VrfResult<(VrfWonSlot, bool)> {
     let VrfEvaluationInput {
         producer_key,
         global_slot,
@@ -141,23 +174,46 @@ pub fn evaluate_vrf(vrf_input: VrfEvaluationInput) -> VrfResult<VrfEvaluationOut
     let value = vrf_output.truncated().into_repr();
     let threshold = Threshold::new(delegated_stake, total_currency);
 
-    if threshold.threshold_met(value) {
-        Ok(VrfEvaluationOutput::SlotWon(VrfWonSlot {
-            producer: producer_key.public.into(),
-            vrf_output: Box::new(vrf_output),
-            winner_account: account_pub_key,
-            global_slot,
-            account_index: delegator_index,
-            value_with_threshold: None.or_else(|| {
-                Some((
-                    self::threshold::get_fractional(value).to_f64()?,
-                    threshold.threshold_rational.to_f64()?,
-                ))
-            }),
-        }))
-    } else {
-        Ok(VrfEvaluationOutput::SlotLost(global_slot))
+    let won_slot = VrfWonSlot {
+        producer: producer_key.public.into(),
+        vrf_output: Box::new(vrf_output),
+        winner_account: account_pub_key,
+        global_slot,
+        account_index: delegator_index,
+        value_with_threshold: None.or_else(|| {
+            Some((
+                self::threshold::get_fractional(value).to_f64()?,
+                threshold.threshold_rational.to_f64()?,
+            ))
+        }),
+    };
+    Ok((won_slot, threshold.threshold_met(value)))
+}
+
+/// Checks the truncated vrf output, as it's included in the block, against
+/// the threshold for the delegated stake. Doesn't need the producer's key,
+/// so it can be used to audit the slot wins of other producers.
+pub fn check_vrf_threshold(
+    vrf_output: &ConsensusVrfOutputTruncatedStableV1,
+    delegated_stake: BigInt,
+    total_currency: BigInt,
+) -> VrfResult<VrfThresholdCheck> {
+    if vrf_output.0.len() != 32 {
+        return Err(VrfError::InvalidTruncatedOutputLength(vrf_output.0.len()));
     }
+    let value = ScalarField::from_bytes(&vrf_output.0)?.into_repr();
+    let threshold = Threshold::new(delegated_stake, total_currency);
+
+    Ok(VrfThresholdCheck {
+        value: self::threshold::get_fractional(value)
+            .to_f64()
+            .ok_or(VrfError::RationalToF64)?,
+        threshold: threshold
+            .threshold_rational
+            .to_f64()
+            .ok_or(VrfError::RationalToF64)?,
+        threshold_met: threshold.threshold_met(value),
+    })
 }
 
 pub fn keypair_from_bs58_string(str: &str) -> Keypair {
@@ -177,13 +233,13 @@ mod test {
 
     use mina_p2p_messages::{
         bigint::BigInt as MinaBigInt,
-        v2::{EpochSeed, MinaBaseEpochSeedStableV1},
+        v2::{ConsensusVrfOutputTruncatedStableV1, EpochSeed, MinaBaseEpochSeedStableV1},
     };
     use openmina_node_account::AccountSecretKey;
 
     use crate::{genesis_vrf, keypair_from_bs58_string, VrfEvaluationInput, VrfEvaluationOutput};
 
-    use super::evaluate_vrf;
+    use super::{check_vrf_threshold, evaluate_vrf, evaluate_vrf_bypassing_threshold};
 
     #[test]
     fn test_genesis_vrf() {
@@ -214,7 +270,13 @@ mod test {
         assert_eq!(
             evaluation_result,
             VrfEvaluationOutput::SlotLost(vrf_input.global_slot)
-        )
+        );
+
+        let won_slot =
+            evaluate_vrf_bypassing_threshold(vrf_input.clone()).expect("Failed to evaluate vrf");
+        assert_eq!(won_slot.global_slot, vrf_input.global_slot);
+        let (value, threshold) = won_slot.value_with_threshold.unwrap();
+        assert!(value > threshold);
     }
 
     #[test]
@@ -252,6 +314,42 @@ mod test {
         // assert_eq!(expected, evaluation_result)
     }
 
+    #[test]
+    fn test_check_vrf_threshold() {
+        let vrf_input = |global_slot| VrfEvaluationInput {
+            producer_key: keypair_from_bs58_string(
+                "EKEEpMELfQkMbJDt2fB4cFXKwSf1x4t7YD4twREy5yuJ84HBZtF9",
+            ),
+            epoch_seed: EpochSeed::from_str("2va9BGv9JrLTtrzZttiEMDYw1Zj6a6EHzXjmP9evHDTG3oEquURA")
+                .unwrap(),
+            global_slot,
+            delegator_index: AccountIndex(2),
+            delegated_stake: BigInt::from_str("1000000000000000")
+                .expect("Cannot convert to BigInt"),
+            total_currency: BigInt::from_str("6000000000001000").expect("Cannot convert to BigInt"),
+            account_pub_key: AccountSecretKey::genesis_producer().public_key(),
+        };
+
+        for (global_slot, won) in [(6, true), (518, false)] {
+            let input = vrf_input(global_slot);
+            let won_slot =
+                evaluate_vrf_bypassing_threshold(input.clone()).expect("Failed to evaluate vrf");
+            let truncated = ConsensusVrfOutputTruncatedStableV1::from(&*won_slot.vrf_output);
+
+            let check =
+                check_vrf_threshold(&truncated, input.delegated_stake, input.total_currency)
+                    .expect("Failed to check threshold");
+            assert_eq!(check.threshold_met, won);
+            assert_eq!(
+                Some((check.value, check.threshold)),
+                won_slot.value_with_threshold
+            );
+        }
+
+        let invalid = ConsensusVrfOutputTruncatedStableV1(vec![0; 31].into());
+        assert!(check_vrf_threshold(&invalid, 1u64.into(), 1u64.into()).is_err());
+    }
+
     #[test]
     #[ignore]
     fn test_slot_calculation_time_big_producer() {